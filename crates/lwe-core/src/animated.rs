@@ -0,0 +1,178 @@
+//! Animated and still image detection
+//!
+//! GIF, APNG and animated WebP files are played by the engine's own decoder
+//! instead of mpv, and still JPEG, PNG and WebP images are drawn by it once.
+//! A `.png` or `.webp` is only animated when its header says so, so
+//! classification sniffs the container rather than trusting the extension.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Animated image container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimatedFormat {
+    Gif,
+    Apng,
+    WebP,
+}
+
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+/// Format of `path` if it is an animated image
+pub fn animated_format(path: &Path) -> Option<AnimatedFormat> {
+    let mut file = File::open(path).ok()?;
+    let mut header = [0u8; 21];
+    let read = file.read(&mut header).ok()?;
+    let header = &header[..read];
+
+    if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+        return Some(AnimatedFormat::Gif);
+    }
+    if header.starts_with(PNG_SIGNATURE) {
+        return png_has_animation(&mut file).then_some(AnimatedFormat::Apng);
+    }
+    if webp_has_animation(header) {
+        return Some(AnimatedFormat::WebP);
+    }
+    None
+}
+
+/// Check if `path` is a GIF, APNG or animated WebP
+pub fn is_animated_image(path: &Path) -> bool {
+    animated_format(path).is_some()
+}
+
+/// Check if `path` is a JPEG, or a PNG or WebP without animation
+pub fn is_still_image(path: &Path) -> bool {
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    let mut header = [0u8; 21];
+    let Ok(read) = file.read(&mut header) else {
+        return false;
+    };
+    let header = &header[..read];
+
+    if header.starts_with(b"\xff\xd8\xff") {
+        return true;
+    }
+    if header.starts_with(PNG_SIGNATURE) {
+        return !png_has_animation(&mut file);
+    }
+    header.len() >= 12
+        && &header[0..4] == b"RIFF"
+        && &header[8..12] == b"WEBP"
+        && !webp_has_animation(header)
+}
+
+/// Walk PNG chunk headers until `acTL` (animated) or `IDAT` (still image)
+///
+/// APNG requires `acTL` before the first `IDAT`, so the pixel data is never read.
+fn png_has_animation(file: &mut File) -> bool {
+    if file
+        .seek(SeekFrom::Start(PNG_SIGNATURE.len() as u64))
+        .is_err()
+    {
+        return false;
+    }
+
+    let mut chunk = [0u8; 8];
+    while file.read_exact(&mut chunk).is_ok() {
+        let length = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        match &chunk[4..8] {
+            b"acTL" => return true,
+            b"IDAT" | b"IEND" => return false,
+            // Skip the chunk data and its CRC
+            _ => {
+                if file.seek(SeekFrom::Current(i64::from(length) + 4)).is_err() {
+                    return false;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Extended WebP (`VP8X`) with the animation flag set
+fn webp_has_animation(header: &[u8]) -> bool {
+    header.len() >= 21
+        && &header[0..4] == b"RIFF"
+        && &header[8..12] == b"WEBP"
+        && &header[12..16] == b"VP8X"
+        && header[20] & 0x02 != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut bytes = (data.len() as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(kind);
+        bytes.extend_from_slice(data);
+        bytes.extend_from_slice(&[0; 4]);
+        bytes
+    }
+
+    fn write_temp(name: &str, bytes: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("lwe-animated-{}-{name}", std::process::id()));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_png_is_animated_only_with_actl_before_idat() {
+        let mut still = PNG_SIGNATURE.to_vec();
+        still.extend(chunk(b"IHDR", &[0; 13]));
+        still.extend(chunk(b"IDAT", &[0; 4]));
+        still.extend(chunk(b"acTL", &[0; 8]));
+
+        let mut animated = PNG_SIGNATURE.to_vec();
+        animated.extend(chunk(b"IHDR", &[0; 13]));
+        animated.extend(chunk(b"acTL", &[0; 8]));
+        animated.extend(chunk(b"IDAT", &[0; 4]));
+
+        let still = write_temp("still.png", &still);
+        let animated = write_temp("animated.png", &animated);
+        assert_eq!(animated_format(&still), None);
+        assert_eq!(animated_format(&animated), Some(AnimatedFormat::Apng));
+        assert!(is_still_image(&still));
+        assert!(!is_still_image(&animated));
+
+        let _ = std::fs::remove_file(still);
+        let _ = std::fs::remove_file(animated);
+    }
+
+    #[test]
+    fn test_webp_animation_flag_and_gif_header() {
+        let mut webp = b"RIFF\0\0\0\0WEBPVP8X\x0a\0\0\0".to_vec();
+        webp.push(0x02);
+        assert!(webp_has_animation(&webp));
+        webp[20] = 0x10;
+        assert!(!webp_has_animation(&webp));
+
+        let gif = write_temp("loop.gif", b"GIF89a\x01\0\x01\0");
+        assert_eq!(animated_format(&gif), Some(AnimatedFormat::Gif));
+        assert!(!is_still_image(&gif));
+        let _ = std::fs::remove_file(gif);
+    }
+
+    #[test]
+    fn test_still_images_are_sniffed_not_named() {
+        let jpeg = write_temp("photo.png", b"\xff\xd8\xff\xe0\0\x10JFIF\0");
+        let webp = write_temp("still.webp", b"RIFF\0\0\0\0WEBPVP8 \0\0\0\0");
+        let video = write_temp("clip.jpg", b"\0\0\0\x20ftypisom\0\0\x02\0");
+
+        assert!(is_still_image(&jpeg));
+        assert!(is_still_image(&webp));
+        assert!(!is_still_image(&video));
+        assert!(!is_still_image(std::path::Path::new(
+            "/nonexistent/lwe.jpg"
+        )));
+
+        for path in [jpeg, webp, video] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -5,6 +5,11 @@
 
 mod pattern;
 mod types;
+mod validate;
 
 pub use pattern::matches_pattern;
 pub use types::*;
+pub use validate::{
+    deserialize_noting_unknown, pattern_problem, validate_app_settings, validate_config,
+    yaml_key_line, ConfigIssue, IssueSeverity,
+};
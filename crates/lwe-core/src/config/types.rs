@@ -5,12 +5,15 @@
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use crate::hdr::{HdrMode, ToneMappingConfig};
-use crate::types::{LayoutMode, RenderBackend, VideoSource};
+use crate::types::{
+    ColorAdjust, HwdecMode, LayoutMode, RenderBackend, SurfaceLayer, SurfaceMargins, VideoSource,
+};
 
 use super::pattern::matches_pattern;
 
@@ -44,6 +47,10 @@ pub struct Config {
     #[serde(default)]
     pub volume: f64,
 
+    /// Audio output device as named by mpv (None = system default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_device: Option<String>,
+
     /// Enable hardware decoding
     #[serde(default = "default_hwdec")]
     pub hwdec: bool,
@@ -60,6 +67,14 @@ pub struct Config {
     #[serde(default)]
     pub render_backend: RenderBackend,
 
+    /// Color grading LUT (.cube) applied in the final pass
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lut: Option<PathBuf>,
+
+    /// Raw mpv options set after the built-in ones, e.g. `scale: ewa_lanczossharp`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mpv_options: BTreeMap<String, String>,
+
     /// Per-output overrides (keyed by output name)
     #[serde(default)]
     pub per_output: HashMap<String, OutputConfig>,
@@ -93,6 +108,33 @@ pub struct OutputConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub volume: Option<f64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_device: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lut: Option<PathBuf>,
+
+    /// Brightness, contrast, gamma and saturation of this output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<ColorAdjust>,
+
+    /// Layer-shell layer of this output's wallpaper surface
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<SurfaceLayer>,
+
+    /// Exclusive zone of the surface (-1 = cover panels, 0 = stay clear of
+    /// them, >0 = reserve that many pixels)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclusive_zone: Option<i32>,
+
+    /// Space left free around the surface, e.g. for a dock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub margins: Option<SurfaceMargins>,
+
+    /// Raw mpv options added to or replacing the global ones
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mpv_options: BTreeMap<String, String>,
 }
 
 /// Power saving configuration
@@ -117,6 +159,140 @@ pub struct PowerConfig {
     /// Maximum number of texture buffers in pool
     #[serde(default = "default_max_buffers")]
     pub max_buffers: usize,
+
+    /// systemd `CPUQuota=` for the wallpaper process in percent of one core (0 = unlimited)
+    #[serde(default)]
+    pub cpu_quota_percent: u32,
+
+    /// systemd `MemoryHigh=` for the wallpaper process in MB (0 = unlimited)
+    #[serde(default)]
+    pub memory_high_mb: usize,
+
+    /// Pause after this many seconds without input, and while the
+    /// screensaver or lock screen is active (0 = never)
+    #[serde(default)]
+    pub pause_on_idle_seconds: u32,
+
+    /// Cheaper playback on battery; `pause_on_battery` takes precedence
+    #[serde(default)]
+    pub battery_profile: BatteryProfile,
+
+    /// Nice level of the engine and its decoders, -20 to 19 (None = inherited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nice: Option<i32>,
+
+    /// Schedule the engine with `SCHED_IDLE`, so it only runs when nothing
+    /// else wants the CPU
+    #[serde(default)]
+    pub idle_priority: bool,
+
+    /// Cores the engine and its decoders may run on
+    #[serde(default)]
+    pub cpu_affinity: CpuAffinity,
+}
+
+/// What changes while the machine runs on battery, instead of a full pause.
+/// Everything switches back on AC power.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BatteryProfile {
+    /// FPS cap for decoding and drawing (0 = keep the usual limit)
+    #[serde(default)]
+    pub fps: u32,
+
+    /// Hardware decoding mode (None = keep the usual mode)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hwdec: Option<HwdecMode>,
+
+    /// Stop decoding and keep the current frame on screen as a still
+    #[serde(default)]
+    pub static_fallback: bool,
+}
+
+impl BatteryProfile {
+    /// Lower of the usual FPS cap and the battery one (None = unlimited)
+    pub fn cap_fps(&self, fps_limit: Option<u32>) -> Option<u32> {
+        let fps_limit = fps_limit.filter(|&fps| fps > 0);
+        match (fps_limit, Some(self.fps).filter(|&fps| fps > 0)) {
+            (Some(limit), Some(battery)) => Some(limit.min(battery)),
+            (limit, battery) => limit.or(battery),
+        }
+    }
+}
+
+/// Which cores the engine may run on: `all`, `efficiency` or a core list
+/// such as `0-3,8`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum CpuAffinity {
+    /// Any core (default)
+    #[default]
+    All,
+    /// Only the efficiency cores of a hybrid CPU; no change elsewhere
+    EfficiencyCores,
+    /// An explicit core list
+    Cores(Vec<usize>),
+}
+
+impl CpuAffinity {
+    /// Parse a kernel CPU list such as `0-3,8,10-11`
+    pub fn parse_cpu_list(list: &str) -> Option<Vec<usize>> {
+        let mut cores = Vec::new();
+        for part in list.trim().split(',').filter(|part| !part.is_empty()) {
+            match part.split_once('-') {
+                Some((first, last)) => {
+                    let first = first.trim().parse::<usize>().ok()?;
+                    let last = last.trim().parse::<usize>().ok()?;
+                    if first > last {
+                        return None;
+                    }
+                    cores.extend(first..=last);
+                }
+                None => cores.push(part.trim().parse().ok()?),
+            }
+        }
+        Some(cores)
+    }
+}
+
+impl std::str::FromStr for CpuAffinity {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "all" | "" => Ok(Self::All),
+            "efficiency" | "e-cores" | "ecores" => Ok(Self::EfficiencyCores),
+            list => Self::parse_cpu_list(list)
+                .filter(|cores| !cores.is_empty())
+                .map(Self::Cores)
+                .ok_or_else(|| {
+                    format!(
+                        "unknown CPU affinity `{value}` (expected all, efficiency or a core list like 0-3,8)"
+                    )
+                }),
+        }
+    }
+}
+
+impl TryFrom<String> for CpuAffinity {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<CpuAffinity> for String {
+    fn from(affinity: CpuAffinity) -> Self {
+        match affinity {
+            CpuAffinity::All => "all".to_string(),
+            CpuAffinity::EfficiencyCores => "efficiency".to_string(),
+            CpuAffinity::Cores(cores) => cores
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
 }
 
 impl Default for PowerConfig {
@@ -127,10 +303,25 @@ impl Default for PowerConfig {
             max_fps: 0,
             max_memory_mb: default_max_memory_mb(),
             max_buffers: default_max_buffers(),
+            cpu_quota_percent: 0,
+            memory_high_mb: 0,
+            pause_on_idle_seconds: 0,
+            battery_profile: BatteryProfile::default(),
+            nice: None,
+            idle_priority: false,
+            cpu_affinity: CpuAffinity::All,
         }
     }
 }
 
+impl PowerConfig {
+    /// Inactivity after which playback pauses, if idle pausing is enabled
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        (self.pause_on_idle_seconds > 0)
+            .then(|| Duration::from_secs(u64::from(self.pause_on_idle_seconds)))
+    }
+}
+
 impl Config {
     /// Load configuration from YAML file
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
@@ -176,22 +367,48 @@ impl Config {
 
     /// Update source for a specific output and save
     pub fn set_output_source(&mut self, output_name: &str, source: VideoSource) {
-        if let Some(output_config) = self.per_output.get_mut(output_name) {
-            output_config.source = Some(source);
-        } else {
-            self.per_output.insert(
-                output_name.to_string(),
-                OutputConfig {
-                    priority: default_priority(),
-                    source: Some(source),
-                    layout: None,
-                    start_time: None,
-                    playback_rate: None,
-                    mute: None,
-                    volume: None,
-                },
-            );
-        }
+        self.output_config_mut(output_name).source = Some(source);
+    }
+
+    /// Store an output's volume (0.0 - 1.0)
+    pub fn set_output_volume(&mut self, output_name: &str, volume: f64) {
+        self.output_config_mut(output_name).volume = Some(volume.clamp(0.0, 1.0));
+    }
+
+    /// Store whether an output is muted
+    pub fn set_output_mute(&mut self, output_name: &str, mute: bool) {
+        self.output_config_mut(output_name).mute = Some(mute);
+    }
+
+    /// Store an output's audio device (None = follow the global device)
+    pub fn set_output_audio_device(&mut self, output_name: &str, device: Option<String>) {
+        self.output_config_mut(output_name).audio_device = device;
+    }
+
+    /// Store an output's color correction (None = leave colors unchanged)
+    pub fn set_output_color(&mut self, output_name: &str, color: Option<ColorAdjust>) {
+        self.output_config_mut(output_name).color = color.filter(|color| !color.is_neutral());
+    }
+
+    fn output_config_mut(&mut self, output_name: &str) -> &mut OutputConfig {
+        self.per_output
+            .entry(output_name.to_string())
+            .or_insert_with(|| OutputConfig {
+                priority: default_priority(),
+                source: None,
+                layout: None,
+                start_time: None,
+                playback_rate: None,
+                mute: None,
+                volume: None,
+                audio_device: None,
+                lut: None,
+                color: None,
+                layer: None,
+                exclusive_zone: None,
+                margins: None,
+                mpv_options: BTreeMap::new(),
+            })
     }
 
     /// Validate and fix configuration values
@@ -249,10 +466,17 @@ impl Config {
                 playback_rate: base.playback_rate,
                 mute: base.mute,
                 volume: base.volume,
+                audio_device: base.audio_device,
                 hwdec: base.hwdec,
                 hdr_mode: base.hdr_mode,
                 tone_mapping: base.tone_mapping.clone(),
                 render_backend: base.render_backend,
+                lut: base.lut,
+                color: ColorAdjust::default(),
+                layer: SurfaceLayer::default(),
+                exclusive_zone: None,
+                margins: SurfaceMargins::default(),
+                mpv_options: base.mpv_options,
                 power: base.power,
             };
         }
@@ -261,6 +485,8 @@ impl Config {
         matches.sort_by_key(|(_, _, score)| *score);
 
         let (_, override_cfg, _) = matches[0];
+        let mut mpv_options = base.mpv_options;
+        mpv_options.extend(override_cfg.mpv_options.clone());
         EffectiveConfig {
             source: override_cfg.source.clone().unwrap_or(base.source),
             layout: override_cfg.layout.unwrap_or(base.layout),
@@ -269,10 +495,17 @@ impl Config {
             playback_rate: override_cfg.playback_rate.unwrap_or(base.playback_rate),
             mute: override_cfg.mute.unwrap_or(base.mute),
             volume: override_cfg.volume.unwrap_or(base.volume),
+            audio_device: override_cfg.audio_device.clone().or(base.audio_device),
             hwdec: base.hwdec,
             hdr_mode: base.hdr_mode,
             tone_mapping: base.tone_mapping.clone(),
             render_backend: base.render_backend,
+            lut: override_cfg.lut.clone().or(base.lut),
+            color: override_cfg.color.unwrap_or_default(),
+            layer: override_cfg.layer.unwrap_or_default(),
+            exclusive_zone: override_cfg.exclusive_zone,
+            margins: override_cfg.margins.unwrap_or_default(),
+            mpv_options,
             power: base.power.clone(),
         }
     }
@@ -288,10 +521,19 @@ pub struct EffectiveConfig {
     pub playback_rate: f64,
     pub mute: bool,
     pub volume: f64,
+    pub audio_device: Option<String>,
     pub hwdec: bool,
     pub hdr_mode: HdrMode,
     pub tone_mapping: ToneMappingConfig,
     pub render_backend: RenderBackend,
+    pub lut: Option<PathBuf>,
+    pub color: ColorAdjust,
+    pub layer: SurfaceLayer,
+    /// None = the compositor workaround's default
+    pub exclusive_zone: Option<i32>,
+    pub margins: SurfaceMargins,
+    /// Global mpv options with the output's own over them
+    pub mpv_options: BTreeMap<String, String>,
     pub power: PowerConfig,
 }
 
@@ -328,7 +570,7 @@ fn default_max_buffers() -> usize {
     8
 }
 
-fn default_priority() -> u32 {
+pub(super) fn default_priority() -> u32 {
     50
 }
 
@@ -348,6 +590,54 @@ source:
         assert_eq!(config.layout, LayoutMode::Fill);
     }
 
+    #[test]
+    fn test_pause_on_idle_is_off_by_default() {
+        let power: PowerConfig = serde_yaml::from_str("pause_on_battery: true").unwrap();
+        assert_eq!(power.idle_timeout(), None);
+
+        let power: PowerConfig = serde_yaml::from_str("pause_on_idle_seconds: 300").unwrap();
+        assert_eq!(power.idle_timeout(), Some(Duration::from_secs(300)));
+    }
+
+    #[test]
+    fn test_battery_profile_caps_fps_below_the_usual_limit() {
+        let power: PowerConfig = serde_yaml::from_str(
+            "battery_profile:\n  fps: 15\n  hwdec: Force\n  static_fallback: false\n",
+        )
+        .unwrap();
+        let profile = power.battery_profile;
+        assert_eq!(profile.hwdec, Some(HwdecMode::Force));
+        assert_eq!(profile.cap_fps(None), Some(15));
+        assert_eq!(profile.cap_fps(Some(60)), Some(15));
+        assert_eq!(profile.cap_fps(Some(10)), Some(10));
+        assert_eq!(BatteryProfile::default().cap_fps(Some(0)), None);
+    }
+
+    #[test]
+    fn test_scheduling_leaves_nice_unset_by_default() {
+        let power: PowerConfig = serde_yaml::from_str("pause_on_battery: true").unwrap();
+        assert_eq!(power.nice, None);
+        assert!(!power.idle_priority);
+        assert_eq!(power.cpu_affinity, CpuAffinity::All);
+
+        let power: PowerConfig =
+            serde_yaml::from_str("nice: 15\nidle_priority: true\ncpu_affinity: 0-2,8\n").unwrap();
+        assert_eq!(power.nice, Some(15));
+        assert!(power.idle_priority);
+        assert_eq!(power.cpu_affinity, CpuAffinity::Cores(vec![0, 1, 2, 8]));
+        assert!(serde_yaml::from_str::<PowerConfig>("cpu_affinity: fast\n").is_err());
+    }
+
+    #[test]
+    fn test_cpu_affinity_parses_names_and_core_lists() {
+        assert_eq!("all".parse(), Ok(CpuAffinity::All));
+        assert_eq!("E-Cores".parse(), Ok(CpuAffinity::EfficiencyCores));
+        assert_eq!("0-2,8".parse(), Ok(CpuAffinity::Cores(vec![0, 1, 2, 8])));
+        assert!("3-1".parse::<CpuAffinity>().is_err());
+        assert!("fast".parse::<CpuAffinity>().is_err());
+        assert_eq!(String::from(CpuAffinity::Cores(vec![0, 3])), "0,3");
+    }
+
     #[test]
     fn test_per_output_override() {
         let yaml = r#"
@@ -365,4 +655,104 @@ per_output:
         assert_eq!(effective.layout, LayoutMode::Contain);
         assert_eq!(effective.start_time, 5.0);
     }
+
+    #[test]
+    fn test_per_output_surface_placement() {
+        let yaml = r#"
+source:
+  type: File
+  path: "/default.mp4"
+per_output:
+  DP-1:
+    layer: bottom
+    exclusive_zone: 0
+    margins:
+      bottom: 48
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let effective = config.for_output("DP-1");
+        assert_eq!(effective.layer, SurfaceLayer::Bottom);
+        assert_eq!(effective.exclusive_zone, Some(0));
+        assert_eq!(
+            effective.margins,
+            SurfaceMargins {
+                bottom: 48,
+                ..SurfaceMargins::default()
+            }
+        );
+
+        let other = config.for_output("HDMI-A-1");
+        assert_eq!(other.layer, SurfaceLayer::Background);
+        assert_eq!(other.exclusive_zone, None);
+    }
+
+    #[test]
+    fn test_per_output_mpv_options_extend_global() {
+        let yaml = r#"
+source:
+  type: File
+  path: "/default.mp4"
+mpv_options:
+  scale: spline36
+  video-sync: display-resample
+per_output:
+  DP-1:
+    mpv_options:
+      scale: ewa_lanczossharp
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let effective = config.for_output("DP-1");
+        assert_eq!(effective.mpv_options["scale"], "ewa_lanczossharp");
+        assert_eq!(effective.mpv_options["video-sync"], "display-resample");
+        assert_eq!(
+            config.for_output("HDMI-A-1").mpv_options["scale"],
+            "spline36"
+        );
+    }
+
+    #[test]
+    fn test_per_output_lut_overrides_global() {
+        let yaml = r#"
+source:
+  type: File
+  path: "/default.mp4"
+lut: "/luts/global.cube"
+per_output:
+  DP-*:
+    lut: "/luts/warm.cube"
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config.for_output("DP-1").lut,
+            Some(PathBuf::from("/luts/warm.cube"))
+        );
+        assert_eq!(
+            config.for_output("HDMI-A-1").lut,
+            Some(PathBuf::from("/luts/global.cube"))
+        );
+    }
+
+    #[test]
+    fn test_output_audio_is_stored_per_output() {
+        let yaml = r#"
+source:
+  type: File
+  path: "/default.mp4"
+volume: 0.3
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        config.set_output_volume("DP-1", 1.4);
+        config.set_output_mute("DP-1", false);
+        config.set_output_audio_device("DP-1", Some("pulse/hdmi".to_string()));
+
+        let effective = config.for_output("DP-1");
+        assert_eq!(effective.volume, 1.0);
+        assert!(!effective.mute);
+        assert_eq!(effective.audio_device.as_deref(), Some("pulse/hdmi"));
+
+        let effective = config.for_output("HDMI-A-1");
+        assert_eq!(effective.volume, 0.3);
+        assert!(effective.mute);
+        assert_eq!(effective.audio_device, None);
+    }
 }
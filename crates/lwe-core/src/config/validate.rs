@@ -0,0 +1,425 @@
+//! Validation of hand-edited configuration files
+//!
+//! The loaders accept unknown keys so files written by newer releases still
+//! load, and a typo in a key is silently ignored. These checks parse a file
+//! against the real types and report what the loader would ignore or refuse,
+//! each with the line it is on: unknown keys, values a type does not accept
+//! (e.g. a misspelled layout), files that do not exist and per-output
+//! patterns that cannot match.
+
+use std::fmt;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::types::{default_priority, Config};
+use crate::settings::AppSettings;
+use crate::types::VideoSource;
+
+/// How much a problem matters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSeverity {
+    /// The file is refused, or the value is ignored
+    Error,
+    /// The file loads, but probably not as intended
+    Warning,
+}
+
+/// One problem found in a configuration file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigIssue {
+    pub severity: IssueSeverity,
+    /// 1-based line (None = not tied to a line)
+    pub line: Option<usize>,
+    /// Dotted path of the key, e.g. `per_output.HDMI-*.layout`
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    pub fn error(line: Option<usize>, key: Option<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Error,
+            line,
+            key,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(line: Option<usize>, key: Option<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Warning,
+            ..Self::error(line, key, message)
+        }
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {line}: ")?;
+        }
+        if let Some(key) = &self.key {
+            write!(f, "{key}: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Check a legacy `config.yaml`
+pub fn validate_config(text: &str) -> Vec<ConfigIssue> {
+    let (config, mut issues) = parse_yaml::<Config>(text);
+    let Some(config) = config else {
+        return issues;
+    };
+
+    issues.extend(missing_files(
+        text,
+        &[],
+        Some(&config.source),
+        config.lut.as_deref(),
+    ));
+
+    let mut patterns = config.per_output.iter().collect::<Vec<_>>();
+    patterns.sort_by_key(|(pattern, _)| pattern.as_str());
+    for (pattern, output) in patterns {
+        let key = ["per_output", pattern.as_str()];
+        let line = yaml_key_line(text, &key);
+        if let Some(problem) = pattern_problem(pattern) {
+            issues.push(ConfigIssue::error(line, Some(key.join(".")), problem));
+        } else if !is_pattern(pattern) && output.priority != default_priority() {
+            issues.push(ConfigIssue::warning(
+                line,
+                Some(key.join(".")),
+                "priority only orders wildcard patterns; an exact output name always wins",
+            ));
+        }
+        issues.extend(missing_files(
+            text,
+            &key,
+            output.source.as_ref(),
+            output.lut.as_deref(),
+        ));
+        if let Some(Err(reason)) = output.color.map(|color| color.check()) {
+            let key = ["per_output", pattern.as_str(), "color"];
+            issues.push(ConfigIssue::error(
+                yaml_key_line(text, &key),
+                Some(key.join(".")),
+                reason,
+            ));
+        }
+    }
+
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+/// Check a `settings.yaml`
+pub fn validate_app_settings(text: &str) -> Vec<ConfigIssue> {
+    let (settings, mut issues) = parse_yaml::<AppSettings>(text);
+    let Some(settings) = settings else {
+        return issues;
+    };
+
+    for (index, folder) in settings.wallpaper_folders.iter().enumerate() {
+        if !expand_home(folder).is_dir() {
+            issues.push(ConfigIssue::error(
+                yaml_item_line(text, "wallpaper_folders", index),
+                Some(format!("wallpaper_folders.{index}")),
+                format!("{} is not a folder", folder.display()),
+            ));
+        }
+    }
+    if let Some(steam) = settings
+        .steam_path
+        .as_ref()
+        .filter(|steam| !expand_home(steam).is_dir())
+    {
+        issues.push(ConfigIssue::error(
+            yaml_key_line(text, &["steam_path"]),
+            Some("steam_path".to_string()),
+            format!("{} is not a folder", steam.display()),
+        ));
+    }
+
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+/// What is wrong with a `per_output` pattern, if anything; patterns support
+/// only `*` and `?`
+pub fn pattern_problem(pattern: &str) -> Option<String> {
+    if pattern.trim().is_empty() {
+        return Some("an empty pattern matches no output".to_string());
+    }
+    if pattern.trim() != pattern {
+        return Some("output names have no surrounding spaces".to_string());
+    }
+    if pattern.contains(".*") {
+        return Some("patterns are not regular expressions; use `*` instead of `.*`".to_string());
+    }
+    pattern
+        .chars()
+        .find(|c| matches!(c, '[' | ']' | '{' | '}' | '(' | ')' | '|' | '^' | '$' | '+'))
+        .map(|c| format!("`{c}` is not supported in patterns; only `*` and `?` are wildcards"))
+}
+
+fn is_pattern(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// Deserialize `T`, collecting the path of every key `T` does not know
+pub fn deserialize_noting_unknown<'de, T, D>(
+    deserializer: D,
+) -> (Result<T, D::Error>, Vec<Vec<String>>)
+where
+    T: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    let mut unknown = Vec::new();
+    let parsed = serde_ignored::deserialize(deserializer, |path| {
+        unknown.push(path_segments(&path));
+    });
+    (parsed, unknown)
+}
+
+/// Parse `text` as `T`, noting every key `T` does not know and the error
+/// that stops parsing, if any
+fn parse_yaml<T: DeserializeOwned>(text: &str) -> (Option<T>, Vec<ConfigIssue>) {
+    let (parsed, unknown) = deserialize_noting_unknown(serde_yaml::Deserializer::from_str(text));
+    let mut issues = unknown
+        .into_iter()
+        .map(|key| {
+            ConfigIssue::warning(
+                yaml_key_line(text, &key.iter().map(String::as_str).collect::<Vec<_>>()),
+                Some(key.join(".")),
+                "unknown key, ignored",
+            )
+        })
+        .collect::<Vec<_>>();
+
+    match parsed {
+        Ok(parsed) => (Some(parsed), issues),
+        Err(error) => {
+            let message = error.to_string();
+            // The location is reported separately
+            let message = match message.rsplit_once(" at line ") {
+                Some((message, _)) => message.to_string(),
+                None => message,
+            };
+            issues.push(ConfigIssue::error(
+                error.location().map(|location| location.line()),
+                None,
+                message,
+            ));
+            (None, issues)
+        }
+    }
+}
+
+fn path_segments(path: &serde_ignored::Path<'_>) -> Vec<String> {
+    use serde_ignored::Path;
+
+    match path {
+        Path::Root => Vec::new(),
+        Path::Seq { parent, index } => {
+            let mut segments = path_segments(parent);
+            segments.push(index.to_string());
+            segments
+        }
+        Path::Map { parent, key } => {
+            let mut segments = path_segments(parent);
+            segments.push(key.clone());
+            segments
+        }
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => path_segments(parent),
+    }
+}
+
+/// A source or LUT under `parent` that points at a missing file
+fn missing_files(
+    text: &str,
+    parent: &[&str],
+    source: Option<&VideoSource>,
+    lut: Option<&Path>,
+) -> Vec<ConfigIssue> {
+    let issue = |key: &str, message: String| {
+        let key = [parent, &[key]].concat();
+        ConfigIssue::error(yaml_key_line(text, &key), Some(key.join(".")), message)
+    };
+
+    let mut issues = Vec::new();
+    if let Some(message) = source.and_then(missing_source) {
+        issues.push(issue("source", message));
+    }
+    if let Some(lut) = lut.filter(|lut| !expand_home(lut).exists()) {
+        issues.push(issue("lut", format!("{} does not exist", lut.display())));
+    }
+    issues
+}
+
+/// The source's file or folder, when it is local and missing
+fn missing_source(source: &VideoSource) -> Option<String> {
+    let path = source.get_mpv_path();
+    let local = !source.is_streaming()
+        && !matches!(source, VideoSource::Pipe { .. })
+        && !path.contains("://");
+    (local && !Path::new(&path).exists()).then(|| format!("{path} does not exist"))
+}
+
+fn expand_home(path: &Path) -> std::path::PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// 1-based line of the key at `path` in block-style YAML, found by following
+/// indentation; sequence indices are skipped, so a key inside a list is
+/// found in its first item
+pub fn yaml_key_line(text: &str, path: &[&str]) -> Option<usize> {
+    let mut line = None;
+    let mut parent_indent = None;
+    let mut start = 0;
+
+    for segment in path
+        .iter()
+        .filter(|segment| segment.parse::<usize>().is_err())
+    {
+        let (index, indent) = text
+            .lines()
+            .enumerate()
+            .skip(start)
+            .map(|(index, line)| (index, line, line.trim_start()))
+            .filter(|(_, _, trimmed)| !trimmed.is_empty() && !trimmed.starts_with('#'))
+            .map(|(index, line, trimmed)| (index, line.len() - trimmed.len(), trimmed))
+            .take_while(|(_, indent, _)| parent_indent.is_none_or(|parent| *indent > parent))
+            .find(|(_, _, trimmed)| yaml_key(trimmed) == Some(segment))
+            .map(|(index, indent, _)| (index, indent))?;
+        line = Some(index + 1);
+        parent_indent = Some(indent);
+        start = index + 1;
+    }
+    line
+}
+
+/// 1-based line of item `index` of the block sequence under `key`
+fn yaml_item_line(text: &str, key: &str, index: usize) -> Option<usize> {
+    let start = yaml_key_line(text, &[key])?;
+    text.lines()
+        .enumerate()
+        .skip(start)
+        .filter(|(_, line)| line.trim_start().starts_with("- "))
+        .nth(index)
+        .map(|(line, _)| line + 1)
+}
+
+/// The key a block mapping line starts with, without quotes
+fn yaml_key(line: &str) -> Option<&str> {
+    let line = line.strip_prefix("- ").unwrap_or(line);
+    let (key, _) = line
+        .split_once(": ")
+        .or_else(|| line.strip_suffix(':').map(|key| (key, "")))?;
+    let key = key.trim();
+    Some(
+        key.strip_prefix('"')
+            .and_then(|key| key.strip_suffix('"'))
+            .or_else(|| {
+                key.strip_prefix('\'')
+                    .and_then(|key| key.strip_suffix('\''))
+            })
+            .unwrap_or(key),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_reports_unknown_keys_and_bad_patterns_by_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let video = dir.path().join("rain.mp4");
+        std::fs::write(&video, b"").unwrap();
+        let yaml = format!(
+            "source:\n  type: File\n  path: {}\nlayuot: Fill\nper_output:\n  \"HDMI-.*\":\n    layout: Contain\n  DP-1:\n    priority: 5\n    source:\n      type: File\n      path: /missing/clip.mp4\n",
+            video.display()
+        );
+
+        assert_eq!(
+            validate_config(&yaml),
+            vec![
+                ConfigIssue::warning(Some(4), Some("layuot".to_string()), "unknown key, ignored"),
+                ConfigIssue::error(
+                    Some(6),
+                    Some("per_output.HDMI-.*".to_string()),
+                    "patterns are not regular expressions; use `*` instead of `.*`",
+                ),
+                ConfigIssue::warning(
+                    Some(8),
+                    Some("per_output.DP-1".to_string()),
+                    "priority only orders wildcard patterns; an exact output name always wins",
+                ),
+                ConfigIssue::error(
+                    Some(10),
+                    Some("per_output.DP-1.source".to_string()),
+                    "/missing/clip.mp4 does not exist",
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_reports_a_bad_enum_value_at_its_line() {
+        let issues = validate_config(
+            "source:\n  type: Url\n  url: https://example.com/a.mp4\nlayout: Fil\n",
+        );
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+        assert_eq!(issues[0].line, Some(4));
+        assert!(issues[0].message.contains("unknown variant `Fil`"));
+
+        let issues = validate_config(
+            "source:\n  type: Url\n  url: https://example.com/a.mp4\nper_output:\n  DP-1:\n    color:\n      gamma: 3\n",
+        );
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, Some(6));
+        assert_eq!(issues[0].message, "gamma is 0 to 2, not 3");
+    }
+
+    #[test]
+    fn test_settings_report_missing_folders() {
+        let issues = validate_app_settings(
+            "language: en\nwallpaper_folders:\n  - /missing/walls\nthemee: dark\n",
+        );
+        assert_eq!(
+            issues,
+            vec![
+                ConfigIssue::error(
+                    Some(3),
+                    Some("wallpaper_folders.0".to_string()),
+                    "/missing/walls is not a folder",
+                ),
+                ConfigIssue::warning(Some(4), Some("themee".to_string()), "unknown key, ignored"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_lines_follow_indentation() {
+        let yaml = "a:\n  layout: x\nper_output:\n  # comment\n  'DP-1':\n    layout: y\n";
+        assert_eq!(
+            yaml_key_line(yaml, &["per_output", "DP-1", "layout"]),
+            Some(6)
+        );
+        assert_eq!(yaml_key_line(yaml, &["a", "layout"]), Some(2));
+        assert_eq!(yaml_key_line(yaml, &["a", "missing"]), None);
+        assert_eq!(pattern_problem("HDMI-*"), None);
+        assert!(pattern_problem("DP-[12]").is_some());
+        assert!(pattern_problem("").is_some());
+    }
+}
@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::types::OutputHdrCapabilities;
+
 /// Color space of video content
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
 pub enum ColorSpace {
@@ -127,6 +129,32 @@ pub enum HdrMode {
     Disable,
 }
 
+impl HdrMode {
+    /// How a video with `metadata` is presented on an output with `output`
+    /// capabilities. `Auto` passes HDR video through to displays that accept
+    /// PQ and tone-maps it for the rest; `Force` always tone-maps.
+    pub fn output_for(self, metadata: &HdrMetadata, output: &OutputHdrCapabilities) -> HdrOutput {
+        match self {
+            HdrMode::Disable => HdrOutput::Sdr,
+            HdrMode::Force => HdrOutput::ToneMapped,
+            HdrMode::Auto if !metadata.is_hdr() => HdrOutput::Sdr,
+            HdrMode::Auto if output.accepts_pq() => HdrOutput::Passthrough,
+            HdrMode::Auto => HdrOutput::ToneMapped,
+        }
+    }
+}
+
+/// How a video is presented on its output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HdrOutput {
+    /// Played with mpv's default SDR output
+    Sdr,
+    /// Tone-mapped to SDR with the configured tone mapping
+    ToneMapped,
+    /// Sent to the compositor as BT.2020 PQ for an HDR display
+    Passthrough,
+}
+
 /// Tone mapping algorithm
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -329,6 +357,41 @@ mod tests {
         assert_eq!(metadata.format_description(), "HDR10");
     }
 
+    #[test]
+    fn test_hdr_output_passes_through_only_to_pq_displays() {
+        let hdr10 = HdrMetadata {
+            color_space: ColorSpace::Hdr10,
+            transfer_function: TransferFunction::Pq,
+            ..Default::default()
+        };
+        let sdr = HdrMetadata::default();
+        let sdr_display = OutputHdrCapabilities::default();
+        let hdr_display = OutputHdrCapabilities {
+            hdr_supported: true,
+            max_luminance: Some(1000.0),
+            min_luminance: Some(0.005),
+            supported_eotf: vec![TransferFunction::Srgb, TransferFunction::Pq],
+        };
+
+        assert_eq!(
+            HdrMode::Auto.output_for(&hdr10, &hdr_display),
+            HdrOutput::Passthrough
+        );
+        assert_eq!(
+            HdrMode::Auto.output_for(&hdr10, &sdr_display),
+            HdrOutput::ToneMapped
+        );
+        assert_eq!(HdrMode::Auto.output_for(&sdr, &hdr_display), HdrOutput::Sdr);
+        assert_eq!(
+            HdrMode::Force.output_for(&hdr10, &hdr_display),
+            HdrOutput::ToneMapped
+        );
+        assert_eq!(
+            HdrMode::Disable.output_for(&hdr10, &hdr_display),
+            HdrOutput::Sdr
+        );
+    }
+
     #[test]
     fn test_tone_mapping_validation() {
         let mut config = ToneMappingConfig {
@@ -14,7 +14,13 @@ pub struct LayoutTransform {
     pub dst_rect: (i32, i32, i32, i32),
 }
 
+/// Rectangle on the desktop in logical coordinates (x, y, width, height)
+pub type SpanRect = (f64, f64, f64, f64);
+
 /// Calculate layout transform for rendering video to output
+///
+/// `Span` without a canvas covers the output like `Fill`; see
+/// [`calculate_span_layout`] for the part of a spanned frame an output shows.
 #[inline]
 pub fn calculate_layout(
     mode: LayoutMode,
@@ -27,7 +33,7 @@ pub fn calculate_layout(
     let output_aspect = output_width as f64 / output_height as f64;
 
     match mode {
-        LayoutMode::Fill | LayoutMode::Cover => {
+        LayoutMode::Fill | LayoutMode::Cover | LayoutMode::Span => {
             // Scale to cover entire output, crop video if needed
             if video_aspect > output_aspect {
                 // Video is wider - crop sides
@@ -54,12 +60,14 @@ pub fn calculate_layout(
             }
         }
 
-        LayoutMode::Contain => {
+        LayoutMode::Contain | LayoutMode::AmbientFill => {
             // Scale to fit inside output, letterbox if needed
             if video_aspect > output_aspect {
                 // Video is wider - letterbox top/bottom
                 let scale = output_width as f64 / video_width as f64;
-                let scaled_height = (video_height as f64 * scale) as i32;
+                // Rounded so exact fits survive float error, and at least one
+                // pixel so extreme banners do not vanish
+                let scaled_height = ((video_height as f64 * scale).round() as i32).max(1);
                 let offset_y = (output_height - scaled_height) / 2;
 
                 LayoutTransform {
@@ -69,7 +77,7 @@ pub fn calculate_layout(
             } else {
                 // Video is taller - letterbox left/right
                 let scale = output_height as f64 / video_height as f64;
-                let scaled_width = (video_width as f64 * scale) as i32;
+                let scaled_width = ((video_width as f64 * scale).round() as i32).max(1);
                 let offset_x = (output_width - scaled_width) / 2;
 
                 LayoutTransform {
@@ -100,6 +108,54 @@ pub fn calculate_layout(
     }
 }
 
+/// Calculate the part of a frame spanned across several outputs that one of them shows
+///
+/// The frame covers `canvas`, the bounding box of every spanning output,
+/// like `Fill` covers a single output. `output` is this output's place on
+/// the canvas; both are logical, so outputs with different scales line up
+/// and each samples the frame at its own buffer size.
+pub fn calculate_span_layout(
+    video_width: i32,
+    video_height: i32,
+    canvas: SpanRect,
+    output: SpanRect,
+    output_width: i32,
+    output_height: i32,
+) -> LayoutTransform {
+    let (canvas_x, canvas_y, canvas_width, canvas_height) = canvas;
+    let (output_x, output_y, logical_width, logical_height) = output;
+    let scale =
+        (canvas_width / f64::from(video_width)).max(canvas_height / f64::from(video_height));
+    let scaled_width = f64::from(video_width) * scale;
+    let scaled_height = f64::from(video_height) * scale;
+    // The frame is centred on the canvas and cropped evenly like Fill
+    let frame_x = canvas_x + (canvas_width - scaled_width) / 2.0;
+    let frame_y = canvas_y + (canvas_height - scaled_height) / 2.0;
+
+    let width = (logical_width / scaled_width).clamp(0.0, 1.0);
+    let height = (logical_height / scaled_height).clamp(0.0, 1.0);
+    LayoutTransform {
+        src_rect: (
+            ((output_x - frame_x) / scaled_width).clamp(0.0, 1.0 - width),
+            ((output_y - frame_y) / scaled_height).clamp(0.0, 1.0 - height),
+            width,
+            height,
+        ),
+        dst_rect: (0, 0, output_width, output_height),
+    }
+}
+
+/// Bounding box of the outputs spanning a wallpaper, None without outputs
+pub fn span_canvas(outputs: impl IntoIterator<Item = SpanRect>) -> Option<SpanRect> {
+    outputs
+        .into_iter()
+        .map(|(x, y, width, height)| (x, y, x + width, y + height))
+        .reduce(|(left, top, right, bottom), (x0, y0, x1, y1)| {
+            (left.min(x0), top.min(y0), right.max(x1), bottom.max(y1))
+        })
+        .map(|(left, top, right, bottom)| (left, top, right - left, bottom - top))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,6 +178,14 @@ mod tests {
         assert!(transform.dst_rect.3 < 768); // Shorter than output
     }
 
+    #[test]
+    fn test_ambient_fill_places_video_like_contain() {
+        let contain = calculate_layout(LayoutMode::Contain, 1080, 1920, 1920, 1080);
+        let ambient = calculate_layout(LayoutMode::AmbientFill, 1080, 1920, 1920, 1080);
+        assert_eq!(ambient.dst_rect, contain.dst_rect);
+        assert!(ambient.dst_rect.0 > 0); // Pillarboxed
+    }
+
     #[test]
     fn test_stretch() {
         let transform = calculate_layout(LayoutMode::Stretch, 1920, 1080, 1024, 768);
@@ -137,4 +201,204 @@ mod tests {
         assert_eq!(transform.dst_rect.0, (1920 - 800) / 2);
         assert_eq!(transform.dst_rect.1, (1080 - 600) / 2);
     }
+
+    #[test]
+    fn test_span_splits_the_frame_across_outputs() {
+        // Two 1920x1080 outputs side by side show the halves of a 32:9 frame
+        let canvas = (0.0, 0.0, 3840.0, 1080.0);
+        let left =
+            calculate_span_layout(3840, 1080, canvas, (0.0, 0.0, 1920.0, 1080.0), 1920, 1080);
+        let right = calculate_span_layout(
+            3840,
+            1080,
+            canvas,
+            (1920.0, 0.0, 1920.0, 1080.0),
+            1920,
+            1080,
+        );
+        assert_eq!(left.src_rect, (0.0, 0.0, 0.5, 1.0));
+        assert_eq!(right.src_rect, (0.5, 0.0, 0.5, 1.0));
+        assert_eq!(right.dst_rect, (0, 0, 1920, 1080));
+    }
+
+    #[test]
+    fn test_span_uses_logical_size_for_scaled_outputs() {
+        // A 2x HiDPI output next to a 1x one covers the same logical
+        // height, so both show the same rows of the frame
+        let canvas = (0.0, 0.0, 3840.0, 1080.0);
+        let hidpi =
+            calculate_span_layout(1920, 540, canvas, (0.0, 0.0, 1920.0, 1080.0), 3840, 2160);
+        let plain =
+            calculate_span_layout(1920, 540, canvas, (1920.0, 0.0, 1920.0, 1080.0), 1920, 1080);
+        assert_eq!(hidpi.src_rect.1, plain.src_rect.1);
+        assert_eq!(hidpi.src_rect.3, plain.src_rect.3);
+        assert_eq!(hidpi.dst_rect, (0, 0, 3840, 2160));
+    }
+
+    #[test]
+    fn test_span_canvas_bounds_offset_outputs() {
+        // A portrait monitor left of a landscape one set lower on the desktop
+        let canvas = span_canvas([(0.0, 0.0, 1080.0, 1920.0), (1080.0, 420.0, 2560.0, 1440.0)]);
+        assert_eq!(canvas, Some((0.0, 0.0, 3640.0, 1920.0)));
+        assert_eq!(span_canvas([]), None);
+    }
+
+    #[test]
+    fn test_span_alone_matches_fill() {
+        let span = calculate_layout(LayoutMode::Span, 1920, 1080, 1024, 768);
+        let fill = calculate_layout(LayoutMode::Fill, 1920, 1080, 1024, 768);
+        assert_eq!(span.src_rect, fill.src_rect);
+
+        let alone = calculate_span_layout(
+            1920,
+            1080,
+            (0.0, 0.0, 1024.0, 768.0),
+            (0.0, 0.0, 1024.0, 768.0),
+            1024,
+            768,
+        );
+        for (a, b) in [
+            (alone.src_rect.0, fill.src_rect.0),
+            (alone.src_rect.2, fill.src_rect.2),
+        ] {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// Largest frame or output edge the engine handles (8K and GL's common max texture)
+        const MAX_EDGE: i32 = 16384;
+
+        fn any_layout() -> impl Strategy<Value = LayoutMode> {
+            prop_oneof![
+                Just(LayoutMode::Fill),
+                Just(LayoutMode::Contain),
+                Just(LayoutMode::Stretch),
+                Just(LayoutMode::Cover),
+                Just(LayoutMode::Centre),
+                Just(LayoutMode::AmbientFill),
+                Just(LayoutMode::Span),
+            ]
+        }
+
+        /// Buffer size of an output: logical size times fractional scale,
+        /// with width and height swapped for 90/270 degree transforms
+        fn any_output() -> impl Strategy<Value = (i32, i32)> {
+            (
+                1..=7680i32,
+                1..=4320i32,
+                prop::sample::select(vec![1.0, 1.25, 1.5, 1.75, 2.0, 3.0]),
+                any::<bool>(),
+            )
+                .prop_map(|(width, height, scale, rotated)| {
+                    let edge = |logical: i32| {
+                        ((f64::from(logical) * scale).round() as i32).clamp(1, MAX_EDGE)
+                    };
+                    let (width, height) = (edge(width), edge(height));
+                    if rotated {
+                        (height, width)
+                    } else {
+                        (width, height)
+                    }
+                })
+        }
+
+        /// Frame sizes from tiny to 16K, including extreme banner and tower shapes
+        fn any_frame() -> impl Strategy<Value = (i32, i32)> {
+            prop_oneof![
+                (1..=MAX_EDGE, 1..=MAX_EDGE),
+                (1..=8i32, 1..=MAX_EDGE),
+                (1..=MAX_EDGE, 1..=8i32),
+            ]
+        }
+
+        proptest! {
+            #[test]
+            fn source_rect_is_finite_and_inside_the_frame(
+                mode in any_layout(),
+                (video_width, video_height) in any_frame(),
+                (output_width, output_height) in any_output(),
+            ) {
+                let layout = calculate_layout(mode, video_width, video_height, output_width, output_height);
+                let (x, y, w, h) = layout.src_rect;
+
+                for value in [x, y, w, h] {
+                    prop_assert!(value.is_finite(), "{:?}", layout);
+                }
+                prop_assert!(x >= 0.0 && y >= 0.0, "{:?}", layout);
+                prop_assert!(w > 0.0 && h > 0.0, "{:?}", layout);
+                prop_assert!(x + w <= 1.0 + 1e-9 && y + h <= 1.0 + 1e-9, "{:?}", layout);
+            }
+
+            #[test]
+            fn fill_covers_the_output_without_distortion(
+                cover in any::<bool>(),
+                (video_width, video_height) in any_frame(),
+                (output_width, output_height) in any_output(),
+            ) {
+                let mode = if cover { LayoutMode::Cover } else { LayoutMode::Fill };
+                let layout = calculate_layout(mode, video_width, video_height, output_width, output_height);
+                prop_assert_eq!(layout.dst_rect, (0, 0, output_width, output_height));
+
+                // The cropped part of the frame has the output's aspect ratio
+                let (_, _, w, h) = layout.src_rect;
+                let scale_x = w * f64::from(video_width) / f64::from(output_width);
+                let scale_y = h * f64::from(video_height) / f64::from(output_height);
+                prop_assert!((scale_x - scale_y).abs() <= scale_x.max(scale_y) * 1e-9, "{:?}", layout);
+            }
+
+            #[test]
+            fn contain_fits_inside_the_output_and_keeps_the_aspect_ratio(
+                ambient in any::<bool>(),
+                (video_width, video_height) in any_frame(),
+                (output_width, output_height) in any_output(),
+            ) {
+                let mode = if ambient { LayoutMode::AmbientFill } else { LayoutMode::Contain };
+                let layout = calculate_layout(mode, video_width, video_height, output_width, output_height);
+                let (x, y, w, h) = layout.dst_rect;
+                prop_assert_eq!(layout.src_rect, (0.0, 0.0, 1.0, 1.0));
+
+                prop_assert!(w >= 1 && h >= 1, "{:?}", layout);
+                prop_assert!(x >= 0 && y >= 0, "{:?}", layout);
+                prop_assert!(x + w <= output_width && y + h <= output_height, "{:?}", layout);
+                // One side touches the output edges
+                prop_assert!(w == output_width || h == output_height, "{:?}", layout);
+                // Bars on both sides differ by at most one pixel
+                prop_assert!((output_width - x - w - x).abs() <= 1, "{:?}", layout);
+                prop_assert!((output_height - y - h - y).abs() <= 1, "{:?}", layout);
+
+                // The scaled side is rounded to the nearest pixel, unless it
+                // had to be widened to stay visible
+                let exact_w = f64::from(h) * f64::from(video_width) / f64::from(video_height);
+                let exact_h = f64::from(w) * f64::from(video_height) / f64::from(video_width);
+                prop_assert!(
+                    (f64::from(w) - exact_w).abs() <= 0.5 + 1e-9
+                        || (f64::from(h) - exact_h).abs() <= 0.5 + 1e-9
+                        || w == 1
+                        || h == 1,
+                    "{:?}",
+                    layout
+                );
+            }
+
+            #[test]
+            fn stretch_and_centre_place_the_whole_frame(
+                (video_width, video_height) in any_frame(),
+                (output_width, output_height) in any_output(),
+            ) {
+                let stretch = calculate_layout(LayoutMode::Stretch, video_width, video_height, output_width, output_height);
+                prop_assert_eq!(stretch.src_rect, (0.0, 0.0, 1.0, 1.0));
+                prop_assert_eq!(stretch.dst_rect, (0, 0, output_width, output_height));
+
+                let centre = calculate_layout(LayoutMode::Centre, video_width, video_height, output_width, output_height);
+                let (x, y, w, h) = centre.dst_rect;
+                prop_assert_eq!((w, h), (video_width, video_height));
+                prop_assert!((output_width - x - w - x).abs() <= 1, "{:?}", centre);
+                prop_assert!((output_height - y - h - y).abs() <= 1, "{:?}", centre);
+            }
+        }
+    }
 }
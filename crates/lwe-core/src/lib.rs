@@ -2,33 +2,57 @@
 //!
 //! This crate provides the foundational types used across all LWE components:
 //! - `VideoSource`: Defines various video/wallpaper source types
+//! - Animated image (GIF/APNG/WebP) detection
 //! - `WallpaperItem`: Wallpaper metadata for the library
 //! - `AppSettings`: Application settings (GUI managed)
-//! - `Config`: Legacy CLI configuration format
-//! - Layout, HDR, and rendering types
-//! - IPC protocol types for daemon communication
+//! - `Config`: Legacy CLI configuration format, and checks for hand-edited files
+//! - Layout, HDR, color LUT, and rendering types
+//! - `Palette`: Colors extracted from the wallpaper for theming
+//! - `PlaylistRotation`: Saved progress of a playlist on one output
+//! - `DaySchedule`: Time-of-day slots, including sunrise and sunset
+//! - `Policy`: Administrator-locked settings from `/etc/lwe/policy.yaml`
+//! - IPC protocol types for daemon communication, from `lwe-protocol`
 
+pub mod animated;
 pub mod config;
 pub mod hdr;
 pub mod ipc;
 pub mod layout;
 pub mod library;
+pub mod lut;
+pub mod palette;
+pub mod playlist;
+pub mod policy;
 pub mod power;
+pub mod schedule;
 pub mod settings;
 pub mod types;
 
 // Re-exports for convenience
-pub use config::{Config, EffectiveConfig, OutputConfig, PowerConfig};
+pub use animated::{animated_format, is_animated_image, is_still_image, AnimatedFormat};
+pub use config::{
+    BatteryProfile, Config, ConfigIssue, CpuAffinity, EffectiveConfig, IssueSeverity, OutputConfig,
+    PowerConfig,
+};
 pub use hdr::{
-    ColorSpace, HdrMetadata, HdrMode, ToneMappingAlgorithm, ToneMappingConfig, TransferFunction,
+    ColorSpace, HdrMetadata, HdrMode, HdrOutput, ToneMappingAlgorithm, ToneMappingConfig,
+    TransferFunction,
 };
 pub use ipc::{
-    default_socket_path, IpcRequest, IpcResponse, LibraryFilter, LibraryItem,
-    OutputInfo as IpcOutputInfo, OutputStatus,
+    default_socket_path, CollectionInfo, Envelope as IpcEnvelope, IpcRequest, IpcResponse,
+    LibraryFilter, LibraryItem, LibrarySort, OutputAudio, OutputInfo as IpcOutputInfo,
+    OutputPalette, OutputStats, OutputStatus, ProcessStats, ProfileInfo, SourceError,
+    PROTOCOL_VERSION,
 };
-pub use layout::{calculate_layout, LayoutTransform};
+pub use layout::{calculate_layout, calculate_span_layout, span_canvas, LayoutTransform, SpanRect};
 pub use library::{SourceType, WallpaperItem, WallpaperMetadata, WallpaperType};
+pub use lut::CubeLut;
+pub use palette::{Palette, PALETTE_SIZE};
+pub use playlist::PlaylistRotation;
+pub use policy::Policy;
+pub use schedule::{sun_times, ActiveSlot, Coordinates, DaySchedule, ScheduleSlot, SlotStart};
 pub use settings::AppSettings;
 pub use types::{
-    HwdecMode, LayoutMode, OutputHdrCapabilities, OutputInfo, RenderBackend, VideoSource,
+    ColorAdjust, HwdecMode, LayoutMode, LetterboxFill, OutputHdrCapabilities, OutputInfo,
+    RenderBackend, SurfaceLayer, SurfaceMargins, TransitionKind, VideoSource,
 };
@@ -6,6 +6,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+use crate::hdr::TransferFunction;
+
 /// Wallpaper item in the library
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WallpaperItem {
@@ -103,11 +105,14 @@ pub enum WallpaperType {
     /// Wallpaper Engine scene (JSON-based)
     Scene,
 
-    /// Animated GIF
-    Gif,
+    /// Animated GIF, APNG or WebP
+    AnimatedImage,
 
     /// Static image (for future support)
     Image,
+
+    /// HTML/JS page
+    Web,
 }
 
 impl WallpaperType {
@@ -115,8 +120,9 @@ impl WallpaperType {
         match self {
             WallpaperType::Video => "video",
             WallpaperType::Scene => "scene",
-            WallpaperType::Gif => "gif",
+            WallpaperType::AnimatedImage => "animated_image",
             WallpaperType::Image => "image",
+            WallpaperType::Web => "web",
         }
     }
 
@@ -125,8 +131,9 @@ impl WallpaperType {
         match self {
             WallpaperType::Video => "🎬",
             WallpaperType::Scene => "🎨",
-            WallpaperType::Gif => "🖼️",
+            WallpaperType::AnimatedImage => "🖼️",
             WallpaperType::Image => "📷",
+            WallpaperType::Web => "🌐",
         }
     }
 }
@@ -155,8 +162,28 @@ pub struct WallpaperMetadata {
     /// File size in bytes
     pub file_size: Option<u64>,
 
+    /// Codec of the first video stream, named as ffmpeg does ("h264", "vp9")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codec: Option<String>,
+
+    /// Average frame rate of the first video stream
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fps: Option<f64>,
+
+    /// HDR transfer of the video (PQ or HLG); `None` for SDR or unknown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hdr: Option<TransferFunction>,
+
     /// Steam Workshop ID (if applicable)
     pub workshop_id: Option<u64>,
+
+    /// User GLSL post-process shader applied on top of this wallpaper
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shader_path: Option<PathBuf>,
+
+    /// Color grading LUT (.cube) applied in the final pass
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lut_path: Option<PathBuf>,
 }
 
 /// Filter criteria for querying wallpapers
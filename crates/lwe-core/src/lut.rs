@@ -0,0 +1,308 @@
+//! Color lookup tables
+//!
+//! Parser for Adobe/Resolve `.cube` 3D LUT files and conversion into an mpv
+//! user shader, so the grade is applied as the final render pass.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+
+/// Largest LUT edge length accepted (the .cube spec allows up to 256)
+const MAX_LUT_SIZE: usize = 256;
+
+/// A parsed 3D color lookup table
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubeLut {
+    /// Optional title from the `TITLE` keyword
+    pub title: Option<String>,
+    /// Edge length of the cube (entries per channel)
+    pub size: usize,
+    /// Input value mapped to the first entry
+    pub domain_min: [f32; 3],
+    /// Input value mapped to the last entry
+    pub domain_max: [f32; 3],
+    /// Output colors, red changing fastest, then green, then blue
+    pub table: Vec<[f32; 3]>,
+}
+
+impl CubeLut {
+    /// Parse a `.cube` file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read LUT file: {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("Invalid LUT file: {}", path.display()))
+    }
+
+    /// Parse `.cube` content
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut title = None;
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+
+        for (index, raw_line) in content.lines().enumerate() {
+            let line_no = index + 1;
+            let line = strip_comment(raw_line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let keyword = parts.next().unwrap_or("");
+
+            match keyword {
+                "TITLE" => {
+                    let value = line["TITLE".len()..].trim().trim_matches('"');
+                    title = Some(value.to_string());
+                }
+                "LUT_3D_SIZE" => {
+                    let value: usize = parts
+                        .next()
+                        .and_then(|v| v.parse().ok())
+                        .with_context(|| format!("line {}: invalid LUT_3D_SIZE", line_no))?;
+                    if !(2..=MAX_LUT_SIZE).contains(&value) {
+                        bail!("line {}: LUT_3D_SIZE {} out of range", line_no, value);
+                    }
+                    size = Some(value);
+                }
+                "LUT_1D_SIZE" => {
+                    bail!("line {}: 1D LUTs are not supported, use a 3D LUT", line_no);
+                }
+                "DOMAIN_MIN" => domain_min = parse_triplet(parts, line_no)?,
+                "DOMAIN_MAX" => domain_max = parse_triplet(parts, line_no)?,
+                // Resolve-specific keyword with no effect on 3D LUTs
+                "LUT_3D_INPUT_RANGE" => {}
+                _ if keyword.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') => {
+                    table.push(parse_triplet(line.split_whitespace(), line_no)?);
+                }
+                _ => bail!("line {}: unknown keyword '{}'", line_no, keyword),
+            }
+        }
+
+        let size = size.context("missing LUT_3D_SIZE")?;
+        let expected = size * size * size;
+        if table.len() != expected {
+            bail!(
+                "expected {} table entries for size {}, found {}",
+                expected,
+                size,
+                table.len()
+            );
+        }
+
+        if (0..3).any(|i| domain_max[i] <= domain_min[i]) {
+            bail!("DOMAIN_MAX must be greater than DOMAIN_MIN");
+        }
+
+        Ok(Self {
+            title,
+            size,
+            domain_min,
+            domain_max,
+            table,
+        })
+    }
+
+    /// Render the LUT as an mpv user shader hooking the final output pass
+    ///
+    /// The table is embedded as a linearly filtered 3D texture, so no extra
+    /// files are needed at runtime.
+    pub fn to_mpv_shader(&self) -> String {
+        let size = self.size as f32;
+        let mut shader = String::new();
+
+        let _ = writeln!(shader, "// Generated by LWE from a .cube LUT");
+        if let Some(ref title) = self.title {
+            let _ = writeln!(shader, "// {}", title);
+        }
+        let _ = writeln!(shader, "//!HOOK OUTPUT");
+        let _ = writeln!(shader, "//!BIND HOOKED");
+        let _ = writeln!(shader, "//!BIND LWE_LUT");
+        let _ = writeln!(shader, "//!DESC LWE color LUT");
+        let _ = writeln!(shader);
+        let _ = writeln!(shader, "vec4 hook() {{");
+        let _ = writeln!(shader, "    vec4 color = HOOKED_tex(HOOKED_pos);");
+        let _ = writeln!(
+            shader,
+            "    vec3 coord = clamp((color.rgb - {}) / ({} - {}), 0.0, 1.0);",
+            glsl_vec3(self.domain_min),
+            glsl_vec3(self.domain_max),
+            glsl_vec3(self.domain_min)
+        );
+        let _ = writeln!(
+            shader,
+            "    coord = coord * {:.1} / {:.1} + 0.5 / {:.1};",
+            size - 1.0,
+            size,
+            size
+        );
+        let _ = writeln!(shader, "    color.rgb = texture(LWE_LUT, coord).rgb;");
+        let _ = writeln!(shader, "    return color;");
+        let _ = writeln!(shader, "}}");
+        let _ = writeln!(shader);
+        let _ = writeln!(shader, "//!TEXTURE LWE_LUT");
+        let _ = writeln!(shader, "//!SIZE {} {} {}", self.size, self.size, self.size);
+        let _ = writeln!(shader, "//!FORMAT rgba32f");
+        let _ = writeln!(shader, "//!FILTER LINEAR");
+        let _ = writeln!(shader, "//!BORDER CLAMP");
+
+        // Texture data is hex-encoded raw texels (little-endian f32 RGBA)
+        shader.reserve(self.table.len() * 32 + 1);
+        for rgb in &self.table {
+            for value in [rgb[0], rgb[1], rgb[2], 1.0] {
+                for byte in value.to_le_bytes() {
+                    let _ = write!(shader, "{:02x}", byte);
+                }
+            }
+        }
+        shader.push('\n');
+
+        shader
+    }
+}
+
+/// Compile a `.cube` file into an mpv shader inside `cache_dir`
+///
+/// The output name is derived from the LUT contents, so an edited LUT gets a
+/// fresh shader while unchanged ones reuse the cached file.
+pub fn compile_cube_to_shader(cube_path: &Path, cache_dir: &Path) -> Result<PathBuf> {
+    let content = fs::read_to_string(cube_path)
+        .with_context(|| format!("Failed to read LUT file: {}", cube_path.display()))?;
+
+    let digest = Sha256::digest(content.as_bytes());
+    let name: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    let shader_path = cache_dir.join(format!("lut-{}.glsl", name));
+
+    if shader_path.exists() {
+        return Ok(shader_path);
+    }
+
+    let lut = CubeLut::parse(&content)
+        .with_context(|| format!("Invalid LUT file: {}", cube_path.display()))?;
+
+    fs::create_dir_all(cache_dir)
+        .with_context(|| format!("Failed to create LUT cache: {}", cache_dir.display()))?;
+    fs::write(&shader_path, lut.to_mpv_shader())
+        .with_context(|| format!("Failed to write LUT shader: {}", shader_path.display()))?;
+
+    Ok(shader_path)
+}
+
+/// Default directory for compiled LUT shaders
+pub fn default_lut_cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("lwe")
+        .join("luts")
+}
+
+/// `line` up to a `#` comment; a `#` inside a quoted title is kept
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_triplet<'a>(mut parts: impl Iterator<Item = &'a str>, line_no: usize) -> Result<[f32; 3]> {
+    let mut values = [0.0; 3];
+    for value in &mut values {
+        *value = parts
+            .next()
+            .and_then(|v| v.parse().ok())
+            .with_context(|| format!("line {}: expected three numbers", line_no))?;
+    }
+    Ok(values)
+}
+
+fn glsl_vec3(v: [f32; 3]) -> String {
+    format!("vec3({:?}, {:?}, {:?})", v[0], v[1], v[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDENTITY_2: &str = "\
+# identity
+TITLE \"Identity\"
+LUT_3D_SIZE 2
+0 0 0
+1 0 0
+0 1 0
+1 1 0
+0 0 1
+1 0 1
+0 1 1
+1 1 1
+";
+
+    #[test]
+    fn test_parse_identity_cube() {
+        let lut = CubeLut::parse(IDENTITY_2).unwrap();
+        assert_eq!(lut.title.as_deref(), Some("Identity"));
+        assert_eq!(lut.size, 2);
+        assert_eq!(lut.domain_min, [0.0; 3]);
+        assert_eq!(lut.domain_max, [1.0; 3]);
+        assert_eq!(lut.table[1], [1.0, 0.0, 0.0]);
+        assert_eq!(lut.table[7], [1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_parse_keeps_hash_in_quoted_title() {
+        let content = IDENTITY_2.replace(
+            "TITLE \"Identity\"",
+            "TITLE \"Film #2 (warm)\" # graded for dusk",
+        );
+        let lut = CubeLut::parse(&content).unwrap();
+        assert_eq!(lut.title.as_deref(), Some("Film #2 (warm)"));
+        assert_eq!(lut.table.len(), 8);
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_cubes() {
+        assert!(CubeLut::parse("LUT_3D_SIZE 2\n0 0 0\n").is_err());
+        assert!(CubeLut::parse("LUT_1D_SIZE 16\n").is_err());
+        assert!(CubeLut::parse("0 0 0\n").is_err());
+        assert!(CubeLut::parse("LUT_3D_SIZE 2\nFOO bar\n").is_err());
+    }
+
+    #[test]
+    fn test_shader_embeds_texture() {
+        let lut = CubeLut::parse(IDENTITY_2).unwrap();
+        let shader = lut.to_mpv_shader();
+        assert!(shader.contains("//!HOOK OUTPUT"));
+        assert!(shader.contains("//!SIZE 2 2 2"));
+
+        // 8 texels * 4 channels * 4 bytes, hex encoded
+        let data = shader.lines().last().unwrap();
+        assert_eq!(data.len(), 8 * 4 * 4 * 2);
+    }
+
+    #[test]
+    fn test_compile_cube_is_cached_by_content() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cube = dir.path().join("grade.cube");
+        fs::write(&cube, IDENTITY_2).unwrap();
+
+        let cache = dir.path().join("cache");
+        let first = compile_cube_to_shader(&cube, &cache).unwrap();
+        let second = compile_cube_to_shader(&cube, &cache).unwrap();
+        assert_eq!(first, second);
+        assert!(first.exists());
+
+        fs::write(&cube, IDENTITY_2.replace("1 1 1", "0.9 0.9 0.9")).unwrap();
+        let third = compile_cube_to_shader(&cube, &cache).unwrap();
+        assert_ne!(first, third);
+    }
+}
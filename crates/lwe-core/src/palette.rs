@@ -0,0 +1,181 @@
+//! Color palettes extracted from wallpaper frames
+//!
+//! The engine samples a downscaled copy of the frame on screen; this module
+//! quantizes it with median cut into a handful of representative colors and
+//! fills pywal-style templates (`{color0}`, `{background}`, ...) so bars and
+//! terminals can follow the wallpaper.
+
+use serde::{Deserialize, Serialize};
+
+/// Number of colors extracted per output
+pub const PALETTE_SIZE: usize = 8;
+
+/// Representative colors of a frame, most common first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Palette {
+    pub colors: Vec<[u8; 3]>,
+}
+
+impl Palette {
+    /// Quantize RGBA pixels into at most `count` colors
+    ///
+    /// Median cut: the box with the widest channel range is split near its
+    /// median until there are `count` boxes, then each box is averaged.
+    pub fn from_rgba(rgba: &[u8], count: usize) -> Self {
+        let pixels: Vec<[u8; 3]> = rgba
+            .chunks_exact(4)
+            .map(|pixel| [pixel[0], pixel[1], pixel[2]])
+            .collect();
+        if pixels.is_empty() || count == 0 {
+            return Self::default();
+        }
+
+        let mut boxes = vec![pixels];
+        while boxes.len() < count {
+            let Some((index, channel)) = boxes
+                .iter()
+                .enumerate()
+                .filter(|(_, pixels)| pixels.len() > 1)
+                .map(|(index, pixels)| (index, widest_channel(pixels)))
+                .filter(|(_, (_, range))| *range > 0)
+                .max_by_key(|(_, (_, range))| *range)
+                .map(|(index, (channel, _))| (index, channel))
+            else {
+                break;
+            };
+
+            let mut pixels = boxes.swap_remove(index);
+            pixels.sort_unstable_by_key(|pixel| pixel[channel]);
+            // Cut where the value changes nearest the median, so pixels of
+            // one color never end up on both sides
+            let median = pixels.len() / 2;
+            let cut = (1..pixels.len())
+                .filter(|&i| pixels[i - 1][channel] != pixels[i][channel])
+                .min_by_key(|&i| i.abs_diff(median))
+                .unwrap_or(median);
+            let upper = pixels.split_off(cut);
+            boxes.push(pixels);
+            boxes.push(upper);
+        }
+
+        boxes.sort_by_key(|pixels| std::cmp::Reverse(pixels.len()));
+        Self {
+            colors: boxes.iter().map(|pixels| average(pixels)).collect(),
+        }
+    }
+
+    /// Colors as `#rrggbb`
+    pub fn hex_colors(&self) -> Vec<String> {
+        self.colors.iter().map(|color| hex(*color)).collect()
+    }
+
+    /// Darkest color
+    pub fn background(&self) -> Option<[u8; 3]> {
+        self.colors.iter().copied().min_by_key(|color| luma(*color))
+    }
+
+    /// Lightest color
+    pub fn foreground(&self) -> Option<[u8; 3]> {
+        self.colors.iter().copied().max_by_key(|color| luma(*color))
+    }
+
+    /// Fill `{color0}`..`{colorN}`, `{background}` and `{foreground}` in a template
+    ///
+    /// Placeholders beyond the palette size are left untouched.
+    pub fn render_template(&self, template: &str) -> String {
+        let mut rendered = template.to_string();
+        if let Some(background) = self.background() {
+            rendered = rendered.replace("{background}", &hex(background));
+        }
+        if let Some(foreground) = self.foreground() {
+            rendered = rendered.replace("{foreground}", &hex(foreground));
+        }
+        for (index, color) in self.colors.iter().enumerate() {
+            rendered = rendered.replace(&format!("{{color{index}}}"), &hex(*color));
+        }
+        rendered
+    }
+}
+
+/// Channel with the widest value range and that range
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let (min, max) = pixels.iter().fold((u8::MAX, u8::MIN), |(min, max), pixel| {
+                (min.min(pixel[channel]), max.max(pixel[channel]))
+            });
+            (channel, max - min)
+        })
+        .max_by_key(|(_, range)| *range)
+        .unwrap_or((0, 0))
+}
+
+fn average(pixels: &[[u8; 3]]) -> [u8; 3] {
+    let mut sums = [0u64; 3];
+    for pixel in pixels {
+        for channel in 0..3 {
+            sums[channel] += u64::from(pixel[channel]);
+        }
+    }
+    sums.map(|sum| (sum / pixels.len().max(1) as u64) as u8)
+}
+
+/// Perceived brightness, Rec. 601 weights scaled by 1000
+fn luma([r, g, b]: [u8; 3]) -> u32 {
+    299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b)
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgba(pixels: &[([u8; 3], usize)]) -> Vec<u8> {
+        pixels
+            .iter()
+            .flat_map(|(color, count)| {
+                std::iter::repeat_n([color[0], color[1], color[2], 255], *count)
+            })
+            .flatten()
+            .collect()
+    }
+
+    #[test]
+    fn test_palette_separates_colors_by_population() {
+        let pixels = rgba(&[([10, 20, 30], 6), ([240, 200, 10], 3), ([0, 180, 90], 2)]);
+        let palette = Palette::from_rgba(&pixels, 3);
+
+        assert_eq!(palette.colors[0], [10, 20, 30]);
+        assert_eq!(palette.colors.len(), 3);
+        assert!(palette.colors.contains(&[240, 200, 10]));
+        assert!(palette.colors.contains(&[0, 180, 90]));
+        assert_eq!(palette.background(), Some([10, 20, 30]));
+        assert_eq!(palette.foreground(), Some([240, 200, 10]));
+    }
+
+    #[test]
+    fn test_palette_of_solid_frame_has_one_color() {
+        let palette = Palette::from_rgba(&rgba(&[([50, 50, 50], 16)]), PALETTE_SIZE);
+
+        assert_eq!(palette.hex_colors(), vec!["#323232".to_string()]);
+        assert_eq!(Palette::from_rgba(&[], PALETTE_SIZE), Palette::default());
+    }
+
+    #[test]
+    fn test_render_template_fills_known_placeholders() {
+        let palette = Palette {
+            colors: vec![[0, 0, 0], [255, 255, 255], [255, 0, 0]],
+        };
+        let rendered = palette.render_template(
+            "bg={background} fg={foreground} c2={color2} c10={color10} x={unknown}",
+        );
+
+        assert_eq!(
+            rendered,
+            "bg=#000000 fg=#ffffff c2=#ff0000 c10={color10} x={unknown}"
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Playlist rotation state
+//!
+//! Tracks which entry of a playlist is showing on one output. Times are
+//! unix seconds rather than monotonic instants so a rotation can be saved
+//! and resumed after the process restarts.
+
+use serde::{Deserialize, Serialize};
+
+/// Rotation progress of one playlist on one output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlaylistRotation {
+    /// Playlist being rotated
+    pub playlist_id: i64,
+    /// Play order as indices into the playlist entries
+    pub order: Vec<usize>,
+    /// Position of the current entry within `order`
+    pub position: usize,
+    /// Unix time at which the current entry started showing
+    pub started_at: u64,
+    /// Seconds the current entry had been shown when rotation was paused
+    #[serde(default)]
+    pub paused_after: Option<u64>,
+    /// Reshuffle each cycle
+    #[serde(default)]
+    pub shuffle: bool,
+    /// Shuffle state carried between cycles
+    #[serde(default)]
+    pub seed: u64,
+}
+
+impl PlaylistRotation {
+    pub fn new(playlist_id: i64, len: usize, shuffle: bool, now: u64, seed: u64) -> Self {
+        let mut rotation = Self {
+            playlist_id,
+            order: Vec::new(),
+            position: 0,
+            started_at: now,
+            paused_after: None,
+            shuffle,
+            seed: seed | 1,
+        };
+        rotation.reorder(len);
+        rotation
+    }
+
+    /// Index of the playlist entry that should be showing
+    pub fn current(&self) -> Option<usize> {
+        self.order.get(self.position).copied()
+    }
+
+    /// Whether this rotation still matches a playlist with `len` entries
+    pub fn fits(&self, len: usize) -> bool {
+        self.order.len() == len && self.position < len && self.order.iter().all(|&i| i < len)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_after.is_some()
+    }
+
+    /// Seconds the current entry has been shown
+    pub fn elapsed(&self, now: u64) -> u64 {
+        self.paused_after
+            .unwrap_or_else(|| now.saturating_sub(self.started_at))
+    }
+
+    /// Move to the next entry once the current one has been shown for
+    /// `duration_secs(index)` seconds
+    ///
+    /// Returns the entry that should be applied now, if the rotation moved.
+    pub fn advance_if_due(
+        &mut self,
+        now: u64,
+        duration_secs: impl Fn(usize) -> u32,
+    ) -> Option<usize> {
+        let current = self.current()?;
+        if self.is_paused() || self.elapsed(now) < u64::from(duration_secs(current)) {
+            return None;
+        }
+
+        self.step(now)
+    }
+
+    /// Seconds until the current entry is due to be replaced, or `None`
+    /// while paused
+    pub fn remaining(&self, now: u64, duration_secs: impl Fn(usize) -> u32) -> Option<u64> {
+        let current = self.current()?;
+        if self.is_paused() {
+            return None;
+        }
+
+        Some(u64::from(duration_secs(current)).saturating_sub(self.elapsed(now)))
+    }
+
+    /// Entry the rotation will move to next, including across a reshuffle
+    pub fn upcoming(&self) -> Option<usize> {
+        self.current()?;
+        self.clone().step(self.started_at)
+    }
+
+    /// Skip to the next entry immediately
+    ///
+    /// A paused rotation stays paused on the new entry.
+    pub fn skip(&mut self, now: u64) -> Option<usize> {
+        self.current()?;
+        self.step(now)
+    }
+
+    /// Go back to the entry shown before the current one
+    ///
+    /// Going back past the start of a cycle keeps its order rather than
+    /// reshuffling. A paused rotation stays paused on the new entry.
+    pub fn back(&mut self, now: u64) -> Option<usize> {
+        self.current()?;
+        self.position = self.position.checked_sub(1).unwrap_or(self.order.len() - 1);
+        self.restart_entry(now);
+
+        self.current()
+    }
+
+    pub fn pause(&mut self, now: u64) {
+        if !self.is_paused() {
+            self.paused_after = Some(self.elapsed(now));
+        }
+    }
+
+    /// Resume so the current entry still gets the rest of its time
+    pub fn resume(&mut self, now: u64) {
+        if let Some(elapsed) = self.paused_after.take() {
+            self.started_at = now.saturating_sub(elapsed);
+        }
+    }
+
+    fn step(&mut self, now: u64) -> Option<usize> {
+        self.position += 1;
+        if self.position >= self.order.len() {
+            self.position = 0;
+            self.reorder(self.order.len());
+        }
+        self.restart_entry(now);
+
+        self.current()
+    }
+
+    /// Give the entry just moved to its full time
+    fn restart_entry(&mut self, now: u64) {
+        self.started_at = now;
+        if self.paused_after.is_some() {
+            self.paused_after = Some(0);
+        }
+    }
+
+    /// Rebuild the play order, reshuffling each cycle when shuffle is on
+    fn reorder(&mut self, len: usize) {
+        self.order = (0..len).collect();
+        if !self.shuffle {
+            return;
+        }
+
+        // Fisher-Yates driven by xorshift; quality only needs to look random
+        for i in (1..self.order.len()).rev() {
+            self.seed ^= self.seed << 13;
+            self.seed ^= self.seed >> 7;
+            self.seed ^= self.seed << 17;
+            let j = (self.seed % (i as u64 + 1)) as usize;
+            self.order.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn durations(index: usize) -> u32 {
+        [10, 60, 60][index]
+    }
+
+    #[test]
+    fn test_rotation_honours_durations_and_wraps() {
+        let mut rotation = PlaylistRotation::new(1, 3, false, 1_000, 1);
+
+        assert_eq!(rotation.current(), Some(0));
+        assert_eq!(rotation.advance_if_due(1_009, durations), None);
+        assert_eq!(rotation.advance_if_due(1_010, durations), Some(1));
+        assert_eq!(rotation.advance_if_due(1_069, durations), None);
+        assert_eq!(rotation.advance_if_due(1_070, durations), Some(2));
+        assert_eq!(rotation.advance_if_due(1_130, durations), Some(0));
+    }
+
+    #[test]
+    fn test_rotation_pause_keeps_remaining_time() {
+        let mut rotation = PlaylistRotation::new(1, 3, false, 1_000, 1);
+
+        rotation.pause(1_004);
+        assert!(rotation.is_paused());
+        assert_eq!(rotation.advance_if_due(2_000, durations), None);
+
+        rotation.resume(2_000);
+        assert_eq!(rotation.advance_if_due(2_005, durations), None);
+        assert_eq!(rotation.advance_if_due(2_006, durations), Some(1));
+    }
+
+    #[test]
+    fn test_rotation_reports_upcoming_entry_and_remaining_time() {
+        let mut rotation = PlaylistRotation::new(1, 3, false, 1_000, 1);
+
+        assert_eq!(rotation.upcoming(), Some(1));
+        assert_eq!(rotation.remaining(1_004, durations), Some(6));
+        assert_eq!(rotation.remaining(1_020, durations), Some(0));
+
+        rotation.pause(1_004);
+        assert_eq!(rotation.remaining(1_004, durations), None);
+
+        // The last entry of a shuffled cycle predicts the reshuffled order
+        let mut shuffled = PlaylistRotation::new(1, 5, true, 0, 0x9e37_79b9);
+        for _ in 0..4 {
+            shuffled.skip(0);
+        }
+        let upcoming = shuffled.upcoming();
+        assert_eq!(upcoming, shuffled.skip(0));
+    }
+
+    #[test]
+    fn test_rotation_skip_moves_even_when_paused() {
+        let mut rotation = PlaylistRotation::new(1, 3, false, 1_000, 1);
+        rotation.pause(1_030);
+
+        assert_eq!(rotation.skip(1_040), Some(1));
+        assert!(rotation.is_paused());
+        assert_eq!(rotation.elapsed(5_000), 0);
+    }
+
+    #[test]
+    fn test_rotation_back_returns_to_the_previous_entry_and_wraps() {
+        let mut rotation = PlaylistRotation::new(1, 3, false, 1_000, 1);
+
+        assert_eq!(rotation.back(1_005), Some(2));
+        assert_eq!(rotation.elapsed(1_010), 5);
+        assert_eq!(rotation.back(1_010), Some(1));
+        assert_eq!(rotation.skip(1_020), Some(2));
+
+        rotation.pause(1_030);
+        assert_eq!(rotation.back(1_040), Some(1));
+        assert!(rotation.is_paused());
+    }
+
+    #[test]
+    fn test_rotation_shuffle_visits_every_entry_once_per_cycle() {
+        let rotation = PlaylistRotation::new(1, 5, true, 0, 0x9e37_79b9);
+
+        let mut order = rotation.order.clone();
+        order.sort();
+        assert_eq!(order, vec![0, 1, 2, 3, 4]);
+        assert!(rotation.fits(5));
+        assert!(!rotation.fits(4));
+    }
+
+    #[test]
+    fn test_rotation_round_trips_through_json() {
+        let mut rotation = PlaylistRotation::new(7, 2, true, 1_000, 42);
+        rotation.pause(1_003);
+
+        let json = serde_json::to_string(&rotation).unwrap();
+        let parsed: PlaylistRotation = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, rotation);
+    }
+}
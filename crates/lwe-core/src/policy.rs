@@ -0,0 +1,124 @@
+//! Administrator policy
+//!
+//! Managed deployments (labs, kiosks, offices) can drop a system-wide
+//! `/etc/lwe/policy.yaml` that locks settings users would otherwise control.
+//! Whatever the policy sets overrides the user's settings; keys it leaves out
+//! are unrestricted. A policy file that exists but cannot be read fails closed.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// System-wide policy file
+pub const SYSTEM_POLICY_PATH: &str = "/etc/lwe/policy.yaml";
+
+/// Settings locked by the administrator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Policy {
+    /// Allow media fetched over the network (stream URLs, remote signage manifests)
+    pub allow_network_sources: bool,
+    /// Allow wallpaper audio
+    pub allow_audio: bool,
+    /// Frame rate cap for every output (None = no cap)
+    pub max_fps: Option<u32>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            allow_network_sources: true,
+            allow_audio: true,
+            max_fps: None,
+        }
+    }
+}
+
+impl Policy {
+    /// Policy used when the policy file exists but is unusable
+    pub fn locked_down() -> Self {
+        Self {
+            allow_network_sources: false,
+            allow_audio: false,
+            max_fps: None,
+        }
+    }
+
+    /// Load a policy file; a missing file means no restrictions
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(contents) if contents.trim().is_empty() => Ok(Self::default()),
+            Ok(contents) => serde_yaml::from_str(&contents)
+                .map_err(|e| format!("Invalid policy {}: {}", path.display(), e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read policy {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Load the system policy, locking everything down if it is broken
+    pub fn system() -> Self {
+        Self::load(Path::new(SYSTEM_POLICY_PATH)).unwrap_or_else(|e| {
+            eprintln!("Warning: {}; applying the locked-down policy", e);
+            Self::locked_down()
+        })
+    }
+
+    /// The user's frame rate limit with the policy cap applied
+    pub fn cap_fps(&self, requested: Option<u32>) -> Option<u32> {
+        match (requested, self.max_fps) {
+            (Some(requested), Some(max)) => Some(requested.min(max)),
+            (requested, max) => requested.or(max),
+        }
+    }
+
+    /// Whether anything is restricted
+    pub fn is_restricted(&self) -> bool {
+        *self != Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_load_missing_empty_and_partial_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.yaml");
+
+        assert_eq!(Policy::load(&path), Ok(Policy::default()));
+        fs::write(&path, "\n").unwrap();
+        assert_eq!(Policy::load(&path), Ok(Policy::default()));
+
+        fs::write(&path, "allow_audio: false\nmax_fps: 30\n").unwrap();
+        let policy = Policy::load(&path).unwrap();
+        assert!(!policy.allow_audio);
+        assert!(policy.allow_network_sources);
+        assert_eq!(policy.max_fps, Some(30));
+        assert!(policy.is_restricted());
+    }
+
+    #[test]
+    fn test_policy_rejects_unknown_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.yaml");
+        fs::write(&path, "allow_sound: false\n").unwrap();
+
+        assert!(Policy::load(&path).unwrap_err().contains("allow_sound"));
+    }
+
+    #[test]
+    fn test_policy_fps_cap() {
+        let policy = Policy {
+            max_fps: Some(30),
+            ..Policy::default()
+        };
+
+        assert_eq!(policy.cap_fps(None), Some(30));
+        assert_eq!(policy.cap_fps(Some(60)), Some(30));
+        assert_eq!(policy.cap_fps(Some(24)), Some(24));
+        assert_eq!(Policy::default().cap_fps(Some(60)), Some(60));
+    }
+}
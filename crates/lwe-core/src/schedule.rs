@@ -0,0 +1,319 @@
+//! Time-of-day wallpaper schedule
+//!
+//! A schedule is a list of named slots, each starting at a local clock time
+//! or relative to sunrise or sunset. The slot that started last is active
+//! until the next one starts, wrapping around midnight. Times are unix
+//! seconds so callers do not need a date library of their own.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+
+/// Stand-in sunrise hour when the location is unknown or the sun does not rise
+pub const FALLBACK_SUNRISE_HOUR: u32 = 6;
+/// Stand-in sunset hour when the location is unknown or the sun does not set
+pub const FALLBACK_SUNSET_HOUR: u32 = 18;
+
+/// Julian date of the unix epoch
+const UNIX_EPOCH_JULIAN: f64 = 2_440_587.5;
+/// Julian date of J2000.0
+const J2000: f64 = 2_451_545.0;
+
+/// Position on earth in degrees, north and east positive
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// When a schedule slot starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotStart {
+    /// Local clock time
+    At(NaiveTime),
+    /// Minutes after sunrise (negative = before)
+    Sunrise(i64),
+    /// Minutes after sunset (negative = before)
+    Sunset(i64),
+}
+
+impl FromStr for SlotStart {
+    type Err = String;
+
+    /// `07:30`, `sunrise`, `sunset`, `sunrise+45` or `sunset-30`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let relative = |rest: &str| -> Result<i64, String> {
+            if rest.is_empty() {
+                return Ok(0);
+            }
+            let minutes = rest
+                .strip_prefix('+')
+                .unwrap_or(rest)
+                .parse::<i64>()
+                .map_err(|_| format!("Invalid offset in slot start {value}"))?;
+            if rest.starts_with(['+', '-']) {
+                Ok(minutes)
+            } else {
+                Err(format!("Invalid offset in slot start {value}"))
+            }
+        };
+
+        if let Some(rest) = value.strip_prefix("sunrise") {
+            relative(rest).map(SlotStart::Sunrise)
+        } else if let Some(rest) = value.strip_prefix("sunset") {
+            relative(rest).map(SlotStart::Sunset)
+        } else {
+            NaiveTime::parse_from_str(value, "%H:%M")
+                .map(SlotStart::At)
+                .map_err(|_| {
+                    format!("Invalid slot start {value}; use HH:MM, sunrise or sunset with an optional +/- minute offset")
+                })
+        }
+    }
+}
+
+impl fmt::Display for SlotStart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, offset) = match self {
+            SlotStart::At(time) => return write!(f, "{}", time.format("%H:%M")),
+            SlotStart::Sunrise(offset) => ("sunrise", *offset),
+            SlotStart::Sunset(offset) => ("sunset", *offset),
+        };
+        match offset {
+            0 => write!(f, "{name}"),
+            offset => write!(f, "{name}{offset:+}"),
+        }
+    }
+}
+
+/// A named part of the day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleSlot {
+    pub name: String,
+    pub start: SlotStart,
+}
+
+/// The slot showing at some moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActiveSlot {
+    /// Index into `DaySchedule::slots`
+    pub index: usize,
+    /// Unix time the slot started
+    pub started_at: i64,
+    /// Unix time the next slot starts
+    pub next_change: i64,
+}
+
+/// Slots repeating every day
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DaySchedule {
+    pub slots: Vec<ScheduleSlot>,
+    /// Where sunrise and sunset are computed (None = fallback times)
+    pub location: Option<Coordinates>,
+}
+
+impl DaySchedule {
+    /// Whether any slot follows the sun
+    pub fn uses_sun(&self) -> bool {
+        self.slots
+            .iter()
+            .any(|slot| !matches!(slot.start, SlotStart::At(_)))
+    }
+
+    /// The slot active at unix time `now` in the local time zone
+    pub fn active_at(&self, now: i64) -> Option<ActiveSlot> {
+        self.active_in(&Local, now)
+    }
+
+    /// The slot active at unix time `now` in time zone `tz`
+    pub fn active_in<Tz: TimeZone>(&self, tz: &Tz, now: i64) -> Option<ActiveSlot> {
+        let today = DateTime::<Utc>::from_timestamp(now, 0)?
+            .with_timezone(tz)
+            .date_naive();
+        let mut starts = [today.pred_opt()?, today, today.succ_opt()?]
+            .into_iter()
+            .flat_map(|date| self.starts_on(date, tz))
+            .collect::<Vec<_>>();
+        starts.sort_unstable();
+
+        let next = starts.iter().position(|(start, _)| *start > now)?;
+        let (started_at, index) = *starts.get(next.checked_sub(1)?)?;
+        Some(ActiveSlot {
+            index,
+            started_at,
+            next_change: starts[next].0,
+        })
+    }
+
+    /// Unix start time of every slot on local `date`, with its index
+    fn starts_on<Tz: TimeZone>(&self, date: NaiveDate, tz: &Tz) -> Vec<(i64, usize)> {
+        let sun = self.location.and_then(|at| sun_times(date, at));
+        let local = |time: NaiveTime| -> Option<i64> {
+            let naive = date.and_time(time);
+            // A time skipped by a DST change starts an hour later
+            tz.from_local_datetime(&naive)
+                .earliest()
+                .or_else(|| {
+                    tz.from_local_datetime(&(naive + Duration::hours(1)))
+                        .earliest()
+                })
+                .map(|start| start.timestamp())
+        };
+
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let start = match slot.start {
+                    SlotStart::At(time) => local(time)?,
+                    SlotStart::Sunrise(offset) => {
+                        sun.map(|(rise, _)| rise).or_else(|| {
+                            local(NaiveTime::from_hms_opt(FALLBACK_SUNRISE_HOUR, 0, 0)?)
+                        })? + offset * 60
+                    }
+                    SlotStart::Sunset(offset) => {
+                        sun.map(|(_, set)| set).or_else(|| {
+                            local(NaiveTime::from_hms_opt(FALLBACK_SUNSET_HOUR, 0, 0)?)
+                        })? + offset * 60
+                    }
+                };
+                Some((start, index))
+            })
+            .collect()
+    }
+}
+
+/// Sunrise and sunset on `date` as unix seconds, from the sunrise equation
+///
+/// None during polar day or night.
+pub fn sun_times(date: NaiveDate, at: Coordinates) -> Option<(i64, i64)> {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)?;
+    let midnight = (date - epoch).num_days() as f64 + UNIX_EPOCH_JULIAN;
+    let day = (midnight - J2000 + 0.0008).ceil();
+
+    let mean_noon = day - at.longitude / 360.0;
+    let anomaly = (357.5291 + 0.985_600_28 * mean_noon)
+        .rem_euclid(360.0)
+        .to_radians();
+    let center =
+        1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic = (anomaly.to_degrees() + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit = J2000 + mean_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic).sin();
+
+    let declination = (ecliptic.sin() * 23.4397_f64.to_radians().sin()).asin();
+    let latitude = at.latitude.to_radians();
+    let hour_angle = ((-0.833_f64).to_radians().sin() - latitude.sin() * declination.sin())
+        / (latitude.cos() * declination.cos());
+    if !(-1.0..=1.0).contains(&hour_angle) {
+        return None;
+    }
+
+    let half_day = hour_angle.acos().to_degrees() / 360.0;
+    let unix = |julian: f64| ((julian - UNIX_EPOCH_JULIAN) * 86_400.0).round() as i64;
+    Some((unix(transit - half_day), unix(transit + half_day)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    const BERLIN: Coordinates = Coordinates {
+        latitude: 52.52,
+        longitude: 13.405,
+    };
+
+    fn utc(date: NaiveDate, hour: u32, minute: u32) -> i64 {
+        date.and_hms_opt(hour, minute, 0)
+            .unwrap()
+            .and_utc()
+            .timestamp()
+    }
+
+    fn slot(name: &str, start: &str) -> ScheduleSlot {
+        ScheduleSlot {
+            name: name.to_string(),
+            start: start.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_slot_start_parses_clock_and_sun_relative_times() {
+        assert_eq!(
+            "07:30".parse(),
+            Ok(SlotStart::At(NaiveTime::from_hms_opt(7, 30, 0).unwrap()))
+        );
+        assert_eq!("sunrise".parse(), Ok(SlotStart::Sunrise(0)));
+        assert_eq!("sunset-30".parse(), Ok(SlotStart::Sunset(-30)));
+        assert_eq!("sunrise+45".parse(), Ok(SlotStart::Sunrise(45)));
+        assert!("sunset30".parse::<SlotStart>().is_err());
+        assert!("7pm".parse::<SlotStart>().is_err());
+
+        for value in ["07:30", "sunrise", "sunset-30", "sunrise+45"] {
+            assert_eq!(value.parse::<SlotStart>().unwrap().to_string(), value);
+        }
+    }
+
+    #[test]
+    fn test_sun_times_match_published_tables() {
+        // Berlin on the June solstice: 04:43 and 21:33 CEST
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let (rise, set) = sun_times(date, BERLIN).unwrap();
+        assert!((rise - utc(date, 2, 43)).abs() <= 180, "sunrise {rise}");
+        assert!((set - utc(date, 19, 33)).abs() <= 180, "sunset {set}");
+
+        // Midnight sun in Tromsø
+        let tromso = Coordinates {
+            latitude: 69.65,
+            longitude: 18.96,
+        };
+        assert_eq!(sun_times(date, tromso), None);
+    }
+
+    #[test]
+    fn test_active_slot_wraps_around_midnight() {
+        let schedule = DaySchedule {
+            slots: vec![slot("day", "07:00"), slot("night", "19:00")],
+            location: None,
+        };
+        let tz = FixedOffset::east_opt(3600).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+
+        // 03:00 local still belongs to the night that began yesterday
+        let early = schedule.active_in(&tz, utc(date, 2, 0)).unwrap();
+        assert_eq!(early.index, 1);
+        assert_eq!(early.started_at, utc(date.pred_opt().unwrap(), 18, 0));
+        assert_eq!(early.next_change, utc(date, 6, 0));
+
+        let noon = schedule.active_in(&tz, utc(date, 11, 0)).unwrap();
+        assert_eq!(noon.index, 0);
+        assert_eq!(noon.next_change, utc(date, 18, 0));
+
+        assert_eq!(DaySchedule::default().active_in(&tz, 0), None);
+    }
+
+    #[test]
+    fn test_sun_slots_follow_the_location_or_fall_back() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let mut schedule = DaySchedule {
+            slots: vec![slot("day", "sunrise"), slot("night", "sunset-30")],
+            location: None,
+        };
+        assert!(schedule.uses_sun());
+
+        // Without a location the sun rises at 06:00 and sets at 18:00
+        let night = schedule.active_in(&Utc, utc(date, 17, 45)).unwrap();
+        assert_eq!(night.index, 1);
+        assert_eq!(night.started_at, utc(date, 17, 30));
+
+        schedule.location = Some(BERLIN);
+        let day = schedule.active_in(&Utc, utc(date, 17, 45)).unwrap();
+        assert_eq!(day.index, 0);
+        let (_, set) = sun_times(date, BERLIN).unwrap();
+        assert_eq!(day.next_change, set - 30 * 60);
+    }
+}
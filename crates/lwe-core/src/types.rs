@@ -26,7 +26,7 @@ pub enum VideoSource {
         path: String, // Empty string means stdin
     },
 
-    /// GIF or image sequence
+    /// Image sequence played at a fixed rate
     ImageSequence {
         path: String,
         #[serde(default = "default_fps")]
@@ -40,6 +40,17 @@ pub enum VideoSource {
     /// Wallpaper Engine scene project (JSON-based with layers)
     #[serde(rename = "WeScene")]
     WeScene { path: String },
+
+    /// HTML/JS page rendered in a webview
+    Web { path: String },
+
+    /// Animated GIF, APNG or WebP, decoded by the engine without mpv
+    AnimatedImage {
+        path: String,
+        /// Times to play the animation before holding the last frame (None = forever)
+        #[serde(default)]
+        loop_count: Option<u32>,
+    },
 }
 
 // Manual Eq implementation for VideoSource (treating f64 as bits)
@@ -83,6 +94,15 @@ impl std::hash::Hash for VideoSource {
                 7u8.hash(state);
                 path.hash(state);
             }
+            VideoSource::Web { path } => {
+                8u8.hash(state);
+                path.hash(state);
+            }
+            VideoSource::AnimatedImage { path, loop_count } => {
+                9u8.hash(state);
+                path.hash(state);
+                loop_count.hash(state);
+            }
         }
     }
 }
@@ -109,6 +129,8 @@ impl VideoSource {
             VideoSource::ImageSequence { path, .. } => expand_tilde(path),
             VideoSource::WeProject { path } => expand_tilde(path),
             VideoSource::WeScene { path } => expand_tilde(path),
+            VideoSource::Web { path } => expand_tilde(path),
+            VideoSource::AnimatedImage { path, .. } => expand_tilde(path),
         }
     }
 
@@ -129,6 +151,8 @@ impl VideoSource {
             VideoSource::ImageSequence { path, .. } => path,
             VideoSource::WeProject { path } => path,
             VideoSource::WeScene { path } => path,
+            VideoSource::Web { path } => path,
+            VideoSource::AnimatedImage { path, .. } => path,
         }
     }
 
@@ -152,6 +176,18 @@ impl VideoSource {
     pub fn is_scene(&self) -> bool {
         matches!(self, VideoSource::WeScene { .. })
     }
+
+    /// Check if this is a web page (needs a webview instead of mpv)
+    #[inline]
+    pub fn is_web(&self) -> bool {
+        matches!(self, VideoSource::Web { .. })
+    }
+
+    /// Check if this is an animated image (uses the built-in decoder instead of mpv)
+    #[inline]
+    pub fn is_animated_image(&self) -> bool {
+        matches!(self, VideoSource::AnimatedImage { .. })
+    }
 }
 
 /// Expand ~ to home directory
@@ -182,10 +218,253 @@ pub enum LayoutMode {
 
     /// Center without scaling
     Centre,
+
+    /// Scale to fit like Contain and fill the bars with a heavily blurred,
+    /// scaled-up copy of the same frame (TV "ambient mode")
+    AmbientFill,
+
+    /// Cover every output showing the wallpaper as one canvas, each output
+    /// showing the part of the frame at its position on the desktop
+    Span,
+}
+
+impl std::str::FromStr for LayoutMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "fill" => Ok(Self::Fill),
+            "contain" => Ok(Self::Contain),
+            "stretch" => Ok(Self::Stretch),
+            "cover" => Ok(Self::Cover),
+            "centre" | "center" => Ok(Self::Centre),
+            "ambientfill" | "ambient-fill" | "ambient_fill" | "ambient" => Ok(Self::AmbientFill),
+            "span" => Ok(Self::Span),
+            _ => Err(format!("Unknown layout mode: {value}")),
+        }
+    }
+}
+
+/// What fills the bars around a letterboxed (Contain) or centred wallpaper
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum LetterboxFill {
+    /// Solid RGB color
+    Color([u8; 3]),
+
+    /// Blurred, stretched copy of the wallpaper itself
+    Blur,
+
+    /// Most common color of the current frame
+    Dominant,
+}
+
+impl Default for LetterboxFill {
+    fn default() -> Self {
+        Self::Color([0, 0, 0])
+    }
+}
+
+impl std::str::FromStr for LetterboxFill {
+    type Err = String;
+
+    /// Accepts `blur`, `dominant`, `black`, or a `#rrggbb` / `#rgb` color
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid letterbox fill: {value}");
+
+        match value.trim().to_ascii_lowercase().as_str() {
+            "blur" => Ok(Self::Blur),
+            "dominant" => Ok(Self::Dominant),
+            "black" => Ok(Self::default()),
+            color => {
+                let hex = color.strip_prefix('#').ok_or_else(invalid)?;
+                let channel = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| invalid());
+                match hex.len() {
+                    6 => Ok(Self::Color([
+                        channel(&hex[0..2])?,
+                        channel(&hex[2..4])?,
+                        channel(&hex[4..6])?,
+                    ])),
+                    3 => Ok(Self::Color([
+                        channel(&hex[0..1])? * 17,
+                        channel(&hex[1..2])? * 17,
+                        channel(&hex[2..3])? * 17,
+                    ])),
+                    _ => Err(invalid()),
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for LetterboxFill {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Color([r, g, b]) => write!(f, "#{r:02x}{g:02x}{b:02x}"),
+            Self::Blur => write!(f, "blur"),
+            Self::Dominant => write!(f, "dominant"),
+        }
+    }
+}
+
+impl TryFrom<String> for LetterboxFill {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<LetterboxFill> for String {
+    fn from(fill: LetterboxFill) -> Self {
+        fill.to_string()
+    }
+}
+
+/// How the outgoing wallpaper gives way when a new one is applied in place
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransitionKind {
+    /// Switch on the first frame of the new wallpaper
+    #[default]
+    None,
+
+    /// Fade the old wallpaper out over the new one
+    Crossfade,
+
+    /// Slide the old wallpaper off to the left
+    Slide,
+
+    /// Zoom into the old wallpaper while it fades out
+    Zoom,
+}
+
+impl std::str::FromStr for TransitionKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" | "off" => Ok(Self::None),
+            "crossfade" | "fade" => Ok(Self::Crossfade),
+            "slide" => Ok(Self::Slide),
+            "zoom" => Ok(Self::Zoom),
+            _ => Err(format!("Unknown transition: {value}")),
+        }
+    }
+}
+
+impl std::fmt::Display for TransitionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Crossfade => write!(f, "crossfade"),
+            Self::Slide => write!(f, "slide"),
+            Self::Zoom => write!(f, "zoom"),
+        }
+    }
+}
+
+/// Layer-shell layer a wallpaper surface is placed on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SurfaceLayer {
+    /// Below everything, where wallpapers normally go
+    #[default]
+    Background,
+
+    /// Above the background, for compositors and setups that draw their own
+    /// background over it
+    Bottom,
+}
+
+impl std::str::FromStr for SurfaceLayer {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "background" => Ok(Self::Background),
+            "bottom" => Ok(Self::Bottom),
+            _ => Err(format!("Unknown layer: {value} (background or bottom)")),
+        }
+    }
+}
+
+impl std::fmt::Display for SurfaceLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Background => write!(f, "background"),
+            Self::Bottom => write!(f, "bottom"),
+        }
+    }
+}
+
+/// Space left free between a wallpaper surface and the output's edges, in
+/// logical pixels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SurfaceMargins {
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub left: i32,
+}
+
+/// Color correction of one output. Each value scales its property, 1.0
+/// leaves it unchanged and the range is 0.0 to 2.0.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ColorAdjust {
+    pub brightness: f32,
+    pub contrast: f32,
+    pub gamma: f32,
+    pub saturation: f32,
+}
+
+impl Default for ColorAdjust {
+    fn default() -> Self {
+        Self {
+            brightness: 1.0,
+            contrast: 1.0,
+            gamma: 1.0,
+            saturation: 1.0,
+        }
+    }
+}
+
+impl ColorAdjust {
+    /// Highest value of each property
+    pub const MAX: f32 = 2.0;
+
+    /// Properties by name, in a fixed order
+    pub fn values(&self) -> [(&'static str, f32); 4] {
+        [
+            ("brightness", self.brightness),
+            ("contrast", self.contrast),
+            ("gamma", self.gamma),
+            ("saturation", self.saturation),
+        ]
+    }
+
+    /// Whether every property is left unchanged
+    pub fn is_neutral(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The first property outside 0.0 to 2.0
+    pub fn check(&self) -> Result<(), String> {
+        match self
+            .values()
+            .into_iter()
+            .find(|(_, value)| !(0.0..=Self::MAX).contains(value))
+        {
+            Some((name, value)) => Err(format!("{name} is 0 to {}, not {value}", Self::MAX)),
+            None => Ok(()),
+        }
+    }
 }
 
 /// HDR capabilities of an output
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct OutputHdrCapabilities {
     /// Whether the output supports HDR
     pub hdr_supported: bool,
@@ -211,6 +490,16 @@ impl Default for OutputHdrCapabilities {
     }
 }
 
+impl OutputHdrCapabilities {
+    /// Whether the display takes PQ-encoded (HDR10) content
+    pub fn accepts_pq(&self) -> bool {
+        self.hdr_supported
+            && self
+                .supported_eotf
+                .contains(&crate::hdr::TransferFunction::Pq)
+    }
+}
+
 /// Output information
 #[derive(Debug, Clone)]
 pub struct OutputInfo {
@@ -244,6 +533,17 @@ impl OutputInfo {
             self.height as f64 / self.scale,
         )
     }
+
+    /// Logical position and size on the desktop (x, y, width, height)
+    pub fn logical_rect(&self) -> (f64, f64, f64, f64) {
+        let (width, height) = self.logical_size();
+        (
+            f64::from(self.position.0),
+            f64::from(self.position.1),
+            width,
+            height,
+        )
+    }
 }
 
 /// Playback state
@@ -269,6 +569,19 @@ pub enum HwdecMode {
     No,
 }
 
+impl std::str::FromStr for HwdecMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "force" => Ok(Self::Force),
+            "no" => Ok(Self::No),
+            _ => Err(format!("Unknown hwdec mode: {value}")),
+        }
+    }
+}
+
 impl From<bool> for HwdecMode {
     fn from(enabled: bool) -> Self {
         if enabled {
@@ -340,4 +653,82 @@ mod tests {
         let mode: LayoutMode = Default::default();
         assert_eq!(mode, LayoutMode::Fill);
     }
+
+    #[test]
+    fn test_layout_mode_from_str() {
+        assert_eq!("Contain".parse::<LayoutMode>(), Ok(LayoutMode::Contain));
+        assert_eq!("center".parse::<LayoutMode>(), Ok(LayoutMode::Centre));
+        assert_eq!(
+            "ambient-fill".parse::<LayoutMode>(),
+            Ok(LayoutMode::AmbientFill)
+        );
+        assert_eq!("span".parse::<LayoutMode>(), Ok(LayoutMode::Span));
+        assert!("zoom".parse::<LayoutMode>().is_err());
+    }
+
+    #[test]
+    fn test_surface_layer_parse_and_display() {
+        assert_eq!("Bottom".parse::<SurfaceLayer>(), Ok(SurfaceLayer::Bottom));
+        assert_eq!(
+            " background ".parse::<SurfaceLayer>(),
+            Ok(SurfaceLayer::Background)
+        );
+        assert!("overlay".parse::<SurfaceLayer>().is_err());
+        assert_eq!(SurfaceLayer::Bottom.to_string(), "bottom");
+    }
+
+    #[test]
+    fn test_letterbox_fill_parse_and_display() {
+        assert_eq!(
+            "#1A2b3c".parse::<LetterboxFill>(),
+            Ok(LetterboxFill::Color([0x1a, 0x2b, 0x3c]))
+        );
+        assert_eq!(
+            "#fff".parse::<LetterboxFill>(),
+            Ok(LetterboxFill::Color([255, 255, 255]))
+        );
+        assert_eq!("Blur".parse::<LetterboxFill>(), Ok(LetterboxFill::Blur));
+        assert_eq!(
+            "black".parse::<LetterboxFill>(),
+            Ok(LetterboxFill::default())
+        );
+        assert!("#12345".parse::<LetterboxFill>().is_err());
+        assert!("red".parse::<LetterboxFill>().is_err());
+
+        assert_eq!(
+            LetterboxFill::Color([0x1a, 0x2b, 0x3c]).to_string(),
+            "#1a2b3c"
+        );
+        assert_eq!(LetterboxFill::Dominant.to_string(), "dominant");
+    }
+
+    #[test]
+    fn test_transition_kind_parse_and_display() {
+        assert_eq!(
+            "Fade".parse::<TransitionKind>(),
+            Ok(TransitionKind::Crossfade)
+        );
+        assert_eq!("off".parse::<TransitionKind>(), Ok(TransitionKind::None));
+        assert!("wipe".parse::<TransitionKind>().is_err());
+        assert_eq!(TransitionKind::Zoom.to_string(), "zoom");
+    }
+
+    #[test]
+    fn test_color_adjust_defaults_missing_values_and_checks_range() {
+        let color: ColorAdjust = serde_json::from_str(r#"{"brightness":0.9}"#).unwrap();
+        assert_eq!(color.brightness, 0.9);
+        assert_eq!(color.gamma, 1.0);
+        assert!(!color.is_neutral());
+        assert!(ColorAdjust::default().is_neutral());
+        assert_eq!(color.check(), Ok(()));
+
+        let color = ColorAdjust {
+            saturation: 2.5,
+            ..ColorAdjust::default()
+        };
+        assert_eq!(
+            color.check(),
+            Err("saturation is 0 to 2, not 2.5".to_string())
+        );
+    }
 }
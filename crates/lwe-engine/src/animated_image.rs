@@ -0,0 +1,419 @@
+//! Built-in player for animated GIF, APNG and WebP wallpapers
+//!
+//! mpv ignores per-frame GIF delays and cannot play animated WebP, so these
+//! sources are decoded with the `image` crate instead. Frames are streamed
+//! from the file rather than held in memory, paced on their own delays with
+//! [`FrameTiming`], and drawn by blitting one texture into the output's
+//! framebuffer with the same layout math mpv uses.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, Frames};
+use tracing::debug;
+
+use lwe_core::{animated_format, AnimatedFormat, LayoutTransform};
+
+use crate::frame_timing::FrameTiming;
+
+/// Delays below this are treated as unset, as browsers do
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+
+/// Delay used for frames that do not set a usable one
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// One composited frame, rows top to bottom
+struct DecodedFrame {
+    width: i32,
+    height: i32,
+    rgba: Vec<u8>,
+    delay: Duration,
+}
+
+/// Decoder and pacing state for one animated image
+pub struct AnimatedImagePlayer {
+    path: PathBuf,
+    format: AnimatedFormat,
+    frames: Frames<'static>,
+    /// Times to play the animation (None = forever)
+    loop_count: Option<u32>,
+    loops_played: u32,
+    /// Next frame to upload
+    pending: Option<DecodedFrame>,
+    /// Size of the frame last handed out
+    dimensions: (i32, i32),
+    timing: FrameTiming,
+    finished: bool,
+}
+
+impl AnimatedImagePlayer {
+    /// Open `path` and decode its first frame
+    pub fn open(path: &Path, loop_count: Option<u32>) -> Result<Self> {
+        let format = animated_format(path)
+            .ok_or_else(|| anyhow!("{} is not an animated image", path.display()))?;
+        let mut frames = open_frames(path, format)?;
+        let first =
+            next_frame(&mut frames)?.ok_or_else(|| anyhow!("{} has no frames", path.display()))?;
+        debug!(
+            "Opened {:?} animation {} ({}x{})",
+            format,
+            path.display(),
+            first.width,
+            first.height
+        );
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            format,
+            frames,
+            loop_count,
+            loops_played: 0,
+            dimensions: (first.width, first.height),
+            pending: Some(first),
+            timing: FrameTiming::new(0),
+            finished: false,
+        })
+    }
+
+    /// Size of the animation's canvas
+    pub fn dimensions(&self) -> (i32, i32) {
+        self.dimensions
+    }
+
+    /// Whether the last loop has played and the last frame is being held
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Time left until the next frame is due
+    pub fn time_until_next_frame(&self, now: Instant) -> Option<Duration> {
+        if self.finished {
+            return None;
+        }
+        self.timing.time_until_due(now)
+    }
+
+    /// Move to the next frame if its predecessor's delay has passed
+    ///
+    /// Returns the frame to show, or None while the current one should stay up.
+    fn advance(&mut self, now: Instant) -> Result<Option<DecodedFrame>> {
+        if self.finished || !self.timing.is_frame_due(now) {
+            return Ok(None);
+        }
+
+        let frame = match self.pending.take() {
+            Some(frame) => frame,
+            None => match next_frame(&mut self.frames)? {
+                Some(frame) => frame,
+                None => {
+                    self.loops_played += 1;
+                    if self
+                        .loop_count
+                        .is_some_and(|count| self.loops_played >= count)
+                    {
+                        self.finished = true;
+                        return Ok(None);
+                    }
+                    self.frames = open_frames(&self.path, self.format)?;
+                    next_frame(&mut self.frames)?
+                        .ok_or_else(|| anyhow!("{} has no frames on replay", self.path.display()))?
+                }
+            },
+        };
+
+        self.timing.schedule_frame_after(frame.delay, now);
+        self.dimensions = (frame.width, frame.height);
+        Ok(Some(frame))
+    }
+}
+
+/// Decode the first frame of `path`, to reject broken files before they are applied
+pub fn probe_animated_image(path: &Path) -> Result<()> {
+    AnimatedImagePlayer::open(path, None).map(|_| ())
+}
+
+fn open_frames(path: &Path, format: AnimatedFormat) -> Result<Frames<'static>> {
+    let reader = BufReader::new(
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?,
+    );
+    let frames = match format {
+        AnimatedFormat::Gif => GifDecoder::new(reader)?.into_frames(),
+        AnimatedFormat::Apng => PngDecoder::new(reader)?.apng()?.into_frames(),
+        AnimatedFormat::WebP => WebPDecoder::new(reader)?.into_frames(),
+    };
+    Ok(frames)
+}
+
+fn next_frame(frames: &mut Frames<'static>) -> Result<Option<DecodedFrame>> {
+    let Some(frame) = frames.next() else {
+        return Ok(None);
+    };
+    let frame = frame.context("Failed to decode animation frame")?;
+    let delay = frame_delay(frame.delay().numer_denom_ms());
+    let buffer = frame.into_buffer();
+
+    Ok(Some(DecodedFrame {
+        width: buffer.width() as i32,
+        height: buffer.height() as i32,
+        rgba: buffer.into_raw(),
+        delay,
+    }))
+}
+
+/// Frame delay from the decoder's `numerator / denominator` milliseconds
+fn frame_delay((numer, denom): (u32, u32)) -> Duration {
+    let delay = Duration::from_secs_f64(f64::from(numer) / f64::from(denom.max(1)) / 1000.0);
+    if delay < MIN_FRAME_DELAY {
+        DEFAULT_FRAME_DELAY
+    } else {
+        delay
+    }
+}
+
+/// GL texture holding the current frame, blitted into the output
+///
+/// Still images are drawn through it too, uploaded once with [`Self::upload`].
+#[derive(Default)]
+pub(crate) struct AnimatedImageBlit {
+    framebuffer: u32,
+    texture: u32,
+    size: (i32, i32),
+}
+
+impl AnimatedImageBlit {
+    /// Upload the player's next frame if one is due
+    ///
+    /// Returns true when the output needs redrawing. The output's context
+    /// must be current.
+    pub(crate) fn update(
+        &mut self,
+        player: &mut AnimatedImagePlayer,
+        now: Instant,
+    ) -> Result<bool> {
+        let Some(frame) = player.advance(now)? else {
+            return Ok(false);
+        };
+        self.upload(frame.width, frame.height, &frame.rgba);
+        Ok(true)
+    }
+
+    /// Replace the texture with `rgba`, rows top to bottom
+    ///
+    /// The output's context must be current.
+    pub(crate) fn upload(&mut self, width: i32, height: i32, rgba: &[u8]) {
+        unsafe {
+            self.ensure_resources();
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            if self.size == (width, height) {
+                gl::TexSubImage2D(
+                    gl::TEXTURE_2D,
+                    0,
+                    0,
+                    0,
+                    width,
+                    height,
+                    gl::RGBA,
+                    gl::UNSIGNED_BYTE,
+                    rgba.as_ptr().cast(),
+                );
+            } else {
+                gl::TexImage2D(
+                    gl::TEXTURE_2D,
+                    0,
+                    gl::RGBA8 as i32,
+                    width,
+                    height,
+                    0,
+                    gl::RGBA,
+                    gl::UNSIGNED_BYTE,
+                    rgba.as_ptr().cast(),
+                );
+                self.size = (width, height);
+            }
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    /// Clear the output and draw the current frame with `layout`
+    pub(crate) fn draw(&self, layout: &LayoutTransform, width: i32, height: i32) {
+        if self.framebuffer == 0 {
+            return;
+        }
+        let (src_x0, src_y0, src_x1, src_y1) = source_rect(layout, self.size);
+        let (dst_x0, dst_y0, dst_x1, dst_y1) = destination_rect(layout, height);
+
+        unsafe {
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
+            gl::Viewport(0, 0, width, height);
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.framebuffer);
+            gl::BlitFramebuffer(
+                src_x0,
+                src_y0,
+                src_x1,
+                src_y1,
+                dst_x0,
+                dst_y0,
+                dst_x1,
+                dst_y1,
+                gl::COLOR_BUFFER_BIT,
+                gl::LINEAR,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    /// Release GL objects; the output's context must be current
+    pub(crate) fn destroy(&mut self) {
+        unsafe {
+            if self.framebuffer != 0 {
+                gl::DeleteFramebuffers(1, &self.framebuffer);
+            }
+            if self.texture != 0 {
+                gl::DeleteTextures(1, &self.texture);
+            }
+        }
+        *self = Self::default();
+    }
+
+    unsafe fn ensure_resources(&mut self) {
+        if self.framebuffer != 0 {
+            return;
+        }
+
+        unsafe {
+            gl::GenTextures(1, &mut self.texture);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            // Allocate storage so the framebuffer is complete before the first upload
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8 as i32,
+                1,
+                1,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                std::ptr::null(),
+            );
+            self.size = (1, 1);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+
+            gl::GenFramebuffers(1, &mut self.framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                self.texture,
+                0,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+}
+
+/// Texture rectangle for the layout's crop as (x0, y0, x1, y1)
+///
+/// Frames are uploaded top row first, so texture y grows downwards in the image.
+fn source_rect(layout: &LayoutTransform, (width, height): (i32, i32)) -> (i32, i32, i32, i32) {
+    let (x, y, w, h) = layout.src_rect;
+    let (width, height) = (f64::from(width), f64::from(height));
+    (
+        (x * width).round() as i32,
+        (y * height).round() as i32,
+        ((x + w) * width).round() as i32,
+        ((y + h) * height).round() as i32,
+    )
+}
+
+/// Framebuffer rectangle as (x0, y0, x1, y1), flipped so the image's top row lands on top
+fn destination_rect(layout: &LayoutTransform, output_height: i32) -> (i32, i32, i32, i32) {
+    let (x, y, w, h) = layout.dst_rect;
+    (x, output_height - y, x + w, output_height - y - h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lwe_core::{calculate_layout, LayoutMode};
+
+    #[test]
+    fn test_frame_delay_treats_tiny_delays_as_unset() {
+        assert_eq!(frame_delay((0, 1)), DEFAULT_FRAME_DELAY);
+        assert_eq!(frame_delay((10, 1)), DEFAULT_FRAME_DELAY);
+        assert_eq!(frame_delay((40, 1)), Duration::from_millis(40));
+        assert_eq!(frame_delay((100, 3)), Duration::from_secs_f64(0.1 / 3.0));
+    }
+
+    #[test]
+    fn test_blit_rects_crop_and_flip() {
+        // 2:1 animation on a 1:1 output crops the sides
+        let layout = calculate_layout(LayoutMode::Fill, 200, 100, 100, 100);
+
+        assert_eq!(source_rect(&layout, (200, 100)), (50, 0, 150, 100));
+        assert_eq!(destination_rect(&layout, 100), (0, 100, 100, 0));
+    }
+
+    fn write_gif(name: &str, frames: usize) -> PathBuf {
+        use image::codecs::gif::GifEncoder;
+        use image::{Delay, Frame, RgbaImage};
+
+        let path = std::env::temp_dir().join(format!("lwe-{}-{name}", std::process::id()));
+        let mut encoder = GifEncoder::new(File::create(&path).unwrap());
+        for i in 0..frames {
+            let buffer = RgbaImage::from_pixel(4, 2, image::Rgba([i as u8 * 60, 0, 0, 255]));
+            encoder
+                .encode_frame(Frame::from_parts(
+                    buffer,
+                    0,
+                    0,
+                    Delay::from_numer_denom_ms(40, 1),
+                ))
+                .unwrap();
+        }
+        drop(encoder);
+        path
+    }
+
+    #[test]
+    fn test_player_paces_frames_and_stops_after_loop_count() {
+        let path = write_gif("twice.gif", 2);
+        let mut player = AnimatedImagePlayer::open(&path, Some(2)).unwrap();
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert_eq!(player.dimensions(), (4, 2));
+        let shown: Vec<u64> = [0, 10, 40, 60, 80, 120, 160, 200]
+            .into_iter()
+            .filter(|&ms| player.advance(at(ms)).unwrap().is_some())
+            .collect();
+
+        // Two loops of two frames, then the last frame is held
+        assert_eq!(shown, vec![0, 40, 80, 120]);
+        assert!(player.is_finished());
+        assert_eq!(player.time_until_next_frame(at(200)), None);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_open_rejects_non_animated_files() {
+        let path = std::env::temp_dir().join(format!("lwe-still-{}.png", std::process::id()));
+        std::fs::write(&path, b"not an image").unwrap();
+
+        assert!(AnimatedImagePlayer::open(&path, None).is_err());
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,103 @@
+//! What this system offers the engine
+//!
+//! Gathered once for `lwe check` and bug reports: the compositor's Wayland
+//! globals, EGL on its display, the hardware decoders behind each render
+//! node, the Vulkan loader and libmpv. Every probe fails on its own, so one
+//! missing piece never hides the others. Nothing here runs while wallpapers
+//! play.
+
+use std::ffi::c_void;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_registry::{self, WlRegistry};
+use wayland_client::{Connection, Dispatch, QueueHandle};
+
+use crate::egl::{egl_info, EglInfo};
+use crate::gpu::{library_loads, nvdec_support, render_nodes, vaapi_support, VaapiSupport};
+use crate::mpv::{mpv_version, MpvVersion};
+
+/// A global the compositor advertises
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaylandGlobal {
+    /// Interface name, e.g. `zwlr_layer_shell_v1`
+    pub interface: String,
+    pub version: u32,
+}
+
+/// What [`probe_system`] found
+#[derive(Debug)]
+pub struct SystemCapabilities {
+    /// Globals of the compositor at `WAYLAND_DISPLAY`
+    pub wayland_globals: Result<Vec<WaylandGlobal>, String>,
+    /// EGL on the compositor's display
+    pub egl: Result<EglInfo, String>,
+    /// VAAPI on each render node
+    pub vaapi: Vec<(PathBuf, Result<VaapiSupport, String>)>,
+    /// NVIDIA driver version NVDEC can decode with
+    pub nvdec: Result<String, String>,
+    /// Whether the Vulkan loader is installed
+    pub vulkan_loader: bool,
+    /// Versions of the libmpv this process loads
+    pub mpv: Result<MpvVersion, String>,
+}
+
+/// Probe the compositor, GPU drivers and libraries the engine relies on
+pub fn probe_system() -> SystemCapabilities {
+    let connection =
+        Connection::connect_to_env().map_err(|e| format!("No Wayland connection: {e}"));
+    let (wayland_globals, egl) = match &connection {
+        Ok(connection) => (
+            wayland_globals(connection).map_err(|e| format!("{e:#}")),
+            egl_info(connection.backend().display_ptr() as *mut c_void)
+                .map_err(|e| format!("{e:#}")),
+        ),
+        Err(e) => (Err(e.clone()), Err(e.clone())),
+    };
+
+    SystemCapabilities {
+        wayland_globals,
+        egl,
+        vaapi: render_nodes()
+            .into_iter()
+            .map(|node| {
+                let support = vaapi_support(&node.path);
+                (node.path, support)
+            })
+            .collect(),
+        nvdec: nvdec_support(),
+        vulkan_loader: library_loads(c"libvulkan.so.1"),
+        mpv: mpv_version().map_err(|e| format!("{e:#}")),
+    }
+}
+
+fn wayland_globals(connection: &Connection) -> Result<Vec<WaylandGlobal>> {
+    let (globals, _queue) =
+        registry_queue_init::<GlobalProbe>(connection).context("Failed to initialize registry")?;
+    Ok(globals
+        .contents()
+        .clone_list()
+        .into_iter()
+        .map(|global| WaylandGlobal {
+            interface: global.interface,
+            version: global.version,
+        })
+        .collect())
+}
+
+/// Registry state for reading the initial global list
+struct GlobalProbe;
+
+// Only the initial global list is read; later additions and removals are ignored
+impl Dispatch<WlRegistry, GlobalListContents> for GlobalProbe {
+    fn event(
+        _state: &mut Self,
+        _registry: &WlRegistry,
+        _event: wl_registry::Event,
+        _data: &GlobalListContents,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
@@ -0,0 +1,411 @@
+//! Deterministic frame capture for visual regression tests
+//!
+//! A hidden mode renders a source at fixed timestamps into PNG files, so
+//! golden images can catch changes to the layout math across refactors.
+//! Decoding runs through libmpv's software renderer with hardware decoding,
+//! dithering and debanding off, and every frame is an exact seek on a
+//! paused player, so the same build produces the same pixels without vsync
+//! or a display. Layout is applied on the CPU with the same
+//! [`calculate_layout`] the GL path uses; shaders, LUTs and letterbox fills
+//! need a GL context and are not part of a capture.
+
+use std::ffi::{c_int, c_void, CString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use image::imageops::{self, FilterType};
+use image::RgbImage;
+use tracing::{debug, info, warn};
+
+use lwe_core::{calculate_layout, LayoutMode};
+
+use crate::mpv::{
+    render_update_callback, MPV_RENDER_PARAM_API_TYPE, MPV_RENDER_PARAM_INVALID,
+    MPV_RENDER_UPDATE_FRAME,
+};
+use crate::preview::{
+    MPV_RENDER_PARAM_SW_FORMAT, MPV_RENDER_PARAM_SW_POINTER, MPV_RENDER_PARAM_SW_SIZE,
+    MPV_RENDER_PARAM_SW_STRIDE,
+};
+
+// Event ids (from libmpv/client.h)
+const MPV_EVENT_END_FILE: u32 = 7;
+const MPV_EVENT_FILE_LOADED: u32 = 8;
+const MPV_EVENT_PLAYBACK_RESTART: u32 = 21;
+
+/// How long loading, seeking or rendering one frame may take
+const STEP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// What to capture
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureConfig {
+    /// Size of the simulated output
+    pub output_size: (u32, u32),
+    pub layout: LayoutMode,
+    /// Positions in the source to capture, one PNG each
+    pub timestamps: Vec<Duration>,
+}
+
+/// Render `source` at each timestamp and write `frame-<ms>.png` files to
+/// `out_dir`; returns the written paths in timestamp order
+pub fn capture_frames(
+    source: &Path,
+    config: &CaptureConfig,
+    out_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let (width, height) = config.output_size;
+    if width == 0 || height == 0 {
+        return Err(anyhow!("Capture size must not be empty"));
+    }
+    fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create {}", out_dir.display()))?;
+
+    let mut capture = FrameCapture::new()?;
+    capture.load(source)?;
+
+    let mut written = Vec::with_capacity(config.timestamps.len());
+    for timestamp in &config.timestamps {
+        let frame = capture.frame_at(*timestamp)?;
+        let composed = compose_frame(&frame, config.layout, config.output_size);
+        let path = out_dir.join(frame_file_name(*timestamp));
+        composed
+            .save(&path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        debug!("Captured {}", path.display());
+        written.push(path);
+    }
+
+    info!(
+        "Captured {} frame(s) of {} at {}x{}",
+        written.len(),
+        source.display(),
+        width,
+        height
+    );
+    Ok(written)
+}
+
+/// Paused libmpv instance that renders exact frames into memory
+struct FrameCapture {
+    handle: *mut libmpv_sys::mpv_handle,
+    render_context: *mut libmpv_sys::mpv_render_context,
+    frame_available: Arc<AtomicBool>,
+}
+
+impl FrameCapture {
+    fn new() -> Result<Self> {
+        let handle = unsafe { libmpv_sys::mpv_create() };
+        if handle.is_null() {
+            return Err(anyhow!("Failed to create MPV handle"));
+        }
+
+        let set_option = |name: &str, value: &str| {
+            let name_c = CString::new(name).unwrap();
+            let value_c = CString::new(value).unwrap();
+            unsafe {
+                let ret =
+                    libmpv_sys::mpv_set_option_string(handle, name_c.as_ptr(), value_c.as_ptr());
+                if ret < 0 {
+                    warn!("Failed to set option {}={}: error {}", name, value, ret);
+                }
+            }
+        };
+
+        set_option("config", "no");
+        set_option("terminal", "no");
+        set_option("msg-level", "all=warn");
+        set_option("vo", "libmpv");
+        set_option("audio", "no");
+        set_option("pause", "yes");
+        set_option("keep-open", "always");
+        set_option("hr-seek", "yes");
+        // Hardware decoders and random dither/deband grain differ between runs
+        set_option("hwdec", "no");
+        set_option("dither-depth", "no");
+        set_option("deband", "no");
+
+        let ret = unsafe { libmpv_sys::mpv_initialize(handle) };
+        if ret < 0 {
+            unsafe { libmpv_sys::mpv_terminate_destroy(handle) };
+            return Err(anyhow!("Failed to initialize MPV: error {}", ret));
+        }
+
+        let api_type = CString::new("sw").unwrap();
+        let params = [
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_API_TYPE,
+                data: api_type.as_ptr() as *mut c_void,
+            },
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_INVALID,
+                data: ptr::null_mut(),
+            },
+        ];
+
+        let mut render_context: *mut libmpv_sys::mpv_render_context = ptr::null_mut();
+        let ret = unsafe {
+            libmpv_sys::mpv_render_context_create(
+                &mut render_context,
+                handle,
+                params.as_ptr() as *mut _,
+            )
+        };
+        if ret < 0 {
+            unsafe { libmpv_sys::mpv_terminate_destroy(handle) };
+            return Err(anyhow!(
+                "Failed to create mpv software render context: error {}",
+                ret
+            ));
+        }
+
+        let frame_available = Arc::new(AtomicBool::new(false));
+        unsafe {
+            libmpv_sys::mpv_render_context_set_update_callback(
+                render_context,
+                Some(render_update_callback),
+                Arc::as_ptr(&frame_available) as *mut c_void,
+            );
+        }
+
+        Ok(Self {
+            handle,
+            render_context,
+            frame_available,
+        })
+    }
+
+    fn load(&mut self, path: &Path) -> Result<()> {
+        self.command(&["loadfile", &path.to_string_lossy(), "replace"])
+            .with_context(|| format!("Failed to load {}", path.display()))?;
+        self.wait_for_event(MPV_EVENT_FILE_LOADED)
+            .with_context(|| format!("Failed to load {}", path.display()))
+    }
+
+    /// Seek exactly to `timestamp` and render the frame shown there
+    fn frame_at(&mut self, timestamp: Duration) -> Result<RgbImage> {
+        self.frame_available.store(false, Ordering::Release);
+        let position = format!("{:.3}", timestamp.as_secs_f64());
+        self.command(&["seek", &position, "absolute+exact"])?;
+        self.wait_for_event(MPV_EVENT_PLAYBACK_RESTART)
+            .with_context(|| format!("Failed to seek to {position}s"))?;
+
+        let deadline = Instant::now() + STEP_TIMEOUT;
+        loop {
+            if self.frame_available.swap(false, Ordering::AcqRel) {
+                let flags = unsafe { libmpv_sys::mpv_render_context_update(self.render_context) };
+                if flags & MPV_RENDER_UPDATE_FRAME != 0 {
+                    return self.render();
+                }
+            }
+            if Instant::now() > deadline {
+                return Err(anyhow!("No frame was decoded at {position}s"));
+            }
+            self.next_event(0.01);
+        }
+    }
+
+    fn render(&mut self) -> Result<RgbImage> {
+        let width = self.property_i64("dwidth").unwrap_or(0);
+        let height = self.property_i64("dheight").unwrap_or(0);
+        if width <= 0 || height <= 0 {
+            return Err(anyhow!("Source has no video frames"));
+        }
+        let (width, height) = (width as usize, height as usize);
+
+        // mpv's converters want 64-byte aligned rows
+        let stride = (width * 4).next_multiple_of(64);
+        let mut pixels = vec![0u8; stride * height];
+        let size: [c_int; 2] = [width as c_int, height as c_int];
+        let format = CString::new("rgb0").unwrap();
+        let params = [
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_SW_SIZE,
+                data: size.as_ptr() as *mut c_void,
+            },
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_SW_FORMAT,
+                data: format.as_ptr() as *mut c_void,
+            },
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_SW_STRIDE,
+                data: &stride as *const usize as *mut c_void,
+            },
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_SW_POINTER,
+                data: pixels.as_mut_ptr() as *mut c_void,
+            },
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_INVALID,
+                data: ptr::null_mut(),
+            },
+        ];
+
+        let ret = unsafe {
+            libmpv_sys::mpv_render_context_render(self.render_context, params.as_ptr() as *mut _)
+        };
+        if ret < 0 {
+            return Err(anyhow!("mpv software render error: {}", ret));
+        }
+
+        let rgb = pixels
+            .chunks_exact(stride)
+            .flat_map(|row| row[..width * 4].chunks_exact(4))
+            .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+            .collect::<Vec<_>>();
+        RgbImage::from_raw(width as u32, height as u32, rgb)
+            .ok_or_else(|| anyhow!("Rendered frame has the wrong size"))
+    }
+
+    fn command(&self, args: &[&str]) -> Result<()> {
+        let args_c = args
+            .iter()
+            .map(|arg| CString::new(*arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut argv = args_c
+            .iter()
+            .map(|arg| arg.as_ptr())
+            .chain(std::iter::once(ptr::null()))
+            .collect::<Vec<_>>();
+
+        let ret = unsafe { libmpv_sys::mpv_command(self.handle, argv.as_mut_ptr()) };
+        if ret < 0 {
+            return Err(anyhow!("mpv command {} failed: error {}", args[0], ret));
+        }
+        Ok(())
+    }
+
+    /// Wait for `event_id`; the file ending first is an error
+    fn wait_for_event(&mut self, event_id: u32) -> Result<()> {
+        let deadline = Instant::now() + STEP_TIMEOUT;
+        while Instant::now() < deadline {
+            match self.next_event(0.1) {
+                Some(id) if id == event_id => return Ok(()),
+                Some(MPV_EVENT_END_FILE) => return Err(anyhow!("mpv reached the end of the file")),
+                _ => {}
+            }
+        }
+        Err(anyhow!("Timed out waiting for mpv"))
+    }
+
+    fn next_event(&mut self, timeout: f64) -> Option<u32> {
+        let event = unsafe { libmpv_sys::mpv_wait_event(self.handle, timeout) };
+        if event.is_null() {
+            return None;
+        }
+        match unsafe { (*event).event_id } {
+            0 => None,
+            id => Some(id),
+        }
+    }
+
+    fn property_i64(&self, name: &str) -> Option<i64> {
+        let name_c = CString::new(name).ok()?;
+        let mut value: i64 = 0;
+        let ret = unsafe {
+            libmpv_sys::mpv_get_property(
+                self.handle,
+                name_c.as_ptr(),
+                4, // MPV_FORMAT_INT64
+                &mut value as *mut i64 as *mut c_void,
+            )
+        };
+        (ret >= 0).then_some(value)
+    }
+}
+
+impl Drop for FrameCapture {
+    fn drop(&mut self) {
+        unsafe {
+            libmpv_sys::mpv_render_context_free(self.render_context);
+            libmpv_sys::mpv_terminate_destroy(self.handle);
+        }
+    }
+}
+
+/// Place `frame` on a black output of `size` the way `layout` does on screen
+pub fn compose_frame(frame: &RgbImage, layout: LayoutMode, size: (u32, u32)) -> RgbImage {
+    let (width, height) = size;
+    let mut canvas = RgbImage::new(width, height);
+    let (frame_width, frame_height) = frame.dimensions();
+    if frame_width == 0 || frame_height == 0 {
+        return canvas;
+    }
+
+    let transform = calculate_layout(
+        layout,
+        frame_width as i32,
+        frame_height as i32,
+        width as i32,
+        height as i32,
+    );
+    let (sx, sy, sw, sh) = transform.src_rect;
+    let (fw, fh) = (f64::from(frame_width), f64::from(frame_height));
+    let x = ((sx * fw).round() as u32).min(frame_width - 1);
+    let y = ((sy * fh).round() as u32).min(frame_height - 1);
+    let crop_width = ((sw * fw).round() as u32).clamp(1, frame_width - x);
+    let crop_height = ((sh * fh).round() as u32).clamp(1, frame_height - y);
+
+    let (dx, dy, dw, dh) = transform.dst_rect;
+    if dw <= 0 || dh <= 0 {
+        return canvas;
+    }
+    let cropped = imageops::crop_imm(frame, x, y, crop_width, crop_height).to_image();
+    let scaled = imageops::resize(&cropped, dw as u32, dh as u32, FilterType::Triangle);
+    imageops::overlay(&mut canvas, &scaled, i64::from(dx), i64::from(dy));
+    canvas
+}
+
+/// `frame-001500.png` for 1.5s; zero-padded so files sort by time
+fn frame_file_name(timestamp: Duration) -> String {
+    format!("frame-{:06}.png", timestamp.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    const RED: Rgb<u8> = Rgb([255, 0, 0]);
+    const GREEN: Rgb<u8> = Rgb([0, 255, 0]);
+    const BLUE: Rgb<u8> = Rgb([0, 0, 255]);
+    const WHITE: Rgb<u8> = Rgb([255, 255, 255]);
+    const BLACK: Rgb<u8> = Rgb([0, 0, 0]);
+
+    fn columns(colors: &[Rgb<u8>], height: u32) -> RgbImage {
+        RgbImage::from_fn(colors.len() as u32, height, |x, _| colors[x as usize])
+    }
+
+    #[test]
+    fn test_compose_frame_matches_golden_layouts() {
+        let frame = columns(&[RED, GREEN, BLUE, WHITE], 2);
+
+        // Fill crops the sides of a wide frame on a square output
+        let filled = compose_frame(&frame, LayoutMode::Fill, (2, 2));
+        assert_eq!(filled, columns(&[GREEN, BLUE], 2));
+
+        // Contain keeps the whole frame and leaves bars above and below
+        let contained = compose_frame(&frame, LayoutMode::Contain, (4, 4));
+        let golden = RgbImage::from_fn(4, 4, |x, y| {
+            if (1..3).contains(&y) {
+                *frame.get_pixel(x, 0)
+            } else {
+                BLACK
+            }
+        });
+        assert_eq!(contained, golden);
+    }
+
+    #[test]
+    fn test_frame_file_names_sort_by_time() {
+        assert_eq!(
+            frame_file_name(Duration::from_millis(1500)),
+            "frame-001500.png"
+        );
+        assert!(frame_file_name(Duration::from_secs(2)) < frame_file_name(Duration::from_secs(10)));
+    }
+}
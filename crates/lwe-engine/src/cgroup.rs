@@ -0,0 +1,175 @@
+//! cgroup resource limits
+//!
+//! When LWE runs as a systemd service, the shell caps its unit with
+//! `CPUQuota=` and `MemoryHigh=` derived from [`PowerConfig`]. The engine
+//! reads back what its cgroup uses so status queries can report it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use lwe_core::PowerConfig;
+
+/// Hard caps for the unit the wallpaper process runs in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// Percent of one core (200 = two cores); None = unlimited
+    pub cpu_quota_percent: Option<u32>,
+    /// Memory above which the kernel throttles and reclaims; None = unlimited
+    pub memory_high_mb: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub fn from_power(power: &PowerConfig) -> Self {
+        Self {
+            cpu_quota_percent: (power.cpu_quota_percent > 0).then_some(power.cpu_quota_percent),
+            memory_high_mb: (power.memory_high_mb > 0).then_some(power.memory_high_mb as u64),
+        }
+    }
+
+    /// `systemctl set-property` assignments; unset limits reset the property
+    pub fn properties(&self) -> Vec<String> {
+        vec![
+            match self.cpu_quota_percent {
+                Some(percent) => format!("CPUQuota={percent}%"),
+                None => "CPUQuota=".to_string(),
+            },
+            match self.memory_high_mb {
+                Some(mb) => format!("MemoryHigh={mb}M"),
+                None => "MemoryHigh=infinity".to_string(),
+            },
+        ]
+    }
+}
+
+/// Resource usage and limits of the cgroup the engine runs in
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CgroupUsage {
+    /// cgroup path, e.g. `/user.slice/user-1000.slice/user@1000.service/app.slice/lwe.service`
+    pub path: String,
+    /// Memory currently charged to the cgroup
+    pub memory_bytes: Option<u64>,
+    /// `memory.high` (None = no limit)
+    pub memory_high_bytes: Option<u64>,
+    /// CPU time used since the cgroup was created
+    pub cpu_time: Option<Duration>,
+    /// `cpu.max` as percent of one core (None = no limit)
+    pub cpu_quota_percent: Option<u32>,
+}
+
+impl CgroupUsage {
+    /// systemd unit owning the cgroup, e.g. `lwe.service`
+    pub fn unit(&self) -> Option<&str> {
+        unit_of(&self.path)
+    }
+}
+
+/// cgroup v2 path of this process
+pub fn current_cgroup() -> Option<String> {
+    cgroup_from_proc(&fs::read_to_string("/proc/self/cgroup").ok()?)
+}
+
+/// Usage of this process's cgroup; None outside a cgroup v2 hierarchy
+pub fn cgroup_usage() -> Option<CgroupUsage> {
+    let path = current_cgroup()?;
+    let dir = PathBuf::from("/sys/fs/cgroup").join(path.trim_start_matches('/'));
+    Some(usage_in(path, &dir))
+}
+
+/// systemd unit named by the last component of a cgroup path
+pub fn unit_of(cgroup: &str) -> Option<&str> {
+    cgroup
+        .rsplit('/')
+        .next()
+        .filter(|unit| unit.ends_with(".service") || unit.ends_with(".scope"))
+}
+
+fn cgroup_from_proc(contents: &str) -> Option<String> {
+    contents
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(|path| path.trim().to_string())
+}
+
+fn usage_in(path: String, dir: &Path) -> CgroupUsage {
+    let read = |file: &str| {
+        fs::read_to_string(dir.join(file))
+            .ok()
+            .map(|value| value.trim().to_string())
+    };
+
+    CgroupUsage {
+        memory_bytes: read("memory.current").and_then(|value| value.parse().ok()),
+        memory_high_bytes: read("memory.high").and_then(|value| value.parse().ok()),
+        cpu_time: read("cpu.stat").and_then(|stat| {
+            stat.lines()
+                .find_map(|line| line.strip_prefix("usage_usec "))
+                .and_then(|usec| usec.trim().parse().ok())
+                .map(Duration::from_micros)
+        }),
+        cpu_quota_percent: read("cpu.max").and_then(|max| {
+            let (quota, period) = max.split_once(' ')?;
+            let quota = quota.parse::<u64>().ok()?;
+            let period = period
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .filter(|period| *period > 0)?;
+            u32::try_from(quota * 100 / period).ok()
+        }),
+        path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_from_power_config_reset_unset_properties() {
+        let power = PowerConfig {
+            cpu_quota_percent: 50,
+            ..PowerConfig::default()
+        };
+        let limits = ResourceLimits::from_power(&power);
+        assert_eq!(
+            limits,
+            ResourceLimits {
+                cpu_quota_percent: Some(50),
+                memory_high_mb: None,
+            }
+        );
+        assert_eq!(limits.properties(), ["CPUQuota=50%", "MemoryHigh=infinity"]);
+    }
+
+    #[test]
+    fn test_usage_reads_cgroup_v2_files() {
+        assert_eq!(
+            cgroup_from_proc(
+                "0::/user.slice/user-1000.slice/user@1000.service/app.slice/lwe.service\n"
+            )
+            .as_deref()
+            .and_then(unit_of),
+            Some("lwe.service")
+        );
+
+        let dir = std::env::temp_dir().join(format!("lwe-cgroup-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("memory.current"), "146800640\n").unwrap();
+        fs::write(dir.join("memory.high"), "max\n").unwrap();
+        fs::write(
+            dir.join("cpu.stat"),
+            "usage_usec 2500000\nuser_usec 2000000\n",
+        )
+        .unwrap();
+        fs::write(dir.join("cpu.max"), "50000 100000\n").unwrap();
+
+        let usage = usage_in("/app.slice/lwe.service".to_string(), &dir);
+        assert_eq!(usage.memory_bytes, Some(146_800_640));
+        assert_eq!(usage.memory_high_bytes, None);
+        assert_eq!(usage.cpu_time, Some(Duration::from_millis(2500)));
+        assert_eq!(usage.cpu_quota_percent, Some(50));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
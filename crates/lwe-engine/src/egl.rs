@@ -1,17 +1,43 @@
 //! EGL context management for OpenGL rendering on Wayland
 
+use std::ffi::{c_char, c_void, CStr};
+use std::path::Path;
+use std::ptr;
+
 use anyhow::{anyhow, Context, Result};
 use khronos_egl as egl;
 use wayland_client::protocol::wl_surface::WlSurface;
 use wayland_client::Proxy;
 use wayland_egl as wegl;
 
+use crate::gpu::canonical_render_node;
+
+// EGL_EXT_platform_wayland, EGL_EXT_explicit_device and
+// EGL_EXT_device_drm_render_node
+const EGL_PLATFORM_WAYLAND_EXT: egl::Enum = 0x31D8;
+const EGL_DEVICE_EXT: egl::Attrib = 0x322C;
+const EGL_DRM_RENDER_NODE_FILE_EXT: egl::Int = 0x3377;
+// EGL_KHR_create_context
+const EGL_CONTEXT_FLAGS_KHR: egl::Int = 0x30FC;
+const EGL_CONTEXT_OPENGL_DEBUG_BIT_KHR: egl::Int = 0x0001;
+const EGL_CONTEXT_OPENGL_ROBUST_ACCESS_BIT_KHR: egl::Int = 0x0004;
+const EGL_CONTEXT_OPENGL_RESET_NOTIFICATION_STRATEGY_KHR: egl::Int = 0x31BD;
+const EGL_LOSE_CONTEXT_ON_RESET_KHR: egl::Int = 0x31BF;
+
+type QueryDevicesExt =
+    unsafe extern "system" fn(egl::Int, *mut *mut c_void, *mut egl::Int) -> egl::Boolean;
+type QueryDeviceStringExt = unsafe extern "system" fn(*mut c_void, egl::Int) -> *const c_char;
+type GetPlatformDisplay =
+    unsafe extern "system" fn(egl::Enum, *mut c_void, *const egl::Attrib) -> egl::EGLDisplay;
+
 /// EGL context manager for OpenGL rendering on Wayland
 pub struct EglContext {
     display: egl::Display,
     config: egl::Config,
     context: egl::Context,
     instance: egl::DynamicInstance<egl::EGL1_4>,
+    /// Whether the driver reports GPU resets to this context
+    robust: bool,
 }
 
 /// Per-surface EGL window
@@ -23,8 +49,11 @@ pub struct EglWindow {
 }
 
 impl EglContext {
-    /// Initialize EGL display and create OpenGL context
-    pub fn new(wl_display: *mut std::ffi::c_void) -> Result<Self> {
+    /// Initialize EGL display and create OpenGL context, on the GPU behind
+    /// `render_device` when one is given and EGL can select it. A `debug`
+    /// context reports driver errors through KHR_debug.
+    #[allow(clippy::not_unsafe_ptr_arg_deref)] // Intentional: the display pointer is only handed to EGL
+    pub fn new(wl_display: *mut c_void, render_device: Option<&Path>, debug: bool) -> Result<Self> {
         // 1. Load EGL library
         let instance = unsafe {
             egl::DynamicInstance::<egl::EGL1_4>::load_required()
@@ -32,10 +61,29 @@ impl EglContext {
         };
 
         // 2. Get EGL display from Wayland display
-        let display = unsafe {
-            instance
-                .get_display(wl_display as egl::NativeDisplayType)
-                .context("Failed to get EGL display")?
+        let device_display = render_device.and_then(|device| {
+            match unsafe { Self::device_display(&instance, wl_display, device) } {
+                Ok(display) => {
+                    tracing::info!("EGL display on render node {}", device.display());
+                    Some(display)
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "Cannot use render node {}: {}; using the default GPU",
+                        device.display(),
+                        e
+                    );
+                    None
+                }
+            }
+        });
+        let display = match device_display {
+            Some(display) => display,
+            None => unsafe {
+                instance
+                    .get_display(wl_display as egl::NativeDisplayType)
+                    .context("Failed to get EGL display")?
+            },
         };
 
         // 3. Initialize EGL
@@ -78,21 +126,71 @@ impl EglContext {
 
         tracing::debug!("EGL config selected");
 
-        // 6. Create OpenGL context
-        let context_attribs = [
+        // 6. Create OpenGL context, robust so a GPU reset is reported
+        // instead of leaving a context that silently draws nothing
+        let base_attribs = [
             egl::CONTEXT_MAJOR_VERSION,
             3,
             egl::CONTEXT_MINOR_VERSION,
             0,
             egl::CONTEXT_OPENGL_PROFILE_MASK,
             egl::CONTEXT_OPENGL_CORE_PROFILE_BIT,
-            egl::NONE,
         ];
+        let context_attribs = |flags: egl::Int, robust: bool| {
+            let mut attribs = base_attribs.to_vec();
+            if flags != 0 {
+                attribs.extend([EGL_CONTEXT_FLAGS_KHR, flags]);
+            }
+            if robust {
+                attribs.extend([
+                    EGL_CONTEXT_OPENGL_RESET_NOTIFICATION_STRATEGY_KHR,
+                    EGL_LOSE_CONTEXT_ON_RESET_KHR,
+                ]);
+            }
+            attribs.push(egl::NONE);
+            attribs
+        };
+        let debug_flags = if debug {
+            EGL_CONTEXT_OPENGL_DEBUG_BIT_KHR
+        } else {
+            0
+        };
 
-        let context = instance
-            .create_context(display, configs, None, &context_attribs)
-            .context("Failed to create EGL context")?;
+        let mut attempts = vec![
+            (debug_flags | EGL_CONTEXT_OPENGL_ROBUST_ACCESS_BIT_KHR, true),
+            (debug_flags, false),
+        ];
+        if debug {
+            attempts.push((0, false));
+        }
+        // The last attempt is plain enough for every driver; its error is the one reported
+        let last = attempts.len() - 1;
+        let mut created = None;
+        for (attempt, (flags, robust)) in attempts.into_iter().enumerate() {
+            match instance.create_context(display, configs, None, &context_attribs(flags, robust)) {
+                Ok(context) => {
+                    created = Some((context, flags, robust));
+                    break;
+                }
+                Err(e) if attempt == last => {
+                    return Err(e).context("Failed to create EGL context");
+                }
+                Err(_) => {}
+            }
+        }
+        let (context, flags, robust) =
+            created.ok_or_else(|| anyhow!("Failed to create EGL context"))?;
 
+        if !robust {
+            tracing::warn!("No robust EGL context; GPU resets will not be detected");
+        }
+        if debug {
+            if flags & EGL_CONTEXT_OPENGL_DEBUG_BIT_KHR != 0 {
+                tracing::info!("EGL debug context created");
+            } else {
+                tracing::warn!("No EGL debug context; GPU debug output is off");
+            }
+        }
         tracing::info!("EGL context created successfully");
 
         Ok(Self {
@@ -100,9 +198,84 @@ impl EglContext {
             config: configs,
             context,
             instance,
+            robust,
         })
     }
 
+    /// Whether a GPU reset is reported through `glGetGraphicsResetStatus`
+    pub fn is_robust(&self) -> bool {
+        self.robust
+    }
+
+    /// Destroy the context after the GPU lost it; window surfaces must be
+    /// destroyed first
+    pub fn destroy(self) {
+        let _ = self.instance.make_current(self.display, None, None, None);
+        if let Err(e) = self.instance.destroy_context(self.display, self.context) {
+            tracing::warn!("Failed to destroy EGL context: {}", e);
+        }
+    }
+
+    /// Wayland EGL display bound to the EGL device whose render node is
+    /// `render_device`
+    unsafe fn device_display(
+        instance: &egl::DynamicInstance<egl::EGL1_4>,
+        wl_display: *mut c_void,
+        render_device: &Path,
+    ) -> Result<egl::Display> {
+        let extensions = instance
+            .query_string(None, egl::EXTENSIONS)
+            .map(|extensions| extensions.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        for required in ["EGL_EXT_device_enumeration", "EGL_EXT_explicit_device"] {
+            if !extensions.split_whitespace().any(|ext| ext == required) {
+                return Err(anyhow!("EGL does not support {}", required));
+            }
+        }
+
+        let load = |name: &str| {
+            instance
+                .get_proc_address(name)
+                .ok_or_else(|| anyhow!("EGL does not export {}", name))
+        };
+        let query_devices: QueryDevicesExt =
+            unsafe { std::mem::transmute(load("eglQueryDevicesEXT")?) };
+        let query_device_string: QueryDeviceStringExt =
+            unsafe { std::mem::transmute(load("eglQueryDeviceStringEXT")?) };
+        let get_platform_display: GetPlatformDisplay =
+            unsafe { std::mem::transmute(load("eglGetPlatformDisplay")?) };
+
+        let mut count: egl::Int = 0;
+        if unsafe { query_devices(0, ptr::null_mut(), &mut count) } == egl::FALSE {
+            return Err(anyhow!("eglQueryDevicesEXT failed"));
+        }
+        let mut devices = vec![ptr::null_mut(); count.max(0) as usize];
+        if unsafe { query_devices(count, devices.as_mut_ptr(), &mut count) } == egl::FALSE {
+            return Err(anyhow!("eglQueryDevicesEXT failed"));
+        }
+        devices.truncate(count.max(0) as usize);
+
+        let wanted = canonical_render_node(render_device);
+        let device = devices
+            .into_iter()
+            .find(|&device| {
+                let node = unsafe { query_device_string(device, EGL_DRM_RENDER_NODE_FILE_EXT) };
+                !node.is_null() && {
+                    let node = unsafe { CStr::from_ptr(node) }.to_string_lossy();
+                    canonical_render_node(Path::new(node.as_ref())) == wanted
+                }
+            })
+            .ok_or_else(|| anyhow!("no EGL device uses this render node"))?;
+
+        let attribs = [EGL_DEVICE_EXT, device as egl::Attrib, egl::ATTRIB_NONE];
+        let display =
+            unsafe { get_platform_display(EGL_PLATFORM_WAYLAND_EXT, wl_display, attribs.as_ptr()) };
+        if display.is_null() {
+            return Err(anyhow!("eglGetPlatformDisplay failed"));
+        }
+        Ok(unsafe { egl::Display::from_ptr(display) })
+    }
+
     /// Create EGL window surface for a Wayland surface
     pub fn create_window(
         &self,
@@ -235,6 +408,62 @@ impl Drop for EglContext {
     }
 }
 
+/// What the EGL implementation on a Wayland display reports about itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EglInfo {
+    /// EGL version, e.g. "1.5"
+    pub version: String,
+    /// e.g. "Mesa Project"
+    pub vendor: String,
+    /// Client APIs, e.g. "OpenGL OpenGL_ES"
+    pub client_apis: String,
+    /// Display extensions
+    pub extensions: Vec<String>,
+}
+
+/// Initialize EGL on `wl_display` just long enough to read its version,
+/// vendor and extensions
+#[allow(clippy::not_unsafe_ptr_arg_deref)] // Intentional: the display pointer is only handed to EGL
+pub fn egl_info(wl_display: *mut c_void) -> Result<EglInfo> {
+    let instance = unsafe {
+        egl::DynamicInstance::<egl::EGL1_4>::load_required()
+            .context("Failed to load EGL library")?
+    };
+    let display = unsafe {
+        instance
+            .get_display(wl_display as egl::NativeDisplayType)
+            .context("Failed to get EGL display")?
+    };
+    let (major, minor) = instance
+        .initialize(display)
+        .context("Failed to initialize EGL")?;
+
+    let query = |name| {
+        instance
+            .query_string(Some(display), name)
+            .map(|value| value.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let info = EglInfo {
+        version: format!("{major}.{minor}"),
+        vendor: query(egl::VENDOR),
+        client_apis: query(egl::CLIENT_APIS),
+        extensions: query(egl::EXTENSIONS)
+            .split_whitespace()
+            .map(str::to_string)
+            .collect(),
+    };
+    let _ = instance.terminate(display);
+    Ok(info)
+}
+
+/// Whether an EGL call failed because the context was lost to a GPU reset
+pub fn is_context_lost(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .any(|cause| cause.downcast_ref::<egl::Error>() == Some(&egl::Error::ContextLost))
+}
+
 // Safety: EGL contexts are thread-safe
 unsafe impl Send for EglContext {}
 unsafe impl Sync for EglContext {}
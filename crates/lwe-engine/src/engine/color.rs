@@ -0,0 +1,482 @@
+//! HDR output detection and surface tagging through wp_color_management_v1
+//!
+//! Each output's image description says whether the compositor drives it in
+//! HDR (PQ or HLG) and how bright it gets. Sessions playing HDR video on such
+//! an output render BT.2020 PQ, and their surface is tagged with a matching
+//! parametric image description so the compositor does not treat it as sRGB.
+
+use std::collections::HashMap;
+
+use lwe_core::hdr::TransferFunction;
+use lwe_core::OutputHdrCapabilities;
+use tracing::{debug, info, warn};
+use wayland_client::{Connection, Dispatch, QueueHandle, WEnum};
+use wayland_protocols::wp::color_management::v1::client::{
+    wp_color_management_output_v1::{self, WpColorManagementOutputV1},
+    wp_color_management_surface_v1::{self, WpColorManagementSurfaceV1},
+    wp_color_manager_v1::{self, WpColorManagerV1},
+    wp_image_description_creator_params_v1::{self, WpImageDescriptionCreatorParamsV1},
+    wp_image_description_info_v1::{self, WpImageDescriptionInfoV1},
+    wp_image_description_v1::{self, WpImageDescriptionV1},
+};
+
+use super::{EngineState, LayerSurfaceInfo};
+
+/// What an image description object was created for
+pub(crate) enum DescriptionUse {
+    /// The current description of the named output
+    Output(String),
+    /// BT.2020 PQ description set on surfaces in passthrough
+    Passthrough,
+}
+
+/// Compositor color management state
+pub(crate) struct ColorManager {
+    manager: WpColorManagerV1,
+    /// Parametric descriptions can be created
+    parametric: bool,
+    /// The perceptual render intent is supported
+    perceptual: bool,
+    /// The PQ transfer function is supported
+    pq: bool,
+    /// BT.2020 primaries are supported
+    bt2020: bool,
+    /// All supported_* events have arrived
+    done: bool,
+    /// Color management objects per output name
+    outputs: HashMap<String, WpColorManagementOutputV1>,
+    /// Capabilities read from each output's image description
+    capabilities: HashMap<String, OutputHdrCapabilities>,
+    /// Image description information being received per output
+    pending: HashMap<String, OutputImageDescription>,
+    /// Description tagged on passthrough surfaces, and whether it is ready
+    passthrough: Option<(WpImageDescriptionV1, bool)>,
+}
+
+impl ColorManager {
+    pub(crate) fn new(manager: WpColorManagerV1) -> Self {
+        Self {
+            manager,
+            parametric: false,
+            perceptual: false,
+            pq: false,
+            bt2020: false,
+            done: false,
+            outputs: HashMap::new(),
+            capabilities: HashMap::new(),
+            pending: HashMap::new(),
+            passthrough: None,
+        }
+    }
+
+    /// Whether the compositor supports everything a BT.2020 PQ surface needs
+    fn supports_pq_surfaces(&self) -> bool {
+        self.done && self.parametric && self.perceptual && self.pq && self.bt2020
+    }
+
+    /// Whether surfaces can be tagged as BT.2020 PQ
+    fn can_tag_pq(&self) -> bool {
+        self.supports_pq_surfaces() && self.passthrough.is_some()
+    }
+
+    /// The passthrough description, once the compositor has accepted it
+    fn passthrough_description(&self) -> Option<&WpImageDescriptionV1> {
+        match self.passthrough.as_ref() {
+            Some((description, true)) => Some(description),
+            _ => None,
+        }
+    }
+}
+
+/// What sessions may assume about an output: HDR needs both an HDR output
+/// and a surface that can be tagged as HDR
+pub(super) fn surface_capabilities(
+    color: Option<&ColorManager>,
+    output: &OutputHdrCapabilities,
+) -> OutputHdrCapabilities {
+    let mut capabilities = output.clone();
+    if !color.is_some_and(ColorManager::can_tag_pq) {
+        capabilities.hdr_supported = false;
+    }
+    capabilities
+}
+
+/// Start following the image description of a ready output, or restore the
+/// capabilities already read for it
+pub(super) fn track_output(
+    state: &mut EngineState,
+    output_name: &str,
+    qh: &QueueHandle<EngineState>,
+) {
+    let Some(color) = state.color.as_mut().filter(|color| color.done) else {
+        return;
+    };
+
+    if color.outputs.contains_key(output_name) {
+        if let Some(capabilities) = color.capabilities.get(output_name).cloned() {
+            apply_capabilities(state, output_name, capabilities);
+        }
+        return;
+    }
+
+    let Some(output) = state.outputs.get(output_name) else {
+        return;
+    };
+    let color_output = color
+        .manager
+        .get_output(&output.wl_output, qh, output_name.to_string());
+    color_output.get_image_description(qh, DescriptionUse::Output(output_name.to_string()));
+    color.outputs.insert(output_name.to_string(), color_output);
+}
+
+/// Drop color state for a removed output
+pub(super) fn forget_output(state: &mut EngineState, output_name: &str) {
+    if let Some(color) = state.color.as_mut() {
+        if let Some(output) = color.outputs.remove(output_name) {
+            output.destroy();
+        }
+        color.capabilities.remove(output_name);
+        color.pending.remove(output_name);
+    }
+}
+
+/// Tag or untag the surface as BT.2020 PQ to match what its session renders
+pub(super) fn sync_surface(
+    color: Option<&ColorManager>,
+    surface: &mut LayerSurfaceInfo,
+    passthrough: bool,
+    qh: &QueueHandle<EngineState>,
+) {
+    if surface.hdr_tagged == passthrough {
+        return;
+    }
+    let Some(color) = color else {
+        return;
+    };
+
+    if passthrough {
+        // Retried on the next frame until the description is ready
+        let Some(description) = color.passthrough_description() else {
+            return;
+        };
+        let color_surface = surface
+            .color_surface
+            .get_or_insert_with(|| color.manager.get_surface(&surface.wl_surface, qh, ()));
+        color_surface
+            .set_image_description(description, wp_color_manager_v1::RenderIntent::Perceptual);
+    } else if let Some(color_surface) = surface.color_surface.as_ref() {
+        color_surface.unset_image_description();
+    }
+    surface.hdr_tagged = passthrough;
+}
+
+/// Store newly read capabilities and let the output's session re-decide
+fn apply_capabilities(
+    state: &mut EngineState,
+    output_name: &str,
+    capabilities: OutputHdrCapabilities,
+) {
+    if let Some(output) = state.outputs.get_mut(output_name) {
+        if output.info.hdr_capabilities != capabilities {
+            info!(
+                "Output {} is {} (peak {:?} nits)",
+                output_name,
+                if capabilities.hdr_supported {
+                    "HDR"
+                } else {
+                    "SDR"
+                },
+                capabilities.max_luminance
+            );
+        }
+        output.info.hdr_capabilities = capabilities.clone();
+    }
+    if let Some(session) = state.sessions.get_mut(output_name) {
+        session.set_hdr_capabilities(surface_capabilities(state.color.as_ref(), &capabilities));
+    }
+}
+
+/// Image description information of one output, collected until `done`
+#[derive(Debug, Default, Clone)]
+struct OutputImageDescription {
+    transfer_function: Option<TransferFunction>,
+    /// Minimum luminance in 0.0001 cd/m²
+    min_luminance: Option<u32>,
+    /// Maximum luminance in cd/m²
+    max_luminance: Option<u32>,
+    /// Luminance range of the target display, same units as above
+    target_luminance: Option<(u32, u32)>,
+}
+
+impl OutputImageDescription {
+    fn capabilities(&self) -> OutputHdrCapabilities {
+        let transfer_function = self.transfer_function.unwrap_or_default();
+        let (min, max) = self
+            .target_luminance
+            .map(|(min, max)| (Some(min), Some(max)))
+            .unwrap_or((self.min_luminance, self.max_luminance));
+
+        let mut supported_eotf = vec![TransferFunction::Srgb];
+        if transfer_function.is_hdr() {
+            supported_eotf.push(transfer_function);
+        }
+
+        OutputHdrCapabilities {
+            hdr_supported: transfer_function.is_hdr(),
+            max_luminance: max.map(f64::from),
+            min_luminance: min.map(|min| f64::from(min) / 10_000.0),
+            supported_eotf,
+        }
+    }
+}
+
+fn transfer_function(tf: wp_color_manager_v1::TransferFunction) -> TransferFunction {
+    use wp_color_manager_v1::TransferFunction as Tf;
+    match tf {
+        Tf::St2084Pq => TransferFunction::Pq,
+        Tf::Hlg => TransferFunction::Hlg,
+        Tf::Bt1886 | Tf::Gamma22 | Tf::Srgb | Tf::ExtSrgb => TransferFunction::Srgb,
+        _ => TransferFunction::Unknown,
+    }
+}
+
+impl Dispatch<WpColorManagerV1, ()> for EngineState {
+    fn event(
+        state: &mut Self,
+        _manager: &WpColorManagerV1,
+        event: wp_color_manager_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        let Some(color) = state.color.as_mut() else {
+            return;
+        };
+
+        match event {
+            wp_color_manager_v1::Event::SupportedIntent {
+                render_intent: WEnum::Value(wp_color_manager_v1::RenderIntent::Perceptual),
+            } => color.perceptual = true,
+            wp_color_manager_v1::Event::SupportedFeature {
+                feature: WEnum::Value(wp_color_manager_v1::Feature::Parametric),
+            } => color.parametric = true,
+            wp_color_manager_v1::Event::SupportedTfNamed {
+                tf: WEnum::Value(wp_color_manager_v1::TransferFunction::St2084Pq),
+            } => color.pq = true,
+            wp_color_manager_v1::Event::SupportedPrimariesNamed {
+                primaries: WEnum::Value(wp_color_manager_v1::Primaries::Bt2020),
+            } => color.bt2020 = true,
+            wp_color_manager_v1::Event::Done => {
+                color.done = true;
+                if color.supports_pq_surfaces() {
+                    let creator = color.manager.create_parametric_creator(qh, ());
+                    creator.set_tf_named(wp_color_manager_v1::TransferFunction::St2084Pq);
+                    creator.set_primaries_named(wp_color_manager_v1::Primaries::Bt2020);
+                    let description = creator.create(qh, DescriptionUse::Passthrough);
+                    color.passthrough = Some((description, false));
+                } else {
+                    info!("Compositor cannot take BT.2020 PQ surfaces; HDR video is tone-mapped");
+                }
+
+                let outputs: Vec<String> = state
+                    .outputs
+                    .ready_outputs()
+                    .map(|(name, _)| name.to_string())
+                    .collect();
+                for output_name in outputs {
+                    track_output(state, &output_name, qh);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WpColorManagementOutputV1, String> for EngineState {
+    fn event(
+        _state: &mut Self,
+        output: &WpColorManagementOutputV1,
+        event: wp_color_management_output_v1::Event,
+        output_name: &String,
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let wp_color_management_output_v1::Event::ImageDescriptionChanged = event {
+            debug!("Image description of {} changed", output_name);
+            output.get_image_description(qh, DescriptionUse::Output(output_name.clone()));
+        }
+    }
+}
+
+impl Dispatch<WpImageDescriptionV1, DescriptionUse> for EngineState {
+    fn event(
+        state: &mut Self,
+        description: &WpImageDescriptionV1,
+        event: wp_image_description_v1::Event,
+        usage: &DescriptionUse,
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+    ) {
+        let Some(color) = state.color.as_mut() else {
+            return;
+        };
+
+        match (event, usage) {
+            (wp_image_description_v1::Event::Ready { .. }, DescriptionUse::Output(output_name)) => {
+                color
+                    .pending
+                    .insert(output_name.clone(), OutputImageDescription::default());
+                description.get_information(qh, output_name.clone());
+                description.destroy();
+            }
+            (wp_image_description_v1::Event::Ready { .. }, DescriptionUse::Passthrough) => {
+                if let Some((_, ready)) = color.passthrough.as_mut() {
+                    *ready = true;
+                }
+            }
+            (
+                wp_image_description_v1::Event::Failed { msg, .. },
+                DescriptionUse::Output(output_name),
+            ) => {
+                warn!("No image description for {}: {}", output_name, msg);
+                description.destroy();
+            }
+            (wp_image_description_v1::Event::Failed { msg, .. }, DescriptionUse::Passthrough) => {
+                warn!("Compositor rejected the HDR surface description: {}", msg);
+                color.passthrough = None;
+                description.destroy();
+                // Sessions fall back to tone mapping
+                let outputs: Vec<(String, OutputHdrCapabilities)> = color
+                    .capabilities
+                    .iter()
+                    .map(|(name, capabilities)| (name.clone(), capabilities.clone()))
+                    .collect();
+                for (output_name, capabilities) in outputs {
+                    apply_capabilities(state, &output_name, capabilities);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WpImageDescriptionInfoV1, String> for EngineState {
+    fn event(
+        state: &mut Self,
+        _info: &WpImageDescriptionInfoV1,
+        event: wp_image_description_info_v1::Event,
+        output_name: &String,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let Some(color) = state.color.as_mut() else {
+            return;
+        };
+
+        if let wp_image_description_info_v1::Event::Done = event {
+            let Some(description) = color.pending.remove(output_name) else {
+                return;
+            };
+            let capabilities = description.capabilities();
+            color
+                .capabilities
+                .insert(output_name.clone(), capabilities.clone());
+            apply_capabilities(state, output_name, capabilities);
+            return;
+        }
+
+        let Some(description) = color.pending.get_mut(output_name) else {
+            return;
+        };
+        match event {
+            wp_image_description_info_v1::Event::TfNamed {
+                tf: WEnum::Value(tf),
+            } => {
+                description.transfer_function = Some(transfer_function(tf));
+            }
+            wp_image_description_info_v1::Event::TfPower { .. } => {
+                description.transfer_function = Some(TransferFunction::Srgb);
+            }
+            wp_image_description_info_v1::Event::Luminances {
+                min_lum, max_lum, ..
+            } => {
+                description.min_luminance = Some(min_lum);
+                description.max_luminance = Some(max_lum);
+            }
+            wp_image_description_info_v1::Event::TargetLuminance { min_lum, max_lum } => {
+                description.target_luminance = Some((min_lum, max_lum));
+            }
+            _ => {}
+        }
+    }
+}
+
+// Parametric creators and surface objects have no events
+impl Dispatch<WpImageDescriptionCreatorParamsV1, ()> for EngineState {
+    fn event(
+        _state: &mut Self,
+        _creator: &WpImageDescriptionCreatorParamsV1,
+        _event: wp_image_description_creator_params_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WpColorManagementSurfaceV1, ()> for EngineState {
+    fn event(
+        _state: &mut Self,
+        _surface: &WpColorManagementSurfaceV1,
+        _event: wp_color_management_surface_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pq_output_reports_hdr_with_target_luminance() {
+        let description = OutputImageDescription {
+            transfer_function: Some(TransferFunction::Pq),
+            min_luminance: Some(50),
+            max_luminance: Some(10_000),
+            target_luminance: Some((20, 1000)),
+        };
+        let capabilities = description.capabilities();
+
+        assert!(capabilities.hdr_supported);
+        assert!(capabilities.accepts_pq());
+        assert_eq!(capabilities.max_luminance, Some(1000.0));
+        assert_eq!(capabilities.min_luminance, Some(0.002));
+    }
+
+    #[test]
+    fn srgb_output_is_sdr_and_untaggable_surfaces_hide_hdr() {
+        let sdr = OutputImageDescription {
+            transfer_function: Some(transfer_function(
+                wp_color_manager_v1::TransferFunction::Gamma22,
+            )),
+            min_luminance: Some(2000),
+            max_luminance: Some(80),
+            target_luminance: None,
+        }
+        .capabilities();
+        assert!(!sdr.hdr_supported);
+        assert_eq!(sdr.max_luminance, Some(80.0));
+        assert_eq!(sdr.supported_eotf, vec![TransferFunction::Srgb]);
+
+        let hdr = OutputImageDescription {
+            transfer_function: Some(TransferFunction::Hlg),
+            ..Default::default()
+        }
+        .capabilities();
+        assert!(hdr.hdr_supported);
+        assert!(!surface_capabilities(None, &hdr).hdr_supported);
+    }
+}
@@ -1,11 +1,22 @@
 //! Engine commands, events, and configuration types
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use lwe_core::OutputInfo;
+use lwe_core::{
+    BatteryProfile, ColorAdjust, LayoutMode, LetterboxFill, OutputInfo, Palette, Policy,
+    RenderBackend, SourceError, SurfaceLayer, SurfaceMargins,
+};
 
-use crate::mpv::VideoConfig;
+use crate::cgroup::CgroupUsage;
+use crate::engine::fallback::Fallback;
+use crate::frame_timing::FrameRateSample;
+use crate::gl_debug::GpuMessage;
+use crate::mpv::{is_audio_option, MediaInfo, Transport, VideoConfig};
+use crate::scheduling::SchedulingConfig;
+use crate::workarounds::WorkaroundOverrides;
 
 /// Commands sent from GUI to engine
 #[derive(Debug, Clone)]
@@ -16,6 +27,10 @@ pub enum EngineCommand {
         path: PathBuf,
         /// Target output (None = all outputs)
         output: Option<String>,
+        /// Custom GLSL post-process shader attached to this wallpaper
+        shader: Option<PathBuf>,
+        /// Color grading LUT attached to this wallpaper (None = global LUT)
+        lut: Option<PathBuf>,
     },
 
     /// Clear wallpaper from output(s)
@@ -32,6 +47,54 @@ pub enum EngineCommand {
         volume: f32,
     },
 
+    /// Mute or unmute an output
+    SetMute {
+        /// Target output
+        output: String,
+        /// Whether audio should be muted
+        muted: bool,
+    },
+
+    /// Select the audio device an output plays through
+    SetAudioDevice {
+        /// Target output
+        output: String,
+        /// Device as named by mpv (None = system default)
+        device: Option<String>,
+    },
+
+    /// Change the brightness, contrast, gamma and saturation of an output
+    SetColor {
+        /// Target output
+        output: String,
+        /// Color correction
+        color: ColorAdjust,
+    },
+
+    /// Open the wallpaper an output will switch to next, so the switch
+    /// starts on an already decoded frame
+    PreloadWallpaper {
+        /// Target output
+        output: String,
+        /// Path to the upcoming video
+        path: PathBuf,
+    },
+
+    /// Keep the screen from idling while this output shows its wallpaper
+    SetIdleInhibit {
+        /// Target output
+        output: String,
+        /// Whether idle should be inhibited
+        inhibit: bool,
+    },
+
+    /// Report whether the session's screensaver or lock screen is active;
+    /// with `pause_on_idle` set, wallpapers pause while it is
+    SetScreenSaverActive {
+        /// Whether the screensaver is active
+        active: bool,
+    },
+
     /// Pause playback
     Pause {
         /// Target output (None = all outputs)
@@ -44,12 +107,54 @@ pub enum EngineCommand {
         output: Option<String>,
     },
 
+    /// Seek, change speed, step a frame or set the A-B loop; a spanned
+    /// video moves on every output it spans
+    Transport {
+        /// Target output (None = all outputs)
+        output: Option<String>,
+        /// What to change
+        transport: Transport,
+    },
+
     /// Request current outputs list
     GetOutputs,
 
     /// Request current status
     GetStatus,
 
+    /// Request render and decode counters of each output
+    GetStats,
+
+    /// Request the color palette of the wallpaper on each output
+    GetPalette {
+        /// Target output (None = all outputs)
+        output: Option<String>,
+    },
+
+    /// Save the frame an output shows as an image; answered with
+    /// `SnapshotSaved` once the next frame is drawn and written
+    Snapshot {
+        /// Target output
+        output: String,
+        /// Image file; the format follows the extension
+        path: PathBuf,
+    },
+
+    /// Set a raw mpv property on the video an output plays, without saving
+    /// it; answered with `MpvPropertySet`
+    SetMpvProperty {
+        /// Target output
+        output: String,
+        /// mpv property name, e.g. `scale`
+        property: String,
+        /// Value as mpv parses it on its command line
+        value: String,
+    },
+
+    /// Settings changed while the engine runs; see [`EngineConfig::startup_changes`]
+    /// for the ones that wait for the next start
+    UpdateConfig(Box<EngineConfig>),
+
     /// Shutdown the engine
     Shutdown,
 }
@@ -72,9 +177,33 @@ pub enum EngineEvent {
     /// Current outputs list (response to GetOutputs)
     OutputsList(Vec<OutputInfo>),
 
-    /// Current status (response to GetStatus)
+    /// Current status (response to GetStatus, and sent on its own when
+    /// wallpapers, outputs or power pausing change)
     Status(EngineStatus),
 
+    /// Render and decode counters by output name (response to GetStats)
+    Stats(HashMap<String, OutputStats>),
+
+    /// Color palettes by output name (response to GetPalette); outputs that
+    /// have not drawn a frame yet are left out
+    Palettes(Vec<(String, Palette)>),
+
+    /// A snapshot was written (response to Snapshot)
+    SnapshotSaved {
+        /// Output name
+        output: String,
+        /// Image file
+        path: PathBuf,
+    },
+
+    /// An mpv property was set (response to SetMpvProperty)
+    MpvPropertySet {
+        /// Output name
+        output: String,
+        /// mpv property name
+        property: String,
+    },
+
     /// Wallpaper applied successfully
     WallpaperApplied {
         /// Output name
@@ -83,16 +212,152 @@ pub enum EngineEvent {
         path: PathBuf,
     },
 
+    /// Wallpaper failed its pre-apply probe; current wallpapers were left in place
+    SourceRejected {
+        /// Wallpaper path
+        path: PathBuf,
+        /// Why the source was rejected
+        error: SourceError,
+    },
+
+    /// A wallpaper failed while playing; its output shows a fallback and
+    /// retries the wallpaper until it plays again
+    SourceFailed {
+        /// Output name
+        output: String,
+        /// Wallpaper that failed
+        path: PathBuf,
+        /// Why it failed
+        reason: String,
+        /// What the output shows instead
+        fallback: Fallback,
+    },
+
+    /// A wallpaper failed too many times in a row and is no longer retried;
+    /// its output stays on the safe-mode fallback until another wallpaper
+    /// is applied
+    WallpaperFailed {
+        /// Output name
+        output: String,
+        /// Wallpaper that failed
+        path: PathBuf,
+        /// Why it failed the last time
+        reason: String,
+        /// Failures in a row
+        failures: u32,
+        /// What the output shows instead
+        fallback: Fallback,
+    },
+
+    /// A failed wallpaper plays again
+    SourceRecovered {
+        /// Output name
+        output: String,
+        /// Wallpaper path
+        path: PathBuf,
+    },
+
     /// Wallpaper cleared
     WallpaperCleared {
         /// Output name
         output: String,
     },
 
+    /// The engine thread panicked and was restarted with its wallpapers re-applied
+    Restarted {
+        /// Restarts since the engine was spawned
+        restarts: u32,
+        /// Panic message of the crashed engine
+        reason: String,
+    },
+
+    /// The GPU reset and lost the rendering context; every wallpaper is
+    /// reopened on a new context once the driver is back
+    GpuReset {
+        /// What the driver reported
+        reason: String,
+    },
+
     /// Error occurred
     Error(String),
 }
 
+/// Number that pairs a command with the events it causes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RequestId(pub u64);
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// A command on its way to the engine
+#[derive(Debug, Clone)]
+pub struct EngineRequest {
+    /// Id from `EngineHandle::send` (None = replayed by the engine itself)
+    pub id: Option<RequestId>,
+    /// When the command was sent, on the monotonic clock
+    pub sent_at: Instant,
+    pub command: EngineCommand,
+}
+
+impl EngineRequest {
+    /// A command the engine issues itself, such as a replay after a restart
+    pub(crate) fn internal(command: EngineCommand) -> Self {
+        Self {
+            id: None,
+            sent_at: Instant::now(),
+            command,
+        }
+    }
+}
+
+/// An event as the GUI receives it
+#[derive(Debug, Clone)]
+pub struct EngineMessage {
+    pub event: EngineEvent,
+    /// When the engine sent the event, on the monotonic clock
+    pub at: Instant,
+    /// The command this event answers (None = the engine raised it on its own)
+    pub request: Option<RequestId>,
+}
+
+/// Per-output overrides of the global video configuration
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutputVideoOverrides {
+    /// Layout mode for this output
+    pub layout: Option<LayoutMode>,
+    /// Letterbox fill for this output
+    pub letterbox: Option<LetterboxFill>,
+    /// Volume for this output (0.0 - 1.0)
+    pub volume: Option<f32>,
+    /// Whether this output is muted
+    pub mute: Option<bool>,
+    /// Audio device for this output
+    pub audio_device: Option<String>,
+    /// Color correction for this output
+    pub color: Option<ColorAdjust>,
+    /// Shown in order while the wallpaper on this output is failing; a
+    /// solid color ends the chain
+    pub fallbacks: Vec<Fallback>,
+    /// Image or color shown ahead of the fallbacks while a live stream on
+    /// this output is down (None = go straight to the fallbacks)
+    pub signal_lost: Option<Fallback>,
+    /// Image or color shown once the wallpaper has failed too often to keep
+    /// retrying (None = stay on the fallbacks)
+    pub safe_mode: Option<Fallback>,
+    /// Layer-shell layer of this output's surface (None = background)
+    pub layer: Option<SurfaceLayer>,
+    /// Exclusive zone of this output's surface (None = the compositor
+    /// workaround's default, normally -1)
+    pub exclusive_zone: Option<i32>,
+    /// Space left free around this output's surface, e.g. for a dock
+    pub margins: SurfaceMargins,
+    /// Raw mpv options for this output, over the global ones
+    pub mpv_options: BTreeMap<String, String>,
+}
+
 /// Engine configuration
 #[derive(Debug, Clone)]
 pub struct EngineConfig {
@@ -104,6 +369,34 @@ pub struct EngineConfig {
     pub fps_limit: Option<u32>,
     /// Pause playback when on battery power
     pub pause_on_battery: bool,
+    /// Cheaper playback on battery power; unused with `pause_on_battery`
+    pub battery: BatteryProfile,
+    /// Pause an output's wallpaper while a fullscreen window covers it
+    pub pause_on_fullscreen: bool,
+    /// With `pause_on_fullscreen`, also pause behind maximized windows
+    pub pause_on_maximized: bool,
+    /// Stop decoding and drawing, and give hardware decoders back, while
+    /// fullscreen or maximized windows cover every output
+    pub deep_sleep: bool,
+    /// Only play audio on the output holding the focused window
+    pub audio_follows_focus: bool,
+    /// Pause playback after this long without input, and while the
+    /// screensaver or lock screen is active (None = never)
+    pub pause_on_idle: Option<Duration>,
+    /// How long to keep retrying the compositor connection and
+    /// layer-shell bind at startup
+    pub startup_timeout: Duration,
+    /// Overrides for the compositor workarounds registry
+    pub workarounds: WorkaroundOverrides,
+    /// Video overrides keyed by output name
+    pub outputs: HashMap<String, OutputVideoOverrides>,
+    /// Nice level, scheduling policy and core pinning for decoding
+    pub scheduling: SchedulingConfig,
+    /// DRM render node for EGL and VAAPI, e.g. `/dev/dri/renderD129`
+    /// (None = whichever GPU EGL picks)
+    pub render_device: Option<PathBuf>,
+    /// Create a KHR_debug context and capture the driver's GPU messages
+    pub gpu_debug: bool,
 }
 
 impl Default for EngineConfig {
@@ -113,6 +406,145 @@ impl Default for EngineConfig {
             auto_play: true,
             fps_limit: None,
             pause_on_battery: false,
+            battery: BatteryProfile::default(),
+            pause_on_fullscreen: false,
+            pause_on_maximized: false,
+            deep_sleep: false,
+            audio_follows_focus: false,
+            pause_on_idle: None,
+            startup_timeout: Duration::from_secs(30),
+            workarounds: WorkaroundOverrides::default(),
+            outputs: HashMap::new(),
+            scheduling: SchedulingConfig::default(),
+            render_device: None,
+            gpu_debug: false,
+        }
+    }
+}
+
+impl EngineConfig {
+    /// Whether fullscreen, maximized or focused windows have to be tracked
+    pub fn tracks_windows(&self) -> bool {
+        self.pause_on_fullscreen || self.deep_sleep || self.audio_follows_focus
+    }
+
+    /// Settings changed in `next` that the engine only reads while starting,
+    /// named for the log
+    pub fn startup_changes(&self, next: &EngineConfig) -> Vec<&'static str> {
+        let mut changes = Vec::new();
+        if next.startup_timeout != self.startup_timeout {
+            changes.push("startup timeout");
+        }
+        if next.workarounds != self.workarounds {
+            changes.push("compositor workarounds");
+        }
+        if next.scheduling != self.scheduling {
+            changes.push("scheduling");
+        }
+        if next.render_device != self.render_device {
+            changes.push("render device");
+        }
+        if next.gpu_debug != self.gpu_debug {
+            changes.push("GPU debug");
+        }
+        if next.pause_on_idle.is_some() != self.pause_on_idle.is_some() {
+            changes.push("pause on idle");
+        }
+        // Outside Hyprland, windows are tracked through a global bound at startup
+        if next.tracks_windows() && !self.tracks_windows() {
+            changes.push("window tracking");
+        }
+        changes
+    }
+
+    /// Whether the battery profile is in effect
+    fn uses_battery_profile(&self, on_battery: bool) -> bool {
+        on_battery && !self.pause_on_battery
+    }
+
+    /// Shortest time between two frames on one output (zero = vsync)
+    pub fn min_frame_interval(&self, on_battery: bool) -> Duration {
+        let fps_limit = if self.uses_battery_profile(on_battery) {
+            self.battery.cap_fps(self.fps_limit)
+        } else {
+            self.fps_limit
+        };
+        fps_limit
+            .filter(|&fps| fps > 0)
+            .map_or(Duration::ZERO, |fps| {
+                Duration::from_micros(1_000_000 / u64::from(fps))
+            })
+    }
+
+    /// [`Self::video_for_output`] with the battery profile's decoding while
+    /// on battery
+    pub fn video_for_power(&self, output: &str, on_battery: bool) -> VideoConfig {
+        let mut video = self.video_for_output(output);
+        if self.uses_battery_profile(on_battery) {
+            video.hwdec = self.battery.hwdec.unwrap_or(video.hwdec);
+            video.decode_fps = self.battery.cap_fps(Some(video.decode_fps)).unwrap_or(0);
+        }
+        video
+    }
+
+    /// Layer, exclusive zone and margins of an output's surface;
+    /// `default_zone` is the exclusive zone the compositor workarounds pick
+    pub fn surface_placement(
+        &self,
+        output: &str,
+        default_zone: i32,
+    ) -> (SurfaceLayer, i32, SurfaceMargins) {
+        match self.outputs.get(output) {
+            Some(overrides) => (
+                overrides.layer.unwrap_or_default(),
+                overrides.exclusive_zone.unwrap_or(default_zone),
+                overrides.margins,
+            ),
+            None => (
+                SurfaceLayer::default(),
+                default_zone,
+                SurfaceMargins::default(),
+            ),
+        }
+    }
+
+    /// Whether wallpapers hold still on the current frame
+    pub fn holds_still(&self, on_battery: bool) -> bool {
+        self.uses_battery_profile(on_battery) && self.battery.static_fallback
+    }
+
+    /// Video configuration for one output with its overrides applied
+    pub fn video_for_output(&self, output: &str) -> VideoConfig {
+        let mut video = self.video.clone();
+        video.render_device.clone_from(&self.render_device);
+        if let Some(overrides) = self.outputs.get(output) {
+            video.layout = overrides.layout.unwrap_or(video.layout);
+            video.letterbox = overrides.letterbox.unwrap_or(video.letterbox);
+            video.volume = overrides.volume.map_or(video.volume, f64::from);
+            video.mute = overrides.mute.unwrap_or(video.mute);
+            if overrides.audio_device.is_some() {
+                video.audio_device.clone_from(&overrides.audio_device);
+            }
+            video.color = overrides.color.unwrap_or(video.color);
+            video.mpv_options.extend(overrides.mpv_options.clone());
+        }
+        video
+    }
+
+    /// Enforce an administrator policy over the user's configuration
+    pub fn apply_policy(&mut self, policy: &Policy) {
+        self.fps_limit = policy.cap_fps(self.fps_limit);
+        if !policy.allow_audio {
+            self.video.mute = true;
+            self.video
+                .mpv_options
+                .retain(|name, _| !is_audio_option(name));
+            for overrides in self.outputs.values_mut() {
+                overrides.mute = Some(true);
+                overrides
+                    .mpv_options
+                    .retain(|name, _| !is_audio_option(name));
+            }
         }
     }
 }
@@ -126,4 +558,215 @@ pub struct EngineStatus {
     pub outputs: Vec<OutputInfo>,
     /// Active wallpapers per output
     pub active_wallpapers: HashMap<String, Option<PathBuf>>,
+    /// Playback state per output with a wallpaper session
+    pub playback: HashMap<String, OutputPlayback>,
+    /// Usage and limits of the engine's cgroup (None outside cgroup v2)
+    pub cgroup: Option<CgroupUsage>,
+    /// Recent GL errors and debug messages per output
+    pub gpu_messages: HashMap<String, Vec<GpuMessage>>,
+    /// Wallpapers that failed, per output showing a fallback instead
+    pub failed_wallpapers: HashMap<String, PathBuf>,
+    /// Whether the machine runs on battery
+    pub on_battery: bool,
+    /// Whether playback is paused to save power
+    pub power_paused: bool,
+    /// Whether wallpapers sleep while windows cover every output
+    pub deep_sleep: bool,
+    /// Graphics API the wallpapers are drawn with
+    pub render_backend: RenderBackend,
+}
+
+/// Playback state of one output's wallpaper
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutputPlayback {
+    /// Whether playback is paused
+    pub paused: bool,
+    /// Position within the current video (None = not a video, or not started)
+    pub position: Option<Duration>,
+    /// Title, length and decoder of a video wallpaper
+    pub media: MediaInfo,
+    /// Frames drawn per second and the share dropped (None = not measured yet)
+    pub frame_rate: Option<FrameRateSample>,
+}
+
+/// Render and decode counters of one output's wallpaper
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutputStats {
+    /// Average time spent drawing a frame (None = drawn outside the engine)
+    pub render_time: Option<Duration>,
+    /// Frames drawn per second and the share dropped (None = not measured yet)
+    pub frame_rate: Option<FrameRateSample>,
+    /// Frames the decoder dropped since the video started
+    pub dropped_frames: u64,
+    /// Hardware decoder in use ("no" = decoding in software, None = not a video)
+    pub hwdec: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use lwe_core::HwdecMode;
+
+    use super::*;
+
+    #[test]
+    fn test_video_for_output_applies_only_that_outputs_overrides() {
+        let mut config = EngineConfig::default();
+        config.outputs.insert(
+            "DP-1".to_string(),
+            OutputVideoOverrides {
+                layout: Some(LayoutMode::Contain),
+                letterbox: Some(LetterboxFill::Blur),
+                volume: Some(0.5),
+                mute: Some(false),
+                color: Some(ColorAdjust {
+                    brightness: 0.9,
+                    ..ColorAdjust::default()
+                }),
+                mpv_options: BTreeMap::from([(
+                    "scale".to_string(),
+                    "ewa_lanczossharp".to_string(),
+                )]),
+                ..OutputVideoOverrides::default()
+            },
+        );
+        config.video.mpv_options = BTreeMap::from([
+            ("scale".to_string(), "spline36".to_string()),
+            ("video-sync".to_string(), "display-resample".to_string()),
+        ]);
+
+        let video = config.video_for_output("DP-1");
+        assert_eq!(video.layout, LayoutMode::Contain);
+        assert_eq!(video.letterbox, LetterboxFill::Blur);
+        assert_eq!(video.volume, 0.5);
+        assert!(!video.mute);
+        assert_eq!(video.color.brightness, 0.9);
+        assert_eq!(video.mpv_options["scale"], "ewa_lanczossharp");
+        assert_eq!(video.mpv_options["video-sync"], "display-resample");
+
+        let video = config.video_for_output("HDMI-A-1");
+        assert_eq!(video.layout, LayoutMode::Fill);
+        assert_eq!(video.letterbox, LetterboxFill::default());
+        assert!(video.mute);
+        assert!(video.color.is_neutral());
+        assert_eq!(video.mpv_options["scale"], "spline36");
+    }
+
+    #[test]
+    fn test_surface_placement_falls_back_to_the_workaround_zone() {
+        let mut config = EngineConfig::default();
+        let margins = SurfaceMargins {
+            bottom: 48,
+            ..SurfaceMargins::default()
+        };
+        config.outputs.insert(
+            "DP-1".to_string(),
+            OutputVideoOverrides {
+                layer: Some(SurfaceLayer::Bottom),
+                margins,
+                ..OutputVideoOverrides::default()
+            },
+        );
+
+        assert_eq!(
+            config.surface_placement("DP-1", 0),
+            (SurfaceLayer::Bottom, 0, margins)
+        );
+        config.outputs.get_mut("DP-1").unwrap().exclusive_zone = Some(-1);
+        assert_eq!(config.surface_placement("DP-1", 0).1, -1);
+        assert_eq!(
+            config.surface_placement("HDMI-A-1", -1),
+            (SurfaceLayer::Background, -1, SurfaceMargins::default())
+        );
+    }
+
+    #[test]
+    fn test_apply_policy_caps_fps_and_silences_outputs() {
+        let mut config = EngineConfig {
+            fps_limit: Some(60),
+            ..EngineConfig::default()
+        };
+        config.outputs.insert(
+            "DP-1".to_string(),
+            OutputVideoOverrides {
+                mute: Some(false),
+                mpv_options: BTreeMap::from([
+                    ("volume".to_string(), "80".to_string()),
+                    ("scale".to_string(), "spline36".to_string()),
+                ]),
+                ..OutputVideoOverrides::default()
+            },
+        );
+        config.video.mpv_options = BTreeMap::from([("audio".to_string(), "auto".to_string())]);
+
+        config.apply_policy(&Policy {
+            allow_network_sources: true,
+            allow_audio: false,
+            max_fps: Some(30),
+        });
+
+        assert_eq!(config.fps_limit, Some(30));
+        assert_eq!(
+            config.min_frame_interval(false),
+            Duration::from_micros(33_333)
+        );
+        assert!(config.video_for_output("DP-1").mute);
+        assert_eq!(
+            config.video_for_output("DP-1").mpv_options,
+            BTreeMap::from([("scale".to_string(), "spline36".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_battery_profile_applies_only_on_battery() {
+        let config = EngineConfig {
+            fps_limit: Some(60),
+            battery: BatteryProfile {
+                fps: 20,
+                hwdec: Some(HwdecMode::Force),
+                static_fallback: true,
+            },
+            ..EngineConfig::default()
+        };
+
+        assert_eq!(
+            config.min_frame_interval(false),
+            Duration::from_micros(16_666)
+        );
+        assert_eq!(
+            config.min_frame_interval(true),
+            Duration::from_micros(50_000)
+        );
+        let video = config.video_for_power("DP-1", true);
+        assert_eq!((video.hwdec, video.decode_fps), (HwdecMode::Force, 20));
+        let video = config.video_for_power("DP-1", false);
+        assert_eq!((video.hwdec, video.decode_fps), (HwdecMode::Auto, 30));
+        assert!(config.holds_still(true) && !config.holds_still(false));
+
+        // A full pause on battery leaves nothing for the profile to do
+        let config = EngineConfig {
+            pause_on_battery: true,
+            ..config
+        };
+        assert!(!config.holds_still(true));
+        assert_eq!(config.video_for_power("DP-1", true).decode_fps, 30);
+    }
+
+    #[test]
+    fn test_startup_changes_name_only_settings_read_at_startup() {
+        let config = EngineConfig::default();
+        let mut next = config.clone();
+        next.pause_on_maximized = true;
+        next.fps_limit = Some(30);
+        next.outputs
+            .insert("DP-1".to_string(), OutputVideoOverrides::default());
+        assert!(config.startup_changes(&next).is_empty());
+
+        next.gpu_debug = true;
+        next.deep_sleep = true;
+        assert_eq!(
+            config.startup_changes(&next),
+            vec!["GPU debug", "window tracking"]
+        );
+        assert!(next.startup_changes(&config).contains(&"GPU debug"));
+    }
 }
@@ -0,0 +1,385 @@
+//! Bounded event channel from the engine to the GUI
+//!
+//! The GUI only reads events while it waits for a reply, so an unbounded
+//! channel grows without limit whenever it looks away. The channel holds
+//! `EVENT_CHANNEL_CAPACITY` events; while it is full, events wait in a
+//! backlog and reach the GUI in order once there is room. Unsolicited
+//! updates that a newer one of the same kind supersedes (status, output and
+//! palette updates) keep only the newest of each kind there; replies to a
+//! request are never merged, since the GUI may be waiting for them. The
+//! backlog holds at most `EVENT_BACKLOG_CAPACITY` events: past that the
+//! oldest unsolicited event is dropped, and a reply only when nothing else
+//! is left. Everything is counted so diagnostics can show when the GUI
+//! falls behind.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{SendError, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+use tracing::{debug, warn};
+
+use super::command::{EngineEvent, EngineMessage, RequestId};
+
+/// Events the GUI may leave unread before the engine coalesces or drops them
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Events held back while the channel is full before the oldest are dropped
+pub const EVENT_BACKLOG_CAPACITY: usize = 1024;
+
+/// How events fared on their way to the GUI since the engine was spawned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventChannelStats {
+    /// Events put on the channel
+    pub delivered: u64,
+    /// Events replaced by a newer one of the same kind while the channel was full
+    pub coalesced: u64,
+    /// Events that waited in the backlog because the channel was full
+    pub delayed: u64,
+    /// Events thrown away because the backlog was full
+    pub dropped: u64,
+}
+
+/// Counters shared by every clone of an `EventSender` and the engine handle
+#[derive(Debug, Default)]
+pub(crate) struct EventCounters {
+    delivered: AtomicU64,
+    coalesced: AtomicU64,
+    delayed: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl EventCounters {
+    pub(crate) fn snapshot(&self) -> EventChannelStats {
+        EventChannelStats {
+            delivered: self.delivered.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+            delayed: self.delayed.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Events of the same kind supersede each other
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CoalesceKey {
+    Status,
+    Stats,
+    Outputs,
+    Output(String),
+    Palettes,
+}
+
+/// What `message` merges with in the backlog; None keeps it as it is
+fn coalesce_key(message: &EngineMessage) -> Option<CoalesceKey> {
+    // Someone waits for this exact reply
+    if message.request.is_some() {
+        return None;
+    }
+    match &message.event {
+        EngineEvent::Status(_) => Some(CoalesceKey::Status),
+        EngineEvent::Stats(_) => Some(CoalesceKey::Stats),
+        EngineEvent::OutputsList(_) => Some(CoalesceKey::Outputs),
+        EngineEvent::OutputAdded(info) => Some(CoalesceKey::Output(info.name.clone())),
+        EngineEvent::OutputRemoved(name) => Some(CoalesceKey::Output(name.clone())),
+        EngineEvent::Palettes(_) => Some(CoalesceKey::Palettes),
+        _ => None,
+    }
+}
+
+type Backlog = VecDeque<(Option<CoalesceKey>, EngineMessage)>;
+
+/// Sending half of the event channel; stamps each event as it is sent
+#[derive(Debug, Clone)]
+pub(crate) struct EventSender {
+    sender: SyncSender<EngineMessage>,
+    /// Events waiting for room, oldest first, with what they merge with
+    backlog: Arc<Mutex<Backlog>>,
+    counters: Arc<EventCounters>,
+}
+
+impl EventSender {
+    pub(crate) fn new(sender: SyncSender<EngineMessage>) -> Self {
+        Self {
+            sender,
+            backlog: Arc::default(),
+            counters: Arc::default(),
+        }
+    }
+
+    /// Counters to read from outside the engine
+    pub(crate) fn counters(&self) -> Arc<EventCounters> {
+        self.counters.clone()
+    }
+
+    /// Send an event the engine raised on its own
+    pub(crate) fn send(&self, event: EngineEvent) -> Result<(), SendError<()>> {
+        self.reply(None, event)
+    }
+
+    /// Send an event caused by the command with id `request`
+    ///
+    /// Fails only once the GUI has dropped its receiver; on a full channel
+    /// the event waits in the backlog instead.
+    pub(crate) fn reply(
+        &self,
+        request: Option<RequestId>,
+        event: EngineEvent,
+    ) -> Result<(), SendError<()>> {
+        let message = EngineMessage {
+            event,
+            at: Instant::now(),
+            request,
+        };
+        let mut backlog = self.backlog.lock().unwrap_or_else(PoisonError::into_inner);
+        self.flush_backlog(&mut backlog)?;
+
+        // Events queue behind the backlog so they stay in order
+        if !backlog.is_empty() {
+            self.hold(&mut backlog, message);
+            return Ok(());
+        }
+        match self.sender.try_send(message) {
+            Ok(()) => {
+                self.counters.delivered.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(TrySendError::Full(message)) => {
+                self.hold(&mut backlog, message);
+                Ok(())
+            }
+            Err(TrySendError::Disconnected(_)) => Err(SendError(())),
+        }
+    }
+
+    /// Move backlogged events onto the channel as far as there is room
+    pub(crate) fn flush(&self) {
+        let mut backlog = self.backlog.lock().unwrap_or_else(PoisonError::into_inner);
+        let _ = self.flush_backlog(&mut backlog);
+    }
+
+    fn flush_backlog(&self, backlog: &mut Backlog) -> Result<(), SendError<()>> {
+        while let Some((key, message)) = backlog.pop_front() {
+            match self.sender.try_send(message) {
+                Ok(()) => {
+                    self.counters.delivered.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(TrySendError::Full(message)) => {
+                    backlog.push_front((key, message));
+                    return Ok(());
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    backlog.clear();
+                    return Err(SendError(()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Keep `message` until there is room, replacing an older update of its
+    /// kind if there is one, and dropping the oldest unsolicited event once
+    /// the backlog is full
+    fn hold(&self, backlog: &mut Backlog, message: EngineMessage) {
+        let key = coalesce_key(&message);
+        if let Some(slot) = key.as_ref().and_then(|key| {
+            backlog
+                .iter_mut()
+                .find(|(held, _)| held.as_ref() == Some(key))
+        }) {
+            slot.1 = message;
+            self.counters.coalesced.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        if backlog.len() >= EVENT_BACKLOG_CAPACITY {
+            let oldest = backlog
+                .iter()
+                .position(|(_, held)| held.request.is_none())
+                .or_else(|| message.request.is_some().then_some(0));
+            match oldest.and_then(|index| backlog.remove(index)) {
+                Some((_, dropped)) => self.record_drop(&dropped),
+                // Only replies are waiting; they go before a new update
+                None => {
+                    self.record_drop(&message);
+                    return;
+                }
+            }
+        }
+
+        let delayed = self.counters.delayed.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("Event channel full, holding {:?}", message.event);
+        if delayed.is_power_of_two() {
+            warn!(
+                "The GUI is not keeping up with engine events; {} delayed so far",
+                delayed
+            );
+        }
+        backlog.push_back((key, message));
+    }
+
+    fn record_drop(&self, message: &EngineMessage) {
+        let dropped = self.counters.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+        debug!("Event backlog full, dropping {:?}", message.event);
+        if dropped.is_power_of_two() {
+            warn!(
+                "The GUI stopped reading engine events; {} dropped so far",
+                dropped
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::sync_channel;
+
+    use crate::engine::command::EngineStatus;
+
+    #[test]
+    fn test_event_sender_stamps_events_with_their_request() {
+        let (sender, receiver) = sync_channel(4);
+        let events = EventSender::new(sender);
+        let before = Instant::now();
+
+        events
+            .reply(Some(RequestId(7)), EngineEvent::Palettes(Vec::new()))
+            .unwrap();
+        events.send(EngineEvent::Started).unwrap();
+
+        let reply = receiver.recv().unwrap();
+        assert_eq!(reply.request, Some(RequestId(7)));
+        assert!(matches!(reply.event, EngineEvent::Palettes(_)));
+        assert!(reply.at >= before);
+        let unsolicited = receiver.recv().unwrap();
+        assert_eq!(unsolicited.request, None);
+        assert!(unsolicited.at >= reply.at);
+        assert_eq!(RequestId(7).to_string(), "#7");
+    }
+
+    #[test]
+    fn test_full_channel_coalesces_updates_and_keeps_replies_in_order() {
+        let (sender, receiver) = sync_channel(1);
+        let events = EventSender::new(sender);
+        let counters = events.counters();
+
+        events.send(EngineEvent::Started).unwrap();
+        // The channel is full from here on
+        events.send(EngineEvent::Palettes(Vec::new())).unwrap();
+        events
+            .reply(Some(RequestId(1)), EngineEvent::Error("lost".to_string()))
+            .unwrap();
+        events.send(EngineEvent::Palettes(Vec::new())).unwrap();
+        events
+            .reply(Some(RequestId(2)), EngineEvent::Palettes(Vec::new()))
+            .unwrap();
+        events.send(EngineEvent::Stopped).unwrap();
+
+        assert_eq!(
+            counters.snapshot(),
+            EventChannelStats {
+                delivered: 1,
+                coalesced: 1,
+                delayed: 4,
+                dropped: 0,
+            }
+        );
+
+        let mut received = Vec::new();
+        for _ in 0..5 {
+            let message = receiver.recv().unwrap();
+            received.push((message.request, format!("{:?}", message.event)));
+            events.flush();
+        }
+        assert_eq!(
+            received,
+            vec![
+                (None, "Started".to_string()),
+                (None, "Palettes([])".to_string()),
+                (Some(RequestId(1)), "Error(\"lost\")".to_string()),
+                (Some(RequestId(2)), "Palettes([])".to_string()),
+                (None, "Stopped".to_string()),
+            ]
+        );
+        assert_eq!(counters.snapshot().delivered, 5);
+
+        drop(receiver);
+        assert!(events.send(EngineEvent::Stopped).is_err());
+    }
+
+    #[test]
+    fn test_full_channel_coalesces_unsolicited_status() {
+        let (sender, receiver) = sync_channel(1);
+        let events = EventSender::new(sender);
+
+        events.send(EngineEvent::Started).unwrap();
+        events
+            .send(EngineEvent::Status(EngineStatus::default()))
+            .unwrap();
+        events
+            .send(EngineEvent::Status(EngineStatus {
+                running: true,
+                ..EngineStatus::default()
+            }))
+            .unwrap();
+
+        assert_eq!(events.counters().snapshot().coalesced, 1);
+        assert!(matches!(
+            receiver.recv().unwrap().event,
+            EngineEvent::Started
+        ));
+        events.flush();
+        assert!(matches!(
+            receiver.recv().unwrap().event,
+            EngineEvent::Status(EngineStatus { running: true, .. })
+        ));
+    }
+
+    #[test]
+    fn test_full_backlog_drops_unsolicited_events_before_replies() {
+        let (sender, receiver) = sync_channel(1);
+        let events = EventSender::new(sender);
+        let counters = events.counters();
+
+        events.send(EngineEvent::Started).unwrap();
+        // The channel is full; fill the backlog behind one unsolicited event
+        events.send(EngineEvent::Started).unwrap();
+        for id in 1..EVENT_BACKLOG_CAPACITY as u64 {
+            events
+                .reply(Some(RequestId(id)), EngineEvent::Error(id.to_string()))
+                .unwrap();
+        }
+
+        // Makes room by dropping the unsolicited event
+        events
+            .reply(
+                Some(RequestId(5000)),
+                EngineEvent::Error("late".to_string()),
+            )
+            .unwrap();
+        // Only replies left: a new update is dropped, a new reply drops the oldest
+        events.send(EngineEvent::Stopped).unwrap();
+        events
+            .reply(
+                Some(RequestId(5001)),
+                EngineEvent::Error("later".to_string()),
+            )
+            .unwrap();
+
+        let stats = counters.snapshot();
+        assert_eq!(stats.dropped, 3);
+        assert_eq!(stats.delayed, EVENT_BACKLOG_CAPACITY as u64 + 2);
+
+        let mut requests = Vec::new();
+        while let Ok(message) = receiver.try_recv() {
+            requests.push(message.request);
+            events.flush();
+        }
+        assert_eq!(requests.len(), EVENT_BACKLOG_CAPACITY + 1);
+        assert_eq!(requests[0], None);
+        assert_eq!(requests[1], Some(RequestId(2)));
+        assert_eq!(requests.last(), Some(&Some(RequestId(5001))));
+    }
+}
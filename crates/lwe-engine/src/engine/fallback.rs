@@ -0,0 +1,298 @@
+//! Fallbacks for a wallpaper that fails while it plays
+//!
+//! When the wallpaper on an output fails (its file is deleted, its stream
+//! dies) the output walks its fallback chain: the configured images in
+//! order, then a solid color. The wallpaper is retried with a growing
+//! delay, and comes back as soon as it is available again. A file that
+//! keeps failing once it is opened (a corrupt file, a codec mpv cannot
+//! decode) is given up on after a few tries, and the output stays in safe
+//! mode until another wallpaper is applied.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Delay before the first retry of a failed wallpaper
+const FIRST_RETRY: Duration = Duration::from_secs(5);
+/// Longest delay between retries
+const MAX_RETRY: Duration = Duration::from_secs(300);
+/// A wallpaper that fails again this soon after recovering waits longer
+/// before its next retry
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+/// Failures in a row, without playing for `STABLE_AFTER` in between, after
+/// which a wallpaper is no longer retried
+pub(crate) const SAFE_MODE_AFTER: u32 = 3;
+
+/// What an output shows while its wallpaper is failing
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fallback {
+    /// Still or animated image
+    Image(PathBuf),
+    /// Solid color as RGB
+    Color([u8; 3]),
+}
+
+impl std::fmt::Display for Fallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Fallback::Image(path) => write!(f, "{}", path.display()),
+            Fallback::Color([r, g, b]) => write!(f, "#{r:02x}{g:02x}{b:02x}"),
+        }
+    }
+}
+
+impl std::str::FromStr for Fallback {
+    type Err = String;
+
+    /// Accepts a `#rrggbb` / `#rgb` color or an image path
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err("Empty fallback".to_string());
+        }
+        if !value.starts_with('#') {
+            return Ok(Fallback::Image(PathBuf::from(value)));
+        }
+        match value.parse::<lwe_core::LetterboxFill>() {
+            Ok(lwe_core::LetterboxFill::Color(color)) => Ok(Fallback::Color(color)),
+            _ => Err(format!("Invalid fallback color: {value}")),
+        }
+    }
+}
+
+/// The configured chain up to its first solid color, which always ends it;
+/// `color` is used when none is configured
+pub(crate) fn fallback_chain(configured: &[Fallback], color: [u8; 3]) -> Vec<Fallback> {
+    let mut chain = Vec::new();
+    for fallback in configured {
+        chain.push(fallback.clone());
+        if let Fallback::Color(_) = fallback {
+            return chain;
+        }
+    }
+    chain.push(Fallback::Color(color));
+    chain
+}
+
+/// Whether a local file can be opened again; streams are always worth a retry
+pub(crate) fn source_available(path: &Path) -> bool {
+    path.to_string_lossy().contains("://") || path.exists()
+}
+
+/// A failing wallpaper and how far down its fallback chain the output is
+#[derive(Debug, Clone, PartialEq)]
+struct Failing {
+    primary: PathBuf,
+    step: usize,
+    retry_at: Instant,
+    /// No more retries; the output stays on its fallback
+    given_up: bool,
+}
+
+/// Failure and recovery bookkeeping for one output's wallpaper
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SourceHealth {
+    failing: Option<Failing>,
+    retry_delay: Duration,
+    recovered_at: Option<Instant>,
+    /// Failures in a row of the same wallpaper
+    failures: u32,
+}
+
+impl Default for SourceHealth {
+    fn default() -> Self {
+        Self {
+            failing: None,
+            retry_delay: FIRST_RETRY,
+            recovered_at: None,
+            failures: 0,
+        }
+    }
+}
+
+impl SourceHealth {
+    /// The wallpaper `primary` failed; the output shows the first fallback.
+    /// Returns how many times in a row it has failed
+    pub(crate) fn fail(&mut self, primary: PathBuf, now: Instant) -> u32 {
+        let unstable = self
+            .recovered_at
+            .is_some_and(|recovered| now.duration_since(recovered) < STABLE_AFTER);
+        (self.retry_delay, self.failures) = if unstable {
+            ((self.retry_delay * 2).min(MAX_RETRY), self.failures + 1)
+        } else {
+            (FIRST_RETRY, 1)
+        };
+        self.recovered_at = None;
+        self.failing = Some(Failing {
+            primary,
+            step: 0,
+            retry_at: now + self.retry_delay,
+            given_up: false,
+        });
+        self.failures
+    }
+
+    /// Stop retrying the failed wallpaper
+    pub(crate) fn give_up(&mut self) {
+        if let Some(failing) = self.failing.as_mut() {
+            failing.given_up = true;
+        }
+    }
+
+    /// Whether the failed wallpaper is no longer retried
+    pub(crate) fn gave_up(&self) -> bool {
+        self.failing
+            .as_ref()
+            .is_some_and(|failing| failing.given_up)
+    }
+
+    /// The fallback showing failed as well; move on to the next one
+    pub(crate) fn advance(&mut self) {
+        if let Some(failing) = self.failing.as_mut() {
+            failing.step += 1;
+        }
+    }
+
+    /// Position in the fallback chain (None = the wallpaper is healthy)
+    pub(crate) fn step(&self) -> Option<usize> {
+        self.failing.as_ref().map(|failing| failing.step)
+    }
+
+    /// The wallpaper that failed
+    pub(crate) fn primary(&self) -> Option<&Path> {
+        self.failing
+            .as_ref()
+            .map(|failing| failing.primary.as_path())
+    }
+
+    /// The wallpaper to switch back to, once its retry is due and
+    /// `available` says it can be opened; otherwise the retry waits longer
+    pub(crate) fn retry(
+        &mut self,
+        now: Instant,
+        available: impl Fn(&Path) -> bool,
+    ) -> Option<PathBuf> {
+        let failing = self.failing.as_mut().filter(|failing| !failing.given_up)?;
+        if now < failing.retry_at {
+            return None;
+        }
+        if !available(&failing.primary) {
+            self.retry_delay = (self.retry_delay * 2).min(MAX_RETRY);
+            failing.retry_at = now + self.retry_delay;
+            return None;
+        }
+
+        self.recovered_at = Some(now);
+        self.failing.take().map(|failing| failing.primary)
+    }
+
+    /// Forget a failure because another wallpaper was applied
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_always_ends_in_a_solid_color() {
+        let image = Fallback::Image(PathBuf::from("/walls/still.png"));
+        assert_eq!(
+            fallback_chain(std::slice::from_ref(&image), [1, 2, 3]),
+            vec![image.clone(), Fallback::Color([1, 2, 3])]
+        );
+        assert_eq!(
+            fallback_chain(&[image.clone(), Fallback::Color([9, 9, 9])], [1, 2, 3]),
+            vec![image, Fallback::Color([9, 9, 9])]
+        );
+        assert_eq!(
+            fallback_chain(&[], [0, 0, 0]),
+            vec![Fallback::Color([0, 0, 0])]
+        );
+    }
+
+    #[test]
+    fn test_fallback_parses_colors_and_paths() {
+        assert_eq!("#202830".parse(), Ok(Fallback::Color([0x20, 0x28, 0x30])));
+        assert_eq!("#fff".parse(), Ok(Fallback::Color([255, 255, 255])));
+        assert_eq!(
+            "/walls/still.png".parse(),
+            Ok(Fallback::Image(PathBuf::from("/walls/still.png")))
+        );
+        assert!("#2028".parse::<Fallback>().is_err());
+        assert!(" ".parse::<Fallback>().is_err());
+        assert_eq!(Fallback::Color([0x20, 0x28, 0x30]).to_string(), "#202830");
+    }
+
+    #[test]
+    fn test_retry_waits_for_the_source_and_backs_off() {
+        let start = Instant::now();
+        let mut health = SourceHealth::default();
+        health.fail(PathBuf::from("/walls/rain.mp4"), start);
+        health.advance();
+        assert_eq!(health.step(), Some(1));
+
+        assert_eq!(health.retry(start + Duration::from_secs(1), |_| true), None);
+        // Still missing: the next retry waits twice as long
+        let missing = start + FIRST_RETRY;
+        assert_eq!(health.retry(missing, |_| false), None);
+        assert_eq!(health.retry(missing + FIRST_RETRY, |_| true), None);
+        assert_eq!(
+            health.retry(missing + FIRST_RETRY * 2, |_| true),
+            Some(PathBuf::from("/walls/rain.mp4"))
+        );
+        assert_eq!(health.step(), None);
+    }
+
+    #[test]
+    fn test_failures_in_a_row_are_counted_until_given_up() {
+        let start = Instant::now();
+        let mut health = SourceHealth::default();
+        let corrupt = PathBuf::from("/walls/corrupt.mp4");
+        assert_eq!(health.fail(corrupt.clone(), start), 1);
+
+        // Each retry opens the file and it fails again right away
+        let mut now = start;
+        for expected in 2..=SAFE_MODE_AFTER {
+            now += MAX_RETRY;
+            assert!(health.retry(now, |_| true).is_some());
+            assert_eq!(health.fail(corrupt.clone(), now), expected);
+        }
+
+        health.give_up();
+        assert!(health.gave_up());
+        assert_eq!(health.retry(now + MAX_RETRY * 10, |_| true), None);
+        assert_eq!(health.primary(), Some(corrupt.as_path()));
+
+        health.reset();
+        assert!(!health.gave_up());
+        assert_eq!(health.fail(corrupt, now), 1);
+    }
+
+    #[test]
+    fn test_failing_soon_after_recovering_retries_later() {
+        let start = Instant::now();
+        let mut health = SourceHealth::default();
+        health.fail(PathBuf::from("rtsp://cam/live"), start);
+        let recovered = start + FIRST_RETRY;
+        assert!(health.retry(recovered, |_| true).is_some());
+
+        health.fail(
+            PathBuf::from("rtsp://cam/live"),
+            recovered + Duration::from_secs(1),
+        );
+        assert_eq!(
+            health.retry(recovered + FIRST_RETRY + Duration::from_secs(1), |_| true),
+            None
+        );
+
+        // A failure long after recovering starts over
+        let mut health = SourceHealth::default();
+        health.fail(PathBuf::from("rtsp://cam/live"), start);
+        assert!(health.retry(recovered, |_| true).is_some());
+        let later = recovered + STABLE_AFTER;
+        health.fail(PathBuf::from("rtsp://cam/live"), later);
+        assert!(health.retry(later + FIRST_RETRY, |_| true).is_some());
+    }
+}
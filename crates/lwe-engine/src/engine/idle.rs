@@ -0,0 +1,196 @@
+//! Pausing wallpapers while the session is idle or locked
+//!
+//! The compositor reports idleness through ext_idle_notify_v1 after the
+//! configured timeout. The shell forwards the session screensaver's
+//! `ActiveChanged` signal, which also covers lock screens on desktops that
+//! lock without going idle first.
+
+use std::collections::HashSet;
+
+use tracing::{debug, info};
+use wayland_client::protocol::wl_seat::{self, WlSeat};
+use wayland_client::{Connection, Dispatch, QueueHandle};
+use wayland_protocols::ext::idle_notify::v1::client::{
+    ext_idle_notification_v1::{self, ExtIdleNotificationV1},
+    ext_idle_notifier_v1::{self, ExtIdleNotifierV1},
+};
+
+use super::{EngineState, PlaybackState};
+
+/// Idle notification objects and the outputs paused for idleness
+#[derive(Default)]
+pub(crate) struct IdleWatcher {
+    notifier: Option<ExtIdleNotifierV1>,
+    seat: Option<WlSeat>,
+    notification: Option<ExtIdleNotificationV1>,
+    /// The compositor reported the user idle
+    idle: bool,
+    /// The session screensaver or lock screen is active
+    screensaver: bool,
+    /// Outputs whose playback was paused for idleness
+    paused: HashSet<String>,
+}
+
+impl IdleWatcher {
+    pub(crate) fn set_notifier(&mut self, notifier: ExtIdleNotifierV1) {
+        self.notifier = Some(notifier);
+    }
+
+    pub(crate) fn set_seat(&mut self, seat: WlSeat) {
+        self.seat = Some(seat);
+    }
+
+    /// Whether wallpapers are held paused for idleness
+    pub(crate) fn is_idle(&self) -> bool {
+        self.idle || self.screensaver
+    }
+
+    /// Whether `output` was paused for idleness and waits to be resumed
+    pub(crate) fn holds(&self, output: &str) -> bool {
+        self.paused.contains(output)
+    }
+
+    /// Resume `output` with the others when the session becomes active
+    pub(crate) fn hold(&mut self, output: &str) {
+        self.paused.insert(output.to_string());
+    }
+
+    /// Stop resuming `output` when the session becomes active, after a user
+    /// pause or once its wallpaper is cleared
+    pub(crate) fn release(&mut self, output: Option<&str>) {
+        match output {
+            Some(output) => {
+                self.paused.remove(output);
+            }
+            None => self.paused.clear(),
+        }
+    }
+}
+
+/// Ask for an idle notification once both the notifier and a seat are bound
+pub(crate) fn watch(state: &mut EngineState, qh: &QueueHandle<EngineState>) {
+    let Some(timeout) = state.config.pause_on_idle else {
+        return;
+    };
+    let watcher = &mut state.idle;
+    let (Some(notifier), Some(seat)) = (&watcher.notifier, &watcher.seat) else {
+        return;
+    };
+    if watcher.notification.is_some() {
+        return;
+    }
+
+    let timeout_ms = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+    watcher.notification = Some(notifier.get_idle_notification(timeout_ms, seat, qh, ()));
+    info!("Pausing wallpapers after {:?} of inactivity", timeout);
+}
+
+/// Ask again after the timeout changed; the session counts as active until
+/// the compositor reports it idle under the new one
+pub(crate) fn rewatch(state: &mut EngineState, qh: &QueueHandle<EngineState>) {
+    if let Some(notification) = state.idle.notification.take() {
+        notification.destroy();
+    }
+    let was_idle = state.idle.is_idle();
+    state.idle.idle = false;
+    sync(state, was_idle);
+    watch(state, qh);
+}
+
+/// Record the screensaver state reported by the shell
+pub(crate) fn set_screensaver_active(state: &mut EngineState, active: bool) {
+    if state.config.pause_on_idle.is_none() {
+        return;
+    }
+    let was_idle = state.idle.is_idle();
+    state.idle.screensaver = active;
+    sync(state, was_idle);
+}
+
+/// Pause playing sessions when the session turns idle and resume the ones
+/// paused for it when it is active again
+fn sync(state: &mut EngineState, was_idle: bool) {
+    let idle = state.idle.is_idle();
+    if idle == was_idle {
+        return;
+    }
+
+    if idle {
+        info!("Session is idle, pausing wallpapers");
+        for (output_name, session) in state.sessions.iter_mut() {
+            // Battery pauses are lifted by the idle resume, so hold them too
+            if session.state() == PlaybackState::Playing
+                || state.power_paused
+                || state.battery_still
+            {
+                session.pause();
+                state.idle.paused.insert(output_name.clone());
+            }
+        }
+        return;
+    }
+
+    info!("Session is active again, resuming wallpapers");
+    for output_name in std::mem::take(&mut state.idle.paused) {
+        if state.power_paused
+            || state.battery_still
+            || state.fullscreen_paused.contains(&output_name)
+        {
+            continue;
+        }
+        if state.deep_sleep.is_asleep() {
+            state.deep_sleep.hold(&output_name);
+        } else if let Some(session) = state.sessions.get_mut(&output_name) {
+            session.resume();
+        }
+    }
+}
+
+// Dispatch for ext_idle_notifier_v1 (no events)
+impl Dispatch<ExtIdleNotifierV1, ()> for EngineState {
+    fn event(
+        _state: &mut Self,
+        _notifier: &ExtIdleNotifierV1,
+        _event: ext_idle_notifier_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // ext_idle_notifier_v1 has no events
+    }
+}
+
+impl Dispatch<ExtIdleNotificationV1, ()> for EngineState {
+    fn event(
+        state: &mut Self,
+        _notification: &ExtIdleNotificationV1,
+        event: ext_idle_notification_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let was_idle = state.idle.is_idle();
+        match event {
+            ext_idle_notification_v1::Event::Idled => state.idle.idle = true,
+            ext_idle_notification_v1::Event::Resumed => state.idle.idle = false,
+            _ => return,
+        }
+        sync(state, was_idle);
+    }
+}
+
+// Dispatch for wl_seat: only needed to address idle notifications
+impl Dispatch<WlSeat, ()> for EngineState {
+    fn event(
+        _state: &mut Self,
+        _seat: &WlSeat,
+        event: wl_seat::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let wl_seat::Event::Name { name } = event {
+            debug!("Seat: {}", name);
+        }
+    }
+}
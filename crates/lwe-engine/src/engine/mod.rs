@@ -4,42 +4,96 @@
 //! within the GUI process. The engine runs in a dedicated thread and
 //! communicates with the GUI via channels.
 
+mod color;
 mod command;
+mod events;
+mod fallback;
+mod idle;
+mod reconfigure;
 mod session;
+mod sleep;
+mod startup;
+mod supervisor;
+mod windows;
+
+pub use command::{
+    EngineCommand, EngineConfig, EngineEvent, EngineMessage, EngineRequest, EngineStatus,
+    OutputPlayback, OutputStats, OutputVideoOverrides, RequestId,
+};
+pub use events::{EventChannelStats, EVENT_BACKLOG_CAPACITY, EVENT_CHANNEL_CAPACITY};
+pub use fallback::Fallback;
+pub use session::{PlaybackState, WallpaperSession};
 
-pub use command::{EngineCommand, EngineConfig, EngineEvent, EngineStatus};
-pub use session::WallpaperSession;
-
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::CString;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{self, Receiver as StdReceiver, Sender as StdSender};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver as StdReceiver};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 
 use anyhow::{Context, Result};
 use calloop::channel::{channel, Sender};
+use calloop::timer::{TimeoutAction, Timer};
 use calloop::EventLoop;
 use calloop_wayland_source::WaylandSource;
+use lwe_core::{span_canvas, LayoutMode, RenderBackend, SpanRect, SurfaceLayer, PALETTE_SIZE};
 use tracing::{debug, error, info, warn};
 use wayland_client::protocol::wl_callback::{self, WlCallback};
 use wayland_client::protocol::wl_compositor::{self, WlCompositor};
 use wayland_client::protocol::wl_output::{self, WlOutput};
 use wayland_client::protocol::wl_registry::{self, WlRegistry};
+use wayland_client::protocol::wl_seat::WlSeat;
 use wayland_client::protocol::wl_surface::{self, WlSurface};
-use wayland_client::{Connection, Dispatch, QueueHandle};
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols::ext::idle_notify::v1::client::ext_idle_notifier_v1::ExtIdleNotifierV1;
+use wayland_protocols::wp::color_management::v1::client::{
+    wp_color_management_surface_v1::WpColorManagementSurfaceV1,
+    wp_color_manager_v1::WpColorManagerV1,
+};
+use wayland_protocols::wp::idle_inhibit::zv1::client::{
+    zwp_idle_inhibit_manager_v1::{self, ZwpIdleInhibitManagerV1},
+    zwp_idle_inhibitor_v1::{self, ZwpIdleInhibitorV1},
+};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1;
 use wayland_protocols_wlr::layer_shell::v1::client::{
     zwlr_layer_shell_v1::{self, ZwlrLayerShellV1},
     zwlr_layer_surface_v1::{self, Anchor, ZwlrLayerSurfaceV1},
 };
 
-use crate::egl::EglContext;
+use crate::egl::{self, EglContext};
+use crate::gl_debug::{self, GpuMessage, GpuMessageLog, GpuSeverity};
+use crate::probe::probe_source;
+use crate::shared_decode::SharedDecoders;
+use crate::snapshot::Frame;
+use crate::systemd;
 use crate::wayland::OutputManager;
+use crate::workarounds::{DetectedCompositor, Workarounds};
+
+use events::EventCounters;
+pub(crate) use events::EventSender;
+use startup::Backoff;
+use windows::{OutputWindows, WindowTracker};
+
+/// How long wallpaper audio takes to fade in or out when focus moves
+const AUDIO_FOCUS_RAMP: std::time::Duration = std::time::Duration::from_millis(400);
+
+/// How often a new EGL context is attempted while the GPU is still resetting
+const GPU_RESET_RETRY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// How often the battery status is read
+const BATTERY_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Step of an audio focus fade while no frames wake the loop
+const FOCUS_RAMP_TICK: std::time::Duration = std::time::Duration::from_millis(16);
 
 /// Handle for controlling a running PlaybackEngine from another thread
 pub struct EngineHandle {
     /// Command sender (calloop channel, cloneable)
-    commands_tx: Sender<EngineCommand>,
+    commands_tx: Sender<EngineRequest>,
+    /// Id of the last command sent
+    last_request: AtomicU64,
+    /// Delivery counters of the event channel
+    event_counters: Arc<EventCounters>,
     /// Shutdown flag
     shutdown: Arc<AtomicBool>,
     /// Thread handle
@@ -47,18 +101,30 @@ pub struct EngineHandle {
 }
 
 impl EngineHandle {
-    /// Send a command to the engine
-    pub fn send(&self, cmd: EngineCommand) -> Result<()> {
+    /// Send a command to the engine; the events it causes carry the returned id
+    pub fn send(&self, cmd: EngineCommand) -> Result<RequestId> {
+        let id = RequestId(self.last_request.fetch_add(1, Ordering::Relaxed) + 1);
         self.commands_tx
-            .send(cmd)
-            .map_err(|e| anyhow::anyhow!("Failed to send command: {}", e))
+            .send(EngineRequest {
+                id: Some(id),
+                sent_at: std::time::Instant::now(),
+                command: cmd,
+            })
+            .map_err(|e| anyhow::anyhow!("Failed to send command: {}", e))?;
+        Ok(id)
     }
 
     /// Get a clone of the command sender for external use (e.g., IPC server)
-    pub fn command_sender(&self) -> Sender<EngineCommand> {
+    pub fn command_sender(&self) -> Sender<EngineRequest> {
         self.commands_tx.clone()
     }
 
+    /// How many events reached the GUI, and how many were coalesced or
+    /// dropped while it was not reading
+    pub fn event_stats(&self) -> EventChannelStats {
+        self.event_counters.snapshot()
+    }
+
     /// Get the shutdown flag for cooperative shutdown
     pub fn shutdown_flag(&self) -> Arc<AtomicBool> {
         self.shutdown.clone()
@@ -67,6 +133,15 @@ impl EngineHandle {
     /// Request the engine to shut down
     pub fn request_shutdown(&self) {
         self.shutdown.store(true, Ordering::SeqCst);
+        self.wake_for_shutdown();
+    }
+
+    /// The engine sleeps until an event arrives; a command wakes it to
+    /// notice the shutdown flag
+    fn wake_for_shutdown(&self) {
+        let _ = self
+            .commands_tx
+            .send(EngineRequest::internal(EngineCommand::Shutdown));
     }
 
     /// Wait for the engine thread to finish
@@ -92,6 +167,7 @@ impl Drop for EngineHandle {
     fn drop(&mut self) {
         // Signal shutdown
         self.shutdown.store(true, Ordering::SeqCst);
+        self.wake_for_shutdown();
         // Don't join here to avoid blocking in destructor
     }
 }
@@ -100,33 +176,38 @@ impl Drop for EngineHandle {
 ///
 /// This is the recommended way to start the engine, as it handles
 /// thread-safety correctly (calloop EventLoop cannot be moved across threads).
+/// A supervisor thread restarts the engine if it panics, re-applying the
+/// active wallpapers and sending `EngineEvent::Restarted`.
 ///
 /// # Arguments
 /// * `config` - Engine configuration
 ///
 /// # Returns
-/// A tuple of (EngineHandle, Receiver<EngineEvent>)
-pub fn spawn_engine(config: EngineConfig) -> Result<(EngineHandle, StdReceiver<EngineEvent>)> {
-    // Create event channel (std mpsc, GUI side)
-    let (events_tx, events_rx) = mpsc::channel::<EngineEvent>();
+/// A tuple of (EngineHandle, Receiver<EngineMessage>); each message is an
+/// event stamped with when it was sent and the request it answers
+pub fn spawn_engine(config: EngineConfig) -> Result<(EngineHandle, StdReceiver<EngineMessage>)> {
+    // Create event channel (bounded std mpsc, GUI side)
+    let (events_tx, events_rx) = mpsc::sync_channel::<EngineMessage>(EVENT_CHANNEL_CAPACITY);
+    let events_tx = EventSender::new(events_tx);
+    let event_counters = events_tx.counters();
 
-    // Create command channel (calloop, for engine thread)
-    let (commands_tx, commands_rx) = channel::<EngineCommand>();
+    // Create command channel (calloop, read by the supervisor)
+    let (commands_tx, commands_rx) = channel::<EngineRequest>();
 
     let shutdown = Arc::new(AtomicBool::new(false));
-    let shutdown_clone = shutdown.clone();
+
+    let worker = supervisor::spawn_worker(config.clone(), events_tx.clone(), shutdown.clone())?;
+    let supervisor = supervisor::Supervisor::new(config, events_tx, shutdown.clone(), worker);
 
     let thread = thread::Builder::new()
-        .name("lwe-engine".to_string())
-        .spawn(move || {
-            if let Err(e) = run_engine_thread(config, events_tx, commands_rx, shutdown_clone) {
-                error!("Engine thread error: {}", e);
-            }
-        })
-        .context("Failed to spawn engine thread")?;
+        .name("lwe-engine-watchdog".to_string())
+        .spawn(move || supervisor.run(commands_rx))
+        .context("Failed to spawn engine supervisor thread")?;
 
     let handle = EngineHandle {
         commands_tx,
+        last_request: AtomicU64::new(0),
+        event_counters,
         shutdown,
         thread: Some(thread),
     };
@@ -137,18 +218,30 @@ pub fn spawn_engine(config: EngineConfig) -> Result<(EngineHandle, StdReceiver<E
 /// Internal: Run engine in the current thread
 fn run_engine_thread(
     config: EngineConfig,
-    events_tx: StdSender<EngineEvent>,
-    commands_rx: calloop::channel::Channel<EngineCommand>,
+    events_tx: EventSender,
+    commands_rx: calloop::channel::Channel<EngineRequest>,
     shutdown: Arc<AtomicBool>,
 ) -> Result<()> {
     info!("Starting PlaybackEngine in thread");
 
-    // Connect to Wayland compositor
-    let connection =
-        Connection::connect_to_env().context("Failed to connect to Wayland compositor")?;
+    // Connect to Wayland compositor (retries while the session is still starting)
+    let startup_timeout = config.startup_timeout;
+    let connection = startup::connect_with_retry(startup_timeout)?;
 
     debug!("Connected to Wayland compositor");
 
+    let compositor = DetectedCompositor::detect();
+    let workarounds = Workarounds::resolve(&compositor, &config.workarounds);
+    if workarounds.applied.is_empty() {
+        info!("Compositor: {} (no workarounds)", compositor);
+    } else {
+        info!(
+            "Compositor: {} (workarounds: {})",
+            compositor,
+            workarounds.applied.join(", ")
+        );
+    }
+
     // Get display pointer for EGL
     let display_ptr = connection.backend().display_ptr() as *mut std::ffi::c_void;
 
@@ -158,16 +251,36 @@ fn run_engine_thread(
         sessions: HashMap::new(),
         events_tx: events_tx.clone(),
         config,
+        workarounds,
         running: true,
         pending_outputs: HashMap::new(),
         compositor: None,
         layer_shell: None,
+        idle_inhibit_manager: None,
+        idle_inhibit_outputs: HashSet::new(),
+        idle: idle::IdleWatcher::default(),
+        color: None,
+        foreign_toplevel_manager: None,
+        windows: WindowTracker::new(),
+        fullscreen_paused: HashSet::new(),
+        deep_sleep: sleep::DeepSleep::default(),
+        audio_focus: None,
+        focus_gains: HashMap::new(),
+        last_focus_sync: std::time::Instant::now(),
+        focus_ramping: false,
+        connection: connection.clone(),
         egl_context: None,
+        egl_retry: None,
+        gpu_debug_pending: false,
+        gpu_messages: GpuMessageLog::default(),
         layer_surfaces: HashMap::new(),
         queue_handle: None,
         on_battery: check_battery_status(),
         power_paused: false,
-        last_battery_check: std::time::Instant::now(),
+        battery_still: false,
+        ready_notified: false,
+        decoders: SharedDecoders::default(),
+        last_wallpapers: HashMap::new(),
     };
 
     // Create event loop
@@ -178,8 +291,8 @@ fn run_engine_thread(
     event_loop
         .handle()
         .insert_source(commands_rx, |event, _, state| {
-            if let calloop::channel::Event::Msg(cmd) = event {
-                handle_command(cmd, state);
+            if let calloop::channel::Event::Msg(request) = event {
+                handle_request(request, state);
             }
         })
         .map_err(|e| anyhow::anyhow!("Failed to register command channel: {:?}", e))?;
@@ -213,13 +326,30 @@ fn run_engine_thread(
             .context("Event loop dispatch failed")?;
     }
 
+    // Compositors started alongside us may advertise layer-shell late
+    let mut backoff = Backoff::new(startup_timeout);
+    while state.compositor.is_none() || state.layer_shell.is_none() {
+        let Some(delay) = backoff.next_delay() else {
+            break;
+        };
+        debug!("Waiting {:?} for compositor globals", delay);
+        event_loop
+            .dispatch(delay, &mut state)
+            .context("Event loop dispatch failed")?;
+    }
+
     // Now initialize EGL if we have compositor and layer_shell
     if state.compositor.is_some() && state.layer_shell.is_some() {
         info!("Initializing EGL context...");
-        match EglContext::new(display_ptr) {
+        match EglContext::new(
+            display_ptr,
+            state.config.render_device.as_deref(),
+            state.config.gpu_debug,
+        ) {
             Ok(egl_ctx) => {
                 info!("  ✓ EGL context initialized");
                 state.egl_context = Some(egl_ctx);
+                state.gpu_debug_pending = state.config.gpu_debug;
             }
             Err(e) => {
                 warn!("  ✗ Failed to initialize EGL: {}", e);
@@ -227,7 +357,14 @@ fn run_engine_thread(
             }
         }
     } else {
-        warn!("Missing compositor or layer_shell - cannot create wallpaper surfaces");
+        warn!(
+            "Missing compositor or layer_shell after {:?} - cannot create wallpaper surfaces",
+            startup_timeout
+        );
+        let _ = events_tx.send(EngineEvent::Error(
+            "Compositor does not provide wlr-layer-shell".to_string(),
+        ));
+        systemd::notify_status("Compositor does not provide wlr-layer-shell");
         if state.compositor.is_none() {
             warn!("  - wl_compositor not bound");
         }
@@ -236,73 +373,295 @@ fn run_engine_thread(
         }
     }
 
-    // Main event loop with power management
-    let battery_check_interval = std::time::Duration::from_secs(10);
+    // Battery status is read on a timer; everything else wakes the loop
+    // through its own fd (Wayland, commands) or the timeout from `next_wakeup`
+    event_loop
+        .handle()
+        .insert_source(Timer::immediate(), |_, _, state| {
+            check_power(state);
+            TimeoutAction::ToDuration(BATTERY_CHECK_INTERVAL)
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to register battery timer: {:?}", e))?;
 
     while !shutdown.load(Ordering::Relaxed) {
-        // Periodically check battery status
-        if state.last_battery_check.elapsed() >= battery_check_interval {
-            state.on_battery = check_battery_status();
-            state.last_battery_check = std::time::Instant::now();
-
-            // Handle pause on battery
-            if state.config.pause_on_battery {
-                if state.on_battery && !state.power_paused {
-                    info!("On battery power, pausing playback for power saving");
-                    for session in state.sessions.values_mut() {
-                        session.pause();
-                    }
-                    state.power_paused = true;
-                } else if !state.on_battery && state.power_paused {
-                    info!("On AC power, resuming playback");
-                    for session in state.sessions.values_mut() {
-                        session.resume();
-                    }
-                    state.power_paused = false;
-                }
+        let timeout = next_wakeup(&state);
+        event_loop
+            .dispatch(timeout, &mut state)
+            .context("Event loop dispatch failed")?;
+        state.events_tx.flush();
+
+        if state.config.tracks_windows() {
+            state.windows.poll();
+            let windows = state.windows.output_windows(&state.outputs);
+            if state.config.deep_sleep {
+                sleep::sync(&mut state, &windows);
+            }
+            if state.config.pause_on_fullscreen {
+                sync_fullscreen_pause(&mut state, &windows);
+            }
+            if state.config.audio_follows_focus {
+                state.focus_ramping = sync_audio_focus(&mut state, &windows);
             }
         }
 
-        // Calculate frame duration based on power state
-        let frame_duration = if state.power_paused {
-            // When paused, use longer sleep to save power
-            std::time::Duration::from_millis(100)
-        } else {
-            state
-                .config
-                .fps_limit
-                .map(|fps| std::time::Duration::from_micros(1_000_000 / fps as u64))
-                .unwrap_or(std::time::Duration::from_millis(16)) // Default 60fps
-        };
-
-        event_loop
-            .dispatch(frame_duration, &mut state)
-            .context("Event loop dispatch failed")?;
+        if state
+            .egl_retry
+            .is_some_and(|attempted| attempted.elapsed() >= GPU_RESET_RETRY)
+        {
+            recreate_egl_context(&mut state);
+        }
 
-        // Render frames for configured layer surfaces (skip if power paused)
-        if !state.power_paused {
+        // Render frames for configured layer surfaces (skip if power paused,
+        // idle or asleep)
+        if !state.power_paused && !state.idle.is_idle() && !state.deep_sleep.is_asleep() {
+            check_sources(&mut state);
             render_all_surfaces(&mut state);
         }
     }
 
     info!("PlaybackEngine shutting down");
 
-    // Cleanup layer surfaces
-    for (output, info) in state.layer_surfaces.drain() {
-        debug!("Destroying layer surface for output: {}", output);
-        info.layer_surface.destroy();
+    state.teardown();
+    state.running = false;
+    let _ = events_tx.send(EngineEvent::Stopped);
+
+    Ok(())
+}
+
+/// Replace failed wallpapers with their fallbacks and retry them, reporting
+/// each failure and recovery, and reload edited shaders and LUTs
+fn check_sources(state: &mut EngineState) {
+    let Some(egl_context) = state.egl_context.as_ref() else {
+        return;
+    };
+    for (output_name, session) in state.sessions.iter_mut() {
+        if session.reload_changed_effects() {
+            if let Some(surface_info) = state.layer_surfaces.get_mut(output_name) {
+                surface_info.frame_pending = true;
+            }
+        }
+        let events = session.check_health(egl_context);
+        if events.is_empty() {
+            continue;
+        }
+        // The new source has to be drawn, even in place of a still image
+        if let Some(surface_info) = state.layer_surfaces.get_mut(output_name) {
+            surface_info.frame_pending = true;
+        }
+        for event in events {
+            let _ = state.events_tx.send(event);
+        }
     }
+}
 
-    // Cleanup sessions
-    for (output, session) in state.sessions.drain() {
-        debug!("Destroying session for output: {}", output);
-        drop(session);
+/// Read the battery status and pause playback for it, or switch to the
+/// battery profile and back
+fn check_power(state: &mut EngineState) {
+    let on_battery = check_battery_status();
+    let switched = on_battery != state.on_battery;
+    state.on_battery = on_battery;
+    if !state.config.pause_on_battery {
+        if switched {
+            apply_battery_decoding(state);
+        }
+        sync_battery_still(state);
+        return;
     }
 
-    state.running = false;
-    let _ = events_tx.send(EngineEvent::Stopped);
+    if state.on_battery && !state.power_paused {
+        info!("On battery power, pausing playback for power saving");
+        for session in state.sessions.values_mut() {
+            session.pause();
+        }
+        state.power_paused = true;
+        push_status(state);
+    } else if !state.on_battery && state.power_paused {
+        info!("On AC power, resuming playback");
+        lift_power_pause(state);
+        push_status(state);
+    }
+}
 
-    Ok(())
+/// Resume the wallpapers paused for battery power
+fn lift_power_pause(state: &mut EngineState) {
+    for (output_name, session) in state.sessions.iter_mut() {
+        if state.idle.holds(output_name) {
+            continue;
+        }
+        if state.deep_sleep.is_asleep() {
+            state.deep_sleep.hold(output_name);
+        } else {
+            session.resume();
+        }
+    }
+    state.power_paused = false;
+}
+
+/// Give every session the decoding of the battery profile, or the usual one
+/// back on AC power
+fn apply_battery_decoding(state: &mut EngineState) {
+    if state.on_battery {
+        info!("On battery power, switching to the battery profile");
+    } else {
+        info!("On AC power, restoring full playback quality");
+    }
+    for (output_name, session) in state.sessions.iter_mut() {
+        let video = state.config.video_for_power(output_name, state.on_battery);
+        session.set_decoding(video.hwdec, video.decode_fps);
+    }
+}
+
+/// Hold the wallpapers on their current frame while the battery profile asks
+/// for a still, and let them play again once it no longer does. Unlike a
+/// battery pause, the surfaces are still drawn, so a resized output shows the
+/// frame instead of going blank.
+fn sync_battery_still(state: &mut EngineState) {
+    let still = state.config.holds_still(state.on_battery);
+    if still == state.battery_still {
+        return;
+    }
+    for (output_name, session) in state.sessions.iter_mut() {
+        if still {
+            session.pause();
+        } else if state.idle.holds(output_name) || state.fullscreen_paused.contains(output_name) {
+            continue;
+        } else if state.deep_sleep.is_asleep() {
+            state.deep_sleep.hold(output_name);
+        } else {
+            session.resume();
+        }
+    }
+    state.battery_still = still;
+}
+
+/// How long the main loop may sleep when no fd wakes it, None = until one does
+///
+/// Frame callbacks, commands and compositor events all arrive on an fd, so a
+/// paused or still wallpaper only wakes for its timed checks. Only work
+/// without an fd of its own sets a deadline: Hyprland window polling, an
+/// audio focus fade, the frame callback fallback, an fps limit holding back a
+/// frame, EGL recreation after a GPU reset, and each session's checks of its
+/// file, fallback retries and shader and LUT edits.
+fn next_wakeup(state: &EngineState) -> Option<std::time::Duration> {
+    let mut deadlines = Vec::new();
+
+    if state.config.tracks_windows() {
+        deadlines.extend(state.windows.poll_interval());
+        if state.focus_ramping {
+            deadlines.push(FOCUS_RAMP_TICK);
+        }
+    }
+    if let Some(attempted) = state.egl_retry {
+        deadlines.push(GPU_RESET_RETRY.saturating_sub(attempted.elapsed()));
+    }
+
+    // The same conditions as `check_sources` and `render_all_surfaces`
+    if state.egl_context.is_some()
+        && !state.power_paused
+        && !state.idle.is_idle()
+        && !state.deep_sleep.is_asleep()
+    {
+        deadlines.extend(
+            state
+                .sessions
+                .values()
+                .filter_map(WallpaperSession::check_deadline),
+        );
+
+        let callback_fallback = state.workarounds.frame_callback_fallback;
+        let min_frame_interval = state.config.min_frame_interval(state.on_battery);
+        for (output_name, info) in state
+            .layer_surfaces
+            .iter()
+            .filter(|(_, info)| info.configured)
+        {
+            let needs_frames = state
+                .sessions
+                .get(output_name)
+                .is_some_and(WallpaperSession::needs_frames);
+            if info.frame_pending {
+                // Only an fps limit keeps a pending frame waiting
+                deadlines.push(min_frame_interval.saturating_sub(info.last_render.elapsed()));
+            } else if let Some(interval) = callback_fallback.filter(|_| needs_frames) {
+                deadlines.push(interval.saturating_sub(info.last_render.elapsed()));
+            }
+        }
+    }
+
+    deadlines.into_iter().min()
+}
+
+/// Pause sessions whose output is covered by a fullscreen (or, if
+/// configured, maximized) window and resume them once it is gone
+fn sync_fullscreen_pause(state: &mut EngineState, windows: &HashMap<String, OutputWindows>) {
+    let pause_on_maximized = state.config.pause_on_maximized;
+
+    for (output_name, session) in state.sessions.iter_mut() {
+        let covered = windows
+            .get(output_name)
+            .is_some_and(|windows| windows.fullscreen || (pause_on_maximized && windows.maximized));
+
+        if covered {
+            if session.state() == PlaybackState::Playing {
+                info!("Window covers {}, pausing its wallpaper", output_name);
+                session.pause();
+                state.fullscreen_paused.insert(output_name.clone());
+            }
+        } else if state.fullscreen_paused.remove(output_name)
+            && !state.power_paused
+            && !state.battery_still
+            && !state.idle.holds(output_name)
+        {
+            if state.deep_sleep.is_asleep() {
+                state.deep_sleep.hold(output_name);
+            } else {
+                info!("{} is visible again, resuming its wallpaper", output_name);
+                session.resume();
+            }
+        }
+    }
+}
+
+/// Ramp each session's focus gain toward full volume on the output holding
+/// the focused window and silence on the others; returns whether a gain has
+/// yet to reach its target
+fn sync_audio_focus(state: &mut EngineState, windows: &HashMap<String, OutputWindows>) -> bool {
+    if let Some(focused) = windows
+        .iter()
+        .find(|(_, windows)| windows.focused)
+        .map(|(name, _)| name)
+    {
+        if state.audio_focus.as_ref() != Some(focused) {
+            debug!("Audio focus moved to {}", focused);
+            state.audio_focus = Some(focused.clone());
+        }
+    }
+
+    let step = state.last_focus_sync.elapsed().as_secs_f32() / AUDIO_FOCUS_RAMP.as_secs_f32();
+    state.last_focus_sync = std::time::Instant::now();
+
+    let mut ramping = false;
+    for (output_name, session) in state.sessions.iter_mut() {
+        // Until some output is known to hold focus, every output keeps its audio
+        let target = match &state.audio_focus {
+            Some(focused) if focused != output_name => 0.0,
+            _ => 1.0,
+        };
+        let gain = state
+            .focus_gains
+            .entry(output_name.clone())
+            .or_insert(target);
+        *gain = if *gain < target {
+            (*gain + step).min(target)
+        } else {
+            (*gain - step).max(target)
+        };
+        if session.focus_gain() != *gain {
+            session.set_focus_gain(*gain);
+        }
+        ramping |= *gain != target;
+    }
+    ramping
 }
 
 /// Render all configured surfaces
@@ -314,13 +673,29 @@ fn render_all_surfaces(state: &mut EngineState) {
     };
 
     // Collect output names to avoid borrow issues
+    let callback_fallback = state.workarounds.frame_callback_fallback;
+    let min_frame_interval = state.config.min_frame_interval(state.on_battery);
+    let sessions = &state.sessions;
     let outputs: Vec<String> = state
         .layer_surfaces
         .iter()
-        .filter(|(_, info)| info.configured && info.frame_pending)
+        .filter(|(name, info)| {
+            let needs_frames = sessions
+                .get(*name)
+                .is_none_or(WallpaperSession::needs_frames);
+            info.configured
+                && ((info.frame_pending && info.last_render.elapsed() >= min_frame_interval)
+                    // Resumed after a pause, which left no frame callback pending
+                    || (needs_frames && !info.awaiting_frame)
+                    || (needs_frames
+                        && callback_fallback
+                            .is_some_and(|interval| info.last_render.elapsed() >= interval)))
+        })
         .map(|(name, _)| name.clone())
         .collect();
 
+    let mut gpu_reset = None;
+    let mut applied = false;
     for output_name in outputs {
         // Get layer surface info
         let surface_info = match state.layer_surfaces.get_mut(&output_name) {
@@ -330,6 +705,7 @@ fn render_all_surfaces(state: &mut EngineState) {
 
         // Clear frame pending flag
         surface_info.frame_pending = false;
+        surface_info.last_render = std::time::Instant::now();
 
         // Get session for this output
         let session = match state.sessions.get_mut(&output_name) {
@@ -338,18 +714,47 @@ fn render_all_surfaces(state: &mut EngineState) {
         };
 
         // Render frame
-        match session.render_frame_to_surface(
+        let result = session.render_frame_to_surface(
             egl_context,
             &surface_info.wl_surface,
             surface_info.width as i32,
             surface_info.height as i32,
-        ) {
+        );
+        let hdr_passthrough = session.hdr_passthrough();
+        let needs_frames = session.needs_frames();
+        for (path, request, frame) in session.take_snapshots() {
+            save_snapshot(&state.events_tx, &output_name, path, request, frame);
+        }
+        if state.gpu_debug_pending && gl::DebugMessageCallback::is_loaded() {
+            state.gpu_debug_pending = false;
+            if gl_debug::enable_debug_output() {
+                info!("GPU debug output enabled");
+            } else {
+                warn!("KHR_debug is not available; only GL errors are captured");
+            }
+        }
+        state
+            .gpu_messages
+            .record(&output_name, gl_debug::take_messages());
+        gpu_reset = match &result {
+            Err(e) if egl::is_context_lost(e) => Some("a lost EGL context"),
+            _ if egl_context.is_robust() => gl_debug::reset_status(),
+            _ => None,
+        };
+        if gpu_reset.is_some() {
+            break;
+        }
+        match result {
             Ok(true) => {
-                if let Some(path) = surface_info.pending_apply_path.take() {
-                    let _ = state.events_tx.send(EngineEvent::WallpaperApplied {
-                        output: output_name.clone(),
-                        path,
-                    });
+                if let Some((path, request)) = surface_info.pending_apply.take() {
+                    let _ = state.events_tx.reply(
+                        request,
+                        EngineEvent::WallpaperApplied {
+                            output: output_name.clone(),
+                            path,
+                        },
+                    );
+                    applied = true;
                 }
             }
             Ok(false) => {}
@@ -358,12 +763,164 @@ fn render_all_surfaces(state: &mut EngineState) {
             }
         }
 
-        // Request next frame callback
+        // Request next frame callback; a drawn still image waits for the
+        // next configure or wallpaper change instead
         if let Some(qh) = state.queue_handle.as_ref() {
-            let _callback = surface_info.wl_surface.frame(qh, output_name.clone());
+            color::sync_surface(state.color.as_ref(), surface_info, hdr_passthrough, qh);
+            if needs_frames {
+                let _callback = surface_info.wl_surface.frame(qh, output_name.clone());
+            }
+            surface_info.awaiting_frame = needs_frames;
             surface_info.wl_surface.commit();
         }
     }
+
+    if applied {
+        push_status(state);
+    }
+    if let Some(reason) = gpu_reset {
+        recover_from_gpu_reset(state, reason);
+    }
+}
+
+/// Everything `GetStatus` reports
+fn engine_status(state: &EngineState) -> EngineStatus {
+    let outputs: Vec<_> = state
+        .outputs
+        .ready_outputs()
+        .map(|(_, info)| info.clone())
+        .collect();
+    let active_wallpapers = state
+        .sessions
+        .iter()
+        .map(|(name, session)| {
+            (
+                name.clone(),
+                session.wallpaper_path().map(std::path::PathBuf::from),
+            )
+        })
+        .collect();
+    EngineStatus {
+        running: state.running,
+        outputs,
+        active_wallpapers,
+        playback: state
+            .sessions
+            .iter()
+            .map(|(name, session)| {
+                (
+                    name.clone(),
+                    OutputPlayback {
+                        paused: session.state() == PlaybackState::Paused,
+                        position: session.position(),
+                        media: session.media_info(),
+                        frame_rate: session.frame_rate(),
+                    },
+                )
+            })
+            .collect(),
+        cgroup: crate::cgroup::cgroup_usage(),
+        gpu_messages: state.gpu_messages.snapshot(),
+        failed_wallpapers: state
+            .sessions
+            .iter()
+            .filter_map(|(name, session)| {
+                Some((name.clone(), session.failed_wallpaper()?.to_path_buf()))
+            })
+            .collect(),
+        on_battery: state.on_battery,
+        power_paused: state.power_paused,
+        deep_sleep: state.deep_sleep.is_asleep(),
+        render_backend: RenderBackend::OpenGL,
+    }
+}
+
+/// Tell the GUI about a change to wallpapers, outputs or power pausing
+/// without waiting for it to ask; a GUI that falls behind only gets the
+/// newest of these
+fn push_status(state: &EngineState) {
+    let _ = state
+        .events_tx
+        .send(EngineEvent::Status(engine_status(state)));
+}
+
+/// Write a snapshot off the render thread and answer its request
+fn save_snapshot(
+    events_tx: &EventSender,
+    output: &str,
+    path: std::path::PathBuf,
+    request: Option<RequestId>,
+    frame: Frame,
+) {
+    let events_tx = events_tx.clone();
+    let output = output.to_string();
+    let spawned = thread::Builder::new()
+        .name("lwe-snapshot".into())
+        .spawn(move || {
+            let event = match frame.save(&path) {
+                Ok(()) => EngineEvent::SnapshotSaved { output, path },
+                Err(e) => EngineEvent::Error(format!("Failed to save snapshot of {output}: {e:#}")),
+            };
+            let _ = events_tx.reply(request, event);
+        });
+    if let Err(e) = spawned {
+        warn!("Failed to start snapshot writer: {}", e);
+    }
+}
+
+/// Drop everything drawn through the context lost to a GPU reset and
+/// reopen the wallpapers on a new one
+fn recover_from_gpu_reset(state: &mut EngineState, reason: &str) {
+    warn!(
+        "GPU reset detected ({}); recreating the rendering context",
+        reason
+    );
+    let Some(egl_context) = state.egl_context.take() else {
+        return;
+    };
+
+    for (output_name, session) in state.sessions.iter_mut() {
+        session.reset_gpu(&egl_context);
+        state.gpu_messages.record(
+            output_name,
+            vec![GpuMessage::new(
+                GpuSeverity::Error,
+                format!("Rendering restarted after {}", reason),
+            )],
+        );
+    }
+    egl_context.destroy();
+
+    let _ = state.events_tx.send(EngineEvent::GpuReset {
+        reason: reason.to_string(),
+    });
+    recreate_egl_context(state);
+}
+
+/// Create the EGL context again after a GPU reset, retrying every
+/// `GPU_RESET_RETRY` until the driver accepts it
+fn recreate_egl_context(state: &mut EngineState) {
+    let display_ptr = state.connection.backend().display_ptr() as *mut std::ffi::c_void;
+    match EglContext::new(
+        display_ptr,
+        state.config.render_device.as_deref(),
+        state.config.gpu_debug,
+    ) {
+        Ok(egl_context) => {
+            info!("Rendering context recreated after the GPU reset");
+            state.egl_context = Some(egl_context);
+            state.egl_retry = None;
+            state.gpu_debug_pending = state.config.gpu_debug;
+            // No frame callbacks are pending for surfaces left mid-frame
+            for surface_info in state.layer_surfaces.values_mut() {
+                surface_info.frame_pending = true;
+            }
+        }
+        Err(e) => {
+            warn!("Cannot recreate the rendering context yet: {:#}", e);
+            state.egl_retry = Some(std::time::Instant::now());
+        }
+    }
 }
 
 /// Internal engine state
@@ -372,10 +929,12 @@ struct EngineState {
     outputs: OutputManager,
     /// Active wallpaper sessions per output
     sessions: HashMap<String, WallpaperSession>,
-    /// Event sender to GUI (std mpsc for cross-thread compatibility)
-    events_tx: StdSender<EngineEvent>,
+    /// Event sender to GUI (bounded std mpsc for cross-thread compatibility)
+    events_tx: EventSender,
     /// Engine configuration
     config: EngineConfig,
+    /// Compositor quirks resolved at startup
+    workarounds: Workarounds,
     /// Whether engine is running
     running: bool,
     /// Pending output info (name -> wl_output global name)
@@ -384,8 +943,40 @@ struct EngineState {
     compositor: Option<WlCompositor>,
     /// Layer shell for creating background surfaces
     layer_shell: Option<ZwlrLayerShellV1>,
+    /// Idle inhibit manager, when the compositor offers one
+    idle_inhibit_manager: Option<ZwpIdleInhibitManagerV1>,
+    /// Outputs whose wallpaper should keep the screen from idling
+    idle_inhibit_outputs: HashSet<String>,
+    /// Idle and screensaver tracking for `pause_on_idle`
+    idle: idle::IdleWatcher,
+    /// Color management, when the compositor offers it
+    color: Option<color::ColorManager>,
+    /// Foreign toplevel manager, bound to track windows outside Hyprland
+    foreign_toplevel_manager: Option<ZwlrForeignToplevelManagerV1>,
+    /// Fullscreen and maximized windows per output
+    windows: WindowTracker,
+    /// Outputs paused because a window covers them
+    fullscreen_paused: HashSet<String>,
+    /// Wallpapers sleeping while windows cover every output
+    deep_sleep: sleep::DeepSleep,
+    /// Output whose wallpaper audio plays while audio follows focus
+    audio_focus: Option<String>,
+    /// Current focus gain per output, ramped toward its target
+    focus_gains: HashMap<String, f32>,
+    /// Last focus gain update, for the ramp step
+    last_focus_sync: std::time::Instant,
+    /// A focus gain is still fading toward its target
+    focus_ramping: bool,
+    /// Wayland connection, for recreating the EGL context after a GPU reset
+    connection: Connection,
     /// EGL context for OpenGL rendering
     egl_context: Option<EglContext>,
+    /// Last failed attempt to replace a context lost to a GPU reset
+    egl_retry: Option<std::time::Instant>,
+    /// KHR_debug output still has to be enabled once GL is loaded
+    gpu_debug_pending: bool,
+    /// Recent GL errors and debug messages per output
+    gpu_messages: GpuMessageLog,
     /// Layer surfaces per output (output_name -> surface info)
     layer_surfaces: HashMap<String, LayerSurfaceInfo>,
     /// Queue handle for creating Wayland objects
@@ -394,8 +985,40 @@ struct EngineState {
     on_battery: bool,
     /// Whether playback is paused due to power saving
     power_paused: bool,
-    /// Last battery check time
-    last_battery_check: std::time::Instant,
+    /// Whether the battery profile holds wallpapers on a still frame
+    battery_still: bool,
+    /// Whether systemd readiness has been reported
+    ready_notified: bool,
+    /// Decoders shared by outputs showing the same video
+    decoders: SharedDecoders,
+    /// Last wallpaper applied to each output name, applied again when an
+    /// unplugged output comes back
+    last_wallpapers: HashMap<String, (std::path::PathBuf, WallpaperEffects)>,
+}
+
+impl EngineState {
+    /// Destroy every layer surface and wallpaper session
+    fn teardown(&mut self) {
+        for (output, info) in self.layer_surfaces.drain() {
+            debug!("Destroying layer surface for output: {}", output);
+            info.destroy();
+        }
+
+        for (output, session) in self.sessions.drain() {
+            debug!("Destroying session for output: {}", output);
+            drop(session);
+        }
+    }
+}
+
+impl Drop for EngineState {
+    fn drop(&mut self) {
+        // A panicking engine skips the shutdown path; remove its surfaces
+        // before the supervisor starts a replacement
+        if thread::panicking() {
+            self.teardown();
+        }
+    }
 }
 
 /// Layer surface state for an output
@@ -404,16 +1027,50 @@ struct LayerSurfaceInfo {
     wl_surface: WlSurface,
     /// The layer surface
     layer_surface: ZwlrLayerSurfaceV1,
-    /// Configured width
+    /// Buffer width (configured width times buffer scale)
     width: u32,
-    /// Configured height  
+    /// Buffer height (configured height times buffer scale)
     height: u32,
     /// Whether surface is configured
     configured: bool,
     /// Frame callback pending
     frame_pending: bool,
-    /// Wallpaper path waiting for first successful rendered frame
-    pending_apply_path: Option<std::path::PathBuf>,
+    /// A frame callback was requested and has not fired yet
+    awaiting_frame: bool,
+    /// Last time a frame was rendered (for the frame callback fallback and fps limit)
+    last_render: std::time::Instant,
+    /// Wallpaper path waiting for first successful rendered frame, and the
+    /// request that applied it
+    pending_apply: Option<(std::path::PathBuf, Option<RequestId>)>,
+    /// Idle inhibitor attached to this surface
+    idle_inhibitor: Option<ZwpIdleInhibitorV1>,
+    /// Color management object, created when the surface is first tagged
+    color_surface: Option<WpColorManagementSurfaceV1>,
+    /// Whether the surface is tagged as BT.2020 PQ
+    hdr_tagged: bool,
+}
+
+impl LayerSurfaceInfo {
+    /// Destroy the layer surface along with its idle inhibitor and color
+    /// management object
+    fn destroy(self) {
+        if let Some(inhibitor) = self.idle_inhibitor {
+            inhibitor.destroy();
+        }
+        if let Some(color_surface) = self.color_surface {
+            color_surface.destroy();
+        }
+        self.layer_surface.destroy();
+    }
+}
+
+/// Per-wallpaper render effects carried by ApplyWallpaper
+#[derive(Debug, Clone)]
+struct WallpaperEffects {
+    /// Custom GLSL post-process shader
+    shader: Option<std::path::PathBuf>,
+    /// Color grading LUT (None = global LUT)
+    lut: Option<std::path::PathBuf>,
 }
 
 /// Pending output information during enumeration
@@ -439,11 +1096,69 @@ struct PendingOutput {
     done: bool,
 }
 
-/// Handle incoming command from GUI
-fn handle_command(cmd: EngineCommand, state: &mut EngineState) {
+/// Handle incoming command from GUI; everything logged while handling it
+/// carries its request id
+fn handle_request(request: EngineRequest, state: &mut EngineState) {
+    let _span = tracing::debug_span!(
+        "request",
+        id = request.id.map(|id| id.0),
+        queued = ?request.sent_at.elapsed()
+    )
+    .entered();
+    handle_command(request.command, request.id, state);
+    sync_span_canvases(state);
+}
+
+/// Place every output that spans its wallpaper on the bounding box of all
+/// outputs spanning the same one
+fn sync_span_canvases(state: &mut EngineState) {
+    let mut rects: HashMap<String, Vec<SpanRect>> = HashMap::new();
+    for (output_name, session) in &state.sessions {
+        if session.layout() != LayoutMode::Span {
+            continue;
+        }
+        if let (Some(path), Some(output)) =
+            (session.wallpaper_path(), state.outputs.get(output_name))
+        {
+            rects
+                .entry(path.to_string())
+                .or_default()
+                .push(output.info.logical_rect());
+        }
+    }
+
+    for (output_name, session) in state.sessions.iter_mut() {
+        let span = session
+            .wallpaper_path()
+            .filter(|_| session.layout() == LayoutMode::Span)
+            .and_then(|path| span_canvas(rects.get(path)?.iter().copied()))
+            .zip(state.outputs.get(output_name))
+            .map(|(canvas, output)| (canvas, output.info.logical_rect()));
+        session.set_span(span);
+    }
+}
+
+fn handle_command(cmd: EngineCommand, request: Option<RequestId>, state: &mut EngineState) {
     match cmd {
-        EngineCommand::ApplyWallpaper { path, output } => {
-            debug!("ApplyWallpaper: {:?} to {:?}", path, output);
+        EngineCommand::ApplyWallpaper {
+            path,
+            output,
+            shader,
+            lut,
+        } => {
+            debug!(
+                "ApplyWallpaper: {:?} to {:?} (shader: {:?}, lut: {:?})",
+                path, output, shader, lut
+            );
+
+            // Probe before touching any session so a bad source keeps the old wallpaper
+            if let Err(error) = probe_source(&path) {
+                warn!("Rejected wallpaper source: {}", error);
+                let _ = state
+                    .events_tx
+                    .reply(request, EngineEvent::SourceRejected { path, error });
+                return;
+            }
 
             let outputs_to_apply: Vec<String> = match output {
                 Some(name) => vec![name],
@@ -455,19 +1170,38 @@ fn handle_command(cmd: EngineCommand, state: &mut EngineState) {
                 Some(qh) => qh,
                 None => {
                     error!("Queue handle not available");
-                    let _ = state
-                        .events_tx
-                        .send(EngineEvent::Error("Queue handle not available".to_string()));
+                    let _ = state.events_tx.reply(
+                        request,
+                        EngineEvent::Error("Queue handle not available".to_string()),
+                    );
                     return;
                 }
             };
 
             for output_name in outputs_to_apply {
-                match apply_wallpaper_to_output(state, &path, &output_name, &qh) {
-                    Ok(()) => {}
+                state.gpu_messages.clear(&output_name);
+                let effects = WallpaperEffects {
+                    shader: shader.clone(),
+                    lut: lut.clone(),
+                };
+                match apply_wallpaper_to_output(
+                    state,
+                    &path,
+                    effects.clone(),
+                    request,
+                    &output_name,
+                    &qh,
+                ) {
+                    Ok(()) => {
+                        state
+                            .last_wallpapers
+                            .insert(output_name, (path.clone(), effects));
+                    }
                     Err(e) => {
                         error!("Failed to apply wallpaper to {}: {}", output_name, e);
-                        let _ = state.events_tx.send(EngineEvent::Error(e.to_string()));
+                        let _ = state
+                            .events_tx
+                            .reply(request, EngineEvent::Error(e.to_string()));
                     }
                 }
             }
@@ -478,20 +1212,33 @@ fn handle_command(cmd: EngineCommand, state: &mut EngineState) {
 
             let outputs_to_clear: Vec<String> = match output {
                 Some(name) => vec![name],
-                None => state.sessions.keys().cloned().collect(),
+                None => {
+                    state.last_wallpapers.clear();
+                    state.sessions.keys().cloned().collect()
+                }
             };
 
             for output_name in outputs_to_clear {
+                state.last_wallpapers.remove(&output_name);
                 // Remove layer surface first
                 if let Some(info) = state.layer_surfaces.remove(&output_name) {
-                    info.layer_surface.destroy();
+                    info.destroy();
                 }
+                state.fullscreen_paused.remove(&output_name);
+                state.idle.release(Some(&output_name));
+                state.deep_sleep.release(Some(&output_name));
+                state.focus_gains.remove(&output_name);
+                state.gpu_messages.clear(&output_name);
                 // Then remove session
                 if let Some(session) = state.sessions.remove(&output_name) {
                     drop(session);
-                    let _ = state.events_tx.send(EngineEvent::WallpaperCleared {
-                        output: output_name,
-                    });
+                    let _ = state.events_tx.reply(
+                        request,
+                        EngineEvent::WallpaperCleared {
+                            output: output_name,
+                        },
+                    );
+                    push_status(state);
                 }
             }
         }
@@ -501,10 +1248,65 @@ fn handle_command(cmd: EngineCommand, state: &mut EngineState) {
             if let Some(session) = state.sessions.get_mut(&output) {
                 session.set_volume(volume);
             }
+            // Remember it for sessions created on this output later
+            state.config.outputs.entry(output).or_default().volume = Some(volume.clamp(0.0, 1.0));
+        }
+
+        EngineCommand::SetMute { output, muted } => {
+            debug!("SetMute: {} = {}", output, muted);
+            if let Some(session) = state.sessions.get_mut(&output) {
+                session.set_muted(muted);
+            }
+            state.config.outputs.entry(output).or_default().mute = Some(muted);
+        }
+
+        EngineCommand::SetAudioDevice { output, device } => {
+            debug!("SetAudioDevice: {} = {:?}", output, device);
+            if let Some(session) = state.sessions.get_mut(&output) {
+                session.set_audio_device(device.clone());
+            }
+            state.config.outputs.entry(output).or_default().audio_device = device;
+        }
+
+        EngineCommand::SetColor { output, color } => {
+            debug!("SetColor: {} = {:?}", output, color);
+            if let Some(session) = state.sessions.get_mut(&output) {
+                session.set_color(color);
+            }
+            state.config.outputs.entry(output).or_default().color = Some(color);
+        }
+
+        EngineCommand::PreloadWallpaper { output, path } => {
+            debug!("PreloadWallpaper: {} = {}", output, path.display());
+            if let Some(session) = state.sessions.get_mut(&output) {
+                session.preload_wallpaper(&path);
+            }
+        }
+
+        EngineCommand::SetIdleInhibit { output, inhibit } => {
+            debug!("SetIdleInhibit: {} = {}", output, inhibit);
+            if inhibit {
+                state.idle_inhibit_outputs.insert(output.clone());
+            } else {
+                state.idle_inhibit_outputs.remove(&output);
+            }
+            if let Some(qh) = state.queue_handle.clone() {
+                sync_idle_inhibitor(state, &output, &qh);
+            }
         }
 
         EngineCommand::Pause { output } => {
             debug!("Pause: {:?}", output);
+            // A user pause outlasts the window that covered the output,
+            // the idle session and deep sleep
+            match &output {
+                Some(name) => {
+                    state.fullscreen_paused.remove(name);
+                }
+                None => state.fullscreen_paused.clear(),
+            }
+            state.idle.release(output.as_deref());
+            state.deep_sleep.release(output.as_deref());
             let sessions: Vec<&mut WallpaperSession> = match &output {
                 Some(name) => state.sessions.get_mut(name).into_iter().collect(),
                 None => state.sessions.values_mut().collect(),
@@ -525,6 +1327,36 @@ fn handle_command(cmd: EngineCommand, state: &mut EngineState) {
             }
         }
 
+        EngineCommand::Transport { output, transport } => {
+            debug!("Transport: {:?} {:?}", output, transport);
+            // Outputs spanning one video share its decoder, which is driven once
+            let spanned = output
+                .as_ref()
+                .and_then(|name| state.sessions.get(name)?.span_decoder());
+            let mut driven = HashSet::new();
+            for (name, session) in state.sessions.iter_mut() {
+                let decoder = session.span_decoder();
+                let targeted = match &output {
+                    Some(target) => name == target || (decoder.is_some() && decoder == spanned),
+                    None => true,
+                };
+                if !targeted {
+                    continue;
+                }
+                match decoder {
+                    Some(id) if !driven.insert(id) => {
+                        session.follow_transport(transport);
+                    }
+                    _ => session.transport(transport),
+                }
+            }
+        }
+
+        EngineCommand::SetScreenSaverActive { active } => {
+            debug!("SetScreenSaverActive: {}", active);
+            idle::set_screensaver_active(state, active);
+        }
+
         EngineCommand::GetOutputs => {
             debug!("GetOutputs requested");
             let outputs: Vec<_> = state
@@ -532,45 +1364,218 @@ fn handle_command(cmd: EngineCommand, state: &mut EngineState) {
                 .ready_outputs()
                 .map(|(_, info)| info.clone())
                 .collect();
-            let _ = state.events_tx.send(EngineEvent::OutputsList(outputs));
+            let _ = state
+                .events_tx
+                .reply(request, EngineEvent::OutputsList(outputs));
         }
 
         EngineCommand::GetStatus => {
             debug!("GetStatus requested");
-            let outputs: Vec<_> = state
-                .outputs
-                .ready_outputs()
-                .map(|(_, info)| info.clone())
-                .collect();
-            let active_wallpapers = state
+            let _ = state
+                .events_tx
+                .reply(request, EngineEvent::Status(engine_status(state)));
+        }
+
+        EngineCommand::GetStats => {
+            let stats = state
                 .sessions
                 .iter()
                 .map(|(name, session)| {
                     (
                         name.clone(),
-                        session.wallpaper_path().map(std::path::PathBuf::from),
+                        OutputStats {
+                            render_time: session.render_time(),
+                            frame_rate: session.frame_rate(),
+                            dropped_frames: session.dropped_frames(),
+                            hwdec: session.media_info().hwdec,
+                        },
                     )
                 })
                 .collect();
-            let status = EngineStatus {
-                running: state.running,
-                outputs,
-                active_wallpapers,
+            let _ = state.events_tx.reply(request, EngineEvent::Stats(stats));
+        }
+
+        EngineCommand::GetPalette { output } => {
+            debug!("GetPalette requested for {:?}", output);
+            let palettes = state
+                .sessions
+                .iter()
+                .filter(|(name, _)| output.as_ref().is_none_or(|output| output == *name))
+                .filter_map(|(name, session)| {
+                    session
+                        .palette(PALETTE_SIZE)
+                        .map(|palette| (name.clone(), palette))
+                })
+                .collect();
+            let _ = state
+                .events_tx
+                .reply(request, EngineEvent::Palettes(palettes));
+        }
+
+        EngineCommand::Snapshot { output, path } => {
+            debug!("Snapshot of {} to {}", output, path.display());
+            match state.sessions.get_mut(&output) {
+                Some(session) => {
+                    session.request_snapshot(path, request);
+                    if let Some(info) = state.layer_surfaces.get_mut(&output) {
+                        info.frame_pending = true;
+                    }
+                }
+                None => {
+                    let _ = state.events_tx.reply(
+                        request,
+                        EngineEvent::Error(format!("No wallpaper on {output}")),
+                    );
+                }
+            }
+        }
+
+        EngineCommand::SetMpvProperty {
+            output,
+            property,
+            value,
+        } => {
+            debug!("Set mpv {}={} on {}", property, value, output);
+            let event = match state.sessions.get_mut(&output) {
+                Some(session) => match session.set_mpv_property(&property, &value) {
+                    Ok(()) => {
+                        if let Some(info) = state.layer_surfaces.get_mut(&output) {
+                            info.frame_pending = true;
+                        }
+                        EngineEvent::MpvPropertySet { output, property }
+                    }
+                    Err(e) => EngineEvent::Error(format!("{e}")),
+                },
+                None => EngineEvent::Error(format!("No wallpaper on {output}")),
             };
-            let _ = state.events_tx.send(EngineEvent::Status(status));
+            let _ = state.events_tx.reply(request, event);
+        }
+
+        EngineCommand::UpdateConfig(config) => {
+            debug!("UpdateConfig: {:?}", config);
+            reconfigure::apply(state, *config);
         }
 
         EngineCommand::Shutdown => {
             debug!("Shutdown requested");
-            // Shutdown is handled by the shutdown flag, not here
+            // The shutdown flag is already set; the command only wakes the loop
         }
     }
 }
 
+fn shell_layer(layer: SurfaceLayer) -> zwlr_layer_shell_v1::Layer {
+    match layer {
+        SurfaceLayer::Background => zwlr_layer_shell_v1::Layer::Background,
+        SurfaceLayer::Bottom => zwlr_layer_shell_v1::Layer::Bottom,
+    }
+}
+
+/// Move an existing surface to the layer, exclusive zone and margins its
+/// output is configured with; the compositor answers with a new configure.
+/// Without `set_layer` the surface is created again on the new layer.
+fn place_surface(state: &mut EngineState, output_name: &str, previous_layer: SurfaceLayer) {
+    let Some(info) = state.layer_surfaces.get(output_name) else {
+        return;
+    };
+    let (layer, exclusive_zone, margins) = state
+        .config
+        .surface_placement(output_name, state.workarounds.exclusive_zone);
+    if layer != previous_layer {
+        // set_layer arrived in version 2 of the protocol
+        if info.layer_surface.version() >= 2 {
+            info.layer_surface.set_layer(shell_layer(layer));
+        } else {
+            info!(
+                "Recreating the surface of {} on the {} layer",
+                output_name, layer
+            );
+            park_wallpaper(state, output_name);
+            if let Some(qh) = state.queue_handle.clone() {
+                restore_wallpaper(state, output_name, &qh);
+            }
+            return;
+        }
+    }
+    info.layer_surface.set_exclusive_zone(exclusive_zone);
+    info.layer_surface
+        .set_margin(margins.top, margins.right, margins.bottom, margins.left);
+    info.wl_surface.commit();
+}
+
+/// Drop the surface and session of an output that went away, keeping its
+/// entry in `last_wallpapers`
+fn park_wallpaper(state: &mut EngineState, output_name: &str) {
+    if let Some(info) = state.layer_surfaces.remove(output_name) {
+        info.destroy();
+    }
+    state.fullscreen_paused.remove(output_name);
+    state.idle.release(Some(output_name));
+    state.deep_sleep.release(Some(output_name));
+    state.focus_gains.remove(output_name);
+    if let Some(mut session) = state.sessions.remove(output_name) {
+        if let Some(egl_context) = state.egl_context.as_ref() {
+            session.cleanup_egl(egl_context);
+        }
+        info!("Parked wallpaper of {} until it comes back", output_name);
+    }
+}
+
+/// Apply the last wallpaper again on an output that came back
+fn restore_wallpaper(state: &mut EngineState, output_name: &str, qh: &QueueHandle<EngineState>) {
+    if state.sessions.contains_key(output_name) {
+        // A mode or scale change on an output that never left
+        return;
+    }
+    let Some((path, effects)) = state.last_wallpapers.get(output_name).cloned() else {
+        return;
+    };
+    info!(
+        "Output {} is back, applying {} again",
+        output_name,
+        path.display()
+    );
+    if let Err(e) = apply_wallpaper_to_output(state, &path, effects, None, output_name, qh) {
+        warn!(
+            "Failed to apply the wallpaper again on {}: {}",
+            output_name, e
+        );
+    }
+}
+
+/// Attach or release the idle inhibitor on an output's surface to match
+/// `idle_inhibit_outputs`
+fn sync_idle_inhibitor(state: &mut EngineState, output_name: &str, qh: &QueueHandle<EngineState>) {
+    let wanted = state.idle_inhibit_outputs.contains(output_name);
+    let Some(info) = state.layer_surfaces.get_mut(output_name) else {
+        return;
+    };
+
+    match (wanted, info.idle_inhibitor.take()) {
+        (true, None) => match state.idle_inhibit_manager.as_ref() {
+            Some(manager) => {
+                info.idle_inhibitor =
+                    Some(manager.create_inhibitor(&info.wl_surface, qh, output_name.to_string()));
+                info!("Inhibiting idle while {} shows its wallpaper", output_name);
+            }
+            None => warn!(
+                "Compositor has no idle inhibit support; {} may still idle",
+                output_name
+            ),
+        },
+        (false, Some(inhibitor)) => {
+            inhibitor.destroy();
+            info!("Released idle inhibitor for {}", output_name);
+        }
+        (_, inhibitor) => info.idle_inhibitor = inhibitor,
+    }
+}
+
 /// Apply wallpaper to a specific output
 fn apply_wallpaper_to_output(
     state: &mut EngineState,
     path: &std::path::Path,
+    effects: WallpaperEffects,
+    request: Option<RequestId>,
     output_name: &str,
     qh: &QueueHandle<EngineState>,
 ) -> Result<()> {
@@ -587,7 +1592,11 @@ fn apply_wallpaper_to_output(
                     output_name
                 );
                 session.load_new_wallpaper(path)?;
-                surface_info.pending_apply_path = Some(path.to_path_buf());
+                session.set_shader(effects.shader);
+                session.set_lut(effects.lut);
+                surface_info.pending_apply = Some((path.to_path_buf(), request));
+                // A surface showing a still image has no frame callback pending
+                surface_info.frame_pending = true;
                 return Ok(());
             }
         }
@@ -618,7 +1627,7 @@ fn apply_wallpaper_to_output(
 
     // Remove existing layer surface
     if let Some(old_info) = state.layer_surfaces.remove(output_name) {
-        old_info.layer_surface.destroy();
+        old_info.destroy();
         // wl_surface is automatically destroyed when dropped
     }
 
@@ -630,21 +1639,24 @@ fn apply_wallpaper_to_output(
     // Create wl_surface
     let wl_surface = compositor.create_surface(qh, output_name.to_string());
 
-    // Create layer surface on background layer
+    // Create layer surface on the output's layer, background by default
+    let (layer, exclusive_zone, margins) = state
+        .config
+        .surface_placement(output_name, state.workarounds.exclusive_zone);
     let layer_surface = layer_shell.get_layer_surface(
         &wl_surface,
         Some(&wl_output),
-        zwlr_layer_shell_v1::Layer::Background,
+        shell_layer(layer),
         CString::new("wayvid").unwrap().into_string().unwrap(),
         qh,
         output_name.to_string(),
     );
 
     // Configure layer surface
-    // Anchor to all edges for full coverage
+    // Anchor to all edges for full coverage, less the margins
     layer_surface.set_anchor(Anchor::Top | Anchor::Bottom | Anchor::Left | Anchor::Right);
-    // Use -1 to ignore other layer surfaces' exclusive zones (e.g., top bars)
-    layer_surface.set_exclusive_zone(-1);
+    layer_surface.set_exclusive_zone(exclusive_zone);
+    layer_surface.set_margin(margins.top, margins.right, margins.bottom, margins.left);
     layer_surface.set_keyboard_interactivity(zwlr_layer_surface_v1::KeyboardInteractivity::None);
     // Set size to 0,0 to let compositor determine full output size
     layer_surface.set_size(0, 0);
@@ -662,13 +1674,38 @@ fn apply_wallpaper_to_output(
             height: output_info.height as u32,
             configured: false,
             frame_pending: false,
-            pending_apply_path: Some(path.to_path_buf()),
+            awaiting_frame: false,
+            last_render: std::time::Instant::now(),
+            pending_apply: Some((path.to_path_buf(), request)),
+            idle_inhibitor: None,
+            color_surface: None,
+            hdr_tagged: false,
         },
     );
-
-    // Create wallpaper session
-    let session =
-        WallpaperSession::new(path.to_path_buf(), output_info, state.config.video.clone())?;
+    sync_idle_inhibitor(state, output_name, qh);
+
+    // Create wallpaper session; HDR output needs a surface that can be tagged
+    let mut output_info = output_info;
+    output_info.hdr_capabilities =
+        color::surface_capabilities(state.color.as_ref(), &output_info.hdr_capabilities);
+    let mut session = WallpaperSession::new(
+        path.to_path_buf(),
+        output_info,
+        state.config.video_for_power(output_name, state.on_battery),
+    )?;
+    session.set_shader(effects.shader);
+    session.set_lut(effects.lut);
+    session.set_shared_decoders(state.decoders.clone());
+    if let Some(overrides) = state.config.outputs.get(output_name) {
+        session.set_fallbacks(
+            overrides.fallbacks.clone(),
+            overrides.signal_lost.clone(),
+            overrides.safe_mode.clone(),
+        );
+    }
+    if state.battery_still {
+        session.start_paused();
+    }
     state.sessions.insert(output_name.to_string(), session);
 
     info!("Wallpaper session created for {}", output_name);
@@ -712,6 +1749,52 @@ impl Dispatch<WlRegistry, ()> for EngineState {
                         state.layer_shell = Some(layer_shell);
                         info!("Bound zwlr_layer_shell_v1");
                     }
+                    "zwp_idle_inhibit_manager_v1" => {
+                        debug!(
+                            "Found zwp_idle_inhibit_manager_v1 global: name={}, version={}",
+                            name, version
+                        );
+                        let manager: ZwpIdleInhibitManagerV1 = registry.bind(name, 1, qh, ());
+                        state.idle_inhibit_manager = Some(manager);
+                        info!("Bound zwp_idle_inhibit_manager_v1");
+                    }
+                    "zwlr_foreign_toplevel_manager_v1"
+                        if state.config.tracks_windows() && !state.windows.uses_hyprland() =>
+                    {
+                        debug!(
+                            "Found zwlr_foreign_toplevel_manager_v1 global: name={}, version={}",
+                            name, version
+                        );
+                        let manager: ZwlrForeignToplevelManagerV1 =
+                            registry.bind(name, version.min(3), qh, ());
+                        state.foreign_toplevel_manager = Some(manager);
+                        info!("Bound zwlr_foreign_toplevel_manager_v1");
+                    }
+                    "ext_idle_notifier_v1" if state.config.pause_on_idle.is_some() => {
+                        debug!(
+                            "Found ext_idle_notifier_v1 global: name={}, version={}",
+                            name, version
+                        );
+                        let notifier: ExtIdleNotifierV1 = registry.bind(name, 1, qh, ());
+                        state.idle.set_notifier(notifier);
+                        info!("Bound ext_idle_notifier_v1");
+                        idle::watch(state, qh);
+                    }
+                    "wl_seat" if state.config.pause_on_idle.is_some() => {
+                        debug!("Found wl_seat global: name={}, version={}", name, version);
+                        let seat: WlSeat = registry.bind(name, 1, qh, ());
+                        state.idle.set_seat(seat);
+                        idle::watch(state, qh);
+                    }
+                    "wp_color_manager_v1" => {
+                        debug!(
+                            "Found wp_color_manager_v1 global: name={}, version={}",
+                            name, version
+                        );
+                        let manager: WpColorManagerV1 = registry.bind(name, 1, qh, ());
+                        state.color = Some(color::ColorManager::new(manager));
+                        info!("Bound wp_color_manager_v1");
+                    }
                     "wl_output" => {
                         debug!("Found wl_output global: name={}, version={}", name, version);
                         // Bind the output
@@ -740,10 +1823,14 @@ impl Dispatch<WlRegistry, ()> for EngineState {
                 if let Some(pending) = state.pending_outputs.remove(&name) {
                     if let Some(output_name) = &pending.output_name {
                         info!("Output removed: {}", output_name);
+                        park_wallpaper(state, output_name);
                         state.outputs.remove_output(output_name);
+                        color::forget_output(state, output_name);
+                        sync_span_canvases(state);
                         let _ = state
                             .events_tx
                             .send(EngineEvent::OutputRemoved(output_name.clone()));
+                        push_status(state);
                     }
                 }
             }
@@ -760,7 +1847,7 @@ impl Dispatch<WlOutput, u32> for EngineState {
         event: wl_output::Event,
         global_name: &u32,
         _conn: &Connection,
-        _qh: &QueueHandle<Self>,
+        qh: &QueueHandle<Self>,
     ) {
         let Some(pending) = state.pending_outputs.get_mut(global_name) else {
             return;
@@ -821,18 +1908,16 @@ impl Dispatch<WlOutput, u32> for EngineState {
                     .update_geometry(&output_name, pending.x, pending.y);
                 state.outputs.update_scale(&output_name, pending.scale);
                 state.outputs.mark_ready(&output_name);
+                color::track_output(state, &output_name, qh);
 
                 // Send event to GUI
-                let info = lwe_core::OutputInfo {
-                    name: output_name.clone(),
-                    width: pending.width,
-                    height: pending.height,
-                    scale: pending.scale as f64,
-                    position: (pending.x, pending.y),
-                    active: true,
-                    hdr_capabilities: lwe_core::OutputHdrCapabilities::default(),
-                };
-                let _ = state.events_tx.send(EngineEvent::OutputAdded(info));
+                if let Some(output) = state.outputs.get(&output_name) {
+                    let _ = state
+                        .events_tx
+                        .send(EngineEvent::OutputAdded(output.info.clone()));
+                }
+                push_status(state);
+                restore_wallpaper(state, &output_name, qh);
             }
             _ => {}
         }
@@ -881,6 +1966,34 @@ impl Dispatch<ZwlrLayerShellV1, ()> for EngineState {
     }
 }
 
+// Dispatch for zwp_idle_inhibit_manager_v1 (no events)
+impl Dispatch<ZwpIdleInhibitManagerV1, ()> for EngineState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpIdleInhibitManagerV1,
+        _event: zwp_idle_inhibit_manager_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // zwp_idle_inhibit_manager_v1 has no events
+    }
+}
+
+// Dispatch for zwp_idle_inhibitor_v1 (no events)
+impl Dispatch<ZwpIdleInhibitorV1, String> for EngineState {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwpIdleInhibitorV1,
+        _event: zwp_idle_inhibitor_v1::Event,
+        _data: &String,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        // zwp_idle_inhibitor_v1 has no events
+    }
+}
+
 // Dispatch for zwlr_layer_surface_v1
 impl Dispatch<ZwlrLayerSurfaceV1, String> for EngineState {
     fn event(
@@ -902,10 +2015,33 @@ impl Dispatch<ZwlrLayerSurfaceV1, String> for EngineState {
                     output_name, width, height, serial
                 );
 
+                let output_info = state.outputs.get(output_name).map(|output| &output.info);
+                let scale = output_info
+                    .map(|info| info.scale.ceil().max(1.0) as u32)
+                    .unwrap_or(1);
+                let (width, height) = match output_info {
+                    Some(info)
+                        if (width == 0 || height == 0)
+                            && state.workarounds.output_size_fallback =>
+                    {
+                        debug!("Zero-size configure for {}, using output mode", output_name);
+                        (info.width as u32 / scale, info.height as u32 / scale)
+                    }
+                    _ => (width, height),
+                };
+                let buffer_scale = if state.workarounds.buffer_scale {
+                    scale
+                } else {
+                    1
+                };
+
                 // Update layer surface info
                 if let Some(info) = state.layer_surfaces.get_mut(output_name) {
-                    info.width = width;
-                    info.height = height;
+                    if buffer_scale > 1 {
+                        info.wl_surface.set_buffer_scale(buffer_scale as i32);
+                    }
+                    info.width = width * buffer_scale;
+                    info.height = height * buffer_scale;
                     info.configured = true;
                     info.frame_pending = true;
                 }
@@ -922,10 +2058,19 @@ impl Dispatch<ZwlrLayerSurfaceV1, String> for EngineState {
                     "Layer surface configured for {}: {}x{}",
                     output_name, width, height
                 );
+
+                // Report readiness once the first wallpaper surface is usable
+                if !state.ready_notified {
+                    state.ready_notified = true;
+                    if systemd::notify_ready() {
+                        info!("Notified systemd: ready");
+                    }
+                }
             }
             zwlr_layer_surface_v1::Event::Closed => {
+                // Some docks close the surface before the output goes away
                 info!("Layer surface closed for {}", output_name);
-                state.layer_surfaces.remove(output_name);
+                park_wallpaper(state, output_name);
             }
             _ => {}
         }
@@ -946,6 +2091,7 @@ impl Dispatch<WlCallback, String> for EngineState {
             // Frame callback triggered - mark surface ready for rendering
             if let Some(info) = state.layer_surfaces.get_mut(output_name) {
                 info.frame_pending = true;
+                info.awaiting_frame = false;
             }
         }
     }
@@ -0,0 +1,136 @@
+//! Settings changed while the engine runs
+//!
+//! The app sends its whole configuration again when the settings file
+//! changes. Each output's wallpaper gets its video settings worked out again,
+//! the way a new wallpaper would, and its surface moves to a new layer or
+//! margins in place, or is created again on the new layer where the
+//! compositor cannot move it. Pausing that was switched off lets go of the wallpapers
+//! it paused. Settings only read while starting keep their old value until
+//! the engine starts again.
+
+use std::collections::HashMap;
+
+use tracing::{info, warn};
+
+use super::command::EngineConfig;
+use super::{
+    check_power, idle, lift_power_pause, place_surface, sleep, sync_fullscreen_pause, EngineState,
+};
+
+/// Switch the running engine to `config`
+pub(crate) fn apply(state: &mut EngineState, mut config: EngineConfig) {
+    let waiting = state.config.startup_changes(&config);
+    if !waiting.is_empty() {
+        warn!(
+            "Changed settings wait for the engine to restart: {}",
+            waiting.join(", ")
+        );
+    }
+    keep_startup_settings(&state.config, &mut config);
+
+    let previous = std::mem::replace(&mut state.config, config);
+    info!("Settings changed, updating wallpapers");
+
+    // Nothing covers the outputs for pausing that was switched off
+    if previous.pause_on_fullscreen && !state.config.pause_on_fullscreen {
+        sync_fullscreen_pause(state, &HashMap::new());
+    }
+    if previous.deep_sleep && !state.config.deep_sleep {
+        sleep::sync(state, &HashMap::new());
+    }
+    if previous.audio_follows_focus && !state.config.audio_follows_focus {
+        state.audio_focus = None;
+        state.focus_gains.clear();
+        state.focus_ramping = false;
+        for session in state.sessions.values_mut() {
+            session.set_focus_gain(1.0);
+        }
+    }
+    if previous.pause_on_idle != state.config.pause_on_idle {
+        if let Some(qh) = state.queue_handle.clone() {
+            idle::rewatch(state, &qh);
+        }
+    }
+    if state.power_paused && !state.config.pause_on_battery {
+        info!("Battery pause switched off, resuming playback");
+        lift_power_pause(state);
+    }
+    // Pauses for battery power, or switches to the battery profile
+    check_power(state);
+
+    // Surfaces whose layer, exclusive zone or margins changed move in place
+    let zone = state.workarounds.exclusive_zone;
+    let moved: Vec<_> = state
+        .layer_surfaces
+        .keys()
+        .map(|output_name| (output_name, previous.surface_placement(output_name, zone)))
+        .filter(|(output_name, placement)| {
+            state.config.surface_placement(output_name, zone) != *placement
+        })
+        .map(|(output_name, (layer, _, _))| (output_name.clone(), layer))
+        .collect();
+    for (output_name, previous_layer) in moved {
+        place_surface(state, &output_name, previous_layer);
+    }
+
+    let egl_context = state.egl_context.as_ref();
+    for (output_name, session) in state.sessions.iter_mut() {
+        let video = state.config.video_for_power(output_name, state.on_battery);
+        let redraw = session.reconfigure(&video, egl_context);
+        let overrides = state.config.outputs.get(output_name);
+        session.set_fallbacks(
+            overrides
+                .map(|overrides| overrides.fallbacks.clone())
+                .unwrap_or_default(),
+            overrides.and_then(|overrides| overrides.signal_lost.clone()),
+            overrides.and_then(|overrides| overrides.safe_mode.clone()),
+        );
+        if let Some(surface_info) = state.layer_surfaces.get_mut(output_name).filter(|_| redraw) {
+            surface_info.frame_pending = true;
+        }
+    }
+}
+
+/// Carry over what `current` started with into `next`, so a later change can
+/// still tell that it waits for a restart
+fn keep_startup_settings(current: &EngineConfig, next: &mut EngineConfig) {
+    next.startup_timeout = current.startup_timeout;
+    next.workarounds.clone_from(&current.workarounds);
+    next.scheduling.clone_from(&current.scheduling);
+    next.render_device.clone_from(&current.render_device);
+    next.gpu_debug = current.gpu_debug;
+    if next.pause_on_idle.is_some() != current.pause_on_idle.is_some() {
+        next.pause_on_idle = current.pause_on_idle;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_startup_settings_keep_their_running_value() {
+        let current = EngineConfig {
+            pause_on_idle: Some(Duration::from_secs(300)),
+            ..EngineConfig::default()
+        };
+        let mut next = EngineConfig {
+            pause_on_idle: Some(Duration::from_secs(60)),
+            render_device: Some(PathBuf::from("/dev/dri/renderD129")),
+            pause_on_battery: true,
+            ..EngineConfig::default()
+        };
+        keep_startup_settings(&current, &mut next);
+
+        assert_eq!(next.pause_on_idle, Some(Duration::from_secs(60)));
+        assert_eq!(next.render_device, None);
+        assert!(next.pause_on_battery);
+
+        next.pause_on_idle = None;
+        keep_startup_settings(&current, &mut next);
+        assert_eq!(next.pause_on_idle, Some(Duration::from_secs(300)));
+    }
+}
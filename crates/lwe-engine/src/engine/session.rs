@@ -1,18 +1,50 @@
 //! Wallpaper playback session for a single output
 //!
 //! A session manages the MPV player for rendering video/image wallpaper
-//! on a specific Wayland output via the shared EGL context.
+//! on a specific Wayland output via the shared EGL context. Animated GIF,
+//! APNG and WebP wallpapers use the built-in animated image player instead,
+//! still images without a shader or LUT are drawn once without mpv, and
+//! muted videos shown on several outputs share one decoder. Spanned videos
+//! always share theirs, so every output draws its crop of the same frame.
+//! A video that fails while it plays is replaced by the output's fallbacks
+//! until it can be played again, or, once it has failed a few times in a
+//! row, by the output's safe-mode image or color.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::Result;
 use tracing::{debug, info, warn};
 use wayland_client::protocol::wl_surface::WlSurface;
 
-use lwe_core::OutputInfo;
+use lwe_core::lut::{compile_cube_to_shader, default_lut_cache_dir};
+use lwe_core::{
+    calculate_layout, calculate_span_layout, is_animated_image, is_still_image, ColorAdjust,
+    HdrOutput, HwdecMode, LayoutMode, LayoutTransform, OutputHdrCapabilities, OutputInfo, Palette,
+    SpanRect, PALETTE_SIZE,
+};
 
+use crate::animated_image::{AnimatedImageBlit, AnimatedImagePlayer};
 use crate::egl::{EglContext, EglWindow};
-use crate::mpv::{MpvPlayer, VideoConfig};
+use crate::frame_sample::FrameSampler;
+use crate::frame_timing::{FrameRateSample, FrameTiming};
+use crate::letterbox::LetterboxPass;
+use crate::mpv::{is_live_source, MediaInfo, MpvPlayer, Transport, VideoConfig};
+use crate::shared_decode::{SharedDecode, SharedDecoders};
+use crate::snapshot::Frame;
+use crate::still_image::StillImage;
+use crate::transition::TransitionPass;
+
+use super::command::{EngineEvent, RequestId};
+use super::fallback::{fallback_chain, source_available, Fallback, SourceHealth, SAFE_MODE_AFTER};
+
+/// How often the custom shader and LUT files are checked for changes
+const SHADER_RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+/// How often a playing wallpaper's file is checked for still being there
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+/// mpv render errors in a row after which the wallpaper counts as failed;
+/// only the first is logged
+const RENDER_ERRORS_BEFORE_FAILURE: u32 = 10;
 
 /// Playback state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -35,16 +67,90 @@ pub struct WallpaperSession {
     video_config: VideoConfig,
     /// MPV player instance
     player: Option<MpvPlayer>,
+    /// Animated image player, used instead of MPV for GIF/APNG/WebP
+    animated: Option<AnimatedImagePlayer>,
+    /// Still image, drawn once instead of playing it through MPV
+    still: Option<StillImage>,
+    /// Texture the animated or still image is drawn from
+    animated_blit: AnimatedImageBlit,
+    /// Output size the animated or still image was last drawn at
+    animated_drawn_size: Option<(i32, i32)>,
     /// EGL window for this surface
     egl_window: Option<EglWindow>,
     /// Current playback state
     state: PlaybackState,
     /// Current volume (0.0 - 1.0)
     volume: f32,
+    /// Factor applied on top of the volume while audio follows focus
+    focus_gain: f32,
     /// Whether resources are initialized
     initialized: bool,
     /// Whether OpenGL functions are loaded
     gl_loaded: bool,
+    /// Custom GLSL post-process shader for this wallpaper
+    shader_path: Option<PathBuf>,
+    /// Color grading LUT for this wallpaper (overrides the global LUT)
+    lut_path: Option<PathBuf>,
+    /// Modification times of the shader and LUT when they were last loaded
+    effects_mtime: (Option<SystemTime>, Option<SystemTime>),
+    /// Last time the shader and LUT files were checked for changes
+    last_shader_check: Instant,
+    /// Blur/dominant-color fill for letterbox bars
+    letterbox: Option<LetterboxPass>,
+    /// Downsampled copy of the frame for palette extraction
+    frame_sampler: FrameSampler,
+    /// Transition from the previous wallpaper on hot-swap
+    transition: Option<TransitionPass>,
+    /// Next wallpaper, opened paused so its hot-swap starts on a decoded frame
+    standby: Option<(PathBuf, MpvPlayer)>,
+    /// Wallpaper to open as standby once the GL context is current
+    pending_preload: Option<PathBuf>,
+    /// Decoders other outputs may share; None = always decode alone
+    shared_decoders: Option<SharedDecoders>,
+    /// Shared decoder in use instead of `player`
+    shared: Option<SharedDecode>,
+    /// Shared frame number and output size last drawn
+    shared_drawn: Option<(u64, (i32, i32))>,
+    /// HDR presentation chosen for the current video; None until decided
+    hdr_output: Option<HdrOutput>,
+    /// Canvas of every output spanning this wallpaper and this output's
+    /// place on it; None = the output is its own canvas
+    span: Option<(SpanRect, SpanRect)>,
+    /// A seek or frame step while paused left a frame to draw
+    redraw_paused: bool,
+    /// Paused in deep sleep, with hardware decoding given back
+    asleep: bool,
+    /// A seek or frame step took this output off a decoder it shared with
+    /// outputs it does not span; cleared by the next wallpaper
+    own_decoder: bool,
+    /// Position the decoder replacing a shared one starts at, and whether
+    /// it holds paused there
+    resume_at: Option<(f64, bool)>,
+    /// Images and color shown, in order, while the wallpaper is failing
+    fallbacks: Vec<Fallback>,
+    /// Shown ahead of the fallbacks when a live stream drops
+    signal_lost: Option<Fallback>,
+    /// Shown ahead of the fallbacks once the wallpaper is given up on
+    safe_mode: Option<Fallback>,
+    /// Color ending the fallback chain when none is configured: the darkest
+    /// color of the wallpaper that failed
+    fallback_color: [u8; 3],
+    /// Failure and recovery state of the wallpaper
+    health: SourceHealth,
+    /// mpv render errors in a row
+    render_errors: u32,
+    /// Set once `render_errors` reaches `RENDER_ERRORS_BEFORE_FAILURE`
+    render_failure: Option<String>,
+    /// Last time the wallpaper's file was checked
+    last_health_check: Instant,
+    /// Solid color drawn instead of a wallpaper, as the last fallback
+    solid: Option<[u8; 3]>,
+    /// Render time, frames drawn per second and the share dropped
+    timing: FrameTiming,
+    /// Snapshots waiting for the next drawn frame
+    pending_snapshots: Vec<(PathBuf, Option<RequestId>)>,
+    /// Frames read back for snapshots, to be written
+    snapshots: Vec<(PathBuf, Option<RequestId>, Frame)>,
 }
 
 impl WallpaperSession {
@@ -62,13 +168,51 @@ impl WallpaperSession {
         Ok(Self {
             output_info,
             wallpaper_path: Some(wallpaper_path),
+            letterbox: LetterboxPass::for_fill(video_config.letterbox, video_config.layout),
+            volume: video_config.volume as f32,
+            focus_gain: 1.0,
+            transition: TransitionPass::new(
+                video_config.transition,
+                video_config.transition_duration,
+            ),
             video_config,
             player: None,
+            standby: None,
+            pending_preload: None,
+            shared_decoders: None,
+            shared: None,
+            shared_drawn: None,
+            hdr_output: None,
+            span: None,
+            redraw_paused: false,
+            asleep: false,
+            own_decoder: false,
+            resume_at: None,
+            fallbacks: Vec::new(),
+            signal_lost: None,
+            safe_mode: None,
+            fallback_color: [0, 0, 0],
+            health: SourceHealth::default(),
+            render_errors: 0,
+            render_failure: None,
+            last_health_check: Instant::now(),
+            solid: None,
+            timing: FrameTiming::new(0),
+            pending_snapshots: Vec::new(),
+            snapshots: Vec::new(),
+            animated: None,
+            still: None,
+            animated_blit: AnimatedImageBlit::default(),
+            animated_drawn_size: None,
             egl_window: None,
             state: PlaybackState::Stopped,
-            volume: 0.0,
             initialized: false,
             gl_loaded: false,
+            shader_path: None,
+            lut_path: None,
+            effects_mtime: (None, None),
+            last_shader_check: Instant::now(),
+            frame_sampler: FrameSampler::default(),
         })
     }
 
@@ -88,12 +232,16 @@ impl WallpaperSession {
             "Initializing rendering resources for {} ({}x{})",
             self.output_info.name, width, height
         );
-        // Create EGL window for this surface
-        let egl_window = egl_context.create_window(wl_surface, width, height)?;
-        info!("  ✓ EGL window created");
+        // Create EGL window for this surface, reusing it when switching decoders
+        if self.egl_window.is_none() {
+            self.egl_window = Some(egl_context.create_window(wl_surface, width, height)?);
+            info!("  ✓ EGL window created");
+        }
 
         // Make context current and load GL functions
-        egl_context.make_current(&egl_window)?;
+        if let Some(ref egl_window) = self.egl_window {
+            egl_context.make_current(egl_window)?;
+        }
 
         if !self.gl_loaded {
             egl_context.load_gl_functions();
@@ -101,16 +249,93 @@ impl WallpaperSession {
             info!("  ✓ OpenGL functions loaded");
         }
 
-        self.egl_window = Some(egl_window);
+        if self.solid.is_some() {
+            self.animated_drawn_size = None;
+            self.initialized = true;
+            self.state = PlaybackState::Playing;
+            info!("✅ Session fully initialized for {}", self.output_info.name);
+            return Ok(());
+        }
+
+        if let Some(path) = self.wallpaper_path.clone().filter(|p| is_animated_image(p)) {
+            self.animated = Some(AnimatedImagePlayer::open(
+                &path,
+                self.animated_loop_count(),
+            )?);
+            self.animated_drawn_size = None;
+            info!("  ✓ Loaded animated wallpaper: {}", path.display());
+            if self.shader_path.is_some()
+                || self.effective_lut().is_some()
+                || !self.video_config.color.is_neutral()
+            {
+                warn!(
+                    "Custom shaders, LUTs and color correction are not applied to animated images on {}",
+                    self.output_info.name
+                );
+            }
+
+            self.initialized = true;
+            self.state = PlaybackState::Playing;
+            info!("✅ Session fully initialized for {}", self.output_info.name);
+            return Ok(());
+        }
+
+        if let Some(path) = self
+            .wallpaper_path
+            .clone()
+            .filter(|path| self.draws_still_image(path))
+        {
+            self.still = Some(StillImage::open(&path)?);
+            self.animated_drawn_size = None;
+            info!("  ✓ Loaded still wallpaper: {}", path.display());
+
+            self.initialized = true;
+            self.state = PlaybackState::Playing;
+            info!("✅ Session fully initialized for {}", self.output_info.name);
+            return Ok(());
+        }
+
+        if let Some(path) = self
+            .wallpaper_path
+            .clone()
+            .filter(|_| self.can_share_decoder())
+        {
+            if let Some(decoders) = self.shared_decoders.as_ref() {
+                self.shared = Some(decoders.join(
+                    &path,
+                    &self.video_config,
+                    &self.output_info,
+                    egl_context,
+                )?);
+                self.shared_drawn = None;
+                self.sync_span_audio();
+                self.effects_mtime = self.effects_modified_time();
+                self.initialized = true;
+                self.state = PlaybackState::Playing;
+                info!("✅ Session fully initialized for {}", self.output_info.name);
+                return Ok(());
+            }
+        }
 
         // Create MPV player
         let mut config = self.video_config.clone();
         if let Some(ref path) = self.wallpaper_path {
             config.source = path.to_string_lossy().to_string();
         }
+        config.volume = f64::from(self.volume * self.focus_gain);
+        let resume_at = self.resume_at.take();
+        if let Some((position, hold_paused)) = resume_at {
+            config.start_time = position;
+            if hold_paused {
+                self.redraw_paused = true;
+            }
+        }
 
         let mut player = MpvPlayer::new(&config, &self.output_info)?;
         info!("  ✓ MPV player created");
+        if resume_at.is_some_and(|(_, hold_paused)| hold_paused) {
+            player.pause()?;
+        }
 
         // Initialize MPV render context with EGL
         player.init_render_context(egl_context)?;
@@ -123,28 +348,50 @@ impl WallpaperSession {
         }
 
         self.player = Some(player);
+        self.hdr_output = None;
+        self.apply_shaders();
         self.initialized = true;
-        self.state = PlaybackState::Playing;
+        self.state = if self.redraw_paused {
+            PlaybackState::Paused
+        } else {
+            PlaybackState::Playing
+        };
 
         info!("✅ Session fully initialized for {}", self.output_info.name);
 
         Ok(())
     }
 
-    /// Render a frame to a Wayland surface
+    /// Render a frame to a Wayland surface, timing the frames that are drawn
     pub fn render_frame_to_surface(
         &mut self,
         egl_context: &EglContext,
         wl_surface: &WlSurface,
         width: i32,
         height: i32,
+    ) -> Result<bool> {
+        self.timing.begin_frame();
+        let drawn = self.draw_frame(egl_context, wl_surface, width, height);
+        if matches!(drawn, Ok(true)) {
+            self.timing.end_frame();
+        }
+        drawn
+    }
+
+    /// Draw and present a frame; false when nothing was drawn
+    fn draw_frame(
+        &mut self,
+        egl_context: &EglContext,
+        wl_surface: &WlSurface,
+        width: i32,
+        height: i32,
     ) -> Result<bool> {
         // Lazy initialization
         if !self.initialized {
             self.initialize_resources(egl_context, wl_surface, width, height)?;
         }
 
-        if self.state != PlaybackState::Playing {
+        if self.state != PlaybackState::Playing && !self.redraw_paused {
             return Ok(false);
         }
 
@@ -161,11 +408,113 @@ impl WallpaperSession {
 
         // Make context current
         egl_context.make_current(egl_window)?;
+        self.open_pending_preload(egl_context);
+
+        if let Some([red, green, blue]) = self.solid {
+            if self.animated_drawn_size == Some((width, height)) {
+                return self.redraw_transition(egl_context, width, height);
+            }
+            unsafe {
+                gl::Viewport(0, 0, width, height);
+                gl::ClearColor(
+                    f32::from(red) / 255.0,
+                    f32::from(green) / 255.0,
+                    f32::from(blue) / 255.0,
+                    1.0,
+                );
+                gl::Clear(gl::COLOR_BUFFER_BIT);
+            }
+            self.present_frame(egl_context, width, height)?;
+            self.animated_drawn_size = Some((width, height));
+            return Ok(true);
+        }
+
+        if let Some(ref mut animated) = self.animated {
+            let resized = self.animated_drawn_size != Some((width, height));
+            let new_frame = self.animated_blit.update(animated, Instant::now())?;
+            if !new_frame && !resized {
+                return self.redraw_transition(egl_context, width, height);
+            }
+
+            let (image_width, image_height) = animated.dimensions();
+            let layout = layout_for(
+                self.video_config.layout,
+                self.span,
+                (image_width, image_height),
+                (width, height),
+            );
+            self.animated_blit.draw(&layout, width, height);
+            if let Some(letterbox) = self.letterbox.as_mut() {
+                letterbox.draw(layout.dst_rect, width, height);
+            }
+            self.present_frame(egl_context, width, height)?;
+            self.animated_drawn_size = Some((width, height));
+            return Ok(true);
+        }
+
+        if let Some(ref mut still) = self.still {
+            let (image_width, image_height) = still.dimensions();
+            let uploaded = match still.take_pixels() {
+                Some(rgba) => {
+                    self.animated_blit.upload(image_width, image_height, &rgba);
+                    true
+                }
+                None => false,
+            };
+            if !uploaded && self.animated_drawn_size == Some((width, height)) {
+                return self.redraw_transition(egl_context, width, height);
+            }
+
+            let layout = layout_for(
+                self.video_config.layout,
+                self.span,
+                (image_width, image_height),
+                (width, height),
+            );
+            self.animated_blit.draw(&layout, width, height);
+            if let Some(letterbox) = self.letterbox.as_mut() {
+                letterbox.draw(layout.dst_rect, width, height);
+            }
+            self.present_frame(egl_context, width, height)?;
+            self.animated_drawn_size = Some((width, height));
+            return Ok(true);
+        }
+
+        if let Some(shared) = self.shared.as_ref() {
+            let mut decoder = shared.decoder().borrow_mut();
+            decoder.update()?;
+            let (frame, size) = (decoder.frame(), decoder.size());
+            let Some((video_width, video_height)) = size.filter(|_| frame > 0) else {
+                drop(decoder);
+                return self.redraw_transition(egl_context, width, height);
+            };
+            if self.shared_drawn == Some((frame, (width, height))) {
+                drop(decoder);
+                return self.redraw_transition(egl_context, width, height);
+            }
+
+            let layout = layout_for(
+                self.video_config.layout,
+                self.span,
+                (video_width, video_height),
+                (width, height),
+            );
+            decoder.draw(&layout, width, height);
+            drop(decoder);
+            if let Some(letterbox) = self.letterbox.as_mut() {
+                letterbox.draw(layout.dst_rect, width, height);
+            }
+            self.present_frame(egl_context, width, height)?;
+            self.shared_drawn = Some((frame, (width, height)));
+            return Ok(true);
+        }
+
+        self.configure_hdr();
 
         // Render MPV frame only if we have a frame ready
         if let Some(ref mut player) = self.player {
-            // Check if there's a new frame available
-            let has_frame = player.has_frame();
+            // Check if there's a new frame available; a snapshot redraws the current one
+            let has_frame = player.has_frame() || !self.pending_snapshots.is_empty();
 
             if has_frame {
                 // Clear background
@@ -178,13 +527,33 @@ impl WallpaperSession {
                 // Render the frame
                 match player.render(width, height, 0) {
                     Ok(true) => {
+                        self.render_errors = 0;
+                        if let (Some(letterbox), Some((video_width, video_height))) =
+                            (self.letterbox.as_mut(), player.get_video_dimensions())
+                        {
+                            let layout = calculate_layout(
+                                self.video_config.layout,
+                                video_width,
+                                video_height,
+                                width,
+                                height,
+                            );
+                            letterbox.draw(layout.dst_rect, width, height);
+                        }
+
                         // Swap buffers only after rendering a valid frame
-                        egl_context.swap_buffers(egl_window)?;
+                        self.present_frame(egl_context, width, height)?;
                         return Ok(true);
                     }
-                    Ok(false) => return Ok(false),
+                    Ok(false) => return self.redraw_transition(egl_context, width, height),
                     Err(e) => {
-                        warn!("MPV render error: {}", e);
+                        self.render_errors += 1;
+                        if self.render_errors == 1 {
+                            warn!("MPV render error on {}: {}", self.output_info.name, e);
+                        }
+                        if self.render_errors == RENDER_ERRORS_BEFORE_FAILURE {
+                            self.render_failure = Some(format!("mpv cannot render it: {e}"));
+                        }
                         return Ok(false);
                     }
                 }
@@ -192,7 +561,93 @@ impl WallpaperSession {
             // If no frame yet, don't swap - keep previous content
         }
 
-        Ok(false)
+        self.redraw_transition(egl_context, width, height)
+    }
+
+    /// Finish a drawn frame: copy it for transitions, sample it, draw the
+    /// outgoing wallpaper over it and swap
+    fn present_frame(&mut self, egl_context: &EglContext, width: i32, height: i32) -> Result<()> {
+        self.redraw_paused = false;
+        if let Some(transition) = self.transition.as_mut() {
+            transition.capture(width, height);
+        }
+        self.frame_sampler.sample_if_due(width, height);
+        if !self.pending_snapshots.is_empty() {
+            let frame = Frame::read(width, height);
+            for (path, request) in self.pending_snapshots.drain(..) {
+                self.snapshots.push((path, request, frame.clone()));
+            }
+        }
+        if let Some(transition) = self.transition.as_mut() {
+            transition.draw_overlay(width, height);
+        }
+        if let Some(egl_window) = self.egl_window.as_ref() {
+            egl_context.swap_buffers(egl_window)?;
+        }
+        let (player, shared) = (self.player.as_ref(), self.shared.as_ref());
+        self.timing
+            .record_presented(Instant::now(), || match (player, shared) {
+                (Some(player), _) => player.dropped_frames(),
+                (None, Some(shared)) => shared.decoder().borrow().dropped_frames(),
+                (None, None) => 0,
+            });
+        Ok(())
+    }
+
+    /// Keep a running transition moving when no new frame was decoded
+    fn redraw_transition(
+        &mut self,
+        egl_context: &EglContext,
+        width: i32,
+        height: i32,
+    ) -> Result<bool> {
+        let Some(transition) = self
+            .transition
+            .as_mut()
+            .filter(|transition| transition.is_animating())
+        else {
+            return Ok(false);
+        };
+
+        transition.redraw(width, height);
+        transition.draw_overlay(width, height);
+        if let Some(egl_window) = self.egl_window.as_ref() {
+            egl_context.swap_buffers(egl_window)?;
+        }
+        Ok(true)
+    }
+
+    /// Read back the next frame for a snapshot to `path`, drawing it again
+    /// if the wallpaper is paused or a still image
+    pub(crate) fn request_snapshot(&mut self, path: PathBuf, request: Option<RequestId>) {
+        self.pending_snapshots.push((path, request));
+        if self.state == PlaybackState::Paused {
+            self.redraw_paused = true;
+        }
+        self.animated_drawn_size = None;
+        self.shared_drawn = None;
+    }
+
+    /// Frames read back for snapshots since the last call
+    pub(crate) fn take_snapshots(&mut self) -> Vec<(PathBuf, Option<RequestId>, Frame)> {
+        std::mem::take(&mut self.snapshots)
+    }
+
+    /// Whether the output has to keep requesting frame callbacks
+    ///
+    /// A still image that is already drawn needs nothing more until the
+    /// output is resized or the wallpaper changes, and a paused wallpaper
+    /// nothing until it resumes.
+    pub fn needs_frames(&self) -> bool {
+        if self.state == PlaybackState::Paused {
+            return self.redraw_paused;
+        }
+        (self.still.is_none() && self.solid.is_none())
+            || self.animated_drawn_size.is_none()
+            || self
+                .transition
+                .as_ref()
+                .is_some_and(TransitionPass::is_animating)
     }
 
     /// Render a frame (legacy method for compatibility)
@@ -208,10 +663,23 @@ impl WallpaperSession {
             if let Some(player) = &mut self.player {
                 let _ = player.pause();
             }
+            if let Some(shared) = self.shared.as_ref() {
+                shared.decoder().borrow_mut().pause(&self.output_info.name);
+            }
             self.state = PlaybackState::Paused;
         }
     }
 
+    /// Open the wallpaper held on its first frame, as [`Self::pause`] does
+    /// once it plays; for sessions created while wallpapers are held still
+    pub fn start_paused(&mut self) {
+        if !self.initialized {
+            // A shared decoder keeps playing for the other outputs
+            self.own_decoder = true;
+            self.resume_at = Some((self.video_config.start_time, true));
+        }
+    }
+
     /// Resume playback
     pub fn resume(&mut self) {
         if self.state == PlaybackState::Paused {
@@ -219,15 +687,361 @@ impl WallpaperSession {
             if let Some(player) = &mut self.player {
                 let _ = player.resume();
             }
+            if let Some(shared) = self.shared.as_ref() {
+                shared.decoder().borrow_mut().resume(&self.output_info.name);
+            }
             self.state = PlaybackState::Playing;
         }
     }
 
+    /// Seek, change speed, step a frame or set the A-B loop of the video
+    ///
+    /// A spanned video moves on every output it spans. An output sharing a
+    /// decoder with outputs it does not span takes a decoder of its own for
+    /// a seek or frame step, so the others play on.
+    pub fn transport(&mut self, transport: Transport) {
+        let Some(moves) = self.follow_transport(transport) else {
+            return;
+        };
+
+        if moves && !self.spans() {
+            if let Some(shared) = self.shared.as_ref() {
+                let position = shared
+                    .decoder()
+                    .borrow()
+                    .position()
+                    .map_or(0.0, |position| position.as_secs_f64());
+                let position = match transport {
+                    Transport::Seek {
+                        seconds,
+                        relative: false,
+                    } => seconds,
+                    Transport::Seek { seconds, .. } => position + seconds,
+                    _ => position,
+                };
+                self.resume_at = Some((position.max(0.0), self.state == PlaybackState::Paused));
+                self.own_decoder = true;
+                self.leave_shared_decoder();
+                return;
+            }
+        }
+
+        let applied = if let Some(shared) = self.shared.as_ref() {
+            shared.decoder().borrow_mut().transport(transport)
+        } else if let Some(player) = self.player.as_mut() {
+            player.transport(transport)
+        } else {
+            // Speed and the A-B loop reach the player through its config
+            Ok(())
+        };
+        if let Err(e) = applied {
+            warn!(
+                "Failed to apply {:?} on {}: {}",
+                transport, self.output_info.name, e
+            );
+        }
+    }
+
+    /// Take on the speed, loop and pause a transport leaves the video in,
+    /// without applying it to the decoder; used directly by the other
+    /// outputs of a span. Returns whether the position moves, or None for
+    /// images, which have no transport.
+    pub(crate) fn follow_transport(&mut self, transport: Transport) -> Option<bool> {
+        if self.animated.is_some()
+            || self.still.is_some()
+            || self
+                .wallpaper_path
+                .as_deref()
+                .is_some_and(|path| is_animated_image(path) || self.draws_still_image(path))
+        {
+            debug!(
+                "{} shows an image, ignoring {:?}",
+                self.output_info.name, transport
+            );
+            return None;
+        }
+
+        match transport {
+            Transport::Speed(speed) => self.video_config.playback_rate = speed,
+            Transport::AbLoop(range) => self.video_config.ab_loop = range,
+            Transport::FrameStep { .. } => self.pause(),
+            Transport::Seek { .. } => {}
+        }
+        let moves = matches!(
+            transport,
+            Transport::Seek { .. } | Transport::FrameStep { .. }
+        );
+        if moves && self.state == PlaybackState::Paused {
+            self.redraw_paused = true;
+        }
+        Some(moves)
+    }
+
+    /// Identity of the decoder a spanned video shares, the same on every
+    /// output it spans
+    pub(crate) fn span_decoder(&self) -> Option<usize> {
+        self.shared
+            .as_ref()
+            .filter(|_| self.spans())
+            .map(|shared| shared.id())
+    }
+
     /// Set volume
     pub fn set_volume(&mut self, volume: f32) {
         self.volume = volume.clamp(0.0, 1.0);
+        self.video_config.volume = f64::from(self.volume);
+        if let Some(player) = &mut self.player {
+            let _ = player.set_volume(f64::from(self.volume * self.focus_gain));
+        }
+        self.sync_span_audio();
+    }
+
+    /// Scale the volume by `gain` (0.0 - 1.0) without changing the volume itself
+    pub fn set_focus_gain(&mut self, gain: f32) {
+        self.focus_gain = gain.clamp(0.0, 1.0);
+        if let Some(player) = &mut self.player {
+            let _ = player.set_volume(f64::from(self.volume * self.focus_gain));
+        }
+        self.sync_span_audio();
+    }
+
+    /// Current focus gain
+    pub fn focus_gain(&self) -> f32 {
+        self.focus_gain
+    }
+
+    /// Mute or unmute audio
+    pub fn set_muted(&mut self, muted: bool) {
+        self.video_config.mute = muted;
+        if !muted && !self.spans() {
+            self.leave_shared_decoder();
+        }
+        self.sync_span_audio();
+        if let Some(player) = &mut self.player {
+            if let Err(e) = player.set_muted(muted) {
+                warn!("Failed to change mute on {}: {}", self.output_info.name, e);
+            }
+        }
+    }
+
+    /// Switch the audio device (None = system default)
+    pub fn set_audio_device(&mut self, device: Option<String>) {
+        if let Some(player) = &mut self.player {
+            if let Err(e) = player.set_audio_device(device.as_deref()) {
+                warn!(
+                    "Failed to switch audio device on {}: {}",
+                    self.output_info.name, e
+                );
+            }
+        }
+        self.video_config.audio_device = device;
+    }
+
+    /// Change the color correction of the video playing now and the ones
+    /// loaded after it; like a LUT it needs a decoder of this output's own
+    pub fn set_color(&mut self, color: ColorAdjust) {
+        if color == self.video_config.color {
+            return;
+        }
+
+        self.video_config.color = color;
+        if !color.is_neutral() {
+            self.leave_unspanned_decoder();
+        }
+        if let Some(player) = &mut self.player {
+            if let Err(e) = player.set_color(color) {
+                warn!(
+                    "Failed to change colors on {}: {}",
+                    self.output_info.name, e
+                );
+            }
+        }
+        self.sync_still_image();
+    }
+
+    /// Set a raw mpv property on this output's video, as `mpv --name=value`
+    /// would; later players of this session start with it too.
+    /// An output sharing its decoder gets one of its own first, unless the
+    /// wallpaper is spanned, where every spanned output takes the change.
+    pub fn set_mpv_property(&mut self, property: &str, value: &str) -> Result<()> {
+        self.video_config
+            .mpv_options
+            .insert(property.to_string(), value.to_string());
+        if self.spans() {
+            if let Some(shared) = self.shared.as_ref() {
+                return shared.decoder().borrow_mut().set_property(property, value);
+            }
+        }
+        // A new player of its own starts with the option set
+        self.leave_shared_decoder();
+        match self.player.as_mut() {
+            Some(player) => player.set_property(property, value),
+            None => Ok(()),
+        }
+    }
+
+    /// Switch hardware decoding and the decoded frame rate, for the video
+    /// playing now and the ones loaded after it. A decoder shared with other
+    /// outputs keeps its settings until the next wallpaper.
+    pub fn set_decoding(&mut self, hwdec: HwdecMode, decode_fps: u32) {
+        self.video_config.hwdec = hwdec;
+        self.video_config.decode_fps = decode_fps;
+        // A sleeping decoder takes the new hardware decoding when it wakes
+        let hwdec = if self.asleep { HwdecMode::No } else { hwdec };
         if let Some(player) = &mut self.player {
-            let _ = player.set_volume((self.volume * 100.0) as f64);
+            if let Err(e) = player.set_decoding(hwdec, decode_fps) {
+                warn!(
+                    "Failed to switch decoding on {}: {}",
+                    self.output_info.name, e
+                );
+            }
+        }
+    }
+
+    /// Take up changed settings for this output. Audio, colors, decoding,
+    /// the global LUT and the layout change at once; HDR, tone mapping and
+    /// looping apply from the next wallpaper. A spanned layout is only
+    /// entered or left by applying the wallpaper again. Returns whether the
+    /// surface has to be drawn again.
+    pub fn reconfigure(&mut self, video: &VideoConfig, egl_context: Option<&EglContext>) -> bool {
+        let mut redraw = false;
+
+        if video.volume != self.video_config.volume {
+            self.set_volume(video.volume as f32);
+        }
+        if video.mute != self.video_config.mute {
+            self.set_muted(video.mute);
+        }
+        if video.audio_device != self.video_config.audio_device {
+            self.set_audio_device(video.audio_device.clone());
+        }
+        if video.color != self.video_config.color {
+            self.set_color(video.color);
+            redraw = true;
+        }
+        if video.hwdec != self.video_config.hwdec
+            || video.decode_fps != self.video_config.decode_fps
+        {
+            self.set_decoding(video.hwdec, video.decode_fps);
+        }
+        if video.mpv_options != self.video_config.mpv_options {
+            // Removed options stay on the player until it is rebuilt
+            for (property, value) in &video.mpv_options {
+                if self.video_config.mpv_options.get(property) == Some(value) {
+                    continue;
+                }
+                if let Err(e) = self.set_mpv_property(property, value) {
+                    warn!(
+                        "Failed to set mpv option on {}: {}",
+                        self.output_info.name, e
+                    );
+                }
+            }
+            self.video_config.mpv_options.clone_from(&video.mpv_options);
+        }
+        if video.lut != self.video_config.lut {
+            self.video_config.lut.clone_from(&video.lut);
+            if self.effective_lut().is_some() {
+                self.leave_unspanned_decoder();
+            }
+            self.apply_shaders();
+            self.sync_still_image();
+            redraw = true;
+        }
+
+        let layout_changed = video.layout != self.video_config.layout
+            || video.letterbox != self.video_config.letterbox;
+        let span_changed =
+            (video.layout == LayoutMode::Span) != (self.video_config.layout == LayoutMode::Span);
+        let transition_changed = video.transition != self.video_config.transition
+            || video.transition_duration != self.video_config.transition_duration;
+        if span_changed {
+            info!(
+                "{} enters or leaves a spanned layout with its next wallpaper",
+                self.output_info.name
+            );
+        }
+        if (layout_changed && !span_changed) || transition_changed {
+            // The passes own GL objects, which go with the context they were made in
+            if let (Some(egl_context), Some(egl_window)) = (egl_context, self.egl_window.as_ref()) {
+                if egl_context.make_current(egl_window).is_ok() {
+                    if let Some(letterbox) = self.letterbox.as_mut().filter(|_| layout_changed) {
+                        letterbox.destroy();
+                    }
+                    if let Some(transition) =
+                        self.transition.as_mut().filter(|_| transition_changed)
+                    {
+                        transition.destroy();
+                    }
+                }
+            }
+        }
+        if layout_changed && !span_changed {
+            self.video_config.layout = video.layout;
+            self.video_config.letterbox = video.letterbox;
+            self.letterbox =
+                LetterboxPass::for_fill(self.video_config.letterbox, self.video_config.layout);
+            self.animated_drawn_size = None;
+            redraw = true;
+        }
+        if transition_changed {
+            self.video_config.transition = video.transition;
+            self.video_config.transition_duration = video.transition_duration;
+            self.transition = TransitionPass::new(
+                self.video_config.transition,
+                self.video_config.transition_duration,
+            );
+        }
+
+        self.video_config.hdr_mode = video.hdr_mode;
+        self.video_config.tone_mapping = video.tone_mapping.clone();
+        self.video_config.loop_playback = video.loop_playback;
+        redraw
+    }
+
+    /// Pause and give the hardware decoder's surfaces back while nothing of
+    /// the output can be seen. The player and its surface stay, so
+    /// [`Self::wake`] brings the wallpaper back without reloading it.
+    /// Returns whether playback was running.
+    pub fn sleep(&mut self) -> bool {
+        if self.asleep {
+            return false;
+        }
+        let playing = self.state == PlaybackState::Playing;
+        self.pause();
+        self.asleep = true;
+        self.switch_hwdec(HwdecMode::No);
+        playing
+    }
+
+    /// Take hardware decoding back after [`Self::sleep`], and resume
+    /// playback if `resume`
+    pub fn wake(&mut self, resume: bool) {
+        if !self.asleep {
+            return;
+        }
+        self.asleep = false;
+        self.switch_hwdec(self.video_config.hwdec);
+        if resume {
+            self.resume();
+        }
+    }
+
+    /// Switch hardware decoding on whichever decoder the output uses,
+    /// leaving the configured mode as it is
+    fn switch_hwdec(&mut self, hwdec: HwdecMode) {
+        let switched = if let Some(shared) = self.shared.as_ref() {
+            shared.decoder().borrow_mut().set_hwdec(hwdec)
+        } else if let Some(player) = self.player.as_mut() {
+            player.set_hwdec(hwdec)
+        } else {
+            Ok(())
+        };
+        if let Err(e) = switched {
+            warn!(
+                "Failed to switch hardware decoding on {}: {}",
+                self.output_info.name, e
+            );
         }
     }
 
@@ -239,10 +1053,72 @@ impl WallpaperSession {
             self.output_info.name,
             path.display()
         );
+        self.health.reset();
+        self.swap_source(path)
+    }
 
+    /// Show `path` in place of what the output shows now
+    fn swap_source(&mut self, path: &Path) -> Result<()> {
+        if self.solid.take().is_some() {
+            self.initialized = false;
+        }
         self.wallpaper_path = Some(path.to_path_buf());
+        self.pending_preload = None;
+        self.hdr_output = None;
+        self.render_errors = 0;
+        self.render_failure = None;
+        self.timing.reset_stats();
+        self.own_decoder = false;
+        self.resume_at = None;
+        self.frame_sampler.reset();
+        // A-B positions belong to the video they were set on
+        if self.video_config.ab_loop.take().is_some() {
+            if let Some(player) = self.player.as_mut() {
+                let _ = player.transport(Transport::AbLoop(None));
+            }
+        }
+        if let Some(transition) = self.transition.as_mut() {
+            transition.begin();
+        }
 
-        if let Some(ref mut player) = self.player {
+        // Switching between mpv, the animated image player and still
+        // images, or leaving a shared decoder: rebuild the decoder on the
+        // next render, keeping the EGL surface
+        if self.shared.is_some() {
+            self.leave_shared_decoder();
+            return Ok(());
+        }
+        if self.initialized
+            && (is_animated_image(path) != self.animated.is_some()
+                || self.still.is_some()
+                || self.draws_still_image(path))
+        {
+            self.player = None;
+            self.animated = None;
+            self.still = None;
+            self.initialized = false;
+            return Ok(());
+        }
+
+        if self.animated.is_some() {
+            self.animated = Some(AnimatedImagePlayer::open(path, self.animated_loop_count())?);
+            self.animated_drawn_size = None;
+            info!("  ✓ New animated wallpaper loaded: {}", path.display());
+            return Ok(());
+        }
+
+        let standby = self
+            .standby
+            .take()
+            .filter(|(standby_path, _)| standby_path == path);
+        if let (Some((_, mut standby)), true) = (standby, self.player.is_some()) {
+            if self.state == PlaybackState::Playing {
+                standby.resume()?;
+            }
+            self.player = Some(standby);
+            self.apply_shaders();
+            info!("  ✓ Preloaded wallpaper swapped in: {}", path.display());
+        } else if let Some(ref mut player) = self.player {
             player.load_file(path)?;
             info!("  ✓ New wallpaper loaded: {}", path.display());
         } else {
@@ -253,6 +1129,490 @@ impl WallpaperSession {
         Ok(())
     }
 
+    /// Set the images and color to show, in order, if the wallpaper fails,
+    /// the placeholder shown first when a live stream drops and what is shown
+    /// once the wallpaper is given up on
+    pub fn set_fallbacks(
+        &mut self,
+        fallbacks: Vec<Fallback>,
+        signal_lost: Option<Fallback>,
+        safe_mode: Option<Fallback>,
+    ) {
+        self.fallbacks = fallbacks;
+        self.signal_lost = signal_lost;
+        self.safe_mode = safe_mode;
+    }
+
+    /// The wallpaper that failed while the output shows a fallback
+    pub fn failed_wallpaper(&self) -> Option<&Path> {
+        self.health.primary()
+    }
+
+    /// How long until the next timed check of the wallpaper's file, a
+    /// failed wallpaper's retry or an edit to the shader and LUT files
+    /// (None = nothing to check)
+    pub(crate) fn check_deadline(&self) -> Option<Duration> {
+        let health = (self.wallpaper_path.is_some() || self.health.primary().is_some())
+            .then(|| HEALTH_CHECK_INTERVAL.saturating_sub(self.last_health_check.elapsed()));
+        let effects = (self.shader_path.is_some() || self.effective_lut().is_some())
+            .then(|| SHADER_RELOAD_INTERVAL.saturating_sub(self.last_shader_check.elapsed()));
+        health.into_iter().chain(effects).min()
+    }
+
+    /// Reload the custom shader and LUT when their files changed; returns
+    /// whether they did, so the output is drawn again
+    pub(crate) fn reload_changed_effects(&mut self) -> bool {
+        if self.last_shader_check.elapsed() < SHADER_RELOAD_INTERVAL {
+            return false;
+        }
+        self.last_shader_check = Instant::now();
+        if self.effects_modified_time() == self.effects_mtime {
+            return false;
+        }
+        info!(
+            "Custom shader or LUT changed for {}, reloading",
+            self.output_info.name
+        );
+        self.apply_shaders();
+        true
+    }
+
+    /// Fall back when the wallpaper or the fallback showing fails, and
+    /// switch back once a failed wallpaper can be played again; returns the
+    /// failures and recoveries to report
+    pub(crate) fn check_health(&mut self, egl_context: &EglContext) -> Vec<EngineEvent> {
+        let now = Instant::now();
+        let decoder_failure = match (self.player.as_mut(), self.shared.as_ref()) {
+            (Some(player), _) => player.failure().map(str::to_string),
+            (None, Some(shared)) => shared.decoder().borrow_mut().failure(),
+            (None, None) => None,
+        }
+        .or_else(|| self.render_failure.take());
+        if decoder_failure.is_none()
+            && now.duration_since(self.last_health_check) < HEALTH_CHECK_INTERVAL
+        {
+            return Vec::new();
+        }
+        self.last_health_check = now;
+
+        // Dropping a player frees GL resources through the current context
+        if let Some(egl_window) = self.egl_window.as_ref() {
+            let _ = egl_context.make_current(egl_window);
+        }
+
+        let mut events = Vec::new();
+        let failure = decoder_failure.or_else(|| {
+            self.wallpaper_path
+                .as_deref()
+                .filter(|path| !source_available(path))
+                .map(|path| format!("{} was removed", path.display()))
+        });
+        if let (Some(reason), Some(path)) = (failure, self.wallpaper_path.clone()) {
+            if self.health.step().is_none() {
+                warn!(
+                    "Wallpaper on {} failed: {}; falling back",
+                    self.output_info.name, reason
+                );
+                self.fallback_color = self
+                    .palette(PALETTE_SIZE)
+                    .and_then(|palette| palette.background())
+                    .unwrap_or([0, 0, 0]);
+                let failures = self.health.fail(path.clone(), now);
+                // Streams come back by themselves; files that fail every
+                // time they are opened are not retried forever
+                let give_up = failures >= SAFE_MODE_AFTER && !is_live_source(&path);
+                if give_up {
+                    self.health.give_up();
+                }
+                let fallback = self.show_fallback();
+                if give_up {
+                    warn!(
+                        "Giving up on {} on {} after {} failures; {} stays until another wallpaper is applied",
+                        path.display(),
+                        self.output_info.name,
+                        failures,
+                        fallback
+                    );
+                    events.push(EngineEvent::WallpaperFailed {
+                        output: self.output_info.name.clone(),
+                        path,
+                        reason,
+                        failures,
+                        fallback,
+                    });
+                } else {
+                    events.push(EngineEvent::SourceFailed {
+                        output: self.output_info.name.clone(),
+                        path,
+                        reason,
+                        fallback,
+                    });
+                }
+            } else {
+                warn!(
+                    "Fallback on {} failed too: {}",
+                    self.output_info.name, reason
+                );
+                self.health.advance();
+                self.show_fallback();
+            }
+        }
+
+        if let Some(primary) = self.health.retry(now, source_available) {
+            info!(
+                "Retrying the failed wallpaper on {}: {}",
+                self.output_info.name,
+                primary.display()
+            );
+            match self.swap_source(&primary) {
+                Ok(()) => events.push(EngineEvent::SourceRecovered {
+                    output: self.output_info.name.clone(),
+                    path: primary,
+                }),
+                Err(e) => {
+                    let failures = self.health.fail(primary.clone(), now);
+                    if failures >= SAFE_MODE_AFTER && !is_live_source(&primary) {
+                        self.health.give_up();
+                    }
+                    let fallback = self.show_fallback();
+                    warn!("Retry on {} failed: {}", self.output_info.name, e);
+                    if self.health.gave_up() {
+                        events.push(EngineEvent::WallpaperFailed {
+                            output: self.output_info.name.clone(),
+                            path: primary,
+                            reason: e.to_string(),
+                            failures,
+                            fallback,
+                        });
+                    }
+                }
+            }
+        }
+        events
+    }
+
+    /// Show the fallback the chain is at, moving past images that cannot
+    /// be opened; returns what is shown
+    fn show_fallback(&mut self) -> Fallback {
+        let mut chain = fallback_chain(&self.fallbacks, self.fallback_color);
+        if let Some(placeholder) = self
+            .signal_lost
+            .clone()
+            .filter(|_| self.health.primary().is_some_and(is_live_source))
+        {
+            chain.insert(0, placeholder);
+        }
+        if let Some(safe_mode) = self.safe_mode.clone().filter(|_| self.health.gave_up()) {
+            chain.insert(0, safe_mode);
+        }
+        let step = self.health.step().unwrap_or(0).min(chain.len() - 1);
+        let fallback = chain[step].clone();
+        match &fallback {
+            Fallback::Image(path) => {
+                let shown = if source_available(path) {
+                    self.swap_source(path)
+                } else {
+                    Err(anyhow::anyhow!("{} does not exist", path.display()))
+                };
+                if let Err(e) = shown {
+                    warn!(
+                        "Fallback on {} cannot be shown: {}",
+                        self.output_info.name, e
+                    );
+                    self.health.advance();
+                    return self.show_fallback();
+                }
+            }
+            Fallback::Color(color) => self.show_solid(*color),
+        }
+        info!("{} falls back to {}", self.output_info.name, fallback);
+        fallback
+    }
+
+    /// Draw a solid color instead of any wallpaper
+    fn show_solid(&mut self, color: [u8; 3]) {
+        if let Some(transition) = self.transition.as_mut() {
+            transition.begin();
+        }
+        self.player = None;
+        self.standby = None;
+        self.pending_preload = None;
+        self.animated = None;
+        self.still = None;
+        self.shared = None;
+        self.shared_drawn = None;
+        self.wallpaper_path = None;
+        self.solid = Some(color);
+        self.initialized = false;
+    }
+
+    /// Open `path` paused in a second player so a later hot-swap to it is
+    /// seamless; costs one more decoder until then
+    pub fn preload_wallpaper(&mut self, path: &std::path::Path) {
+        if is_animated_image(path)
+            || self.animated.is_some()
+            || self.still.is_some()
+            || self.draws_still_image(path)
+            || self.shared.is_some()
+            || self.wallpaper_path.as_deref() == Some(path)
+            || self
+                .standby
+                .as_ref()
+                .is_some_and(|(standby_path, _)| standby_path == path)
+        {
+            return;
+        }
+
+        self.standby = None;
+        self.pending_preload = Some(path.to_path_buf());
+    }
+
+    /// Open the pending preload; the GL context must be current
+    fn open_pending_preload(&mut self, egl_context: &EglContext) {
+        let Some(path) = self.pending_preload.take() else {
+            return;
+        };
+        if self.player.is_none() {
+            return;
+        }
+
+        let mut config = self.video_config.clone();
+        config.source = path.to_string_lossy().to_string();
+        config.volume = f64::from(self.volume * self.focus_gain);
+        config.ab_loop = None;
+
+        let standby = MpvPlayer::new(&config, &self.output_info).and_then(|mut player| {
+            player.pause()?;
+            player.init_render_context(egl_context)?;
+            Ok(player)
+        });
+        match standby {
+            Ok(player) => {
+                info!(
+                    "Preloaded next wallpaper for {}: {}",
+                    self.output_info.name,
+                    path.display()
+                );
+                self.standby = Some((path, player));
+            }
+            Err(e) => warn!(
+                "Failed to preload {} on {}: {}",
+                path.display(),
+                self.output_info.name,
+                e
+            ),
+        }
+    }
+
+    /// Attach a custom GLSL post-process shader (None = remove)
+    ///
+    /// The shader file is watched and recompiled whenever it changes on disk.
+    pub fn set_shader(&mut self, shader: Option<PathBuf>) {
+        if shader == self.shader_path {
+            return;
+        }
+
+        self.shader_path = shader;
+        if self.shader_path.is_some() {
+            self.leave_unspanned_decoder();
+        }
+        self.apply_shaders();
+        self.sync_still_image();
+    }
+
+    /// Attach a color grading LUT (.cube) to this wallpaper (None = use global LUT)
+    pub fn set_lut(&mut self, lut: Option<PathBuf>) {
+        if lut == self.lut_path {
+            return;
+        }
+
+        self.lut_path = lut;
+        if self.effective_lut().is_some() {
+            self.leave_unspanned_decoder();
+        }
+        self.apply_shaders();
+        self.sync_still_image();
+    }
+
+    /// Get the LUT in effect: the wallpaper's own, else the global one
+    pub fn effective_lut(&self) -> Option<&std::path::Path> {
+        self.lut_path
+            .as_deref()
+            .or(self.video_config.lut.as_deref())
+    }
+
+    /// Get the custom shader path
+    pub fn shader_path(&self) -> Option<&std::path::Path> {
+        self.shader_path.as_deref()
+    }
+
+    /// Push the shader chain to the player and remember the files' modification times
+    ///
+    /// The user shader runs first; the LUT is always the last pass so the grade
+    /// applies to the final image.
+    fn apply_shaders(&mut self) {
+        self.effects_mtime = self.effects_modified_time();
+
+        if self.player.is_none() {
+            return;
+        }
+
+        let mut shaders = Vec::new();
+        if let Some(ref shader) = self.shader_path {
+            shaders.push(shader.clone());
+        }
+        if let Some(lut) = self.effective_lut() {
+            match compile_cube_to_shader(lut, &default_lut_cache_dir()) {
+                Ok(lut_shader) => shaders.push(lut_shader),
+                Err(e) => warn!("Failed to load LUT for {}: {:#}", self.output_info.name, e),
+            }
+        }
+
+        if let Some(ref mut player) = self.player {
+            if let Err(e) = player.set_shaders(&shaders) {
+                warn!(
+                    "Failed to set custom shaders for {}: {}",
+                    self.output_info.name, e
+                );
+            }
+        }
+    }
+
+    /// Let this session share its decoder with other outputs showing the same video
+    pub(crate) fn set_shared_decoders(&mut self, decoders: SharedDecoders) {
+        self.shared_decoders = Some(decoders);
+    }
+
+    /// Whether the wallpaper can come from a shared decoder: a muted video
+    /// without this output's own shader, LUT or color correction, or any
+    /// spanned video
+    fn can_share_decoder(&self) -> bool {
+        self.shared_decoders.is_some()
+            && !self.own_decoder
+            && (self.spans()
+                || (self.video_config.mute
+                    && self.shader_path.is_none()
+                    && self.effective_lut().is_none()
+                    && self.video_config.color.is_neutral()
+                    && self.video_config.mpv_options.is_empty()))
+            && self
+                .wallpaper_path
+                .as_deref()
+                .is_some_and(|path| !is_animated_image(path) && !is_still_image(path))
+    }
+
+    /// Whether the wallpaper is spanned across outputs; spanned videos keep
+    /// their shared decoder through audio, shader and LUT changes
+    fn spans(&self) -> bool {
+        self.video_config.layout == LayoutMode::Span
+    }
+
+    /// Place this output on the canvas of every output spanning its
+    /// wallpaper, as (canvas, output) in logical coordinates
+    pub(crate) fn set_span(&mut self, span: Option<(SpanRect, SpanRect)>) {
+        if span == self.span {
+            return;
+        }
+        debug!("{} spans {:?}", self.output_info.name, span);
+        self.span = span;
+        // Redraw the crop even if no new frame arrives
+        self.shared_drawn = None;
+        self.animated_drawn_size = None;
+    }
+
+    /// Layout mode of the wallpaper on this output
+    pub(crate) fn layout(&self) -> LayoutMode {
+        self.video_config.layout
+    }
+
+    /// Spanned videos play their sound through the shared decoder
+    fn sync_span_audio(&mut self) {
+        if !self.spans() {
+            return;
+        }
+        if let Some(shared) = self.shared.as_ref() {
+            shared.decoder().borrow_mut().set_audio(
+                &self.output_info.name,
+                f64::from(self.volume * self.focus_gain),
+                self.video_config.mute,
+            );
+        }
+    }
+
+    /// Stop sharing the decoder for this output's own shader, LUT or color
+    /// correction, which a spanned video goes without instead
+    fn leave_unspanned_decoder(&mut self) {
+        if self.spans() {
+            warn!(
+                "Custom shaders, LUTs and color correction are not applied to spanned wallpapers on {}",
+                self.output_info.name
+            );
+        } else {
+            self.leave_shared_decoder();
+        }
+    }
+
+    /// Whether `path` is drawn once without MPV: a still image without this
+    /// output's own shader, LUT or color correction, which need MPV
+    fn draws_still_image(&self, path: &Path) -> bool {
+        self.shader_path.is_none()
+            && self.effective_lut().is_none()
+            && self.video_config.color.is_neutral()
+            && is_still_image(path)
+    }
+
+    /// Move a still image to or from MPV after its shader or LUT changed
+    fn sync_still_image(&mut self) {
+        if !self.initialized || self.animated.is_some() || self.shared.is_some() {
+            return;
+        }
+        let draws_still = self
+            .wallpaper_path
+            .as_deref()
+            .is_some_and(|path| self.draws_still_image(path));
+        if draws_still != self.still.is_some() {
+            debug!(
+                "{} switches still image rendering {}",
+                self.output_info.name,
+                if draws_still { "on" } else { "off" }
+            );
+            self.player = None;
+            self.still = None;
+            self.animated_drawn_size = None;
+            self.initialized = false;
+        }
+    }
+
+    /// Stop using the shared decoder; the next render opens a decoder again
+    fn leave_shared_decoder(&mut self) {
+        if self.shared.take().is_some() {
+            debug!("{} leaves its shared decoder", self.output_info.name);
+            self.shared_drawn = None;
+            self.initialized = false;
+        }
+    }
+
+    /// Loop forever unless looping is turned off, then play once and hold
+    fn animated_loop_count(&self) -> Option<u32> {
+        if self.video_config.loop_playback {
+            None
+        } else {
+            Some(1)
+        }
+    }
+
+    fn effects_modified_time(&self) -> (Option<SystemTime>, Option<SystemTime>) {
+        (
+            file_modified_time(self.shader_path.as_deref()),
+            file_modified_time(self.effective_lut()),
+        )
+    }
+
+    /// Colors of the last sampled frame (None until a frame has been drawn)
+    pub fn palette(&self, count: usize) -> Option<Palette> {
+        self.frame_sampler.palette(count)
+    }
+
     /// Get current wallpaper path
     pub fn wallpaper_path(&self) -> Option<&str> {
         self.wallpaper_path
@@ -260,11 +1620,90 @@ impl WallpaperSession {
             .map(|p| p.to_str().unwrap_or(""))
     }
 
+    /// Playback position of a video wallpaper (None for animated and still images)
+    pub fn position(&self) -> Option<Duration> {
+        match self.shared.as_ref() {
+            Some(shared) => shared.decoder().borrow().position(),
+            None => self.player.as_ref().and_then(MpvPlayer::position),
+        }
+    }
+
+    /// Title, length and decoder of a video wallpaper (empty for animated
+    /// and still images)
+    pub fn media_info(&self) -> MediaInfo {
+        match self.shared.as_ref() {
+            Some(shared) => shared.decoder().borrow().media_info(),
+            None => self
+                .player
+                .as_ref()
+                .map(MpvPlayer::media_info)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Frames drawn per second and the share dropped, over the last second
+    pub fn frame_rate(&self) -> Option<FrameRateSample> {
+        self.timing.frame_rate(Instant::now())
+    }
+
+    /// Average time spent drawing a frame, including the swap
+    pub fn render_time(&self) -> Option<Duration> {
+        self.timing.render_time()
+    }
+
+    /// Frames the decoder dropped since the video started
+    pub fn dropped_frames(&self) -> u64 {
+        match (self.player.as_ref(), self.shared.as_ref()) {
+            (Some(player), _) => player.dropped_frames(),
+            (None, Some(shared)) => shared.decoder().borrow().dropped_frames(),
+            (None, None) => 0,
+        }
+    }
+
     /// Get current playback state
     pub fn state(&self) -> PlaybackState {
         self.state
     }
 
+    /// Whether the surface should be tagged as BT.2020 PQ for HDR passthrough
+    pub fn hdr_passthrough(&self) -> bool {
+        self.hdr_output == Some(HdrOutput::Passthrough)
+    }
+
+    /// Update what the output can display and re-decide HDR handling.
+    /// `hdr_supported` must only be set when the surface can be tagged as HDR.
+    pub fn set_hdr_capabilities(&mut self, capabilities: OutputHdrCapabilities) {
+        if self.output_info.hdr_capabilities != capabilities {
+            self.output_info.hdr_capabilities = capabilities;
+            self.hdr_output = None;
+        }
+    }
+
+    /// Pick SDR, tone-mapped or passthrough output once mpv knows the
+    /// video's color parameters
+    fn configure_hdr(&mut self) {
+        if self.hdr_output.is_some() {
+            return;
+        }
+        let Some(player) = self.player.as_mut() else {
+            return;
+        };
+        match player.configure_hdr(
+            self.video_config.hdr_mode,
+            &self.video_config.tone_mapping,
+            &self.output_info.hdr_capabilities,
+        ) {
+            Ok(output) => self.hdr_output = output,
+            Err(e) => {
+                warn!(
+                    "Failed to configure HDR for {}: {}",
+                    self.output_info.name, e
+                );
+                self.hdr_output = Some(HdrOutput::Sdr);
+            }
+        }
+    }
+
     /// Get output name
     pub fn output_name(&self) -> &str {
         &self.output_info.name
@@ -277,9 +1716,25 @@ impl WallpaperSession {
         if let Some(player) = self.player.take() {
             drop(player);
         }
+        self.standby = None;
+        self.pending_preload = None;
+        self.animated = None;
+        self.still = None;
+        self.shared = None;
+        self.shared_drawn = None;
 
         // Destroy EGL surface properly
         if let Some(ref egl_window) = self.egl_window {
+            if egl_context.make_current(egl_window).is_ok() {
+                if let Some(letterbox) = self.letterbox.as_mut() {
+                    letterbox.destroy();
+                }
+                self.animated_blit.destroy();
+                self.frame_sampler.destroy();
+                if let Some(transition) = self.transition.as_mut() {
+                    transition.destroy();
+                }
+            }
             if let Err(e) = egl_context.destroy_surface(egl_window) {
                 warn!("Failed to destroy EGL surface: {}", e);
             }
@@ -287,6 +1742,66 @@ impl WallpaperSession {
         self.egl_window = None;
         self.initialized = false;
     }
+
+    /// Forget every GL and EGL resource after the GPU lost `egl_context`,
+    /// so the next frame reopens the wallpaper on a fresh context
+    ///
+    /// GL objects are dropped rather than deleted; their names mean nothing
+    /// to the replacement context.
+    pub fn reset_gpu(&mut self, egl_context: &EglContext) {
+        // mpv frees its render context through whatever context is current
+        if let Some(ref egl_window) = self.egl_window {
+            let _ = egl_context.make_current(egl_window);
+        }
+        self.player = None;
+        self.standby = None;
+        self.pending_preload = None;
+        self.animated = None;
+        self.still = None;
+        self.shared = None;
+        self.shared_drawn = None;
+        self.hdr_output = None;
+
+        self.letterbox =
+            LetterboxPass::for_fill(self.video_config.letterbox, self.video_config.layout);
+        self.transition = TransitionPass::new(
+            self.video_config.transition,
+            self.video_config.transition_duration,
+        );
+        self.animated_blit = AnimatedImageBlit::default();
+        self.animated_drawn_size = None;
+        self.frame_sampler = FrameSampler::default();
+
+        if let Some(egl_window) = self.egl_window.take() {
+            if let Err(e) = egl_context.destroy_surface(&egl_window) {
+                warn!("Failed to destroy EGL surface: {}", e);
+            }
+        }
+        self.initialized = false;
+        self.gl_loaded = false;
+    }
+}
+
+/// Layout of a frame on an output, cropped to the output's place on the
+/// span canvas when the wallpaper is spanned
+fn layout_for(
+    mode: LayoutMode,
+    span: Option<(SpanRect, SpanRect)>,
+    (frame_width, frame_height): (i32, i32),
+    (width, height): (i32, i32),
+) -> LayoutTransform {
+    match span.filter(|_| mode == LayoutMode::Span) {
+        Some((canvas, output)) => {
+            calculate_span_layout(frame_width, frame_height, canvas, output, width, height)
+        }
+        None => calculate_layout(mode, frame_width, frame_height, width, height),
+    }
+}
+
+/// Modification time of a file, if it exists
+fn file_modified_time(path: Option<&std::path::Path>) -> Option<SystemTime> {
+    path.and_then(|p| std::fs::metadata(p).ok())
+        .and_then(|m| m.modified().ok())
 }
 
 impl Drop for WallpaperSession {
@@ -304,3 +1819,45 @@ impl Drop for WallpaperSession {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> WallpaperSession {
+        let output = OutputInfo {
+            name: "DP-1".to_string(),
+            width: 1920,
+            height: 1080,
+            scale: 1.0,
+            position: (0, 0),
+            active: true,
+            hdr_capabilities: OutputHdrCapabilities::default(),
+        };
+        WallpaperSession::new(
+            "/wallpapers/rain.mp4".into(),
+            output,
+            VideoConfig::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_deadline_covers_health_and_effect_checks() {
+        let mut session = session();
+        let deadline = session.check_deadline().unwrap();
+        assert!(deadline > SHADER_RELOAD_INTERVAL && deadline <= HEALTH_CHECK_INTERVAL);
+
+        // An edited shader is picked up sooner than the file check
+        session.shader_path = Some("/shaders/crt.glsl".into());
+        assert!(session.check_deadline().unwrap() <= SHADER_RELOAD_INTERVAL);
+
+        // Overdue checks wake the loop at once
+        session.last_shader_check = Instant::now() - 2 * SHADER_RELOAD_INTERVAL;
+        assert_eq!(session.check_deadline(), Some(Duration::ZERO));
+
+        session.shader_path = None;
+        session.wallpaper_path = None;
+        assert_eq!(session.check_deadline(), None);
+    }
+}
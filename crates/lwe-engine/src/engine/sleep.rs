@@ -0,0 +1,124 @@
+//! Deep sleep while windows cover every output
+//!
+//! When a fullscreen or maximized window covers every output that shows a
+//! wallpaper, nothing the engine draws can be seen. The wallpapers then
+//! pause, give their hardware decoder surfaces back and stop drawing. Only
+//! the layer surfaces and players stay, so the wallpapers come back on the
+//! next frame once a window leaves.
+
+use std::collections::{HashMap, HashSet};
+
+use tracing::info;
+
+use super::windows::OutputWindows;
+use super::EngineState;
+
+/// Whether the engine sleeps, and the outputs it paused for it
+#[derive(Default)]
+pub(crate) struct DeepSleep {
+    asleep: bool,
+    /// Outputs whose playback was paused for deep sleep
+    paused: HashSet<String>,
+}
+
+impl DeepSleep {
+    /// Whether every wallpaper sleeps
+    pub(crate) fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    /// Resume `output` on waking; for wallpapers whose pause for another
+    /// reason ends during deep sleep
+    pub(crate) fn hold(&mut self, output: &str) {
+        self.paused.insert(output.to_string());
+    }
+
+    /// Stop resuming `output` on waking, after a user pause or once its
+    /// wallpaper is cleared
+    pub(crate) fn release(&mut self, output: Option<&str>) {
+        match output {
+            Some(output) => {
+                self.paused.remove(output);
+            }
+            None => self.paused.clear(),
+        }
+    }
+}
+
+/// Whether windows cover every one of `outputs`; false without outputs
+pub(crate) fn covers_all<'a>(
+    mut outputs: impl Iterator<Item = &'a String>,
+    windows: &HashMap<String, OutputWindows>,
+) -> bool {
+    let mut any = false;
+    let all = outputs.all(|output| {
+        any = true;
+        windows
+            .get(output)
+            .is_some_and(|windows| windows.fullscreen || windows.maximized)
+    });
+    any && all
+}
+
+/// Put every wallpaper to sleep while windows cover all outputs, including
+/// ones applied meanwhile, and wake them once an output shows again
+pub(crate) fn sync(state: &mut EngineState, windows: &HashMap<String, OutputWindows>) {
+    let asleep = covers_all(state.sessions.keys(), windows);
+
+    if asleep {
+        if !state.deep_sleep.asleep {
+            info!("Windows cover every output, wallpapers go to sleep");
+            state.deep_sleep.asleep = true;
+        }
+        for (output_name, session) in state.sessions.iter_mut() {
+            if session.sleep() {
+                state.deep_sleep.paused.insert(output_name.clone());
+            }
+        }
+        return;
+    }
+    if !state.deep_sleep.asleep {
+        return;
+    }
+
+    info!("An output shows again, waking wallpapers");
+    state.deep_sleep.asleep = false;
+    let paused = std::mem::take(&mut state.deep_sleep.paused);
+    for (output_name, session) in state.sessions.iter_mut() {
+        let held = paused.contains(output_name);
+        if held && state.idle.is_idle() {
+            // Resumed with the others once the session is active again
+            state.idle.hold(output_name);
+        }
+        session.wake(
+            held && !state.power_paused
+                && !state.battery_still
+                && !state.fullscreen_paused.contains(output_name)
+                && !state.idle.is_idle(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_output_has_to_be_covered() {
+        let covered = |fullscreen, maximized| OutputWindows {
+            fullscreen,
+            maximized,
+            focused: false,
+        };
+        let outputs = ["DP-1".to_string(), "HDMI-A-1".to_string()];
+        let mut windows = HashMap::from([
+            ("DP-1".to_string(), covered(true, false)),
+            ("HDMI-A-1".to_string(), covered(false, false)),
+        ]);
+        assert!(!covers_all(outputs.iter(), &windows));
+
+        windows.insert("HDMI-A-1".to_string(), covered(false, true));
+        assert!(covers_all(outputs.iter(), &windows));
+        assert!(!covers_all([].iter(), &windows));
+    }
+}
@@ -0,0 +1,112 @@
+//! Startup helpers for racing the compositor at login
+//!
+//! When launched by systemd or an autostart entry, the engine may start before
+//! the compositor socket exists or before layer-shell is advertised. These
+//! helpers retry with exponential backoff until a configurable deadline.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use tracing::{info, warn};
+use wayland_client::Connection;
+
+/// First retry delay
+const INITIAL_DELAY: Duration = Duration::from_millis(100);
+/// Upper bound for a single retry delay
+const MAX_DELAY: Duration = Duration::from_secs(2);
+
+/// Exponential backoff schedule bounded by a deadline
+#[derive(Debug, Clone)]
+pub(crate) struct Backoff {
+    delay: Duration,
+    max_delay: Duration,
+    deadline: Instant,
+}
+
+impl Backoff {
+    /// Create a backoff that gives up after `timeout`
+    pub(crate) fn new(timeout: Duration) -> Self {
+        Self::with_delays(timeout, INITIAL_DELAY, MAX_DELAY)
+    }
+
+    fn with_delays(timeout: Duration, initial: Duration, max_delay: Duration) -> Self {
+        Self {
+            delay: initial,
+            max_delay,
+            deadline: Instant::now() + timeout,
+        }
+    }
+
+    /// Next delay to wait, or None once the deadline has passed
+    ///
+    /// The last delay is shortened so waiting never overshoots the deadline.
+    pub(crate) fn next_delay(&mut self) -> Option<Duration> {
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return None;
+        }
+
+        let delay = self.delay.min(remaining);
+        self.delay = (self.delay * 2).min(self.max_delay);
+        Some(delay)
+    }
+}
+
+/// Connect to the Wayland compositor, retrying until `timeout` elapses
+pub(crate) fn connect_with_retry(timeout: Duration) -> Result<Connection> {
+    let mut backoff = Backoff::new(timeout);
+    let mut attempt = 1;
+
+    loop {
+        match Connection::connect_to_env() {
+            Ok(connection) => {
+                if attempt > 1 {
+                    info!("Connected to Wayland compositor after {} attempts", attempt);
+                }
+                return Ok(connection);
+            }
+            Err(e) => match backoff.next_delay() {
+                Some(delay) => {
+                    warn!(
+                        "Wayland compositor not ready ({}), retrying in {:?}",
+                        e, delay
+                    );
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                None => {
+                    return Err(anyhow!(
+                        "Failed to connect to Wayland compositor within {:?}: {}",
+                        timeout,
+                        e
+                    ));
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let mut backoff = Backoff::with_delays(
+            Duration::from_secs(60),
+            Duration::from_millis(100),
+            Duration::from_millis(350),
+        );
+
+        assert_eq!(backoff.next_delay(), Some(Duration::from_millis(100)));
+        assert_eq!(backoff.next_delay(), Some(Duration::from_millis(200)));
+        assert_eq!(backoff.next_delay(), Some(Duration::from_millis(350)));
+        assert_eq!(backoff.next_delay(), Some(Duration::from_millis(350)));
+    }
+
+    #[test]
+    fn test_backoff_stops_at_deadline() {
+        let mut backoff = Backoff::new(Duration::ZERO);
+        assert_eq!(backoff.next_delay(), None);
+    }
+}
@@ -0,0 +1,561 @@
+//! Watchdog that restarts the engine thread after a panic
+//!
+//! Commands from the GUI pass through the supervisor, which forwards them to
+//! the current engine thread and remembers which wallpapers are active and
+//! the volume, color and playback settings sent for them. When the engine
+//! thread dies from a panic, its surfaces are torn down as it unwinds; the
+//! supervisor starts a fresh engine, replays those commands and reports
+//! `EngineEvent::Restarted`. This needs panics to unwind, so no build profile
+//! sets `panic = "abort"`.
+
+use std::any::Any;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use calloop::channel::{channel, Channel, Event, Sender};
+use calloop::EventLoop;
+use tracing::{error, info, warn};
+
+use super::command::{EngineCommand, EngineConfig, EngineEvent, EngineRequest};
+use super::EventSender;
+use crate::mpv::Transport;
+
+/// How often the supervisor checks whether the engine thread is alive
+const WATCHDOG_INTERVAL: Duration = Duration::from_millis(250);
+/// Pause before restarting, so a crash on startup does not spin
+const RESTART_DELAY: Duration = Duration::from_millis(500);
+/// Most restarts allowed within `RESTART_WINDOW` before giving up
+const MAX_RESTARTS: usize = 3;
+const RESTART_WINDOW: Duration = Duration::from_secs(60);
+
+/// A running engine thread and its command channel
+pub(super) struct Worker {
+    commands_tx: Sender<EngineRequest>,
+    thread: JoinHandle<()>,
+}
+
+/// Spawn one engine thread on the Wayland or X11 backend
+pub(super) fn spawn_worker(
+    config: EngineConfig,
+    events_tx: EventSender,
+    shutdown: Arc<AtomicBool>,
+) -> Result<Worker> {
+    let (commands_tx, commands_rx) = channel::<EngineRequest>();
+
+    let thread = thread::Builder::new()
+        .name("lwe-engine".to_string())
+        .spawn(move || {
+            crate::scheduling::apply_to_current_thread(&config.scheduling);
+
+            #[cfg(feature = "backend-x11")]
+            let result = if crate::x11::should_use_x11() {
+                crate::x11::run_x11_engine(config, events_tx, commands_rx, shutdown)
+            } else {
+                super::run_engine_thread(config, events_tx, commands_rx, shutdown)
+            };
+            #[cfg(not(feature = "backend-x11"))]
+            let result = super::run_engine_thread(config, events_tx, commands_rx, shutdown);
+
+            if let Err(e) = result {
+                error!("Engine thread error: {}", e);
+            }
+        })
+        .context("Failed to spawn engine thread")?;
+
+    Ok(Worker {
+        commands_tx,
+        thread,
+    })
+}
+
+/// Supervises the engine thread for the lifetime of an `EngineHandle`
+pub(super) struct Supervisor {
+    config: EngineConfig,
+    events_tx: EventSender,
+    shutdown: Arc<AtomicBool>,
+    worker: Option<Worker>,
+    active: ActiveWallpapers,
+    restarts: RestartBudget,
+}
+
+impl Supervisor {
+    pub(super) fn new(
+        config: EngineConfig,
+        events_tx: EventSender,
+        shutdown: Arc<AtomicBool>,
+        worker: Worker,
+    ) -> Self {
+        Self {
+            config,
+            events_tx,
+            shutdown,
+            worker: Some(worker),
+            active: ActiveWallpapers::default(),
+            restarts: RestartBudget::new(MAX_RESTARTS, RESTART_WINDOW),
+        }
+    }
+
+    /// Forward commands and watch the engine thread until it exits for good
+    pub(super) fn run(mut self, commands_rx: Channel<EngineRequest>) {
+        let mut event_loop: EventLoop<'static, Supervisor> = match EventLoop::try_new() {
+            Ok(event_loop) => event_loop,
+            Err(e) => {
+                error!("Failed to create engine supervisor loop: {}", e);
+                self.shutdown.store(true, Ordering::SeqCst);
+                self.join_worker();
+                return;
+            }
+        };
+
+        let inserted = event_loop
+            .handle()
+            .insert_source(commands_rx, |event, _, supervisor| match event {
+                Event::Msg(request) => supervisor.forward(request),
+                // Every handle and sender is gone; nobody can stop the engine otherwise
+                Event::Closed => {
+                    supervisor.shutdown.store(true, Ordering::SeqCst);
+                    // The engine sleeps until something wakes it to see the flag
+                    supervisor.forward(EngineRequest::internal(EngineCommand::Shutdown));
+                }
+            });
+        if let Err(e) = inserted {
+            error!("Failed to register engine command channel: {:?}", e);
+            self.shutdown.store(true, Ordering::SeqCst);
+            self.join_worker();
+            return;
+        }
+
+        loop {
+            if let Err(e) = event_loop.dispatch(WATCHDOG_INTERVAL, &mut self) {
+                warn!("Engine supervisor dispatch failed: {}", e);
+            }
+
+            let finished = self
+                .worker
+                .as_ref()
+                .is_none_or(|worker| worker.thread.is_finished());
+            if finished && !self.recover() {
+                break;
+            }
+        }
+    }
+
+    fn forward(&mut self, request: EngineRequest) {
+        self.active.observe(&request.command);
+        // A restarted engine starts with the settings in effect by then
+        if let EngineCommand::UpdateConfig(config) = &request.command {
+            self.config = (**config).clone();
+        }
+        if let Some(worker) = &self.worker {
+            // A dead engine drops the command; wallpapers are replayed on restart
+            let _ = worker.commands_tx.send(request);
+        }
+    }
+
+    fn join_worker(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.thread.join();
+        }
+    }
+
+    /// Handle a finished engine thread; returns whether the engine is running again
+    fn recover(&mut self) -> bool {
+        let Some(worker) = self.worker.take() else {
+            return false;
+        };
+        // Exiting without a panic means shutdown or a startup failure the
+        // engine already reported, neither of which a restart would fix
+        let Err(payload) = worker.thread.join() else {
+            return false;
+        };
+
+        let reason = panic_message(payload.as_ref());
+        error!("Engine thread panicked: {}", reason);
+
+        // The panicking engine never got to report that it stopped
+        if self.shutdown.load(Ordering::SeqCst) {
+            let _ = self.events_tx.send(EngineEvent::Stopped);
+            return false;
+        }
+
+        if !self.restarts.allow(Instant::now()) {
+            error!(
+                "Engine crashed {} times within {:?}, not restarting",
+                MAX_RESTARTS + 1,
+                RESTART_WINDOW
+            );
+            let _ = self.events_tx.send(EngineEvent::Error(format!(
+                "Engine crashed repeatedly and was not restarted: {reason}"
+            )));
+            let _ = self.events_tx.send(EngineEvent::Stopped);
+            return false;
+        }
+
+        thread::sleep(RESTART_DELAY);
+        let worker = match spawn_worker(
+            self.config.clone(),
+            self.events_tx.clone(),
+            self.shutdown.clone(),
+        ) {
+            Ok(worker) => worker,
+            Err(e) => {
+                error!("Failed to restart engine: {:#}", e);
+                let _ = self.events_tx.send(EngineEvent::Error(format!(
+                    "Failed to restart engine: {e:#}"
+                )));
+                let _ = self.events_tx.send(EngineEvent::Stopped);
+                return false;
+            }
+        };
+
+        let restore = self.active.restore_commands();
+        info!(
+            "Engine restarted (restart {}), restoring {} wallpaper and setting command(s)",
+            self.restarts.total(),
+            restore.len()
+        );
+        for cmd in restore {
+            let _ = worker.commands_tx.send(EngineRequest::internal(cmd));
+        }
+        self.worker = Some(worker);
+
+        let _ = self.events_tx.send(EngineEvent::Restarted {
+            restarts: self.restarts.total(),
+            reason,
+        });
+        true
+    }
+}
+
+/// Text of a panic payload, for logs and the `Restarted` event
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// What a replayed per-output command sets
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Setting {
+    Volume,
+    Mute,
+    AudioDevice,
+    Color,
+    IdleInhibit,
+    ScreenSaver,
+    /// Paused or playing
+    Playback,
+    Speed,
+    AbLoop,
+    MpvProperty(String),
+}
+
+impl Setting {
+    /// The setting a command changes and the output it targets (None = all
+    /// outputs); None for commands that change nothing lasting
+    fn of(cmd: &EngineCommand) -> Option<(Option<&String>, Setting)> {
+        match cmd {
+            EngineCommand::SetVolume { output, .. } => Some((Some(output), Setting::Volume)),
+            EngineCommand::SetMute { output, .. } => Some((Some(output), Setting::Mute)),
+            EngineCommand::SetAudioDevice { output, .. } => {
+                Some((Some(output), Setting::AudioDevice))
+            }
+            EngineCommand::SetColor { output, .. } => Some((Some(output), Setting::Color)),
+            EngineCommand::SetIdleInhibit { output, .. } => {
+                Some((Some(output), Setting::IdleInhibit))
+            }
+            EngineCommand::SetScreenSaverActive { .. } => Some((None, Setting::ScreenSaver)),
+            EngineCommand::Pause { output } | EngineCommand::Resume { output } => {
+                Some((output.as_ref(), Setting::Playback))
+            }
+            EngineCommand::Transport {
+                output,
+                transport: Transport::Speed(_),
+            } => Some((output.as_ref(), Setting::Speed)),
+            EngineCommand::Transport {
+                output,
+                transport: Transport::AbLoop(_),
+            } => Some((output.as_ref(), Setting::AbLoop)),
+            EngineCommand::SetMpvProperty {
+                output, property, ..
+            } => Some((Some(output), Setting::MpvProperty(property.clone()))),
+            _ => None,
+        }
+    }
+
+    /// Whether a new wallpaper starts without it; the rest belong to the output
+    fn follows_wallpaper(&self) -> bool {
+        matches!(
+            self,
+            Setting::Playback | Setting::Speed | Setting::AbLoop | Setting::MpvProperty(_)
+        )
+    }
+}
+
+/// Wallpapers the GUI has applied and the settings sent since, kept as the
+/// commands that would set them again
+#[derive(Debug, Default)]
+struct ActiveWallpapers {
+    /// Last apply or clear targeting every output
+    all: Option<EngineCommand>,
+    /// Later applies and clears targeting one output
+    outputs: BTreeMap<String, EngineCommand>,
+    /// Last command of each setting, by target output (None = all outputs,
+    /// which sort first so later per-output commands still win)
+    settings: BTreeMap<(Option<String>, Setting), EngineCommand>,
+}
+
+impl ActiveWallpapers {
+    fn observe(&mut self, cmd: &EngineCommand) {
+        match cmd {
+            EngineCommand::ApplyWallpaper { output: None, .. } => {
+                self.all = Some(cmd.clone());
+                self.outputs.clear();
+                self.forget_wallpaper_settings(None);
+            }
+            EngineCommand::ClearWallpaper { output: None } => {
+                self.all = None;
+                self.outputs.clear();
+                self.forget_wallpaper_settings(None);
+            }
+            EngineCommand::ApplyWallpaper {
+                output: Some(output),
+                ..
+            } => {
+                self.outputs.insert(output.clone(), cmd.clone());
+                self.forget_wallpaper_settings(Some(output));
+            }
+            EngineCommand::ClearWallpaper {
+                output: Some(output),
+            } => {
+                if self.all.is_some() {
+                    self.outputs.insert(output.clone(), cmd.clone());
+                } else {
+                    self.outputs.remove(output);
+                }
+                self.forget_wallpaper_settings(Some(output));
+            }
+            _ => {
+                let Some((output, setting)) = Setting::of(cmd) else {
+                    return;
+                };
+                // A command for every output replaces what was set on each
+                if output.is_none() {
+                    self.settings.retain(|(_, kept), _| *kept != setting);
+                }
+                self.settings
+                    .insert((output.cloned(), setting), cmd.clone());
+            }
+        }
+    }
+
+    /// Drop the playback settings of the wallpaper an output (None = every
+    /// output) no longer shows
+    fn forget_wallpaper_settings(&mut self, output: Option<&String>) {
+        self.settings.retain(|(target, setting), _| {
+            !setting.follows_wallpaper() || (output.is_some() && target.as_ref() != output)
+        });
+    }
+
+    /// Commands that rebuild the current wallpapers and their settings on a
+    /// fresh engine, in order
+    fn restore_commands(&self) -> Vec<EngineCommand> {
+        self.all
+            .iter()
+            .chain(self.outputs.values())
+            .chain(self.settings.values())
+            .cloned()
+            .collect()
+    }
+}
+
+/// Limits restarts to `max` within a sliding `window`
+#[derive(Debug)]
+struct RestartBudget {
+    max: usize,
+    window: Duration,
+    recent: VecDeque<Instant>,
+    total: u32,
+}
+
+impl RestartBudget {
+    fn new(max: usize, window: Duration) -> Self {
+        Self {
+            max,
+            window,
+            recent: VecDeque::new(),
+            total: 0,
+        }
+    }
+
+    /// Record a restart at `now` if the budget allows one
+    fn allow(&mut self, now: Instant) -> bool {
+        while self
+            .recent
+            .front()
+            .is_some_and(|restart| now.duration_since(*restart) >= self.window)
+        {
+            self.recent.pop_front();
+        }
+
+        if self.recent.len() >= self.max {
+            return false;
+        }
+
+        self.recent.push_back(now);
+        self.total += 1;
+        true
+    }
+
+    /// Restarts since the engine was spawned
+    fn total(&self) -> u32 {
+        self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn apply(path: &str, output: Option<&str>) -> EngineCommand {
+        EngineCommand::ApplyWallpaper {
+            path: PathBuf::from(path),
+            output: output.map(str::to_string),
+            shader: None,
+            lut: None,
+        }
+    }
+
+    fn clear(output: Option<&str>) -> EngineCommand {
+        EngineCommand::ClearWallpaper {
+            output: output.map(str::to_string),
+        }
+    }
+
+    fn paths(commands: &[EngineCommand]) -> Vec<String> {
+        commands
+            .iter()
+            .map(|cmd| match cmd {
+                EngineCommand::ApplyWallpaper { path, output, .. } => {
+                    format!("{}@{}", path.display(), output.as_deref().unwrap_or("*"))
+                }
+                EngineCommand::ClearWallpaper { output } => {
+                    format!("clear@{}", output.as_deref().unwrap_or("*"))
+                }
+                other => format!("{other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_active_wallpapers_replay_latest_state_per_output() {
+        let mut active = ActiveWallpapers::default();
+        active.observe(&apply("/a.mp4", Some("DP-1")));
+        active.observe(&apply("/b.mp4", Some("HDMI-A-1")));
+        active.observe(&EngineCommand::GetStatus);
+        active.observe(&apply("/c.mp4", Some("DP-1")));
+        active.observe(&clear(Some("HDMI-A-1")));
+
+        assert_eq!(paths(&active.restore_commands()), vec!["/c.mp4@DP-1"]);
+    }
+
+    #[test]
+    fn test_active_wallpapers_keep_clears_over_an_apply_to_all() {
+        let mut active = ActiveWallpapers::default();
+        active.observe(&apply("/a.mp4", Some("DP-1")));
+        active.observe(&apply("/all.mp4", None));
+        active.observe(&clear(Some("DP-2")));
+        active.observe(&apply("/b.mp4", Some("DP-3")));
+
+        assert_eq!(
+            paths(&active.restore_commands()),
+            vec!["/all.mp4@*", "clear@DP-2", "/b.mp4@DP-3"]
+        );
+
+        active.observe(&clear(None));
+        assert!(active.restore_commands().is_empty());
+    }
+
+    #[test]
+    fn test_active_wallpapers_replay_output_settings_after_the_wallpapers() {
+        let mut active = ActiveWallpapers::default();
+        active.observe(&EngineCommand::SetVolume {
+            output: "DP-1".to_string(),
+            volume: 0.2,
+        });
+        active.observe(&apply("/a.mp4", Some("DP-1")));
+        active.observe(&EngineCommand::SetVolume {
+            output: "DP-1".to_string(),
+            volume: 0.4,
+        });
+        active.observe(&EngineCommand::SetMute {
+            output: "DP-1".to_string(),
+            muted: false,
+        });
+        active.observe(&EngineCommand::Pause { output: None });
+        active.observe(&EngineCommand::Transport {
+            output: Some("DP-1".to_string()),
+            transport: Transport::Speed(1.5),
+        });
+        active.observe(&EngineCommand::Transport {
+            output: Some("DP-1".to_string()),
+            transport: Transport::Seek {
+                seconds: 10.0,
+                relative: false,
+            },
+        });
+
+        assert_eq!(
+            paths(&active.restore_commands()),
+            vec![
+                "/a.mp4@DP-1".to_string(),
+                "Pause { output: None }".to_string(),
+                "SetVolume { output: \"DP-1\", volume: 0.4 }".to_string(),
+                "SetMute { output: \"DP-1\", muted: false }".to_string(),
+                "Transport { output: Some(\"DP-1\"), transport: Speed(1.5) }".to_string(),
+            ]
+        );
+
+        // A new wallpaper plays at normal speed; the volume stays with the output
+        active.observe(&apply("/b.mp4", Some("DP-1")));
+        active.observe(&EngineCommand::Resume { output: None });
+        assert_eq!(
+            paths(&active.restore_commands()),
+            vec![
+                "/b.mp4@DP-1".to_string(),
+                "Resume { output: None }".to_string(),
+                "SetVolume { output: \"DP-1\", volume: 0.4 }".to_string(),
+                "SetMute { output: \"DP-1\", muted: false }".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_restart_budget_refills_after_the_window() {
+        let start = Instant::now();
+        let mut budget = RestartBudget::new(2, Duration::from_secs(60));
+
+        assert!(budget.allow(start));
+        assert!(budget.allow(start + Duration::from_secs(10)));
+        assert!(!budget.allow(start + Duration::from_secs(20)));
+        assert!(budget.allow(start + Duration::from_secs(61)));
+        assert_eq!(budget.total(), 3);
+    }
+
+    #[test]
+    fn test_panic_message_reads_str_and_string_payloads() {
+        let payload = std::panic::catch_unwind(|| panic!("render thread lost")).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "render thread lost");
+
+        let payload =
+            std::panic::catch_unwind(|| panic!("{} failed", "eglSwapBuffers")).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "eglSwapBuffers failed");
+    }
+}
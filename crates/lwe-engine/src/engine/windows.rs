@@ -0,0 +1,394 @@
+//! Which outputs are covered by fullscreen or maximized windows, and which
+//! one holds the focused window
+//!
+//! Hyprland is asked over its IPC socket, which also says which workspace
+//! each monitor shows. Other compositors (sway, niri, labwc, ...) report
+//! their windows through wlr-foreign-toplevel-management; a window counts on
+//! every output it has entered until it is minimized or closed.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use tracing::debug;
+use wayland_client::backend::ObjectId;
+use wayland_client::protocol::wl_output::WlOutput;
+use wayland_client::{event_created_child, Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+    zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+};
+
+use super::EngineState;
+use crate::wayland::OutputManager;
+
+/// How often Hyprland is asked for its windows
+const HYPRLAND_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Hyprland socket reads give up after this long so rendering never stalls
+const HYPRLAND_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Windows shown on one output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct OutputWindows {
+    /// A fullscreen window is shown
+    pub fullscreen: bool,
+    /// A maximized window is shown
+    pub maximized: bool,
+    /// The focused window is on this output
+    pub focused: bool,
+}
+
+/// State of one foreign toplevel
+#[derive(Debug, Default)]
+struct Toplevel {
+    outputs: Vec<WlOutput>,
+    fullscreen: bool,
+    maximized: bool,
+    minimized: bool,
+    activated: bool,
+}
+
+/// Tracks window coverage per output from whichever source the compositor offers
+pub(crate) struct WindowTracker {
+    /// Hyprland IPC socket, when running under Hyprland
+    hyprland: Option<PathBuf>,
+    /// Last Hyprland answer, by output name
+    hyprland_windows: HashMap<String, OutputWindows>,
+    last_poll: Option<Instant>,
+    /// Foreign toplevels by handle
+    toplevels: HashMap<ObjectId, Toplevel>,
+}
+
+impl WindowTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            hyprland: hyprland_socket(),
+            hyprland_windows: HashMap::new(),
+            last_poll: None,
+            toplevels: HashMap::new(),
+        }
+    }
+
+    /// Whether windows come from Hyprland IPC instead of foreign-toplevel
+    pub(crate) fn uses_hyprland(&self) -> bool {
+        self.hyprland.is_some()
+    }
+
+    /// How long until the Hyprland answer is due again; foreign toplevels
+    /// need no polling
+    pub(crate) fn poll_interval(&self) -> Option<Duration> {
+        self.hyprland.as_ref()?;
+        Some(self.last_poll.map_or(Duration::ZERO, |at| {
+            HYPRLAND_POLL_INTERVAL.saturating_sub(at.elapsed())
+        }))
+    }
+
+    /// Refresh the Hyprland answer if it is due; foreign toplevels update
+    /// through Wayland events
+    pub(crate) fn poll(&mut self) {
+        let Some(socket) = &self.hyprland else {
+            return;
+        };
+        if self
+            .last_poll
+            .is_some_and(|at| at.elapsed() < HYPRLAND_POLL_INTERVAL)
+        {
+            return;
+        }
+        self.last_poll = Some(Instant::now());
+
+        match query_hyprland(socket) {
+            Ok(windows) => self.hyprland_windows = windows,
+            Err(e) => debug!("Hyprland window query failed: {:#}", e),
+        }
+    }
+
+    /// Window coverage by output name
+    pub(crate) fn output_windows(&self, outputs: &OutputManager) -> HashMap<String, OutputWindows> {
+        if self.hyprland.is_some() {
+            return self.hyprland_windows.clone();
+        }
+
+        let mut windows: HashMap<String, OutputWindows> = HashMap::new();
+        for toplevel in self
+            .toplevels
+            .values()
+            .filter(|toplevel| !toplevel.minimized)
+        {
+            for output in outputs
+                .get_all_ready()
+                .filter(|output| toplevel.outputs.contains(&output.wl_output))
+            {
+                let entry = windows.entry(output.info.name.clone()).or_default();
+                entry.fullscreen |= toplevel.fullscreen;
+                entry.maximized |= toplevel.maximized;
+                entry.focused |= toplevel.activated;
+            }
+        }
+        windows
+    }
+}
+
+/// `$XDG_RUNTIME_DIR/hypr/<signature>/.socket.sock`, or the pre-0.40 `/tmp/hypr` location
+fn hyprland_socket() -> Option<PathBuf> {
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE")
+        .ok()
+        .filter(|value| !value.is_empty())?;
+    let runtime = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(|dir| PathBuf::from(dir).join("hypr").join(&signature))
+        .filter(|dir| dir.is_dir());
+    Some(
+        runtime
+            .unwrap_or_else(|| PathBuf::from("/tmp/hypr").join(&signature))
+            .join(".socket.sock"),
+    )
+}
+
+fn hyprland_request(socket: &Path, command: &str) -> Result<String> {
+    let mut stream = UnixStream::connect(socket)
+        .with_context(|| format!("Cannot connect to {}", socket.display()))?;
+    stream.set_read_timeout(Some(HYPRLAND_TIMEOUT))?;
+    stream.set_write_timeout(Some(HYPRLAND_TIMEOUT))?;
+    stream.write_all(command.as_bytes())?;
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    Ok(reply)
+}
+
+fn query_hyprland(socket: &Path) -> Result<HashMap<String, OutputWindows>> {
+    let monitors = hyprland_request(socket, "j/monitors")?;
+    let clients = hyprland_request(socket, "j/clients")?;
+    parse_hyprland(&monitors, &clients)
+}
+
+#[derive(Deserialize)]
+struct HyprlandWorkspaceRef {
+    id: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HyprlandMonitor {
+    name: String,
+    active_workspace: HyprlandWorkspaceRef,
+    special_workspace: Option<HyprlandWorkspaceRef>,
+    #[serde(default)]
+    focused: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HyprlandClient {
+    workspace: HyprlandWorkspaceRef,
+    #[serde(default)]
+    hidden: bool,
+    /// 0.41+: 0 none, 1 maximized, 2 fullscreen, 3 both; earlier: a bool
+    #[serde(default)]
+    fullscreen: serde_json::Value,
+    /// Before 0.41: 0 fullscreen, 1 maximized
+    #[serde(default)]
+    fullscreen_mode: Option<i64>,
+}
+
+impl HyprlandClient {
+    fn windows(&self) -> OutputWindows {
+        match &self.fullscreen {
+            serde_json::Value::Bool(true) => OutputWindows {
+                fullscreen: self.fullscreen_mode != Some(1),
+                maximized: self.fullscreen_mode == Some(1),
+                focused: false,
+            },
+            serde_json::Value::Number(mode) => {
+                let mode = mode.as_i64().unwrap_or(0);
+                OutputWindows {
+                    fullscreen: mode & 2 != 0,
+                    maximized: mode & 1 != 0,
+                    focused: false,
+                }
+            }
+            _ => OutputWindows::default(),
+        }
+    }
+}
+
+/// Coverage of each monitor's visible workspaces from `j/monitors` and `j/clients`
+fn parse_hyprland(monitors: &str, clients: &str) -> Result<HashMap<String, OutputWindows>> {
+    let monitors: Vec<HyprlandMonitor> =
+        serde_json::from_str(monitors).context("Invalid Hyprland monitors reply")?;
+    let clients: Vec<HyprlandClient> =
+        serde_json::from_str(clients).context("Invalid Hyprland clients reply")?;
+
+    Ok(monitors
+        .into_iter()
+        .map(|monitor| {
+            let visible = |id: i64| {
+                id == monitor.active_workspace.id
+                    || monitor
+                        .special_workspace
+                        .as_ref()
+                        .is_some_and(|special| special.id != 0 && special.id == id)
+            };
+            let windows = clients
+                .iter()
+                .filter(|client| !client.hidden && visible(client.workspace.id))
+                .map(HyprlandClient::windows)
+                .fold(
+                    OutputWindows {
+                        focused: monitor.focused,
+                        ..OutputWindows::default()
+                    },
+                    |acc, windows| OutputWindows {
+                        fullscreen: acc.fullscreen || windows.fullscreen,
+                        maximized: acc.maximized || windows.maximized,
+                        focused: acc.focused,
+                    },
+                );
+            (monitor.name, windows)
+        })
+        .collect())
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for EngineState {
+    fn event(
+        state: &mut Self,
+        _proxy: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } => {
+                state
+                    .windows
+                    .toplevels
+                    .insert(toplevel.id(), Toplevel::default());
+            }
+            zwlr_foreign_toplevel_manager_v1::Event::Finished => {
+                state.windows.toplevels.clear();
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(EngineState, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for EngineState {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let zwlr_foreign_toplevel_handle_v1::Event::Closed = event {
+            state.windows.toplevels.remove(&handle.id());
+            handle.destroy();
+            return;
+        }
+
+        let Some(toplevel) = state.windows.toplevels.get_mut(&handle.id()) else {
+            return;
+        };
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::State { state: flags } => {
+                let has = |flag: zwlr_foreign_toplevel_handle_v1::State| {
+                    flags
+                        .chunks_exact(4)
+                        .any(|bytes| u32::from_ne_bytes(bytes.try_into().unwrap()) == flag as u32)
+                };
+                toplevel.fullscreen = has(zwlr_foreign_toplevel_handle_v1::State::Fullscreen);
+                toplevel.maximized = has(zwlr_foreign_toplevel_handle_v1::State::Maximized);
+                toplevel.minimized = has(zwlr_foreign_toplevel_handle_v1::State::Minimized);
+                toplevel.activated = has(zwlr_foreign_toplevel_handle_v1::State::Activated);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::OutputEnter { output }
+                if !toplevel.outputs.contains(&output) =>
+            {
+                toplevel.outputs.push(output);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::OutputLeave { output } => {
+                toplevel.outputs.retain(|entered| *entered != output);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONITORS: &str = r#"[
+        {"id": 0, "name": "DP-1", "activeWorkspace": {"id": 1, "name": "1"},
+         "specialWorkspace": {"id": 0, "name": ""}},
+        {"id": 1, "name": "HDMI-A-1", "activeWorkspace": {"id": 4, "name": "4"},
+         "specialWorkspace": {"id": -98, "name": "special:scratch"}, "focused": true}
+    ]"#;
+
+    #[test]
+    fn test_hyprland_counts_only_visible_workspaces() {
+        let clients = r#"[
+            {"workspace": {"id": 2}, "hidden": false, "fullscreen": 2},
+            {"workspace": {"id": 1}, "hidden": false, "fullscreen": 1},
+            {"workspace": {"id": -98}, "hidden": false, "fullscreen": 2},
+            {"workspace": {"id": 4}, "hidden": true, "fullscreen": 1}
+        ]"#;
+        let windows = parse_hyprland(MONITORS, clients).unwrap();
+
+        assert_eq!(
+            windows["DP-1"],
+            OutputWindows {
+                fullscreen: false,
+                maximized: true,
+                focused: false
+            }
+        );
+        assert_eq!(
+            windows["HDMI-A-1"],
+            OutputWindows {
+                fullscreen: true,
+                maximized: false,
+                focused: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_hyprland_reads_pre_041_fullscreen_flags() {
+        let clients = r#"[
+            {"workspace": {"id": 1}, "fullscreen": true, "fullscreenMode": 0},
+            {"workspace": {"id": 4}, "fullscreen": true, "fullscreenMode": 1}
+        ]"#;
+        let windows = parse_hyprland(MONITORS, clients).unwrap();
+
+        assert!(windows["DP-1"].fullscreen);
+        assert!(!windows["HDMI-A-1"].fullscreen);
+        assert!(windows["HDMI-A-1"].maximized);
+    }
+
+    #[test]
+    fn test_only_hyprland_schedules_polls() {
+        let mut tracker = WindowTracker {
+            hyprland: None,
+            hyprland_windows: HashMap::new(),
+            last_poll: None,
+            toplevels: HashMap::new(),
+        };
+        assert_eq!(tracker.poll_interval(), None);
+
+        tracker.hyprland = Some(PathBuf::from("/run/user/1000/hypr/x/.socket.sock"));
+        assert_eq!(tracker.poll_interval(), Some(Duration::ZERO));
+        tracker.last_poll = Some(Instant::now());
+        assert!(tracker.poll_interval().unwrap() > Duration::from_millis(900));
+    }
+}
@@ -0,0 +1,143 @@
+//! Frame readback for palette extraction
+//!
+//! After a frame is drawn, the whole output is downsampled into a small
+//! framebuffer and read back to the CPU. `glReadPixels` stalls the pipeline,
+//! so a wallpaper is sampled on its first frame and then only every few
+//! seconds; palette requests are answered from the last sample.
+
+use std::time::{Duration, Instant};
+
+use lwe_core::Palette;
+
+/// Size of the downsampled frame
+const SAMPLE_SIZE: (i32, i32) = (64, 36);
+
+/// How often a playing wallpaper is re-sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// GL resources and the latest sample for one output
+#[derive(Default)]
+pub(crate) struct FrameSampler {
+    framebuffer: u32,
+    texture: u32,
+    /// RGBA pixels of the last sample, bottom row first
+    pixels: Option<Vec<u8>>,
+    last_sample: Option<Instant>,
+}
+
+impl FrameSampler {
+    /// Sample the default framebuffer if the last sample is stale
+    ///
+    /// Must run with the output's context current, after the frame was drawn
+    /// and before the buffers are swapped.
+    pub(crate) fn sample_if_due(&mut self, width: i32, height: i32) {
+        if self
+            .last_sample
+            .is_some_and(|at| at.elapsed() < SAMPLE_INTERVAL)
+        {
+            return;
+        }
+
+        let (sample_width, sample_height) = SAMPLE_SIZE;
+        let mut pixels = vec![0u8; (sample_width * sample_height * 4) as usize];
+
+        unsafe {
+            self.ensure_resources();
+
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.framebuffer);
+            gl::BlitFramebuffer(
+                0,
+                0,
+                width,
+                height,
+                0,
+                0,
+                sample_width,
+                sample_height,
+                gl::COLOR_BUFFER_BIT,
+                gl::LINEAR,
+            );
+
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.framebuffer);
+            gl::ReadPixels(
+                0,
+                0,
+                sample_width,
+                sample_height,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_mut_ptr().cast(),
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        self.pixels = Some(pixels);
+        self.last_sample = Some(Instant::now());
+    }
+
+    /// Palette of the last sample, if a frame has been sampled
+    pub(crate) fn palette(&self, count: usize) -> Option<Palette> {
+        self.pixels
+            .as_deref()
+            .map(|pixels| Palette::from_rgba(pixels, count))
+    }
+
+    /// Forget the last sample so the next frame is sampled (new wallpaper)
+    pub(crate) fn reset(&mut self) {
+        self.pixels = None;
+        self.last_sample = None;
+    }
+
+    /// Release GL objects; the output's context must be current
+    pub(crate) fn destroy(&mut self) {
+        unsafe {
+            if self.framebuffer != 0 {
+                gl::DeleteFramebuffers(1, &self.framebuffer);
+            }
+            if self.texture != 0 {
+                gl::DeleteTextures(1, &self.texture);
+            }
+        }
+        self.framebuffer = 0;
+        self.texture = 0;
+        self.reset();
+    }
+
+    unsafe fn ensure_resources(&mut self) {
+        if self.framebuffer != 0 {
+            return;
+        }
+
+        unsafe {
+            gl::GenTextures(1, &mut self.texture);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8 as i32,
+                SAMPLE_SIZE.0,
+                SAMPLE_SIZE.1,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                std::ptr::null(),
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+
+            gl::GenFramebuffers(1, &mut self.framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                self.texture,
+                0,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+}
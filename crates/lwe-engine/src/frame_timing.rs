@@ -1,6 +1,7 @@
 //! Frame timing and adaptive frame skip logic
 //!
 //! This module implements intelligent frame skipping to handle system overload gracefully.
+//! Sources with their own per-frame delays (animated images) are also paced here.
 
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
@@ -18,6 +19,9 @@ const RECOVERY_THRESHOLD: f64 = 0.60;
 /// Minimum consecutive frames before changing skip state
 const HYSTERESIS_FRAMES: usize = 3;
 
+/// Window the drawn frame rate is measured over
+const FRAME_RATE_WINDOW: Duration = Duration::from_secs(1);
+
 /// Frame timing tracker with adaptive skip logic
 #[derive(Debug)]
 pub struct FrameTiming {
@@ -44,6 +48,12 @@ pub struct FrameTiming {
 
     /// Last load percentage reported
     last_load_pct: f64,
+
+    /// When the next frame of a delay-paced source is due
+    next_frame_due: Option<Instant>,
+
+    /// Frames presented per second and the share dropped by the source
+    rate: FrameRate,
 }
 
 impl FrameTiming {
@@ -64,6 +74,8 @@ impl FrameTiming {
             in_skip_mode: false,
             consecutive_state_frames: 0,
             last_load_pct: 0.0,
+            next_frame_due: None,
+            rate: FrameRate::new(Instant::now()),
         }
     }
 
@@ -87,6 +99,23 @@ impl FrameTiming {
         self.frames_rendered += 1;
     }
 
+    /// Record a frame reaching the screen; `dropped` is the source's running
+    /// count of dropped frames
+    pub fn record_presented(&mut self, now: Instant, dropped: impl FnOnce() -> u64) {
+        self.rate.record_frame(now, dropped);
+    }
+
+    /// Frames presented per second and the share dropped, over the last second
+    pub fn frame_rate(&self, now: Instant) -> Option<FrameRateSample> {
+        self.rate.sample(now)
+    }
+
+    /// Average time spent drawing a frame (None = nothing drawn yet)
+    pub fn render_time(&self) -> Option<Duration> {
+        let len = u32::try_from(self.frame_durations.len()).ok()?;
+        (len > 0).then(|| self.frame_durations.iter().sum::<Duration>() / len)
+    }
+
     /// Record that a frame was skipped
     #[inline]
     pub fn record_skip(&mut self) {
@@ -140,6 +169,38 @@ impl FrameTiming {
         self.in_skip_mode
     }
 
+    /// Schedule the next frame `delay` after the one just shown was due
+    ///
+    /// Deadlines advance from the previous deadline rather than from `now`, so
+    /// render time does not accumulate into drift. After a stall longer than
+    /// `delay` the schedule restarts from `now` instead of rushing through the
+    /// missed frames. The frame budget follows the delay.
+    pub fn schedule_frame_after(&mut self, delay: Duration, now: Instant) {
+        let delay = delay.max(Duration::from_millis(1));
+        self.target_frame_duration = delay;
+        self.next_frame_due = Some(match self.next_frame_due {
+            Some(due) if now.saturating_duration_since(due) < delay => due + delay,
+            _ => now + delay,
+        });
+    }
+
+    /// Check if a delay-paced frame is due (always true before the first one)
+    #[inline]
+    pub fn is_frame_due(&self, now: Instant) -> bool {
+        self.next_frame_due.is_none_or(|due| now >= due)
+    }
+
+    /// Time left until the next delay-paced frame
+    pub fn time_until_due(&self, now: Instant) -> Option<Duration> {
+        self.next_frame_due
+            .map(|due| due.saturating_duration_since(now))
+    }
+
+    /// Forget the delay schedule, e.g. when the source changes
+    pub fn clear_frame_schedule(&mut self) {
+        self.next_frame_due = None;
+    }
+
     /// Calculate current load as percentage of frame budget
     #[inline]
     pub fn get_load_percentage(&self) -> f64 {
@@ -168,12 +229,10 @@ impl FrameTiming {
             },
             current_load_pct: self.last_load_pct * 100.0,
             in_skip_mode: self.in_skip_mode,
-            avg_frame_duration_ms: if !self.frame_durations.is_empty() {
-                let total: Duration = self.frame_durations.iter().sum();
-                (total / self.frame_durations.len() as u32).as_secs_f64() * 1000.0
-            } else {
-                0.0
-            },
+            avg_frame_duration_ms: self
+                .render_time()
+                .map_or(0.0, |duration| duration.as_secs_f64() * 1000.0),
+            frame_rate: self.rate.sample(Instant::now()),
         }
     }
 
@@ -183,6 +242,8 @@ impl FrameTiming {
         self.frames_rendered = 0;
         self.in_skip_mode = false;
         self.consecutive_state_frames = 0;
+        self.frame_durations.clear();
+        self.rate.reset(Instant::now());
     }
 }
 
@@ -196,6 +257,89 @@ pub struct FrameStats {
     pub current_load_pct: f64,
     pub in_skip_mode: bool,
     pub avg_frame_duration_ms: f64,
+    pub frame_rate: Option<FrameRateSample>,
+}
+
+/// Frames drawn per second and the share of frames dropped, measured over
+/// one-second windows
+#[derive(Debug, Clone)]
+pub struct FrameRate {
+    window_start: Instant,
+    drawn: u32,
+    dropped_at_start: u64,
+    last: Option<(Instant, FrameRateSample)>,
+}
+
+/// One measurement of [`FrameRate`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameRateSample {
+    /// Frames drawn per second
+    pub fps: f64,
+    /// Share of frames dropped instead of drawn (0.0 - 1.0)
+    pub skip_rate: f64,
+}
+
+impl FrameRate {
+    /// Start measuring at `now`
+    pub fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            drawn: 0,
+            dropped_at_start: 0,
+            last: None,
+        }
+    }
+
+    /// Record a drawn frame; `dropped` is the source's running count of
+    /// dropped frames, read only when a window closes
+    pub fn record_frame(&mut self, now: Instant, dropped: impl FnOnce() -> u64) {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed > FRAME_RATE_WINDOW * 2 {
+            // Nothing was drawn for a while (paused, still image): a window
+            // spanning the gap would understate the rate
+            self.window_start = now;
+            self.drawn = 1;
+            self.dropped_at_start = dropped();
+            return;
+        }
+
+        self.drawn += 1;
+        if elapsed < FRAME_RATE_WINDOW {
+            return;
+        }
+
+        let dropped_total = dropped();
+        let dropped = dropped_total.saturating_sub(self.dropped_at_start) as f64;
+        let drawn = f64::from(self.drawn);
+        self.last = Some((
+            now,
+            FrameRateSample {
+                fps: drawn / elapsed.as_secs_f64(),
+                skip_rate: dropped / (drawn + dropped),
+            },
+        ));
+        self.window_start = now;
+        self.drawn = 0;
+        self.dropped_at_start = dropped_total;
+    }
+
+    /// The latest measurement; nothing drawn lately reads as 0 fps
+    /// (None = no window has closed yet)
+    pub fn sample(&self, now: Instant) -> Option<FrameRateSample> {
+        let (at, sample) = self.last?;
+        if now.saturating_duration_since(at) > FRAME_RATE_WINDOW * 2 {
+            return Some(FrameRateSample {
+                fps: 0.0,
+                skip_rate: 0.0,
+            });
+        }
+        Some(sample)
+    }
+
+    /// Start over, e.g. when the source changes
+    pub fn reset(&mut self, now: Instant) {
+        *self = Self::new(now);
+    }
 }
 
 #[cfg(test)]
@@ -218,5 +362,64 @@ mod tests {
         let stats = timing.get_stats();
         assert_eq!(stats.frames_rendered, 20);
         assert_eq!(stats.frames_skipped, 0);
+        assert!(timing.render_time().unwrap() >= Duration::from_millis(10));
+
+        timing.reset_stats();
+        assert_eq!(timing.render_time(), None);
+    }
+
+    #[test]
+    fn test_frame_delay_pacing_does_not_drift() {
+        let mut timing = FrameTiming::new(60);
+        let start = Instant::now();
+        let delay = Duration::from_millis(40);
+        assert!(timing.is_frame_due(start));
+
+        timing.schedule_frame_after(delay, start);
+        assert!(!timing.is_frame_due(start + Duration::from_millis(39)));
+
+        // Shown 5ms late; the following deadline still lands on the 40ms grid
+        timing.schedule_frame_after(delay, start + Duration::from_millis(45));
+        assert_eq!(
+            timing.time_until_due(start + Duration::from_millis(45)),
+            Some(Duration::from_millis(35))
+        );
+    }
+
+    #[test]
+    fn test_frame_delay_pacing_resyncs_after_stall() {
+        let mut timing = FrameTiming::new(60);
+        let start = Instant::now();
+        let delay = Duration::from_millis(40);
+
+        timing.schedule_frame_after(delay, start);
+        let late = start + Duration::from_millis(500);
+        timing.schedule_frame_after(delay, late);
+
+        assert!(!timing.is_frame_due(late));
+        assert_eq!(timing.time_until_due(late), Some(delay));
+
+        timing.clear_frame_schedule();
+        assert!(timing.is_frame_due(late));
+    }
+
+    #[test]
+    fn test_frame_rate_counts_drawn_and_dropped_frames() {
+        let start = Instant::now();
+        let mut rate = FrameRate::new(start);
+        for frame in 1..=30 {
+            rate.record_frame(start + Duration::from_millis(frame * 33), || 10);
+        }
+        assert_eq!(rate.sample(start), None);
+
+        // The window closes at one second: 30 drawn, 10 dropped
+        rate.record_frame(start + Duration::from_secs(1), || 10);
+        let sample = rate.sample(start + Duration::from_secs(1)).unwrap();
+        assert!((sample.fps - 31.0).abs() < 0.01);
+        assert!((sample.skip_rate - 10.0 / 41.0).abs() < 0.001);
+
+        // Nothing drawn since: the output is idle
+        let idle = rate.sample(start + Duration::from_secs(4)).unwrap();
+        assert_eq!(idle.fps, 0.0);
     }
 }
@@ -0,0 +1,262 @@
+//! GPU error capture for the shared OpenGL context
+//!
+//! GL errors are polled after every frame so a broken shader or failed
+//! allocation shows up per output in diagnostics instead of as a silent
+//! black wallpaper. With `[gpu] debug` the context is created with
+//! KHR_debug and the driver's own error and warning messages are kept too.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::ffi::{c_void, CStr};
+
+use tracing::warn;
+
+/// Distinct messages kept per output, oldest dropped first
+pub const MESSAGES_PER_OUTPUT: usize = 16;
+
+/// Upper bound on glGetError calls per frame; a lost context can keep
+/// returning errors forever
+const MAX_POLLED_ERRORS: usize = 8;
+
+/// How serious a GPU message is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuSeverity {
+    Error,
+    Warning,
+}
+
+/// An error or warning raised while rendering an output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuMessage {
+    pub severity: GpuSeverity,
+    pub message: String,
+    /// How many times in a row the message was raised
+    pub count: u32,
+}
+
+impl GpuMessage {
+    pub(crate) fn new(severity: GpuSeverity, message: String) -> Self {
+        Self {
+            severity,
+            message,
+            count: 1,
+        }
+    }
+}
+
+thread_local! {
+    /// Messages from the KHR_debug callback since the last `take_messages`
+    static DEBUG_MESSAGES: RefCell<Vec<GpuMessage>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Route driver messages to the debug callback; needs a debug context and
+/// GL functions loaded. Returns false when KHR_debug is missing.
+pub(crate) fn enable_debug_output() -> bool {
+    if !gl::DebugMessageCallback::is_loaded() || !gl::DebugMessageControl::is_loaded() {
+        return false;
+    }
+
+    unsafe {
+        gl::Enable(gl::DEBUG_OUTPUT);
+        // Synchronous delivery attributes each message to the output being drawn
+        gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+        gl::DebugMessageCallback(Some(debug_callback), std::ptr::null());
+        gl::DebugMessageControl(
+            gl::DONT_CARE,
+            gl::DONT_CARE,
+            gl::DEBUG_SEVERITY_NOTIFICATION,
+            0,
+            std::ptr::null(),
+            gl::FALSE,
+        );
+    }
+    true
+}
+
+/// Debug callback messages and pending GL errors raised since the last call
+pub(crate) fn take_messages() -> Vec<GpuMessage> {
+    if !gl::GetError::is_loaded() {
+        return Vec::new();
+    }
+
+    let mut messages = DEBUG_MESSAGES.with(|messages| std::mem::take(&mut *messages.borrow_mut()));
+
+    for _ in 0..MAX_POLLED_ERRORS {
+        let code = unsafe { gl::GetError() };
+        if code == gl::NO_ERROR {
+            break;
+        }
+        messages.push(GpuMessage::new(
+            GpuSeverity::Error,
+            format!("{} (0x{code:04X})", error_name(code)),
+        ));
+    }
+    messages
+}
+
+/// Why the GPU reset the current context, or None while it is intact; only
+/// robust contexts are told about resets
+pub(crate) fn reset_status() -> Option<&'static str> {
+    if !gl::GetGraphicsResetStatus::is_loaded() {
+        return None;
+    }
+
+    match unsafe { gl::GetGraphicsResetStatus() } {
+        gl::NO_ERROR => None,
+        gl::GUILTY_CONTEXT_RESET => Some("a GPU reset caused by LWE's rendering"),
+        gl::INNOCENT_CONTEXT_RESET => Some("a GPU reset caused by another application"),
+        _ => Some("a GPU reset of unknown cause"),
+    }
+}
+
+extern "system" fn debug_callback(
+    _source: gl::types::GLenum,
+    gltype: gl::types::GLenum,
+    _id: gl::types::GLuint,
+    severity: gl::types::GLenum,
+    _length: gl::types::GLsizei,
+    message: *const gl::types::GLchar,
+    _user_param: *mut c_void,
+) {
+    if message.is_null() {
+        return;
+    }
+    let Some(severity) = severity_for(gltype, severity) else {
+        return;
+    };
+
+    let message = unsafe { CStr::from_ptr(message) }
+        .to_string_lossy()
+        .trim_end()
+        .to_string();
+    DEBUG_MESSAGES.with(|messages| {
+        messages
+            .borrow_mut()
+            .push(GpuMessage::new(severity, message))
+    });
+}
+
+/// Errors are error-type or high-severity messages; notifications are dropped
+fn severity_for(gltype: gl::types::GLenum, severity: gl::types::GLenum) -> Option<GpuSeverity> {
+    if severity == gl::DEBUG_SEVERITY_NOTIFICATION {
+        None
+    } else if gltype == gl::DEBUG_TYPE_ERROR || severity == gl::DEBUG_SEVERITY_HIGH {
+        Some(GpuSeverity::Error)
+    } else {
+        Some(GpuSeverity::Warning)
+    }
+}
+
+fn error_name(code: gl::types::GLenum) -> &'static str {
+    match code {
+        gl::INVALID_ENUM => "GL_INVALID_ENUM",
+        gl::INVALID_VALUE => "GL_INVALID_VALUE",
+        gl::INVALID_OPERATION => "GL_INVALID_OPERATION",
+        gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+        gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+        gl::STACK_UNDERFLOW => "GL_STACK_UNDERFLOW",
+        gl::STACK_OVERFLOW => "GL_STACK_OVERFLOW",
+        gl::CONTEXT_LOST => "GL_CONTEXT_LOST",
+        _ => "Unknown GL error",
+    }
+}
+
+/// Recent GPU messages per output
+#[derive(Debug, Default)]
+pub(crate) struct GpuMessageLog {
+    outputs: HashMap<String, VecDeque<GpuMessage>>,
+}
+
+impl GpuMessageLog {
+    /// Keep messages raised while drawing `output`, logging each new one once
+    /// and counting repeats of the latest
+    pub(crate) fn record(&mut self, output: &str, messages: Vec<GpuMessage>) {
+        if messages.is_empty() {
+            return;
+        }
+
+        let kept = self.outputs.entry(output.to_string()).or_default();
+        for message in messages {
+            if let Some(last) = kept
+                .back_mut()
+                .filter(|last| last.severity == message.severity && last.message == message.message)
+            {
+                last.count = last.count.saturating_add(1);
+                continue;
+            }
+
+            warn!(
+                "GPU {:?} on {}: {}",
+                message.severity, output, message.message
+            );
+            if kept.len() == MESSAGES_PER_OUTPUT {
+                kept.pop_front();
+            }
+            kept.push_back(message);
+        }
+    }
+
+    /// Forget an output's messages once its wallpaper is replaced or cleared
+    pub(crate) fn clear(&mut self, output: &str) {
+        self.outputs.remove(output);
+    }
+
+    pub(crate) fn snapshot(&self) -> HashMap<String, Vec<GpuMessage>> {
+        self.outputs
+            .iter()
+            .map(|(output, messages)| (output.clone(), messages.iter().cloned().collect()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(message: &str) -> GpuMessage {
+        GpuMessage::new(GpuSeverity::Error, message.to_string())
+    }
+
+    #[test]
+    fn test_repeated_messages_are_counted_and_old_ones_dropped() {
+        let mut log = GpuMessageLog::default();
+        log.record("DP-1", vec![error("GL_INVALID_OPERATION (0x0502)")]);
+        log.record("DP-1", vec![error("GL_INVALID_OPERATION (0x0502)")]);
+
+        let snapshot = log.snapshot();
+        assert_eq!(snapshot["DP-1"].len(), 1);
+        assert_eq!(snapshot["DP-1"][0].count, 2);
+
+        let many = (0..MESSAGES_PER_OUTPUT + 2)
+            .map(|index| error(&format!("shader {index}")))
+            .collect();
+        log.record("DP-1", many);
+
+        let kept = &log.snapshot()["DP-1"];
+        assert_eq!(kept.len(), MESSAGES_PER_OUTPUT);
+        assert_eq!(kept[0].message, "shader 2");
+
+        log.clear("DP-1");
+        assert!(log.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_debug_severity_maps_to_errors_and_warnings() {
+        assert_eq!(
+            severity_for(gl::DEBUG_TYPE_ERROR, gl::DEBUG_SEVERITY_MEDIUM),
+            Some(GpuSeverity::Error)
+        );
+        assert_eq!(
+            severity_for(gl::DEBUG_TYPE_PERFORMANCE, gl::DEBUG_SEVERITY_HIGH),
+            Some(GpuSeverity::Error)
+        );
+        assert_eq!(
+            severity_for(gl::DEBUG_TYPE_PERFORMANCE, gl::DEBUG_SEVERITY_LOW),
+            Some(GpuSeverity::Warning)
+        );
+        assert_eq!(
+            severity_for(gl::DEBUG_TYPE_OTHER, gl::DEBUG_SEVERITY_NOTIFICATION),
+            None
+        );
+    }
+}
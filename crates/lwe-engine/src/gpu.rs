@@ -0,0 +1,237 @@
+//! DRM render nodes
+//!
+//! On hybrid laptops the compositor usually runs on the integrated GPU while
+//! EGL may pick either one. The engine can be pointed at a render node such
+//! as `/dev/dri/renderD129`; this module lists the nodes the kernel exposes
+//! and which hardware decoders can use them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// One `/dev/dri/renderD*` node and the device behind it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderNode {
+    /// Device path, e.g. `/dev/dri/renderD128`
+    pub path: PathBuf,
+    /// Kernel driver, e.g. `amdgpu`, `i915` or `nvidia`
+    pub driver: Option<String>,
+    /// PCI slot, e.g. `0000:03:00.0`
+    pub pci_slot: Option<String>,
+    /// PCI vendor and device ids, e.g. `1002:1681`
+    pub pci_id: Option<String>,
+}
+
+/// Render nodes present on this system, in node order
+pub fn render_nodes() -> Vec<RenderNode> {
+    render_nodes_in(Path::new("/dev/dri"), Path::new("/sys/class/drm"))
+}
+
+fn render_nodes_in(dev_dri: &Path, sys_class_drm: &Path) -> Vec<RenderNode> {
+    let Ok(entries) = fs::read_dir(dev_dri) else {
+        return Vec::new();
+    };
+
+    let mut nodes = entries
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let minor = name.strip_prefix("renderD")?.parse::<u32>().ok()?;
+            Some((minor, name))
+        })
+        .collect::<Vec<_>>();
+    nodes.sort_unstable();
+
+    nodes
+        .into_iter()
+        .map(|(_, name)| {
+            let device = sys_class_drm.join(&name).join("device");
+            let link_name = |link: &str| {
+                fs::read_link(device.join(link))
+                    .ok()
+                    .and_then(|target| Some(target.file_name()?.to_str()?.to_string()))
+            };
+            let read_id = |file: &str| {
+                fs::read_to_string(device.join(file))
+                    .ok()
+                    .map(|id| id.trim().trim_start_matches("0x").to_string())
+            };
+
+            RenderNode {
+                path: dev_dri.join(&name),
+                driver: link_name("driver"),
+                pci_slot: fs::canonicalize(&device)
+                    .ok()
+                    .and_then(|target| Some(target.file_name()?.to_str()?.to_string())),
+                pci_id: read_id("vendor")
+                    .zip(read_id("device"))
+                    .map(|(vendor, device)| format!("{vendor}:{device}")),
+            }
+        })
+        .collect()
+}
+
+/// VAAPI driver behind a render node and the codecs it decodes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaapiSupport {
+    /// Driver description, e.g. "Mesa Gallium driver 24.0.5 for AMD Radeon 780M"
+    pub driver: String,
+    /// Profiles with a decode entrypoint, e.g. "H264Main" or "AV1Profile0"
+    pub decode_profiles: Vec<String>,
+}
+
+/// Ask `vainfo` which VAAPI driver loads on `node` and what it decodes
+pub fn vaapi_support(node: &Path) -> Result<VaapiSupport, String> {
+    let output = Command::new("vainfo")
+        .args(["--display", "drm", "--device"])
+        .arg(node)
+        .output()
+        .map_err(|e| format!("vainfo could not run: {e}"))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_vainfo(&stdout).ok_or_else(|| {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match stderr.lines().rev().find(|line| !line.trim().is_empty()) {
+            Some(line) => line.trim().to_string(),
+            None => "No VAAPI driver loaded".to_string(),
+        }
+    })
+}
+
+fn parse_vainfo(output: &str) -> Option<VaapiSupport> {
+    let driver = output
+        .lines()
+        .find_map(|line| line.split_once("Driver version:"))
+        .map(|(_, driver)| driver.trim().to_string())?;
+    let mut decode_profiles = output
+        .lines()
+        .filter(|line| line.contains("VAEntrypointVLD"))
+        .filter_map(|line| {
+            let profile = line.split(':').next()?.trim();
+            Some(
+                profile
+                    .strip_prefix("VAProfile")
+                    .unwrap_or(profile)
+                    .to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    decode_profiles.dedup();
+    Some(VaapiSupport {
+        driver,
+        decode_profiles,
+    })
+}
+
+/// The NVIDIA kernel driver version when it and the NVDEC library are
+/// installed
+pub fn nvdec_support() -> Result<String, String> {
+    let version = fs::read_to_string("/proc/driver/nvidia/version")
+        .map_err(|_| "The NVIDIA kernel driver is not loaded".to_string())?;
+    let version = parse_nvidia_version(&version).unwrap_or_else(|| "unknown".to_string());
+    if !library_loads(c"libnvcuvid.so.1") {
+        return Err(format!(
+            "NVIDIA driver {version} is loaded but libnvcuvid.so.1 is missing"
+        ));
+    }
+    Ok(version)
+}
+
+/// Driver version from the `NVRM version:` line of /proc/driver/nvidia/version
+fn parse_nvidia_version(contents: &str) -> Option<String> {
+    let line = contents
+        .lines()
+        .find(|line| line.starts_with("NVRM version:"))?;
+    line.split_whitespace()
+        .find(|word| word.chars().next().is_some_and(|c| c.is_ascii_digit()) && word.contains('.'))
+        .map(str::to_string)
+}
+
+/// Whether the dynamic loader finds and loads `name`
+pub(crate) fn library_loads(name: &std::ffi::CStr) -> bool {
+    let handle = unsafe { libc::dlopen(name.as_ptr(), libc::RTLD_LAZY | libc::RTLD_LOCAL) };
+    if handle.is_null() {
+        return false;
+    }
+    unsafe { libc::dlclose(handle) };
+    true
+}
+
+/// Resolve `by-path` and other symlinks so a configured node can be
+/// compared with what EGL reports
+pub(crate) fn canonical_render_node(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_nodes_read_driver_and_pci_details() {
+        let root = std::env::temp_dir().join(format!("lwe-gpu-{}", std::process::id()));
+        let dev_dri = root.join("dev/dri");
+        let sys_drm = root.join("sys/class/drm");
+        let pci = root.join("sys/devices/pci0000:00/0000:03:00.0");
+        let drivers = root.join("sys/bus/pci/drivers/amdgpu");
+        fs::create_dir_all(&dev_dri).unwrap();
+        fs::create_dir_all(&pci).unwrap();
+        fs::create_dir_all(&drivers).unwrap();
+        for name in ["card0", "renderD129", "renderD128"] {
+            fs::write(dev_dri.join(name), "").unwrap();
+        }
+        fs::write(pci.join("vendor"), "0x1002\n").unwrap();
+        fs::write(pci.join("device"), "0x1681\n").unwrap();
+        std::os::unix::fs::symlink(&drivers, pci.join("driver")).unwrap();
+        fs::create_dir_all(sys_drm.join("renderD128")).unwrap();
+        std::os::unix::fs::symlink(&pci, sys_drm.join("renderD128/device")).unwrap();
+
+        let nodes = render_nodes_in(&dev_dri, &sys_drm);
+        assert_eq!(
+            nodes,
+            vec![
+                RenderNode {
+                    path: dev_dri.join("renderD128"),
+                    driver: Some("amdgpu".to_string()),
+                    pci_slot: Some("0000:03:00.0".to_string()),
+                    pci_id: Some("1002:1681".to_string()),
+                },
+                RenderNode {
+                    path: dev_dri.join("renderD129"),
+                    driver: None,
+                    pci_slot: None,
+                    pci_id: None,
+                },
+            ]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_vainfo_and_nvidia_versions_parse() {
+        let vainfo = "vainfo: VA-API version: 1.20 (libva 2.20.0)\n\
+            vainfo: Driver version: Mesa Gallium driver 24.0.5 for AMD Radeon 780M\n\
+            vainfo: Supported profile and entrypoints\n\
+            \tVAProfileH264Main               :\tVAEntrypointVLD\n\
+            \tVAProfileH264Main               :\tVAEntrypointEncSlice\n\
+            \tVAProfileAV1Profile0            :\tVAEntrypointVLD\n\
+            \tVAProfileNone                   :\tVAEntrypointVideoProc\n";
+        assert_eq!(
+            parse_vainfo(vainfo),
+            Some(VaapiSupport {
+                driver: "Mesa Gallium driver 24.0.5 for AMD Radeon 780M".to_string(),
+                decode_profiles: vec!["H264Main".to_string(), "AV1Profile0".to_string()],
+            })
+        );
+        assert_eq!(
+            parse_vainfo("libva error: vaGetDriverNameByIndex() failed\n"),
+            None
+        );
+
+        assert_eq!(
+            parse_nvidia_version(
+                "NVRM version: NVIDIA UNIX x86_64 Kernel Module  550.67  Tue Mar 12 23:54:15 UTC 2024\n"
+            ),
+            Some("550.67".to_string())
+        );
+    }
+}
@@ -0,0 +1,382 @@
+//! Letterbox fill pass
+//!
+//! mpv clears the bars around a Contain/Centre wallpaper to its background
+//! color, which covers solid fills. Blurred and dominant-color fills need the
+//! video itself, so after mpv has drawn the frame the video rectangle is
+//! downsampled into a tiny framebuffer and either stretched back over the
+//! bars (the linear upscale is the blur) or sampled for its most common color.
+//! The AmbientFill layout always blurs, from an even smaller sample cropped
+//! to the output's aspect ratio so the backdrop is a scaled-up copy of the
+//! frame rather than a stretched one.
+
+use std::time::{Duration, Instant};
+
+use lwe_core::{LayoutMode, LetterboxFill};
+
+/// Size of the downsampled copy of the video
+const SAMPLE_SIZE: (i32, i32) = (32, 18);
+
+/// Smaller sample for AmbientFill, which wants a much heavier blur
+const AMBIENT_SAMPLE_SIZE: (i32, i32) = (12, 7);
+
+/// How often the dominant color is re-sampled; `glReadPixels` stalls the pipeline
+const DOMINANT_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Rectangle as (x, y, width, height)
+type Rect = (i32, i32, i32, i32);
+
+/// GL resources and cached state for one output's letterbox fill
+pub(crate) struct LetterboxPass {
+    fill: LetterboxFill,
+    /// Sample framebuffer size as (width, height)
+    sample: (i32, i32),
+    /// Crop the sample to the output's aspect ratio (AmbientFill)
+    cover: bool,
+    framebuffer: u32,
+    texture: u32,
+    dominant: [u8; 3],
+    last_sample: Option<Instant>,
+}
+
+impl LetterboxPass {
+    /// A pass is only needed when bars can appear and the fill is not a solid color
+    ///
+    /// AmbientFill always gets a blur pass whatever the fill.
+    pub(crate) fn for_fill(fill: LetterboxFill, layout: LayoutMode) -> Option<Self> {
+        let (fill, sample, cover) = match layout {
+            LayoutMode::AmbientFill => (LetterboxFill::Blur, AMBIENT_SAMPLE_SIZE, true),
+            LayoutMode::Contain | LayoutMode::Centre
+                if !matches!(fill, LetterboxFill::Color(_)) =>
+            {
+                (fill, SAMPLE_SIZE, false)
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            fill,
+            sample,
+            cover,
+            framebuffer: 0,
+            texture: 0,
+            dominant: [0, 0, 0],
+            last_sample: None,
+        })
+    }
+
+    /// Paint the bars around `video_rect` (top-left origin, output pixels)
+    ///
+    /// Must run with the output's context current, after mpv rendered into
+    /// the default framebuffer and before the buffers are swapped.
+    pub(crate) fn draw(&mut self, video_rect: Rect, width: i32, height: i32) {
+        let bars = bar_rects(video_rect, width, height);
+        if bars.is_empty() {
+            return;
+        }
+        let Some(source) = visible_rect(video_rect, width, height) else {
+            return;
+        };
+
+        unsafe {
+            self.ensure_resources();
+
+            match self.fill {
+                LetterboxFill::Blur => {
+                    let source = if self.cover {
+                        cover_crop(source, width, height)
+                    } else {
+                        source
+                    };
+                    self.downsample(source, height);
+                    self.blit_over(&bars, width, height);
+                }
+                LetterboxFill::Dominant => {
+                    if self
+                        .last_sample
+                        .is_none_or(|at| at.elapsed() >= DOMINANT_SAMPLE_INTERVAL)
+                    {
+                        self.downsample(source, height);
+                        self.dominant = self.read_dominant();
+                        self.last_sample = Some(Instant::now());
+                    }
+                    clear_bars(&bars, self.dominant, height);
+                }
+                LetterboxFill::Color(color) => clear_bars(&bars, color, height),
+            }
+
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    /// Release GL objects; the output's context must be current
+    pub(crate) fn destroy(&mut self) {
+        unsafe {
+            if self.framebuffer != 0 {
+                gl::DeleteFramebuffers(1, &self.framebuffer);
+            }
+            if self.texture != 0 {
+                gl::DeleteTextures(1, &self.texture);
+            }
+        }
+        self.framebuffer = 0;
+        self.texture = 0;
+        self.last_sample = None;
+    }
+
+    unsafe fn ensure_resources(&mut self) {
+        if self.framebuffer != 0 {
+            return;
+        }
+
+        unsafe {
+            gl::GenTextures(1, &mut self.texture);
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8 as i32,
+                self.sample.0,
+                self.sample.1,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                std::ptr::null(),
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+
+            gl::GenFramebuffers(1, &mut self.framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                self.texture,
+                0,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    /// Shrink the visible video into the sample framebuffer
+    unsafe fn downsample(&self, source: Rect, height: i32) {
+        let (x, y, w, h) = to_gl(source, height);
+
+        unsafe {
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.framebuffer);
+            gl::BlitFramebuffer(
+                x,
+                y,
+                x + w,
+                y + h,
+                0,
+                0,
+                self.sample.0,
+                self.sample.1,
+                gl::COLOR_BUFFER_BIT,
+                gl::LINEAR,
+            );
+        }
+    }
+
+    /// Stretch the sample over the whole output, clipped to each bar
+    unsafe fn blit_over(&self, bars: &[Rect], width: i32, height: i32) {
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.framebuffer);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
+            gl::Enable(gl::SCISSOR_TEST);
+            for &bar in bars {
+                let (x, y, w, h) = to_gl(bar, height);
+                gl::Scissor(x, y, w, h);
+                gl::BlitFramebuffer(
+                    0,
+                    0,
+                    self.sample.0,
+                    self.sample.1,
+                    0,
+                    0,
+                    width,
+                    height,
+                    gl::COLOR_BUFFER_BIT,
+                    gl::LINEAR,
+                );
+            }
+        }
+    }
+
+    unsafe fn read_dominant(&self) -> [u8; 3] {
+        let (sample_width, sample_height) = self.sample;
+        let mut pixels = vec![0u8; (sample_width * sample_height * 4) as usize];
+
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.framebuffer);
+            gl::ReadPixels(
+                0,
+                0,
+                sample_width,
+                sample_height,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_mut_ptr().cast(),
+            );
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+        }
+
+        dominant_color(&pixels)
+    }
+}
+
+unsafe fn clear_bars(bars: &[Rect], color: [u8; 3], height: i32) {
+    let [r, g, b] = color.map(|channel| f32::from(channel) / 255.0);
+
+    unsafe {
+        gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
+        gl::Enable(gl::SCISSOR_TEST);
+        gl::ClearColor(r, g, b, 1.0);
+        for &bar in bars {
+            let (x, y, w, h) = to_gl(bar, height);
+            gl::Scissor(x, y, w, h);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+        }
+    }
+}
+
+/// Flip a top-left origin rectangle into GL's bottom-left origin
+fn to_gl((x, y, w, h): Rect, height: i32) -> Rect {
+    (x, height - (y + h), w, h)
+}
+
+/// Part of the video rectangle that lands on the output
+fn visible_rect((x, y, w, h): Rect, width: i32, height: i32) -> Option<Rect> {
+    let (x0, y0) = (x.max(0), y.max(0));
+    let (x1, y1) = ((x + w).min(width), (y + h).min(height));
+
+    (x1 > x0 && y1 > y0).then_some((x0, y0, x1 - x0, y1 - y0))
+}
+
+/// Centre of `rect` cropped to the output's aspect ratio
+fn cover_crop((x, y, w, h): Rect, width: i32, height: i32) -> Rect {
+    let (w64, h64) = (i64::from(w), i64::from(h));
+    let (width, height) = (i64::from(width.max(1)), i64::from(height.max(1)));
+
+    if w64 * height > h64 * width {
+        let cropped = (h64 * width / height) as i32;
+        (x + (w - cropped) / 2, y, cropped.max(1), h)
+    } else {
+        let cropped = (w64 * height / width) as i32;
+        (x, y + (h - cropped) / 2, w, cropped.max(1))
+    }
+}
+
+/// Output areas not covered by the video, top-left origin
+fn bar_rects(video_rect: Rect, width: i32, height: i32) -> Vec<Rect> {
+    let Some((x, y, w, h)) = visible_rect(video_rect, width, height) else {
+        return vec![(0, 0, width, height)];
+    };
+
+    [
+        (0, 0, width, y),
+        (0, y + h, width, height - (y + h)),
+        (0, y, x, h),
+        (x + w, y, width - (x + w), h),
+    ]
+    .into_iter()
+    .filter(|&(_, _, w, h)| w > 0 && h > 0)
+    .collect()
+}
+
+/// Most common color in RGBA pixels, bucketed to 4 bits per channel and
+/// averaged within the winning bucket
+fn dominant_color(rgba: &[u8]) -> [u8; 3] {
+    let mut buckets = vec![(0u32, [0u32; 3]); 16 * 16 * 16];
+
+    for pixel in rgba.chunks_exact(4) {
+        let index = (usize::from(pixel[0] >> 4) << 8)
+            | (usize::from(pixel[1] >> 4) << 4)
+            | usize::from(pixel[2] >> 4);
+        let (count, sums) = &mut buckets[index];
+        *count += 1;
+        for channel in 0..3 {
+            sums[channel] += u32::from(pixel[channel]);
+        }
+    }
+
+    buckets
+        .iter()
+        .max_by_key(|(count, _)| *count)
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, sums)| sums.map(|sum| (sum / count) as u8))
+        .unwrap_or([0, 0, 0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_rects_for_letterbox_and_pillarbox() {
+        // 16:9 video letterboxed on a 16:10 output
+        assert_eq!(
+            bar_rects((0, 60, 1920, 1080), 1920, 1200),
+            vec![(0, 0, 1920, 60), (0, 1140, 1920, 60)]
+        );
+        // Centred video smaller than the output on both axes
+        assert_eq!(bar_rects((560, 240, 800, 600), 1920, 1080).len(), 4);
+        // Video that covers the output leaves nothing to fill
+        assert!(bar_rects((-100, 0, 2120, 1080), 1920, 1080).is_empty());
+    }
+
+    #[test]
+    fn test_to_gl_flips_vertically() {
+        assert_eq!(to_gl((0, 0, 1920, 60), 1200), (0, 1140, 1920, 60));
+        assert_eq!(to_gl((0, 1140, 1920, 60), 1200), (0, 0, 1920, 60));
+    }
+
+    #[test]
+    fn test_dominant_color_picks_largest_bucket() {
+        let mut pixels = Vec::new();
+        for _ in 0..5 {
+            pixels.extend_from_slice(&[200, 10, 10, 255]);
+            pixels.extend_from_slice(&[202, 12, 14, 255]);
+        }
+        for _ in 0..3 {
+            pixels.extend_from_slice(&[0, 0, 255, 255]);
+        }
+
+        assert_eq!(dominant_color(&pixels), [201, 11, 12]);
+        assert_eq!(dominant_color(&[]), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_solid_fills_and_fill_layouts_skip_the_pass() {
+        assert!(LetterboxPass::for_fill(LetterboxFill::Blur, LayoutMode::Fill).is_none());
+        assert!(
+            LetterboxPass::for_fill(LetterboxFill::Color([9, 9, 9]), LayoutMode::Contain).is_none()
+        );
+        assert!(LetterboxPass::for_fill(LetterboxFill::Dominant, LayoutMode::Centre).is_some());
+    }
+
+    #[test]
+    fn test_ambient_fill_always_blurs_a_cover_crop() {
+        let pass =
+            LetterboxPass::for_fill(LetterboxFill::Color([0, 0, 0]), LayoutMode::AmbientFill)
+                .unwrap();
+        assert_eq!(pass.fill, LetterboxFill::Blur);
+        assert!(pass.cover);
+
+        // Portrait video pillarboxed on a 16:9 output: keep a 16:9 band
+        assert_eq!(
+            cover_crop((656, 0, 608, 1080), 1920, 1080),
+            (656, 369, 608, 342)
+        );
+        // Wide video letterboxed on a 16:10 output: trim the sides
+        assert_eq!(
+            cover_crop((0, 60, 1920, 1080), 1920, 1200),
+            (96, 60, 1728, 1080)
+        );
+    }
+}
@@ -3,7 +3,21 @@
 //! This crate provides the core rendering functionality:
 //! - Wayland layer-shell surface management
 //! - MPV video playback integration  
+//! - Animated GIF/APNG/WebP playback without mpv
 //! - EGL/OpenGL rendering
+//! - Frame readback for wallpaper color palettes and snapshots
+//! - Source probing before a wallpaper replaces the current one
+//! - Crossfade, slide and zoom transitions between wallpapers
+//! - One shared decoder for outputs showing the same video
+//! - Software-rendered previews for the desktop shell
+//! - Deterministic frame capture for visual regression tests
+//! - Render node selection on multi-GPU systems
+//! - Compositor, EGL, hardware decoder and libmpv probes for `lwe check`
+//! - Per-output GL error capture, with KHR_debug output in debug mode
+//! - cgroup resource limits and usage under systemd
+//! - Render, decode and process CPU/memory stats
+//! - Watchdog that restarts a crashed engine with its wallpapers
+//! - X11 root-window fallback when no Wayland display is present (optional)
 //! - Vulkan rendering (optional)
 //!
 //! # Architecture
@@ -13,33 +27,73 @@
 //!     ├── WallpaperSession (per-output)
 //!     │   ├── LayerSurface (wlr-layer-shell)
 //!     │   ├── EglContext (OpenGL rendering)
-//!     │   └── MpvPlayer (video decoding)
+//!     │   ├── MpvPlayer (video decoding)
+//!     │   ├── AnimatedImagePlayer (GIF/APNG/WebP decoding)
+//!     │   └── StillImage (JPEG/PNG/WebP, drawn once)
 //!     └── OutputManager (output tracking)
 //! ```
 
+pub mod animated_image;
+pub mod capabilities;
+pub mod capture;
+pub mod cgroup;
 pub mod egl;
 pub mod engine;
+mod frame_sample;
 pub mod frame_timing;
+pub mod gl_debug;
+pub mod gpu;
+mod letterbox;
 pub mod mpv;
+pub mod preview;
+pub mod probe;
+pub mod scheduling;
+mod shared_decode;
+mod snapshot;
+pub mod stats;
+pub mod still_image;
+pub mod systemd;
+mod transition;
 pub mod wayland;
+pub mod workarounds;
+#[cfg(feature = "backend-x11")]
+pub mod x11;
 
 // Re-exports - Engine API
 pub use engine::{
-    spawn_engine, EngineCommand, EngineConfig, EngineEvent, EngineHandle, EngineStatus,
-    WallpaperSession,
+    spawn_engine, EngineCommand, EngineConfig, EngineEvent, EngineHandle, EngineMessage,
+    EngineRequest, EngineStatus, EventChannelStats, Fallback, OutputPlayback, OutputStats,
+    OutputVideoOverrides, PlaybackState, RequestId, WallpaperSession, EVENT_BACKLOG_CAPACITY,
+    EVENT_CHANNEL_CAPACITY,
 };
 
 // Re-export calloop Sender for IPC integration
 pub use calloop::channel::Sender as CommandSender;
 
 // Re-exports - Low-level components
-pub use egl::{EglContext, EglWindow};
-pub use frame_timing::FrameTiming;
-pub use mpv::{MpvPlayer, VideoConfig};
+pub use animated_image::AnimatedImagePlayer;
+pub use capabilities::{probe_system, SystemCapabilities, WaylandGlobal};
+pub use capture::{capture_frames, CaptureConfig};
+pub use cgroup::{CgroupUsage, ResourceLimits};
+pub use egl::{EglContext, EglInfo, EglWindow};
+pub use frame_timing::{FrameRate, FrameRateSample, FrameTiming};
+pub use gl_debug::{GpuMessage, GpuSeverity};
+pub use gpu::{render_nodes, RenderNode, VaapiSupport};
+pub use mpv::{
+    is_audio_option, is_live_source, MediaInfo, MpvPlayer, MpvVersion, Transport, VideoConfig,
+};
+pub use preview::PreviewPlayer;
+pub use probe::probe_source;
+pub use scheduling::SchedulingConfig;
+pub use stats::{process_usage, ProcessUsage};
+pub use still_image::StillImage;
 pub use wayland::{LayerSurface, OutputManager};
+pub use workarounds::{
+    CompositorKind, CompositorVersion, DetectedCompositor, WorkaroundOverrides, Workarounds,
+};
 
 // Re-exports from lwe-core
 pub use lwe_core::{
-    calculate_layout, HdrMetadata, HdrMode, HwdecMode, LayoutMode, LayoutTransform, OutputInfo,
-    RenderBackend, ToneMappingConfig,
+    calculate_layout, HdrMetadata, HdrMode, HwdecMode, LayoutMode, LayoutTransform, LetterboxFill,
+    OutputInfo, RenderBackend, ToneMappingConfig, TransitionKind,
 };
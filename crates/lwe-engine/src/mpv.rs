@@ -3,30 +3,111 @@
 //! This module provides a high-level wrapper around libmpv for video playback
 //! with hardware decoding, HDR tone mapping, and OpenGL rendering support.
 
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::ffi::{c_char, c_void, CString};
+use std::path::{Path, PathBuf};
 use std::ptr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use tracing::{debug, info, warn};
 
 use lwe_core::{
-    hdr::{parse_colorspace, parse_transfer_function, HdrMetadata, HdrMode, ToneMappingConfig},
-    HwdecMode, LayoutMode, OutputInfo,
+    hdr::{
+        parse_colorspace, parse_transfer_function, HdrMetadata, HdrMode, HdrOutput,
+        ToneMappingConfig,
+    },
+    ColorAdjust, HwdecMode, LayoutMode, LetterboxFill, OutputHdrCapabilities, OutputInfo,
+    TransitionKind,
 };
 
 use crate::egl::EglContext;
 
 // mpv_render_param_type constants (from libmpv/render.h)
-const MPV_RENDER_PARAM_INVALID: u32 = 0;
-const MPV_RENDER_PARAM_API_TYPE: u32 = 1;
+pub(crate) const MPV_RENDER_PARAM_INVALID: u32 = 0;
+pub(crate) const MPV_RENDER_PARAM_API_TYPE: u32 = 1;
 const MPV_RENDER_PARAM_OPENGL_INIT_PARAMS: u32 = 2;
 const MPV_RENDER_PARAM_OPENGL_FBO: u32 = 3;
 const MPV_RENDER_PARAM_FLIP_Y: u32 = 4;
 
 // mpv_render_update_flag constants
-const MPV_RENDER_UPDATE_FRAME: u64 = 1;
+pub(crate) const MPV_RENDER_UPDATE_FRAME: u64 = 1;
+
+/// Frame rate videos are decoded at; a wallpaper gains little from more
+const DEFAULT_DECODE_FPS: u32 = 30;
+
+/// URL schemes of cameras and live feeds, which are played as they arrive
+const LIVE_SCHEMES: [&str; 8] = [
+    "rtsp://", "rtsps://", "rtmp://", "rtmps://", "srt://", "udp://", "rtp://", "av://",
+];
+
+/// Low-latency profile for live sources: option, live value, file value.
+/// A feed that stops sending times out instead of freezing on its last frame.
+const LIVE_OPTIONS: [(&str, &str, &str); 6] = [
+    ("cache", "no", "yes"),
+    ("untimed", "yes", "no"),
+    ("demuxer-readahead-secs", "0", "2"),
+    ("demuxer-lavf-o", "fflags=+nobuffer", ""),
+    ("demuxer-lavf-analyzeduration", "0.1", "0"),
+    ("network-timeout", "5", "60"),
+];
+
+/// Whether `path` is a camera or live feed rather than a file: an RTSP,
+/// RTMP, SRT, UDP or RTP URL, an `av://` device, or a V4L2 webcam such as
+/// `/dev/video0`
+pub fn is_live_source(path: &Path) -> bool {
+    let source = path.to_string_lossy();
+    LIVE_SCHEMES.iter().any(|scheme| source.starts_with(scheme)) || source.starts_with("/dev/video")
+}
+
+/// Whether the mpv option `name` can turn wallpaper audio on, which the
+/// system policy may forbid
+pub fn is_audio_option(name: &str) -> bool {
+    let name = name.trim_start_matches("--").to_ascii_lowercase();
+    matches!(name.as_str(), "aid" | "alang" | "ao" | "mute" | "volume")
+        || name == "audio"
+        || name.starts_with("audio-")
+        || name.starts_with("no-audio")
+}
+
+/// What mpv is asked to open for `path`; webcams go through libavdevice
+fn mpv_source(path: &str) -> Cow<'_, str> {
+    if path.starts_with("/dev/video") {
+        Cow::Owned(format!("av://v4l2:{path}"))
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
+/// mpv `hwdec` value for a decode mode
+fn hwdec_option(mode: HwdecMode) -> &'static str {
+    match mode {
+        HwdecMode::Auto => "vaapi-copy", // VAAPI copy-back for AMD APU
+        HwdecMode::Force => "vaapi",     // Direct VAAPI
+        HwdecMode::No => "no",
+    }
+}
+
+/// mpv `vf` chain capping the decoded frame rate (0 = no cap)
+fn fps_filter(fps: u32) -> String {
+    if fps == 0 {
+        String::new()
+    } else {
+        format!("fps={fps}")
+    }
+}
+
+/// mpv equalizer properties for a color correction; mpv takes -100 to 100
+/// with 0 unchanged, so 0.9 brightness is `brightness=-10`
+fn equalizer(color: ColorAdjust) -> [(&'static str, String); 4] {
+    color.values().map(|(name, value)| {
+        let level = ((value - 1.0) * 100.0).round().clamp(-100.0, 100.0) as i64;
+        (name, level.to_string())
+    })
+}
 
 // OpenGL get_proc_address callback wrapper
 extern "C" fn get_proc_address_wrapper(ctx: *mut c_void, name: *const c_char) -> *mut c_void {
@@ -42,7 +123,7 @@ extern "C" fn get_proc_address_wrapper(ctx: *mut c_void, name: *const c_char) ->
 }
 
 /// Callback for mpv render context update notification
-extern "C" fn render_update_callback(ctx: *mut c_void) {
+pub(crate) extern "C" fn render_update_callback(ctx: *mut c_void) {
     if ctx.is_null() {
         return;
     }
@@ -61,20 +142,40 @@ pub struct VideoConfig {
     pub loop_playback: bool,
     /// Layout mode for video rendering
     pub layout: LayoutMode,
+    /// Fill for the bars around Contain/Centre layouts
+    pub letterbox: LetterboxFill,
     /// Hardware decoding mode
     pub hwdec: HwdecMode,
     /// Mute audio
     pub mute: bool,
     /// Volume (0.0 - 1.0)
     pub volume: f64,
+    /// Audio output device as named by mpv (None = system default)
+    pub audio_device: Option<String>,
     /// Start time in seconds
     pub start_time: f64,
     /// Playback rate
     pub playback_rate: f64,
+    /// Loop between these positions in seconds instead of the whole file
+    pub ab_loop: Option<(f64, f64)>,
     /// HDR mode
     pub hdr_mode: HdrMode,
     /// Tone mapping configuration
     pub tone_mapping: ToneMappingConfig,
+    /// Global color grading LUT (.cube), overridable per wallpaper
+    pub lut: Option<PathBuf>,
+    /// Transition from the previous wallpaper when one is swapped in
+    pub transition: TransitionKind,
+    /// How long the transition takes
+    pub transition_duration: Duration,
+    /// Render node VAAPI decodes on (None = mpv's default)
+    pub render_device: Option<PathBuf>,
+    /// Frame rate video is decoded at (0 = the file's own)
+    pub decode_fps: u32,
+    /// Brightness, contrast, gamma and saturation of the picture
+    pub color: ColorAdjust,
+    /// Raw mpv options set after ours, e.g. `scale=ewa_lanczossharp`
+    pub mpv_options: BTreeMap<String, String>,
 }
 
 impl Default for VideoConfig {
@@ -83,14 +184,119 @@ impl Default for VideoConfig {
             source: String::new(),
             loop_playback: true,
             layout: LayoutMode::Fill,
+            letterbox: LetterboxFill::default(),
             hwdec: HwdecMode::Auto,
             mute: true,
             volume: 0.0,
+            audio_device: None,
             start_time: 0.0,
             playback_rate: 1.0,
+            ab_loop: None,
             hdr_mode: HdrMode::Auto,
             tone_mapping: ToneMappingConfig::default(),
+            lut: None,
+            transition: TransitionKind::None,
+            transition_duration: Duration::from_millis(600),
+            render_device: None,
+            decode_fps: DEFAULT_DECODE_FPS,
+            color: ColorAdjust::default(),
+            mpv_options: BTreeMap::new(),
+        }
+    }
+}
+
+/// Change to where and how fast a video plays
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transport {
+    /// Jump to a position, or by an offset when `relative`, in seconds
+    Seek { seconds: f64, relative: bool },
+    /// Playback speed, 1.0 = normal
+    Speed(f64),
+    /// Show the next (or previous) frame and pause there
+    FrameStep { backward: bool },
+    /// Loop between two positions in seconds (None = loop the whole file)
+    AbLoop(Option<(f64, f64)>),
+}
+
+/// What mpv knows about the file it plays
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaInfo {
+    /// Title from the file's metadata, or its file name
+    pub title: Option<String>,
+    /// Length of the file (None = a live stream, or not loaded yet)
+    pub duration: Option<Duration>,
+    /// Hardware decoder in use ("no" = decoding in software)
+    pub hwdec: Option<String>,
+}
+
+/// Versions of the libmpv this process loaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MpvVersion {
+    /// Client API version, major and minor
+    pub client_api: (u32, u32),
+    /// e.g. "mpv v0.39.0"
+    pub mpv: Option<String>,
+    /// FFmpeg libmpv was built against, e.g. "7.1"
+    pub ffmpeg: Option<String>,
+}
+
+/// Start libmpv without video or audio output and read its versions
+pub fn mpv_version() -> Result<MpvVersion> {
+    let api = unsafe { libmpv_sys::mpv_client_api_version() };
+    let handle = unsafe { libmpv_sys::mpv_create() };
+    if handle.is_null() {
+        return Err(anyhow!("Failed to create MPV handle"));
+    }
+
+    for (name, value) in [
+        ("config", "no"),
+        ("terminal", "no"),
+        ("vo", "null"),
+        ("ao", "null"),
+        ("idle", "yes"),
+    ] {
+        let name = CString::new(name).unwrap();
+        let value = CString::new(value).unwrap();
+        unsafe { libmpv_sys::mpv_set_option_string(handle, name.as_ptr(), value.as_ptr()) };
+    }
+    let ret = unsafe { libmpv_sys::mpv_initialize(handle) };
+    if ret < 0 {
+        unsafe { libmpv_sys::mpv_terminate_destroy(handle) };
+        return Err(anyhow!("Failed to initialize MPV: error {}", ret));
+    }
+
+    let version = MpvVersion {
+        client_api: ((api >> 16) as u32, (api & 0xffff) as u32),
+        mpv: property_string(handle, "mpv-version"),
+        ffmpeg: property_string(handle, "ffmpeg-version"),
+    };
+    unsafe { libmpv_sys::mpv_terminate_destroy(handle) };
+    Ok(version)
+}
+
+/// A string property of `handle` (None = unset or unknown)
+fn property_string(handle: *mut libmpv_sys::mpv_handle, name: &str) -> Option<String> {
+    let prop_name = CString::new(name).ok()?;
+    let mut value_ptr: *mut c_char = std::ptr::null_mut();
+
+    let ret = unsafe {
+        libmpv_sys::mpv_get_property(
+            handle,
+            prop_name.as_ptr(),
+            1, // MPV_FORMAT_STRING
+            &mut value_ptr as *mut *mut c_char as *mut c_void,
+        )
+    };
+
+    if ret == 0 && !value_ptr.is_null() {
+        let c_str = unsafe { std::ffi::CStr::from_ptr(value_ptr) };
+        let result = c_str.to_string_lossy().into_owned();
+        unsafe {
+            libmpv_sys::mpv_free(value_ptr as *mut c_void);
         }
+        Some(result)
+    } else {
+        None
     }
 }
 
@@ -103,6 +309,12 @@ pub struct MpvPlayer {
     frame_available: Arc<AtomicBool>,
     pending_source: Option<String>,
     source_loaded: bool,
+    /// Why the current source stopped with an error
+    failure: Option<String>,
+    /// Whether files loop; live sources never do
+    loop_playback: bool,
+    /// Whether the current source is a live feed played with low latency
+    live: bool,
 }
 
 // Safety: mpv_handle can be safely sent between threads
@@ -111,6 +323,20 @@ unsafe impl Send for MpvPlayer {}
 impl MpvPlayer {
     /// Create a new MPV player
     pub fn new(config: &VideoConfig, output_info: &OutputInfo) -> Result<Self> {
+        Self::create(config, output_info, None)
+    }
+
+    /// Create a player that draws into an existing X11 window with its own
+    /// video output instead of the render API, and start playing right away
+    pub fn new_embedded(config: &VideoConfig, output_info: &OutputInfo, wid: u64) -> Result<Self> {
+        let mut player = Self::create(config, output_info, Some(wid))?;
+        if let Some(source) = player.pending_source.take() {
+            player.load_source(&source)?;
+        }
+        Ok(player)
+    }
+
+    fn create(config: &VideoConfig, output_info: &OutputInfo, wid: Option<u64>) -> Result<Self> {
         info!("🎬 Initializing libmpv for output {}", output_info.name);
 
         let handle = unsafe { libmpv_sys::mpv_create() };
@@ -140,21 +366,28 @@ impl MpvPlayer {
 
         // Layout configuration
         Self::configure_layout(&set_option, config.layout);
+        Self::configure_background(&set_option, config.letterbox);
 
         // ===== Critical Performance Optimizations =====
         // Optimized for integrated GPUs (AMD APU, Intel UHD, etc.)
 
         // Hardware decoding - VAAPI for AMD/Intel on Linux
-        let hwdec_str = match config.hwdec {
-            HwdecMode::Auto => "vaapi-copy", // VAAPI copy-back for AMD APU
-            HwdecMode::Force => "vaapi",     // Direct VAAPI
-            HwdecMode::No => "no",
-        };
-        set_option("hwdec", hwdec_str);
+        set_option("hwdec", hwdec_option(config.hwdec));
         set_option("hwdec-codecs", "all");
+        if let Some(device) = &config.render_device {
+            set_option("vaapi-device", &device.to_string_lossy());
+        }
 
         // Video output - minimal GPU load
-        set_option("vo", "libmpv");
+        match wid {
+            Some(wid) => {
+                set_option("wid", &wid.to_string());
+                set_option("vo", "gpu");
+                set_option("cursor-autohide", "always");
+                set_option("input-cursor", "no");
+            }
+            None => set_option("vo", "libmpv"),
+        }
         set_option("gpu-api", "opengl");
         set_option("opengl-swapinterval", "0");
         set_option("opengl-pbo", "no"); // Disable PBO on integrated GPU (can cause overhead)
@@ -179,7 +412,10 @@ impl MpvPlayer {
         set_option("fbo-format", "rgba8"); // Simpler FBO format
 
         // Limit video FPS to reduce GPU load
-        set_option("vf", "fps=30"); // Cap video to 30fps for wallpaper use
+        set_option("vf", &fps_filter(config.decode_fps));
+        for (name, level) in equalizer(config.color) {
+            set_option(name, &level);
+        }
 
         // Memory optimization
         set_option("demuxer-max-bytes", "16M");
@@ -202,6 +438,9 @@ impl MpvPlayer {
             let volume = format!("{}", (config.volume * 100.0) as i64);
             set_option("volume", &volume);
         }
+        if let Some(device) = &config.audio_device {
+            set_option("audio-device", device);
+        }
 
         // Start time
         if config.start_time > 0.0 {
@@ -212,6 +451,19 @@ impl MpvPlayer {
         if (config.playback_rate - 1.0).abs() > 0.01 {
             set_option("speed", &format!("{}", config.playback_rate));
         }
+        if let Some((a, b)) = config.ab_loop {
+            set_option("ab-loop-a", &format!("{}", a));
+            set_option("ab-loop-b", &format!("{}", b));
+        }
+
+        // Options passed through from the config win over everything above
+        for (name, value) in &config.mpv_options {
+            if name.contains('\0') || value.contains('\0') {
+                warn!("Skipping mpv option {:?}: contains a NUL byte", name);
+                continue;
+            }
+            set_option(name, value);
+        }
 
         // Initialize MPV
         let ret = unsafe { libmpv_sys::mpv_initialize(handle) };
@@ -236,12 +488,16 @@ impl MpvPlayer {
             frame_available: Arc::new(AtomicBool::new(false)),
             pending_source: Some(config.source.clone()),
             source_loaded: false,
+            failure: None,
+            loop_playback: config.loop_playback,
+            live: false,
         })
     }
 
     fn configure_layout(set_option: &impl Fn(&str, &str), layout: LayoutMode) {
         match layout {
-            LayoutMode::Fill | LayoutMode::Cover => {
+            // A spanned video decoding alone covers its output
+            LayoutMode::Fill | LayoutMode::Cover | LayoutMode::Span => {
                 set_option("keepaspect", "yes");
                 set_option("panscan", "1.0");
                 set_option("video-align-x", "0");
@@ -251,7 +507,7 @@ impl MpvPlayer {
                 set_option("keepaspect", "no");
                 set_option("video-unscaled", "no");
             }
-            LayoutMode::Contain => {
+            LayoutMode::Contain | LayoutMode::AmbientFill => {
                 set_option("keepaspect", "yes");
                 set_option("panscan", "0.0");
                 set_option("video-align-x", "0");
@@ -266,6 +522,17 @@ impl MpvPlayer {
         }
     }
 
+    /// mpv clears the bars to its background color; fills drawn by the
+    /// letterbox pass start from black
+    fn configure_background(set_option: &impl Fn(&str, &str), letterbox: LetterboxFill) {
+        let color = match letterbox {
+            LetterboxFill::Color(_) => letterbox.to_string(),
+            LetterboxFill::Blur | LetterboxFill::Dominant => "#000000".to_string(),
+        };
+        set_option("background", "color");
+        set_option("background-color", &color);
+    }
+
     /// Initialize OpenGL render context
     pub fn init_render_context(&mut self, egl_context: &EglContext) -> Result<()> {
         if self.render_context.is_some() {
@@ -337,9 +604,13 @@ impl MpvPlayer {
 
     fn load_source(&mut self, path: &str) -> Result<()> {
         info!("  📁 Loading source: {}", path);
+        // An error from the previous source is not the new one's
+        self.process_events();
+        self.failure = None;
+        self.configure_latency(is_live_source(Path::new(path)));
 
         let cmd = CString::new("loadfile").unwrap();
-        let path_c = CString::new(path)?;
+        let path_c = CString::new(mpv_source(path).as_ref())?;
         let mode = CString::new("replace").unwrap();
 
         let mut args = [
@@ -360,49 +631,139 @@ impl MpvPlayer {
         Ok(())
     }
 
-    /// Configure HDR handling
+    /// Switch between the low-latency profile for live sources and the
+    /// buffered, looping playback files get
+    fn configure_latency(&mut self, live: bool) {
+        if live == self.live {
+            return;
+        }
+        self.live = live;
+        let loop_file = if self.loop_playback && !live {
+            "inf"
+        } else {
+            "no"
+        };
+        for (name, value) in LIVE_OPTIONS
+            .iter()
+            .map(|&(name, live_value, file_value)| {
+                (name, if live { live_value } else { file_value })
+            })
+            .chain([("loop-file", loop_file)])
+        {
+            if let Err(ret) = self.set_option(name, value) {
+                warn!("    Failed to set {}={}: error {}", name, value, ret);
+            }
+        }
+        if live {
+            info!("  📡 Live source: low-latency playback");
+        }
+    }
+
+    /// Save the picture as shown, scaling and filters included; the format
+    /// follows the file extension
+    pub fn screenshot(&self, path: &Path) -> Result<()> {
+        self.command(&["screenshot-to-file", &path.to_string_lossy(), "window"])
+    }
+
+    /// Run an mpv command given as a list of string arguments
+    fn command(&self, args: &[&str]) -> Result<()> {
+        let args_c = args
+            .iter()
+            .map(|arg| CString::new(*arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut argv: Vec<*const c_char> = args_c.iter().map(|arg| arg.as_ptr()).collect();
+        argv.push(std::ptr::null());
+
+        let ret = unsafe { libmpv_sys::mpv_command(self.handle, argv.as_mut_ptr()) };
+        if ret < 0 {
+            return Err(anyhow!("mpv command {:?} failed: error {}", args, ret));
+        }
+        Ok(())
+    }
+
+    /// Set the chain of user GLSL shaders (empty = remove all)
+    ///
+    /// mpv runs user shaders as extra passes in its render pipeline, in list
+    /// order. Calling this again with the same paths recompiles them from disk.
+    pub fn set_shaders(&mut self, paths: &[PathBuf]) -> Result<()> {
+        self.command(&["change-list", "glsl-shaders", "clr", ""])?;
+
+        for path in paths {
+            let path_str = path.to_string_lossy();
+            self.command(&["change-list", "glsl-shaders", "append", &path_str])?;
+            info!("  ✨ Custom shader: {}", path.display());
+        }
+
+        Ok(())
+    }
+
+    /// Configure HDR handling for the current video on a display with
+    /// `display` capabilities
+    ///
+    /// Returns the chosen presentation, or `None` in `Auto` mode while mpv
+    /// has not decoded the video's color parameters yet.
     pub fn configure_hdr(
         &mut self,
         hdr_mode: HdrMode,
         tone_mapping: &ToneMappingConfig,
-    ) -> Result<()> {
-        info!("🎨 Configuring HDR handling...");
+        display: &OutputHdrCapabilities,
+    ) -> Result<Option<HdrOutput>> {
+        let metadata = match (hdr_mode, self.get_hdr_metadata()) {
+            (HdrMode::Auto, None) => return Ok(None),
+            (_, metadata) => metadata.unwrap_or_default(),
+        };
 
-        match hdr_mode {
-            HdrMode::Disable => {
-                info!("  HDR mode: Disabled (forced SDR)");
-                return Ok(());
+        let output = hdr_mode.output_for(&metadata, display);
+        match output {
+            HdrOutput::Sdr => {
+                info!("🎨 HDR: SDR output ({:?} mode)", hdr_mode);
+                self.configure_sdr_target();
             }
-            HdrMode::Force => {
-                info!("  HDR mode: Force (always apply HDR processing)");
+            HdrOutput::ToneMapped => {
+                info!(
+                    "🎨 HDR: tone-mapping {} to SDR",
+                    metadata.format_description()
+                );
                 self.configure_tone_mapping(tone_mapping)?;
-                return Ok(());
             }
-            HdrMode::Auto => {
-                info!("  HDR mode: Auto (detect from video)");
+            HdrOutput::Passthrough => {
+                info!(
+                    "🎨 HDR: passing {} through to the display",
+                    metadata.format_description()
+                );
+                self.configure_passthrough(display);
             }
         }
 
-        match self.get_hdr_metadata() {
-            Some(metadata) => {
-                info!("  📊 Video HDR metadata detected");
-                if metadata.is_hdr() {
-                    info!(
-                        "  ✨ HDR content detected: {}",
-                        metadata.format_description()
-                    );
-                    self.configure_tone_mapping(tone_mapping)?;
-                } else {
-                    info!("  📺 SDR content detected");
-                }
-            }
-            None => {
-                debug!("  ⚠️  Could not detect HDR metadata");
-                self.configure_tone_mapping(tone_mapping)?;
+        Ok(Some(output))
+    }
+
+    /// Render in BT.2020 PQ for a surface the compositor treats as HDR10
+    fn configure_passthrough(&mut self, display: &OutputHdrCapabilities) {
+        let peak = display
+            .max_luminance
+            .map(|nits| format!("{:.0}", nits))
+            .unwrap_or_else(|| "auto".to_string());
+        for (name, value) in [
+            ("target-trc", "pq"),
+            ("target-prim", "bt.2020"),
+            ("target-peak", peak.as_str()),
+            // Surfaces are 8 bits per channel; dithering hides PQ banding
+            ("dither-depth", "auto"),
+        ] {
+            if let Err(ret) = self.set_option(name, value) {
+                warn!("    Failed to set {}={}: error {}", name, value, ret);
             }
         }
+    }
 
-        Ok(())
+    /// Undo targets left by an earlier tone-mapped or passthrough video
+    fn configure_sdr_target(&mut self) {
+        for name in ["target-trc", "target-prim", "target-peak"] {
+            if let Err(ret) = self.set_option(name, "auto") {
+                warn!("    Failed to reset {}: error {}", name, ret);
+            }
+        }
     }
 
     fn configure_tone_mapping(&self, config: &ToneMappingConfig) -> Result<()> {
@@ -561,15 +922,34 @@ impl MpvPlayer {
 
             match event_id {
                 7 => {
+                    // MPV_EVENT_END_FILE
+                    let data = unsafe { (*event).data } as *const libmpv_sys::mpv_event_end_file;
+                    let end = unsafe { &*data };
+                    // MPV_END_FILE_REASON_ERROR
+                    if end.reason as i64 == 4 {
+                        let reason = unsafe {
+                            std::ffi::CStr::from_ptr(libmpv_sys::mpv_error_string(end.error))
+                        }
+                        .to_string_lossy()
+                        .into_owned();
+                        warn!("📺 MPV: playback failed: {}", reason);
+                        self.failure = Some(reason);
+                    } else if end.reason as i64 == 0 && self.live {
+                        // MPV_END_FILE_REASON_EOF: a feed that ends has dropped
+                        warn!("📺 MPV: live stream ended");
+                        self.failure = Some("live stream ended".to_string());
+                    }
+                }
+                8 => {
                     // MPV_EVENT_FILE_LOADED
                     info!("📺 MPV: file loaded");
                     self.cached_dimensions = None;
                 }
-                16 => {
+                21 => {
                     // MPV_EVENT_PLAYBACK_RESTART
                     info!("📺 MPV: playback restart");
                 }
-                20 => {
+                17 => {
                     // MPV_EVENT_VIDEO_RECONFIG
                     info!("📺 MPV: video reconfig");
                     self.cached_dimensions = None;
@@ -581,6 +961,13 @@ impl MpvPlayer {
         }
     }
 
+    /// Why the source stopped playing with an error (None = it plays);
+    /// cleared when another source is loaded
+    pub fn failure(&mut self) -> Option<&str> {
+        self.process_events();
+        self.failure.as_deref()
+    }
+
     /// Get video dimensions
     pub fn get_video_dimensions(&mut self) -> Option<(i32, i32)> {
         if let Some(dims) = self.cached_dimensions {
@@ -616,28 +1003,7 @@ impl MpvPlayer {
     }
 
     fn get_property_string(&self, name: &str) -> Option<String> {
-        let prop_name = CString::new(name).ok()?;
-        let mut value_ptr: *mut c_char = std::ptr::null_mut();
-
-        let ret = unsafe {
-            libmpv_sys::mpv_get_property(
-                self.handle,
-                prop_name.as_ptr(),
-                1, // MPV_FORMAT_STRING
-                &mut value_ptr as *mut *mut c_char as *mut c_void,
-            )
-        };
-
-        if ret == 0 && !value_ptr.is_null() {
-            let c_str = unsafe { std::ffi::CStr::from_ptr(value_ptr) };
-            let result = c_str.to_string_lossy().into_owned();
-            unsafe {
-                libmpv_sys::mpv_free(value_ptr as *mut c_void);
-            }
-            Some(result)
-        } else {
-            None
-        }
+        property_string(self.handle, name)
     }
 
     fn get_property_f64(&self, name: &str) -> Option<f64> {
@@ -656,6 +1022,34 @@ impl MpvPlayer {
         (ret == 0).then_some(value)
     }
 
+    /// Playback position within the current file
+    pub fn position(&self) -> Option<Duration> {
+        self.get_property_f64("time-pos")
+            .filter(|secs| secs.is_finite() && *secs >= 0.0)
+            .map(Duration::from_secs_f64)
+    }
+
+    /// Title, length and the decoder actually used for the current file
+    pub fn media_info(&self) -> MediaInfo {
+        MediaInfo {
+            title: self.get_property_string("media-title"),
+            duration: self
+                .get_property_f64("duration")
+                .filter(|secs| secs.is_finite() && *secs > 0.0)
+                .map(Duration::from_secs_f64),
+            hwdec: self.get_property_string("hwdec-current"),
+        }
+    }
+
+    /// Frames the decoder and the video output dropped in the current file
+    pub fn dropped_frames(&self) -> u64 {
+        ["decoder-frame-drop-count", "frame-drop-count"]
+            .iter()
+            .filter_map(|name| self.get_property_i64(name))
+            .map(|count| count.max(0) as u64)
+            .sum()
+    }
+
     /// Get HDR metadata from video
     pub fn get_hdr_metadata(&self) -> Option<HdrMetadata> {
         let colorspace_str = self.get_property_string("video-params/colorspace")?;
@@ -704,15 +1098,99 @@ impl MpvPlayer {
 
     /// Set volume (0.0 - 1.0)
     pub fn set_volume(&mut self, volume: f64) -> Result<()> {
-        let prop = CString::new("volume").unwrap();
-        let value = CString::new(format!("{}", (volume * 100.0) as i64)).unwrap();
+        let value = format!("{}", (volume * 100.0) as i64);
+        self.set_option("volume", &value)
+            .map_err(|ret| anyhow!("Failed to set volume: error {}", ret))
+    }
+
+    /// Mute or unmute audio
+    ///
+    /// Players created muted run with `audio=no`, so unmuting also selects an
+    /// audio track.
+    pub fn set_muted(&mut self, muted: bool) -> Result<()> {
+        if !muted {
+            self.set_option("aid", "auto")
+                .map_err(|ret| anyhow!("Failed to enable audio: error {}", ret))?;
+        }
+        self.set_option("mute", if muted { "yes" } else { "no" })
+            .map_err(|ret| anyhow!("Failed to set mute: error {}", ret))
+    }
+
+    /// Switch the audio device (None = system default)
+    pub fn set_audio_device(&mut self, device: Option<&str>) -> Result<()> {
+        self.set_option("audio-device", device.unwrap_or("auto"))
+            .map_err(|ret| anyhow!("Failed to set audio device: error {}", ret))
+    }
+
+    /// Switch hardware decoding and the decoded frame rate of the open file
+    pub fn set_decoding(&mut self, hwdec: HwdecMode, decode_fps: u32) -> Result<()> {
+        self.set_option("hwdec", hwdec_option(hwdec))
+            .map_err(|ret| anyhow!("Failed to set hwdec: error {}", ret))?;
+        self.set_option("vf", &fps_filter(decode_fps))
+            .map_err(|ret| anyhow!("Failed to set the frame rate: error {}", ret))
+    }
+
+    /// Switch hardware decoding alone; `HwdecMode::No` gives the hardware
+    /// decoder's surfaces back
+    pub fn set_hwdec(&mut self, hwdec: HwdecMode) -> Result<()> {
+        self.set_option("hwdec", hwdec_option(hwdec))
+            .map_err(|ret| anyhow!("Failed to set hwdec: error {}", ret))
+    }
+
+    /// Set any mpv property on the playing player, as `mpv --name=value` would
+    pub fn set_property(&mut self, name: &str, value: &str) -> Result<()> {
+        self.set_option(name, value)
+            .map_err(|ret| anyhow!("Failed to set {}={}: error {}", name, value, ret))
+    }
+
+    /// Change the brightness, contrast, gamma and saturation of the picture
+    pub fn set_color(&mut self, color: ColorAdjust) -> Result<()> {
+        for (name, level) in equalizer(color) {
+            self.set_option(name, &level)
+                .map_err(|ret| anyhow!("Failed to set {}: error {}", name, ret))?;
+        }
+        Ok(())
+    }
+
+    /// Seek, change speed, step a frame or set the A-B loop
+    pub fn transport(&mut self, transport: Transport) -> Result<()> {
+        match transport {
+            Transport::Seek { seconds, relative } => self.command(&[
+                "seek",
+                &format!("{}", seconds),
+                if relative { "relative" } else { "absolute" },
+            ]),
+            Transport::Speed(speed) => self
+                .set_option("speed", &format!("{}", speed))
+                .map_err(|ret| anyhow!("Failed to set speed: error {}", ret)),
+            Transport::FrameStep { backward } => self.command(&[if backward {
+                "frame-back-step"
+            } else {
+                "frame-step"
+            }]),
+            Transport::AbLoop(range) => {
+                let (a, b) = match range {
+                    Some((a, b)) => (format!("{}", a), format!("{}", b)),
+                    None => ("no".to_string(), "no".to_string()),
+                };
+                self.set_option("ab-loop-a", &a)
+                    .and_then(|()| self.set_option("ab-loop-b", &b))
+                    .map_err(|ret| anyhow!("Failed to set A-B loop: error {}", ret))
+            }
+        }
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) -> std::result::Result<(), i32> {
+        let prop = CString::new(name).unwrap();
+        let value = CString::new(value).map_err(|_| -4)?; // MPV_ERROR_INVALID_PARAMETER
         let ret = unsafe {
             libmpv_sys::mpv_set_option_string(self.handle, prop.as_ptr(), value.as_ptr())
         };
         if ret < 0 {
-            return Err(anyhow!("Failed to set volume: error {}", ret));
+            Err(ret)
+        } else {
+            Ok(())
         }
-        Ok(())
     }
 }
 
@@ -733,3 +1211,29 @@ impl Drop for MpvPlayer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_sources_are_cameras_and_feeds() {
+        assert!(is_live_source(Path::new("rtsp://cam.local:554/stream1")));
+        assert!(is_live_source(Path::new("srt://10.0.0.2:9000")));
+        assert!(is_live_source(Path::new("/dev/video0")));
+        assert!(!is_live_source(Path::new("https://cdn.example/loop.mp4")));
+        assert!(!is_live_source(Path::new("/home/user/Videos/rain.mp4")));
+
+        assert_eq!(mpv_source("/dev/video2"), "av://v4l2:/dev/video2");
+        assert_eq!(mpv_source("rtsp://cam/live"), "rtsp://cam/live");
+    }
+
+    #[test]
+    fn test_audio_options_are_the_ones_that_can_unmute() {
+        assert!(is_audio_option("volume"));
+        assert!(is_audio_option("--audio-device"));
+        assert!(is_audio_option("AID"));
+        assert!(!is_audio_option("scale"));
+        assert!(!is_audio_option("video-sync"));
+    }
+}
@@ -0,0 +1,279 @@
+//! Software-rendered wallpaper previews
+//!
+//! The shell shows a wallpaper playing before it is applied, without a
+//! running engine, layer surface or GL context. A preview player decodes the
+//! file with libmpv's software render API into a small CPU buffer, and the
+//! latest frame is handed out as PNG.
+
+use std::ffi::{c_int, c_void, CString};
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use image::codecs::png::PngEncoder;
+use image::{ExtendedColorType, ImageEncoder};
+use tracing::{debug, info, warn};
+
+use crate::mpv::{
+    render_update_callback, MPV_RENDER_PARAM_API_TYPE, MPV_RENDER_PARAM_INVALID,
+    MPV_RENDER_UPDATE_FRAME,
+};
+
+// Software render parameters (from libmpv/render.h)
+pub(crate) const MPV_RENDER_PARAM_SW_SIZE: u32 = 17;
+pub(crate) const MPV_RENDER_PARAM_SW_FORMAT: u32 = 18;
+pub(crate) const MPV_RENDER_PARAM_SW_STRIDE: u32 = 19;
+pub(crate) const MPV_RENDER_PARAM_SW_POINTER: u32 = 20;
+
+/// Size of preview frames; rows stay 64-byte aligned for mpv's converters
+pub const PREVIEW_SIZE: (u32, u32) = (480, 270);
+
+/// Bytes per pixel of the `rgb0` frames mpv renders
+const PIXEL_BYTES: usize = 4;
+
+/// Muted, looping libmpv instance rendering into memory
+pub struct PreviewPlayer {
+    handle: *mut libmpv_sys::mpv_handle,
+    render_context: *mut libmpv_sys::mpv_render_context,
+    frame_available: Arc<AtomicBool>,
+    /// `rgb0` pixels of the latest frame, top row first
+    pixels: Vec<u8>,
+    rendered: bool,
+    source: Option<PathBuf>,
+}
+
+// libmpv's client and software render APIs may be called from any thread as
+// long as calls are not concurrent, which `&mut self` guarantees
+unsafe impl Send for PreviewPlayer {}
+
+impl PreviewPlayer {
+    pub fn new() -> Result<Self> {
+        let handle = unsafe { libmpv_sys::mpv_create() };
+        if handle.is_null() {
+            return Err(anyhow!("Failed to create MPV handle"));
+        }
+
+        let set_option = |name: &str, value: &str| {
+            let name_c = CString::new(name).unwrap();
+            let value_c = CString::new(value).unwrap();
+            unsafe {
+                let ret =
+                    libmpv_sys::mpv_set_option_string(handle, name_c.as_ptr(), value_c.as_ptr());
+                if ret < 0 {
+                    warn!("Failed to set option {}={}: error {}", name, value, ret);
+                }
+            }
+        };
+
+        set_option("config", "no");
+        set_option("terminal", "no");
+        set_option("msg-level", "all=warn");
+        set_option("vo", "libmpv");
+        set_option("hwdec", "auto-copy");
+        set_option("audio", "no");
+        set_option("loop-file", "inf");
+        set_option("keepaspect", "yes");
+        set_option("panscan", "1.0");
+        // A preview does not need the full frame rate
+        set_option("vf", "fps=15");
+        set_option("demuxer-max-bytes", "8M");
+
+        let ret = unsafe { libmpv_sys::mpv_initialize(handle) };
+        if ret < 0 {
+            unsafe { libmpv_sys::mpv_terminate_destroy(handle) };
+            return Err(anyhow!("Failed to initialize MPV: error {}", ret));
+        }
+
+        let api_type = CString::new("sw").unwrap();
+        let params = [
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_API_TYPE,
+                data: api_type.as_ptr() as *mut c_void,
+            },
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_INVALID,
+                data: ptr::null_mut(),
+            },
+        ];
+
+        let mut render_context: *mut libmpv_sys::mpv_render_context = ptr::null_mut();
+        let ret = unsafe {
+            libmpv_sys::mpv_render_context_create(
+                &mut render_context,
+                handle,
+                params.as_ptr() as *mut _,
+            )
+        };
+        if ret < 0 {
+            unsafe { libmpv_sys::mpv_terminate_destroy(handle) };
+            return Err(anyhow!(
+                "Failed to create mpv software render context: error {}",
+                ret
+            ));
+        }
+
+        let frame_available = Arc::new(AtomicBool::new(false));
+        unsafe {
+            libmpv_sys::mpv_render_context_set_update_callback(
+                render_context,
+                Some(render_update_callback),
+                Arc::as_ptr(&frame_available) as *mut c_void,
+            );
+        }
+
+        let (width, height) = PREVIEW_SIZE;
+        info!("🎞️ Preview player ready ({}x{})", width, height);
+
+        Ok(Self {
+            handle,
+            render_context,
+            frame_available,
+            pixels: vec![0; width as usize * height as usize * PIXEL_BYTES],
+            rendered: false,
+            source: None,
+        })
+    }
+
+    /// The file currently previewed
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    /// Replace the previewed file; frames of the old one are dropped
+    pub fn load(&mut self, path: &Path) -> Result<()> {
+        let cmd = CString::new("loadfile").unwrap();
+        let path_c = CString::new(path.to_string_lossy().as_bytes())?;
+        let mode = CString::new("replace").unwrap();
+        let mut args = [cmd.as_ptr(), path_c.as_ptr(), mode.as_ptr(), ptr::null()];
+
+        let ret = unsafe { libmpv_sys::mpv_command(self.handle, args.as_mut_ptr()) };
+        if ret < 0 {
+            return Err(anyhow!(
+                "Failed to preview {}: error {}",
+                path.display(),
+                ret
+            ));
+        }
+
+        debug!("Previewing {}", path.display());
+        self.source = Some(path.to_path_buf());
+        self.rendered = false;
+        Ok(())
+    }
+
+    /// Whether a frame of the current file has been rendered
+    pub fn has_frame(&self) -> bool {
+        self.rendered
+    }
+
+    /// Render the next frame if mpv has one; returns whether it did
+    pub fn update(&mut self) -> Result<bool> {
+        self.drain_events();
+
+        if !self.frame_available.swap(false, Ordering::AcqRel) {
+            return Ok(false);
+        }
+        let flags = unsafe { libmpv_sys::mpv_render_context_update(self.render_context) };
+        if flags & MPV_RENDER_UPDATE_FRAME == 0 {
+            return Ok(false);
+        }
+
+        let (width, height) = PREVIEW_SIZE;
+        let size: [c_int; 2] = [width as c_int, height as c_int];
+        let format = CString::new("rgb0").unwrap();
+        let stride: usize = width as usize * PIXEL_BYTES;
+        let params = [
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_SW_SIZE,
+                data: size.as_ptr() as *mut c_void,
+            },
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_SW_FORMAT,
+                data: format.as_ptr() as *mut c_void,
+            },
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_SW_STRIDE,
+                data: &stride as *const usize as *mut c_void,
+            },
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_SW_POINTER,
+                data: self.pixels.as_mut_ptr() as *mut c_void,
+            },
+            libmpv_sys::mpv_render_param {
+                type_: MPV_RENDER_PARAM_INVALID,
+                data: ptr::null_mut(),
+            },
+        ];
+
+        let ret = unsafe {
+            libmpv_sys::mpv_render_context_render(self.render_context, params.as_ptr() as *mut _)
+        };
+        if ret < 0 {
+            return Err(anyhow!("mpv software render error: {}", ret));
+        }
+
+        self.rendered = true;
+        Ok(true)
+    }
+
+    /// The latest frame encoded as PNG
+    pub fn frame_png(&self) -> Result<Vec<u8>> {
+        if !self.rendered {
+            return Err(anyhow!("No preview frame has been rendered yet"));
+        }
+        let (width, height) = PREVIEW_SIZE;
+        encode_png(&self.pixels, width, height)
+    }
+
+    fn drain_events(&mut self) {
+        loop {
+            let event = unsafe { libmpv_sys::mpv_wait_event(self.handle, 0.0) };
+            if event.is_null() || unsafe { (*event).event_id } == 0 {
+                break;
+            }
+        }
+    }
+}
+
+impl Drop for PreviewPlayer {
+    fn drop(&mut self) {
+        unsafe {
+            libmpv_sys::mpv_render_context_free(self.render_context);
+            libmpv_sys::mpv_terminate_destroy(self.handle);
+        }
+    }
+}
+
+/// Encode `rgb0` pixels as an RGB PNG
+fn encode_png(pixels: &[u8], width: u32, height: u32) -> Result<Vec<u8>> {
+    let rgb = pixels
+        .chunks_exact(PIXEL_BYTES)
+        .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+        .collect::<Vec<_>>();
+
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png)
+        .write_image(&rgb, width, height, ExtendedColorType::Rgb8)
+        .context("Failed to encode preview frame")?;
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::png::PngDecoder;
+    use image::ImageDecoder;
+
+    #[test]
+    fn test_encode_png_drops_padding_byte() {
+        let png = encode_png(&[255, 0, 0, 0, 0, 128, 255, 0], 2, 1).unwrap();
+
+        let decoder = PngDecoder::new(std::io::Cursor::new(png)).unwrap();
+        assert_eq!(decoder.dimensions(), (2, 1));
+        let mut rgb = vec![0; decoder.total_bytes() as usize];
+        decoder.read_image(&mut rgb).unwrap();
+        assert_eq!(rgb, [255, 0, 0, 0, 128, 255]);
+    }
+}
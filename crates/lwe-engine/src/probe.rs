@@ -0,0 +1,180 @@
+//! Wallpaper source probing
+//!
+//! Checks that a new source exists, can be read and has a decodable video
+//! stream before any session is torn down for it, so a bad file leaves the
+//! current wallpaper showing instead of a black surface. Decoding is checked
+//! with `ffprobe` reading a single frame; without ffprobe only the file checks
+//! run and mpv reports decode failures as before. Animated and still images
+//! are checked with the built-in decoders that will show them. Streams,
+//! URLs and capture devices are left to mpv, since probing them would block
+//! on the network or a live device.
+
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::path::Path;
+use std::process::Command;
+
+use lwe_core::{is_animated_image, is_still_image, SourceError};
+use tracing::debug;
+
+use crate::animated_image::probe_animated_image;
+use crate::mpv::is_live_source;
+use crate::still_image::probe_still_image;
+
+/// Probe `path` before it replaces the current wallpaper
+pub fn probe_source(path: &Path) -> Result<(), SourceError> {
+    if is_live_source(path) || path.to_string_lossy().contains("://") {
+        return Ok(());
+    }
+
+    let source = path.display().to_string();
+    let metadata = std::fs::metadata(path).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => SourceError::NotFound {
+            path: source.clone(),
+        },
+        _ => SourceError::Unreadable {
+            path: source.clone(),
+            reason: e.to_string(),
+        },
+    })?;
+    // mpv plays directories as playlists; their entries are checked by mpv
+    if metadata.is_dir() {
+        return Ok(());
+    }
+
+    File::open(path)
+        .and_then(|mut file| file.read(&mut [0u8; 1]))
+        .map_err(|e| SourceError::Unreadable {
+            path: source.clone(),
+            reason: e.to_string(),
+        })?;
+
+    if is_animated_image(path) {
+        return probe_animated_image(path).map_err(|e| SourceError::Undecodable {
+            path: source,
+            reason: format!("{:#}", e),
+        });
+    }
+    if is_still_image(path) {
+        return probe_still_image(path).map_err(|e| SourceError::Undecodable {
+            path: source,
+            reason: format!("{:#}", e),
+        });
+    }
+
+    let output = match Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-select_streams",
+            "v:0",
+            "-read_intervals",
+            "%+#1",
+            "-count_frames",
+            "-show_entries",
+            "stream=codec_name,nb_read_frames",
+            "-of",
+            "default=noprint_wrappers=1",
+        ])
+        .arg(path)
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            debug!("Skipping decode probe for {}: {}", source, e);
+            return Ok(());
+        }
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(SourceError::Undecodable {
+            path: source,
+            reason: if stderr.is_empty() {
+                format!("ffprobe exited with status {}", output.status)
+            } else {
+                stderr
+            },
+        });
+    }
+
+    check_probe_output(&source, &String::from_utf8_lossy(&output.stdout))
+}
+
+/// Interpret ffprobe's `key=value` report for the first video stream
+fn check_probe_output(path: &str, stdout: &str) -> Result<(), SourceError> {
+    let field = |key: &str| {
+        stdout
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+            .map(str::trim)
+    };
+
+    let Some(codec) = field("codec_name") else {
+        return Err(SourceError::Undecodable {
+            path: path.to_string(),
+            reason: "no video stream found".to_string(),
+        });
+    };
+
+    let frames = field("nb_read_frames").and_then(|frames| frames.parse::<u64>().ok());
+    if frames.unwrap_or(0) == 0 {
+        return Err(SourceError::UnsupportedCodec {
+            path: path.to_string(),
+            codec: codec.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_source_reports_missing_file() {
+        assert_eq!(
+            probe_source(Path::new("/nonexistent/lwe-wallpaper.mp4")),
+            Err(SourceError::NotFound {
+                path: "/nonexistent/lwe-wallpaper.mp4".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_probe_source_passes_streams_and_capture_devices_to_mpv() {
+        assert_eq!(
+            probe_source(Path::new("rtsp://camera.local:554/stream")),
+            Ok(())
+        );
+        assert_eq!(probe_source(Path::new("/dev/video0")), Ok(()));
+        assert_eq!(
+            probe_source(Path::new("https://example.com/wallpaper.mp4")),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_check_probe_output_accepts_decoded_frame() {
+        assert_eq!(
+            check_probe_output("a.mp4", "codec_name=h264\nnb_read_frames=1\n"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_check_probe_output_rejects_missing_stream_and_undecoded_codec() {
+        assert!(matches!(
+            check_probe_output("a.mp3", ""),
+            Err(SourceError::Undecodable { .. })
+        ));
+        assert_eq!(
+            check_probe_output("a.mkv", "codec_name=prores\nnb_read_frames=N/A\n"),
+            Err(SourceError::UnsupportedCodec {
+                path: "a.mkv".to_string(),
+                codec: "prores".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! CPU scheduling for the engine thread
+//!
+//! Applied at the start of the engine thread, before any decoder exists.
+//! Linux threads inherit nice level, policy and affinity from the thread
+//! that creates them, so mpv's demuxer and decoder threads and the audio
+//! capture thread run with the same settings.
+
+use std::fs;
+use std::path::Path;
+
+use lwe_core::{CpuAffinity, PowerConfig};
+use tracing::{info, warn};
+
+/// Scheduling settings for the engine and its decoders
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchedulingConfig {
+    /// Nice level, -20 (highest) to 19 (lowest); None leaves it unchanged
+    pub nice: Option<i32>,
+    /// Use `SCHED_IDLE`, so the engine only runs when nothing else wants the CPU
+    pub idle_priority: bool,
+    /// Cores the engine may run on
+    pub cpu_affinity: CpuAffinity,
+}
+
+impl SchedulingConfig {
+    pub fn from_power(power: &PowerConfig) -> Self {
+        Self {
+            nice: power.nice,
+            idle_priority: power.idle_priority,
+            cpu_affinity: power.cpu_affinity.clone(),
+        }
+    }
+}
+
+/// Apply `config` to the calling thread; failures are logged and skipped
+pub(crate) fn apply_to_current_thread(config: &SchedulingConfig) {
+    if let Some(nice) = config.nice {
+        let nice = nice.clamp(-20, 19);
+        // With PRIO_PROCESS, Linux treats the id as a thread id
+        let ret =
+            unsafe { libc::setpriority(libc::PRIO_PROCESS, libc::gettid() as libc::id_t, nice) };
+        if ret == 0 {
+            info!("Engine nice level set to {}", nice);
+        } else {
+            warn!(
+                "Failed to set engine nice level {}: {}",
+                nice,
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+
+    if config.idle_priority {
+        let param = libc::sched_param { sched_priority: 0 };
+        let ret = unsafe { libc::sched_setscheduler(0, libc::SCHED_IDLE, &param) };
+        if ret == 0 {
+            info!("Engine scheduled with SCHED_IDLE");
+        } else {
+            warn!(
+                "Failed to switch engine to SCHED_IDLE: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+
+    let cores = match &config.cpu_affinity {
+        CpuAffinity::All => return,
+        CpuAffinity::EfficiencyCores => match efficiency_cores(Path::new("/sys/devices")) {
+            Some(cores) => cores,
+            None => {
+                info!("No efficiency cores found, leaving engine CPU affinity unchanged");
+                return;
+            }
+        },
+        CpuAffinity::Cores(cores) => cores.clone(),
+    };
+    set_affinity(&cores);
+}
+
+fn set_affinity(cores: &[usize]) {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for &core in cores {
+        if core < libc::CPU_SETSIZE as usize {
+            unsafe { libc::CPU_SET(core, &mut set) };
+        }
+    }
+
+    let ret = unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) };
+    if ret == 0 {
+        info!("Engine pinned to cores {:?}", cores);
+    } else {
+        warn!(
+            "Failed to pin engine to cores {:?}: {}",
+            cores,
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+/// Efficiency cores of a hybrid CPU, from Intel's `cpu_atom` PMU or, on
+/// big.LITTLE systems, the cores below the highest `cpu_capacity`
+fn efficiency_cores(sys_devices: &Path) -> Option<Vec<usize>> {
+    if let Some(cores) = fs::read_to_string(sys_devices.join("cpu_atom/cpus"))
+        .ok()
+        .and_then(|list| CpuAffinity::parse_cpu_list(&list))
+        .filter(|cores| !cores.is_empty())
+    {
+        return Some(cores);
+    }
+
+    let capacities = fs::read_dir(sys_devices.join("system/cpu"))
+        .ok()?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let core = entry
+                .file_name()
+                .to_str()?
+                .strip_prefix("cpu")?
+                .parse::<usize>()
+                .ok()?;
+            let capacity = fs::read_to_string(entry.path().join("cpu_capacity"))
+                .ok()?
+                .trim()
+                .parse::<u32>()
+                .ok()?;
+            Some((core, capacity))
+        })
+        .collect::<Vec<_>>();
+
+    let highest = capacities.iter().map(|(_, capacity)| *capacity).max()?;
+    let mut cores = capacities
+        .into_iter()
+        .filter(|(_, capacity)| *capacity < highest)
+        .map(|(core, _)| core)
+        .collect::<Vec<_>>();
+    cores.sort_unstable();
+
+    (!cores.is_empty()).then_some(cores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scheduling_follows_power_config() {
+        assert_eq!(
+            SchedulingConfig::from_power(&PowerConfig::default()),
+            SchedulingConfig::default()
+        );
+
+        let power = PowerConfig {
+            nice: Some(15),
+            idle_priority: true,
+            cpu_affinity: CpuAffinity::EfficiencyCores,
+            ..PowerConfig::default()
+        };
+        let scheduling = SchedulingConfig::from_power(&power);
+        assert_eq!(scheduling.nice, Some(15));
+        assert!(scheduling.idle_priority);
+        assert_eq!(scheduling.cpu_affinity, CpuAffinity::EfficiencyCores);
+    }
+
+    #[test]
+    fn test_efficiency_cores_from_atom_pmu_or_capacity() {
+        let root = std::env::temp_dir().join(format!("lwe-sched-{}", std::process::id()));
+        let hybrid = root.join("intel");
+        fs::create_dir_all(hybrid.join("cpu_atom")).unwrap();
+        fs::write(hybrid.join("cpu_atom/cpus"), "16-19\n").unwrap();
+        assert_eq!(efficiency_cores(&hybrid), Some(vec![16, 17, 18, 19]));
+
+        let arm = root.join("arm");
+        for (core, capacity) in [(0, 446), (1, 446), (2, 1024), (3, 1024)] {
+            let dir = arm.join(format!("system/cpu/cpu{core}"));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("cpu_capacity"), format!("{capacity}\n")).unwrap();
+        }
+        assert_eq!(efficiency_cores(&arm), Some(vec![0, 1]));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,396 @@
+//! Shared video decoding for outputs showing the same wallpaper
+//!
+//! Outputs that play the same muted video without their own shader or LUT
+//! share one mpv instance. It renders each new frame once, at the video's
+//! own size, into an offscreen texture, and every output then crops and
+//! scales that texture with its own layout. All outputs render through the
+//! engine's single EGL context, so the texture is visible to each surface.
+//!
+//! Spanned videos always share a decoder, separate from the muted one for
+//! the same file, so the outputs draw their crops of the same frame. Their
+//! sound plays at the loudest volume any spanning output asks for.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+
+use anyhow::Result;
+use tracing::{debug, info, warn};
+
+use lwe_core::{HwdecMode, LayoutMode, LayoutTransform, OutputInfo};
+
+use crate::egl::EglContext;
+use crate::mpv::{MediaInfo, MpvPlayer, Transport, VideoConfig};
+
+/// One mpv decoder feeding every output that shows `path`
+pub(crate) struct SharedDecoder {
+    path: PathBuf,
+    player: MpvPlayer,
+    framebuffer: u32,
+    texture: u32,
+    size: Option<(i32, i32)>,
+    /// Incremented whenever a new frame lands in the texture
+    frame: u64,
+    /// Outputs using the decoder
+    users: HashSet<String>,
+    /// Outputs that asked for a pause; playback stops once all of them have
+    paused: HashSet<String>,
+    /// Volume each output of a spanned video asks for (0.0 = muted)
+    volumes: HashMap<String, f64>,
+}
+
+impl SharedDecoder {
+    fn open(
+        path: &Path,
+        config: &VideoConfig,
+        output_info: &OutputInfo,
+        egl_context: &EglContext,
+    ) -> Result<Self> {
+        let mut config = config.clone();
+        config.source = path.to_string_lossy().to_string();
+        // The texture always has the video's own size; outputs apply their layouts
+        let spanned = config.layout == LayoutMode::Span;
+        config.layout = LayoutMode::Stretch;
+        config.mute |= !spanned;
+
+        let mut player = MpvPlayer::new(&config, output_info)?;
+        player.init_render_context(egl_context)?;
+        player.load_file(path)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            player,
+            framebuffer: 0,
+            texture: 0,
+            size: None,
+            frame: 0,
+            users: HashSet::new(),
+            paused: HashSet::new(),
+            volumes: HashMap::new(),
+        })
+    }
+
+    /// Render mpv's next frame into the texture if one is ready; the GL
+    /// context must be current
+    pub(crate) fn update(&mut self) -> Result<()> {
+        if !self.player.has_frame() {
+            return Ok(());
+        }
+        let Some((width, height)) = self.player.get_video_dimensions() else {
+            return Ok(());
+        };
+
+        unsafe { self.ensure_texture(width, height) };
+        if self.player.render(width, height, self.framebuffer as i32)? {
+            self.frame += 1;
+        }
+        unsafe { gl::BindFramebuffer(gl::FRAMEBUFFER, 0) };
+        Ok(())
+    }
+
+    /// Number of the frame currently in the texture (0 = none yet)
+    pub(crate) fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Size of the decoded video, once known
+    pub(crate) fn size(&self) -> Option<(i32, i32)> {
+        self.size
+    }
+
+    /// Playback position of the shared video
+    pub(crate) fn position(&self) -> Option<Duration> {
+        self.player.position()
+    }
+
+    /// Title, length and decoder of the shared video
+    pub(crate) fn media_info(&self) -> MediaInfo {
+        self.player.media_info()
+    }
+
+    /// Frames the shared decoder dropped
+    pub(crate) fn dropped_frames(&self) -> u64 {
+        self.player.dropped_frames()
+    }
+
+    /// Clear the output and draw the current frame with `layout`
+    pub(crate) fn draw(&self, layout: &LayoutTransform, width: i32, height: i32) {
+        let Some(size) = self.size.filter(|_| self.frame > 0) else {
+            return;
+        };
+        let (src, dst) = blit_rects(layout, size, height);
+
+        unsafe {
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
+            gl::Viewport(0, 0, width, height);
+            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.framebuffer);
+            gl::BlitFramebuffer(
+                src[0],
+                src[1],
+                src[2],
+                src[3],
+                dst[0],
+                dst[1],
+                dst[2],
+                dst[3],
+                gl::COLOR_BUFFER_BIT,
+                gl::LINEAR,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    /// Stop decoding for `output`; playback pauses once every user has paused
+    pub(crate) fn pause(&mut self, output: &str) {
+        self.paused.insert(output.to_string());
+        if self.paused.is_superset(&self.users) {
+            let _ = self.player.pause();
+        }
+    }
+
+    /// Resume decoding for `output`
+    pub(crate) fn resume(&mut self, output: &str) {
+        if self.paused.remove(output) {
+            let _ = self.player.resume();
+        }
+    }
+
+    /// Switch hardware decoding for every user
+    pub(crate) fn set_hwdec(&mut self, hwdec: HwdecMode) -> Result<()> {
+        self.player.set_hwdec(hwdec)
+    }
+
+    /// Set a raw mpv property for every user
+    pub(crate) fn set_property(&mut self, name: &str, value: &str) -> Result<()> {
+        self.player.set_property(name, value)
+    }
+
+    /// Why the video stopped playing with an error (None = it plays)
+    pub(crate) fn failure(&mut self) -> Option<String> {
+        self.player.failure().map(str::to_string)
+    }
+
+    /// Seek, change speed, step a frame or set the A-B loop for every user
+    pub(crate) fn transport(&mut self, transport: Transport) -> Result<()> {
+        self.player.transport(transport)
+    }
+
+    /// Set the volume `output` asks for; the decoder plays the loudest
+    pub(crate) fn set_audio(&mut self, output: &str, volume: f64, muted: bool) {
+        self.volumes
+            .insert(output.to_string(), if muted { 0.0 } else { volume });
+        self.apply_audio();
+    }
+
+    fn apply_audio(&mut self) {
+        let volume = self.volumes.values().copied().fold(0.0, f64::max);
+        if let Err(e) = self
+            .player
+            .set_muted(volume <= 0.0)
+            .and_then(|()| self.player.set_volume(volume))
+        {
+            warn!(
+                "Failed to set the volume of the shared decoder for {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+
+    fn leave(&mut self, output: &str) {
+        self.users.remove(output);
+        self.paused.remove(output);
+        if self.volumes.remove(output).is_some() {
+            self.apply_audio();
+        }
+        if !self.users.is_empty() && self.paused.is_superset(&self.users) {
+            let _ = self.player.pause();
+        }
+    }
+
+    unsafe fn ensure_texture(&mut self, width: i32, height: i32) {
+        if self.size == Some((width, height)) {
+            return;
+        }
+
+        unsafe {
+            if self.framebuffer == 0 {
+                gl::GenTextures(1, &mut self.texture);
+                gl::GenFramebuffers(1, &mut self.framebuffer);
+            }
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8 as i32,
+                width,
+                height,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                std::ptr::null(),
+            );
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                self.texture,
+                0,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        debug!(
+            "Shared decode texture for {} is {}x{}",
+            self.path.display(),
+            width,
+            height
+        );
+        self.size = Some((width, height));
+        // The old frame did not survive the reallocation
+        self.frame = 0;
+    }
+}
+
+impl Drop for SharedDecoder {
+    /// The engine's GL context must be current, as when dropping an `MpvPlayer`
+    fn drop(&mut self) {
+        info!("Stopping shared decoder for {}", self.path.display());
+        unsafe {
+            if self.framebuffer != 0 {
+                gl::DeleteFramebuffers(1, &self.framebuffer);
+            }
+            if self.texture != 0 {
+                gl::DeleteTextures(1, &self.texture);
+            }
+        }
+    }
+}
+
+/// A session's hold on a shared decoder; leaving drops the decoder with its last user
+pub(crate) struct SharedDecode {
+    output: String,
+    decoder: Rc<RefCell<SharedDecoder>>,
+}
+
+impl SharedDecode {
+    pub(crate) fn decoder(&self) -> &RefCell<SharedDecoder> {
+        &self.decoder
+    }
+
+    /// Identity of the decoder, the same for every output sharing it
+    pub(crate) fn id(&self) -> usize {
+        Rc::as_ptr(&self.decoder) as usize
+    }
+}
+
+impl Drop for SharedDecode {
+    fn drop(&mut self) {
+        self.decoder.borrow_mut().leave(&self.output);
+    }
+}
+
+/// Decoders currently running, by video path and whether they are spanned
+#[derive(Clone, Default)]
+pub(crate) struct SharedDecoders(
+    Rc<RefCell<HashMap<(PathBuf, bool), Weak<RefCell<SharedDecoder>>>>>,
+);
+
+impl SharedDecoders {
+    /// Join the decoder for `path`, starting one if no other output plays
+    /// it; the GL context must be current
+    pub(crate) fn join(
+        &self,
+        path: &Path,
+        config: &VideoConfig,
+        output_info: &OutputInfo,
+        egl_context: &EglContext,
+    ) -> Result<SharedDecode> {
+        let mut decoders = self.0.borrow_mut();
+        decoders.retain(|_, decoder| decoder.strong_count() > 0);
+
+        let key = (path.to_path_buf(), config.layout == LayoutMode::Span);
+        let decoder = match decoders.get(&key).and_then(Weak::upgrade) {
+            Some(decoder) => {
+                info!(
+                    "Sharing the decoder for {} with {}",
+                    path.display(),
+                    output_info.name
+                );
+                decoder
+            }
+            None => {
+                let decoder = Rc::new(RefCell::new(SharedDecoder::open(
+                    path,
+                    config,
+                    output_info,
+                    egl_context,
+                )?));
+                decoders.insert(key, Rc::downgrade(&decoder));
+                decoder
+            }
+        };
+        decoder.borrow_mut().users.insert(output_info.name.clone());
+
+        Ok(SharedDecode {
+            output: output_info.name.clone(),
+            decoder,
+        })
+    }
+}
+
+/// Source and destination rectangles as (x0, y0, x1, y1) for a blit from the
+/// decoded frame to the output
+///
+/// mpv renders into the texture with the image's top row at the top in GL
+/// coordinates, so both rectangles count rows from the bottom.
+fn blit_rects(
+    layout: &LayoutTransform,
+    (width, height): (i32, i32),
+    output_height: i32,
+) -> ([i32; 4], [i32; 4]) {
+    let (sx, sy, sw, sh) = layout.src_rect;
+    let (width, height) = (f64::from(width), f64::from(height));
+    let src = [
+        (sx * width).round() as i32,
+        ((1.0 - sy - sh) * height).round() as i32,
+        ((sx + sw) * width).round() as i32,
+        ((1.0 - sy) * height).round() as i32,
+    ];
+
+    let (dx, dy, dw, dh) = layout.dst_rect;
+    let dst = [dx, output_height - dy - dh, dx + dw, output_height - dy];
+    (src, dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lwe_core::calculate_layout;
+
+    #[test]
+    fn test_blit_rects_crop_without_flipping() {
+        // A 16:9 video filling a 4:3 output crops its sides
+        let layout = calculate_layout(LayoutMode::Fill, 1920, 1080, 1440, 1080);
+        let (src, dst) = blit_rects(&layout, (1920, 1080), 1080);
+        assert_eq!(src, [240, 0, 1680, 1080]);
+        assert_eq!(dst, [0, 0, 1440, 1080]);
+
+        // Contain leaves bars above and below; the image stays upright
+        let layout = calculate_layout(LayoutMode::Contain, 1920, 1080, 1920, 1200);
+        let (src, dst) = blit_rects(&layout, (1920, 1080), 1200);
+        assert_eq!(src, [0, 0, 1920, 1080]);
+        assert_eq!(dst, [0, 60, 1920, 1140]);
+    }
+}
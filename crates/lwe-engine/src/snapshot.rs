@@ -0,0 +1,98 @@
+//! Full-size frame readback for snapshots
+//!
+//! A snapshot is the frame an output shows, letterbox and color correction
+//! included, read back before the buffers are swapped. Encoding a 4K image
+//! takes a while, so the file is written off the render thread.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// An output's frame as RGBA pixels, top row first
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Frame {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+impl Frame {
+    /// Read the default framebuffer
+    ///
+    /// Must run with the output's context current, after the frame was drawn
+    /// and before the buffers are swapped.
+    pub(crate) fn read(width: i32, height: i32) -> Self {
+        let (width, height) = (width.max(0), height.max(0));
+        let mut rgba = vec![0u8; width as usize * height as usize * 4];
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(
+                0,
+                0,
+                width,
+                height,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                rgba.as_mut_ptr().cast(),
+            );
+        }
+
+        Self {
+            width: width as u32,
+            height: height as u32,
+            rgba: flip_rows(&rgba, width as usize * 4),
+        }
+    }
+
+    /// Write the frame without alpha; the format follows the file extension
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        let image = image::RgbaImage::from_raw(self.width, self.height, self.rgba.clone())
+            .context("Frame size does not match its pixels")?;
+        image::DynamicImage::ImageRgba8(image)
+            .to_rgb8()
+            .save(path)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// GL reads rows bottom first; images store them top first
+fn flip_rows(pixels: &[u8], row_bytes: usize) -> Vec<u8> {
+    if row_bytes == 0 {
+        return Vec::new();
+    }
+    pixels
+        .chunks_exact(row_bytes)
+        .rev()
+        .flatten()
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_is_saved_top_row_first() {
+        let bottom_up = [
+            [0, 0, 255, 255], // bottom row: blue
+            [255, 0, 0, 255], // top row: red
+        ]
+        .concat();
+        let frame = Frame {
+            width: 1,
+            height: 2,
+            rgba: flip_rows(&bottom_up, 4),
+        };
+
+        let path = std::env::temp_dir().join(format!("lwe-snapshot-{}.png", std::process::id()));
+        frame.save(&path).unwrap();
+        let saved = image::open(&path).unwrap().to_rgb8();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(saved.dimensions(), (1, 2));
+        assert_eq!(saved.get_pixel(0, 0).0, [255, 0, 0]);
+        assert_eq!(saved.get_pixel(0, 1).0, [0, 0, 255]);
+    }
+}
@@ -0,0 +1,93 @@
+//! Process CPU and memory usage
+//!
+//! CPU usage is the CPU time this process used between two reads of
+//! `/proc/self/stat`, as percent of one core; memory is the resident set
+//! from `/proc/self/status`. Render and decode counters come from the
+//! engine with [`EngineCommand::GetStats`](crate::EngineCommand::GetStats).
+
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// CPU and memory used by this process
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessUsage {
+    /// Percent of one core (200 = two cores busy); None = not measurable
+    pub cpu_percent: Option<f64>,
+    /// Resident memory
+    pub rss_bytes: Option<u64>,
+}
+
+/// Measure this process over `interval`; blocks for that long
+pub fn process_usage(interval: Duration) -> ProcessUsage {
+    let before = cpu_time();
+    let started = Instant::now();
+    thread::sleep(interval);
+    let after = cpu_time();
+    let elapsed = started.elapsed().as_secs_f64();
+
+    ProcessUsage {
+        cpu_percent: match (before, after) {
+            (Some(before), Some(after)) if elapsed > 0.0 => {
+                Some(after.saturating_sub(before).as_secs_f64() / elapsed * 100.0)
+            }
+            _ => None,
+        },
+        rss_bytes: fs::read_to_string("/proc/self/status")
+            .ok()
+            .and_then(|status| rss_from_status(&status)),
+    }
+}
+
+/// User plus system CPU time of this process
+fn cpu_time() -> Option<Duration> {
+    // SAFETY: sysconf only reads a configuration value
+    let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    let ticks_per_second = u64::try_from(ticks_per_second)
+        .ok()
+        .filter(|ticks| *ticks > 0)?;
+    let ticks = cpu_ticks_from_stat(&fs::read_to_string("/proc/self/stat").ok()?)?;
+    Some(Duration::from_secs_f64(
+        ticks as f64 / ticks_per_second as f64,
+    ))
+}
+
+/// `utime + stime` in clock ticks; the fields are counted after the command
+/// name, which may itself contain spaces and parentheses
+fn cpu_ticks_from_stat(stat: &str) -> Option<u64> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    let mut fields = fields.split_whitespace();
+    // state is field 3, utime field 14 and stime field 15
+    let utime = fields.nth(11)?.parse::<u64>().ok()?;
+    let stime = fields.next()?.parse::<u64>().ok()?;
+    Some(utime + stime)
+}
+
+fn rss_from_status(status: &str) -> Option<u64> {
+    let kib = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_cpu_ticks_and_rss() {
+        let stat = "4242 (lwe (main) x) S 1 4242 4242 0 -1 4194560 9120 0 0 0 \
+                    150 25 0 0 20 0 12 0 5531 1287581696 38214 18446744073709551615";
+        assert_eq!(cpu_ticks_from_stat(stat), Some(175));
+        assert_eq!(cpu_ticks_from_stat("4242 lwe"), None);
+
+        let status = "Name:\tlwe\nVmPeak:\t 1300000 kB\nVmRSS:\t  152856 kB\nThreads:\t12\n";
+        assert_eq!(rss_from_status(status), Some(152_856 * 1024));
+        assert_eq!(rss_from_status("Name:\tkthreadd\n"), None);
+    }
+}
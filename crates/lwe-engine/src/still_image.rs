@@ -0,0 +1,101 @@
+//! Still JPEG, PNG and WebP wallpapers without mpv
+//!
+//! A still image is decoded once with the `image` crate, uploaded to the
+//! output's texture and drawn through [`AnimatedImageBlit`]. Once that frame
+//! is up the output stops requesting frame callbacks until it is resized or
+//! the wallpaper changes, so a still wallpaper costs no CPU or GPU time while
+//! it is shown.
+//!
+//! [`AnimatedImageBlit`]: crate::animated_image::AnimatedImageBlit
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use image::ImageReader;
+use tracing::debug;
+
+/// Longest side kept after decoding; larger images are scaled down to fit
+/// every GPU's texture limit
+const MAX_STILL_SIZE: u32 = 8192;
+
+/// A decoded still image waiting for, or past, its upload
+pub struct StillImage {
+    width: i32,
+    height: i32,
+    /// RGBA pixels, rows top to bottom; dropped once uploaded
+    rgba: Option<Vec<u8>>,
+}
+
+impl StillImage {
+    /// Decode `path` into RGBA
+    pub fn open(path: &Path) -> Result<Self> {
+        let image = ImageReader::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?
+            .with_guessed_format()
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .decode()
+            .with_context(|| format!("Failed to decode {}", path.display()))?;
+        let image = if image.width().max(image.height()) > MAX_STILL_SIZE {
+            image.thumbnail(MAX_STILL_SIZE, MAX_STILL_SIZE)
+        } else {
+            image
+        };
+        let buffer = image.into_rgba8();
+        debug!(
+            "Decoded still image {} ({}x{})",
+            path.display(),
+            buffer.width(),
+            buffer.height()
+        );
+
+        Ok(Self {
+            width: buffer.width() as i32,
+            height: buffer.height() as i32,
+            rgba: Some(buffer.into_raw()),
+        })
+    }
+
+    /// Size of the image as drawn
+    pub fn dimensions(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// Pixels still to be uploaded; None once they have been taken
+    pub(crate) fn take_pixels(&mut self) -> Option<Vec<u8>> {
+        self.rgba.take()
+    }
+}
+
+/// Read `path`'s header, to reject files that are not images before they are applied
+pub fn probe_still_image(path: &Path) -> Result<()> {
+    ImageReader::open(path)?
+        .with_guessed_format()?
+        .into_dimensions()
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_still_image_pixels_are_handed_out_once() {
+        let path = std::env::temp_dir().join(format!("lwe-still-{}.png", std::process::id()));
+        image::RgbaImage::from_pixel(4, 2, image::Rgba([10, 20, 30, 255]))
+            .save(&path)
+            .unwrap();
+
+        let mut still = StillImage::open(&path).unwrap();
+        assert_eq!(still.dimensions(), (4, 2));
+        let pixels = still.take_pixels().unwrap();
+        assert_eq!(pixels.len(), 4 * 2 * 4);
+        assert_eq!(&pixels[..4], &[10, 20, 30, 255]);
+        assert!(still.take_pixels().is_none());
+
+        probe_still_image(&path).unwrap();
+        std::fs::write(&path, b"\x89PNG\r\n\x1a\nbroken").unwrap();
+        assert!(probe_still_image(&path).is_err());
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,78 @@
+//! Minimal systemd `sd_notify` support
+//!
+//! Lets a `Type=notify` service report readiness once the first wallpaper
+//! surface is configured, without linking libsystemd.
+
+use std::os::unix::net::UnixDatagram;
+
+use tracing::debug;
+
+/// Send a state string (e.g. `READY=1`) to the service manager
+///
+/// Returns `false` when not running under systemd or the message could not be sent.
+pub fn notify(state: &str) -> bool {
+    match std::env::var("NOTIFY_SOCKET") {
+        Ok(socket) if !socket.is_empty() => notify_to(&socket, state),
+        _ => false,
+    }
+}
+
+/// Tell systemd the service is ready
+pub fn notify_ready() -> bool {
+    notify("READY=1")
+}
+
+/// Update the status line shown by `systemctl status`
+pub fn notify_status(status: &str) -> bool {
+    notify(&format!("STATUS={}", status))
+}
+
+fn notify_to(socket: &str, state: &str) -> bool {
+    let Ok(sock) = UnixDatagram::unbound() else {
+        return false;
+    };
+
+    let result = if let Some(name) = socket.strip_prefix('@') {
+        // Abstract namespace socket
+        use std::os::linux::net::SocketAddrExt;
+        std::os::unix::net::SocketAddr::from_abstract_name(name.as_bytes())
+            .and_then(|addr| sock.send_to_addr(state.as_bytes(), &addr))
+    } else {
+        sock.send_to(state.as_bytes(), socket)
+    };
+
+    match result {
+        Ok(_) => true,
+        Err(e) => {
+            debug!("sd_notify to {} failed: {}", socket, e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notify_to_path_socket() {
+        let dir = std::env::temp_dir().join(format!("lwe-notify-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("notify.sock");
+        let _ = std::fs::remove_file(&path);
+
+        let receiver = UnixDatagram::bind(&path).unwrap();
+        assert!(notify_to(path.to_str().unwrap(), "READY=1"));
+
+        let mut buf = [0u8; 64];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"READY=1");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_notify_to_missing_socket_fails() {
+        assert!(!notify_to("/nonexistent/lwe/notify.sock", "READY=1"));
+    }
+}
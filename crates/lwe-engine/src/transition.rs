@@ -0,0 +1,413 @@
+//! Transitions between wallpapers on a hot-swap
+//!
+//! While a transition is configured, every finished frame is copied into a
+//! texture. When a new wallpaper is loaded in place, that copy becomes the
+//! outgoing frame; once the new wallpaper has drawn its first frame, the
+//! outgoing frame is drawn over it with the transition's offset, scale and
+//! opacity until the duration has passed. Between decoded frames the last
+//! copy is blitted back so the animation keeps moving on still images.
+
+use std::ffi::CString;
+use std::time::{Duration, Instant};
+
+use lwe_core::TransitionKind;
+use tracing::warn;
+
+const VERTEX_SHADER: &str = r#"#version 130
+uniform vec2 u_offset;
+uniform float u_scale;
+out vec2 v_uv;
+void main() {
+    vec2 corner = vec2(float(gl_VertexID & 1), float(gl_VertexID >> 1));
+    v_uv = corner;
+    gl_Position = vec4((corner * 2.0 - 1.0) * u_scale + u_offset, 0.0, 1.0);
+}
+"#;
+
+const FRAGMENT_SHADER: &str = r#"#version 130
+uniform sampler2D u_frame;
+uniform float u_alpha;
+in vec2 v_uv;
+out vec4 color;
+void main() {
+    color = vec4(texture(u_frame, v_uv).rgb, u_alpha);
+}
+"#;
+
+/// How the outgoing frame is drawn at one point of the transition
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Overlay {
+    /// Horizontal offset in clip space (2.0 = one output width)
+    offset_x: f32,
+    scale: f32,
+    alpha: f32,
+}
+
+impl Overlay {
+    /// Overlay for `kind` at `progress` (0.0 - 1.0), eased at both ends
+    fn at(kind: TransitionKind, progress: f32) -> Self {
+        let t = progress.clamp(0.0, 1.0);
+        let eased = t * t * (3.0 - 2.0 * t);
+        match kind {
+            TransitionKind::None => Self {
+                offset_x: 0.0,
+                scale: 1.0,
+                alpha: 0.0,
+            },
+            TransitionKind::Crossfade => Self {
+                offset_x: 0.0,
+                scale: 1.0,
+                alpha: 1.0 - eased,
+            },
+            TransitionKind::Slide => Self {
+                offset_x: -2.0 * eased,
+                scale: 1.0,
+                alpha: 1.0,
+            },
+            TransitionKind::Zoom => Self {
+                offset_x: 0.0,
+                scale: 1.0 + 0.25 * eased,
+                alpha: 1.0 - eased,
+            },
+        }
+    }
+}
+
+/// Texture-backed copy of a full output frame
+#[derive(Default)]
+struct FrameCopy {
+    framebuffer: u32,
+    texture: u32,
+    size: (i32, i32),
+}
+
+impl FrameCopy {
+    /// Copy the default framebuffer into this texture
+    unsafe fn capture(&mut self, width: i32, height: i32) {
+        unsafe {
+            self.ensure_size(width, height);
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.framebuffer);
+            gl::BlitFramebuffer(
+                0,
+                0,
+                width,
+                height,
+                0,
+                0,
+                width,
+                height,
+                gl::COLOR_BUFFER_BIT,
+                gl::NEAREST,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    /// Draw this copy back into the default framebuffer, scaled to fit
+    unsafe fn restore(&self, width: i32, height: i32) {
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.framebuffer);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
+            gl::BlitFramebuffer(
+                0,
+                0,
+                self.size.0,
+                self.size.1,
+                0,
+                0,
+                width,
+                height,
+                gl::COLOR_BUFFER_BIT,
+                gl::LINEAR,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    unsafe fn ensure_size(&mut self, width: i32, height: i32) {
+        if self.framebuffer != 0 && self.size == (width, height) {
+            return;
+        }
+
+        unsafe {
+            if self.texture == 0 {
+                gl::GenTextures(1, &mut self.texture);
+            }
+            gl::BindTexture(gl::TEXTURE_2D, self.texture);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8 as i32,
+                width,
+                height,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                std::ptr::null(),
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+
+            if self.framebuffer == 0 {
+                gl::GenFramebuffers(1, &mut self.framebuffer);
+                gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+                gl::FramebufferTexture2D(
+                    gl::FRAMEBUFFER,
+                    gl::COLOR_ATTACHMENT0,
+                    gl::TEXTURE_2D,
+                    self.texture,
+                    0,
+                );
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            }
+        }
+        self.size = (width, height);
+    }
+
+    unsafe fn destroy(&mut self) {
+        unsafe {
+            if self.framebuffer != 0 {
+                gl::DeleteFramebuffers(1, &self.framebuffer);
+            }
+            if self.texture != 0 {
+                gl::DeleteTextures(1, &self.texture);
+            }
+        }
+        *self = Self::default();
+    }
+}
+
+/// GL resources and progress of one output's wallpaper transitions
+pub(crate) struct TransitionPass {
+    kind: TransitionKind,
+    duration: Duration,
+    /// Copy of the last finished frame
+    latest: FrameCopy,
+    /// Whether `latest` holds a frame of the current wallpaper
+    latest_valid: bool,
+    /// Last frame of the previous wallpaper
+    outgoing: FrameCopy,
+    /// A wallpaper was swapped in and its first frame has not arrived yet
+    pending: bool,
+    /// When the outgoing frame started to leave
+    started: Option<Instant>,
+    program: u32,
+    vertex_array: u32,
+}
+
+impl TransitionPass {
+    /// A pass is only needed when a transition is configured
+    pub(crate) fn new(kind: TransitionKind, duration: Duration) -> Option<Self> {
+        if kind == TransitionKind::None || duration.is_zero() {
+            return None;
+        }
+
+        Some(Self {
+            kind,
+            duration,
+            latest: FrameCopy::default(),
+            latest_valid: false,
+            outgoing: FrameCopy::default(),
+            pending: false,
+            started: None,
+            program: 0,
+            vertex_array: 0,
+        })
+    }
+
+    /// A new wallpaper replaces the current one: keep its last frame as the
+    /// outgoing frame
+    pub(crate) fn begin(&mut self) {
+        if !self.latest_valid {
+            return;
+        }
+        std::mem::swap(&mut self.latest, &mut self.outgoing);
+        self.latest_valid = false;
+        self.pending = true;
+        self.started = None;
+    }
+
+    /// Whether frames have to be drawn even without a new decoded frame
+    pub(crate) fn is_animating(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Copy a freshly drawn frame; starts the transition on the new
+    /// wallpaper's first frame
+    ///
+    /// Must run with the output's context current, after the frame was drawn
+    /// and before the buffers are swapped.
+    pub(crate) fn capture(&mut self, width: i32, height: i32) {
+        unsafe { self.latest.capture(width, height) };
+        self.latest_valid = true;
+        if self.pending {
+            self.pending = false;
+            self.started = Some(Instant::now());
+        }
+    }
+
+    /// Draw the latest copy into the default framebuffer again
+    pub(crate) fn redraw(&self, width: i32, height: i32) {
+        if self.latest_valid {
+            unsafe { self.latest.restore(width, height) };
+        }
+    }
+
+    /// Draw the outgoing frame over the default framebuffer; finishes the
+    /// transition once its duration has passed
+    pub(crate) fn draw_overlay(&mut self, width: i32, height: i32) {
+        let Some(started) = self.started else {
+            return;
+        };
+        let progress = started.elapsed().as_secs_f32() / self.duration.as_secs_f32();
+        if progress >= 1.0 {
+            self.started = None;
+            return;
+        }
+
+        let overlay = Overlay::at(self.kind, progress);
+        unsafe {
+            if !self.ensure_program() {
+                self.started = None;
+                return;
+            }
+
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Viewport(0, 0, width, height);
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+
+            gl::UseProgram(self.program);
+            gl::Uniform2f(self.uniform("u_offset"), overlay.offset_x, 0.0);
+            gl::Uniform1f(self.uniform("u_scale"), overlay.scale);
+            gl::Uniform1f(self.uniform("u_alpha"), overlay.alpha);
+            gl::Uniform1i(self.uniform("u_frame"), 0);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.outgoing.texture);
+            gl::BindVertexArray(self.vertex_array);
+            gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+
+            gl::BindVertexArray(0);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::UseProgram(0);
+            gl::Disable(gl::BLEND);
+        }
+    }
+
+    /// Release GL objects; the output's context must be current
+    pub(crate) fn destroy(&mut self) {
+        unsafe {
+            self.latest.destroy();
+            self.outgoing.destroy();
+            if self.program != 0 {
+                gl::DeleteProgram(self.program);
+            }
+            if self.vertex_array != 0 {
+                gl::DeleteVertexArrays(1, &self.vertex_array);
+            }
+        }
+        self.program = 0;
+        self.vertex_array = 0;
+        self.latest_valid = false;
+        self.pending = false;
+        self.started = None;
+    }
+
+    unsafe fn uniform(&self, name: &str) -> i32 {
+        let name = CString::new(name).unwrap();
+        unsafe { gl::GetUniformLocation(self.program, name.as_ptr()) }
+    }
+
+    /// Compile the overlay program on first use; false if it failed
+    unsafe fn ensure_program(&mut self) -> bool {
+        if self.program != 0 {
+            return true;
+        }
+
+        unsafe {
+            let (Some(vertex), Some(fragment)) = (
+                compile_shader(gl::VERTEX_SHADER, VERTEX_SHADER),
+                compile_shader(gl::FRAGMENT_SHADER, FRAGMENT_SHADER),
+            ) else {
+                return false;
+            };
+
+            let program = gl::CreateProgram();
+            gl::AttachShader(program, vertex);
+            gl::AttachShader(program, fragment);
+            gl::LinkProgram(program);
+            gl::DeleteShader(vertex);
+            gl::DeleteShader(fragment);
+
+            let mut linked = 0;
+            gl::GetProgramiv(program, gl::LINK_STATUS, &mut linked);
+            if linked == 0 {
+                warn!("Transition shader failed to link, transitions are disabled");
+                gl::DeleteProgram(program);
+                self.kind = TransitionKind::None;
+                return false;
+            }
+
+            self.program = program;
+            gl::GenVertexArrays(1, &mut self.vertex_array);
+        }
+        true
+    }
+}
+
+unsafe fn compile_shader(kind: u32, source: &str) -> Option<u32> {
+    let source = CString::new(source).unwrap();
+    unsafe {
+        let shader = gl::CreateShader(kind);
+        gl::ShaderSource(shader, 1, &source.as_ptr(), std::ptr::null());
+        gl::CompileShader(shader);
+
+        let mut compiled = 0;
+        gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut compiled);
+        if compiled == 0 {
+            warn!("Transition shader failed to compile");
+            gl::DeleteShader(shader);
+            return None;
+        }
+        Some(shader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_starts_on_the_old_frame_and_ends_off_it() {
+        for kind in [
+            TransitionKind::Crossfade,
+            TransitionKind::Slide,
+            TransitionKind::Zoom,
+        ] {
+            assert_eq!(
+                Overlay::at(kind, 0.0),
+                Overlay {
+                    offset_x: 0.0,
+                    scale: 1.0,
+                    alpha: 1.0
+                }
+            );
+        }
+
+        assert_eq!(Overlay::at(TransitionKind::Crossfade, 1.0).alpha, 0.0);
+        assert_eq!(Overlay::at(TransitionKind::Slide, 1.0).offset_x, -2.0);
+        assert_eq!(Overlay::at(TransitionKind::Zoom, 1.0).scale, 1.25);
+        assert_eq!(Overlay::at(TransitionKind::Crossfade, 0.5).alpha, 0.5);
+    }
+
+    #[test]
+    fn test_no_pass_without_a_transition() {
+        assert!(TransitionPass::new(TransitionKind::None, Duration::from_millis(500)).is_none());
+        assert!(TransitionPass::new(TransitionKind::Slide, Duration::ZERO).is_none());
+    }
+}
@@ -4,6 +4,7 @@
 //! Uses smithay-client-toolkit (sctk) for higher-level abstractions.
 
 use anyhow::{Context, Result};
+use lwe_core::SurfaceLayer;
 use smithay_client_toolkit::{
     compositor::{CompositorHandler, CompositorState},
     delegate_compositor, delegate_layer, delegate_output, delegate_registry,
@@ -33,6 +34,8 @@ pub struct LayerSurfaceConfig {
     pub width: u32,
     /// Surface height (0 = match output)
     pub height: u32,
+    /// Layer to place the surface on
+    pub layer: SurfaceLayer,
     /// Exclusive zone (-1 = ignore, 0 = auto, >0 = pixels)
     pub exclusive_zone: i32,
     /// Margin from edges
@@ -47,6 +50,7 @@ impl Default for LayerSurfaceConfig {
             output: None,
             width: 0,
             height: 0,
+            layer: SurfaceLayer::Background,
             exclusive_zone: -1,
             margin: LayerMargin::default(),
             keyboard_interactive: false,
@@ -101,11 +105,15 @@ impl LayerSurface {
         // Create the underlying wl_surface
         let surface = compositor.create_surface(qh);
 
-        // Create layer surface on the background layer (for wallpaper)
+        // Create layer surface on the configured layer, background by default
+        let shell_layer = match config.layer {
+            SurfaceLayer::Background => Layer::Background,
+            SurfaceLayer::Bottom => Layer::Bottom,
+        };
         let layer = layer_shell.create_layer_surface(
             qh,
             surface,
-            Layer::Background,
+            shell_layer,
             Some("wayvid-wallpaper"),
             output,
         );
@@ -386,6 +394,11 @@ impl LayerSurfaceBuilder {
         self
     }
 
+    pub fn layer(mut self, layer: SurfaceLayer) -> Self {
+        self.config.layer = layer;
+        self
+    }
+
     pub fn exclusive_zone(mut self, zone: i32) -> Self {
         self.config.exclusive_zone = zone;
         self
@@ -0,0 +1,379 @@
+//! Compositor-specific workarounds
+//!
+//! Known compositor quirks are kept in a single registry keyed by the
+//! detected compositor. The engine resolves the registry once
+//! at startup and reads the resulting [`Workarounds`] instead of checking for
+//! particular compositors at call sites. Every toggle can be overridden from
+//! the engine configuration.
+
+use std::fmt;
+use std::process::Command;
+use std::time::Duration;
+
+use tracing::debug;
+
+/// Compositors with known quirks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompositorKind {
+    Hyprland,
+    Niri,
+    Sway,
+    Kwin,
+    Labwc,
+    Unknown,
+}
+
+impl CompositorKind {
+    /// Parse a compositor name as written in configuration
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "hyprland" => Some(Self::Hyprland),
+            "niri" => Some(Self::Niri),
+            "sway" => Some(Self::Sway),
+            "kwin" | "kde" => Some(Self::Kwin),
+            "labwc" => Some(Self::Labwc),
+            "unknown" => Some(Self::Unknown),
+            _ => None,
+        }
+    }
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Hyprland => "Hyprland",
+            Self::Niri => "niri",
+            Self::Sway => "sway",
+            Self::Kwin => "KWin",
+            Self::Labwc => "labwc",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// Binary that reports the compositor version with `--version`
+    fn version_binary(self) -> Option<&'static str> {
+        match self {
+            Self::Hyprland => Some("Hyprland"),
+            Self::Niri => Some("niri"),
+            Self::Sway => Some("sway"),
+            Self::Kwin => Some("kwin_wayland"),
+            Self::Labwc => Some("labwc"),
+            Self::Unknown => None,
+        }
+    }
+}
+
+/// Compositor release version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CompositorVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl CompositorVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Find the first `major.minor[.patch]` in `--version` output
+    pub fn parse(text: &str) -> Option<Self> {
+        text.split(|ch: char| !(ch.is_ascii_digit() || ch == '.'))
+            .find_map(|token| {
+                let mut parts = token.split('.').filter(|part| !part.is_empty());
+                let major = parts.next()?.parse().ok()?;
+                let minor = parts.next()?.parse().ok()?;
+                let patch = parts.next().and_then(|part| part.parse().ok()).unwrap_or(0);
+                Some(Self::new(major, minor, patch))
+            })
+    }
+}
+
+impl fmt::Display for CompositorVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Compositor the engine is running under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectedCompositor {
+    pub kind: CompositorKind,
+    pub version: Option<CompositorVersion>,
+}
+
+impl DetectedCompositor {
+    /// Detect the compositor from the session environment and query its version
+    pub fn detect() -> Self {
+        let kind = detect_kind(|key| std::env::var(key).ok());
+        Self {
+            kind,
+            version: query_version(kind),
+        }
+    }
+}
+
+impl fmt::Display for DetectedCompositor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.version {
+            Some(version) => write!(f, "{} {}", self.kind.name(), version),
+            None => f.write_str(self.kind.name()),
+        }
+    }
+}
+
+/// Resolved quirk toggles used by the engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workarounds {
+    /// Exclusive zone requested for wallpaper layer surfaces
+    pub exclusive_zone: i32,
+    /// Render at the integer output scale via `wl_surface.set_buffer_scale`
+    pub buffer_scale: bool,
+    /// Size the surface from the output mode when configure reports 0x0
+    pub output_size_fallback: bool,
+    /// Render anyway when no frame callback arrived within this interval
+    pub frame_callback_fallback: Option<Duration>,
+    /// Registry entries that were applied, for logs and diagnostics
+    pub applied: Vec<&'static str>,
+}
+
+impl Default for Workarounds {
+    fn default() -> Self {
+        Self {
+            // -1 ignores other surfaces' exclusive zones (e.g., top bars)
+            exclusive_zone: -1,
+            buffer_scale: false,
+            output_size_fallback: false,
+            frame_callback_fallback: None,
+            applied: Vec::new(),
+        }
+    }
+}
+
+/// User overrides for compositor detection and individual quirks
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkaroundOverrides {
+    /// Use this compositor's quirks instead of the detected one
+    pub compositor: Option<CompositorKind>,
+    pub exclusive_zone: Option<i32>,
+    pub buffer_scale: Option<bool>,
+    pub output_size_fallback: Option<bool>,
+    /// `Some(Duration::ZERO)` disables the fallback
+    pub frame_callback_fallback: Option<Duration>,
+}
+
+/// Registry entry for a known compositor quirk
+struct Quirk {
+    id: &'static str,
+    compositor: CompositorKind,
+    apply: fn(&mut Workarounds),
+}
+
+const QUIRKS: &[Quirk] = &[
+    // Frame callbacks for background surfaces can stop after a monitor
+    // hot-plug until the next configure, freezing the wallpaper.
+    Quirk {
+        id: "hyprland-frame-callback-stall",
+        compositor: CompositorKind::Hyprland,
+        apply: |workarounds| {
+            workarounds.frame_callback_fallback = Some(Duration::from_millis(500));
+        },
+    },
+    // sway only scales buffers by integer factors, so rendering at the output
+    // scale keeps HiDPI wallpapers sharp instead of upscaling them.
+    Quirk {
+        id: "sway-integer-buffer-scale",
+        compositor: CompositorKind::Sway,
+        apply: |workarounds| workarounds.buffer_scale = true,
+    },
+    // KWin can offset background surfaces by panel struts when they ask to
+    // ignore exclusive zones; a zero zone keeps them covering the output.
+    Quirk {
+        id: "kwin-background-exclusive-zone",
+        compositor: CompositorKind::Kwin,
+        apply: |workarounds| workarounds.exclusive_zone = 0,
+    },
+    // labwc can send a 0x0 configure for fully anchored surfaces.
+    Quirk {
+        id: "labwc-zero-size-configure",
+        compositor: CompositorKind::Labwc,
+        apply: |workarounds| workarounds.output_size_fallback = true,
+    },
+    // Unrecognized compositors get the harmless size guard.
+    Quirk {
+        id: "unknown-zero-size-configure",
+        compositor: CompositorKind::Unknown,
+        apply: |workarounds| workarounds.output_size_fallback = true,
+    },
+];
+
+impl Workarounds {
+    /// Apply registry entries for the compositor, then user overrides
+    pub fn resolve(detected: &DetectedCompositor, overrides: &WorkaroundOverrides) -> Self {
+        let mut workarounds = Self::default();
+
+        let kind = overrides.compositor.unwrap_or(detected.kind);
+        for quirk in QUIRKS.iter().filter(|quirk| quirk.compositor == kind) {
+            (quirk.apply)(&mut workarounds);
+            workarounds.applied.push(quirk.id);
+        }
+
+        if let Some(zone) = overrides.exclusive_zone {
+            workarounds.exclusive_zone = zone;
+        }
+        if let Some(enabled) = overrides.buffer_scale {
+            workarounds.buffer_scale = enabled;
+        }
+        if let Some(enabled) = overrides.output_size_fallback {
+            workarounds.output_size_fallback = enabled;
+        }
+        if let Some(interval) = overrides.frame_callback_fallback {
+            workarounds.frame_callback_fallback = (!interval.is_zero()).then_some(interval);
+        }
+
+        workarounds
+    }
+}
+
+fn detect_kind(lookup: impl Fn(&str) -> Option<String>) -> CompositorKind {
+    let is_set = |key: &str| lookup(key).is_some_and(|value| !value.is_empty());
+
+    if is_set("HYPRLAND_INSTANCE_SIGNATURE") {
+        return CompositorKind::Hyprland;
+    }
+    if is_set("NIRI_SOCKET") {
+        return CompositorKind::Niri;
+    }
+    if is_set("SWAYSOCK") {
+        return CompositorKind::Sway;
+    }
+    if is_set("LABWC_PID") {
+        return CompositorKind::Labwc;
+    }
+
+    lookup("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .split(':')
+        .find_map(CompositorKind::from_name)
+        .unwrap_or(CompositorKind::Unknown)
+}
+
+fn query_version(kind: CompositorKind) -> Option<CompositorVersion> {
+    let binary = kind.version_binary()?;
+    let output = match Command::new(binary).arg("--version").output() {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            debug!("{} --version exited with {}", binary, output.status);
+            return None;
+        }
+        Err(e) => {
+            debug!("Could not run {} --version: {}", binary, e);
+            return None;
+        }
+    };
+
+    CompositorVersion::parse(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(kind: CompositorKind, version: Option<CompositorVersion>) -> DetectedCompositor {
+        DetectedCompositor { kind, version }
+    }
+
+    #[test]
+    fn test_detect_kind_prefers_compositor_sockets_over_desktop_name() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |key: &str| {
+                pairs
+                    .iter()
+                    .find(|(name, _)| *name == key)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert_eq!(
+            detect_kind(env(&[
+                ("XDG_CURRENT_DESKTOP", "sway"),
+                ("NIRI_SOCKET", "/run/niri.sock")
+            ])),
+            CompositorKind::Niri
+        );
+        assert_eq!(
+            detect_kind(env(&[("XDG_CURRENT_DESKTOP", "KDE")])),
+            CompositorKind::Kwin
+        );
+        assert_eq!(
+            detect_kind(env(&[("XDG_CURRENT_DESKTOP", "GNOME")])),
+            CompositorKind::Unknown
+        );
+    }
+
+    #[test]
+    fn test_version_parse_skips_leading_words() {
+        assert_eq!(
+            CompositorVersion::parse("Hyprland 0.45.2 built from branch main"),
+            Some(CompositorVersion::new(0, 45, 2))
+        );
+        assert_eq!(
+            CompositorVersion::parse("sway version 1.9"),
+            Some(CompositorVersion::new(1, 9, 0))
+        );
+        assert_eq!(CompositorVersion::parse("niri unknown"), None);
+    }
+
+    #[test]
+    fn test_quirks_apply_to_every_version_of_their_compositor() {
+        for version in [
+            Some(CompositorVersion::new(5, 27, 11)),
+            Some(CompositorVersion::new(6, 1, 0)),
+            None,
+        ] {
+            let workarounds = Workarounds::resolve(
+                &detected(CompositorKind::Kwin, version),
+                &WorkaroundOverrides::default(),
+            );
+            assert_eq!(workarounds.exclusive_zone, 0);
+            assert_eq!(workarounds.applied, vec!["kwin-background-exclusive-zone"]);
+        }
+
+        let niri = Workarounds::resolve(
+            &detected(CompositorKind::Niri, Some(CompositorVersion::new(25, 2, 0))),
+            &WorkaroundOverrides::default(),
+        );
+        assert!(niri.applied.is_empty());
+    }
+
+    #[test]
+    fn test_overrides_select_compositor_and_replace_toggles() {
+        let workarounds = Workarounds::resolve(
+            &detected(CompositorKind::Niri, Some(CompositorVersion::new(25, 2, 0))),
+            &WorkaroundOverrides {
+                compositor: Some(CompositorKind::Hyprland),
+                buffer_scale: Some(true),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(workarounds.applied, vec!["hyprland-frame-callback-stall"]);
+        assert_eq!(
+            workarounds.frame_callback_fallback,
+            Some(Duration::from_millis(500))
+        );
+        assert!(workarounds.buffer_scale);
+
+        let disabled = Workarounds::resolve(
+            &detected(CompositorKind::Hyprland, None),
+            &WorkaroundOverrides {
+                frame_callback_fallback: Some(Duration::ZERO),
+                ..Default::default()
+            },
+        );
+        assert_eq!(disabled.frame_callback_fallback, None);
+    }
+}
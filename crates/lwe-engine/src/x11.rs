@@ -0,0 +1,641 @@
+//! X11 fallback backend
+//!
+//! X11 and XWayland-only sessions have no layer shell. Like xwinwrap, each
+//! monitor gets a desktop-type window kept below everything else, and mpv
+//! draws into it through `--wid`. The backend answers the same commands and
+//! sends the same events as the Wayland engine, so the shell and IPC do not
+//! care which one is running. Animated images, custom shaders, letterbox
+//! fills and palette sampling need the EGL renderer and are Wayland-only.
+
+use std::collections::HashMap;
+use std::ffi::{CStr, CString, OsStr};
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use calloop::EventLoop;
+use tracing::{debug, info, warn};
+use x11_dl::xlib::{self, Xlib};
+use x11_dl::xrandr::Xrandr;
+
+use lwe_core::{OutputHdrCapabilities, OutputInfo, RenderBackend};
+
+use crate::engine::{
+    EngineCommand, EngineConfig, EngineEvent, EngineRequest, EngineStatus, EventSender,
+    OutputPlayback, OutputStats, RequestId,
+};
+use crate::mpv::{MpvPlayer, Transport};
+use crate::probe::probe_source;
+
+/// How long the event loop waits for a command before checking for shutdown
+const X11_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Use the X11 backend when there is an X display but no Wayland display
+pub fn should_use_x11() -> bool {
+    select_x11(
+        std::env::var_os("WAYLAND_DISPLAY").as_deref(),
+        std::env::var_os("DISPLAY").as_deref(),
+    )
+}
+
+fn select_x11(wayland_display: Option<&OsStr>, x_display: Option<&OsStr>) -> bool {
+    let set = |value: Option<&OsStr>| value.is_some_and(|value| !value.is_empty());
+    !set(wayland_display) && set(x_display)
+}
+
+/// Connection to the X server and the libraries loaded for it
+struct X11Display {
+    xlib: Xlib,
+    xrandr: Option<Xrandr>,
+    display: *mut xlib::Display,
+    root: xlib::Window,
+}
+
+impl X11Display {
+    fn open() -> Result<Self> {
+        let xlib = Xlib::open().map_err(|e| anyhow!("libX11 is not available: {}", e))?;
+        let display = unsafe { (xlib.XOpenDisplay)(ptr::null()) };
+        if display.is_null() {
+            return Err(anyhow!("Failed to open the X display"));
+        }
+        let root = unsafe { (xlib.XDefaultRootWindow)(display) };
+        let xrandr = Xrandr::open()
+            .map_err(|e| warn!("libXrandr is not available, using one screen: {}", e))
+            .ok();
+
+        Ok(Self {
+            xlib,
+            xrandr,
+            display,
+            root,
+        })
+    }
+
+    /// Monitors from RandR, or the whole screen as one output without it
+    fn outputs(&self) -> Vec<OutputInfo> {
+        let output = |name: String, x, y, width, height| OutputInfo {
+            name,
+            width,
+            height,
+            scale: 1.0,
+            position: (x, y),
+            active: true,
+            hdr_capabilities: OutputHdrCapabilities::default(),
+        };
+
+        if let Some(xrandr) = &self.xrandr {
+            let mut count = 0;
+            let monitors =
+                unsafe { (xrandr.XRRGetMonitors)(self.display, self.root, xlib::True, &mut count) };
+            if !monitors.is_null() {
+                let outputs =
+                    unsafe { std::slice::from_raw_parts(monitors, count.max(0) as usize) }
+                        .iter()
+                        .map(|monitor| {
+                            output(
+                                self.atom_name(monitor.name),
+                                monitor.x,
+                                monitor.y,
+                                monitor.width,
+                                monitor.height,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                unsafe { (xrandr.XRRFreeMonitors)(monitors) };
+                if !outputs.is_empty() {
+                    return outputs;
+                }
+            }
+        }
+
+        let screen = unsafe { (self.xlib.XDefaultScreen)(self.display) };
+        let (width, height) = unsafe {
+            (
+                (self.xlib.XDisplayWidth)(self.display, screen),
+                (self.xlib.XDisplayHeight)(self.display, screen),
+            )
+        };
+        vec![output("X11".to_string(), 0, 0, width, height)]
+    }
+
+    fn atom_name(&self, atom: xlib::Atom) -> String {
+        let name = unsafe { (self.xlib.XGetAtomName)(self.display, atom) };
+        if name.is_null() {
+            return format!("X11-{}", atom);
+        }
+        let owned = unsafe { CStr::from_ptr(name) }
+            .to_string_lossy()
+            .into_owned();
+        unsafe { (self.xlib.XFree)(name.cast()) };
+        owned
+    }
+
+    fn atom(&self, name: &str) -> xlib::Atom {
+        let name = CString::new(name).unwrap();
+        unsafe { (self.xlib.XInternAtom)(self.display, name.as_ptr(), xlib::False) }
+    }
+
+    /// Borderless window covering `output`, typed as the desktop so window
+    /// managers keep it below every other window
+    fn create_desktop_window(&self, output: &OutputInfo) -> xlib::Window {
+        let mut attributes: xlib::XSetWindowAttributes = unsafe { std::mem::zeroed() };
+        let window = unsafe {
+            (self.xlib.XCreateWindow)(
+                self.display,
+                self.root,
+                output.position.0,
+                output.position.1,
+                output.width.max(1) as u32,
+                output.height.max(1) as u32,
+                0,
+                xlib::CopyFromParent,
+                xlib::InputOutput as u32,
+                ptr::null_mut(),
+                xlib::CWBackPixel,
+                &mut attributes,
+            )
+        };
+
+        let set_atoms = |property: &str, values: &[xlib::Atom]| unsafe {
+            (self.xlib.XChangeProperty)(
+                self.display,
+                window,
+                self.atom(property),
+                xlib::XA_ATOM,
+                32,
+                xlib::PropModeReplace,
+                values.as_ptr().cast(),
+                values.len() as i32,
+            );
+        };
+        set_atoms(
+            "_NET_WM_WINDOW_TYPE",
+            &[self.atom("_NET_WM_WINDOW_TYPE_DESKTOP")],
+        );
+        set_atoms(
+            "_NET_WM_STATE",
+            &[
+                self.atom("_NET_WM_STATE_BELOW"),
+                self.atom("_NET_WM_STATE_STICKY"),
+                self.atom("_NET_WM_STATE_SKIP_TASKBAR"),
+                self.atom("_NET_WM_STATE_SKIP_PAGER"),
+            ],
+        );
+
+        unsafe {
+            (self.xlib.XMapWindow)(self.display, window);
+            (self.xlib.XLowerWindow)(self.display, window);
+            (self.xlib.XFlush)(self.display);
+        }
+        window
+    }
+
+    fn destroy_window(&self, window: xlib::Window) {
+        unsafe {
+            (self.xlib.XDestroyWindow)(self.display, window);
+            (self.xlib.XFlush)(self.display);
+        }
+    }
+}
+
+impl Drop for X11Display {
+    fn drop(&mut self) {
+        unsafe { (self.xlib.XCloseDisplay)(self.display) };
+    }
+}
+
+/// Wallpaper playing in one output's desktop window
+struct X11Session {
+    window: xlib::Window,
+    player: MpvPlayer,
+    path: PathBuf,
+    paused: bool,
+}
+
+struct X11State {
+    x11: X11Display,
+    outputs: Vec<OutputInfo>,
+    sessions: HashMap<String, X11Session>,
+    config: EngineConfig,
+    events_tx: EventSender,
+    /// Outputs paused while the screensaver is active
+    screensaver_paused: Vec<String>,
+}
+
+/// Run the X11 backend in the current thread until shutdown is requested
+pub(crate) fn run_x11_engine(
+    config: EngineConfig,
+    events_tx: EventSender,
+    commands_rx: calloop::channel::Channel<EngineRequest>,
+    shutdown: Arc<AtomicBool>,
+) -> Result<()> {
+    info!("Starting PlaybackEngine on the X11 fallback backend");
+
+    let x11 = X11Display::open()?;
+    let outputs = x11.outputs();
+    for output in &outputs {
+        info!(
+            "X11 output {} ({}x{} at {},{})",
+            output.name, output.width, output.height, output.position.0, output.position.1
+        );
+    }
+
+    let mut state = X11State {
+        x11,
+        outputs,
+        sessions: HashMap::new(),
+        config,
+        events_tx: events_tx.clone(),
+        screensaver_paused: Vec::new(),
+    };
+
+    let mut event_loop: EventLoop<'static, X11State> =
+        EventLoop::try_new().context("Failed to create event loop")?;
+    event_loop
+        .handle()
+        .insert_source(commands_rx, |event, _, state| {
+            if let calloop::channel::Event::Msg(request) = event {
+                let _span =
+                    tracing::debug_span!("request", id = request.id.map(|id| id.0)).entered();
+                handle_x11_command(request.command, request.id, state);
+            }
+        })
+        .map_err(|e| anyhow!("Failed to register command channel: {:?}", e))?;
+
+    let _ = events_tx.send(EngineEvent::Started);
+    for output in &state.outputs {
+        let _ = events_tx.send(EngineEvent::OutputAdded(output.clone()));
+    }
+
+    while !shutdown.load(Ordering::Relaxed) {
+        event_loop
+            .dispatch(X11_POLL_INTERVAL, &mut state)
+            .context("Event loop dispatch failed")?;
+        events_tx.flush();
+    }
+
+    info!("PlaybackEngine (X11) shutting down");
+    let outputs: Vec<String> = state.sessions.keys().cloned().collect();
+    for output in outputs {
+        clear_output(&mut state, &output);
+    }
+    let _ = events_tx.send(EngineEvent::Stopped);
+
+    Ok(())
+}
+
+fn handle_x11_command(cmd: EngineCommand, request: Option<RequestId>, state: &mut X11State) {
+    match cmd {
+        EngineCommand::ApplyWallpaper {
+            path,
+            output,
+            shader,
+            lut,
+        } => {
+            if let Err(error) = probe_source(&path) {
+                warn!("Rejected wallpaper {}: {}", path.display(), error);
+                let _ = state
+                    .events_tx
+                    .reply(request, EngineEvent::SourceRejected { path, error });
+                return;
+            }
+            if shader.is_some() || lut.is_some() {
+                warn!("Custom shaders and LUTs are not applied on the X11 backend");
+            }
+
+            for output in target_outputs(state, output.as_deref()) {
+                match apply_to_output(state, &output, &path) {
+                    Ok(()) => {
+                        let _ = state.events_tx.reply(
+                            request,
+                            EngineEvent::WallpaperApplied {
+                                output: output.name.clone(),
+                                path: path.clone(),
+                            },
+                        );
+                    }
+                    Err(e) => {
+                        warn!("Failed to apply wallpaper to {}: {:#}", output.name, e);
+                        let _ = state
+                            .events_tx
+                            .reply(request, EngineEvent::Error(e.to_string()));
+                    }
+                }
+            }
+        }
+
+        EngineCommand::ClearWallpaper { output } => {
+            for output in target_outputs(state, output.as_deref()) {
+                if clear_output(state, &output.name) {
+                    let _ = state.events_tx.reply(
+                        request,
+                        EngineEvent::WallpaperCleared {
+                            output: output.name.clone(),
+                        },
+                    );
+                }
+            }
+        }
+
+        EngineCommand::SetVolume { output, volume } => {
+            if let Some(session) = state.sessions.get_mut(&output) {
+                let _ = session.player.set_volume(f64::from(volume.clamp(0.0, 1.0)));
+            }
+        }
+
+        EngineCommand::SetMute { output, muted } => {
+            if let Some(session) = state.sessions.get_mut(&output) {
+                if let Err(e) = session.player.set_muted(muted) {
+                    warn!("Failed to change mute on {}: {}", output, e);
+                }
+            }
+        }
+
+        EngineCommand::SetAudioDevice { output, device } => {
+            if let Some(session) = state.sessions.get_mut(&output) {
+                if let Err(e) = session.player.set_audio_device(device.as_deref()) {
+                    warn!("Failed to switch audio device on {}: {}", output, e);
+                }
+            }
+        }
+
+        EngineCommand::SetColor { output, color } => {
+            if let Some(session) = state.sessions.get_mut(&output) {
+                if let Err(e) = session.player.set_color(color) {
+                    warn!("Failed to change colors on {}: {}", output, e);
+                }
+            }
+        }
+
+        EngineCommand::PreloadWallpaper { output, .. } => {
+            // Each embedded player owns its window, so there is nothing to swap in
+            debug!(
+                "Preloading is not supported by the X11 backend ({})",
+                output
+            );
+        }
+
+        EngineCommand::SetIdleInhibit { output, inhibit } => {
+            if inhibit {
+                debug!(
+                    "Idle inhibit requested for {}, which the X11 backend does not support",
+                    output
+                );
+            }
+        }
+
+        EngineCommand::SetScreenSaverActive { active } => {
+            if state.config.pause_on_idle.is_none() {
+                return;
+            }
+            if active {
+                for (name, session) in &mut state.sessions {
+                    if !session.paused {
+                        let _ = session.player.pause();
+                        session.paused = true;
+                        state.screensaver_paused.push(name.clone());
+                    }
+                }
+            } else {
+                for name in std::mem::take(&mut state.screensaver_paused) {
+                    if let Some(session) = state.sessions.get_mut(&name) {
+                        let _ = session.player.resume();
+                        session.paused = false;
+                    }
+                }
+            }
+        }
+
+        EngineCommand::Pause { output } => {
+            // A user pause outlasts the screensaver
+            state
+                .screensaver_paused
+                .retain(|name| output.as_ref().is_some_and(|output| output != name));
+            for (name, session) in &mut state.sessions {
+                if output.as_ref().is_none_or(|output| output == name) {
+                    let _ = session.player.pause();
+                    session.paused = true;
+                }
+            }
+        }
+
+        EngineCommand::Resume { output } => {
+            for (name, session) in &mut state.sessions {
+                if output.as_ref().is_none_or(|output| output == name) {
+                    let _ = session.player.resume();
+                    session.paused = false;
+                }
+            }
+        }
+
+        EngineCommand::Transport { output, transport } => {
+            for (name, session) in &mut state.sessions {
+                if output.as_ref().is_none_or(|output| output == name) {
+                    // mpv pauses on the stepped frame
+                    if matches!(transport, Transport::FrameStep { .. }) {
+                        session.paused = true;
+                    }
+                    if let Err(e) = session.player.transport(transport) {
+                        warn!("Failed to apply {:?} on {}: {}", transport, name, e);
+                    }
+                }
+            }
+        }
+
+        EngineCommand::GetOutputs => {
+            let _ = state
+                .events_tx
+                .reply(request, EngineEvent::OutputsList(state.outputs.clone()));
+        }
+
+        EngineCommand::GetStatus => {
+            let status = EngineStatus {
+                running: true,
+                outputs: state.outputs.clone(),
+                active_wallpapers: state
+                    .sessions
+                    .iter()
+                    .map(|(name, session)| (name.clone(), Some(session.path.clone())))
+                    .collect(),
+                playback: state
+                    .sessions
+                    .iter()
+                    .map(|(name, session)| {
+                        (
+                            name.clone(),
+                            OutputPlayback {
+                                paused: session.paused,
+                                position: session.player.position(),
+                                media: session.player.media_info(),
+                                // mpv draws into the window itself, unseen here
+                                frame_rate: None,
+                            },
+                        )
+                    })
+                    .collect(),
+                cgroup: crate::cgroup::cgroup_usage(),
+                // mpv renders into the root window with its own context
+                gpu_messages: HashMap::new(),
+                // the root window has no fallback chain
+                failed_wallpapers: HashMap::new(),
+                // battery pausing is not wired up on X11
+                on_battery: false,
+                power_paused: false,
+                deep_sleep: false,
+                render_backend: RenderBackend::OpenGL,
+            };
+            let _ = state.events_tx.reply(request, EngineEvent::Status(status));
+        }
+
+        EngineCommand::GetStats => {
+            let stats = state
+                .sessions
+                .iter()
+                .map(|(name, session)| {
+                    (
+                        name.clone(),
+                        OutputStats {
+                            // mpv draws into the window itself, unseen here
+                            render_time: None,
+                            frame_rate: None,
+                            dropped_frames: session.player.dropped_frames(),
+                            hwdec: session.player.media_info().hwdec,
+                        },
+                    )
+                })
+                .collect();
+            let _ = state.events_tx.reply(request, EngineEvent::Stats(stats));
+        }
+
+        EngineCommand::Snapshot { output, path } => {
+            // mpv draws into the window itself, so it takes the screenshot
+            let event = match state.sessions.get(&output) {
+                Some(session) => match session.player.screenshot(&path) {
+                    Ok(()) => EngineEvent::SnapshotSaved { output, path },
+                    Err(e) => EngineEvent::Error(format!("Snapshot of {output} failed: {e}")),
+                },
+                None => EngineEvent::Error(format!("No wallpaper on {output}")),
+            };
+            let _ = state.events_tx.reply(request, event);
+        }
+
+        EngineCommand::SetMpvProperty {
+            output,
+            property,
+            value,
+        } => {
+            let event = match state.sessions.get_mut(&output) {
+                Some(session) => match session.player.set_property(&property, &value) {
+                    Ok(()) => EngineEvent::MpvPropertySet { output, property },
+                    Err(e) => EngineEvent::Error(format!("{e}")),
+                },
+                None => EngineEvent::Error(format!("No wallpaper on {output}")),
+            };
+            let _ = state.events_tx.reply(request, event);
+        }
+
+        EngineCommand::GetPalette { .. } => {
+            // No frame readback without the EGL renderer
+            let _ = state
+                .events_tx
+                .reply(request, EngineEvent::Palettes(Vec::new()));
+        }
+
+        EngineCommand::UpdateConfig(config) => {
+            // Running players take the new audio and colors; the rest of the
+            // settings reach the players created after this
+            for (name, session) in &mut state.sessions {
+                let video = config.video_for_output(name);
+                let _ = session.player.set_volume(video.volume);
+                if let Err(e) = session.player.set_muted(video.mute) {
+                    warn!("Failed to change mute on {}: {}", name, e);
+                }
+                if let Err(e) = session
+                    .player
+                    .set_audio_device(video.audio_device.as_deref())
+                {
+                    warn!("Failed to switch audio device on {}: {}", name, e);
+                }
+                if let Err(e) = session.player.set_color(video.color) {
+                    warn!("Failed to change colors on {}: {}", name, e);
+                }
+            }
+            state.config = *config;
+        }
+
+        EngineCommand::Shutdown => {
+            debug!("Shutdown requested");
+        }
+    }
+}
+
+fn target_outputs(state: &X11State, output: Option<&str>) -> Vec<OutputInfo> {
+    state
+        .outputs
+        .iter()
+        .filter(|info| output.is_none_or(|output| output == info.name))
+        .cloned()
+        .collect()
+}
+
+fn apply_to_output(state: &mut X11State, output: &OutputInfo, path: &Path) -> Result<()> {
+    // Hot-swap the file when the output already has a player
+    if let Some(session) = state.sessions.get_mut(&output.name) {
+        session.player.load_file(path)?;
+        session.path = path.to_path_buf();
+        return Ok(());
+    }
+
+    let mut video = state.config.video_for_output(&output.name);
+    video.source = path.to_string_lossy().to_string();
+    let window = state.x11.create_desktop_window(output);
+    let player = match MpvPlayer::new_embedded(&video, output, window) {
+        Ok(player) => player,
+        Err(e) => {
+            state.x11.destroy_window(window);
+            return Err(e);
+        }
+    };
+
+    state.sessions.insert(
+        output.name.clone(),
+        X11Session {
+            window,
+            player,
+            path: path.to_path_buf(),
+            paused: false,
+        },
+    );
+    Ok(())
+}
+
+/// Stop the player and remove the window; false if nothing was showing
+fn clear_output(state: &mut X11State, output: &str) -> bool {
+    let Some(session) = state.sessions.remove(output) else {
+        return false;
+    };
+    let X11Session { window, player, .. } = session;
+    // mpv must let go of the window before it is destroyed
+    drop(player);
+    state.x11.destroy_window(window);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_x11_is_only_used_without_a_wayland_display() {
+        let some = |value: &'static str| Some(OsStr::new(value));
+
+        assert!(select_x11(None, some(":0")));
+        assert!(select_x11(some(""), some(":0")));
+        assert!(!select_x11(some("wayland-1"), some(":0")));
+        assert!(!select_x11(None, None));
+        assert!(!select_x11(None, some("")));
+    }
+}
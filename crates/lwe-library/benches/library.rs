@@ -0,0 +1,219 @@
+//! Benchmarks for library scanning, thumbnails and database queries
+//!
+//! Run with `cargo bench -p lwe-library`. The database and scanner benches
+//! use a synthetic library of 10,000 items (override with
+//! `LWE_BENCH_ITEMS`), large enough for FTS, paging and index changes to
+//! show up in the numbers.
+
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use image::{Rgb, RgbImage};
+use tempfile::TempDir;
+
+use lwe_core::{LibraryFilter, LibrarySort};
+use lwe_library::{
+    FolderScanner, LibraryDatabase, SearchOptions, SortBy, SourceType, ThumbnailFormat,
+    ThumbnailGenerator, WallpaperFilter, WallpaperItem, WallpaperType,
+};
+
+const ADJECTIVES: &[&str] = &["rainy", "neon", "quiet", "golden", "frozen", "drifting"];
+const NOUNS: &[&str] = &[
+    "city", "forest", "ocean", "street", "mountain", "garden", "nebula",
+];
+const TAGS: &[&str] = &[
+    "nature", "anime", "space", "city", "abstract", "minimal", "dark", "loop",
+];
+const EXTENSIONS: &[&str] = &["mp4", "webm", "mkv", "jpg"];
+
+fn item_count() -> usize {
+    std::env::var("LWE_BENCH_ITEMS")
+        .ok()
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(10_000)
+}
+
+/// File stem of the `index`th synthetic wallpaper, e.g. `rainy_city_0042`
+fn item_name(index: usize) -> String {
+    format!(
+        "{}_{}_{index:04}",
+        ADJECTIVES[index % ADJECTIVES.len()],
+        NOUNS[index / ADJECTIVES.len() % NOUNS.len()]
+    )
+}
+
+fn synthetic_item(root: &Path, index: usize) -> WallpaperItem {
+    let name = item_name(index);
+    let extension = EXTENSIONS[index % EXTENSIONS.len()];
+    let wallpaper_type = if extension == "jpg" {
+        WallpaperType::Image
+    } else {
+        WallpaperType::Video
+    };
+    let mut item = WallpaperItem::new(
+        root.join(format!("{name}.{extension}")),
+        name.clone(),
+        SourceType::LocalFile,
+        wallpaper_type,
+    );
+    item.metadata.title = Some(name.replace('_', " "));
+    item.metadata.author = Some(format!("author{}", index % 50));
+    item.metadata.tags = vec![TAGS[index % TAGS.len()].to_string()];
+    item.metadata.file_size = Some(1_000_000 + index as u64 * 1_000);
+    item
+}
+
+/// A library database filled with synthetic items, tags and ratings
+fn synthetic_library() -> (LibraryDatabase, TempDir) {
+    let dir = TempDir::new().unwrap();
+    let database = LibraryDatabase::open(dir.path().join("library.db")).unwrap();
+    let root = PathBuf::from("/wallpapers");
+
+    for index in 0..item_count() {
+        let item = synthetic_item(&root, index);
+        database.upsert_wallpaper(&item).unwrap();
+        database
+            .add_tag_to_wallpaper(&item.id, TAGS[index % TAGS.len()])
+            .unwrap();
+        if index % 3 == 0 {
+            database.set_rating(&item.id, (index % 6) as u8).unwrap();
+        }
+        if index % 10 == 0 {
+            database.toggle_favorite(&item.id).unwrap();
+        }
+    }
+
+    (database, dir)
+}
+
+/// A folder tree of empty wallpaper files; the scanner only looks at names
+/// and sizes for these types
+fn synthetic_folder() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    for index in 0..item_count() {
+        let subdir = dir.path().join(NOUNS[index % NOUNS.len()]);
+        fs::create_dir_all(&subdir).unwrap();
+        let name = item_name(index);
+        let extension = EXTENSIONS[index % EXTENSIONS.len()];
+        fs::write(subdir.join(format!("{name}.{extension}")), b"").unwrap();
+    }
+    dir
+}
+
+fn database_benches(c: &mut Criterion) {
+    let (database, _dir) = synthetic_library();
+    let mut group = c.benchmark_group("database");
+
+    group.bench_function("list_all_by_name", |b| {
+        b.iter(|| {
+            black_box(
+                database
+                    .list_wallpapers(&WallpaperFilter::default())
+                    .unwrap(),
+            )
+        })
+    });
+    group.bench_function("fts_search", |b| {
+        b.iter(|| black_box(database.search(black_box("rainy"), Some(100)).unwrap()))
+    });
+    group.bench_function("advanced_tag_rating_search", |b| {
+        let options = SearchOptions {
+            query: Some("ocean".to_string()),
+            tags: vec!["nature".to_string(), "space".to_string()],
+            min_rating: Some(2),
+            sort_by: SortBy::Rating,
+            limit: Some(50),
+            ..SearchOptions::default()
+        };
+        b.iter(|| black_box(database.search_advanced(&options).unwrap()))
+    });
+    group.bench_function("query_library_deep_page", |b| {
+        let filter = LibraryFilter {
+            wallpaper_type: Some("video".to_string()),
+            sort: LibrarySort::DateAdded,
+            offset: item_count() / 2,
+            limit: Some(100),
+            ..LibraryFilter::default()
+        };
+        b.iter(|| black_box(database.query_library(&filter).unwrap()))
+    });
+    group.bench_function("favorites", |b| {
+        let filter = WallpaperFilter {
+            favorites_only: true,
+            ..WallpaperFilter::default()
+        };
+        b.iter(|| black_box(database.list_wallpapers(&filter).unwrap()))
+    });
+    group.bench_function("stats", |b| {
+        b.iter(|| black_box(database.get_stats().unwrap()))
+    });
+    group.bench_function("upsert_existing", |b| {
+        let item = synthetic_item(Path::new("/wallpapers"), 7);
+        b.iter(|| database.upsert_wallpaper(black_box(&item)).unwrap())
+    });
+
+    group.finish();
+}
+
+fn scanner_benches(c: &mut Criterion) {
+    let folder = synthetic_folder();
+    let scanner = FolderScanner::new();
+    let mut group = c.benchmark_group("scanner");
+    group.sample_size(20);
+
+    group.bench_function("scan_folder", |b| {
+        b.iter(|| black_box(scanner.scan_folder(folder.path(), true).unwrap()))
+    });
+    group.bench_function("scan_folder_parallel", |b| {
+        b.iter(|| black_box(scanner.scan_folder_parallel(folder.path(), true).unwrap()))
+    });
+
+    group.finish();
+}
+
+fn thumbnail_benches(c: &mut Criterion) {
+    let dir = TempDir::new().unwrap();
+    let source = dir.path().join("source.png");
+    RgbImage::from_fn(1920, 1080, |x, y| {
+        Rgb([(x % 256) as u8, (y % 256) as u8, ((x + y) % 256) as u8])
+    })
+    .save(&source)
+    .unwrap();
+
+    let mut group = c.benchmark_group("thumbnail");
+    group.sample_size(20);
+
+    for format in [ThumbnailFormat::WebP, ThumbnailFormat::Jpeg] {
+        let generator = ThumbnailGenerator::with_options(
+            320,
+            180,
+            format,
+            dir.path().join(format!("cache-{}", format.extension())),
+        );
+        group.bench_function(format!("image_1080p_{}", format.extension()), |b| {
+            b.iter_batched(
+                || {
+                    let _ = fs::remove_file(generator.cache_path(&source));
+                },
+                |()| black_box(generator.generate(&source).unwrap()),
+                BatchSize::PerIteration,
+            )
+        });
+        group.bench_function(format!("cached_{}", format.extension()), |b| {
+            generator.generate(&source).unwrap();
+            b.iter(|| black_box(generator.generate(&source).unwrap()))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    database_benches,
+    scanner_benches,
+    thumbnail_benches
+);
+criterion_main!(benches);
@@ -2,11 +2,12 @@
 //!
 //! Stores wallpaper metadata, thumbnails, and user settings.
 //! Features:
-//! - Full-text search (FTS5)
+//! - Typo-tolerant, accent-insensitive text search
 //! - Tag system with many-to-many relationships
 //! - Favorites with toggle functionality
 //! - Usage tracking and statistics
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 
@@ -15,7 +16,14 @@ use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
 use tracing::{debug, info};
 
-use lwe_core::{SourceType, WallpaperItem, WallpaperMetadata, WallpaperType};
+use crate::fuzzy::FuzzyQuery;
+use crate::scanner::{FileEvent, FolderScanner};
+use crate::thumbnail::ThumbnailStrip;
+use lwe_core::hdr::TransferFunction;
+use lwe_core::{
+    LibraryFilter, LibraryItem, LibrarySort, SourceType, WallpaperItem, WallpaperMetadata,
+    WallpaperType,
+};
 
 /// Wallpaper library database
 #[allow(clippy::arc_with_non_send_sync)] // Intentional: Connection is used in single-threaded context
@@ -64,6 +72,7 @@ impl LibraryDatabase {
 
     fn initialize_schema(&self) -> Result<()> {
         let conn = self.conn.write().unwrap();
+        Self::drop_indexed_only_thumbnails(&conn)?;
 
         conn.execute_batch(r#"
             -- Wallpaper items table
@@ -84,7 +93,12 @@ impl LibraryDatabase {
                 resolution_w INTEGER,
                 resolution_h INTEGER,
                 file_size INTEGER,
+                codec TEXT,
+                fps REAL,
+                hdr TEXT,
                 workshop_id INTEGER,
+                shader_path TEXT,
+                lut_path TEXT,
                 
                 -- Timestamps
                 added_at TEXT NOT NULL,
@@ -123,15 +137,29 @@ impl LibraryDatabase {
                 wallpaper_count INTEGER NOT NULL DEFAULT 0
             );
 
-            -- Thumbnails cache
+            -- Thumbnails cache; like strips, kept for covers that are not
+            -- indexed wallpapers, so there is no foreign key
             CREATE TABLE IF NOT EXISTS thumbnails (
                 wallpaper_id TEXT PRIMARY KEY,
                 data BLOB NOT NULL,
                 width INTEGER NOT NULL,
                 height INTEGER NOT NULL,
                 format TEXT NOT NULL DEFAULT 'webp',
-                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (wallpaper_id) REFERENCES wallpapers(id) ON DELETE CASCADE
+                source_modified INTEGER,
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- Video sprite strips for hover scrubbing; kept for items that are
+            -- not indexed yet, so there is no foreign key
+            CREATE TABLE IF NOT EXISTS thumbnail_strips (
+                wallpaper_id TEXT PRIMARY KEY,
+                data BLOB NOT NULL,
+                frame_width INTEGER NOT NULL,
+                frame_height INTEGER NOT NULL,
+                frame_count INTEGER NOT NULL,
+                format TEXT NOT NULL DEFAULT 'webp',
+                source_modified INTEGER,
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
             );
 
             -- Collections (user-defined groups)
@@ -144,14 +172,43 @@ impl LibraryDatabase {
                 FOREIGN KEY (cover_wallpaper_id) REFERENCES wallpapers(id) ON DELETE SET NULL
             );
 
-            -- Collection-wallpaper relationship
-            CREATE TABLE IF NOT EXISTS collection_wallpapers (
+            -- Collection entries; like playlist items, the ids are not
+            -- constrained so collections can hold Workshop projects and local
+            -- files that are not indexed in this database
+            CREATE TABLE IF NOT EXISTS collection_items (
                 collection_id INTEGER NOT NULL,
-                wallpaper_id TEXT NOT NULL,
+                item_id TEXT NOT NULL,
                 position INTEGER NOT NULL DEFAULT 0,
-                PRIMARY KEY (collection_id, wallpaper_id),
-                FOREIGN KEY (collection_id) REFERENCES collections(id) ON DELETE CASCADE,
-                FOREIGN KEY (wallpaper_id) REFERENCES wallpapers(id) ON DELETE CASCADE
+                PRIMARY KEY (collection_id, item_id),
+                FOREIGN KEY (collection_id) REFERENCES collections(id) ON DELETE CASCADE
+            );
+
+            -- Playlists (ordered rotations assigned to outputs)
+            CREATE TABLE IF NOT EXISTS playlists (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                shuffle INTEGER NOT NULL DEFAULT 0,
+                transition TEXT NOT NULL DEFAULT 'none',
+                default_duration_secs INTEGER NOT NULL DEFAULT 300,
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- Playlist entries; item ids are not constrained so playlists can
+            -- reference Workshop projects that are not indexed in this database
+            CREATE TABLE IF NOT EXISTS playlist_items (
+                playlist_id INTEGER NOT NULL,
+                position INTEGER NOT NULL,
+                item_id TEXT NOT NULL,
+                duration_secs INTEGER,
+                PRIMARY KEY (playlist_id, position),
+                FOREIGN KEY (playlist_id) REFERENCES playlists(id) ON DELETE CASCADE
+            );
+
+            -- Output-playlist assignment (one playlist per output)
+            CREATE TABLE IF NOT EXISTS playlist_outputs (
+                output_name TEXT PRIMARY KEY,
+                playlist_id INTEGER NOT NULL,
+                FOREIGN KEY (playlist_id) REFERENCES playlists(id) ON DELETE CASCADE
             );
 
             -- Create indexes for common queries
@@ -194,10 +251,85 @@ impl LibraryDatabase {
             END;
         "#).context("Failed to initialize database schema")?;
 
+        // Columns added after the initial schema; older databases need them appended
+        Self::ensure_column(&conn, "wallpapers", "shader_path", "TEXT")?;
+        Self::ensure_column(&conn, "wallpapers", "lut_path", "TEXT")?;
+        Self::ensure_column(&conn, "wallpapers", "user_properties", "TEXT")?;
+        Self::ensure_column(&conn, "wallpapers", "custom_title", "TEXT")?;
+        Self::ensure_column(&conn, "wallpapers", "codec", "TEXT")?;
+        Self::ensure_column(&conn, "wallpapers", "fps", "REAL")?;
+        Self::ensure_column(&conn, "wallpapers", "hdr", "TEXT")?;
+        Self::ensure_column(&conn, "thumbnails", "source_path", "TEXT")?;
+        Self::ensure_column(&conn, "thumbnails", "last_used_at", "INTEGER")?;
+        Self::ensure_column(&conn, "thumbnail_strips", "source_path", "TEXT")?;
+        Self::ensure_column(&conn, "thumbnail_strips", "last_used_at", "INTEGER")?;
+        Self::ensure_column(&conn, "playlists", "folder_path", "TEXT")?;
+        Self::ensure_column(&conn, "playlists", "folder_pattern", "TEXT")?;
+        Self::ensure_column(&conn, "playlists", "image_duration_secs", "INTEGER")?;
+        Self::migrate_collection_wallpapers(&conn)?;
+
         debug!("  ✓ Database schema initialized with FTS5");
         Ok(())
     }
 
+    /// The first `thumbnails` table only took indexed wallpapers. It is a
+    /// cache, so it is dropped and created again without the foreign key.
+    fn drop_indexed_only_thumbnails(conn: &Connection) -> Result<()> {
+        let foreign_keys: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_foreign_key_list('thumbnails')",
+            [],
+            |row| row.get(0),
+        )?;
+        if foreign_keys > 0 {
+            conn.execute_batch("DROP TABLE thumbnails;")
+                .context("Failed to drop the old thumbnails table")?;
+            debug!("  ✓ Dropped thumbnails table with wallpaper foreign key");
+        }
+        Ok(())
+    }
+
+    /// Move entries of the old `collection_wallpapers` table, which only
+    /// accepted indexed wallpapers, into `collection_items`
+    fn migrate_collection_wallpapers(conn: &Connection) -> Result<()> {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'collection_wallpapers')",
+            [],
+            |row| row.get(0),
+        )?;
+        if exists {
+            conn.execute_batch(
+                r#"
+                INSERT OR IGNORE INTO collection_items (collection_id, item_id, position)
+                    SELECT collection_id, wallpaper_id, position FROM collection_wallpapers;
+                DROP TABLE collection_wallpapers;
+                "#,
+            )
+            .context("Failed to migrate collection_wallpapers")?;
+            debug!("  ✓ Migrated collection_wallpapers to collection_items");
+        }
+        Ok(())
+    }
+
+    /// Add a column to an existing table if it is missing
+    fn ensure_column(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<()> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .filter_map(|r| r.ok())
+            .any(|name| name == column);
+
+        if !exists {
+            conn.execute_batch(&format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table, column, decl
+            ))
+            .with_context(|| format!("Failed to add column {}.{}", table, column))?;
+            debug!("  ✓ Migrated {}: added column {}", table, column);
+        }
+
+        Ok(())
+    }
+
     // ========== Wallpaper CRUD ==========
 
     /// Insert or update a wallpaper
@@ -212,9 +344,10 @@ impl LibraryDatabase {
             INSERT INTO wallpapers (
                 id, name, source_path, source_type, wallpaper_type, thumbnail_path,
                 title, author, description, tags, duration_secs, resolution_w, resolution_h,
-                file_size, workshop_id, added_at, last_used
+                file_size, workshop_id, added_at, last_used, shader_path, lut_path,
+                codec, fps, hdr
             )
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)
             ON CONFLICT(source_path) DO UPDATE SET
                 name = excluded.name,
                 wallpaper_type = excluded.wallpaper_type,
@@ -226,7 +359,10 @@ impl LibraryDatabase {
                 duration_secs = excluded.duration_secs,
                 resolution_w = excluded.resolution_w,
                 resolution_h = excluded.resolution_h,
-                file_size = excluded.file_size
+                file_size = excluded.file_size,
+                codec = excluded.codec,
+                fps = excluded.fps,
+                hdr = excluded.hdr
             "#,
             params![
                 item.id,
@@ -248,6 +384,17 @@ impl LibraryDatabase {
                 item.metadata.workshop_id,
                 item.added_at.to_rfc3339(),
                 item.last_used.map(|d| d.to_rfc3339()),
+                item.metadata
+                    .shader_path
+                    .as_ref()
+                    .map(|p| p.to_string_lossy().to_string()),
+                item.metadata
+                    .lut_path
+                    .as_ref()
+                    .map(|p| p.to_string_lossy().to_string()),
+                item.metadata.codec,
+                item.metadata.fps,
+                item.metadata.hdr.and_then(hdr_column),
             ],
         )?;
 
@@ -344,6 +491,70 @@ impl LibraryDatabase {
         Ok(new_state)
     }
 
+    /// Set or clear the favorite flag
+    pub fn set_favorite(&self, id: &str, favorite: bool) -> Result<()> {
+        let conn = self.conn.write().unwrap();
+        conn.execute(
+            "UPDATE wallpapers SET favorite = ?2 WHERE id = ?1",
+            params![id, favorite],
+        )?;
+        Ok(())
+    }
+
+    /// Favorite, rating, usage, title, tags, shader and LUT of every
+    /// wallpaper that has any
+    pub fn item_marks(&self) -> Result<HashMap<String, ItemMarks>> {
+        let conn = self.conn.read().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, favorite, COALESCE(rating, 0), use_count, last_used, custom_title,
+                    shader_path, lut_path
+             FROM wallpapers
+             WHERE favorite = 1 OR rating > 0 OR use_count > 0 OR custom_title IS NOT NULL
+                OR shader_path IS NOT NULL OR lut_path IS NOT NULL
+                OR id IN (SELECT wallpaper_id FROM wallpaper_tags)",
+        )?;
+        let mut marks = stmt
+            .query_map([], |row| {
+                let rating: i64 = row.get(2)?;
+                let use_count: i64 = row.get(3)?;
+                let last_used: Option<String> = row.get(4)?;
+                Ok((
+                    row.get::<_, String>(0)?,
+                    ItemMarks {
+                        favorite: row.get(1)?,
+                        rating: rating.clamp(0, 5) as u8,
+                        use_count: use_count.max(0) as u32,
+                        last_used: last_used.and_then(|s| {
+                            DateTime::parse_from_rfc3339(&s)
+                                .ok()
+                                .map(|d| d.with_timezone(&Utc))
+                        }),
+                        title: row.get(5)?,
+                        tags: Vec::new(),
+                        shader: row.get::<_, Option<String>>(6)?.map(PathBuf::from),
+                        lut: row.get::<_, Option<String>>(7)?.map(PathBuf::from),
+                    },
+                ))
+            })?
+            .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT wt.wallpaper_id, t.name FROM wallpaper_tags wt
+             JOIN tags t ON t.id = wt.tag_id
+             ORDER BY t.name",
+        )?;
+        let tags = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for tag in tags {
+            let (id, name) = tag?;
+            if let Some(marks) = marks.get_mut(&id) {
+                marks.tags.push(name);
+            }
+        }
+        Ok(marks)
+    }
+
     /// Record wallpaper usage
     pub fn record_usage(&self, id: &str) -> Result<()> {
         let conn = self.conn.write().unwrap();
@@ -378,114 +589,228 @@ impl LibraryDatabase {
         Ok(rating as u8)
     }
 
-    // ========== Full-Text Search ==========
+    /// Show a wallpaper under a title of the user's choosing (None = the
+    /// scanned title)
+    ///
+    /// Rescans keep it, like the shader and LUT.
+    pub fn set_custom_title(&self, id: &str, title: Option<&str>) -> Result<()> {
+        let conn = self.conn.write().unwrap();
+        conn.execute(
+            "UPDATE wallpapers SET custom_title = ?2 WHERE id = ?1",
+            params![id, title],
+        )?;
+        Ok(())
+    }
 
-    /// Search wallpapers using full-text search
-    pub fn search(&self, query: &str, limit: Option<usize>) -> Result<Vec<WallpaperItem>> {
+    // ========== Shaders & LUTs ==========
+
+    /// Attach a GLSL post-process shader to a wallpaper (None = remove)
+    ///
+    /// Rescans keep the stored shader, since upserts never overwrite this column.
+    pub fn set_shader_path(&self, id: &str, shader: Option<&Path>) -> Result<()> {
+        let conn = self.conn.write().unwrap();
+        conn.execute(
+            "UPDATE wallpapers SET shader_path = ?2 WHERE id = ?1",
+            params![id, shader.map(|p| p.to_string_lossy().to_string())],
+        )?;
+        Ok(())
+    }
+
+    /// Get the shader attached to a wallpaper
+    pub fn get_shader_path(&self, id: &str) -> Result<Option<PathBuf>> {
         let conn = self.conn.read().unwrap();
+        let shader: Option<String> = conn
+            .query_row(
+                "SELECT shader_path FROM wallpapers WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(shader.map(PathBuf::from))
+    }
 
-        // Sanitize query for FTS5
-        let sanitized = query.replace('"', "\"\"");
-        let fts_query = format!("\"{}\"*", sanitized);
+    /// Attach a color grading LUT (.cube) to a wallpaper (None = remove)
+    pub fn set_lut_path(&self, id: &str, lut: Option<&Path>) -> Result<()> {
+        let conn = self.conn.write().unwrap();
+        conn.execute(
+            "UPDATE wallpapers SET lut_path = ?2 WHERE id = ?1",
+            params![id, lut.map(|p| p.to_string_lossy().to_string())],
+        )?;
+        Ok(())
+    }
 
-        let sql = format!(
-            r#"
-            SELECT w.* FROM wallpapers w
-            JOIN wallpapers_fts ON w.rowid = wallpapers_fts.rowid
-            WHERE wallpapers_fts MATCH ?1
-            ORDER BY bm25(wallpapers_fts)
-            {}
-            "#,
-            limit.map(|l| format!("LIMIT {}", l)).unwrap_or_default()
-        );
+    /// Get the LUT attached to a wallpaper
+    pub fn get_lut_path(&self, id: &str) -> Result<Option<PathBuf>> {
+        let conn = self.conn.read().unwrap();
+        let lut: Option<String> = conn
+            .query_row(
+                "SELECT lut_path FROM wallpapers WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(lut.map(PathBuf::from))
+    }
 
-        let mut stmt = conn.prepare(&sql)?;
-        let wallpapers = stmt
-            .query_map(params![fts_query], |row| self.row_to_wallpaper(row))?
-            .filter_map(|r| r.ok())
-            .collect();
+    // ========== User Properties ==========
 
-        Ok(wallpapers)
+    /// Store the values a user picked for a wallpaper's project.json
+    /// properties, by property name (empty = use the author's defaults)
+    ///
+    /// Rescans keep them, like the shader and LUT.
+    pub fn set_user_properties(
+        &self,
+        id: &str,
+        values: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<()> {
+        let conn = self.conn.write().unwrap();
+        let values = (!values.is_empty()).then(|| serde_json::Value::Object(values.clone()));
+        conn.execute(
+            "UPDATE wallpapers SET user_properties = ?2 WHERE id = ?1",
+            params![id, values.map(|values| values.to_string())],
+        )?;
+        Ok(())
     }
 
-    /// Search wallpapers with advanced options
-    pub fn search_advanced(&self, options: &SearchOptions) -> Result<Vec<WallpaperItem>> {
+    /// Values a user picked for a wallpaper's properties
+    pub fn get_user_properties(
+        &self,
+        id: &str,
+    ) -> Result<serde_json::Map<String, serde_json::Value>> {
         let conn = self.conn.read().unwrap();
+        let values: Option<String> = conn
+            .query_row(
+                "SELECT user_properties FROM wallpapers WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(values
+            .and_then(|values| serde_json::from_str(&values).ok())
+            .unwrap_or_default())
+    }
 
-        let mut conditions = vec!["1=1".to_string()];
-
-        // Full-text search condition
-        if let Some(ref query) = options.query {
-            let sanitized = query.replace('"', "\"\"");
-            conditions.push(format!(
-                "w.rowid IN (SELECT rowid FROM wallpapers_fts WHERE wallpapers_fts MATCH '\"{}\"*')",
-                sanitized
-            ));
-        }
+    // ========== Full-Text Search ==========
 
-        // Type filter
-        if let Some(ref wtype) = options.wallpaper_type {
-            conditions.push(format!("w.wallpaper_type = '{}'", wtype.as_str()));
-        }
+    /// Search wallpapers by text, best matches first
+    pub fn search(&self, query: &str, limit: Option<usize>) -> Result<Vec<WallpaperItem>> {
+        self.search_advanced(&SearchOptions {
+            query: Some(query.to_string()),
+            sort_by: SortBy::Relevance,
+            limit,
+            ..Default::default()
+        })
+    }
 
-        // Source filter
-        if let Some(ref stype) = options.source_type {
-            conditions.push(format!("w.source_type = '{}'", stype.as_str()));
-        }
+    /// Search wallpapers with advanced options
+    pub fn search_advanced(&self, options: &SearchOptions) -> Result<Vec<WallpaperItem>> {
+        let query = text_query(options);
+        let conn = self.conn.read().unwrap();
 
-        // Favorites filter
-        if options.favorites_only {
-            conditions.push("w.favorite = 1".to_string());
-        }
+        // Text matching happens after the SQL filters, so paging has to wait for it
+        let sql = format!(
+            "SELECT w.* FROM wallpapers w WHERE {} ORDER BY {} {}",
+            search_conditions(options),
+            search_order(options.sort_by),
+            if query.is_some() {
+                String::new()
+            } else {
+                search_page(options)
+            }
+        );
 
-        // Rating filter
-        if let Some(min_rating) = options.min_rating {
-            conditions.push(format!("w.rating >= {}", min_rating));
-        }
+        let mut stmt = conn.prepare(&sql)?;
+        let wallpapers = stmt
+            .query_map([], |row| self.row_to_wallpaper(row))?
+            .filter_map(|r| r.ok())
+            .collect();
 
-        // Tag filter (requires join)
-        let tag_join = if !options.tags.is_empty() {
-            let tag_names: Vec<String> = options
-                .tags
-                .iter()
-                .map(|t| format!("'{}'", t.replace('\'', "''")))
-                .collect();
-            conditions.push(format!(
-                "w.id IN (SELECT wt.wallpaper_id FROM wallpaper_tags wt JOIN tags t ON wt.tag_id = t.id WHERE t.name IN ({}))",
-                tag_names.join(",")
-            ));
-            ""
-        } else {
-            ""
-        };
+        Ok(match query {
+            Some(query) => {
+                rank_text_matches(&query, options, wallpapers, |wallpaper| {
+                    search_fields(
+                        &wallpaper.name,
+                        &wallpaper.metadata,
+                        &wallpaper.metadata.tags,
+                    )
+                })
+                .0
+            }
+            None => wallpapers,
+        })
+    }
 
-        let order_by = match options.sort_by {
-            SortBy::Name => "w.name ASC",
-            SortBy::DateAdded => "w.added_at DESC",
-            SortBy::LastUsed => "w.last_used DESC NULLS LAST",
-            SortBy::UseCount => "w.use_count DESC",
-            SortBy::Rating => "w.rating DESC, w.name ASC",
-            SortBy::Relevance => "1", // FTS handles relevance
-        };
+    /// Answer an IPC library query: one page of matches and the number of
+    /// matches across all pages
+    pub fn query_library(&self, filter: &LibraryFilter) -> Result<(Vec<LibraryItem>, usize)> {
+        let options = SearchOptions::try_from(filter)?;
+        let query = text_query(&options);
+        let conditions = search_conditions(&options);
+        let conn = self.conn.read().unwrap();
 
         let sql = format!(
-            "SELECT w.* FROM wallpapers w {} WHERE {} ORDER BY {} {}",
-            tag_join,
-            conditions.join(" AND "),
-            order_by,
-            options
-                .limit
-                .map(|l| format!("LIMIT {}", l))
-                .unwrap_or_default()
+            r#"
+            SELECT w.*,
+                (SELECT group_concat(t.name, char(31)) FROM wallpaper_tags wt
+                    JOIN tags t ON wt.tag_id = t.id WHERE wt.wallpaper_id = w.id) AS tag_names
+            FROM wallpapers w WHERE {} ORDER BY {} {}
+            "#,
+            conditions,
+            search_order(options.sort_by),
+            if query.is_some() {
+                String::new()
+            } else {
+                search_page(&options)
+            }
         );
 
         let mut stmt = conn.prepare(&sql)?;
-        let wallpapers = stmt
-            .query_map([], |row| self.row_to_wallpaper(row))?
+        let rows: Vec<(LibraryItem, WallpaperMetadata)> = stmt
+            .query_map([], |row| {
+                let wallpaper = self.row_to_wallpaper(row)?;
+                let favorite: bool = row.get("favorite")?;
+                let rating: Option<i64> = row.get("rating")?;
+                let tag_names: Option<String> = row.get("tag_names")?;
+
+                let mut tags = wallpaper.metadata.tags.clone();
+                for tag in tag_names.iter().flat_map(|names| names.split('\u{1f}')) {
+                    if !tags.iter().any(|existing| existing == tag) {
+                        tags.push(tag.to_string());
+                    }
+                }
+
+                let item = LibraryItem {
+                    id: wallpaper.id,
+                    name: wallpaper.name,
+                    path: wallpaper.source_path.to_string_lossy().to_string(),
+                    wallpaper_type: wallpaper.wallpaper_type.as_str().to_string(),
+                    tags,
+                    favorite,
+                    rating: rating.unwrap_or(0).clamp(0, 5) as u8,
+                };
+                Ok((item, wallpaper.metadata))
+            })?
             .filter_map(|r| r.ok())
             .collect();
 
-        Ok(wallpapers)
+        let (rows, total) = match query {
+            Some(query) => rank_text_matches(&query, &options, rows, |(item, metadata)| {
+                search_fields(&item.name, metadata, &item.tags)
+            }),
+            None => {
+                let total: i64 = conn.query_row(
+                    &format!("SELECT COUNT(*) FROM wallpapers w WHERE {conditions}"),
+                    [],
+                    |row| row.get(0),
+                )?;
+                (rows, total as usize)
+            }
+        };
+
+        Ok((rows.into_iter().map(|(item, _)| item).collect(), total))
     }
 
     // ========== Tags ==========
@@ -573,6 +898,29 @@ impl LibraryDatabase {
         Ok(())
     }
 
+    /// Replace a wallpaper's tags with `tag_names`, creating missing tags
+    pub fn set_wallpaper_tags(&self, wallpaper_id: &str, tag_names: &[String]) -> Result<()> {
+        let mut conn = self.conn.write().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM wallpaper_tags WHERE wallpaper_id = ?1",
+            params![wallpaper_id],
+        )?;
+        for name in tag_names {
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (name) VALUES (?1)",
+                params![name],
+            )?;
+            tx.execute(
+                "INSERT OR IGNORE INTO wallpaper_tags (wallpaper_id, tag_id)
+                 SELECT ?1, id FROM tags WHERE name = ?2",
+                params![wallpaper_id, name],
+            )?;
+        }
+        tx.commit().context("Failed to save wallpaper tags")?;
+        Ok(())
+    }
+
     /// Get tags for a wallpaper
     pub fn get_wallpaper_tags(&self, wallpaper_id: &str) -> Result<Vec<Tag>> {
         let conn = self.conn.read().unwrap();
@@ -636,9 +984,9 @@ impl LibraryDatabase {
         let mut stmt = conn.prepare(
             r#"
             SELECT c.id, c.name, c.description, c.cover_wallpaper_id, 
-                   COUNT(cw.wallpaper_id) as count, c.created_at
+                   COUNT(ci.item_id) as count, c.created_at
             FROM collections c
-            LEFT JOIN collection_wallpapers cw ON c.id = cw.collection_id
+            LEFT JOIN collection_items ci ON c.id = ci.collection_id
             GROUP BY c.id
             ORDER BY c.name
             "#,
@@ -661,19 +1009,29 @@ impl LibraryDatabase {
         Ok(collections)
     }
 
+    /// Rename a collection; false when there is no collection with that id
+    pub fn rename_collection(&self, collection_id: i64, name: &str) -> Result<bool> {
+        let conn = self.conn.write().unwrap();
+        let rows = conn.execute(
+            "UPDATE collections SET name = ?2 WHERE id = ?1",
+            params![collection_id, name],
+        )?;
+        Ok(rows > 0)
+    }
+
     /// Add wallpaper to collection
     pub fn add_to_collection(&self, collection_id: i64, wallpaper_id: &str) -> Result<()> {
         let conn = self.conn.write().unwrap();
 
         // Get next position
         let position: i32 = conn.query_row(
-            "SELECT COALESCE(MAX(position), -1) + 1 FROM collection_wallpapers WHERE collection_id = ?1",
+            "SELECT COALESCE(MAX(position), -1) + 1 FROM collection_items WHERE collection_id = ?1",
             params![collection_id],
             |row| row.get(0),
         )?;
 
         conn.execute(
-            "INSERT OR IGNORE INTO collection_wallpapers (collection_id, wallpaper_id, position) VALUES (?1, ?2, ?3)",
+            "INSERT OR IGNORE INTO collection_items (collection_id, item_id, position) VALUES (?1, ?2, ?3)",
             params![collection_id, wallpaper_id, position],
         )?;
         Ok(())
@@ -683,7 +1041,7 @@ impl LibraryDatabase {
     pub fn remove_from_collection(&self, collection_id: i64, wallpaper_id: &str) -> Result<()> {
         let conn = self.conn.write().unwrap();
         conn.execute(
-            "DELETE FROM collection_wallpapers WHERE collection_id = ?1 AND wallpaper_id = ?2",
+            "DELETE FROM collection_items WHERE collection_id = ?1 AND item_id = ?2",
             params![collection_id, wallpaper_id],
         )?;
         Ok(())
@@ -695,9 +1053,9 @@ impl LibraryDatabase {
         let mut stmt = conn.prepare(
             r#"
             SELECT w.* FROM wallpapers w
-            JOIN collection_wallpapers cw ON w.id = cw.wallpaper_id
-            WHERE cw.collection_id = ?1
-            ORDER BY cw.position
+            JOIN collection_items ci ON w.id = ci.item_id
+            WHERE ci.collection_id = ?1
+            ORDER BY ci.position
             "#,
         )?;
 
@@ -709,6 +1067,20 @@ impl LibraryDatabase {
         Ok(wallpapers)
     }
 
+    /// Ids of every entry in a collection, in order, including items that
+    /// are not indexed in this database
+    pub fn collection_item_ids(&self, collection_id: i64) -> Result<Vec<String>> {
+        let conn = self.conn.read().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT item_id FROM collection_items WHERE collection_id = ?1 ORDER BY position",
+        )?;
+        let ids = stmt
+            .query_map(params![collection_id], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(ids)
+    }
+
     /// Delete collection
     pub fn delete_collection(&self, collection_id: i64) -> Result<bool> {
         let conn = self.conn.write().unwrap();
@@ -719,6 +1091,189 @@ impl LibraryDatabase {
         Ok(rows > 0)
     }
 
+    // ========== Playlists ==========
+
+    /// Create or update a playlist, replacing its entries and output assignments
+    ///
+    /// A playlist with `id == 0` is inserted; the stored id is returned.
+    pub fn save_playlist(&self, playlist: &Playlist) -> Result<i64> {
+        let mut conn = self.conn.write().unwrap();
+        let tx = conn.transaction()?;
+        let folder = playlist.folder.as_ref();
+        let folder_path = folder.map(|folder| folder.path.to_string_lossy().into_owned());
+        let folder_pattern = folder.and_then(|folder| folder.pattern.as_deref());
+        let image_duration_secs = folder.and_then(|folder| folder.image_duration_secs);
+
+        let id = if playlist.id == 0 {
+            tx.execute(
+                "INSERT INTO playlists (name, shuffle, transition, default_duration_secs, folder_path, folder_pattern, image_duration_secs) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    playlist.name,
+                    playlist.shuffle,
+                    playlist.transition,
+                    playlist.default_duration_secs,
+                    folder_path,
+                    folder_pattern,
+                    image_duration_secs
+                ],
+            )
+            .with_context(|| format!("Failed to create playlist '{}'", playlist.name))?;
+            tx.last_insert_rowid()
+        } else {
+            let rows = tx.execute(
+                "UPDATE playlists SET name = ?2, shuffle = ?3, transition = ?4, default_duration_secs = ?5, folder_path = ?6, folder_pattern = ?7, image_duration_secs = ?8 WHERE id = ?1",
+                params![
+                    playlist.id,
+                    playlist.name,
+                    playlist.shuffle,
+                    playlist.transition,
+                    playlist.default_duration_secs,
+                    folder_path,
+                    folder_pattern,
+                    image_duration_secs
+                ],
+            )?;
+            if rows == 0 {
+                anyhow::bail!("Playlist {} does not exist", playlist.id);
+            }
+            playlist.id
+        };
+
+        tx.execute(
+            "DELETE FROM playlist_items WHERE playlist_id = ?1",
+            params![id],
+        )?;
+        for (position, entry) in playlist.items.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO playlist_items (playlist_id, position, item_id, duration_secs) VALUES (?1, ?2, ?3, ?4)",
+                params![id, position as i64, entry.item_id, entry.duration_secs],
+            )?;
+        }
+
+        tx.execute(
+            "DELETE FROM playlist_outputs WHERE playlist_id = ?1",
+            params![id],
+        )?;
+        for output in &playlist.outputs {
+            // An output plays a single playlist, so assigning here moves it
+            tx.execute(
+                "INSERT OR REPLACE INTO playlist_outputs (output_name, playlist_id) VALUES (?1, ?2)",
+                params![output, id],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// List all playlists with their entries, ordered by name
+    pub fn list_playlists(&self) -> Result<Vec<Playlist>> {
+        let conn = self.conn.read().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, shuffle, transition, default_duration_secs, folder_path, folder_pattern, image_duration_secs FROM playlists ORDER BY name",
+        )?;
+
+        let mut playlists: Vec<Playlist> = stmt
+            .query_map([], Self::row_to_playlist)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        for playlist in &mut playlists {
+            Self::load_playlist_children(&conn, playlist)?;
+        }
+
+        Ok(playlists)
+    }
+
+    /// Get a playlist by id
+    pub fn get_playlist(&self, id: i64) -> Result<Option<Playlist>> {
+        let conn = self.conn.read().unwrap();
+        let playlist = conn
+            .query_row(
+                "SELECT id, name, shuffle, transition, default_duration_secs, folder_path, folder_pattern, image_duration_secs FROM playlists WHERE id = ?1",
+                params![id],
+                Self::row_to_playlist,
+            )
+            .optional()?;
+
+        match playlist {
+            Some(mut playlist) => {
+                Self::load_playlist_children(&conn, &mut playlist)?;
+                Ok(Some(playlist))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Delete a playlist and its output assignments
+    pub fn delete_playlist(&self, id: i64) -> Result<bool> {
+        let conn = self.conn.write().unwrap();
+        let rows = conn.execute("DELETE FROM playlists WHERE id = ?1", params![id])?;
+        Ok(rows > 0)
+    }
+
+    /// Get the playlist assigned to each output
+    pub fn playlist_assignments(&self) -> Result<Vec<(String, i64)>> {
+        let conn = self.conn.read().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT output_name, playlist_id FROM playlist_outputs ORDER BY output_name",
+        )?;
+
+        let assignments = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(assignments)
+    }
+
+    fn row_to_playlist(row: &rusqlite::Row) -> rusqlite::Result<Playlist> {
+        let folder = match row.get::<_, Option<String>>(5)? {
+            Some(path) => Some(PlaylistFolder {
+                path: PathBuf::from(path),
+                pattern: row.get(6)?,
+                image_duration_secs: row.get(7)?,
+            }),
+            None => None,
+        };
+
+        Ok(Playlist {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            shuffle: row.get(2)?,
+            transition: row.get(3)?,
+            default_duration_secs: row.get(4)?,
+            items: Vec::new(),
+            outputs: Vec::new(),
+            folder,
+        })
+    }
+
+    fn load_playlist_children(conn: &Connection, playlist: &mut Playlist) -> Result<()> {
+        let mut stmt = conn.prepare(
+            "SELECT item_id, duration_secs FROM playlist_items WHERE playlist_id = ?1 ORDER BY position",
+        )?;
+        playlist.items = stmt
+            .query_map(params![playlist.id], |row| {
+                Ok(PlaylistEntry {
+                    item_id: row.get(0)?,
+                    duration_secs: row.get(1)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut stmt = conn.prepare(
+            "SELECT output_name FROM playlist_outputs WHERE playlist_id = ?1 ORDER BY output_name",
+        )?;
+        playlist.outputs = stmt
+            .query_map(params![playlist.id], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(())
+    }
+
     // ========== Folders ==========
 
     /// Add a library folder
@@ -762,35 +1317,165 @@ impl LibraryDatabase {
         Ok(rows > 0)
     }
 
-    // ========== Thumbnails ==========
-
-    /// Store thumbnail data
-    pub fn store_thumbnail(
-        &self,
-        wallpaper_id: &str,
-        data: &[u8],
-        width: u32,
-        height: u32,
-    ) -> Result<()> {
-        let conn = self.conn.write().unwrap();
-        conn.execute(
-            "INSERT OR REPLACE INTO thumbnails (wallpaper_id, data, width, height) VALUES (?1, ?2, ?3, ?4)",
-            params![wallpaper_id, data, width, height],
-        )?;
+    // ========== Watched folders ==========
+
+    /// Bring the index in line with a change seen by a file watcher
+    pub fn apply_file_event(&self, event: &FileEvent, scanner: &FolderScanner) -> Result<()> {
+        match event {
+            FileEvent::Created(path) | FileEvent::Modified(path) => {
+                if let Some(item) = scanner.process_file(path) {
+                    self.upsert_wallpaper(&item)?;
+                }
+            }
+            FileEvent::Deleted(path) => {
+                self.delete_wallpaper(&WallpaperItem::generate_id(path))?;
+            }
+            FileEvent::Renamed { from, to } => {
+                if let Some(item) = scanner.process_file(to) {
+                    if !self.rename_wallpaper(from, &item)? {
+                        self.upsert_wallpaper(&item)?;
+                    }
+                }
+            }
+        }
         Ok(())
     }
 
-    /// Get thumbnail data
-    pub fn get_thumbnail(&self, wallpaper_id: &str) -> Result<Option<ThumbnailData>> {
-        let conn = self.conn.read().unwrap();
-        conn.query_row(
-            "SELECT data, width, height FROM thumbnails WHERE wallpaper_id = ?1",
+    /// Move the wallpaper indexed at `from` to `item`'s path and id, keeping
+    /// its favorite, rating, tags, thumbnails and collection and playlist
+    /// entries. False when `from` was not indexed.
+    pub fn rename_wallpaper(&self, from: &Path, item: &WallpaperItem) -> Result<bool> {
+        let mut conn = self.conn.write().unwrap();
+        let tx = conn.transaction()?;
+        let old_id: Option<String> = tx
+            .query_row(
+                "SELECT id FROM wallpapers WHERE source_path = ?1",
+                params![from.to_string_lossy()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(old_id) = old_id else {
+            return Ok(false);
+        };
+
+        // Tags point at the old id until they are moved below
+        tx.execute_batch("PRAGMA defer_foreign_keys = ON")?;
+        tx.execute(
+            "DELETE FROM wallpapers WHERE source_path = ?1 AND id != ?2",
+            params![item.source_path.to_string_lossy(), old_id],
+        )?;
+        tx.execute(
+            "UPDATE wallpapers SET id = ?1, source_path = ?2, name = ?3 WHERE id = ?4",
+            params![
+                item.id,
+                item.source_path.to_string_lossy(),
+                item.name,
+                old_id
+            ],
+        )?;
+        for (table, column) in [
+            ("wallpaper_tags", "wallpaper_id"),
+            ("collection_items", "item_id"),
+            ("collections", "cover_wallpaper_id"),
+            ("playlist_items", "item_id"),
+            ("thumbnails", "wallpaper_id"),
+            ("thumbnail_strips", "wallpaper_id"),
+        ] {
+            tx.execute(
+                &format!("UPDATE OR REPLACE {table} SET {column} = ?1 WHERE {column} = ?2"),
+                params![item.id, old_id],
+            )?;
+        }
+        tx.commit().context("Failed to rename wallpaper")?;
+        Ok(true)
+    }
+
+    /// Drop wallpapers under `folder` whose files are gone, such as ones
+    /// deleted while nothing was watching. Returns how many were dropped.
+    pub fn prune_folder(&self, folder: &Path) -> Result<usize> {
+        let missing: Vec<String> = {
+            let conn = self.conn.read().unwrap();
+            let mut stmt = conn.prepare("SELECT id, source_path FROM wallpapers")?;
+            stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    PathBuf::from(row.get::<_, String>(1)?),
+                ))
+            })?
+            .filter_map(|r| r.ok())
+            .filter(|(_, path)| path.starts_with(folder) && !path.exists())
+            .map(|(id, _)| id)
+            .collect()
+        };
+
+        for id in &missing {
+            self.delete_wallpaper(id)?;
+        }
+        Ok(missing.len())
+    }
+
+    // ========== Thumbnails ==========
+
+    /// Store thumbnail data
+    pub fn store_thumbnail(
+        &self,
+        wallpaper_id: &str,
+        data: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<()> {
+        let conn = self.conn.write().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO thumbnails (wallpaper_id, data, width, height, last_used_at)
+             VALUES (?1, ?2, ?3, ?4, strftime('%s', 'now'))",
+            params![wallpaper_id, data, width, height],
+        )?;
+        Ok(())
+    }
+
+    /// Store several thumbnails, with the source file and mtime each was
+    /// generated from, in one transaction
+    pub fn store_thumbnails(&self, thumbnails: &[(String, ThumbnailData)]) -> Result<()> {
+        let mut conn = self.conn.write().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO thumbnails
+                    (wallpaper_id, data, width, height, source_modified, source_path, last_used_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, strftime('%s', 'now'))",
+            )?;
+            for (wallpaper_id, thumbnail) in thumbnails {
+                stmt.execute(params![
+                    wallpaper_id,
+                    thumbnail.data,
+                    thumbnail.width,
+                    thumbnail.height,
+                    thumbnail.source_modified,
+                    thumbnail
+                        .source_path
+                        .as_ref()
+                        .map(|path| path.to_string_lossy().into_owned())
+                ])?;
+            }
+        }
+        tx.commit().context("Failed to store thumbnails")?;
+        Ok(())
+    }
+
+    /// Get thumbnail data
+    pub fn get_thumbnail(&self, wallpaper_id: &str) -> Result<Option<ThumbnailData>> {
+        let conn = self.conn.read().unwrap();
+        conn.query_row(
+            "SELECT data, width, height, source_modified, source_path
+             FROM thumbnails WHERE wallpaper_id = ?1",
             params![wallpaper_id],
             |row| {
                 Ok(ThumbnailData {
                     data: row.get(0)?,
                     width: row.get(1)?,
                     height: row.get(2)?,
+                    source_modified: row.get(3)?,
+                    source_path: row.get::<_, Option<String>>(4)?.map(PathBuf::from),
                 })
             },
         )
@@ -798,6 +1483,209 @@ impl LibraryDatabase {
         .context("Failed to query thumbnail")
     }
 
+    /// Store a video sprite strip, with the source file and mtime it was
+    /// generated from
+    pub fn store_thumbnail_strip(
+        &self,
+        wallpaper_id: &str,
+        source_path: &Path,
+        strip: &ThumbnailStrip,
+        source_modified: Option<i64>,
+    ) -> Result<()> {
+        let conn = self.conn.write().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO thumbnail_strips
+                (wallpaper_id, data, frame_width, frame_height, frame_count, format,
+                 source_modified, source_path, last_used_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, strftime('%s', 'now'))",
+            params![
+                wallpaper_id,
+                strip.data,
+                strip.frame_width,
+                strip.frame_height,
+                strip.frame_count,
+                strip.format,
+                source_modified,
+                source_path.to_string_lossy()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Get a stored video sprite strip
+    pub fn get_thumbnail_strip(&self, wallpaper_id: &str) -> Result<Option<ThumbnailStripData>> {
+        let conn = self.conn.read().unwrap();
+        conn.query_row(
+            "SELECT data, frame_width, frame_height, frame_count, source_modified
+             FROM thumbnail_strips WHERE wallpaper_id = ?1",
+            params![wallpaper_id],
+            |row| {
+                Ok(ThumbnailStripData {
+                    data: row.get(0)?,
+                    frame_width: row.get(1)?,
+                    frame_height: row.get(2)?,
+                    frame_count: row.get(3)?,
+                    source_modified: row.get(4)?,
+                })
+            },
+        )
+        .optional()
+        .context("Failed to query thumbnail strip")
+    }
+
+    /// Bytes of stored thumbnail and strip data for a wallpaper
+    pub fn thumbnail_bytes(&self, wallpaper_id: &str) -> Result<u64> {
+        let conn = self.conn.read().unwrap();
+        let bytes: i64 = conn.query_row(
+            "SELECT COALESCE((SELECT SUM(LENGTH(data)) FROM thumbnails WHERE wallpaper_id = ?1), 0)
+                  + COALESCE((SELECT SUM(LENGTH(data)) FROM thumbnail_strips WHERE wallpaper_id = ?1), 0)",
+            params![wallpaper_id],
+            |row| row.get(0),
+        )?;
+        Ok(bytes.max(0) as u64)
+    }
+
+    /// Delete a wallpaper's stored thumbnail and strip
+    pub fn delete_thumbnails(&self, wallpaper_id: &str) -> Result<bool> {
+        let conn = self.conn.write().unwrap();
+        let rows = conn.execute(
+            "DELETE FROM thumbnails WHERE wallpaper_id = ?1",
+            params![wallpaper_id],
+        )? + conn.execute(
+            "DELETE FROM thumbnail_strips WHERE wallpaper_id = ?1",
+            params![wallpaper_id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Mark a wallpaper's stored thumbnail and strip as just shown, so
+    /// pruning keeps them over ones not seen for a while
+    pub fn touch_thumbnails(&self, wallpaper_id: &str) -> Result<()> {
+        let conn = self.conn.write().unwrap();
+        for table in ["thumbnails", "thumbnail_strips"] {
+            conn.execute(
+                &format!(
+                    "UPDATE {table} SET last_used_at = strftime('%s', 'now') WHERE wallpaper_id = ?1"
+                ),
+                params![wallpaper_id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Bytes of all stored thumbnail and strip data
+    pub fn thumbnail_cache_bytes(&self) -> Result<u64> {
+        let conn = self.conn.read().unwrap();
+        let bytes: i64 = conn.query_row(
+            "SELECT COALESCE((SELECT SUM(LENGTH(data)) FROM thumbnails), 0)
+                  + COALESCE((SELECT SUM(LENGTH(data)) FROM thumbnail_strips), 0)",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(bytes.max(0) as u64)
+    }
+
+    /// Drop stored thumbnails and strips whose source file is gone, then the
+    /// least recently used ones until the rest fit in `budget_bytes`.
+    /// Entries from before sources were recorded count as gone unless they
+    /// belong to an indexed wallpaper; they are generated again when shown.
+    pub fn prune_thumbnails(&self, budget_bytes: u64) -> Result<ThumbnailPrune> {
+        let entries: Vec<StoredThumbnail> = {
+            let conn = self.conn.read().unwrap();
+            let mut stmt = conn.prepare(
+                "SELECT 'thumbnails', t.rowid, LENGTH(t.data), COALESCE(t.last_used_at, 0),
+                        COALESCE(t.source_path, w.source_path)
+                 FROM thumbnails t LEFT JOIN wallpapers w ON w.id = t.wallpaper_id
+                 UNION ALL
+                 SELECT 'thumbnail_strips', s.rowid, LENGTH(s.data), COALESCE(s.last_used_at, 0),
+                        COALESCE(s.source_path, w.source_path)
+                 FROM thumbnail_strips s LEFT JOIN wallpapers w ON w.id = s.wallpaper_id",
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok(StoredThumbnail {
+                    table: if row.get::<_, String>(0)? == "thumbnails" {
+                        "thumbnails"
+                    } else {
+                        "thumbnail_strips"
+                    },
+                    rowid: row.get(1)?,
+                    bytes: row.get::<_, i64>(2)?.max(0) as u64,
+                    last_used_at: row.get(3)?,
+                    source_path: row.get::<_, Option<String>>(4)?.map(PathBuf::from),
+                })
+            })?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let (orphans, mut kept): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(|entry| entry.source_path.as_ref().is_none_or(|path| !path.exists()));
+        // Most recently used first; what no longer fits goes
+        kept.sort_by_key(|entry| std::cmp::Reverse((entry.last_used_at, entry.rowid)));
+        let mut kept_bytes = 0;
+        let evicted: Vec<_> = kept
+            .into_iter()
+            .filter(|entry| {
+                kept_bytes += entry.bytes;
+                kept_bytes > budget_bytes
+            })
+            .collect();
+
+        let prune = ThumbnailPrune {
+            orphaned: orphans.len(),
+            evicted: evicted.len(),
+            bytes_reclaimed: orphans
+                .iter()
+                .chain(&evicted)
+                .map(|entry| entry.bytes)
+                .sum(),
+        };
+        self.remove_thumbnails(orphans.iter().chain(&evicted))?;
+        Ok(prune)
+    }
+
+    /// Drop every stored thumbnail and strip
+    pub fn clear_thumbnails(&self) -> Result<ThumbnailPrune> {
+        let count: i64 = self.conn.read().unwrap().query_row(
+            "SELECT (SELECT COUNT(*) FROM thumbnails) + (SELECT COUNT(*) FROM thumbnail_strips)",
+            [],
+            |row| row.get(0),
+        )?;
+        let prune = ThumbnailPrune {
+            orphaned: 0,
+            evicted: count.max(0) as usize,
+            bytes_reclaimed: self.thumbnail_cache_bytes()?,
+        };
+        if prune.evicted > 0 {
+            let conn = self.conn.write().unwrap();
+            conn.execute_batch("DELETE FROM thumbnails; DELETE FROM thumbnail_strips; VACUUM;")
+                .context("Failed to clear thumbnails")?;
+        }
+        Ok(prune)
+    }
+
+    /// Delete pruned entries, then give their space back to the file system
+    fn remove_thumbnails<'a>(
+        &self,
+        entries: impl Iterator<Item = &'a StoredThumbnail>,
+    ) -> Result<()> {
+        let mut conn = self.conn.write().unwrap();
+        let tx = conn.transaction()?;
+        let mut removed = 0;
+        for entry in entries {
+            removed += tx.execute(
+                &format!("DELETE FROM {} WHERE rowid = ?1", entry.table),
+                params![entry.rowid],
+            )?;
+        }
+        tx.commit().context("Failed to prune thumbnails")?;
+        if removed > 0 {
+            conn.execute_batch("VACUUM")
+                .context("Failed to compact the library database")?;
+        }
+        Ok(())
+    }
+
     // ========== Stats ==========
 
     /// Get library statistics
@@ -867,6 +1755,11 @@ impl LibraryDatabase {
         let workshop_id: Option<u64> = row.get("workshop_id")?;
         let added_at_str: String = row.get("added_at")?;
         let last_used_str: Option<String> = row.get("last_used")?;
+        let shader_path: Option<String> = row.get("shader_path")?;
+        let lut_path: Option<String> = row.get("lut_path")?;
+        let codec: Option<String> = row.get("codec")?;
+        let fps: Option<f64> = row.get("fps")?;
+        let hdr: Option<String> = row.get("hdr")?;
 
         let tags: Vec<String> = tags_json
             .and_then(|j| serde_json::from_str(&j).ok())
@@ -885,7 +1778,12 @@ impl LibraryDatabase {
             duration_secs,
             resolution,
             file_size,
+            codec,
+            fps,
+            hdr: hdr.as_deref().and_then(parse_hdr_column),
             workshop_id,
+            shader_path: shader_path.map(PathBuf::from),
+            lut_path: lut_path.map(PathBuf::from),
         };
 
         let added_at = DateTime::parse_from_rfc3339(&added_at_str)
@@ -927,7 +1825,7 @@ pub struct WallpaperFilter {
 /// Advanced search options
 #[derive(Debug, Clone, Default)]
 pub struct SearchOptions {
-    /// Full-text search query
+    /// Text query, matched with typo tolerance and without accents
     pub query: Option<String>,
     /// Filter by wallpaper type
     pub wallpaper_type: Option<WallpaperType>,
@@ -939,10 +1837,55 @@ pub struct SearchOptions {
     pub min_rating: Option<u8>,
     /// Filter by tags (match any)
     pub tags: Vec<String>,
+    /// Filter to files under this directory
+    pub folder: Option<PathBuf>,
     /// Sort order
     pub sort_by: SortBy,
     /// Maximum results
     pub limit: Option<usize>,
+    /// Results to skip before the first one returned
+    pub offset: usize,
+}
+
+impl TryFrom<&LibraryFilter> for SearchOptions {
+    type Error = anyhow::Error;
+
+    fn try_from(filter: &LibraryFilter) -> Result<Self> {
+        let wallpaper_type = filter
+            .wallpaper_type
+            .as_deref()
+            .map(|name| match name {
+                "video" => Ok(WallpaperType::Video),
+                "scene" => Ok(WallpaperType::Scene),
+                "animated_image" | "gif" => Ok(WallpaperType::AnimatedImage),
+                "image" => Ok(WallpaperType::Image),
+                "web" => Ok(WallpaperType::Web),
+                _ => Err(anyhow::anyhow!("Unknown wallpaper type: {name}")),
+            })
+            .transpose()?;
+
+        Ok(Self {
+            query: filter
+                .search
+                .as_deref()
+                .map(str::trim)
+                .filter(|query| !query.is_empty())
+                .map(str::to_string),
+            wallpaper_type,
+            source_type: None,
+            favorites_only: filter.favorites_only,
+            min_rating: filter.min_rating,
+            tags: filter.tags.clone().unwrap_or_default(),
+            folder: filter
+                .folder
+                .as_deref()
+                .filter(|folder| !folder.is_empty())
+                .map(PathBuf::from),
+            sort_by: filter.sort.into(),
+            limit: filter.limit,
+            offset: filter.offset,
+        })
+    }
 }
 
 /// Sort order for wallpaper queries
@@ -957,6 +1900,38 @@ pub enum SortBy {
     Relevance,
 }
 
+impl From<LibrarySort> for SortBy {
+    fn from(sort: LibrarySort) -> Self {
+        match sort {
+            LibrarySort::Name => SortBy::Name,
+            LibrarySort::DateAdded => SortBy::DateAdded,
+            LibrarySort::LastUsed => SortBy::LastUsed,
+            LibrarySort::UseCount => SortBy::UseCount,
+            LibrarySort::Rating => SortBy::Rating,
+            LibrarySort::Relevance => SortBy::Relevance,
+        }
+    }
+}
+
+/// What the user marked on a wallpaper and how often it was shown
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemMarks {
+    pub favorite: bool,
+    /// 1-5, or 0 when unrated
+    pub rating: u8,
+    /// Times the wallpaper was applied
+    pub use_count: u32,
+    pub last_used: Option<DateTime<Utc>>,
+    /// Title the user gave the wallpaper in place of the scanned one
+    pub title: Option<String>,
+    /// User tags, by name
+    pub tags: Vec<String>,
+    /// GLSL shader the user attached to the wallpaper
+    pub shader: Option<PathBuf>,
+    /// Color grading LUT (.cube) the user attached to the wallpaper
+    pub lut: Option<PathBuf>,
+}
+
 /// Library folder info
 #[derive(Debug, Clone)]
 pub struct LibraryFolder {
@@ -986,12 +1961,90 @@ pub struct Collection {
     pub created_at: String,
 }
 
+/// An ordered wallpaper rotation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Playlist {
+    /// Database id (0 for a playlist that has not been saved yet)
+    pub id: i64,
+    pub name: String,
+    pub shuffle: bool,
+    /// Transition between entries (e.g. "none", "fade")
+    pub transition: String,
+    /// Duration used for entries without their own
+    pub default_duration_secs: u32,
+    pub items: Vec<PlaylistEntry>,
+    /// Outputs this playlist is assigned to
+    pub outputs: Vec<String>,
+    /// Folder the entries are read from instead of `items`
+    pub folder: Option<PlaylistFolder>,
+}
+
+/// Folder whose files a playlist plays in file name order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistFolder {
+    pub path: PathBuf,
+    /// File name patterns such as `*.mp4 *.webm`; every file matches without one
+    pub pattern: Option<String>,
+    /// How long images are shown; videos use the playlist default
+    pub image_duration_secs: Option<u32>,
+}
+
+/// A single playlist entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistEntry {
+    pub item_id: String,
+    /// Display duration override in seconds
+    pub duration_secs: Option<u32>,
+}
+
+impl PlaylistEntry {
+    /// Effective display duration for this entry
+    pub fn duration(&self, playlist: &Playlist) -> u32 {
+        self.duration_secs.unwrap_or(playlist.default_duration_secs)
+    }
+}
+
 /// Thumbnail data
 #[derive(Debug, Clone)]
 pub struct ThumbnailData {
     pub data: Vec<u8>,
     pub width: u32,
     pub height: u32,
+    /// Source file mtime (seconds since the epoch) the thumbnail was made from
+    pub source_modified: Option<i64>,
+    /// Source file the thumbnail was made from; pruning drops the thumbnail
+    /// once it is gone
+    pub source_path: Option<PathBuf>,
+}
+
+/// What [`LibraryDatabase::prune_thumbnails`] removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThumbnailPrune {
+    /// Entries whose source file is gone
+    pub orphaned: usize,
+    /// Least recently used entries dropped to fit the budget
+    pub evicted: usize,
+    pub bytes_reclaimed: u64,
+}
+
+/// A stored thumbnail or strip, as pruning sees it
+struct StoredThumbnail {
+    table: &'static str,
+    rowid: i64,
+    bytes: u64,
+    last_used_at: i64,
+    source_path: Option<PathBuf>,
+}
+
+/// Stored video sprite strip
+#[derive(Debug, Clone)]
+pub struct ThumbnailStripData {
+    pub data: Vec<u8>,
+    pub frame_width: u32,
+    pub frame_height: u32,
+    pub frame_count: u32,
+    /// Source file mtime (unix seconds) the strip was generated from
+    pub source_modified: Option<i64>,
 }
 
 /// Library statistics
@@ -1008,16 +2061,168 @@ pub struct LibraryStats {
 
 // ========== Conversion helpers ==========
 
+/// SQL conditions on `wallpapers w` for the filters in `options`; the text
+/// query is matched by [`rank_text_matches`] instead
+fn search_conditions(options: &SearchOptions) -> String {
+    let mut conditions = vec!["1=1".to_string()];
+
+    // Type filter
+    if let Some(ref wtype) = options.wallpaper_type {
+        conditions.push(format!("w.wallpaper_type = '{}'", wtype.as_str()));
+    }
+
+    // Source filter
+    if let Some(ref stype) = options.source_type {
+        conditions.push(format!("w.source_type = '{}'", stype.as_str()));
+    }
+
+    // Favorites filter
+    if options.favorites_only {
+        conditions.push("w.favorite = 1".to_string());
+    }
+
+    // Rating filter
+    if let Some(min_rating) = options.min_rating {
+        conditions.push(format!("w.rating >= {}", min_rating));
+    }
+
+    // Tag filter (match any)
+    if !options.tags.is_empty() {
+        let tag_names: Vec<String> = options
+            .tags
+            .iter()
+            .map(|t| format!("'{}'", t.replace('\'', "''")))
+            .collect();
+        conditions.push(format!(
+            "w.id IN (SELECT wt.wallpaper_id FROM wallpaper_tags wt JOIN tags t ON wt.tag_id = t.id WHERE t.name IN ({}))",
+            tag_names.join(",")
+        ));
+    }
+
+    // Folder filter: a plain prefix compare, so `_` and `%` in paths match literally
+    if let Some(ref folder) = options.folder {
+        let mut prefix = folder.to_string_lossy().trim_end_matches('/').to_string();
+        prefix.push('/');
+        conditions.push(format!(
+            "substr(w.source_path, 1, {}) = '{}'",
+            prefix.chars().count(),
+            prefix.replace('\'', "''")
+        ));
+    }
+
+    conditions.join(" AND ")
+}
+
+fn search_order(sort_by: SortBy) -> &'static str {
+    match sort_by {
+        SortBy::Name => "w.name ASC",
+        SortBy::DateAdded => "w.added_at DESC",
+        SortBy::LastUsed => "w.last_used DESC NULLS LAST",
+        SortBy::UseCount => "w.use_count DESC",
+        SortBy::Rating => "w.rating DESC, w.name ASC",
+        SortBy::Relevance => "w.name ASC", // Ties after text ranking stay in name order
+    }
+}
+
+/// The fuzzy text query in `options`, if it has anything to match
+fn text_query(options: &SearchOptions) -> Option<FuzzyQuery> {
+    options
+        .query
+        .as_deref()
+        .map(FuzzyQuery::new)
+        .filter(|query| !query.is_empty())
+}
+
+/// Searchable text of a wallpaper with its weight; names and titles count
+/// most, descriptions least
+fn search_fields<'a>(
+    name: &'a str,
+    metadata: &'a WallpaperMetadata,
+    tags: &'a [String],
+) -> Vec<(&'a str, f64)> {
+    let mut fields = vec![(name, 1.0)];
+    fields.extend(metadata.title.as_deref().map(|title| (title, 1.0)));
+    fields.extend(tags.iter().map(|tag| (tag.as_str(), 0.8)));
+    fields.extend(metadata.author.as_deref().map(|author| (author, 0.7)));
+    fields.extend(
+        metadata
+            .description
+            .as_deref()
+            .map(|description| (description, 0.5)),
+    );
+    fields
+}
+
+/// Keeps the rows matching `query`, ranks them by score for relevance
+/// sorting (other sorts keep the SQL order) and applies the page. Also
+/// returns the number of matches across all pages.
+fn rank_text_matches<T>(
+    query: &FuzzyQuery,
+    options: &SearchOptions,
+    rows: Vec<T>,
+    fields: impl Fn(&T) -> Vec<(&str, f64)>,
+) -> (Vec<T>, usize) {
+    let mut matches: Vec<(T, f64)> = rows
+        .into_iter()
+        .filter_map(|row| {
+            let score = query.score(&fields(&row))?;
+            Some((row, score))
+        })
+        .collect();
+
+    if matches!(options.sort_by, SortBy::Relevance) {
+        matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+    }
+
+    let total = matches.len();
+    let page = matches
+        .into_iter()
+        .skip(options.offset)
+        .take(options.limit.unwrap_or(usize::MAX))
+        .map(|(row, _)| row)
+        .collect();
+    (page, total)
+}
+
+/// `LIMIT`/`OFFSET` clause; SQLite needs a limit before an offset
+fn search_page(options: &SearchOptions) -> String {
+    match (options.limit, options.offset) {
+        (Some(limit), 0) => format!("LIMIT {limit}"),
+        (Some(limit), offset) => format!("LIMIT {limit} OFFSET {offset}"),
+        (None, 0) => String::new(),
+        (None, offset) => format!("LIMIT -1 OFFSET {offset}"),
+    }
+}
+
 fn str_to_wallpaper_type(s: &str) -> WallpaperType {
     match s {
         "video" => WallpaperType::Video,
         "image" => WallpaperType::Image,
         "scene" => WallpaperType::Scene,
-        "gif" => WallpaperType::Gif,
+        // "gif" is how animated images were stored before APNG/WebP support
+        "animated_image" | "gif" => WallpaperType::AnimatedImage,
+        "web" => WallpaperType::Web,
         _ => WallpaperType::Video,
     }
 }
 
+/// SDR and unknown transfers are not stored, so the column only says "pq" or "hlg"
+fn hdr_column(transfer: TransferFunction) -> Option<&'static str> {
+    match transfer {
+        TransferFunction::Pq => Some("pq"),
+        TransferFunction::Hlg => Some("hlg"),
+        TransferFunction::Srgb | TransferFunction::Unknown => None,
+    }
+}
+
+fn parse_hdr_column(s: &str) -> Option<TransferFunction> {
+    match s {
+        "pq" => Some(TransferFunction::Pq),
+        "hlg" => Some(TransferFunction::Hlg),
+        _ => None,
+    }
+}
+
 fn str_to_source_type(s: &str) -> SourceType {
     match s {
         "local_file" => SourceType::LocalFile,
@@ -1093,6 +2298,79 @@ mod tests {
         assert!(none.is_none());
     }
 
+    #[test]
+    fn test_shader_and_lut_survive_rescan() {
+        let (db, _temp) = create_test_db();
+
+        let item = create_test_wallpaper("crt_retro", WallpaperType::Video);
+        db.upsert_wallpaper(&item).unwrap();
+
+        db.set_shader_path(&item.id, Some(Path::new("/home/user/shaders/crt.glsl")))
+            .unwrap();
+
+        // A rescan upserts the item without a shader
+        db.upsert_wallpaper(&item).unwrap();
+
+        let retrieved = db.get_wallpaper(&item.id).unwrap().unwrap();
+        assert_eq!(
+            retrieved.metadata.shader_path,
+            Some(PathBuf::from("/home/user/shaders/crt.glsl"))
+        );
+
+        db.set_shader_path(&item.id, None).unwrap();
+        assert_eq!(db.get_shader_path(&item.id).unwrap(), None);
+
+        db.set_lut_path(&item.id, Some(Path::new("/home/user/luts/teal.cube")))
+            .unwrap();
+        db.upsert_wallpaper(&item).unwrap();
+        let retrieved = db.get_wallpaper(&item.id).unwrap().unwrap();
+        assert_eq!(
+            retrieved.metadata.lut_path,
+            Some(PathBuf::from("/home/user/luts/teal.cube"))
+        );
+    }
+
+    #[test]
+    fn test_media_metadata_roundtrip() {
+        let (db, _temp) = create_test_db();
+
+        let mut item = create_test_wallpaper("ocean_hdr", WallpaperType::Video);
+        item.metadata.codec = Some("hevc".to_string());
+        item.metadata.fps = Some(59.94);
+        item.metadata.hdr = Some(TransferFunction::Pq);
+        db.upsert_wallpaper(&item).unwrap();
+
+        let retrieved = db.get_wallpaper(&item.id).unwrap().unwrap();
+        assert_eq!(retrieved.metadata.codec.as_deref(), Some("hevc"));
+        assert_eq!(retrieved.metadata.fps, Some(59.94));
+        assert_eq!(retrieved.metadata.hdr, Some(TransferFunction::Pq));
+
+        item.metadata.hdr = Some(TransferFunction::Srgb);
+        db.upsert_wallpaper(&item).unwrap();
+        let retrieved = db.get_wallpaper(&item.id).unwrap().unwrap();
+        assert_eq!(retrieved.metadata.hdr, None);
+    }
+
+    #[test]
+    fn test_user_properties_survive_rescan() {
+        let (db, _temp) = create_test_db();
+
+        let item = create_test_wallpaper("rainy_city", WallpaperType::Web);
+        db.upsert_wallpaper(&item).unwrap();
+        assert!(db.get_user_properties(&item.id).unwrap().is_empty());
+
+        let mut values = serde_json::Map::new();
+        values.insert("speed".to_string(), serde_json::json!(2.5));
+        values.insert("schemecolor".to_string(), serde_json::json!("1 0 0"));
+        db.set_user_properties(&item.id, &values).unwrap();
+        db.upsert_wallpaper(&item).unwrap();
+        assert_eq!(db.get_user_properties(&item.id).unwrap(), values);
+
+        db.set_user_properties(&item.id, &serde_json::Map::new())
+            .unwrap();
+        assert!(db.get_user_properties(&item.id).unwrap().is_empty());
+    }
+
     #[test]
     fn test_library_folders() {
         let (db, _temp) = create_test_db();
@@ -1138,6 +2416,40 @@ mod tests {
         assert!(results[0].name.contains("city"));
     }
 
+    #[test]
+    fn test_search_tolerates_typos_and_ranks_by_relevance() {
+        let (db, _temp) = create_test_db();
+
+        let mut night = create_test_wallpaper("sakura_night", WallpaperType::Video);
+        night.metadata.title = Some("Sakura Night".to_string());
+        let mut cafe = create_test_wallpaper("cafe", WallpaperType::Image);
+        cafe.metadata.title = Some("Café Nuit".to_string());
+        let mut mention = create_test_wallpaper("garden", WallpaperType::Video);
+        mention.metadata.description = Some("A garden seen on a sakura night".to_string());
+
+        for item in [&night, &cafe, &mention] {
+            db.upsert_wallpaper(item).unwrap();
+        }
+
+        let results = db.search("sakura nigth", None).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, night.id);
+
+        let results = db.search("cafe nuit", None).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, cafe.id);
+
+        let filter = LibraryFilter {
+            search: Some("sakrua".to_string()),
+            sort: LibrarySort::Relevance,
+            limit: Some(1),
+            ..LibraryFilter::default()
+        };
+        let (items, total) = db.query_library(&filter).unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(items[0].id, night.id);
+    }
+
     #[test]
     fn test_tags() {
         let (db, _temp) = create_test_db();
@@ -1211,6 +2523,109 @@ mod tests {
         assert_eq!(collections.len(), 0);
     }
 
+    #[test]
+    fn test_collections_hold_unindexed_items_and_rename() {
+        let (db, _temp) = create_test_db();
+
+        let collection_id = db.create_collection("Evening", None).unwrap();
+        db.add_to_collection(collection_id, "workshop-123").unwrap();
+        db.add_to_collection(collection_id, "local-abc").unwrap();
+        db.add_to_collection(collection_id, "workshop-123").unwrap();
+        assert_eq!(
+            db.collection_item_ids(collection_id).unwrap(),
+            vec!["workshop-123".to_string(), "local-abc".to_string()]
+        );
+        // Only indexed wallpapers resolve to full items
+        assert!(db
+            .get_collection_wallpapers(collection_id)
+            .unwrap()
+            .is_empty());
+
+        assert!(db.rename_collection(collection_id, "Night").unwrap());
+        assert!(!db.rename_collection(collection_id + 1, "Night").unwrap());
+        let collections = db.list_collections().unwrap();
+        assert_eq!(collections[0].name, "Night");
+        assert_eq!(collections[0].wallpaper_count, 2);
+    }
+
+    #[test]
+    fn test_playlists() {
+        let (db, _temp) = create_test_db();
+
+        let mut playlist = Playlist {
+            id: 0,
+            name: "Evening".to_string(),
+            shuffle: false,
+            transition: "fade".to_string(),
+            default_duration_secs: 300,
+            items: vec![
+                PlaylistEntry {
+                    item_id: "scene-1".to_string(),
+                    duration_secs: Some(60),
+                },
+                PlaylistEntry {
+                    item_id: "video-2".to_string(),
+                    duration_secs: None,
+                },
+            ],
+            outputs: vec!["DP-1".to_string()],
+            folder: None,
+        };
+
+        playlist.id = db.save_playlist(&playlist).unwrap();
+        assert!(playlist.id > 0);
+        assert_eq!(
+            db.get_playlist(playlist.id).unwrap(),
+            Some(playlist.clone())
+        );
+        assert_eq!(playlist.items[1].duration(&playlist), 300);
+
+        // Reorder and reassign
+        playlist.items.reverse();
+        playlist.outputs = vec!["HDMI-A-1".to_string()];
+        db.save_playlist(&playlist).unwrap();
+        let stored = db.get_playlist(playlist.id).unwrap().unwrap();
+        assert_eq!(stored.items[0].item_id, "video-2");
+        assert_eq!(
+            db.playlist_assignments().unwrap(),
+            vec![("HDMI-A-1".to_string(), playlist.id)]
+        );
+
+        // An output moves to the most recently assigned playlist
+        let other = Playlist {
+            id: 0,
+            name: "Morning".to_string(),
+            outputs: vec!["HDMI-A-1".to_string()],
+            ..playlist.clone()
+        };
+        let other_id = db.save_playlist(&other).unwrap();
+        assert_eq!(
+            db.playlist_assignments().unwrap(),
+            vec![("HDMI-A-1".to_string(), other_id)]
+        );
+
+        // A folder playlist keeps its folder, pattern and image duration
+        let mut folder = Playlist {
+            id: 0,
+            name: "Wallpapers".to_string(),
+            items: Vec::new(),
+            outputs: Vec::new(),
+            folder: Some(PlaylistFolder {
+                path: PathBuf::from("/home/user/Wallpapers"),
+                pattern: Some("*.mp4 *.png".to_string()),
+                image_duration_secs: Some(30),
+            }),
+            ..playlist.clone()
+        };
+        folder.id = db.save_playlist(&folder).unwrap();
+        assert_eq!(db.get_playlist(folder.id).unwrap(), Some(folder.clone()));
+        assert!(db.delete_playlist(folder.id).unwrap());
+
+        assert!(db.delete_playlist(other_id).unwrap());
+        assert!(db.playlist_assignments().unwrap().is_empty());
+        assert_eq!(db.list_playlists().unwrap().len(), 1);
+    }
+
     #[test]
     fn test_rating() {
         let (db, _temp) = create_test_db();
@@ -1227,6 +2642,200 @@ mod tests {
         assert_eq!(db.get_rating(&item.id).unwrap(), 5);
     }
 
+    #[test]
+    fn test_item_marks_cover_marked_and_used_wallpapers() {
+        let (db, _temp) = create_test_db();
+
+        let favorite = create_test_wallpaper("favorite", WallpaperType::Video);
+        let used = create_test_wallpaper("used", WallpaperType::Video);
+        let untouched = create_test_wallpaper("untouched", WallpaperType::Video);
+        for item in [&favorite, &used, &untouched] {
+            db.upsert_wallpaper(item).unwrap();
+        }
+        db.set_favorite(&favorite.id, true).unwrap();
+        db.set_rating(&favorite.id, 3).unwrap();
+        db.record_usage(&used.id).unwrap();
+        db.record_usage(&used.id).unwrap();
+
+        let marks = db.item_marks().unwrap();
+        assert_eq!(marks.len(), 2);
+        assert!(marks[&favorite.id].favorite);
+        assert_eq!(marks[&favorite.id].rating, 3);
+        assert_eq!(marks[&used.id].use_count, 2);
+        assert!(marks[&used.id].last_used.is_some());
+        assert!(!marks.contains_key(&untouched.id));
+
+        db.set_favorite(&favorite.id, false).unwrap();
+        db.set_rating(&favorite.id, 0).unwrap();
+        assert!(!db.item_marks().unwrap().contains_key(&favorite.id));
+    }
+
+    #[test]
+    fn test_item_marks_carry_custom_title_and_tags() {
+        let (db, _temp) = create_test_db();
+
+        let item = create_test_wallpaper("rainy_city", WallpaperType::Video);
+        db.upsert_wallpaper(&item).unwrap();
+        db.set_custom_title(&item.id, Some("Rain at night"))
+            .unwrap();
+        db.set_wallpaper_tags(&item.id, &["rain".to_string(), "city".to_string()])
+            .unwrap();
+        db.upsert_wallpaper(&item).unwrap();
+
+        let marks = &db.item_marks().unwrap()[&item.id];
+        assert_eq!(marks.title.as_deref(), Some("Rain at night"));
+        assert_eq!(marks.tags, vec!["city".to_string(), "rain".to_string()]);
+
+        db.set_custom_title(&item.id, None).unwrap();
+        db.set_wallpaper_tags(&item.id, &[]).unwrap();
+        assert!(!db.item_marks().unwrap().contains_key(&item.id));
+    }
+
+    #[test]
+    fn test_item_marks_carry_shader_and_lut() {
+        let (db, _temp) = create_test_db();
+
+        let item = create_test_wallpaper("crt_retro", WallpaperType::Video);
+        db.upsert_wallpaper(&item).unwrap();
+        db.set_shader_path(&item.id, Some(Path::new("/home/user/shaders/crt.glsl")))
+            .unwrap();
+
+        assert_eq!(
+            db.item_marks().unwrap()[&item.id].shader,
+            Some(PathBuf::from("/home/user/shaders/crt.glsl"))
+        );
+
+        db.set_lut_path(&item.id, Some(Path::new("/home/user/luts/teal.cube")))
+            .unwrap();
+        db.set_shader_path(&item.id, None).unwrap();
+        let marks = &db.item_marks().unwrap()[&item.id];
+        assert_eq!(marks.shader, None);
+        assert_eq!(marks.lut, Some(PathBuf::from("/home/user/luts/teal.cube")));
+
+        db.set_lut_path(&item.id, None).unwrap();
+        assert_eq!(db.get_lut_path(&item.id).unwrap(), None);
+        assert!(!db.item_marks().unwrap().contains_key(&item.id));
+    }
+
+    #[test]
+    fn test_thumbnail_strip_roundtrip_without_indexed_wallpaper() {
+        let (db, _temp) = create_test_db();
+        assert!(db.get_thumbnail_strip("not-indexed").unwrap().is_none());
+
+        let strip = ThumbnailStrip {
+            data: vec![1, 2, 3],
+            frame_width: 240,
+            frame_height: 240,
+            frame_count: 10,
+            format: "webp".to_string(),
+        };
+        let video = Path::new("/videos/rain.mp4");
+        db.store_thumbnail_strip("not-indexed", video, &strip, Some(1_700_000_000))
+            .unwrap();
+        db.store_thumbnail_strip("not-indexed", video, &strip, Some(1_700_000_100))
+            .unwrap();
+
+        let stored = db.get_thumbnail_strip("not-indexed").unwrap().unwrap();
+        assert_eq!(stored.data, vec![1, 2, 3]);
+        assert_eq!((stored.frame_width, stored.frame_height), (240, 240));
+        assert_eq!(stored.frame_count, 10);
+        assert_eq!(stored.source_modified, Some(1_700_000_100));
+    }
+
+    #[test]
+    fn test_store_thumbnails_in_one_batch() {
+        let (db, _temp) = create_test_db();
+        let thumbnail = |byte: u8, source_modified| ThumbnailData {
+            data: vec![byte; 4],
+            width: 640,
+            height: 360,
+            source_modified,
+            source_path: None,
+        };
+
+        db.store_thumbnails(&[
+            ("not-indexed".to_string(), thumbnail(1, Some(100))),
+            ("other".to_string(), thumbnail(2, None)),
+        ])
+        .unwrap();
+        db.store_thumbnails(&[("not-indexed".to_string(), thumbnail(3, Some(200)))])
+            .unwrap();
+
+        let stored = db.get_thumbnail("not-indexed").unwrap().unwrap();
+        assert_eq!(stored.data, vec![3; 4]);
+        assert_eq!(stored.source_modified, Some(200));
+        assert_eq!(db.get_thumbnail("other").unwrap().unwrap().data, vec![2; 4]);
+        assert_eq!(db.thumbnail_bytes("other").unwrap(), 4);
+    }
+
+    #[test]
+    fn test_thumbnail_bytes_and_delete() {
+        let (db, _temp) = create_test_db();
+        assert_eq!(db.thumbnail_bytes("cached").unwrap(), 0);
+        assert!(!db.delete_thumbnails("cached").unwrap());
+
+        let strip = ThumbnailStrip {
+            data: vec![0; 64],
+            frame_width: 240,
+            frame_height: 240,
+            frame_count: 10,
+            format: "webp".to_string(),
+        };
+        let video = Path::new("/videos/rain.mp4");
+        db.store_thumbnail_strip("cached", video, &strip, None)
+            .unwrap();
+        db.store_thumbnail_strip("other", video, &strip, None)
+            .unwrap();
+        assert_eq!(db.thumbnail_bytes("cached").unwrap(), 64);
+
+        assert!(db.delete_thumbnails("cached").unwrap());
+        assert_eq!(db.thumbnail_bytes("cached").unwrap(), 0);
+        assert!(db.get_thumbnail_strip("other").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_prune_thumbnails_drops_orphans_then_least_recently_used() {
+        let (db, temp) = create_test_db();
+        let source = |name: &str| {
+            let path = temp.path().join(name);
+            std::fs::write(&path, b"cover").unwrap();
+            path
+        };
+        let thumbnail = |source_path: PathBuf| ThumbnailData {
+            data: vec![0; 10],
+            width: 640,
+            height: 360,
+            source_modified: None,
+            source_path: Some(source_path),
+        };
+
+        db.store_thumbnails(&[
+            ("old".to_string(), thumbnail(source("old.gif"))),
+            ("new".to_string(), thumbnail(source("new.gif"))),
+            ("gone".to_string(), thumbnail(temp.path().join("gone.gif"))),
+        ])
+        .unwrap();
+        db.store_thumbnail("untracked", &[0; 10], 640, 360).unwrap();
+        assert_eq!(db.thumbnail_cache_bytes().unwrap(), 40);
+
+        let prune = db.prune_thumbnails(15).unwrap();
+        assert_eq!(
+            prune,
+            ThumbnailPrune {
+                orphaned: 2,
+                evicted: 1,
+                bytes_reclaimed: 30,
+            }
+        );
+        assert!(db.get_thumbnail("new").unwrap().is_some());
+        assert!(db.get_thumbnail("old").unwrap().is_none());
+        assert_eq!(db.prune_thumbnails(15).unwrap(), ThumbnailPrune::default());
+
+        let cleared = db.clear_thumbnails().unwrap();
+        assert_eq!((cleared.evicted, cleared.bytes_reclaimed), (1, 10));
+        assert_eq!(db.thumbnail_cache_bytes().unwrap(), 0);
+    }
+
     #[test]
     fn test_advanced_search() {
         let (db, _temp) = create_test_db();
@@ -1263,4 +2872,154 @@ mod tests {
         let results = db.search_advanced(&options).unwrap();
         assert_eq!(results.len(), 1);
     }
+
+    #[test]
+    fn test_query_library_pages_and_counts_matches() {
+        let (db, _temp) = create_test_db();
+
+        for (name, rating) in [("rain_a", 2), ("rain_b", 5), ("rain_c", 4), ("city", 5)] {
+            let item = create_test_wallpaper(name, WallpaperType::Video);
+            db.upsert_wallpaper(&item).unwrap();
+            db.set_rating(&item.id, rating).unwrap();
+            if name.starts_with("rain") {
+                db.add_tag_to_wallpaper(&item.id, "rain").unwrap();
+            }
+        }
+
+        let filter = LibraryFilter {
+            tags: Some(vec!["rain".to_string()]),
+            sort: LibrarySort::Rating,
+            offset: 1,
+            limit: Some(1),
+            ..LibraryFilter::default()
+        };
+        let (items, total) = db.query_library(&filter).unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].name, "rain_c");
+        assert_eq!(items[0].rating, 4);
+        assert_eq!(items[0].tags, ["rain"]);
+
+        let filter = LibraryFilter {
+            search: Some("city".to_string()),
+            min_rating: Some(5),
+            ..LibraryFilter::default()
+        };
+        let (items, total) = db.query_library(&filter).unwrap();
+        assert_eq!((items.len(), total), (1, 1));
+
+        let filter = LibraryFilter {
+            wallpaper_type: Some("hologram".to_string()),
+            ..LibraryFilter::default()
+        };
+        assert!(db.query_library(&filter).is_err());
+    }
+
+    #[test]
+    fn test_query_library_by_folder() {
+        let (db, _temp) = create_test_db();
+
+        for path in [
+            "/walls/nature/forest.mp4",
+            "/walls/nature/deep/reef.mp4",
+            "/walls/nature_extra/dunes.mp4",
+            "/walls/city.mp4",
+        ] {
+            let item = WallpaperItem::new(
+                PathBuf::from(path),
+                path.to_string(),
+                SourceType::LocalFile,
+                WallpaperType::Video,
+            );
+            db.upsert_wallpaper(&item).unwrap();
+        }
+
+        for folder in ["/walls/nature", "/walls/nature/"] {
+            let filter = LibraryFilter {
+                folder: Some(folder.to_string()),
+                ..LibraryFilter::default()
+            };
+            let (items, total) = db.query_library(&filter).unwrap();
+            assert_eq!(total, 2);
+            assert_eq!(
+                items
+                    .iter()
+                    .map(|item| item.path.as_str())
+                    .collect::<Vec<_>>(),
+                ["/walls/nature/deep/reef.mp4", "/walls/nature/forest.mp4"]
+            );
+        }
+
+        let filter = LibraryFilter {
+            folder: Some("/walls/nat".to_string()),
+            ..LibraryFilter::default()
+        };
+        assert_eq!(db.query_library(&filter).unwrap().1, 0);
+    }
+
+    #[test]
+    fn test_file_events_keep_user_data_across_renames() {
+        let (db, temp) = create_test_db();
+        let scanner = FolderScanner::new();
+        let beach = temp.path().join("beach.mp4");
+        let coast = temp.path().join("coast.mp4");
+        std::fs::write(&beach, b"waves").unwrap();
+
+        db.apply_file_event(&FileEvent::Created(beach.clone()), &scanner)
+            .unwrap();
+        let item = db.get_wallpaper_by_path(&beach).unwrap().unwrap();
+        db.set_rating(&item.id, 4).unwrap();
+        db.add_tag_to_wallpaper(&item.id, "summer").unwrap();
+        let collection = db.create_collection("Calm", None).unwrap();
+        db.add_to_collection(collection, &item.id).unwrap();
+
+        std::fs::rename(&beach, &coast).unwrap();
+        db.apply_file_event(
+            &FileEvent::Renamed {
+                from: beach.clone(),
+                to: coast.clone(),
+            },
+            &scanner,
+        )
+        .unwrap();
+
+        assert!(db.get_wallpaper_by_path(&beach).unwrap().is_none());
+        let renamed = db.get_wallpaper_by_path(&coast).unwrap().unwrap();
+        assert_eq!(renamed.id, WallpaperItem::generate_id(&coast));
+        assert_eq!(renamed.name, "coast");
+        assert_eq!(db.get_rating(&renamed.id).unwrap(), 4);
+        assert_eq!(
+            db.get_wallpaper_tags(&renamed.id).unwrap()[0].name,
+            "summer"
+        );
+        assert_eq!(
+            db.collection_item_ids(collection).unwrap(),
+            vec![renamed.id.clone()]
+        );
+
+        db.apply_file_event(&FileEvent::Deleted(coast.clone()), &scanner)
+            .unwrap();
+        assert!(db.get_wallpaper(&renamed.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_prune_folder_drops_missing_files() {
+        let (db, temp) = create_test_db();
+        let scanner = FolderScanner::new();
+        let kept = temp.path().join("kept.png");
+        let gone = temp.path().join("gone.png");
+        std::fs::write(&kept, b"kept").unwrap();
+        std::fs::write(&gone, b"gone").unwrap();
+        for path in [&kept, &gone] {
+            db.upsert_wallpaper(&scanner.process_file(path).unwrap())
+                .unwrap();
+        }
+        db.upsert_wallpaper(&create_test_wallpaper("elsewhere", WallpaperType::Video))
+            .unwrap();
+
+        std::fs::remove_file(&gone).unwrap();
+        assert_eq!(db.prune_folder(temp.path()).unwrap(), 1);
+        assert!(db.get_wallpaper_by_path(&kept).unwrap().is_some());
+        assert_eq!(db.get_stats().unwrap().total_wallpapers, 2);
+    }
 }
@@ -0,0 +1,270 @@
+//! Typo-tolerant text matching for library search
+//!
+//! Text is folded to lowercase tokens without diacritics, so "Café Nuit"
+//! becomes `cafe` `nuit`. Each query token is then scored against the field
+//! tokens by exact, prefix, edit-distance (with transpositions) and trigram
+//! similarity. An item matches when every query token matches some field.
+
+use std::collections::HashSet;
+
+/// Score bonus when a multi-word query appears as a phrase in a field
+const PHRASE_BONUS: f64 = 0.1;
+
+/// Minimum trigram similarity that still counts as a match
+const MIN_TRIGRAM_SIMILARITY: f64 = 0.5;
+
+/// A search query folded once and scored against many items
+#[derive(Debug, Clone)]
+pub struct FuzzyQuery {
+    tokens: Vec<String>,
+    phrase: String,
+}
+
+impl FuzzyQuery {
+    pub fn new(query: &str) -> Self {
+        let tokens: Vec<String> = fold(query).split_whitespace().map(str::to_string).collect();
+        let phrase = tokens.join(" ");
+        Self { tokens, phrase }
+    }
+
+    /// True when the query has no searchable characters
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Relevance of an item given its `(text, weight)` fields, or `None` if
+    /// some query token matches none of them
+    pub fn score(&self, fields: &[(&str, f64)]) -> Option<f64> {
+        let fields: Vec<(String, f64)> = fields
+            .iter()
+            .map(|(text, weight)| (fold(text), *weight))
+            .collect();
+
+        let mut total = 0.0;
+        for query_token in &self.tokens {
+            let best = fields
+                .iter()
+                .flat_map(|(text, weight)| {
+                    text.split_whitespace()
+                        .map(move |token| token_score(query_token, token) * weight)
+                })
+                .fold(0.0, f64::max);
+            if best <= 0.0 {
+                return None;
+            }
+            total += best;
+        }
+
+        let mut score = total / self.tokens.len().max(1) as f64;
+        if self.tokens.len() > 1
+            && fields
+                .iter()
+                .any(|(text, _)| normalize_spaces(text).contains(&self.phrase))
+        {
+            score += PHRASE_BONUS;
+        }
+        Some(score)
+    }
+}
+
+/// Lowercases `text`, strips diacritics and turns punctuation into spaces
+pub fn fold(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.chars() {
+        if is_combining_mark(c) {
+            continue;
+        }
+        for lower in c.to_lowercase() {
+            match fold_char(lower) {
+                Some(base) => folded.push_str(base),
+                None if lower.is_alphanumeric() => folded.push(lower),
+                None => folded.push(' '),
+            }
+        }
+    }
+    folded
+}
+
+fn normalize_spaces(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Combining diacritical marks, left over from decomposed (NFD) input
+fn is_combining_mark(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036f}')
+}
+
+/// ASCII base of accented Latin letters
+fn fold_char(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
+/// How well one folded query token matches one folded field token (0 = no match)
+fn token_score(query: &str, token: &str) -> f64 {
+    if query == token {
+        return 1.0;
+    }
+    if token.starts_with(query) {
+        return 0.9;
+    }
+
+    let query_len = query.chars().count();
+    // Scripts without spaces (CJK) need substring matches even for short queries
+    if (query_len >= 3 || !query.is_ascii()) && token.contains(query) {
+        return 0.7;
+    }
+
+    let typos = allowed_typos(query_len);
+    if typos > 0 {
+        let query_chars: Vec<char> = query.chars().collect();
+        let token_chars: Vec<char> = token.chars().collect();
+
+        let distance = edit_distance(&query_chars, &token_chars);
+        if distance <= typos {
+            return 0.8 - 0.15 * distance as f64;
+        }
+        // A misspelled prefix, e.g. "watre" for "waterfall"
+        if token_chars.len() > query_len {
+            let distance = edit_distance(&query_chars, &token_chars[..query_len]);
+            if distance <= typos {
+                return 0.7 - 0.15 * distance as f64;
+            }
+        }
+    }
+
+    if query_len >= 4 {
+        let similarity = trigram_similarity(query, token);
+        if similarity >= MIN_TRIGRAM_SIMILARITY {
+            return 0.5 * similarity;
+        }
+    }
+    0.0
+}
+
+/// Short words must match exactly; longer ones may carry a typo or two
+fn allowed_typos(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Levenshtein distance that counts swapping two adjacent characters as one
+/// edit (optimal string alignment)
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let width = b.len() + 1;
+    let mut rows = vec![0usize; (a.len() + 1) * width];
+    for (j, cell) in rows.iter_mut().take(width).enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        rows[i * width] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[(i - 1) * width + j] + 1)
+                .min(rows[i * width + j - 1] + 1)
+                .min(rows[(i - 1) * width + j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[(i - 2) * width + j - 2] + 1);
+            }
+            rows[i * width + j] = distance;
+        }
+    }
+    rows[a.len() * width + b.len()]
+}
+
+/// Dice coefficient of the two words' character trigrams
+fn trigram_similarity(a: &str, b: &str) -> f64 {
+    let a = trigrams(a);
+    let b = trigrams(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(&b).count();
+    2.0 * shared as f64 / (a.len() + b.len()) as f64
+}
+
+fn trigrams(word: &str) -> HashSet<[char; 3]> {
+    let chars: Vec<char> = word.chars().collect();
+    chars.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_strips_diacritics_and_punctuation() {
+        assert_eq!(fold("Café_Nuit"), "cafe nuit");
+        assert_eq!(fold("Straße Ø"), "strasse o");
+        // Decomposed "é" (e + combining acute)
+        assert_eq!(fold("Cafe\u{0301}"), "cafe");
+        assert_eq!(fold("夜桜 Night"), "夜桜 night");
+    }
+
+    #[test]
+    fn test_edit_distance_counts_transpositions_once() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert_eq!(edit_distance(&chars("nigth"), &chars("night")), 1);
+        assert_eq!(edit_distance(&chars("sakra"), &chars("sakura")), 1);
+        assert_eq!(edit_distance(&chars("city"), &chars("rain")), 4);
+        assert_eq!(edit_distance(&chars(""), &chars("abc")), 3);
+    }
+
+    #[test]
+    fn test_query_tolerates_typos_and_accents() {
+        let query = FuzzyQuery::new("sakura nigth");
+        assert!(query.score(&[("Sakura Night", 1.0)]).is_some());
+        assert!(query.score(&[("Sakura Day", 1.0)]).is_none());
+
+        let query = FuzzyQuery::new("cafe");
+        assert!(query.score(&[("Café de Flore", 1.0)]).is_some());
+
+        let query = FuzzyQuery::new("watre");
+        assert!(query.score(&[("Waterfall", 1.0)]).is_some());
+
+        // Short words are not fuzzed into unrelated ones
+        let query = FuzzyQuery::new("cat");
+        assert!(query.score(&[("car park", 1.0)]).is_none());
+    }
+
+    #[test]
+    fn test_query_ranks_closer_matches_higher() {
+        let query = FuzzyQuery::new("sakura night");
+        let exact = query.score(&[("Sakura Night", 1.0)]).unwrap();
+        let reordered = query.score(&[("Night of Sakura", 1.0)]).unwrap();
+        let typo = query.score(&[("Sakura Nigth", 1.0)]).unwrap();
+        let in_description = query.score(&[("Sakura Night", 0.5)]).unwrap();
+
+        assert!(exact > reordered);
+        assert!(reordered > typo);
+        assert!(exact > in_description);
+    }
+}
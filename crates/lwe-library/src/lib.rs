@@ -2,9 +2,12 @@
 //!
 //! This crate provides LWE wallpaper library functionality:
 //! - SQLite database for wallpaper indexing and metadata
+//! - Typo-tolerant library search
 //! - Folder scanning and change detection
 //! - Thumbnail generation and caching
+//! - Media metadata read from container headers, without ffprobe
 //! - Library statistics and queries
+//! - Wallpaper Engine scene package and texture unpacking
 //!
 //! # Example
 //!
@@ -34,22 +37,29 @@
 //! ```
 
 pub mod database;
+pub mod fuzzy;
+pub mod media_info;
 pub mod scanner;
+pub mod scene_package;
 pub mod thumbnail;
 pub mod workshop;
 pub mod workshop_catalog;
 
 // Re-exports
 pub use database::{
-    Collection, LibraryDatabase, LibraryFolder, LibraryStats, SearchOptions, SortBy, Tag,
-    ThumbnailData, WallpaperFilter,
+    Collection, ItemMarks, LibraryDatabase, LibraryFolder, LibraryStats, Playlist, PlaylistEntry,
+    PlaylistFolder, SearchOptions, SortBy, Tag, ThumbnailData, ThumbnailPrune, ThumbnailStripData,
+    WallpaperFilter,
 };
+pub use fuzzy::FuzzyQuery;
+pub use media_info::{probe_media, MediaInfo};
 pub use scanner::{
     AsyncFileWatcher, FileEvent, FileWatcher, FolderScanner, IncrementalScanner, ScanResult,
 };
+pub use scene_package::{decode_tex, read_package_file, PackageEntry, TexContent};
 pub use thumbnail::{
-    get_video_dimensions, get_video_duration, CacheStats, ThumbnailFormat, ThumbnailGenerator,
-    ThumbnailPriority, ThumbnailRequest, ThumbnailResponse, ThumbnailResult, ThumbnailService,
+    CacheStats, ThumbnailFormat, ThumbnailGenerator, ThumbnailPriority, ThumbnailRequest,
+    ThumbnailResponse, ThumbnailResult, ThumbnailService, ThumbnailStrip, STRIP_FRAMES,
 };
 pub use workshop_catalog::{WorkshopCatalogEntry, WorkshopProjectType, WorkshopSyncState};
 
@@ -58,6 +68,6 @@ pub use lwe_core::{SourceType, WallpaperItem, WallpaperMetadata, WallpaperType};
 
 // Workshop exports
 pub use workshop::{
-    get_project_type, is_we_project, SteamLibrary, WeProject, WorkshopScanner,
-    WALLPAPER_ENGINE_APP_ID,
+    get_project_type, is_we_project, SteamLibrary, WeGeneral, WeProject, WeProperty,
+    WePropertyOption, WorkshopScanner, WALLPAPER_ENGINE_APP_ID,
 };
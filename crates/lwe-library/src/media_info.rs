@@ -0,0 +1,872 @@
+//! Media metadata read straight from container headers
+//!
+//! Features:
+//! - MP4/MOV/M4V: `moov` boxes, wherever they sit in the file
+//! - Matroska/WebM: `Info` and `Tracks` of the first segment
+//! - AVI: the `hdrl` list
+//! - Still and animated images: dimensions through the image decoders
+//!
+//! Only headers are read, never frames, so no ffprobe is needed and probing a
+//! large video costs a few small reads. HDR is recognized from the transfer
+//! characteristics the container declares (H.273 code 16 is PQ, 18 is HLG);
+//! HEVC streams that only carry them in the SPS count as SDR.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use lwe_core::hdr::TransferFunction;
+use lwe_core::WallpaperMetadata;
+
+/// Header boxes larger than this are treated as corrupt rather than read
+const MAX_HEADER_BYTES: u64 = 64 * 1024 * 1024;
+
+/// What the container says about a media file's first video stream
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaInfo {
+    pub duration_secs: Option<f64>,
+    pub resolution: Option<(u32, u32)>,
+    /// Named as ffmpeg names decoders ("h264", "hevc", "vp9", "av1")
+    pub codec: Option<String>,
+    /// Average frame rate
+    pub fps: Option<f64>,
+    /// PQ or HLG; `None` for SDR or when the container does not say
+    pub hdr: Option<TransferFunction>,
+}
+
+impl MediaInfo {
+    /// Copy the known fields into `metadata`, keeping what is already there
+    /// for the rest
+    pub fn apply_to(&self, metadata: &mut WallpaperMetadata) {
+        metadata.duration_secs = self.duration_secs.or(metadata.duration_secs);
+        metadata.resolution = self.resolution.or(metadata.resolution);
+        metadata.codec = self.codec.clone().or(metadata.codec.take());
+        metadata.fps = self.fps.or(metadata.fps);
+        metadata.hdr = self.hdr.or(metadata.hdr);
+    }
+}
+
+/// Read the metadata of a video or image; `None` when the file cannot be
+/// opened or its format is not recognized
+pub fn probe_media(path: &Path) -> Option<MediaInfo> {
+    let mut file = File::open(path).ok()?;
+    let mut magic = [0u8; 12];
+    let read = file.read(&mut magic).ok()?;
+    file.seek(SeekFrom::Start(0)).ok()?;
+
+    if read >= 4 && magic[..4] == [0x1A, 0x45, 0xDF, 0xA3] {
+        matroska::probe(file)
+    } else if read == 12 && &magic[..4] == b"RIFF" && &magic[8..12] == b"AVI " {
+        avi::probe(file)
+    } else if read >= 8
+        && matches!(
+            &magic[4..8],
+            b"ftyp" | b"moov" | b"mdat" | b"free" | b"skip" | b"wide"
+        )
+    {
+        mp4::probe(file)
+    } else {
+        let (width, height) = image::image_dimensions(path).ok()?;
+        Some(MediaInfo {
+            resolution: Some((width, height)),
+            ..MediaInfo::default()
+        })
+    }
+}
+
+/// ITU-T H.273 transfer characteristics, as MP4 `colr`, `vpcC` and
+/// Matroska `Colour` store them
+fn hdr_transfer(code: u64) -> Option<TransferFunction> {
+    match code {
+        16 => Some(TransferFunction::Pq),
+        18 => Some(TransferFunction::Hlg),
+        _ => None,
+    }
+}
+
+fn frame_rate(frames: f64, seconds: f64) -> Option<f64> {
+    (frames > 0.0 && seconds > 0.0).then(|| frames / seconds)
+}
+
+fn be_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn be_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn be_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(
+        data.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+fn le_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_exact_vec(reader: &mut impl Read, len: u64) -> Option<Vec<u8>> {
+    if len > MAX_HEADER_BYTES {
+        return None;
+    }
+    let mut data = vec![0; len as usize];
+    reader.read_exact(&mut data).ok()?;
+    Some(data)
+}
+
+mod mp4 {
+    use super::*;
+
+    /// Boxes inside an already read box payload
+    struct Boxes<'a> {
+        data: &'a [u8],
+    }
+
+    impl<'a> Iterator for Boxes<'a> {
+        type Item = ([u8; 4], &'a [u8]);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let kind: [u8; 4] = self.data.get(4..8)?.try_into().ok()?;
+            let (header, size) = match be_u32(self.data, 0)? {
+                0 => (8, self.data.len() as u64),
+                1 => (16, be_u64(self.data, 8)?),
+                size => (8, u64::from(size)),
+            };
+            if size < header || size > self.data.len() as u64 {
+                self.data = &[];
+                return None;
+            }
+            let (current, rest) = self.data.split_at(size as usize);
+            self.data = rest;
+            Some((kind, &current[header as usize..]))
+        }
+    }
+
+    fn children(data: &[u8]) -> Boxes<'_> {
+        Boxes { data }
+    }
+
+    fn child<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+        children(data)
+            .find(|(found, _)| found == kind)
+            .map(|(_, payload)| payload)
+    }
+
+    /// Version 1 boxes widen times to 64 bits; returns (timescale, duration)
+    fn timescale_and_duration(full_box: &[u8]) -> Option<(u32, u64)> {
+        if full_box.first()? == &1 {
+            Some((be_u32(full_box, 20)?, be_u64(full_box, 24)?))
+        } else {
+            Some((be_u32(full_box, 12)?, u64::from(be_u32(full_box, 16)?)))
+        }
+    }
+
+    fn seconds(timescale: u32, duration: u64) -> Option<f64> {
+        (timescale > 0 && duration > 0 && duration != u64::from(u32::MAX) && duration != u64::MAX)
+            .then(|| duration as f64 / f64::from(timescale))
+    }
+
+    pub(super) fn probe(file: File) -> Option<MediaInfo> {
+        let moov = read_top_level(file, b"moov")?;
+        Some(parse_moov(&moov))
+    }
+
+    /// Walk the top-level boxes, seeking over `mdat`, and read the payload of
+    /// the first `kind`
+    fn read_top_level(file: File, kind: &[u8; 4]) -> Option<Vec<u8>> {
+        let file_len = file.metadata().ok()?.len();
+        let mut reader = BufReader::new(file);
+        let mut offset = 0u64;
+
+        while offset.checked_add(8)? <= file_len {
+            reader.seek(SeekFrom::Start(offset)).ok()?;
+            let mut header = [0u8; 16];
+            reader.read_exact(&mut header[..8]).ok()?;
+            let (header_len, size) = match be_u32(&header, 0)? {
+                0 => (8, file_len - offset),
+                1 => {
+                    reader.read_exact(&mut header[8..]).ok()?;
+                    (16, be_u64(&header, 8)?)
+                }
+                size => (8, u64::from(size)),
+            };
+            if size < header_len {
+                return None;
+            }
+            if &header[4..8] == kind {
+                return read_exact_vec(&mut reader, size - header_len);
+            }
+            offset = offset.checked_add(size)?;
+        }
+        None
+    }
+
+    fn parse_moov(moov: &[u8]) -> MediaInfo {
+        let mut info = MediaInfo::default();
+        if let Some((timescale, duration)) = child(moov, b"mvhd").and_then(timescale_and_duration) {
+            info.duration_secs = seconds(timescale, duration);
+            // Fragmented files leave mvhd empty and give the length in mehd
+            if info.duration_secs.is_none() {
+                let fragment_duration = child(moov, b"mvex")
+                    .and_then(|mvex| child(mvex, b"mehd"))
+                    .and_then(|mehd| match *mehd.first()? {
+                        1 => be_u64(mehd, 4),
+                        _ => be_u32(mehd, 4).map(u64::from),
+                    });
+                info.duration_secs =
+                    fragment_duration.and_then(|duration| seconds(timescale, duration));
+            }
+        }
+
+        let video = children(moov)
+            .filter(|(kind, _)| kind == b"trak")
+            .find(|(_, trak)| {
+                child(trak, b"mdia")
+                    .and_then(|mdia| child(mdia, b"hdlr"))
+                    .and_then(|hdlr| hdlr.get(8..12))
+                    == Some(b"vide".as_slice())
+            });
+        if let Some((_, trak)) = video {
+            parse_video_track(trak, &mut info);
+        }
+        info
+    }
+
+    fn parse_video_track(trak: &[u8], info: &mut MediaInfo) {
+        let Some(mdia) = child(trak, b"mdia") else {
+            return;
+        };
+        let track_time = child(mdia, b"mdhd").and_then(timescale_and_duration);
+        if info.duration_secs.is_none() {
+            info.duration_secs =
+                track_time.and_then(|(timescale, duration)| seconds(timescale, duration));
+        }
+
+        let stbl = child(mdia, b"minf").and_then(|minf| child(minf, b"stbl"));
+        if let (Some((timescale, _)), Some(stts)) =
+            (track_time, stbl.and_then(|stbl| child(stbl, b"stts")))
+        {
+            info.fps = average_frame_rate(stts, timescale);
+        }
+
+        if let Some((fourcc, entry)) = stbl
+            .and_then(|stbl| child(stbl, b"stsd"))
+            .and_then(|stsd| children(stsd.get(8..)?).next())
+        {
+            parse_sample_entry(fourcc, entry, info);
+        }
+
+        if info.resolution.is_none() {
+            info.resolution = child(trak, b"tkhd").and_then(|tkhd| {
+                let offset = if tkhd.first()? == &1 { 88 } else { 76 };
+                let width = be_u32(tkhd, offset)? >> 16;
+                let height = be_u32(tkhd, offset + 4)? >> 16;
+                (width > 0 && height > 0).then_some((width, height))
+            });
+        }
+    }
+
+    /// Frames over the summed sample durations of `stts`, so variable frame
+    /// rates average out like ffprobe's `avg_frame_rate`
+    fn average_frame_rate(stts: &[u8], timescale: u32) -> Option<f64> {
+        let count = be_u32(stts, 4)? as usize;
+        let (mut frames, mut ticks) = (0u64, 0u64);
+        for index in 0..count {
+            let offset = 8 + index * 8;
+            let samples = u64::from(be_u32(stts, offset)?);
+            frames += samples;
+            ticks += samples * u64::from(be_u32(stts, offset + 4)?);
+        }
+        if timescale == 0 {
+            return None;
+        }
+        frame_rate(frames as f64, ticks as f64 / f64::from(timescale))
+    }
+
+    /// A VisualSampleEntry: coded size at 24, child boxes after 78 bytes
+    fn parse_sample_entry(fourcc: [u8; 4], entry: &[u8], info: &mut MediaInfo) {
+        let boxes = entry.get(78..).unwrap_or_default();
+        // Encrypted entries name the real format in sinf/frma
+        let fourcc = if &fourcc == b"encv" {
+            child(boxes, b"sinf")
+                .and_then(|sinf| child(sinf, b"frma"))
+                .and_then(|frma| frma.get(..4)?.try_into().ok())
+                .unwrap_or(fourcc)
+        } else {
+            fourcc
+        };
+        info.codec = Some(codec_name(&fourcc));
+
+        if let (Some(width), Some(height)) = (be_u16(entry, 24), be_u16(entry, 26)) {
+            if width > 0 && height > 0 {
+                info.resolution = Some((u32::from(width), u32::from(height)));
+            }
+        }
+
+        let nclx = child(boxes, b"colr")
+            .filter(|colr| colr.get(..4) == Some(b"nclx".as_slice()))
+            .and_then(|colr| be_u16(colr, 6));
+        let vp9 = child(boxes, b"vpcC").and_then(|vpcc| vpcc.get(8).copied());
+        info.hdr = match nclx.map(u64::from).or(vp9.map(u64::from)) {
+            Some(code) => hdr_transfer(code),
+            // Mastering display data without a colour box is written for HDR10
+            None => child(boxes, b"mdcv").map(|_| TransferFunction::Pq),
+        };
+    }
+
+    fn codec_name(fourcc: &[u8; 4]) -> String {
+        match fourcc {
+            b"avc1" | b"avc3" => "h264",
+            b"hvc1" | b"hev1" | b"dvh1" | b"dvhe" => "hevc",
+            b"vp08" => "vp8",
+            b"vp09" => "vp9",
+            b"av01" => "av1",
+            b"mp4v" => "mpeg4",
+            b"jpeg" | b"mjpa" | b"mjpb" => "mjpeg",
+            b"apch" | b"apcn" | b"apcs" | b"apco" | b"ap4h" | b"ap4x" => "prores",
+            b"s263" | b"h263" => "h263",
+            other => return String::from_utf8_lossy(other).trim().to_ascii_lowercase(),
+        }
+        .to_string()
+    }
+}
+
+mod matroska {
+    use super::*;
+
+    const SEGMENT: u64 = 0x1853_8067;
+    const INFO: u64 = 0x1549_A966;
+    const TRACKS: u64 = 0x1654_AE6B;
+    const CLUSTER: u64 = 0x1F43_B675;
+    const TIMECODE_SCALE: u64 = 0x2A_D7B1;
+    const DURATION: u64 = 0x4489;
+    const TRACK_ENTRY: u64 = 0xAE;
+    const TRACK_TYPE: u64 = 0x83;
+    const CODEC_ID: u64 = 0x86;
+    const DEFAULT_DURATION: u64 = 0x23_E383;
+    const VIDEO: u64 = 0xE0;
+    const PIXEL_WIDTH: u64 = 0xB0;
+    const PIXEL_HEIGHT: u64 = 0xBA;
+    const COLOUR: u64 = 0x55B0;
+    const TRANSFER_CHARACTERISTICS: u64 = 0x55BA;
+    const MASTERING_METADATA: u64 = 0x55D0;
+    const VIDEO_TRACK: u64 = 1;
+
+    /// An EBML variable-length integer: (value without the length marker,
+    /// value with it, length in bytes)
+    fn vint(data: &[u8]) -> Option<(u64, u64, usize)> {
+        let first = *data.first()?;
+        if first == 0 {
+            return None;
+        }
+        let len = first.leading_zeros() as usize + 1;
+        let raw = data
+            .get(..len)?
+            .iter()
+            .fold(0u64, |value, byte| value << 8 | u64::from(*byte));
+        let marker = 1u64 << (7 * len);
+        Some((raw & (marker - 1), raw, len))
+    }
+
+    /// Element id and payload size; all size bits set means unknown
+    fn element_header(data: &[u8]) -> Option<(u64, Option<u64>, usize)> {
+        let (_, id, id_len) = vint(data)?;
+        let (size, _, size_len) = vint(data.get(id_len..)?)?;
+        let unknown = size == (1u64 << (7 * size_len)) - 1;
+        Some((id, (!unknown).then_some(size), id_len + size_len))
+    }
+
+    struct Elements<'a> {
+        data: &'a [u8],
+    }
+
+    impl<'a> Iterator for Elements<'a> {
+        type Item = (u64, &'a [u8]);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let (id, size, header) = element_header(self.data)?;
+            let end = size.map_or(self.data.len(), |size| header + size as usize);
+            let Some(payload) = self.data.get(header..end) else {
+                self.data = &[];
+                return None;
+            };
+            self.data = &self.data[end..];
+            Some((id, payload))
+        }
+    }
+
+    fn elements(data: &[u8]) -> Elements<'_> {
+        Elements { data }
+    }
+
+    fn element(data: &[u8], id: u64) -> Option<&[u8]> {
+        elements(data)
+            .find(|(found, _)| *found == id)
+            .map(|(_, payload)| payload)
+    }
+
+    fn uint(data: &[u8]) -> Option<u64> {
+        (data.len() <= 8).then(|| {
+            data.iter()
+                .fold(0u64, |value, byte| value << 8 | u64::from(*byte))
+        })
+    }
+
+    fn float(data: &[u8]) -> Option<f64> {
+        match data.len() {
+            4 => Some(f64::from(f32::from_be_bytes(data.try_into().ok()?))),
+            8 => Some(f64::from_be_bytes(data.try_into().ok()?)),
+            _ => None,
+        }
+    }
+
+    /// Reads the header of the element at the reader's position, leaving the
+    /// reader at its payload
+    fn read_header(reader: &mut impl Read) -> Option<(u64, Option<u64>)> {
+        let mut buf = [0u8; 16];
+        reader.read_exact(&mut buf[..1]).ok()?;
+        let id_len = buf[0].leading_zeros() as usize + 1;
+        if id_len > 4 {
+            return None;
+        }
+        reader.read_exact(&mut buf[1..id_len + 1]).ok()?;
+        let size_len = buf[id_len].leading_zeros() as usize + 1;
+        if size_len > 8 {
+            return None;
+        }
+        reader
+            .read_exact(&mut buf[id_len + 1..id_len + size_len])
+            .ok()?;
+        let (id, size, _) = element_header(&buf)?;
+        Some((id, size))
+    }
+
+    pub(super) fn probe(file: File) -> Option<MediaInfo> {
+        let mut reader = BufReader::new(file);
+        // The EBML header, then the segment
+        loop {
+            match read_header(&mut reader)? {
+                (SEGMENT, _) => break,
+                (_, Some(size)) => {
+                    reader.seek_relative(i64::try_from(size).ok()?).ok()?;
+                }
+                (_, None) => return None,
+            }
+        }
+
+        let (mut info, mut tracks) = (None, None);
+        while info.is_none() || tracks.is_none() {
+            let Some((id, size)) = read_header(&mut reader) else {
+                break;
+            };
+            match (id, size) {
+                (CLUSTER, _) | (_, None) => break,
+                (INFO, Some(size)) => info = Some(read_exact_vec(&mut reader, size)?),
+                (TRACKS, Some(size)) => tracks = Some(read_exact_vec(&mut reader, size)?),
+                (_, Some(size)) => {
+                    reader.seek_relative(i64::try_from(size).ok()?).ok()?;
+                }
+            }
+        }
+
+        Some(parse(info.as_deref(), tracks.as_deref()))
+    }
+
+    fn parse(info: Option<&[u8]>, tracks: Option<&[u8]>) -> MediaInfo {
+        let mut media = MediaInfo::default();
+        if let Some(info) = info {
+            let scale = element(info, TIMECODE_SCALE)
+                .and_then(uint)
+                .unwrap_or(1_000_000);
+            media.duration_secs = element(info, DURATION)
+                .and_then(float)
+                .filter(|duration| *duration > 0.0)
+                .map(|duration| duration * scale as f64 / 1e9);
+        }
+
+        let video = tracks.and_then(|tracks| {
+            elements(tracks)
+                .filter(|(id, _)| *id == TRACK_ENTRY)
+                .map(|(_, entry)| entry)
+                .find(|entry| element(entry, TRACK_TYPE).and_then(uint) == Some(VIDEO_TRACK))
+        });
+        let Some(track) = video else {
+            return media;
+        };
+
+        media.codec = element(track, CODEC_ID)
+            .map(|codec| codec_name(String::from_utf8_lossy(codec).trim_end_matches('\0')));
+        media.fps = element(track, DEFAULT_DURATION)
+            .and_then(uint)
+            .and_then(|nanos| frame_rate(1e9, nanos as f64));
+        if let Some(video) = element(track, VIDEO) {
+            let width = element(video, PIXEL_WIDTH).and_then(uint);
+            let height = element(video, PIXEL_HEIGHT).and_then(uint);
+            if let (Some(width), Some(height)) = (width, height) {
+                media.resolution = u32::try_from(width).ok().zip(u32::try_from(height).ok());
+            }
+            media.hdr = element(video, COLOUR).and_then(|colour| {
+                match element(colour, TRANSFER_CHARACTERISTICS).and_then(uint) {
+                    Some(code) => hdr_transfer(code),
+                    None => element(colour, MASTERING_METADATA).map(|_| TransferFunction::Pq),
+                }
+            });
+        }
+        media
+    }
+
+    fn codec_name(codec_id: &str) -> String {
+        match codec_id {
+            "V_MPEG4/ISO/AVC" => "h264",
+            "V_MPEGH/ISO/HEVC" => "hevc",
+            "V_VP8" => "vp8",
+            "V_VP9" => "vp9",
+            "V_AV1" => "av1",
+            "V_MPEG1" => "mpeg1video",
+            "V_MPEG2" => "mpeg2video",
+            "V_THEORA" => "theora",
+            "V_MJPEG" => "mjpeg",
+            "V_PRORES" => "prores",
+            id if id.starts_with("V_MPEG4/ISO/") => "mpeg4",
+            id => return id.trim_start_matches("V_").to_ascii_lowercase(),
+        }
+        .to_string()
+    }
+}
+
+mod avi {
+    use super::*;
+
+    /// RIFF chunks inside an already read list: (fourcc, data)
+    struct Chunks<'a> {
+        data: &'a [u8],
+    }
+
+    impl<'a> Iterator for Chunks<'a> {
+        type Item = ([u8; 4], &'a [u8]);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let kind: [u8; 4] = self.data.get(..4)?.try_into().ok()?;
+            let size = le_u32(self.data, 4)? as usize;
+            let Some(data) = self.data.get(8..8 + size) else {
+                self.data = &[];
+                return None;
+            };
+            // Chunks are padded to an even length
+            let next = (8 + size + size % 2).min(self.data.len());
+            self.data = &self.data[next..];
+            Some((kind, data))
+        }
+    }
+
+    fn chunks(data: &[u8]) -> Chunks<'_> {
+        Chunks { data }
+    }
+
+    pub(super) fn probe(file: File) -> Option<MediaInfo> {
+        let mut reader = BufReader::new(file);
+        reader.seek(SeekFrom::Start(12)).ok()?;
+        let mut header = [0u8; 12];
+        reader.read_exact(&mut header).ok()?;
+        if &header[..4] != b"LIST" || &header[8..12] != b"hdrl" {
+            return None;
+        }
+        let hdrl = read_exact_vec(
+            &mut reader,
+            u64::from(le_u32(&header, 4)?.saturating_sub(4)),
+        )?;
+        Some(parse_hdrl(&hdrl))
+    }
+
+    fn parse_hdrl(hdrl: &[u8]) -> MediaInfo {
+        let mut info = MediaInfo::default();
+        if let Some((_, avih)) = chunks(hdrl).find(|(kind, _)| kind == b"avih") {
+            let micros_per_frame = le_u32(avih, 0).unwrap_or(0);
+            let frames = le_u32(avih, 16).unwrap_or(0);
+            if micros_per_frame > 0 {
+                info.fps = Some(1e6 / f64::from(micros_per_frame));
+                info.duration_secs =
+                    (frames > 0).then(|| f64::from(frames) * f64::from(micros_per_frame) / 1e6);
+            }
+            if let (Some(width), Some(height)) = (le_u32(avih, 32), le_u32(avih, 36)) {
+                info.resolution = (width > 0 && height > 0).then_some((width, height));
+            }
+        }
+
+        let video = chunks(hdrl)
+            .filter(|(kind, list)| kind == b"LIST" && list.get(..4) == Some(b"strl".as_slice()))
+            .map(|(_, list)| &list[4..])
+            .find(|strl| {
+                chunks(strl).any(|(kind, strh)| {
+                    kind == *b"strh" && strh.get(..4) == Some(b"vids".as_slice())
+                })
+            });
+        let Some(strl) = video else {
+            return info;
+        };
+
+        for (kind, data) in chunks(strl) {
+            match &kind {
+                b"strh" => {
+                    let scale = le_u32(data, 20).unwrap_or(0);
+                    let rate = le_u32(data, 24).unwrap_or(0);
+                    let length = le_u32(data, 32).unwrap_or(0);
+                    if scale > 0 && rate > 0 {
+                        info.fps = Some(f64::from(rate) / f64::from(scale));
+                        if length > 0 {
+                            info.duration_secs =
+                                Some(f64::from(length) * f64::from(scale) / f64::from(rate));
+                        }
+                    }
+                }
+                b"strf" => {
+                    info.codec = data
+                        .get(16..20)
+                        .and_then(|fourcc| fourcc.try_into().ok())
+                        .map(|fourcc| codec_name(&fourcc));
+                }
+                _ => {}
+            }
+        }
+        info
+    }
+
+    fn codec_name(fourcc: &[u8; 4]) -> String {
+        match &fourcc.map(|byte| byte.to_ascii_uppercase()) {
+            b"H264" | b"X264" | b"AVC1" => "h264",
+            b"HEVC" | b"H265" | b"HVC1" => "hevc",
+            b"XVID" | b"DIVX" | b"DX50" | b"FMP4" | b"MP4V" => "mpeg4",
+            b"MJPG" => "mjpeg",
+            b"VP80" => "vp8",
+            b"VP90" => "vp9",
+            b"AV01" => "av1",
+            _ => {
+                return String::from_utf8_lossy(fourcc)
+                    .trim_end_matches(['\0', ' '])
+                    .to_ascii_lowercase()
+            }
+        }
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_media(dir: &TempDir, name: &str, data: &[u8]) -> std::path::PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    fn mp4_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(kind);
+        data.extend_from_slice(payload);
+        data
+    }
+
+    fn full_box(kind: &[u8; 4], fields: &[u32]) -> Vec<u8> {
+        let payload: Vec<u8> = fields
+            .iter()
+            .flat_map(|field| field.to_be_bytes())
+            .collect();
+        mp4_box(kind, &payload)
+    }
+
+    fn ebml(id: u32, payload: &[u8]) -> Vec<u8> {
+        let mut data: Vec<u8> = id
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|byte| *byte == 0)
+            .collect();
+        data.push(0x01);
+        data.extend_from_slice(&(payload.len() as u64).to_be_bytes()[1..]);
+        data.extend_from_slice(payload);
+        data
+    }
+
+    fn riff(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = kind.to_vec();
+        data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        data.extend_from_slice(payload);
+        data
+    }
+
+    #[test]
+    fn test_mp4_with_moov_after_mdat() {
+        let mut entry = vec![0u8; 78];
+        entry[24..26].copy_from_slice(&3840u16.to_be_bytes());
+        entry[26..28].copy_from_slice(&2160u16.to_be_bytes());
+        entry.extend(mp4_box(b"colr", b"nclx\x00\x09\x00\x10\x00\x09\x00"));
+        let stsd = mp4_box(
+            b"stsd",
+            &[
+                &[0, 0, 0, 0, 0, 0, 0, 1],
+                mp4_box(b"hvc1", &entry).as_slice(),
+            ]
+            .concat(),
+        );
+        let stbl = mp4_box(
+            b"stbl",
+            &[stsd, full_box(b"stts", &[0, 1, 375, 1001])].concat(),
+        );
+        let mdia = mp4_box(
+            b"mdia",
+            &[
+                full_box(b"mdhd", &[0, 0, 0, 30000, 375375]),
+                full_box(b"hdlr", &[0, 0, u32::from_be_bytes(*b"vide")]),
+                mp4_box(b"minf", &stbl),
+            ]
+            .concat(),
+        );
+        let moov = mp4_box(
+            b"moov",
+            &[
+                full_box(b"mvhd", &[0, 0, 0, 1000, 12512]),
+                mp4_box(b"trak", &mdia),
+            ]
+            .concat(),
+        );
+        let file = [
+            mp4_box(b"ftyp", b"isom\x00\x00\x02\x00"),
+            mp4_box(b"mdat", &[0; 4096]),
+            moov,
+        ]
+        .concat();
+
+        let dir = TempDir::new().unwrap();
+        let info = probe_media(&write_media(&dir, "ocean.mp4", &file)).unwrap();
+
+        assert_eq!(info.duration_secs, Some(12.512));
+        assert_eq!(info.resolution, Some((3840, 2160)));
+        assert_eq!(info.codec.as_deref(), Some("hevc"));
+        assert!((info.fps.unwrap() - 29.97).abs() < 0.01);
+        assert_eq!(info.hdr, Some(TransferFunction::Pq));
+    }
+
+    #[test]
+    fn test_mp4_with_oversized_box_is_not_probed() {
+        let mut huge = 1u32.to_be_bytes().to_vec();
+        huge.extend_from_slice(b"free");
+        huge.extend_from_slice(&u64::MAX.to_be_bytes());
+        let file = [mp4_box(b"ftyp", b"isom\x00\x00\x02\x00"), huge].concat();
+
+        let dir = TempDir::new().unwrap();
+        assert_eq!(probe_media(&write_media(&dir, "huge.mp4", &file)), None);
+    }
+
+    #[test]
+    fn test_webm_with_unknown_segment_size() {
+        let info = ebml(
+            0x1549_A966,
+            &[
+                ebml(0x2A_D7B1, &1_000_000u32.to_be_bytes()),
+                ebml(0x4489, &8000.0f64.to_be_bytes()),
+            ]
+            .concat(),
+        );
+        let audio = ebml(0xAE, &[ebml(0x83, &[2]), ebml(0x86, b"A_OPUS")].concat());
+        let video = ebml(
+            0xAE,
+            &[
+                ebml(0x83, &[1]),
+                ebml(0x86, b"V_VP9"),
+                ebml(0x23_E383, &41_666_667u32.to_be_bytes()),
+                ebml(
+                    0xE0,
+                    &[
+                        ebml(0xB0, &1920u16.to_be_bytes()),
+                        ebml(0xBA, &1080u16.to_be_bytes()),
+                        ebml(0x55B0, &ebml(0x55BA, &[18])),
+                    ]
+                    .concat(),
+                ),
+            ]
+            .concat(),
+        );
+        let mut file = ebml(0x1A45_DFA3, &ebml(0x4282, b"webm"));
+        file.extend_from_slice(&[
+            0x18, 0x53, 0x80, 0x67, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        ]);
+        file.extend(ebml(0x114D_9B74, &[0; 32]));
+        file.extend(info);
+        file.extend(ebml(0x1654_AE6B, &[audio, video].concat()));
+        file.extend(ebml(0x1F43_B675, &[0; 64]));
+
+        let dir = TempDir::new().unwrap();
+        let info = probe_media(&write_media(&dir, "aurora.webm", &file)).unwrap();
+
+        assert_eq!(info.duration_secs, Some(8.0));
+        assert_eq!(info.resolution, Some((1920, 1080)));
+        assert_eq!(info.codec.as_deref(), Some("vp9"));
+        assert!((info.fps.unwrap() - 24.0).abs() < 0.001);
+        assert_eq!(info.hdr, Some(TransferFunction::Hlg));
+    }
+
+    #[test]
+    fn test_avi_stream_header() {
+        let mut avih = vec![0u8; 56];
+        avih[0..4].copy_from_slice(&40_000u32.to_le_bytes());
+        avih[16..20].copy_from_slice(&250u32.to_le_bytes());
+        avih[32..36].copy_from_slice(&1280u32.to_le_bytes());
+        avih[36..40].copy_from_slice(&720u32.to_le_bytes());
+        let mut strh = vec![0u8; 56];
+        strh[0..4].copy_from_slice(b"vids");
+        strh[20..24].copy_from_slice(&1u32.to_le_bytes());
+        strh[24..28].copy_from_slice(&25u32.to_le_bytes());
+        strh[32..36].copy_from_slice(&250u32.to_le_bytes());
+        let mut strf = vec![0u8; 40];
+        strf[16..20].copy_from_slice(b"XVID");
+        let strl = riff(
+            b"LIST",
+            &[b"strl".to_vec(), riff(b"strh", &strh), riff(b"strf", &strf)].concat(),
+        );
+        let hdrl = riff(
+            b"LIST",
+            &[b"hdrl".to_vec(), riff(b"avih", &avih), strl].concat(),
+        );
+        let file = riff(
+            b"RIFF",
+            &[b"AVI ".to_vec(), hdrl, riff(b"LIST", b"movi")].concat(),
+        );
+
+        let dir = TempDir::new().unwrap();
+        let info = probe_media(&write_media(&dir, "old.avi", &file)).unwrap();
+
+        assert_eq!(info.duration_secs, Some(10.0));
+        assert_eq!(info.resolution, Some((1280, 720)));
+        assert_eq!(info.codec.as_deref(), Some("mpeg4"));
+        assert_eq!(info.fps, Some(25.0));
+        assert_eq!(info.hdr, None);
+    }
+
+    #[test]
+    fn test_image_dimensions_and_unknown_files() {
+        let dir = TempDir::new().unwrap();
+        let image_path = dir.path().join("still.png");
+        image::RgbaImage::new(64, 48).save(&image_path).unwrap();
+        let text_path = write_media(&dir, "notes.mp4", b"not a video");
+
+        let info = probe_media(&image_path).unwrap();
+        let mut metadata = WallpaperMetadata {
+            duration_secs: Some(3.0),
+            ..WallpaperMetadata::default()
+        };
+        info.apply_to(&mut metadata);
+
+        assert_eq!(metadata.resolution, Some((64, 48)));
+        assert_eq!(metadata.duration_secs, Some(3.0));
+        assert_eq!(metadata.codec, None);
+        assert_eq!(probe_media(&text_path), None);
+    }
+}
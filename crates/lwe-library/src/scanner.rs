@@ -5,6 +5,7 @@
 //! - Incremental scanning with change detection
 //! - File system watching with debounced events
 //! - Automatic file type detection
+//! - Duration, resolution, codec, frame rate and HDR read from media headers
 
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -16,12 +17,14 @@ use std::time::{Duration, Instant, SystemTime};
 use anyhow::{Context, Result};
 use notify::RecursiveMode;
 use notify_debouncer_mini::{new_debouncer, DebounceEventResult, DebouncedEventKind, Debouncer};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use rayon::prelude::*;
 use tracing::{debug, info, warn};
 use walkdir::WalkDir;
 
-use lwe_core::{SourceType, WallpaperItem, WallpaperType};
+use lwe_core::{is_animated_image, SourceType, WallpaperItem, WallpaperType};
+
+use crate::media_info::probe_media;
 
 /// File scanner for discovering wallpapers
 #[derive(Debug, Clone)]
@@ -157,15 +160,7 @@ impl FolderScanner {
     pub fn process_file(&self, path: &Path) -> Option<WallpaperItem> {
         let extension = path.extension()?.to_string_lossy().to_lowercase();
 
-        let wallpaper_type = if self.video_extensions.contains(&extension) {
-            WallpaperType::Video
-        } else if extension == "gif" {
-            WallpaperType::Gif
-        } else if self.image_extensions.contains(&extension) {
-            WallpaperType::Image
-        } else {
-            return None;
-        };
+        let wallpaper_type = self.classify(path, &extension)?;
 
         let name = path.file_stem()?.to_string_lossy().to_string();
 
@@ -175,8 +170,11 @@ impl FolderScanner {
         let mut item =
             WallpaperItem::new(path.to_owned(), name, SourceType::LocalFile, wallpaper_type);
 
-        // Update metadata with file size
+        // Update metadata with file size, and what the headers say about the media
         item.metadata.file_size = file_size;
+        if let Some(info) = probe_media(path) {
+            info.apply_to(&mut item.metadata);
+        }
 
         Some(item)
     }
@@ -194,12 +192,16 @@ impl FolderScanner {
     /// Get the wallpaper type for a file
     pub fn get_wallpaper_type(&self, path: &Path) -> Option<WallpaperType> {
         let ext = path.extension()?.to_string_lossy().to_lowercase();
+        self.classify(path, &ext)
+    }
 
-        if self.video_extensions.contains(&ext) {
+    /// PNG and WebP files are only animated when their header says so
+    fn classify(&self, path: &Path, ext: &str) -> Option<WallpaperType> {
+        if self.video_extensions.contains(ext) {
             Some(WallpaperType::Video)
-        } else if ext == "gif" {
-            Some(WallpaperType::Gif)
-        } else if self.image_extensions.contains(&ext) {
+        } else if ext == "gif" || (matches!(ext, "png" | "webp") && is_animated_image(path)) {
+            Some(WallpaperType::AnimatedImage)
+        } else if self.image_extensions.contains(ext) {
             Some(WallpaperType::Image)
         } else {
             None
@@ -448,6 +450,8 @@ pub enum FileEvent {
     Modified(PathBuf),
     /// File deleted
     Deleted(PathBuf),
+    /// File moved or renamed within the watched paths
+    Renamed { from: PathBuf, to: PathBuf },
 }
 
 /// File system watcher for real-time change detection
@@ -532,46 +536,144 @@ impl FileWatcher {
     /// Process event and return WallpaperItem for created/modified files
     pub fn process_event(&self, event: &FileEvent) -> Option<WallpaperItem> {
         match event {
-            FileEvent::Created(path) | FileEvent::Modified(path) => self.scanner.process_file(path),
+            FileEvent::Created(path)
+            | FileEvent::Modified(path)
+            | FileEvent::Renamed { to: path, .. } => self.scanner.process_file(path),
             FileEvent::Deleted(_) => None,
         }
     }
 }
 
-/// Async file watcher using tokio channels
+/// Size and modification time of a watched file; a rename keeps both, which
+/// is how a deletion and a creation in one batch are paired up
+type FileStamp = (u64, Option<SystemTime>);
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    metadata
+        .is_file()
+        .then(|| (metadata.len(), metadata.modified().ok()))
+}
+
+/// Async file watcher using tokio channels. Changes are debounced into
+/// batches and turned into created, modified, deleted and renamed events.
 pub struct AsyncFileWatcher {
     scanner: Arc<FolderScanner>,
     watched_paths: Arc<RwLock<Vec<(PathBuf, bool)>>>,
+    /// Wallpaper files seen under the watched paths
+    known_files: Arc<Mutex<HashMap<PathBuf, FileStamp>>>,
+    debouncer: Mutex<Debouncer<notify::RecommendedWatcher>>,
     event_tx: tokio::sync::mpsc::Sender<FileEvent>,
-    event_rx: tokio::sync::mpsc::Receiver<FileEvent>,
+    event_rx: tokio::sync::Mutex<tokio::sync::mpsc::Receiver<FileEvent>>,
 }
 
 impl AsyncFileWatcher {
     /// Create a new async file watcher
-    pub fn new(buffer_size: usize) -> Self {
+    pub fn new(buffer_size: usize, debounce_duration: Duration) -> Result<Self> {
         let (event_tx, event_rx) = tokio::sync::mpsc::channel(buffer_size);
+        let scanner = Arc::new(FolderScanner::new());
+        let known_files = Arc::new(Mutex::new(HashMap::new()));
 
-        Self {
-            scanner: Arc::new(FolderScanner::new()),
+        let callback_scanner = scanner.clone();
+        let callback_known = known_files.clone();
+        let callback_tx = event_tx.clone();
+        let debouncer = new_debouncer(debounce_duration, move |res: DebounceEventResult| {
+            let events = match res {
+                Ok(events) => events,
+                Err(error) => {
+                    warn!("File watcher error: {}", error);
+                    return;
+                }
+            };
+            let changes = changes_in_batch(
+                &callback_scanner,
+                &mut callback_known.lock(),
+                events.into_iter().map(|event| event.path),
+            );
+            for change in changes {
+                // Runs on the debouncer's thread, outside the runtime
+                if callback_tx.blocking_send(change).is_err() {
+                    return;
+                }
+            }
+        })
+        .context("Failed to create file watcher")?;
+
+        Ok(Self {
+            scanner,
             watched_paths: Arc::new(RwLock::new(Vec::new())),
+            known_files,
+            debouncer: Mutex::new(debouncer),
             event_tx,
-            event_rx,
-        }
+            event_rx: tokio::sync::Mutex::new(event_rx),
+        })
     }
 
-    /// Add path to watch
-    pub fn add_path(&self, path: PathBuf, recursive: bool) {
+    /// Add path to watch; files already there are not reported
+    pub fn add_path(&self, path: PathBuf, recursive: bool) -> Result<()> {
+        if self.is_watching(&path) {
+            return Ok(());
+        }
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        self.debouncer
+            .lock()
+            .watcher()
+            .watch(&path, mode)
+            .with_context(|| format!("Failed to watch path: {}", path.display()))?;
+
+        let walker = if recursive {
+            WalkDir::new(&path)
+        } else {
+            WalkDir::new(&path).max_depth(1)
+        };
+        let mut known_files = self.known_files.lock();
+        for entry in walker.into_iter().filter_map(|e| e.ok()) {
+            let file_path = entry.path();
+            if !self.scanner.is_wallpaper_file(file_path) {
+                continue;
+            }
+            if let Some(stamp) = file_stamp(file_path) {
+                known_files.insert(file_path.to_owned(), stamp);
+            }
+        }
+        drop(known_files);
+
+        debug!("Watching {} for changes", path.display());
         self.watched_paths.write().push((path, recursive));
+        Ok(())
     }
 
     /// Remove path from watch
     pub fn remove_path(&self, path: &Path) {
+        if !self.is_watching(path) {
+            return;
+        }
+        if let Err(error) = self.debouncer.lock().watcher().unwatch(path) {
+            warn!("Failed to stop watching {}: {}", path.display(), error);
+        }
         self.watched_paths.write().retain(|(p, _)| p != path);
+        self.known_files
+            .lock()
+            .retain(|known, _| !known.starts_with(path));
+    }
+
+    /// Whether `path` was added with [`Self::add_path`]
+    pub fn is_watching(&self, path: &Path) -> bool {
+        self.watched_paths.read().iter().any(|(p, _)| p == path)
     }
 
     /// Receive next event (async)
-    pub async fn recv(&mut self) -> Option<FileEvent> {
-        self.event_rx.recv().await
+    pub async fn recv(&self) -> Option<FileEvent> {
+        self.event_rx.lock().await.recv().await
+    }
+
+    /// Take an event that is already waiting, such as the rest of a batch
+    pub fn try_recv(&self) -> Option<FileEvent> {
+        self.event_rx.try_lock().ok()?.try_recv().ok()
     }
 
     /// Get event sender for external use
@@ -585,6 +687,79 @@ impl AsyncFileWatcher {
     }
 }
 
+/// Turn the paths of one debounced batch into events. The debouncer only
+/// says that a path changed, so `known_files` tells a new file from an
+/// edited one, and a vanished file whose size and modification time turn up
+/// under a new path is reported as renamed.
+fn changes_in_batch(
+    scanner: &FolderScanner,
+    known_files: &mut HashMap<PathBuf, FileStamp>,
+    paths: impl IntoIterator<Item = PathBuf>,
+) -> Vec<FileEvent> {
+    let mut present = Vec::new();
+    let mut deleted = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            // A folder moved or copied in brings its files along
+            present.extend(
+                WalkDir::new(&path)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .map(|entry| entry.into_path()),
+            );
+        } else if path.exists() {
+            present.push(path);
+        } else {
+            // The path may have been a file or a whole folder
+            let gone: Vec<PathBuf> = known_files
+                .keys()
+                .filter(|known| known.starts_with(&path))
+                .cloned()
+                .collect();
+            for known in gone {
+                if let Some(stamp) = known_files.remove(&known) {
+                    deleted.push((known, stamp));
+                }
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut created = Vec::new();
+    let mut modified = Vec::new();
+    for path in present {
+        if !seen.insert(path.clone()) || !scanner.is_wallpaper_file(&path) {
+            continue;
+        }
+        let Some(stamp) = file_stamp(&path) else {
+            continue;
+        };
+        match known_files.insert(path.clone(), stamp) {
+            None => created.push((path, stamp)),
+            Some(known) if known != stamp => modified.push(path),
+            Some(_) => {}
+        }
+    }
+
+    let mut events = Vec::new();
+    for (to, stamp) in created {
+        match deleted.iter().position(|(_, gone)| *gone == stamp) {
+            Some(index) => {
+                let (from, _) = deleted.remove(index);
+                events.push(FileEvent::Renamed { from, to });
+            }
+            None => events.push(FileEvent::Created(to)),
+        }
+    }
+    events.extend(modified.into_iter().map(FileEvent::Modified));
+    events.extend(
+        deleted
+            .into_iter()
+            .map(|(path, _)| FileEvent::Deleted(path)),
+    );
+    events
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -659,11 +834,32 @@ mod tests {
         );
         assert_eq!(
             scanner.get_wallpaper_type(Path::new("test.gif")),
-            Some(WallpaperType::Gif)
+            Some(WallpaperType::AnimatedImage)
         );
         assert_eq!(scanner.get_wallpaper_type(Path::new("test.txt")), None);
     }
 
+    #[test]
+    fn test_animated_webp_is_classified_by_header() {
+        let temp_dir = TempDir::new().unwrap();
+        let animated = temp_dir.path().join("loop.webp");
+        let mut header = b"RIFF\0\0\0\0WEBPVP8X\x0a\0\0\0".to_vec();
+        header.push(0x02);
+        fs::write(&animated, &header).unwrap();
+        let still = temp_dir.path().join("still.webp");
+        fs::write(&still, b"RIFF\0\0\0\0WEBPVP8 \0\0\0\0").unwrap();
+
+        let scanner = FolderScanner::new();
+        assert_eq!(
+            scanner.process_file(&animated).unwrap().wallpaper_type,
+            WallpaperType::AnimatedImage
+        );
+        assert_eq!(
+            scanner.process_file(&still).unwrap().wallpaper_type,
+            WallpaperType::Image
+        );
+    }
+
     #[test]
     fn test_incremental_scanner() {
         let temp_dir = TempDir::new().unwrap();
@@ -728,4 +924,52 @@ mod tests {
         assert_eq!(result1.files_scanned, 30);
         assert_eq!(result1.duration_ms, 300);
     }
+
+    #[test]
+    fn test_batch_changes_pair_renames() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("beach.mp4"), b"beach").unwrap();
+        fs::write(dir.join("forest.png"), b"forest!").unwrap();
+        fs::write(dir.join("city.mkv"), b"city lights").unwrap();
+
+        let scanner = FolderScanner::new();
+        let mut known_files: HashMap<PathBuf, FileStamp> = ["beach.mp4", "forest.png", "city.mkv"]
+            .into_iter()
+            .map(|name| (dir.join(name), file_stamp(&dir.join(name)).unwrap()))
+            .collect();
+
+        fs::rename(dir.join("beach.mp4"), dir.join("coast.mp4")).unwrap();
+        fs::remove_file(dir.join("forest.png")).unwrap();
+        fs::write(dir.join("city.mkv"), b"city lights at night").unwrap();
+        fs::write(dir.join("notes.txt"), b"not a wallpaper").unwrap();
+        fs::create_dir(dir.join("rainy")).unwrap();
+        fs::write(dir.join("rainy").join("rain.webm"), b"rain").unwrap();
+
+        let batch = vec![
+            dir.join("beach.mp4"),
+            dir.join("coast.mp4"),
+            dir.join("forest.png"),
+            dir.join("city.mkv"),
+            dir.join("notes.txt"),
+            dir.join("rainy"),
+        ];
+        let events = changes_in_batch(&scanner, &mut known_files, batch.clone());
+        assert_eq!(
+            events,
+            vec![
+                FileEvent::Renamed {
+                    from: dir.join("beach.mp4"),
+                    to: dir.join("coast.mp4"),
+                },
+                FileEvent::Created(dir.join("rainy").join("rain.webm")),
+                FileEvent::Modified(dir.join("city.mkv")),
+                FileEvent::Deleted(dir.join("forest.png")),
+            ]
+        );
+        assert_eq!(known_files.len(), 3);
+
+        // The same batch again changes nothing
+        assert!(changes_in_batch(&scanner, &mut known_files, batch).is_empty());
+    }
 }
@@ -0,0 +1,507 @@
+//! Wallpaper Engine scene packages and textures
+//!
+//! Scene projects ship their `scene.json`, models, materials and textures
+//! inside a `scene.pkg` container. Textures are `.tex` files: a small header
+//! followed by raw RGBA, DXT-compressed blocks (optionally LZ4-compressed) or
+//! an embedded PNG/JPEG. This module reads both so they can be unpacked for
+//! inspection; nothing here renders scenes.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use image::{imageops, RgbaImage};
+
+/// One file stored in a scene package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageEntry {
+    /// Path inside the package, e.g. `materials/sky.tex`
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// What a `.tex` file holds once decoded
+#[derive(Debug, Clone, PartialEq)]
+pub enum TexContent {
+    /// The first frame, cropped to the image size
+    Image(RgbaImage),
+    /// Video textures embed a whole MP4 file
+    Mp4(Vec<u8>),
+}
+
+/// Read every entry of the package at `path`
+pub fn read_package_file(path: &Path) -> Result<Vec<PackageEntry>> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    read_package(&bytes).with_context(|| format!("{} is not a scene package", path.display()))
+}
+
+/// Split a `PKGV` container into its entries
+pub fn read_package(bytes: &[u8]) -> Result<Vec<PackageEntry>> {
+    let mut reader = Reader::new(bytes);
+    let magic = reader.sized_string()?;
+    if !magic.starts_with("PKGV") {
+        bail!("Unknown package header {magic:?}");
+    }
+
+    let count = reader.u32()?;
+    let mut index = Vec::new();
+    for _ in 0..count {
+        let name = reader.sized_string()?;
+        let offset = reader.u32()? as usize;
+        let length = reader.u32()? as usize;
+        index.push((name, offset, length));
+    }
+
+    let data = &bytes[reader.position..];
+    index
+        .into_iter()
+        .map(|(name, offset, length)| {
+            let data = offset
+                .checked_add(length)
+                .and_then(|end| data.get(offset..end))
+                .with_context(|| format!("Entry {name} runs past the end of the package"))?;
+            Ok(PackageEntry {
+                name,
+                data: data.to_vec(),
+            })
+        })
+        .collect()
+}
+
+/// Texel formats a `.tex` header can declare
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TexFormat {
+    Rgba8888,
+    Dxt5,
+    Dxt3,
+    Dxt1,
+    Rg88,
+    R8,
+}
+
+impl TexFormat {
+    fn from_id(id: u32) -> Result<Self> {
+        Ok(match id {
+            0 => Self::Rgba8888,
+            4 => Self::Dxt5,
+            6 => Self::Dxt3,
+            7 => Self::Dxt1,
+            8 => Self::Rg88,
+            9 => Self::R8,
+            _ => bail!("Unsupported texture format {id}"),
+        })
+    }
+}
+
+/// Decode the first frame of a `.tex` file
+pub fn decode_tex(bytes: &[u8]) -> Result<TexContent> {
+    let mut reader = Reader::new(bytes);
+    let texv = reader.nul_string()?;
+    let texi = reader.nul_string()?;
+    if !texv.starts_with("TEXV") || !texi.starts_with("TEXI") {
+        bail!("Unknown texture header {texv:?}");
+    }
+    let format = reader.u32()?;
+    let _flags = reader.u32()?;
+    let _texture_width = reader.u32()?;
+    let _texture_height = reader.u32()?;
+    let image_width = reader.u32()?;
+    let image_height = reader.u32()?;
+    let _unknown = reader.u32()?;
+
+    let container = reader.nul_string()?;
+    let version = match container.as_str() {
+        "TEXB0001" => 1,
+        "TEXB0002" => 2,
+        "TEXB0003" => 3,
+        "TEXB0004" => 4,
+        _ => bail!("Unknown texture container {container:?}"),
+    };
+    let image_count = reader.u32()?;
+    // FreeImage format of embedded files; -1 means raw texels
+    let mut encoded = false;
+    let mut mp4 = false;
+    if version >= 3 {
+        encoded = reader.i32()? != -1;
+    }
+    if version == 4 {
+        mp4 = reader.u32()? == 1;
+    }
+    if image_count == 0 || reader.u32()? == 0 {
+        bail!("Texture has no images");
+    }
+
+    if version == 4 {
+        // Extra parameters and a JSON condition precede every v4 mipmap
+        let _ = reader.u32()?;
+        let _ = reader.u32()?;
+        let _condition = reader.nul_string()?;
+        let _ = reader.u32()?;
+    }
+    let width = reader.u32()?;
+    let height = reader.u32()?;
+    let data = if version == 1 {
+        let length = reader.u32()? as usize;
+        reader.bytes(length)?.to_vec()
+    } else {
+        let lz4 = reader.u32()? == 1;
+        let decompressed_length = reader.u32()? as usize;
+        let length = reader.u32()? as usize;
+        let data = reader.bytes(length)?;
+        if lz4 {
+            lz4_decompress(data, decompressed_length)?
+        } else {
+            data.to_vec()
+        }
+    };
+
+    if mp4 {
+        return Ok(TexContent::Mp4(data));
+    }
+    let image = if encoded {
+        image::load_from_memory(&data)
+            .context("Failed to decode the embedded image")?
+            .to_rgba8()
+    } else {
+        decode_texels(TexFormat::from_id(format)?, width, height, &data)?
+    };
+
+    let (crop_width, crop_height) = (
+        image_width.min(image.width()),
+        image_height.min(image.height()),
+    );
+    if crop_width == 0 || crop_height == 0 || image.dimensions() == (crop_width, crop_height) {
+        return Ok(TexContent::Image(image));
+    }
+    Ok(TexContent::Image(
+        imageops::crop_imm(&image, 0, 0, crop_width, crop_height).to_image(),
+    ))
+}
+
+fn decode_texels(format: TexFormat, width: u32, height: u32, data: &[u8]) -> Result<RgbaImage> {
+    let pixels = width as usize * height as usize;
+    let blocks = width.div_ceil(4) as usize * height.div_ceil(4) as usize;
+    let needed = match format {
+        TexFormat::Rgba8888 => pixels * 4,
+        TexFormat::Rg88 => pixels * 2,
+        TexFormat::R8 => pixels,
+        TexFormat::Dxt1 => blocks * 8,
+        TexFormat::Dxt3 | TexFormat::Dxt5 => blocks * 16,
+    };
+    if data.len() < needed {
+        bail!(
+            "Texture data is {} bytes, {format:?} at {width}x{height} needs {needed}",
+            data.len()
+        );
+    }
+
+    let rgba = match format {
+        TexFormat::Rgba8888 => data[..needed].to_vec(),
+        TexFormat::Rg88 => data[..needed]
+            .chunks_exact(2)
+            .flat_map(|texel| [texel[0], texel[0], texel[0], texel[1]])
+            .collect(),
+        TexFormat::R8 => data[..needed]
+            .iter()
+            .flat_map(|&value| [value, value, value, 255])
+            .collect(),
+        TexFormat::Dxt1 | TexFormat::Dxt3 | TexFormat::Dxt5 => {
+            decode_dxt(format, width, height, data)
+        }
+    };
+    RgbaImage::from_raw(width, height, rgba).context("Texture size does not match its data")
+}
+
+/// Decode BC1 (DXT1), BC2 (DXT3) or BC3 (DXT5) blocks into RGBA
+fn decode_dxt(format: TexFormat, width: u32, height: u32, data: &[u8]) -> Vec<u8> {
+    let (width, height) = (width as usize, height as usize);
+    let blocks_wide = width.div_ceil(4);
+    let block_size = if format == TexFormat::Dxt1 { 8 } else { 16 };
+    let mut rgba = vec![0; width * height * 4];
+
+    for (index, block) in data
+        .chunks_exact(block_size)
+        .take(blocks_wide * height.div_ceil(4))
+        .enumerate()
+    {
+        let (block_x, block_y) = (index % blocks_wide * 4, index / blocks_wide * 4);
+        let (alpha, color) = block.split_at(block_size - 8);
+        let colors = dxt_palette(color, format == TexFormat::Dxt1);
+        let color_indices = u32::from_le_bytes([color[4], color[5], color[6], color[7]]);
+        let alphas = match format {
+            TexFormat::Dxt3 => dxt3_alpha(alpha),
+            TexFormat::Dxt5 => dxt5_alpha(alpha),
+            _ => [255; 16],
+        };
+
+        for (texel, &alpha) in alphas.iter().enumerate() {
+            let (x, y) = (block_x + texel % 4, block_y + texel / 4);
+            if x >= width || y >= height {
+                continue;
+            }
+            let mut pixel = colors[(color_indices >> (texel * 2) & 0b11) as usize];
+            pixel[3] = pixel[3].min(alpha);
+            let offset = (y * width + x) * 4;
+            rgba[offset..offset + 4].copy_from_slice(&pixel);
+        }
+    }
+    rgba
+}
+
+/// The four colors of a BC1 color block; DXT1 blocks with `c0 <= c1` have
+/// a transparent fourth color
+fn dxt_palette(block: &[u8], dxt1: bool) -> [[u8; 4]; 4] {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let (a, b) = (rgb565(c0), rgb565(c1));
+    let mix = |weight_a: u16, weight_b: u16| {
+        let total = weight_a + weight_b;
+        let channel = |i: usize| ((a[i] as u16 * weight_a + b[i] as u16 * weight_b) / total) as u8;
+        [channel(0), channel(1), channel(2), 255]
+    };
+
+    if c0 > c1 || !dxt1 {
+        [a, b, mix(2, 1), mix(1, 2)]
+    } else {
+        [a, b, mix(1, 1), [0, 0, 0, 0]]
+    }
+}
+
+fn rgb565(color: u16) -> [u8; 4] {
+    let r = (color >> 11 & 0x1f) as u8;
+    let g = (color >> 5 & 0x3f) as u8;
+    let b = (color & 0x1f) as u8;
+    [r << 3 | r >> 2, g << 2 | g >> 4, b << 3 | b >> 2, 255]
+}
+
+/// Explicit 4-bit alpha of a DXT3 block
+fn dxt3_alpha(block: &[u8]) -> [u8; 16] {
+    let mut alphas = [0; 16];
+    for (texel, alpha) in alphas.iter_mut().enumerate() {
+        *alpha = (block[texel / 2] >> (texel % 2 * 4) & 0x0f) * 17;
+    }
+    alphas
+}
+
+/// Interpolated alpha of a DXT5 block
+fn dxt5_alpha(block: &[u8]) -> [u8; 16] {
+    let (a0, a1) = (block[0] as u16, block[1] as u16);
+    // Six interpolated values, or four plus fully transparent and opaque
+    let steps = if a0 > a1 { 7 } else { 5 };
+    let mut palette = [a0 as u8, a1 as u8, 0, 0, 0, 0, 0, 255];
+    for (step, alpha) in palette[2..=steps].iter_mut().enumerate() {
+        let step = step as u16 + 1;
+        *alpha = ((a0 * (steps as u16 - step) + a1 * step) / steps as u16) as u8;
+    }
+
+    let bits = block[2..8]
+        .iter()
+        .rev()
+        .fold(0u64, |bits, &byte| bits << 8 | byte as u64);
+    let mut alphas = [0; 16];
+    for (texel, alpha) in alphas.iter_mut().enumerate() {
+        *alpha = palette[(bits >> (texel * 3) & 0b111) as usize];
+    }
+    alphas
+}
+
+/// Most an LZ4 block can expand by: a 255 run-length byte per 255 bytes out
+const LZ4_MAX_RATIO: usize = 255;
+
+/// Decompress one LZ4 block of `expected` bytes
+///
+/// `expected` comes from the file, so it only reserves what the block could
+/// hold and a block that grows past it fails at once.
+fn lz4_decompress(input: &[u8], expected: usize) -> Result<Vec<u8>> {
+    let truncated = || anyhow::anyhow!("LZ4 data is truncated");
+    let overrun = || anyhow::anyhow!("LZ4 data decompresses past the expected {expected} bytes");
+    let mut output = Vec::with_capacity(expected.min(input.len().saturating_mul(LZ4_MAX_RATIO)));
+    let mut position = 0;
+    let read_length = |position: &mut usize, mut length: usize| -> Result<usize> {
+        if length == 15 {
+            loop {
+                let byte = *input.get(*position).ok_or_else(truncated)?;
+                *position += 1;
+                length += byte as usize;
+                if byte != 255 {
+                    break;
+                }
+            }
+        }
+        Ok(length)
+    };
+
+    while position < input.len() {
+        let token = input[position];
+        position += 1;
+
+        let literals = read_length(&mut position, (token >> 4) as usize)?;
+        let end = position + literals;
+        if output.len() + literals > expected {
+            return Err(overrun());
+        }
+        output.extend_from_slice(input.get(position..end).ok_or_else(truncated)?);
+        position = end;
+        // The last sequence ends after its literals
+        if position == input.len() {
+            break;
+        }
+
+        let offset = input
+            .get(position..position + 2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+            .ok_or_else(truncated)?;
+        position += 2;
+        if offset == 0 || offset > output.len() {
+            bail!("LZ4 match offset {offset} is out of range");
+        }
+        let length = read_length(&mut position, (token & 0x0f) as usize)? + 4;
+        if output.len() + length > expected {
+            return Err(overrun());
+        }
+        // Matches may overlap the bytes they produce
+        for _ in 0..length {
+            output.push(output[output.len() - offset]);
+        }
+    }
+
+    if output.len() != expected {
+        bail!(
+            "LZ4 data decompressed to {} bytes, expected {expected}",
+            output.len()
+        );
+    }
+    Ok(output)
+}
+
+/// Little-endian reader over package and texture bytes
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn bytes(&mut self, length: usize) -> Result<&'a [u8]> {
+        let end = self.position.saturating_add(length);
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .context("Unexpected end of data")?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        Ok(self.u32()? as i32)
+    }
+
+    /// A string prefixed with its length
+    fn sized_string(&mut self) -> Result<String> {
+        let length = self.u32()? as usize;
+        Ok(String::from_utf8_lossy(self.bytes(length)?).into_owned())
+    }
+
+    /// A NUL-terminated string
+    fn nul_string(&mut self) -> Result<String> {
+        let rest = &self.bytes[self.position..];
+        let length = rest
+            .iter()
+            .position(|&byte| byte == 0)
+            .context("Unterminated string")?;
+        self.position += length + 1;
+        Ok(String::from_utf8_lossy(&rest[..length]).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sized(text: &str) -> Vec<u8> {
+        let mut bytes = (text.len() as u32).to_le_bytes().to_vec();
+        bytes.extend_from_slice(text.as_bytes());
+        bytes
+    }
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn test_package_entries_are_read_at_their_offsets() {
+        let mut package = sized("PKGV0019");
+        package.extend(words(&[2]));
+        package.extend(sized("scene.json"));
+        package.extend(words(&[0, 2]));
+        package.extend(sized("materials/sky.tex"));
+        package.extend(words(&[2, 3]));
+        package.extend_from_slice(b"{}abc");
+
+        let entries = read_package(&package).unwrap();
+        assert_eq!(entries[0].name, "scene.json");
+        assert_eq!(entries[0].data, b"{}");
+        assert_eq!(entries[1].name, "materials/sky.tex");
+        assert_eq!(entries[1].data, b"abc");
+
+        package.truncate(package.len() - 1);
+        assert!(read_package(&package).is_err());
+        assert!(read_package(b"\x04\0\0\0TEXV").is_err());
+    }
+
+    #[test]
+    fn test_lz4_blocks_expand_literals_and_overlapping_matches() {
+        // "ab" as literals, then a 6-byte match at offset 2, then "c"
+        let block = [0x22, b'a', b'b', 0x02, 0x00, 0x10, b'c'];
+        assert_eq!(lz4_decompress(&block, 9).unwrap(), b"ababababc".to_vec());
+        assert!(lz4_decompress(&block, 10).is_err());
+        // Stops as soon as the output outgrows what the header promised
+        assert!(lz4_decompress(&block, 8)
+            .unwrap_err()
+            .to_string()
+            .contains("past the expected 8 bytes"));
+        // A huge claimed size is not reserved up front
+        assert!(lz4_decompress(&block, usize::MAX).is_err());
+        assert!(lz4_decompress(&[0x20, b'a', b'b', 0x09, 0x00], 6).is_err());
+    }
+
+    #[test]
+    fn test_tex_decodes_and_crops_the_first_mipmap() {
+        let mut tex = b"TEXV0005\0TEXI0001\0".to_vec();
+        // RGBA8888, 2x2 texture holding a 1x2 image
+        tex.extend(words(&[0, 0, 2, 2, 1, 2, 0]));
+        tex.extend_from_slice(b"TEXB0003\0");
+        tex.extend(words(&[1, u32::MAX, 1, 2, 2, 0, 16, 16]));
+        tex.extend((0..16).collect::<Vec<u8>>());
+
+        let TexContent::Image(image) = decode_tex(&tex).unwrap() else {
+            panic!("expected an image");
+        };
+        assert_eq!(image.dimensions(), (1, 2));
+        assert_eq!(image.get_pixel(0, 1).0, [8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn test_dxt1_blocks_use_the_transparent_color_when_c0_is_not_greater() {
+        // Pure blue and pure red, every texel picking the fourth color
+        let block = [0x1f, 0x00, 0x00, 0xf8, 0xff, 0xff, 0xff, 0xff];
+        let rgba = decode_dxt(TexFormat::Dxt1, 4, 4, &block);
+        assert_eq!(&rgba[..4], &[0, 0, 0, 0]);
+
+        let block = [0x00, 0xf8, 0x1f, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let rgba = decode_dxt(TexFormat::Dxt1, 2, 2, &block);
+        assert_eq!(rgba.len(), 16);
+        assert_eq!(&rgba[..4], &[255, 0, 0, 255]);
+    }
+}
@@ -3,28 +3,39 @@
 //! Features:
 //! - WebP output format (smaller, better quality)
 //! - Persistent cache directory (~/.cache/wayvid/thumbnails/)
-//! - Background generation with async API
+//! - Background generation on worker threads, visible items first
 //! - GIF animation preview (first frame)
 //! - Video frame extraction with ffmpeg
+//! - Multi-frame sprite strips for hover scrubbing
 
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, SystemTime};
 
 use anyhow::{Context, Result};
-use image::{DynamicImage, GenericImageView, ImageFormat};
-use parking_lot::RwLock;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageFormat, RgbaImage};
+use parking_lot::{Condvar, Mutex};
 use sha2::{Digest, Sha256};
-use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
 
+use crate::database::{LibraryDatabase, ThumbnailData};
+use crate::media_info::probe_media;
+
 /// Default thumbnail width
 pub const THUMBNAIL_WIDTH: u32 = 320;
 /// Default thumbnail height
 pub const THUMBNAIL_HEIGHT: u32 = 180;
+/// Generated thumbnails written to the library database in one transaction
+pub const THUMBNAIL_BATCH_SIZE: usize = 32;
 /// WebP quality (0-100, higher = better)
 pub const WEBP_QUALITY: u8 = 80;
+/// Default number of frames in a video sprite strip
+pub const STRIP_FRAMES: u32 = 10;
 
 /// Output format for thumbnails
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -137,6 +148,9 @@ impl ThumbnailGenerator {
     }
 
     /// Get cached thumbnail if exists
+    ///
+    /// A hit refreshes the file's modification time, which
+    /// [`prune_cache`](Self::prune_cache) reads as its last use.
     pub fn get_cached(&self, source_path: &Path) -> Option<ThumbnailResult> {
         let cache_path = self.cache_path(source_path);
         if !cache_path.exists() {
@@ -146,6 +160,13 @@ impl ThumbnailGenerator {
         let data = std::fs::read(&cache_path).ok()?;
         let img = image::load_from_memory(&data).ok()?;
         let (width, height) = img.dimensions();
+        if let Err(e) = std::fs::File::options()
+            .write(true)
+            .open(&cache_path)
+            .and_then(|file| file.set_modified(SystemTime::now()))
+        {
+            debug!("Failed to mark {} as used: {}", cache_path.display(), e);
+        }
 
         Some(ThumbnailResult {
             data,
@@ -199,6 +220,12 @@ impl ThumbnailGenerator {
         Ok(result)
     }
 
+    /// Thumbnail of an image that changes between calls, such as a snapshot
+    /// of a playing wallpaper; the cache is neither read nor written
+    pub fn generate_fresh(&self, path: &Path) -> Result<ThumbnailResult> {
+        self.generate_image_thumbnail(path)
+    }
+
     /// Generate thumbnail for image file
     fn generate_image_thumbnail(&self, path: &Path) -> Result<ThumbnailResult> {
         debug!("Generating image thumbnail for: {}", path.display());
@@ -266,7 +293,8 @@ impl ThumbnailGenerator {
         ));
 
         // Run ffmpeg to extract frame at 1 second (or 10% for longer videos)
-        let duration = get_video_duration(path).unwrap_or(10.0);
+        let media = probe_media(path).unwrap_or_default();
+        let duration = media.duration_secs.unwrap_or(10.0);
         let seek_time = if duration > 30.0 {
             (duration * 0.1).min(10.0) // 10% but max 10 seconds
         } else if duration > 3.0 {
@@ -304,7 +332,7 @@ impl ThumbnailGenerator {
         };
 
         // Get original video dimensions
-        let (orig_width, orig_height) = get_video_dimensions(path).unwrap_or((0, 0));
+        let (orig_width, orig_height) = media.resolution.unwrap_or((0, 0));
 
         Ok(ThumbnailResult {
             data,
@@ -317,6 +345,58 @@ impl ThumbnailGenerator {
         })
     }
 
+    /// Generate a sprite strip of `frame_count` frames spread evenly across a video
+    ///
+    /// Frames are cropped to fill `width`x`height` and laid out left to right, so
+    /// frame `i` starts at `i * frame_width` in the encoded image.
+    pub fn generate_strip(&self, path: &Path, frame_count: u32) -> Result<ThumbnailStrip> {
+        if !self.ffmpeg_available {
+            anyhow::bail!("ffmpeg not available for video strip generation");
+        }
+        if frame_count == 0 {
+            anyhow::bail!("A thumbnail strip needs at least one frame");
+        }
+
+        debug!(
+            "Generating {}-frame strip for: {}",
+            frame_count,
+            path.display()
+        );
+
+        let duration = probe_media(path)
+            .and_then(|media| media.duration_secs)
+            .unwrap_or(10.0);
+        let mut frames: Vec<DynamicImage> = Vec::with_capacity(frame_count as usize);
+        for seek_time in strip_timestamps(duration, frame_count) {
+            let temp_path = std::env::temp_dir().join(format!(
+                "wayvid_strip_{}_{}.png",
+                std::process::id(),
+                rand_suffix()
+            ));
+            let frame = run_ffmpeg_extract(path, &temp_path, seek_time, self.width, self.height)
+                .then(|| image::open(&temp_path).ok())
+                .flatten();
+            let _ = std::fs::remove_file(&temp_path);
+
+            // Seeks past the last keyframe can fail; repeat the previous frame instead
+            match frame.or_else(|| frames.last().cloned()) {
+                Some(frame) => frames.push(frame),
+                None => anyhow::bail!("Failed to extract video frame with ffmpeg"),
+            }
+        }
+
+        let strip = tile_strip(&frames, self.width, self.height);
+        let data = encode_image(&DynamicImage::ImageRgba8(strip), self.format)?;
+
+        Ok(ThumbnailStrip {
+            data,
+            frame_width: self.width,
+            frame_height: self.height,
+            frame_count,
+            format: self.format.extension().to_string(),
+        })
+    }
+
     /// Check if video thumbnail generation is available
     pub fn can_generate_video_thumbnails(&self) -> bool {
         self.ffmpeg_available
@@ -337,6 +417,42 @@ impl ThumbnailGenerator {
         Ok(count)
     }
 
+    /// Delete the least recently used cached thumbnails until the rest fit
+    /// in `budget_bytes`; returns what was deleted
+    pub fn prune_cache(&self, budget_bytes: u64) -> Result<CacheStats> {
+        let mut files = Vec::new();
+        if self.cache_dir.exists() {
+            for entry in std::fs::read_dir(&self.cache_dir)?.flatten() {
+                if let Ok(metadata) = entry.metadata() {
+                    if metadata.is_file() {
+                        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                        files.push((modified, metadata.len(), entry.path()));
+                    }
+                }
+            }
+        }
+
+        // Most recently used first; what no longer fits goes
+        files.sort_by_key(|file| std::cmp::Reverse(file.0));
+        let mut kept_bytes = 0;
+        let mut removed = CacheStats::default();
+        for (_, len, path) in files {
+            kept_bytes += len;
+            if kept_bytes > budget_bytes && std::fs::remove_file(&path).is_ok() {
+                removed.count += 1;
+                removed.total_bytes += len;
+            }
+        }
+        if removed.count > 0 {
+            info!(
+                "Pruned {} cached thumbnails ({:.1} MB)",
+                removed.count,
+                removed.total_mb()
+            );
+        }
+        Ok(removed)
+    }
+
     /// Get cache statistics
     pub fn cache_stats(&self) -> CacheStats {
         let mut stats = CacheStats::default();
@@ -381,6 +497,21 @@ pub struct ThumbnailResult {
     pub cached: bool,
 }
 
+/// A horizontal sprite strip of video frames
+#[derive(Debug, Clone)]
+pub struct ThumbnailStrip {
+    /// Encoded strip image
+    pub data: Vec<u8>,
+    /// Width of a single frame
+    pub frame_width: u32,
+    /// Height of a single frame
+    pub frame_height: u32,
+    /// Number of frames laid out left to right
+    pub frame_count: u32,
+    /// Image format
+    pub format: String,
+}
+
 /// Cache statistics
 #[derive(Debug, Clone, Default)]
 pub struct CacheStats {
@@ -395,18 +526,20 @@ impl CacheStats {
 }
 
 /// Request for background thumbnail generation
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ThumbnailRequest {
     pub source_path: PathBuf,
     pub wallpaper_id: String,
     pub priority: ThumbnailPriority,
 }
 
-/// Priority for thumbnail generation
+/// Priority for thumbnail generation; higher priorities run first
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ThumbnailPriority {
+    /// Prefetching items the user has not scrolled to yet
     Low = 0,
     Normal = 1,
+    /// Items on screen right now
     High = 2,
 }
 
@@ -414,87 +547,183 @@ pub enum ThumbnailPriority {
 #[derive(Debug)]
 pub struct ThumbnailResponse {
     pub wallpaper_id: String,
+    pub source_path: PathBuf,
     pub result: Result<ThumbnailResult, String>,
 }
 
-/// Background thumbnail generation service
+struct QueuedRequest {
+    priority: ThumbnailPriority,
+    sequence: u64,
+    request: ThumbnailRequest,
+}
+
+impl PartialEq for QueuedRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedRequest {}
+
+impl PartialOrd for QueuedRequest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedRequest {
+    /// Higher priority first, then the older request
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+/// Requests waiting for a worker
+#[derive(Default)]
+struct ThumbnailQueue {
+    heap: BinaryHeap<QueuedRequest>,
+    /// Priority each waiting item was last requested at; heap entries with
+    /// another priority were superseded and are skipped
+    waiting: HashMap<String, ThumbnailPriority>,
+    next_sequence: u64,
+    running: usize,
+    /// Generated thumbnails not yet written to the library database
+    unsaved: Vec<(String, ThumbnailData)>,
+    closed: bool,
+}
+
+impl ThumbnailQueue {
+    /// Queue a request, or move a waiting one up to a higher priority.
+    /// Returns false when the item already waits at this priority or above.
+    fn push(&mut self, request: ThumbnailRequest) -> bool {
+        if self
+            .waiting
+            .get(&request.wallpaper_id)
+            .is_some_and(|waiting| *waiting >= request.priority)
+        {
+            return false;
+        }
+
+        self.waiting
+            .insert(request.wallpaper_id.clone(), request.priority);
+        self.next_sequence += 1;
+        self.heap.push(QueuedRequest {
+            priority: request.priority,
+            sequence: self.next_sequence,
+            request,
+        });
+        true
+    }
+
+    fn pop(&mut self) -> Option<ThumbnailRequest> {
+        while let Some(queued) = self.heap.pop() {
+            let id = &queued.request.wallpaper_id;
+            if self.waiting.get(id) == Some(&queued.priority) {
+                self.waiting.remove(id);
+                return Some(queued.request);
+            }
+        }
+        None
+    }
+}
+
+type SharedQueue = Arc<(Mutex<ThumbnailQueue>, Condvar)>;
+
+/// Background thumbnail generation on worker threads. Requests are served
+/// by priority, so items on screen are generated before prefetched ones.
 pub struct ThumbnailService {
     generator: Arc<ThumbnailGenerator>,
-    request_tx: mpsc::Sender<ThumbnailRequest>,
-    response_rx: mpsc::Receiver<ThumbnailResponse>,
-    pending_count: Arc<RwLock<usize>>,
+    queue: SharedQueue,
+    response_rx: Mutex<mpsc::Receiver<ThumbnailResponse>>,
 }
 
 impl ThumbnailService {
     /// Create a new thumbnail service with specified worker count
     pub fn new(worker_count: usize) -> Self {
-        let (request_tx, request_rx) = mpsc::channel::<ThumbnailRequest>(1000);
-        let (response_tx, response_rx) = mpsc::channel::<ThumbnailResponse>(1000);
+        Self::with_generator(ThumbnailGenerator::new(), worker_count)
+    }
 
-        let generator = Arc::new(ThumbnailGenerator::new());
-        let pending_count = Arc::new(RwLock::new(0));
+    /// Create a service whose workers share `generator`
+    pub fn with_generator(generator: ThumbnailGenerator, worker_count: usize) -> Self {
+        Self::start(generator, worker_count, None)
+    }
+
+    /// Like [`Self::with_generator`], and keeps thumbnails in the library
+    /// database at `database_path`: stored ones are reused while their
+    /// source is unchanged, new ones are written in batches
+    pub fn with_database(
+        generator: ThumbnailGenerator,
+        worker_count: usize,
+        database_path: PathBuf,
+    ) -> Self {
+        Self::start(generator, worker_count, Some(database_path))
+    }
+
+    fn start(
+        generator: ThumbnailGenerator,
+        worker_count: usize,
+        database_path: Option<PathBuf>,
+    ) -> Self {
+        let generator = Arc::new(generator);
+        let queue: SharedQueue = Arc::default();
+        let (response_tx, response_rx) = mpsc::channel();
 
-        // Spawn worker tasks
-        let request_rx = Arc::new(tokio::sync::Mutex::new(request_rx));
-        for _ in 0..worker_count {
+        for index in 0..worker_count.max(1) {
             let generator = generator.clone();
-            let rx = request_rx.clone();
-            let tx = response_tx.clone();
-            let pending = pending_count.clone();
-
-            tokio::spawn(async move {
-                loop {
-                    let request = {
-                        let mut guard = rx.lock().await;
-                        guard.recv().await
-                    };
-
-                    match request {
-                        Some(req) => {
-                            let result = generator.generate(&req.source_path);
-                            let response = ThumbnailResponse {
-                                wallpaper_id: req.wallpaper_id,
-                                result: result.map_err(|e| e.to_string()),
-                            };
-                            let _ = tx.send(response).await;
-                            *pending.write() -= 1;
-                        }
-                        None => break,
-                    }
-                }
-            });
+            let queue = queue.clone();
+            let response_tx = response_tx.clone();
+            let database_path = database_path.clone();
+            let spawned = std::thread::Builder::new()
+                .name(format!("thumbnail-{index}"))
+                .spawn(move || {
+                    // A connection per worker; a database that fails to open
+                    // only costs the persistence
+                    let database = database_path.and_then(|path| {
+                        LibraryDatabase::open(&path)
+                            .map_err(|e| warn!("Thumbnails will not be stored: {:#}", e))
+                            .ok()
+                    });
+                    run_worker(&generator, database.as_ref(), &queue, &response_tx)
+                });
+            if let Err(e) = spawned {
+                warn!("Failed to start thumbnail worker: {}", e);
+            }
         }
 
         Self {
             generator,
-            request_tx,
-            response_rx,
-            pending_count,
+            queue,
+            response_rx: Mutex::new(response_rx),
         }
     }
 
-    /// Submit a thumbnail request
-    pub async fn request(&self, request: ThumbnailRequest) -> Result<()> {
-        *self.pending_count.write() += 1;
-        self.request_tx
-            .send(request)
-            .await
-            .map_err(|_| anyhow::anyhow!("Thumbnail service channel closed"))
+    /// Submit a thumbnail request. Returns false when the item is already
+    /// waiting at this priority or a higher one.
+    pub fn request(&self, request: ThumbnailRequest) -> bool {
+        let (queue, ready) = &*self.queue;
+        let queued = queue.lock().push(request);
+        if queued {
+            ready.notify_one();
+        }
+        queued
     }
 
     /// Try to receive a completed thumbnail
-    pub fn try_recv(&mut self) -> Option<ThumbnailResponse> {
-        self.response_rx.try_recv().ok()
+    pub fn try_recv(&self) -> Option<ThumbnailResponse> {
+        self.response_rx.lock().try_recv().ok()
     }
 
-    /// Receive next completed thumbnail (async)
-    pub async fn recv(&mut self) -> Option<ThumbnailResponse> {
-        self.response_rx.recv().await
+    /// Wait up to `timeout` for the next completed thumbnail
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<ThumbnailResponse> {
+        self.response_rx.lock().recv_timeout(timeout).ok()
     }
 
-    /// Get number of pending requests
+    /// Requests waiting or being generated
     pub fn pending_count(&self) -> usize {
-        *self.pending_count.read()
+        let queue = self.queue.0.lock();
+        queue.waiting.len() + queue.running
     }
 
     /// Get reference to generator
@@ -503,6 +732,131 @@ impl ThumbnailService {
     }
 }
 
+impl Drop for ThumbnailService {
+    fn drop(&mut self) {
+        let (queue, ready) = &*self.queue;
+        queue.lock().closed = true;
+        ready.notify_all();
+    }
+}
+
+fn run_worker(
+    generator: &ThumbnailGenerator,
+    database: Option<&LibraryDatabase>,
+    queue: &SharedQueue,
+    responses: &mpsc::Sender<ThumbnailResponse>,
+) {
+    let (queue, ready) = &**queue;
+    loop {
+        let request = {
+            let mut queue = queue.lock();
+            loop {
+                if queue.closed {
+                    return;
+                }
+                if let Some(request) = queue.pop() {
+                    queue.running += 1;
+                    break request;
+                }
+                ready.wait(&mut queue);
+            }
+        };
+
+        let source_modified = source_modified(&request.source_path);
+        let stored = database.and_then(|database| {
+            stored_thumbnail(database, generator, &request.wallpaper_id, source_modified)
+        });
+        let generate = stored.is_none();
+        if let (Some(database), false) = (database, generate) {
+            if let Err(e) = database.touch_thumbnails(&request.wallpaper_id) {
+                debug!("Failed to mark thumbnail as used: {:#}", e);
+            }
+        }
+        let result = match stored {
+            Some(stored) => Ok(stored),
+            None => generator
+                .generate(&request.source_path)
+                .map_err(|e| format!("{e:#}")),
+        };
+
+        let batch = {
+            let mut queue = queue.lock();
+            queue.running -= 1;
+            if database.is_none() {
+                Vec::new()
+            } else {
+                if let (true, Ok(thumbnail)) = (generate, &result) {
+                    queue.unsaved.push((
+                        request.wallpaper_id.clone(),
+                        ThumbnailData {
+                            data: thumbnail.data.clone(),
+                            width: thumbnail.width,
+                            height: thumbnail.height,
+                            source_modified,
+                            source_path: Some(request.source_path.clone()),
+                        },
+                    ));
+                }
+                // Write once a batch is full or the queue runs dry
+                let idle = queue.waiting.is_empty() && queue.running == 0;
+                if queue.unsaved.len() >= THUMBNAIL_BATCH_SIZE
+                    || (idle && !queue.unsaved.is_empty())
+                {
+                    std::mem::take(&mut queue.unsaved)
+                } else {
+                    Vec::new()
+                }
+            }
+        };
+        if let Some(database) = database.filter(|_| !batch.is_empty()) {
+            if let Err(e) = database.store_thumbnails(&batch) {
+                warn!("Failed to store {} thumbnails: {:#}", batch.len(), e);
+            }
+        }
+
+        let response = ThumbnailResponse {
+            wallpaper_id: request.wallpaper_id,
+            source_path: request.source_path,
+            result,
+        };
+        if responses.send(response).is_err() {
+            return;
+        }
+    }
+}
+
+/// Source mtime in seconds since the epoch, for telling stale thumbnails apart
+fn source_modified(path: &Path) -> Option<i64> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|since_epoch| since_epoch.as_secs() as i64)
+}
+
+/// A stored thumbnail made from the current source at the generator's size
+fn stored_thumbnail(
+    database: &LibraryDatabase,
+    generator: &ThumbnailGenerator,
+    wallpaper_id: &str,
+    source_modified: Option<i64>,
+) -> Option<ThumbnailResult> {
+    let stored = database.get_thumbnail(wallpaper_id).ok().flatten()?;
+    let current = source_modified.is_some()
+        && stored.source_modified == source_modified
+        && stored.width <= generator.width
+        && stored.height <= generator.height;
+    current.then(|| ThumbnailResult {
+        data: stored.data,
+        width: stored.width,
+        height: stored.height,
+        original_width: 0, // Unknown from the database
+        original_height: 0,
+        format: generator.format.extension().to_string(),
+        cached: true,
+    })
+}
+
 // ========== Helper Functions ==========
 
 /// Encode image to specified format
@@ -513,6 +867,28 @@ fn encode_image(img: &DynamicImage, format: ThumbnailFormat) -> Result<Vec<u8>>
     Ok(buffer)
 }
 
+/// Seek times for `count` strip frames, each centred in an equal slice of the video
+fn strip_timestamps(duration: f64, count: u32) -> Vec<f64> {
+    (0..count)
+        .map(|index| duration.max(0.0) * (index as f64 + 0.5) / count as f64)
+        .collect()
+}
+
+/// Crop each frame to fill `width`x`height` and lay them out left to right
+fn tile_strip(frames: &[DynamicImage], width: u32, height: u32) -> RgbaImage {
+    let mut strip = RgbaImage::new(width * frames.len() as u32, height);
+    for (index, frame) in frames.iter().enumerate() {
+        let tile = frame.resize_to_fill(width, height, FilterType::Triangle);
+        image::imageops::replace(
+            &mut strip,
+            &tile.to_rgba8(),
+            (index as u32 * width) as i64,
+            0,
+        );
+    }
+    strip
+}
+
 /// Hash a path for cache filename
 fn hash_path(path: &Path) -> String {
     let mut hasher = Sha256::new();
@@ -570,62 +946,6 @@ fn run_ffmpeg_extract(
         .unwrap_or(false)
 }
 
-/// Get video dimensions using ffprobe
-pub fn get_video_dimensions(path: &Path) -> Option<(u32, u32)> {
-    let output = Command::new("ffprobe")
-        .args([
-            "-v",
-            "error",
-            "-select_streams",
-            "v:0",
-            "-show_entries",
-            "stream=width,height",
-            "-of",
-            "csv=p=0:s=x",
-            path.to_str()?,
-        ])
-        .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let parts: Vec<&str> = stdout.trim().split('x').collect();
-
-    if parts.len() == 2 {
-        let width = parts[0].parse().ok()?;
-        let height = parts[1].parse().ok()?;
-        Some((width, height))
-    } else {
-        None
-    }
-}
-
-/// Get video duration using ffprobe
-pub fn get_video_duration(path: &Path) -> Option<f64> {
-    let output = Command::new("ffprobe")
-        .args([
-            "-v",
-            "error",
-            "-show_entries",
-            "format=duration",
-            "-of",
-            "default=noprint_wrappers=1:nokey=1",
-            path.to_str()?,
-        ])
-        .output()
-        .ok()?;
-
-    if !output.status.success() {
-        return None;
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    stdout.trim().parse().ok()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -696,6 +1016,29 @@ mod tests {
         assert!(result2.cached);
     }
 
+    #[test]
+    fn test_fresh_thumbnail_skips_the_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let image_path = temp_dir.path().join("snapshot.png");
+        let cache_dir = temp_dir.path().join("cache");
+        DynamicImage::new_rgb8(100, 100).save(&image_path).unwrap();
+
+        let generator = ThumbnailGenerator::with_options(
+            THUMBNAIL_WIDTH,
+            THUMBNAIL_HEIGHT,
+            ThumbnailFormat::Png,
+            cache_dir,
+        );
+        generator.generate_fresh(&image_path).unwrap();
+
+        DynamicImage::new_rgb8(200, 100).save(&image_path).unwrap();
+        let result = generator.generate_fresh(&image_path).unwrap();
+
+        assert!(!result.cached);
+        assert_eq!(result.original_width, 200);
+        assert!(!generator.is_cached(&image_path));
+    }
+
     #[test]
     fn test_cache_stats() {
         let temp_dir = TempDir::new().unwrap();
@@ -714,6 +1057,69 @@ mod tests {
         assert_eq!(stats.total_bytes, 0);
     }
 
+    #[test]
+    fn test_prune_cache_keeps_newest_within_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = temp_dir.path().join("cache");
+        let generator = ThumbnailGenerator::with_options(
+            THUMBNAIL_WIDTH,
+            THUMBNAIL_HEIGHT,
+            ThumbnailFormat::Png,
+            cache_dir.clone(),
+        );
+        for (name, age_secs) in [("old.png", 300), ("mid.png", 200), ("new.png", 100)] {
+            let path = cache_dir.join(name);
+            std::fs::write(&path, [0; 10]).unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+                .unwrap();
+        }
+
+        let removed = generator.prune_cache(25).unwrap();
+        assert_eq!((removed.count, removed.total_bytes), (1, 10));
+        assert!(!cache_dir.join("old.png").exists());
+        assert!(cache_dir.join("new.png").exists());
+        assert_eq!(generator.cache_stats().total_bytes, 20);
+    }
+
+    #[test]
+    fn test_prune_cache_keeps_recently_read_thumbnails() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache_dir = temp_dir.path().join("cache");
+        let generator = ThumbnailGenerator::with_options(
+            THUMBNAIL_WIDTH,
+            THUMBNAIL_HEIGHT,
+            ThumbnailFormat::Png,
+            cache_dir.clone(),
+        );
+        let mut png = Vec::new();
+        DynamicImage::ImageRgb8(image::RgbImage::new(4, 4))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let sources = [Path::new("/videos/old.mp4"), Path::new("/videos/new.mp4")];
+        for (source, age_secs) in sources.iter().zip([300, 100]) {
+            let path = generator.cache_path(source);
+            std::fs::write(&path, &png).unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(SystemTime::now() - Duration::from_secs(age_secs))
+                .unwrap();
+        }
+
+        // Written first, but read last
+        assert!(generator.get_cached(sources[0]).is_some());
+
+        let removed = generator.prune_cache(png.len() as u64).unwrap();
+        assert_eq!(removed.count, 1);
+        assert!(generator.is_cached(sources[0]));
+        assert!(!generator.is_cached(sources[1]));
+    }
+
     #[test]
     fn test_thumbnail_format() {
         assert_eq!(ThumbnailFormat::WebP.extension(), "webp");
@@ -721,6 +1127,89 @@ mod tests {
         assert_eq!(ThumbnailFormat::Jpeg.extension(), "jpg");
     }
 
+    #[test]
+    fn test_strip_timestamps_centre_each_slice() {
+        assert_eq!(strip_timestamps(10.0, 4), vec![1.25, 3.75, 6.25, 8.75]);
+        assert_eq!(strip_timestamps(0.0, 2), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_tile_strip_lays_frames_out_left_to_right() {
+        let colors = [[255, 0, 0], [0, 255, 0], [0, 0, 255]];
+        // Mixed aspect ratios are cropped to the same tile size
+        let frames: Vec<DynamicImage> = colors
+            .iter()
+            .enumerate()
+            .map(|(index, color)| {
+                DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+                    40 + index as u32 * 30,
+                    20,
+                    image::Rgb(*color),
+                ))
+            })
+            .collect();
+
+        let strip = tile_strip(&frames, 16, 9);
+
+        assert_eq!(strip.dimensions(), (48, 9));
+        for (index, color) in colors.iter().enumerate() {
+            let pixel = strip.get_pixel(index as u32 * 16 + 8, 4);
+            assert_eq!(&pixel.0[..3], color);
+        }
+    }
+
+    fn request(id: &str, priority: ThumbnailPriority) -> ThumbnailRequest {
+        ThumbnailRequest {
+            source_path: PathBuf::from(format!("/covers/{id}.png")),
+            wallpaper_id: id.to_string(),
+            priority,
+        }
+    }
+
+    #[test]
+    fn test_queue_serves_visible_items_first_and_reprioritizes() {
+        let mut queue = ThumbnailQueue::default();
+        assert!(queue.push(request("a", ThumbnailPriority::Low)));
+        assert!(queue.push(request("b", ThumbnailPriority::Low)));
+        assert!(queue.push(request("c", ThumbnailPriority::High)));
+        // Scrolling to "b" moves it ahead of the prefetch
+        assert!(queue.push(request("b", ThumbnailPriority::High)));
+        assert!(!queue.push(request("b", ThumbnailPriority::Low)));
+        assert_eq!(queue.waiting.len(), 3);
+
+        let order: Vec<String> = std::iter::from_fn(|| queue.pop())
+            .map(|request| request.wallpaper_id)
+            .collect();
+        assert_eq!(order, vec!["c", "b", "a"]);
+        assert!(queue.waiting.is_empty());
+    }
+
+    #[test]
+    fn test_service_generates_in_the_background() {
+        let temp_dir = TempDir::new().unwrap();
+        let image_path = temp_dir.path().join("test.png");
+        DynamicImage::new_rgb8(100, 100).save(&image_path).unwrap();
+        let generator = ThumbnailGenerator::with_options(
+            THUMBNAIL_WIDTH,
+            THUMBNAIL_HEIGHT,
+            ThumbnailFormat::Png,
+            temp_dir.path().join("cache"),
+        );
+
+        let service = ThumbnailService::with_generator(generator, 1);
+        assert!(service.request(ThumbnailRequest {
+            source_path: image_path.clone(),
+            wallpaper_id: "test".to_string(),
+            priority: ThumbnailPriority::High,
+        }));
+        let response = service.recv_timeout(Duration::from_secs(10)).unwrap();
+
+        assert_eq!(response.wallpaper_id, "test");
+        assert_eq!(response.source_path, image_path);
+        assert_eq!(response.result.unwrap().original_width, 100);
+        assert_eq!(service.pending_count(), 0);
+    }
+
     #[test]
     fn test_hash_path() {
         let path1 = Path::new("/home/user/wallpaper.mp4");
@@ -6,7 +6,7 @@
 //! - project.json parsing
 //! - Wallpaper metadata extraction
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -180,6 +180,70 @@ pub struct WeProject {
     /// Content rating
     #[serde(default)]
     pub contentrating: Option<String>,
+
+    /// Settings shared by every wallpaper type, such as user properties
+    #[serde(default)]
+    pub general: WeGeneral,
+}
+
+/// `general` block of project.json
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WeGeneral {
+    /// Properties the author lets users change, keyed by name
+    #[serde(default)]
+    pub properties: BTreeMap<String, WeProperty>,
+}
+
+/// A user-configurable property: a color tint, a speed slider, a toggle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeProperty {
+    /// Control type: "slider", "bool", "color", "combo", "textinput", or
+    /// "text" for a plain label
+    #[serde(rename = "type", default)]
+    pub kind: String,
+
+    /// Label shown next to the control; may hold HTML or a localization key
+    #[serde(default)]
+    pub text: Option<String>,
+
+    /// Default value; colors are "r g b" with channels from 0 to 1
+    #[serde(default)]
+    pub value: serde_json::Value,
+
+    /// Position among the other properties
+    #[serde(default)]
+    pub order: i64,
+
+    #[serde(default)]
+    pub min: Option<f64>,
+
+    #[serde(default)]
+    pub max: Option<f64>,
+
+    #[serde(default)]
+    pub step: Option<f64>,
+
+    /// Choices of a combo box
+    #[serde(default)]
+    pub options: Vec<WePropertyOption>,
+}
+
+/// One choice of a combo property
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WePropertyOption {
+    #[serde(default)]
+    pub label: String,
+    pub value: serde_json::Value,
+}
+
+impl WeProperty {
+    /// Whether users can change it; labels and file pickers are left out
+    pub fn is_editable(&self) -> bool {
+        matches!(
+            self.kind.as_str(),
+            "slider" | "bool" | "color" | "combo" | "textinput"
+        )
+    }
 }
 
 impl WeProject {
@@ -203,9 +267,14 @@ impl WeProject {
         self.project_type.to_lowercase() == "scene"
     }
 
+    /// Check if this is a web (HTML/JS) type project
+    pub fn is_web(&self) -> bool {
+        self.project_type.to_lowercase() == "web"
+    }
+
     /// Check if this project type is supported
     pub fn is_supported(&self) -> bool {
-        self.is_video() || self.is_scene()
+        self.is_video() || self.is_scene() || self.is_web()
     }
 
     /// Get the wallpaper type
@@ -213,6 +282,7 @@ impl WeProject {
         match self.project_type.to_lowercase().as_str() {
             "video" => WallpaperType::Video,
             "scene" => WallpaperType::Scene,
+            "web" => WallpaperType::Web,
             _ => WallpaperType::Video, // Default
         }
     }
@@ -241,6 +311,19 @@ impl WeProject {
     pub fn cover_image(&self, project_dir: &Path) -> Option<PathBuf> {
         self.preview_image(project_dir).filter(|path| path.exists())
     }
+
+    /// Editable user properties in the order the author gave them
+    pub fn user_properties(&self) -> Vec<(&str, &WeProperty)> {
+        let mut properties = self
+            .general
+            .properties
+            .iter()
+            .filter(|(_, property)| property.is_editable())
+            .map(|(name, property)| (name.as_str(), property))
+            .collect::<Vec<_>>();
+        properties.sort_by_key(|(name, property)| (property.order, *name));
+        properties
+    }
 }
 
 /// Workshop scanner for discovering Wallpaper Engine wallpapers
@@ -346,17 +429,19 @@ impl WorkshopScanner {
                 };
                 let project_type = project.project_type_enum();
                 let parsed_item = match project_type {
-                    WorkshopProjectType::Video | WorkshopProjectType::Scene => {
+                    WorkshopProjectType::Video
+                    | WorkshopProjectType::Scene
+                    | WorkshopProjectType::Web => {
                         self.parse_workshop_item_const(&item_path, workshop_id)?
                     }
-                    WorkshopProjectType::Web | WorkshopProjectType::Other => None,
+                    WorkshopProjectType::Other => None,
                 };
                 let supported_first_release = parsed_item.is_some();
                 let sync_state = match project_type {
-                    WorkshopProjectType::Web | WorkshopProjectType::Other => {
-                        WorkshopSyncState::UnsupportedType
-                    }
-                    WorkshopProjectType::Video | WorkshopProjectType::Scene => {
+                    WorkshopProjectType::Other => WorkshopSyncState::UnsupportedType,
+                    WorkshopProjectType::Video
+                    | WorkshopProjectType::Scene
+                    | WorkshopProjectType::Web => {
                         if supported_first_release {
                             WorkshopSyncState::Synced
                         } else {
@@ -459,7 +544,7 @@ impl WorkshopScanner {
 
         let project = WeProject::load(item_path)?;
 
-        // Only support video and scene types for now
+        // Only support video, scene and web types for now
         if !project.is_supported() {
             debug!("  ⏭️ Skipping unsupported type: {}", project.project_type);
             return Ok(None);
@@ -504,7 +589,12 @@ impl WorkshopScanner {
             duration_secs: None,
             resolution: None,
             file_size: None,
+            codec: None,
+            fps: None,
+            hdr: None,
             workshop_id: Some(workshop_id),
+            shader_path: None,
+            lut_path: None,
         };
 
         // Set thumbnail path if preview exists
@@ -630,6 +720,37 @@ mod tests {
         assert!(project.is_video());
         assert!(project.is_supported());
         assert_eq!(project.tags.len(), 2);
+        assert!(project.user_properties().is_empty());
+    }
+
+    #[test]
+    fn test_we_project_user_properties() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = r#"{
+            "type": "web",
+            "file": "index.html",
+            "general": {
+                "properties": {
+                    "schemecolor": { "order": 0, "text": "ui_browse_properties_scheme_color", "type": "color", "value": "0.2 0.4 1" },
+                    "speed": { "order": 2, "text": "Speed", "type": "slider", "value": 1.5, "min": 0, "max": 5, "step": 0.5 },
+                    "rain": { "order": 1, "text": "Rain", "type": "bool", "value": true },
+                    "heading": { "order": 3, "text": "<b>Extras</b>", "type": "text" },
+                    "mode": {
+                        "order": 4, "text": "Mode", "type": "combo", "value": "2",
+                        "options": [{ "label": "Day", "value": "1" }, { "label": "Night", "value": "2" }]
+                    }
+                }
+            }
+        }"#;
+        fs::write(temp_dir.path().join("project.json"), content).unwrap();
+
+        let project = WeProject::load(temp_dir.path()).unwrap();
+        let properties = project.user_properties();
+        let names = properties.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        assert_eq!(names, ["schemecolor", "rain", "speed", "mode"]);
+        assert_eq!(properties[2].1.max, Some(5.0));
+        assert_eq!(properties[3].1.options[1].label, "Night");
+        assert_eq!(properties[0].1.value, serde_json::json!("0.2 0.4 1"));
     }
 
     #[test]
@@ -653,12 +774,20 @@ mod tests {
         assert!(project.is_scene());
         assert!(project.is_supported());
 
-        // Web type (unsupported)
+        // Web type
         let content = r#"{"type": "web", "file": "index.html"}"#;
         fs::write(&project_file, content).unwrap();
         let project = WeProject::load(temp_dir.path()).unwrap();
         assert!(!project.is_video());
         assert!(!project.is_scene());
+        assert!(project.is_web());
+        assert!(project.is_supported());
+        assert_eq!(project.wallpaper_type(), WallpaperType::Web);
+
+        // Application type (unsupported)
+        let content = r#"{"type": "application", "file": "app.exe"}"#;
+        fs::write(&project_file, content).unwrap();
+        let project = WeProject::load(temp_dir.path()).unwrap();
         assert!(!project.is_supported());
     }
 
@@ -177,8 +177,8 @@ mod tests {
         write_project(
             &item_dir,
             r#"{
-                "type": "web",
-                "title": "Web Wallpaper",
+                "type": "application",
+                "title": "Desktop App",
                 "preview": "preview.jpg"
             }"#,
         );
@@ -189,11 +189,38 @@ mod tests {
             .find(|entry| entry.workshop_id == 501)
             .unwrap();
 
-        assert_eq!(entry.project_type, WorkshopProjectType::Web);
+        assert_eq!(entry.project_type, WorkshopProjectType::Other);
         assert_eq!(entry.sync_state, WorkshopSyncState::UnsupportedType);
         assert_eq!(entry.cover_path, Some(item_dir.join("preview.jpg")));
         assert!(entry.has_cover());
         assert!(!entry.supported_first_release);
         assert!(entry.library_item_id.is_none());
     }
+
+    #[test]
+    fn web_item_with_page_is_synced_into_library() {
+        let (_temp_dir, mut scanner, workshop_root) = create_scanner();
+
+        let item_dir = create_item_dir(&workshop_root, 601);
+        fs::write(item_dir.join("index.html"), b"<html></html>").unwrap();
+        write_project(
+            &item_dir,
+            r#"{
+                "type": "web",
+                "title": "Web Wallpaper",
+                "file": "index.html"
+            }"#,
+        );
+
+        let entries = scanner.scan_catalog().unwrap();
+        let entry = entries
+            .into_iter()
+            .find(|entry| entry.workshop_id == 601)
+            .unwrap();
+
+        assert_eq!(entry.project_type, WorkshopProjectType::Web);
+        assert_eq!(entry.sync_state, WorkshopSyncState::Synced);
+        assert!(entry.supported_first_release);
+        assert!(entry.library_item_id.is_some());
+    }
 }
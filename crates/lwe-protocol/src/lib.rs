@@ -0,0 +1,1052 @@
+//! lwe-protocol: IPC wire types shared between the LWE daemon, app and
+//! command line
+//!
+//! Only types that cross a process boundary as JSON live here, so peers from
+//! slightly different releases agree on the wire format. The rules that keep
+//! them compatible:
+//! - never rename a `type` or `kind` tag or a field;
+//! - new fields are `Option` or `#[serde(default)]`, and unknown fields are
+//!   ignored, so older messages still parse and older peers skip new data;
+//! - a request or response type a peer does not know parses as `Unknown`
+//!   instead of failing;
+//! - `PROTOCOL_VERSION` goes up when messages change, and `Pong` reports it.
+//!
+//! Since version 3 messages travel in an [`Envelope`]: a request may carry an
+//! `id`, and the response to it echoes that `id` with the time spent
+//! answering, so a client can match responses to requests and measure them.
+//!
+//! `fixtures/` holds messages recorded from each protocol version; the tests
+//! parse all of them and round-trip the current ones.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Version of the messages in this crate; 0 is a peer from before versioning
+pub const PROTOCOL_VERSION: u32 = 13;
+
+/// IPC request from client to daemon
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IpcRequest {
+    /// Ping - check if daemon is alive
+    Ping,
+
+    /// Get daemon status
+    Status,
+
+    /// List available outputs/monitors
+    Outputs,
+
+    /// Show the GUI window (for single instance support)
+    ShowWindow,
+
+    /// Apply wallpaper to output(s)
+    Apply {
+        /// Path to wallpaper file
+        path: PathBuf,
+        /// Target output (None = all outputs)
+        output: Option<String>,
+        /// Scale mode: fill, contain, stretch, centre
+        #[serde(default = "default_mode")]
+        mode: String,
+    },
+
+    /// Pause playback
+    Pause {
+        /// Target output (None = all)
+        output: Option<String>,
+    },
+
+    /// Resume playback
+    Resume {
+        /// Target output (None = all)
+        output: Option<String>,
+    },
+
+    /// Stop playback and clear wallpaper
+    Stop {
+        /// Target output (None = all)
+        output: Option<String>,
+    },
+
+    /// Set volume
+    SetVolume {
+        /// Target output
+        output: String,
+        /// Volume level 0.0 - 1.0
+        volume: f32,
+    },
+
+    /// Get volume, mute and audio device per output
+    GetAudio {
+        /// Target output (None = all)
+        output: Option<String>,
+    },
+
+    /// Mute or unmute an output
+    SetMute {
+        /// Target output
+        output: String,
+        /// Whether audio should be muted
+        muted: bool,
+    },
+
+    /// Select the audio device an output plays through
+    SetAudioDevice {
+        /// Target output
+        output: String,
+        /// Device as named by mpv (None = system default)
+        device: Option<String>,
+    },
+
+    /// Change the brightness, contrast, gamma and saturation of an output;
+    /// each scales its property, 1.0 = unchanged
+    SetColor {
+        /// Target output
+        output: String,
+        /// Brightness 0.0 - 2.0 (None = keep the current value)
+        brightness: Option<f32>,
+        /// Contrast 0.0 - 2.0 (None = keep the current value)
+        contrast: Option<f32>,
+        /// Gamma 0.0 - 2.0 (None = keep the current value)
+        gamma: Option<f32>,
+        /// Saturation 0.0 - 2.0 (None = keep the current value)
+        saturation: Option<f32>,
+    },
+
+    /// Jump to a position in the video
+    Seek {
+        /// Target output (None = all)
+        output: Option<String>,
+        /// Position in seconds, or offset from the current one when relative
+        seconds: f64,
+        /// Whether `seconds` is an offset
+        #[serde(default)]
+        relative: bool,
+    },
+
+    /// Change the playback speed
+    SetRate {
+        /// Target output (None = all)
+        output: Option<String>,
+        /// Speed, 1.0 = normal
+        rate: f64,
+    },
+
+    /// Pause on the next (or previous) frame
+    FrameStep {
+        /// Target output (None = all)
+        output: Option<String>,
+        /// Step back instead of forward
+        #[serde(default)]
+        backward: bool,
+    },
+
+    /// Loop part of the video
+    SetAbLoop {
+        /// Target output (None = all)
+        output: Option<String>,
+        /// Start and end in seconds (None = loop the whole video)
+        range: Option<(f64, f64)>,
+    },
+
+    /// Get the colors extracted from the frame on screen
+    GetPalette {
+        /// Target output (None = all)
+        output: Option<String>,
+    },
+
+    /// Save the frame on screen as an image; the format follows the file
+    /// extension (png, jpg or webp)
+    Snapshot {
+        /// Target output
+        output: String,
+        /// Image file to write
+        path: PathBuf,
+    },
+
+    /// Set a raw mpv property on the video an output plays; it is not saved
+    MpvSet {
+        /// Target output
+        output: String,
+        /// mpv property name, e.g. `scale`
+        property: String,
+        /// Value as mpv parses it on its command line
+        value: String,
+    },
+
+    /// Get render and decode stats of each output and the daemon's CPU and
+    /// memory use
+    GetStats,
+
+    /// Reload configuration
+    Reload,
+
+    /// Assign a playlist to an output
+    PlaylistSet {
+        /// Target output
+        output: String,
+        /// Playlist to rotate (None = stop rotating on this output)
+        playlist_id: Option<i64>,
+    },
+
+    /// Skip to the next playlist entry
+    PlaylistNext {
+        /// Target output (None = all)
+        output: Option<String>,
+    },
+
+    /// Go back to the previous playlist entry
+    PlaylistPrevious {
+        /// Target output (None = all)
+        output: Option<String>,
+    },
+
+    /// Pause or resume playlist rotation
+    PlaylistPause {
+        /// Target output (None = all)
+        output: Option<String>,
+        /// Whether rotation should be paused
+        paused: bool,
+    },
+
+    /// Request library list (used by GUI)
+    GetLibrary {
+        /// Optional filter
+        filter: Option<LibraryFilter>,
+    },
+
+    /// Search the library by text, tags, type and rating, one page at a time
+    QueryLibrary {
+        #[serde(default)]
+        filter: LibraryFilter,
+    },
+
+    /// Apply a wallpaper picked at random from the library matches
+    ApplyRandom {
+        #[serde(default)]
+        filter: LibraryFilter,
+        /// Target output (None = all outputs)
+        output: Option<String>,
+    },
+
+    /// Save the wallpaper on every output and the output settings as a
+    /// named profile, replacing a profile of the same name
+    ProfileSave {
+        /// Profile name
+        name: String,
+    },
+
+    /// Switch every output to a saved profile
+    ProfileLoad {
+        /// Profile name
+        name: String,
+    },
+
+    /// List the saved profiles
+    ProfileList,
+
+    /// List the library collections
+    CollectionList,
+
+    /// Rotate a collection's wallpapers as a playlist
+    CollectionApply {
+        /// Collection name
+        name: String,
+        /// Target output (None = all outputs)
+        output: Option<String>,
+    },
+
+    /// Quit daemon
+    Quit,
+
+    /// A request from a newer client; answered with an error
+    #[serde(other)]
+    Unknown,
+}
+
+fn default_mode() -> String {
+    "fill".to_string()
+}
+
+/// Library filter for GetLibrary and QueryLibrary requests
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct LibraryFilter {
+    /// Filter by wallpaper type: video, scene, animated_image, image or web
+    pub wallpaper_type: Option<String>,
+    /// Typo-tolerant, accent-insensitive search over name, title, author,
+    /// description and tags
+    pub search: Option<String>,
+    /// Tags to include (match any)
+    pub tags: Option<Vec<String>>,
+    /// Only wallpapers whose file is under this directory
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Only favorites
+    #[serde(default)]
+    pub favorites_only: bool,
+    /// Minimum rating (0-5)
+    #[serde(default)]
+    pub min_rating: Option<u8>,
+    /// Sort order
+    #[serde(default)]
+    pub sort: LibrarySort,
+    /// Matches to skip, for paging
+    #[serde(default)]
+    pub offset: usize,
+    /// Page size (None = all remaining matches)
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Sort order of library queries
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LibrarySort {
+    #[default]
+    Name,
+    DateAdded,
+    LastUsed,
+    UseCount,
+    Rating,
+    Relevance,
+}
+
+impl std::str::FromStr for LibrarySort {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().replace('-', "_").as_str() {
+            "name" => Ok(Self::Name),
+            "date_added" | "added" => Ok(Self::DateAdded),
+            "last_used" => Ok(Self::LastUsed),
+            "use_count" => Ok(Self::UseCount),
+            "rating" => Ok(Self::Rating),
+            "relevance" => Ok(Self::Relevance),
+            _ => Err(format!("Unknown library sort order: {value}")),
+        }
+    }
+}
+
+/// IPC response from daemon to client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IpcResponse {
+    /// Success with optional message
+    Ok { message: Option<String> },
+
+    /// Error response
+    Error { error: String },
+
+    /// Pong response to ping, with the daemon's protocol version
+    Pong {
+        #[serde(default)]
+        protocol: u32,
+    },
+
+    /// Status response
+    Status {
+        running: bool,
+        version: Option<String>,
+        outputs: Vec<OutputStatus>,
+        /// Whether the machine runs on battery
+        #[serde(default)]
+        on_battery: bool,
+        /// Whether playback is paused to save power
+        #[serde(default)]
+        power_paused: bool,
+        /// Whether wallpapers sleep while windows cover every output
+        #[serde(default)]
+        deep_sleep: bool,
+        /// Graphics API the wallpapers are drawn with, e.g. "OpenGL"
+        render_backend: Option<String>,
+    },
+
+    /// Outputs list response
+    Outputs { outputs: Vec<OutputInfo> },
+
+    /// Library list response; `total` counts matches across all pages
+    Library {
+        items: Vec<LibraryItem>,
+        total: usize,
+    },
+
+    /// Audio settings response
+    Audio { outputs: Vec<OutputAudio> },
+
+    /// Palette response
+    Palette { outputs: Vec<OutputPalette> },
+
+    /// The new wallpaper failed its pre-apply probe; the old one is still showing
+    SourceRejected { error: SourceError },
+
+    /// Saved profiles response
+    Profiles { profiles: Vec<ProfileInfo> },
+
+    /// Library collections response
+    Collections { collections: Vec<CollectionInfo> },
+
+    /// Stats response
+    Stats {
+        outputs: Vec<OutputStats>,
+        process: ProcessStats,
+    },
+
+    /// A response from a newer daemon
+    #[serde(other)]
+    Unknown,
+}
+
+/// Why a wallpaper source was rejected before replacing the current wallpaper
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SourceError {
+    /// Nothing exists at the path
+    NotFound { path: String },
+    /// The file exists but cannot be opened or read
+    Unreadable { path: String, reason: String },
+    /// The file is not a media file the decoder understands
+    Undecodable { path: String, reason: String },
+    /// The file has a video stream in a codec that cannot be played
+    UnsupportedCodec { path: String, codec: String },
+}
+
+impl std::fmt::Display for SourceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SourceError::NotFound { path } => write!(f, "{path} does not exist"),
+            SourceError::Unreadable { path, reason } => {
+                write!(f, "{path} cannot be read: {reason}")
+            }
+            SourceError::Undecodable { path, reason } => {
+                write!(f, "{path} is not a playable video or image: {reason}")
+            }
+            SourceError::UnsupportedCodec { path, codec } => {
+                write!(f, "{path} uses the unsupported codec {codec}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+/// Output status information
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputStatus {
+    /// Output name (e.g., "eDP-1")
+    pub name: String,
+    /// Currently playing wallpaper path
+    pub wallpaper: Option<String>,
+    /// Whether playback is paused
+    pub paused: bool,
+    /// Current volume level
+    pub volume: f32,
+    /// Title of the wallpaper
+    pub title: Option<String>,
+    /// Length of the video in seconds (None = not a video, or a live stream)
+    pub duration: Option<f64>,
+    /// Hardware decoder in use ("no" = decoding in software)
+    pub hwdec: Option<String>,
+    /// Frames drawn per second
+    pub fps: Option<f64>,
+    /// Share of frames dropped instead of drawn (0.0 - 1.0)
+    pub skip_rate: Option<f64>,
+}
+
+/// Render and decode stats of one output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputStats {
+    /// Output name
+    pub output: String,
+    /// Average milliseconds spent drawing a frame (None = drawn outside the daemon)
+    pub render_ms: Option<f64>,
+    /// Frames drawn per second
+    pub fps: Option<f64>,
+    /// Share of frames dropped instead of drawn (0.0 - 1.0)
+    pub skip_rate: Option<f64>,
+    /// Frames the decoder dropped since the video started
+    pub dropped_frames: u64,
+    /// Hardware decoder in use ("no" = decoding in software)
+    pub hwdec: Option<String>,
+}
+
+/// CPU and memory used by the daemon
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessStats {
+    /// Percent of one core (200 = two cores busy)
+    pub cpu_percent: Option<f64>,
+    /// Resident memory in bytes
+    pub rss_bytes: Option<u64>,
+}
+
+/// Audio settings of one output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputAudio {
+    /// Output name
+    pub output: String,
+    /// Volume level 0.0 - 1.0
+    pub volume: f32,
+    /// Whether audio is muted
+    pub muted: bool,
+    /// Audio device as named by mpv (None = system default)
+    pub device: Option<String>,
+}
+
+/// Colors extracted from the wallpaper on one output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputPalette {
+    /// Output name
+    pub output: String,
+    /// Colors as `#rrggbb`, most common first
+    pub colors: Vec<String>,
+}
+
+/// A saved wallpaper profile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileInfo {
+    /// Profile name
+    pub name: String,
+    /// Outputs the profile puts a wallpaper on
+    pub outputs: Vec<String>,
+}
+
+/// A library collection
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionInfo {
+    /// Collection name
+    pub name: String,
+    /// Number of wallpapers in the collection
+    pub items: usize,
+}
+
+/// Output/monitor information
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputInfo {
+    /// Output name
+    pub name: String,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// Refresh rate in Hz
+    pub refresh: Option<u32>,
+    /// Monitor manufacturer
+    pub make: Option<String>,
+    /// Monitor model
+    pub model: Option<String>,
+    /// Whether this is the primary monitor
+    pub primary: bool,
+    /// Position X
+    pub x: i32,
+    /// Position Y
+    pub y: i32,
+}
+
+/// A request or response with its correlation fields alongside the `type` tag
+///
+/// Messages from peers before version 3 parse with both fields unset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Envelope<T> {
+    /// Chosen by the client; the response to a request repeats it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    /// Milliseconds the peer took to answer (responses only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+    #[serde(flatten)]
+    pub message: T,
+}
+
+impl<T> Envelope<T> {
+    /// A message without correlation fields
+    pub fn new(message: T) -> Self {
+        Self {
+            id: None,
+            elapsed_ms: None,
+            message,
+        }
+    }
+
+    /// A request the response will be matched to by `id`
+    pub fn request(id: u64, message: T) -> Self {
+        Self {
+            id: Some(id),
+            ..Self::new(message)
+        }
+    }
+}
+
+/// Library item for GetLibrary response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryItem {
+    /// Unique ID
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// File path
+    pub path: String,
+    /// Wallpaper type
+    pub wallpaper_type: String,
+    /// Tags
+    pub tags: Vec<String>,
+    /// Is favorite
+    pub favorite: bool,
+    /// Rating (0-5)
+    #[serde(default)]
+    pub rating: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_ping() {
+        let request = IpcRequest::Ping;
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("ping"));
+
+        let parsed: IpcRequest = serde_json::from_str(&json).unwrap();
+        assert!(matches!(parsed, IpcRequest::Ping));
+    }
+
+    #[test]
+    fn test_request_apply() {
+        let request = IpcRequest::Apply {
+            path: PathBuf::from("/home/user/wallpaper.mp4"),
+            output: Some("DP-1".to_string()),
+            mode: "fill".to_string(),
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("apply"));
+        assert!(json.contains("wallpaper.mp4"));
+        assert!(json.contains("DP-1"));
+    }
+
+    #[test]
+    fn test_request_playlist_pause() {
+        let request = IpcRequest::PlaylistPause {
+            output: None,
+            paused: true,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("playlist_pause"));
+
+        let parsed: IpcRequest = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            parsed,
+            IpcRequest::PlaylistPause {
+                output: None,
+                paused: true
+            }
+        ));
+    }
+
+    #[test]
+    fn test_response_ok() {
+        let response = IpcResponse::Ok {
+            message: Some("Wallpaper applied".to_string()),
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("ok"));
+
+        let parsed: IpcResponse = serde_json::from_str(&json).unwrap();
+        if let IpcResponse::Ok { message } = parsed {
+            assert_eq!(message, Some("Wallpaper applied".to_string()));
+        } else {
+            panic!("Expected Ok response");
+        }
+    }
+
+    #[test]
+    fn test_response_status() {
+        let response = IpcResponse::Status {
+            running: true,
+            version: Some("0.5.0".to_string()),
+            outputs: vec![OutputStatus {
+                name: "eDP-1".to_string(),
+                wallpaper: Some("/home/user/bg.mp4".to_string()),
+                paused: false,
+                volume: 0.5,
+                title: Some("Rain".to_string()),
+                duration: Some(42.5),
+                hwdec: Some("vaapi".to_string()),
+                fps: Some(59.9),
+                skip_rate: Some(0.0),
+            }],
+            on_battery: true,
+            power_paused: false,
+            deep_sleep: false,
+            render_backend: Some("OpenGL".to_string()),
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("status"));
+        assert!(json.contains("eDP-1"));
+
+        // Daemons before version 6 leave out power, backend and frame stats
+        let parsed: IpcResponse = serde_json::from_str(
+            r#"{"type":"status","running":true,"version":null,"outputs":[{"name":"eDP-1","wallpaper":null,"paused":true,"volume":1.0}]}"#,
+        )
+        .unwrap();
+        let IpcResponse::Status {
+            outputs,
+            on_battery,
+            render_backend,
+            ..
+        } = parsed
+        else {
+            panic!("Expected Status response");
+        };
+        assert!(!on_battery);
+        assert_eq!(render_backend, None);
+        assert_eq!(outputs[0].fps, None);
+    }
+
+    #[test]
+    fn test_audio_request_and_response() {
+        let request = IpcRequest::SetAudioDevice {
+            output: "DP-1".to_string(),
+            device: Some("pulse/hdmi".to_string()),
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("set_audio_device"));
+
+        let response = IpcResponse::Audio {
+            outputs: vec![OutputAudio {
+                output: "DP-1".to_string(),
+                volume: 0.4,
+                muted: false,
+                device: None,
+            }],
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        let parsed: IpcResponse = serde_json::from_str(&json).unwrap();
+        if let IpcResponse::Audio { outputs } = parsed {
+            assert_eq!(outputs[0].output, "DP-1");
+            assert!(!outputs[0].muted);
+        } else {
+            panic!("Expected Audio response");
+        }
+    }
+
+    #[test]
+    fn test_palette_request_and_response() {
+        let request = IpcRequest::GetPalette { output: None };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("get_palette"));
+
+        let response = IpcResponse::Palette {
+            outputs: vec![OutputPalette {
+                output: "DP-1".to_string(),
+                colors: vec!["#1a2b3c".to_string()],
+            }],
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        let parsed: IpcResponse = serde_json::from_str(&json).unwrap();
+        if let IpcResponse::Palette { outputs } = parsed {
+            assert_eq!(outputs[0].colors, vec!["#1a2b3c".to_string()]);
+        } else {
+            panic!("Expected Palette response");
+        }
+    }
+
+    #[test]
+    fn test_source_rejected_response() {
+        let response = IpcResponse::SourceRejected {
+            error: SourceError::UnsupportedCodec {
+                path: "/walls/a.mkv".to_string(),
+                codec: "prores".to_string(),
+            },
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"type\":\"source_rejected\""));
+        assert!(json.contains("\"kind\":\"unsupported_codec\""));
+
+        let parsed: IpcResponse = serde_json::from_str(&json).unwrap();
+        if let IpcResponse::SourceRejected { error } = parsed {
+            assert_eq!(
+                error.to_string(),
+                "/walls/a.mkv uses the unsupported codec prores"
+            );
+        } else {
+            panic!("Expected SourceRejected response");
+        }
+    }
+
+    #[test]
+    fn test_query_library_defaults_missing_fields() {
+        let parsed: IpcRequest = serde_json::from_str(
+            r#"{"type":"query_library","filter":{"tags":["rain"],"sort":"rating","limit":20}}"#,
+        )
+        .unwrap();
+        let IpcRequest::QueryLibrary { filter } = parsed else {
+            panic!("Expected QueryLibrary request");
+        };
+        assert_eq!(
+            filter,
+            LibraryFilter {
+                tags: Some(vec!["rain".to_string()]),
+                sort: LibrarySort::Rating,
+                limit: Some(20),
+                ..LibraryFilter::default()
+            }
+        );
+
+        let parsed: IpcRequest = serde_json::from_str(r#"{"type":"query_library"}"#).unwrap();
+        assert!(matches!(
+            parsed,
+            IpcRequest::QueryLibrary { filter } if filter == LibraryFilter::default()
+        ));
+    }
+
+    /// Messages recorded from a protocol version, parsed as `T`
+    fn fixture<T: serde::de::DeserializeOwned>(json: &str) -> Vec<(serde_json::Value, T)> {
+        serde_json::from_str::<Vec<serde_json::Value>>(json)
+            .unwrap()
+            .into_iter()
+            .map(|message| {
+                let parsed = serde_json::from_value(message.clone())
+                    .unwrap_or_else(|e| panic!("{message} does not parse: {e}"));
+                (message, parsed)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_current_fixtures_round_trip() {
+        let requests =
+            fixture::<Envelope<IpcRequest>>(include_str!("../fixtures/v13/requests.json"));
+        let responses =
+            fixture::<Envelope<IpcResponse>>(include_str!("../fixtures/v13/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| request.message != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .any(|(_, response)| response.id == Some(1) && response.elapsed_ms == Some(0)));
+
+        for (message, request) in requests {
+            assert_eq!(serde_json::to_value(&request).unwrap(), message);
+        }
+        for (message, response) in responses {
+            assert_eq!(serde_json::to_value(&response).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn test_unversioned_peers_still_parse() {
+        let requests = fixture::<IpcRequest>(include_str!("../fixtures/v0/requests.json"));
+        let responses = fixture::<IpcResponse>(include_str!("../fixtures/v0/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| *request != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .all(|(_, response)| *response != IpcResponse::Unknown));
+
+        // Daemons from before versioning answer with a bare pong
+        assert!(responses
+            .iter()
+            .any(|(_, response)| *response == IpcResponse::Pong { protocol: 0 }));
+    }
+
+    #[test]
+    fn test_v1_peers_still_parse() {
+        let requests = fixture::<IpcRequest>(include_str!("../fixtures/v1/requests.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| *request != IpcRequest::Unknown));
+
+        // Filters from before folders match every folder
+        assert!(requests.iter().all(|(_, request)| match request {
+            IpcRequest::GetLibrary {
+                filter: Some(filter),
+            }
+            | IpcRequest::QueryLibrary { filter } => filter.folder.is_none(),
+            _ => true,
+        }));
+    }
+
+    #[test]
+    fn test_v2_peers_parse_without_correlation() {
+        let requests =
+            fixture::<Envelope<IpcRequest>>(include_str!("../fixtures/v2/requests.json"));
+        let responses =
+            fixture::<Envelope<IpcResponse>>(include_str!("../fixtures/v2/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| request.id.is_none() && request.message != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .all(|(_, response)| response.id.is_none() && response.elapsed_ms.is_none()));
+
+        // Older peers ignore the id on a request they receive
+        let request: IpcRequest = serde_json::from_str(r#"{"type":"status","id":4}"#).unwrap();
+        assert_eq!(request, IpcRequest::Status);
+    }
+
+    #[test]
+    fn test_v3_peers_still_parse() {
+        let requests =
+            fixture::<Envelope<IpcRequest>>(include_str!("../fixtures/v3/requests.json"));
+        let responses =
+            fixture::<Envelope<IpcResponse>>(include_str!("../fixtures/v3/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| request.message != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .all(|(_, response)| response.message != IpcResponse::Unknown));
+    }
+
+    #[test]
+    fn test_v4_peers_still_parse() {
+        let requests =
+            fixture::<Envelope<IpcRequest>>(include_str!("../fixtures/v4/requests.json"));
+        let responses =
+            fixture::<Envelope<IpcResponse>>(include_str!("../fixtures/v4/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| request.message != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .all(|(_, response)| response.message != IpcResponse::Unknown));
+    }
+
+    #[test]
+    fn test_v5_peers_still_parse() {
+        let requests =
+            fixture::<Envelope<IpcRequest>>(include_str!("../fixtures/v5/requests.json"));
+        let responses =
+            fixture::<Envelope<IpcResponse>>(include_str!("../fixtures/v5/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| request.message != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .all(|(_, response)| response.message != IpcResponse::Unknown));
+    }
+
+    #[test]
+    fn test_v6_peers_still_parse() {
+        let requests =
+            fixture::<Envelope<IpcRequest>>(include_str!("../fixtures/v6/requests.json"));
+        let responses =
+            fixture::<Envelope<IpcResponse>>(include_str!("../fixtures/v6/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| request.message != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .all(|(_, response)| response.message != IpcResponse::Unknown));
+    }
+
+    #[test]
+    fn test_v7_peers_still_parse() {
+        let requests =
+            fixture::<Envelope<IpcRequest>>(include_str!("../fixtures/v7/requests.json"));
+        let responses =
+            fixture::<Envelope<IpcResponse>>(include_str!("../fixtures/v7/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| request.message != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .all(|(_, response)| response.message != IpcResponse::Unknown));
+    }
+
+    #[test]
+    fn test_v8_peers_still_parse() {
+        let requests =
+            fixture::<Envelope<IpcRequest>>(include_str!("../fixtures/v8/requests.json"));
+        let responses =
+            fixture::<Envelope<IpcResponse>>(include_str!("../fixtures/v8/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| request.message != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .all(|(_, response)| response.message != IpcResponse::Unknown));
+    }
+
+    #[test]
+    fn test_v9_peers_still_parse() {
+        let requests =
+            fixture::<Envelope<IpcRequest>>(include_str!("../fixtures/v9/requests.json"));
+        let responses =
+            fixture::<Envelope<IpcResponse>>(include_str!("../fixtures/v9/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| request.message != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .all(|(_, response)| response.message != IpcResponse::Unknown));
+    }
+
+    #[test]
+    fn test_v10_peers_still_parse() {
+        let requests =
+            fixture::<Envelope<IpcRequest>>(include_str!("../fixtures/v10/requests.json"));
+        let responses =
+            fixture::<Envelope<IpcResponse>>(include_str!("../fixtures/v10/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| request.message != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .all(|(_, response)| response.message != IpcResponse::Unknown));
+    }
+
+    #[test]
+    fn test_v11_peers_still_parse() {
+        let requests =
+            fixture::<Envelope<IpcRequest>>(include_str!("../fixtures/v11/requests.json"));
+        let responses =
+            fixture::<Envelope<IpcResponse>>(include_str!("../fixtures/v11/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| request.message != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .all(|(_, response)| response.message != IpcResponse::Unknown));
+    }
+
+    #[test]
+    fn test_v12_peers_still_parse() {
+        let requests =
+            fixture::<Envelope<IpcRequest>>(include_str!("../fixtures/v12/requests.json"));
+        let responses =
+            fixture::<Envelope<IpcResponse>>(include_str!("../fixtures/v12/responses.json"));
+        assert!(requests
+            .iter()
+            .all(|(_, request)| request.message != IpcRequest::Unknown));
+        assert!(responses
+            .iter()
+            .all(|(_, response)| response.message != IpcResponse::Unknown));
+    }
+
+    #[test]
+    fn test_messages_from_newer_peers_degrade() {
+        // New message types parse as Unknown rather than failing
+        let request: IpcRequest =
+            serde_json::from_str(r#"{"type":"record","output":"DP-1"}"#).unwrap();
+        assert_eq!(request, IpcRequest::Unknown);
+        let response: IpcResponse = serde_json::from_str(r#"{"type":"schedule"}"#).unwrap();
+        assert_eq!(response, IpcResponse::Unknown);
+
+        // New fields are ignored by older peers
+        let request: IpcRequest =
+            serde_json::from_str(r#"{"type":"pause","output":"DP-1","fade_ms":300}"#).unwrap();
+        assert_eq!(
+            request,
+            IpcRequest::Pause {
+                output: Some("DP-1".to_string())
+            }
+        );
+
+        // A unit message still parses when a newer peer adds fields to it
+        let request: IpcRequest = serde_json::from_str(r#"{"type":"ping","protocol":2}"#).unwrap();
+        assert_eq!(request, IpcRequest::Ping);
+    }
+}
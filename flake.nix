@@ -0,0 +1,59 @@
+{
+  description = "LWE - Linux dynamic wallpaper shell for Wallpaper Engine content";
+
+  inputs = {
+    nixpkgs.url = "github:NixOS/nixpkgs/nixos-unstable";
+    flake-utils.url = "github:numtide/flake-utils";
+  };
+
+  outputs = { nixpkgs, flake-utils, ... }:
+    flake-utils.lib.eachSystem [ "x86_64-linux" "aarch64-linux" ] (system:
+      let
+        pkgs = nixpkgs.legacyPackages.${system};
+
+        # No openssl here: HTTP goes through rustls (see the `network` feature
+        # of lwe-shell)
+        libraries = with pkgs; [
+          mpv-unwrapped
+          ffmpeg
+          gtk3
+          gtk-layer-shell
+          webkitgtk_4_1
+          libsoup_3
+          glib
+          glib-networking
+          cairo
+          pango
+          gdk-pixbuf
+          librsvg
+          libappindicator-gtk3
+          wayland
+          libxkbcommon
+          libGL
+          libva
+          vulkan-loader
+        ];
+      in
+      {
+        devShells.default = pkgs.mkShell {
+          nativeBuildInputs = with pkgs; [
+            cargo
+            rustc
+            clippy
+            rustfmt
+            cargo-tauri
+            pkg-config
+            wrapGAppsHook3
+            nodejs
+            pnpm
+          ];
+          buildInputs = libraries;
+
+          # mpv, EGL and VAAPI are opened at runtime
+          LD_LIBRARY_PATH = pkgs.lib.makeLibraryPath libraries;
+          GIO_MODULE_DIR = "${pkgs.glib-networking}/lib/gio/modules";
+        };
+
+        formatter = pkgs.nixpkgs-fmt;
+      });
+}
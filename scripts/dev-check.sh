@@ -86,6 +86,7 @@ echo "  - lwe-shell   (active LWE application shell)"
 echo "  - lwe-core        (retained core types and config)"
 echo "  - lwe-engine      (retained Wayland + MPV rendering)"
 echo "  - lwe-library     (retained wallpaper library logic)"
+echo "  - lwe-protocol    (versioned IPC wire types)"
 echo
 echo "  Retired reference crates (not active workspace members):"
 echo "  - wayvid-gui      (retired legacy GUI shell)"
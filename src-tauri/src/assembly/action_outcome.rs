@@ -2,7 +2,7 @@ use crate::action_outcome::InvalidatedPage;
 use crate::action_outcome::{ActionOutcome, AppShellPatch};
 use crate::models::WorkshopPageSnapshot;
 use crate::policies::shared::invalidation_policy::pages_after_workshop_refresh;
-use crate::results::desktop::DesktopApplyResult;
+use crate::results::desktop::{DesktopApplyResult, DesktopBulkApplyResult};
 use crate::results::workshop::WorkshopRefreshResult;
 
 use super::workshop_page::assemble_workshop_page;
@@ -76,6 +76,38 @@ pub fn assemble_desktop_apply_outcome(result: DesktopApplyResult) -> ActionOutco
     }
 }
 
+pub fn assemble_desktop_bulk_apply_outcome(result: DesktopBulkApplyResult) -> ActionOutcome<()> {
+    let mut outcome = ActionOutcome {
+        ok: !result.results.is_empty(),
+        message: None,
+        shell_patch: None,
+        current_update: None,
+        invalidations: Vec::new(),
+    };
+    let mut messages = Vec::new();
+
+    for monitor_outcome in result
+        .results
+        .into_iter()
+        .map(assemble_desktop_apply_outcome)
+    {
+        outcome.ok &= monitor_outcome.ok;
+        messages.extend(monitor_outcome.message);
+        for page in monitor_outcome.invalidations {
+            if !outcome.invalidations.contains(&page) {
+                outcome.invalidations.push(page);
+            }
+        }
+    }
+
+    outcome.message = Some(if messages.is_empty() {
+        "No monitors were available".to_string()
+    } else {
+        messages.join("; ")
+    });
+    outcome
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,4 +164,43 @@ mod tests {
             Some("Applied scene-7 to DISPLAY-1 via lwe_engine_wayland")
         );
     }
+
+    #[test]
+    fn desktop_bulk_apply_outcome_fails_when_any_monitor_fails() {
+        let outcome = assemble_desktop_bulk_apply_outcome(DesktopBulkApplyResult {
+            results: vec![
+                DesktopApplyResult::Applied {
+                    monitor_id: "DISPLAY-1".to_string(),
+                    item_id: "scene-7".to_string(),
+                },
+                DesktopApplyResult::Applied {
+                    monitor_id: "DISPLAY-2".to_string(),
+                    item_id: "scene-7".to_string(),
+                },
+                DesktopApplyResult::MonitorNotFound {
+                    monitor_id: "DISPLAY-3".to_string(),
+                },
+            ],
+        });
+
+        assert!(!outcome.ok);
+        assert_eq!(
+            outcome.message.as_deref(),
+            Some("Applied scene-7 to DISPLAY-1; Applied scene-7 to DISPLAY-2; Monitor DISPLAY-3 was not found")
+        );
+        assert_eq!(outcome.invalidations.len(), 2);
+    }
+
+    #[test]
+    fn desktop_bulk_apply_outcome_treats_no_monitors_as_failure() {
+        let outcome = assemble_desktop_bulk_apply_outcome(DesktopBulkApplyResult {
+            results: Vec::new(),
+        });
+
+        assert!(!outcome.ok);
+        assert_eq!(
+            outcome.message.as_deref(),
+            Some("No monitors were available")
+        );
+    }
 }
@@ -1,8 +1,10 @@
 use crate::models::{
     DesktopMissingMonitorRestore, DesktopMonitorSummary, DesktopPageSnapshot, DesktopRestoreState,
-    RuntimeStatus,
+    MonitorColorModel, ProfileModel, RuntimeStatus, ScheduleSlotModel, ScheduleStatusSnapshot,
 };
 use crate::results::desktop::{DesktopPageResult, DesktopResolvedMonitorAssignment};
+use crate::results::profile::ProfileSummary;
+use crate::results::schedule::ScheduleStatus;
 
 fn runtime_status_for_assignment(
     assignment: Option<&DesktopResolvedMonitorAssignment>,
@@ -35,6 +37,9 @@ pub fn assemble_desktop_page(result: DesktopPageResult) -> DesktopPageSnapshot {
         persistence_issue,
         assignments_available,
         stale,
+        monitor_audio,
+        monitor_color,
+        monitor_applied,
     } = result;
 
     let known_monitor_ids = monitors
@@ -48,6 +53,9 @@ pub fn assemble_desktop_page(result: DesktopPageResult) -> DesktopPageSnapshot {
             .map(|monitor| {
                 let monitor_id = monitor.id;
                 let assignment = resolved_assignments.get(&monitor_id);
+                let audio = monitor_audio.get(&monitor_id).copied().unwrap_or_default();
+                let color = monitor_color.get(&monitor_id).copied().unwrap_or_default();
+                let applied = monitor_applied.get(&monitor_id);
 
                 DesktopMonitorSummary {
                     current_wallpaper_title: match assignment {
@@ -100,6 +108,16 @@ pub fn assemble_desktop_page(result: DesktopPageResult) -> DesktopPageSnapshot {
                         assignment,
                         assignments_available,
                     ),
+                    volume: audio.volume,
+                    muted: audio.muted,
+                    color: MonitorColorModel {
+                        brightness: color.brightness,
+                        contrast: color.contrast,
+                        gamma: color.gamma,
+                        saturation: color.saturation,
+                    },
+                    applied_seconds_ago: applied.map(|applied| applied.ago.as_secs()),
+                    apply_latency_ms: applied.map(|applied| applied.latency.as_millis() as u64),
                 }
             })
             .collect(),
@@ -143,12 +161,44 @@ pub fn assemble_desktop_page(result: DesktopPageResult) -> DesktopPageSnapshot {
     }
 }
 
+pub fn assemble_schedule_status(status: ScheduleStatus) -> ScheduleStatusSnapshot {
+    ScheduleStatusSnapshot {
+        slots: status
+            .slots
+            .into_iter()
+            .map(|slot| ScheduleSlotModel {
+                name: slot.name,
+                start: slot.start,
+                item_id: slot.item_id,
+            })
+            .collect(),
+        active_slot: status.active_slot,
+        next_change: status.next_change,
+        pinned: status.pinned,
+        issue: status.issue,
+    }
+}
+
+pub fn assemble_profiles(profiles: Vec<ProfileSummary>) -> Vec<ProfileModel> {
+    profiles
+        .into_iter()
+        .map(|profile| ProfileModel {
+            name: profile.name,
+            monitor_ids: profile.monitor_ids,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
+    use std::time::Duration;
 
     use super::*;
     use crate::models::RuntimeStatus;
+    use crate::results::desktop::{
+        DesktopMonitorApplied, DesktopMonitorAudio, DesktopMonitorColor,
+    };
     use crate::services::monitor_service::MonitorDescriptor;
 
     #[test]
@@ -178,10 +228,37 @@ mod tests {
             persistence_issue: None,
             assignments_available: true,
             stale: false,
+            monitor_audio: BTreeMap::from([(
+                "DISPLAY-1".to_string(),
+                DesktopMonitorAudio {
+                    volume: 35,
+                    muted: false,
+                },
+            )]),
+            monitor_color: BTreeMap::from([(
+                "DISPLAY-1".to_string(),
+                DesktopMonitorColor {
+                    brightness: 90,
+                    ..DesktopMonitorColor::default()
+                },
+            )]),
+            monitor_applied: BTreeMap::from([(
+                "DISPLAY-1".to_string(),
+                DesktopMonitorApplied {
+                    ago: Duration::from_secs(12),
+                    latency: Duration::from_millis(340),
+                },
+            )]),
         });
 
         assert_eq!(snapshot.monitors.len(), 1);
         assert_eq!(snapshot.monitors[0].display_name, "Primary");
+        assert_eq!(snapshot.monitors[0].volume, 35);
+        assert!(!snapshot.monitors[0].muted);
+        assert_eq!(snapshot.monitors[0].color.brightness, 90);
+        assert_eq!(snapshot.monitors[0].color.saturation, 100);
+        assert_eq!(snapshot.monitors[0].applied_seconds_ago, Some(12));
+        assert_eq!(snapshot.monitors[0].apply_latency_ms, Some(340));
         assert_eq!(snapshot.monitors[0].resolution, "1920x1080");
         assert_eq!(
             snapshot.monitors[0].current_wallpaper_title.as_deref(),
@@ -223,6 +300,9 @@ mod tests {
             persistence_issue: Some("Desktop persistence is not available yet".to_string()),
             assignments_available: false,
             stale: true,
+            monitor_audio: BTreeMap::new(),
+            monitor_color: BTreeMap::new(),
+            monitor_applied: BTreeMap::new(),
         });
 
         assert_eq!(snapshot.monitors.len(), 1);
@@ -251,6 +331,9 @@ mod tests {
             persistence_issue: None,
             assignments_available: true,
             stale: false,
+            monitor_audio: BTreeMap::new(),
+            monitor_color: BTreeMap::new(),
+            monitor_applied: BTreeMap::new(),
         });
 
         assert_eq!(snapshot.monitors[0].runtime_status, RuntimeStatus::Idle);
@@ -280,6 +363,9 @@ mod tests {
             persistence_issue: None,
             assignments_available: true,
             stale: true,
+            monitor_audio: BTreeMap::new(),
+            monitor_color: BTreeMap::new(),
+            monitor_applied: BTreeMap::new(),
         });
 
         assert_eq!(snapshot.monitors[0].runtime_status, RuntimeStatus::Error);
@@ -317,6 +403,9 @@ mod tests {
             persistence_issue: None,
             assignments_available: true,
             stale: false,
+            monitor_audio: BTreeMap::new(),
+            monitor_color: BTreeMap::new(),
+            monitor_applied: BTreeMap::new(),
         });
 
         assert_eq!(
@@ -356,6 +445,9 @@ mod tests {
             persistence_issue: None,
             assignments_available: true,
             stale: false,
+            monitor_audio: BTreeMap::new(),
+            monitor_color: BTreeMap::new(),
+            monitor_applied: BTreeMap::new(),
         });
 
         assert_eq!(snapshot.missing_monitor_restores.len(), 1);
@@ -392,6 +484,9 @@ mod tests {
             persistence_issue: None,
             assignments_available: true,
             stale: true,
+            monitor_audio: BTreeMap::new(),
+            monitor_color: BTreeMap::new(),
+            monitor_applied: BTreeMap::new(),
         });
 
         assert_eq!(snapshot.missing_monitor_restores.len(), 1);
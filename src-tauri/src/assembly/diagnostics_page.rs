@@ -0,0 +1,188 @@
+use crate::models::{
+    DiagnosticsCapabilityModel, DiagnosticsEngineModel, DiagnosticsEngineStatus,
+    DiagnosticsGpuMessageModel, DiagnosticsGpuSeverity, DiagnosticsLogLineModel,
+    DiagnosticsOutputModel, DiagnosticsPageSnapshot, DiagnosticsPlaybackModel,
+};
+use crate::results::diagnostics::{
+    self, DiagnosticsEngineState, DiagnosticsLogLine, DiagnosticsPageResult,
+};
+
+pub fn assemble_diagnostics_page(result: DiagnosticsPageResult) -> DiagnosticsPageSnapshot {
+    let engine = match result.engine {
+        DiagnosticsEngineState::Running {
+            outputs,
+            render_backend,
+            on_battery,
+            power_paused,
+        } => DiagnosticsEngineModel {
+            status: DiagnosticsEngineStatus::Running,
+            issue: None,
+            render_backend: Some(render_backend),
+            on_battery,
+            power_paused,
+            outputs: outputs
+                .into_iter()
+                .map(|output| DiagnosticsOutputModel {
+                    name: output.name,
+                    resolution: output.resolution,
+                    wallpaper_path: output.wallpaper_path,
+                    gpu_messages: output
+                        .gpu_messages
+                        .into_iter()
+                        .map(|message| DiagnosticsGpuMessageModel {
+                            severity: match message.severity {
+                                diagnostics::DiagnosticsGpuSeverity::Error => {
+                                    DiagnosticsGpuSeverity::Error
+                                }
+                                diagnostics::DiagnosticsGpuSeverity::Warning => {
+                                    DiagnosticsGpuSeverity::Warning
+                                }
+                            },
+                            message: message.message,
+                            count: message.count,
+                        })
+                        .collect(),
+                    playback: output.playback.map(|playback| DiagnosticsPlaybackModel {
+                        title: playback.title,
+                        duration_secs: playback.duration_secs,
+                        hwdec: playback.hwdec,
+                        fps: playback.fps,
+                        skip_rate: playback.skip_rate,
+                    }),
+                })
+                .collect(),
+        },
+        DiagnosticsEngineState::NotStarted => DiagnosticsEngineModel {
+            status: DiagnosticsEngineStatus::NotStarted,
+            issue: None,
+            outputs: Vec::new(),
+            render_backend: None,
+            on_battery: false,
+            power_paused: false,
+        },
+        DiagnosticsEngineState::Unavailable { reason } => DiagnosticsEngineModel {
+            status: DiagnosticsEngineStatus::Unavailable,
+            issue: Some(reason),
+            outputs: Vec::new(),
+            render_backend: None,
+            on_battery: false,
+            power_paused: false,
+        },
+    };
+
+    DiagnosticsPageSnapshot {
+        app_version: result.app_version,
+        engine,
+        capabilities: result
+            .capabilities
+            .into_iter()
+            .map(|capability| DiagnosticsCapabilityModel {
+                key: capability.key,
+                available: capability.available,
+                detail: capability.detail,
+            })
+            .collect(),
+        logs: assemble_diagnostics_logs(result.logs),
+    }
+}
+
+pub fn assemble_diagnostics_logs(lines: Vec<DiagnosticsLogLine>) -> Vec<DiagnosticsLogLineModel> {
+    lines
+        .into_iter()
+        .map(|line| DiagnosticsLogLineModel {
+            sequence: line.sequence,
+            timestamp_ms: line.timestamp_ms,
+            level: line.level,
+            target: line.target,
+            message: line.message,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::diagnostics::{
+        DiagnosticsCapability, DiagnosticsGpuMessage, DiagnosticsOutput, DiagnosticsPlayback,
+    };
+
+    #[test]
+    fn diagnostics_page_maps_engine_outputs_capabilities_and_logs() {
+        let snapshot = assemble_diagnostics_page(DiagnosticsPageResult {
+            app_version: "0.1.0".to_string(),
+            engine: DiagnosticsEngineState::Running {
+                outputs: vec![DiagnosticsOutput {
+                    name: "DP-1".to_string(),
+                    resolution: "2560x1440".to_string(),
+                    wallpaper_path: Some("/videos/forest.mp4".to_string()),
+                    gpu_messages: vec![DiagnosticsGpuMessage {
+                        severity: diagnostics::DiagnosticsGpuSeverity::Error,
+                        message: "GL_INVALID_OPERATION (0x0502)".to_string(),
+                        count: 3,
+                    }],
+                    playback: Some(DiagnosticsPlayback {
+                        hwdec: Some("vaapi".to_string()),
+                        fps: Some(59.5),
+                        skip_rate: Some(0.0),
+                        ..Default::default()
+                    }),
+                }],
+                render_backend: "OpenGL".to_string(),
+                on_battery: true,
+                power_paused: false,
+            },
+            capabilities: vec![DiagnosticsCapability {
+                key: "steam".to_string(),
+                available: false,
+                detail: "Steam was not detected".to_string(),
+            }],
+            logs: vec![DiagnosticsLogLine {
+                sequence: 7,
+                timestamp_ms: 1_700_000_000_000,
+                level: "WARN".to_string(),
+                target: "lwe_engine".to_string(),
+                message: "Surface configure timed out".to_string(),
+            }],
+        });
+
+        assert_eq!(snapshot.engine.status, DiagnosticsEngineStatus::Running);
+        assert!(snapshot.engine.issue.is_none());
+        assert!(!snapshot.capabilities[0].available);
+
+        let value = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(value["appVersion"], "0.1.0");
+        assert_eq!(value["engine"]["status"], "running");
+        assert_eq!(
+            value["engine"]["outputs"][0]["wallpaperPath"],
+            "/videos/forest.mp4"
+        );
+        assert_eq!(
+            value["engine"]["outputs"][0]["gpuMessages"][0]["severity"],
+            "error"
+        );
+        assert_eq!(value["engine"]["outputs"][0]["gpuMessages"][0]["count"], 3);
+        assert_eq!(value["engine"]["renderBackend"], "OpenGL");
+        assert_eq!(value["engine"]["onBattery"], true);
+        assert_eq!(value["engine"]["outputs"][0]["playback"]["hwdec"], "vaapi");
+        assert_eq!(value["engine"]["outputs"][0]["playback"]["fps"], 59.5);
+        assert_eq!(value["logs"][0]["timestampMs"], 1_700_000_000_000u64);
+    }
+
+    #[test]
+    fn diagnostics_page_surfaces_unavailable_engine_reason() {
+        let snapshot = assemble_diagnostics_page(DiagnosticsPageResult {
+            app_version: "0.1.0".to_string(),
+            engine: DiagnosticsEngineState::Unavailable {
+                reason: "engine did not answer".to_string(),
+            },
+            capabilities: Vec::new(),
+            logs: Vec::new(),
+        });
+
+        assert_eq!(snapshot.engine.status, DiagnosticsEngineStatus::Unavailable);
+        assert_eq!(
+            snapshot.engine.issue.as_deref(),
+            Some("engine did not answer")
+        );
+    }
+}
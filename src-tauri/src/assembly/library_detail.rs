@@ -1,10 +1,11 @@
 use crate::assembly::compatibility::compatibility_explanation;
-use crate::models::{ItemType, LibraryItemDetail, LibrarySource};
+use crate::models::{ItemType, LibraryItemDetail, LibraryItemMedia, LibrarySource};
 use crate::policies::shared::cover_policy::{cover_art_source, CoverArtSource};
 use crate::results::desktop::DesktopPageResult;
 use crate::results::workshop::AssessedWorkshopCatalogEntry;
 use crate::services::library_service::LibraryService;
-use lwe_library::{WorkshopCatalogEntry, WorkshopProjectType};
+use crate::services::wallpaper_properties_service::WallpaperPropertiesService;
+use lwe_library::{ItemMarks, WorkshopCatalogEntry, WorkshopProjectType};
 
 fn item_type_from_project_type(project_type: WorkshopProjectType) -> ItemType {
     match project_type {
@@ -15,6 +16,14 @@ fn item_type_from_project_type(project_type: WorkshopProjectType) -> ItemType {
     }
 }
 
+fn library_source(entry: &WorkshopCatalogEntry) -> LibrarySource {
+    if LibraryService::is_local(entry) {
+        LibrarySource::Local
+    } else {
+        LibrarySource::Workshop
+    }
+}
+
 fn cover_path(entry: &WorkshopCatalogEntry) -> Option<String> {
     let bundled_cover_path = entry
         .cover_path
@@ -30,25 +39,39 @@ fn cover_path(entry: &WorkshopCatalogEntry) -> Option<String> {
 pub fn assemble_library_detail(
     entry: AssessedWorkshopCatalogEntry,
     desktop: &DesktopPageResult,
+    marks: &ItemMarks,
+    property_values: &serde_json::Map<String, serde_json::Value>,
+    media: LibraryItemMedia,
 ) -> LibraryItemDetail {
     let desktop_status = LibraryService::desktop_status(desktop);
     let assignment_issue = desktop_status.desktop_assignment_issue.clone();
     let monitor_discovery_issue = desktop_status.monitor_discovery_issue.clone();
     let id = entry.entry.library_item_id.clone().unwrap_or_default();
-    let title = entry.entry.title.clone();
+    let original_title = entry.entry.title.clone();
+    let title = marks
+        .title
+        .clone()
+        .unwrap_or_else(|| original_title.clone());
     let item_type = item_type_from_project_type(entry.entry.project_type);
     let cover_path = cover_path(&entry.entry);
     let description = entry.project_metadata.description.clone();
     let tags = entry.project_metadata.tags.clone();
+    let workshop_id =
+        (!LibraryService::is_local(&entry.entry)).then(|| entry.entry.workshop_id.to_string());
     let assigned_monitor_labels = LibraryService::assigned_monitor_labels(desktop, &id);
     let compatibility = compatibility_explanation(&entry.compatibility);
+    let properties = WallpaperPropertiesService::controls(
+        &entry.project_metadata.user_properties,
+        property_values,
+    );
 
     LibraryItemDetail {
         id,
         title,
+        original_title,
         item_type,
         cover_path,
-        source: LibrarySource::Workshop,
+        source: library_source(&entry.entry),
         compatibility,
         monitors_available: desktop_status.monitors_available,
         monitor_discovery_issue,
@@ -57,6 +80,17 @@ pub fn assemble_library_detail(
         assigned_monitor_labels,
         description,
         tags,
+        user_tags: marks.tags.clone(),
+        workshop_id,
+        media,
+        favorite: marks.favorite,
+        rating: marks.rating,
+        properties,
+        shader_path: marks
+            .shader
+            .as_ref()
+            .map(|shader| shader.display().to_string()),
+        lut_path: marks.lut.as_ref().map(|lut| lut.display().to_string()),
     }
 }
 
@@ -103,7 +137,13 @@ mod tests {
                 persistence_issue: Some("Desktop persistence is not available yet".to_string()),
                 assignments_available: false,
                 stale: true,
+                monitor_audio: std::collections::BTreeMap::new(),
+                monitor_color: std::collections::BTreeMap::new(),
+                monitor_applied: std::collections::BTreeMap::new(),
             },
+            &ItemMarks::default(),
+            &serde_json::Map::new(),
+            LibraryItemMedia::default(),
         );
 
         assert_eq!(
@@ -156,9 +196,80 @@ mod tests {
                 persistence_issue: None,
                 assignments_available: true,
                 stale: false,
+                monitor_audio: std::collections::BTreeMap::new(),
+                monitor_color: std::collections::BTreeMap::new(),
+                monitor_applied: std::collections::BTreeMap::new(),
             },
+            &ItemMarks::default(),
+            &serde_json::Map::new(),
+            LibraryItemMedia::default(),
         );
 
         assert_eq!(detail.assigned_monitor_labels, vec!["Primary".to_string()]);
     }
+
+    #[test]
+    fn library_detail_prefers_the_user_title_and_keeps_workshop_origin() {
+        let detail = assemble_library_detail(
+            AssessedWorkshopCatalogEntry {
+                entry: WorkshopCatalogEntry {
+                    workshop_id: 7,
+                    title: "Forest Scene".to_string(),
+                    project_type: WorkshopProjectType::Scene,
+                    project_dir: std::path::PathBuf::from("/tmp/7"),
+                    cover_path: None,
+                    sync_state: WorkshopSyncState::Synced,
+                    supported_first_release: true,
+                    library_item_id: Some("scene-7".to_string()),
+                },
+                compatibility: CompatibilityDecision {
+                    level: CompatibilityLevel::FullySupported,
+                    reason: CompatibilityReason::ReadyForLibrary,
+                    next_step: CompatibilityNextStep::None,
+                },
+                project_metadata: WorkshopProjectMetadata::default(),
+            },
+            &DesktopPageResult {
+                monitors: Vec::new(),
+                assignments: std::collections::BTreeMap::new(),
+                resolved_assignments: std::collections::BTreeMap::new(),
+                library_item_assignments: std::collections::BTreeMap::new(),
+                restore_issues: Vec::new(),
+                monitors_available: true,
+                monitor_discovery_issue: None,
+                persistence_issue: None,
+                assignments_available: true,
+                stale: false,
+                monitor_audio: std::collections::BTreeMap::new(),
+                monitor_color: std::collections::BTreeMap::new(),
+                monitor_applied: std::collections::BTreeMap::new(),
+            },
+            &ItemMarks {
+                title: Some("Misty Forest".to_string()),
+                tags: vec!["nature".to_string()],
+                shader: Some("/home/user/shaders/crt.glsl".into()),
+                lut: Some("/home/user/luts/teal.cube".into()),
+                ..ItemMarks::default()
+            },
+            &serde_json::Map::new(),
+            LibraryItemMedia {
+                file_size: Some(2048),
+                ..LibraryItemMedia::default()
+            },
+        );
+
+        assert_eq!(detail.title, "Misty Forest");
+        assert_eq!(detail.original_title, "Forest Scene");
+        assert_eq!(detail.user_tags, vec!["nature".to_string()]);
+        assert_eq!(detail.workshop_id.as_deref(), Some("7"));
+        assert_eq!(detail.media.file_size, Some(2048));
+        assert_eq!(
+            detail.shader_path.as_deref(),
+            Some("/home/user/shaders/crt.glsl")
+        );
+        assert_eq!(
+            detail.lut_path.as_deref(),
+            Some("/home/user/luts/teal.cube")
+        );
+    }
 }
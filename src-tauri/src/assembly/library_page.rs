@@ -6,7 +6,8 @@ use crate::results::desktop::DesktopPageResult;
 use crate::results::library::LibraryProjection;
 use crate::results::workshop::AssessedWorkshopCatalogEntry;
 use crate::services::library_service::LibraryService;
-use lwe_library::{WorkshopCatalogEntry, WorkshopProjectType};
+use lwe_library::{ItemMarks, WorkshopCatalogEntry, WorkshopProjectType};
+use std::collections::HashMap;
 
 fn item_type_from_project_type(project_type: WorkshopProjectType) -> ItemType {
     match project_type {
@@ -17,6 +18,14 @@ fn item_type_from_project_type(project_type: WorkshopProjectType) -> ItemType {
     }
 }
 
+fn library_source(entry: &WorkshopCatalogEntry) -> LibrarySource {
+    if LibraryService::is_local(entry) {
+        LibrarySource::Local
+    } else {
+        LibrarySource::Workshop
+    }
+}
+
 fn cover_path(entry: &WorkshopCatalogEntry) -> Option<String> {
     let bundled_cover_path = entry
         .cover_path
@@ -29,7 +38,10 @@ fn cover_path(entry: &WorkshopCatalogEntry) -> Option<String> {
     }
 }
 
-fn assemble_library_summary(entry: AssessedWorkshopCatalogEntry) -> LibraryItemSummary {
+fn assemble_library_summary(
+    entry: AssessedWorkshopCatalogEntry,
+    marks: &ItemMarks,
+) -> LibraryItemSummary {
     let age_rating = entry
         .project_metadata
         .inferred_age_rating
@@ -43,13 +55,19 @@ fn assemble_library_summary(entry: AssessedWorkshopCatalogEntry) -> LibraryItemS
 
     LibraryItemSummary {
         id: entry.entry.library_item_id.clone().unwrap_or_default(),
-        title: entry.entry.title.clone(),
+        title: marks
+            .title
+            .clone()
+            .unwrap_or_else(|| entry.entry.title.clone()),
         item_type: item_type_from_project_type(entry.entry.project_type),
         cover_path: cover_path(&entry.entry),
         age_rating,
-        source: LibrarySource::Workshop,
+        source: library_source(&entry.entry),
         compatibility: compatibility_summary(&entry.compatibility),
-        favorite: false,
+        favorite: marks.favorite,
+        rating: marks.rating,
+        use_count: marks.use_count,
+        last_used_at: marks.last_used.map(|time| time.to_rfc3339()),
         assigned_monitor_labels: Vec::new(),
     }
 }
@@ -57,6 +75,7 @@ fn assemble_library_summary(entry: AssessedWorkshopCatalogEntry) -> LibraryItemS
 pub fn assemble_library_page(
     result: LibraryProjection,
     desktop: &DesktopPageResult,
+    marks: &HashMap<String, ItemMarks>,
 ) -> LibraryPageSnapshot {
     let desktop_status = LibraryService::desktop_status(desktop);
     let stale =
@@ -68,7 +87,10 @@ pub fn assemble_library_page(
             .into_iter()
             .map(|entry| {
                 let item_id = entry.entry.library_item_id.clone().unwrap_or_default();
-                let mut summary = assemble_library_summary(entry);
+                let mut summary = assemble_library_summary(
+                    entry,
+                    marks.get(&item_id).unwrap_or(&ItemMarks::default()),
+                );
                 summary.assigned_monitor_labels =
                     LibraryService::assigned_monitor_labels(desktop, &item_id);
                 summary
@@ -134,7 +156,11 @@ mod tests {
                 persistence_issue: Some("Desktop persistence is not available yet".to_string()),
                 assignments_available: false,
                 stale: true,
+                monitor_audio: std::collections::BTreeMap::new(),
+                monitor_color: std::collections::BTreeMap::new(),
+                monitor_applied: std::collections::BTreeMap::new(),
             },
+            &HashMap::new(),
         );
 
         assert_eq!(snapshot.items.len(), 1);
@@ -173,7 +199,11 @@ mod tests {
                 persistence_issue: None,
                 assignments_available: true,
                 stale: false,
+                monitor_audio: std::collections::BTreeMap::new(),
+                monitor_color: std::collections::BTreeMap::new(),
+                monitor_applied: std::collections::BTreeMap::new(),
             },
+            &HashMap::new(),
         );
 
         assert_eq!(
@@ -181,4 +211,55 @@ mod tests {
             vec!["Primary".to_string()]
         );
     }
+
+    #[test]
+    fn library_page_carries_title_favorite_rating_and_last_use() {
+        let marks = HashMap::from([(
+            "scene-7".to_string(),
+            ItemMarks {
+                favorite: true,
+                rating: 4,
+                use_count: 3,
+                // The Unix epoch
+                last_used: Some(Default::default()),
+                title: Some("Misty Forest".to_string()),
+                tags: Vec::new(),
+                shader: None,
+                lut: None,
+            },
+        )]);
+
+        let snapshot = assemble_library_page(
+            LibraryProjection {
+                entries: vec![assessed_entry()],
+                source_catalog_count: 1,
+            },
+            &DesktopPageResult {
+                monitors: Vec::new(),
+                assignments: std::collections::BTreeMap::new(),
+                resolved_assignments: std::collections::BTreeMap::new(),
+                library_item_assignments: std::collections::BTreeMap::new(),
+                restore_issues: Vec::new(),
+                monitors_available: true,
+                monitor_discovery_issue: None,
+                persistence_issue: None,
+                assignments_available: true,
+                stale: false,
+                monitor_audio: std::collections::BTreeMap::new(),
+                monitor_color: std::collections::BTreeMap::new(),
+                monitor_applied: std::collections::BTreeMap::new(),
+            },
+            &marks,
+        );
+
+        let item = &snapshot.items[0];
+        assert_eq!(item.title, "Misty Forest");
+        assert!(item.favorite);
+        assert_eq!(item.rating, 4);
+        assert_eq!(item.use_count, 3);
+        assert_eq!(
+            item.last_used_at.as_deref(),
+            Some("1970-01-01T00:00:00+00:00")
+        );
+    }
 }
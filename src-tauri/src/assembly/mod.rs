@@ -2,8 +2,10 @@ pub mod action_outcome;
 pub mod app_shell;
 pub mod compatibility;
 pub mod desktop_page;
+pub mod diagnostics_page;
 pub mod library_detail;
 pub mod library_page;
+pub mod playlist_page;
 pub mod settings_page;
 pub mod workshop_detail;
 pub mod workshop_page;
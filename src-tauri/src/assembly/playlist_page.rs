@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+
+use crate::models::{
+    CollectionItemModel, CollectionModel, PlaylistEntryModel, PlaylistFolderModel,
+    PlaylistLibraryItemModel, PlaylistMonitorModel, PlaylistPageSnapshot, PlaylistRotationModel,
+    PlaylistSummary, PlaylistTransition,
+};
+use crate::results::playlist::PlaylistPageResult;
+
+pub fn assemble_playlist_page(result: PlaylistPageResult) -> PlaylistPageSnapshot {
+    let PlaylistPageResult {
+        playlists,
+        collections,
+        library_items,
+        library_issue,
+        monitors,
+        monitor_discovery_issue,
+        rotations,
+    } = result;
+
+    let item_titles = library_items
+        .iter()
+        .map(|item| (item.id.as_str(), item.title.as_str()))
+        .collect::<BTreeMap<_, _>>();
+
+    let rotations = rotations
+        .into_iter()
+        .map(|rotation| PlaylistRotationModel {
+            current_item_title: rotation
+                .current_item_id
+                .as_deref()
+                .and_then(|item_id| item_titles.get(item_id))
+                .map(|title| title.to_string()),
+            monitor_id: rotation.monitor_id,
+            playlist_id: rotation.playlist_id,
+            current_item_id: rotation.current_item_id,
+            paused: rotation.paused,
+        })
+        .collect();
+
+    let playlists = playlists
+        .into_iter()
+        .map(|playlist| PlaylistSummary {
+            id: playlist.id,
+            transition: PlaylistTransition::from_stored(&playlist.transition),
+            items: playlist
+                .items
+                .into_iter()
+                .map(|entry| PlaylistEntryModel {
+                    item_title: item_titles
+                        .get(entry.item_id.as_str())
+                        .map(|title| title.to_string()),
+                    item_id: entry.item_id,
+                    duration_secs: entry.duration_secs,
+                })
+                .collect(),
+            name: playlist.name,
+            shuffle: playlist.shuffle,
+            default_duration_secs: playlist.default_duration_secs,
+            monitor_ids: playlist.outputs,
+            folder: playlist.folder.map(|folder| PlaylistFolderModel {
+                path: folder.path.to_string_lossy().into_owned(),
+                pattern: folder.pattern,
+                image_duration_secs: folder.image_duration_secs,
+            }),
+        })
+        .collect();
+
+    let collections = collections
+        .into_iter()
+        .map(|collection| CollectionModel {
+            id: collection.id,
+            name: collection.name,
+            items: collection
+                .item_ids
+                .into_iter()
+                .map(|item_id| CollectionItemModel {
+                    item_title: item_titles
+                        .get(item_id.as_str())
+                        .map(|title| title.to_string()),
+                    item_id,
+                })
+                .collect(),
+        })
+        .collect();
+
+    PlaylistPageSnapshot {
+        playlists,
+        collections,
+        library_items: library_items
+            .into_iter()
+            .map(|item| PlaylistLibraryItemModel {
+                id: item.id,
+                title: item.title,
+            })
+            .collect(),
+        stale: library_issue.is_some() || monitor_discovery_issue.is_some(),
+        library_issue,
+        monitors_available: monitor_discovery_issue.is_none(),
+        monitors: monitors
+            .into_iter()
+            .map(|monitor| PlaylistMonitorModel {
+                display_name: format!("{} ({})", monitor.name, monitor.resolution),
+                monitor_id: monitor.id,
+            })
+            .collect(),
+        monitor_discovery_issue,
+        rotations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::collection::CollectionSummary;
+    use crate::results::playlist::{PlaylistLibraryItem, PlaylistRotationStatus};
+    use crate::services::monitor_service::MonitorDescriptor;
+    use lwe_library::{Playlist, PlaylistEntry};
+
+    #[test]
+    fn playlist_page_resolves_item_titles_and_flags_missing_items() {
+        let snapshot = assemble_playlist_page(PlaylistPageResult {
+            playlists: vec![Playlist {
+                id: 4,
+                name: "Evening".to_string(),
+                shuffle: true,
+                transition: "fade".to_string(),
+                default_duration_secs: 300,
+                items: vec![
+                    PlaylistEntry {
+                        item_id: "scene-7".to_string(),
+                        duration_secs: Some(60),
+                    },
+                    PlaylistEntry {
+                        item_id: "scene-removed".to_string(),
+                        duration_secs: None,
+                    },
+                ],
+                outputs: vec!["DP-1".to_string()],
+                folder: None,
+            }],
+            collections: vec![CollectionSummary {
+                id: 2,
+                name: "Nature".to_string(),
+                item_ids: vec!["scene-7".to_string(), "local-gone".to_string()],
+            }],
+            library_items: vec![PlaylistLibraryItem {
+                id: "scene-7".to_string(),
+                title: "Forest Scene".to_string(),
+            }],
+            library_issue: None,
+            monitors: vec![MonitorDescriptor {
+                id: "DP-1".to_string(),
+                backend_output_id: "DP-1".to_string(),
+                name: "Primary".to_string(),
+                resolution: "2560x1440".to_string(),
+            }],
+            monitor_discovery_issue: None,
+            rotations: vec![PlaylistRotationStatus {
+                monitor_id: "DP-1".to_string(),
+                playlist_id: 4,
+                current_item_id: Some("scene-7".to_string()),
+                paused: true,
+            }],
+        });
+
+        let playlist = &snapshot.playlists[0];
+        assert_eq!(playlist.transition, PlaylistTransition::Fade);
+        assert_eq!(
+            playlist.items[0].item_title.as_deref(),
+            Some("Forest Scene")
+        );
+        assert!(playlist.items[1].item_title.is_none());
+        let collection = &snapshot.collections[0];
+        assert_eq!(
+            collection.items[0].item_title.as_deref(),
+            Some("Forest Scene")
+        );
+        assert!(collection.items[1].item_title.is_none());
+        assert_eq!(snapshot.monitors[0].display_name, "Primary (2560x1440)");
+        assert!(snapshot.monitors_available);
+        assert!(!snapshot.stale);
+        assert_eq!(
+            snapshot.rotations[0].current_item_title.as_deref(),
+            Some("Forest Scene")
+        );
+
+        let value = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(value["playlists"][0]["monitorIds"][0], "DP-1");
+        assert_eq!(value["playlists"][0]["transition"], "fade");
+        assert_eq!(value["rotations"][0]["paused"], true);
+        assert_eq!(value["collections"][0]["items"][1]["itemId"], "local-gone");
+    }
+}
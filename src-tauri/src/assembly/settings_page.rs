@@ -1,4 +1,6 @@
-use crate::models::SettingsPageSnapshot;
+use lwe_core::{ConfigIssue, IssueSeverity};
+
+use crate::models::{ConfigIssueSeverity, SettingsConfigIssue, SettingsPageSnapshot};
 use crate::services::settings_service::SettingsPageData;
 
 pub(crate) fn assemble_settings_page(result: SettingsPageData) -> SettingsPageSnapshot {
@@ -11,8 +13,26 @@ pub(crate) fn assemble_settings_page(result: SettingsPageData) -> SettingsPageSn
         workshop_query: result.workshop_query,
         workshop_age_ratings: result.workshop_age_ratings,
         workshop_item_types: result.workshop_item_types,
+        confirm_apply_to_all: result.confirm_apply_to_all,
         steam_required: result.steam_required,
         steam_status_message: result.steam_status_message,
         stale: result.stale,
+        config_issues: result
+            .config_issues
+            .into_iter()
+            .map(assemble_config_issue)
+            .collect(),
+    }
+}
+
+fn assemble_config_issue(issue: ConfigIssue) -> SettingsConfigIssue {
+    SettingsConfigIssue {
+        severity: match issue.severity {
+            IssueSeverity::Error => ConfigIssueSeverity::Error,
+            IssueSeverity::Warning => ConfigIssueSeverity::Warning,
+        },
+        line: issue.line,
+        key: issue.key,
+        message: issue.message,
     }
 }
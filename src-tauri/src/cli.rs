@@ -0,0 +1,2609 @@
+mod i18n;
+
+use std::path::{Path, PathBuf};
+
+use std::time::Duration;
+
+use lwe_core::{
+    IpcRequest, IpcResponse, IssueSeverity, LibraryFilter, OutputStats, OutputStatus, ProcessStats,
+};
+use lwe_engine::{capture_frames, render_nodes, CaptureConfig, LayoutMode, RenderNode, Transport};
+use lwe_library::LibraryDatabase;
+
+use crate::results::migration::MigrationSource;
+use crate::results::workshop::WorkshopItemDetails;
+use crate::services::config_validation_service::ConfigValidationService;
+use crate::services::control_service::ControlService;
+use crate::services::desktop_service::DesktopService;
+use crate::services::diagnostics_service::DiagnosticsService;
+use crate::services::doctor_service::DoctorService;
+use crate::services::item_open_service::ItemOpenService;
+use crate::services::migration_service::MigrationService;
+use crate::services::scene_extract_service::SceneExtractService;
+use crate::services::service_install_service::{
+    CompositorTarget, ServiceInstallService, SYSTEMD_UNIT_NAME,
+};
+use crate::services::thumbnail_cache_service::ThumbnailCacheService;
+use crate::services::wallpaper_convert_service::{ConvertOperation, WallpaperConvertService};
+use crate::services::workshop_service::WorkshopService;
+use i18n::{format_count, format_date, format_size, tr, trf};
+
+const INSTALL_SERVICE_USAGE: &str = "Usage: lwe install-service [--hyprland|--sway|--niri]";
+const CHECK_USAGE: &str = "Usage: lwe check [--json]\n       lwe check --gpus";
+const DOCTOR_USAGE: &str = "Usage: lwe doctor [--fix]";
+const OPEN_USAGE: &str = "Usage: lwe open <id> [--player]";
+// Hidden developer command; its messages are not translated.
+const CAPTURE_USAGE: &str =
+    "Usage: lwe capture <source> <out-dir> --size <width>x<height> --at <seconds>[,<seconds>...] [--layout <mode>]";
+const WORKSHOP_USAGE: &str = "Usage: lwe workshop info <id>\n       lwe workshop search <id> [<id>...]\n       lwe workshop extract <dir|pkg> --out <dir>";
+const LIBRARY_USAGE: &str = "Usage: lwe library query [<text>] [--tag <tag>]... [--type <type>] [--folder <dir>] [--favorites] [--min-rating <0-5>] [--sort <order>] [--offset <n>] [--limit <n>]";
+const RANDOM_USAGE: &str = "Usage: lwe random [<text>] [--tag <tag>]... [--type <type>] [--folder <dir>] [--favorites] [--min-rating <0-5>] [--output <output>]";
+const PROFILE_USAGE: &str =
+    "Usage: lwe profile save <name>\n       lwe profile load <name>\n       lwe profile list";
+const COLLECTION_USAGE: &str =
+    "Usage: lwe collection list\n       lwe collection apply <name> [--output <output>]";
+const PLAYLIST_USAGE: &str =
+    "Usage: lwe playlist next [--output <output>]\n       lwe playlist prev [--output <output>]";
+const PLAYBACK_USAGE: &str = "Usage: lwe seek <[+|-]seconds> [--output <output>]\n       lwe rate <speed> [--output <output>]\n       lwe frame-step [--back] [--output <output>]\n       lwe loop <start> <end> [--output <output>]\n       lwe loop off [--output <output>]";
+const COLOR_USAGE: &str = "Usage: lwe color --output <output> [--brightness <0-2>] [--contrast <0-2>] [--gamma <0-2>] [--saturation <0-2>]\n       lwe color --output <output> --reset";
+const STATUS_USAGE: &str = "Usage: lwe status [--json]";
+const SNAPSHOT_USAGE: &str = "Usage: lwe snapshot --output <output> --file <image>";
+const MPV_USAGE: &str = "Usage: lwe mpv set <output> <property> <value>";
+const CACHE_USAGE: &str = "Usage: lwe cache prune\n       lwe cache clear";
+const STATS_USAGE: &str = "Usage: lwe stats [--watch] [--json]";
+const CONFIG_USAGE: &str = "Usage: lwe config validate [<path>]";
+const MIGRATE_USAGE: &str = "Usage: lwe migrate [mpvpaper|swww|hyprpaper] [<path>] [--dry-run]";
+const TOOLS_USAGE: &str = "Usage: lwe tools rotate <in> <out> --for-output <output> [--ccw]\n       lwe tools crop <in> <out> --for-output <output>";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    InstallService {
+        compositor: Option<CompositorTarget>,
+    },
+    Convert {
+        operation: ConvertOperation,
+        input: PathBuf,
+        output: PathBuf,
+        for_output: String,
+    },
+    /// Print every capability probe, as JSON for bug reports
+    Check {
+        json: bool,
+    },
+    CheckGpus,
+    Doctor {
+        fix: bool,
+    },
+    QueryLibrary {
+        filter: LibraryFilter,
+    },
+    /// Ask the running app to show a random library match
+    Random {
+        filter: LibraryFilter,
+        output: Option<String>,
+    },
+    /// Save what every monitor shows in the running app as a named profile
+    ProfileSave {
+        name: String,
+    },
+    /// Switch the running app to a saved profile
+    ProfileLoad {
+        name: String,
+    },
+    ProfileList,
+    CollectionList,
+    /// Rotate a library collection as a playlist in the running app
+    CollectionApply {
+        name: String,
+        output: Option<String>,
+    },
+    /// Step the playlist rotating in the running app forward or back
+    PlaylistStep {
+        backward: bool,
+        output: Option<String>,
+    },
+    /// Show what the running app plays on each output, and how well
+    Status {
+        json: bool,
+    },
+    /// Show render, decode, CPU and memory stats of the running app, once or
+    /// refreshed every second
+    Stats {
+        watch: bool,
+        json: bool,
+    },
+    /// Check a settings or config file for unknown keys and bad values;
+    /// `None` checks the app's own settings file
+    ValidateConfig {
+        path: Option<PathBuf>,
+    },
+    /// Import wallpapers set up in mpvpaper, swww or hyprpaper; without a
+    /// source every tool's usual config is read
+    Migrate {
+        source: Option<MigrationSource>,
+        path: Option<PathBuf>,
+        dry_run: bool,
+    },
+    /// Seek, change speed, step a frame or loop part of the video in the running app
+    Transport {
+        output: Option<String>,
+        transport: Transport,
+    },
+    /// Change the color correction of an output in the running app; values
+    /// left out keep their current setting
+    Color {
+        output: String,
+        brightness: Option<f32>,
+        contrast: Option<f32>,
+        gamma: Option<f32>,
+        saturation: Option<f32>,
+    },
+    /// Save the frame an output shows in the running app as an image
+    Snapshot {
+        output: String,
+        file: PathBuf,
+    },
+    /// Set a raw mpv property on an output in the running app
+    MpvSet {
+        output: String,
+        property: String,
+        value: String,
+    },
+    /// Trim the thumbnail cache to its budget, or empty it
+    Cache {
+        clear: bool,
+    },
+    /// Reveal a library item in the file manager, or play it externally
+    Open {
+        item_id: String,
+        player: bool,
+    },
+    WorkshopInfo {
+        workshop_id: String,
+    },
+    /// Unpack a scene project's package and convert its textures
+    WorkshopExtract {
+        source: PathBuf,
+        out: PathBuf,
+    },
+    WorkshopSearch {
+        workshop_ids: Vec<String>,
+    },
+    /// Hidden: render golden frames for visual regression tests
+    Capture {
+        source: PathBuf,
+        out_dir: PathBuf,
+        config: CaptureConfig,
+    },
+}
+
+/// Parses the arguments after the executable name. `None` means the GUI should start.
+pub fn parse_cli_command(args: &[String]) -> Result<Option<CliCommand>, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(None);
+    };
+
+    match command.as_str() {
+        "install-service" => {
+            let mut compositor = None;
+            for flag in rest {
+                let Some(target) = CompositorTarget::from_flag(flag) else {
+                    return Err(with_usage(
+                        trf("Unknown install-service option {}", &[flag]),
+                        INSTALL_SERVICE_USAGE,
+                    ));
+                };
+                if compositor.replace(target).is_some() {
+                    return Err(with_usage(
+                        tr("Only one compositor can be selected").to_string(),
+                        INSTALL_SERVICE_USAGE,
+                    ));
+                }
+            }
+            Ok(Some(CliCommand::InstallService { compositor }))
+        }
+        "tools" => parse_tools_command(rest).map(Some),
+        "check" => match rest {
+            [] => Ok(Some(CliCommand::Check { json: false })),
+            [flag] if flag == "--json" => Ok(Some(CliCommand::Check { json: true })),
+            [flag] if flag == "--gpus" => Ok(Some(CliCommand::CheckGpus)),
+            [flag, ..] => Err(with_usage(
+                trf("Unknown check option {}", &[flag]),
+                CHECK_USAGE,
+            )),
+        },
+        "doctor" => match rest {
+            [] => Ok(Some(CliCommand::Doctor { fix: false })),
+            [flag] if flag == "--fix" => Ok(Some(CliCommand::Doctor { fix: true })),
+            [flag, ..] => Err(with_usage(
+                trf("Unknown doctor option {}", &[flag]),
+                DOCTOR_USAGE,
+            )),
+        },
+        "library" => match rest.split_first() {
+            Some((action, rest)) if action == "query" => parse_library_query(rest).map(Some),
+            Some((action, _)) => Err(with_usage(
+                trf("Unknown library command {}", &[action]),
+                LIBRARY_USAGE,
+            )),
+            None => Err(tr(LIBRARY_USAGE).to_string()),
+        },
+        "random" => parse_random_command(rest).map(Some),
+        "profile" => parse_profile_command(rest).map(Some),
+        "collection" => parse_collection_command(rest).map(Some),
+        "playlist" => parse_playlist_command(rest).map(Some),
+        "status" => match rest {
+            [] => Ok(Some(CliCommand::Status { json: false })),
+            [flag] if flag == "--json" => Ok(Some(CliCommand::Status { json: true })),
+            [flag, ..] => Err(with_usage(
+                trf("Unknown status option {}", &[flag]),
+                STATUS_USAGE,
+            )),
+        },
+        "stats" => parse_stats_command(rest).map(Some),
+        "config" => match rest {
+            [action] if action == "validate" => Ok(Some(CliCommand::ValidateConfig { path: None })),
+            [action, path] if action == "validate" && !path.starts_with('-') => {
+                Ok(Some(CliCommand::ValidateConfig {
+                    path: Some(PathBuf::from(path)),
+                }))
+            }
+            [action, option, ..] if action == "validate" => Err(with_usage(
+                trf("Unknown config validate option {}", &[option]),
+                CONFIG_USAGE,
+            )),
+            [action, ..] => Err(with_usage(
+                trf("Unknown config command {}", &[action]),
+                CONFIG_USAGE,
+            )),
+            [] => Err(tr(CONFIG_USAGE).to_string()),
+        },
+        "migrate" => parse_migrate_command(rest).map(Some),
+        "seek" | "rate" | "frame-step" | "loop" => parse_transport_command(command, rest).map(Some),
+        "color" => parse_color_command(rest).map(Some),
+        "snapshot" => parse_snapshot_command(rest).map(Some),
+        "mpv" => match rest {
+            [action, output, property, value] if action == "set" => Ok(Some(CliCommand::MpvSet {
+                output: output.clone(),
+                property: property.clone(),
+                value: value.clone(),
+            })),
+            [action, ..] if action == "set" => Err(with_usage(
+                tr("mpv set needs an output, a property and a value").to_string(),
+                MPV_USAGE,
+            )),
+            [action, ..] => Err(with_usage(
+                trf("Unknown mpv command {}", &[action]),
+                MPV_USAGE,
+            )),
+            [] => Err(tr(MPV_USAGE).to_string()),
+        },
+        "cache" => match rest {
+            [action] if action == "prune" => Ok(Some(CliCommand::Cache { clear: false })),
+            [action] if action == "clear" => Ok(Some(CliCommand::Cache { clear: true })),
+            [action, option, ..] if action == "prune" || action == "clear" => Err(with_usage(
+                trf("Unknown cache {} option {}", &[action, option]),
+                CACHE_USAGE,
+            )),
+            [action, ..] => Err(with_usage(
+                trf("Unknown cache command {}", &[action]),
+                CACHE_USAGE,
+            )),
+            [] => Err(tr(CACHE_USAGE).to_string()),
+        },
+        "open" => parse_open_command(rest).map(Some),
+        "workshop" => parse_workshop_command(rest).map(Some),
+        "capture" => parse_capture_command(rest).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// A translated error followed by the translated usage text
+fn with_usage(message: String, usage: &'static str) -> String {
+    format!("{message}\n{}", tr(usage))
+}
+
+fn parse_tools_command(args: &[String]) -> Result<CliCommand, String> {
+    let Some((tool, rest)) = args.split_first() else {
+        return Err(tr(TOOLS_USAGE).to_string());
+    };
+    let mut operation = match tool.as_str() {
+        "rotate" => ConvertOperation::Rotate {
+            counterclockwise: false,
+        },
+        "crop" => ConvertOperation::Crop,
+        _ => return Err(with_usage(trf("Unknown tool {}", &[tool]), TOOLS_USAGE)),
+    };
+
+    let mut paths = Vec::new();
+    let mut for_output = None;
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        match (arg.as_str(), &mut operation) {
+            ("--for-output", _) => {
+                let Some(name) = rest.next() else {
+                    return Err(with_usage(
+                        tr("--for-output needs an output name").to_string(),
+                        TOOLS_USAGE,
+                    ));
+                };
+                for_output = Some(name.clone());
+            }
+            ("--ccw", ConvertOperation::Rotate { counterclockwise }) => *counterclockwise = true,
+            (flag, _) if flag.starts_with("--") => {
+                return Err(with_usage(
+                    trf("Unknown tools {} option {}", &[tool, &flag]),
+                    TOOLS_USAGE,
+                ));
+            }
+            (path, _) => paths.push(PathBuf::from(path)),
+        }
+    }
+
+    let [input, output] = <[PathBuf; 2]>::try_from(paths).map_err(|_| {
+        with_usage(
+            trf("tools {} needs an input and an output path", &[tool]),
+            TOOLS_USAGE,
+        )
+    })?;
+    let for_output = for_output
+        .ok_or_else(|| with_usage(trf("tools {} needs --for-output", &[tool]), TOOLS_USAGE))?;
+
+    Ok(CliCommand::Convert {
+        operation,
+        input,
+        output,
+        for_output,
+    })
+}
+
+fn parse_library_query(args: &[String]) -> Result<CliCommand, String> {
+    let filter = parse_library_filter(args, "Unknown library query option {}", LIBRARY_USAGE)?;
+    Ok(CliCommand::QueryLibrary { filter })
+}
+
+/// `lwe random` takes the `library query` filters plus the output to show the pick on
+fn parse_random_command(args: &[String]) -> Result<CliCommand, String> {
+    let mut output = None;
+    let mut filter_args = Vec::new();
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        if arg == "--output" {
+            let value = rest
+                .next()
+                .ok_or_else(|| with_usage(trf("{} needs a value", &[arg]), RANDOM_USAGE))?;
+            output = Some(value.clone());
+        } else {
+            filter_args.push(arg.clone());
+        }
+    }
+
+    let filter = parse_library_filter(&filter_args, "Unknown random option {}", RANDOM_USAGE)?;
+    Ok(CliCommand::Random { filter, output })
+}
+
+fn parse_profile_command(args: &[String]) -> Result<CliCommand, String> {
+    match args {
+        [action] if action == "list" => Ok(CliCommand::ProfileList),
+        [action, name] if action == "save" => Ok(CliCommand::ProfileSave { name: name.clone() }),
+        [action, name] if action == "load" => Ok(CliCommand::ProfileLoad { name: name.clone() }),
+        [action, ..] if matches!(action.as_str(), "list" | "save" | "load") => Err(with_usage(
+            trf("profile {} takes a profile name", &[action]),
+            PROFILE_USAGE,
+        )),
+        [action, ..] => Err(with_usage(
+            trf("Unknown profile command {}", &[action]),
+            PROFILE_USAGE,
+        )),
+        [] => Err(tr(PROFILE_USAGE).to_string()),
+    }
+}
+
+fn parse_playlist_command(args: &[String]) -> Result<CliCommand, String> {
+    let (backward, rest) = match args.split_first() {
+        Some((action, rest)) if action == "next" => (false, rest),
+        Some((action, rest)) if action == "prev" => (true, rest),
+        Some((action, _)) => {
+            return Err(with_usage(
+                trf("Unknown playlist command {}", &[action]),
+                PLAYLIST_USAGE,
+            ))
+        }
+        None => return Err(tr(PLAYLIST_USAGE).to_string()),
+    };
+
+    let output = match rest {
+        [] => None,
+        [flag, value] if flag == "--output" => Some(value.clone()),
+        [flag] if flag == "--output" => {
+            return Err(with_usage(trf("{} needs a value", &[flag]), PLAYLIST_USAGE))
+        }
+        [option, ..] => {
+            return Err(with_usage(
+                trf("Unknown playlist option {}", &[option]),
+                PLAYLIST_USAGE,
+            ))
+        }
+    };
+
+    Ok(CliCommand::PlaylistStep { backward, output })
+}
+
+fn parse_collection_command(args: &[String]) -> Result<CliCommand, String> {
+    match args.split_first() {
+        Some((action, [])) if action == "list" => Ok(CliCommand::CollectionList),
+        Some((action, rest)) if action == "apply" => {
+            let mut name = None;
+            let mut output = None;
+            let mut rest = rest.iter();
+            while let Some(arg) = rest.next() {
+                if arg == "--output" {
+                    let value = rest.next().ok_or_else(|| {
+                        with_usage(trf("{} needs a value", &[arg]), COLLECTION_USAGE)
+                    })?;
+                    output = Some(value.clone());
+                } else if arg.starts_with("--") || name.is_some() {
+                    return Err(with_usage(
+                        trf("Unknown collection apply option {}", &[arg]),
+                        COLLECTION_USAGE,
+                    ));
+                } else {
+                    name = Some(arg.clone());
+                }
+            }
+            let name = name.ok_or_else(|| {
+                with_usage(
+                    tr("collection apply takes a collection name").to_string(),
+                    COLLECTION_USAGE,
+                )
+            })?;
+            Ok(CliCommand::CollectionApply { name, output })
+        }
+        Some((action, _)) => Err(with_usage(
+            trf("Unknown collection command {}", &[action]),
+            COLLECTION_USAGE,
+        )),
+        None => Err(tr(COLLECTION_USAGE).to_string()),
+    }
+}
+
+fn parse_migrate_command(args: &[String]) -> Result<CliCommand, String> {
+    let mut source = None;
+    let mut path = None;
+    let mut dry_run = false;
+    for arg in args {
+        if arg == "--dry-run" {
+            dry_run = true;
+        } else if arg.starts_with("--") || path.is_some() {
+            return Err(with_usage(
+                trf("Unknown migrate option {}", &[arg]),
+                MIGRATE_USAGE,
+            ));
+        } else if let Some(tool) = MigrationSource::from_name(arg).filter(|_| source.is_none()) {
+            source = Some(tool);
+        } else {
+            path = Some(PathBuf::from(arg));
+        }
+    }
+    if path.is_some() && source.is_none() {
+        return Err(with_usage(
+            tr("migrate needs the tool a path belongs to").to_string(),
+            MIGRATE_USAGE,
+        ));
+    }
+    Ok(CliCommand::Migrate {
+        source,
+        path,
+        dry_run,
+    })
+}
+
+fn parse_stats_command(args: &[String]) -> Result<CliCommand, String> {
+    let (mut watch, mut json) = (false, false);
+    for arg in args {
+        let flag = match arg.as_str() {
+            "--watch" => &mut watch,
+            "--json" => &mut json,
+            _ => {
+                return Err(with_usage(
+                    trf("Unknown stats option {}", &[arg]),
+                    STATS_USAGE,
+                ))
+            }
+        };
+        *flag = true;
+    }
+    Ok(CliCommand::Stats { watch, json })
+}
+
+/// `lwe seek`, `rate`, `frame-step` and `loop`; `seek +5` and `seek -5` move
+/// relative to the current position
+fn parse_transport_command(command: &str, args: &[String]) -> Result<CliCommand, String> {
+    let mut output = None;
+    let mut backward = false;
+    let mut words = Vec::new();
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--output" => {
+                let value = rest
+                    .next()
+                    .ok_or_else(|| with_usage(trf("{} needs a value", &[arg]), PLAYBACK_USAGE))?;
+                output = Some(value.clone());
+            }
+            "--back" if command == "frame-step" => backward = true,
+            // Negative numbers are relative seeks, not options
+            flag if flag.starts_with("--") || (flag.starts_with('-') && command != "seek") => {
+                return Err(with_usage(
+                    trf("Unknown {} option {}", &[&command, &flag]),
+                    PLAYBACK_USAGE,
+                ));
+            }
+            word => words.push(word),
+        }
+    }
+
+    let seconds = |value: &str| {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|seconds| seconds.is_finite())
+            .ok_or_else(|| trf("{} needs a number of seconds, not {}", &[&command, &value]))
+    };
+    let transport = match (command, words.as_slice()) {
+        ("seek", [position]) => Transport::Seek {
+            seconds: seconds(position)?,
+            relative: position.starts_with(['+', '-']),
+        },
+        ("rate", [speed]) => Transport::Speed(
+            speed
+                .parse::<f64>()
+                .ok()
+                .filter(|speed| speed.is_finite() && *speed > 0.0)
+                .ok_or_else(|| trf("rate needs a speed above 0, not {}", &[speed]))?,
+        ),
+        ("frame-step", []) => Transport::FrameStep { backward },
+        ("loop", ["off"]) => Transport::AbLoop(None),
+        ("loop", [start, end]) => {
+            let (start, end) = (seconds(start)?, seconds(end)?);
+            if start < 0.0 || end <= start {
+                return Err(tr("loop needs an end after its start").to_string());
+            }
+            Transport::AbLoop(Some((start, end)))
+        }
+        _ => return Err(tr(PLAYBACK_USAGE).to_string()),
+    };
+    Ok(CliCommand::Transport { output, transport })
+}
+
+/// `lwe color`; `--reset` puts every value back to 1
+fn parse_color_command(args: &[String]) -> Result<CliCommand, String> {
+    let mut output = None;
+    let mut values = [None; 4];
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        let slot = match arg.as_str() {
+            "--reset" => {
+                values = [Some(1.0); 4];
+                continue;
+            }
+            "--output" => None,
+            "--brightness" => Some(0),
+            "--contrast" => Some(1),
+            "--gamma" => Some(2),
+            "--saturation" => Some(3),
+            _ => {
+                return Err(with_usage(
+                    trf("Unknown color option {}", &[arg]),
+                    COLOR_USAGE,
+                ))
+            }
+        };
+        let value = rest
+            .next()
+            .ok_or_else(|| with_usage(trf("{} needs a value", &[arg]), COLOR_USAGE))?;
+        match slot {
+            None => output = Some(value.clone()),
+            Some(index) => {
+                values[index] = Some(
+                    value
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|value| (0.0..=2.0).contains(value))
+                        .ok_or_else(|| trf("{} needs 0 to 2, not {}", &[arg, value]))?,
+                );
+            }
+        }
+    }
+
+    let output =
+        output.ok_or_else(|| with_usage(tr("color needs --output").to_string(), COLOR_USAGE))?;
+    if values.iter().all(Option::is_none) {
+        return Err(tr(COLOR_USAGE).to_string());
+    }
+    let [brightness, contrast, gamma, saturation] = values;
+    Ok(CliCommand::Color {
+        output,
+        brightness,
+        contrast,
+        gamma,
+        saturation,
+    })
+}
+
+/// `lwe snapshot`; the file is resolved here since the app has another cwd
+fn parse_snapshot_command(args: &[String]) -> Result<CliCommand, String> {
+    let mut output = None;
+    let mut file = None;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        let slot = match arg.as_str() {
+            "--output" => &mut output,
+            "--file" => &mut file,
+            _ => {
+                return Err(with_usage(
+                    trf("Unknown snapshot option {}", &[arg]),
+                    SNAPSHOT_USAGE,
+                ))
+            }
+        };
+        let value = rest
+            .next()
+            .ok_or_else(|| with_usage(trf("{} needs a value", &[arg]), SNAPSHOT_USAGE))?;
+        *slot = Some(value.clone());
+    }
+
+    let (Some(output), Some(file)) = (output, file) else {
+        return Err(with_usage(
+            tr("snapshot needs --output and --file").to_string(),
+            SNAPSHOT_USAGE,
+        ));
+    };
+    let file = std::path::absolute(&file)
+        .map_err(|error| trf("Unable to resolve file {}: {}", &[&file, &error]))?;
+    Ok(CliCommand::Snapshot { output, file })
+}
+
+fn parse_library_filter(
+    args: &[String],
+    unknown_option: &'static str,
+    usage: &'static str,
+) -> Result<LibraryFilter, String> {
+    let mut filter = LibraryFilter::default();
+    let mut words = Vec::new();
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        let mut value = || {
+            rest.next()
+                .ok_or_else(|| with_usage(trf("{} needs a value", &[arg]), usage))
+        };
+        let number = |value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|_| trf("{} needs a number, not {}", &[arg, &value]))
+        };
+        match arg.as_str() {
+            "--tag" => filter
+                .tags
+                .get_or_insert_with(Vec::new)
+                .push(value()?.clone()),
+            "--type" => filter.wallpaper_type = Some(value()?.clone()),
+            "--folder" => {
+                let folder = value()?;
+                let folder = std::path::absolute(folder)
+                    .map_err(|error| trf("Unable to resolve folder {}: {}", &[folder, &error]))?;
+                filter.folder = Some(folder.to_string_lossy().into_owned());
+            }
+            "--favorites" => filter.favorites_only = true,
+            "--min-rating" => {
+                let rating = value()?;
+                filter.min_rating = Some(
+                    rating
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|rating| *rating <= 5)
+                        .ok_or_else(|| trf("--min-rating needs 0 to 5, not {}", &[rating]))?,
+                );
+            }
+            "--sort" => filter.sort = value()?.parse()?,
+            "--offset" => filter.offset = number(value()?)?,
+            "--limit" => filter.limit = Some(number(value()?)?),
+            flag if flag.starts_with("--") => {
+                return Err(with_usage(trf(unknown_option, &[&flag]), usage));
+            }
+            word => words.push(word),
+        }
+    }
+    if !words.is_empty() {
+        filter.search = Some(words.join(" "));
+    }
+
+    Ok(filter)
+}
+
+fn parse_open_command(args: &[String]) -> Result<CliCommand, String> {
+    let mut item_id = None;
+    let mut player = false;
+    for arg in args {
+        match arg.as_str() {
+            "--player" => player = true,
+            option if option.starts_with("--") => {
+                return Err(with_usage(
+                    trf("Unknown open option {}", &[&option]),
+                    OPEN_USAGE,
+                ))
+            }
+            id if item_id.is_none() => item_id = Some(id.to_string()),
+            _ => return Err(tr(OPEN_USAGE).to_string()),
+        }
+    }
+
+    let item_id = item_id.ok_or_else(|| tr(OPEN_USAGE).to_string())?;
+    Ok(CliCommand::Open { item_id, player })
+}
+
+fn parse_workshop_command(args: &[String]) -> Result<CliCommand, String> {
+    let Some((action, rest)) = args.split_first() else {
+        return Err(tr(WORKSHOP_USAGE).to_string());
+    };
+    if action == "extract" {
+        return parse_workshop_extract(rest);
+    }
+    let workshop_ids = rest
+        .iter()
+        .map(|arg| {
+            workshop_id_from(arg).ok_or_else(|| {
+                with_usage(
+                    trf("{} is not a Workshop id or URL", &[arg]),
+                    WORKSHOP_USAGE,
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match (action.as_str(), workshop_ids.as_slice()) {
+        ("info", [workshop_id]) => Ok(CliCommand::WorkshopInfo {
+            workshop_id: workshop_id.clone(),
+        }),
+        ("search", [_, ..]) => Ok(CliCommand::WorkshopSearch { workshop_ids }),
+        ("info" | "search", _) => Err(with_usage(
+            trf("workshop {} needs Workshop ids", &[action]),
+            WORKSHOP_USAGE,
+        )),
+        _ => Err(with_usage(
+            trf("Unknown workshop command {}", &[action]),
+            WORKSHOP_USAGE,
+        )),
+    }
+}
+
+fn parse_workshop_extract(args: &[String]) -> Result<CliCommand, String> {
+    let mut sources = Vec::new();
+    let mut out = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => {
+                let Some(dir) = args.next() else {
+                    return Err(with_usage(trf("{} needs a value", &[arg]), WORKSHOP_USAGE));
+                };
+                out = Some(PathBuf::from(dir));
+            }
+            flag if flag.starts_with("--") => {
+                return Err(with_usage(
+                    trf("Unknown workshop extract option {}", &[&flag]),
+                    WORKSHOP_USAGE,
+                ));
+            }
+            source => sources.push(PathBuf::from(source)),
+        }
+    }
+
+    let [source] = <[PathBuf; 1]>::try_from(sources).map_err(|_| {
+        with_usage(
+            tr("workshop extract needs one project directory or package").to_string(),
+            WORKSHOP_USAGE,
+        )
+    })?;
+    let out = out.ok_or_else(|| {
+        with_usage(
+            tr("workshop extract needs --out").to_string(),
+            WORKSHOP_USAGE,
+        )
+    })?;
+    Ok(CliCommand::WorkshopExtract { source, out })
+}
+
+/// Accepts `1234567890` or a Workshop page URL ending in `?id=1234567890`
+fn workshop_id_from(arg: &str) -> Option<String> {
+    let id = match arg.split_once("id=") {
+        Some((_, query)) => query.split('&').next().unwrap_or_default(),
+        None => arg,
+    };
+    (!id.is_empty() && id.bytes().all(|byte| byte.is_ascii_digit())).then(|| id.to_string())
+}
+
+fn parse_capture_command(args: &[String]) -> Result<CliCommand, String> {
+    let mut paths = Vec::new();
+    let mut output_size = None;
+    let mut layout = LayoutMode::default();
+    let mut timestamps = Vec::new();
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--size" | "--at" | "--layout" => {
+                let Some(value) = rest.next() else {
+                    return Err(format!("{arg} needs a value\n{CAPTURE_USAGE}"));
+                };
+                match arg.as_str() {
+                    "--size" => output_size = Some(parse_size(value)?),
+                    "--at" => timestamps = parse_timestamps(value)?,
+                    _ => layout = value.parse()?,
+                }
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown capture option {flag}\n{CAPTURE_USAGE}"));
+            }
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+
+    let [source, out_dir] = <[PathBuf; 2]>::try_from(paths)
+        .map_err(|_| format!("capture needs a source and an output directory\n{CAPTURE_USAGE}"))?;
+    let output_size =
+        output_size.ok_or_else(|| format!("capture needs --size\n{CAPTURE_USAGE}"))?;
+    if timestamps.is_empty() {
+        return Err(format!("capture needs --at\n{CAPTURE_USAGE}"));
+    }
+
+    Ok(CliCommand::Capture {
+        source,
+        out_dir,
+        config: CaptureConfig {
+            output_size,
+            layout,
+            timestamps,
+        },
+    })
+}
+
+/// `1920x1080`
+fn parse_size(value: &str) -> Result<(u32, u32), String> {
+    value
+        .split_once('x')
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+        .filter(|(width, height)| *width > 0 && *height > 0)
+        .ok_or_else(|| format!("Invalid size {value}, expected <width>x<height>"))
+}
+
+/// `0,1.5,3` in seconds
+fn parse_timestamps(value: &str) -> Result<Vec<Duration>, String> {
+    value
+        .split(',')
+        .map(|seconds| {
+            seconds
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                .ok_or_else(|| format!("Invalid timestamp {seconds}, expected seconds"))
+        })
+        .collect()
+}
+
+/// Runs a CLI subcommand when one was given and returns the process exit code.
+pub fn run_from_env() -> Option<i32> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.is_empty() {
+        return None;
+    }
+    i18n::init();
+
+    match parse_cli_command(&args) {
+        Ok(None) => None,
+        Ok(Some(command)) => Some(match run(command) {
+            Ok(()) => 0,
+            Err(reason) => {
+                eprintln!("{reason}");
+                1
+            }
+        }),
+        Err(reason) => {
+            eprintln!("{reason}");
+            Some(2)
+        }
+    }
+}
+
+fn run(command: CliCommand) -> Result<(), String> {
+    match command {
+        CliCommand::InstallService { compositor } => install_service(compositor),
+        CliCommand::Convert {
+            operation,
+            input,
+            output,
+            for_output,
+        } => convert(operation, &input, &output, &for_output),
+        CliCommand::Check { json } => check(json),
+        CliCommand::CheckGpus => {
+            check_gpus();
+            Ok(())
+        }
+        CliCommand::Doctor { fix } => doctor(fix),
+        CliCommand::QueryLibrary { filter } => query_library(&filter),
+        CliCommand::Random { filter, output } => {
+            send_to_app(&IpcRequest::ApplyRandom { filter, output })
+        }
+        CliCommand::ProfileSave { name } => send_to_app(&IpcRequest::ProfileSave { name }),
+        CliCommand::ProfileLoad { name } => send_to_app(&IpcRequest::ProfileLoad { name }),
+        CliCommand::ProfileList => list_profiles(),
+        CliCommand::CollectionList => list_collections(),
+        CliCommand::CollectionApply { name, output } => {
+            send_to_app(&IpcRequest::CollectionApply { name, output })
+        }
+        CliCommand::PlaylistStep {
+            backward: false,
+            output,
+        } => send_to_app(&IpcRequest::PlaylistNext { output }),
+        CliCommand::PlaylistStep {
+            backward: true,
+            output,
+        } => send_to_app(&IpcRequest::PlaylistPrevious { output }),
+        CliCommand::Status { json } => show_status(json),
+        CliCommand::Stats { watch, json } => show_stats(watch, json),
+        CliCommand::ValidateConfig { path } => validate_config(path.as_deref()),
+        CliCommand::Migrate {
+            source,
+            path,
+            dry_run,
+        } => migrate(source, path.as_deref(), dry_run),
+        CliCommand::Transport { output, transport } => {
+            send_to_app(&transport_request(output, transport))
+        }
+        CliCommand::Color {
+            output,
+            brightness,
+            contrast,
+            gamma,
+            saturation,
+        } => send_to_app(&IpcRequest::SetColor {
+            output,
+            brightness,
+            contrast,
+            gamma,
+            saturation,
+        }),
+        CliCommand::Snapshot { output, file } => {
+            send_to_app(&IpcRequest::Snapshot { output, path: file })
+        }
+        CliCommand::MpvSet {
+            output,
+            property,
+            value,
+        } => send_to_app(&IpcRequest::MpvSet {
+            output,
+            property,
+            value,
+        }),
+        CliCommand::Cache { clear } => cache(clear),
+        CliCommand::Open { item_id, player } => open_item(&item_id, player),
+        CliCommand::WorkshopInfo { workshop_id } => workshop_info(&workshop_id),
+        CliCommand::WorkshopSearch { workshop_ids } => workshop_search(&workshop_ids),
+        CliCommand::WorkshopExtract { source, out } => workshop_extract(&source, &out),
+        CliCommand::Capture {
+            source,
+            out_dir,
+            config,
+        } => {
+            for path in capture_frames(&source, &config, &out_dir)
+                .map_err(|error| format!("Capture failed: {error:#}"))?
+            {
+                println!("{}", path.display());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// One `path:line: key: message` line per issue, as compilers print them
+fn validate_config(path: Option<&Path>) -> Result<(), String> {
+    let (path, issues) = ConfigValidationService::validate_file(path)?;
+    if issues.is_empty() {
+        println!("{}", trf("No problems found in {}", &[&path.display()]));
+        return Ok(());
+    }
+
+    for issue in &issues {
+        let severity = match issue.severity {
+            IssueSeverity::Error => tr("error"),
+            IssueSeverity::Warning => tr("warning"),
+        };
+        let location = match issue.line {
+            Some(line) => format!("{}:{line}", path.display()),
+            None => path.display().to_string(),
+        };
+        match &issue.key {
+            Some(key) => println!("{location}: {severity}: {key}: {}", issue.message),
+            None => println!("{location}: {severity}: {}", issue.message),
+        }
+    }
+
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity == IssueSeverity::Error)
+        .count();
+    if errors > 0 {
+        return Err(trf("{}: {} errors", &[&path.display(), &errors]));
+    }
+    Ok(())
+}
+
+/// List what was found, then what was (or with `--dry-run` would be) imported
+fn migrate(
+    source: Option<MigrationSource>,
+    path: Option<&Path>,
+    dry_run: bool,
+) -> Result<(), String> {
+    let wallpapers = MigrationService::discover(source, path)?;
+    if wallpapers.is_empty() {
+        println!("{}", tr("No mpvpaper, swww or hyprpaper wallpapers found"));
+        return Ok(());
+    }
+    for wallpaper in &wallpapers {
+        let output = wallpaper
+            .output
+            .clone()
+            .unwrap_or_else(|| tr("all outputs").to_string());
+        println!(
+            "{}: {} {} -> {}",
+            wallpaper.origin,
+            wallpaper.source,
+            wallpaper.path.display(),
+            output
+        );
+    }
+
+    let report = MigrationService::import(&wallpapers, dry_run)?;
+    println!();
+    let added = report.files.len() + report.folders.len();
+    println!(
+        "{}",
+        if dry_run {
+            trf("Would add {} to the Library", &[&added])
+        } else {
+            trf("Added {} to the Library", &[&added])
+        }
+    );
+    for (output, path) in &report.assignments {
+        println!("  {output}: {}", path.display());
+    }
+    if !report.configured_outputs.is_empty() {
+        println!(
+            "{}",
+            trf(
+                "Layout and audio settings for {}",
+                &[&report.configured_outputs.join(", ")]
+            )
+        );
+    }
+    for note in &report.notes {
+        println!("{}", trf("Skipped: {}", &[note]));
+    }
+    if !dry_run && !report.assignments.is_empty() {
+        println!(
+            "{}",
+            tr("LWE shows these wallpapers the next time it starts; stop the other tool first.")
+        );
+    }
+    Ok(())
+}
+
+fn doctor(fix: bool) -> Result<(), String> {
+    for capability in DiagnosticsService::capabilities() {
+        println!(
+            "{} {}: {}",
+            if capability.available { "✓" } else { "✗" },
+            capability.key,
+            capability.detail
+        );
+    }
+
+    let issues = DoctorService::find_issues();
+    if issues.is_empty() {
+        println!();
+        println!("{}", tr("No setup problems found."));
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", tr("Problems:"));
+    for issue in &issues {
+        println!("  - {}", issue.problem);
+    }
+    if !fix {
+        println!();
+        println!("{}", tr("Run `lwe doctor --fix` to repair them."));
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for issue in &issues {
+        println!();
+        if !confirm(&issue.fix.prompt())? {
+            println!("{}", tr("Skipped."));
+            continue;
+        }
+        match issue.fix.apply() {
+            Ok(done) => println!("{done}"),
+            Err(reason) => {
+                eprintln!("{reason}");
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(trf("{} fixes failed", &[&failed]));
+    }
+    Ok(())
+}
+
+/// Ask a yes/no question on the terminal; anything but `y` or `yes` is no
+fn confirm(question: &str) -> Result<bool, String> {
+    use std::io::Write as _;
+
+    print!("{question} [y/N] ");
+    std::io::stdout()
+        .flush()
+        .map_err(|error| trf("Failed to write prompt: {}", &[&error]))?;
+    let mut answer = String::new();
+    std::io::stdin()
+        .read_line(&mut answer)
+        .map_err(|error| trf("Failed to read answer: {}", &[&error]))?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+fn cache(clear: bool) -> Result<(), String> {
+    let cleanup = if clear {
+        ThumbnailCacheService::clear()?
+    } else {
+        ThumbnailCacheService::prune()?
+    };
+    println!(
+        "{}",
+        trf(
+            "Removed {} thumbnails and reclaimed {}",
+            &[&cleanup.removed, &format_size(cleanup.bytes_reclaimed)]
+        )
+    );
+    let usage = ThumbnailCacheService::usage()?;
+    println!(
+        "{}",
+        trf(
+            "The thumbnail cache now takes {} of {}",
+            &[&format_size(usage.bytes), &format_size(usage.budget_bytes)]
+        )
+    );
+    Ok(())
+}
+
+fn open_item(item_id: &str, player: bool) -> Result<(), String> {
+    if player {
+        let player = ItemOpenService::play_external(item_id)?;
+        println!("{}", trf("Playing in {}", &[&player]));
+    } else {
+        let path = ItemOpenService::reveal(item_id)?;
+        println!("{}", trf("Showing {}", &[&path.display()]));
+    }
+    Ok(())
+}
+
+/// Print the same `library` response the IPC `query_library` request returns
+fn query_library(filter: &LibraryFilter) -> Result<(), String> {
+    let database = LibraryDatabase::open(LibraryDatabase::default_path())
+        .map_err(|error| trf("Unable to open the library database: {}", &[&error]))?;
+    let (items, total) = database
+        .query_library(filter)
+        .map_err(|error| trf("Library query failed: {}", &[&format!("{error:#}")]))?;
+    let response = serde_json::to_string_pretty(&IpcResponse::Library { items, total })
+        .map_err(|error| trf("Failed to encode the library response: {}", &[&error]))?;
+    println!("{response}");
+    Ok(())
+}
+
+fn request_app(request: &IpcRequest) -> Result<IpcResponse, String> {
+    ControlService::send(request)
+        .map_err(|reason| trf("Could not reach the running LWE app: {}", &[&reason]))
+}
+
+/// Random picks, profiles and playback controls are handled by the running
+/// app, which owns the wallpapers; its reply message is printed
+fn send_to_app(request: &IpcRequest) -> Result<(), String> {
+    match request_app(request)? {
+        IpcResponse::Ok { message } => {
+            if let Some(message) = message {
+                println!("{message}");
+            }
+            Ok(())
+        }
+        IpcResponse::Error { error } => Err(error),
+        _ => Err(tr("The running LWE app sent an unexpected reply").to_string()),
+    }
+}
+
+fn transport_request(output: Option<String>, transport: Transport) -> IpcRequest {
+    match transport {
+        Transport::Seek { seconds, relative } => IpcRequest::Seek {
+            output,
+            seconds,
+            relative,
+        },
+        Transport::Speed(rate) => IpcRequest::SetRate { output, rate },
+        Transport::FrameStep { backward } => IpcRequest::FrameStep { output, backward },
+        Transport::AbLoop(range) => IpcRequest::SetAbLoop { output, range },
+    }
+}
+
+fn list_profiles() -> Result<(), String> {
+    match request_app(&IpcRequest::ProfileList)? {
+        IpcResponse::Profiles { profiles } if profiles.is_empty() => {
+            println!("{}", tr("No profiles saved yet"));
+            Ok(())
+        }
+        IpcResponse::Profiles { profiles } => {
+            for profile in profiles {
+                println!("{}\t{}", profile.name, profile.outputs.join(", "));
+            }
+            Ok(())
+        }
+        IpcResponse::Error { error } => Err(error),
+        _ => Err(tr("The running LWE app sent an unexpected reply").to_string()),
+    }
+}
+
+fn list_collections() -> Result<(), String> {
+    match request_app(&IpcRequest::CollectionList)? {
+        IpcResponse::Collections { collections } if collections.is_empty() => {
+            println!("{}", tr("No collections yet"));
+            Ok(())
+        }
+        IpcResponse::Collections { collections } => {
+            for collection in collections {
+                println!(
+                    "{}\t{}",
+                    collection.name,
+                    trf("{} wallpapers", &[&collection.items])
+                );
+            }
+            Ok(())
+        }
+        IpcResponse::Error { error } => Err(error),
+        _ => Err(tr("The running LWE app sent an unexpected reply").to_string()),
+    }
+}
+
+fn show_status(json: bool) -> Result<(), String> {
+    let response = request_app(&IpcRequest::Status)?;
+    match &response {
+        IpcResponse::Status { .. } if json => {
+            let response = serde_json::to_string_pretty(&response)
+                .map_err(|error| trf("Failed to encode the status response: {}", &[&error]))?;
+            println!("{response}");
+            Ok(())
+        }
+        IpcResponse::Status {
+            running,
+            outputs,
+            on_battery,
+            power_paused,
+            deep_sleep,
+            render_backend,
+            ..
+        } => {
+            let power = (*on_battery, *power_paused);
+            let backend = render_backend.as_deref();
+            for line in status_report(*running, backend, power, *deep_sleep, outputs) {
+                println!("{line}");
+            }
+            Ok(())
+        }
+        IpcResponse::Error { error } => Err(error.clone()),
+        _ => Err(tr("The running LWE app sent an unexpected reply").to_string()),
+    }
+}
+
+/// The engine, power and deep sleep state, then each output's wallpaper, its
+/// title and length, the decoder and the frame rate it is drawn at
+fn status_report(
+    running: bool,
+    render_backend: Option<&str>,
+    (on_battery, power_paused): (bool, bool),
+    deep_sleep: bool,
+    outputs: &[OutputStatus],
+) -> Vec<String> {
+    if !running {
+        return vec![tr("No wallpaper is playing").to_string()];
+    }
+
+    let mut lines = vec![
+        trf("Engine: running with {}", &[&render_backend.unwrap_or("?")]),
+        match (on_battery, power_paused) {
+            (true, true) => tr("Power: on battery, wallpapers paused to save power"),
+            (true, false) => tr("Power: on battery"),
+            (false, _) => tr("Power: on AC"),
+        }
+        .to_string(),
+    ];
+    if deep_sleep {
+        lines.push(tr("Deep sleep: windows cover every output, decoding stopped").to_string());
+    }
+    for output in outputs {
+        let wallpaper = output.wallpaper.as_deref().unwrap_or(tr("no wallpaper"));
+        lines.push(if output.paused {
+            trf("{}: {} (paused)", &[&output.name, &wallpaper])
+        } else {
+            format!("{}: {wallpaper}", output.name)
+        });
+        if let Some(title) = &output.title {
+            lines.push(match output.duration {
+                Some(seconds) => format!("  {title} ({})", clock(seconds)),
+                None => format!("  {title}"),
+            });
+        }
+
+        let mut details = Vec::new();
+        match output.hwdec.as_deref() {
+            Some("no") => details.push(tr("software decoding").to_string()),
+            Some(hwdec) => details.push(trf("{} decoding", &[&hwdec])),
+            None => {}
+        }
+        if let Some(fps) = output.fps {
+            details.push(format!("{fps:.1} fps"));
+        }
+        if let Some(skip_rate) = output.skip_rate {
+            details.push(trf("{}% dropped", &[&format!("{:.1}", skip_rate * 100.0)]));
+        }
+        if !details.is_empty() {
+            lines.push(format!("  {}", details.join(", ")));
+        }
+    }
+    lines
+}
+
+/// Ask for stats once, or every second until interrupted; watching redraws
+/// the screen, or prints one JSON line per sample
+fn show_stats(watch: bool, json: bool) -> Result<(), String> {
+    loop {
+        let response = request_app(&IpcRequest::GetStats)?;
+        match &response {
+            IpcResponse::Stats { .. } if json => {
+                let encoded = if watch {
+                    serde_json::to_string(&response)
+                } else {
+                    serde_json::to_string_pretty(&response)
+                };
+                let encoded = encoded
+                    .map_err(|error| trf("Failed to encode the stats response: {}", &[&error]))?;
+                println!("{encoded}");
+            }
+            IpcResponse::Stats { outputs, process } => {
+                if watch {
+                    // Clear the screen and move the cursor home
+                    print!("\x1b[2J\x1b[H");
+                }
+                for line in stats_report(outputs, process) {
+                    println!("{line}");
+                }
+            }
+            IpcResponse::Error { error } => return Err(error.clone()),
+            _ => return Err(tr("The running LWE app sent an unexpected reply").to_string()),
+        }
+        if !watch {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// The app's CPU and memory use, then a table of each output's render
+/// time, frame rate, dropped frames and decoder
+fn stats_report(outputs: &[OutputStats], process: &ProcessStats) -> Vec<String> {
+    let unknown = || "-".to_string();
+    let mut lines = vec![trf(
+        "CPU: {}  Memory: {}",
+        &[
+            &process
+                .cpu_percent
+                .map(|percent| format!("{percent:.1}%"))
+                .unwrap_or_else(unknown),
+            &process.rss_bytes.map(format_size).unwrap_or_else(unknown),
+        ],
+    )];
+    if outputs.is_empty() {
+        lines.push(tr("No wallpaper is playing").to_string());
+        return lines;
+    }
+
+    let width = outputs
+        .iter()
+        .map(|stats| stats.output.chars().count())
+        .chain([tr("OUTPUT").chars().count()])
+        .max()
+        .unwrap_or_default();
+    lines.push(format!(
+        "{:<width$}  {:>9}  {:>6}  {:>12}  {}",
+        tr("OUTPUT"),
+        tr("RENDER"),
+        tr("FPS"),
+        tr("DROPPED"),
+        tr("DECODER"),
+    ));
+    for stats in outputs {
+        let dropped = match stats.skip_rate {
+            Some(skip_rate) => format!(
+                "{} ({:.1}%)",
+                format_count(stats.dropped_frames),
+                skip_rate * 100.0
+            ),
+            None => format_count(stats.dropped_frames),
+        };
+        lines.push(format!(
+            "{:<width$}  {:>9}  {:>6}  {:>12}  {}",
+            stats.output,
+            stats
+                .render_ms
+                .map(|ms| format!("{ms:.1} ms"))
+                .unwrap_or_else(unknown),
+            stats
+                .fps
+                .map(|fps| format!("{fps:.1}"))
+                .unwrap_or_else(unknown),
+            dropped,
+            match stats.hwdec.as_deref() {
+                Some("no") => tr("software").to_string(),
+                Some(hwdec) => hwdec.to_string(),
+                None => unknown(),
+            },
+        ));
+    }
+    lines
+}
+
+/// `75.4` -> `1:15`
+fn clock(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{hours}:{:02}:{:02}", seconds / 60 % 60, seconds % 60),
+    }
+}
+
+fn workshop_info(workshop_id: &str) -> Result<(), String> {
+    let local = WorkshopService::inspect_item(workshop_id).ok();
+    let online = WorkshopService::fetch_item_details(&[workshop_id.to_string()])
+        .map(|mut details| details.remove(0));
+
+    match (online, &local) {
+        (Ok(details), _) if details.found => {
+            for line in workshop_details_lines(&details) {
+                println!("{line}");
+            }
+        }
+        (Ok(_), None) => {
+            return Err(trf(
+                "Workshop item {} was not found on Steam",
+                &[&workshop_id],
+            ))
+        }
+        (Err(reason), None) => return Err(reason),
+        (Ok(_), Some(inspection)) => {
+            println!("{} ({workshop_id})", inspection.entry.entry.title);
+            println!("{}", tr("Not listed on Steam anymore"));
+        }
+        (Err(reason), Some(inspection)) => {
+            eprintln!("{reason}");
+            println!("{} ({workshop_id})", inspection.entry.entry.title);
+        }
+    }
+
+    match local {
+        Some(inspection) => println!(
+            "{}",
+            trf(
+                "Downloaded: {}",
+                &[&inspection.entry.entry.project_dir.display()]
+            )
+        ),
+        None => println!("{}", tr("Downloaded: no")),
+    }
+    Ok(())
+}
+
+fn workshop_search(workshop_ids: &[String]) -> Result<(), String> {
+    for details in WorkshopService::fetch_item_details(workshop_ids)? {
+        if !details.found {
+            println!("{}\t{}", details.workshop_id, tr("not found"));
+            continue;
+        }
+        println!(
+            "{}\t{}\t{}\t{}",
+            details.workshop_id,
+            details.title.as_deref().unwrap_or(tr("Untitled")),
+            details.file_size.map(format_size).unwrap_or_default(),
+            details.preview_url.as_deref().unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+fn workshop_extract(source: &Path, out: &Path) -> Result<(), String> {
+    let report = SceneExtractService::extract(source, out)?;
+    println!(
+        "{}",
+        trf(
+            "Extracted {} files and {} textures to {}",
+            &[&report.files, &report.textures, &out.display()],
+        )
+    );
+    if let Some(scene_json) = &report.scene_json {
+        println!("{}", trf("Scene: {}", &[&scene_json.display()]));
+    }
+    if !report.texture_failures.is_empty() {
+        println!();
+        println!("{}", tr("Textures that could not be converted:"));
+        for (name, reason) in &report.texture_failures {
+            println!("  {name}: {reason}");
+        }
+    }
+    Ok(())
+}
+
+fn workshop_details_lines(details: &WorkshopItemDetails) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{} ({})",
+        details.title.as_deref().unwrap_or(tr("Untitled")),
+        details.workshop_id
+    )];
+    if let Some(size) = details.file_size {
+        lines.push(trf("Size: {}", &[&format_size(size)]));
+    }
+    if !details.tags.is_empty() {
+        lines.push(trf("Tags: {}", &[&details.tags.join(", ")]));
+    }
+    if let Some(subscriptions) = details.subscriptions {
+        lines.push(trf("Subscriptions: {}", &[&format_count(subscriptions)]));
+    }
+    if let Some(updated) = details.time_updated {
+        lines.push(trf("Updated: {}", &[&format_date(updated)]));
+    }
+    if let Some(preview_url) = &details.preview_url {
+        lines.push(trf("Preview: {}", &[preview_url]));
+    }
+    if let Some(description) = &details.description {
+        lines.push(String::new());
+        lines.push(description.trim().to_string());
+    }
+    lines
+}
+
+fn check(json: bool) -> Result<(), String> {
+    let capabilities = DiagnosticsService::check_capabilities();
+    if json {
+        let report = serde_json::json!({
+            "appVersion": env!("CARGO_PKG_VERSION"),
+            "capabilities": capabilities
+                .iter()
+                .map(|capability| serde_json::json!({
+                    "key": capability.key,
+                    "available": capability.available,
+                    "detail": capability.detail,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        let report = serde_json::to_string_pretty(&report)
+            .map_err(|error| trf("Failed to encode the capability report: {}", &[&error]))?;
+        println!("{report}");
+        return Ok(());
+    }
+
+    for capability in capabilities {
+        println!(
+            "{} {}: {}",
+            if capability.available { "✓" } else { "✗" },
+            capability.key,
+            capability.detail
+        );
+    }
+    Ok(())
+}
+
+fn check_gpus() {
+    let configured = DesktopService::render_device();
+    for line in render_node_report(&render_nodes(), configured.as_deref()) {
+        println!("{line}");
+    }
+}
+
+/// One line per render node, marking the configured one, plus a note on how
+/// the GPU is picked.
+fn render_node_report(nodes: &[RenderNode], configured: Option<&Path>) -> Vec<String> {
+    let resolve = |path: &Path| std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let configured_node = configured.map(resolve);
+
+    let mut lines = if nodes.is_empty() {
+        vec![tr("No DRM render nodes found in /dev/dri").to_string()]
+    } else {
+        vec![tr("Render nodes:").to_string()]
+    };
+    let mut matched = false;
+    for node in nodes {
+        let chosen = configured_node.as_deref() == Some(resolve(&node.path).as_path());
+        matched |= chosen;
+        let details = [
+            node.driver.as_deref(),
+            node.pci_slot.as_deref(),
+            node.pci_id.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ");
+        lines.push(format!(
+            "{} {}{}{}",
+            if chosen { "*" } else { " " },
+            node.path.display(),
+            if details.is_empty() {
+                String::new()
+            } else {
+                format!(" ({details})")
+            },
+            if chosen { " [render_device]" } else { "" }
+        ));
+    }
+
+    match configured {
+        Some(device) if !matched => lines.push(trf(
+            "Configured render_device {} is not a render node on this system; the engine falls back to the default GPU",
+            &[&device.display()],
+        )),
+        Some(_) => {}
+        None => lines.push(
+            tr("No render_device set in [gpu]; EGL picks the GPU (DRI_PRIME=1 selects the discrete one)")
+                .to_string(),
+        ),
+    }
+    lines
+}
+
+fn convert(
+    operation: ConvertOperation,
+    input: &std::path::Path,
+    output: &std::path::Path,
+    for_output: &str,
+) -> Result<(), String> {
+    let report = WallpaperConvertService::convert_for_output(operation, input, output, for_output)?;
+
+    println!(
+        "{}",
+        trf(
+            "Wrote {} at {}x{} for {}",
+            &[
+                &report.output_path.display(),
+                &report.target.width,
+                &report.target.height,
+                &for_output,
+            ],
+        )
+    );
+    match report.library_id {
+        Ok(id) => println!("{}", trf("Added to the library as {}", &[&id])),
+        Err(reason) => eprintln!("{}", trf("Not added to the library: {}", &[&reason])),
+    }
+
+    Ok(())
+}
+
+fn install_service(compositor: Option<CompositorTarget>) -> Result<(), String> {
+    let executable = std::env::current_exe()
+        .map_err(|error| trf("Unable to resolve current executable: {}", &[&error]))?;
+    let executable = executable.to_str().ok_or_else(|| {
+        trf(
+            "Unable to use non-UTF-8 executable path {} for the systemd unit",
+            &[&executable.display()],
+        )
+    })?;
+
+    let report = ServiceInstallService::for_user_path()?
+        .install(&[executable, "--minimized"], compositor)?;
+
+    println!("{}", trf("Wrote {}", &[&report.unit_path.display()]));
+    match (&report.compositor, compositor) {
+        (Some(snippet), Some(target)) if snippet.appended => println!(
+            "{}",
+            trf(
+                "Added {} startup line to {}",
+                &[&target.label(), &snippet.config_path.display()],
+            )
+        ),
+        (Some(snippet), Some(target)) => println!(
+            "{}",
+            trf(
+                "{} config {} already starts {}",
+                &[
+                    &target.label(),
+                    &snippet.config_path.display(),
+                    &SYSTEMD_UNIT_NAME,
+                ],
+            )
+        ),
+        _ => {}
+    }
+    println!();
+    println!("{}", tr("Enable it with:"));
+    println!("  systemctl --user daemon-reload");
+    println!("  systemctl --user enable --now {SYSTEMD_UNIT_NAME}");
+    println!(
+        "{}",
+        tr("Turn off \"Launch on login\" in Settings so LWE is not started twice.")
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    use lwe_core::{LibraryFilter, LibrarySort, OutputStats, OutputStatus, ProcessStats};
+    use lwe_engine::{CaptureConfig, LayoutMode, RenderNode, Transport};
+
+    use super::{
+        clock, format_size, parse_cli_command, render_node_report, stats_report, status_report,
+        workshop_details_lines, CliCommand,
+    };
+    use crate::results::workshop::WorkshopItemDetails;
+    use crate::services::service_install_service::CompositorTarget;
+    use crate::services::wallpaper_convert_service::ConvertOperation;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_cli_command_leaves_gui_launches_alone() {
+        assert_eq!(parse_cli_command(&args(&[])), Ok(None));
+        assert_eq!(parse_cli_command(&args(&["--minimized"])), Ok(None));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_install_service_compositor_flag() {
+        assert_eq!(
+            parse_cli_command(&args(&["install-service"])),
+            Ok(Some(CliCommand::InstallService { compositor: None }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["install-service", "--sway"])),
+            Ok(Some(CliCommand::InstallService {
+                compositor: Some(CompositorTarget::Sway)
+            }))
+        );
+        assert!(parse_cli_command(&args(&["install-service", "--kwin"]))
+            .unwrap_err()
+            .starts_with("Unknown install-service option --kwin"));
+        assert!(
+            parse_cli_command(&args(&["install-service", "--sway", "--niri"]))
+                .unwrap_err()
+                .starts_with("Only one compositor can be selected")
+        );
+    }
+
+    #[test]
+    fn parse_cli_command_reads_tools_conversions() {
+        assert_eq!(
+            parse_cli_command(&args(&[
+                "tools",
+                "rotate",
+                "in.mp4",
+                "out.mp4",
+                "--for-output",
+                "DP-2",
+                "--ccw"
+            ])),
+            Ok(Some(CliCommand::Convert {
+                operation: ConvertOperation::Rotate {
+                    counterclockwise: true
+                },
+                input: PathBuf::from("in.mp4"),
+                output: PathBuf::from("out.mp4"),
+                for_output: "DP-2".to_string(),
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&[
+                "tools",
+                "crop",
+                "--for-output",
+                "DP-2",
+                "a.png",
+                "b.png"
+            ])),
+            Ok(Some(CliCommand::Convert {
+                operation: ConvertOperation::Crop,
+                input: PathBuf::from("a.png"),
+                output: PathBuf::from("b.png"),
+                for_output: "DP-2".to_string(),
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_cli_command_rejects_incomplete_tools_conversions() {
+        assert!(parse_cli_command(&args(&["tools"]))
+            .unwrap_err()
+            .starts_with("Usage: lwe tools"));
+        assert!(
+            parse_cli_command(&args(&["tools", "crop", "in.mp4", "out.mp4"]))
+                .unwrap_err()
+                .starts_with("tools crop needs --for-output")
+        );
+        assert!(
+            parse_cli_command(&args(&["tools", "crop", "in.mp4", "--for-output", "DP-2"]))
+                .unwrap_err()
+                .starts_with("tools crop needs an input and an output path")
+        );
+        assert!(parse_cli_command(&args(&[
+            "tools",
+            "crop",
+            "a.png",
+            "b.png",
+            "--for-output",
+            "DP-2",
+            "--ccw"
+        ]))
+        .unwrap_err()
+        .starts_with("Unknown tools crop option --ccw"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_check_options() {
+        assert_eq!(
+            parse_cli_command(&args(&["check", "--gpus"])),
+            Ok(Some(CliCommand::CheckGpus))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["check"])),
+            Ok(Some(CliCommand::Check { json: false }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["check", "--json"])),
+            Ok(Some(CliCommand::Check { json: true }))
+        );
+        assert!(parse_cli_command(&args(&["check", "--json", "--gpus"]))
+            .unwrap_err()
+            .starts_with("Unknown check option --json"));
+        assert!(parse_cli_command(&args(&["check", "--vulkan"]))
+            .unwrap_err()
+            .starts_with("Unknown check option --vulkan"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_doctor_fix_flag() {
+        assert_eq!(
+            parse_cli_command(&args(&["doctor"])),
+            Ok(Some(CliCommand::Doctor { fix: false }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["doctor", "--fix"])),
+            Ok(Some(CliCommand::Doctor { fix: true }))
+        );
+        assert!(parse_cli_command(&args(&["doctor", "--yes"]))
+            .unwrap_err()
+            .starts_with("Unknown doctor option --yes"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_open_target() {
+        assert_eq!(
+            parse_cli_command(&args(&["open", "1234"])),
+            Ok(Some(CliCommand::Open {
+                item_id: "1234".to_string(),
+                player: false,
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["open", "--player", "1234"])),
+            Ok(Some(CliCommand::Open {
+                item_id: "1234".to_string(),
+                player: true,
+            }))
+        );
+        assert!(parse_cli_command(&args(&["open"]))
+            .unwrap_err()
+            .starts_with("Usage: lwe open"));
+        assert!(parse_cli_command(&args(&["open", "1234", "--vlc"]))
+            .unwrap_err()
+            .starts_with("Unknown open option --vlc"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_library_query_filters() {
+        assert_eq!(
+            parse_cli_command(&args(&[
+                "library",
+                "query",
+                "rainy",
+                "night",
+                "--tag",
+                "rain",
+                "--tag",
+                "city",
+                "--favorites",
+                "--min-rating",
+                "4",
+                "--sort",
+                "date-added",
+                "--offset",
+                "20",
+                "--limit",
+                "10"
+            ])),
+            Ok(Some(CliCommand::QueryLibrary {
+                filter: LibraryFilter {
+                    search: Some("rainy night".to_string()),
+                    tags: Some(vec!["rain".to_string(), "city".to_string()]),
+                    favorites_only: true,
+                    min_rating: Some(4),
+                    sort: LibrarySort::DateAdded,
+                    offset: 20,
+                    limit: Some(10),
+                    ..LibraryFilter::default()
+                }
+            }))
+        );
+        assert!(
+            parse_cli_command(&args(&["library", "query", "--min-rating", "6"]))
+                .unwrap_err()
+                .starts_with("--min-rating needs 0 to 5")
+        );
+        assert!(parse_cli_command(&args(&["library", "query", "--limit"]))
+            .unwrap_err()
+            .starts_with("--limit needs a value"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_random_filters_and_output() {
+        assert_eq!(
+            parse_cli_command(&args(&[
+                "random",
+                "--tag",
+                "nature",
+                "--output",
+                "DP-1",
+                "--folder",
+                "/home/user/walls",
+                "--type",
+                "video"
+            ])),
+            Ok(Some(CliCommand::Random {
+                filter: LibraryFilter {
+                    wallpaper_type: Some("video".to_string()),
+                    tags: Some(vec!["nature".to_string()]),
+                    folder: Some("/home/user/walls".to_string()),
+                    ..LibraryFilter::default()
+                },
+                output: Some("DP-1".to_string()),
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["random"])),
+            Ok(Some(CliCommand::Random {
+                filter: LibraryFilter::default(),
+                output: None,
+            }))
+        );
+        assert!(parse_cli_command(&args(&["random", "--output"]))
+            .unwrap_err()
+            .starts_with("--output needs a value"));
+        assert!(parse_cli_command(&args(&["random", "--limit-to", "3"]))
+            .unwrap_err()
+            .starts_with("Unknown random option --limit-to"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_profile_commands() {
+        assert_eq!(
+            parse_cli_command(&args(&["profile", "save", "evening"])),
+            Ok(Some(CliCommand::ProfileSave {
+                name: "evening".to_string()
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["profile", "load", "evening"])),
+            Ok(Some(CliCommand::ProfileLoad {
+                name: "evening".to_string()
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["profile", "list"])),
+            Ok(Some(CliCommand::ProfileList))
+        );
+        assert!(parse_cli_command(&args(&["profile", "load"]))
+            .unwrap_err()
+            .starts_with("profile load takes a profile name"));
+        assert!(parse_cli_command(&args(&["profile", "delete", "evening"]))
+            .unwrap_err()
+            .starts_with("Unknown profile command delete"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_playlist_commands() {
+        assert_eq!(
+            parse_cli_command(&args(&["playlist", "next"])),
+            Ok(Some(CliCommand::PlaylistStep {
+                backward: false,
+                output: None,
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["playlist", "prev", "--output", "DP-1"])),
+            Ok(Some(CliCommand::PlaylistStep {
+                backward: true,
+                output: Some("DP-1".to_string()),
+            }))
+        );
+        assert!(parse_cli_command(&args(&["playlist", "prev", "--output"]))
+            .unwrap_err()
+            .starts_with("--output needs a value"));
+        assert!(parse_cli_command(&args(&["playlist", "shuffle"]))
+            .unwrap_err()
+            .starts_with("Unknown playlist command shuffle"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_collection_commands() {
+        assert_eq!(
+            parse_cli_command(&args(&["collection", "list"])),
+            Ok(Some(CliCommand::CollectionList))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["collection", "apply", "Nature"])),
+            Ok(Some(CliCommand::CollectionApply {
+                name: "Nature".to_string(),
+                output: None,
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&[
+                "collection",
+                "apply",
+                "--output",
+                "DP-1",
+                "Nature"
+            ])),
+            Ok(Some(CliCommand::CollectionApply {
+                name: "Nature".to_string(),
+                output: Some("DP-1".to_string()),
+            }))
+        );
+        assert!(parse_cli_command(&args(&["collection", "apply"]))
+            .unwrap_err()
+            .starts_with("collection apply takes a collection name"));
+        assert!(parse_cli_command(&args(&["collection", "apply", "a", "b"]))
+            .unwrap_err()
+            .starts_with("Unknown collection apply option b"));
+        assert!(parse_cli_command(&args(&["collection", "rename"]))
+            .unwrap_err()
+            .starts_with("Unknown collection command rename"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_migrate() {
+        assert_eq!(
+            parse_cli_command(&args(&["migrate"])),
+            Ok(Some(CliCommand::Migrate {
+                source: None,
+                path: None,
+                dry_run: false,
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&[
+                "migrate",
+                "hyprpaper",
+                "--dry-run",
+                "/tmp/hyprpaper.conf"
+            ])),
+            Ok(Some(CliCommand::Migrate {
+                source: Some(MigrationSource::Hyprpaper),
+                path: Some(PathBuf::from("/tmp/hyprpaper.conf")),
+                dry_run: true,
+            }))
+        );
+        assert!(parse_cli_command(&args(&["migrate", "start.sh"]))
+            .unwrap_err()
+            .starts_with("migrate needs the tool a path belongs to"));
+        assert!(parse_cli_command(&args(&["migrate", "--force"]))
+            .unwrap_err()
+            .starts_with("Unknown migrate option --force"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_color() {
+        assert_eq!(
+            parse_cli_command(&args(&[
+                "color",
+                "--output",
+                "DP-1",
+                "--brightness",
+                "0.9",
+                "--saturation",
+                "1.2"
+            ])),
+            Ok(Some(CliCommand::Color {
+                output: "DP-1".to_string(),
+                brightness: Some(0.9),
+                contrast: None,
+                gamma: None,
+                saturation: Some(1.2),
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["color", "--reset", "--output", "DP-1"])),
+            Ok(Some(CliCommand::Color {
+                output: "DP-1".to_string(),
+                brightness: Some(1.0),
+                contrast: Some(1.0),
+                gamma: Some(1.0),
+                saturation: Some(1.0),
+            }))
+        );
+        assert!(parse_cli_command(&args(&["color", "--gamma", "0.8"]))
+            .unwrap_err()
+            .starts_with("color needs --output"));
+        assert!(
+            parse_cli_command(&args(&["color", "--output", "DP-1", "--gamma", "3"]))
+                .unwrap_err()
+                .starts_with("--gamma needs 0 to 2, not 3")
+        );
+        assert!(parse_cli_command(&args(&["color", "--output", "DP-1"]))
+            .unwrap_err()
+            .starts_with("Usage: lwe color"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_snapshot() {
+        assert_eq!(
+            parse_cli_command(&args(&[
+                "snapshot",
+                "--output",
+                "DP-1",
+                "--file",
+                "/tmp/shot.png"
+            ])),
+            Ok(Some(CliCommand::Snapshot {
+                output: "DP-1".to_string(),
+                file: PathBuf::from("/tmp/shot.png"),
+            }))
+        );
+        let Ok(Some(CliCommand::Snapshot { file, .. })) = parse_cli_command(&args(&[
+            "snapshot", "--file", "shot.png", "--output", "DP-1",
+        ])) else {
+            panic!("snapshot with a relative file should parse");
+        };
+        assert!(file.is_absolute());
+        assert!(file.ends_with("shot.png"));
+        assert!(parse_cli_command(&args(&["snapshot", "--output", "DP-1"]))
+            .unwrap_err()
+            .starts_with("snapshot needs --output and --file"));
+        assert!(parse_cli_command(&args(&["snapshot", "--output"]))
+            .unwrap_err()
+            .starts_with("--output needs a value"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_mpv_set() {
+        assert_eq!(
+            parse_cli_command(&args(&[
+                "mpv",
+                "set",
+                "DP-1",
+                "video-sync",
+                "display-resample"
+            ])),
+            Ok(Some(CliCommand::MpvSet {
+                output: "DP-1".to_string(),
+                property: "video-sync".to_string(),
+                value: "display-resample".to_string(),
+            }))
+        );
+        assert!(parse_cli_command(&args(&["mpv", "set", "DP-1", "scale"]))
+            .unwrap_err()
+            .starts_with("mpv set needs an output, a property and a value"));
+        assert!(parse_cli_command(&args(&["mpv", "get", "DP-1"]))
+            .unwrap_err()
+            .starts_with("Unknown mpv command get"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_cache_actions() {
+        assert_eq!(
+            parse_cli_command(&args(&["cache", "prune"])),
+            Ok(Some(CliCommand::Cache { clear: false }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["cache", "clear"])),
+            Ok(Some(CliCommand::Cache { clear: true }))
+        );
+        assert!(parse_cli_command(&args(&["cache", "prune", "--all"]))
+            .unwrap_err()
+            .starts_with("Unknown cache prune option --all"));
+        assert!(parse_cli_command(&args(&["cache", "size"]))
+            .unwrap_err()
+            .starts_with("Unknown cache command size"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_playback_controls() {
+        assert_eq!(
+            parse_cli_command(&args(&["seek", "90.5", "--output", "DP-1"])),
+            Ok(Some(CliCommand::Transport {
+                output: Some("DP-1".to_string()),
+                transport: Transport::Seek {
+                    seconds: 90.5,
+                    relative: false,
+                },
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["seek", "-10"])),
+            Ok(Some(CliCommand::Transport {
+                output: None,
+                transport: Transport::Seek {
+                    seconds: -10.0,
+                    relative: true,
+                },
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["rate", "0.5"])),
+            Ok(Some(CliCommand::Transport {
+                output: None,
+                transport: Transport::Speed(0.5),
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["frame-step", "--back"])),
+            Ok(Some(CliCommand::Transport {
+                output: None,
+                transport: Transport::FrameStep { backward: true },
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["loop", "12", "18.5"])),
+            Ok(Some(CliCommand::Transport {
+                output: None,
+                transport: Transport::AbLoop(Some((12.0, 18.5))),
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["loop", "off"])),
+            Ok(Some(CliCommand::Transport {
+                output: None,
+                transport: Transport::AbLoop(None),
+            }))
+        );
+        assert!(parse_cli_command(&args(&["rate", "0"]))
+            .unwrap_err()
+            .starts_with("rate needs a speed above 0, not 0"));
+        assert!(parse_cli_command(&args(&["loop", "18", "12"]))
+            .unwrap_err()
+            .starts_with("loop needs an end after its start"));
+        assert!(parse_cli_command(&args(&["rate", "-2"]))
+            .unwrap_err()
+            .starts_with("Unknown rate option -2"));
+        assert!(parse_cli_command(&args(&["seek"]))
+            .unwrap_err()
+            .starts_with("Usage: lwe seek"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_workshop_ids_and_urls() {
+        assert_eq!(
+            parse_cli_command(&args(&[
+                "workshop",
+                "info",
+                "https://steamcommunity.com/sharedfiles/filedetails/?id=1234567890&searchtext="
+            ])),
+            Ok(Some(CliCommand::WorkshopInfo {
+                workshop_id: "1234567890".to_string()
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["workshop", "search", "1", "22"])),
+            Ok(Some(CliCommand::WorkshopSearch {
+                workshop_ids: vec!["1".to_string(), "22".to_string()]
+            }))
+        );
+        assert!(parse_cli_command(&args(&["workshop", "info", "1", "2"]))
+            .unwrap_err()
+            .starts_with("workshop info needs Workshop ids"));
+        assert!(parse_cli_command(&args(&["workshop", "search", "rain"]))
+            .unwrap_err()
+            .starts_with("rain is not a Workshop id or URL"));
+        assert_eq!(
+            parse_cli_command(&args(&[
+                "workshop",
+                "extract",
+                "scene.pkg",
+                "--out",
+                "dump"
+            ])),
+            Ok(Some(CliCommand::WorkshopExtract {
+                source: PathBuf::from("scene.pkg"),
+                out: PathBuf::from("dump"),
+            }))
+        );
+        assert!(
+            parse_cli_command(&args(&["workshop", "extract", "scene.pkg"]))
+                .unwrap_err()
+                .starts_with("workshop extract needs --out")
+        );
+    }
+
+    #[test]
+    fn workshop_details_lines_show_size_tags_and_preview() {
+        let details = WorkshopItemDetails {
+            workshop_id: "1234567890".to_string(),
+            found: true,
+            title: Some("Rainy Window".to_string()),
+            file_size: Some(52_428_800),
+            tags: vec!["Video".to_string(), "Nature".to_string()],
+            preview_url: Some("https://example.com/rain.jpg".to_string()),
+            ..WorkshopItemDetails::default()
+        };
+
+        assert_eq!(
+            workshop_details_lines(&details),
+            [
+                "Rainy Window (1234567890)",
+                "Size: 50.0 MiB",
+                "Tags: Video, Nature",
+                "Preview: https://example.com/rain.jpg",
+            ]
+        );
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+    }
+
+    #[test]
+    fn parse_cli_command_reads_capture_options() {
+        assert_eq!(
+            parse_cli_command(&args(&[
+                "capture",
+                "clip.mp4",
+                "golden",
+                "--size",
+                "1440x1080",
+                "--at",
+                "0,1.5",
+                "--layout",
+                "contain"
+            ])),
+            Ok(Some(CliCommand::Capture {
+                source: PathBuf::from("clip.mp4"),
+                out_dir: PathBuf::from("golden"),
+                config: CaptureConfig {
+                    output_size: (1440, 1080),
+                    layout: LayoutMode::Contain,
+                    timestamps: vec![Duration::ZERO, Duration::from_millis(1500)],
+                },
+            }))
+        );
+        assert!(parse_cli_command(&args(&[
+            "capture", "clip.mp4", "golden", "--size", "1440", "--at", "0"
+        ]))
+        .unwrap_err()
+        .starts_with("Invalid size 1440"));
+        assert!(parse_cli_command(&args(&[
+            "capture", "clip.mp4", "golden", "--size", "8x8", "--at", "-1"
+        ]))
+        .unwrap_err()
+        .starts_with("Invalid timestamp -1"));
+        assert!(
+            parse_cli_command(&args(&["capture", "clip.mp4", "golden", "--size", "8x8"]))
+                .unwrap_err()
+                .starts_with("capture needs --at")
+        );
+    }
+
+    #[test]
+    fn parse_cli_command_reads_status() {
+        assert_eq!(
+            parse_cli_command(&args(&["status"])),
+            Ok(Some(CliCommand::Status { json: false }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["status", "--json"])),
+            Ok(Some(CliCommand::Status { json: true }))
+        );
+        assert!(parse_cli_command(&args(&["status", "--all"])).is_err());
+    }
+
+    #[test]
+    fn parse_cli_command_reads_stats() {
+        assert_eq!(
+            parse_cli_command(&args(&["stats"])),
+            Ok(Some(CliCommand::Stats {
+                watch: false,
+                json: false
+            }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["stats", "--json", "--watch"])),
+            Ok(Some(CliCommand::Stats {
+                watch: true,
+                json: true
+            }))
+        );
+        assert!(parse_cli_command(&args(&["stats", "--all"]))
+            .unwrap_err()
+            .starts_with("Unknown stats option --all"));
+    }
+
+    #[test]
+    fn parse_cli_command_reads_config_validate() {
+        assert_eq!(
+            parse_cli_command(&args(&["config", "validate"])),
+            Ok(Some(CliCommand::ValidateConfig { path: None }))
+        );
+        assert_eq!(
+            parse_cli_command(&args(&["config", "validate", "~/.config/lwe/config.yaml"])),
+            Ok(Some(CliCommand::ValidateConfig {
+                path: Some(PathBuf::from("~/.config/lwe/config.yaml")),
+            }))
+        );
+        assert!(parse_cli_command(&args(&["config", "check"]))
+            .unwrap_err()
+            .starts_with("Unknown config command check"));
+        assert!(
+            parse_cli_command(&args(&["config", "validate", "--strict"]))
+                .unwrap_err()
+                .starts_with("Unknown config validate option --strict")
+        );
+    }
+
+    #[test]
+    fn stats_report_tabulates_each_output() {
+        let outputs = [
+            OutputStats {
+                output: "DP-1".to_string(),
+                render_ms: Some(1.84),
+                fps: Some(59.94),
+                skip_rate: Some(0.012),
+                dropped_frames: 1234,
+                hwdec: Some("vaapi".to_string()),
+            },
+            OutputStats {
+                output: "HDMI-A-1".to_string(),
+                render_ms: None,
+                fps: None,
+                skip_rate: None,
+                dropped_frames: 0,
+                hwdec: Some("no".to_string()),
+            },
+        ];
+        let process = ProcessStats {
+            cpu_percent: Some(4.52),
+            rss_bytes: Some(150 << 20),
+        };
+
+        assert_eq!(
+            stats_report(&outputs, &process),
+            vec![
+                "CPU: 4.5%  Memory: 150.0 MiB".to_string(),
+                "OUTPUT       RENDER     FPS       DROPPED  DECODER".to_string(),
+                "DP-1         1.8 ms    59.9  1,234 (1.2%)  vaapi".to_string(),
+                "HDMI-A-1          -       -             0  software".to_string(),
+            ]
+        );
+        assert_eq!(
+            stats_report(&[], &ProcessStats::default()),
+            vec![
+                "CPU: -  Memory: -".to_string(),
+                "No wallpaper is playing".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn status_report_lists_each_output_with_its_decoder_and_frame_rate() {
+        let outputs = [
+            OutputStatus {
+                name: "DP-1".to_string(),
+                wallpaper: Some("/walls/rain.mp4".to_string()),
+                paused: false,
+                volume: 0.0,
+                title: Some("Rain".to_string()),
+                duration: Some(75.4),
+                hwdec: Some("vaapi".to_string()),
+                fps: Some(59.94),
+                skip_rate: Some(0.012),
+            },
+            OutputStatus {
+                name: "HDMI-A-1".to_string(),
+                wallpaper: Some("/walls/still.png".to_string()),
+                paused: true,
+                volume: 0.0,
+                title: None,
+                duration: None,
+                hwdec: None,
+                fps: None,
+                skip_rate: None,
+            },
+        ];
+
+        assert_eq!(
+            status_report(true, Some("OpenGL"), (true, false), false, &outputs),
+            vec![
+                "Engine: running with OpenGL".to_string(),
+                "Power: on battery".to_string(),
+                "DP-1: /walls/rain.mp4".to_string(),
+                "  Rain (1:15)".to_string(),
+                "  vaapi decoding, 59.9 fps, 1.2% dropped".to_string(),
+                "HDMI-A-1: /walls/still.png (paused)".to_string(),
+            ]
+        );
+        assert_eq!(
+            status_report(true, Some("OpenGL"), (false, false), true, &outputs[1..]),
+            vec![
+                "Engine: running with OpenGL".to_string(),
+                "Power: on AC".to_string(),
+                "Deep sleep: windows cover every output, decoding stopped".to_string(),
+                "HDMI-A-1: /walls/still.png (paused)".to_string(),
+            ]
+        );
+        assert_eq!(
+            status_report(false, None, (false, false), false, &[]),
+            vec!["No wallpaper is playing".to_string()]
+        );
+        assert_eq!(clock(3725.0), "1:02:05");
+    }
+
+    #[test]
+    fn render_node_report_marks_the_configured_node() {
+        let nodes = [
+            RenderNode {
+                path: PathBuf::from("/nonexistent/dri/renderD128"),
+                driver: Some("i915".to_string()),
+                pci_slot: Some("0000:00:02.0".to_string()),
+                pci_id: Some("8086:a7a0".to_string()),
+            },
+            RenderNode {
+                path: PathBuf::from("/nonexistent/dri/renderD129"),
+                driver: None,
+                pci_slot: None,
+                pci_id: None,
+            },
+        ];
+
+        assert_eq!(
+            render_node_report(&nodes, Some(Path::new("/nonexistent/dri/renderD129"))),
+            vec![
+                "Render nodes:".to_string(),
+                "  /nonexistent/dri/renderD128 (i915, 0000:00:02.0, 8086:a7a0)".to_string(),
+                "* /nonexistent/dri/renderD129 [render_device]".to_string(),
+            ]
+        );
+        assert!(
+            render_node_report(&nodes, Some(Path::new("/nonexistent/dri/renderD130")))
+                .last()
+                .unwrap()
+                .starts_with("Configured render_device /nonexistent/dri/renderD130 is not")
+        );
+        assert!(render_node_report(&[], None)[1].starts_with("No render_device set"));
+    }
+}
@@ -0,0 +1,526 @@
+//! Translations for `lwe` command-line output.
+//!
+//! Messages are written in English and looked up in a small catalog, like
+//! gettext. The language follows the GUI's saved language; with `system` it
+//! comes from `LC_ALL`, `LC_MESSAGES` or `LANG`. Counts, sizes and dates are
+//! formatted for the same language.
+
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use crate::results::settings_persistence::SettingsPersistenceLoad;
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CliLanguage {
+    English,
+    SimplifiedChinese,
+}
+
+static LANGUAGE: OnceLock<CliLanguage> = OnceLock::new();
+
+/// Detects the language once for this process. Until this runs, output is English.
+pub fn init() -> CliLanguage {
+    *LANGUAGE.get_or_init(|| {
+        let setting = match SettingsPersistenceService::for_user_path()
+            .map(|service| service.load_settings())
+        {
+            Ok(SettingsPersistenceLoad::Loaded(settings)) => settings.language,
+            _ => "system".to_string(),
+        };
+        language_for(&setting, |key| std::env::var(key).ok())
+    })
+}
+
+fn language() -> CliLanguage {
+    LANGUAGE.get().copied().unwrap_or(CliLanguage::English)
+}
+
+/// The saved GUI language wins; `system` falls back to the POSIX locale variables.
+fn language_for(setting: &str, env: impl Fn(&str) -> Option<String>) -> CliLanguage {
+    let locale = match setting {
+        "system" => ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(env)
+            .find(|value| !value.is_empty())
+            .unwrap_or_default(),
+        setting => setting.to_string(),
+    };
+
+    if locale.to_ascii_lowercase().starts_with("zh") {
+        CliLanguage::SimplifiedChinese
+    } else {
+        CliLanguage::English
+    }
+}
+
+/// Translates an English message.
+pub fn tr(english: &'static str) -> &'static str {
+    translate(language(), english)
+}
+
+/// Translates an English message and fills its `{}` placeholders in order.
+pub fn trf(english: &'static str, args: &[&dyn Display]) -> String {
+    fill(tr(english), args)
+}
+
+fn translate(language: CliLanguage, english: &'static str) -> &'static str {
+    match language {
+        CliLanguage::English => english,
+        CliLanguage::SimplifiedChinese => ZH_CN
+            .iter()
+            .find(|(key, _)| *key == english)
+            .map(|(_, translated)| *translated)
+            .unwrap_or(english),
+    }
+}
+
+/// `1234567` -> `1,234,567`
+pub fn format_count(count: u64) -> String {
+    count_for(language(), count)
+}
+
+/// `52428800` -> `50.0 MiB`
+pub fn format_size(bytes: u64) -> String {
+    size_for(language(), bytes)
+}
+
+/// Unix time -> `Mar 5, 2024` or `2024年3月5日`, in UTC
+pub fn format_date(unix_secs: u64) -> String {
+    date_for(language(), unix_secs)
+}
+
+fn count_for(language: CliLanguage, count: u64) -> String {
+    let separator = match language {
+        CliLanguage::English | CliLanguage::SimplifiedChinese => ',',
+    };
+    let digits = count.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+fn size_for(language: CliLanguage, bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", count_for(language, bytes));
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    let tenths = (size * 10.0).round() as u64;
+    format!(
+        "{}.{} {}",
+        count_for(language, tenths / 10),
+        tenths % 10,
+        UNITS[unit]
+    )
+}
+
+fn date_for(language: CliLanguage, unix_secs: u64) -> String {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let (year, month, day) = civil_date(unix_secs / 86_400);
+    match language {
+        CliLanguage::English => format!("{} {day}, {year}", MONTHS[month as usize - 1]),
+        CliLanguage::SimplifiedChinese => format!("{year}年{month}月{day}日"),
+    }
+}
+
+/// Days since 1970-01-01 -> (year, month, day), after Howard Hinnant's
+/// `civil_from_days`
+fn civil_date(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    if let Some(first) = parts.next() {
+        filled.push_str(first);
+    }
+    for part in parts {
+        if let Some(arg) = args.next() {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
+
+const ZH_CN: &[(&str, &str)] = &[
+    // Usage
+    (
+        "Usage: lwe install-service [--hyprland|--sway|--niri]",
+        "用法：lwe install-service [--hyprland|--sway|--niri]",
+    ),
+    (
+        "Usage: lwe check [--json]\n       lwe check --gpus",
+        "用法：lwe check [--json]\n      lwe check --gpus",
+    ),
+    ("Usage: lwe doctor [--fix]", "用法：lwe doctor [--fix]"),
+    ("Usage: lwe open <id> [--player]", "用法：lwe open <ID> [--player]"),
+    (
+        "Usage: lwe workshop info <id>\n       lwe workshop search <id> [<id>...]\n       lwe workshop extract <dir|pkg> --out <dir>",
+        "用法：lwe workshop info <id>\n      lwe workshop search <id> [<id>...]\n      lwe workshop extract <dir|pkg> --out <dir>",
+    ),
+    (
+        "Usage: lwe library query [<text>] [--tag <tag>]... [--type <type>] [--folder <dir>] [--favorites] [--min-rating <0-5>] [--sort <order>] [--offset <n>] [--limit <n>]",
+        "用法：lwe library query [<文本>] [--tag <标签>]... [--type <类型>] [--folder <目录>] [--favorites] [--min-rating <0-5>] [--sort <排序>] [--offset <n>] [--limit <n>]",
+    ),
+    (
+        "Usage: lwe random [<text>] [--tag <tag>]... [--type <type>] [--folder <dir>] [--favorites] [--min-rating <0-5>] [--output <output>]",
+        "用法：lwe random [<文本>] [--tag <标签>]... [--type <类型>] [--folder <目录>] [--favorites] [--min-rating <0-5>] [--output <显示输出>]",
+    ),
+    (
+        "Usage: lwe profile save <name>\n       lwe profile load <name>\n       lwe profile list",
+        "用法：lwe profile save <名称>\n      lwe profile load <名称>\n      lwe profile list",
+    ),
+    (
+        "Usage: lwe collection list\n       lwe collection apply <name> [--output <output>]",
+        "用法：lwe collection list\n      lwe collection apply <名称> [--output <显示输出>]",
+    ),
+    (
+        "Usage: lwe playlist next [--output <output>]\n       lwe playlist prev [--output <output>]",
+        "用法：lwe playlist next [--output <显示输出>]\n      lwe playlist prev [--output <显示输出>]",
+    ),
+    (
+        "Usage: lwe seek <[+|-]seconds> [--output <output>]\n       lwe rate <speed> [--output <output>]\n       lwe frame-step [--back] [--output <output>]\n       lwe loop <start> <end> [--output <output>]\n       lwe loop off [--output <output>]",
+        "用法：lwe seek <[+|-]秒数> [--output <显示输出>]\n      lwe rate <速度> [--output <显示输出>]\n      lwe frame-step [--back] [--output <显示输出>]\n      lwe loop <起点> <终点> [--output <显示输出>]\n      lwe loop off [--output <显示输出>]",
+    ),
+    (
+        "Usage: lwe color --output <output> [--brightness <0-2>] [--contrast <0-2>] [--gamma <0-2>] [--saturation <0-2>]\n       lwe color --output <output> --reset",
+        "用法：lwe color --output <显示输出> [--brightness <0-2>] [--contrast <0-2>] [--gamma <0-2>] [--saturation <0-2>]\n      lwe color --output <显示输出> --reset",
+    ),
+    (
+        "Usage: lwe snapshot --output <output> --file <image>",
+        "用法：lwe snapshot --output <显示输出> --file <图片>",
+    ),
+    (
+        "Usage: lwe mpv set <output> <property> <value>",
+        "用法：lwe mpv set <显示输出> <属性> <值>",
+    ),
+    (
+        "Usage: lwe cache prune\n       lwe cache clear",
+        "用法：lwe cache prune\n      lwe cache clear",
+    ),
+    ("Usage: lwe status [--json]", "用法：lwe status [--json]"),
+    (
+        "Usage: lwe stats [--watch] [--json]",
+        "用法：lwe stats [--watch] [--json]",
+    ),
+    ("Usage: lwe config validate [<path>]", "用法：lwe config validate [<路径>]"),
+    (
+        "Usage: lwe migrate [mpvpaper|swww|hyprpaper] [<path>] [--dry-run]",
+        "用法：lwe migrate [mpvpaper|swww|hyprpaper] [<路径>] [--dry-run]",
+    ),
+    (
+        "Usage: lwe tools rotate <in> <out> --for-output <output> [--ccw]\n       lwe tools crop <in> <out> --for-output <output>",
+        "用法：lwe tools rotate <输入> <输出> --for-output <显示输出> [--ccw]\n      lwe tools crop <输入> <输出> --for-output <显示输出>",
+    ),
+    // Argument errors
+    ("Unknown install-service option {}", "未知的 install-service 选项 {}"),
+    ("Only one compositor can be selected", "只能选择一个合成器"),
+    ("Unknown check option {}", "未知的 check 选项 {}"),
+    ("Unknown doctor option {}", "未知的 doctor 选项 {}"),
+    ("Unknown library command {}", "未知的 library 命令 {}"),
+    ("Unknown open option {}", "未知的 open 选项 {}"),
+    ("Unknown tool {}", "未知的工具 {}"),
+    ("--for-output needs an output name", "--for-output 需要显示输出名称"),
+    ("Unknown tools {} option {}", "未知的 tools {} 选项 {}"),
+    (
+        "tools {} needs an input and an output path",
+        "tools {} 需要输入路径和输出路径",
+    ),
+    ("tools {} needs --for-output", "tools {} 需要 --for-output"),
+    ("{} needs a value", "{} 需要一个值"),
+    ("{} needs a number, not {}", "{} 需要数字，而不是 {}"),
+    ("--min-rating needs 0 to 5, not {}", "--min-rating 需要 0 到 5，而不是 {}"),
+    ("Unknown library query option {}", "未知的 library query 选项 {}"),
+    ("Unknown random option {}", "未知的 random 选项 {}"),
+    ("profile {} takes a profile name", "profile {} 需要一个方案名称"),
+    ("Unknown profile command {}", "未知的 profile 命令 {}"),
+    ("collection apply takes a collection name", "collection apply 需要一个合集名称"),
+    ("Unknown collection apply option {}", "未知的 collection apply 选项 {}"),
+    ("Unknown collection command {}", "未知的 collection 命令 {}"),
+    ("Unknown playlist command {}", "未知的 playlist 命令 {}"),
+    ("Unknown playlist option {}", "未知的 playlist 选项 {}"),
+    ("Unknown {} option {}", "未知的 {} 选项 {}"),
+    ("Unknown status option {}", "未知的 status 选项 {}"),
+    ("Unknown stats option {}", "未知的 stats 选项 {}"),
+    ("Unknown config command {}", "未知的 config 命令 {}"),
+    ("Unknown config validate option {}", "未知的 config validate 选项 {}"),
+    ("Unknown migrate option {}", "未知的 migrate 选项 {}"),
+    ("migrate needs the tool a path belongs to", "migrate 需要指明路径所属的工具"),
+    ("{} needs a number of seconds, not {}", "{} 需要秒数，而不是 {}"),
+    ("rate needs a speed above 0, not {}", "rate 需要大于 0 的速度，而不是 {}"),
+    ("loop needs an end after its start", "loop 的终点必须在起点之后"),
+    ("Unknown color option {}", "未知的 color 选项 {}"),
+    ("{} needs 0 to 2, not {}", "{} 需要 0 到 2，而不是 {}"),
+    ("color needs --output", "color 需要 --output"),
+    ("Unknown snapshot option {}", "未知的 snapshot 选项 {}"),
+    (
+        "snapshot needs --output and --file",
+        "snapshot 需要 --output 和 --file",
+    ),
+    (
+        "mpv set needs an output, a property and a value",
+        "mpv set 需要显示输出、属性和值",
+    ),
+    ("Unknown mpv command {}", "未知的 mpv 命令 {}"),
+    ("Unknown cache command {}", "未知的 cache 命令 {}"),
+    ("Unknown cache {} option {}", "未知的 cache {} 选项 {}"),
+    (
+        "Removed {} thumbnails and reclaimed {}",
+        "已删除 {} 个缩略图，释放 {}",
+    ),
+    (
+        "The thumbnail cache now takes {} of {}",
+        "缩略图缓存现在占用 {}，上限 {}",
+    ),
+    ("Unable to resolve file {}: {}", "无法解析文件 {}：{}"),
+    ("Unable to resolve folder {}: {}", "无法解析目录 {}：{}"),
+    ("{} is not a Workshop id or URL", "{} 不是创意工坊 ID 或链接"),
+    ("workshop {} needs Workshop ids", "workshop {} 需要创意工坊 ID"),
+    ("Unknown workshop command {}", "未知的 workshop 命令 {}"),
+    (
+        "Unknown workshop extract option {}",
+        "未知的 workshop extract 选项 {}",
+    ),
+    (
+        "workshop extract needs one project directory or package",
+        "workshop extract 需要一个项目目录或资源包",
+    ),
+    ("workshop extract needs --out", "workshop extract 需要 --out"),
+    // doctor
+    ("No setup problems found.", "未发现配置问题。"),
+    ("Problems:", "问题："),
+    ("Run `lwe doctor --fix` to repair them.", "运行 `lwe doctor --fix` 进行修复。"),
+    ("Skipped.", "已跳过。"),
+    ("{} fixes failed", "{} 项修复失败"),
+    ("Failed to write prompt: {}", "无法写入提示：{}"),
+    ("Failed to read answer: {}", "无法读取回答：{}"),
+    // library
+    ("Unable to open the library database: {}", "无法打开壁纸库数据库：{}"),
+    ("Library query failed: {}", "壁纸库查询失败：{}"),
+    ("Failed to encode the library response: {}", "无法编码壁纸库查询结果：{}"),
+    // random
+    ("Could not reach the running LWE app: {}", "无法连接正在运行的 LWE 应用：{}"),
+    (
+        "The running LWE app sent an unexpected reply",
+        "正在运行的 LWE 应用返回了无法识别的回复",
+    ),
+    // profile
+    ("No profiles saved yet", "尚未保存任何壁纸方案"),
+    // collection
+    ("No collections yet", "还没有合集"),
+    ("{} wallpapers", "{} 张壁纸"),
+    // status
+    ("Failed to encode the status response: {}", "无法编码状态信息：{}"),
+    ("No wallpaper is playing", "当前没有播放壁纸"),
+    ("Engine: running with {}", "引擎：正在运行，使用 {}"),
+    (
+        "Power: on battery, wallpapers paused to save power",
+        "电源：使用电池，已暂停壁纸以节省电量",
+    ),
+    ("Power: on battery", "电源：使用电池"),
+    ("Power: on AC", "电源：使用交流电"),
+    (
+        "Deep sleep: windows cover every output, decoding stopped",
+        "深度休眠：所有显示器都被窗口遮挡，已停止解码",
+    ),
+    ("no wallpaper", "无壁纸"),
+    ("{}: {} (paused)", "{}：{}（已暂停）"),
+    ("software decoding", "软件解码"),
+    ("{} decoding", "{} 解码"),
+    ("{}% dropped", "丢帧 {}%"),
+    // stats
+    ("Failed to encode the stats response: {}", "无法编码统计信息：{}"),
+    ("CPU: {}  Memory: {}", "CPU：{}  内存：{}"),
+    ("OUTPUT", "显示输出"),
+    ("RENDER", "渲染"),
+    ("FPS", "帧率"),
+    ("DROPPED", "丢帧"),
+    ("DECODER", "解码器"),
+    ("software", "软件"),
+    // config
+    ("No problems found in {}", "{} 中未发现问题"),
+    ("error", "错误"),
+    ("warning", "警告"),
+    ("{}: {} errors", "{}：{} 处错误"),
+    // migrate
+    (
+        "No mpvpaper, swww or hyprpaper wallpapers found",
+        "未找到 mpvpaper、swww 或 hyprpaper 的壁纸",
+    ),
+    ("all outputs", "所有显示输出"),
+    ("Would add {} to the Library", "将向壁纸库添加 {} 项"),
+    ("Added {} to the Library", "已向壁纸库添加 {} 项"),
+    ("Layout and audio settings for {}", "{} 的布局和音频设置"),
+    ("Skipped: {}", "已跳过：{}"),
+    (
+        "LWE shows these wallpapers the next time it starts; stop the other tool first.",
+        "LWE 下次启动时会显示这些壁纸；请先停止原来的工具。",
+    ),
+    // open
+    ("Playing in {}", "正在使用 {} 播放"),
+    ("Showing {}", "正在显示 {}"),
+    // workshop
+    ("Workshop item {} was not found on Steam", "在 Steam 上找不到创意工坊条目 {}"),
+    ("Not listed on Steam anymore", "已不在 Steam 上公开"),
+    ("Downloaded: {}", "已下载：{}"),
+    ("Downloaded: no", "已下载：否"),
+    ("not found", "未找到"),
+    ("Untitled", "无标题"),
+    ("Size: {}", "大小：{}"),
+    ("Updated: {}", "更新于：{}"),
+    ("Tags: {}", "标签：{}"),
+    ("Subscriptions: {}", "订阅数：{}"),
+    ("Preview: {}", "预览图：{}"),
+    // check
+    (
+        "Failed to encode the capability report: {}",
+        "无法编码功能报告：{}",
+    ),
+    // check --gpus
+    ("No DRM render nodes found in /dev/dri", "在 /dev/dri 中未找到 DRM 渲染节点"),
+    ("Render nodes:", "渲染节点："),
+    (
+        "Configured render_device {} is not a render node on this system; the engine falls back to the default GPU",
+        "配置的 render_device {} 不是本机的渲染节点，引擎将改用默认 GPU",
+    ),
+    (
+        "No render_device set in [gpu]; EGL picks the GPU (DRI_PRIME=1 selects the discrete one)",
+        "[gpu] 中未设置 render_device，由 EGL 选择 GPU（DRI_PRIME=1 会选择独立显卡）",
+    ),
+    // workshop extract
+    (
+        "Extracted {} files and {} textures to {}",
+        "已将 {} 个文件和 {} 张纹理解包到 {}",
+    ),
+    ("Scene: {}", "场景：{}"),
+    (
+        "Textures that could not be converted:",
+        "无法转换的纹理：",
+    ),
+    // tools
+    ("Wrote {} at {}x{} for {}", "已写入 {}，分辨率 {}x{}，用于 {}"),
+    ("Added to the library as {}", "已添加到壁纸库，ID 为 {}"),
+    ("Not added to the library: {}", "未添加到壁纸库：{}"),
+    // install-service
+    ("Unable to resolve current executable: {}", "无法确定当前可执行文件：{}"),
+    (
+        "Unable to use non-UTF-8 executable path {} for the systemd unit",
+        "systemd 单元无法使用非 UTF-8 的可执行文件路径 {}",
+    ),
+    ("Wrote {}", "已写入 {}"),
+    ("Added {} startup line to {}", "已将 {} 启动命令添加到 {}"),
+    ("{} config {} already starts {}", "{} 配置 {} 已经会启动 {}"),
+    ("Enable it with:", "使用以下命令启用："),
+    (
+        "Turn off \"Launch on login\" in Settings so LWE is not started twice.",
+        "请在设置中关闭“登录时启动”，以免 LWE 被启动两次。",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{count_for, date_for, fill, language_for, size_for, translate, CliLanguage, ZH_CN};
+
+    #[test]
+    fn cli_language_follows_saved_language_then_locale() {
+        let env =
+            |locale: &'static str| move |key: &str| (key == "LANG").then(|| locale.to_string());
+
+        assert_eq!(
+            language_for("zh-CN", env("en_US.UTF-8")),
+            CliLanguage::SimplifiedChinese
+        );
+        assert_eq!(language_for("en", env("zh_CN.UTF-8")), CliLanguage::English);
+        assert_eq!(
+            language_for("system", env("zh_CN.UTF-8")),
+            CliLanguage::SimplifiedChinese
+        );
+        assert_eq!(language_for("system", env("C")), CliLanguage::English);
+        assert_eq!(language_for("system", |_| None), CliLanguage::English);
+    }
+
+    #[test]
+    fn cli_translations_keep_every_placeholder() {
+        for (english, translated) in ZH_CN {
+            assert_eq!(
+                english.matches("{}").count(),
+                translated.matches("{}").count(),
+                "{english}"
+            );
+        }
+    }
+
+    #[test]
+    fn cli_messages_translate_and_fill_placeholders() {
+        let template = translate(CliLanguage::SimplifiedChinese, "Unknown tools {} option {}");
+        assert_eq!(
+            fill(template, &[&"crop", &"--ccw"]),
+            "未知的 tools crop 选项 --ccw"
+        );
+        assert_eq!(
+            translate(
+                CliLanguage::SimplifiedChinese,
+                "A message without a translation"
+            ),
+            "A message without a translation"
+        );
+        assert_eq!(
+            fill(
+                translate(CliLanguage::English, "Wrote {} at {}x{} for {}"),
+                &[&"out.mp4", &1080, &1920, &"DP-2"]
+            ),
+            "Wrote out.mp4 at 1080x1920 for DP-2"
+        );
+    }
+
+    #[test]
+    fn cli_counts_sizes_and_dates_follow_the_language() {
+        assert_eq!(count_for(CliLanguage::English, 999), "999");
+        assert_eq!(count_for(CliLanguage::English, 1_234_567), "1,234,567");
+        assert_eq!(size_for(CliLanguage::English, 512), "512 B");
+        assert_eq!(size_for(CliLanguage::English, 1536), "1.5 KiB");
+        assert_eq!(size_for(CliLanguage::English, 52_428_800), "50.0 MiB");
+        assert_eq!(
+            size_for(CliLanguage::English, 1_500 * 1024 * 1024 * 1024 * 1024),
+            "1,500.0 TiB"
+        );
+
+        // 2024-03-05 12:00 UTC
+        assert_eq!(date_for(CliLanguage::English, 1_709_640_000), "Mar 5, 2024");
+        assert_eq!(
+            date_for(CliLanguage::SimplifiedChinese, 1_709_640_000),
+            "2024年3月5日"
+        );
+        assert_eq!(date_for(CliLanguage::English, 0), "Jan 1, 1970");
+    }
+}
@@ -1,8 +1,36 @@
+use std::path::PathBuf;
+
+use crate::action_outcome::{ActionOutcome, InvalidatedPage};
 use crate::assembly::app_shell::assemble_app_shell;
-use crate::models::AppShellSnapshot;
+use crate::models::{AppShellSnapshot, OnboardingFinishInput, OnboardingSnapshot};
 use crate::services::app_shell_service::AppShellService;
+use crate::services::library_watch_service::LibraryWatchService;
+use crate::services::onboarding_service::OnboardingService;
 
 #[tauri::command]
 pub fn load_app_shell() -> Result<AppShellSnapshot, String> {
     Ok(assemble_app_shell(AppShellService::load_summary()?))
 }
+
+/// Runs on a worker thread: probing EGL and every render node takes a moment
+#[tauri::command]
+pub async fn load_onboarding() -> Result<OnboardingSnapshot, String> {
+    tauri::async_runtime::spawn_blocking(OnboardingService::load)
+        .await
+        .map_err(|error| format!("Failed to probe the system: {error}"))?
+}
+
+#[tauri::command]
+pub fn finish_onboarding(input: OnboardingFinishInput) -> Result<ActionOutcome<()>, String> {
+    let folders = input.folders.iter().map(PathBuf::from).collect::<Vec<_>>();
+    OnboardingService::finish(input)?;
+    LibraryWatchService::watch_folders(&folders);
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some("Settings created".to_string()),
+        shell_patch: None,
+        current_update: None,
+        invalidations: vec![InvalidatedPage::Settings, InvalidatedPage::Library],
+    })
+}
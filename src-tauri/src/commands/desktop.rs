@@ -1,8 +1,17 @@
-use crate::action_outcome::ActionOutcome;
-use crate::assembly::action_outcome::assemble_desktop_apply_outcome;
-use crate::assembly::desktop_page::assemble_desktop_page;
-use crate::models::DesktopPageSnapshot;
+use crate::action_outcome::{ActionOutcome, InvalidatedPage};
+use crate::assembly::action_outcome::{
+    assemble_desktop_apply_outcome, assemble_desktop_bulk_apply_outcome,
+};
+use crate::assembly::desktop_page::{
+    assemble_desktop_page, assemble_profiles, assemble_schedule_status,
+};
+use crate::models::{
+    DesktopPageSnapshot, MonitorColorModel, MonitorPalette, ProfileModel, ScheduleStatusSnapshot,
+};
+use crate::results::desktop::DesktopMonitorColor;
 use crate::services::desktop_service::DesktopService;
+use crate::services::profile_service::ProfileService;
+use crate::services::schedule_service::ScheduleService;
 
 #[tauri::command]
 pub fn load_desktop_page() -> Result<DesktopPageSnapshot, String> {
@@ -19,6 +28,20 @@ pub fn apply_library_item_to_monitor(
     ))
 }
 
+#[tauri::command]
+pub fn apply_library_item_to_all_monitors(item_id: String) -> Result<ActionOutcome<()>, String> {
+    Ok(assemble_desktop_bulk_apply_outcome(
+        DesktopService::apply_to_all_monitors(&item_id)?,
+    ))
+}
+
+#[tauri::command]
+pub fn undo_desktop_apply() -> Result<ActionOutcome<()>, String> {
+    Ok(assemble_desktop_bulk_apply_outcome(
+        DesktopService::undo_last_apply()?,
+    ))
+}
+
 #[tauri::command]
 pub fn clear_library_item_from_monitor(monitor_id: String) -> Result<ActionOutcome<()>, String> {
     Ok(assemble_desktop_apply_outcome(
@@ -26,6 +49,106 @@ pub fn clear_library_item_from_monitor(monitor_id: String) -> Result<ActionOutco
     ))
 }
 
+#[tauri::command]
+pub fn set_monitor_audio(
+    monitor_id: String,
+    volume: u8,
+    muted: bool,
+) -> Result<ActionOutcome<DesktopPageSnapshot>, String> {
+    DesktopService::set_monitor_audio(&monitor_id, volume, muted)?;
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: None,
+        shell_patch: None,
+        current_update: Some(assemble_desktop_page(DesktopService::load_page()?)),
+        invalidations: vec![InvalidatedPage::Desktop],
+    })
+}
+
+#[tauri::command]
+pub fn set_monitor_color(
+    monitor_id: String,
+    color: MonitorColorModel,
+) -> Result<ActionOutcome<DesktopPageSnapshot>, String> {
+    DesktopService::set_monitor_color(
+        &monitor_id,
+        DesktopMonitorColor {
+            brightness: color.brightness,
+            contrast: color.contrast,
+            gamma: color.gamma,
+            saturation: color.saturation,
+        },
+    )?;
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: None,
+        shell_patch: None,
+        current_update: Some(assemble_desktop_page(DesktopService::load_page()?)),
+        invalidations: vec![InvalidatedPage::Desktop],
+    })
+}
+
+#[tauri::command]
+pub fn load_monitor_palettes(monitor_id: Option<String>) -> Result<Vec<MonitorPalette>, String> {
+    Ok(DesktopService::palettes(monitor_id.as_deref())?
+        .into_iter()
+        .map(|(monitor_id, palette)| MonitorPalette {
+            monitor_id,
+            colors: palette.hex_colors(),
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn load_schedule_status() -> Result<ScheduleStatusSnapshot, String> {
+    Ok(assemble_schedule_status(ScheduleService::status()))
+}
+
+#[tauri::command]
+pub fn set_schedule_slot(
+    slot: Option<String>,
+) -> Result<ActionOutcome<ScheduleStatusSnapshot>, String> {
+    ScheduleService::pin(slot.as_deref())?;
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some(match slot {
+            Some(slot) => format!("Showing the {slot} slot until the schedule changes"),
+            None => "Following the schedule".to_string(),
+        }),
+        shell_patch: None,
+        current_update: Some(assemble_schedule_status(ScheduleService::status())),
+        invalidations: vec![InvalidatedPage::Desktop],
+    })
+}
+
+#[tauri::command]
+pub fn list_profiles() -> Result<Vec<ProfileModel>, String> {
+    ProfileService::list().map(assemble_profiles)
+}
+
+#[tauri::command]
+pub fn save_profile(name: String) -> Result<ActionOutcome<Vec<ProfileModel>>, String> {
+    let profile = ProfileService::save(&name)?;
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some(format!("Saved profile {}", profile.name)),
+        shell_patch: None,
+        current_update: Some(assemble_profiles(ProfileService::list()?)),
+        invalidations: Vec::new(),
+    })
+}
+
+#[tauri::command]
+pub fn apply_profile(name: String) -> Result<ActionOutcome<()>, String> {
+    Ok(assemble_desktop_bulk_apply_outcome(ProfileService::load(
+        &name,
+    )?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,17 @@
+use crate::assembly::diagnostics_page::{assemble_diagnostics_logs, assemble_diagnostics_page};
+use crate::models::{DiagnosticsLogLineModel, DiagnosticsPageSnapshot};
+use crate::services::diagnostics_service::DiagnosticsService;
+
+#[tauri::command]
+pub fn load_diagnostics_page() -> Result<DiagnosticsPageSnapshot, String> {
+    DiagnosticsService::load_page().map(assemble_diagnostics_page)
+}
+
+#[tauri::command]
+pub fn load_diagnostics_logs(
+    after_sequence: Option<u64>,
+) -> Result<Vec<DiagnosticsLogLineModel>, String> {
+    Ok(assemble_diagnostics_logs(DiagnosticsService::logs_after(
+        after_sequence,
+    )))
+}
@@ -1,15 +1,30 @@
+use crate::action_outcome::{ActionOutcome, InvalidatedPage};
 use crate::assembly::library_detail::assemble_library_detail;
 use crate::assembly::library_page::assemble_library_page;
-use crate::models::{LibraryItemDetail, LibraryPageSnapshot};
+use crate::models::{
+    LibraryDeleteEstimate, LibraryDeleteMode, LibraryItemDetail, LibraryPageSnapshot,
+};
 use crate::services::desktop_service::DesktopService;
+use crate::services::file_picker_service::FilePickerService;
+use crate::services::item_open_service::ItemOpenService;
+use crate::services::library_cleanup_service::{format_size, LibraryCleanupService};
+use crate::services::library_marks_service::LibraryMarksService;
 use crate::services::library_service::LibraryService;
+use crate::services::library_watch_service::LibraryWatchService;
+use crate::services::preview_service::PreviewService;
+use crate::services::thumbnail_cache_service::ThumbnailCacheService;
+use crate::services::wallpaper_properties_service::WallpaperPropertiesService;
 
 #[tauri::command]
 pub fn load_library_page() -> Result<LibraryPageSnapshot, String> {
     let projection = LibraryService::load_projection()?;
     let desktop = DesktopService::load_page_with_projection(Ok(projection.clone()))?;
 
-    Ok(assemble_library_page(projection, &desktop))
+    Ok(assemble_library_page(
+        projection,
+        &desktop,
+        &LibraryMarksService::marks(),
+    ))
 }
 
 #[tauri::command]
@@ -17,12 +32,331 @@ pub fn load_library_item_detail(item_id: String) -> Result<LibraryItemDetail, St
     let projection = LibraryService::load_projection()?;
     let desktop = DesktopService::load_page_with_projection(Ok(projection.clone()))?;
 
+    let entry = LibraryService::inspect_item_in_projection(&projection, &item_id)?;
+    let media = LibraryService::media(&entry);
+
     Ok(assemble_library_detail(
-        LibraryService::inspect_item_in_projection(&projection, &item_id)?,
+        entry,
         &desktop,
+        &LibraryMarksService::marks_for(&item_id),
+        &LibraryMarksService::user_properties(&item_id),
+        media,
     ))
 }
 
+#[tauri::command]
+pub fn set_library_item_favorite(
+    item_id: String,
+    favorite: bool,
+) -> Result<ActionOutcome<()>, String> {
+    LibraryMarksService::set_favorite(&item_id, favorite)?;
+    Ok(marked_outcome())
+}
+
+/// `rating` is 1 to 5, or 0 to clear it
+#[tauri::command]
+pub fn set_library_item_rating(item_id: String, rating: u8) -> Result<ActionOutcome<()>, String> {
+    LibraryMarksService::set_rating(&item_id, rating)?;
+    Ok(marked_outcome())
+}
+
+/// `title` of None or blank restores the scanned title
+#[tauri::command]
+pub fn set_library_item_title(
+    item_id: String,
+    title: Option<String>,
+) -> Result<ActionOutcome<()>, String> {
+    LibraryMarksService::set_title(&item_id, title.as_deref())?;
+    Ok(marked_outcome())
+}
+
+#[tauri::command]
+pub fn set_library_item_tags(
+    item_id: String,
+    tags: Vec<String>,
+) -> Result<ActionOutcome<()>, String> {
+    LibraryMarksService::set_tags(&item_id, &tags)?;
+    Ok(marked_outcome())
+}
+
+/// `value` must suit the property's control; a default value clears the
+/// user's choice
+#[tauri::command]
+pub fn set_library_item_property(
+    item_id: String,
+    name: String,
+    value: serde_json::Value,
+) -> Result<ActionOutcome<()>, String> {
+    WallpaperPropertiesService::set(&item_id, &name, value)?;
+    Ok(marked_outcome())
+}
+
+#[tauri::command]
+pub fn reset_library_item_properties(item_id: String) -> Result<ActionOutcome<()>, String> {
+    WallpaperPropertiesService::reset(&item_id)?;
+    Ok(marked_outcome())
+}
+
+/// Runs on a worker thread while the file dialog is open. The shader takes
+/// effect the next time the item is applied.
+#[tauri::command]
+pub async fn pick_library_item_shader(item_id: String) -> Result<ActionOutcome<()>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let Some(shader) =
+            FilePickerService::pick_file("Choose a shader", "GLSL shaders", &["*.glsl"])?
+        else {
+            return Ok(ActionOutcome {
+                ok: true,
+                message: None,
+                shell_patch: None,
+                current_update: None,
+                invalidations: Vec::new(),
+            });
+        };
+        LibraryMarksService::set_shader(&item_id, Some(&shader))?;
+        Ok(marked_outcome())
+    })
+    .await
+    .map_err(|error| format!("Failed to pick a shader: {error}"))?
+}
+
+#[tauri::command]
+pub fn clear_library_item_shader(item_id: String) -> Result<ActionOutcome<()>, String> {
+    LibraryMarksService::set_shader(&item_id, None)?;
+    Ok(marked_outcome())
+}
+
+/// Runs on a worker thread while the file dialog is open. The LUT takes
+/// effect the next time the item is applied.
+#[tauri::command]
+pub async fn pick_library_item_lut(item_id: String) -> Result<ActionOutcome<()>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let Some(lut) = FilePickerService::pick_file("Choose a LUT", "Cube LUTs", &["*.cube"])?
+        else {
+            return Ok(ActionOutcome {
+                ok: true,
+                message: None,
+                shell_patch: None,
+                current_update: None,
+                invalidations: Vec::new(),
+            });
+        };
+        LibraryMarksService::set_lut(&item_id, Some(&lut))?;
+        Ok(marked_outcome())
+    })
+    .await
+    .map_err(|error| format!("Failed to pick a LUT: {error}"))?
+}
+
+#[tauri::command]
+pub fn clear_library_item_lut(item_id: String) -> Result<ActionOutcome<()>, String> {
+    LibraryMarksService::set_lut(&item_id, None)?;
+    Ok(marked_outcome())
+}
+
+fn marked_outcome() -> ActionOutcome<()> {
+    ActionOutcome {
+        ok: true,
+        message: None,
+        shell_patch: None,
+        current_update: None,
+        invalidations: vec![InvalidatedPage::Library],
+    }
+}
+
+#[tauri::command]
+pub fn stop_library_preview() -> Result<(), String> {
+    PreviewService::stop()
+}
+
+/// Queue covers the user is likely to see next behind the ones on screen
+#[tauri::command]
+pub fn queue_thumbnails(cover_paths: Vec<String>) {
+    ThumbnailCacheService::prefetch(&cover_paths);
+}
+
+#[tauri::command]
+pub fn add_library_items_tag(
+    item_ids: Vec<String>,
+    tag: String,
+) -> Result<ActionOutcome<()>, String> {
+    for item_id in &item_ids {
+        LibraryMarksService::add_tag(item_id, &tag)?;
+    }
+
+    Ok(ActionOutcome {
+        message: Some(format!(
+            "Tagged {} item(s) with {}",
+            item_ids.len(),
+            tag.trim()
+        )),
+        ..marked_outcome()
+    })
+}
+
+/// Fresh covers arrive from the background queue; scrub strips are rebuilt
+/// the next time a card is hovered
+#[tauri::command]
+pub fn regenerate_library_thumbnails(item_ids: Vec<String>) -> Result<ActionOutcome<()>, String> {
+    let projection = LibraryService::load_projection()?;
+    let entries = item_ids
+        .iter()
+        .map(|item_id| LibraryService::inspect_item_in_projection(&projection, item_id))
+        .collect::<Result<Vec<_>, _>>()?;
+    ThumbnailCacheService::regenerate(&entries)?;
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some(format!(
+            "Regenerating thumbnails of {} item(s)",
+            entries.len()
+        )),
+        shell_patch: None,
+        current_update: None,
+        invalidations: Vec::new(),
+    })
+}
+
+/// Runs on a worker thread while the save dialog is open
+#[tauri::command]
+pub async fn export_library_item_paths(item_ids: Vec<String>) -> Result<ActionOutcome<()>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let Some(destination) =
+            FilePickerService::pick_save_file("Export wallpaper paths", "wallpapers.txt")?
+        else {
+            return Ok(ActionOutcome {
+                ok: true,
+                message: None,
+                shell_patch: None,
+                current_update: None,
+                invalidations: Vec::new(),
+            });
+        };
+        let exported = LibraryService::export_paths(&item_ids, &destination)?;
+
+        Ok(ActionOutcome {
+            ok: true,
+            message: Some(format!(
+                "Exported {exported} path(s) to {}",
+                destination.display()
+            )),
+            shell_patch: None,
+            current_update: None,
+            invalidations: Vec::new(),
+        })
+    })
+    .await
+    .map_err(|error| format!("Failed to export paths: {error}"))?
+}
+
+#[tauri::command]
+pub fn reveal_library_item(item_id: String) -> Result<ActionOutcome<()>, String> {
+    let path = ItemOpenService::reveal(&item_id)?;
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some(format!("Showing {} in the file manager", path.display())),
+        shell_patch: None,
+        current_update: None,
+        invalidations: Vec::new(),
+    })
+}
+
+#[tauri::command]
+pub fn play_library_item_externally(item_id: String) -> Result<ActionOutcome<()>, String> {
+    let player = ItemOpenService::play_external(&item_id)?;
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some(format!("Playing in {player}")),
+        shell_patch: None,
+        current_update: None,
+        invalidations: Vec::new(),
+    })
+}
+
+/// Runs on a worker thread: the portal dialog stays open until the user
+/// answers, and the window must keep drawing meanwhile
+#[tauri::command]
+pub async fn add_library_folders() -> Result<ActionOutcome<()>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let folders = FilePickerService::pick_folders("Add wallpaper folders")?;
+        let added = LibraryService::add_local_sources(folders.clone(), Vec::new())?;
+        LibraryWatchService::watch_folders(&folders);
+        added_sources_outcome(added)
+    })
+    .await
+    .map_err(|error| format!("Failed to pick folders: {error}"))?
+}
+
+#[tauri::command]
+pub async fn add_library_files() -> Result<ActionOutcome<()>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let files = FilePickerService::pick_media_files("Add wallpaper files")?;
+        added_sources_outcome(LibraryService::add_local_sources(Vec::new(), files)?)
+    })
+    .await
+    .map_err(|error| format!("Failed to pick files: {error}"))?
+}
+
+fn added_sources_outcome(added: usize) -> Result<ActionOutcome<()>, String> {
+    Ok(ActionOutcome {
+        ok: true,
+        message: (added > 0).then(|| format!("Added {added} location(s) to the library")),
+        shell_patch: None,
+        current_update: None,
+        invalidations: if added > 0 {
+            vec![InvalidatedPage::Library, InvalidatedPage::Desktop]
+        } else {
+            Vec::new()
+        },
+    })
+}
+
+#[tauri::command]
+pub fn estimate_library_deletion(item_ids: Vec<String>) -> Result<LibraryDeleteEstimate, String> {
+    LibraryCleanupService::estimate(&item_ids)
+}
+
+#[tauri::command]
+pub fn delete_library_items(
+    item_ids: Vec<String>,
+    mode: LibraryDeleteMode,
+) -> Result<ActionOutcome<()>, String> {
+    let report = LibraryCleanupService::delete(&item_ids, mode)?;
+
+    let mut message = match mode {
+        LibraryDeleteMode::RemoveFromLibrary => {
+            format!("Removed {} item(s) from the library", report.deleted)
+        }
+        _ => format!(
+            "Freed {} from {} item(s)",
+            format_size(report.freed_bytes),
+            report.deleted
+        ),
+    };
+    for failure in &report.failures {
+        message.push_str(&format!("; {failure}"));
+    }
+
+    let invalidations = match mode {
+        LibraryDeleteMode::ClearWorkshopCache => Vec::new(),
+        _ => vec![
+            InvalidatedPage::Library,
+            InvalidatedPage::Desktop,
+            InvalidatedPage::Playlists,
+        ],
+    };
+
+    Ok(ActionOutcome {
+        ok: report.failures.is_empty(),
+        message: Some(message),
+        shell_patch: None,
+        current_update: None,
+        invalidations,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
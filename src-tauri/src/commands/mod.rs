@@ -1,5 +1,7 @@
 pub mod app_shell;
 pub mod desktop;
+pub mod diagnostics;
 pub mod library;
+pub mod playlist;
 pub mod settings;
 pub mod workshop;
@@ -0,0 +1,189 @@
+use crate::action_outcome::{ActionOutcome, InvalidatedPage};
+use crate::assembly::playlist_page::assemble_playlist_page;
+use crate::models::{PlaylistPageSnapshot, PlaylistSaveInput};
+use crate::services::collection_service::CollectionService;
+use crate::services::playlist_scheduler_service::PlaylistSchedulerService;
+use crate::services::playlist_service::PlaylistService;
+
+#[tauri::command]
+pub fn load_playlist_page() -> Result<PlaylistPageSnapshot, String> {
+    PlaylistService::load_page().map(assemble_playlist_page)
+}
+
+#[tauri::command]
+pub fn save_playlist(
+    input: PlaylistSaveInput,
+) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    let name = input.name.trim().to_string();
+    let snapshot = assemble_playlist_page(PlaylistService::save_playlist(input)?);
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some(format!("Playlist {name} saved")),
+        shell_patch: None,
+        current_update: Some(snapshot),
+        invalidations: vec![InvalidatedPage::Playlists],
+    })
+}
+
+#[tauri::command]
+pub fn delete_playlist(playlist_id: i64) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    let snapshot = assemble_playlist_page(PlaylistService::delete_playlist(playlist_id)?);
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some("Playlist deleted".to_string()),
+        shell_patch: None,
+        current_update: Some(snapshot),
+        invalidations: vec![InvalidatedPage::Playlists],
+    })
+}
+
+#[tauri::command]
+pub fn set_monitor_playlist(
+    monitor_id: String,
+    playlist_id: Option<i64>,
+) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    let snapshot =
+        assemble_playlist_page(PlaylistService::assign_monitor(&monitor_id, playlist_id)?);
+    let message = match playlist_id {
+        Some(_) => format!("Playlist assigned to {monitor_id}"),
+        None => format!("Playlist cleared from {monitor_id}"),
+    };
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some(message),
+        shell_patch: None,
+        current_update: Some(snapshot),
+        invalidations: vec![InvalidatedPage::Playlists, InvalidatedPage::Desktop],
+    })
+}
+
+#[tauri::command]
+pub fn skip_playlist_item(
+    monitor_id: Option<String>,
+) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    PlaylistSchedulerService::skip(monitor_id.as_deref())?;
+    let snapshot = assemble_playlist_page(PlaylistService::load_page()?);
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some("Skipped to the next playlist item".to_string()),
+        shell_patch: None,
+        current_update: Some(snapshot),
+        invalidations: vec![InvalidatedPage::Playlists, InvalidatedPage::Desktop],
+    })
+}
+
+#[tauri::command]
+pub fn previous_playlist_item(
+    monitor_id: Option<String>,
+) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    PlaylistSchedulerService::previous(monitor_id.as_deref())?;
+    let snapshot = assemble_playlist_page(PlaylistService::load_page()?);
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some("Went back to the previous playlist item".to_string()),
+        shell_patch: None,
+        current_update: Some(snapshot),
+        invalidations: vec![InvalidatedPage::Playlists, InvalidatedPage::Desktop],
+    })
+}
+
+#[tauri::command]
+pub fn set_playlist_paused(
+    monitor_id: Option<String>,
+    paused: bool,
+) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    PlaylistSchedulerService::set_paused(monitor_id.as_deref(), paused)?;
+    let snapshot = assemble_playlist_page(PlaylistService::load_page()?);
+    let message = if paused {
+        "Playlist rotation paused"
+    } else {
+        "Playlist rotation resumed"
+    };
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some(message.to_string()),
+        shell_patch: None,
+        current_update: Some(snapshot),
+        invalidations: vec![InvalidatedPage::Playlists],
+    })
+}
+
+#[tauri::command]
+pub fn create_collection(
+    name: String,
+    item_ids: Vec<String>,
+) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    CollectionService::for_user_path().create(&name, &item_ids)?;
+    collection_outcome(format!("Collection {} created", name.trim()))
+}
+
+#[tauri::command]
+pub fn rename_collection(
+    collection_id: i64,
+    name: String,
+) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    CollectionService::for_user_path().rename(collection_id, &name)?;
+    collection_outcome(format!("Collection renamed to {}", name.trim()))
+}
+
+#[tauri::command]
+pub fn delete_collection(
+    collection_id: i64,
+) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    CollectionService::for_user_path().delete(collection_id)?;
+    collection_outcome("Collection deleted".to_string())
+}
+
+#[tauri::command]
+pub fn add_collection_items(
+    collection_id: i64,
+    item_ids: Vec<String>,
+) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    CollectionService::for_user_path().add_items(collection_id, &item_ids)?;
+    collection_outcome("Collection updated".to_string())
+}
+
+#[tauri::command]
+pub fn remove_collection_item(
+    collection_id: i64,
+    item_id: String,
+) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    CollectionService::for_user_path().remove_item(collection_id, &item_id)?;
+    collection_outcome("Collection updated".to_string())
+}
+
+/// Rotate a collection as a playlist on one monitor, or on all of them
+#[tauri::command]
+pub fn apply_collection(
+    collection_id: i64,
+    monitor_id: Option<String>,
+) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    let monitor_ids = CollectionService::apply(collection_id, monitor_id.as_deref())?;
+    let snapshot = assemble_playlist_page(PlaylistService::load_page()?);
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some(format!("Collection rotating on {}", monitor_ids.join(", "))),
+        shell_patch: None,
+        current_update: Some(snapshot),
+        invalidations: vec![InvalidatedPage::Playlists, InvalidatedPage::Desktop],
+    })
+}
+
+fn collection_outcome(message: String) -> Result<ActionOutcome<PlaylistPageSnapshot>, String> {
+    let snapshot = assemble_playlist_page(PlaylistService::load_page()?);
+
+    Ok(ActionOutcome {
+        ok: true,
+        message: Some(message),
+        shell_patch: None,
+        current_update: Some(snapshot),
+        invalidations: vec![InvalidatedPage::Playlists],
+    })
+}
@@ -1,7 +1,9 @@
 use crate::action_outcome::{ActionOutcome, InvalidatedPage};
 use crate::assembly::settings_page::assemble_settings_page;
-use crate::models::{SettingsPageSnapshot, SettingsUpdateInput};
+use crate::models::{SettingsPageSnapshot, SettingsUpdateInput, ThumbnailCacheSummary};
+use crate::services::library_cleanup_service::format_size;
 use crate::services::settings_service::SettingsService;
+use crate::services::thumbnail_cache_service::ThumbnailCacheService;
 
 #[tauri::command]
 pub fn load_settings_page() -> Result<SettingsPageSnapshot, String> {
@@ -29,6 +31,37 @@ pub fn update_settings(
     })
 }
 
+#[tauri::command]
+pub fn load_thumbnail_cache() -> Result<ThumbnailCacheSummary, String> {
+    let usage = ThumbnailCacheService::usage()?;
+    Ok(ThumbnailCacheSummary {
+        bytes: usage.bytes,
+        budget_bytes: usage.budget_bytes,
+    })
+}
+
+/// Runs on a worker thread: compacting the library database afterwards can
+/// take a while
+#[tauri::command]
+pub async fn clear_thumbnail_cache() -> Result<ActionOutcome<ThumbnailCacheSummary>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let cleanup = ThumbnailCacheService::clear()?;
+        Ok(ActionOutcome {
+            ok: true,
+            message: Some(format!(
+                "Removed {} thumbnails and reclaimed {}",
+                cleanup.removed,
+                format_size(cleanup.bytes_reclaimed)
+            )),
+            shell_patch: None,
+            current_update: Some(load_thumbnail_cache()?),
+            invalidations: Vec::new(),
+        })
+    })
+    .await
+    .map_err(|error| format!("Failed to clear the thumbnail cache: {error}"))?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
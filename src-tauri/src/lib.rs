@@ -3,17 +3,19 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use tauri::{
     menu::{MenuBuilder, MenuItemBuilder},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder},
-    Manager,
+    Emitter, Manager,
 };
 
 // Layered application core for the Tauri shell.
 pub mod action_outcome;
 pub mod assembly;
+pub mod cli;
 pub mod commands;
 pub mod models;
 pub mod policies;
 pub mod results;
 pub mod services;
+mod web_wallpaper_host;
 
 pub const APP_CODE_NAME: &str = "lwe";
 
@@ -74,13 +76,32 @@ fn show_main_window(app: &tauri::AppHandle) {
     }
 }
 
-fn is_start_hidden() -> bool {
-    std::env::args_os().any(|arg| arg == "--start-hidden")
+/// `--minimized` starts with only the tray icon and the engine; `--start-hidden`
+/// is the flag older autostart entries were written with.
+fn is_minimized_launch<I>(args: I) -> bool
+where
+    I: IntoIterator,
+    I::Item: AsRef<std::ffi::OsStr>,
+{
+    args.into_iter()
+        .any(|arg| arg.as_ref() == "--minimized" || arg.as_ref() == "--start-hidden")
+}
+
+/// `--no-restore` starts with every monitor empty, for when a saved wallpaper
+/// keeps the desktop from working; the saved assignments are kept.
+fn skips_restore<I>(args: I) -> bool
+where
+    I: IntoIterator,
+    I::Item: AsRef<std::ffi::OsStr>,
+{
+    args.into_iter().any(|arg| arg.as_ref() == "--no-restore")
 }
 
 pub fn register_commands(builder: tauri::Builder<tauri::Wry>) -> tauri::Builder<tauri::Wry> {
     builder.invoke_handler(tauri::generate_handler![
         commands::app_shell::load_app_shell,
+        commands::app_shell::load_onboarding,
+        commands::app_shell::finish_onboarding,
         commands::workshop::load_workshop_page,
         commands::workshop::load_workshop_item_detail,
         commands::workshop::refresh_workshop_catalog,
@@ -88,19 +109,259 @@ pub fn register_commands(builder: tauri::Builder<tauri::Wry>) -> tauri::Builder<
         commands::workshop::open_workshop_in_steam,
         commands::library::load_library_page,
         commands::library::load_library_item_detail,
+        commands::library::stop_library_preview,
+        commands::library::queue_thumbnails,
+        commands::library::set_library_item_favorite,
+        commands::library::set_library_item_rating,
+        commands::library::set_library_item_title,
+        commands::library::set_library_item_tags,
+        commands::library::set_library_item_property,
+        commands::library::reset_library_item_properties,
+        commands::library::pick_library_item_shader,
+        commands::library::clear_library_item_shader,
+        commands::library::pick_library_item_lut,
+        commands::library::clear_library_item_lut,
+        commands::library::add_library_items_tag,
+        commands::library::regenerate_library_thumbnails,
+        commands::library::export_library_item_paths,
+        commands::library::reveal_library_item,
+        commands::library::play_library_item_externally,
+        commands::library::add_library_folders,
+        commands::library::add_library_files,
+        commands::library::estimate_library_deletion,
+        commands::library::delete_library_items,
         commands::desktop::load_desktop_page,
         commands::desktop::apply_library_item_to_monitor,
+        commands::desktop::apply_library_item_to_all_monitors,
+        commands::desktop::undo_desktop_apply,
         commands::desktop::clear_library_item_from_monitor,
+        commands::desktop::set_monitor_audio,
+        commands::desktop::set_monitor_color,
+        commands::desktop::load_monitor_palettes,
+        commands::desktop::load_schedule_status,
+        commands::desktop::set_schedule_slot,
+        commands::desktop::list_profiles,
+        commands::desktop::save_profile,
+        commands::desktop::apply_profile,
+        commands::playlist::load_playlist_page,
+        commands::playlist::save_playlist,
+        commands::playlist::delete_playlist,
+        commands::playlist::set_monitor_playlist,
+        commands::playlist::skip_playlist_item,
+        commands::playlist::previous_playlist_item,
+        commands::playlist::set_playlist_paused,
+        commands::playlist::create_collection,
+        commands::playlist::rename_collection,
+        commands::playlist::delete_collection,
+        commands::playlist::add_collection_items,
+        commands::playlist::remove_collection_item,
+        commands::playlist::apply_collection,
+        commands::diagnostics::load_diagnostics_page,
+        commands::diagnostics::load_diagnostics_logs,
         commands::settings::load_settings_page,
         commands::settings::update_settings,
+        commands::settings::load_thumbnail_cache,
+        commands::settings::clear_thumbnail_cache,
     ])
 }
 
+fn thumbnail_protocol_response(
+    thumbnail: Result<crate::services::thumbnail_cache_service::ThumbnailBytes, String>,
+) -> tauri::http::Response<Vec<u8>> {
+    use tauri::http::{header::CONTENT_TYPE, Response, StatusCode};
+
+    let response = match thumbnail {
+        Ok(thumbnail) => Response::builder()
+            .header(CONTENT_TYPE, thumbnail.mime_type)
+            .body(thumbnail.bytes.to_vec()),
+        Err(reason) => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(reason.into_bytes()),
+    };
+
+    response.unwrap_or_else(|error| {
+        eprintln!("failed to build thumbnail response: {error}");
+        Response::new(Vec::new())
+    })
+}
+
+fn strip_protocol_response(request_path: &str) -> tauri::http::Response<Vec<u8>> {
+    use crate::services::thumbnail_cache_service::ThumbnailCacheService;
+    use tauri::http::{header::CONTENT_TYPE, Response, StatusCode};
+
+    let response = match ThumbnailCacheService::load_strip_for_request_path(request_path) {
+        Ok(strip) => Response::builder()
+            .header(CONTENT_TYPE, strip.mime_type)
+            .body(strip.bytes.to_vec()),
+        Err(reason) => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(reason.into_bytes()),
+    };
+
+    response.unwrap_or_else(|error| {
+        eprintln!("failed to build thumbnail strip response: {error}");
+        Response::new(Vec::new())
+    })
+}
+
+fn preview_protocol_response(request_path: &str) -> tauri::http::Response<Vec<u8>> {
+    use crate::services::preview_service::PreviewService;
+    use tauri::http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE},
+        Response, StatusCode,
+    };
+
+    let response = match PreviewService::frame_for_request_path(request_path) {
+        Ok(frame) => Response::builder()
+            .header(CONTENT_TYPE, "image/png")
+            .header(CACHE_CONTROL, "no-store")
+            .body(frame),
+        Err(reason) => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(reason.into_bytes()),
+    };
+
+    response.unwrap_or_else(|error| {
+        eprintln!("failed to build preview response: {error}");
+        Response::new(Vec::new())
+    })
+}
+
+fn monitor_preview_protocol_response(request_path: &str) -> tauri::http::Response<Vec<u8>> {
+    use crate::services::monitor_preview_service::MonitorPreviewService;
+    use tauri::http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE},
+        Response, StatusCode,
+    };
+
+    let response = match MonitorPreviewService::frame_for_request_path(request_path) {
+        Ok(frame) => Response::builder()
+            .header(CONTENT_TYPE, "image/jpeg")
+            .header(CACHE_CONTROL, "no-store")
+            .body(frame),
+        Err(reason) => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(reason.into_bytes()),
+    };
+
+    response.unwrap_or_else(|error| {
+        eprintln!("failed to build monitor preview response: {error}");
+        Response::new(Vec::new())
+    })
+}
+
 pub fn builder() -> tauri::Builder<tauri::Wry> {
+    crate::services::diagnostics_service::DiagnosticsService::install_log_capture();
+
     register_commands(tauri::Builder::default())
+        .register_asynchronous_uri_scheme_protocol(
+            crate::services::thumbnail_cache_service::THUMBNAIL_SCHEME,
+            |_context, request, responder| {
+                let request_path = request.uri().path().to_string();
+                // Covers are queued for the thumbnail workers, so large ones never stall the UI.
+                std::thread::spawn(move || {
+                    crate::services::thumbnail_cache_service::ThumbnailCacheService::load_for_request_path(
+                        &request_path,
+                        Box::new(move |thumbnail| {
+                            responder.respond(thumbnail_protocol_response(thumbnail))
+                        }),
+                    );
+                });
+            },
+        )
+        .register_asynchronous_uri_scheme_protocol(
+            crate::services::thumbnail_cache_service::STRIP_SCHEME,
+            |_context, request, responder| {
+                let request_path = request.uri().path().to_string();
+                // First hovers run ffmpeg for every frame, far too slow for the webview thread.
+                std::thread::spawn(move || {
+                    responder.respond(strip_protocol_response(&request_path));
+                });
+            },
+        )
+        .register_asynchronous_uri_scheme_protocol(
+            crate::services::preview_service::PREVIEW_SCHEME,
+            |_context, request, responder| {
+                let request_path = request.uri().path().to_string();
+                std::thread::spawn(move || {
+                    responder.respond(preview_protocol_response(&request_path));
+                });
+            },
+        )
+        .register_asynchronous_uri_scheme_protocol(
+            crate::services::monitor_preview_service::MONITOR_PREVIEW_SCHEME,
+            |_context, request, responder| {
+                let request_path = request.uri().path().to_string();
+                // A readback waits for the engine to draw its next frame.
+                std::thread::spawn(move || {
+                    responder.respond(monitor_preview_protocol_response(&request_path));
+                });
+            },
+        )
         .setup(|app| {
             let app = app.app_handle();
             app.manage(QuitRequested(AtomicBool::new(false)));
+            crate::services::web_wallpaper_service::WebWallpaperService::install_host(Box::new(
+                web_wallpaper_host::TauriWebWallpaperHost::new(app.clone()),
+            ));
+            let progress_app = app.clone();
+            crate::services::thumbnail_cache_service::ThumbnailCacheService::install_progress_sink(
+                Box::new(move |progress| {
+                    let event = models::ThumbnailProgressEvent {
+                        cover_path: progress.source_path.to_string_lossy().into_owned(),
+                        remaining: progress.remaining,
+                    };
+                    if let Err(error) = progress_app.emit(models::THUMBNAIL_PROGRESS_EVENT, event)
+                    {
+                        eprintln!("failed to report thumbnail progress: {error}");
+                    }
+                }),
+            );
+            // Keeps stored thumbnails inside `[library] thumbnail_cache_mb` between runs
+            std::thread::spawn(|| {
+                if let Err(reason) =
+                    crate::services::thumbnail_cache_service::ThumbnailCacheService::prune()
+                {
+                    eprintln!("{reason}");
+                }
+            });
+            let library_app = app.clone();
+            crate::services::library_watch_service::LibraryWatchService::start(Box::new(
+                move |paths| {
+                    let event = models::LibraryChangedEvent {
+                        paths: paths
+                            .iter()
+                            .map(|path| path.to_string_lossy().into_owned())
+                            .collect(),
+                    };
+                    if let Err(error) = library_app.emit(models::LIBRARY_CHANGED_EVENT, event) {
+                        eprintln!("failed to report library changes: {error}");
+                    }
+                },
+            ));
+            let settings_app = app.clone();
+            crate::services::settings_sync_service::SettingsSyncService::start(Box::new(
+                move || {
+                    if let Err(error) = settings_app.emit(models::SETTINGS_CHANGED_EVENT, ()) {
+                        eprintln!("failed to report settings changes: {error}");
+                    }
+                },
+            ));
+            let failure_app = app.clone();
+            crate::services::desktop_service::DesktopService::watch_wallpaper_failures(Box::new(
+                move |failure| {
+                    let event = models::WallpaperFailedEvent {
+                        output: failure.output,
+                        path: failure.path.to_string_lossy().into_owned(),
+                        reason: failure.reason,
+                        failures: failure.failures,
+                        fallback: failure.fallback,
+                    };
+                    if let Err(error) = failure_app.emit(models::WALLPAPER_FAILED_EVENT, event) {
+                        eprintln!("failed to report a failing wallpaper: {error}");
+                    }
+                },
+            ));
 
             let language = crate::services::settings_service::SettingsService::load_page()
                 .map(|page| page.language)
@@ -143,17 +404,34 @@ pub fn builder() -> tauri::Builder<tauri::Wry> {
                     })
                     .build(app)?;
 
-            if is_start_hidden() {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.hide();
-                }
+            // The main window is created hidden so a minimized launch never flashes it.
+            if !is_minimized_launch(std::env::args_os().skip(1)) {
+                show_main_window(app.handle());
             }
 
-            if let Err(reason) =
+            if skips_restore(std::env::args_os().skip(1)) {
+                eprintln!("desktop restore skipped: started with --no-restore");
+            } else if let Err(reason) =
                 crate::services::desktop_service::DesktopService::restore_saved_assignments()
             {
                 eprintln!("desktop restore failed during startup: {reason}");
             }
+            // Under `Type=notify` the engine reports ready once its first
+            // wallpaper surface is up; with nothing restored, the app is
+            // ready now
+            if !crate::services::desktop_service::DesktopService::engine_running() {
+                lwe_engine::systemd::notify_ready();
+            }
+
+            crate::services::playlist_scheduler_service::PlaylistSchedulerService::start();
+            crate::services::signage_service::SignageService::start();
+            crate::services::stream_service::StreamService::start();
+            crate::services::mpris_service::MprisService::start();
+            crate::services::control_service::ControlService::start();
+            crate::services::hook_service::HookService::start();
+            crate::services::resource_limits_service::ResourceLimitsService::start();
+            crate::services::screensaver_service::ScreenSaverService::start();
+            crate::services::schedule_service::ScheduleService::start();
 
             Ok(())
         })
@@ -176,4 +454,18 @@ mod tests {
     fn app_name_uses_lwe_code_name() {
         assert_eq!(super::APP_CODE_NAME, "lwe");
     }
+
+    #[test]
+    fn minimized_launch_accepts_new_and_legacy_flags() {
+        assert!(super::is_minimized_launch(["--minimized"]));
+        assert!(super::is_minimized_launch(["--start-hidden"]));
+        assert!(!super::is_minimized_launch(["--profile", "minimized"]));
+    }
+
+    #[test]
+    fn no_restore_is_only_read_as_a_flag() {
+        assert!(super::skips_restore(["--minimized", "--no-restore"]));
+        assert!(!super::skips_restore(["--minimized"]));
+        assert!(!super::skips_restore(["no-restore"]));
+    }
 }
@@ -4,6 +4,10 @@ fn main() {
         std::env::set_var("LC_NUMERIC", "C");
     }
 
+    if let Some(code) = lwe_shell::cli::run_from_env() {
+        std::process::exit(code);
+    }
+
     lwe_shell::builder()
         .run(tauri::generate_context!())
         .expect("error while running LWE application shell");
@@ -191,9 +191,83 @@ pub struct LibraryItemSummary {
     pub source: LibrarySource,
     pub compatibility: CompatibilitySummaryModel,
     pub favorite: bool,
+    /// 1-5, or 0 when unrated
+    pub rating: u8,
+    /// Times the item was applied to a monitor
+    pub use_count: u32,
+    /// RFC 3339 time the item was last applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<String>,
     pub assigned_monitor_labels: Vec<String>,
 }
 
+/// What deleting library items removes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LibraryDeleteMode {
+    /// Hide the items from the library and keep their files
+    RemoveFromLibrary,
+    /// Delete the downloaded Workshop folders
+    DeleteLocalFiles,
+    /// Delete Steam's download copies and LWE's stored thumbnails
+    ClearWorkshopCache,
+}
+
+/// Event sent as each background thumbnail finishes
+pub const THUMBNAIL_PROGRESS_EVENT: &str = "thumbnail-progress";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailProgressEvent {
+    pub cover_path: String,
+    /// Thumbnails still queued or being generated
+    pub remaining: usize,
+}
+
+/// Event sent when `settings.toml` changed and the engine took the new settings
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+/// Event sent when files in a library folder were added, removed or renamed
+pub const LIBRARY_CHANGED_EVENT: &str = "library-changed";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryChangedEvent {
+    pub paths: Vec<String>,
+}
+
+/// Event sent when the engine stops retrying a wallpaper that keeps failing
+pub const WALLPAPER_FAILED_EVENT: &str = "wallpaper-failed";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WallpaperFailedEvent {
+    /// Backend output id
+    pub output: String,
+    pub path: String,
+    pub reason: String,
+    pub failures: u32,
+    /// Safe-mode image path or `#rrggbb` color shown instead
+    pub fallback: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryDeleteEstimateItem {
+    pub id: String,
+    pub title: String,
+    pub local_file_bytes: u64,
+    pub workshop_cache_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryDeleteEstimate {
+    pub items: Vec<LibraryDeleteEstimateItem>,
+    pub local_file_bytes: u64,
+    pub workshop_cache_bytes: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LibraryPageSnapshot {
@@ -212,7 +286,9 @@ pub struct LibraryPageSnapshot {
 #[serde(rename_all = "camelCase")]
 pub struct LibraryItemDetail {
     pub id: String,
+    /// The user's title when they gave one, else the scanned title
     pub title: String,
+    pub original_title: String,
     pub item_type: ItemType,
     pub cover_path: Option<String>,
     pub source: LibrarySource,
@@ -225,7 +301,72 @@ pub struct LibraryItemDetail {
     pub desktop_assignments_available: bool,
     pub assigned_monitor_labels: Vec<String>,
     pub description: Option<String>,
+    /// Tags the author gave in project.json
     pub tags: Vec<String>,
+    /// Tags the user added in the library
+    pub user_tags: Vec<String>,
+    /// Steam Workshop id of items that came from the Workshop
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workshop_id: Option<String>,
+    pub media: LibraryItemMedia,
+    pub favorite: bool,
+    /// 1-5, or 0 when unrated
+    pub rating: u8,
+    /// Controls the author exposes in project.json, with the user's values
+    pub properties: Vec<LibraryItemProperty>,
+    /// GLSL shader the item is post-processed with
+    pub shader_path: Option<String>,
+    /// Color grading LUT the item uses in place of the global one
+    pub lut_path: Option<String>,
+}
+
+/// What is known about an item's main file; fields are missing when its
+/// headers do not say
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryItemMedia {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub codec: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fps: Option<f64>,
+    /// "pq" or "hlg"; only set for HDR video
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hdr: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<u64>,
+}
+
+/// A user property of a Wallpaper Engine project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryItemProperty {
+    pub name: String,
+    /// "slider", "bool", "color", "combo" or "textinput"
+    pub kind: String,
+    pub label: String,
+    /// Colors are "r g b" with channels from 0 to 1
+    pub value: serde_json::Value,
+    pub default_value: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<f64>,
+    pub options: Vec<LibraryItemPropertyOption>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryItemPropertyOption {
+    pub label: String,
+    pub value: serde_json::Value,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -243,6 +384,60 @@ pub struct DesktopMonitorSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub restore_issue: Option<String>,
     pub runtime_status: RuntimeStatus,
+    pub volume: u8,
+    pub muted: bool,
+    pub color: MonitorColorModel,
+    /// Seconds since this session last applied a wallpaper to the monitor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applied_seconds_ago: Option<u64>,
+    /// How long that apply took to reach its first frame
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apply_latency_ms: Option<u64>,
+}
+
+/// Brightness, contrast, gamma and saturation in percent; 100 is unchanged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorColorModel {
+    pub brightness: u16,
+    pub contrast: u16,
+    pub gamma: u16,
+    pub saturation: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorPalette {
+    pub monitor_id: String,
+    pub colors: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileModel {
+    pub name: String,
+    pub monitor_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleSlotModel {
+    pub name: String,
+    pub start: String,
+    pub item_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleStatusSnapshot {
+    pub slots: Vec<ScheduleSlotModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_slot: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_change: Option<i64>,
+    pub pinned: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -270,6 +465,7 @@ pub struct SettingsUpdateInput {
     pub workshop_query: Option<String>,
     pub workshop_age_ratings: Option<Vec<WorkshopAgeRating>>,
     pub workshop_item_types: Option<Vec<WorkshopOnlineItemType>>,
+    pub confirm_apply_to_all: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -283,9 +479,289 @@ pub struct SettingsPageSnapshot {
     pub workshop_query: String,
     pub workshop_age_ratings: Vec<WorkshopAgeRating>,
     pub workshop_item_types: Vec<WorkshopOnlineItemType>,
+    pub confirm_apply_to_all: bool,
     pub steam_required: bool,
     pub steam_status_message: String,
     pub stale: bool,
+    pub config_issues: Vec<SettingsConfigIssue>,
+}
+
+/// Space the stored thumbnails take, and the `[library] thumbnail_cache_mb` budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailCacheSummary {
+    pub bytes: u64,
+    pub budget_bytes: u64,
+}
+
+/// What the first-run wizard found; `needed` is set until `settings.toml` exists
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingSnapshot {
+    pub needed: bool,
+    pub steam_found: bool,
+    pub wallpaper_engine_found: bool,
+    pub workshop_item_count: usize,
+    /// Existing folders that look like they hold wallpapers
+    pub suggested_folders: Vec<String>,
+    pub render_backend: OnboardingCheck,
+    pub hardware_decoding: OnboardingCheck,
+    /// DRM render nodes; only worth a choice when there are several
+    pub render_devices: Vec<OnboardingRenderDevice>,
+    pub launch_on_login_available: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingCheck {
+    pub ok: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingRenderDevice {
+    pub path: String,
+    pub detail: String,
+    pub hardware_decoding: bool,
+}
+
+/// Choices from the first-run wizard, written as the initial `settings.toml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingFinishInput {
+    pub language: Option<String>,
+    pub folders: Vec<String>,
+    pub render_device: Option<String>,
+    pub launch_on_login: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigIssueSeverity {
+    Error,
+    Warning,
+}
+
+/// A problem in `settings.toml`, with the line it was found on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsConfigIssue {
+    pub severity: ConfigIssueSeverity,
+    pub line: Option<usize>,
+    pub key: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaylistTransition {
+    None,
+    Fade,
+}
+
+impl PlaylistTransition {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Fade => "fade",
+        }
+    }
+
+    pub fn from_stored(value: &str) -> Self {
+        match value {
+            "fade" => Self::Fade,
+            _ => Self::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistEntryModel {
+    pub item_id: String,
+    pub item_title: Option<String>,
+    pub duration_secs: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistSummary {
+    pub id: i64,
+    pub name: String,
+    pub shuffle: bool,
+    pub transition: PlaylistTransition,
+    pub default_duration_secs: u32,
+    pub items: Vec<PlaylistEntryModel>,
+    pub monitor_ids: Vec<String>,
+    pub folder: Option<PlaylistFolderModel>,
+}
+
+/// Folder a playlist plays instead of its own entries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistFolderModel {
+    pub path: String,
+    pub pattern: Option<String>,
+    pub image_duration_secs: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionItemModel {
+    pub item_id: String,
+    pub item_title: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionModel {
+    pub id: i64,
+    pub name: String,
+    pub items: Vec<CollectionItemModel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistLibraryItemModel {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistMonitorModel {
+    pub monitor_id: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistRotationModel {
+    pub monitor_id: String,
+    pub playlist_id: i64,
+    pub current_item_id: Option<String>,
+    pub current_item_title: Option<String>,
+    pub paused: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistPageSnapshot {
+    pub playlists: Vec<PlaylistSummary>,
+    pub collections: Vec<CollectionModel>,
+    pub library_items: Vec<PlaylistLibraryItemModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub library_issue: Option<String>,
+    pub monitors: Vec<PlaylistMonitorModel>,
+    pub monitors_available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitor_discovery_issue: Option<String>,
+    pub rotations: Vec<PlaylistRotationModel>,
+    pub stale: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistEntryInput {
+    pub item_id: String,
+    pub duration_secs: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistSaveInput {
+    pub id: Option<i64>,
+    pub name: String,
+    pub shuffle: bool,
+    pub transition: PlaylistTransition,
+    pub default_duration_secs: u32,
+    pub items: Vec<PlaylistEntryInput>,
+    pub monitor_ids: Vec<String>,
+    #[serde(default)]
+    pub folder: Option<PlaylistFolderModel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsLogLineModel {
+    pub sequence: u64,
+    pub timestamp_ms: u64,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsCapabilityModel {
+    pub key: String,
+    pub available: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticsGpuSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsGpuMessageModel {
+    pub severity: DiagnosticsGpuSeverity,
+    pub message: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsPlaybackModel {
+    pub title: Option<String>,
+    pub duration_secs: Option<f64>,
+    pub hwdec: Option<String>,
+    pub fps: Option<f64>,
+    pub skip_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsOutputModel {
+    pub name: String,
+    pub resolution: String,
+    pub wallpaper_path: Option<String>,
+    pub gpu_messages: Vec<DiagnosticsGpuMessageModel>,
+    pub playback: Option<DiagnosticsPlaybackModel>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticsEngineStatus {
+    Running,
+    NotStarted,
+    Unavailable,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsEngineModel {
+    pub status: DiagnosticsEngineStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+    pub outputs: Vec<DiagnosticsOutputModel>,
+    pub render_backend: Option<String>,
+    pub on_battery: bool,
+    pub power_paused: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsPageSnapshot {
+    pub app_version: String,
+    pub engine: DiagnosticsEngineModel,
+    pub capabilities: Vec<DiagnosticsCapabilityModel>,
+    pub logs: Vec<DiagnosticsLogLineModel>,
 }
 
 #[cfg(test)]
@@ -415,6 +891,7 @@ mod tests {
                 WorkshopOnlineItemType::Video,
                 WorkshopOnlineItemType::Scene,
             ]),
+            confirm_apply_to_all: Some(false),
         };
 
         let snapshot = SettingsPageSnapshot {
@@ -426,9 +903,16 @@ mod tests {
             workshop_query: "forest".to_string(),
             workshop_age_ratings: vec![WorkshopAgeRating::G, WorkshopAgeRating::Pg13],
             workshop_item_types: vec![WorkshopOnlineItemType::Video, WorkshopOnlineItemType::Scene],
+            confirm_apply_to_all: true,
             steam_required: true,
             steam_status_message: "Steam is required for Workshop features".to_string(),
             stale: false,
+            config_issues: vec![SettingsConfigIssue {
+                severity: ConfigIssueSeverity::Warning,
+                line: Some(2),
+                key: Some("them".to_string()),
+                message: "unknown key, ignored".to_string(),
+            }],
         };
 
         let update_value = serde_json::to_value(&update).unwrap();
@@ -443,6 +927,7 @@ mod tests {
         assert_eq!(update_value["workshopAgeRatings"][1], "pg_13");
         assert_eq!(update_value["workshopItemTypes"][0], "video");
         assert_eq!(update_value["workshopItemTypes"][1], "scene");
+        assert_eq!(update_value["confirmApplyToAll"], false);
         assert_eq!(snapshot_value["language"], "en");
         assert_eq!(snapshot_value["theme"], "system");
         assert_eq!(snapshot_value["launchOnLogin"], true);
@@ -453,12 +938,16 @@ mod tests {
         assert_eq!(snapshot_value["workshopAgeRatings"][1], "pg_13");
         assert_eq!(snapshot_value["workshopItemTypes"][0], "video");
         assert_eq!(snapshot_value["workshopItemTypes"][1], "scene");
+        assert_eq!(snapshot_value["confirmApplyToAll"], true);
         assert_eq!(snapshot_value["steamRequired"], true);
         assert_eq!(
             snapshot_value["steamStatusMessage"],
             "Steam is required for Workshop features"
         );
         assert_eq!(snapshot_value["stale"], false);
+        assert_eq!(snapshot_value["configIssues"][0]["severity"], "warning");
+        assert_eq!(snapshot_value["configIssues"][0]["line"], 2);
+        assert_eq!(snapshot_value["configIssues"][0]["key"], "them");
     }
 
     #[test]
@@ -515,6 +1004,16 @@ mod tests {
                 restore_state: Some(DesktopRestoreState::Restored),
                 restore_issue: None,
                 runtime_status: RuntimeStatus::Unsupported,
+                volume: 100,
+                muted: true,
+                color: MonitorColorModel {
+                    brightness: 90,
+                    contrast: 100,
+                    gamma: 100,
+                    saturation: 120,
+                },
+                applied_seconds_ago: Some(2),
+                apply_latency_ms: Some(180),
             }],
             missing_monitor_restores: vec![DesktopMissingMonitorRestore {
                 monitor_id: "DISPLAY-2".to_string(),
@@ -543,6 +1042,9 @@ mod tests {
             source: LibrarySource::Workshop,
             compatibility: summary_compatibility(),
             favorite: false,
+            rating: 0,
+            use_count: 0,
+            last_used_at: None,
             assigned_monitor_labels: vec!["Primary".to_string()],
         };
 
@@ -550,6 +1052,9 @@ mod tests {
         let library_value = serde_json::to_value(&library_item).unwrap();
 
         assert_eq!(desktop_value["monitors"][0]["restoreState"], "restored");
+        assert_eq!(desktop_value["monitors"][0]["appliedSecondsAgo"], 2);
+        assert_eq!(desktop_value["monitors"][0]["color"]["saturation"], 120);
+        assert_eq!(desktop_value["monitors"][0]["applyLatencyMs"], 180);
         assert_eq!(
             desktop_value["missingMonitorRestores"][0]["restoreState"],
             "missing_monitor"
@@ -58,7 +58,7 @@ pub fn compatibility_decision(entry: &WorkshopCatalogEntry) -> CompatibilityDeci
         (
             _,
             WorkshopSyncState::MissingPrimaryAsset,
-            WorkshopProjectType::Video | WorkshopProjectType::Scene,
+            WorkshopProjectType::Video | WorkshopProjectType::Scene | WorkshopProjectType::Web,
         ) => CompatibilityDecision {
             level: CompatibilityLevel::PartiallySupported,
             reason: CompatibilityReason::MissingPrimaryAsset,
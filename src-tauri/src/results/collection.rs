@@ -0,0 +1,8 @@
+/// A library collection with its entries in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectionSummary {
+    pub id: i64,
+    pub name: String,
+    /// Library item ids; Workshop projects and local files alike
+    pub item_ids: Vec<String>,
+}
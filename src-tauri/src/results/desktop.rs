@@ -1,4 +1,6 @@
 use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::services::monitor_service::MonitorDescriptor;
 
@@ -27,6 +29,71 @@ pub enum DesktopResolvedMonitorAssignment {
     },
 }
 
+/// Per-monitor results of an apply or undo that touched several monitors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesktopBulkApplyResult {
+    pub results: Vec<DesktopApplyResult>,
+}
+
+/// A wallpaper the engine stopped retrying after it failed too often
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesktopWallpaperFailure {
+    /// Backend output id
+    pub output: String,
+    pub path: PathBuf,
+    /// Why it failed the last time
+    pub reason: String,
+    /// Failures in a row
+    pub failures: u32,
+    /// The safe-mode image or color shown instead
+    pub fallback: String,
+}
+
+/// Volume and mute state of one monitor, from its `[outputs]` settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DesktopMonitorAudio {
+    pub volume: u8,
+    pub muted: bool,
+}
+
+impl Default for DesktopMonitorAudio {
+    /// Wallpapers start muted; unmuting without a saved volume plays at full volume
+    fn default() -> Self {
+        Self {
+            volume: 100,
+            muted: true,
+        }
+    }
+}
+
+/// Color correction of one monitor in percent, from its `[outputs]` settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DesktopMonitorColor {
+    pub brightness: u16,
+    pub contrast: u16,
+    pub gamma: u16,
+    pub saturation: u16,
+}
+
+impl Default for DesktopMonitorColor {
+    /// 100 percent leaves the picture unchanged
+    fn default() -> Self {
+        Self {
+            brightness: 100,
+            contrast: 100,
+            gamma: 100,
+            saturation: 100,
+        }
+    }
+}
+
+/// When this session last put a wallpaper on a monitor, and how long the engine took
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DesktopMonitorApplied {
+    pub ago: Duration,
+    pub latency: Duration,
+}
+
 #[derive(Debug, Clone)]
 pub struct DesktopPageResult {
     pub monitors: Vec<MonitorDescriptor>,
@@ -39,6 +106,12 @@ pub struct DesktopPageResult {
     pub persistence_issue: Option<String>,
     pub assignments_available: bool,
     pub stale: bool,
+    /// Keyed by monitor id; monitors without saved audio settings are absent
+    pub monitor_audio: BTreeMap<String, DesktopMonitorAudio>,
+    /// Keyed by monitor id; monitors without saved color settings are absent
+    pub monitor_color: BTreeMap<String, DesktopMonitorColor>,
+    /// Keyed by monitor id; only monitors applied since the engine started
+    pub monitor_applied: BTreeMap<String, DesktopMonitorApplied>,
 }
 
 #[cfg(test)]
@@ -67,6 +140,9 @@ mod tests {
             persistence_issue: Some("Desktop persistence is not available yet".to_string()),
             assignments_available: false,
             stale: true,
+            monitor_audio: BTreeMap::new(),
+            monitor_color: BTreeMap::new(),
+            monitor_applied: BTreeMap::new(),
         };
 
         assert!(result.monitors.is_empty());
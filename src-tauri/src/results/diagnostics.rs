@@ -0,0 +1,88 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticsLogLine {
+    pub sequence: u64,
+    pub timestamp_ms: u64,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticsCapability {
+    pub key: String,
+    pub available: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticsGpuSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticsGpuMessage {
+    pub severity: DiagnosticsGpuSeverity,
+    pub message: String,
+    pub count: u32,
+}
+
+/// What the engine reports about a wallpaper while it plays
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiagnosticsPlayback {
+    pub title: Option<String>,
+    pub duration_secs: Option<f64>,
+    pub hwdec: Option<String>,
+    pub fps: Option<f64>,
+    pub skip_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagnosticsOutput {
+    pub name: String,
+    pub resolution: String,
+    pub wallpaper_path: Option<String>,
+    pub gpu_messages: Vec<DiagnosticsGpuMessage>,
+    pub playback: Option<DiagnosticsPlayback>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticsEngineState {
+    Running {
+        outputs: Vec<DiagnosticsOutput>,
+        render_backend: String,
+        on_battery: bool,
+        power_paused: bool,
+    },
+    NotStarted,
+    Unavailable {
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct DiagnosticsPageResult {
+    pub app_version: String,
+    pub engine: DiagnosticsEngineState,
+    pub capabilities: Vec<DiagnosticsCapability>,
+    pub logs: Vec<DiagnosticsLogLine>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostics_engine_state_distinguishes_idle_from_unavailable() {
+        let idle = DiagnosticsEngineState::NotStarted;
+        let unavailable = DiagnosticsEngineState::Unavailable {
+            reason: "engine lock was poisoned".to_string(),
+        };
+
+        assert!(matches!(idle, DiagnosticsEngineState::NotStarted));
+        assert!(matches!(
+            unavailable,
+            DiagnosticsEngineState::Unavailable { .. }
+        ));
+    }
+}
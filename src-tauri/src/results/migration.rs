@@ -0,0 +1,60 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Wallpaper tools `lwe migrate` reads setups from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationSource {
+    Mpvpaper,
+    Swww,
+    Hyprpaper,
+}
+
+impl MigrationSource {
+    pub const ALL: [Self; 3] = [Self::Mpvpaper, Self::Swww, Self::Hyprpaper];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "mpvpaper" => Some(Self::Mpvpaper),
+            "swww" => Some(Self::Swww),
+            "hyprpaper" => Some(Self::Hyprpaper),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MigrationSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Mpvpaper => "mpvpaper",
+            Self::Swww => "swww",
+            Self::Hyprpaper => "hyprpaper",
+        })
+    }
+}
+
+/// A wallpaper another tool was set up to show
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigratedWallpaper {
+    pub source: MigrationSource,
+    /// `file:line` the setup was read from
+    pub origin: String,
+    /// Output name such as `DP-1`; None = every output
+    pub output: Option<String>,
+    pub path: PathBuf,
+    /// `[outputs]` layout matching how the tool scaled the wallpaper
+    pub layout: Option<&'static str>,
+    pub muted: Option<bool>,
+}
+
+/// What `lwe migrate` added, or would add with `--dry-run`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub files: Vec<PathBuf>,
+    pub folders: Vec<PathBuf>,
+    /// Output name and the file it shows
+    pub assignments: Vec<(String, PathBuf)>,
+    /// Outputs whose `[outputs]` layout or mute setting was filled in
+    pub configured_outputs: Vec<String>,
+    /// Wallpapers that could not be carried over, and why
+    pub notes: Vec<String>,
+}
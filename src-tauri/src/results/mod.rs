@@ -1,9 +1,15 @@
 pub mod app_shell;
+pub mod collection;
 pub mod compatibility;
 pub mod desktop;
 pub mod desktop_persistence;
+pub mod diagnostics;
 pub mod library;
+pub mod migration;
 pub mod monitor_discovery;
+pub mod playlist;
+pub mod profile;
+pub mod schedule;
 pub mod session_persistence;
 pub mod settings;
 pub mod settings_persistence;
@@ -0,0 +1,60 @@
+use lwe_library::Playlist;
+
+use crate::results::collection::CollectionSummary;
+use crate::services::monitor_service::MonitorDescriptor;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistLibraryItem {
+    pub id: String,
+    pub title: String,
+}
+
+/// Where a playlist currently is on one monitor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistRotationStatus {
+    pub monitor_id: String,
+    pub playlist_id: i64,
+    pub current_item_id: Option<String>,
+    pub paused: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlaylistPageResult {
+    pub playlists: Vec<Playlist>,
+    pub collections: Vec<CollectionSummary>,
+    pub library_items: Vec<PlaylistLibraryItem>,
+    pub library_issue: Option<String>,
+    pub monitors: Vec<MonitorDescriptor>,
+    pub monitor_discovery_issue: Option<String>,
+    pub rotations: Vec<PlaylistRotationStatus>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playlist_page_result_keeps_playlists_when_library_is_unavailable() {
+        let result = PlaylistPageResult {
+            playlists: vec![Playlist {
+                id: 1,
+                name: "Evening".to_string(),
+                shuffle: false,
+                transition: "none".to_string(),
+                default_duration_secs: 300,
+                items: Vec::new(),
+                outputs: Vec::new(),
+                folder: None,
+            }],
+            collections: Vec::new(),
+            library_items: Vec::new(),
+            library_issue: Some("Workshop catalog unavailable".to_string()),
+            monitors: Vec::new(),
+            monitor_discovery_issue: None,
+            rotations: Vec::new(),
+        };
+
+        assert_eq!(result.playlists.len(), 1);
+        assert!(result.library_items.is_empty());
+    }
+}
@@ -0,0 +1,7 @@
+/// A saved profile as listed by `lwe profile list` and the desktop page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileSummary {
+    pub name: String,
+    /// Monitors the profile puts a wallpaper on
+    pub monitor_ids: Vec<String>,
+}
@@ -0,0 +1,19 @@
+/// One slot of the time-of-day schedule as configured
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleSlotStatus {
+    pub name: String,
+    pub start: String,
+    pub item_id: String,
+}
+
+/// Where the time-of-day schedule currently is
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScheduleStatus {
+    pub slots: Vec<ScheduleSlotStatus>,
+    pub active_slot: Option<String>,
+    /// Unix time the schedule switches next, or takes over from a pinned slot
+    pub next_change: Option<i64>,
+    pub pinned: bool,
+    /// Why the schedule cannot run, e.g. a slot with an invalid start
+    pub issue: Option<String>,
+}
@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
+
 use crate::models::{WorkshopAgeRating, WorkshopOnlineItemType};
 
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(default)]
 pub struct PersistedSettings {
     pub language: String,
@@ -10,6 +12,270 @@ pub struct PersistedSettings {
     pub workshop_query: String,
     pub workshop_age_ratings: Vec<WorkshopAgeRating>,
     pub workshop_item_types: Vec<WorkshopOnlineItemType>,
+    pub confirm_apply_to_all: bool,
+    pub compositor_workarounds: PersistedCompositorWorkarounds,
+    pub outputs: BTreeMap<String, PersistedOutputSettings>,
+    pub presentation: PersistedPresentation,
+    pub signage: PersistedSignage,
+    pub streams: PersistedStreams,
+    pub playback: PersistedPlayback,
+    pub hooks: PersistedHooks,
+    pub scheduling: PersistedScheduling,
+    pub gpu: PersistedGpu,
+    pub resources: PersistedResources,
+    pub library: PersistedLibrary,
+    pub schedule: PersistedSchedule,
+    pub profiles: BTreeMap<String, PersistedProfile>,
+}
+
+/// Hand-edited `[compositor_workarounds]` overrides; unset keys keep the registry defaults.
+/// `startup_timeout_secs` is how long the engine waits for the compositor at
+/// startup (default 30).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedCompositorWorkarounds {
+    pub compositor: Option<String>,
+    pub exclusive_zone: Option<i32>,
+    pub buffer_scale: Option<bool>,
+    pub output_size_fallback: Option<bool>,
+    pub frame_callback_fallback_ms: Option<u64>,
+    pub startup_timeout_secs: Option<u64>,
+}
+
+/// `[outputs."<name>"]` table: `layout` is fill, contain, stretch, centre,
+/// ambient-fill, or span and `letterbox` is a `#rrggbb` color, `blur`, or `dominant`. `volume` (0-100) and
+/// `muted` are also written by the desktop page; `audio_device` takes an mpv
+/// device name such as `pulse/alsa_output.pci-0000_00_1f.3.hdmi-stereo`.
+/// `fallbacks` lists image paths and `#rrggbb` colors shown in order while
+/// the wallpaper on the output fails; `signal_lost`, an image path or
+/// `#rrggbb` color, is shown ahead of them while a camera or live stream is
+/// down. `safe_mode`, also an image path or `#rrggbb` color, replaces a
+/// wallpaper that keeps failing until another one is applied.
+/// `brightness`, `contrast`, `gamma` and
+/// `saturation` are percentages from 0 to 200, where 100 leaves the picture
+/// unchanged. `layer` (`background` or `bottom`), `exclusive_zone` and
+/// `margins` place the wallpaper surface, e.g. clear of a dock.
+/// `mpv_options` adds to or replaces `[playback.mpv_options]` on this monitor.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedOutputSettings {
+    pub layout: Option<String>,
+    pub letterbox: Option<String>,
+    pub volume: Option<u8>,
+    pub muted: Option<bool>,
+    pub audio_device: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fallbacks: Vec<String>,
+    pub signal_lost: Option<String>,
+    pub safe_mode: Option<String>,
+    pub brightness: Option<u16>,
+    pub contrast: Option<u16>,
+    pub gamma: Option<u16>,
+    pub saturation: Option<u16>,
+    pub layer: Option<String>,
+    pub exclusive_zone: Option<i32>,
+    pub margins: Option<PersistedMargins>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub mpv_options: BTreeMap<String, String>,
+}
+
+/// `margins` of an `[outputs."<name>"]` table in logical pixels; unset edges are 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedMargins {
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub left: i32,
+}
+
+/// Hand-edited `[presentation]` table for signage setups: while one of these
+/// Library items, or an item from one of these playlist ids, is on a monitor,
+/// LWE keeps the screen from idling.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedPresentation {
+    pub items: Vec<String>,
+    pub playlists: Vec<i64>,
+}
+
+/// Hand-edited `[signage]` table: when `manifest_url` is set, LWE fetches that
+/// JSON manifest every `refresh_secs` (default 300), caches its media and rotates
+/// it on `monitors` (monitor ids; empty means every connected monitor).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedSignage {
+    pub manifest_url: String,
+    pub refresh_secs: Option<u64>,
+    pub monitors: Vec<String>,
+}
+
+/// Hand-edited `[streams]` table: each of `urls` (a direct media link, or a
+/// YouTube, Vimeo or other page `yt_dlp` understands) is downloaded into the
+/// cache and shown in the Library. Downloads older than `ttl_hours` (default
+/// 168) are fetched again, and the oldest are evicted once the cache passes
+/// `cache_mb` (default 2048). `yt_dlp` names the yt-dlp binary (default `yt-dlp`).
+/// Cameras and live feeds (`rtsp://` and similar URLs, `/dev/video0`) are
+/// listed as they are and played live.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedStreams {
+    pub urls: Vec<String>,
+    pub ttl_hours: Option<u64>,
+    pub cache_mb: Option<u64>,
+    pub yt_dlp: Option<String>,
+}
+
+/// Hand-edited `[playback]` table: `pause_on_fullscreen` (default on) pauses a
+/// monitor's wallpaper while a fullscreen window covers it, and
+/// `pause_on_maximized` extends that to maximized windows. `deep_sleep` stops
+/// decoding and drawing altogether while fullscreen or maximized windows cover
+/// every monitor. With `audio_follows_focus`, only the monitor holding the
+/// focused window plays audio.
+/// `transition` (`none`, `crossfade`, `slide` or `zoom`) animates wallpaper
+/// switches over `transition_ms`. `preload_secs` opens the next playlist
+/// video that long before its switch, trading one more decoder for a seamless cut.
+/// `pause_on_idle_seconds` pauses wallpapers after that long without input and
+/// while the screensaver or lock screen is active. `lut` color grades every
+/// wallpaper with a .cube file unless the wallpaper has its own LUT.
+/// `[playback.battery]` makes
+/// playback cheaper while on battery. `[playback.mpv_options]` passes raw mpv
+/// options such as `scale = "ewa_lanczossharp"` to every player, after ours.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedPlayback {
+    pub pause_on_fullscreen: bool,
+    pub pause_on_maximized: bool,
+    pub deep_sleep: bool,
+    pub audio_follows_focus: bool,
+    pub transition: Option<String>,
+    pub transition_ms: Option<u64>,
+    pub preload_secs: Option<u64>,
+    pub pause_on_idle_seconds: Option<u32>,
+    pub lut: Option<std::path::PathBuf>,
+    pub battery: PersistedBatteryProfile,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub mpv_options: BTreeMap<String, String>,
+}
+
+/// Hand-edited `[playback.battery]` table, used while the machine runs on
+/// battery: `fps` caps decoding and drawing, `hwdec` (`auto`, `force` or
+/// `no`) switches hardware decoding, and `static_fallback` holds every
+/// wallpaper on its current frame.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedBatteryProfile {
+    pub fps: Option<u32>,
+    pub hwdec: Option<String>,
+    pub static_fallback: bool,
+}
+
+impl Default for PersistedPlayback {
+    fn default() -> Self {
+        Self {
+            pause_on_fullscreen: true,
+            pause_on_maximized: false,
+            deep_sleep: false,
+            audio_follows_focus: false,
+            transition: None,
+            transition_ms: None,
+            preload_secs: None,
+            pause_on_idle_seconds: None,
+            lut: None,
+            battery: PersistedBatteryProfile::default(),
+            mpv_options: BTreeMap::new(),
+        }
+    }
+}
+
+/// Hand-edited `[hooks]` table: shell commands run on wallpaper changes
+/// (`on_apply`), pause and resume (`on_pause`) and new monitors
+/// (`on_output_added`), with `LWE_EVENT`, `LWE_OUTPUT`, `LWE_WALLPAPER` and
+/// `LWE_RESOLUTION` describing the event.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedHooks {
+    pub on_apply: Option<String>,
+    pub on_pause: Option<String>,
+    pub on_output_added: Option<String>,
+}
+
+/// Hand-edited `[scheduling]` table for the wallpaper engine and its decoders,
+/// read into the scheduling fields of `PowerConfig`: `nice` (unset by default),
+/// `idle_priority` for `SCHED_IDLE`, and `cpu_affinity` as `all`, `efficiency`
+/// (the E-cores of a hybrid CPU) or a core list like `0-3,8`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedScheduling {
+    pub nice: Option<i32>,
+    pub idle_priority: bool,
+    pub cpu_affinity: Option<String>,
+}
+
+/// Hand-edited `[gpu]` table: `render_device` pins the engine's EGL context and
+/// VAAPI decoding to one DRM render node, e.g. `/dev/dri/renderD129`. `debug`
+/// creates a KHR_debug context so driver errors reach the diagnostics page.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedGpu {
+    pub render_device: Option<String>,
+    pub debug: bool,
+}
+
+/// Hand-edited `[resources]` table: `cpu_quota_percent` (100 = one core) and
+/// `memory_high_mb` cap `lwe.service` when LWE runs as a systemd user service.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedResources {
+    pub cpu_quota_percent: Option<u32>,
+    pub memory_high_mb: Option<usize>,
+}
+
+/// `[library]` table: `hidden_items` lists library item ids removed from the
+/// library. Their files stay on disk; delete an id to show the item again.
+/// `folders` are scanned recursively for videos and images, and `files` are
+/// single media files; both are added from the Library page.
+/// `thumbnail_cache_mb` caps the stored thumbnails and scrub strips, 512 when
+/// unset; the least recently shown go first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedLibrary {
+    pub hidden_items: Vec<String>,
+    pub folders: Vec<std::path::PathBuf>,
+    pub files: Vec<std::path::PathBuf>,
+    pub thumbnail_cache_mb: Option<u64>,
+}
+
+/// Hand-edited `[schedule]` table: each `[[schedule.slots]]` entry shows
+/// library `item` on `monitors` (empty = every monitor) from `start` until the
+/// next slot starts. `start` is `HH:MM`, `sunrise` or `sunset`, optionally
+/// shifted like `sunset-30`. `latitude`/`longitude` place the sun; without
+/// them the location is asked from GeoClue.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedSchedule {
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub monitors: Vec<String>,
+    pub slots: Vec<PersistedScheduleSlot>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedScheduleSlot {
+    pub name: String,
+    pub start: String,
+    pub item: String,
+}
+
+/// `[profiles."<name>"]` table written by `lwe profile save` and the desktop
+/// page: the library item on each monitor (`assignments`, keyed by monitor id)
+/// and the `[outputs]` settings when the profile was saved.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PersistedProfile {
+    pub assignments: BTreeMap<String, String>,
+    pub outputs: BTreeMap<String, PersistedOutputSettings>,
 }
 
 impl Default for PersistedSettings {
@@ -27,11 +293,26 @@ impl Default for PersistedSettings {
                 WorkshopOnlineItemType::Web,
                 WorkshopOnlineItemType::Application,
             ],
+            confirm_apply_to_all: true,
+            compositor_workarounds: PersistedCompositorWorkarounds::default(),
+            outputs: BTreeMap::new(),
+            presentation: PersistedPresentation::default(),
+            signage: PersistedSignage::default(),
+            streams: PersistedStreams::default(),
+            playback: PersistedPlayback::default(),
+            hooks: PersistedHooks::default(),
+            scheduling: PersistedScheduling::default(),
+            gpu: PersistedGpu::default(),
+            resources: PersistedResources::default(),
+            library: PersistedLibrary::default(),
+            schedule: PersistedSchedule::default(),
+            profiles: BTreeMap::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)] // Intentional: short-lived load result, matched once
 pub enum SettingsPersistenceLoad {
     Loaded(PersistedSettings),
     Unavailable { reason: String },
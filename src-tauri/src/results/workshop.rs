@@ -1,11 +1,13 @@
 use crate::results::compatibility::CompatibilityAssessment;
-use lwe_library::{WorkshopCatalogEntry, WorkshopSyncState};
+use lwe_library::{WeProperty, WorkshopCatalogEntry, WorkshopSyncState};
 
 #[derive(Debug, Clone, Default)]
 pub struct WorkshopProjectMetadata {
     pub description: Option<String>,
     pub tags: Vec<String>,
     pub inferred_age_rating: Option<String>,
+    /// Editable project.json properties in display order
+    pub user_properties: Vec<(String, WeProperty)>,
 }
 
 #[derive(Debug, Clone)]
@@ -36,6 +38,22 @@ pub struct WorkshopInspection {
     pub entry: AssessedWorkshopCatalogEntry,
 }
 
+/// Public Steam metadata for one Workshop item, downloaded or not
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkshopItemDetails {
+    pub workshop_id: String,
+    /// False when Steam has no public item with this id
+    pub found: bool,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub preview_url: Option<String>,
+    pub file_size: Option<u64>,
+    pub tags: Vec<String>,
+    pub subscriptions: Option<u64>,
+    /// Unix time of the last update
+    pub time_updated: Option<u64>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,6 +160,18 @@ fn current_resolution(
         .and_then(Value::as_u64)
         .ok_or_else(|| format!("Output `{output_id}` is missing resolution height"))?;
 
+    // Rotated outputs present the mode sideways
+    let rotated = output
+        .get("logical")
+        .and_then(|logical| logical.get("transform"))
+        .and_then(Value::as_str)
+        .is_some_and(|transform| transform.ends_with("90") || transform.ends_with("270"));
+    let (width, height) = if rotated {
+        (height, width)
+    } else {
+        (width, height)
+    };
+
     Ok(Some(format!("{width}x{height}")))
 }
 
@@ -258,4 +270,27 @@ mod tests {
                     && monitors[0].resolution == "3840x2160"
         ));
     }
+
+    #[test]
+    fn parse_outputs_reports_rotated_outputs_in_portrait() {
+        let result = parse_outputs(json!({
+            "DP-2": {
+                "name": "DP-2",
+                "make": "Dell",
+                "model": "U2720Q",
+                "serial": null,
+                "modes": [
+                    { "width": 2560, "height": 1440 }
+                ],
+                "current_mode": 0,
+                "logical": { "transform": "Flipped270" }
+            }
+        }));
+
+        assert!(matches!(
+            result,
+            BackendMonitorDiscovery::Known(monitors)
+                if monitors.len() == 1 && monitors[0].resolution == "1440x2560"
+        ));
+    }
 }
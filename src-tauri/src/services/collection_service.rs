@@ -0,0 +1,311 @@
+use std::path::PathBuf;
+
+use lwe_library::{LibraryDatabase, Playlist, PlaylistEntry};
+
+use crate::results::collection::CollectionSummary;
+use crate::results::monitor_discovery::MonitorDiscoveryResult;
+use crate::services::monitor_service::MonitorService;
+use crate::services::playlist_scheduler_service::PlaylistSchedulerService;
+use crate::services::playlist_service::PlaylistService;
+
+/// Entry duration of a playlist made from a collection, until it is edited
+const COLLECTION_PLAYLIST_DURATION_SECS: u32 = 300;
+
+/// User-named groups of library items, kept in the library database
+pub struct CollectionService;
+
+pub struct ScopedCollectionService {
+    path: PathBuf,
+}
+
+impl CollectionService {
+    /// Rotate a collection as the playlist of the same name on `monitor_id`,
+    /// or on every monitor; returns the monitors it now rotates on
+    pub fn apply(collection_id: i64, monitor_id: Option<&str>) -> Result<Vec<String>, String> {
+        let monitor_ids = match monitor_id {
+            Some(monitor_id) => vec![monitor_id.to_string()],
+            None => match MonitorService::list_monitors() {
+                MonitorDiscoveryResult::Known(monitors) => monitors
+                    .into_iter()
+                    .map(|monitor| monitor.id)
+                    .collect::<Vec<_>>(),
+                MonitorDiscoveryResult::Unavailable { reason } => return Err(reason),
+            },
+        };
+        if monitor_ids.is_empty() {
+            return Err("No monitors are connected".to_string());
+        }
+
+        let playlist_id = Self::for_user_path().save_as_playlist(collection_id)?;
+        let playlists = PlaylistService::for_user_path();
+        for monitor_id in &monitor_ids {
+            playlists.assign_monitor(monitor_id, Some(playlist_id))?;
+        }
+        PlaylistSchedulerService::sync();
+        Ok(monitor_ids)
+    }
+
+    pub fn for_user_path() -> ScopedCollectionService {
+        Self::for_path(LibraryDatabase::default_path())
+    }
+
+    pub fn for_path(path: PathBuf) -> ScopedCollectionService {
+        ScopedCollectionService { path }
+    }
+
+    pub fn for_test(path: PathBuf) -> ScopedCollectionService {
+        Self::for_path(path)
+    }
+}
+
+impl ScopedCollectionService {
+    fn open(&self) -> Result<LibraryDatabase, String> {
+        LibraryDatabase::open(&self.path).map_err(|error| {
+            format!(
+                "Failed to open collection database {}: {error:#}",
+                self.path.display()
+            )
+        })
+    }
+
+    pub fn load_collections(&self) -> Result<Vec<CollectionSummary>, String> {
+        let database = self.open()?;
+        database
+            .list_collections()
+            .map_err(|error| format!("Failed to load collections: {error:#}"))?
+            .into_iter()
+            .map(|collection| {
+                let item_ids = database
+                    .collection_item_ids(collection.id)
+                    .map_err(|error| {
+                        format!("Failed to load collection '{}': {error:#}", collection.name)
+                    })?;
+                Ok(CollectionSummary {
+                    id: collection.id,
+                    name: collection.name,
+                    item_ids,
+                })
+            })
+            .collect()
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Result<CollectionSummary, String> {
+        let name = collection_name(name)?;
+        self.load_collections()?
+            .into_iter()
+            .find(|collection| collection.name == name)
+            .ok_or_else(|| format!("There is no collection named {name}"))
+    }
+
+    /// Create a collection holding `item_ids`, in order
+    pub fn create(&self, name: &str, item_ids: &[String]) -> Result<i64, String> {
+        let name = collection_name(name)?;
+        self.ensure_name_free(name, None)?;
+
+        let database = self.open()?;
+        let id = database
+            .create_collection(name, None)
+            .map_err(|error| format!("Failed to create collection '{name}': {error:#}"))?;
+        add_items(&database, id, item_ids)?;
+        Ok(id)
+    }
+
+    pub fn rename(&self, collection_id: i64, name: &str) -> Result<(), String> {
+        let name = collection_name(name)?;
+        self.ensure_name_free(name, Some(collection_id))?;
+
+        match self.open()?.rename_collection(collection_id, name) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(format!("Collection {collection_id} not found")),
+            Err(error) => Err(format!(
+                "Failed to rename collection {collection_id}: {error:#}"
+            )),
+        }
+    }
+
+    pub fn delete(&self, collection_id: i64) -> Result<(), String> {
+        match self.open()?.delete_collection(collection_id) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(format!("Collection {collection_id} not found")),
+            Err(error) => Err(format!(
+                "Failed to delete collection {collection_id}: {error:#}"
+            )),
+        }
+    }
+
+    /// Append items to a collection; items already in it keep their place
+    pub fn add_items(&self, collection_id: i64, item_ids: &[String]) -> Result<(), String> {
+        self.find(collection_id)?;
+        add_items(&self.open()?, collection_id, item_ids)
+    }
+
+    pub fn remove_item(&self, collection_id: i64, item_id: &str) -> Result<(), String> {
+        self.find(collection_id)?;
+        self.open()?
+            .remove_from_collection(collection_id, item_id)
+            .map_err(|error| format!("Failed to update collection {collection_id}: {error:#}"))
+    }
+
+    /// Save the collection as the playlist of the same name, replacing that
+    /// playlist's entries but keeping its timing, transition and monitors
+    pub fn save_as_playlist(&self, collection_id: i64) -> Result<i64, String> {
+        let collection = self.find(collection_id)?;
+        if collection.item_ids.is_empty() {
+            return Err(format!("Collection {} is empty", collection.name));
+        }
+
+        let database = self.open()?;
+        let mut playlist = database
+            .list_playlists()
+            .map_err(|error| format!("Failed to load playlists: {error:#}"))?
+            .into_iter()
+            .find(|playlist| playlist.name == collection.name)
+            .unwrap_or_else(|| Playlist {
+                id: 0,
+                name: collection.name.clone(),
+                shuffle: false,
+                transition: "none".to_string(),
+                default_duration_secs: COLLECTION_PLAYLIST_DURATION_SECS,
+                items: Vec::new(),
+                outputs: Vec::new(),
+                folder: None,
+            });
+        playlist.items = collection
+            .item_ids
+            .into_iter()
+            .map(|item_id| PlaylistEntry {
+                item_id,
+                duration_secs: None,
+            })
+            .collect();
+        playlist.folder = None;
+
+        database
+            .save_playlist(&playlist)
+            .map_err(|error| format!("Failed to save playlist '{}': {error:#}", playlist.name))
+    }
+
+    fn find(&self, collection_id: i64) -> Result<CollectionSummary, String> {
+        self.load_collections()?
+            .into_iter()
+            .find(|collection| collection.id == collection_id)
+            .ok_or_else(|| format!("Collection {collection_id} not found"))
+    }
+
+    fn ensure_name_free(&self, name: &str, renamed: Option<i64>) -> Result<(), String> {
+        let taken = self
+            .load_collections()?
+            .iter()
+            .any(|collection| collection.name == name && Some(collection.id) != renamed);
+        if taken {
+            return Err(format!("There is already a collection named {name}"));
+        }
+        Ok(())
+    }
+}
+
+fn add_items(
+    database: &LibraryDatabase,
+    collection_id: i64,
+    item_ids: &[String],
+) -> Result<(), String> {
+    for item_id in item_ids {
+        database
+            .add_to_collection(collection_id, item_id)
+            .map_err(|error| format!("Failed to update collection {collection_id}: {error:#}"))?;
+    }
+    Ok(())
+}
+
+fn collection_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("A collection needs a name".to_string());
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn test_database_path() -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+
+        std::env::temp_dir()
+            .join(format!("lwe-collection-service-{unique}"))
+            .join("library.db")
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn collection_service_creates_renames_and_edits_collections() {
+        let service = CollectionService::for_test(test_database_path());
+
+        let id = service
+            .create("  Nature  ", &ids(&["scene-7", "video-3"]))
+            .unwrap();
+        service
+            .add_items(id, &ids(&["video-3", "local-1"]))
+            .unwrap();
+        service.remove_item(id, "scene-7").unwrap();
+        service.rename(id, "Forests").unwrap();
+
+        let collections = service.load_collections().unwrap();
+        assert_eq!(
+            collections,
+            vec![CollectionSummary {
+                id,
+                name: "Forests".to_string(),
+                item_ids: ids(&["video-3", "local-1"]),
+            }]
+        );
+        assert_eq!(service.find_by_name("Forests").unwrap().id, id);
+
+        assert!(service.create(" ", &[]).is_err());
+        let other = service.create("Evening", &[]).unwrap();
+        assert_eq!(
+            service.rename(other, "Forests"),
+            Err("There is already a collection named Forests".to_string())
+        );
+
+        service.delete(id).unwrap();
+        assert!(service.delete(id).is_err());
+        assert!(service.find_by_name("Forests").is_err());
+    }
+
+    #[test]
+    fn collection_service_saves_a_collection_as_its_playlist() {
+        let path = test_database_path();
+        let service = CollectionService::for_test(path.clone());
+        let playlists = PlaylistService::for_test(path);
+
+        let id = service.create("Evening", &ids(&["scene-7"])).unwrap();
+        let playlist_id = service.save_as_playlist(id).unwrap();
+        playlists.assign_monitor("DP-1", Some(playlist_id)).unwrap();
+
+        // Applying again refreshes the entries of the same playlist
+        service.add_items(id, &ids(&["video-3"])).unwrap();
+        assert_eq!(service.save_as_playlist(id).unwrap(), playlist_id);
+
+        let saved = playlists.load_playlists().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].name, "Evening");
+        assert_eq!(saved[0].items.len(), 2);
+        assert_eq!(saved[0].items[1].item_id, "video-3");
+        assert_eq!(saved[0].outputs, ids(&["DP-1"]));
+
+        let empty = service.create("Empty", &[]).unwrap();
+        assert_eq!(
+            service.save_as_playlist(empty),
+            Err("Collection Empty is empty".to_string())
+        );
+    }
+}
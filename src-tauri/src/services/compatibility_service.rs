@@ -8,6 +8,11 @@ impl CompatibilityService {
     fn project_metadata(entry: &WorkshopCatalogEntry) -> WorkshopProjectMetadata {
         WeProject::load(&entry.project_dir)
             .map(|project| WorkshopProjectMetadata {
+                user_properties: project
+                    .user_properties()
+                    .into_iter()
+                    .map(|(name, property)| (name.to_string(), property.clone()))
+                    .collect(),
                 description: project.description,
                 tags: project.tags,
                 inferred_age_rating: None,
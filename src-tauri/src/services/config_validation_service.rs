@@ -0,0 +1,321 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use lwe_core::config::{
+    deserialize_noting_unknown, validate_app_settings, validate_config, ConfigIssue,
+};
+use lwe_core::{HwdecMode, LayoutMode, LetterboxFill, SurfaceLayer, TransitionKind};
+use lwe_engine::Fallback;
+
+use crate::results::settings_persistence::PersistedSettings;
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+
+pub struct ConfigValidationService;
+
+impl ConfigValidationService {
+    /// Check `path`, or the app's `settings.toml` when none is given. A
+    /// `.toml` file is read as the app's settings, `settings.yaml` as the
+    /// GUI settings and any other file as a legacy `config.yaml`.
+    pub fn validate_file(path: Option<&Path>) -> Result<(PathBuf, Vec<ConfigIssue>), String> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => SettingsPersistenceService::for_user_path()?
+                .path()
+                .to_path_buf(),
+        };
+        let text = fs::read_to_string(&path)
+            .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+        let issues = validate_text(&path, &text);
+        Ok((path, issues))
+    }
+
+    /// Problems in the app's `settings.toml`; none when it does not exist yet
+    pub fn settings_issues(path: &Path) -> Vec<ConfigIssue> {
+        match fs::read_to_string(path) {
+            Ok(text) => validate_settings_toml(&text),
+            Err(error) if error.kind() == ErrorKind::NotFound => Vec::new(),
+            Err(error) => vec![ConfigIssue::error(
+                None,
+                None,
+                format!("Failed to read {}: {error}", path.display()),
+            )],
+        }
+    }
+}
+
+fn validate_text(path: &Path, text: &str) -> Vec<ConfigIssue> {
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let stem = path.file_stem().and_then(|stem| stem.to_str());
+    match (extension, stem) {
+        (Some("toml"), _) => validate_settings_toml(text),
+        (Some("yaml" | "yml"), Some("settings")) => validate_app_settings(text),
+        _ => validate_config(text),
+    }
+}
+
+/// Unknown keys, values the app ignores and missing fallback images in
+/// `settings.toml`
+fn validate_settings_toml(text: &str) -> Vec<ConfigIssue> {
+    let (parsed, unknown) =
+        deserialize_noting_unknown::<PersistedSettings, _>(toml::Deserializer::new(text));
+    let mut issues = unknown
+        .into_iter()
+        .map(|key| {
+            ConfigIssue::warning(
+                toml_key_line(text, &key),
+                Some(key.join(".")),
+                "unknown key, ignored",
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let settings = match parsed {
+        Ok(settings) => settings,
+        Err(error) => {
+            let line = error
+                .span()
+                .map(|span| text[..span.start].matches('\n').count() + 1);
+            issues.push(ConfigIssue::error(line, None, error.message().to_string()));
+            return issues;
+        }
+    };
+
+    let mut invalid = |key: &[&str], message: String| {
+        let key = key.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+        issues.push(ConfigIssue::error(
+            toml_key_line(text, &key),
+            Some(key.join(".")),
+            message,
+        ));
+    };
+    for (output, settings) in &settings.outputs {
+        if output.contains(['*', '?']) {
+            invalid(
+                &["outputs", output.as_str()],
+                "outputs are matched by exact name; wildcards are not supported here".to_string(),
+            );
+        }
+        if let Some(Err(reason)) = settings.layout.as_deref().map(str::parse::<LayoutMode>) {
+            invalid(&["outputs", output.as_str(), "layout"], reason);
+        }
+        if let Some(Err(reason)) = settings
+            .letterbox
+            .as_deref()
+            .map(str::parse::<LetterboxFill>)
+        {
+            invalid(&["outputs", output.as_str(), "letterbox"], reason);
+        }
+        if let Some(Err(reason)) = settings.layer.as_deref().map(str::parse::<SurfaceLayer>) {
+            invalid(&["outputs", output.as_str(), "layer"], reason);
+        }
+        if let Some(volume) = settings.volume.filter(|volume| *volume > 100) {
+            invalid(
+                &["outputs", output.as_str(), "volume"],
+                format!("volume is 0 to 100, not {volume}"),
+            );
+        }
+        for (key, percent) in [
+            ("brightness", settings.brightness),
+            ("contrast", settings.contrast),
+            ("gamma", settings.gamma),
+            ("saturation", settings.saturation),
+        ] {
+            if let Some(percent) = percent.filter(|percent| *percent > 200) {
+                invalid(
+                    &["outputs", output.as_str(), key],
+                    format!("{key} is 0 to 200, not {percent}"),
+                );
+            }
+        }
+        let fallbacks = settings
+            .fallbacks
+            .iter()
+            .map(|fallback| ("fallbacks", fallback))
+            .chain(
+                settings
+                    .signal_lost
+                    .iter()
+                    .map(|image| ("signal_lost", image)),
+            )
+            .chain(settings.safe_mode.iter().map(|image| ("safe_mode", image)));
+        for (key, fallback) in fallbacks {
+            match fallback.parse::<Fallback>() {
+                Err(reason) => invalid(&["outputs", output.as_str(), key], reason),
+                Ok(Fallback::Image(path)) if !path.exists() => invalid(
+                    &["outputs", output.as_str(), key],
+                    format!("{} does not exist", path.display()),
+                ),
+                Ok(_) => {}
+            }
+        }
+    }
+    if let Some(Err(reason)) = settings
+        .playback
+        .transition
+        .as_deref()
+        .map(str::parse::<TransitionKind>)
+    {
+        invalid(&["playback", "transition"], reason);
+    }
+    if let Some(lut) = settings.playback.lut.as_ref().filter(|lut| !lut.exists()) {
+        invalid(
+            &["playback", "lut"],
+            format!("{} does not exist", lut.display()),
+        );
+    }
+    if let Some(Err(reason)) = settings
+        .playback
+        .battery
+        .hwdec
+        .as_deref()
+        .map(str::parse::<HwdecMode>)
+    {
+        invalid(&["playback", "battery", "hwdec"], reason);
+    }
+
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+/// 1-based line of `key` in a TOML file: the `key =` line inside its
+/// table, or the `[table]` header when the key names a table
+fn toml_key_line(text: &str, key: &[String]) -> Option<usize> {
+    let mut table = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if let Some(header) = line
+            .strip_prefix('[')
+            .and_then(|header| header.split(']').next())
+        {
+            table = toml_key_parts(header.trim_start_matches('['));
+            if table == key {
+                return Some(index + 1);
+            }
+            continue;
+        }
+        let Some((name, _)) = line.split_once('=') else {
+            continue;
+        };
+        let mut path = table.clone();
+        path.extend(toml_key_parts(name));
+        if path == key {
+            return Some(index + 1);
+        }
+    }
+    None
+}
+
+/// `outputs."DP-1"` -> `["outputs", "DP-1"]`
+fn toml_key_parts(key: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quoted = false;
+    for c in key.trim().chars() {
+        match c {
+            '"' | '\'' => quoted = !quoted,
+            '.' if !quoted => parts.push(String::new()),
+            c if c.is_whitespace() && !quoted => {}
+            c => parts.last_mut().expect("parts is never empty").push(c),
+        }
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use lwe_core::IssueSeverity;
+
+    use super::*;
+
+    #[test]
+    fn settings_toml_reports_unknown_keys_and_bad_values_by_line() {
+        let toml = "language = \"en\"\nlanguaje = \"fr\"\n\n[outputs.\"DP-1\"]\nlayout = \"fil\"\nletterbox = \"blur\"\nvolume = 140\nlayer = \"top\"\n\n[outputs.\"HDMI-*\"]\nfallbacks = [\"/missing/still.png\", \"#20\"]\n";
+
+        let issues = validate_settings_toml(toml);
+        let summary = issues
+            .iter()
+            .map(|issue| {
+                (
+                    issue.severity,
+                    issue.line,
+                    issue.key.clone().unwrap_or_default(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (IssueSeverity::Warning, Some(2), "languaje".to_string()),
+                (
+                    IssueSeverity::Error,
+                    Some(5),
+                    "outputs.DP-1.layout".to_string()
+                ),
+                (
+                    IssueSeverity::Error,
+                    Some(7),
+                    "outputs.DP-1.volume".to_string()
+                ),
+                (
+                    IssueSeverity::Error,
+                    Some(8),
+                    "outputs.DP-1.layer".to_string()
+                ),
+                (IssueSeverity::Error, Some(10), "outputs.HDMI-*".to_string()),
+                (
+                    IssueSeverity::Error,
+                    Some(11),
+                    "outputs.HDMI-*.fallbacks".to_string()
+                ),
+                (
+                    IssueSeverity::Error,
+                    Some(11),
+                    "outputs.HDMI-*.fallbacks".to_string()
+                ),
+            ]
+        );
+        assert_eq!(issues[1].message, "Unknown layout mode: fil");
+    }
+
+    #[test]
+    fn settings_toml_reports_a_type_error_at_its_line() {
+        let issues = validate_settings_toml(
+            "language = \"en\"\n[playback]\npause_on_fullscreen = \"yes\"\n",
+        );
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+        assert_eq!(issues[0].line, Some(3));
+
+        let issues = validate_settings_toml("[playback.battery]\nfps = 15\nhwdec = \"vaapi\"\n");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, Some(3));
+        assert_eq!(issues[0].message, "Unknown hwdec mode: vaapi");
+
+        let issues = validate_settings_toml("[outputs.\"DP-1\"]\nbrightness = 90\ngamma = 250\n");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, Some(3));
+        assert_eq!(issues[0].message, "gamma is 0 to 200, not 250");
+
+        let issues = validate_settings_toml("[playback]\nlut = \"/missing/teal.cube\"\n");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, Some(2));
+        assert_eq!(issues[0].message, "/missing/teal.cube does not exist");
+    }
+
+    #[test]
+    fn validation_follows_the_file_name() {
+        let yaml = "source:\n  type: Url\n  url: https://example.com/a.mp4\nlayuot: Fill\n";
+        assert_eq!(
+            validate_text(Path::new("/etc/lwe/config.yaml"), yaml).len(),
+            1
+        );
+        assert_eq!(
+            validate_text(Path::new("settings.yaml"), "language: en\n"),
+            Vec::new()
+        );
+        assert_eq!(
+            toml_key_parts("outputs.\"DP-1.5\" "),
+            vec!["outputs".to_string(), "DP-1.5".to_string()]
+        );
+    }
+}
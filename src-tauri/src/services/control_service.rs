@@ -0,0 +1,650 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use lwe_core::{
+    CollectionInfo, ColorAdjust, IpcEnvelope, IpcRequest, IpcResponse, LibraryFilter, LibraryItem,
+    OutputStats, OutputStatus, ProcessStats, ProfileInfo, PROTOCOL_VERSION,
+};
+use lwe_engine::{process_usage, EngineStatus, OutputVideoOverrides, ProcessUsage, Transport};
+use lwe_library::LibraryDatabase;
+use zbus::blocking::{connection, Connection};
+use zbus::interface;
+
+use crate::assembly::action_outcome::assemble_desktop_bulk_apply_outcome;
+use crate::results::desktop::DesktopMonitorColor;
+use crate::results::monitor_discovery::MonitorDiscoveryResult;
+use crate::services::collection_service::CollectionService;
+use crate::services::desktop_service::DesktopService;
+use crate::services::monitor_service::MonitorService;
+use crate::services::playlist_scheduler_service::{time_seed, PlaylistSchedulerService};
+use crate::services::profile_service::ProfileService;
+
+/// Bus name the `lwe` command line reaches the running app under
+const CONTROL_BUS_NAME: &str = "dev.lwe.App";
+const CONTROL_OBJECT_PATH: &str = "/dev/lwe/App";
+const CONTROL_INTERFACE: &str = "dev.lwe.App1";
+
+/// How long CPU use is measured for a stats request
+const CPU_SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// `dev.lwe.App1`: protocol requests as JSON strings, one response per call
+struct ControlInterface;
+
+#[interface(name = "dev.lwe.App1")]
+impl ControlInterface {
+    /// Applying can take a few seconds; callers wait for the response
+    fn request(&self, request: &str) -> String {
+        let received = Instant::now();
+        let (id, response) = match serde_json::from_str::<IpcEnvelope<IpcRequest>>(request) {
+            Ok(request) => (request.id, handle_request(request.message)),
+            Err(error) => (
+                None,
+                IpcResponse::Error {
+                    error: format!("Invalid request: {error}"),
+                },
+            ),
+        };
+        let response = IpcEnvelope {
+            id,
+            elapsed_ms: Some(received.elapsed().as_millis() as u64),
+            message: response,
+        };
+        serde_json::to_string(&response).unwrap_or_default()
+    }
+}
+
+/// Answer the requests the app serves itself; everything else is refused
+/// with an error instead of being dropped
+pub(crate) fn handle_request(request: IpcRequest) -> IpcResponse {
+    match request {
+        IpcRequest::Ping => IpcResponse::Pong {
+            protocol: PROTOCOL_VERSION,
+        },
+        IpcRequest::Status => match DesktopService::engine_status() {
+            Ok(status) => {
+                status_response(status.as_ref(), &DesktopService::output_video_overrides())
+            }
+            Err(error) => IpcResponse::Error { error },
+        },
+        IpcRequest::GetStats => match DesktopService::engine_stats() {
+            Ok(stats) => stats_response(
+                &stats.unwrap_or_default(),
+                process_usage(CPU_SAMPLE_INTERVAL),
+            ),
+            Err(error) => IpcResponse::Error { error },
+        },
+        IpcRequest::Snapshot { output, path } => match DesktopService::snapshot(&output, &path) {
+            Ok(()) => IpcResponse::Ok {
+                message: Some(format!("Saved {output} to {}", path.display())),
+            },
+            Err(error) => IpcResponse::Error { error },
+        },
+        IpcRequest::MpvSet {
+            output,
+            property,
+            value,
+        } => match DesktopService::set_mpv_property(&output, &property, &value) {
+            Ok(()) => IpcResponse::Ok {
+                message: Some(format!("Set {property}={value} on {output}")),
+            },
+            Err(error) => IpcResponse::Error { error },
+        },
+        IpcRequest::ApplyRandom { filter, output } => {
+            match apply_random(&filter, output.as_deref()) {
+                Ok(message) => IpcResponse::Ok {
+                    message: Some(message),
+                },
+                Err(error) => IpcResponse::Error { error },
+            }
+        }
+        IpcRequest::ProfileSave { name } => match ProfileService::save(&name) {
+            Ok(profile) => IpcResponse::Ok {
+                message: Some(format!(
+                    "Saved profile {} for {}",
+                    profile.name,
+                    profile.monitor_ids.join(", ")
+                )),
+            },
+            Err(error) => IpcResponse::Error { error },
+        },
+        IpcRequest::ProfileLoad { name } => match ProfileService::load(&name) {
+            Ok(result) => {
+                let outcome = assemble_desktop_bulk_apply_outcome(result);
+                if outcome.ok {
+                    IpcResponse::Ok {
+                        message: outcome.message,
+                    }
+                } else {
+                    IpcResponse::Error {
+                        error: outcome.message.unwrap_or_default(),
+                    }
+                }
+            }
+            Err(error) => IpcResponse::Error { error },
+        },
+        IpcRequest::ProfileList => match ProfileService::list() {
+            Ok(profiles) => IpcResponse::Profiles {
+                profiles: profiles
+                    .into_iter()
+                    .map(|profile| ProfileInfo {
+                        name: profile.name,
+                        outputs: profile.monitor_ids,
+                    })
+                    .collect(),
+            },
+            Err(error) => IpcResponse::Error { error },
+        },
+        IpcRequest::CollectionList => match CollectionService::for_user_path().load_collections() {
+            Ok(collections) => IpcResponse::Collections {
+                collections: collections
+                    .into_iter()
+                    .map(|collection| CollectionInfo {
+                        name: collection.name,
+                        items: collection.item_ids.len(),
+                    })
+                    .collect(),
+            },
+            Err(error) => IpcResponse::Error { error },
+        },
+        IpcRequest::CollectionApply { name, output } => {
+            match apply_collection(&name, output.as_deref()) {
+                Ok(message) => IpcResponse::Ok {
+                    message: Some(message),
+                },
+                Err(error) => IpcResponse::Error { error },
+            }
+        }
+        IpcRequest::PlaylistNext { output } => {
+            match PlaylistSchedulerService::skip(output.as_deref()) {
+                Ok(()) => IpcResponse::Ok { message: None },
+                Err(error) => IpcResponse::Error { error },
+            }
+        }
+        IpcRequest::PlaylistPrevious { output } => {
+            match PlaylistSchedulerService::previous(output.as_deref()) {
+                Ok(()) => IpcResponse::Ok { message: None },
+                Err(error) => IpcResponse::Error { error },
+            }
+        }
+        IpcRequest::SetColor {
+            output,
+            brightness,
+            contrast,
+            gamma,
+            saturation,
+        } => match set_color(&output, [brightness, contrast, gamma, saturation]) {
+            Ok(message) => IpcResponse::Ok {
+                message: Some(message),
+            },
+            Err(error) => IpcResponse::Error { error },
+        },
+        IpcRequest::Seek {
+            output,
+            seconds,
+            relative,
+        } => control_playback(output, Transport::Seek { seconds, relative }),
+        IpcRequest::SetRate { output, rate } => control_playback(output, Transport::Speed(rate)),
+        IpcRequest::FrameStep { output, backward } => {
+            control_playback(output, Transport::FrameStep { backward })
+        }
+        IpcRequest::SetAbLoop { output, range } => {
+            control_playback(output, Transport::AbLoop(range))
+        }
+        IpcRequest::Unknown => IpcResponse::Error {
+            error: format!("This request needs a newer LWE (protocol {PROTOCOL_VERSION})"),
+        },
+        _ => IpcResponse::Error {
+            error: "The app does not answer this request".to_string(),
+        },
+    }
+}
+
+fn control_playback(output: Option<String>, transport: Transport) -> IpcResponse {
+    match DesktopService::control_playback(output.as_deref(), transport) {
+        Ok(()) => IpcResponse::Ok { message: None },
+        Err(error) => IpcResponse::Error { error },
+    }
+}
+
+/// Everything the engine reports about each output in one response; volume
+/// comes from the output settings, since the engine does not report it
+fn status_response(
+    status: Option<&EngineStatus>,
+    overrides: &HashMap<String, OutputVideoOverrides>,
+) -> IpcResponse {
+    let version = Some(env!("CARGO_PKG_VERSION").to_string());
+    let Some(status) = status else {
+        return IpcResponse::Status {
+            running: false,
+            version,
+            outputs: Vec::new(),
+            on_battery: false,
+            power_paused: false,
+            deep_sleep: false,
+            render_backend: None,
+        };
+    };
+
+    let mut names = status.active_wallpapers.keys().collect::<Vec<_>>();
+    names.sort();
+    let outputs = names
+        .into_iter()
+        .map(|name| {
+            let playback = status.playback.get(name).cloned().unwrap_or_default();
+            let volume = overrides
+                .get(name)
+                .filter(|overrides| overrides.mute == Some(false))
+                .and_then(|overrides| overrides.volume)
+                .unwrap_or(0.0);
+            OutputStatus {
+                name: name.clone(),
+                wallpaper: status.active_wallpapers[name]
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().into_owned()),
+                paused: playback.paused,
+                volume,
+                title: playback.media.title,
+                duration: playback.media.duration.map(|length| length.as_secs_f64()),
+                hwdec: playback.media.hwdec,
+                fps: playback.frame_rate.map(|rate| rate.fps),
+                skip_rate: playback.frame_rate.map(|rate| rate.skip_rate),
+            }
+        })
+        .collect();
+
+    IpcResponse::Status {
+        running: status.running,
+        version,
+        outputs,
+        on_battery: status.on_battery,
+        power_paused: status.power_paused,
+        deep_sleep: status.deep_sleep,
+        render_backend: Some(status.render_backend.display_name().to_string()),
+    }
+}
+
+/// Render and decode counters of each output, by name, with the app's CPU
+/// and memory use
+fn stats_response(
+    outputs: &HashMap<String, lwe_engine::OutputStats>,
+    process: ProcessUsage,
+) -> IpcResponse {
+    let mut names = outputs.keys().collect::<Vec<_>>();
+    names.sort();
+    IpcResponse::Stats {
+        outputs: names
+            .into_iter()
+            .map(|name| {
+                let stats = &outputs[name];
+                OutputStats {
+                    output: name.clone(),
+                    render_ms: stats
+                        .render_time
+                        .map(|render| render.as_secs_f64() * 1000.0),
+                    fps: stats.frame_rate.map(|rate| rate.fps),
+                    skip_rate: stats.frame_rate.map(|rate| rate.skip_rate),
+                    dropped_frames: stats.dropped_frames,
+                    hwdec: stats.hwdec.clone(),
+                }
+            })
+            .collect(),
+        process: ProcessStats {
+            cpu_percent: process.cpu_percent,
+            rss_bytes: process.rss_bytes,
+        },
+    }
+}
+
+/// One of the matches, chosen by `seed`
+pub(crate) fn pick_random(items: &[LibraryItem], seed: u64) -> Option<&LibraryItem> {
+    if items.is_empty() {
+        return None;
+    }
+    items.get((seed % items.len() as u64) as usize)
+}
+
+fn apply_random(filter: &LibraryFilter, output: Option<&str>) -> Result<String, String> {
+    let database = LibraryDatabase::open(LibraryDatabase::default_path())
+        .map_err(|error| format!("Unable to open the library database: {error}"))?;
+    let (items, _) = database
+        .query_library(filter)
+        .map_err(|error| format!("Library query failed: {error:#}"))?;
+    let item = pick_random(&items, time_seed())
+        .ok_or_else(|| "No library wallpaper matches the filter".to_string())?;
+
+    let monitor_ids = match output {
+        Some(output) => vec![output.to_string()],
+        None => match MonitorService::list_monitors() {
+            MonitorDiscoveryResult::Known(monitors) => monitors
+                .into_iter()
+                .map(|monitor| monitor.id)
+                .collect::<Vec<_>>(),
+            MonitorDiscoveryResult::Unavailable { reason } => return Err(reason),
+        },
+    };
+    if monitor_ids.is_empty() {
+        return Err("No monitors are connected".to_string());
+    }
+
+    for monitor_id in &monitor_ids {
+        DesktopService::apply_media_file(monitor_id, Path::new(&item.path), false)
+            .map_err(|reason| format!("Could not show {} on {monitor_id}: {reason}", item.name))?;
+    }
+    Ok(format!(
+        "Showing {} on {}",
+        item.name,
+        monitor_ids.join(", ")
+    ))
+}
+
+fn apply_collection(name: &str, output: Option<&str>) -> Result<String, String> {
+    let collection = CollectionService::for_user_path().find_by_name(name)?;
+    let monitor_ids = CollectionService::apply(collection.id, output)?;
+    Ok(format!(
+        "Rotating {} on {}",
+        collection.name,
+        monitor_ids.join(", ")
+    ))
+}
+
+/// Change the given color values of an output and keep the others
+fn set_color(output: &str, changes: [Option<f32>; 4]) -> Result<String, String> {
+    let color = merge_color(DesktopService::monitor_color(output)?, changes)?;
+    DesktopService::set_monitor_color(output, color)?;
+    Ok(format!(
+        "{output}: brightness {}%, contrast {}%, gamma {}%, saturation {}%",
+        color.brightness, color.contrast, color.gamma, color.saturation
+    ))
+}
+
+/// Scale factors from a request over saved percentages, 0.9 -> 90
+fn merge_color(
+    current: DesktopMonitorColor,
+    [brightness, contrast, gamma, saturation]: [Option<f32>; 4],
+) -> Result<DesktopMonitorColor, String> {
+    let percent = |name: &str, value: Option<f32>, current: u16| match value {
+        None => Ok(current),
+        Some(value) if (0.0..=ColorAdjust::MAX).contains(&value) => {
+            Ok((value * 100.0).round() as u16)
+        }
+        Some(value) => Err(format!("{name} is 0 to {}, not {value}", ColorAdjust::MAX)),
+    };
+    Ok(DesktopMonitorColor {
+        brightness: percent("brightness", brightness, current.brightness)?,
+        contrast: percent("contrast", contrast, current.contrast)?,
+        gamma: percent("gamma", gamma, current.gamma)?,
+        saturation: percent("saturation", saturation, current.saturation)?,
+    })
+}
+
+pub struct ControlService;
+
+impl ControlService {
+    /// Let the `lwe` command line drive the running app over the session bus
+    pub fn start() {
+        let spawned = thread::Builder::new()
+            .name("lwe-control".to_string())
+            .spawn(|| match Self::serve() {
+                // The object server answers on the connection's own thread
+                Ok(_connection) => loop {
+                    thread::park();
+                },
+                Err(reason) => eprintln!("app control unavailable: {reason}"),
+            });
+
+        if let Err(reason) = spawned {
+            eprintln!("failed to start app control: {reason}");
+        }
+    }
+
+    fn serve() -> Result<Connection, String> {
+        connection::Builder::session()
+            .and_then(|builder| builder.name(CONTROL_BUS_NAME))
+            .and_then(|builder| builder.serve_at(CONTROL_OBJECT_PATH, ControlInterface))
+            .and_then(|builder| builder.build())
+            .map_err(|error| format!("Failed to register {CONTROL_BUS_NAME}: {error}"))
+    }
+
+    /// Send one request to the running app and wait for its response
+    pub fn send(request: &IpcRequest) -> Result<IpcResponse, String> {
+        let id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        let request = serde_json::to_string(&IpcEnvelope::request(id, request))
+            .map_err(|error| format!("Failed to encode the request: {error}"))?;
+        let connection = Connection::session()
+            .map_err(|error| format!("Failed to connect to the session bus: {error}"))?;
+        let reply = connection
+            .call_method(
+                Some(CONTROL_BUS_NAME),
+                CONTROL_OBJECT_PATH,
+                Some(CONTROL_INTERFACE),
+                "Request",
+                &request,
+            )
+            .map_err(|error| match error {
+                zbus::Error::MethodError(name, _, _)
+                    if name.as_str() == "org.freedesktop.DBus.Error.ServiceUnknown" =>
+                {
+                    "LWE is not running".to_string()
+                }
+                error => format!("{CONTROL_BUS_NAME} did not answer: {error}"),
+            })?;
+        let response: String = reply
+            .body()
+            .deserialize()
+            .map_err(|error| format!("{CONTROL_BUS_NAME} sent an unreadable reply: {error}"))?;
+        let response: IpcEnvelope<IpcResponse> = serde_json::from_str(&response)
+            .map_err(|error| format!("{CONTROL_BUS_NAME} sent an unreadable reply: {error}"))?;
+        // Apps from before protocol 3 answer without an id
+        if response.id.is_some_and(|reply_to| reply_to != id) {
+            return Err(format!(
+                "{CONTROL_BUS_NAME} answered a different request than #{id}"
+            ));
+        }
+        Ok(response.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lwe_engine::{FrameRateSample, MediaInfo, OutputPlayback, RenderBackend};
+
+    fn item(id: &str) -> LibraryItem {
+        LibraryItem {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: format!("/walls/{id}.mp4"),
+            wallpaper_type: "video".to_string(),
+            tags: Vec::new(),
+            favorite: false,
+            rating: 0,
+        }
+    }
+
+    #[test]
+    fn random_pick_covers_every_match() {
+        let items = [item("rain"), item("forest"), item("city")];
+
+        assert_eq!(pick_random(&[], 7), None);
+        let mut picked = (0..3)
+            .filter_map(|seed| pick_random(&items, seed))
+            .map(|item| item.id.as_str())
+            .collect::<Vec<_>>();
+        picked.sort();
+        assert_eq!(picked, ["city", "forest", "rain"]);
+    }
+
+    #[test]
+    fn color_requests_change_only_the_given_values() {
+        let current = DesktopMonitorColor {
+            gamma: 80,
+            ..DesktopMonitorColor::default()
+        };
+
+        assert_eq!(
+            merge_color(current, [Some(0.9), None, None, Some(1.25)]),
+            Ok(DesktopMonitorColor {
+                brightness: 90,
+                contrast: 100,
+                gamma: 80,
+                saturation: 125,
+            })
+        );
+        assert_eq!(
+            merge_color(current, [None, Some(2.5), None, None]),
+            Err("contrast is 0 to 2, not 2.5".to_string())
+        );
+    }
+
+    #[test]
+    fn control_answers_pings_and_refuses_other_requests() {
+        assert_eq!(
+            handle_request(IpcRequest::Ping),
+            IpcResponse::Pong {
+                protocol: PROTOCOL_VERSION
+            }
+        );
+        assert!(matches!(
+            handle_request(IpcRequest::Unknown),
+            IpcResponse::Error { .. }
+        ));
+        assert!(matches!(
+            handle_request(IpcRequest::Quit),
+            IpcResponse::Error { .. }
+        ));
+    }
+
+    #[test]
+    fn control_status_reports_each_output_with_its_frame_stats() {
+        let mut status = EngineStatus {
+            running: true,
+            on_battery: true,
+            render_backend: RenderBackend::OpenGL,
+            ..Default::default()
+        };
+        status
+            .active_wallpapers
+            .insert("DP-1".to_string(), Some("/walls/rain.mp4".into()));
+        status.playback.insert(
+            "DP-1".to_string(),
+            OutputPlayback {
+                media: MediaInfo {
+                    title: Some("Rain".to_string()),
+                    duration: Some(Duration::from_secs(30)),
+                    hwdec: Some("vaapi".to_string()),
+                },
+                frame_rate: Some(FrameRateSample {
+                    fps: 60.0,
+                    skip_rate: 0.25,
+                }),
+                ..Default::default()
+            },
+        );
+        let overrides = HashMap::from([(
+            "DP-1".to_string(),
+            OutputVideoOverrides {
+                volume: Some(0.4),
+                mute: Some(false),
+                ..Default::default()
+            },
+        )]);
+
+        let IpcResponse::Status {
+            running,
+            outputs,
+            on_battery,
+            render_backend,
+            ..
+        } = status_response(Some(&status), &overrides)
+        else {
+            panic!("expected a status response");
+        };
+        assert!(running && on_battery);
+        assert_eq!(render_backend.as_deref(), Some("OpenGL"));
+        assert_eq!(
+            outputs,
+            vec![OutputStatus {
+                name: "DP-1".to_string(),
+                wallpaper: Some("/walls/rain.mp4".to_string()),
+                paused: false,
+                volume: 0.4,
+                title: Some("Rain".to_string()),
+                duration: Some(30.0),
+                hwdec: Some("vaapi".to_string()),
+                fps: Some(60.0),
+                skip_rate: Some(0.25),
+            }]
+        );
+
+        assert!(matches!(
+            status_response(None, &overrides),
+            IpcResponse::Status { running: false, .. }
+        ));
+    }
+
+    #[test]
+    fn control_stats_list_outputs_by_name_with_process_usage() {
+        let stats = HashMap::from([
+            (
+                "HDMI-A-1".to_string(),
+                lwe_engine::OutputStats {
+                    hwdec: Some("no".to_string()),
+                    dropped_frames: 3,
+                    ..Default::default()
+                },
+            ),
+            (
+                "DP-1".to_string(),
+                lwe_engine::OutputStats {
+                    render_time: Some(Duration::from_micros(1500)),
+                    frame_rate: Some(FrameRateSample {
+                        fps: 30.0,
+                        skip_rate: 0.1,
+                    }),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let usage = ProcessUsage {
+            cpu_percent: Some(12.5),
+            rss_bytes: Some(64 << 20),
+        };
+
+        let IpcResponse::Stats { outputs, process } = stats_response(&stats, usage) else {
+            panic!("expected a stats response");
+        };
+        assert_eq!(
+            outputs
+                .iter()
+                .map(|stats| stats.output.as_str())
+                .collect::<Vec<_>>(),
+            ["DP-1", "HDMI-A-1"]
+        );
+        assert_eq!(outputs[0].render_ms, Some(1.5));
+        assert_eq!(outputs[0].fps, Some(30.0));
+        assert_eq!(outputs[1].render_ms, None);
+        assert_eq!(outputs[1].dropped_frames, 3);
+        assert_eq!(process.cpu_percent, Some(12.5));
+        assert_eq!(process.rss_bytes, Some(64 << 20));
+    }
+
+    #[test]
+    fn control_replies_carry_the_request_id() {
+        let reply = ControlInterface.request(r#"{"type":"ping","id":5}"#);
+        let reply: IpcEnvelope<IpcResponse> = serde_json::from_str(&reply).unwrap();
+
+        assert_eq!(reply.id, Some(5));
+        assert!(reply.elapsed_ms.is_some());
+        assert_eq!(
+            reply.message,
+            IpcResponse::Pong {
+                protocol: PROTOCOL_VERSION
+            }
+        );
+    }
+}
@@ -181,7 +181,7 @@ fn session_state_path_from_env(
     }
 }
 
-fn session_state_path() -> Result<PathBuf, String> {
+pub(crate) fn session_state_path() -> Result<PathBuf, String> {
     session_state_path_from_env(
         std::env::var_os("XDG_CONFIG_HOME")
             .filter(|value| !value.is_empty())
@@ -1,30 +1,57 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{Receiver, RecvTimeoutError};
 use std::sync::{Mutex, OnceLock};
+use std::thread;
 use std::time::{Duration, Instant};
 
-use lwe_engine::{spawn_engine, EngineCommand, EngineConfig, EngineEvent, EngineHandle};
-use lwe_library::{WeProject, WorkshopProjectType};
+use lwe_core::{
+    BatteryProfile, ColorAdjust, Palette, Policy, PowerConfig, SurfaceLayer, SurfaceMargins,
+};
+use lwe_engine::{
+    is_audio_option, is_live_source, spawn_engine, CompositorKind, EngineCommand, EngineConfig,
+    EngineEvent, EngineHandle, EngineMessage, EngineStatus, EventChannelStats, Fallback,
+    LayoutMode, LetterboxFill, OutputInfo, OutputStats, OutputVideoOverrides, RequestId,
+    SchedulingConfig, TransitionKind, Transport, VideoConfig, WorkaroundOverrides,
+};
+use lwe_library::{ItemMarks, WeProject, WorkshopProjectType};
 
 use crate::results::desktop::{
-    DesktopApplyResult, DesktopPageResult, DesktopResolvedMonitorAssignment,
+    DesktopApplyResult, DesktopBulkApplyResult, DesktopMonitorApplied, DesktopMonitorAudio,
+    DesktopMonitorColor, DesktopPageResult, DesktopResolvedMonitorAssignment,
+    DesktopWallpaperFailure,
 };
 use crate::results::desktop_persistence::{DesktopPersistenceLoad, DesktopPersistenceWrite};
 use crate::results::library::LibraryProjection;
 use crate::results::monitor_discovery::MonitorDiscoveryResult;
+use crate::results::settings_persistence::{
+    PersistedBatteryProfile, PersistedCompositorWorkarounds, PersistedGpu, PersistedOutputSettings,
+    PersistedPlayback, PersistedPresentation, PersistedProfile, PersistedScheduling,
+    SettingsPersistenceLoad, SettingsPersistenceWrite,
+};
 use crate::services::desktop_persistence_service::DesktopPersistenceService;
+use crate::services::hook_service::{HookEvent, HookService};
+use crate::services::library_marks_service::LibraryMarksService;
 use crate::services::library_service::LibraryService;
 use crate::services::monitor_service::MonitorService;
+use crate::services::palette_service::PaletteService;
+use crate::services::playlist_service::PlaylistService;
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+use crate::services::wallpaper_properties_service::WallpaperPropertiesService;
+use crate::services::web_wallpaper_service::{WebWallpaperService, WebWallpaperTarget};
 
 pub(crate) const LIBRARY_RESOLUTION_ISSUE_PREFIX: &str =
     "Unable to resolve desktop items against the current Library snapshot:";
 const REAL_APPLY_BACKEND: &str = "lwe_engine_wayland";
 const REAL_APPLY_BACKEND_TIMEOUT: Duration = Duration::from_secs(5);
+/// Waiting for the next frame plus encoding the image
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often engine events are read while no request waits on the engine
+const BACKEND_EVENT_POLL: Duration = Duration::from_secs(1);
 
 struct RunningDesktopApplyBackend {
     handle: EngineHandle,
-    events: Receiver<EngineEvent>,
+    events: Receiver<EngineMessage>,
 }
 
 static DESKTOP_APPLY_BACKEND: OnceLock<Mutex<Option<RunningDesktopApplyBackend>>> = OnceLock::new();
@@ -33,6 +60,36 @@ fn desktop_apply_backend_slot() -> &'static Mutex<Option<RunningDesktopApplyBack
     DESKTOP_APPLY_BACKEND.get_or_init(|| Mutex::new(None))
 }
 
+/// Called when the engine gives up on a wallpaper that keeps failing
+pub type WallpaperFailureSink = Box<dyn Fn(DesktopWallpaperFailure) + Send + Sync>;
+
+static WALLPAPER_FAILURE_SINK: OnceLock<WallpaperFailureSink> = OnceLock::new();
+
+/// When the wallpaper on an output finished applying
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct AppliedWallpaper {
+    /// When the engine drew its first frame
+    pub(crate) at: Instant,
+    /// From sending the apply to that first frame
+    pub(crate) latency: Duration,
+}
+
+static APPLIED_WALLPAPERS: OnceLock<Mutex<HashMap<String, AppliedWallpaper>>> = OnceLock::new();
+
+/// Applied wallpapers keyed by backend output id
+fn applied_wallpapers_slot() -> &'static Mutex<HashMap<String, AppliedWallpaper>> {
+    APPLIED_WALLPAPERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Assignments replaced by the last apply-to-all, keyed by monitor id
+type DesktopUndoSnapshot = BTreeMap<String, Option<String>>;
+
+static DESKTOP_APPLY_UNDO: OnceLock<Mutex<Option<DesktopUndoSnapshot>>> = OnceLock::new();
+
+fn desktop_apply_undo_slot() -> &'static Mutex<Option<DesktopUndoSnapshot>> {
+    DESKTOP_APPLY_UNDO.get_or_init(|| Mutex::new(None))
+}
+
 #[cfg(test)]
 pub(crate) fn real_desktop_flow_test_guard() -> std::sync::MutexGuard<'static, ()> {
     static REAL_DESKTOP_FLOW_TEST_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
@@ -74,11 +131,26 @@ impl DesktopService {
             Err(reason) => DesktopPersistenceLoad::Unavailable { reason },
         };
 
-        Ok(Self::build_page_result(
-            monitors,
-            assignments,
-            library_projection,
-        ))
+        let mut result = Self::build_page_result(monitors, assignments, library_projection);
+        let outputs = Self::output_settings();
+        result.monitor_audio = monitor_audio_from(&result.monitors, &outputs);
+        result.monitor_color = monitor_color_from(&result.monitors, &outputs);
+        result.monitor_applied = result
+            .monitors
+            .iter()
+            .filter_map(|monitor| {
+                let applied = Self::applied_wallpaper(&monitor.backend_output_id)?;
+                Some((
+                    monitor.id.clone(),
+                    DesktopMonitorApplied {
+                        ago: applied.at.elapsed(),
+                        latency: applied.latency,
+                    },
+                ))
+            })
+            .collect();
+
+        Ok(result)
     }
 
     fn library_item_titles(projection: LibraryProjection) -> BTreeMap<String, String> {
@@ -259,6 +331,9 @@ impl DesktopService {
             persistence_issue,
             assignments_available,
             stale,
+            monitor_audio: BTreeMap::new(),
+            monitor_color: BTreeMap::new(),
+            monitor_applied: BTreeMap::new(),
         }
     }
 
@@ -314,6 +389,7 @@ impl DesktopService {
                 if let Err(reason) = Self::apply_with_real_backend(&monitor, item_id) {
                     return Ok(DesktopApplyResult::BackendUnavailable { reason });
                 }
+                LibraryMarksService::record_use(item_id);
 
                 let persistence = match DesktopPersistenceService::for_user_path() {
                     Ok(service) => service,
@@ -345,58 +421,390 @@ impl DesktopService {
         }
     }
 
+    pub fn apply_to_all_monitors(item_id: &str) -> Result<DesktopBulkApplyResult, String> {
+        let monitors = match MonitorService::list_monitors() {
+            MonitorDiscoveryResult::Known(monitors) => monitors,
+            MonitorDiscoveryResult::Unavailable { reason } => {
+                return Ok(DesktopBulkApplyResult {
+                    results: vec![DesktopApplyResult::MonitorDiscoveryUnavailable { reason }],
+                });
+            }
+        };
+
+        let previous = match DesktopPersistenceService::load_state() {
+            DesktopPersistenceLoad::Loaded(assignments) => assignments,
+            DesktopPersistenceLoad::Unavailable { reason } => {
+                return Ok(DesktopBulkApplyResult {
+                    results: vec![DesktopApplyResult::PersistenceUnavailable { reason }],
+                });
+            }
+        };
+
+        let mut undo = DesktopUndoSnapshot::new();
+        let mut results = Vec::with_capacity(monitors.len());
+        for monitor in &monitors {
+            let result = Self::apply_to_monitor(&monitor.id, item_id)?;
+            if matches!(result, DesktopApplyResult::AppliedWithBackend { .. }) {
+                undo.insert(monitor.id.clone(), previous.get(&monitor.id).cloned());
+            }
+            results.push(result);
+        }
+
+        *desktop_apply_undo_slot()
+            .lock()
+            .map_err(|_| "Desktop undo lock was poisoned".to_string())? =
+            (!undo.is_empty()).then_some(undo);
+
+        Ok(DesktopBulkApplyResult { results })
+    }
+
+    /// Put back the assignments replaced by the last apply-to-all
+    pub fn undo_last_apply() -> Result<DesktopBulkApplyResult, String> {
+        let snapshot = desktop_apply_undo_slot()
+            .lock()
+            .map_err(|_| "Desktop undo lock was poisoned".to_string())?
+            .take()
+            .ok_or_else(|| "There is no desktop apply to undo".to_string())?;
+
+        let mut results = Vec::with_capacity(snapshot.len());
+        for (monitor_id, item_id) in undo_plan(snapshot) {
+            results.push(match item_id {
+                Some(item_id) => Self::apply_to_monitor(&monitor_id, &item_id)?,
+                None => Self::clear_monitor(&monitor_id)?,
+            });
+        }
+
+        Ok(DesktopBulkApplyResult { results })
+    }
+
+    /// Switch every monitor to a saved profile. Its items are all resolved
+    /// before anything changes, so a profile with a missing item leaves the
+    /// desktop as it was. Connected monitors the profile leaves empty are
+    /// cleared, and its monitors that are not connected keep their assignment
+    /// for when they come back.
+    pub(crate) fn apply_profile(
+        profile: &PersistedProfile,
+    ) -> Result<DesktopBulkApplyResult, String> {
+        let monitors = match MonitorService::list_monitors() {
+            MonitorDiscoveryResult::Known(monitors) => monitors,
+            MonitorDiscoveryResult::Unavailable { reason } => return Err(reason),
+        };
+        for item_id in profile.assignments.values() {
+            Self::resolve_real_apply_source(item_id)?;
+        }
+        let persistence = DesktopPersistenceService::for_user_path()?;
+        let current = match persistence.load_state() {
+            DesktopPersistenceLoad::Loaded(assignments) => assignments,
+            DesktopPersistenceLoad::Unavailable { reason } => return Err(reason),
+        };
+
+        // Layouts are read when the engine starts, so other output settings restart it
+        if Self::output_settings() != profile.outputs {
+            let settings_persistence = SettingsPersistenceService::for_user_path()?;
+            let mut settings = match settings_persistence.load_settings() {
+                SettingsPersistenceLoad::Loaded(settings) => settings,
+                SettingsPersistenceLoad::Unavailable { reason } => return Err(reason),
+            };
+            settings.outputs = profile.outputs.clone();
+            if let SettingsPersistenceWrite::Unavailable { reason } =
+                settings_persistence.save_settings(&settings)
+            {
+                return Err(reason);
+            }
+            Self::stop_running_backend()?;
+        }
+
+        let connected = monitors
+            .iter()
+            .map(|monitor| monitor.id.as_str())
+            .collect::<BTreeSet<_>>();
+        let mut results = Vec::with_capacity(profile.assignments.len());
+        for (monitor_id, item_id) in profile_plan(&connected, &current, &profile.assignments) {
+            results.push(match item_id {
+                Some(item_id) => Self::apply_to_monitor(&monitor_id, &item_id)?,
+                None => Self::clear_monitor(&monitor_id)?,
+            });
+        }
+        for (monitor_id, item_id) in &profile.assignments {
+            if connected.contains(monitor_id.as_str()) {
+                continue;
+            }
+            if let DesktopPersistenceWrite::Unavailable { reason } =
+                persistence.save_assignment(monitor_id, item_id)
+            {
+                results.push(DesktopApplyResult::PersistenceUnavailable { reason });
+            }
+        }
+
+        // Undoing an earlier apply-to-all now would mix the two setups
+        *desktop_apply_undo_slot()
+            .lock()
+            .map_err(|_| "Desktop undo lock was poisoned".to_string())? = None;
+
+        Ok(DesktopBulkApplyResult { results })
+    }
+
     fn apply_with_real_backend(
         monitor: &crate::services::monitor_service::MonitorDescriptor,
         item_id: &str,
     ) -> Result<(), String> {
-        let path = Self::resolve_real_apply_path(item_id)?;
+        let (project_type, path) = Self::resolve_real_apply_source(item_id)?;
+        let mut backend_guard = Self::ensure_running_apply_backend()?;
+        let backend = backend_guard
+            .as_mut()
+            .ok_or_else(|| "Desktop apply backend failed to initialize".to_string())?;
+
+        let output = Self::wait_for_output(backend, &monitor.backend_output_id)?;
+        if project_type == WorkshopProjectType::Web {
+            // The engine's background surface would sit over the page
+            backend
+                .handle
+                .send(EngineCommand::ClearWallpaper {
+                    output: Some(monitor.backend_output_id.clone()),
+                })
+                .map_err(|error| format!("Failed to send real desktop clear command: {error}"))?;
+            if let Ok(mut applied) = applied_wallpapers_slot().lock() {
+                applied.remove(&monitor.backend_output_id);
+            }
+            return WebWallpaperService::show(
+                &WebWallpaperTarget {
+                    output: output.name,
+                    x: output.position.0,
+                    y: output.position.1,
+                    width: output.width,
+                    height: output.height,
+                },
+                &path,
+                &WallpaperPropertiesService::page_properties(item_id),
+            );
+        }
+
+        Self::apply_path(
+            backend,
+            &monitor.backend_output_id,
+            &path,
+            &LibraryMarksService::marks_for(item_id),
+            Self::presentation_active(&monitor.id, item_id),
+        )
+    }
+
+    /// Show a media file that is not a Library item, such as cached signage
+    /// media or a random pick from the library database; signage keeps the
+    /// monitor awake while it is up
+    pub(crate) fn apply_media_file(
+        monitor_id: &str,
+        path: &Path,
+        keep_awake: bool,
+    ) -> Result<(), String> {
+        let monitor = match MonitorService::resolve_specific_monitor(
+            &MonitorService::list_monitors(),
+            monitor_id,
+        ) {
+            MonitorDiscoveryResult::Known(monitors) => monitors
+                .into_iter()
+                .next()
+                .ok_or_else(|| format!("Monitor {monitor_id} not found"))?,
+            MonitorDiscoveryResult::Unavailable { reason } => return Err(reason),
+        };
         let mut backend_guard = Self::ensure_running_apply_backend()?;
         let backend = backend_guard
             .as_mut()
             .ok_or_else(|| "Desktop apply backend failed to initialize".to_string())?;
 
         Self::wait_for_output(backend, &monitor.backend_output_id)?;
+        Self::apply_path(
+            backend,
+            &monitor.backend_output_id,
+            path,
+            &ItemMarks::default(),
+            keep_awake,
+        )
+    }
+
+    /// Open a playlist's next video on a monitor ahead of its switch; nothing
+    /// is started when no wallpaper engine is running
+    pub(crate) fn preload_on_monitor(monitor_id: &str, item_id: &str) -> Result<(), String> {
+        let (project_type, path) = Self::resolve_real_apply_source(item_id)?;
+        if project_type != WorkshopProjectType::Video {
+            return Ok(());
+        }
+        let monitor = match MonitorService::resolve_specific_monitor(
+            &MonitorService::list_monitors(),
+            monitor_id,
+        ) {
+            MonitorDiscoveryResult::Known(monitors) => monitors
+                .into_iter()
+                .next()
+                .ok_or_else(|| format!("Monitor {monitor_id} not found"))?,
+            MonitorDiscoveryResult::Unavailable { reason } => return Err(reason),
+        };
+
+        let backend = desktop_apply_backend_slot()
+            .lock()
+            .map_err(|_| "Desktop apply backend lock was poisoned".to_string())?;
+        let Some(backend) = backend
+            .as_ref()
+            .filter(|backend| backend.handle.is_running())
+        else {
+            return Ok(());
+        };
+
         backend
+            .handle
+            .send(EngineCommand::PreloadWallpaper {
+                output: monitor.backend_output_id,
+                path,
+            })
+            .map(|_| ())
+            .map_err(|error| format!("Failed to send preload command: {error}"))
+    }
+
+    /// `marks` of a Library item carry the shader and LUT the user attached
+    /// to it; the LUT replaces the global `[playback]` one
+    fn apply_path(
+        backend: &mut RunningDesktopApplyBackend,
+        output_id: &str,
+        path: &Path,
+        marks: &ItemMarks,
+        keep_awake: bool,
+    ) -> Result<(), String> {
+        WebWallpaperService::close(output_id);
+        let sent_at = Instant::now();
+        let request = backend
             .handle
             .send(EngineCommand::ApplyWallpaper {
-                path: path.clone(),
-                output: Some(monitor.backend_output_id.clone()),
+                path: path.to_path_buf(),
+                output: Some(output_id.to_string()),
+                shader: marks.shader.clone(),
+                lut: marks.lut.clone(),
             })
             .map_err(|error| format!("Failed to send real desktop apply command: {error}"))?;
-        Self::wait_for_apply(backend, &monitor.backend_output_id, &path)
+        let applied_at = Self::wait_for_apply(backend, request, output_id, path)?;
+        if let Ok(mut applied) = applied_wallpapers_slot().lock() {
+            applied.insert(
+                output_id.to_string(),
+                AppliedWallpaper {
+                    at: applied_at,
+                    latency: applied_at.saturating_duration_since(sent_at),
+                },
+            );
+        }
+        if PaletteService::has_templates() {
+            Self::write_palette_templates(backend, output_id);
+        }
+        HookService::run(HookEvent::Applied {
+            output: output_id,
+            path,
+        });
+
+        backend
+            .handle
+            .send(EngineCommand::SetIdleInhibit {
+                output: output_id.to_string(),
+                inhibit: keep_awake,
+            })
+            .map(|_| ())
+            .map_err(|error| format!("Failed to send idle inhibit command: {error}"))
     }
 
-    fn resolve_real_apply_path(item_id: &str) -> Result<PathBuf, String> {
+    /// When the wallpaper on a backend output was applied and how long it took;
+    /// None for web wallpapers and outputs without one
+    pub(crate) fn applied_wallpaper(output_id: &str) -> Option<AppliedWallpaper> {
+        applied_wallpapers_slot()
+            .lock()
+            .ok()
+            .and_then(|applied| applied.get(output_id).copied())
+    }
+
+    /// Theme the user's palette templates from the wallpaper that was just applied
+    fn write_palette_templates(backend: &mut RunningDesktopApplyBackend, output_id: &str) {
+        let palette = Self::request_palettes(backend, Some(output_id)).map(|palettes| {
+            palettes
+                .into_iter()
+                .find_map(|(output, palette)| (output == output_id).then_some(palette))
+        });
+
+        match palette {
+            Ok(Some(palette)) => {
+                if let Err(reason) = PaletteService::write_templates(&palette) {
+                    eprintln!("palette templates not written: {reason}");
+                }
+            }
+            Ok(None) => eprintln!("no palette available yet for {output_id}"),
+            Err(reason) => eprintln!("palette templates not written: {reason}"),
+        }
+    }
+
+    /// Whether the `[presentation]` settings ask to keep this monitor awake
+    fn presentation_active(monitor_id: &str, item_id: &str) -> bool {
+        let loaded = SettingsPersistenceService::for_user_path()
+            .map(|service| service.load_settings())
+            .unwrap_or_else(|reason| SettingsPersistenceLoad::Unavailable { reason });
+        let presentation = match loaded {
+            SettingsPersistenceLoad::Loaded(settings) => settings.presentation,
+            SettingsPersistenceLoad::Unavailable { reason } => {
+                eprintln!("presentation settings unavailable: {reason}");
+                return false;
+            }
+        };
+
+        let playlist_id = if presentation.playlists.is_empty() {
+            None
+        } else {
+            PlaylistService::for_user_path()
+                .load_assignments()
+                .unwrap_or_else(|reason| {
+                    eprintln!("presentation playlists unavailable: {reason}");
+                    Vec::new()
+                })
+                .into_iter()
+                .find(|(assigned, _)| assigned == monitor_id)
+                .map(|(_, playlist)| playlist.id)
+        };
+
+        presentation_matches(&presentation, item_id, playlist_id)
+    }
+
+    pub(crate) fn resolve_real_apply_source(
+        item_id: &str,
+    ) -> Result<(WorkshopProjectType, PathBuf), String> {
         let entry = LibraryService::inspect_item(item_id).map_err(|reason| {
             format!("Library item {item_id} is unavailable for real desktop apply: {reason}")
         })?;
 
-        if entry.entry.project_type != WorkshopProjectType::Video {
+        if !matches!(
+            entry.entry.project_type,
+            WorkshopProjectType::Video | WorkshopProjectType::Web
+        ) {
             return Err(format!(
-                "Library item {item_id} uses {:?}, but the current real desktop apply path only supports synced video items on this machine",
+                "Library item {item_id} uses {:?}, but the current real desktop apply path only supports synced video and web items on this machine",
                 entry.entry.project_type
             ));
         }
 
-        let project = WeProject::load(&entry.entry.project_dir).map_err(|error| {
-            format!(
-                "Library item {item_id} could not load project metadata for real desktop apply: {error}"
-            )
-        })?;
-        let path = project.main_file(&entry.entry.project_dir).ok_or_else(|| {
-            format!(
-                "Library item {item_id} does not expose a primary video asset for real desktop apply"
-            )
-        })?;
+        let path = if LibraryService::is_local(&entry.entry) {
+            entry.entry.project_dir.clone()
+        } else {
+            let project = WeProject::load(&entry.entry.project_dir).map_err(|error| {
+                format!(
+                    "Library item {item_id} could not load project metadata for real desktop apply: {error}"
+                )
+            })?;
+            project.main_file(&entry.entry.project_dir).ok_or_else(|| {
+                format!(
+                    "Library item {item_id} does not expose a primary asset for real desktop apply"
+                )
+            })?
+        };
 
-        if !path.is_file() {
+        if !path.is_file() && !is_live_source(&path) {
             return Err(format!(
-                "Library item {item_id} points to missing video asset {}",
+                "Library item {item_id} points to missing asset {}",
                 path.display()
             ));
         }
 
-        Ok(path)
+        Ok((entry.entry.project_type, path))
     }
 
     fn ensure_running_apply_backend(
@@ -406,31 +814,528 @@ impl DesktopService {
             .map_err(|_| "Desktop apply backend lock was poisoned".to_string())?;
         let should_restart = backend
             .as_ref()
-            .map(|backend| !backend.handle.is_running())
-            .unwrap_or(true);
+            .map(|backend| !backend.handle.is_running())
+            .unwrap_or(true);
+
+        if should_restart {
+            *backend = Some(Self::start_apply_backend()?);
+        }
+
+        Ok(backend)
+    }
+
+    /// Query the running engine, without starting one if none is running
+    pub(crate) fn engine_status() -> Result<Option<EngineStatus>, String> {
+        let mut backend = desktop_apply_backend_slot()
+            .lock()
+            .map_err(|_| "Desktop apply backend lock was poisoned".to_string())?;
+        let Some(backend) = backend
+            .as_mut()
+            .filter(|backend| backend.handle.is_running())
+        else {
+            return Ok(None);
+        };
+
+        let request = backend
+            .handle
+            .send(EngineCommand::GetStatus)
+            .map_err(|error| {
+                format!("Failed to request status from {REAL_APPLY_BACKEND}: {error}")
+            })?;
+
+        let deadline = Instant::now() + REAL_APPLY_BACKEND_TIMEOUT;
+        loop {
+            match Self::recv_backend_reply(backend, request, deadline)? {
+                Some(EngineEvent::Status(status)) => return Ok(Some(status)),
+                Some(_) => {}
+                None => {
+                    return Err(format!(
+                        "Timed out waiting for {REAL_APPLY_BACKEND} to report its status"
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Render and decode counters of each output; None when no engine is running
+    pub(crate) fn engine_stats() -> Result<Option<HashMap<String, OutputStats>>, String> {
+        let mut backend = desktop_apply_backend_slot()
+            .lock()
+            .map_err(|_| "Desktop apply backend lock was poisoned".to_string())?;
+        let Some(backend) = backend
+            .as_mut()
+            .filter(|backend| backend.handle.is_running())
+        else {
+            return Ok(None);
+        };
+
+        let request = backend
+            .handle
+            .send(EngineCommand::GetStats)
+            .map_err(|error| {
+                format!("Failed to request stats from {REAL_APPLY_BACKEND}: {error}")
+            })?;
+
+        let deadline = Instant::now() + REAL_APPLY_BACKEND_TIMEOUT;
+        loop {
+            match Self::recv_backend_reply(backend, request, deadline)? {
+                Some(EngineEvent::Stats(stats)) => return Ok(Some(stats)),
+                Some(_) => {}
+                None => {
+                    return Err(format!(
+                        "Timed out waiting for {REAL_APPLY_BACKEND} to report its stats"
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Delivery counters of the running engine's event channel
+    pub(crate) fn event_channel_stats() -> Option<EventChannelStats> {
+        let backend = desktop_apply_backend_slot().lock().ok()?;
+        backend
+            .as_ref()
+            .filter(|backend| backend.handle.is_running())
+            .map(|backend| backend.handle.event_stats())
+    }
+
+    /// Colors of the wallpaper on each monitor, without starting an engine if none is running
+    pub(crate) fn palettes(monitor_id: Option<&str>) -> Result<Vec<(String, Palette)>, String> {
+        let mut backend = desktop_apply_backend_slot()
+            .lock()
+            .map_err(|_| "Desktop apply backend lock was poisoned".to_string())?;
+        match backend
+            .as_mut()
+            .filter(|backend| backend.handle.is_running())
+        {
+            Some(backend) => Self::request_palettes(backend, monitor_id),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn request_palettes(
+        backend: &mut RunningDesktopApplyBackend,
+        monitor_id: Option<&str>,
+    ) -> Result<Vec<(String, Palette)>, String> {
+        let request = backend
+            .handle
+            .send(EngineCommand::GetPalette {
+                output: monitor_id.map(str::to_string),
+            })
+            .map_err(|error| {
+                format!("Failed to request palettes from {REAL_APPLY_BACKEND}: {error}")
+            })?;
+
+        let deadline = Instant::now() + REAL_APPLY_BACKEND_TIMEOUT;
+        loop {
+            match Self::recv_backend_reply(backend, request, deadline)? {
+                Some(EngineEvent::Palettes(palettes)) => return Ok(palettes),
+                Some(_) => {}
+                None => {
+                    return Err(format!(
+                        "Timed out waiting for {REAL_APPLY_BACKEND} to report palettes"
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Save the frame a monitor shows to `path`; the format follows the extension
+    pub(crate) fn snapshot(monitor_id: &str, path: &Path) -> Result<(), String> {
+        let output_name = Self::backend_output_id(monitor_id)?;
+        let mut backend = desktop_apply_backend_slot()
+            .lock()
+            .map_err(|_| "Desktop apply backend lock was poisoned".to_string())?;
+        let Some(backend) = backend
+            .as_mut()
+            .filter(|backend| backend.handle.is_running())
+        else {
+            return Err("No wallpaper is playing".to_string());
+        };
+
+        let request = backend
+            .handle
+            .send(EngineCommand::Snapshot {
+                output: output_name.clone(),
+                path: path.to_path_buf(),
+            })
+            .map_err(|error| {
+                format!("Failed to request a snapshot from {REAL_APPLY_BACKEND}: {error}")
+            })?;
+
+        let deadline = Instant::now() + SNAPSHOT_TIMEOUT;
+        loop {
+            match Self::recv_backend_reply(backend, request, deadline)? {
+                Some(EngineEvent::SnapshotSaved { .. }) => return Ok(()),
+                Some(EngineEvent::Error(reason)) => return Err(reason),
+                Some(_) => {}
+                None => {
+                    return Err(format!("Timed out waiting for a snapshot of {output_name}"));
+                }
+            }
+        }
+    }
+
+    /// Set a raw mpv property on the video a monitor plays, without saving it
+    pub(crate) fn set_mpv_property(
+        monitor_id: &str,
+        property: &str,
+        value: &str,
+    ) -> Result<(), String> {
+        let output_name = Self::backend_output_id(monitor_id)?;
+        if is_audio_option(property) && !Policy::system().allow_audio {
+            return Err("Wallpaper audio is disabled by the system policy".to_string());
+        }
+        let mut backend = desktop_apply_backend_slot()
+            .lock()
+            .map_err(|_| "Desktop apply backend lock was poisoned".to_string())?;
+        let Some(backend) = backend
+            .as_mut()
+            .filter(|backend| backend.handle.is_running())
+        else {
+            return Err("No wallpaper is playing".to_string());
+        };
+
+        let request = backend
+            .handle
+            .send(EngineCommand::SetMpvProperty {
+                output: output_name.clone(),
+                property: property.to_string(),
+                value: value.to_string(),
+            })
+            .map_err(|error| {
+                format!("Failed to send an mpv property to {REAL_APPLY_BACKEND}: {error}")
+            })?;
+
+        let deadline = Instant::now() + REAL_APPLY_BACKEND_TIMEOUT;
+        loop {
+            match Self::recv_backend_reply(backend, request, deadline)? {
+                Some(EngineEvent::MpvPropertySet { .. }) => return Ok(()),
+                Some(EngineEvent::Error(reason)) => return Err(reason),
+                Some(_) => {}
+                None => {
+                    return Err(format!(
+                        "Timed out waiting for mpv on {output_name} to take {property}"
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Compositor workaround overrides from the `[compositor_workarounds]` settings table.
+    pub fn workaround_overrides() -> WorkaroundOverrides {
+        workaround_overrides_from(&Self::compositor_workaround_settings())
+    }
+
+    fn compositor_workaround_settings() -> PersistedCompositorWorkarounds {
+        let loaded = SettingsPersistenceService::for_user_path()
+            .map(|service| service.load_settings())
+            .unwrap_or_else(|reason| SettingsPersistenceLoad::Unavailable { reason });
+
+        match loaded {
+            SettingsPersistenceLoad::Loaded(settings) => settings.compositor_workarounds,
+            SettingsPersistenceLoad::Unavailable { reason } => {
+                eprintln!("compositor workaround overrides unavailable: {reason}");
+                PersistedCompositorWorkarounds::default()
+            }
+        }
+    }
+
+    /// Per-output layout, letterbox and audio overrides from the `[outputs]` settings table.
+    pub fn output_video_overrides() -> HashMap<String, OutputVideoOverrides> {
+        output_overrides_from(&Self::output_settings())
+    }
+
+    /// Nice level, policy and core pinning from the `[scheduling]` settings table.
+    fn scheduling_config() -> SchedulingConfig {
+        let loaded = SettingsPersistenceService::for_user_path()
+            .map(|service| service.load_settings())
+            .unwrap_or_else(|reason| SettingsPersistenceLoad::Unavailable { reason });
+
+        match loaded {
+            SettingsPersistenceLoad::Loaded(settings) => {
+                scheduling_config_from(&settings.scheduling)
+            }
+            SettingsPersistenceLoad::Unavailable { reason } => {
+                eprintln!("scheduling settings unavailable: {reason}");
+                SchedulingConfig::default()
+            }
+        }
+    }
+
+    /// Render node from the `[gpu]` settings table.
+    pub(crate) fn render_device() -> Option<PathBuf> {
+        Self::gpu_settings()
+            .render_device
+            .filter(|device| !device.trim().is_empty())
+            .map(PathBuf::from)
+    }
+
+    /// Whether `[gpu] debug` asks for a KHR_debug context
+    pub(crate) fn gpu_debug() -> bool {
+        Self::gpu_settings().debug
+    }
+
+    fn gpu_settings() -> PersistedGpu {
+        let loaded = SettingsPersistenceService::for_user_path()
+            .map(|service| service.load_settings())
+            .unwrap_or_else(|reason| SettingsPersistenceLoad::Unavailable { reason });
+
+        match loaded {
+            SettingsPersistenceLoad::Loaded(settings) => settings.gpu,
+            SettingsPersistenceLoad::Unavailable { reason } => {
+                eprintln!("gpu settings unavailable: {reason}");
+                PersistedGpu::default()
+            }
+        }
+    }
+
+    /// Fullscreen pause and audio focus settings from the `[playback]` settings table.
+    pub(crate) fn playback_settings() -> PersistedPlayback {
+        let loaded = SettingsPersistenceService::for_user_path()
+            .map(|service| service.load_settings())
+            .unwrap_or_else(|reason| SettingsPersistenceLoad::Unavailable { reason });
+
+        match loaded {
+            SettingsPersistenceLoad::Loaded(settings) => settings.playback,
+            SettingsPersistenceLoad::Unavailable { reason } => {
+                eprintln!("playback settings unavailable: {reason}");
+                PersistedPlayback::default()
+            }
+        }
+    }
+
+    fn output_settings() -> BTreeMap<String, PersistedOutputSettings> {
+        let loaded = SettingsPersistenceService::for_user_path()
+            .map(|service| service.load_settings())
+            .unwrap_or_else(|reason| SettingsPersistenceLoad::Unavailable { reason });
+
+        match loaded {
+            SettingsPersistenceLoad::Loaded(settings) => settings.outputs,
+            SettingsPersistenceLoad::Unavailable { reason } => {
+                eprintln!("output settings unavailable: {reason}");
+                BTreeMap::new()
+            }
+        }
+    }
+
+    /// Save a monitor's volume and mute state and hand them to the running engine
+    pub fn set_monitor_audio(monitor_id: &str, volume: u8, muted: bool) -> Result<(), String> {
+        let output_id = Self::backend_output_id(monitor_id)?;
+        let volume = volume.min(100);
+        if !muted && !Policy::system().allow_audio {
+            return Err("Wallpaper audio is disabled by the system policy".to_string());
+        }
+
+        let persistence = SettingsPersistenceService::for_user_path()?;
+        let mut settings = match persistence.load_settings() {
+            SettingsPersistenceLoad::Loaded(settings) => settings,
+            SettingsPersistenceLoad::Unavailable { reason } => return Err(reason),
+        };
+        let output = settings.outputs.entry(output_id.clone()).or_default();
+        output.volume = Some(volume);
+        output.muted = Some(muted);
+        if let SettingsPersistenceWrite::Unavailable { reason } =
+            persistence.save_settings(&settings)
+        {
+            return Err(reason);
+        }
+
+        Self::send_to_running_backend(EngineCommand::SetVolume {
+            output: output_id.clone(),
+            volume: f32::from(volume) / 100.0,
+        })?;
+        Self::send_to_running_backend(EngineCommand::SetMute {
+            output: output_id,
+            muted,
+        })
+    }
+
+    /// A monitor's saved color correction
+    pub(crate) fn monitor_color(monitor_id: &str) -> Result<DesktopMonitorColor, String> {
+        let output_id = Self::backend_output_id(monitor_id)?;
+        Ok(Self::output_settings()
+            .get(&output_id)
+            .map(desktop_color_from)
+            .unwrap_or_default())
+    }
+
+    /// Save a monitor's color correction and hand it to the running engine
+    pub fn set_monitor_color(monitor_id: &str, color: DesktopMonitorColor) -> Result<(), String> {
+        let output_id = Self::backend_output_id(monitor_id)?;
+        let adjust = color_adjust_from(color);
+        adjust.check()?;
+
+        let persistence = SettingsPersistenceService::for_user_path()?;
+        let mut settings = match persistence.load_settings() {
+            SettingsPersistenceLoad::Loaded(settings) => settings,
+            SettingsPersistenceLoad::Unavailable { reason } => return Err(reason),
+        };
+        let unchanged = |percent: u16| (percent != 100).then_some(percent);
+        let output = settings.outputs.entry(output_id.clone()).or_default();
+        output.brightness = unchanged(color.brightness);
+        output.contrast = unchanged(color.contrast);
+        output.gamma = unchanged(color.gamma);
+        output.saturation = unchanged(color.saturation);
+        if let SettingsPersistenceWrite::Unavailable { reason } =
+            persistence.save_settings(&settings)
+        {
+            return Err(reason);
+        }
+
+        Self::send_to_running_backend(EngineCommand::SetColor {
+            output: output_id,
+            color: adjust,
+        })
+    }
+
+    /// Seek, change speed, step a frame or loop part of the video on one
+    /// monitor (None = every monitor)
+    pub(crate) fn control_playback(
+        monitor_id: Option<&str>,
+        transport: Transport,
+    ) -> Result<(), String> {
+        let output = monitor_id.map(Self::backend_output_id).transpose()?;
+        let backend = desktop_apply_backend_slot()
+            .lock()
+            .map_err(|_| "Desktop apply backend lock was poisoned".to_string())?;
+        let Some(backend) = backend
+            .as_ref()
+            .filter(|backend| backend.handle.is_running())
+        else {
+            return Err("No wallpaper is playing".to_string());
+        };
+
+        backend
+            .handle
+            .send(EngineCommand::Transport { output, transport })
+            .map(|_| ())
+            .map_err(|error| format!("Failed to send command to {REAL_APPLY_BACKEND}: {error}"))
+    }
+
+    fn backend_output_id(monitor_id: &str) -> Result<String, String> {
+        match MonitorService::resolve_specific_monitor(&MonitorService::list_monitors(), monitor_id)
+        {
+            MonitorDiscoveryResult::Known(monitors) => monitors
+                .into_iter()
+                .next()
+                .map(|monitor| monitor.backend_output_id)
+                .ok_or_else(|| format!("Monitor {monitor_id} not found")),
+            MonitorDiscoveryResult::Unavailable { reason } => Err(reason),
+        }
+    }
+
+    /// Pause or resume every wallpaper; does nothing when no engine is running
+    pub fn set_playback_paused(paused: bool) -> Result<(), String> {
+        Self::send_to_running_backend(if paused {
+            EngineCommand::Pause { output: None }
+        } else {
+            EngineCommand::Resume { output: None }
+        })?;
+        HookService::run(if paused {
+            HookEvent::Paused
+        } else {
+            HookEvent::Resumed
+        });
+        Ok(())
+    }
+
+    /// Whether `[playback] pause_on_idle_seconds` asks to pause while idle
+    pub fn pauses_on_idle() -> bool {
+        idle_power_config(&Self::playback_settings())
+            .idle_timeout()
+            .is_some()
+    }
+
+    /// Tell the engine whether the screensaver or lock screen is active
+    pub fn set_screensaver_active(active: bool) -> Result<(), String> {
+        Self::send_to_running_backend(EngineCommand::SetScreenSaverActive { active })
+    }
+
+    /// Whether the wallpaper engine has been started and has not exited
+    pub(crate) fn engine_running() -> bool {
+        desktop_apply_backend_slot()
+            .lock()
+            .map(|backend| {
+                backend
+                    .as_ref()
+                    .is_some_and(|backend| backend.handle.is_running())
+            })
+            .unwrap_or(false)
+    }
+
+    /// Send a command if the engine is running; a stopped engine picks the
+    /// saved settings up when it next starts
+    fn send_to_running_backend(command: EngineCommand) -> Result<(), String> {
+        let backend = desktop_apply_backend_slot()
+            .lock()
+            .map_err(|_| "Desktop apply backend lock was poisoned".to_string())?;
+        let Some(backend) = backend
+            .as_ref()
+            .filter(|backend| backend.handle.is_running())
+        else {
+            return Ok(());
+        };
 
-        if should_restart {
-            *backend = Some(Self::start_apply_backend()?);
-        }
+        backend
+            .handle
+            .send(command)
+            .map(|_| ())
+            .map_err(|error| format!("Failed to send command to {REAL_APPLY_BACKEND}: {error}"))
+    }
 
-        Ok(backend)
+    /// Hand the engine the settings as they are saved now. Playback, power
+    /// and each output's settings change at once; see
+    /// [`EngineConfig::startup_changes`] for what waits for a restart.
+    pub(crate) fn sync_engine_config() -> Result<(), String> {
+        Self::send_to_running_backend(EngineCommand::UpdateConfig(Box::new(Self::engine_config())))
     }
 
     fn start_apply_backend() -> Result<RunningDesktopApplyBackend, String> {
-        let (handle, events) = spawn_engine(EngineConfig::default())
+        let config = Self::engine_config();
+        let startup_timeout = config.startup_timeout;
+        let (handle, events) = spawn_engine(config)
             .map_err(|error| format!("Failed to start {REAL_APPLY_BACKEND}: {error}"))?;
         let mut backend = RunningDesktopApplyBackend { handle, events };
 
-        Self::wait_for_backend_start(&mut backend)?;
+        Self::wait_for_backend_start(&mut backend, startup_timeout)?;
 
         Ok(backend)
     }
 
-    fn wait_for_backend_start(backend: &mut RunningDesktopApplyBackend) -> Result<(), String> {
-        let deadline = Instant::now() + REAL_APPLY_BACKEND_TIMEOUT;
+    fn engine_config() -> EngineConfig {
+        let playback = Self::playback_settings();
+        let compositor = Self::compositor_workaround_settings();
+        let mut config = EngineConfig {
+            pause_on_fullscreen: playback.pause_on_fullscreen,
+            pause_on_maximized: playback.pause_on_maximized,
+            deep_sleep: playback.deep_sleep,
+            audio_follows_focus: playback.audio_follows_focus,
+            pause_on_idle: idle_power_config(&playback).idle_timeout(),
+            battery: battery_profile_from(&playback.battery),
+            startup_timeout: startup_timeout_from(&compositor),
+            workarounds: workaround_overrides_from(&compositor),
+            outputs: Self::output_video_overrides(),
+            video: video_config_from(&playback),
+            scheduling: Self::scheduling_config(),
+            render_device: Self::render_device(),
+            gpu_debug: Self::gpu_debug(),
+            ..EngineConfig::default()
+        };
+        config.apply_policy(&Policy::system());
+        config
+    }
+
+    /// The engine keeps retrying the compositor for `startup_timeout`, so
+    /// this waits at least that long before giving up on it
+    fn wait_for_backend_start(
+        backend: &mut RunningDesktopApplyBackend,
+        startup_timeout: Duration,
+    ) -> Result<(), String> {
+        let deadline = Instant::now() + backend_start_timeout(startup_timeout);
 
         loop {
-            match Self::recv_backend_event(backend, deadline)? {
+            match Self::recv_backend_event(backend, deadline)?.map(|message| message.event) {
                 Some(EngineEvent::Started) => return Ok(()),
                 Some(EngineEvent::Error(reason)) => {
                     return Err(format!("{REAL_APPLY_BACKEND} failed to start: {reason}"));
@@ -448,7 +1353,7 @@ impl DesktopService {
     fn wait_for_output(
         backend: &mut RunningDesktopApplyBackend,
         output_name: &str,
-    ) -> Result<(), String> {
+    ) -> Result<OutputInfo, String> {
         let deadline = Instant::now() + REAL_APPLY_BACKEND_TIMEOUT;
         backend
             .handle
@@ -458,15 +1363,19 @@ impl DesktopService {
             })?;
 
         loop {
-            match Self::recv_backend_event(backend, deadline)? {
-                Some(EngineEvent::OutputAdded(info)) if info.name == output_name => return Ok(()),
+            match Self::recv_backend_event(backend, deadline)?.map(|message| message.event) {
+                Some(EngineEvent::OutputAdded(info)) if info.name == output_name => {
+                    return Ok(info);
+                }
                 Some(EngineEvent::OutputAdded(_)) => {}
-                Some(EngineEvent::OutputsList(outputs))
-                    if outputs.iter().any(|output| output.name == output_name) =>
-                {
-                    return Ok(());
+                Some(EngineEvent::OutputsList(outputs)) => {
+                    if let Some(info) = outputs
+                        .into_iter()
+                        .find(|output| output.name == output_name)
+                    {
+                        return Ok(info);
+                    }
                 }
-                Some(EngineEvent::OutputsList(_)) => {}
                 Some(EngineEvent::Error(reason)) => {
                     return Err(format!(
                         "{REAL_APPLY_BACKEND} could not prepare output {output_name}: {reason}"
@@ -482,40 +1391,93 @@ impl DesktopService {
         }
     }
 
+    /// Wait for the first frame of an apply; returns when the engine drew it
     fn wait_for_apply(
         backend: &mut RunningDesktopApplyBackend,
+        request: RequestId,
         output_name: &str,
         path: &Path,
-    ) -> Result<(), String> {
+    ) -> Result<Instant, String> {
         let deadline = Instant::now() + REAL_APPLY_BACKEND_TIMEOUT;
 
         loop {
-            match Self::recv_backend_event(backend, deadline)? {
-                Some(EngineEvent::WallpaperApplied {
-                    output,
-                    path: applied_path,
-                }) if output == output_name && applied_path == path => return Ok(()),
-                Some(EngineEvent::Error(reason)) => {
+            let Some(message) = Self::recv_backend_event(backend, deadline)? else {
+                return Err(format!(
+                    "Timed out waiting for {REAL_APPLY_BACKEND} to apply {} to {output_name}",
+                    path.display()
+                ));
+            };
+            if message.request != Some(request) {
+                continue;
+            }
+            match message.event {
+                EngineEvent::WallpaperApplied { output, .. } if output == output_name => {
+                    return Ok(message.at);
+                }
+                EngineEvent::SourceRejected { error, .. } => {
                     return Err(format!(
-                        "{REAL_APPLY_BACKEND} failed to apply {} to {output_name}: {reason}",
-                        path.display()
+                        "Kept the current wallpaper on {output_name}: {error}"
                     ));
                 }
-                Some(_) => {}
-                None => {
+                EngineEvent::Error(reason) => {
                     return Err(format!(
-                        "Timed out waiting for {REAL_APPLY_BACKEND} to apply {} to {output_name}",
+                        "{REAL_APPLY_BACKEND} failed to apply {} to {output_name}: {reason}",
                         path.display()
                     ));
                 }
+                _ => {}
             }
         }
     }
 
-    fn recv_backend_event(
+    /// Next event caused by `request`; events for other requests are skipped
+    fn recv_backend_reply(
         backend: &mut RunningDesktopApplyBackend,
+        request: RequestId,
         deadline: Instant,
     ) -> Result<Option<EngineEvent>, String> {
+        loop {
+            match Self::recv_backend_event(backend, deadline)? {
+                Some(message) if message.request == Some(request) => {
+                    return Ok(Some(message.event))
+                }
+                Some(_) => {}
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Hand wallpapers the engine gives up on to `on_failure`. Requests read
+    /// engine events while they wait; a background thread reads the rest.
+    pub fn watch_wallpaper_failures(on_failure: WallpaperFailureSink) {
+        if WALLPAPER_FAILURE_SINK.set(on_failure).is_err() {
+            return;
+        }
+
+        let spawned = thread::Builder::new()
+            .name("lwe-engine-events".to_string())
+            .spawn(|| loop {
+                thread::sleep(BACKEND_EVENT_POLL);
+                // Busy means a request is waiting and reads the events itself
+                let Ok(mut backend) = desktop_apply_backend_slot().try_lock() else {
+                    continue;
+                };
+                if let Some(backend) = backend.as_mut() {
+                    while let Ok(message) = backend.events.try_recv() {
+                        report_backend_event(&message.event);
+                    }
+                }
+            });
+
+        if let Err(reason) = spawned {
+            eprintln!("failed to start the engine event reader: {reason}");
+        }
+    }
+
+    fn recv_backend_event(
+        backend: &mut RunningDesktopApplyBackend,
+        deadline: Instant,
+    ) -> Result<Option<EngineMessage>, String> {
         let now = Instant::now();
         if now >= deadline {
             return Ok(None);
@@ -525,7 +1487,10 @@ impl DesktopService {
             .events
             .recv_timeout(deadline.saturating_duration_since(now))
         {
-            Ok(event) => Ok(Some(event)),
+            Ok(event) => {
+                report_backend_event(&event.event);
+                Ok(Some(event))
+            }
             Err(RecvTimeoutError::Timeout) => Ok(None),
             Err(RecvTimeoutError::Disconnected) => Err(format!(
                 "{REAL_APPLY_BACKEND} stopped before completing the desktop apply request"
@@ -583,6 +1548,8 @@ impl DesktopService {
     fn clear_with_real_backend(
         monitor: &crate::services::monitor_service::MonitorDescriptor,
     ) -> Result<(), String> {
+        WebWallpaperService::close(&monitor.backend_output_id);
+
         let mut backend_guard = desktop_apply_backend_slot()
             .lock()
             .map_err(|_| "Desktop apply backend lock was poisoned".to_string())?;
@@ -596,7 +1563,27 @@ impl DesktopService {
             return Err(reason);
         }
 
+        Self::shut_down_backend(backend)
+    }
+
+    /// Stop the engine so the next apply starts it with the saved settings
+    fn stop_running_backend() -> Result<(), String> {
+        let backend = desktop_apply_backend_slot()
+            .lock()
+            .map_err(|_| "Desktop apply backend lock was poisoned".to_string())?
+            .take();
+
+        match backend.filter(|backend| backend.handle.is_running()) {
+            Some(backend) => Self::shut_down_backend(backend),
+            None => Ok(()),
+        }
+    }
+
+    fn shut_down_backend(mut backend: RunningDesktopApplyBackend) -> Result<(), String> {
         backend.handle.request_shutdown();
+        if let Ok(mut applied) = applied_wallpapers_slot().lock() {
+            applied.clear();
+        }
 
         let stop_result = Self::wait_for_backend_stop(&mut backend);
         let join_result = backend.handle.join().map_err(|error| error.to_string());
@@ -614,7 +1601,7 @@ impl DesktopService {
         let deadline = Instant::now() + REAL_APPLY_BACKEND_TIMEOUT;
 
         loop {
-            match Self::recv_backend_event(backend, deadline)? {
+            match Self::recv_backend_event(backend, deadline)?.map(|message| message.event) {
                 Some(EngineEvent::Stopped) => return Ok(()),
                 Some(EngineEvent::Error(reason)) => {
                     return Err(format!(
@@ -632,6 +1619,313 @@ impl DesktopService {
     }
 }
 
+/// Log failing and recovering wallpapers and pass on the ones given up on
+fn report_backend_event(event: &EngineEvent) {
+    match event {
+        EngineEvent::SourceFailed {
+            output,
+            path,
+            reason,
+            fallback,
+        } => eprintln!(
+            "wallpaper {} failed on {output} ({reason}); showing {fallback}",
+            path.display()
+        ),
+        EngineEvent::SourceRecovered { output, path } => {
+            eprintln!("wallpaper {} recovered on {output}", path.display())
+        }
+        _ => {}
+    }
+    if let Some(failure) = wallpaper_failure_from(event) {
+        eprintln!(
+            "gave up on wallpaper {} on {} after {} failures ({}); showing {}",
+            failure.path.display(),
+            failure.output,
+            failure.failures,
+            failure.reason,
+            failure.fallback
+        );
+        if let Some(sink) = WALLPAPER_FAILURE_SINK.get() {
+            sink(failure);
+        }
+    }
+}
+
+fn wallpaper_failure_from(event: &EngineEvent) -> Option<DesktopWallpaperFailure> {
+    match event {
+        EngineEvent::WallpaperFailed {
+            output,
+            path,
+            reason,
+            failures,
+            fallback,
+        } => Some(DesktopWallpaperFailure {
+            output: output.clone(),
+            path: path.clone(),
+            reason: reason.clone(),
+            failures: *failures,
+            fallback: fallback.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+fn workaround_overrides_from(persisted: &PersistedCompositorWorkarounds) -> WorkaroundOverrides {
+    let compositor = persisted.compositor.as_deref().and_then(|name| {
+        let kind = CompositorKind::from_name(name);
+        if kind.is_none() {
+            eprintln!("ignoring unknown compositor workaround override: {name}");
+        }
+        kind
+    });
+
+    WorkaroundOverrides {
+        compositor,
+        exclusive_zone: persisted.exclusive_zone,
+        buffer_scale: persisted.buffer_scale,
+        output_size_fallback: persisted.output_size_fallback,
+        frame_callback_fallback: persisted
+            .frame_callback_fallback_ms
+            .map(Duration::from_millis),
+    }
+}
+
+fn startup_timeout_from(persisted: &PersistedCompositorWorkarounds) -> Duration {
+    persisted
+        .startup_timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(EngineConfig::default().startup_timeout)
+}
+
+/// The engine's own retry window plus the usual margin for it to report back
+fn backend_start_timeout(startup_timeout: Duration) -> Duration {
+    startup_timeout + REAL_APPLY_BACKEND_TIMEOUT
+}
+
+fn output_overrides_from(
+    persisted: &BTreeMap<String, PersistedOutputSettings>,
+) -> HashMap<String, OutputVideoOverrides> {
+    fn parse<T: std::str::FromStr<Err = String>>(output: &str, value: Option<&str>) -> Option<T> {
+        value.and_then(|value| {
+            value
+                .parse()
+                .map_err(|reason| eprintln!("ignoring {output} output override: {reason}"))
+                .ok()
+        })
+    }
+
+    persisted
+        .iter()
+        .map(|(output, settings)| {
+            (
+                output.clone(),
+                OutputVideoOverrides {
+                    layout: parse::<LayoutMode>(output, settings.layout.as_deref()),
+                    letterbox: parse::<LetterboxFill>(output, settings.letterbox.as_deref()),
+                    volume: settings
+                        .volume
+                        .map(|percent| f32::from(percent.min(100)) / 100.0),
+                    mute: settings.muted,
+                    audio_device: settings.audio_device.clone(),
+                    color: has_color(settings)
+                        .then(|| color_adjust_from(desktop_color_from(settings))),
+                    fallbacks: settings
+                        .fallbacks
+                        .iter()
+                        .filter_map(|fallback| parse::<Fallback>(output, Some(fallback)))
+                        .collect(),
+                    signal_lost: parse::<Fallback>(output, settings.signal_lost.as_deref()),
+                    safe_mode: parse::<Fallback>(output, settings.safe_mode.as_deref()),
+                    layer: parse::<SurfaceLayer>(output, settings.layer.as_deref()),
+                    exclusive_zone: settings.exclusive_zone,
+                    margins: settings
+                        .margins
+                        .map(|margins| SurfaceMargins {
+                            top: margins.top,
+                            right: margins.right,
+                            bottom: margins.bottom,
+                            left: margins.left,
+                        })
+                        .unwrap_or_default(),
+                    mpv_options: settings.mpv_options.clone(),
+                },
+            )
+        })
+        .collect()
+}
+
+fn video_config_from(playback: &PersistedPlayback) -> VideoConfig {
+    let defaults = VideoConfig::default();
+    let transition = playback
+        .transition
+        .as_deref()
+        .and_then(|value| {
+            value
+                .parse::<TransitionKind>()
+                .map_err(|reason| eprintln!("ignoring playback transition: {reason}"))
+                .ok()
+        })
+        .unwrap_or(defaults.transition);
+
+    VideoConfig {
+        transition,
+        transition_duration: playback
+            .transition_ms
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.transition_duration),
+        lut: playback.lut.clone(),
+        mpv_options: playback.mpv_options.clone(),
+        ..defaults
+    }
+}
+
+fn idle_power_config(playback: &PersistedPlayback) -> PowerConfig {
+    PowerConfig {
+        pause_on_idle_seconds: playback.pause_on_idle_seconds.unwrap_or(0),
+        ..PowerConfig::default()
+    }
+}
+
+fn battery_profile_from(persisted: &PersistedBatteryProfile) -> BatteryProfile {
+    BatteryProfile {
+        fps: persisted.fps.unwrap_or(0),
+        hwdec: persisted.hwdec.as_deref().and_then(|value| {
+            value
+                .parse()
+                .map_err(|reason| eprintln!("ignoring battery hwdec: {reason}"))
+                .ok()
+        }),
+        static_fallback: persisted.static_fallback,
+    }
+}
+
+fn scheduling_config_from(persisted: &PersistedScheduling) -> SchedulingConfig {
+    SchedulingConfig::from_power(&scheduling_power_config(persisted))
+}
+
+fn scheduling_power_config(persisted: &PersistedScheduling) -> PowerConfig {
+    PowerConfig {
+        nice: persisted.nice,
+        idle_priority: persisted.idle_priority,
+        cpu_affinity: persisted
+            .cpu_affinity
+            .as_deref()
+            .and_then(|value| {
+                value
+                    .parse()
+                    .map_err(|reason| eprintln!("ignoring scheduling cpu_affinity: {reason}"))
+                    .ok()
+            })
+            .unwrap_or_default(),
+        ..PowerConfig::default()
+    }
+}
+
+fn presentation_matches(
+    presentation: &PersistedPresentation,
+    item_id: &str,
+    playlist_id: Option<i64>,
+) -> bool {
+    presentation.items.iter().any(|item| item == item_id)
+        || playlist_id.is_some_and(|playlist_id| presentation.playlists.contains(&playlist_id))
+}
+
+fn monitor_audio_from(
+    monitors: &[crate::services::monitor_service::MonitorDescriptor],
+    outputs: &BTreeMap<String, PersistedOutputSettings>,
+) -> BTreeMap<String, DesktopMonitorAudio> {
+    monitors
+        .iter()
+        .filter_map(|monitor| {
+            let settings = outputs.get(&monitor.backend_output_id)?;
+            let defaults = DesktopMonitorAudio::default();
+            Some((
+                monitor.id.clone(),
+                DesktopMonitorAudio {
+                    volume: settings
+                        .volume
+                        .map_or(defaults.volume, |volume| volume.min(100)),
+                    muted: settings.muted.unwrap_or(defaults.muted),
+                },
+            ))
+        })
+        .collect()
+}
+
+fn monitor_color_from(
+    monitors: &[crate::services::monitor_service::MonitorDescriptor],
+    outputs: &BTreeMap<String, PersistedOutputSettings>,
+) -> BTreeMap<String, DesktopMonitorColor> {
+    monitors
+        .iter()
+        .filter_map(|monitor| {
+            let settings = outputs
+                .get(&monitor.backend_output_id)
+                .filter(|settings| has_color(settings))?;
+            Some((monitor.id.clone(), desktop_color_from(settings)))
+        })
+        .collect()
+}
+
+fn has_color(settings: &PersistedOutputSettings) -> bool {
+    settings.brightness.is_some()
+        || settings.contrast.is_some()
+        || settings.gamma.is_some()
+        || settings.saturation.is_some()
+}
+
+fn desktop_color_from(settings: &PersistedOutputSettings) -> DesktopMonitorColor {
+    let defaults = DesktopMonitorColor::default();
+    DesktopMonitorColor {
+        brightness: settings.brightness.unwrap_or(defaults.brightness),
+        contrast: settings.contrast.unwrap_or(defaults.contrast),
+        gamma: settings.gamma.unwrap_or(defaults.gamma),
+        saturation: settings.saturation.unwrap_or(defaults.saturation),
+    }
+}
+
+/// Percentages to the engine's scale factors, 100 -> 1.0
+fn color_adjust_from(color: DesktopMonitorColor) -> ColorAdjust {
+    let factor = |percent: u16| f32::from(percent) / 100.0;
+    ColorAdjust {
+        brightness: factor(color.brightness),
+        contrast: factor(color.contrast),
+        gamma: factor(color.gamma),
+        saturation: factor(color.saturation),
+    }
+}
+
+/// Clears go first because clearing a monitor stops the shared engine, which
+/// would otherwise drop wallpapers restored earlier in the same undo.
+fn undo_plan(snapshot: DesktopUndoSnapshot) -> Vec<(String, Option<String>)> {
+    let (clears, applies): (Vec<_>, Vec<_>) = snapshot
+        .into_iter()
+        .partition(|(_, item_id)| item_id.is_none());
+
+    clears.into_iter().chain(applies).collect()
+}
+
+/// Clears for the connected monitors a profile leaves empty, then the
+/// profile's items on its connected monitors, in the order `undo_plan` uses.
+fn profile_plan(
+    connected: &BTreeSet<&str>,
+    current: &BTreeMap<String, String>,
+    profile: &BTreeMap<String, String>,
+) -> Vec<(String, Option<String>)> {
+    let clears = current
+        .keys()
+        .filter(|monitor_id| {
+            connected.contains(monitor_id.as_str()) && !profile.contains_key(*monitor_id)
+        })
+        .map(|monitor_id| (monitor_id.clone(), None));
+    let applies = profile
+        .iter()
+        .filter(|(monitor_id, _)| connected.contains(monitor_id.as_str()))
+        .map(|(monitor_id, item_id)| (monitor_id.clone(), Some(item_id.clone())));
+
+    clears.chain(applies).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::ffi::OsString;
@@ -639,11 +1933,14 @@ mod tests {
     use std::path::{Path, PathBuf};
     use std::time::{SystemTime, UNIX_EPOCH};
 
+    use lwe_core::{CpuAffinity, HwdecMode};
+
     use super::*;
     use crate::results::desktop::DesktopApplyResult;
     use crate::results::desktop::DesktopResolvedMonitorAssignment;
     use crate::results::library::LibraryProjection;
     use crate::results::monitor_discovery::MonitorDiscoveryResult;
+    use crate::results::settings_persistence::PersistedMargins;
     use crate::results::workshop::AssessedWorkshopCatalogEntry;
     use crate::services::library_service::LibraryService;
     use crate::services::monitor_service::MonitorService;
@@ -964,6 +2261,9 @@ mod tests {
             persistence_issue: None,
             assignments_available: true,
             stale: false,
+            monitor_audio: BTreeMap::new(),
+            monitor_color: BTreeMap::new(),
+            monitor_applied: BTreeMap::new(),
         };
         let mut applied = Vec::new();
 
@@ -1049,4 +2349,368 @@ mod tests {
 
         assert!(matches!(result, DesktopApplyResult::Cleared { .. }));
     }
+
+    #[test]
+    fn desktop_undo_plan_clears_before_restoring_previous_items() {
+        let plan = undo_plan(BTreeMap::from([
+            ("DISPLAY-1".to_string(), Some("scene-7".to_string())),
+            ("DISPLAY-2".to_string(), None),
+            ("DISPLAY-3".to_string(), Some("video-3".to_string())),
+        ]));
+
+        assert_eq!(
+            plan,
+            vec![
+                ("DISPLAY-2".to_string(), None),
+                ("DISPLAY-1".to_string(), Some("scene-7".to_string())),
+                ("DISPLAY-3".to_string(), Some("video-3".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn desktop_profile_plan_clears_uncovered_monitors_and_skips_disconnected_ones() {
+        let connected = BTreeSet::from(["DISPLAY-1", "DISPLAY-2", "DISPLAY-3"]);
+        let current = BTreeMap::from([
+            ("DISPLAY-1".to_string(), "scene-7".to_string()),
+            ("DISPLAY-3".to_string(), "video-3".to_string()),
+            ("DISPLAY-9".to_string(), "video-9".to_string()),
+        ]);
+        let profile = BTreeMap::from([
+            ("DISPLAY-1".to_string(), "video-1".to_string()),
+            ("DISPLAY-2".to_string(), "video-2".to_string()),
+            ("DISPLAY-4".to_string(), "video-4".to_string()),
+        ]);
+
+        assert_eq!(
+            profile_plan(&connected, &current, &profile),
+            vec![
+                ("DISPLAY-3".to_string(), None),
+                ("DISPLAY-1".to_string(), Some("video-1".to_string())),
+                ("DISPLAY-2".to_string(), Some("video-2".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn desktop_undo_reports_when_nothing_was_applied_to_all_monitors() {
+        let _guard = real_desktop_flow_test_guard();
+        desktop_apply_undo_slot().lock().unwrap().take();
+
+        assert_eq!(
+            DesktopService::undo_last_apply().unwrap_err(),
+            "There is no desktop apply to undo"
+        );
+    }
+
+    #[test]
+    fn desktop_workaround_overrides_map_settings_and_ignore_unknown_compositors() {
+        let overrides = workaround_overrides_from(&PersistedCompositorWorkarounds {
+            compositor: Some("Hyprland".to_string()),
+            exclusive_zone: Some(0),
+            frame_callback_fallback_ms: Some(250),
+            ..Default::default()
+        });
+
+        assert_eq!(overrides.compositor, Some(CompositorKind::Hyprland));
+        assert_eq!(overrides.exclusive_zone, Some(0));
+        assert_eq!(overrides.buffer_scale, None);
+        assert_eq!(
+            overrides.frame_callback_fallback,
+            Some(Duration::from_millis(250))
+        );
+
+        let unknown = workaround_overrides_from(&PersistedCompositorWorkarounds {
+            compositor: Some("weston".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(unknown, WorkaroundOverrides::default());
+    }
+
+    #[test]
+    fn desktop_output_overrides_parse_video_and_audio_settings_and_skip_invalid_values() {
+        let overrides = output_overrides_from(&BTreeMap::from([
+            (
+                "DP-1".to_string(),
+                PersistedOutputSettings {
+                    layout: Some("Contain".to_string()),
+                    letterbox: Some("#202830".to_string()),
+                    volume: Some(40),
+                    muted: Some(false),
+                    audio_device: Some("pulse/hdmi".to_string()),
+                    fallbacks: vec!["/walls/still.png".to_string(), "#20283".to_string()],
+                    signal_lost: Some("#101010".to_string()),
+                    safe_mode: Some("#0".to_string()),
+                    saturation: Some(150),
+                    layer: Some("bottom".to_string()),
+                    exclusive_zone: Some(0),
+                    margins: Some(PersistedMargins {
+                        bottom: 48,
+                        ..Default::default()
+                    }),
+                    mpv_options: BTreeMap::from([(
+                        "video-sync".to_string(),
+                        "display-resample".to_string(),
+                    )]),
+                    ..Default::default()
+                },
+            ),
+            (
+                "HDMI-A-1".to_string(),
+                PersistedOutputSettings {
+                    layout: Some("zoom".to_string()),
+                    letterbox: Some("dominant".to_string()),
+                    layer: Some("overlay".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ]));
+
+        assert_eq!(
+            overrides["DP-1"],
+            OutputVideoOverrides {
+                layout: Some(LayoutMode::Contain),
+                letterbox: Some(LetterboxFill::Color([0x20, 0x28, 0x30])),
+                volume: Some(0.4),
+                mute: Some(false),
+                audio_device: Some("pulse/hdmi".to_string()),
+                color: Some(ColorAdjust {
+                    saturation: 1.5,
+                    ..ColorAdjust::default()
+                }),
+                fallbacks: vec![Fallback::Image(PathBuf::from("/walls/still.png"))],
+                signal_lost: Some(Fallback::Color([0x10, 0x10, 0x10])),
+                safe_mode: None,
+                layer: Some(SurfaceLayer::Bottom),
+                exclusive_zone: Some(0),
+                margins: SurfaceMargins {
+                    bottom: 48,
+                    ..SurfaceMargins::default()
+                },
+                mpv_options: BTreeMap::from([(
+                    "video-sync".to_string(),
+                    "display-resample".to_string(),
+                )]),
+            }
+        );
+        assert_eq!(overrides["HDMI-A-1"].color, None);
+        assert_eq!(overrides["HDMI-A-1"].layer, None);
+        assert_eq!(overrides["HDMI-A-1"].layout, None);
+        assert_eq!(
+            overrides["HDMI-A-1"].letterbox,
+            Some(LetterboxFill::Dominant)
+        );
+    }
+
+    #[test]
+    fn desktop_reports_only_wallpapers_the_engine_gave_up_on() {
+        let failed = EngineEvent::WallpaperFailed {
+            output: "DP-1".to_string(),
+            path: PathBuf::from("/walls/broken.mkv"),
+            reason: "mpv cannot render it: unsupported codec".to_string(),
+            failures: 3,
+            fallback: Fallback::Color([0, 0, 0]),
+        };
+        let retrying = EngineEvent::SourceFailed {
+            output: "DP-1".to_string(),
+            path: PathBuf::from("/walls/broken.mkv"),
+            reason: "mpv cannot render it: unsupported codec".to_string(),
+            fallback: Fallback::Color([0, 0, 0]),
+        };
+
+        assert_eq!(
+            wallpaper_failure_from(&failed),
+            Some(DesktopWallpaperFailure {
+                output: "DP-1".to_string(),
+                path: PathBuf::from("/walls/broken.mkv"),
+                reason: "mpv cannot render it: unsupported codec".to_string(),
+                failures: 3,
+                fallback: "#000000".to_string(),
+            })
+        );
+        assert_eq!(wallpaper_failure_from(&retrying), None);
+    }
+
+    #[test]
+    fn desktop_video_config_reads_transition_and_ignores_unknown_kinds() {
+        let video = video_config_from(&PersistedPlayback {
+            transition: Some("slide".to_string()),
+            transition_ms: Some(250),
+            ..Default::default()
+        });
+        assert_eq!(video.transition, TransitionKind::Slide);
+        assert_eq!(video.transition_duration, Duration::from_millis(250));
+        assert_eq!(video.lut, None);
+        assert!(video.mpv_options.is_empty());
+
+        let video = video_config_from(&PersistedPlayback {
+            transition: Some("wipe".to_string()),
+            lut: Some(PathBuf::from("/home/user/luts/teal.cube")),
+            ..Default::default()
+        });
+        assert_eq!(video.transition, TransitionKind::None);
+        assert_eq!(video.lut, Some(PathBuf::from("/home/user/luts/teal.cube")));
+        assert_eq!(
+            video.transition_duration,
+            VideoConfig::default().transition_duration
+        );
+    }
+
+    #[test]
+    fn desktop_backend_start_outwaits_the_engine_startup_timeout() {
+        let default = startup_timeout_from(&PersistedCompositorWorkarounds::default());
+        assert_eq!(default, EngineConfig::default().startup_timeout);
+        assert!(backend_start_timeout(default) > default);
+
+        let configured = startup_timeout_from(&PersistedCompositorWorkarounds {
+            startup_timeout_secs: Some(90),
+            ..Default::default()
+        });
+        assert_eq!(configured, Duration::from_secs(90));
+        assert!(backend_start_timeout(configured) >= Duration::from_secs(90));
+        assert!(backend_start_timeout(Duration::ZERO) >= REAL_APPLY_BACKEND_TIMEOUT);
+    }
+
+    #[test]
+    fn desktop_idle_pause_is_off_unless_configured() {
+        assert_eq!(
+            idle_power_config(&PersistedPlayback::default()).idle_timeout(),
+            None
+        );
+        assert_eq!(
+            idle_power_config(&PersistedPlayback {
+                pause_on_idle_seconds: Some(120),
+                ..Default::default()
+            })
+            .idle_timeout(),
+            Some(Duration::from_secs(120))
+        );
+    }
+
+    #[test]
+    fn desktop_battery_profile_reads_the_battery_table() {
+        assert_eq!(
+            battery_profile_from(&PersistedBatteryProfile::default()),
+            BatteryProfile::default()
+        );
+        assert_eq!(
+            battery_profile_from(&PersistedBatteryProfile {
+                fps: Some(15),
+                hwdec: Some("Force".to_string()),
+                static_fallback: true,
+            }),
+            BatteryProfile {
+                fps: 15,
+                hwdec: Some(HwdecMode::Force),
+                static_fallback: true,
+            }
+        );
+        assert_eq!(
+            battery_profile_from(&PersistedBatteryProfile {
+                hwdec: Some("vaapi".to_string()),
+                ..Default::default()
+            })
+            .hwdec,
+            None
+        );
+    }
+
+    #[test]
+    fn desktop_scheduling_config_keeps_defaults_for_unset_and_invalid_values() {
+        assert_eq!(
+            scheduling_config_from(&PersistedScheduling::default()),
+            SchedulingConfig::default()
+        );
+        assert_eq!(SchedulingConfig::default().nice, None);
+
+        let scheduling = scheduling_config_from(&PersistedScheduling {
+            nice: Some(19),
+            idle_priority: true,
+            cpu_affinity: Some("0-1".to_string()),
+        });
+        assert_eq!(scheduling.nice, Some(19));
+        assert!(scheduling.idle_priority);
+        assert_eq!(scheduling.cpu_affinity, CpuAffinity::Cores(vec![0, 1]));
+
+        let scheduling = scheduling_config_from(&PersistedScheduling {
+            cpu_affinity: Some("turbo".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(scheduling.cpu_affinity, CpuAffinity::All);
+    }
+
+    #[test]
+    fn desktop_monitor_audio_and_color_read_saved_settings_by_backend_output() {
+        let monitors = vec![
+            crate::services::monitor_service::MonitorDescriptor {
+                id: "niri:DP-1".to_string(),
+                backend_output_id: "DP-1".to_string(),
+                name: "Primary".to_string(),
+                resolution: "1920x1080".to_string(),
+            },
+            crate::services::monitor_service::MonitorDescriptor {
+                id: "niri:HDMI-A-1".to_string(),
+                backend_output_id: "HDMI-A-1".to_string(),
+                name: "Secondary".to_string(),
+                resolution: "2560x1440".to_string(),
+            },
+        ];
+        let outputs = BTreeMap::from([
+            (
+                "DP-1".to_string(),
+                PersistedOutputSettings {
+                    muted: Some(false),
+                    ..Default::default()
+                },
+            ),
+            (
+                "HDMI-A-1".to_string(),
+                PersistedOutputSettings {
+                    gamma: Some(80),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let audio = monitor_audio_from(&monitors, &outputs);
+        let color = monitor_color_from(&monitors, &outputs);
+
+        assert_eq!(
+            audio.get("niri:DP-1"),
+            Some(&DesktopMonitorAudio {
+                volume: 100,
+                muted: false,
+            })
+        );
+        assert_eq!(
+            audio.get("niri:HDMI-A-1"),
+            Some(&DesktopMonitorAudio::default())
+        );
+        assert!(!color.contains_key("niri:DP-1"));
+        assert_eq!(
+            color.get("niri:HDMI-A-1"),
+            Some(&DesktopMonitorColor {
+                gamma: 80,
+                ..DesktopMonitorColor::default()
+            })
+        );
+        assert_eq!(color_adjust_from(color["niri:HDMI-A-1"]).gamma, 0.8);
+    }
+
+    #[test]
+    fn desktop_presentation_matches_listed_items_and_rotating_playlists() {
+        let presentation = PersistedPresentation {
+            items: vec!["signage-1".to_string()],
+            playlists: vec![3],
+        };
+
+        assert!(presentation_matches(&presentation, "signage-1", None));
+        assert!(presentation_matches(&presentation, "video-9", Some(3)));
+        assert!(!presentation_matches(&presentation, "video-9", Some(4)));
+        assert!(!presentation_matches(
+            &PersistedPresentation::default(),
+            "signage-1",
+            Some(3)
+        ));
+    }
 }
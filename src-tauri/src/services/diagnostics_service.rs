@@ -0,0 +1,786 @@
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use lwe_engine::{
+    CgroupUsage, CompositorKind, DetectedCompositor, EventChannelStats, GpuMessage, GpuSeverity,
+    SystemCapabilities, Workarounds,
+};
+use lwe_library::{LibraryDatabase, SteamLibrary};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+use zbus::blocking::{Connection, Proxy};
+
+use crate::results::diagnostics::{
+    DiagnosticsCapability, DiagnosticsEngineState, DiagnosticsGpuMessage, DiagnosticsGpuSeverity,
+    DiagnosticsLogLine, DiagnosticsOutput, DiagnosticsPageResult, DiagnosticsPlayback,
+};
+use crate::results::monitor_discovery::MonitorDiscoveryResult;
+use crate::services::desktop_service::DesktopService;
+use crate::services::monitor_service::MonitorService;
+
+const LOG_BUFFER_CAPACITY: usize = 500;
+const DEFAULT_LOG_FILTER: &str = "info";
+/// Globals the engine uses when the compositor offers them
+const OPTIONAL_GLOBALS: &[&str] = &[
+    "zwlr_foreign_toplevel_manager_v1",
+    "ext_idle_notifier_v1",
+    "zwp_idle_inhibit_manager_v1",
+    "wp_color_manager_v1",
+];
+const PORTAL_BUS_NAME: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_OBJECT_PATH: &str = "/org/freedesktop/portal/desktop";
+/// Portals the shell opens files and folders through
+const PORTAL_INTERFACES: &[&str] = &[
+    "org.freedesktop.portal.FileChooser",
+    "org.freedesktop.portal.OpenURI",
+];
+
+/// Most recent log lines, oldest first
+struct LogRingBuffer {
+    lines: VecDeque<DiagnosticsLogLine>,
+    next_sequence: u64,
+    capacity: usize,
+}
+
+impl LogRingBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            next_sequence: 1,
+            capacity,
+        }
+    }
+
+    fn push(&mut self, level: &str, target: &str, message: String) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+
+        self.lines.push_back(DiagnosticsLogLine {
+            sequence: self.next_sequence,
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .unwrap_or(0),
+            level: level.to_string(),
+            target: target.to_string(),
+            message,
+        });
+        self.next_sequence += 1;
+    }
+
+    fn lines_after(&self, after_sequence: Option<u64>) -> Vec<DiagnosticsLogLine> {
+        self.lines
+            .iter()
+            .filter(|line| after_sequence.is_none_or(|after| line.sequence > after))
+            .cloned()
+            .collect()
+    }
+}
+
+fn log_buffer() -> &'static Mutex<LogRingBuffer> {
+    static LOG_BUFFER: OnceLock<Mutex<LogRingBuffer>> = OnceLock::new();
+
+    LOG_BUFFER.get_or_init(|| Mutex::new(LogRingBuffer::new(LOG_BUFFER_CAPACITY)))
+}
+
+/// Tracing layer copying every event into the diagnostics ring buffer
+struct LogCaptureLayer;
+
+impl<S: Subscriber> Layer<S> for LogCaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        if let Ok(mut buffer) = log_buffer().lock() {
+            buffer.push(
+                metadata.level().as_str(),
+                metadata.target(),
+                visitor.message,
+            );
+        }
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let separator = if self.message.is_empty() { "" } else { " " };
+            let _ = write!(self.message, "{separator}{}={value:?}", field.name());
+        }
+    }
+}
+
+pub struct DiagnosticsService;
+
+impl DiagnosticsService {
+    /// Route engine and shell tracing output to stderr and the diagnostics buffer
+    pub fn install_log_capture() {
+        let filter = EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER));
+
+        let installed = tracing_subscriber::registry()
+            .with(filter)
+            .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+            .with(LogCaptureLayer)
+            .try_init();
+
+        if let Err(reason) = installed {
+            eprintln!("failed to install diagnostics log capture: {reason}");
+        }
+    }
+
+    pub fn load_page() -> Result<DiagnosticsPageResult, String> {
+        let status = DesktopService::engine_status();
+        // The engine shares the shell's process, so its cgroup can also be
+        // read directly before the engine has started
+        let cgroup = match &status {
+            Ok(Some(status)) => status.cgroup.clone(),
+            _ => None,
+        }
+        .or_else(lwe_engine::cgroup::cgroup_usage);
+        let engine = match status {
+            Ok(Some(mut status)) => DiagnosticsEngineState::Running {
+                render_backend: status.render_backend.display_name().to_string(),
+                on_battery: status.on_battery,
+                power_paused: status.power_paused,
+                outputs: status
+                    .outputs
+                    .into_iter()
+                    .map(|output| DiagnosticsOutput {
+                        wallpaper_path: status
+                            .active_wallpapers
+                            .get(&output.name)
+                            .cloned()
+                            .flatten()
+                            .map(|path| path.to_string_lossy().into_owned()),
+                        resolution: format!("{}x{}", output.width, output.height),
+                        gpu_messages: status
+                            .gpu_messages
+                            .remove(&output.name)
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(gpu_message)
+                            .collect(),
+                        playback: status.playback.remove(&output.name).map(|playback| {
+                            DiagnosticsPlayback {
+                                title: playback.media.title,
+                                duration_secs: playback
+                                    .media
+                                    .duration
+                                    .map(|length| length.as_secs_f64()),
+                                hwdec: playback.media.hwdec,
+                                fps: playback.frame_rate.map(|rate| rate.fps),
+                                skip_rate: playback.frame_rate.map(|rate| rate.skip_rate),
+                            }
+                        }),
+                        name: output.name,
+                    })
+                    .collect(),
+            },
+            Ok(None) => DiagnosticsEngineState::NotStarted,
+            Err(reason) => DiagnosticsEngineState::Unavailable { reason },
+        };
+
+        Ok(DiagnosticsPageResult {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            engine,
+            capabilities: {
+                let mut capabilities = Self::capabilities();
+                capabilities.push(resource_capability(cgroup.as_ref()));
+                capabilities.push(gpu_debug_capability());
+                capabilities.push(event_channel_capability(
+                    DesktopService::event_channel_stats(),
+                ));
+                capabilities
+            },
+            logs: Self::logs_after(None),
+        })
+    }
+
+    pub fn logs_after(after_sequence: Option<u64>) -> Vec<DiagnosticsLogLine> {
+        log_buffer()
+            .lock()
+            .map(|buffer| buffer.lines_after(after_sequence))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn capabilities() -> Vec<DiagnosticsCapability> {
+        let wayland_display = std::env::var("WAYLAND_DISPLAY").ok();
+        let desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+        let steam = SteamLibrary::try_discover();
+
+        vec![
+            capability(
+                "wayland_session",
+                wayland_display.is_some(),
+                match wayland_display {
+                    Some(display) if desktop.is_empty() => display,
+                    Some(display) => format!("{display} ({desktop})"),
+                    None => "WAYLAND_DISPLAY is not set".to_string(),
+                },
+            ),
+            match MonitorService::list_monitors() {
+                MonitorDiscoveryResult::Known(monitors) => capability(
+                    "monitor_discovery",
+                    true,
+                    format!("{} monitors", monitors.len()),
+                ),
+                MonitorDiscoveryResult::Unavailable { reason } => {
+                    capability("monitor_discovery", false, reason)
+                }
+            },
+            capability(
+                "steam",
+                steam.is_some(),
+                if steam.is_some() {
+                    "Steam library detected"
+                } else {
+                    "Steam was not detected"
+                },
+            ),
+            capability(
+                "wallpaper_engine",
+                steam
+                    .as_ref()
+                    .is_some_and(|steam| steam.has_wallpaper_engine()),
+                "Required for Workshop content",
+            ),
+            compositor_capability(),
+            match LibraryDatabase::open(LibraryDatabase::default_path()) {
+                Ok(_) => capability(
+                    "library_database",
+                    true,
+                    LibraryDatabase::default_path().display().to_string(),
+                ),
+                Err(error) => capability("library_database", false, format!("{error:#}")),
+            },
+        ]
+    }
+
+    /// Everything `lwe check` reports: the page's capabilities plus probes
+    /// of the compositor, GPU drivers, libmpv, portals and power supplies,
+    /// which are too slow to run on every page load
+    pub(crate) fn check_capabilities() -> Vec<DiagnosticsCapability> {
+        let mut capabilities = Self::capabilities();
+        capabilities.extend(system_capabilities(&lwe_engine::probe_system()));
+        capabilities.push(portal_capability());
+        capabilities.push(power_supply_capability(Path::new(
+            "/sys/class/power_supply",
+        )));
+        capabilities.push(resource_capability(
+            lwe_engine::cgroup::cgroup_usage().as_ref(),
+        ));
+        capabilities.push(gpu_debug_capability());
+        capabilities
+    }
+}
+
+fn system_capabilities(system: &SystemCapabilities) -> Vec<DiagnosticsCapability> {
+    let global = |interface: &str| match &system.wayland_globals {
+        Ok(globals) => globals
+            .iter()
+            .find(|global| global.interface == interface)
+            .map(|global| format!("{interface} v{}", global.version)),
+        Err(_) => None,
+    };
+
+    let mut capabilities = vec![match &system.wayland_globals {
+        Ok(_) => match global("zwlr_layer_shell_v1") {
+            Some(detail) => capability("layer_shell", true, detail),
+            None => capability(
+                "layer_shell",
+                false,
+                "The compositor does not offer zwlr_layer_shell_v1",
+            ),
+        },
+        Err(reason) => capability("layer_shell", false, reason.as_str()),
+    }];
+    if system.wayland_globals.is_ok() {
+        let optional = OPTIONAL_GLOBALS
+            .iter()
+            .map(|interface| global(interface).ok_or(*interface))
+            .collect::<Vec<_>>();
+        capabilities.push(capability(
+            "wayland_protocols",
+            optional.iter().all(Result::is_ok),
+            optional
+                .iter()
+                .map(|found| match found {
+                    Ok(detail) => detail.clone(),
+                    Err(interface) => format!("{interface} missing"),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        ));
+    }
+
+    capabilities.push(match &system.egl {
+        Ok(egl) => {
+            let dmabuf = egl
+                .extensions
+                .iter()
+                .any(|extension| extension == "EGL_EXT_image_dma_buf_import");
+            capability(
+                "egl",
+                true,
+                format!(
+                    "EGL {}, {}, {}; {}",
+                    egl.version,
+                    egl.vendor,
+                    egl.client_apis,
+                    if dmabuf {
+                        "dma-buf import"
+                    } else {
+                        "no dma-buf import"
+                    }
+                ),
+            )
+        }
+        Err(reason) => capability("egl", false, reason.as_str()),
+    });
+
+    let vaapi = system
+        .vaapi
+        .iter()
+        .map(|(node, support)| {
+            let node = node
+                .file_name()
+                .unwrap_or(node.as_os_str())
+                .to_string_lossy();
+            match support {
+                Ok(support) if support.decode_profiles.is_empty() => {
+                    format!("{node}: {}, no decode profiles", support.driver)
+                }
+                Ok(support) => format!(
+                    "{node}: {} ({})",
+                    support.driver,
+                    support.decode_profiles.join(", ")
+                ),
+                Err(reason) => format!("{node}: {reason}"),
+            }
+        })
+        .collect::<Vec<_>>();
+    capabilities.push(capability(
+        "vaapi",
+        system.vaapi.iter().any(|(_, support)| {
+            support
+                .as_ref()
+                .is_ok_and(|support| !support.decode_profiles.is_empty())
+        }),
+        if vaapi.is_empty() {
+            "No DRM render nodes found in /dev/dri".to_string()
+        } else {
+            vaapi.join("; ")
+        },
+    ));
+    capabilities.push(match &system.nvdec {
+        Ok(version) => capability("nvdec", true, format!("NVIDIA driver {version}")),
+        Err(reason) => capability("nvdec", false, reason.as_str()),
+    });
+    capabilities.push(if system.vulkan_loader {
+        capability("vulkan", true, "libvulkan.so.1 found")
+    } else {
+        capability("vulkan", false, "libvulkan.so.1 is not installed")
+    });
+    capabilities.push(match &system.mpv {
+        Ok(mpv) => capability(
+            "mpv",
+            true,
+            format!(
+                "{}, FFmpeg {}, client API {}.{}",
+                mpv.mpv.as_deref().unwrap_or("mpv"),
+                mpv.ffmpeg.as_deref().unwrap_or("?"),
+                mpv.client_api.0,
+                mpv.client_api.1
+            ),
+        ),
+        Err(reason) => capability("mpv", false, reason.as_str()),
+    });
+    capabilities
+}
+
+/// Version of each portal the shell uses, read from the session bus
+fn portal_capability() -> DiagnosticsCapability {
+    let connection = match Connection::session() {
+        Ok(connection) => connection,
+        Err(error) => {
+            return capability(
+                "portals",
+                false,
+                format!("Failed to connect to the session bus: {error}"),
+            );
+        }
+    };
+
+    let versions = PORTAL_INTERFACES
+        .iter()
+        .map(|interface| {
+            let name = interface.rsplit('.').next().unwrap_or(interface);
+            let version = Proxy::new(&connection, PORTAL_BUS_NAME, PORTAL_OBJECT_PATH, *interface)
+                .and_then(|proxy| proxy.get_property::<u32>("version"));
+            match version {
+                Ok(version) => (true, format!("{name} v{version}")),
+                Err(_) => (false, format!("{name} missing")),
+            }
+        })
+        .collect::<Vec<_>>();
+    capability(
+        "portals",
+        versions.iter().all(|(found, _)| *found),
+        versions
+            .into_iter()
+            .map(|(_, detail)| detail)
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+/// Batteries and mains adapters under `power_supply_dir`, with their state
+fn power_supply_capability(power_supply_dir: &Path) -> DiagnosticsCapability {
+    let read = |path: &Path| {
+        std::fs::read_to_string(path)
+            .ok()
+            .map(|value| value.trim().to_string())
+    };
+    let mut supplies = std::fs::read_dir(power_supply_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let state = match read(&path.join("type"))?.as_str() {
+                "Battery" => read(&path.join("status")).unwrap_or_else(|| "Unknown".to_string()),
+                "Mains" => match read(&path.join("online")).as_deref() {
+                    Some("1") => "online".to_string(),
+                    _ => "offline".to_string(),
+                },
+                _ => return None,
+            };
+            Some(format!("{name} {state}"))
+        })
+        .collect::<Vec<_>>();
+    supplies.sort();
+
+    if supplies.is_empty() {
+        capability(
+            "power_supply",
+            false,
+            "No battery or AC adapter reported; battery saving stays off",
+        )
+    } else {
+        capability("power_supply", true, supplies.join(", "))
+    }
+}
+
+fn compositor_capability() -> DiagnosticsCapability {
+    let compositor = DetectedCompositor::detect();
+    let workarounds = Workarounds::resolve(&compositor, &DesktopService::workaround_overrides());
+    let detail = if workarounds.applied.is_empty() {
+        format!("{compositor}; no workarounds")
+    } else {
+        format!(
+            "{compositor}; workarounds: {}",
+            workarounds.applied.join(", ")
+        )
+    };
+
+    capability(
+        "compositor",
+        compositor.kind != CompositorKind::Unknown,
+        detail,
+    )
+}
+
+fn gpu_message(message: GpuMessage) -> DiagnosticsGpuMessage {
+    DiagnosticsGpuMessage {
+        severity: match message.severity {
+            GpuSeverity::Error => DiagnosticsGpuSeverity::Error,
+            GpuSeverity::Warning => DiagnosticsGpuSeverity::Warning,
+        },
+        message: message.message,
+        count: message.count,
+    }
+}
+
+fn gpu_debug_capability() -> DiagnosticsCapability {
+    if DesktopService::gpu_debug() {
+        capability(
+            "gpu_debug",
+            true,
+            "KHR_debug output; driver messages are listed per output",
+        )
+    } else {
+        capability(
+            "gpu_debug",
+            false,
+            "GL errors only; set [gpu] debug = true for driver messages",
+        )
+    }
+}
+
+/// Whether the shell keeps up with engine events; events that had to wait
+/// for room mean it fell behind, dropped ones that it missed some
+fn event_channel_capability(stats: Option<EventChannelStats>) -> DiagnosticsCapability {
+    match stats {
+        Some(stats) => capability(
+            "event_channel",
+            stats.delayed == 0,
+            format!(
+                "{} delivered, {} coalesced, {} delayed, {} dropped",
+                stats.delivered, stats.coalesced, stats.delayed, stats.dropped
+            ),
+        ),
+        None => capability("event_channel", false, "The engine is not running"),
+    }
+}
+
+fn resource_capability(cgroup: Option<&CgroupUsage>) -> DiagnosticsCapability {
+    match cgroup {
+        Some(usage) => capability(
+            "resource_limits",
+            usage.cpu_quota_percent.is_some() || usage.memory_high_bytes.is_some(),
+            cgroup_detail(usage),
+        ),
+        None => capability("resource_limits", false, "cgroup v2 is not available"),
+    }
+}
+
+/// Memory, CPU time and limits of the cgroup, e.g.
+/// `lwe.service: 140 MiB of 512 MiB, 2.5 s CPU, CPU quota 50%`
+fn cgroup_detail(usage: &CgroupUsage) -> String {
+    const MIB: u64 = 1024 * 1024;
+    let mut parts = Vec::new();
+    match (usage.memory_bytes, usage.memory_high_bytes) {
+        (Some(memory), Some(high)) => {
+            parts.push(format!("{} MiB of {} MiB", memory / MIB, high / MIB))
+        }
+        (Some(memory), None) => parts.push(format!("{} MiB", memory / MIB)),
+        (None, _) => {}
+    }
+    if let Some(cpu_time) = usage.cpu_time {
+        parts.push(format!("{:.1} s CPU", cpu_time.as_secs_f64()));
+    }
+    match usage.cpu_quota_percent {
+        Some(percent) => parts.push(format!("CPU quota {percent}%")),
+        None if usage.memory_high_bytes.is_none() => parts.push("no limits".to_string()),
+        None => {}
+    }
+    format!(
+        "{}: {}",
+        usage.unit().unwrap_or(usage.path.as_str()),
+        parts.join(", ")
+    )
+}
+
+fn capability(key: &str, available: bool, detail: impl Into<String>) -> DiagnosticsCapability {
+    DiagnosticsCapability {
+        key: key.to_string(),
+        available,
+        detail: detail.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_ring_buffer_drops_oldest_lines_and_resumes_after_sequence() {
+        let mut buffer = LogRingBuffer::new(2);
+        buffer.push("INFO", "lwe_engine", "first".to_string());
+        buffer.push("WARN", "lwe_engine", "second".to_string());
+        buffer.push("ERROR", "lwe_shell", "third".to_string());
+
+        let lines = buffer.lines_after(None);
+        assert_eq!(
+            lines
+                .iter()
+                .map(|line| line.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["second", "third"]
+        );
+        assert_eq!(lines[1].sequence, 3);
+
+        let newer = buffer.lines_after(Some(2));
+        assert_eq!(newer.len(), 1);
+        assert_eq!(newer[0].level, "ERROR");
+    }
+
+    #[test]
+    fn log_capture_layer_records_message_and_fields() {
+        let subscriber = tracing_subscriber::registry().with(LogCaptureLayer);
+        let before = DiagnosticsService::logs_after(None)
+            .last()
+            .map(|line| line.sequence);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(output = "DP-1", "Surface configure timed out");
+        });
+
+        let lines = DiagnosticsService::logs_after(before);
+        assert!(lines.iter().any(|line| line.level == "WARN"
+            && line.message == "Surface configure timed out output=\"DP-1\""));
+    }
+
+    #[test]
+    fn cgroup_detail_summarizes_usage_and_limits() {
+        let usage = CgroupUsage {
+            path: "/user.slice/user@1000.service/app.slice/lwe.service".to_string(),
+            memory_bytes: Some(140 * 1024 * 1024),
+            memory_high_bytes: Some(512 * 1024 * 1024),
+            cpu_time: Some(std::time::Duration::from_millis(2500)),
+            cpu_quota_percent: Some(50),
+        };
+        assert_eq!(
+            cgroup_detail(&usage),
+            "lwe.service: 140 MiB of 512 MiB, 2.5 s CPU, CPU quota 50%"
+        );
+
+        let unlimited = CgroupUsage {
+            memory_high_bytes: None,
+            cpu_quota_percent: None,
+            ..usage
+        };
+        assert_eq!(
+            cgroup_detail(&unlimited),
+            "lwe.service: 140 MiB, 2.5 s CPU, no limits"
+        );
+        assert!(!resource_capability(Some(&unlimited)).available);
+    }
+
+    #[test]
+    fn event_channel_capability_reports_counters_and_flags_delays() {
+        let healthy = event_channel_capability(Some(EventChannelStats {
+            delivered: 120,
+            coalesced: 0,
+            delayed: 0,
+            dropped: 0,
+        }));
+        assert!(healthy.available);
+        assert_eq!(
+            healthy.detail,
+            "120 delivered, 0 coalesced, 0 delayed, 0 dropped"
+        );
+
+        let lossy = event_channel_capability(Some(EventChannelStats {
+            delivered: 256,
+            coalesced: 30,
+            delayed: 1280,
+            dropped: 12,
+        }));
+        assert!(!lossy.available);
+        assert_eq!(
+            lossy.detail,
+            "256 delivered, 30 coalesced, 1280 delayed, 12 dropped"
+        );
+        assert!(!event_channel_capability(None).available);
+    }
+
+    #[test]
+    fn system_capabilities_summarize_probes() {
+        let global = |interface: &str, version| lwe_engine::WaylandGlobal {
+            interface: interface.to_string(),
+            version,
+        };
+        let system = SystemCapabilities {
+            wayland_globals: Ok(vec![
+                global("zwlr_layer_shell_v1", 4),
+                global("zwlr_foreign_toplevel_manager_v1", 3),
+                global("ext_idle_notifier_v1", 1),
+            ]),
+            egl: Ok(lwe_engine::EglInfo {
+                version: "1.5".to_string(),
+                vendor: "Mesa Project".to_string(),
+                client_apis: "OpenGL OpenGL_ES".to_string(),
+                extensions: vec!["EGL_EXT_image_dma_buf_import".to_string()],
+            }),
+            vaapi: vec![(
+                "/dev/dri/renderD128".into(),
+                Ok(lwe_engine::VaapiSupport {
+                    driver: "Mesa Gallium driver".to_string(),
+                    decode_profiles: vec!["H264Main".to_string(), "HEVCMain".to_string()],
+                }),
+            )],
+            nvdec: Err("No NVIDIA driver loaded".to_string()),
+            vulkan_loader: true,
+            mpv: Ok(lwe_engine::MpvVersion {
+                client_api: (2, 3),
+                mpv: Some("mpv v0.39.0".to_string()),
+                ffmpeg: Some("7.1".to_string()),
+            }),
+        };
+
+        let capabilities = system_capabilities(&system);
+        let find = |key: &str| {
+            capabilities
+                .iter()
+                .find(|capability| capability.key == key)
+                .unwrap()
+        };
+        assert_eq!(find("layer_shell").detail, "zwlr_layer_shell_v1 v4");
+        let protocols = find("wayland_protocols");
+        assert!(!protocols.available);
+        assert!(protocols.detail.contains("wp_color_manager_v1 missing"));
+        assert_eq!(
+            find("egl").detail,
+            "EGL 1.5, Mesa Project, OpenGL OpenGL_ES; dma-buf import"
+        );
+        assert_eq!(
+            find("vaapi").detail,
+            "renderD128: Mesa Gallium driver (H264Main, HEVCMain)"
+        );
+        assert!(!find("nvdec").available);
+        assert_eq!(
+            find("mpv").detail,
+            "mpv v0.39.0, FFmpeg 7.1, client API 2.3"
+        );
+
+        let offline = SystemCapabilities {
+            wayland_globals: Err("No Wayland connection".to_string()),
+            ..system
+        };
+        let capabilities = system_capabilities(&offline);
+        assert!(!capabilities[0].available);
+        assert!(capabilities
+            .iter()
+            .all(|capability| capability.key != "wayland_protocols"));
+    }
+
+    #[test]
+    fn power_supply_capability_lists_batteries_and_adapters() {
+        let root = std::env::temp_dir().join(format!("lwe-power-supply-{}", std::process::id()));
+        let supply = |name: &str, files: &[(&str, &str)]| {
+            let dir = root.join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            for (file, contents) in files {
+                std::fs::write(dir.join(file), format!("{contents}\n")).unwrap();
+            }
+        };
+        assert!(!power_supply_capability(&root).available);
+
+        supply("BAT0", &[("type", "Battery"), ("status", "Discharging")]);
+        supply("AC", &[("type", "Mains"), ("online", "0")]);
+        supply("hidpp_battery_0", &[("type", "USB")]);
+        let capability = power_supply_capability(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert!(capability.available);
+        assert_eq!(capability.detail, "AC offline, BAT0 Discharging");
+    }
+}
@@ -0,0 +1,267 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use lwe_library::LibraryDatabase;
+
+use crate::services::service_install_service::{
+    ServiceInstallService, SESSION_ENVIRONMENT, SYSTEMD_UNIT_NAME,
+};
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+
+/// Other wallpaper daemons whose surfaces fight with LWE's
+const CONFLICTING_UNITS: &[&str] = &[
+    "swaybg.service",
+    "hyprpaper.service",
+    "swww.service",
+    "swww-daemon.service",
+    "mpvpaper.service",
+    "wpaperd.service",
+    "linux-wallpaperengine.service",
+];
+
+/// A repair `lwe doctor --fix` can make
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DoctorFix {
+    InstallService,
+    CreateDirectory(PathBuf),
+    DisableUnit(String),
+    ImportEnvironment(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoctorIssue {
+    pub problem: String,
+    pub fix: DoctorFix,
+}
+
+/// What the doctor found on this system
+#[derive(Debug, Default)]
+struct DoctorFacts {
+    unit_installed: bool,
+    missing_directories: Vec<PathBuf>,
+    active_conflicts: Vec<String>,
+    missing_environment: Vec<String>,
+}
+
+/// Finds setup problems the diagnostics capabilities point at and repairs them.
+pub struct DoctorService;
+
+impl DoctorService {
+    pub fn find_issues() -> Vec<DoctorIssue> {
+        issues_from(&gather_facts())
+    }
+}
+
+impl DoctorFix {
+    /// Question asked before applying the fix
+    pub fn prompt(&self) -> String {
+        match self {
+            Self::InstallService => format!("Install the {SYSTEMD_UNIT_NAME} systemd user unit?"),
+            Self::CreateDirectory(path) => format!("Create {}?", path.display()),
+            Self::DisableUnit(unit) => format!("Stop and disable {unit}?"),
+            Self::ImportEnvironment(names) => format!(
+                "Import {} into the systemd user environment?",
+                names.join(" ")
+            ),
+        }
+    }
+
+    /// Apply the fix and describe what changed
+    pub fn apply(&self) -> Result<String, String> {
+        match self {
+            Self::InstallService => {
+                let executable = std::env::current_exe()
+                    .map_err(|error| format!("Unable to resolve current executable: {error}"))?;
+                let executable = executable.to_str().ok_or_else(|| {
+                    format!(
+                        "Unable to use non-UTF-8 executable path {} for the systemd unit",
+                        executable.display()
+                    )
+                })?;
+                let report = ServiceInstallService::for_user_path()?
+                    .install(&[executable, "--minimized"], None)?;
+                Ok(format!(
+                    "Wrote {}; enable it with `systemctl --user enable --now {SYSTEMD_UNIT_NAME}`",
+                    report.unit_path.display()
+                ))
+            }
+            Self::CreateDirectory(path) => std::fs::create_dir_all(path)
+                .map(|()| format!("Created {}", path.display()))
+                .map_err(|error| format!("Failed to create {}: {error}", path.display())),
+            Self::DisableUnit(unit) => {
+                systemctl(&["disable", "--now", unit])?;
+                Ok(format!("Disabled {unit}"))
+            }
+            Self::ImportEnvironment(names) => {
+                let mut args = vec!["import-environment"];
+                args.extend(names.iter().map(String::as_str));
+                systemctl(&args)?;
+                Ok(format!("Imported {}", names.join(" ")))
+            }
+        }
+    }
+}
+
+fn issues_from(facts: &DoctorFacts) -> Vec<DoctorIssue> {
+    let mut issues = Vec::new();
+
+    for path in &facts.missing_directories {
+        issues.push(DoctorIssue {
+            problem: format!("{} does not exist", path.display()),
+            fix: DoctorFix::CreateDirectory(path.clone()),
+        });
+    }
+    if !facts.unit_installed {
+        issues.push(DoctorIssue {
+            problem: format!("The {SYSTEMD_UNIT_NAME} user unit is not installed"),
+            fix: DoctorFix::InstallService,
+        });
+    }
+    for unit in &facts.active_conflicts {
+        issues.push(DoctorIssue {
+            problem: format!("{unit} is running and draws its own wallpaper"),
+            fix: DoctorFix::DisableUnit(unit.clone()),
+        });
+    }
+    if !facts.missing_environment.is_empty() {
+        issues.push(DoctorIssue {
+            problem: format!(
+                "The systemd user environment lacks {}, so the service cannot reach the session",
+                facts.missing_environment.join(" ")
+            ),
+            fix: DoctorFix::ImportEnvironment(facts.missing_environment.clone()),
+        });
+    }
+
+    issues
+}
+
+fn gather_facts() -> DoctorFacts {
+    let mut directories = Vec::new();
+    if let Some(settings_dir) = SettingsPersistenceService::for_user_path()
+        .ok()
+        .and_then(|service| service.path().parent().map(Path::to_path_buf))
+    {
+        directories.push(settings_dir);
+    }
+    if let Some(library_dir) = LibraryDatabase::default_path().parent() {
+        directories.push(library_dir.to_path_buf());
+    }
+
+    let mut is_active = vec!["is-active"];
+    is_active.extend_from_slice(CONFLICTING_UNITS);
+    let active_conflicts = systemctl(&is_active)
+        .map(|output| active_units(CONFLICTING_UNITS, &output))
+        .unwrap_or_default();
+
+    let missing_environment = systemctl(&["show-environment"])
+        .map(|output| {
+            missing_environment(&output, |name| {
+                std::env::var_os(name).is_some_and(|value| !value.is_empty())
+            })
+        })
+        .unwrap_or_default();
+
+    DoctorFacts {
+        unit_installed: ServiceInstallService::for_user_path()
+            .is_ok_and(|service| service.unit_path().exists()),
+        missing_directories: directories
+            .into_iter()
+            .filter(|dir| !dir.exists())
+            .collect(),
+        active_conflicts,
+        missing_environment,
+    }
+}
+
+/// Run `systemctl --user` and return its standard output
+///
+/// `is-active` exits non-zero when a unit is inactive, so only a failure to
+/// run or an empty output counts as an error.
+fn systemctl(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|error| format!("Failed to run systemctl: {error}"))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    if !output.status.success() && stdout.trim().is_empty() {
+        return Err(format!(
+            "systemctl --user {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(stdout)
+}
+
+/// Units reported `active` by `systemctl is-active`, which prints one state per unit
+fn active_units(units: &[&str], output: &str) -> Vec<String> {
+    units
+        .iter()
+        .zip(output.lines())
+        .filter(|(_, state)| state.trim() == "active")
+        .map(|(unit, _)| unit.to_string())
+        .collect()
+}
+
+/// Session variables set in this process but missing from `systemctl show-environment`
+fn missing_environment(output: &str, is_set: impl Fn(&str) -> bool) -> Vec<String> {
+    SESSION_ENVIRONMENT
+        .split_whitespace()
+        .filter(|name| is_set(name))
+        .filter(|name| {
+            !output
+                .lines()
+                .any(|line| line.split_once('=').is_some_and(|(key, _)| key == *name))
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doctor_issues_offer_one_fix_per_problem() {
+        let issues = issues_from(&DoctorFacts {
+            unit_installed: false,
+            missing_directories: vec![PathBuf::from("/home/user/.config/lwe")],
+            active_conflicts: vec!["swaybg.service".to_string()],
+            missing_environment: vec!["WAYLAND_DISPLAY".to_string()],
+        });
+
+        assert_eq!(
+            issues.iter().map(|issue| &issue.fix).collect::<Vec<_>>(),
+            [
+                &DoctorFix::CreateDirectory(PathBuf::from("/home/user/.config/lwe")),
+                &DoctorFix::InstallService,
+                &DoctorFix::DisableUnit("swaybg.service".to_string()),
+                &DoctorFix::ImportEnvironment(vec!["WAYLAND_DISPLAY".to_string()]),
+            ]
+        );
+        assert!(issues_from(&DoctorFacts {
+            unit_installed: true,
+            ..DoctorFacts::default()
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn doctor_reads_systemctl_unit_states_and_environment() {
+        assert_eq!(
+            active_units(
+                &["swaybg.service", "hyprpaper.service", "swww.service"],
+                "inactive\nactive\nfailed\n"
+            ),
+            ["hyprpaper.service"]
+        );
+        assert_eq!(
+            missing_environment("HOME=/home/user\nWAYLAND_DISPLAY=wayland-1\n", |name| name
+                != "DISPLAY"),
+            ["XDG_CURRENT_DESKTOP", "XDG_SESSION_TYPE"]
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+const PORTAL_BUS_NAME: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_OBJECT_PATH: &str = "/org/freedesktop/portal/desktop";
+const FILE_CHOOSER_INTERFACE: &str = "org.freedesktop.portal.FileChooser";
+const REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
+/// `Response` code of a request the user cancelled
+const RESPONSE_CANCELLED: u32 = 1;
+/// Filter entry that matches a file name glob
+const FILTER_GLOB: u32 = 0;
+/// Filter entry that matches a MIME type rather than a glob
+const FILTER_MIME_TYPE: u32 = 1;
+
+static NEXT_REQUEST: AtomicU32 = AtomicU32::new(0);
+
+/// Picks files and folders with the FileChooser portal, so the desktop's own
+/// dialog is used and sandboxed installs get access to what was chosen.
+pub struct FilePickerService;
+
+impl FilePickerService {
+    /// Folders chosen by the user; empty when the dialog was cancelled
+    pub fn pick_folders(title: &str) -> Result<Vec<PathBuf>, String> {
+        let mut options = HashMap::new();
+        options.insert("directory", Value::from(true));
+        options.insert("multiple", Value::from(true));
+        choose_files("OpenFile", title, options)
+    }
+
+    /// Videos and images chosen by the user; empty when the dialog was cancelled
+    pub fn pick_media_files(title: &str) -> Result<Vec<PathBuf>, String> {
+        let filters = vec![(
+            "Videos and images",
+            vec![(FILTER_MIME_TYPE, "video/*"), (FILTER_MIME_TYPE, "image/*")],
+        )];
+        let mut options = HashMap::new();
+        options.insert("filters", Value::from(filters));
+        options.insert("multiple", Value::from(true));
+        choose_files("OpenFile", title, options)
+    }
+
+    /// One file matching `globs`, listed under `filter_name`; None when the
+    /// dialog was cancelled
+    pub fn pick_file(
+        title: &str,
+        filter_name: &str,
+        globs: &[&str],
+    ) -> Result<Option<PathBuf>, String> {
+        let filters = vec![(
+            filter_name,
+            globs
+                .iter()
+                .map(|glob| (FILTER_GLOB, *glob))
+                .collect::<Vec<_>>(),
+        )];
+        let mut options = HashMap::new();
+        options.insert("filters", Value::from(filters));
+        Ok(choose_files("OpenFile", title, options)?.into_iter().next())
+    }
+
+    /// Where to save a new file, suggesting `current_name`; None when the
+    /// dialog was cancelled
+    pub fn pick_save_file(title: &str, current_name: &str) -> Result<Option<PathBuf>, String> {
+        let mut options = HashMap::new();
+        options.insert("current_name", Value::from(current_name));
+        Ok(choose_files("SaveFile", title, options)?.into_iter().next())
+    }
+}
+
+/// Runs a FileChooser `method` and waits for the chosen paths
+fn choose_files(
+    method: &str,
+    title: &str,
+    mut options: HashMap<&str, Value<'_>>,
+) -> Result<Vec<PathBuf>, String> {
+    let connection = Connection::session()
+        .map_err(|error| format!("Failed to connect to the session bus: {error}"))?;
+    let token = format!(
+        "lwe_{}_{}",
+        std::process::id(),
+        NEXT_REQUEST.fetch_add(1, Ordering::Relaxed)
+    );
+    let sender = connection
+        .unique_name()
+        .ok_or_else(|| "The session bus did not assign a name".to_string())?
+        .trim_start_matches(':')
+        .replace('.', "_");
+
+    // Watch the request before making it, so a quick answer is not missed
+    let request = Proxy::new(
+        &connection,
+        PORTAL_BUS_NAME,
+        format!("{PORTAL_OBJECT_PATH}/request/{sender}/{token}"),
+        REQUEST_INTERFACE,
+    )
+    .map_err(|error| format!("Failed to create a portal request proxy: {error}"))?;
+    let mut responses = request
+        .receive_signal("Response")
+        .map_err(|error| format!("Failed to watch the file chooser: {error}"))?;
+
+    options.insert("handle_token", Value::from(token.as_str()));
+    let chooser = Proxy::new(
+        &connection,
+        PORTAL_BUS_NAME,
+        PORTAL_OBJECT_PATH,
+        FILE_CHOOSER_INTERFACE,
+    )
+    .map_err(|error| format!("Failed to create a file chooser proxy: {error}"))?;
+    let _: OwnedObjectPath = chooser
+        .call(method, &("", title, options))
+        .map_err(|error| format!("The file chooser portal is not available: {error}"))?;
+
+    let response = responses
+        .next()
+        .ok_or_else(|| "The file chooser closed without an answer".to_string())?;
+    let (code, results): (u32, HashMap<String, OwnedValue>) = response
+        .body()
+        .deserialize()
+        .map_err(|error| format!("Unexpected file chooser response: {error}"))?;
+    match code {
+        0 => {}
+        RESPONSE_CANCELLED => return Ok(Vec::new()),
+        _ => return Err("The file chooser failed".to_string()),
+    }
+
+    let uris = results
+        .get("uris")
+        .map(|uris| Vec::<String>::try_from(uris.try_clone()?))
+        .transpose()
+        .map_err(|error| format!("Unexpected file chooser selection: {error}"))?
+        .unwrap_or_default();
+    Ok(uris
+        .iter()
+        .filter_map(|uri| path_from_file_uri(uri))
+        .collect())
+}
+
+/// `file:///home/me/My%20Videos` -> `/home/me/My Videos`; other schemes are
+/// not local files and give None
+fn path_from_file_uri(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'%')
+            .then(|| tail.get(..2))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    bytes
+        .starts_with(b"/")
+        .then(|| PathBuf::from(OsStr::from_bytes(&bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_uris_decode_to_local_paths() {
+        assert_eq!(
+            path_from_file_uri("file:///home/me/My%20Videos"),
+            Some(PathBuf::from("/home/me/My Videos"))
+        );
+        assert_eq!(
+            path_from_file_uri("file:///run/user/1000/doc/3f2a/%E5%A3%81%E7%BA%B8.mp4"),
+            Some(PathBuf::from("/run/user/1000/doc/3f2a/壁纸.mp4"))
+        );
+        // A stray percent sign is kept as it is
+        assert_eq!(
+            path_from_file_uri("file:///tmp/100%.png"),
+            Some(PathBuf::from("/tmp/100%.png"))
+        );
+        assert_eq!(path_from_file_uri("https://example.com/a.mp4"), None);
+        assert_eq!(path_from_file_uri("file://host/share"), None);
+    }
+}
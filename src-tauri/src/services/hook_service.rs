@@ -0,0 +1,188 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use crate::results::monitor_discovery::MonitorDiscoveryResult;
+use crate::results::settings_persistence::{PersistedHooks, SettingsPersistenceLoad};
+use crate::services::monitor_service::MonitorService;
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+
+/// How often monitors are listed to notice new outputs
+const OUTPUT_WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Something a user hook can react to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookEvent<'a> {
+    Applied {
+        output: &'a str,
+        path: &'a Path,
+    },
+    Paused,
+    Resumed,
+    OutputAdded {
+        output: &'a str,
+        resolution: &'a str,
+    },
+}
+
+/// Runs the `[hooks]` commands from settings with `sh -c`, describing the
+/// event in `LWE_*` environment variables.
+pub struct HookService;
+
+impl HookService {
+    /// Run the hook configured for `event`, if any, without waiting for it
+    pub fn run(event: HookEvent<'_>) {
+        let Some(hooks) = Self::config() else {
+            return;
+        };
+        let command = match event {
+            HookEvent::Applied { .. } => hooks.on_apply,
+            HookEvent::Paused | HookEvent::Resumed => hooks.on_pause,
+            HookEvent::OutputAdded { .. } => hooks.on_output_added,
+        };
+        let Some(command) = command.filter(|command| !command.trim().is_empty()) else {
+            return;
+        };
+
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .envs(hook_env(&event))
+            .stdin(Stdio::null())
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(reason) => {
+                eprintln!("failed to run hook `{command}`: {reason}");
+                return;
+            }
+        };
+
+        // Reap the hook in the background so a slow script never blocks playback
+        let spawned =
+            thread::Builder::new()
+                .name("lwe-hook".to_string())
+                .spawn(move || match child.wait() {
+                    Ok(status) if !status.success() => {
+                        eprintln!("hook `{command}` exited with {status}");
+                    }
+                    Ok(_) => {}
+                    Err(reason) => eprintln!("hook `{command}` could not be awaited: {reason}"),
+                });
+        if let Err(reason) = spawned {
+            eprintln!("failed to watch hook: {reason}");
+        }
+    }
+
+    /// Watch for new monitors when an `on_output_added` hook is configured
+    pub fn start() {
+        if Self::config()
+            .and_then(|hooks| hooks.on_output_added)
+            .is_none()
+        {
+            return;
+        }
+
+        let spawned = thread::Builder::new()
+            .name("lwe-hook-outputs".to_string())
+            .spawn(|| {
+                let mut known: Option<BTreeSet<String>> = None;
+                loop {
+                    if let MonitorDiscoveryResult::Known(monitors) = MonitorService::list_monitors()
+                    {
+                        let current = monitors
+                            .iter()
+                            .map(|monitor| monitor.backend_output_id.clone())
+                            .collect::<BTreeSet<_>>();
+                        // The first listing only records what is already connected
+                        if let Some(known) = &known {
+                            for monitor in monitors
+                                .iter()
+                                .filter(|monitor| !known.contains(&monitor.backend_output_id))
+                            {
+                                Self::run(HookEvent::OutputAdded {
+                                    output: &monitor.backend_output_id,
+                                    resolution: &monitor.resolution,
+                                });
+                            }
+                        }
+                        known = Some(current);
+                    }
+                    thread::sleep(OUTPUT_WATCH_INTERVAL);
+                }
+            });
+
+        if let Err(reason) = spawned {
+            eprintln!("failed to start output hook watcher: {reason}");
+        }
+    }
+
+    fn config() -> Option<PersistedHooks> {
+        let loaded = SettingsPersistenceService::for_user_path()
+            .map(|service| service.load_settings())
+            .unwrap_or_else(|reason| SettingsPersistenceLoad::Unavailable { reason });
+
+        match loaded {
+            SettingsPersistenceLoad::Loaded(settings) => Some(settings.hooks),
+            SettingsPersistenceLoad::Unavailable { reason } => {
+                eprintln!("hook settings unavailable: {reason}");
+                None
+            }
+        }
+    }
+}
+
+/// Environment describing `event` to the hook command
+fn hook_env(event: &HookEvent<'_>) -> Vec<(&'static str, String)> {
+    match event {
+        HookEvent::Applied { output, path } => vec![
+            ("LWE_EVENT", "apply".to_string()),
+            ("LWE_OUTPUT", output.to_string()),
+            ("LWE_WALLPAPER", path.display().to_string()),
+        ],
+        HookEvent::Paused => vec![("LWE_EVENT", "pause".to_string())],
+        HookEvent::Resumed => vec![("LWE_EVENT", "resume".to_string())],
+        HookEvent::OutputAdded { output, resolution } => vec![
+            ("LWE_EVENT", "output_added".to_string()),
+            ("LWE_OUTPUT", output.to_string()),
+            ("LWE_RESOLUTION", resolution.to_string()),
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hook_env_describes_apply_and_output_events() {
+        assert_eq!(
+            hook_env(&HookEvent::Applied {
+                output: "DP-1",
+                path: Path::new("/wallpapers/rain.mp4"),
+            }),
+            vec![
+                ("LWE_EVENT", "apply".to_string()),
+                ("LWE_OUTPUT", "DP-1".to_string()),
+                ("LWE_WALLPAPER", "/wallpapers/rain.mp4".to_string()),
+            ]
+        );
+        assert_eq!(
+            hook_env(&HookEvent::OutputAdded {
+                output: "HDMI-A-1",
+                resolution: "1920x1080",
+            }),
+            vec![
+                ("LWE_EVENT", "output_added".to_string()),
+                ("LWE_OUTPUT", "HDMI-A-1".to_string()),
+                ("LWE_RESOLUTION", "1920x1080".to_string()),
+            ]
+        );
+        assert_eq!(
+            hook_env(&HookEvent::Resumed),
+            vec![("LWE_EVENT", "resume".to_string())]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Outgoing HTTP for the Workshop API, signage manifests and stream
+//! downloads. TLS goes through rustls, so no OpenSSL is linked; builds
+//! without the `network` feature have no client at all and every request
+//! fails with [`OFFLINE_BUILD`].
+
+use std::io::Write;
+use std::time::Duration;
+
+use serde_json::Value;
+
+pub const OFFLINE_BUILD: &str = "This build of LWE was made without network support";
+
+pub struct HttpService;
+
+#[cfg(feature = "network")]
+impl HttpService {
+    /// GET `url` and return the body as text
+    pub fn get_text(url: &str, timeout: Duration) -> Result<String, String> {
+        client(timeout)?
+            .get(url)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.text())
+            .map_err(|error| error.to_string())
+    }
+
+    /// GET `url` with `query` appended and parse the body as JSON
+    pub fn get_json(url: &str, query: &[(&str, &str)], timeout: Duration) -> Result<Value, String> {
+        client(timeout)?
+            .get(url)
+            .query(query)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json::<Value>())
+            .map_err(|error| error.to_string())
+    }
+
+    /// POST `form` url-encoded and parse the body as JSON
+    pub fn post_form_json(
+        url: &str,
+        form: &[(String, String)],
+        timeout: Duration,
+    ) -> Result<Value, String> {
+        client(timeout)?
+            .post(url)
+            .form(form)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json::<Value>())
+            .map_err(|error| error.to_string())
+    }
+
+    /// Stream the body of `url` into `writer` and return its length
+    pub fn download_to(
+        url: &str,
+        timeout: Duration,
+        writer: &mut impl Write,
+    ) -> Result<u64, String> {
+        client(timeout)?
+            .get(url)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|mut response| response.copy_to(writer))
+            .map_err(|error| error.to_string())
+    }
+}
+
+#[cfg(feature = "network")]
+fn client(timeout: Duration) -> Result<reqwest::blocking::Client, String> {
+    reqwest::blocking::Client::builder()
+        .use_rustls_tls()
+        .timeout(timeout)
+        .build()
+        .map_err(|error| format!("Failed to create HTTP client: {error}"))
+}
+
+#[cfg(not(feature = "network"))]
+impl HttpService {
+    pub fn get_text(_url: &str, _timeout: Duration) -> Result<String, String> {
+        Err(OFFLINE_BUILD.to_string())
+    }
+
+    pub fn get_json(
+        _url: &str,
+        _query: &[(&str, &str)],
+        _timeout: Duration,
+    ) -> Result<Value, String> {
+        Err(OFFLINE_BUILD.to_string())
+    }
+
+    pub fn post_form_json(
+        _url: &str,
+        _form: &[(String, String)],
+        _timeout: Duration,
+    ) -> Result<Value, String> {
+        Err(OFFLINE_BUILD.to_string())
+    }
+
+    pub fn download_to(
+        _url: &str,
+        _timeout: Duration,
+        _writer: &mut impl Write,
+    ) -> Result<u64, String> {
+        Err(OFFLINE_BUILD.to_string())
+    }
+}
+
+#[cfg(all(test, not(feature = "network")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_builds_refuse_every_request() {
+        let timeout = Duration::from_secs(1);
+
+        assert_eq!(
+            HttpService::get_text("https://example.com", timeout),
+            Err(OFFLINE_BUILD.to_string())
+        );
+        assert_eq!(
+            HttpService::download_to("https://example.com", timeout, &mut Vec::new()),
+            Err(OFFLINE_BUILD.to_string())
+        );
+    }
+}
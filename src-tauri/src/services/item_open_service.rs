@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use lwe_library::{WeProject, WorkshopProjectType};
+use zbus::blocking::Connection;
+use zbus::zvariant::{Fd, Value};
+
+use crate::results::workshop::AssessedWorkshopCatalogEntry;
+use crate::services::library_service::LibraryService;
+
+const PORTAL_BUS_NAME: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_OBJECT_PATH: &str = "/org/freedesktop/portal/desktop";
+const OPEN_URI_INTERFACE: &str = "org.freedesktop.portal.OpenURI";
+/// External players, in order of preference
+const EXTERNAL_PLAYERS: &[&str] = &["mpv", "vlc"];
+
+/// Hands library items to other desktop apps: the file manager and a
+/// full-quality video player.
+pub struct ItemOpenService;
+
+impl ItemOpenService {
+    /// Shows the item's main file in the file manager and returns its path.
+    /// Uses the OpenURI portal, which selects the file where the file manager
+    /// supports it, and falls back to opening the containing folder.
+    pub fn reveal(item_id: &str) -> Result<PathBuf, String> {
+        let entry = LibraryService::inspect_item(item_id)?;
+        let path = item_file(&entry);
+
+        if let Err(error) = open_directory_with_portal(&path) {
+            tracing::debug!(
+                "OpenURI portal could not reveal {}: {error}",
+                path.display()
+            );
+            let folder = match path.parent() {
+                Some(parent) if path.is_file() => parent,
+                _ => path.as_path(),
+            };
+            open::that_detached(folder)
+                .map_err(|error| format!("Failed to open {}: {error}", folder.display()))?;
+        }
+
+        Ok(path)
+    }
+
+    /// Plays a video item in mpv, or VLC without mpv, and returns the player used
+    pub fn play_external(item_id: &str) -> Result<&'static str, String> {
+        let entry = LibraryService::inspect_item(item_id)?;
+        if entry.entry.project_type != WorkshopProjectType::Video {
+            return Err(format!(
+                "Library item {item_id} is not a video, so it cannot be played in an external player"
+            ));
+        }
+
+        let path = item_file(&entry);
+        if !path.is_file() {
+            return Err(format!(
+                "Library item {item_id} points to missing asset {}",
+                path.display()
+            ));
+        }
+
+        let player = find_player(std::env::var_os("PATH"))
+            .ok_or_else(|| "Install mpv or VLC to play wallpapers externally".to_string())?;
+        let mut child = Command::new(player)
+            .arg(&path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|error| format!("Failed to start {player}: {error}"))?;
+        // Reap the player when it exits so it does not linger as a zombie
+        thread::spawn(move || child.wait());
+
+        Ok(player)
+    }
+}
+
+/// The item's main file, or its project folder when the project names none
+pub(crate) fn item_file(entry: &AssessedWorkshopCatalogEntry) -> PathBuf {
+    let project_dir = &entry.entry.project_dir;
+    WeProject::load(project_dir)
+        .ok()
+        .and_then(|project| project.main_file(project_dir))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| project_dir.clone())
+}
+
+fn open_directory_with_portal(path: &Path) -> Result<(), String> {
+    let file = File::open(path).map_err(|error| error.to_string())?;
+    let connection = Connection::session().map_err(|error| error.to_string())?;
+    let options: HashMap<&str, Value> = HashMap::new();
+
+    connection
+        .call_method(
+            Some(PORTAL_BUS_NAME),
+            PORTAL_OBJECT_PATH,
+            Some(OPEN_URI_INTERFACE),
+            "OpenDirectory",
+            &("", Fd::from(&file), options),
+        )
+        .map_err(|error| error.to_string())?;
+    Ok(())
+}
+
+fn find_player(path_var: Option<OsString>) -> Option<&'static str> {
+    let directories: Vec<PathBuf> = path_var
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+
+    EXTERNAL_PLAYERS.iter().copied().find(|player| {
+        directories.iter().any(|directory| {
+            fs::metadata(directory.join(player))
+                .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+                .unwrap_or(false)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn executable(path: &Path) {
+        fs::write(path, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn external_player_prefers_mpv_and_falls_back_to_vlc() {
+        let root = std::env::temp_dir().join(format!("lwe-player-{}", std::process::id()));
+        let (first, second) = (root.join("a"), root.join("b"));
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        let path_var = || Some(std::env::join_paths([&first, &second]).unwrap());
+
+        assert_eq!(find_player(path_var()), None);
+
+        executable(&first.join("vlc"));
+        assert_eq!(find_player(path_var()), Some("vlc"));
+
+        executable(&second.join("mpv"));
+        assert_eq!(find_player(path_var()), Some("mpv"));
+
+        // Files without the executable bit are not players
+        fs::remove_file(second.join("mpv")).unwrap();
+        fs::write(second.join("mpv"), "").unwrap();
+        assert_eq!(find_player(path_var()), Some("vlc"));
+        assert_eq!(find_player(None), None);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
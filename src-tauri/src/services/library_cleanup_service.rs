@@ -0,0 +1,284 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lwe_library::{LibraryDatabase, WallpaperItem};
+
+use crate::models::{LibraryDeleteEstimate, LibraryDeleteEstimateItem, LibraryDeleteMode};
+use crate::results::settings_persistence::{SettingsPersistenceLoad, SettingsPersistenceWrite};
+use crate::results::workshop::AssessedWorkshopCatalogEntry;
+use crate::services::item_open_service::item_file;
+use crate::services::library_service::LibraryService;
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+
+const WORKSHOP_CONTENT_COMPONENTS: [&str; 3] = ["steamapps", "workshop", "content"];
+/// Folders next to `workshop/content` where Steam keeps download copies
+const WORKSHOP_CACHE_FOLDERS: [&str; 2] = ["downloads", "temp"];
+
+/// Outcome of deleting several library items
+#[derive(Debug, Default)]
+pub struct LibraryDeleteReport {
+    pub deleted: usize,
+    pub freed_bytes: u64,
+    pub failures: Vec<String>,
+}
+
+/// Reclaims disk space held by library items
+pub struct LibraryCleanupService;
+
+impl LibraryCleanupService {
+    /// Disk space each delete mode would free for `item_ids`
+    pub fn estimate(item_ids: &[String]) -> Result<LibraryDeleteEstimate, String> {
+        let entries = resolve_items(item_ids)?;
+        let database = open_database();
+
+        let items: Vec<LibraryDeleteEstimateItem> = entries
+            .iter()
+            .map(|entry| LibraryDeleteEstimateItem {
+                id: entry.entry.library_item_id.clone().unwrap_or_default(),
+                title: entry.entry.title.clone(),
+                local_file_bytes: dir_size(&entry.entry.project_dir),
+                workshop_cache_bytes: cache_bytes(entry, database.as_ref()),
+            })
+            .collect();
+
+        Ok(LibraryDeleteEstimate {
+            local_file_bytes: items.iter().map(|item| item.local_file_bytes).sum(),
+            workshop_cache_bytes: items.iter().map(|item| item.workshop_cache_bytes).sum(),
+            items,
+        })
+    }
+
+    /// Removes `item_ids` as `mode` says. Items are handled one by one, so
+    /// one failure does not stop the rest.
+    pub fn delete(
+        item_ids: &[String],
+        mode: LibraryDeleteMode,
+    ) -> Result<LibraryDeleteReport, String> {
+        let entries = resolve_items(item_ids)?;
+
+        if mode == LibraryDeleteMode::RemoveFromLibrary {
+            hide_items(item_ids)?;
+            return Ok(LibraryDeleteReport {
+                deleted: entries.len(),
+                ..Default::default()
+            });
+        }
+
+        let database = open_database();
+        let mut report = LibraryDeleteReport::default();
+        for entry in &entries {
+            let freed = match mode {
+                LibraryDeleteMode::DeleteLocalFiles => delete_local_files(entry, database.as_ref()),
+                _ => clear_workshop_cache(entry, database.as_ref()),
+            };
+            match freed {
+                Ok(bytes) => {
+                    report.deleted += 1;
+                    report.freed_bytes += bytes;
+                }
+                Err(reason) => report.failures.push(reason),
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Human-readable size in binary units, such as `1.5 GiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+fn resolve_items(item_ids: &[String]) -> Result<Vec<AssessedWorkshopCatalogEntry>, String> {
+    if item_ids.is_empty() {
+        return Err("No library items selected".to_string());
+    }
+
+    let projection = LibraryService::load_projection()?;
+    item_ids
+        .iter()
+        .map(|item_id| LibraryService::inspect_item_in_projection(&projection, item_id))
+        .collect()
+}
+
+fn hide_items(item_ids: &[String]) -> Result<(), String> {
+    let persistence = SettingsPersistenceService::for_user_path()?;
+    let mut settings = match persistence.load_settings() {
+        SettingsPersistenceLoad::Loaded(settings) => settings,
+        SettingsPersistenceLoad::Unavailable { reason } => return Err(reason),
+    };
+
+    for item_id in item_ids {
+        if !settings.library.hidden_items.contains(item_id) {
+            settings.library.hidden_items.push(item_id.clone());
+        }
+    }
+
+    match persistence.save_settings(&settings) {
+        SettingsPersistenceWrite::Saved => Ok(()),
+        SettingsPersistenceWrite::Unavailable { reason } => Err(reason),
+    }
+}
+
+/// Deletes the item's Workshop folder along with its cached copies
+fn delete_local_files(
+    entry: &AssessedWorkshopCatalogEntry,
+    database: Option<&LibraryDatabase>,
+) -> Result<u64, String> {
+    let project_dir = &entry.entry.project_dir;
+    if !is_workshop_content_dir(project_dir) {
+        return Err(format!(
+            "{} is not a Workshop download, so LWE does not delete it",
+            project_dir.display()
+        ));
+    }
+
+    // Thumbnails are keyed by the main file, so clear them while it exists
+    let cache = clear_workshop_cache(entry, database)?;
+    let files = dir_size(project_dir);
+    fs::remove_dir_all(project_dir)
+        .map_err(|error| format!("Failed to delete {}: {error}", project_dir.display()))?;
+
+    Ok(cache + files)
+}
+
+/// Deletes Steam's download copies of the item and LWE's stored thumbnails
+fn clear_workshop_cache(
+    entry: &AssessedWorkshopCatalogEntry,
+    database: Option<&LibraryDatabase>,
+) -> Result<u64, String> {
+    let mut freed = 0;
+
+    for dir in workshop_cache_dirs(&entry.entry.project_dir) {
+        let size = dir_size(&dir);
+        fs::remove_dir_all(&dir)
+            .map_err(|error| format!("Failed to delete {}: {error}", dir.display()))?;
+        freed += size;
+    }
+
+    if let Some(database) = database {
+        let wallpaper_id = WallpaperItem::generate_id(&item_file(entry));
+        freed += database.thumbnail_bytes(&wallpaper_id).unwrap_or(0);
+        database.delete_thumbnails(&wallpaper_id).map_err(|error| {
+            format!(
+                "Failed to delete thumbnails of {}: {error:#}",
+                entry.entry.title
+            )
+        })?;
+    }
+
+    Ok(freed)
+}
+
+fn cache_bytes(entry: &AssessedWorkshopCatalogEntry, database: Option<&LibraryDatabase>) -> u64 {
+    let stored = database
+        .and_then(|database| {
+            database
+                .thumbnail_bytes(&WallpaperItem::generate_id(&item_file(entry)))
+                .ok()
+        })
+        .unwrap_or(0);
+
+    workshop_cache_dirs(&entry.entry.project_dir)
+        .iter()
+        .map(|dir| dir_size(dir))
+        .sum::<u64>()
+        + stored
+}
+
+fn open_database() -> Option<LibraryDatabase> {
+    LibraryDatabase::open(LibraryDatabase::default_path())
+        .map_err(|error| eprintln!("failed to open library database: {error:#}"))
+        .ok()
+}
+
+/// `steamapps/workshop/content/<app>/<item>`
+fn is_workshop_content_dir(dir: &Path) -> bool {
+    dir.parent().and_then(Path::parent).is_some_and(|content| {
+        content.ends_with(WORKSHOP_CONTENT_COMPONENTS.iter().collect::<PathBuf>())
+    })
+}
+
+/// Existing `steamapps/workshop/{downloads,temp}/<app>/<item>` folders of a
+/// Workshop download
+fn workshop_cache_dirs(project_dir: &Path) -> Vec<PathBuf> {
+    if !is_workshop_content_dir(project_dir) {
+        return Vec::new();
+    }
+    let (Some(item), Some(app_dir)) = (project_dir.file_name(), project_dir.parent()) else {
+        return Vec::new();
+    };
+    let (Some(app), Some(workshop)) =
+        (app_dir.file_name(), app_dir.parent().and_then(Path::parent))
+    else {
+        return Vec::new();
+    };
+
+    WORKSHOP_CACHE_FOLDERS
+        .iter()
+        .map(|folder| workshop.join(folder).join(app).join(item))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// Total size of the files under `path`, without following symlinks
+fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| dir_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workshop_cache_dirs_sit_next_to_the_content_folder() {
+        let root = std::env::temp_dir().join(format!("lwe-cleanup-{}", std::process::id()));
+        let workshop = root.join("steamapps/workshop");
+        let project_dir = workshop.join("content/431960/101");
+        let download = workshop.join("downloads/431960/101");
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::create_dir_all(&download).unwrap();
+        fs::write(project_dir.join("video.mp4"), vec![0; 2048]).unwrap();
+        fs::write(download.join("video.mp4"), vec![0; 512]).unwrap();
+
+        assert!(is_workshop_content_dir(&project_dir));
+        assert!(!is_workshop_content_dir(&root.join("Videos/101")));
+        assert_eq!(workshop_cache_dirs(&project_dir), vec![download]);
+        assert!(workshop_cache_dirs(&root.join("Videos/101")).is_empty());
+        assert_eq!(dir_size(&project_dir), 2048);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn sizes_are_formatted_in_binary_units() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use lwe_library::{
+    FolderScanner, ItemMarks, LibraryDatabase, WallpaperItem, WorkshopCatalogEntry, WorkshopScanner,
+};
+
+use crate::services::library_service::LibraryService;
+
+/// Favorites, ratings, titles, tags, shaders, LUTs, usage and user property values
+/// of library items, kept in the library database. Workshop items are only written there once they are marked or
+/// applied.
+pub struct LibraryMarksService;
+
+impl LibraryMarksService {
+    /// Marks of every item that has any; empty when the database is unavailable
+    pub fn marks() -> HashMap<String, ItemMarks> {
+        match open_database().and_then(|database| {
+            database
+                .item_marks()
+                .map_err(|error| format!("Failed to read library marks: {error:#}"))
+        }) {
+            Ok(marks) => marks,
+            Err(reason) => {
+                tracing::warn!("{reason}");
+                HashMap::new()
+            }
+        }
+    }
+
+    pub fn marks_for(item_id: &str) -> ItemMarks {
+        Self::marks().remove(item_id).unwrap_or_default()
+    }
+
+    pub fn set_favorite(item_id: &str, favorite: bool) -> Result<(), String> {
+        let database = open_database()?;
+        ensure_indexed(&database, item_id)?;
+        database
+            .set_favorite(item_id, favorite)
+            .map_err(|error| format!("Failed to save favorite for {item_id}: {error:#}"))
+    }
+
+    /// Rate an item 1 to 5; 0 clears the rating
+    pub fn set_rating(item_id: &str, rating: u8) -> Result<(), String> {
+        if rating > 5 {
+            return Err(format!("Rating must be 0 to 5, not {rating}"));
+        }
+        let database = open_database()?;
+        ensure_indexed(&database, item_id)?;
+        database
+            .set_rating(item_id, rating)
+            .map_err(|error| format!("Failed to save rating for {item_id}: {error:#}"))
+    }
+
+    /// Show an item under `title`; None or a blank title restores the scanned one
+    pub fn set_title(item_id: &str, title: Option<&str>) -> Result<(), String> {
+        let title = title.map(str::trim).filter(|title| !title.is_empty());
+        let database = open_database()?;
+        ensure_indexed(&database, item_id)?;
+        database
+            .set_custom_title(item_id, title)
+            .map_err(|error| format!("Failed to save title for {item_id}: {error:#}"))
+    }
+
+    /// Replace an item's tags; blank and repeated tags are dropped
+    pub fn set_tags(item_id: &str, tags: &[String]) -> Result<(), String> {
+        let tags = normalize_tags(tags);
+        let database = open_database()?;
+        ensure_indexed(&database, item_id)?;
+        database
+            .set_wallpaper_tags(item_id, &tags)
+            .map_err(|error| format!("Failed to save tags for {item_id}: {error:#}"))
+    }
+
+    /// Add one tag to an item, keeping the ones it has
+    pub fn add_tag(item_id: &str, tag: &str) -> Result<(), String> {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err("Tags cannot be blank".to_string());
+        }
+        let database = open_database()?;
+        ensure_indexed(&database, item_id)?;
+        database
+            .add_tag_to_wallpaper(item_id, tag)
+            .map_err(|error| format!("Failed to tag {item_id}: {error:#}"))
+    }
+
+    /// Post-process an item with the GLSL shader at `shader`; None removes it
+    pub fn set_shader(item_id: &str, shader: Option<&Path>) -> Result<(), String> {
+        if let Some(shader) = shader.filter(|shader| !shader.is_file()) {
+            return Err(format!("Shader {} is not a file", shader.display()));
+        }
+        let database = open_database()?;
+        ensure_indexed(&database, item_id)?;
+        database
+            .set_shader_path(item_id, shader)
+            .map_err(|error| format!("Failed to save shader for {item_id}: {error:#}"))
+    }
+
+    /// Color grade an item with the .cube LUT at `lut`; None removes it
+    pub fn set_lut(item_id: &str, lut: Option<&Path>) -> Result<(), String> {
+        if let Some(lut) = lut.filter(|lut| !lut.is_file()) {
+            return Err(format!("LUT {} is not a file", lut.display()));
+        }
+        let database = open_database()?;
+        ensure_indexed(&database, item_id)?;
+        database
+            .set_lut_path(item_id, lut)
+            .map_err(|error| format!("Failed to save LUT for {item_id}: {error:#}"))
+    }
+
+    /// Values the user picked for an item's project.json properties; empty
+    /// when there are none or the database is unavailable
+    pub fn user_properties(item_id: &str) -> serde_json::Map<String, serde_json::Value> {
+        match open_database().and_then(|database| {
+            database
+                .get_user_properties(item_id)
+                .map_err(|error| format!("Failed to read properties of {item_id}: {error:#}"))
+        }) {
+            Ok(values) => values,
+            Err(reason) => {
+                tracing::warn!("{reason}");
+                serde_json::Map::new()
+            }
+        }
+    }
+
+    pub fn set_user_properties(
+        item_id: &str,
+        values: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<(), String> {
+        let database = open_database()?;
+        ensure_indexed(&database, item_id)?;
+        database
+            .set_user_properties(item_id, values)
+            .map_err(|error| format!("Failed to save properties for {item_id}: {error:#}"))
+    }
+
+    /// Count one more use of an item that was just applied. Failures are only
+    /// logged, since the wallpaper is already showing.
+    pub fn record_use(item_id: &str) {
+        let recorded = open_database().and_then(|database| {
+            ensure_indexed(&database, item_id)?;
+            database
+                .record_usage(item_id)
+                .map_err(|error| format!("{error:#}"))
+        });
+        if let Err(reason) = recorded {
+            tracing::warn!("Failed to record use of {item_id}: {reason}");
+        }
+    }
+}
+
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|tag| tag.trim()) {
+        if !tag.is_empty() && !normalized.iter().any(|kept| kept == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+fn open_database() -> Result<LibraryDatabase, String> {
+    LibraryDatabase::open(LibraryDatabase::default_path())
+        .map_err(|error| format!("Unable to open the library database: {error:#}"))
+}
+
+/// Add a library item to the database if folder scans have not yet
+fn ensure_indexed(database: &LibraryDatabase, item_id: &str) -> Result<(), String> {
+    let indexed = database
+        .get_wallpaper(item_id)
+        .map_err(|error| format!("{error:#}"))?;
+    if indexed.is_some() {
+        return Ok(());
+    }
+
+    let entry = LibraryService::inspect_item(item_id)?;
+    let mut item = indexed_item(&entry.entry)?
+        .ok_or_else(|| format!("Library item {item_id} has no playable file"))?;
+    // Catalog ids come from the same path hash, but the library id is what
+    // the pages and commands know the item by
+    item.id = item_id.to_string();
+    database
+        .upsert_wallpaper(&item)
+        .map_err(|error| format!("Failed to add {item_id} to the library database: {error:#}"))
+}
+
+fn indexed_item(entry: &WorkshopCatalogEntry) -> Result<Option<WallpaperItem>, String> {
+    if LibraryService::is_local(entry) {
+        return Ok(FolderScanner::new().process_file(&entry.project_dir));
+    }
+    WorkshopScanner::discover()
+        .and_then(|scanner| scanner.get_item(entry.workshop_id))
+        .map_err(|error| format!("{error:#}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_tags_trims_and_drops_blank_and_repeated_tags() {
+        let tags = ["  rain ", "", "city", "rain", "   "].map(String::from);
+
+        assert_eq!(
+            normalize_tags(&tags),
+            vec!["rain".to_string(), "city".to_string()]
+        );
+    }
+}
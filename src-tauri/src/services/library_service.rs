@@ -1,10 +1,29 @@
+use std::path::{Path, PathBuf};
+
+use lwe_core::hdr::TransferFunction;
+use lwe_engine::is_live_source;
+use lwe_library::{
+    probe_media, FolderScanner, SourceType, WallpaperItem, WallpaperType, WorkshopCatalogEntry,
+    WorkshopProjectType, WorkshopSyncState,
+};
+
+use crate::models::LibraryItemMedia;
 use crate::results::desktop::DesktopPageResult;
 use crate::results::library::LibraryProjection;
+use crate::results::settings_persistence::{
+    PersistedLibrary, SettingsPersistenceLoad, SettingsPersistenceWrite,
+};
 use crate::results::workshop::{AssessedWorkshopCatalogEntry, WorkshopRefreshResult};
 use crate::services::compatibility_service::CompatibilityService;
 use crate::services::desktop_service::LIBRARY_RESOLUTION_ISSUE_PREFIX;
+use crate::services::item_open_service::item_file;
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+use crate::services::stream_service::StreamService;
 use crate::services::workshop_service::WorkshopService;
 
+/// Workshop id of files added from local folders; Steam never hands out 0
+pub(crate) const LOCAL_WORKSHOP_ID: u64 = 0;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LibraryDesktopStatus {
     pub monitors_available: bool,
@@ -35,10 +54,140 @@ impl LibraryService {
         }
     }
 
+    /// Workshop items plus the files in `[library] folders` and `files` and
+    /// the cached `[streams]`. The Workshop may be missing when local files
+    /// were added.
     pub fn load_projection() -> Result<LibraryProjection, String> {
-        Ok(Self::projection_from_refresh(
-            WorkshopService::refresh_catalog()?,
-        ))
+        let library = Self::library_settings();
+        let mut streams = StreamService::cached_streams();
+        streams.extend(
+            StreamService::live_streams()
+                .into_iter()
+                .map(|url| (PathBuf::from(&url), url)),
+        );
+        let mut projection = match WorkshopService::refresh_catalog() {
+            Ok(refresh) => Self::projection_from_refresh(refresh),
+            Err(reason)
+                if library.folders.is_empty() && library.files.is_empty() && streams.is_empty() =>
+            {
+                return Err(reason)
+            }
+            Err(reason) => {
+                eprintln!("showing local files only: {reason}");
+                LibraryProjection {
+                    entries: Vec::new(),
+                    source_catalog_count: 0,
+                }
+            }
+        };
+
+        let local = Self::local_entries(&library, streams);
+        projection.source_catalog_count += local.len();
+        projection.entries.extend(local);
+        Self::hide_items(&mut projection, &library.hidden_items);
+        Ok(projection)
+    }
+
+    /// Videos and images from the added folders and files, once each, and
+    /// the downloaded streams under their titles; cameras and live feeds are
+    /// listed by their URLs
+    fn local_entries(
+        library: &PersistedLibrary,
+        streams: Vec<(PathBuf, String)>,
+    ) -> Vec<AssessedWorkshopCatalogEntry> {
+        let scanner = FolderScanner::new();
+        let mut items = Vec::new();
+        for folder in &library.folders {
+            match scanner.scan_folder_parallel(folder, true) {
+                Ok(found) => items.extend(found),
+                Err(error) => eprintln!("failed to scan {}: {error:#}", folder.display()),
+            }
+        }
+        items.extend(
+            library
+                .files
+                .iter()
+                .filter_map(|file| scanner.process_file(file)),
+        );
+        items.extend(streams.into_iter().filter_map(|(path, title)| {
+            if is_live_source(&path) {
+                return Some(WallpaperItem::new(
+                    path,
+                    title,
+                    SourceType::LocalFile,
+                    WallpaperType::Video,
+                ));
+            }
+            let mut item = scanner.process_file(&path)?;
+            item.name = title;
+            Some(item)
+        }));
+
+        let mut seen = std::collections::HashSet::new();
+        items.retain(|item| seen.insert(item.id.clone()));
+        items.sort_by(|a, b| a.name.cmp(&b.name));
+        CompatibilityService::assess_catalog_entries(
+            items.into_iter().filter_map(local_catalog_entry).collect(),
+        )
+    }
+
+    pub(crate) fn is_local(entry: &WorkshopCatalogEntry) -> bool {
+        entry.workshop_id == LOCAL_WORKSHOP_ID
+    }
+
+    /// Remembers picked folders and files under `[library]` and returns how
+    /// many were new
+    pub fn add_local_sources(folders: Vec<PathBuf>, files: Vec<PathBuf>) -> Result<usize, String> {
+        let persistence = SettingsPersistenceService::for_user_path()?;
+        let mut settings = match persistence.load_settings() {
+            SettingsPersistenceLoad::Loaded(settings) => settings,
+            SettingsPersistenceLoad::Unavailable { reason } => return Err(reason),
+        };
+
+        let added = add_new_paths(&mut settings.library.folders, folders)
+            + add_new_paths(&mut settings.library.files, files);
+        if added == 0 {
+            return Ok(0);
+        }
+        match persistence.save_settings(&settings) {
+            SettingsPersistenceWrite::Saved => Ok(added),
+            SettingsPersistenceWrite::Unavailable { reason } => Err(reason),
+        }
+    }
+
+    /// Drops items removed from the library; their catalog count is kept
+    pub fn hide_items(projection: &mut LibraryProjection, hidden_items: &[String]) {
+        projection.entries.retain(|entry| {
+            !entry
+                .entry
+                .library_item_id
+                .as_ref()
+                .is_some_and(|id| hidden_items.contains(id))
+        });
+    }
+
+    /// Item ids listed in `[library] hidden_items`
+    pub fn hidden_items() -> Vec<String> {
+        Self::library_settings().hidden_items
+    }
+
+    /// Folders listed in `[library] folders`
+    pub fn folders() -> Vec<PathBuf> {
+        Self::library_settings().folders
+    }
+
+    pub(crate) fn library_settings() -> PersistedLibrary {
+        let loaded = SettingsPersistenceService::for_user_path()
+            .map(|service| service.load_settings())
+            .unwrap_or_else(|reason| SettingsPersistenceLoad::Unavailable { reason });
+
+        match loaded {
+            SettingsPersistenceLoad::Loaded(settings) => settings.library,
+            SettingsPersistenceLoad::Unavailable { reason } => {
+                eprintln!("library settings unavailable: {reason}");
+                PersistedLibrary::default()
+            }
+        }
     }
 
     pub fn inspect_item_in_projection(
@@ -58,6 +207,54 @@ impl LibraryService {
         Self::inspect_item_in_projection(&projection, item_id)
     }
 
+    /// Write the main file of each item to `destination`, one path per line,
+    /// and return how many were written
+    pub fn export_paths(item_ids: &[String], destination: &Path) -> Result<usize, String> {
+        let projection = Self::load_projection()?;
+        let mut lines = String::new();
+        for item_id in item_ids {
+            let entry = Self::inspect_item_in_projection(&projection, item_id)?;
+            lines.push_str(&item_file(&entry).to_string_lossy());
+            lines.push('\n');
+        }
+        std::fs::write(destination, lines)
+            .map_err(|error| format!("Failed to write {}: {error}", destination.display()))?;
+        Ok(item_ids.len())
+    }
+
+    /// Size of the item's main file, plus the resolution, duration, codec,
+    /// frame rate and HDR transfer its video headers declare
+    pub fn media(entry: &AssessedWorkshopCatalogEntry) -> LibraryItemMedia {
+        let path = item_file(entry);
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            return LibraryItemMedia::default();
+        };
+        if !metadata.is_file() {
+            return LibraryItemMedia::default();
+        }
+
+        let mut media = LibraryItemMedia {
+            file_size: Some(metadata.len()),
+            ..LibraryItemMedia::default()
+        };
+        if entry.entry.project_type == WorkshopProjectType::Video {
+            let Some(info) = probe_media(&path) else {
+                return media;
+            };
+            media.width = info.resolution.map(|(width, _)| width);
+            media.height = info.resolution.map(|(_, height)| height);
+            media.duration_secs = info.duration_secs;
+            media.codec = info.codec;
+            media.fps = info.fps;
+            media.hdr = info.hdr.and_then(|transfer| match transfer {
+                TransferFunction::Pq => Some("pq".to_string()),
+                TransferFunction::Hlg => Some("hlg".to_string()),
+                TransferFunction::Srgb | TransferFunction::Unknown => None,
+            });
+        }
+        media
+    }
+
     pub fn desktop_status(desktop: &DesktopPageResult) -> LibraryDesktopStatus {
         LibraryDesktopStatus {
             monitors_available: desktop.monitors_available,
@@ -83,6 +280,38 @@ impl LibraryService {
     }
 }
 
+/// A scanned file as a library entry; the engine plays images and videos
+/// alike, so both are shown as video items
+fn local_catalog_entry(item: WallpaperItem) -> Option<WorkshopCatalogEntry> {
+    let cover_path = match item.wallpaper_type {
+        WallpaperType::Video => None,
+        WallpaperType::Image | WallpaperType::AnimatedImage => Some(item.source_path.clone()),
+        _ => return None,
+    };
+
+    Some(WorkshopCatalogEntry {
+        workshop_id: LOCAL_WORKSHOP_ID,
+        title: item.name,
+        project_type: WorkshopProjectType::Video,
+        project_dir: item.source_path,
+        cover_path,
+        sync_state: WorkshopSyncState::Synced,
+        supported_first_release: true,
+        library_item_id: Some(item.id),
+    })
+}
+
+/// Appends the paths not listed yet
+pub(crate) fn add_new_paths(paths: &mut Vec<PathBuf>, picked: Vec<PathBuf>) -> usize {
+    let before = paths.len();
+    for path in picked {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths.len() - before
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeMap;
@@ -91,7 +320,7 @@ mod tests {
     use crate::results::library::LibraryProjection;
     use crate::results::workshop::AssessedWorkshopCatalogEntry;
 
-    use super::LibraryService;
+    use super::{add_new_paths, local_catalog_entry, LibraryService};
 
     #[test]
     fn service_layer_library_service_uses_application_projection_result() {
@@ -117,6 +346,9 @@ mod tests {
             persistence_issue: Some("Desktop persistence is not available yet".to_string()),
             assignments_available: false,
             stale: true,
+            monitor_audio: BTreeMap::new(),
+            monitor_color: BTreeMap::new(),
+            monitor_applied: BTreeMap::new(),
         });
 
         assert_eq!(
@@ -149,6 +381,9 @@ mod tests {
                 persistence_issue: None,
                 assignments_available: true,
                 stale: false,
+                monitor_audio: BTreeMap::new(),
+                monitor_color: BTreeMap::new(),
+                monitor_applied: BTreeMap::new(),
             },
             "scene-7",
         );
@@ -175,6 +410,9 @@ mod tests {
             persistence_issue: None,
             assignments_available: true,
             stale: true,
+            monitor_audio: BTreeMap::new(),
+            monitor_color: BTreeMap::new(),
+            monitor_applied: BTreeMap::new(),
         });
 
         assert_eq!(
@@ -225,4 +463,77 @@ mod tests {
         assert_eq!(item.entry.library_item_id.as_deref(), Some("scene-7"));
         assert_eq!(item.entry.title, "Forest Scene");
     }
+
+    #[test]
+    fn service_layer_library_service_hides_removed_items() {
+        let entry = |workshop_id: u64, item_id: &str| {
+            let catalog_entry = lwe_library::WorkshopCatalogEntry {
+                workshop_id,
+                title: item_id.to_string(),
+                project_type: lwe_library::WorkshopProjectType::Video,
+                project_dir: std::path::PathBuf::from(format!("/tmp/{workshop_id}")),
+                cover_path: None,
+                sync_state: lwe_library::WorkshopSyncState::Synced,
+                supported_first_release: true,
+                library_item_id: Some(item_id.to_string()),
+            };
+            AssessedWorkshopCatalogEntry {
+                compatibility:
+                    crate::policies::shared::compatibility_policy::compatibility_decision(
+                        &catalog_entry,
+                    ),
+                entry: catalog_entry,
+                project_metadata: Default::default(),
+            }
+        };
+        let mut projection = LibraryProjection {
+            entries: vec![entry(1, "video-1"), entry(2, "video-2")],
+            source_catalog_count: 2,
+        };
+
+        LibraryService::hide_items(&mut projection, &["video-1".to_string()]);
+
+        assert_eq!(projection.entries.len(), 1);
+        assert_eq!(
+            projection.entries[0].entry.library_item_id.as_deref(),
+            Some("video-2")
+        );
+        assert_eq!(projection.source_catalog_count, 2);
+    }
+
+    #[test]
+    fn service_layer_library_service_lists_local_files_as_playable_items() {
+        let still = lwe_library::WallpaperItem::new(
+            std::path::PathBuf::from("/home/me/Pictures/dunes.jpg"),
+            "dunes".to_string(),
+            lwe_library::SourceType::LocalFile,
+            lwe_library::WallpaperType::Image,
+        );
+        let entry = local_catalog_entry(still.clone()).unwrap();
+
+        assert!(LibraryService::is_local(&entry));
+        assert_eq!(entry.title, "dunes");
+        assert_eq!(entry.project_type, lwe_library::WorkshopProjectType::Video);
+        assert_eq!(entry.project_dir, still.source_path);
+        assert_eq!(entry.cover_path.as_ref(), Some(&still.source_path));
+        assert_eq!(entry.library_item_id, Some(still.id));
+        assert!(
+            crate::services::compatibility_service::CompatibilityService::supports_library_projection(
+                &crate::services::compatibility_service::CompatibilityService::assess_catalog_entry(
+                    entry
+                )
+            )
+        );
+
+        let mut folders = vec![std::path::PathBuf::from("/home/me/Videos")];
+        let added = add_new_paths(
+            &mut folders,
+            vec![
+                std::path::PathBuf::from("/home/me/Videos"),
+                std::path::PathBuf::from("/media/walls"),
+            ],
+        );
+        assert_eq!(added, 1);
+        assert_eq!(folders.len(), 2);
+    }
 }
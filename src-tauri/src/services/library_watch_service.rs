@@ -0,0 +1,133 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use lwe_library::{AsyncFileWatcher, FileEvent, LibraryDatabase};
+
+use crate::services::library_service::LibraryService;
+
+/// Changes closer together than this reach the library as one batch, so a
+/// copy of many files refreshes the grid once
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(750);
+const WATCH_BUFFER: usize = 256;
+
+/// Called with the paths that changed in each batch, after the library
+/// database was updated
+pub type LibraryChangeSink = Box<dyn Fn(Vec<PathBuf>) + Send + Sync>;
+
+fn file_watcher() -> &'static OnceLock<Arc<AsyncFileWatcher>> {
+    static WATCHER: OnceLock<Arc<AsyncFileWatcher>> = OnceLock::new();
+    &WATCHER
+}
+
+/// Keeps the library database and the library page in step with files
+/// added, removed or renamed in `[library] folders`.
+pub struct LibraryWatchService;
+
+impl LibraryWatchService {
+    pub fn start(on_change: LibraryChangeSink) {
+        let watcher = match AsyncFileWatcher::new(WATCH_BUFFER, WATCH_DEBOUNCE) {
+            Ok(watcher) => Arc::new(watcher),
+            Err(error) => {
+                eprintln!("library folders will not be watched: {error:#}");
+                return;
+            }
+        };
+        if file_watcher().set(watcher.clone()).is_err() {
+            eprintln!("library watcher was already started");
+            return;
+        }
+
+        tauri::async_runtime::spawn(async move {
+            // Indexing the folders reads every file, so keep it off the runtime
+            let indexed = tauri::async_runtime::spawn_blocking(|| {
+                Self::watch_folders(&LibraryService::folders())
+            })
+            .await;
+            if let Err(error) = indexed {
+                eprintln!("failed to index library folders: {error}");
+            }
+
+            while let Some(event) = watcher.recv().await {
+                let mut events = vec![event];
+                while let Some(event) = watcher.try_recv() {
+                    events.push(event);
+                }
+                let changed = apply_events(&watcher, &events);
+                if !changed.is_empty() {
+                    on_change(changed);
+                }
+            }
+        });
+    }
+
+    /// Watch folders that were just added to the library and bring their
+    /// files into the database. Does nothing before [`Self::start`].
+    pub fn watch_folders(folders: &[PathBuf]) {
+        let Some(watcher) = file_watcher().get() else {
+            return;
+        };
+        for folder in folders {
+            if watcher.is_watching(folder) {
+                continue;
+            }
+            if let Err(error) = watcher.add_path(folder.clone(), true) {
+                eprintln!("failed to watch {}: {error:#}", folder.display());
+                continue;
+            }
+            if let Err(reason) = index_folder(watcher, folder) {
+                eprintln!("{reason}");
+            }
+        }
+    }
+}
+
+/// Catch up on changes made while nothing was watching
+fn index_folder(watcher: &AsyncFileWatcher, folder: &Path) -> Result<(), String> {
+    let database = open_database()?;
+    let items = watcher
+        .scanner()
+        .scan_folder_parallel(folder, true)
+        .map_err(|error| format!("Failed to scan {}: {error:#}", folder.display()))?;
+    database
+        .add_folder(folder, true)
+        .and_then(|()| {
+            items
+                .iter()
+                .try_for_each(|item| database.upsert_wallpaper(item))
+        })
+        .and_then(|()| database.prune_folder(folder))
+        .map(|_| ())
+        .map_err(|error| format!("Failed to index {}: {error:#}", folder.display()))
+}
+
+/// Update the database for one batch and return the paths that changed
+fn apply_events(watcher: &AsyncFileWatcher, events: &[FileEvent]) -> Vec<PathBuf> {
+    let database = match open_database() {
+        Ok(database) => Some(database),
+        Err(reason) => {
+            eprintln!("{reason}");
+            None
+        }
+    };
+    let mut changed = Vec::new();
+    for event in events {
+        if let Some(database) = &database {
+            if let Err(error) = database.apply_file_event(event, watcher.scanner()) {
+                eprintln!("failed to update the library for {event:?}: {error:#}");
+            }
+        }
+        match event {
+            FileEvent::Created(path) | FileEvent::Modified(path) | FileEvent::Deleted(path) => {
+                changed.push(path.clone())
+            }
+            FileEvent::Renamed { from, to } => changed.extend([from.clone(), to.clone()]),
+        }
+    }
+    changed
+}
+
+fn open_database() -> Result<LibraryDatabase, String> {
+    LibraryDatabase::open(LibraryDatabase::default_path())
+        .map_err(|error| format!("Failed to open the library database: {error:#}"))
+}
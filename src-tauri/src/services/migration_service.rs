@@ -0,0 +1,668 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lwe_library::FolderScanner;
+
+use crate::results::desktop_persistence::DesktopPersistenceWrite;
+use crate::results::migration::{MigratedWallpaper, MigrationReport, MigrationSource};
+use crate::results::monitor_discovery::MonitorDiscoveryResult;
+use crate::results::settings_persistence::{SettingsPersistenceLoad, SettingsPersistenceWrite};
+use crate::services::desktop_persistence_service::DesktopPersistenceService;
+use crate::services::library_service::LibraryService;
+use crate::services::monitor_service::MonitorService;
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+
+/// Compositor configs, under the config directory, that usually start mpvpaper
+const MPVPAPER_LAUNCHERS: [&str; 3] = ["hypr/hyprland.conf", "sway/config", "niri/config.kdl"];
+
+/// Carries wallpapers set up in mpvpaper, swww or hyprpaper over to LWE: the
+/// files join the Library, each output shows what it showed before and the
+/// way the tool scaled it becomes the output's `[outputs]` layout.
+pub struct MigrationService;
+
+impl MigrationService {
+    /// Wallpapers set up in `file`, or in each tool's usual place when no
+    /// file is given. A swww `file` is its cache directory.
+    pub fn discover(
+        source: Option<MigrationSource>,
+        file: Option<&Path>,
+    ) -> Result<Vec<MigratedWallpaper>, String> {
+        let dirs = Dirs::from_env();
+        if let Some(file) = file {
+            let source = source.ok_or_else(|| "Name the tool the file belongs to".to_string())?;
+            return read_source(source, file, &dirs);
+        }
+
+        let mut found = Vec::new();
+        for candidate in MigrationSource::ALL {
+            if source.is_some_and(|source| source != candidate) {
+                continue;
+            }
+            for path in dirs.default_paths(candidate) {
+                if path.exists() {
+                    found.extend(read_source(candidate, &path, &dirs)?);
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Add the wallpapers to the Library, fill in missing `[outputs]`
+    /// settings and put each wallpaper on its monitors. Settings that are
+    /// already set are kept; `dry_run` only reports what would change.
+    pub fn import(
+        wallpapers: &[MigratedWallpaper],
+        dry_run: bool,
+    ) -> Result<MigrationReport, String> {
+        let scanner = FolderScanner::new();
+        let mut report = MigrationReport::default();
+        let mut items = Vec::new();
+        for wallpaper in wallpapers {
+            let path = &wallpaper.path;
+            if path.is_dir() {
+                push_new(&mut report.folders, path.clone());
+            } else if let Some(item) = path.is_file().then(|| scanner.process_file(path)).flatten()
+            {
+                push_new(&mut report.files, path.clone());
+                items.push((wallpaper, item.id));
+            } else if path.exists() {
+                report.notes.push(format!(
+                    "{}: {} is not a video or image LWE can play",
+                    wallpaper.origin,
+                    path.display()
+                ));
+            } else {
+                report.notes.push(format!(
+                    "{}: {} does not exist",
+                    wallpaper.origin,
+                    path.display()
+                ));
+            }
+        }
+        if items.is_empty() {
+            return Self::finish(report, BTreeMap::new(), BTreeMap::new(), dry_run);
+        }
+
+        let monitors = match MonitorService::list_monitors() {
+            MonitorDiscoveryResult::Known(monitors) => monitors,
+            MonitorDiscoveryResult::Unavailable { reason } => {
+                report.notes.push(format!(
+                    "The wallpapers were added to the Library but not put on monitors: {reason}"
+                ));
+                Vec::new()
+            }
+        };
+
+        // Later setups win, as they would when the tools ran in that order
+        let mut assignments = BTreeMap::new();
+        let mut output_settings = BTreeMap::new();
+        for (wallpaper, item_id) in &items {
+            let targets = monitors
+                .iter()
+                .filter(|monitor| {
+                    wallpaper
+                        .output
+                        .as_ref()
+                        .is_none_or(|output| *output == monitor.backend_output_id)
+                })
+                .collect::<Vec<_>>();
+            if targets.is_empty() && !monitors.is_empty() {
+                report.notes.push(format!(
+                    "{}: {} is not connected; {} was only added to the Library",
+                    wallpaper.origin,
+                    wallpaper.output.as_deref().unwrap_or_default(),
+                    wallpaper.path.display()
+                ));
+            }
+            for monitor in targets {
+                assignments.insert(
+                    monitor.id.clone(),
+                    (
+                        monitor.backend_output_id.clone(),
+                        item_id.clone(),
+                        wallpaper.path.clone(),
+                    ),
+                );
+                output_settings.insert(
+                    monitor.backend_output_id.clone(),
+                    (wallpaper.layout, wallpaper.muted),
+                );
+            }
+        }
+
+        Self::finish(report, assignments, output_settings, dry_run)
+    }
+
+    fn finish(
+        mut report: MigrationReport,
+        assignments: BTreeMap<String, (String, String, PathBuf)>,
+        output_settings: BTreeMap<String, (Option<&'static str>, Option<bool>)>,
+        dry_run: bool,
+    ) -> Result<MigrationReport, String> {
+        report.assignments = assignments
+            .values()
+            .map(|(output, _, path)| (output.clone(), path.clone()))
+            .collect();
+
+        let persistence = SettingsPersistenceService::for_user_path()?;
+        let mut settings = match persistence.load_settings() {
+            SettingsPersistenceLoad::Loaded(settings) => settings,
+            SettingsPersistenceLoad::Unavailable { reason } => return Err(reason),
+        };
+        for (output, (layout, muted)) in output_settings {
+            let entry = settings.outputs.entry(output.clone()).or_default();
+            let mut changed = false;
+            if entry.layout.is_none() && layout.is_some() {
+                entry.layout = layout.map(str::to_string);
+                changed = true;
+            }
+            if entry.muted.is_none() && muted.is_some() {
+                entry.muted = muted;
+                changed = true;
+            }
+            if changed {
+                report.configured_outputs.push(output);
+            }
+        }
+        if dry_run {
+            return Ok(report);
+        }
+
+        LibraryService::add_local_sources(report.folders.clone(), report.files.clone())?;
+        if !report.configured_outputs.is_empty() {
+            if let SettingsPersistenceWrite::Unavailable { reason } =
+                persistence.save_settings(&settings)
+            {
+                return Err(reason);
+            }
+        }
+        if !assignments.is_empty() {
+            let desktop = DesktopPersistenceService::for_user_path()?;
+            for (monitor_id, (_, item_id, _)) in &assignments {
+                if let DesktopPersistenceWrite::Unavailable { reason } =
+                    desktop.save_assignment(monitor_id, item_id)
+                {
+                    return Err(reason);
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+fn read_source(
+    source: MigrationSource,
+    path: &Path,
+    dirs: &Dirs,
+) -> Result<Vec<MigratedWallpaper>, String> {
+    if source == MigrationSource::Swww {
+        return read_swww_cache(path, dirs, 0);
+    }
+
+    let text = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+    let origin = path.display().to_string();
+    Ok(match source {
+        MigrationSource::Hyprpaper => parse_hyprpaper(&text, &origin, dirs),
+        _ => parse_mpvpaper(&text, &origin, dirs),
+    })
+}
+
+/// swww keeps one file per output, named after it; newer releases put them
+/// in a folder per swww version
+fn read_swww_cache(
+    dir: &Path,
+    dirs: &Dirs,
+    depth: usize,
+) -> Result<Vec<MigratedWallpaper>, String> {
+    let entries =
+        fs::read_dir(dir).map_err(|error| format!("Failed to read {}: {error}", dir.display()))?;
+    let mut found = Vec::new();
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        if path.is_dir() {
+            if depth == 0 {
+                found.extend(read_swww_cache(&path, dirs, depth + 1)?);
+            }
+            continue;
+        }
+        let (Some(output), Ok(contents)) = (
+            path.file_name().and_then(|name| name.to_str()),
+            fs::read(&path),
+        ) else {
+            continue;
+        };
+        found.extend(parse_swww_cache(
+            output,
+            &contents,
+            &path.display().to_string(),
+            dirs,
+        ));
+    }
+    found.sort_by(|a, b| a.output.cmp(&b.output));
+    Ok(found)
+}
+
+/// `wallpaper = DP-1,contain:~/walls/a.png` lines and `wallpaper { ... }`
+/// blocks; an empty monitor means every output
+fn parse_hyprpaper(text: &str, origin: &str, dirs: &Dirs) -> Vec<MigratedWallpaper> {
+    let mut found = Vec::new();
+    let mut block: Option<(usize, BTreeMap<String, String>)> = None;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if let Some((start, fields)) = &mut block {
+            if line == "}" {
+                let start = *start;
+                let fields = std::mem::take(fields);
+                block = None;
+                if let Some(path) = fields.get("path") {
+                    found.push(hyprpaper_wallpaper(
+                        format!("{origin}:{start}"),
+                        fields.get("monitor").map_or("", String::as_str),
+                        path,
+                        fields.get("fit_mode").map(String::as_str),
+                        dirs,
+                    ));
+                }
+            } else if let Some((key, value)) = line.split_once('=') {
+                fields.insert(key.trim().to_string(), value.trim().to_string());
+            }
+            continue;
+        }
+
+        if line.starts_with("wallpaper") && line.ends_with('{') {
+            block = Some((index + 1, BTreeMap::new()));
+            continue;
+        }
+        let Some(("wallpaper", value)) =
+            line.split_once('=').map(|(key, value)| (key.trim(), value))
+        else {
+            continue;
+        };
+        let Some((monitor, path)) = value.split_once(',') else {
+            continue;
+        };
+        let path = path.trim();
+        let (fit, path) = match path.split_once(':') {
+            Some((fit @ ("contain" | "tile"), path)) => (Some(fit), path),
+            _ => (None, path),
+        };
+        found.push(hyprpaper_wallpaper(
+            format!("{origin}:{}", index + 1),
+            monitor,
+            path,
+            fit,
+            dirs,
+        ));
+    }
+    found
+}
+
+fn hyprpaper_wallpaper(
+    origin: String,
+    monitor: &str,
+    path: &str,
+    fit: Option<&str>,
+    dirs: &Dirs,
+) -> MigratedWallpaper {
+    let monitor = monitor.trim();
+    MigratedWallpaper {
+        source: MigrationSource::Hyprpaper,
+        origin,
+        output: (!monitor.is_empty()).then(|| monitor.to_string()),
+        path: dirs.expand(path.trim()),
+        // hyprpaper covers the output unless told otherwise; tiling has no
+        // LWE layout and keeps the default
+        layout: match fit {
+            None | Some("cover") => Some("fill"),
+            Some("contain") => Some("contain"),
+            Some("fill") => Some("stretch"),
+            Some(_) => None,
+        },
+        muted: None,
+    }
+}
+
+/// mpvpaper commands in shell scripts and compositor configs, such as
+/// `exec-once = mpvpaper -o "no-audio loop" DP-1 ~/walls/rain.mp4`
+fn parse_mpvpaper(text: &str, origin: &str, dirs: &Dirs) -> Vec<MigratedWallpaper> {
+    let mut found = Vec::new();
+    let mut lines = text.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let mut line = line.trim().to_string();
+        // Shell line continuations
+        while line.ends_with('\\') {
+            line.pop();
+            match lines.next() {
+                Some((_, next)) => line.push_str(next.trim()),
+                None => break,
+            }
+        }
+        if line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+
+        for command in shell_commands(&line) {
+            let Some(start) = command.iter().position(|word| {
+                let program = word
+                    .rsplit_once('=')
+                    .map_or(word.as_str(), |(_, program)| program);
+                Path::new(program)
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    == Some("mpvpaper")
+            }) else {
+                continue;
+            };
+            if let Some(wallpaper) = mpvpaper_wallpaper(
+                &command[start + 1..],
+                format!("{origin}:{}", index + 1),
+                dirs,
+            ) {
+                found.push(wallpaper);
+            }
+        }
+    }
+    found
+}
+
+fn mpvpaper_wallpaper(args: &[String], origin: String, dirs: &Dirs) -> Option<MigratedWallpaper> {
+    let mut positional = Vec::new();
+    let mut mpv_options = String::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if let Some(options) = arg.strip_prefix("--mpv-options=") {
+            mpv_options = options.to_string();
+            continue;
+        }
+        match arg.as_str() {
+            "-o" | "--mpv-options" => mpv_options = args.next()?.clone(),
+            "-l" | "--layer" | "-n" | "--slideshow" => {
+                args.next();
+            }
+            option if option.starts_with('-') && option.len() > 1 => {}
+            word => positional.push(word),
+        }
+    }
+    let [output, path] = positional[..] else {
+        return None;
+    };
+
+    // mpv letterboxes by default
+    let options = mpv_options
+        .split_whitespace()
+        .map(|option| option.trim_start_matches("--"))
+        .collect::<Vec<_>>();
+    let layout = if options
+        .iter()
+        .any(|option| matches!(*option, "panscan=1" | "panscan=1.0"))
+    {
+        "fill"
+    } else if options.contains(&"keepaspect=no") {
+        "stretch"
+    } else {
+        "contain"
+    };
+    let muted = options
+        .iter()
+        .any(|option| {
+            matches!(
+                *option,
+                "no-audio" | "mute" | "mute=yes" | "volume=0" | "audio=no" | "aid=no"
+            )
+        })
+        .then_some(true);
+
+    Some(MigratedWallpaper {
+        source: MigrationSource::Mpvpaper,
+        origin,
+        output: (!matches!(output, "*" | "ALL")).then(|| output.to_string()),
+        path: dirs.expand(path),
+        layout: Some(layout),
+        muted,
+    })
+}
+
+/// The image swww last showed on `output`: the cache file holds the path,
+/// after the resize filter in newer releases
+fn parse_swww_cache(
+    output: &str,
+    contents: &[u8],
+    origin: &str,
+    dirs: &Dirs,
+) -> Option<MigratedWallpaper> {
+    let contents = String::from_utf8_lossy(contents);
+    let path = contents.split(['\n', '\0']).map(str::trim).rfind(|part| {
+        (part.starts_with('/') || part.starts_with('~'))
+            && !part.chars().any(|c| c.is_control() || c == '\u{FFFD}')
+    })?;
+    Some(MigratedWallpaper {
+        source: MigrationSource::Swww,
+        origin: origin.to_string(),
+        output: Some(output.to_string()),
+        path: dirs.expand(path),
+        // swww crops to cover the output by default
+        layout: Some("fill"),
+        muted: None,
+    })
+}
+
+/// Shell words of each command on a line; `;`, `&` and `|` end a command
+fn shell_commands(line: &str) -> Vec<Vec<String>> {
+    let mut commands = Vec::new();
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                if let Some(escaped) = chars.next() {
+                    word.get_or_insert_with(String::new).push(escaped);
+                }
+            }
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, ';' | '&' | '|') => {
+                words.extend(word.take());
+                if !words.is_empty() {
+                    commands.push(std::mem::take(&mut words));
+                }
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    if !words.is_empty() {
+        commands.push(words);
+    }
+    commands
+}
+
+fn push_new(paths: &mut Vec<PathBuf>, path: PathBuf) {
+    if !paths.contains(&path) {
+        paths.push(path);
+    }
+}
+
+/// Home, config and cache directories from the XDG variables
+struct Dirs {
+    home: Option<PathBuf>,
+    config: Option<PathBuf>,
+    cache: Option<PathBuf>,
+}
+
+impl Dirs {
+    fn from_env() -> Self {
+        let var = |name: &str| {
+            std::env::var_os(name)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        };
+        let home = var("HOME");
+        Self {
+            config: var("XDG_CONFIG_HOME")
+                .or_else(|| home.as_ref().map(|home| home.join(".config"))),
+            cache: var("XDG_CACHE_HOME").or_else(|| home.as_ref().map(|home| home.join(".cache"))),
+            home,
+        }
+    }
+
+    fn default_paths(&self, source: MigrationSource) -> Vec<PathBuf> {
+        match source {
+            MigrationSource::Mpvpaper => self
+                .config
+                .iter()
+                .flat_map(|config| MPVPAPER_LAUNCHERS.iter().map(|file| config.join(file)))
+                .collect(),
+            MigrationSource::Swww => self.cache.iter().map(|cache| cache.join("swww")).collect(),
+            MigrationSource::Hyprpaper => self
+                .config
+                .iter()
+                .map(|config| config.join("hypr").join("hyprpaper.conf"))
+                .collect(),
+        }
+    }
+
+    /// `~/walls` and `$HOME/walls` -> `/home/me/walls`
+    fn expand(&self, path: &str) -> PathBuf {
+        let rest = path
+            .strip_prefix("~/")
+            .or_else(|| path.strip_prefix("$HOME/"))
+            .or_else(|| path.strip_prefix("${HOME}/"));
+        match (rest, &self.home) {
+            (Some(rest), Some(home)) => home.join(rest),
+            _ => PathBuf::from(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dirs() -> Dirs {
+        Dirs {
+            home: Some(PathBuf::from("/home/me")),
+            config: None,
+            cache: None,
+        }
+    }
+
+    fn summary(found: &[MigratedWallpaper]) -> Vec<(Option<&str>, &Path, Option<&str>)> {
+        found
+            .iter()
+            .map(|wallpaper| {
+                (
+                    wallpaper.output.as_deref(),
+                    wallpaper.path.as_path(),
+                    wallpaper.layout,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hyprpaper_lines_and_blocks_become_wallpapers() {
+        let conf = "preload = ~/walls/a.png\n\
+                    wallpaper = DP-1,~/walls/a.png\n\
+                    wallpaper = ,contain:/srv/walls/b.jpg # every output\n\
+                    \n\
+                    wallpaper {\n\
+                    \x20   monitor = HDMI-A-1\n\
+                    \x20   path = /srv/walls/c.png\n\
+                    \x20   fit_mode = tile\n\
+                    }\n";
+
+        let found = parse_hyprpaper(conf, "hyprpaper.conf", &dirs());
+        assert_eq!(
+            summary(&found),
+            vec![
+                (
+                    Some("DP-1"),
+                    Path::new("/home/me/walls/a.png"),
+                    Some("fill")
+                ),
+                (None, Path::new("/srv/walls/b.jpg"), Some("contain")),
+                (Some("HDMI-A-1"), Path::new("/srv/walls/c.png"), None),
+            ]
+        );
+        assert_eq!(found[1].origin, "hyprpaper.conf:3");
+        assert_eq!(found[2].origin, "hyprpaper.conf:5");
+    }
+
+    #[test]
+    fn mpvpaper_commands_are_found_in_compositor_configs() {
+        let config = "# exec-once = mpvpaper DP-2 /old.mp4\n\
+                      exec-once = mpvpaper -o \"no-audio --loop panscan=1.0\" DP-1 ~/walls/rain.mp4\n\
+                      exec pkill mpvpaper; mpvpaper -p -l background '*' \"/srv/walls/city lights.webm\"\n\
+                      spawn-at-startup \"mpvpaper\" \"-o\" \"keepaspect=no\" \"eDP-1\" \"/srv/walls/sea.mkv\"\n\
+                      mpvpaper --mpv-options=mute \\\n\
+                      \x20   HDMI-A-1 $HOME/walls/snow.mp4\n";
+
+        let found = parse_mpvpaper(config, "hyprland.conf", &dirs());
+        assert_eq!(
+            summary(&found),
+            vec![
+                (
+                    Some("DP-1"),
+                    Path::new("/home/me/walls/rain.mp4"),
+                    Some("fill")
+                ),
+                (
+                    None,
+                    Path::new("/srv/walls/city lights.webm"),
+                    Some("contain")
+                ),
+                (
+                    Some("eDP-1"),
+                    Path::new("/srv/walls/sea.mkv"),
+                    Some("stretch")
+                ),
+                (
+                    Some("HDMI-A-1"),
+                    Path::new("/home/me/walls/snow.mp4"),
+                    Some("contain")
+                ),
+            ]
+        );
+        assert_eq!(found[0].muted, Some(true));
+        assert_eq!(found[1].muted, None);
+        assert_eq!(found[3].muted, Some(true));
+        assert_eq!(found[3].origin, "hyprland.conf:5");
+    }
+
+    #[test]
+    fn swww_cache_files_name_the_output_and_image() {
+        let old = parse_swww_cache("DP-1", b"/home/me/walls/a.png", "swww/DP-1", &dirs()).unwrap();
+        assert_eq!(old.output.as_deref(), Some("DP-1"));
+        assert_eq!(old.path, PathBuf::from("/home/me/walls/a.png"));
+
+        let new = parse_swww_cache(
+            "eDP-1",
+            b"Lanczos3\n/home/me/walls/b.gif\n",
+            "swww/0.9.5/eDP-1",
+            &dirs(),
+        )
+        .unwrap();
+        assert_eq!(new.path, PathBuf::from("/home/me/walls/b.gif"));
+
+        assert_eq!(
+            parse_swww_cache("DP-1", b"\x89PNG\0\x01", "swww/DP-1", &dirs()),
+            None
+        );
+        assert_eq!(
+            MigrationSource::from_name("swww"),
+            Some(MigrationSource::Swww)
+        );
+        assert_eq!(MigrationSource::from_name("feh"), None);
+    }
+}
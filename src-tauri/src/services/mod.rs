@@ -1,11 +1,44 @@
 pub mod app_shell_service;
 pub mod autostart_service;
 pub mod backends;
+pub mod collection_service;
 pub mod compatibility_service;
+pub mod config_validation_service;
+pub mod control_service;
 pub mod desktop_persistence_service;
 pub mod desktop_service;
+pub mod diagnostics_service;
+pub mod doctor_service;
+pub mod file_picker_service;
+pub mod hook_service;
+pub mod http_service;
+pub mod item_open_service;
+pub mod library_cleanup_service;
+pub mod library_marks_service;
 pub mod library_service;
+pub mod library_watch_service;
+pub mod migration_service;
+pub mod monitor_preview_service;
 pub mod monitor_service;
+pub mod mpris_service;
+pub mod onboarding_service;
+pub mod palette_service;
+pub mod playlist_scheduler_service;
+pub mod playlist_service;
+pub mod preview_service;
+pub mod profile_service;
+pub mod resource_limits_service;
+pub mod scene_extract_service;
+pub mod schedule_service;
+pub mod screensaver_service;
+pub mod service_install_service;
 pub mod settings_persistence_service;
 pub mod settings_service;
+pub mod settings_sync_service;
+pub mod signage_service;
+pub mod stream_service;
+pub mod thumbnail_cache_service;
+pub mod wallpaper_convert_service;
+pub mod wallpaper_properties_service;
+pub mod web_wallpaper_service;
 pub mod workshop_service;
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use lwe_library::{ThumbnailFormat, ThumbnailGenerator};
+
+use crate::services::desktop_service::DesktopService;
+
+pub const MONITOR_PREVIEW_SCHEME: &str = "lwe-monitor";
+/// Requests within this window share one readback, so several open views
+/// polling the same monitor cost the engine a single frame
+const FRAME_REUSE: Duration = Duration::from_secs(2);
+const PREVIEW_WIDTH: u32 = 320;
+const PREVIEW_HEIGHT: u32 = 180;
+
+struct MonitorFrame {
+    taken_at: Instant,
+    jpeg: Vec<u8>,
+}
+
+fn frame_slot() -> &'static Mutex<HashMap<String, MonitorFrame>> {
+    static SLOT: OnceLock<Mutex<HashMap<String, MonitorFrame>>> = OnceLock::new();
+    SLOT.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn shrinker() -> &'static ThumbnailGenerator {
+    static GENERATOR: OnceLock<ThumbnailGenerator> = OnceLock::new();
+    GENERATOR.get_or_init(|| {
+        ThumbnailGenerator::with_options(
+            PREVIEW_WIDTH,
+            PREVIEW_HEIGHT,
+            ThumbnailFormat::Jpeg,
+            std::env::temp_dir(),
+        )
+    })
+}
+
+/// Small snapshots of what each monitor shows, read back from the running
+/// wallpaper engine for the monitor tiles.
+pub struct MonitorPreviewService;
+
+impl MonitorPreviewService {
+    /// Resolves a `lwe-monitor://localhost/<monitor id>` request path to a
+    /// JPEG of the monitor's current frame, at most 320x180.
+    pub fn frame_for_request_path(request_path: &str) -> Result<Vec<u8>, String> {
+        let monitor_id = request_path.trim_start_matches('/');
+        let mut frames = frame_slot()
+            .lock()
+            .map_err(|_| "Monitor preview lock was poisoned".to_string())?;
+        if let Some(frame) = frames
+            .get(monitor_id)
+            .filter(|frame| frame.taken_at.elapsed() < FRAME_REUSE)
+        {
+            return Ok(frame.jpeg.clone());
+        }
+
+        let path = snapshot_path(monitor_id);
+        let shrunk = DesktopService::snapshot(monitor_id, &path).and_then(|()| {
+            shrinker()
+                .generate_fresh(&path)
+                .map_err(|error| format!("Failed to shrink the frame of {monitor_id}: {error:#}"))
+        });
+        let _ = std::fs::remove_file(&path);
+        let jpeg = shrunk?.data;
+
+        frames.insert(
+            monitor_id.to_string(),
+            MonitorFrame {
+                taken_at: Instant::now(),
+                jpeg: jpeg.clone(),
+            },
+        );
+        Ok(jpeg)
+    }
+}
+
+/// Full-size readback the engine writes before it is shrunk; monitor ids are
+/// connector names, but anything else is kept out of the file name
+fn snapshot_path(monitor_id: &str) -> PathBuf {
+    let name: String = monitor_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    std::env::temp_dir().join(format!("lwe-monitor-{}-{name}.jpg", std::process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_path_keeps_connector_names_and_drops_separators() {
+        let pid = std::process::id();
+
+        assert_eq!(
+            snapshot_path("HDMI-A-1").file_name().unwrap(),
+            format!("lwe-monitor-{pid}-HDMI-A-1.jpg").as_str()
+        );
+        assert_eq!(
+            snapshot_path("../DP 1").file_name().unwrap(),
+            format!("lwe-monitor-{pid}-___DP_1.jpg").as_str()
+        );
+    }
+}
@@ -0,0 +1,441 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use lwe_engine::EngineStatus;
+use zbus::blocking::connection;
+use zbus::interface;
+use zbus::zvariant::{ObjectPath, OwnedValue, Value};
+
+use crate::services::desktop_service::DesktopService;
+use crate::services::playlist_scheduler_service::PlaylistSchedulerService;
+
+/// Bus name `playerctl` finds the wallpaper player under
+const MPRIS_BUS_NAME: &str = "org.mpris.MediaPlayer2.lwe";
+const MPRIS_OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+/// Track id for the wallpaper; MPRIS requires one even without a track list
+const WALLPAPER_TRACK_ID: &str = "/io/github/lwe/Wallpaper";
+/// How often the engine is polled for changes to announce over D-Bus
+const MPRIS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What MPRIS clients see of the wallpaper engine
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct MprisSnapshot {
+    pub(crate) status: PlaybackStatus,
+    /// Wallpaper on the first output, by output name
+    pub(crate) wallpaper: Option<PathBuf>,
+    pub(crate) position: Option<Duration>,
+    /// A playlist is rotating, so there are entries to go forward and back to
+    pub(crate) can_go_next: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum PlaybackStatus {
+    Playing,
+    Paused,
+    #[default]
+    Stopped,
+}
+
+impl PlaybackStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            PlaybackStatus::Playing => "Playing",
+            PlaybackStatus::Paused => "Paused",
+            PlaybackStatus::Stopped => "Stopped",
+        }
+    }
+}
+
+impl MprisSnapshot {
+    /// Summarize every output as one player: playing if any wallpaper plays
+    pub(crate) fn from_status(status: Option<&EngineStatus>, can_go_next: bool) -> Self {
+        let Some(status) = status.filter(|status| status.running) else {
+            return Self {
+                can_go_next,
+                ..Self::default()
+            };
+        };
+
+        // Sorted so the reported wallpaper does not jump between outputs
+        let mut outputs: Vec<(&String, &PathBuf)> = status
+            .active_wallpapers
+            .iter()
+            .filter_map(|(output, path)| path.as_ref().map(|path| (output, path)))
+            .collect();
+        outputs.sort();
+        let Some((first_output, wallpaper)) = outputs.first().copied() else {
+            return Self {
+                can_go_next,
+                ..Self::default()
+            };
+        };
+
+        let playing = outputs.iter().any(|(output, _)| {
+            status
+                .playback
+                .get(*output)
+                .is_none_or(|playback| !playback.paused)
+        });
+
+        Self {
+            status: if playing {
+                PlaybackStatus::Playing
+            } else {
+                PlaybackStatus::Paused
+            },
+            wallpaper: Some(wallpaper.clone()),
+            position: status
+                .playback
+                .get(first_output)
+                .and_then(|playback| playback.position),
+            can_go_next,
+        }
+    }
+
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        let mut metadata = HashMap::new();
+        let Some(wallpaper) = self.wallpaper.as_deref() else {
+            return metadata;
+        };
+
+        insert_value(
+            &mut metadata,
+            "mpris:trackid",
+            Value::from(ObjectPath::from_static_str_unchecked(WALLPAPER_TRACK_ID)),
+        );
+        insert_value(
+            &mut metadata,
+            "xesam:title",
+            Value::from(wallpaper_title(wallpaper)),
+        );
+        insert_value(
+            &mut metadata,
+            "xesam:url",
+            Value::from(format!("file://{}", wallpaper.display())),
+        );
+        metadata
+    }
+}
+
+fn insert_value(metadata: &mut HashMap<String, OwnedValue>, key: &str, value: Value<'_>) {
+    if let Ok(value) = OwnedValue::try_from(value) {
+        metadata.insert(key.to_string(), value);
+    }
+}
+
+/// Title shown by `playerctl metadata title`: the file name without extension
+pub(crate) fn wallpaper_title(path: &Path) -> String {
+    path.file_stem()
+        .or_else(|| path.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// `org.mpris.MediaPlayer2`
+struct MprisRoot;
+
+#[interface(name = "org.mpris.MediaPlayer2")]
+impl MprisRoot {
+    fn raise(&self) {}
+
+    fn quit(&self) {}
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> &str {
+        "LWE"
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// `org.mpris.MediaPlayer2.Player`, answered from the last polled snapshot
+struct MprisPlayer {
+    snapshot: MprisSnapshot,
+}
+
+impl MprisPlayer {
+    fn set_paused(&self, paused: bool) {
+        if let Err(reason) = DesktopService::set_playback_paused(paused) {
+            eprintln!("mpris could not change playback: {reason}");
+        }
+    }
+}
+
+#[interface(name = "org.mpris.MediaPlayer2.Player")]
+impl MprisPlayer {
+    fn play(&self) {
+        self.set_paused(false);
+    }
+
+    fn pause(&self) {
+        self.set_paused(true);
+    }
+
+    fn play_pause(&self) {
+        self.set_paused(self.snapshot.status == PlaybackStatus::Playing);
+    }
+
+    /// Wallpapers are never torn down from a media key; stop just pauses
+    fn stop(&self) {
+        self.set_paused(true);
+    }
+
+    /// Skip to the next playlist entry; applying it can take a while, so it
+    /// runs off the D-Bus thread
+    fn next(&self) {
+        thread::spawn(|| {
+            if let Err(reason) = PlaylistSchedulerService::skip(None) {
+                eprintln!("mpris could not skip: {reason}");
+            }
+        });
+    }
+
+    fn previous(&self) {
+        thread::spawn(|| {
+            if let Err(reason) = PlaylistSchedulerService::previous(None) {
+                eprintln!("mpris could not go back: {reason}");
+            }
+        });
+    }
+
+    fn seek(&self, _offset: i64) {}
+
+    fn set_position(&self, _track_id: ObjectPath<'_>, _position: i64) {}
+
+    fn open_uri(&self, _uri: &str) {}
+
+    #[zbus(property)]
+    fn playback_status(&self) -> &str {
+        self.snapshot.status.as_str()
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        self.snapshot.metadata()
+    }
+
+    /// Microseconds into the first output's video
+    #[zbus(property)]
+    fn position(&self) -> i64 {
+        self.snapshot
+            .position
+            .map_or(0, |position| position.as_micros() as i64)
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        self.snapshot.can_go_next
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        self.snapshot.can_go_next
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        self.snapshot.wallpaper.is_some()
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        self.snapshot.wallpaper.is_some()
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+pub struct MprisService;
+
+impl MprisService {
+    /// Publish wallpaper playback on the session bus so `playerctl` and media
+    /// keys can pause, resume and skip it
+    pub fn start() {
+        let spawned = thread::Builder::new()
+            .name("lwe-mpris".to_string())
+            .spawn(|| {
+                if let Err(reason) = Self::serve() {
+                    eprintln!("mpris player unavailable: {reason}");
+                }
+            });
+
+        if let Err(reason) = spawned {
+            eprintln!("failed to start mpris player: {reason}");
+        }
+    }
+
+    fn serve() -> Result<(), String> {
+        let connection = connection::Builder::session()
+            .and_then(|builder| builder.name(MPRIS_BUS_NAME))
+            .and_then(|builder| builder.serve_at(MPRIS_OBJECT_PATH, MprisRoot))
+            .and_then(|builder| {
+                builder.serve_at(
+                    MPRIS_OBJECT_PATH,
+                    MprisPlayer {
+                        snapshot: MprisSnapshot::default(),
+                    },
+                )
+            })
+            .and_then(|builder| builder.build())
+            .map_err(|error| format!("Failed to register {MPRIS_BUS_NAME}: {error}"))?;
+        let player = connection
+            .object_server()
+            .interface::<_, MprisPlayer>(MPRIS_OBJECT_PATH)
+            .map_err(|error| format!("Failed to look up the mpris player: {error}"))?;
+
+        loop {
+            let status = DesktopService::engine_status().unwrap_or_else(|reason| {
+                eprintln!("mpris could not read engine status: {reason}");
+                None
+            });
+            let can_go_next = !PlaylistSchedulerService::rotation_status().is_empty();
+            let snapshot = MprisSnapshot::from_status(status.as_ref(), can_go_next);
+
+            let mut iface = player.get_mut();
+            let previous = std::mem::replace(&mut iface.snapshot, snapshot);
+            let emitter = player.signal_emitter();
+            // Position changes every poll; MPRIS clients read it on demand
+            // instead of expecting change signals
+            let announced = zbus::block_on(async {
+                if previous.status != iface.snapshot.status {
+                    iface.playback_status_changed(emitter).await?;
+                    iface.can_play_changed(emitter).await?;
+                    iface.can_pause_changed(emitter).await?;
+                }
+                if previous.wallpaper != iface.snapshot.wallpaper {
+                    iface.metadata_changed(emitter).await?;
+                }
+                if previous.can_go_next != iface.snapshot.can_go_next {
+                    iface.can_go_next_changed(emitter).await?;
+                    iface.can_go_previous_changed(emitter).await?;
+                }
+                zbus::Result::Ok(())
+            });
+            drop(iface);
+            if let Err(error) = announced {
+                eprintln!("mpris could not announce a playback change: {error}");
+            }
+
+            thread::sleep(MPRIS_POLL_INTERVAL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lwe_engine::OutputPlayback;
+
+    fn engine_status(outputs: &[(&str, Option<&str>, bool)]) -> EngineStatus {
+        EngineStatus {
+            running: true,
+            outputs: Vec::new(),
+            active_wallpapers: outputs
+                .iter()
+                .map(|(output, path, _)| (output.to_string(), path.map(PathBuf::from)))
+                .collect(),
+            playback: outputs
+                .iter()
+                .map(|(output, _, paused)| {
+                    (
+                        output.to_string(),
+                        OutputPlayback {
+                            paused: *paused,
+                            position: Some(Duration::from_secs(3)),
+                            ..Default::default()
+                        },
+                    )
+                })
+                .collect(),
+            cgroup: None,
+            gpu_messages: HashMap::new(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn mpris_snapshot_is_playing_while_any_output_plays() {
+        let status = engine_status(&[
+            ("HDMI-A-1", Some("/walls/b.mp4"), true),
+            ("DP-1", Some("/walls/a.mp4"), false),
+        ]);
+
+        let snapshot = MprisSnapshot::from_status(Some(&status), true);
+
+        assert_eq!(snapshot.status, PlaybackStatus::Playing);
+        assert_eq!(snapshot.wallpaper, Some(PathBuf::from("/walls/a.mp4")));
+        assert_eq!(snapshot.position, Some(Duration::from_secs(3)));
+        assert!(snapshot.can_go_next);
+    }
+
+    #[test]
+    fn mpris_snapshot_reports_paused_and_stopped_engines() {
+        let paused = engine_status(&[("DP-1", Some("/walls/a.mp4"), true)]);
+        let empty = engine_status(&[("DP-1", None, false)]);
+
+        assert_eq!(
+            MprisSnapshot::from_status(Some(&paused), false).status,
+            PlaybackStatus::Paused
+        );
+        assert_eq!(
+            MprisSnapshot::from_status(Some(&empty), false),
+            MprisSnapshot::default()
+        );
+        assert_eq!(
+            MprisSnapshot::from_status(None, false),
+            MprisSnapshot::default()
+        );
+        assert_eq!(
+            wallpaper_title(Path::new("/walls/Rainy Street.mp4")),
+            "Rainy Street"
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use std::path::{Path, PathBuf};
+
+use lwe_engine::SystemCapabilities;
+use lwe_library::{SteamLibrary, WALLPAPER_ENGINE_APP_ID};
+
+use crate::models::{
+    OnboardingCheck, OnboardingFinishInput, OnboardingRenderDevice, OnboardingSnapshot,
+};
+use crate::results::settings_persistence::{
+    PersistedSettings, SettingsPersistenceLoad, SettingsPersistenceWrite,
+};
+use crate::services::library_service::add_new_paths;
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+use crate::services::settings_service::SettingsService;
+
+/// Folders under the home directory that commonly hold wallpapers, most
+/// specific first
+const WALLPAPER_FOLDER_CANDIDATES: &[&str] = &[
+    "Videos/Wallpapers",
+    "Videos/wallpapers",
+    "Pictures/Wallpapers",
+    "Pictures/wallpapers",
+    "Wallpapers",
+    ".local/share/wallpapers",
+];
+
+/// The first-run wizard: what this system offers, and the initial
+/// `settings.toml` written from the user's choices.
+pub struct OnboardingService;
+
+impl OnboardingService {
+    /// Probe Steam, the usual wallpaper folders, the compositor and the
+    /// hardware decoders; takes a moment, so run it off the UI thread
+    pub fn load() -> Result<OnboardingSnapshot, String> {
+        let persistence = SettingsPersistenceService::for_user_path()?;
+        let workshop_dirs = SteamLibrary::try_discover()
+            .map(|steam| steam.workshop_content_path(WALLPAPER_ENGINE_APP_ID));
+        let system = lwe_engine::probe_system();
+        let suggested_folders = std::env::var_os("HOME")
+            .filter(|home| !home.is_empty())
+            .map(|home| suggested_folders(Path::new(&home)))
+            .unwrap_or_default();
+
+        Ok(OnboardingSnapshot {
+            needed: !persistence.path().exists(),
+            steam_found: workshop_dirs.is_some(),
+            wallpaper_engine_found: workshop_dirs.as_ref().is_some_and(|dirs| !dirs.is_empty()),
+            workshop_item_count: workshop_dirs
+                .iter()
+                .flatten()
+                .map(|dir| count_subdirs(dir))
+                .sum(),
+            suggested_folders: suggested_folders
+                .iter()
+                .map(|folder| folder.to_string_lossy().into_owned())
+                .collect(),
+            render_backend: render_backend(&system, std::env::var_os("DISPLAY").is_some()),
+            hardware_decoding: hardware_decoding(&system),
+            render_devices: render_devices(&system),
+            launch_on_login_available: SettingsService::launch_on_login_available(),
+        })
+    }
+
+    /// Write the choices into `settings.toml`, creating it; skipping the
+    /// wizard finishes with no choices so it is not shown again
+    pub fn finish(input: OnboardingFinishInput) -> Result<(), String> {
+        let persistence = SettingsPersistenceService::for_user_path()?;
+        let mut settings = match persistence.load_settings() {
+            SettingsPersistenceLoad::Loaded(settings) => settings,
+            SettingsPersistenceLoad::Unavailable { reason } => return Err(reason),
+        };
+        let launch_on_login = input.launch_on_login;
+        apply_choices(&mut settings, input);
+
+        if let SettingsPersistenceWrite::Unavailable { reason } =
+            persistence.save_settings(&settings)
+        {
+            return Err(reason);
+        }
+        if launch_on_login {
+            SettingsService::apply_launch_on_login(true)?;
+        }
+        Ok(())
+    }
+}
+
+fn apply_choices(settings: &mut PersistedSettings, input: OnboardingFinishInput) {
+    if let Some(language) = input.language {
+        settings.language = language;
+    }
+    add_new_paths(
+        &mut settings.library.folders,
+        input.folders.into_iter().map(PathBuf::from).collect(),
+    );
+    if input.render_device.is_some() {
+        settings.gpu.render_device = input.render_device;
+    }
+    settings.launch_on_login = input.launch_on_login;
+}
+
+fn suggested_folders(home: &Path) -> Vec<PathBuf> {
+    WALLPAPER_FOLDER_CANDIDATES
+        .iter()
+        .map(|candidate| home.join(candidate))
+        .filter(|folder| folder.is_dir())
+        .collect()
+}
+
+fn count_subdirs(dir: &Path) -> usize {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+/// Layer shell plus EGL on Wayland; on X11 mpv draws into a desktop window
+fn render_backend(system: &SystemCapabilities, x11_display: bool) -> OnboardingCheck {
+    let globals = match &system.wayland_globals {
+        Ok(globals) => globals,
+        Err(_) if x11_display => {
+            return OnboardingCheck {
+                ok: true,
+                detail: "X11 desktop window".to_string(),
+            }
+        }
+        Err(reason) => {
+            return OnboardingCheck {
+                ok: false,
+                detail: reason.clone(),
+            }
+        }
+    };
+    let Some(layer_shell) = globals
+        .iter()
+        .find(|global| global.interface == "zwlr_layer_shell_v1")
+    else {
+        return OnboardingCheck {
+            ok: false,
+            detail: "The compositor does not offer zwlr_layer_shell_v1".to_string(),
+        };
+    };
+
+    match &system.egl {
+        Ok(egl) => OnboardingCheck {
+            ok: true,
+            detail: format!(
+                "Wayland layer shell v{}, EGL {} ({})",
+                layer_shell.version, egl.version, egl.vendor
+            ),
+        },
+        Err(reason) => OnboardingCheck {
+            ok: false,
+            detail: reason.clone(),
+        },
+    }
+}
+
+fn hardware_decoding(system: &SystemCapabilities) -> OnboardingCheck {
+    let vaapi = system.vaapi.iter().find_map(|(node, support)| {
+        support
+            .as_ref()
+            .ok()
+            .filter(|support| !support.decode_profiles.is_empty())
+            .map(|support| (node, support))
+    });
+
+    match (vaapi, &system.nvdec) {
+        (Some((node, support)), _) => OnboardingCheck {
+            ok: true,
+            detail: format!("VAAPI on {} ({})", node.display(), support.driver),
+        },
+        (None, Ok(driver)) => OnboardingCheck {
+            ok: true,
+            detail: format!("NVDEC with NVIDIA driver {driver}"),
+        },
+        (None, Err(_)) => OnboardingCheck {
+            ok: false,
+            detail: "No hardware decoder found; videos will decode on the CPU".to_string(),
+        },
+    }
+}
+
+fn render_devices(system: &SystemCapabilities) -> Vec<OnboardingRenderDevice> {
+    system
+        .vaapi
+        .iter()
+        .map(|(node, support)| OnboardingRenderDevice {
+            path: node.to_string_lossy().into_owned(),
+            detail: match support {
+                Ok(support) => support.driver.clone(),
+                Err(reason) => reason.clone(),
+            },
+            hardware_decoding: support
+                .as_ref()
+                .is_ok_and(|support| !support.decode_profiles.is_empty()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn system(layer_shell: bool) -> SystemCapabilities {
+        SystemCapabilities {
+            wayland_globals: Ok(if layer_shell {
+                vec![lwe_engine::WaylandGlobal {
+                    interface: "zwlr_layer_shell_v1".to_string(),
+                    version: 4,
+                }]
+            } else {
+                Vec::new()
+            }),
+            egl: Ok(lwe_engine::EglInfo {
+                version: "1.5".to_string(),
+                vendor: "Mesa Project".to_string(),
+                client_apis: "OpenGL OpenGL_ES".to_string(),
+                extensions: Vec::new(),
+            }),
+            vaapi: vec![
+                (
+                    "/dev/dri/renderD128".into(),
+                    Ok(lwe_engine::VaapiSupport {
+                        driver: "Intel iHD driver".to_string(),
+                        decode_profiles: Vec::new(),
+                    }),
+                ),
+                (
+                    "/dev/dri/renderD129".into(),
+                    Ok(lwe_engine::VaapiSupport {
+                        driver: "Mesa Gallium driver".to_string(),
+                        decode_profiles: vec!["HEVCMain".to_string()],
+                    }),
+                ),
+            ],
+            nvdec: Err("No NVIDIA driver loaded".to_string()),
+            vulkan_loader: false,
+            mpv: Err("not probed".to_string()),
+        }
+    }
+
+    #[test]
+    fn probes_summarize_backend_and_decoder() {
+        let backend = render_backend(&system(true), false);
+        let decoding = hardware_decoding(&system(true));
+        let devices = render_devices(&system(true));
+
+        assert!(backend.ok);
+        assert_eq!(
+            backend.detail,
+            "Wayland layer shell v4, EGL 1.5 (Mesa Project)"
+        );
+        assert!(decoding.ok);
+        assert_eq!(
+            decoding.detail,
+            "VAAPI on /dev/dri/renderD129 (Mesa Gallium driver)"
+        );
+        assert_eq!(devices.len(), 2);
+        assert!(!devices[0].hardware_decoding);
+        assert!(devices[1].hardware_decoding);
+        assert!(!render_backend(&system(false), false).ok);
+    }
+
+    #[test]
+    fn suggests_only_existing_wallpaper_folders() {
+        let home = std::env::temp_dir().join(format!("lwe-onboarding-{}", std::process::id()));
+        std::fs::create_dir_all(home.join("Pictures/Wallpapers")).unwrap();
+        std::fs::create_dir_all(home.join("Videos")).unwrap();
+
+        let folders = suggested_folders(&home);
+        let _ = std::fs::remove_dir_all(&home);
+
+        assert_eq!(folders, vec![home.join("Pictures/Wallpapers")]);
+    }
+
+    #[test]
+    fn choices_add_folders_once_and_keep_auto_gpu() {
+        let mut settings = PersistedSettings::default();
+        settings
+            .library
+            .folders
+            .push(PathBuf::from("/home/u/Wallpapers"));
+
+        apply_choices(
+            &mut settings,
+            OnboardingFinishInput {
+                language: Some("zh-CN".to_string()),
+                folders: vec![
+                    "/home/u/Wallpapers".to_string(),
+                    "/home/u/Videos/Wallpapers".to_string(),
+                ],
+                render_device: None,
+                launch_on_login: true,
+            },
+        );
+
+        assert_eq!(settings.language, "zh-CN");
+        assert_eq!(
+            settings.library.folders,
+            vec![
+                PathBuf::from("/home/u/Wallpapers"),
+                PathBuf::from("/home/u/Videos/Wallpapers")
+            ]
+        );
+        assert_eq!(settings.gpu.render_device, None);
+        assert!(settings.launch_on_login);
+    }
+}
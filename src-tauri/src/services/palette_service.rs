@@ -0,0 +1,139 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use lwe_core::Palette;
+
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+
+/// Fills the user's palette templates from the colors of the wallpaper on screen.
+///
+/// Every file in `~/.config/lwe/templates` is rendered into
+/// `~/.cache/lwe/palette` under the same name.
+pub struct PaletteService;
+
+impl PaletteService {
+    /// Whether the user has a templates directory at all
+    pub fn has_templates() -> bool {
+        templates_dir().is_ok_and(|dir| dir.is_dir())
+    }
+
+    /// Render every template with `palette`, returning how many were written
+    pub fn write_templates(palette: &Palette) -> Result<usize, String> {
+        render_templates(&templates_dir()?, &palette_cache_dir()?, palette)
+    }
+}
+
+fn render_templates(
+    templates_dir: &Path,
+    output_dir: &Path,
+    palette: &Palette,
+) -> Result<usize, String> {
+    let entries = match fs::read_dir(templates_dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(error) => {
+            return Err(format!(
+                "Failed to read palette templates in {}: {error}",
+                templates_dir.display()
+            ));
+        }
+    };
+
+    fs::create_dir_all(output_dir).map_err(|error| {
+        format!(
+            "Failed to create palette output directory {}: {error}",
+            output_dir.display()
+        )
+    })?;
+
+    let mut written = 0;
+    for entry in entries.flatten() {
+        let template = entry.path();
+        if !template.is_file() {
+            continue;
+        }
+
+        let contents = match fs::read_to_string(&template) {
+            Ok(contents) => contents,
+            Err(error) => {
+                eprintln!("skipping palette template {}: {error}", template.display());
+                continue;
+            }
+        };
+        let target = output_dir.join(entry.file_name());
+        fs::write(&target, palette.render_template(&contents)).map_err(|error| {
+            format!("Failed to write palette file {}: {error}", target.display())
+        })?;
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+fn templates_dir() -> Result<PathBuf, String> {
+    let service = SettingsPersistenceService::for_user_path()?;
+    service
+        .path()
+        .parent()
+        .map(|config_dir| config_dir.join("templates"))
+        .ok_or_else(|| "Unable to resolve the palette templates directory".to_string())
+}
+
+fn palette_cache_dir() -> Result<PathBuf, String> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|value| !value.is_empty())
+                .map(|home| PathBuf::from(home).join(".cache"))
+        });
+
+    match base {
+        Some(path) if path.is_absolute() => Ok(path.join("lwe").join("palette")),
+        _ => Err(
+            "Unable to resolve the palette cache because XDG_CACHE_HOME and HOME are unset"
+                .to_string(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    #[test]
+    fn palette_templates_render_into_output_directory_by_name() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("palette-service-{unique}"));
+        let templates = root.join("templates");
+        let output = root.join("palette");
+        fs::create_dir_all(&templates).unwrap();
+        fs::write(
+            templates.join("waybar.css"),
+            "@define-color bg {background};\n@define-color accent {color1};\n",
+        )
+        .unwrap();
+        let palette = Palette {
+            colors: vec![[16, 16, 16], [200, 80, 40]],
+        };
+
+        assert_eq!(render_templates(&templates, &output, &palette), Ok(1));
+        assert_eq!(
+            fs::read_to_string(output.join("waybar.css")).unwrap(),
+            "@define-color bg #101010;\n@define-color accent #c85028;\n"
+        );
+        assert_eq!(
+            render_templates(&root.join("missing"), &output, &palette),
+            Ok(0)
+        );
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,726 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use lwe_core::PlaylistRotation;
+use lwe_library::Playlist;
+
+use crate::results::desktop::DesktopApplyResult;
+use crate::results::playlist::PlaylistRotationStatus;
+use crate::services::desktop_persistence_service::session_state_path;
+use crate::services::desktop_service::DesktopService;
+use crate::services::playlist_service::PlaylistService;
+
+const SCHEDULER_TICK: Duration = Duration::from_secs(5);
+const ROTATION_STATE_FILE: &str = "playlist-rotation.json";
+
+/// Rotation progress of one playlist on one monitor
+#[derive(Debug, Clone)]
+pub(crate) struct PlaylistCursor {
+    playlist: Playlist,
+    rotation: PlaylistRotation,
+}
+
+impl PlaylistCursor {
+    pub(crate) fn new(playlist: Playlist, now: u64, seed: u64) -> Self {
+        Self {
+            rotation: PlaylistRotation::new(
+                playlist.id,
+                playlist.items.len(),
+                playlist.shuffle,
+                now,
+                seed,
+            ),
+            playlist,
+        }
+    }
+
+    /// Pick up a rotation saved by an earlier run, if it still matches the playlist
+    pub(crate) fn resume(playlist: Playlist, rotation: PlaylistRotation) -> Option<Self> {
+        (rotation.playlist_id == playlist.id
+            && rotation.shuffle == playlist.shuffle
+            && rotation.fits(playlist.items.len()))
+        .then_some(Self { playlist, rotation })
+    }
+
+    /// Whether `playlist` is this cursor's folder playlist with other files
+    pub(crate) fn shares_folder(&self, playlist: &Playlist) -> bool {
+        let settings = |playlist: &Playlist| Playlist {
+            items: Vec::new(),
+            ..playlist.clone()
+        };
+        self.playlist.folder.is_some() && settings(&self.playlist) == settings(playlist)
+    }
+
+    /// Follow files added to or removed from the folder, staying on the file
+    /// being shown while it is still there
+    pub(crate) fn refill(&self, playlist: Playlist, now: u64, seed: u64) -> Self {
+        let mut cursor = Self::new(playlist, now, seed);
+        let shown = self.current_item_id().and_then(|item_id| {
+            cursor
+                .rotation
+                .order
+                .iter()
+                .position(|&index| cursor.playlist.items[index].item_id == item_id)
+        });
+        if let Some(position) = shown {
+            cursor.rotation.position = position;
+            cursor.rotation.started_at = self.rotation.started_at;
+            cursor.rotation.paused_after = self.rotation.paused_after;
+        }
+
+        cursor
+    }
+
+    pub(crate) fn current_item_id(&self) -> Option<&str> {
+        self.item_id(self.rotation.current())
+    }
+
+    fn item_id(&self, index: Option<usize>) -> Option<&str> {
+        index.map(|index| self.playlist.items[index].item_id.as_str())
+    }
+
+    /// Move to the next entry once the current one has been shown long enough
+    ///
+    /// Returns the item that should be applied now, if the cursor moved.
+    pub(crate) fn advance_if_due(&mut self, now: u64) -> Option<&str> {
+        let playlist = &self.playlist;
+        let next = self
+            .rotation
+            .advance_if_due(now, |index| playlist.items[index].duration(playlist));
+
+        self.item_id(next)
+    }
+
+    /// The entry to open ahead of time once the switch is `lead_secs` away
+    pub(crate) fn preload_if_due(&self, now: u64, lead_secs: u64) -> Option<&str> {
+        let playlist = &self.playlist;
+        let remaining = self
+            .rotation
+            .remaining(now, |index| playlist.items[index].duration(playlist))?;
+        let upcoming = self.item_id(self.rotation.upcoming())?;
+
+        (remaining <= lead_secs && Some(upcoming) != self.current_item_id()).then_some(upcoming)
+    }
+
+    pub(crate) fn skip(&mut self, now: u64) -> Option<&str> {
+        let next = self.rotation.skip(now);
+        self.item_id(next)
+    }
+
+    pub(crate) fn back(&mut self, now: u64) -> Option<&str> {
+        let previous = self.rotation.back(now);
+        self.item_id(previous)
+    }
+
+    pub(crate) fn set_paused(&mut self, paused: bool, now: u64) {
+        if paused {
+            self.rotation.pause(now);
+        } else {
+            self.rotation.resume(now);
+        }
+    }
+}
+
+/// Advance every assigned playlist and collect the applies that are due
+///
+/// Cursors for monitors without an assignment are dropped, and a cursor is
+/// restarted when its playlist was edited since it was created. Folder
+/// playlists whose files changed keep showing the current file. Rotations
+/// saved by an earlier run are resumed in place instead of restarting.
+pub(crate) fn collect_due_applies(
+    cursors: &mut BTreeMap<String, PlaylistCursor>,
+    saved: &mut BTreeMap<String, PlaylistRotation>,
+    assignments: Vec<(String, Playlist)>,
+    now: u64,
+    seed: u64,
+) -> Vec<(String, String)> {
+    let assigned = assignments
+        .iter()
+        .map(|(monitor_id, _)| monitor_id.clone())
+        .collect::<Vec<_>>();
+    cursors.retain(|monitor_id, _| assigned.contains(monitor_id));
+
+    let mut applies = Vec::new();
+    for (monitor_id, playlist) in assignments {
+        if playlist.items.is_empty() {
+            cursors.remove(&monitor_id);
+            continue;
+        }
+
+        match cursors.get_mut(&monitor_id) {
+            Some(cursor) if cursor.playlist == playlist => {
+                if let Some(item_id) = cursor.advance_if_due(now) {
+                    applies.push((monitor_id.clone(), item_id.to_string()));
+                }
+            }
+            Some(cursor) if cursor.shares_folder(&playlist) => {
+                let shown = cursor.current_item_id().map(str::to_string);
+                *cursor = cursor.refill(playlist, now, seed);
+                if let Some(item_id) = cursor
+                    .current_item_id()
+                    .filter(|item_id| Some(*item_id) != shown.as_deref())
+                {
+                    applies.push((monitor_id.clone(), item_id.to_string()));
+                }
+            }
+            _ => {
+                // The restored session already shows the saved entry, so only
+                // a fresh rotation needs an immediate apply.
+                let cursor = match saved
+                    .remove(&monitor_id)
+                    .and_then(|rotation| PlaylistCursor::resume(playlist.clone(), rotation))
+                {
+                    Some(cursor) => cursor,
+                    None => {
+                        let cursor = PlaylistCursor::new(playlist, now, seed);
+                        if let Some(item_id) = cursor.current_item_id() {
+                            applies.push((monitor_id.clone(), item_id.to_string()));
+                        }
+                        cursor
+                    }
+                };
+                cursors.insert(monitor_id, cursor);
+            }
+        }
+    }
+    saved.clear();
+
+    applies
+}
+
+/// Collect the upcoming entries to preload, skipping those already requested
+pub(crate) fn collect_due_preloads(
+    cursors: &BTreeMap<String, PlaylistCursor>,
+    preloaded: &mut BTreeMap<String, String>,
+    now: u64,
+    lead_secs: u64,
+) -> Vec<(String, String)> {
+    preloaded.retain(|monitor_id, _| cursors.contains_key(monitor_id));
+
+    let mut preloads = Vec::new();
+    for (monitor_id, cursor) in cursors {
+        let Some(item_id) = cursor.preload_if_due(now, lead_secs) else {
+            continue;
+        };
+        if preloaded.get(monitor_id).map(String::as_str) != Some(item_id) {
+            preloaded.insert(monitor_id.clone(), item_id.to_string());
+            preloads.push((monitor_id.clone(), item_id.to_string()));
+        }
+    }
+
+    preloads
+}
+
+struct SchedulerState {
+    cursors: BTreeMap<String, PlaylistCursor>,
+    /// Upcoming entry last preloaded on each monitor
+    preloaded: BTreeMap<String, String>,
+    /// Rotations loaded from disk that have not been matched to a playlist yet
+    saved: BTreeMap<String, PlaylistRotation>,
+    /// Rotations as last written to disk
+    persisted: BTreeMap<String, PlaylistRotation>,
+}
+
+impl SchedulerState {
+    fn load() -> Self {
+        let saved = match rotation_state_path() {
+            Ok(path) => load_rotations(&path).unwrap_or_else(|reason| {
+                eprintln!("playlist scheduler could not restore rotation state: {reason}");
+                BTreeMap::new()
+            }),
+            Err(reason) => {
+                eprintln!("playlist scheduler could not restore rotation state: {reason}");
+                BTreeMap::new()
+            }
+        };
+
+        Self {
+            cursors: BTreeMap::new(),
+            preloaded: BTreeMap::new(),
+            persisted: saved.clone(),
+            saved,
+        }
+    }
+
+    /// Cursors addressed by a command; `None` means every rotating monitor
+    fn targets(
+        &mut self,
+        monitor_id: Option<&str>,
+    ) -> Result<Vec<(&String, &mut PlaylistCursor)>, String> {
+        let targets = self
+            .cursors
+            .iter_mut()
+            .filter(|(id, _)| monitor_id.is_none_or(|monitor_id| id.as_str() == monitor_id))
+            .collect::<Vec<_>>();
+
+        match (targets.is_empty(), monitor_id) {
+            (true, Some(monitor_id)) => Err(format!("No playlist is rotating on {monitor_id}")),
+            (true, None) => Err("No playlist is rotating".to_string()),
+            (false, _) => Ok(targets),
+        }
+    }
+
+    fn persist(&mut self) {
+        let rotations = self
+            .cursors
+            .iter()
+            .map(|(monitor_id, cursor)| (monitor_id.clone(), cursor.rotation.clone()))
+            .collect::<BTreeMap<_, _>>();
+        if rotations == self.persisted {
+            return;
+        }
+
+        match rotation_state_path().and_then(|path| save_rotations(&path, &rotations)) {
+            Ok(()) => self.persisted = rotations,
+            Err(reason) => eprintln!("playlist scheduler could not save rotation state: {reason}"),
+        }
+    }
+}
+
+fn scheduler_state() -> MutexGuard<'static, SchedulerState> {
+    static STATE: OnceLock<Mutex<SchedulerState>> = OnceLock::new();
+    STATE
+        .get_or_init(|| Mutex::new(SchedulerState::load()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub struct PlaylistSchedulerService;
+
+impl PlaylistSchedulerService {
+    /// Run playlists assigned to monitors on a background thread
+    pub fn start() {
+        let spawned = thread::Builder::new()
+            .name("lwe-playlist-scheduler".to_string())
+            .spawn(|| loop {
+                Self::sync();
+                thread::sleep(SCHEDULER_TICK);
+            });
+
+        if let Err(reason) = spawned {
+            eprintln!("failed to start playlist scheduler: {reason}");
+        }
+    }
+
+    /// Reconcile rotations with the saved assignments and apply whatever is due
+    pub fn sync() {
+        let assignments = match PlaylistService::for_user_path().load_assignments() {
+            Ok(assignments) => assignments,
+            Err(reason) => {
+                eprintln!("playlist scheduler could not load assignments: {reason}");
+                return;
+            }
+        };
+
+        let preload_secs = DesktopService::playback_settings().preload_secs;
+        let (applies, preloads) = {
+            let mut state = scheduler_state();
+            let now = unix_now();
+            let SchedulerState {
+                cursors,
+                saved,
+                preloaded,
+                ..
+            } = &mut *state;
+            let applies = collect_due_applies(cursors, saved, assignments, now, time_seed());
+            // The scheduler only looks once per tick, so preload at least a tick early
+            let preloads = preload_secs
+                .filter(|secs| *secs > 0)
+                .map(|secs| {
+                    collect_due_preloads(
+                        cursors,
+                        preloaded,
+                        now,
+                        secs.max(SCHEDULER_TICK.as_secs()),
+                    )
+                })
+                .unwrap_or_default();
+            state.persist();
+            (applies, preloads)
+        };
+
+        apply_all(applies);
+        for (monitor_id, item_id) in preloads {
+            if let Err(reason) = DesktopService::preload_on_monitor(&monitor_id, &item_id) {
+                eprintln!(
+                    "playlist scheduler could not preload {item_id} on {monitor_id}: {reason}"
+                );
+            }
+        }
+    }
+
+    /// Skip to the next entry on one monitor, or on every rotating monitor
+    pub fn skip(monitor_id: Option<&str>) -> Result<(), String> {
+        Self::step(monitor_id, PlaylistCursor::skip)
+    }
+
+    /// Go back to the previous entry on one monitor, or on every rotating monitor
+    pub fn previous(monitor_id: Option<&str>) -> Result<(), String> {
+        Self::step(monitor_id, PlaylistCursor::back)
+    }
+
+    fn step(
+        monitor_id: Option<&str>,
+        step: impl for<'a> Fn(&'a mut PlaylistCursor, u64) -> Option<&'a str>,
+    ) -> Result<(), String> {
+        let applies = {
+            let mut state = scheduler_state();
+            let now = unix_now();
+            let applies = state
+                .targets(monitor_id)?
+                .into_iter()
+                .filter_map(|(monitor_id, cursor)| {
+                    step(cursor, now).map(|item_id| (monitor_id.clone(), item_id.to_string()))
+                })
+                .collect::<Vec<_>>();
+            state.persist();
+            applies
+        };
+
+        apply_all(applies);
+        Ok(())
+    }
+
+    /// Pause or resume rotation without touching the wallpaper being shown
+    pub fn set_paused(monitor_id: Option<&str>, paused: bool) -> Result<(), String> {
+        let mut state = scheduler_state();
+        let now = unix_now();
+        for (_, cursor) in state.targets(monitor_id)? {
+            cursor.set_paused(paused, now);
+        }
+        state.persist();
+
+        Ok(())
+    }
+
+    pub fn rotation_status() -> Vec<PlaylistRotationStatus> {
+        scheduler_state()
+            .cursors
+            .iter()
+            .map(|(monitor_id, cursor)| PlaylistRotationStatus {
+                monitor_id: monitor_id.clone(),
+                playlist_id: cursor.playlist.id,
+                current_item_id: cursor.current_item_id().map(str::to_string),
+                paused: cursor.rotation.is_paused(),
+            })
+            .collect()
+    }
+}
+
+fn apply_all(applies: Vec<(String, String)>) {
+    for (monitor_id, item_id) in applies {
+        match DesktopService::apply_to_monitor(&monitor_id, &item_id) {
+            Ok(
+                DesktopApplyResult::Applied { .. } | DesktopApplyResult::AppliedWithBackend { .. },
+            ) => {}
+            Ok(result) => {
+                eprintln!(
+                    "playlist scheduler could not apply {item_id} to {monitor_id}: {result:?}"
+                )
+            }
+            Err(reason) => {
+                eprintln!("playlist scheduler could not apply {item_id} to {monitor_id}: {reason}")
+            }
+        }
+    }
+}
+
+fn rotation_state_path() -> Result<PathBuf, String> {
+    session_state_path().map(|path| path.with_file_name(ROTATION_STATE_FILE))
+}
+
+fn load_rotations(path: &Path) -> Result<BTreeMap<String, PlaylistRotation>, String> {
+    match fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents).map_err(|reason| {
+            format!(
+                "Failed to parse playlist rotation state from {}: {reason}",
+                path.display()
+            )
+        }),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(error) => Err(format!(
+            "Failed to read playlist rotation state from {}: {error}",
+            path.display()
+        )),
+    }
+}
+
+fn save_rotations(
+    path: &Path,
+    rotations: &BTreeMap<String, PlaylistRotation>,
+) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed to create {}: {error}", parent.display()))?;
+    }
+
+    let contents = serde_json::to_string_pretty(rotations)
+        .map_err(|reason| format!("Failed to serialize playlist rotation state: {reason}"))?;
+    fs::write(path, contents).map_err(|error| {
+        format!(
+            "Failed to write playlist rotation state to {}: {error}",
+            path.display()
+        )
+    })
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+pub(crate) fn time_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or(0)
+        | 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lwe_library::{PlaylistEntry, PlaylistFolder};
+
+    fn playlist(shuffle: bool) -> Playlist {
+        Playlist {
+            id: 1,
+            name: "Evening".to_string(),
+            shuffle,
+            transition: "none".to_string(),
+            default_duration_secs: 60,
+            items: vec![
+                PlaylistEntry {
+                    item_id: "scene-1".to_string(),
+                    duration_secs: Some(10),
+                },
+                PlaylistEntry {
+                    item_id: "scene-2".to_string(),
+                    duration_secs: None,
+                },
+                PlaylistEntry {
+                    item_id: "scene-3".to_string(),
+                    duration_secs: None,
+                },
+            ],
+            outputs: vec!["DP-1".to_string()],
+            folder: None,
+        }
+    }
+
+    #[test]
+    fn playlist_cursor_honours_per_item_durations_and_wraps() {
+        let start = 1_000;
+        let mut cursor = PlaylistCursor::new(playlist(false), start, 1);
+
+        assert_eq!(cursor.current_item_id(), Some("scene-1"));
+        assert_eq!(cursor.advance_if_due(start + 9), None);
+        assert_eq!(cursor.advance_if_due(start + 10), Some("scene-2"));
+        assert_eq!(cursor.advance_if_due(start + 30), None);
+        assert_eq!(cursor.advance_if_due(start + 70), Some("scene-3"));
+        assert_eq!(cursor.advance_if_due(start + 130), Some("scene-1"));
+    }
+
+    #[test]
+    fn playlist_cursor_shuffle_visits_every_item_once_per_cycle() {
+        let cursor = PlaylistCursor::new(playlist(true), 0, 0x9e37_79b9);
+
+        let mut seen = cursor
+            .rotation
+            .order
+            .iter()
+            .map(|&index| cursor.playlist.items[index].item_id.clone())
+            .collect::<Vec<_>>();
+        seen.sort();
+
+        assert_eq!(seen, vec!["scene-1", "scene-2", "scene-3"]);
+    }
+
+    #[test]
+    fn playlist_cursor_pause_holds_the_current_item_until_resumed() {
+        let mut cursor = PlaylistCursor::new(playlist(false), 1_000, 1);
+
+        cursor.set_paused(true, 1_005);
+        assert_eq!(cursor.advance_if_due(1_500), None);
+        assert_eq!(cursor.skip(1_500), Some("scene-2"));
+
+        cursor.set_paused(false, 2_000);
+        assert_eq!(cursor.advance_if_due(2_059), None);
+        assert_eq!(cursor.advance_if_due(2_060), Some("scene-3"));
+    }
+
+    #[test]
+    fn collect_due_applies_starts_new_assignments_and_restarts_edited_playlists() {
+        let start = 1_000;
+        let mut cursors = BTreeMap::new();
+        let mut saved = BTreeMap::new();
+
+        let applies = collect_due_applies(
+            &mut cursors,
+            &mut saved,
+            vec![("DP-1".to_string(), playlist(false))],
+            start,
+            1,
+        );
+        assert_eq!(applies, vec![("DP-1".to_string(), "scene-1".to_string())]);
+
+        let applies = collect_due_applies(
+            &mut cursors,
+            &mut saved,
+            vec![("DP-1".to_string(), playlist(false))],
+            start + 5,
+            1,
+        );
+        assert!(applies.is_empty());
+
+        let mut edited = playlist(false);
+        edited.items.reverse();
+        let applies = collect_due_applies(
+            &mut cursors,
+            &mut saved,
+            vec![("DP-1".to_string(), edited)],
+            start + 6,
+            1,
+        );
+        assert_eq!(applies, vec![("DP-1".to_string(), "scene-3".to_string())]);
+
+        collect_due_applies(&mut cursors, &mut saved, Vec::new(), start, 1);
+        assert!(cursors.is_empty());
+    }
+
+    #[test]
+    fn collect_due_applies_keeps_the_shown_file_when_a_folder_changes() {
+        let mut folder = playlist(false);
+        folder.folder = Some(PlaylistFolder {
+            path: PathBuf::from("/wallpapers"),
+            pattern: None,
+            image_duration_secs: None,
+        });
+        let mut cursors = BTreeMap::new();
+        let mut saved = BTreeMap::new();
+        collect_due_applies(
+            &mut cursors,
+            &mut saved,
+            vec![("DP-1".to_string(), folder.clone())],
+            1_000,
+            1,
+        );
+        cursors.get_mut("DP-1").unwrap().skip(1_010);
+
+        let mut added = folder.clone();
+        added.items.insert(
+            0,
+            PlaylistEntry {
+                item_id: "scene-0".to_string(),
+                duration_secs: None,
+            },
+        );
+        let applies = collect_due_applies(
+            &mut cursors,
+            &mut saved,
+            vec![("DP-1".to_string(), added)],
+            1_020,
+            1,
+        );
+        assert!(applies.is_empty());
+        assert_eq!(cursors["DP-1"].current_item_id(), Some("scene-2"));
+        assert_eq!(cursors["DP-1"].rotation.elapsed(1_020), 10);
+
+        let mut removed = folder;
+        removed.items.remove(1);
+        let applies = collect_due_applies(
+            &mut cursors,
+            &mut saved,
+            vec![("DP-1".to_string(), removed)],
+            1_030,
+            1,
+        );
+        assert_eq!(applies, vec![("DP-1".to_string(), "scene-1".to_string())]);
+    }
+
+    #[test]
+    fn playlist_cursor_back_returns_to_the_previous_item() {
+        let mut cursor = PlaylistCursor::new(playlist(false), 1_000, 1);
+
+        assert_eq!(cursor.skip(1_010), Some("scene-2"));
+        assert_eq!(cursor.back(1_020), Some("scene-1"));
+        assert_eq!(cursor.back(1_030), Some("scene-3"));
+    }
+
+    #[test]
+    fn collect_due_applies_resumes_saved_rotations_that_still_fit() {
+        let mut saved_rotation = PlaylistCursor::new(playlist(false), 1_000, 1).rotation;
+        saved_rotation.skip(1_010);
+        let mut stale_rotation = saved_rotation.clone();
+        stale_rotation.playlist_id = 9;
+
+        let mut cursors = BTreeMap::new();
+        let mut saved = BTreeMap::from([
+            ("DP-1".to_string(), saved_rotation),
+            ("DP-2".to_string(), stale_rotation),
+        ]);
+        let mut other = playlist(false);
+        other.outputs = vec!["DP-2".to_string()];
+
+        let applies = collect_due_applies(
+            &mut cursors,
+            &mut saved,
+            vec![
+                ("DP-1".to_string(), playlist(false)),
+                ("DP-2".to_string(), other),
+            ],
+            1_020,
+            1,
+        );
+
+        assert_eq!(applies, vec![("DP-2".to_string(), "scene-1".to_string())]);
+        assert_eq!(cursors["DP-1"].current_item_id(), Some("scene-2"));
+        assert!(saved.is_empty());
+    }
+
+    #[test]
+    fn collect_due_preloads_requests_each_upcoming_item_once() {
+        let start = 1_000;
+        let cursors = BTreeMap::from([(
+            "DP-1".to_string(),
+            PlaylistCursor::new(playlist(false), start, 1),
+        )]);
+        let mut preloaded = BTreeMap::from([("DP-2".to_string(), "scene-9".to_string())]);
+
+        assert!(collect_due_preloads(&cursors, &mut preloaded, start, 5).is_empty());
+        assert!(!preloaded.contains_key("DP-2"));
+
+        let preloads = collect_due_preloads(&cursors, &mut preloaded, start + 5, 5);
+        assert_eq!(preloads, vec![("DP-1".to_string(), "scene-2".to_string())]);
+        assert!(collect_due_preloads(&cursors, &mut preloaded, start + 8, 5).is_empty());
+    }
+
+    #[test]
+    fn rotation_state_round_trips_through_the_state_file() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir()
+            .join(format!("playlist-scheduler-service-{unique}"))
+            .join(ROTATION_STATE_FILE);
+
+        assert_eq!(load_rotations(&path), Ok(BTreeMap::new()));
+
+        let mut rotation = PlaylistCursor::new(playlist(true), 1_000, u64::MAX).rotation;
+        rotation.pause(1_004);
+        let rotations = BTreeMap::from([("DP-1".to_string(), rotation)]);
+        save_rotations(&path, &rotations).unwrap();
+
+        assert_eq!(load_rotations(&path), Ok(rotations));
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,488 @@
+use std::fs;
+use std::path::PathBuf;
+
+use lwe_library::{
+    FolderScanner, LibraryDatabase, Playlist, PlaylistEntry, PlaylistFolder, WallpaperType,
+};
+
+use crate::models::{PlaylistFolderModel, PlaylistSaveInput};
+use crate::results::monitor_discovery::MonitorDiscoveryResult;
+use crate::results::playlist::{PlaylistLibraryItem, PlaylistPageResult};
+use crate::services::collection_service::CollectionService;
+use crate::services::library_service::LibraryService;
+use crate::services::monitor_service::MonitorService;
+use crate::services::playlist_scheduler_service::PlaylistSchedulerService;
+
+pub(crate) const MIN_PLAYLIST_DURATION_SECS: u32 = 5;
+
+pub struct PlaylistService;
+
+pub struct ScopedPlaylistService {
+    path: PathBuf,
+}
+
+impl PlaylistService {
+    pub fn load_page() -> Result<PlaylistPageResult, String> {
+        let playlists = Self::for_user_path().load_playlists()?;
+        let collections = CollectionService::for_user_path().load_collections()?;
+
+        let (library_items, library_issue) = match LibraryService::load_projection() {
+            Ok(projection) => (
+                projection
+                    .entries
+                    .into_iter()
+                    .filter_map(|entry| {
+                        entry.entry.library_item_id.map(|id| PlaylistLibraryItem {
+                            id,
+                            title: entry.entry.title,
+                        })
+                    })
+                    .collect(),
+                None,
+            ),
+            Err(reason) => (Vec::new(), Some(reason)),
+        };
+
+        let (monitors, monitor_discovery_issue) = match MonitorService::list_monitors() {
+            MonitorDiscoveryResult::Known(monitors) => (monitors, None),
+            MonitorDiscoveryResult::Unavailable { reason } => (Vec::new(), Some(reason)),
+        };
+
+        Ok(PlaylistPageResult {
+            playlists,
+            collections,
+            library_items,
+            library_issue,
+            monitors,
+            monitor_discovery_issue,
+            rotations: PlaylistSchedulerService::rotation_status(),
+        })
+    }
+
+    pub fn save_playlist(input: PlaylistSaveInput) -> Result<PlaylistPageResult, String> {
+        // Files only apply once they are part of the library
+        let folder = input
+            .folder
+            .as_ref()
+            .map(|folder| PathBuf::from(folder.path.trim()));
+        Self::for_user_path().save_playlist(input)?;
+        if let Some(folder) = folder {
+            LibraryService::add_local_sources(vec![folder], Vec::new())?;
+        }
+        Self::load_page()
+    }
+
+    pub fn delete_playlist(playlist_id: i64) -> Result<PlaylistPageResult, String> {
+        Self::for_user_path().delete_playlist(playlist_id)?;
+        Self::load_page()
+    }
+
+    pub fn assign_monitor(
+        monitor_id: &str,
+        playlist_id: Option<i64>,
+    ) -> Result<PlaylistPageResult, String> {
+        Self::for_user_path().assign_monitor(monitor_id, playlist_id)?;
+        PlaylistSchedulerService::sync();
+        Self::load_page()
+    }
+
+    pub fn for_user_path() -> ScopedPlaylistService {
+        Self::for_path(LibraryDatabase::default_path())
+    }
+
+    pub fn for_path(path: PathBuf) -> ScopedPlaylistService {
+        ScopedPlaylistService { path }
+    }
+
+    pub fn for_test(path: PathBuf) -> ScopedPlaylistService {
+        Self::for_path(path)
+    }
+}
+
+impl ScopedPlaylistService {
+    fn open(&self) -> Result<LibraryDatabase, String> {
+        LibraryDatabase::open(&self.path).map_err(|error| {
+            format!(
+                "Failed to open playlist database {}: {error:#}",
+                self.path.display()
+            )
+        })
+    }
+
+    pub fn load_playlists(&self) -> Result<Vec<Playlist>, String> {
+        self.open()?
+            .list_playlists()
+            .map_err(|error| format!("Failed to load playlists: {error:#}"))
+    }
+
+    /// Playlists keyed by the monitor they are assigned to, with folder
+    /// playlists filled with the files their folder holds right now
+    pub fn load_assignments(&self) -> Result<Vec<(String, Playlist)>, String> {
+        Ok(self
+            .load_playlists()?
+            .into_iter()
+            .filter(|playlist| !playlist.outputs.is_empty())
+            .map(|mut playlist| {
+                if let Some(folder) = &playlist.folder {
+                    playlist.items = folder_entries(folder);
+                }
+                playlist
+            })
+            .flat_map(|playlist| {
+                playlist
+                    .outputs
+                    .clone()
+                    .into_iter()
+                    .map(move |monitor_id| (monitor_id, playlist.clone()))
+            })
+            .collect())
+    }
+
+    pub fn save_playlist(&self, input: PlaylistSaveInput) -> Result<i64, String> {
+        let playlist = playlist_from_input(input)?;
+
+        self.open()?
+            .save_playlist(&playlist)
+            .map_err(|error| format!("Failed to save playlist '{}': {error:#}", playlist.name))
+    }
+
+    /// Make `playlist_id` the only playlist rotating on a monitor, or clear it
+    pub fn assign_monitor(&self, monitor_id: &str, playlist_id: Option<i64>) -> Result<(), String> {
+        let database = self.open()?;
+        let mut playlists = database
+            .list_playlists()
+            .map_err(|error| format!("Failed to load playlists: {error:#}"))?;
+
+        if let Some(missing) =
+            playlist_id.filter(|id| !playlists.iter().any(|playlist| playlist.id == *id))
+        {
+            return Err(format!("Playlist {missing} not found"));
+        }
+
+        for playlist in &mut playlists {
+            let assigned = playlist.outputs.iter().any(|output| output == monitor_id);
+            let wanted = Some(playlist.id) == playlist_id;
+            if assigned == wanted {
+                continue;
+            }
+
+            if wanted {
+                playlist.outputs.push(monitor_id.to_string());
+                playlist.outputs.sort();
+            } else {
+                playlist.outputs.retain(|output| output != monitor_id);
+            }
+            database.save_playlist(playlist).map_err(|error| {
+                format!("Failed to save playlist '{}': {error:#}", playlist.name)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn delete_playlist(&self, playlist_id: i64) -> Result<(), String> {
+        match self.open()?.delete_playlist(playlist_id) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(format!("Playlist {playlist_id} not found")),
+            Err(error) => Err(format!(
+                "Failed to delete playlist {playlist_id}: {error:#}"
+            )),
+        }
+    }
+}
+
+fn playlist_from_input(input: PlaylistSaveInput) -> Result<Playlist, String> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Playlist name must not be empty".to_string());
+    }
+
+    let durations = std::iter::once(Some(input.default_duration_secs))
+        .chain(input.items.iter().map(|item| item.duration_secs));
+    if durations
+        .flatten()
+        .any(|duration| duration < MIN_PLAYLIST_DURATION_SECS)
+    {
+        return Err(format!(
+            "Playlist durations must be at least {MIN_PLAYLIST_DURATION_SECS} seconds"
+        ));
+    }
+
+    let folder = input.folder.map(folder_from_input).transpose()?;
+
+    let mut monitor_ids = input.monitor_ids;
+    monitor_ids.sort();
+    monitor_ids.dedup();
+
+    Ok(Playlist {
+        id: input.id.unwrap_or(0),
+        name,
+        shuffle: input.shuffle,
+        transition: input.transition.as_str().to_string(),
+        default_duration_secs: input.default_duration_secs,
+        // A folder playlist reads its entries from the folder when it plays
+        items: match folder {
+            Some(_) => Vec::new(),
+            None => input
+                .items
+                .into_iter()
+                .map(|item| PlaylistEntry {
+                    item_id: item.item_id,
+                    duration_secs: item.duration_secs,
+                })
+                .collect(),
+        },
+        outputs: monitor_ids,
+        folder,
+    })
+}
+
+fn folder_from_input(input: PlaylistFolderModel) -> Result<PlaylistFolder, String> {
+    let path = PathBuf::from(input.path.trim());
+    if !path.is_dir() {
+        return Err(format!("Playlist folder {} does not exist", path.display()));
+    }
+    if input
+        .image_duration_secs
+        .is_some_and(|duration| duration < MIN_PLAYLIST_DURATION_SECS)
+    {
+        return Err(format!(
+            "Playlist durations must be at least {MIN_PLAYLIST_DURATION_SECS} seconds"
+        ));
+    }
+
+    Ok(PlaylistFolder {
+        path,
+        pattern: input
+            .pattern
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty()),
+        image_duration_secs: input.image_duration_secs,
+    })
+}
+
+/// Wallpapers directly inside `folder` that match its pattern, in file name
+/// order; images get the folder's image duration
+pub(crate) fn folder_entries(folder: &PlaylistFolder) -> Vec<PlaylistEntry> {
+    let entries = match fs::read_dir(&folder.path) {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!(
+                "playlist folder {} is unreadable: {error}",
+                folder.path.display()
+            );
+            return Vec::new();
+        }
+    };
+
+    let scanner = FolderScanner::new();
+    let mut items = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_default();
+            folder
+                .pattern
+                .as_deref()
+                .is_none_or(|pattern| matches_pattern(pattern, &name))
+        })
+        .filter_map(|path| scanner.process_file(&path))
+        .collect::<Vec<_>>();
+    items.sort_by(|a, b| a.source_path.cmp(&b.source_path));
+
+    items
+        .into_iter()
+        .map(|item| PlaylistEntry {
+            duration_secs: match item.wallpaper_type {
+                WallpaperType::Image | WallpaperType::AnimatedImage => folder.image_duration_secs,
+                _ => None,
+            },
+            item_id: item.id,
+        })
+        .collect()
+}
+
+/// Whether a file name matches any of the whitespace or comma separated
+/// globs in `pattern`; `*` and `?` are the only wildcards and case is ignored
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let name = name.to_lowercase().chars().collect::<Vec<_>>();
+    pattern
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|glob| !glob.is_empty())
+        .any(|glob| matches_glob(&glob.to_lowercase().chars().collect::<Vec<_>>(), &name))
+}
+
+fn matches_glob(glob: &[char], name: &[char]) -> bool {
+    match glob.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| matches_glob(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && matches_glob(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && matches_glob(rest, &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{PlaylistEntryInput, PlaylistTransition};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn test_database_path() -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+
+        std::env::temp_dir()
+            .join(format!("lwe-playlist-service-{unique}"))
+            .join("library.db")
+    }
+
+    fn save_input(name: &str, monitor_ids: &[&str]) -> PlaylistSaveInput {
+        PlaylistSaveInput {
+            id: None,
+            name: name.to_string(),
+            shuffle: true,
+            transition: PlaylistTransition::Fade,
+            default_duration_secs: 600,
+            items: vec![
+                PlaylistEntryInput {
+                    item_id: "scene-7".to_string(),
+                    duration_secs: Some(30),
+                },
+                PlaylistEntryInput {
+                    item_id: "video-3".to_string(),
+                    duration_secs: None,
+                },
+            ],
+            monitor_ids: monitor_ids.iter().map(|id| id.to_string()).collect(),
+            folder: None,
+        }
+    }
+
+    #[test]
+    fn playlist_service_round_trips_order_durations_and_assignments() {
+        let service = PlaylistService::for_test(test_database_path());
+
+        let id = service
+            .save_playlist(save_input("  Evening  ", &["DP-1", "DP-1"]))
+            .unwrap();
+        let playlists = service.load_playlists().unwrap();
+
+        assert_eq!(playlists.len(), 1);
+        assert_eq!(playlists[0].id, id);
+        assert_eq!(playlists[0].name, "Evening");
+        assert_eq!(playlists[0].transition, "fade");
+        assert_eq!(playlists[0].items[0].item_id, "scene-7");
+        assert_eq!(playlists[0].items[1].duration_secs, None);
+        assert_eq!(playlists[0].outputs, vec!["DP-1".to_string()]);
+
+        let assignments = service.load_assignments().unwrap();
+        assert_eq!(assignments.len(), 1);
+        assert_eq!(assignments[0].0, "DP-1");
+    }
+
+    #[test]
+    fn playlist_service_rejects_empty_names_and_short_durations() {
+        let service = PlaylistService::for_test(test_database_path());
+
+        assert!(service.save_playlist(save_input("   ", &[])).is_err());
+
+        let mut input = save_input("Fast", &[]);
+        input.items[0].duration_secs = Some(1);
+        assert!(service.save_playlist(input).is_err());
+
+        assert!(service.load_playlists().unwrap().is_empty());
+    }
+
+    #[test]
+    fn playlist_service_reports_missing_playlist_on_delete() {
+        let service = PlaylistService::for_test(test_database_path());
+
+        let id = service.save_playlist(save_input("Evening", &[])).unwrap();
+
+        assert!(service.delete_playlist(id).is_ok());
+        assert!(service.delete_playlist(id).is_err());
+    }
+
+    #[test]
+    fn playlist_service_assign_monitor_moves_the_monitor_between_playlists() {
+        let service = PlaylistService::for_test(test_database_path());
+
+        let evening = service
+            .save_playlist(save_input("Evening", &["DP-1", "HDMI-A-1"]))
+            .unwrap();
+        let morning = service.save_playlist(save_input("Morning", &[])).unwrap();
+
+        service.assign_monitor("DP-1", Some(morning)).unwrap();
+        let outputs = |id: i64| {
+            service
+                .load_playlists()
+                .unwrap()
+                .into_iter()
+                .find(|playlist| playlist.id == id)
+                .unwrap()
+                .outputs
+        };
+        assert_eq!(outputs(evening), vec!["HDMI-A-1".to_string()]);
+        assert_eq!(outputs(morning), vec!["DP-1".to_string()]);
+
+        service.assign_monitor("DP-1", None).unwrap();
+        assert!(outputs(morning).is_empty());
+
+        assert_eq!(
+            service.assign_monitor("DP-1", Some(morning + evening + 1)),
+            Err(format!("Playlist {} not found", morning + evening + 1))
+        );
+    }
+
+    #[test]
+    fn playlist_service_matches_file_names_against_globs() {
+        assert!(matches_pattern("*.mp4 *.webm", "Beach.MP4"));
+        assert!(matches_pattern("*.png,night-??.jpg", "night-01.jpg"));
+        assert!(!matches_pattern("*.png,night-??.jpg", "night-1.jpg"));
+        assert!(!matches_pattern("*.mp4", "clip.mp4.part"));
+    }
+
+    #[test]
+    fn playlist_service_fills_folder_playlists_from_matching_files() {
+        let database = test_database_path();
+        let folder = database.with_file_name("wallpapers");
+        fs::create_dir_all(&folder).unwrap();
+        for name in ["b.mp4", "a.jpg", "notes.txt", "c.mkv"] {
+            fs::write(folder.join(name), b"").unwrap();
+        }
+        let service = PlaylistService::for_test(database);
+
+        let mut input = save_input("Folder", &["DP-1"]);
+        input.folder = Some(PlaylistFolderModel {
+            path: folder.to_string_lossy().into_owned(),
+            pattern: Some("*.mp4 *.jpg".to_string()),
+            image_duration_secs: Some(20),
+        });
+        service.save_playlist(input).unwrap();
+
+        let stored = service.load_playlists().unwrap();
+        assert!(stored[0].items.is_empty());
+        assert_eq!(stored[0].folder.as_ref().unwrap().path, folder);
+
+        let (_, playlist) = service.load_assignments().unwrap().remove(0);
+        let durations = playlist
+            .items
+            .iter()
+            .map(|entry| entry.duration_secs)
+            .collect::<Vec<_>>();
+        assert_eq!(durations, vec![Some(20), None]);
+
+        let mut missing = save_input("Missing", &[]);
+        missing.folder = Some(PlaylistFolderModel {
+            path: folder.join("gone").to_string_lossy().into_owned(),
+            pattern: None,
+            image_duration_secs: None,
+        });
+        assert!(service.save_playlist(missing).is_err());
+    }
+}
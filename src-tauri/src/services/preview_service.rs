@@ -0,0 +1,90 @@
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use lwe_engine::PreviewPlayer;
+use lwe_library::WorkshopProjectType;
+
+use crate::services::desktop_service::DesktopService;
+
+pub const PREVIEW_SCHEME: &str = "lwe-preview";
+/// How long a newly selected wallpaper may take to produce its first frame
+const FIRST_FRAME_TIMEOUT: Duration = Duration::from_secs(3);
+const FRAME_POLL_INTERVAL: Duration = Duration::from_millis(15);
+
+struct PreviewState {
+    item_id: String,
+    player: PreviewPlayer,
+}
+
+fn preview_slot() -> &'static Mutex<Option<PreviewState>> {
+    static SLOT: OnceLock<Mutex<Option<PreviewState>>> = OnceLock::new();
+    SLOT.get_or_init(|| Mutex::new(None))
+}
+
+/// Live previews of library videos, decoded by one shared player in the shell
+/// so they work without the wallpaper engine running.
+pub struct PreviewService;
+
+impl PreviewService {
+    /// Resolves a `lwe-preview://localhost/<item id>` request path to the
+    /// item's latest preview frame as PNG, switching the player to that item.
+    pub fn frame_for_request_path(request_path: &str) -> Result<Vec<u8>, String> {
+        let item_id = request_path.trim_start_matches('/');
+        let mut slot = preview_slot()
+            .lock()
+            .map_err(|_| "Preview player lock was poisoned".to_string())?;
+
+        if slot.as_ref().map(|state| state.item_id.as_str()) != Some(item_id) {
+            let (project_type, path) = DesktopService::resolve_real_apply_source(item_id)?;
+            if project_type != WorkshopProjectType::Video {
+                return Err(format!("Library item {item_id} has no video to preview"));
+            }
+
+            let mut player = match slot.take() {
+                Some(state) => state.player,
+                None => PreviewPlayer::new()
+                    .map_err(|error| format!("Failed to start preview player: {error:#}"))?,
+            };
+            player
+                .load(&path)
+                .map_err(|error| format!("Failed to preview {item_id}: {error:#}"))?;
+            *slot = Some(PreviewState {
+                item_id: item_id.to_string(),
+                player,
+            });
+        }
+
+        let Some(state) = slot.as_mut() else {
+            return Err("Preview player is unavailable".to_string());
+        };
+        let deadline = Instant::now() + FIRST_FRAME_TIMEOUT;
+        loop {
+            state
+                .player
+                .update()
+                .map_err(|error| format!("Failed to render preview of {item_id}: {error:#}"))?;
+            if state.player.has_frame() {
+                break;
+            }
+            if Instant::now() >= deadline {
+                return Err(format!("Preview of {item_id} produced no frame in time"));
+            }
+            thread::sleep(FRAME_POLL_INTERVAL);
+        }
+
+        state
+            .player
+            .frame_png()
+            .map_err(|error| format!("Failed to encode preview of {item_id}: {error:#}"))
+    }
+
+    /// Release the shared player once no preview is on screen
+    pub fn stop() -> Result<(), String> {
+        preview_slot()
+            .lock()
+            .map_err(|_| "Preview player lock was poisoned".to_string())?
+            .take();
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use crate::results::desktop::DesktopBulkApplyResult;
+use crate::results::desktop_persistence::DesktopPersistenceLoad;
+use crate::results::profile::ProfileSummary;
+use crate::results::settings_persistence::{
+    PersistedProfile, PersistedSettings, SettingsPersistenceLoad, SettingsPersistenceWrite,
+};
+use crate::services::desktop_persistence_service::DesktopPersistenceService;
+use crate::services::desktop_service::DesktopService;
+use crate::services::settings_persistence_service::{
+    ScopedSettingsPersistenceService, SettingsPersistenceService,
+};
+
+/// Named wallpaper setups kept in the `[profiles]` settings table
+pub struct ProfileService;
+
+impl ProfileService {
+    pub fn list() -> Result<Vec<ProfileSummary>, String> {
+        let (_, settings) = load_settings()?;
+        Ok(profile_summaries(&settings.profiles))
+    }
+
+    /// Save what every monitor shows and the `[outputs]` settings under
+    /// `name`, replacing a profile of the same name
+    pub fn save(name: &str) -> Result<ProfileSummary, String> {
+        let name = profile_name(name)?;
+        let assignments = match DesktopPersistenceService::for_user_path()?.load_state() {
+            DesktopPersistenceLoad::Loaded(assignments) => assignments,
+            DesktopPersistenceLoad::Unavailable { reason } => return Err(reason),
+        };
+        if assignments.is_empty() {
+            return Err("No monitor shows a Library wallpaper to save".to_string());
+        }
+
+        let (persistence, mut settings) = load_settings()?;
+        let profile = PersistedProfile {
+            assignments,
+            outputs: settings.outputs.clone(),
+        };
+        let summary = profile_summary(name, &profile);
+        settings.profiles.insert(name.to_string(), profile);
+        match persistence.save_settings(&settings) {
+            SettingsPersistenceWrite::Saved => Ok(summary),
+            SettingsPersistenceWrite::Unavailable { reason } => Err(reason),
+        }
+    }
+
+    /// Switch every monitor to the profile saved as `name`
+    pub fn load(name: &str) -> Result<DesktopBulkApplyResult, String> {
+        let name = profile_name(name)?;
+        let (_, settings) = load_settings()?;
+        let profile = settings
+            .profiles
+            .get(name)
+            .ok_or_else(|| format!("There is no profile named {name}"))?;
+
+        DesktopService::apply_profile(profile)
+    }
+}
+
+fn load_settings() -> Result<(ScopedSettingsPersistenceService, PersistedSettings), String> {
+    let persistence = SettingsPersistenceService::for_user_path()?;
+    match persistence.load_settings() {
+        SettingsPersistenceLoad::Loaded(settings) => Ok((persistence, settings)),
+        SettingsPersistenceLoad::Unavailable { reason } => Err(reason),
+    }
+}
+
+fn profile_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("A profile needs a name".to_string());
+    }
+    Ok(name)
+}
+
+fn profile_summary(name: &str, profile: &PersistedProfile) -> ProfileSummary {
+    ProfileSummary {
+        name: name.to_string(),
+        monitor_ids: profile.assignments.keys().cloned().collect(),
+    }
+}
+
+fn profile_summaries(profiles: &BTreeMap<String, PersistedProfile>) -> Vec<ProfileSummary> {
+    profiles
+        .iter()
+        .map(|(name, profile)| profile_summary(name, profile))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_names_are_trimmed_and_required() {
+        assert_eq!(profile_name("  evening "), Ok("evening"));
+        assert!(profile_name(" ").is_err());
+    }
+
+    #[test]
+    fn profile_summaries_list_assigned_monitors_by_name() {
+        let profiles = BTreeMap::from([
+            (
+                "work".to_string(),
+                PersistedProfile {
+                    assignments: BTreeMap::from([
+                        ("HDMI-A-1".to_string(), "video-2".to_string()),
+                        ("DP-1".to_string(), "video-1".to_string()),
+                    ]),
+                    outputs: BTreeMap::new(),
+                },
+            ),
+            ("evening".to_string(), PersistedProfile::default()),
+        ]);
+
+        assert_eq!(
+            profile_summaries(&profiles),
+            vec![
+                ProfileSummary {
+                    name: "evening".to_string(),
+                    monitor_ids: Vec::new(),
+                },
+                ProfileSummary {
+                    name: "work".to_string(),
+                    monitor_ids: vec!["DP-1".to_string(), "HDMI-A-1".to_string()],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::process::{Command, Stdio};
+
+use lwe_core::PowerConfig;
+use lwe_engine::cgroup::{current_cgroup, unit_of};
+use lwe_engine::ResourceLimits;
+
+use crate::results::settings_persistence::{PersistedResources, SettingsPersistenceLoad};
+use crate::services::service_install_service::SYSTEMD_UNIT_NAME;
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+
+/// Caps the `lwe.service` unit with the `[resources]` limits from settings.
+pub struct ResourceLimitsService;
+
+impl ResourceLimitsService {
+    /// Set the unit's runtime `CPUQuota=` and `MemoryHigh=` when LWE was
+    /// started by its systemd unit; other launches are left alone.
+    pub fn start() {
+        let Some(cgroup) = current_cgroup() else {
+            return;
+        };
+        if unit_of(&cgroup) != Some(SYSTEMD_UNIT_NAME) {
+            return;
+        }
+
+        let limits = ResourceLimits::from_power(&power_config_from(&Self::config()));
+        let status = Command::new("systemctl")
+            .args(["--user", "set-property", "--runtime", SYSTEMD_UNIT_NAME])
+            .args(limits.properties())
+            .stdin(Stdio::null())
+            .status();
+        match status {
+            Ok(status) if status.success() => {}
+            Ok(status) => eprintln!("systemctl set-property exited with {status}"),
+            Err(reason) => eprintln!("failed to run systemctl set-property: {reason}"),
+        }
+    }
+
+    fn config() -> PersistedResources {
+        let loaded = SettingsPersistenceService::for_user_path()
+            .map(|service| service.load_settings())
+            .unwrap_or_else(|reason| SettingsPersistenceLoad::Unavailable { reason });
+
+        match loaded {
+            SettingsPersistenceLoad::Loaded(settings) => settings.resources,
+            SettingsPersistenceLoad::Unavailable { reason } => {
+                eprintln!("resource limit settings unavailable: {reason}");
+                PersistedResources::default()
+            }
+        }
+    }
+}
+
+fn power_config_from(resources: &PersistedResources) -> PowerConfig {
+    PowerConfig {
+        cpu_quota_percent: resources.cpu_quota_percent.unwrap_or(0),
+        memory_high_mb: resources.memory_high_mb.unwrap_or(0),
+        ..PowerConfig::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_limits_follow_resources_settings() {
+        let limits = ResourceLimits::from_power(&power_config_from(&PersistedResources {
+            cpu_quota_percent: Some(25),
+            memory_high_mb: Some(512),
+        }));
+        assert_eq!(limits.properties(), ["CPUQuota=25%", "MemoryHigh=512M"]);
+
+        let unlimited =
+            ResourceLimits::from_power(&power_config_from(&PersistedResources::default()));
+        assert_eq!(unlimited, ResourceLimits::default());
+    }
+}
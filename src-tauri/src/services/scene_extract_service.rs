@@ -0,0 +1,201 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use lwe_library::{decode_tex, read_package_file, PackageEntry, TexContent};
+
+/// Name of the package scene projects ship their files in
+const SCENE_PACKAGE: &str = "scene.pkg";
+const SCENE_JSON: &str = "scene.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SceneExtractReport {
+    /// Files written, not counting converted textures
+    pub files: usize,
+    /// Textures written as PNG or MP4
+    pub textures: usize,
+    /// Textures that could not be converted, with the reason
+    pub texture_failures: Vec<(String, String)>,
+    /// The indented scene description, when the package has one
+    pub scene_json: Option<PathBuf>,
+}
+
+/// Unpacks Wallpaper Engine scene projects so wallpapers that fail to
+/// render can be inspected by hand.
+pub struct SceneExtractService;
+
+impl SceneExtractService {
+    /// Writes the files of a `scene.pkg`, or of a scene project directory,
+    /// into `out`. Every `.tex` is also written next to itself as PNG, or as
+    /// MP4 for video textures, and `scene.json` is indented for reading.
+    pub fn extract(source: &Path, out: &Path) -> Result<SceneExtractReport, String> {
+        let entries = Self::read_entries(source)?;
+        let mut report = SceneExtractReport {
+            files: 0,
+            textures: 0,
+            texture_failures: Vec::new(),
+            scene_json: None,
+        };
+
+        for entry in entries {
+            let path = out.join(entry_path(&entry.name).ok_or_else(|| {
+                format!(
+                    "{} has an entry outside the package: {}",
+                    source.display(),
+                    entry.name
+                )
+            })?);
+            let data = if entry.name == SCENE_JSON {
+                report.scene_json = Some(path.clone());
+                indent_json(&entry.data).unwrap_or(entry.data)
+            } else {
+                entry.data
+            };
+            write_file(&path, &data)?;
+            report.files += 1;
+
+            if path.extension().is_some_and(|extension| extension == "tex") {
+                match decode_tex(&data) {
+                    Ok(TexContent::Image(image)) => {
+                        let png = path.with_extension("png");
+                        image.save(&png).map_err(|error| {
+                            format!("Failed to write {}: {error}", png.display())
+                        })?;
+                        report.textures += 1;
+                    }
+                    Ok(TexContent::Mp4(video)) => {
+                        write_file(&path.with_extension("mp4"), &video)?;
+                        report.textures += 1;
+                    }
+                    Err(error) => report
+                        .texture_failures
+                        .push((entry.name, format!("{error:#}"))),
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// The package itself, the `scene.pkg` of a project directory, or the
+    /// loose files of a project that ships without one
+    fn read_entries(source: &Path) -> Result<Vec<PackageEntry>, String> {
+        let package = if source.is_dir() {
+            source.join(SCENE_PACKAGE)
+        } else {
+            source.to_path_buf()
+        };
+        if package.is_file() {
+            return read_package_file(&package).map_err(|error| format!("{error:#}"));
+        }
+        if !source.is_dir() {
+            return Err(format!("{} does not exist", source.display()));
+        }
+
+        let mut entries = Vec::new();
+        collect_files(source, source, &mut entries)?;
+        if entries.is_empty() {
+            return Err(format!("{} has no scene files", source.display()));
+        }
+        Ok(entries)
+    }
+}
+
+fn collect_files(root: &Path, dir: &Path, entries: &mut Vec<PackageEntry>) -> Result<(), String> {
+    let read_error =
+        |path: &Path, error: std::io::Error| format!("Failed to read {}: {error}", path.display());
+    for entry in fs::read_dir(dir).map_err(|error| read_error(dir, error))? {
+        let path = entry.map_err(|error| read_error(dir, error))?.path();
+        if path.is_dir() {
+            collect_files(root, &path, entries)?;
+            continue;
+        }
+        let Ok(name) = path.strip_prefix(root) else {
+            continue;
+        };
+        entries.push(PackageEntry {
+            name: name.to_string_lossy().into_owned(),
+            data: fs::read(&path).map_err(|error| read_error(&path, error))?,
+        });
+    }
+    Ok(())
+}
+
+/// `name` as a relative path, or `None` if it would leave the output directory
+fn entry_path(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        .then(|| path.to_path_buf())
+}
+
+fn indent_json(data: &[u8]) -> Option<Vec<u8>> {
+    let value = serde_json::from_slice::<serde_json::Value>(data).ok()?;
+    serde_json::to_vec_pretty(&value).ok()
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed to create {}: {error}", parent.display()))?;
+    }
+    fs::write(path, data).map_err(|error| format!("Failed to write {}: {error}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let sized = |text: &str| {
+            let mut bytes = (text.len() as u32).to_le_bytes().to_vec();
+            bytes.extend_from_slice(text.as_bytes());
+            bytes
+        };
+        let mut header = sized("PKGV0019");
+        header.extend((entries.len() as u32).to_le_bytes());
+        let mut data = Vec::new();
+        for (name, contents) in entries {
+            header.extend(sized(name));
+            header.extend((data.len() as u32).to_le_bytes());
+            header.extend((contents.len() as u32).to_le_bytes());
+            data.extend_from_slice(contents);
+        }
+        header.extend(data);
+        header
+    }
+
+    #[test]
+    fn extract_writes_entries_and_reports_unreadable_textures() {
+        let root = std::env::temp_dir().join(format!("lwe-scene-extract-{}", std::process::id()));
+        let project = root.join("project");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(
+            project.join(SCENE_PACKAGE),
+            package(&[
+                (SCENE_JSON, br#"{"objects":[]}"#),
+                ("materials/sky.tex", b"not a texture"),
+            ]),
+        )
+        .unwrap();
+
+        let out = root.join("out");
+        let report = SceneExtractService::extract(&project, &out).unwrap();
+        let scene = fs::read_to_string(out.join(SCENE_JSON)).unwrap();
+        let sky = fs::read(out.join("materials/sky.tex")).unwrap();
+
+        fs::write(project.join(SCENE_PACKAGE), package(&[("../escape", b"")])).unwrap();
+        let escape = SceneExtractService::extract(&project, &out);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(report.files, 2);
+        assert_eq!(report.textures, 0);
+        assert_eq!(report.texture_failures.len(), 1);
+        assert_eq!(report.texture_failures[0].0, "materials/sky.tex");
+        assert_eq!(report.scene_json, Some(out.join(SCENE_JSON)));
+        assert_eq!(scene, "{\n  \"objects\": []\n}");
+        assert_eq!(sky, b"not a texture");
+        assert!(escape
+            .unwrap_err()
+            .contains("outside the package: ../escape"));
+    }
+}
@@ -0,0 +1,453 @@
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use lwe_core::{Coordinates, DaySchedule, ScheduleSlot};
+use zbus::blocking::{Connection, Proxy};
+use zbus::zvariant::OwnedObjectPath;
+
+use crate::results::desktop::DesktopApplyResult;
+use crate::results::monitor_discovery::MonitorDiscoveryResult;
+use crate::results::schedule::{ScheduleSlotStatus, ScheduleStatus};
+use crate::results::settings_persistence::{PersistedSchedule, SettingsPersistenceLoad};
+use crate::services::desktop_service::DesktopService;
+use crate::services::monitor_service::MonitorService;
+use crate::services::playlist_service::PlaylistService;
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+
+const SCHEDULE_TICK: Duration = Duration::from_secs(30);
+const GEOCLUE: &str = "org.freedesktop.GeoClue2";
+/// GeoClue only answers apps it can match to a desktop file
+const GEOCLUE_DESKTOP_ID: &str = "dev.lwe.app";
+/// GeoClue accuracy level for a city, plenty for sunrise and sunset
+const GEOCLUE_CITY_ACCURACY: u32 = 4;
+
+#[derive(Default)]
+struct ScheduleState {
+    /// Slot whose wallpaper was last applied
+    applied: Option<String>,
+    /// Slot picked by hand and the unix time the schedule takes over again
+    pinned: Option<(String, i64)>,
+    /// Location reported by GeoClue
+    located: Option<Coordinates>,
+    /// Whether a GeoClue lookup was started
+    locating: bool,
+    /// Last configuration problem reported, so it is logged once
+    issue: Option<String>,
+}
+
+fn schedule_state() -> MutexGuard<'static, ScheduleState> {
+    static STATE: OnceLock<Mutex<ScheduleState>> = OnceLock::new();
+    STATE
+        .get_or_init(|| Mutex::new(ScheduleState::default()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Build the schedule from `[schedule]`; configured coordinates win over
+/// the located ones
+pub(crate) fn day_schedule(
+    settings: &PersistedSchedule,
+    located: Option<Coordinates>,
+) -> Result<DaySchedule, String> {
+    let slots = settings
+        .slots
+        .iter()
+        .map(|slot| {
+            let start = slot
+                .start
+                .parse()
+                .map_err(|reason| format!("Schedule slot {}: {reason}", slot.name))?;
+            Ok(ScheduleSlot {
+                name: slot.name.clone(),
+                start,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let location = match (settings.latitude, settings.longitude) {
+        (Some(latitude), Some(longitude)) => Some(Coordinates {
+            latitude,
+            longitude,
+        }),
+        _ => located,
+    };
+
+    Ok(DaySchedule { slots, location })
+}
+
+/// The slot to show at `now` and when that changes: the pinned slot until
+/// its time is up, otherwise the scheduled one
+pub(crate) fn due_slot(
+    schedule: &DaySchedule,
+    pinned: &mut Option<(String, i64)>,
+    now: i64,
+) -> Option<(usize, i64)> {
+    let active = schedule.active_at(now)?;
+    if let Some((name, until)) = pinned.as_ref() {
+        let index = schedule.slots.iter().position(|slot| &slot.name == name);
+        match index {
+            Some(index) if now < *until => return Some((index, *until)),
+            _ => *pinned = None,
+        }
+    }
+    Some((active.index, active.next_change))
+}
+
+/// Monitors the schedule drives: the configured ones (all when empty) that
+/// are connected and not rotating a playlist
+pub(crate) fn schedule_targets(
+    configured: &[String],
+    connected: &[String],
+    rotating: &[String],
+) -> Vec<String> {
+    connected
+        .iter()
+        .filter(|monitor_id| configured.is_empty() || configured.contains(monitor_id))
+        .filter(|monitor_id| !rotating.contains(monitor_id))
+        .cloned()
+        .collect()
+}
+
+/// Switches wallpapers by time of day from `[schedule]` in `settings.toml`
+pub struct ScheduleService;
+
+impl ScheduleService {
+    /// Check the schedule on a background thread
+    pub fn start() {
+        let spawned = thread::Builder::new()
+            .name("lwe-schedule".to_string())
+            .spawn(|| loop {
+                Self::sync();
+                thread::sleep(SCHEDULE_TICK);
+            });
+
+        if let Err(reason) = spawned {
+            eprintln!("failed to start the wallpaper schedule: {reason}");
+        }
+    }
+
+    /// Apply the due slot's wallpaper when the slot changed
+    pub fn sync() {
+        let settings = schedule_settings();
+        let due = {
+            let mut state = schedule_state();
+            if settings.slots.is_empty() {
+                *state = ScheduleState {
+                    located: state.located,
+                    locating: state.locating,
+                    ..ScheduleState::default()
+                };
+                return;
+            }
+
+            let schedule = match day_schedule(&settings, state.located) {
+                Ok(schedule) => schedule,
+                Err(reason) => {
+                    if state.issue.as_ref() != Some(&reason) {
+                        eprintln!("wallpaper schedule is not running: {reason}");
+                        state.issue = Some(reason);
+                    }
+                    return;
+                }
+            };
+            state.issue = None;
+            if schedule.uses_sun() && schedule.location.is_none() && !state.locating {
+                state.locating = true;
+                locate();
+            }
+
+            let ScheduleState {
+                applied, pinned, ..
+            } = &mut *state;
+            let Some((index, _)) = due_slot(&schedule, pinned, unix_now()) else {
+                return;
+            };
+            let name = &schedule.slots[index].name;
+            if applied.as_ref() == Some(name) {
+                return;
+            }
+            *applied = Some(name.clone());
+            settings.slots[index].clone()
+        };
+
+        let connected = match MonitorService::list_monitors() {
+            MonitorDiscoveryResult::Known(monitors) => monitors
+                .into_iter()
+                .map(|monitor| monitor.id)
+                .collect::<Vec<_>>(),
+            MonitorDiscoveryResult::Unavailable { reason } => {
+                eprintln!("wallpaper schedule could not list monitors: {reason}");
+                schedule_state().applied = None;
+                return;
+            }
+        };
+        let rotating = PlaylistService::for_user_path()
+            .load_assignments()
+            .map(|assignments| {
+                assignments
+                    .into_iter()
+                    .map(|(monitor_id, _)| monitor_id)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        for monitor_id in schedule_targets(&settings.monitors, &connected, &rotating) {
+            match DesktopService::apply_to_monitor(&monitor_id, &due.item) {
+                Ok(
+                    DesktopApplyResult::Applied { .. }
+                    | DesktopApplyResult::AppliedWithBackend { .. },
+                ) => {}
+                Ok(result) => eprintln!(
+                    "wallpaper schedule could not apply {} to {monitor_id}: {result:?}",
+                    due.item
+                ),
+                Err(reason) => eprintln!(
+                    "wallpaper schedule could not apply {} to {monitor_id}: {reason}",
+                    due.item
+                ),
+            }
+        }
+    }
+
+    pub fn status() -> ScheduleStatus {
+        let settings = schedule_settings();
+        let mut state = schedule_state();
+        let slots = settings
+            .slots
+            .iter()
+            .map(|slot| ScheduleSlotStatus {
+                name: slot.name.clone(),
+                start: slot.start.clone(),
+                item_id: slot.item.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let schedule = match day_schedule(&settings, state.located) {
+            Ok(schedule) => schedule,
+            Err(reason) => {
+                return ScheduleStatus {
+                    slots,
+                    issue: Some(reason),
+                    ..ScheduleStatus::default()
+                }
+            }
+        };
+        let due = due_slot(&schedule, &mut state.pinned, unix_now());
+
+        ScheduleStatus {
+            slots,
+            active_slot: due.map(|(index, _)| schedule.slots[index].name.clone()),
+            next_change: due.map(|(_, next_change)| next_change),
+            pinned: state.pinned.is_some(),
+            issue: None,
+        }
+    }
+
+    /// Show `slot` until the schedule would next switch, or follow the
+    /// schedule again when `None`
+    pub fn pin(slot: Option<&str>) -> Result<(), String> {
+        {
+            let settings = schedule_settings();
+            let mut state = schedule_state();
+            match slot {
+                Some(name) => {
+                    let schedule = day_schedule(&settings, state.located)?;
+                    if !schedule.slots.iter().any(|slot| slot.name == name) {
+                        return Err(format!("No schedule slot named {name}"));
+                    }
+                    let active = schedule
+                        .active_at(unix_now())
+                        .ok_or_else(|| "The schedule has no slots".to_string())?;
+                    state.pinned = Some((name.to_string(), active.next_change));
+                }
+                None => state.pinned = None,
+            }
+        }
+
+        Self::sync();
+        Ok(())
+    }
+}
+
+fn schedule_settings() -> PersistedSchedule {
+    let loaded = SettingsPersistenceService::for_user_path()
+        .map(|service| service.load_settings())
+        .unwrap_or_else(|reason| SettingsPersistenceLoad::Unavailable { reason });
+
+    match loaded {
+        SettingsPersistenceLoad::Loaded(settings) => settings.schedule,
+        SettingsPersistenceLoad::Unavailable { reason } => {
+            eprintln!("schedule settings unavailable: {reason}");
+            PersistedSchedule::default()
+        }
+    }
+}
+
+/// Ask GeoClue for the location on its own thread; it may wait for
+/// permission, and until it answers the sun follows the fallback times
+fn locate() {
+    let spawned = thread::Builder::new()
+        .name("lwe-geoclue".to_string())
+        .spawn(|| match geoclue_location() {
+            Ok(location) => {
+                schedule_state().located = Some(location);
+                ScheduleService::sync();
+            }
+            Err(reason) => eprintln!(
+                "location unavailable, sunrise and sunset fall back to 06:00 and 18:00: {reason}"
+            ),
+        });
+
+    if let Err(reason) = spawned {
+        eprintln!("failed to look up the location: {reason}");
+    }
+}
+
+fn geoclue_location() -> Result<Coordinates, String> {
+    let connection = Connection::system()
+        .map_err(|error| format!("Failed to connect to the system bus: {error}"))?;
+    let manager = Proxy::new(
+        &connection,
+        GEOCLUE,
+        "/org/freedesktop/GeoClue2/Manager",
+        "org.freedesktop.GeoClue2.Manager",
+    )
+    .map_err(|error| format!("Failed to create a GeoClue proxy: {error}"))?;
+    let client_path: OwnedObjectPath = manager
+        .call("GetClient", &())
+        .map_err(|error| format!("GeoClue is not available: {error}"))?;
+    let client = Proxy::new(
+        &connection,
+        GEOCLUE,
+        client_path,
+        "org.freedesktop.GeoClue2.Client",
+    )
+    .map_err(|error| format!("Failed to create a GeoClue client proxy: {error}"))?;
+
+    client
+        .set_property("DesktopId", GEOCLUE_DESKTOP_ID)
+        .and_then(|()| client.set_property("RequestedAccuracyLevel", GEOCLUE_CITY_ACCURACY))
+        .map_err(|error| format!("Failed to configure the GeoClue client: {error}"))?;
+    let mut updates = client
+        .receive_signal("LocationUpdated")
+        .map_err(|error| format!("Failed to watch GeoClue: {error}"))?;
+    client
+        .call::<_, _, ()>("Start", &())
+        .map_err(|error| format!("GeoClue refused the location: {error}"))?;
+
+    let update = updates
+        .next()
+        .ok_or_else(|| "GeoClue stopped before reporting a location".to_string())?;
+    let (_, location_path): (OwnedObjectPath, OwnedObjectPath) = update
+        .body()
+        .deserialize()
+        .map_err(|error| format!("Unexpected GeoClue location update: {error}"))?;
+    let location = Proxy::new(
+        &connection,
+        GEOCLUE,
+        location_path,
+        "org.freedesktop.GeoClue2.Location",
+    )
+    .map_err(|error| format!("Failed to create a GeoClue location proxy: {error}"))?;
+    let coordinates = location
+        .get_property("Latitude")
+        .and_then(|latitude| {
+            Ok(Coordinates {
+                latitude,
+                longitude: location.get_property("Longitude")?,
+            })
+        })
+        .map_err(|error| format!("Failed to read the GeoClue location: {error}"))?;
+
+    let _ = client.call::<_, _, ()>("Stop", &());
+    Ok(coordinates)
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::settings_persistence::PersistedScheduleSlot;
+
+    fn settings(slots: &[(&str, &str)]) -> PersistedSchedule {
+        PersistedSchedule {
+            slots: slots
+                .iter()
+                .map(|(name, start)| PersistedScheduleSlot {
+                    name: name.to_string(),
+                    start: start.to_string(),
+                    item: format!("{name}-item"),
+                })
+                .collect(),
+            ..PersistedSchedule::default()
+        }
+    }
+
+    #[test]
+    fn schedule_slots_must_have_valid_starts() {
+        let error =
+            day_schedule(&settings(&[("day", "07:00"), ("night", "dusk")]), None).unwrap_err();
+        assert!(error.starts_with("Schedule slot night: "), "{error}");
+
+        let located = Coordinates {
+            latitude: 1.0,
+            longitude: 2.0,
+        };
+        let mut configured = settings(&[("day", "sunrise")]);
+        assert_eq!(
+            day_schedule(&configured, Some(located)).unwrap().location,
+            Some(located)
+        );
+        configured.latitude = Some(52.5);
+        configured.longitude = Some(13.4);
+        assert_eq!(
+            day_schedule(&configured, Some(located))
+                .unwrap()
+                .location
+                .map(|location| location.latitude),
+            Some(52.5)
+        );
+    }
+
+    #[test]
+    fn pinned_slot_expires_when_the_schedule_next_switches() {
+        let schedule =
+            day_schedule(&settings(&[("day", "07:00"), ("night", "19:00")]), None).unwrap();
+        let now = unix_now();
+        let scheduled = due_slot(&schedule, &mut None, now).unwrap();
+
+        let other = 1 - scheduled.0;
+        let mut pinned = Some((schedule.slots[other].name.clone(), now + 60));
+        assert_eq!(
+            due_slot(&schedule, &mut pinned, now),
+            Some((other, now + 60))
+        );
+
+        assert_eq!(
+            due_slot(&schedule, &mut pinned, now + 60).map(|due| due.0),
+            Some(scheduled.0)
+        );
+        assert_eq!(pinned, None);
+    }
+
+    #[test]
+    fn schedule_skips_monitors_rotating_a_playlist() {
+        let connected = vec!["DP-1".to_string(), "HDMI-A-1".to_string()];
+        let rotating = vec!["HDMI-A-1".to_string()];
+
+        assert_eq!(
+            schedule_targets(&[], &connected, &rotating),
+            vec!["DP-1".to_string()]
+        );
+        assert_eq!(
+            schedule_targets(&["HDMI-A-1".to_string()], &connected, &[]),
+            vec!["HDMI-A-1".to_string()]
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use std::thread;
+
+use zbus::blocking::{Connection, Proxy};
+
+use crate::services::desktop_service::DesktopService;
+
+/// Screensaver services by bus name and object path; GNOME only answers
+/// under its own name
+const SCREENSAVERS: [(&str, &str); 2] = [
+    (
+        "org.freedesktop.ScreenSaver",
+        "/org/freedesktop/ScreenSaver",
+    ),
+    ("org.gnome.ScreenSaver", "/org/gnome/ScreenSaver"),
+];
+
+/// Forwards the session screensaver state to the engine so wallpapers pause
+/// while it or the lock screen is active.
+pub struct ScreenSaverService;
+
+impl ScreenSaverService {
+    /// Watch the screensaver when `[playback] pause_on_idle_seconds` is set
+    pub fn start() {
+        if !DesktopService::pauses_on_idle() {
+            return;
+        }
+
+        let spawned = thread::Builder::new()
+            .name("lwe-screensaver".to_string())
+            .spawn(|| {
+                if let Err(reason) = Self::watch() {
+                    eprintln!("screensaver state unavailable: {reason}");
+                }
+            });
+
+        if let Err(reason) = spawned {
+            eprintln!("failed to watch the screensaver: {reason}");
+        }
+    }
+
+    fn watch() -> Result<(), String> {
+        let connection = Connection::session()
+            .map_err(|error| format!("Failed to connect to the session bus: {error}"))?;
+
+        for (name, path) in SCREENSAVERS {
+            let proxy = Proxy::new(&connection, name, path, name)
+                .map_err(|error| format!("Failed to create a {name} proxy: {error}"))?;
+            // Only a screensaver that answers GetActive is running
+            let Ok(active) = proxy.call::<_, _, bool>("GetActive", &()) else {
+                continue;
+            };
+            report(active);
+
+            let signals = proxy
+                .receive_signal("ActiveChanged")
+                .map_err(|error| format!("Failed to watch {name}: {error}"))?;
+            for message in signals {
+                match message.body().deserialize::<bool>() {
+                    Ok(active) => report(active),
+                    Err(error) => eprintln!("ignoring {name} ActiveChanged: {error}"),
+                }
+            }
+            return Ok(());
+        }
+
+        Err("No screensaver service on the session bus".to_string())
+    }
+}
+
+fn report(active: bool) {
+    if let Err(reason) = DesktopService::set_screensaver_active(active) {
+        eprintln!("could not report the screensaver state: {reason}");
+    }
+}
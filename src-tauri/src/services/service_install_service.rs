@@ -0,0 +1,327 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+pub const SYSTEMD_UNIT_NAME: &str = "lwe.service";
+pub(crate) const SESSION_ENVIRONMENT: &str =
+    "WAYLAND_DISPLAY XDG_CURRENT_DESKTOP XDG_SESSION_TYPE DISPLAY";
+
+pub struct ServiceInstallService;
+
+pub struct ScopedServiceInstallService {
+    config_root: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompositorTarget {
+    Hyprland,
+    Sway,
+    Niri,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositorSnippet {
+    pub config_path: PathBuf,
+    pub appended: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceInstallReport {
+    pub unit_path: PathBuf,
+    pub compositor: Option<CompositorSnippet>,
+}
+
+impl CompositorTarget {
+    pub fn from_flag(flag: &str) -> Option<Self> {
+        match flag {
+            "--hyprland" => Some(Self::Hyprland),
+            "--sway" => Some(Self::Sway),
+            "--niri" => Some(Self::Niri),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Hyprland => "Hyprland",
+            Self::Sway => "sway",
+            Self::Niri => "niri",
+        }
+    }
+
+    fn config_path(self, config_root: &Path) -> PathBuf {
+        match self {
+            Self::Hyprland => config_root.join("hypr").join("hyprland.conf"),
+            Self::Sway => config_root.join("sway").join("config"),
+            Self::Niri => config_root.join("niri").join("config.kdl"),
+        }
+    }
+
+    fn snippet(self) -> String {
+        let import = format!("systemctl --user import-environment {SESSION_ENVIRONMENT}");
+        let start = format!("systemctl --user start {SYSTEMD_UNIT_NAME}");
+
+        match self {
+            Self::Hyprland => format!("exec-once = {import} && {start}\n"),
+            Self::Sway => format!("exec \"{import} && {start}\"\n"),
+            Self::Niri => format!("spawn-at-startup \"sh\" \"-c\" \"{import} && {start}\"\n"),
+        }
+    }
+}
+
+impl ServiceInstallService {
+    pub fn for_path(config_root: PathBuf) -> ScopedServiceInstallService {
+        ScopedServiceInstallService { config_root }
+    }
+
+    pub fn for_test(config_root: PathBuf) -> ScopedServiceInstallService {
+        Self::for_path(config_root)
+    }
+
+    pub fn for_user_path() -> Result<ScopedServiceInstallService, String> {
+        let config_root = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .filter(|value| !value.is_empty())
+                    .map(|home| PathBuf::from(home).join(".config"))
+            })
+            .filter(|path| path.is_absolute())
+            .ok_or_else(|| {
+                "Unable to resolve the user config directory from XDG_CONFIG_HOME or HOME"
+                    .to_string()
+            })?;
+
+        Ok(Self::for_path(config_root))
+    }
+}
+
+impl ScopedServiceInstallService {
+    pub fn unit_path(&self) -> PathBuf {
+        self.config_root
+            .join("systemd")
+            .join("user")
+            .join(SYSTEMD_UNIT_NAME)
+    }
+
+    pub fn install(
+        &self,
+        launch_command: &[&str],
+        compositor: Option<CompositorTarget>,
+    ) -> Result<ServiceInstallReport, String> {
+        let unit_path = self.unit_path();
+        let contents = systemd_unit_contents(launch_command)?;
+        write_file(&unit_path, &contents)?;
+
+        let compositor = compositor
+            .map(|target| self.append_compositor_snippet(target))
+            .transpose()?;
+
+        Ok(ServiceInstallReport {
+            unit_path,
+            compositor,
+        })
+    }
+
+    fn append_compositor_snippet(
+        &self,
+        target: CompositorTarget,
+    ) -> Result<CompositorSnippet, String> {
+        let config_path = target.config_path(&self.config_root);
+        let existing = match fs::read_to_string(&config_path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                return Err(format!(
+                    "{} config was not found at {}",
+                    target.label(),
+                    config_path.display()
+                ));
+            }
+            Err(error) => {
+                return Err(format!(
+                    "Failed to read {} config {}: {error}",
+                    target.label(),
+                    config_path.display()
+                ));
+            }
+        };
+
+        // Re-running the installer must not stack duplicate startup lines.
+        if existing.contains(&format!("systemctl --user start {SYSTEMD_UNIT_NAME}")) {
+            return Ok(CompositorSnippet {
+                config_path,
+                appended: false,
+            });
+        }
+
+        let separator = if existing.is_empty() || existing.ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+        let updated = format!(
+            "{existing}{separator}\n# Added by `lwe install-service`\n{}",
+            target.snippet()
+        );
+        write_file(&config_path, &updated)?;
+
+        Ok(CompositorSnippet {
+            config_path,
+            appended: true,
+        })
+    }
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed to create {}: {error}", parent.display()))?;
+    }
+
+    fs::write(path, contents)
+        .map_err(|error| format!("Failed to write {}: {error}", path.display()))
+}
+
+fn systemd_unit_contents(launch_command: &[&str]) -> Result<String, String> {
+    if launch_command.is_empty() {
+        return Err("Unable to write a systemd unit from an empty launch command".to_string());
+    }
+
+    let exec_start = launch_command
+        .iter()
+        .map(|part| systemd_exec_arg(part))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Ok(format!(
+        "[Unit]\n\
+         Description=LWE wallpaper engine\n\
+         PartOf=graphical-session.target\n\
+         After=graphical-session.target\n\
+         Requisite=graphical-session.target\n\
+         \n\
+         [Service]\n\
+         Type=notify\n\
+         ExecStart={exec_start}\n\
+         Restart=on-failure\n\
+         RestartSec=2\n\
+         \n\
+         [Install]\n\
+         WantedBy=graphical-session.target\n"
+    ))
+}
+
+fn systemd_exec_arg(argument: &str) -> String {
+    let needs_quotes = argument.is_empty()
+        || argument
+            .chars()
+            .any(|ch| ch.is_whitespace() || matches!(ch, '"' | '\'' | '\\' | ';'));
+
+    let mut escaped = String::with_capacity(argument.len());
+
+    for ch in argument.chars() {
+        match ch {
+            '%' => escaped.push_str("%%"),
+            '$' => escaped.push_str("$$"),
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            _ => escaped.push(ch),
+        }
+    }
+
+    if !needs_quotes {
+        return escaped;
+    }
+
+    format!("\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{CompositorTarget, ServiceInstallService};
+
+    fn test_config_root() -> std::path::PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+
+        std::env::temp_dir().join(format!("service-install-service-{unique}"))
+    }
+
+    #[test]
+    fn install_writes_graphical_session_unit_with_escaped_exec_start() {
+        let config_root = test_config_root();
+        let service = ServiceInstallService::for_test(config_root.clone());
+
+        let report = service
+            .install(&["/opt/LWE Apps/lwe", "--minimized", "50%"], None)
+            .unwrap();
+
+        assert_eq!(
+            report.unit_path,
+            config_root.join("systemd/user/lwe.service")
+        );
+        assert_eq!(report.compositor, None);
+
+        let unit = fs::read_to_string(&report.unit_path).unwrap();
+        assert!(unit.contains("ExecStart=\"/opt/LWE Apps/lwe\" --minimized 50%%\n"));
+        assert!(unit.contains("PartOf=graphical-session.target\n"));
+        assert!(unit.contains("After=graphical-session.target\n"));
+        assert!(unit.contains("Type=notify\n"));
+        assert!(unit.contains("Restart=on-failure\n"));
+        assert!(!unit.contains("ConditionEnvironment="));
+        assert!(unit.contains("WantedBy=graphical-session.target\n"));
+
+        let _ = fs::remove_dir_all(config_root);
+    }
+
+    #[test]
+    fn install_appends_compositor_snippet_once_and_requires_existing_config() {
+        let config_root = test_config_root();
+        let service = ServiceInstallService::for_test(config_root.clone());
+
+        let missing = service
+            .install(
+                &["/usr/bin/lwe", "--minimized"],
+                Some(CompositorTarget::Niri),
+            )
+            .unwrap_err();
+        assert!(missing.starts_with("niri config was not found at "));
+
+        let hypr_config = config_root.join("hypr/hyprland.conf");
+        fs::create_dir_all(hypr_config.parent().unwrap()).unwrap();
+        fs::write(&hypr_config, "monitor = ,preferred,auto,1").unwrap();
+
+        let first = service
+            .install(
+                &["/usr/bin/lwe", "--minimized"],
+                Some(CompositorTarget::Hyprland),
+            )
+            .unwrap();
+        let second = service
+            .install(
+                &["/usr/bin/lwe", "--minimized"],
+                Some(CompositorTarget::Hyprland),
+            )
+            .unwrap();
+
+        assert!(first.compositor.unwrap().appended);
+        assert!(!second.compositor.unwrap().appended);
+
+        let contents = fs::read_to_string(&hypr_config).unwrap();
+        assert!(contents.starts_with("monitor = ,preferred,auto,1\n\n# Added by"));
+        assert_eq!(contents.matches("exec-once = ").count(), 1);
+        assert!(contents.ends_with(
+            "exec-once = systemctl --user import-environment WAYLAND_DISPLAY XDG_CURRENT_DESKTOP XDG_SESSION_TYPE DISPLAY && systemctl --user start lwe.service\n"
+        ));
+
+        let _ = fs::remove_dir_all(config_root);
+    }
+}
@@ -156,7 +156,13 @@ mod tests {
     use std::time::{SystemTime, UNIX_EPOCH};
 
     use crate::models::{WorkshopAgeRating, WorkshopOnlineItemType};
-    use crate::results::settings_persistence::{PersistedSettings, SettingsPersistenceLoad};
+    use crate::results::settings_persistence::{
+        PersistedBatteryProfile, PersistedCompositorWorkarounds, PersistedGpu, PersistedHooks,
+        PersistedLibrary, PersistedOutputSettings, PersistedPlayback, PersistedPresentation,
+        PersistedProfile, PersistedResources, PersistedSchedule, PersistedScheduleSlot,
+        PersistedScheduling, PersistedSettings, PersistedSignage, PersistedStreams,
+        SettingsPersistenceLoad,
+    };
 
     use super::{atomic_write_path_for, settings_path_from_env, SettingsPersistenceService};
 
@@ -218,6 +224,20 @@ mod tests {
                     WorkshopOnlineItemType::Video,
                     WorkshopOnlineItemType::Application,
                 ],
+                confirm_apply_to_all: true,
+                compositor_workarounds: Default::default(),
+                outputs: Default::default(),
+                presentation: Default::default(),
+                signage: Default::default(),
+                streams: Default::default(),
+                playback: Default::default(),
+                hooks: Default::default(),
+                scheduling: Default::default(),
+                gpu: Default::default(),
+                resources: Default::default(),
+                library: Default::default(),
+                schedule: Default::default(),
+                profiles: Default::default(),
             })
         );
     }
@@ -245,6 +265,20 @@ mod tests {
                     WorkshopOnlineItemType::Web,
                     WorkshopOnlineItemType::Application,
                 ],
+                confirm_apply_to_all: true,
+                compositor_workarounds: Default::default(),
+                outputs: Default::default(),
+                presentation: Default::default(),
+                signage: Default::default(),
+                streams: Default::default(),
+                playback: Default::default(),
+                hooks: Default::default(),
+                scheduling: Default::default(),
+                gpu: Default::default(),
+                resources: Default::default(),
+                library: Default::default(),
+                schedule: Default::default(),
+                profiles: Default::default(),
             })
         );
     }
@@ -264,6 +298,122 @@ mod tests {
                 WorkshopOnlineItemType::Scene,
                 WorkshopOnlineItemType::Application,
             ],
+            confirm_apply_to_all: false,
+            compositor_workarounds: PersistedCompositorWorkarounds {
+                compositor: Some("sway".to_string()),
+                frame_callback_fallback_ms: Some(0),
+                ..Default::default()
+            },
+            outputs: [(
+                "DP-1".to_string(),
+                PersistedOutputSettings {
+                    layout: Some("contain".to_string()),
+                    letterbox: Some("blur".to_string()),
+                    volume: Some(40),
+                    muted: Some(false),
+                    audio_device: None,
+                    fallbacks: Vec::new(),
+                    brightness: Some(90),
+                    mpv_options: [("scale".to_string(), "ewa_lanczossharp".to_string())]
+                        .into_iter()
+                        .collect(),
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+            presentation: PersistedPresentation {
+                items: vec!["signage-1".to_string()],
+                playlists: vec![3],
+            },
+            signage: PersistedSignage {
+                manifest_url: "https://signage.example/lobby.json".to_string(),
+                refresh_secs: Some(60),
+                monitors: Vec::new(),
+            },
+            streams: PersistedStreams {
+                urls: vec!["https://www.youtube.com/watch?v=abc".to_string()],
+                ttl_hours: Some(24),
+                ..Default::default()
+            },
+            playback: PersistedPlayback {
+                pause_on_fullscreen: false,
+                pause_on_maximized: true,
+                deep_sleep: true,
+                audio_follows_focus: true,
+                transition: Some("crossfade".to_string()),
+                transition_ms: Some(800),
+                preload_secs: Some(10),
+                pause_on_idle_seconds: Some(300),
+                lut: Some(PathBuf::from("/home/user/luts/teal.cube")),
+                battery: PersistedBatteryProfile {
+                    fps: Some(15),
+                    hwdec: Some("force".to_string()),
+                    static_fallback: false,
+                },
+                mpv_options: [("video-sync".to_string(), "display-resample".to_string())]
+                    .into_iter()
+                    .collect(),
+            },
+            hooks: PersistedHooks {
+                on_apply: Some("wal -i \"$LWE_WALLPAPER\"".to_string()),
+                ..Default::default()
+            },
+            scheduling: PersistedScheduling {
+                nice: Some(15),
+                idle_priority: true,
+                cpu_affinity: Some("efficiency".to_string()),
+            },
+            gpu: PersistedGpu {
+                render_device: Some("/dev/dri/renderD129".to_string()),
+                debug: true,
+            },
+            resources: PersistedResources {
+                cpu_quota_percent: Some(50),
+                memory_high_mb: Some(512),
+            },
+            library: PersistedLibrary {
+                hidden_items: vec!["forest-101".to_string()],
+                folders: vec![PathBuf::from("/home/user/Videos/wallpapers")],
+                files: vec![PathBuf::from("/home/user/Pictures/dunes.jpg")],
+            },
+            schedule: PersistedSchedule {
+                latitude: Some(52.5),
+                longitude: Some(13.4),
+                monitors: vec!["DP-1".to_string()],
+                slots: vec![
+                    PersistedScheduleSlot {
+                        name: "day".to_string(),
+                        start: "sunrise".to_string(),
+                        item: "forest-101".to_string(),
+                    },
+                    PersistedScheduleSlot {
+                        name: "night".to_string(),
+                        start: "sunset-30".to_string(),
+                        item: "city-202".to_string(),
+                    },
+                ],
+            },
+            profiles: [(
+                "evening".to_string(),
+                PersistedProfile {
+                    assignments: [("DP-1".to_string(), "city-202".to_string())]
+                        .into_iter()
+                        .collect(),
+                    outputs: [(
+                        "DP-1".to_string(),
+                        PersistedOutputSettings {
+                            layout: Some("fill".to_string()),
+                            muted: Some(true),
+                            ..Default::default()
+                        },
+                    )]
+                    .into_iter()
+                    .collect(),
+                },
+            )]
+            .into_iter()
+            .collect(),
         };
 
         assert!(matches!(
@@ -279,6 +429,30 @@ mod tests {
         assert!(contents.contains("workshop_query = \"forest\""));
         assert!(contents.contains("workshop_age_ratings = [\"g\", \"r_18\"]"));
         assert!(contents.contains("workshop_item_types = [\"scene\", \"application\"]"));
+        assert!(contents.contains("confirm_apply_to_all = false"));
+        assert!(contents.contains("[compositor_workarounds]\ncompositor = \"sway\"\n"));
+        assert!(!contents.contains("exclusive_zone"));
+        assert!(contents.contains("[outputs.DP-1]\nlayout = \"contain\"\nletterbox = \"blur\"\nvolume = 40\nmuted = false\n"));
+        assert!(contents.contains("[presentation]\nitems = [\"signage-1\"]\nplaylists = [3]\n"));
+        assert!(contents.contains(
+            "[signage]\nmanifest_url = \"https://signage.example/lobby.json\"\nrefresh_secs = 60\nmonitors = []\n"
+        ));
+        assert!(contents.contains(
+            "[playback]\npause_on_fullscreen = false\npause_on_maximized = true\ndeep_sleep = true\naudio_follows_focus = true\ntransition = \"crossfade\"\ntransition_ms = 800\npreload_secs = 10\npause_on_idle_seconds = 300\nlut = \"/home/user/luts/teal.cube\"\n"
+        ));
+        assert!(contents.contains("[hooks]\non_apply = 'wal -i \"$LWE_WALLPAPER\"'\n"));
+        assert!(contents.contains(
+            "[scheduling]\nnice = 15\nidle_priority = true\ncpu_affinity = \"efficiency\"\n"
+        ));
+        assert!(contents.contains("[gpu]\nrender_device = \"/dev/dri/renderD129\"\ndebug = true\n"));
+        assert!(contents.contains("[resources]\ncpu_quota_percent = 50\nmemory_high_mb = 512\n"));
+        assert!(contents.contains("[library]\nhidden_items = [\"forest-101\"]\n"));
+        assert!(contents
+            .contains("[schedule]\nlatitude = 52.5\nlongitude = 13.4\nmonitors = [\"DP-1\"]\n"));
+        assert!(contents.contains(
+            "[[schedule.slots]]\nname = \"night\"\nstart = \"sunset-30\"\nitem = \"city-202\"\n"
+        ));
+        assert!(contents.contains("[profiles.evening.assignments]\nDP-1 = \"city-202\"\n"));
 
         let loaded = service.load_settings();
 
@@ -1,3 +1,4 @@
+use lwe_core::ConfigIssue;
 use lwe_library::SteamLibrary;
 
 use crate::models::SettingsUpdateInput;
@@ -5,6 +6,7 @@ use crate::results::settings_persistence::{
     PersistedSettings, SettingsPersistenceLoad, SettingsPersistenceWrite,
 };
 use crate::services::autostart_service::{AutostartService, AutostartState};
+use crate::services::config_validation_service::ConfigValidationService;
 use crate::services::settings_persistence_service::SettingsPersistenceService;
 
 #[derive(Debug)]
@@ -17,9 +19,12 @@ pub(crate) struct SettingsPageData {
     pub workshop_query: String,
     pub workshop_age_ratings: Vec<crate::models::WorkshopAgeRating>,
     pub workshop_item_types: Vec<crate::models::WorkshopOnlineItemType>,
+    pub confirm_apply_to_all: bool,
     pub steam_required: bool,
     pub steam_status_message: String,
     pub stale: bool,
+    /// Problems in the hand-editable `settings.toml`, shown inline
+    pub config_issues: Vec<ConfigIssue>,
 }
 
 pub struct SettingsService;
@@ -39,8 +44,9 @@ impl SettingsService {
             },
             Err(reason) => AutostartState::Unavailable { reason },
         };
+        let config_issues = ConfigValidationService::settings_issues(persistence.path());
 
-        Ok(Self::build_page_data(settings, autostart))
+        Ok(Self::build_page_data(settings, autostart, config_issues))
     }
 
     pub(crate) fn update_settings(input: SettingsUpdateInput) -> Result<SettingsPageData, String> {
@@ -79,6 +85,10 @@ impl SettingsService {
             settings.workshop_item_types = workshop_item_types;
         }
 
+        if let Some(confirm_apply_to_all) = input.confirm_apply_to_all {
+            settings.confirm_apply_to_all = confirm_apply_to_all;
+        }
+
         match persistence.save_settings(&settings) {
             SettingsPersistenceWrite::Saved => {
                 if let Some(launch_on_login) = input.launch_on_login {
@@ -100,7 +110,12 @@ impl SettingsService {
         }
     }
 
-    fn apply_launch_on_login(enabled: bool) -> Result<(), String> {
+    /// Whether an autostart entry can be written for this executable
+    pub(crate) fn launch_on_login_available() -> bool {
+        AutostartService::for_user_path().is_ok() && current_launch_command().is_ok()
+    }
+
+    pub(crate) fn apply_launch_on_login(enabled: bool) -> Result<(), String> {
         let autostart = AutostartService::for_user_path()?;
         let command = current_launch_command()?;
         let command_refs = command_refs(&command);
@@ -112,7 +127,11 @@ impl SettingsService {
         }
     }
 
-    fn build_page_data(settings: PersistedSettings, autostart: AutostartState) -> SettingsPageData {
+    fn build_page_data(
+        settings: PersistedSettings,
+        autostart: AutostartState,
+        config_issues: Vec<ConfigIssue>,
+    ) -> SettingsPageData {
         let (launch_on_login, launch_on_login_available) = match autostart {
             AutostartState::Enabled => (true, true),
             AutostartState::Disabled => (false, true),
@@ -130,9 +149,11 @@ impl SettingsService {
             workshop_query: settings.workshop_query,
             workshop_age_ratings: settings.workshop_age_ratings,
             workshop_item_types: settings.workshop_item_types,
+            confirm_apply_to_all: settings.confirm_apply_to_all,
             steam_required,
             steam_status_message,
             stale: !launch_on_login_available,
+            config_issues,
         }
     }
 
@@ -141,6 +162,7 @@ impl SettingsService {
         settings_path: std::path::PathBuf,
         config_root: std::path::PathBuf,
     ) -> Result<SettingsPageData, String> {
+        let config_issues = ConfigValidationService::settings_issues(&settings_path);
         let settings = match SettingsPersistenceService::for_test(settings_path).load_settings() {
             SettingsPersistenceLoad::Loaded(settings) => settings,
             SettingsPersistenceLoad::Unavailable { reason } => return Err(reason),
@@ -150,7 +172,7 @@ impl SettingsService {
             .status(&command_refs(&command))
             .state;
 
-        Ok(Self::build_page_data(settings, autostart))
+        Ok(Self::build_page_data(settings, autostart, config_issues))
     }
 
     #[cfg(test)]
@@ -194,6 +216,10 @@ impl SettingsService {
             settings.workshop_item_types = workshop_item_types;
         }
 
+        if let Some(confirm_apply_to_all) = input.confirm_apply_to_all {
+            settings.confirm_apply_to_all = confirm_apply_to_all;
+        }
+
         match persistence.save_settings(&settings) {
             SettingsPersistenceWrite::Saved => {
                 if let Some(launch_on_login) = input.launch_on_login {
@@ -315,6 +341,30 @@ mod tests {
         assert!(result.steam_status_message.contains("Steam"));
     }
 
+    #[test]
+    fn settings_service_load_page_lists_problems_in_the_settings_file() {
+        let settings_path = unique_test_path("settings-issues").with_extension("toml");
+        let config_root = unique_test_path("settings-issues-config");
+
+        std::fs::write(
+            &settings_path,
+            "language = \"en\"\nthem = \"dark\"\n\n[outputs.\"DP-1\"]\nlayout = \"fil\"\n",
+        )
+        .unwrap();
+
+        let result = SettingsService::load_page_for_test(settings_path, config_root).unwrap();
+
+        let issues = result
+            .config_issues
+            .iter()
+            .map(|issue| (issue.line, issue.key.as_deref().unwrap_or_default()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            issues,
+            vec![(Some(2), "them"), (Some(5), "outputs.DP-1.layout")]
+        );
+    }
+
     #[test]
     fn settings_service_uses_persisted_launch_preference_when_autostart_is_unavailable() {
         let result = SettingsService::build_page_data(
@@ -326,10 +376,25 @@ mod tests {
                 workshop_query: "nature".to_string(),
                 workshop_age_ratings: vec![crate::models::WorkshopAgeRating::G],
                 workshop_item_types: vec![crate::models::WorkshopOnlineItemType::Video],
+                confirm_apply_to_all: true,
+                compositor_workarounds: Default::default(),
+                outputs: Default::default(),
+                presentation: Default::default(),
+                signage: Default::default(),
+                streams: Default::default(),
+                playback: Default::default(),
+                hooks: Default::default(),
+                scheduling: Default::default(),
+                gpu: Default::default(),
+                resources: Default::default(),
+                library: Default::default(),
+                schedule: Default::default(),
+                profiles: Default::default(),
             },
             AutostartState::Unavailable {
                 reason: "missing XDG config root".to_string(),
             },
+            Vec::new(),
         );
 
         assert!(result.launch_on_login);
@@ -363,6 +428,7 @@ mod tests {
                     crate::models::WorkshopOnlineItemType::Video,
                     crate::models::WorkshopOnlineItemType::Application,
                 ]),
+                confirm_apply_to_all: None,
             },
         )
         .unwrap();
@@ -421,6 +487,7 @@ mod tests {
                 workshop_query: None,
                 workshop_age_ratings: None,
                 workshop_item_types: None,
+                confirm_apply_to_all: None,
             },
         )
         .expect_err("save failure should abort settings update");
@@ -457,6 +524,7 @@ mod tests {
                 workshop_query: Some("new-query".to_string()),
                 workshop_age_ratings: Some(vec![crate::models::WorkshopAgeRating::R18]),
                 workshop_item_types: Some(vec![crate::models::WorkshopOnlineItemType::Web]),
+                confirm_apply_to_all: None,
             },
         )
         .expect_err("autostart failure should roll back saved settings");
@@ -0,0 +1,98 @@
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::services::desktop_service::DesktopService;
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+
+/// How often `settings.toml` is checked for changes
+const SETTINGS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Called after a change to the settings file reached the engine
+pub type SettingsChangeSink = Box<dyn Fn() + Send + Sync>;
+
+/// What tells one version of the settings file from the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SettingsStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+/// Hands changes to `settings.toml` to the running engine, whether the app
+/// saved them or the file was edited by hand.
+pub struct SettingsSyncService;
+
+impl SettingsSyncService {
+    /// Watch the settings file on a background thread
+    pub fn start(on_change: SettingsChangeSink) {
+        let path = match SettingsPersistenceService::for_user_path() {
+            Ok(persistence) => persistence.path().to_path_buf(),
+            Err(reason) => {
+                eprintln!("settings changes will not reach the engine: {reason}");
+                return;
+            }
+        };
+
+        let spawned = thread::Builder::new()
+            .name("lwe-settings-sync".to_string())
+            .spawn(move || {
+                let mut stamp = settings_stamp(&path);
+                loop {
+                    thread::sleep(SETTINGS_POLL_INTERVAL);
+                    let current = settings_stamp(&path);
+                    if current == stamp {
+                        continue;
+                    }
+                    stamp = current;
+                    // A deleted file leaves the engine as it is
+                    if current.is_none() {
+                        continue;
+                    }
+                    if let Err(reason) = DesktopService::sync_engine_config() {
+                        eprintln!("failed to hand changed settings to the engine: {reason}");
+                    }
+                    on_change();
+                }
+            });
+
+        if let Err(reason) = spawned {
+            eprintln!("failed to start the settings watcher: {reason}");
+        }
+    }
+}
+
+fn settings_stamp(path: &Path) -> Option<SettingsStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(SettingsStamp {
+        modified: metadata.modified().ok()?,
+        len: metadata.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_stamp_changes_with_the_file() {
+        let path = std::env::temp_dir().join(format!(
+            "lwe-settings-sync-{}-{}.toml",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        assert_eq!(settings_stamp(&path), None);
+
+        fs::write(&path, "language = \"en\"\n").unwrap();
+        let written = settings_stamp(&path);
+        fs::write(&path, "language = \"zh-CN\"\n").unwrap();
+        let edited = settings_stamp(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(written.is_some());
+        assert_ne!(written, edited);
+    }
+}
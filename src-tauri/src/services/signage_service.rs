@@ -0,0 +1,453 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use lwe_core::{PlaylistRotation, Policy};
+
+use crate::results::monitor_discovery::MonitorDiscoveryResult;
+use crate::results::settings_persistence::{PersistedSignage, SettingsPersistenceLoad};
+use crate::services::desktop_service::DesktopService;
+use crate::services::http_service::HttpService;
+use crate::services::monitor_service::MonitorService;
+use crate::services::playlist_service::MIN_PLAYLIST_DURATION_SECS;
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+
+const SIGNAGE_TICK: Duration = Duration::from_secs(5);
+const DEFAULT_REFRESH_SECS: u64 = 300;
+const MIN_REFRESH_SECS: u64 = 30;
+const MANIFEST_TIMEOUT: Duration = Duration::from_secs(30);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+const DEFAULT_SIGNAGE_DURATION_SECS: u32 = 30;
+
+/// Remote playlist fetched from `[signage] manifest_url`:
+///
+/// ```json
+/// { "default_duration_secs": 30,
+///   "items": [{ "url": "https://cdn.example/menu.mp4", "duration_secs": 45 }] }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct SignageManifest {
+    #[serde(default = "default_signage_duration")]
+    pub default_duration_secs: u32,
+    pub items: Vec<SignageManifestItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct SignageManifestItem {
+    pub url: String,
+    #[serde(default)]
+    pub duration_secs: Option<u32>,
+}
+
+fn default_signage_duration() -> u32 {
+    DEFAULT_SIGNAGE_DURATION_SECS
+}
+
+impl SignageManifest {
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let manifest: Self = serde_json::from_str(contents)
+            .map_err(|reason| format!("Signage manifest is not valid: {reason}"))?;
+
+        if manifest.items.is_empty() {
+            return Err("Signage manifest does not list any media".to_string());
+        }
+        if let Some(item) = manifest
+            .items
+            .iter()
+            .find(|item| !item.url.starts_with("https://") && !item.url.starts_with("http://"))
+        {
+            return Err(format!(
+                "Signage media {} must be an http or https URL",
+                item.url
+            ));
+        }
+        let durations = std::iter::once(Some(manifest.default_duration_secs))
+            .chain(manifest.items.iter().map(|item| item.duration_secs));
+        if durations
+            .flatten()
+            .any(|duration| duration < MIN_PLAYLIST_DURATION_SECS)
+        {
+            return Err(format!(
+                "Signage durations must be at least {MIN_PLAYLIST_DURATION_SECS} seconds"
+            ));
+        }
+
+        Ok(manifest)
+    }
+
+    fn duration(&self, index: usize) -> u32 {
+        self.items[index]
+            .duration_secs
+            .unwrap_or(self.default_duration_secs)
+    }
+}
+
+/// FNV-1a of a URL; stable across runs and Rust versions so caches survive upgrades
+pub(crate) fn url_hash(url: &str) -> u64 {
+    url.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Cache file for a media URL: a stable hash plus the URL's extension
+pub(crate) fn cache_file_name(url: &str) -> String {
+    let hash = url_hash(url);
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let extension = path
+        .rsplit_once('/')
+        .map_or(path, |(_, name)| name)
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .filter(|extension| {
+            !extension.is_empty()
+                && extension.len() <= 5
+                && extension.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+
+    match extension {
+        Some(extension) => format!("{hash:016x}.{extension}"),
+        None => format!("{hash:016x}"),
+    }
+}
+
+/// Monitors the rotation runs on: the configured ones that are connected, or all of them
+pub(crate) fn signage_targets(configured: &[String], connected: &[String]) -> Vec<String> {
+    if configured.is_empty() {
+        return connected.to_vec();
+    }
+    connected
+        .iter()
+        .filter(|monitor_id| configured.contains(monitor_id))
+        .cloned()
+        .collect()
+}
+
+/// A manifest whose media are all in the cache
+struct SignageProgram {
+    manifest: SignageManifest,
+    files: Vec<PathBuf>,
+}
+
+struct SignageState {
+    manifest_url: String,
+    program: Option<SignageProgram>,
+    rotation: Option<PlaylistRotation>,
+    last_fetch: Option<Instant>,
+    /// The current entry still has to be shown (new entry, or an apply failed)
+    apply_pending: bool,
+}
+
+fn signage_state() -> MutexGuard<'static, SignageState> {
+    static STATE: OnceLock<Mutex<SignageState>> = OnceLock::new();
+    STATE
+        .get_or_init(|| {
+            Mutex::new(SignageState {
+                manifest_url: String::new(),
+                program: None,
+                rotation: None,
+                last_fetch: None,
+                apply_pending: false,
+            })
+        })
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub struct SignageService;
+
+impl SignageService {
+    /// Run the `[signage]` manifest on a background thread
+    ///
+    /// The settings are re-read every tick, so signage can be turned on or
+    /// pointed at another manifest without restarting LWE.
+    pub fn start() {
+        if !Policy::system().allow_network_sources {
+            if Self::config().is_some() {
+                eprintln!("signage is disabled: the system policy does not allow network sources");
+            }
+            return;
+        }
+
+        let spawned = thread::Builder::new()
+            .name("lwe-signage".to_string())
+            .spawn(|| loop {
+                if let Some(config) = Self::config() {
+                    Self::tick(&config);
+                }
+                thread::sleep(SIGNAGE_TICK);
+            });
+
+        if let Err(reason) = spawned {
+            eprintln!("failed to start signage player: {reason}");
+        }
+    }
+
+    fn config() -> Option<PersistedSignage> {
+        let loaded = SettingsPersistenceService::for_user_path()
+            .map(|service| service.load_settings())
+            .unwrap_or_else(|reason| SettingsPersistenceLoad::Unavailable { reason });
+
+        match loaded {
+            SettingsPersistenceLoad::Loaded(settings) => {
+                Some(settings.signage).filter(|signage| !signage.manifest_url.trim().is_empty())
+            }
+            SettingsPersistenceLoad::Unavailable { reason } => {
+                eprintln!("signage settings unavailable: {reason}");
+                None
+            }
+        }
+    }
+
+    fn tick(config: &PersistedSignage) {
+        let mut state = signage_state();
+        let now = unix_now();
+
+        let refresh = Duration::from_secs(
+            config
+                .refresh_secs
+                .unwrap_or(DEFAULT_REFRESH_SECS)
+                .max(MIN_REFRESH_SECS),
+        );
+        let url_changed = state.manifest_url != config.manifest_url;
+        if url_changed || state.last_fetch.is_none_or(|at| at.elapsed() >= refresh) {
+            state.manifest_url = config.manifest_url.clone();
+            state.last_fetch = Some(Instant::now());
+            // A failed refresh keeps the last good program playing
+            match Self::refresh(&config.manifest_url) {
+                Ok(program) => {
+                    let changed = state
+                        .program
+                        .as_ref()
+                        .is_none_or(|current| current.manifest != program.manifest);
+                    if changed {
+                        state.rotation =
+                            Some(PlaylistRotation::new(0, program.files.len(), false, now, 0));
+                        state.program = Some(program);
+                        state.apply_pending = true;
+                    }
+                }
+                Err(reason) => eprintln!("signage manifest refresh failed: {reason}"),
+            }
+        }
+
+        let SignageState {
+            program,
+            rotation,
+            apply_pending,
+            ..
+        } = &mut *state;
+        let (Some(program), Some(rotation)) = (program.as_ref(), rotation.as_mut()) else {
+            return;
+        };
+
+        // A one-item program wraps onto itself; leave it playing
+        let previous = rotation.current();
+        if rotation
+            .advance_if_due(now, |index| program.manifest.duration(index))
+            .is_some_and(|next| Some(next) != previous)
+        {
+            *apply_pending = true;
+        }
+
+        // Watchdog: bring the engine back if it exited or crashed
+        if !*apply_pending && matches!(DesktopService::engine_status(), Ok(None)) {
+            eprintln!("signage engine is not running, restarting it");
+            *apply_pending = true;
+        }
+
+        if let Some(path) = rotation
+            .current()
+            .map(|index| &program.files[index])
+            .filter(|_| *apply_pending)
+        {
+            *apply_pending = !Self::show(path, &config.monitors);
+        }
+    }
+
+    /// Show `path` on every signage monitor; false if any of them failed
+    fn show(path: &Path, configured: &[String]) -> bool {
+        let connected = match MonitorService::list_monitors() {
+            MonitorDiscoveryResult::Known(monitors) => monitors
+                .into_iter()
+                .map(|monitor| monitor.id)
+                .collect::<Vec<_>>(),
+            MonitorDiscoveryResult::Unavailable { reason } => {
+                eprintln!("signage could not list monitors: {reason}");
+                return false;
+            }
+        };
+
+        let mut shown = true;
+        for monitor_id in signage_targets(configured, &connected) {
+            if let Err(reason) = DesktopService::apply_media_file(&monitor_id, path, true) {
+                eprintln!(
+                    "signage could not show {} on {monitor_id}: {reason}",
+                    path.display()
+                );
+                shown = false;
+            }
+        }
+        shown
+    }
+
+    /// Fetch the manifest and download any media not cached yet
+    fn refresh(manifest_url: &str) -> Result<SignageProgram, String> {
+        let contents = HttpService::get_text(manifest_url, MANIFEST_TIMEOUT)
+            .map_err(|error| format!("Failed to fetch {manifest_url}: {error}"))?;
+        let manifest = SignageManifest::parse(&contents)?;
+
+        let cache_dir = signage_cache_dir()?;
+        fs::create_dir_all(&cache_dir).map_err(|error| {
+            format!(
+                "Failed to create signage cache {}: {error}",
+                cache_dir.display()
+            )
+        })?;
+
+        let files = manifest
+            .items
+            .iter()
+            .map(|item| {
+                let path = cache_dir.join(cache_file_name(&item.url));
+                if !path.is_file() {
+                    download(&item.url, &path)?;
+                }
+                Ok(path)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        prune_cache(&cache_dir, &files);
+
+        Ok(SignageProgram { manifest, files })
+    }
+}
+
+pub(crate) fn download(url: &str, path: &Path) -> Result<(), String> {
+    let partial = path.with_extension("part");
+    let result = fs::File::create(&partial)
+        .map_err(|error| format!("Failed to create {}: {error}", partial.display()))
+        .and_then(|mut file| {
+            HttpService::download_to(url, DOWNLOAD_TIMEOUT, &mut file)
+                .map_err(|error| format!("Failed to download {url}: {error}"))
+        })
+        .and_then(|_| {
+            fs::rename(&partial, path)
+                .map_err(|error| format!("Failed to store {}: {error}", path.display()))
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    result
+}
+
+/// Remove cached media the manifest no longer lists
+fn prune_cache(cache_dir: &Path, keep: &[PathBuf]) {
+    let keep = keep.iter().collect::<BTreeSet<_>>();
+    let entries = match fs::read_dir(cache_dir) {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!(
+                "signage could not read cache {}: {error}",
+                cache_dir.display()
+            );
+            return;
+        }
+    };
+
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if keep.contains(&path) {
+            continue;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => eprintln!(
+                "signage could not remove stale media {}: {error}",
+                path.display()
+            ),
+        }
+    }
+}
+
+pub(crate) fn signage_cache_dir() -> Result<PathBuf, String> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|value| !value.is_empty())
+                .map(|home| PathBuf::from(home).join(".cache"))
+        });
+
+    match base {
+        Some(path) if path.is_absolute() => Ok(path.join("lwe").join("signage")),
+        _ => Err(
+            "Unable to resolve the signage cache because XDG_CACHE_HOME and HOME are unset"
+                .to_string(),
+        ),
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signage_manifest_applies_default_durations_and_rejects_bad_entries() {
+        let manifest = SignageManifest::parse(
+            r#"{"items": [{"url": "https://cdn.example/a.mp4"},
+                          {"url": "http://cdn.example/b.gif", "duration_secs": 10}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.duration(0), DEFAULT_SIGNAGE_DURATION_SECS);
+        assert_eq!(manifest.duration(1), 10);
+        assert_eq!(
+            SignageManifest::parse(r#"{"items": []}"#),
+            Err("Signage manifest does not list any media".to_string())
+        );
+        assert_eq!(
+            SignageManifest::parse(r#"{"items": [{"url": "file:///etc/passwd"}]}"#),
+            Err("Signage media file:///etc/passwd must be an http or https URL".to_string())
+        );
+        assert!(SignageManifest::parse(
+            r#"{"items": [{"url": "https://cdn.example/a.mp4", "duration_secs": 1}]}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn signage_cache_names_are_stable_and_keep_the_extension() {
+        let name = cache_file_name("https://cdn.example/menu/Lunch.MP4?token=abc");
+
+        assert_eq!(
+            name,
+            cache_file_name("https://cdn.example/menu/Lunch.MP4?token=abc")
+        );
+        assert!(name.ends_with(".mp4"));
+        assert_ne!(name, cache_file_name("https://cdn.example/menu/Dinner.mp4"));
+        assert!(!cache_file_name("https://cdn.example/stream").contains('.'));
+    }
+
+    #[test]
+    fn signage_targets_default_to_every_connected_monitor() {
+        let connected = vec!["DP-1".to_string(), "HDMI-A-1".to_string()];
+
+        assert_eq!(signage_targets(&[], &connected), connected);
+        assert_eq!(
+            signage_targets(&["HDMI-A-1".to_string(), "DP-9".to_string()], &connected),
+            vec!["HDMI-A-1".to_string()]
+        );
+    }
+}
@@ -0,0 +1,467 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use lwe_core::Policy;
+use lwe_engine::is_live_source;
+use lwe_library::FolderScanner;
+
+use crate::results::settings_persistence::{PersistedStreams, SettingsPersistenceLoad};
+use crate::services::settings_persistence_service::SettingsPersistenceService;
+use crate::services::signage_service::{cache_file_name, download, signage_cache_dir, url_hash};
+
+const STREAM_TICK: Duration = Duration::from_secs(30);
+const DEFAULT_TTL_HOURS: u64 = 168;
+const DEFAULT_CACHE_MB: u64 = 2048;
+const FIRST_RETRY_SECS: u64 = 30;
+const MAX_RETRY_SECS: u64 = 3600;
+const INDEX_FILE: &str = "index.json";
+// Progressive http(s) formats only, so the result is a single downloadable file
+const YT_DLP_FORMAT: &str = "b[ext=mp4][protocol^=http]/b[protocol^=http]";
+
+/// A `[streams]` URL downloaded into the cache
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CachedStream {
+    pub file: String,
+    pub title: String,
+    pub fetched_at: u64,
+}
+
+/// Cached streams keyed by their configured URL, kept in `index.json`
+type StreamIndex = BTreeMap<String, CachedStream>;
+
+/// Where a configured URL's media can be downloaded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ResolvedStream {
+    media_url: String,
+    title: Option<String>,
+    extension: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct StreamState {
+    /// Failed fetches in a row per URL
+    failures: BTreeMap<String, u32>,
+    /// URLs not fetched again before this unix time
+    not_before: BTreeMap<String, u64>,
+}
+
+fn stream_state() -> MutexGuard<'static, StreamState> {
+    static STATE: OnceLock<Mutex<StreamState>> = OnceLock::new();
+    STATE
+        .get_or_init(|| Mutex::new(StreamState::default()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub struct StreamService;
+
+impl StreamService {
+    /// Keep the `[streams]` cache filled on a background thread
+    ///
+    /// Failed URLs are resolved again after an exponential backoff, so a
+    /// dropped connection or an expired yt-dlp link recovers on its own.
+    pub fn start() {
+        if !Policy::system().allow_network_sources {
+            if Self::config().is_some() {
+                eprintln!("streams are disabled: the system policy does not allow network sources");
+            }
+            return;
+        }
+
+        let spawned = thread::Builder::new()
+            .name("lwe-stream-cache".to_string())
+            .spawn(|| loop {
+                if let Some(config) = Self::config() {
+                    if let Err(reason) = Self::tick(&config) {
+                        eprintln!("stream cache update failed: {reason}");
+                    }
+                }
+                thread::sleep(STREAM_TICK);
+            });
+
+        if let Err(reason) = spawned {
+            eprintln!("failed to start stream cache: {reason}");
+        }
+    }
+
+    /// Cameras and live feeds listed under `[streams]`; they are played as
+    /// they arrive instead of being downloaded
+    pub fn live_streams() -> Vec<String> {
+        let network = Policy::system().allow_network_sources;
+        Self::config()
+            .map(|config| config.urls)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|url| is_live_source(Path::new(url)))
+            .filter(|url| network || url.starts_with("/dev/video"))
+            .collect()
+    }
+
+    /// Cached stream files and their titles, for the Library
+    pub fn cached_streams() -> Vec<(PathBuf, String)> {
+        let Ok(cache_dir) = stream_cache_dir() else {
+            return Vec::new();
+        };
+        let urls = Self::config().map(|config| config.urls).unwrap_or_default();
+
+        load_index(&cache_dir)
+            .into_iter()
+            .filter(|(url, _)| urls.contains(url))
+            .map(|(_, cached)| (cache_dir.join(cached.file), cached.title))
+            .filter(|(path, _)| path.is_file())
+            .collect()
+    }
+
+    fn config() -> Option<PersistedStreams> {
+        let loaded = SettingsPersistenceService::for_user_path()
+            .map(|service| service.load_settings())
+            .unwrap_or_else(|reason| SettingsPersistenceLoad::Unavailable { reason });
+
+        match loaded {
+            SettingsPersistenceLoad::Loaded(settings) => {
+                Some(settings.streams).filter(|streams| !streams.urls.is_empty())
+            }
+            SettingsPersistenceLoad::Unavailable { reason } => {
+                eprintln!("stream settings unavailable: {reason}");
+                None
+            }
+        }
+    }
+
+    fn tick(config: &PersistedStreams) -> Result<(), String> {
+        let cache_dir = stream_cache_dir()?;
+        fs::create_dir_all(&cache_dir).map_err(|error| {
+            format!(
+                "Failed to create stream cache {}: {error}",
+                cache_dir.display()
+            )
+        })?;
+
+        let mut index = load_index(&cache_dir);
+        let before = index.clone();
+        let now = unix_now();
+        let ttl_secs = config.ttl_hours.unwrap_or(DEFAULT_TTL_HOURS).max(1) * 3600;
+        index.retain(|url, _| config.urls.contains(url));
+
+        let mut state = stream_state();
+        for url in config
+            .urls
+            .iter()
+            .filter(|url| !is_live_source(Path::new(url)))
+        {
+            let fresh = index.get(url).is_some_and(|cached| {
+                now.saturating_sub(cached.fetched_at) < ttl_secs
+                    && cache_dir.join(&cached.file).is_file()
+            });
+            if fresh || state.not_before.get(url).is_some_and(|at| *at > now) {
+                continue;
+            }
+
+            // A failed refresh keeps the previous download playing
+            match Self::fetch(config, url, &cache_dir) {
+                Ok(cached) => {
+                    state.failures.remove(url);
+                    state.not_before.remove(url);
+                    index.insert(url.clone(), cached);
+                }
+                Err(reason) => {
+                    let failures = state.failures.entry(url.clone()).or_default();
+                    *failures += 1;
+                    let delay = retry_delay_secs(*failures);
+                    state.not_before.insert(url.clone(), now + delay);
+                    eprintln!("stream {url} failed, retrying in {delay}s: {reason}");
+                }
+            }
+        }
+
+        let sizes = index
+            .iter()
+            .map(|(url, cached)| {
+                let size = fs::metadata(cache_dir.join(&cached.file)).map_or(0, |meta| meta.len());
+                (url.clone(), size)
+            })
+            .collect();
+        let budget = config.cache_mb.unwrap_or(DEFAULT_CACHE_MB) * 1024 * 1024;
+        for url in evict_over_budget(&mut index, &sizes, budget) {
+            // Evicted streams wait out their TTL instead of being fetched straight back
+            state.not_before.insert(url, now + ttl_secs);
+        }
+        drop(state);
+
+        if index != before {
+            save_index(&cache_dir, &index)?;
+        }
+        prune_cache(&cache_dir, &index);
+        Ok(())
+    }
+
+    fn fetch(
+        config: &PersistedStreams,
+        url: &str,
+        cache_dir: &Path,
+    ) -> Result<CachedStream, String> {
+        let resolved = resolve(config, url)?;
+        let file = match &resolved.extension {
+            Some(extension) => format!("{:016x}.{extension}", url_hash(url)),
+            None => cache_file_name(url),
+        };
+        download(&resolved.media_url, &cache_dir.join(&file))?;
+
+        Ok(CachedStream {
+            file,
+            title: resolved.title.unwrap_or_else(|| url.to_string()),
+            fetched_at: unix_now(),
+        })
+    }
+}
+
+/// Direct media links are downloaded as-is; anything else goes through yt-dlp
+fn resolve(config: &PersistedStreams, url: &str) -> Result<ResolvedStream, String> {
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err(format!("{url} is not an http(s) URL"));
+    }
+    if is_direct_media(url) {
+        return Ok(ResolvedStream {
+            media_url: url.to_string(),
+            title: None,
+            extension: None,
+        });
+    }
+
+    let program = config.yt_dlp.as_deref().unwrap_or("yt-dlp");
+    let output = Command::new(program)
+        .args(["--no-playlist", "--no-warnings", "-f", YT_DLP_FORMAT])
+        .args([
+            "--print",
+            "%(title)s",
+            "--print",
+            "%(ext)s",
+            "--print",
+            "%(url)s",
+        ])
+        .arg(url)
+        .output()
+        .map_err(|error| match error.kind() {
+            ErrorKind::NotFound => format!("{program} is not installed; it is needed for {url}"),
+            _ => format!("Failed to run {program}: {error}"),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "{program} could not resolve {url}: {}",
+            stderr.trim()
+        ));
+    }
+
+    parse_yt_dlp_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn is_direct_media(url: &str) -> bool {
+    FolderScanner::new().is_wallpaper_file(Path::new(&cache_file_name(url)))
+}
+
+/// The title, extension and media URL lines printed by yt-dlp
+fn parse_yt_dlp_output(stdout: &str) -> Result<ResolvedStream, String> {
+    let mut lines = stdout.lines().map(str::trim);
+    let (Some(title), Some(extension), Some(media_url)) =
+        (lines.next(), lines.next(), lines.next())
+    else {
+        return Err("yt-dlp did not print a media URL".to_string());
+    };
+    if !media_url.starts_with("https://") && !media_url.starts_with("http://") {
+        return Err(format!(
+            "yt-dlp returned an unusable media URL: {media_url}"
+        ));
+    }
+
+    let extension = Some(extension.to_ascii_lowercase()).filter(|extension| {
+        !extension.is_empty()
+            && extension.len() <= 5
+            && extension.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+    Ok(ResolvedStream {
+        media_url: media_url.to_string(),
+        title: Some(title.to_string()).filter(|title| !title.is_empty() && title != "NA"),
+        extension,
+    })
+}
+
+/// 30s after the first failure, doubling up to an hour
+fn retry_delay_secs(failures: u32) -> u64 {
+    let doublings = failures.saturating_sub(1).min(16);
+    (FIRST_RETRY_SECS << doublings).min(MAX_RETRY_SECS)
+}
+
+/// Drops the oldest downloads until the rest fit in `budget` bytes and
+/// returns their URLs
+fn evict_over_budget(
+    index: &mut StreamIndex,
+    sizes: &BTreeMap<String, u64>,
+    budget: u64,
+) -> Vec<String> {
+    let mut total = sizes.values().sum::<u64>();
+    let mut oldest_first = index
+        .iter()
+        .map(|(url, cached)| (cached.fetched_at, url.clone()))
+        .collect::<Vec<_>>();
+    oldest_first.sort();
+
+    let mut evicted = Vec::new();
+    for (_, url) in oldest_first {
+        if total <= budget {
+            break;
+        }
+        total = total.saturating_sub(sizes.get(&url).copied().unwrap_or(0));
+        index.remove(&url);
+        evicted.push(url);
+    }
+    evicted
+}
+
+fn load_index(cache_dir: &Path) -> StreamIndex {
+    match fs::read_to_string(cache_dir.join(INDEX_FILE)) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|error| {
+            eprintln!("ignoring unreadable stream cache index: {error}");
+            StreamIndex::new()
+        }),
+        Err(error) if error.kind() == ErrorKind::NotFound => StreamIndex::new(),
+        Err(error) => {
+            eprintln!("stream cache index unavailable: {error}");
+            StreamIndex::new()
+        }
+    }
+}
+
+fn save_index(cache_dir: &Path, index: &StreamIndex) -> Result<(), String> {
+    let path = cache_dir.join(INDEX_FILE);
+    let partial = path.with_extension("json.part");
+    let contents = serde_json::to_string_pretty(index)
+        .map_err(|error| format!("Failed to encode stream cache index: {error}"))?;
+    fs::write(&partial, contents)
+        .and_then(|()| fs::rename(&partial, &path))
+        .map_err(|error| format!("Failed to store {}: {error}", path.display()))
+}
+
+/// Remove downloads the index no longer lists
+fn prune_cache(cache_dir: &Path, index: &StreamIndex) {
+    let entries = match fs::read_dir(cache_dir) {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!(
+                "stream cache could not read {}: {error}",
+                cache_dir.display()
+            );
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name == INDEX_FILE || index.values().any(|cached| cached.file == name) {
+            continue;
+        }
+        match fs::remove_file(entry.path()) {
+            Ok(()) => {}
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => eprintln!(
+                "stream cache could not remove {}: {error}",
+                entry.path().display()
+            ),
+        }
+    }
+}
+
+fn stream_cache_dir() -> Result<PathBuf, String> {
+    signage_cache_dir().map(|signage| signage.with_file_name("streams"))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_retries_back_off_exponentially_up_to_an_hour() {
+        assert_eq!(retry_delay_secs(1), 30);
+        assert_eq!(retry_delay_secs(2), 60);
+        assert_eq!(retry_delay_secs(4), 240);
+        assert_eq!(retry_delay_secs(8), 3600);
+        assert_eq!(retry_delay_secs(u32::MAX), 3600);
+    }
+
+    #[test]
+    fn stream_yt_dlp_output_yields_title_extension_and_media_url() {
+        let resolved = parse_yt_dlp_output(
+            "Ocean waves 4K\nMP4\nhttps://rr1.googlevideo.com/videoplayback?id=1\n",
+        )
+        .unwrap();
+
+        assert_eq!(resolved.title.as_deref(), Some("Ocean waves 4K"));
+        assert_eq!(resolved.extension.as_deref(), Some("mp4"));
+        assert_eq!(
+            resolved.media_url,
+            "https://rr1.googlevideo.com/videoplayback?id=1"
+        );
+
+        assert!(parse_yt_dlp_output("NA\nmp4\n").is_err());
+        assert!(parse_yt_dlp_output("Clip\nmp4\nfile:///etc/passwd\n").is_err());
+        assert_eq!(
+            parse_yt_dlp_output("NA\nmp4\nhttps://cdn.example/a")
+                .unwrap()
+                .title,
+            None
+        );
+    }
+
+    #[test]
+    fn stream_direct_media_links_skip_yt_dlp() {
+        assert!(is_direct_media("https://cdn.example/loop.mp4?token=1"));
+        assert!(!is_direct_media("https://www.youtube.com/watch?v=abc"));
+        assert!(!is_direct_media("https://vimeo.com/123456"));
+    }
+
+    #[test]
+    fn stream_cameras_and_live_feeds_are_not_downloaded() {
+        assert!(is_live_source(Path::new("rtsp://cam.local/stream1")));
+        assert!(is_live_source(Path::new("/dev/video0")));
+        assert!(!is_live_source(Path::new("https://vimeo.com/123456")));
+    }
+
+    #[test]
+    fn stream_cache_evicts_the_oldest_downloads_over_budget() {
+        let cached = |file: &str, fetched_at| CachedStream {
+            file: file.to_string(),
+            title: file.to_string(),
+            fetched_at,
+        };
+        let mut index = StreamIndex::from([
+            ("https://a".to_string(), cached("a.mp4", 10)),
+            ("https://b".to_string(), cached("b.mp4", 30)),
+            ("https://c".to_string(), cached("c.mp4", 20)),
+        ]);
+        let sizes = BTreeMap::from([
+            ("https://a".to_string(), 400),
+            ("https://b".to_string(), 400),
+            ("https://c".to_string(), 400),
+        ]);
+
+        assert!(evict_over_budget(&mut index.clone(), &sizes, 1200).is_empty());
+        assert_eq!(
+            evict_over_budget(&mut index, &sizes, 500),
+            vec!["https://a".to_string(), "https://c".to_string()]
+        );
+        assert_eq!(index.keys().collect::<Vec<_>>(), vec!["https://b"]);
+    }
+}
@@ -0,0 +1,688 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, Once, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use lwe_library::{
+    LibraryDatabase, ThumbnailFormat, ThumbnailGenerator, ThumbnailPriority, ThumbnailRequest,
+    ThumbnailService, ThumbnailStripData, WallpaperItem, WorkshopProjectType, STRIP_FRAMES,
+};
+
+use crate::results::workshop::AssessedWorkshopCatalogEntry;
+use crate::services::desktop_service::DesktopService;
+use crate::services::item_open_service::item_file;
+use crate::services::library_service::LibraryService;
+
+pub const THUMBNAIL_SCHEME: &str = "lwe-thumb";
+pub const STRIP_SCHEME: &str = "lwe-strip";
+const THUMBNAIL_WIDTH: u32 = 640;
+const THUMBNAIL_HEIGHT: u32 = 360;
+/// Strip frames are square to match the library cards they scrub over.
+const STRIP_FRAME_SIZE: u32 = 240;
+const THUMBNAIL_MEMORY_BUDGET_BYTES: usize = 32 * 1024 * 1024;
+/// Stored thumbnails kept when `[library] thumbnail_cache_mb` is unset
+pub const DEFAULT_THUMBNAIL_CACHE_MB: u64 = 512;
+/// Generation runs ffmpeg or decodes full-size images; a few at a time keep
+/// the rest of the desktop responsive
+const MAX_THUMBNAIL_WORKERS: usize = 4;
+const WORKSHOP_CONTENT_COMPONENTS: [&str; 3] = ["steamapps", "workshop", "content"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThumbnailBytes {
+    pub bytes: Arc<Vec<u8>>,
+    pub mime_type: &'static str,
+}
+
+/// A thumbnail finished in the background, and how many are still queued
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThumbnailProgress {
+    pub source_path: PathBuf,
+    pub remaining: usize,
+}
+
+/// Called for every finished thumbnail; the desktop shell forwards these to
+/// the frontend
+pub type ThumbnailProgressSink = Box<dyn Fn(ThumbnailProgress) + Send + Sync>;
+
+pub type ThumbnailResponder = Box<dyn FnOnce(Result<ThumbnailBytes, String>) + Send>;
+
+/// Space the stored thumbnails take and may take
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThumbnailCacheUsage {
+    pub bytes: u64,
+    pub budget_bytes: u64,
+}
+
+/// What pruning or clearing the thumbnail cache removed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThumbnailCacheCleanup {
+    pub removed: usize,
+    pub bytes_reclaimed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ThumbnailKey {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+struct ThumbnailEntry {
+    bytes: Arc<Vec<u8>>,
+    last_used: u64,
+}
+
+/// Size-bounded LRU of encoded thumbnails keyed by source path and mtime.
+pub struct ThumbnailLru {
+    budget_bytes: usize,
+    total_bytes: usize,
+    tick: u64,
+    entries: HashMap<ThumbnailKey, ThumbnailEntry>,
+}
+
+impl ThumbnailLru {
+    pub fn new(budget_bytes: usize) -> Self {
+        Self {
+            budget_bytes,
+            total_bytes: 0,
+            tick: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.total_bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn get(&mut self, key: &ThumbnailKey) -> Option<Arc<Vec<u8>>> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(key).map(|entry| {
+            entry.last_used = tick;
+            entry.bytes.clone()
+        })
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.total_bytes = 0;
+    }
+
+    fn remove_path(&mut self, path: &Path) {
+        let total_bytes = &mut self.total_bytes;
+        self.entries.retain(|key, entry| {
+            let keep = key.path != path;
+            if !keep {
+                *total_bytes -= entry.bytes.len();
+            }
+            keep
+        });
+    }
+
+    fn insert(&mut self, key: ThumbnailKey, bytes: Arc<Vec<u8>>) {
+        // A single thumbnail larger than the whole budget is served but not kept.
+        if bytes.len() > self.budget_bytes {
+            return;
+        }
+
+        self.tick += 1;
+        self.total_bytes += bytes.len();
+        if let Some(previous) = self.entries.insert(
+            key,
+            ThumbnailEntry {
+                bytes,
+                last_used: self.tick,
+            },
+        ) {
+            self.total_bytes -= previous.bytes.len();
+        }
+
+        while self.total_bytes > self.budget_bytes {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some(evicted) = self.entries.remove(&oldest) {
+                self.total_bytes -= evicted.bytes.len();
+            }
+        }
+    }
+}
+
+struct ThumbnailCacheState {
+    service: ThumbnailService,
+    strip_generator: ThumbnailGenerator,
+    lru: Mutex<ThumbnailLru>,
+    /// Cover requests waiting for their thumbnail, by wallpaper id
+    waiting: Mutex<HashMap<String, Vec<ThumbnailResponder>>>,
+}
+
+static THUMBNAIL_STATE: OnceLock<ThumbnailCacheState> = OnceLock::new();
+
+fn thumbnail_cache_state() -> &'static ThumbnailCacheState {
+    static RESULTS: Once = Once::new();
+    let state = THUMBNAIL_STATE.get_or_init(|| ThumbnailCacheState {
+        service: ThumbnailService::with_database(
+            thumbnail_generator(),
+            std::thread::available_parallelism()
+                .map_or(1, |count| count.get() / 2)
+                .clamp(1, MAX_THUMBNAIL_WORKERS),
+            LibraryDatabase::default_path(),
+        ),
+        strip_generator: strip_generator(),
+        lru: Mutex::new(ThumbnailLru::new(THUMBNAIL_MEMORY_BUDGET_BYTES)),
+        waiting: Mutex::new(HashMap::new()),
+    });
+    RESULTS.call_once(|| {
+        if let Err(error) = std::thread::Builder::new()
+            .name("thumbnail-results".to_string())
+            .spawn(|| deliver_thumbnails(thumbnail_cache_state()))
+        {
+            eprintln!("failed to start thumbnail delivery: {error}");
+        }
+    });
+    state
+}
+
+fn thumbnail_generator() -> ThumbnailGenerator {
+    ThumbnailGenerator::with_options(
+        THUMBNAIL_WIDTH,
+        THUMBNAIL_HEIGHT,
+        ThumbnailFormat::WebP,
+        ThumbnailGenerator::default_cache_dir()
+            .join(format!("{THUMBNAIL_WIDTH}x{THUMBNAIL_HEIGHT}")),
+    )
+}
+
+fn strip_generator() -> ThumbnailGenerator {
+    ThumbnailGenerator::with_options(
+        STRIP_FRAME_SIZE,
+        STRIP_FRAME_SIZE,
+        ThumbnailFormat::WebP,
+        ThumbnailGenerator::default_cache_dir().join("strips"),
+    )
+}
+
+fn progress_sink() -> &'static OnceLock<ThumbnailProgressSink> {
+    static SINK: OnceLock<ThumbnailProgressSink> = OnceLock::new();
+    &SINK
+}
+
+/// Hands finished thumbnails to the requests waiting for them, keeps them in
+/// the LRU and reports progress
+fn deliver_thumbnails(state: &ThumbnailCacheState) {
+    loop {
+        let Some(response) = state.service.recv_timeout(Duration::from_secs(60)) else {
+            continue;
+        };
+        let path = response.source_path;
+        let responders = state
+            .waiting
+            .lock()
+            .unwrap()
+            .remove(&response.wallpaper_id)
+            .unwrap_or_default();
+        let loaded = match response.result {
+            Ok(thumbnail) => {
+                let bytes = Arc::new(thumbnail.data);
+                state
+                    .lru
+                    .lock()
+                    .unwrap()
+                    .insert(thumbnail_key(&path), bytes.clone());
+                Ok(ThumbnailBytes {
+                    bytes,
+                    mime_type: "image/webp",
+                })
+            }
+            Err(error) => {
+                eprintln!(
+                    "thumbnail generation failed for {}: {error}",
+                    path.display()
+                );
+                // Formats the generator cannot decode still render from the original file.
+                if responders.is_empty() {
+                    Err(error)
+                } else {
+                    std::fs::read(&path)
+                        .map(|bytes| ThumbnailBytes {
+                            bytes: Arc::new(bytes),
+                            mime_type: mime_type_for(&path),
+                        })
+                        .map_err(|error| {
+                            format!("Failed to read cover {}: {error}", path.display())
+                        })
+                }
+            }
+        };
+        for respond in responders {
+            respond(loaded.clone());
+        }
+        if let Some(sink) = progress_sink().get() {
+            sink(ThumbnailProgress {
+                source_path: path,
+                remaining: state.service.pending_count(),
+            });
+        }
+    }
+}
+
+pub struct ThumbnailCacheService;
+
+impl ThumbnailCacheService {
+    pub fn install_progress_sink(sink: ThumbnailProgressSink) {
+        if progress_sink().set(sink).is_err() {
+            eprintln!("thumbnail progress sink was already installed");
+        }
+    }
+
+    /// Resolves a `lwe-thumb://localhost/<encoded path>` request path to
+    /// thumbnail bytes. The webview only asks for covers on screen, so these
+    /// go ahead of prefetched ones; `respond` is called from a worker thread.
+    pub fn load_for_request_path(request_path: &str, respond: ThumbnailResponder) {
+        match decode_request_path(request_path).and_then(|path| workshop_cover_path(&path)) {
+            Ok(path) => Self::load(path, ThumbnailPriority::High, Some(respond)),
+            Err(reason) => respond(Err(reason)),
+        }
+    }
+
+    /// Generate thumbnails for covers the user is likely to scroll to next,
+    /// after the ones on screen
+    pub fn prefetch(cover_paths: &[String]) {
+        for cover_path in cover_paths {
+            if let Ok(path) = workshop_cover_path(Path::new(cover_path)) {
+                Self::load(path, ThumbnailPriority::Low, None);
+            }
+        }
+    }
+
+    /// Resolves a `lwe-strip://localhost/<item id>` request path to the item's
+    /// hover-scrub sprite strip, generating it into the library database on first use.
+    pub fn load_strip_for_request_path(request_path: &str) -> Result<ThumbnailBytes, String> {
+        let item_id = request_path.trim_start_matches('/');
+        let (project_type, path) = DesktopService::resolve_real_apply_source(item_id)?;
+        if project_type != WorkshopProjectType::Video {
+            return Err(format!("Library item {item_id} has no video to scrub"));
+        }
+
+        let modified = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|since_epoch| since_epoch.as_secs() as i64);
+        let database = LibraryDatabase::open(LibraryDatabase::default_path())
+            .map_err(|error| format!("Failed to open library database: {error:#}"))?;
+        let wallpaper_id = WallpaperItem::generate_id(&path);
+
+        let stored = database
+            .get_thumbnail_strip(&wallpaper_id)
+            .map_err(|error| format!("Failed to read strip of {item_id}: {error:#}"))?;
+        if let Some(stored) = stored.filter(|stored| is_current_strip(stored, modified)) {
+            if let Err(error) = database.touch_thumbnails(&wallpaper_id) {
+                eprintln!("failed to mark strip of {item_id} as used: {error:#}");
+            }
+            return Ok(ThumbnailBytes {
+                bytes: Arc::new(stored.data),
+                mime_type: "image/webp",
+            });
+        }
+
+        let strip = thumbnail_cache_state()
+            .strip_generator
+            .generate_strip(&path, STRIP_FRAMES)
+            .map_err(|error| format!("Failed to generate strip of {item_id}: {error:#}"))?;
+        if let Err(error) = database.store_thumbnail_strip(&wallpaper_id, &path, &strip, modified) {
+            eprintln!("failed to store thumbnail strip of {item_id}: {error:#}");
+        }
+
+        Ok(ThumbnailBytes {
+            bytes: Arc::new(strip.data),
+            mime_type: "image/webp",
+        })
+    }
+
+    pub fn usage() -> Result<ThumbnailCacheUsage, String> {
+        let bytes = open_database()?
+            .thumbnail_cache_bytes()
+            .map_err(|error| format!("Failed to measure the thumbnail cache: {error:#}"))?;
+        let files = thumbnail_generator().cache_stats().total_bytes
+            + strip_generator().cache_stats().total_bytes;
+        Ok(ThumbnailCacheUsage {
+            bytes: bytes + files,
+            budget_bytes: budget_bytes(),
+        })
+    }
+
+    /// Drop thumbnails of wallpapers that are gone, then the least recently
+    /// shown ones over the `[library] thumbnail_cache_mb` budget. Runs at
+    /// startup and from `lwe cache prune`.
+    pub fn prune() -> Result<ThumbnailCacheCleanup, String> {
+        let budget = budget_bytes();
+        let prune = open_database()?
+            .prune_thumbnails(budget)
+            .map_err(|error| format!("Failed to prune the thumbnail cache: {error:#}"))?;
+        let mut cleanup = ThumbnailCacheCleanup {
+            removed: prune.orphaned + prune.evicted,
+            bytes_reclaimed: prune.bytes_reclaimed,
+        };
+        // Files next to the database are copies made while generating; they
+        // get the same budget
+        for generator in [thumbnail_generator(), strip_generator()] {
+            let removed = generator
+                .prune_cache(budget)
+                .map_err(|error| format!("Failed to prune cached thumbnail files: {error:#}"))?;
+            cleanup.removed += removed.count;
+            cleanup.bytes_reclaimed += removed.total_bytes;
+        }
+        Ok(cleanup)
+    }
+
+    /// Delete every stored thumbnail; covers are generated again as they are shown
+    pub fn clear() -> Result<ThumbnailCacheCleanup, String> {
+        let prune = open_database()?
+            .clear_thumbnails()
+            .map_err(|error| format!("Failed to clear the thumbnail cache: {error:#}"))?;
+        let mut cleanup = ThumbnailCacheCleanup {
+            removed: prune.evicted,
+            bytes_reclaimed: prune.bytes_reclaimed,
+        };
+        for generator in [thumbnail_generator(), strip_generator()] {
+            let files = generator.cache_stats();
+            let removed = generator
+                .clear_cache()
+                .map_err(|error| format!("Failed to clear cached thumbnail files: {error:#}"))?;
+            cleanup.removed += removed;
+            cleanup.bytes_reclaimed += files.total_bytes;
+        }
+        if let Some(state) = THUMBNAIL_STATE.get() {
+            state.lru.lock().unwrap().clear();
+        }
+        Ok(cleanup)
+    }
+
+    /// Forget the stored cover thumbnails and strips of `entries` and queue
+    /// their covers again; returns how many items had anything stored
+    pub fn regenerate(entries: &[AssessedWorkshopCatalogEntry]) -> Result<usize, String> {
+        let database = open_database()?;
+        let mut regenerated = 0;
+        for entry in entries {
+            let cover = entry
+                .entry
+                .cover_path
+                .as_ref()
+                .and_then(|cover| cover.canonicalize().ok());
+            let mut forgotten = false;
+            for path in cover.iter().cloned().chain([item_file(entry)]) {
+                forgotten |= database
+                    .delete_thumbnails(&WallpaperItem::generate_id(&path))
+                    .map_err(|error| {
+                        format!(
+                            "Failed to delete thumbnails of {}: {error:#}",
+                            entry.entry.title
+                        )
+                    })?;
+                for generator in [thumbnail_generator(), strip_generator()] {
+                    let file = generator.cache_path(&path);
+                    forgotten |= std::fs::remove_file(file).is_ok();
+                }
+                if let Some(state) = THUMBNAIL_STATE.get() {
+                    state.lru.lock().unwrap().remove_path(&path);
+                }
+            }
+            if forgotten {
+                regenerated += 1;
+            }
+            if let Some(cover) = cover.filter(|cover| is_workshop_content_path(cover)) {
+                Self::load(cover, ThumbnailPriority::Low, None);
+            }
+        }
+        Ok(regenerated)
+    }
+
+    fn load(path: PathBuf, priority: ThumbnailPriority, respond: Option<ThumbnailResponder>) {
+        let state = thumbnail_cache_state();
+        if let Some(bytes) = state.lru.lock().unwrap().get(&thumbnail_key(&path)) {
+            if let Some(respond) = respond {
+                respond(Ok(ThumbnailBytes {
+                    bytes,
+                    mime_type: "image/webp",
+                }));
+            }
+            return;
+        }
+
+        let wallpaper_id = WallpaperItem::generate_id(&path);
+        if let Some(respond) = respond {
+            state
+                .waiting
+                .lock()
+                .unwrap()
+                .entry(wallpaper_id.clone())
+                .or_default()
+                .push(respond);
+        }
+        state.service.request(ThumbnailRequest {
+            source_path: path,
+            wallpaper_id,
+            priority,
+        });
+    }
+}
+
+fn open_database() -> Result<LibraryDatabase, String> {
+    LibraryDatabase::open(LibraryDatabase::default_path())
+        .map_err(|error| format!("Failed to open library database: {error:#}"))
+}
+
+fn budget_bytes() -> u64 {
+    LibraryService::library_settings()
+        .thumbnail_cache_mb
+        .unwrap_or(DEFAULT_THUMBNAIL_CACHE_MB)
+        .saturating_mul(1024 * 1024)
+}
+
+fn thumbnail_key(path: &Path) -> ThumbnailKey {
+    ThumbnailKey {
+        path: path.to_path_buf(),
+        modified: std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok(),
+    }
+}
+
+/// Covers are only served from Workshop content, never arbitrary files
+fn workshop_cover_path(path: &Path) -> Result<PathBuf, String> {
+    let path = path
+        .canonicalize()
+        .map_err(|error| format!("Cover {} is unavailable: {error}", path.display()))?;
+    if !is_workshop_content_path(&path) {
+        return Err(format!(
+            "Cover {} is outside Workshop content",
+            path.display()
+        ));
+    }
+    Ok(path)
+}
+
+/// A stored strip is reused while its source is unchanged and its layout matches
+/// what the library cards expect.
+fn is_current_strip(stored: &ThumbnailStripData, source_modified: Option<i64>) -> bool {
+    source_modified.is_some()
+        && stored.source_modified == source_modified
+        && stored.frame_count == STRIP_FRAMES
+        && stored.frame_width == STRIP_FRAME_SIZE
+        && stored.frame_height == STRIP_FRAME_SIZE
+}
+
+fn decode_request_path(request_path: &str) -> Result<PathBuf, String> {
+    let encoded = request_path.strip_prefix('/').unwrap_or(request_path);
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let byte = bytes
+                .get(index + 1..index + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| format!("Invalid thumbnail request path {request_path}"))?;
+            decoded.push(byte);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+
+    let path = String::from_utf8(decoded)
+        .map(PathBuf::from)
+        .map_err(|_| format!("Invalid thumbnail request path {request_path}"))?;
+
+    if !path.is_absolute() {
+        return Err(format!(
+            "Thumbnail request path {} is not absolute",
+            path.display()
+        ));
+    }
+
+    Ok(path)
+}
+
+fn is_workshop_content_path(path: &Path) -> bool {
+    let components = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => name.to_str(),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    components
+        .windows(WORKSHOP_CONTENT_COMPONENTS.len())
+        .any(|window| window == WORKSHOP_CONTENT_COMPONENTS)
+}
+
+fn mime_type_for(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("gif") => "image/gif",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use lwe_library::{ThumbnailStripData, STRIP_FRAMES};
+
+    use super::{
+        decode_request_path, is_current_strip, is_workshop_content_path, ThumbnailKey,
+        ThumbnailLru, STRIP_FRAME_SIZE,
+    };
+
+    fn key(name: &str) -> ThumbnailKey {
+        ThumbnailKey {
+            path: PathBuf::from(format!("/covers/{name}.gif")),
+            modified: None,
+        }
+    }
+
+    #[test]
+    fn thumbnail_lru_evicts_least_recently_used_entries_over_budget() {
+        let mut lru = ThumbnailLru::new(10);
+
+        lru.insert(key("a"), Arc::new(vec![0; 4]));
+        lru.insert(key("b"), Arc::new(vec![0; 4]));
+        assert!(lru.get(&key("a")).is_some());
+        lru.insert(key("c"), Arc::new(vec![0; 4]));
+
+        assert_eq!(lru.len(), 2);
+        assert_eq!(lru.total_bytes(), 8);
+        assert!(lru.get(&key("a")).is_some());
+        assert!(lru.get(&key("b")).is_none());
+
+        lru.insert(key("huge"), Arc::new(vec![0; 11]));
+        assert!(lru.get(&key("huge")).is_none());
+        assert_eq!(lru.total_bytes(), 8);
+    }
+
+    #[test]
+    fn thumbnail_lru_forgets_every_version_of_a_path() {
+        let mut lru = ThumbnailLru::new(100);
+        let modified = ThumbnailKey {
+            modified: Some(std::time::UNIX_EPOCH),
+            ..key("a")
+        };
+
+        lru.insert(key("a"), Arc::new(vec![0; 4]));
+        lru.insert(modified, Arc::new(vec![0; 4]));
+        lru.insert(key("b"), Arc::new(vec![0; 4]));
+        lru.remove_path(Path::new("/covers/a.gif"));
+
+        assert_eq!(lru.len(), 1);
+        assert_eq!(lru.total_bytes(), 4);
+        assert!(lru.get(&key("b")).is_some());
+    }
+
+    #[test]
+    fn thumbnail_request_paths_decode_and_stay_inside_workshop_content() {
+        let path = decode_request_path(
+            "/%2Fhome%2Fuser%2F.steam%2Fsteam%2Fsteamapps%2Fworkshop%2Fcontent%2F431960%2F1%2Fpreview%20a.gif",
+        )
+        .unwrap();
+
+        assert_eq!(
+            path,
+            Path::new("/home/user/.steam/steam/steamapps/workshop/content/431960/1/preview a.gif")
+        );
+        assert!(is_workshop_content_path(&path));
+        assert!(!is_workshop_content_path(Path::new(
+            "/home/user/.ssh/id_ed25519"
+        )));
+        assert!(decode_request_path("/relative%2Fcover.gif").is_err());
+        assert!(decode_request_path("/%2Fbad%zz").is_err());
+    }
+
+    #[test]
+    fn stored_strips_are_regenerated_when_the_source_or_layout_changes() {
+        let stored = ThumbnailStripData {
+            data: Vec::new(),
+            frame_width: STRIP_FRAME_SIZE,
+            frame_height: STRIP_FRAME_SIZE,
+            frame_count: STRIP_FRAMES,
+            source_modified: Some(100),
+        };
+
+        assert!(is_current_strip(&stored, Some(100)));
+        assert!(!is_current_strip(&stored, Some(101)));
+        assert!(!is_current_strip(&stored, None));
+        assert!(!is_current_strip(
+            &ThumbnailStripData {
+                frame_count: STRIP_FRAMES + 1,
+                ..stored.clone()
+            },
+            Some(100)
+        ));
+    }
+}
@@ -0,0 +1,254 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use lwe_library::{FolderScanner, LibraryDatabase};
+
+use crate::results::monitor_discovery::MonitorDiscoveryResult;
+use crate::services::monitor_service::MonitorService;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertOperation {
+    /// Turn the picture a quarter turn, then fill the output
+    Rotate { counterclockwise: bool },
+    /// Center-crop and scale to fill the output
+    Crop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvertTarget {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertReport {
+    pub output_path: PathBuf,
+    pub target: ConvertTarget,
+    /// Library id of the converted file, or why it could not be added
+    pub library_id: Result<String, String>,
+}
+
+pub struct WallpaperConvertService;
+
+impl ConvertTarget {
+    pub fn from_resolution(resolution: &str) -> Option<Self> {
+        let (width, height) = resolution.split_once('x')?;
+        let width = width.trim().parse().ok().filter(|width| *width > 0)?;
+        let height = height.trim().parse().ok().filter(|height| *height > 0)?;
+        Some(Self { width, height })
+    }
+}
+
+impl WallpaperConvertService {
+    /// Converts `input` for the output named `output_name` and adds the result to the library.
+    pub fn convert_for_output(
+        operation: ConvertOperation,
+        input: &Path,
+        output: &Path,
+        output_name: &str,
+    ) -> Result<ConvertReport, String> {
+        let target = Self::target_for_output(&MonitorService::list_monitors(), output_name)?;
+        Self::convert(operation, input, output, target)?;
+
+        Ok(ConvertReport {
+            output_path: output.to_path_buf(),
+            target,
+            library_id: Self::add_to_library(output),
+        })
+    }
+
+    pub fn target_for_output(
+        monitors: &MonitorDiscoveryResult,
+        output_name: &str,
+    ) -> Result<ConvertTarget, String> {
+        let monitors = match monitors {
+            MonitorDiscoveryResult::Known(monitors) => monitors,
+            MonitorDiscoveryResult::Unavailable { reason } => {
+                return Err(format!("Unable to look up output {output_name}: {reason}"));
+            }
+        };
+        let monitor = monitors
+            .iter()
+            .find(|monitor| monitor.backend_output_id == output_name || monitor.id == output_name)
+            .ok_or_else(|| format!("Output {output_name} is not connected"))?;
+
+        ConvertTarget::from_resolution(&monitor.resolution).ok_or_else(|| {
+            format!(
+                "Output {output_name} reported an unusable resolution {}",
+                monitor.resolution
+            )
+        })
+    }
+
+    pub fn ffmpeg_args(
+        operation: ConvertOperation,
+        input: &Path,
+        output: &Path,
+        target: ConvertTarget,
+    ) -> Vec<String> {
+        let ConvertTarget { width, height } = target;
+        let mut filters = Vec::new();
+        if let ConvertOperation::Rotate { counterclockwise } = operation {
+            filters.push(format!(
+                "transpose={}",
+                if counterclockwise { 2 } else { 1 }
+            ));
+        }
+        filters.push(format!(
+            "scale={width}:{height}:force_original_aspect_ratio=increase"
+        ));
+        filters.push(format!("crop={width}:{height}"));
+        filters.push("setsar=1".to_string());
+
+        vec![
+            "-hide_banner".to_string(),
+            "-loglevel".to_string(),
+            "error".to_string(),
+            "-n".to_string(),
+            "-i".to_string(),
+            input.to_string_lossy().into_owned(),
+            "-vf".to_string(),
+            filters.join(","),
+            "-map_metadata".to_string(),
+            "-1".to_string(),
+            "-c:a".to_string(),
+            "copy".to_string(),
+            output.to_string_lossy().into_owned(),
+        ]
+    }
+
+    fn convert(
+        operation: ConvertOperation,
+        input: &Path,
+        output: &Path,
+        target: ConvertTarget,
+    ) -> Result<(), String> {
+        if !input.is_file() {
+            return Err(format!("Input {} does not exist", input.display()));
+        }
+        if output.exists() {
+            return Err(format!("Output {} already exists", output.display()));
+        }
+
+        let result = Command::new("ffmpeg")
+            .args(Self::ffmpeg_args(operation, input, output, target))
+            .output()
+            .map_err(|error| format!("Failed to run ffmpeg: {error}"))?;
+
+        if !result.status.success() {
+            let stderr = String::from_utf8_lossy(&result.stderr).trim().to_string();
+            return Err(if stderr.is_empty() {
+                format!("ffmpeg exited with status {}", result.status)
+            } else {
+                format!("ffmpeg failed: {stderr}")
+            });
+        }
+
+        Ok(())
+    }
+
+    fn add_to_library(path: &Path) -> Result<String, String> {
+        let path = path
+            .canonicalize()
+            .map_err(|error| format!("Unable to resolve {}: {error}", path.display()))?;
+        let item = FolderScanner::new()
+            .process_file(&path)
+            .ok_or_else(|| format!("{} is not a supported wallpaper file", path.display()))?;
+        let database = LibraryDatabase::open(LibraryDatabase::default_path())
+            .map_err(|error| format!("Unable to open the library database: {error}"))?;
+        database
+            .upsert_wallpaper(&item)
+            .map_err(|error| format!("Unable to save {}: {error}", path.display()))?;
+
+        Ok(item.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::monitor_service::MonitorDescriptor;
+
+    fn monitors() -> MonitorDiscoveryResult {
+        MonitorDiscoveryResult::Known(vec![MonitorDescriptor {
+            id: "niri:Dell:U2720Q:DP-2".to_string(),
+            backend_output_id: "DP-2".to_string(),
+            name: "Dell U2720Q".to_string(),
+            resolution: "1440x2560".to_string(),
+        }])
+    }
+
+    #[test]
+    fn target_for_output_matches_connector_or_monitor_id() {
+        let portrait = ConvertTarget {
+            width: 1440,
+            height: 2560,
+        };
+
+        assert_eq!(
+            WallpaperConvertService::target_for_output(&monitors(), "DP-2"),
+            Ok(portrait)
+        );
+        assert_eq!(
+            WallpaperConvertService::target_for_output(&monitors(), "niri:Dell:U2720Q:DP-2"),
+            Ok(portrait)
+        );
+        assert_eq!(
+            WallpaperConvertService::target_for_output(&monitors(), "HDMI-A-1"),
+            Err("Output HDMI-A-1 is not connected".to_string())
+        );
+    }
+
+    #[test]
+    fn ffmpeg_args_rotate_before_filling_the_output() {
+        let args = WallpaperConvertService::ffmpeg_args(
+            ConvertOperation::Rotate {
+                counterclockwise: true,
+            },
+            Path::new("in.mp4"),
+            Path::new("out.mp4"),
+            ConvertTarget {
+                width: 1440,
+                height: 2560,
+            },
+        );
+        let filter = &args[args.iter().position(|arg| arg == "-vf").unwrap() + 1];
+
+        assert_eq!(
+            filter,
+            "transpose=2,scale=1440:2560:force_original_aspect_ratio=increase,crop=1440:2560,setsar=1"
+        );
+        assert_eq!(args.last().map(String::as_str), Some("out.mp4"));
+    }
+
+    #[test]
+    fn ffmpeg_args_crop_only_fills_the_output() {
+        let args = WallpaperConvertService::ffmpeg_args(
+            ConvertOperation::Crop,
+            Path::new("in.png"),
+            Path::new("out.png"),
+            ConvertTarget {
+                width: 1920,
+                height: 1080,
+            },
+        );
+
+        assert!(args.contains(
+            &"scale=1920:1080:force_original_aspect_ratio=increase,crop=1920:1080,setsar=1"
+                .to_string()
+        ));
+    }
+
+    #[test]
+    fn convert_target_rejects_malformed_resolutions() {
+        assert_eq!(
+            ConvertTarget::from_resolution("2560x1440"),
+            Some(ConvertTarget {
+                width: 2560,
+                height: 1440
+            })
+        );
+        assert_eq!(ConvertTarget::from_resolution("0x1440"), None);
+        assert_eq!(ConvertTarget::from_resolution("auto"), None);
+    }
+}
@@ -0,0 +1,306 @@
+//! User properties of Wallpaper Engine projects: the controls an author
+//! declares in project.json, with the values the user picked over the
+//! defaults. Web wallpapers receive them through `applyUserProperties`.
+
+use lwe_library::{WeProject, WeProperty, WorkshopProjectType};
+use serde_json::{Map, Value};
+
+use crate::models::{LibraryItemProperty, LibraryItemPropertyOption};
+use crate::services::desktop_service::DesktopService;
+use crate::services::library_marks_service::LibraryMarksService;
+use crate::services::library_service::LibraryService;
+use crate::services::web_wallpaper_service::WebWallpaperService;
+
+pub struct WallpaperPropertiesService;
+
+impl WallpaperPropertiesService {
+    /// Controls for `properties` showing the user's `values`, or the
+    /// author's default where there is none
+    pub fn controls(
+        properties: &[(String, WeProperty)],
+        values: &Map<String, Value>,
+    ) -> Vec<LibraryItemProperty> {
+        properties
+            .iter()
+            .map(|(name, property)| LibraryItemProperty {
+                name: name.clone(),
+                kind: property.kind.clone(),
+                label: label(property.text.as_deref(), name),
+                value: values
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| property.value.clone()),
+                default_value: property.value.clone(),
+                min: property.min,
+                max: property.max,
+                step: property.step,
+                options: property
+                    .options
+                    .iter()
+                    .map(|option| LibraryItemPropertyOption {
+                        label: label(Some(&option.label), &option.value.to_string()),
+                        value: option.value.clone(),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Save the value of one property and pass it to web wallpapers
+    /// showing the item
+    pub fn set(item_id: &str, name: &str, value: Value) -> Result<(), String> {
+        let properties = Self::project_properties(item_id)?;
+        let property = properties
+            .iter()
+            .find(|(property_name, _)| property_name == name)
+            .map(|(_, property)| property)
+            .ok_or_else(|| format!("Library item {item_id} has no property {name}"))?;
+        let value = checked_value(name, property, value)?;
+
+        let mut values = LibraryMarksService::user_properties(item_id);
+        let is_default = value == property.value
+            || (value.is_number() && value.as_f64() == property.value.as_f64());
+        if is_default {
+            values.remove(name);
+        } else {
+            values.insert(name.to_string(), value);
+        }
+        LibraryMarksService::set_user_properties(item_id, &values)?;
+        Self::push_to_web_wallpapers(item_id, &properties, &values);
+        Ok(())
+    }
+
+    /// Go back to the author's defaults
+    pub fn reset(item_id: &str) -> Result<(), String> {
+        let properties = Self::project_properties(item_id)?;
+        let values = Map::new();
+        LibraryMarksService::set_user_properties(item_id, &values)?;
+        Self::push_to_web_wallpapers(item_id, &properties, &values);
+        Ok(())
+    }
+
+    /// What a web wallpaper page of `item_id` receives through
+    /// `wallpaperPropertyListener.applyUserProperties`
+    pub fn page_properties(item_id: &str) -> Value {
+        match Self::project_properties(item_id) {
+            Ok(properties) => {
+                page_properties(&properties, &LibraryMarksService::user_properties(item_id))
+            }
+            Err(_) => Value::Object(Map::new()),
+        }
+    }
+
+    fn project_properties(item_id: &str) -> Result<Vec<(String, WeProperty)>, String> {
+        let entry = LibraryService::inspect_item(item_id)?;
+        if LibraryService::is_local(&entry.entry) {
+            return Ok(Vec::new());
+        }
+        let project = WeProject::load(&entry.entry.project_dir).map_err(|error| {
+            format!("Library item {item_id} has no readable project: {error:#}")
+        })?;
+        Ok(project
+            .user_properties()
+            .into_iter()
+            .map(|(name, property)| (name.to_string(), property.clone()))
+            .collect())
+    }
+
+    fn push_to_web_wallpapers(
+        item_id: &str,
+        properties: &[(String, WeProperty)],
+        values: &Map<String, Value>,
+    ) {
+        if let Ok((WorkshopProjectType::Web, page)) =
+            DesktopService::resolve_real_apply_source(item_id)
+        {
+            WebWallpaperService::apply_properties(&page, &page_properties(properties, values));
+        }
+    }
+}
+
+/// `{ "<name>": { "value": ... } }` for every property, as Wallpaper Engine
+/// passes them to pages
+fn page_properties(properties: &[(String, WeProperty)], values: &Map<String, Value>) -> Value {
+    Value::Object(
+        properties
+            .iter()
+            .map(|(name, property)| {
+                let value = values.get(name).unwrap_or(&property.value).clone();
+                (name.clone(), serde_json::json!({ "value": value }))
+            })
+            .collect(),
+    )
+}
+
+/// `value` in the form `property` takes; sliders are clamped to their range
+fn checked_value(name: &str, property: &WeProperty, value: Value) -> Result<Value, String> {
+    let invalid = || format!("{value} is not a valid value for {name}");
+    match property.kind.as_str() {
+        "slider" => {
+            let mut number = value.as_f64().ok_or_else(invalid)?;
+            if let Some(min) = property.min {
+                number = number.max(min);
+            }
+            if let Some(max) = property.max {
+                number = number.min(max);
+            }
+            Ok(serde_json::json!(number))
+        }
+        "bool" if value.is_boolean() => Ok(value),
+        "color" => {
+            let channels = value
+                .as_str()
+                .map(|color| {
+                    color
+                        .split_whitespace()
+                        .map(str::parse::<f64>)
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .and_then(Result::ok)
+                .filter(|channels| {
+                    channels.len() == 3
+                        && channels.iter().all(|channel| (0.0..=1.0).contains(channel))
+                })
+                .ok_or_else(invalid)?;
+            Ok(Value::String(
+                channels
+                    .iter()
+                    .map(f64::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ))
+        }
+        "combo" => property
+            .options
+            .iter()
+            .find(|option| same_option_value(&option.value, &value))
+            .map(|option| option.value.clone())
+            .ok_or_else(invalid),
+        "textinput" if value.is_string() => Ok(value),
+        _ => Err(invalid()),
+    }
+}
+
+/// Combo values are strings in some projects and numbers in others
+fn same_option_value(option: &Value, value: &Value) -> bool {
+    option == value
+        || match (option, value) {
+            (Value::String(text), other) | (other, Value::String(text)) => {
+                other.to_string() == *text
+            }
+            _ => false,
+        }
+}
+
+/// Readable label from a property's `text`, which may hold HTML or an
+/// untranslated `ui_` localization key
+fn label(text: Option<&str>, fallback: &str) -> String {
+    let mut plain = String::new();
+    let mut in_tag = false;
+    for c in text.unwrap_or_default().chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => plain.push(c),
+            _ => {}
+        }
+    }
+    let plain = plain.trim();
+    if plain.is_empty() || plain.starts_with("ui_") {
+        fallback.to_string()
+    } else {
+        plain.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lwe_library::WePropertyOption;
+    use serde_json::json;
+
+    fn property(kind: &str, value: Value) -> WeProperty {
+        WeProperty {
+            kind: kind.to_string(),
+            text: None,
+            value,
+            order: 0,
+            min: None,
+            max: None,
+            step: None,
+            options: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn wallpaper_properties_controls_prefer_user_values_and_readable_labels() {
+        let properties = vec![
+            (
+                "schemecolor".to_string(),
+                WeProperty {
+                    text: Some("ui_browse_properties_scheme_color".to_string()),
+                    ..property("color", json!("0 0 1"))
+                },
+            ),
+            (
+                "speed".to_string(),
+                WeProperty {
+                    text: Some("<b>Rain</b> speed".to_string()),
+                    min: Some(0.0),
+                    max: Some(5.0),
+                    ..property("slider", json!(1.0))
+                },
+            ),
+        ];
+        let values = Map::from_iter([("speed".to_string(), json!(3.0))]);
+
+        let controls = WallpaperPropertiesService::controls(&properties, &values);
+        assert_eq!(controls[0].label, "schemecolor");
+        assert_eq!(controls[0].value, json!("0 0 1"));
+        assert_eq!(controls[1].label, "Rain speed");
+        assert_eq!(controls[1].value, json!(3.0));
+        assert_eq!(controls[1].default_value, json!(1.0));
+
+        assert_eq!(
+            page_properties(&properties, &values),
+            json!({ "schemecolor": { "value": "0 0 1" }, "speed": { "value": 3.0 } })
+        );
+    }
+
+    #[test]
+    fn wallpaper_properties_check_values_against_their_control() {
+        let slider = WeProperty {
+            min: Some(0.0),
+            max: Some(5.0),
+            ..property("slider", json!(1.0))
+        };
+        assert_eq!(checked_value("speed", &slider, json!(9)), Ok(json!(5.0)));
+        assert!(checked_value("speed", &slider, json!("fast")).is_err());
+
+        let color = property("color", json!("0 0 1"));
+        assert_eq!(
+            checked_value("tint", &color, json!("1 0.5 0")),
+            Ok(json!("1 0.5 0"))
+        );
+        assert!(checked_value("tint", &color, json!("1 0.5")).is_err());
+        assert!(checked_value("tint", &color, json!("2 0 0")).is_err());
+
+        let combo = WeProperty {
+            options: vec![
+                WePropertyOption {
+                    label: "Day".to_string(),
+                    value: json!("1"),
+                },
+                WePropertyOption {
+                    label: "Night".to_string(),
+                    value: json!("2"),
+                },
+            ],
+            ..property("combo", json!("1"))
+        };
+        assert_eq!(checked_value("mode", &combo, json!(2)), Ok(json!("2")));
+        assert!(checked_value("mode", &combo, json!("3")).is_err());
+
+        assert!(checked_value("rain", &property("bool", json!(true)), json!(1)).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Output a web wallpaper covers, in compositor layout coordinates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebWallpaperTarget {
+    pub output: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Shows HTML wallpapers; the desktop shell installs a webview-backed host at startup.
+/// `properties` are the user properties handed to the page's
+/// `wallpaperPropertyListener.applyUserProperties` once it has loaded.
+pub trait WebWallpaperHost: Send + Sync {
+    fn show(
+        &self,
+        target: &WebWallpaperTarget,
+        page: &Path,
+        properties: &serde_json::Value,
+    ) -> Result<(), String>;
+    /// Pass changed user properties to the page already showing on `output`
+    fn apply_properties(&self, output: &str, properties: &serde_json::Value);
+    fn close(&self, output: &str);
+}
+
+fn web_wallpaper_host() -> &'static OnceLock<Box<dyn WebWallpaperHost>> {
+    static HOST: OnceLock<Box<dyn WebWallpaperHost>> = OnceLock::new();
+    &HOST
+}
+
+/// Page showing on each output
+fn shown_pages() -> &'static Mutex<HashMap<String, PathBuf>> {
+    static PAGES: OnceLock<Mutex<HashMap<String, PathBuf>>> = OnceLock::new();
+    PAGES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub struct WebWallpaperService;
+
+impl WebWallpaperService {
+    pub fn install_host(host: Box<dyn WebWallpaperHost>) {
+        if web_wallpaper_host().set(host).is_err() {
+            eprintln!("web wallpaper host was already installed");
+        }
+    }
+
+    pub fn show(
+        target: &WebWallpaperTarget,
+        page: &Path,
+        properties: &serde_json::Value,
+    ) -> Result<(), String> {
+        if !page.is_file() {
+            return Err(format!("Web wallpaper page {} is missing", page.display()));
+        }
+
+        web_wallpaper_host()
+            .get()
+            .ok_or_else(|| "Web wallpapers need the LWE desktop shell to be running".to_string())?
+            .show(target, page, properties)?;
+        shown_pages()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(target.output.clone(), page.to_path_buf());
+        Ok(())
+    }
+
+    /// Pass user properties to every output showing `page`
+    pub fn apply_properties(page: &Path, properties: &serde_json::Value) {
+        let Some(host) = web_wallpaper_host().get() else {
+            return;
+        };
+        let outputs = shown_pages()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .filter(|(_, shown)| shown.as_path() == page)
+            .map(|(output, _)| output.clone())
+            .collect::<Vec<_>>();
+        for output in outputs {
+            host.apply_properties(&output, properties);
+        }
+    }
+
+    /// Remove the web wallpaper from an output, if one is showing
+    pub fn close(output: &str) {
+        shown_pages()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(output);
+        if let Some(host) = web_wallpaper_host().get() {
+            host.close(output);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn web_wallpaper_show_rejects_missing_page_before_reaching_host() {
+        let target = WebWallpaperTarget {
+            output: "DP-1".to_string(),
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+        };
+
+        assert_eq!(
+            WebWallpaperService::show(
+                &target,
+                Path::new("/nonexistent/index.html"),
+                &serde_json::Value::Null
+            ),
+            Err("Web wallpaper page /nonexistent/index.html is missing".to_string())
+        );
+    }
+}
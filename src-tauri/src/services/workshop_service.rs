@@ -3,11 +3,21 @@ use crate::models::{
     WorkshopOnlineSearchResult,
 };
 use crate::results::settings_persistence::SettingsPersistenceLoad;
-use crate::results::workshop::{WorkshopInspection, WorkshopRefreshResult};
+use crate::results::workshop::{WorkshopInspection, WorkshopItemDetails, WorkshopRefreshResult};
 use crate::services::compatibility_service::CompatibilityService;
+use crate::services::http_service::HttpService;
 use crate::services::settings_persistence_service::SettingsPersistenceService;
 use lwe_library::{SteamLibrary, WorkshopCatalogEntry, WorkshopScanner};
 use serde_json::Value;
+use std::time::Duration;
+
+const QUERY_FILES_URL: &str = "https://api.steampowered.com/IPublishedFileService/QueryFiles/v1/";
+/// Pages with previews and vote data can take Steam a while
+const QUERY_TIMEOUT: Duration = Duration::from_secs(30);
+/// Public endpoint; unlike QueryFiles it needs no API key
+const PUBLISHED_FILE_DETAILS_URL: &str =
+    "https://api.steampowered.com/ISteamRemoteStorage/GetPublishedFileDetails/v1/";
+const DETAILS_TIMEOUT: Duration = Duration::from_secs(20);
 
 pub struct WorkshopService;
 
@@ -337,38 +347,26 @@ impl WorkshopService {
         let api_key = Self::load_steam_web_api_key()?;
         let query = input.query.trim().to_string();
         let (page, page_size) = Self::normalized_pagination(&input);
-        let client = reqwest::blocking::Client::new();
-        let mut request = client
-            .get("https://api.steampowered.com/IPublishedFileService/QueryFiles/v1/")
-            .query(&[
-                ("key", api_key.as_str()),
-                ("appid", "431960"),
-                ("page", &page.to_string()),
-                ("numperpage", &page_size.to_string()),
-                ("return_tags", "1"),
-                ("return_metadata", "1"),
-                ("return_previews", "1"),
-                ("return_short_description", "1"),
-                ("return_vote_data", "1"),
-                ("return_for_sale_data", "1"),
-            ]);
-
+        let (page_text, page_size_text) = (page.to_string(), page_size.to_string());
+        let mut params = vec![
+            ("key", api_key.as_str()),
+            ("appid", "431960"),
+            ("page", page_text.as_str()),
+            ("numperpage", page_size_text.as_str()),
+            ("return_tags", "1"),
+            ("return_metadata", "1"),
+            ("return_previews", "1"),
+            ("return_short_description", "1"),
+            ("return_vote_data", "1"),
+            ("return_for_sale_data", "1"),
+        ];
         if !query.is_empty() {
-            request = request.query(&[("search_text", query.as_str())]);
+            params.push(("search_text", query.as_str()));
         }
 
-        let response = request
-            .send()
+        let payload = HttpService::get_json(QUERY_FILES_URL, &params, QUERY_TIMEOUT)
             .map_err(|error| format!("Failed to call Steam Workshop QueryFiles: {error}"))?;
 
-        let payload = response
-            .error_for_status()
-            .map_err(|error| format!("Steam Workshop QueryFiles returned an error: {error}"))?
-            .json::<Value>()
-            .map_err(|error| {
-                format!("Failed to parse Steam Workshop QueryFiles response: {error}")
-            })?;
-
         let total_approx = Self::parse_total_results(&payload);
         let items = Self::parse_online_items(&payload, &input);
         let has_more = total_approx
@@ -417,6 +415,78 @@ impl WorkshopService {
         })
     }
 
+    /// Steam metadata for `workshop_ids`, in the same order
+    pub fn fetch_item_details(workshop_ids: &[String]) -> Result<Vec<WorkshopItemDetails>, String> {
+        if workshop_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut form = vec![("itemcount".to_string(), workshop_ids.len().to_string())];
+        for (index, id) in workshop_ids.iter().enumerate() {
+            form.push((format!("publishedfileids[{index}]"), id.clone()));
+        }
+
+        let payload =
+            HttpService::post_form_json(PUBLISHED_FILE_DETAILS_URL, &form, DETAILS_TIMEOUT)
+                .map_err(|error| {
+                    format!("Failed to call Steam GetPublishedFileDetails: {error}")
+                })?;
+
+        let details = Self::parse_item_details(&payload);
+        Ok(workshop_ids
+            .iter()
+            .map(|id| {
+                details
+                    .iter()
+                    .find(|item| &item.workshop_id == id)
+                    .cloned()
+                    .unwrap_or_else(|| WorkshopItemDetails {
+                        workshop_id: id.clone(),
+                        ..WorkshopItemDetails::default()
+                    })
+            })
+            .collect())
+    }
+
+    fn parse_item_details(payload: &Value) -> Vec<WorkshopItemDetails> {
+        // Steam sends 64-bit numbers as strings in this API
+        let number = |value: &Value| {
+            value
+                .as_u64()
+                .or_else(|| value.as_str().and_then(|text| text.parse().ok()))
+        };
+        let text = |value: &Value| {
+            value
+                .as_str()
+                .filter(|text| !text.trim().is_empty())
+                .map(str::to_string)
+        };
+
+        payload["response"]["publishedfiledetails"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|entry| WorkshopItemDetails {
+                        workshop_id: text(&entry["publishedfileid"]).unwrap_or_default(),
+                        // result is an EResult; 1 is OK, 9 is "file not found"
+                        found: number(&entry["result"]) == Some(1),
+                        title: text(&entry["title"]),
+                        description: text(&entry["description"]),
+                        preview_url: text(&entry["preview_url"]),
+                        file_size: number(&entry["file_size"]),
+                        tags: entry["tags"]
+                            .as_array()
+                            .map(|tags| tags.iter().filter_map(|tag| text(&tag["tag"])).collect())
+                            .unwrap_or_default(),
+                        subscriptions: number(&entry["subscriptions"]),
+                        time_updated: number(&entry["time_updated"]),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub fn inspect_item(workshop_id: &str) -> Result<WorkshopInspection, String> {
         let entry = Self::refresh_catalog()?
             .catalog_entries
@@ -512,4 +582,39 @@ mod tests {
         assert_eq!(items[0].item_type, WorkshopOnlineItemType::Scene);
         assert_eq!(items[0].age_rating, WorkshopAgeRating::G);
     }
+
+    #[test]
+    fn parse_item_details_reads_string_numbers_and_missing_items() {
+        let payload = json!({
+            "response": {
+                "result": 1,
+                "resultcount": 2,
+                "publishedfiledetails": [
+                    {
+                        "publishedfileid": "1234567890",
+                        "result": 1,
+                        "title": "Rainy Window",
+                        "description": "",
+                        "file_size": "52428800",
+                        "preview_url": "https://example.com/rain.jpg",
+                        "subscriptions": 4200,
+                        "time_updated": 1700000000,
+                        "tags": [{ "tag": "Video" }, { "tag": "Nature" }]
+                    },
+                    { "publishedfileid": "42", "result": 9 }
+                ]
+            }
+        });
+
+        let details = WorkshopService::parse_item_details(&payload);
+        assert_eq!(details.len(), 2);
+        assert!(details[0].found);
+        assert_eq!(details[0].title.as_deref(), Some("Rainy Window"));
+        assert_eq!(details[0].description, None);
+        assert_eq!(details[0].file_size, Some(52_428_800));
+        assert_eq!(details[0].tags, ["Video", "Nature"]);
+        assert_eq!(details[0].subscriptions, Some(4200));
+        assert!(!details[1].found);
+        assert_eq!(details[1].workshop_id, "42");
+    }
 }
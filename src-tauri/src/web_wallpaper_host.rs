@@ -0,0 +1,206 @@
+//! Webview host for HTML wallpapers.
+//!
+//! Each output showing a web wallpaper gets an undecorated Tauri webview window.
+//! On Linux the window is moved onto the wlr-layer-shell background layer with
+//! gtk-layer-shell, so it sits where the engine's video surface would.
+//! User properties reach the page through Wallpaper Engine's
+//! `wallpaperPropertyListener.applyUserProperties` after every load.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use tauri::webview::PageLoadEvent;
+use tauri::{AppHandle, Manager, Url, WebviewUrl, WebviewWindowBuilder};
+
+use crate::services::web_wallpaper_service::{WebWallpaperHost, WebWallpaperTarget};
+
+pub struct TauriWebWallpaperHost {
+    app: AppHandle,
+    /// User properties of the page in each window, as JSON
+    properties: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl TauriWebWallpaperHost {
+    pub fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            properties: Arc::default(),
+        }
+    }
+}
+
+fn apply_properties_script(properties: &str) -> String {
+    format!(
+        "(function(){{var l=window.wallpaperPropertyListener;\
+         if(l&&typeof l.applyUserProperties==='function'){{l.applyUserProperties({properties});}}}})();"
+    )
+}
+
+/// Window labels only allow alphanumerics, `-`, `/`, `:` and `_`.
+fn window_label(output: &str) -> String {
+    let output = output
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format!("web-wallpaper-{output}")
+}
+
+impl WebWallpaperHost for TauriWebWallpaperHost {
+    fn show(
+        &self,
+        target: &WebWallpaperTarget,
+        page: &Path,
+        properties: &serde_json::Value,
+    ) -> Result<(), String> {
+        let url = Url::from_file_path(page).map_err(|_| {
+            format!(
+                "Web wallpaper page {} is not an absolute path",
+                page.display()
+            )
+        })?;
+        let label = window_label(&target.output);
+        if let Ok(mut stored) = self.properties.lock() {
+            stored.insert(label.clone(), properties.to_string());
+        }
+
+        if let Some(window) = self.app.get_webview_window(&label) {
+            return window.navigate(url).map_err(|error| {
+                format!("Failed to load web wallpaper {}: {error}", page.display())
+            });
+        }
+
+        let app = self.app.clone();
+        let target = target.clone();
+        let stored = Arc::clone(&self.properties);
+        self.app
+            .run_on_main_thread(move || {
+                let window =
+                    match WebviewWindowBuilder::new(&app, &label, WebviewUrl::External(url))
+                        .title("LWE web wallpaper")
+                        .decorations(false)
+                        .skip_taskbar(true)
+                        .focused(false)
+                        .visible(false)
+                        .inner_size(f64::from(target.width), f64::from(target.height))
+                        .on_page_load(move |window, payload| {
+                            if payload.event() != PageLoadEvent::Finished {
+                                return;
+                            }
+                            let properties = stored
+                                .lock()
+                                .ok()
+                                .and_then(|stored| stored.get(window.label()).cloned());
+                            if let Some(properties) = properties {
+                                if let Err(reason) =
+                                    window.eval(&apply_properties_script(&properties))
+                                {
+                                    eprintln!(
+                                        "failed to pass properties to web wallpaper: {reason}"
+                                    );
+                                }
+                            }
+                        })
+                        .build()
+                    {
+                        Ok(window) => window,
+                        Err(reason) => {
+                            eprintln!(
+                                "failed to create web wallpaper window for {}: {reason}",
+                                target.output
+                            );
+                            return;
+                        }
+                    };
+
+                if let Err(reason) = place_on_background(&window, &target) {
+                    eprintln!(
+                        "failed to place web wallpaper on {}: {reason}",
+                        target.output
+                    );
+                    let _ = window.destroy();
+                }
+            })
+            .map_err(|error| format!("Failed to schedule web wallpaper window: {error}"))
+    }
+
+    fn apply_properties(&self, output: &str, properties: &serde_json::Value) {
+        let label = window_label(output);
+        let properties = properties.to_string();
+        if let Ok(mut stored) = self.properties.lock() {
+            stored.insert(label.clone(), properties.clone());
+        }
+        if let Some(window) = self.app.get_webview_window(&label) {
+            if let Err(reason) = window.eval(&apply_properties_script(&properties)) {
+                eprintln!("failed to pass properties to web wallpaper on {output}: {reason}");
+            }
+        }
+    }
+
+    fn close(&self, output: &str) {
+        if let Ok(mut stored) = self.properties.lock() {
+            stored.remove(&window_label(output));
+        }
+        if let Some(window) = self.app.get_webview_window(&window_label(output)) {
+            if let Err(reason) = window.destroy() {
+                eprintln!("failed to close web wallpaper on {output}: {reason}");
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn place_on_background(
+    window: &tauri::WebviewWindow,
+    target: &WebWallpaperTarget,
+) -> Result<(), String> {
+    use gtk::prelude::*;
+    use gtk_layer_shell::{Edge, KeyboardMode, Layer, LayerShell};
+
+    let gtk_window = window.gtk_window().map_err(|error| error.to_string())?;
+
+    // Layer shell has to be set up before the window is mapped
+    gtk_window.hide();
+    gtk_window.unrealize();
+    gtk_window.init_layer_shell();
+    gtk_window.set_layer(Layer::Background);
+    gtk_window.set_namespace("lwe-web");
+    gtk_window.set_keyboard_mode(KeyboardMode::None);
+    gtk_window.set_exclusive_zone(-1);
+    for edge in [Edge::Top, Edge::Bottom, Edge::Left, Edge::Right] {
+        gtk_window.set_anchor(edge, true);
+    }
+    let monitor = WidgetExt::display(&gtk_window).monitor_at_point(target.x + 1, target.y + 1);
+    match monitor {
+        Some(monitor) => gtk_window.set_monitor(&monitor),
+        None => return Err(format!("no GDK monitor at {},{}", target.x, target.y)),
+    }
+    gtk_window.show_all();
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn place_on_background(
+    _window: &tauri::WebviewWindow,
+    _target: &WebWallpaperTarget,
+) -> Result<(), String> {
+    Err("web wallpapers need a wlr-layer-shell compositor".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::window_label;
+
+    #[test]
+    fn window_label_keeps_connector_names_valid() {
+        assert_eq!(window_label("DP-2"), "web-wallpaper-DP-2");
+        assert_eq!(window_label("HDMI A.1"), "web-wallpaper-HDMI_A_1");
+    }
+}
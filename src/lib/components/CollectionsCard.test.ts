@@ -0,0 +1,48 @@
+import { afterEach, describe, expect, it } from 'vitest';
+import { render } from 'svelte/server';
+
+import { resetPreferredLanguage, setPreferredLanguage } from '$lib/i18n';
+import CollectionsCard from './CollectionsCard.svelte';
+
+const collections = [
+  {
+    id: 2,
+    name: 'Nature',
+    items: [
+      { itemId: 'scene-7', itemTitle: 'Forest Scene' },
+      { itemId: 'local-gone', itemTitle: null }
+    ]
+  },
+  { id: 3, name: 'Evening', items: [] }
+];
+
+describe('CollectionsCard', () => {
+  afterEach(() => {
+    resetPreferredLanguage();
+  });
+
+  it('lists collections with their items and apply targets', () => {
+    const { body } = render(CollectionsCard, {
+      props: {
+        collections,
+        libraryItems: [{ id: 'scene-7', title: 'Forest Scene' }],
+        monitors: [{ monitorId: 'DP-1', displayName: 'Primary (2560x1440)' }]
+      }
+    });
+
+    expect(body).toContain('value="Nature"');
+    expect(body).toContain('Forest Scene');
+    expect(body).toContain('Missing item (local-gone)');
+    expect(body).toContain('Remove Forest Scene from Nature');
+    expect(body).toContain('This collection has no items yet.');
+    expect(body).toContain('All monitors');
+    expect(body).toContain('Create collection');
+  });
+
+  it('localizes the empty state', () => {
+    setPreferredLanguage('zh-CN');
+    const { body } = render(CollectionsCard, { props: { collections: [] } });
+
+    expect(body).toContain('还没有合集');
+  });
+});
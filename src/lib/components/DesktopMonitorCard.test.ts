@@ -102,4 +102,72 @@ describe('DesktopMonitorCard', () => {
 
     expect(body.match(/Missing Monitor/g)).toHaveLength(2);
   });
+
+  it('shows a volume slider only when the card can change audio', () => {
+    const props = {
+      displayName: 'Primary',
+      monitorId: 'DISPLAY-1',
+      resolution: '1920x1080',
+      currentItemLabel: 'Forest Scene',
+      volume: 35,
+      muted: false
+    };
+
+    expect(render(DesktopMonitorCard, { props }).body).not.toContain('type="range"');
+
+    const { body } = render(DesktopMonitorCard, {
+      props: { ...props, onAudioChange: () => {} }
+    });
+
+    expect(body).toContain('type="range"');
+    expect(body).toContain('value="35"');
+    expect(body).toContain('35%');
+    expect(body).toContain('Mute Primary');
+  });
+
+  it('shows color sliders with a reset once a value is changed', () => {
+    const props = {
+      displayName: 'Primary',
+      monitorId: 'DISPLAY-1',
+      currentItemLabel: 'Forest Scene',
+      color: { brightness: 100, contrast: 100, gamma: 100, saturation: 100 }
+    };
+
+    expect(render(DesktopMonitorCard, { props }).body).not.toContain('Brightness for Primary');
+
+    const neutral = render(DesktopMonitorCard, {
+      props: { ...props, onColorChange: () => {} }
+    }).body;
+    expect(neutral).toContain('Brightness for Primary');
+    expect(neutral).toContain('Saturation for Primary');
+    const resetButton = (body: string) => body.match(/<button[^>]*Reset colors of Primary[^>]*>/)?.[0];
+    expect(resetButton(neutral)).toMatch(/\sdisabled(=""|[\s>])/);
+
+    const { body } = render(DesktopMonitorCard, {
+      props: {
+        ...props,
+        color: { ...props.color, brightness: 90 },
+        onColorChange: () => {}
+      }
+    });
+    expect(body).toContain('value="90"');
+    expect(body).toContain('90%');
+    expect(resetButton(body)).not.toMatch(/\sdisabled(=""|[\s>])/);
+  });
+
+  it('shows when the wallpaper was applied and how long it took', () => {
+    const props = {
+      displayName: 'Primary',
+      monitorId: 'DISPLAY-1',
+      currentItemLabel: 'Forest Scene'
+    };
+
+    expect(render(DesktopMonitorCard, { props }).body).not.toContain('Applied');
+
+    const { body } = render(DesktopMonitorCard, {
+      props: { ...props, appliedSecondsAgo: 2, applyLatencyMs: 340 }
+    });
+
+    expect(body).toContain('Applied 2s ago · took 340 ms');
+  });
 });
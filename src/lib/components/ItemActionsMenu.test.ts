@@ -22,4 +22,19 @@ describe('ItemActionsMenu', () => {
 
     expect(body).toContain('aria-label="显示 Forest Scene 的快捷操作"');
   });
+
+  it('lists only the actions the card provides', () => {
+    const { body } = render(ItemActionsMenu, {
+      props: {
+        itemTitle: 'Forest Scene',
+        open: true,
+        onApplyShortcut: () => {},
+        onReveal: () => {}
+      }
+    });
+
+    expect(body).toContain('Show in folder');
+    expect(body).not.toContain('Play in external player');
+    expect(body).not.toContain('Delete…');
+  });
 });
@@ -0,0 +1,34 @@
+import { afterEach, describe, expect, it } from 'vitest';
+import { render } from 'svelte/server';
+
+import { resetPreferredLanguage, setPreferredLanguage } from '$lib/i18n';
+import LibraryBatchBar from './LibraryBatchBar.svelte';
+
+describe('LibraryBatchBar', () => {
+  afterEach(() => {
+    resetPreferredLanguage();
+  });
+
+  it('renders the marked count and every batch action', () => {
+    const { body } = render(LibraryBatchBar, {
+      props: { count: 3, collections: [{ id: 1, name: 'Evening', items: [] }] }
+    });
+
+    expect(body).toContain('data-library-batch-bar');
+    expect(body).toContain('3 marked');
+    expect(body).toContain('Add tag');
+    expect(body).toContain('Add to collection…');
+    expect(body).toContain('Regenerate thumbnails');
+    expect(body).toContain('Export paths…');
+    expect(body).toContain('Delete…');
+  });
+
+  it('says when there is no collection to add to', () => {
+    setPreferredLanguage('zh-CN');
+
+    const { body } = render(LibraryBatchBar, { props: { count: 1 } });
+
+    expect(body).toContain('已标记 1 项');
+    expect(body).toContain('尚无合集');
+  });
+});
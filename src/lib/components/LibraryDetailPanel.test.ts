@@ -15,6 +15,7 @@ describe('LibraryDetailPanel', () => {
         detail: {
           id: 'scene-1',
           title: 'Forest Scene',
+          originalTitle: 'Forest Scene',
           itemType: 'scene',
           coverPath: null,
           source: 'workshop',
@@ -33,7 +34,12 @@ describe('LibraryDetailPanel', () => {
           desktopAssignmentsAvailable: false,
           assignedMonitorLabels: [],
           description: null,
-          tags: []
+          tags: [],
+          userTags: [],
+          media: {},
+          favorite: false,
+          rating: 0,
+          properties: []
         }
       }
     });
@@ -51,6 +57,7 @@ describe('LibraryDetailPanel', () => {
         detail: {
           id: 'scene-1',
           title: 'Forest Scene',
+          originalTitle: 'Forest Scene',
           itemType: 'scene',
           coverPath: null,
           source: 'workshop',
@@ -69,7 +76,12 @@ describe('LibraryDetailPanel', () => {
           desktopAssignmentsAvailable: true,
           assignedMonitorLabels: ['Primary', 'DISPLAY-2 (missing)'],
           description: null,
-          tags: []
+          tags: [],
+          userTags: [],
+          media: {},
+          favorite: false,
+          rating: 0,
+          properties: []
         }
       }
     });
@@ -85,6 +97,7 @@ describe('LibraryDetailPanel', () => {
         detail: {
           id: 'scene-1',
           title: 'Forest Scene',
+          originalTitle: 'Forest Scene',
           itemType: 'scene',
           coverPath: null,
           source: 'workshop',
@@ -103,7 +116,12 @@ describe('LibraryDetailPanel', () => {
           desktopAssignmentsAvailable: true,
           assignedMonitorLabels: ['Primary'],
           description: null,
-          tags: []
+          tags: [],
+          userTags: [],
+          media: {},
+          favorite: false,
+          rating: 0,
+          properties: []
         }
       }
     });
@@ -118,6 +136,7 @@ describe('LibraryDetailPanel', () => {
         detail: {
           id: 'scene-1',
           title: 'Forest Scene',
+          originalTitle: 'Forest Scene',
           itemType: 'scene',
           coverPath: null,
           source: 'workshop',
@@ -136,7 +155,12 @@ describe('LibraryDetailPanel', () => {
           desktopAssignmentsAvailable: true,
           assignedMonitorLabels: ['Primary'],
           description: null,
-          tags: []
+          tags: [],
+          userTags: [],
+          media: {},
+          favorite: false,
+          rating: 0,
+          properties: []
         },
         applyError: 'Unable to refresh the Library snapshot.'
       }
@@ -148,12 +172,71 @@ describe('LibraryDetailPanel', () => {
     expect(body).not.toContain('Select a Library item to inspect its current detail payload.');
   });
 
+  it('asks for confirmation before applying to all monitors and offers a timed undo', () => {
+    const monitor = (monitorId: string) => ({
+      monitorId,
+      displayName: monitorId,
+      resolution: '1920x1080',
+      currentWallpaperTitle: null,
+      currentCoverPath: null,
+      currentItemId: null,
+      clearSupported: false,
+      runtimeStatus: 'idle' as const,
+      volume: 100,
+      muted: true,
+      color: { brightness: 100, contrast: 100, gamma: 100, saturation: 100 }
+    });
+    const { body } = render(LibraryDetailPanel, {
+      props: {
+        detail: {
+          id: 'scene-1',
+          title: 'Forest Scene',
+          originalTitle: 'Forest Scene',
+          itemType: 'scene',
+          coverPath: null,
+          source: 'workshop',
+          compatibility: {
+            badge: 'fully_supported',
+            reasonCode: 'ready_for_library',
+            summaryCopy: 'Ready to use',
+            headline: 'Ready to use',
+            detail: 'This item is synchronized locally and available for Library and desktop use.',
+            nextStep: 'none',
+            nextStepCopy: null
+          },
+          monitorsAvailable: true,
+          monitorDiscoveryIssue: null,
+          desktopAssignmentIssue: null,
+          desktopAssignmentsAvailable: true,
+          assignedMonitorLabels: [],
+          description: null,
+          tags: [],
+          userTags: [],
+          media: {},
+          favorite: false,
+          rating: 0,
+          properties: []
+        },
+        monitors: [monitor('DISPLAY-1'), monitor('DISPLAY-2')],
+        selectedMonitorId: '__all__',
+        confirmPending: true,
+        undoSecondsLeft: 4
+      }
+    });
+
+    expect(body).toContain('All monitors');
+    expect(body).toContain('Replace the wallpaper on all 2 monitors?');
+    expect(body).toContain('Apply to all');
+    expect(body).toContain('Undo (4s)');
+  });
+
   it('uses a compact vertical detail structure for populated detail', () => {
     const { body } = render(LibraryDetailPanel, {
       props: {
         detail: {
           id: 'scene-1',
           title: 'Forest Scene',
+          originalTitle: 'Forest Scene',
           itemType: 'scene',
           coverPath: null,
           source: 'workshop',
@@ -172,7 +255,12 @@ describe('LibraryDetailPanel', () => {
           desktopAssignmentsAvailable: true,
           assignedMonitorLabels: ['Primary'],
           description: 'Dense detail flow.',
-          tags: ['forest']
+          tags: ['forest'],
+          userTags: [],
+          media: {},
+          favorite: false,
+          rating: 0,
+          properties: []
         }
       }
     });
@@ -234,6 +322,182 @@ describe('LibraryDetailPanel', () => {
     expect(body).toContain('Library detail');
   });
 
+  it('renders a control for each user property of the project', () => {
+    const { body } = render(LibraryDetailPanel, {
+      props: {
+        detail: {
+          id: 'web-1',
+          title: 'Rain Page',
+          originalTitle: 'Rain Page',
+          itemType: 'web',
+          coverPath: null,
+          source: 'workshop',
+          compatibility: {
+            badge: 'fully_supported',
+            reasonCode: 'ready_for_library',
+            summaryCopy: 'Ready to use',
+            headline: 'Ready to use',
+            detail: 'This item is synchronized locally and available for Library and desktop use.',
+            nextStep: 'none',
+            nextStepCopy: null
+          },
+          monitorsAvailable: true,
+          monitorDiscoveryIssue: null,
+          desktopAssignmentIssue: null,
+          desktopAssignmentsAvailable: true,
+          assignedMonitorLabels: [],
+          description: null,
+          tags: [],
+          userTags: [],
+          media: {},
+          favorite: false,
+          rating: 0,
+          properties: [
+            {
+              name: 'speed',
+              kind: 'slider',
+              label: 'Rain speed',
+              value: 3,
+              defaultValue: 1,
+              min: 0,
+              max: 5,
+              options: []
+            },
+            {
+              name: 'tint',
+              kind: 'color',
+              label: 'Tint',
+              value: '1 0.5 0',
+              defaultValue: '1 0.5 0',
+              options: []
+            },
+            {
+              name: 'mode',
+              kind: 'combo',
+              label: 'Mode',
+              value: '2',
+              defaultValue: '1',
+              options: [
+                { label: 'Day', value: '1' },
+                { label: 'Night', value: '2' }
+              ]
+            }
+          ]
+        }
+      }
+    });
+
+    expect(body).toContain('data-detail-section="properties"');
+    expect(body).toContain('Rain speed');
+    expect(body).toContain('type="range"');
+    expect(body).toContain('#ff8000');
+    expect(body).toContain('Night');
+    expect(body).toContain('Reset to defaults');
+    expect(body).not.toContain('Scenes are not rendered yet');
+  });
+
+  it('shows media details, user tags, the original title and the Workshop origin', () => {
+    const { body } = render(LibraryDetailPanel, {
+      props: {
+        detail: {
+          id: 'video-1',
+          title: 'Rain at night',
+          originalTitle: 'Rainy City',
+          itemType: 'video',
+          coverPath: null,
+          source: 'workshop',
+          compatibility: {
+            badge: 'fully_supported',
+            reasonCode: 'ready_for_library',
+            summaryCopy: 'Ready to use',
+            headline: 'Ready to use',
+            detail: 'This item is synchronized locally and available for Library and desktop use.',
+            nextStep: 'none',
+            nextStepCopy: null
+          },
+          monitorsAvailable: true,
+          monitorDiscoveryIssue: null,
+          desktopAssignmentIssue: null,
+          desktopAssignmentsAvailable: true,
+          assignedMonitorLabels: [],
+          description: null,
+          tags: ['Nature'],
+          userTags: ['rain', 'night'],
+          workshopId: '1234567890',
+          media: {
+            width: 3840,
+            height: 2160,
+            durationSecs: 95,
+            codec: 'hevc',
+            fps: 59.94,
+            hdr: 'pq',
+            fileSize: 52428800
+          },
+          favorite: false,
+          rating: 0,
+          properties: []
+        }
+      }
+    });
+
+    expect(body).toContain('Rain at night');
+    expect(body).toContain('Originally “Rainy City”');
+    expect(body).toContain('Use original title');
+    expect(body).toContain('3840 × 2160');
+    expect(body).toContain('hevc');
+    expect(body).toContain('59.94 fps');
+    expect(body).toContain('HDR10 (PQ)');
+    expect(body).toContain('50.0 MiB');
+    expect(body).toContain('value="rain, night"');
+    expect(body).toContain('From the author: Nature');
+    expect(body).toContain('Steam Workshop item 1234567890');
+    expect(body).toContain('Open in Steam');
+  });
+
+  it('shows the attached shader and LUT with a way to remove them', () => {
+    const { body } = render(LibraryDetailPanel, {
+      props: {
+        detail: {
+          id: 'video-1',
+          title: 'Rainy City',
+          originalTitle: 'Rainy City',
+          itemType: 'video',
+          coverPath: null,
+          source: 'local',
+          compatibility: {
+            badge: 'fully_supported',
+            reasonCode: 'ready_for_library',
+            summaryCopy: 'Ready to use',
+            headline: 'Ready to use',
+            detail: 'This item is synchronized locally and available for Library and desktop use.',
+            nextStep: 'none',
+            nextStepCopy: null
+          },
+          monitorsAvailable: true,
+          monitorDiscoveryIssue: null,
+          desktopAssignmentIssue: null,
+          desktopAssignmentsAvailable: true,
+          assignedMonitorLabels: [],
+          description: null,
+          tags: [],
+          userTags: [],
+          media: {},
+          favorite: false,
+          rating: 0,
+          properties: [],
+          shaderPath: '/home/user/shaders/crt.glsl',
+          lutPath: '/home/user/luts/teal.cube'
+        }
+      }
+    });
+
+    expect(body).toContain('data-detail-section="effects"');
+    expect(body).toContain('/home/user/shaders/crt.glsl');
+    expect(body).toContain('/home/user/luts/teal.cube');
+    expect(body).toContain('Choose…');
+    expect(body).toContain('Remove');
+  });
+
   it('localizes source and item type labels from centralized i18n copy', () => {
     setPreferredLanguage('zh-CN');
 
@@ -242,6 +506,7 @@ describe('LibraryDetailPanel', () => {
         detail: {
           id: 'scene-1',
           title: 'Forest Scene',
+          originalTitle: 'Forest Scene',
           itemType: 'scene',
           coverPath: null,
           source: 'workshop',
@@ -260,7 +525,12 @@ describe('LibraryDetailPanel', () => {
           desktopAssignmentsAvailable: true,
           assignedMonitorLabels: [],
           description: null,
-          tags: []
+          tags: [],
+          userTags: [],
+          media: {},
+          favorite: false,
+          rating: 0,
+          properties: []
         }
       }
     });
@@ -0,0 +1,54 @@
+import { afterEach, describe, expect, it } from 'vitest';
+import { render } from 'svelte/server';
+
+import { resetPreferredLanguage, setPreferredLanguage } from '$lib/i18n';
+import type { DesktopMonitorSummary } from '$lib/types';
+import MonitorDropStrip from './MonitorDropStrip.svelte';
+
+const monitor = (monitorId: string, displayName: string, currentWallpaperTitle: string | null) =>
+  ({
+    monitorId,
+    displayName,
+    resolution: '1920x1080',
+    currentWallpaperTitle,
+    currentCoverPath: null,
+    currentItemId: null,
+    clearSupported: true,
+    restoreState: null,
+    restoreIssue: null,
+    runtimeStatus: 'unsupported',
+    volume: 100,
+    muted: true,
+    color: { brightness: 100, contrast: 100, gamma: 100, saturation: 100 }
+  }) satisfies DesktopMonitorSummary;
+
+describe('MonitorDropStrip', () => {
+  afterEach(() => {
+    resetPreferredLanguage();
+  });
+
+  it('renders one drop target per monitor with its current wallpaper', () => {
+    const { body } = render(MonitorDropStrip, {
+      props: {
+        monitors: [monitor('DP-1', 'Primary', 'Forest Scene'), monitor('HDMI-A-1', 'Side', null)]
+      }
+    });
+
+    expect(body).toContain('data-monitor-drop-target="DP-1"');
+    expect(body).toContain('data-monitor-drop-target="HDMI-A-1"');
+    expect(body).toContain('Forest Scene');
+    expect(body).toContain('No wallpaper');
+    expect(body).toContain('Drag a card onto a monitor to show it there.');
+  });
+
+  it('tells how to drop or cancel while a card is dragged', () => {
+    setPreferredLanguage('zh-CN');
+
+    const { body } = render(MonitorDropStrip, {
+      props: { monitors: [monitor('DP-1', 'Primary', null)], dragging: true }
+    });
+
+    expect(body).toContain('松开以应用到该显示器，按 Esc 取消。');
+    expect(body).toContain('border-dashed');
+  });
+});
@@ -0,0 +1,43 @@
+import { afterEach, describe, expect, it } from 'vitest';
+import { render } from 'svelte/server';
+
+import { resetPreferredLanguage, setPreferredLanguage } from '$lib/i18n';
+import type { OnboardingSnapshot } from '$lib/types';
+import OnboardingWizard from './OnboardingWizard.svelte';
+
+const snapshot: OnboardingSnapshot = {
+  needed: true,
+  steamFound: false,
+  wallpaperEngineFound: false,
+  workshopItemCount: 0,
+  suggestedFolders: ['/home/u/Videos/Wallpapers'],
+  renderBackend: { ok: true, detail: 'Wayland layer shell v4, EGL 1.5 (Mesa Project)' },
+  hardwareDecoding: { ok: false, detail: 'No hardware decoder found; videos will decode on the CPU' },
+  renderDevices: [],
+  launchOnLoginAvailable: true
+};
+
+describe('OnboardingWizard', () => {
+  afterEach(() => {
+    resetPreferredLanguage();
+  });
+
+  it('opens on the language step with a way to skip', () => {
+    const { body } = render(OnboardingWizard, { props: { snapshot, open: true } });
+
+    expect(body).toContain('data-onboarding-wizard');
+    expect(body).toContain('Welcome to LWE');
+    expect(body).toContain('Step 1 of 5');
+    expect(body).toContain('Everything here can be changed later in Settings.');
+    expect(body).toContain('Skip setup');
+    expect(body).toContain('Next');
+  });
+
+  it('says it is still probing before the system check returns', () => {
+    setPreferredLanguage('zh-CN');
+
+    const { body } = render(OnboardingWizard, { props: { snapshot: null, open: true } });
+
+    expect(body).toContain('正在检查本机…');
+  });
+});
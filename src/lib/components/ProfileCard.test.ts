@@ -0,0 +1,32 @@
+import { afterEach, describe, expect, it } from 'vitest';
+import { render } from 'svelte/server';
+
+import { resetPreferredLanguage, setPreferredLanguage } from '$lib/i18n';
+import ProfileCard from './ProfileCard.svelte';
+
+const profiles = [
+  { name: 'evening', monitorIds: ['DP-1', 'eDP-1'] },
+  { name: 'work', monitorIds: ['DP-1'] }
+];
+
+describe('ProfileCard', () => {
+  afterEach(() => {
+    resetPreferredLanguage();
+  });
+
+  it('lists saved profiles with the monitors they cover', () => {
+    const { body } = render(ProfileCard, { props: { profiles } });
+
+    expect(body).toContain('evening');
+    expect(body).toContain('DP-1, eDP-1');
+    expect(body).toContain('Switch to work');
+    expect(body).toContain('Save current setup');
+  });
+
+  it('localizes the empty state', () => {
+    setPreferredLanguage('zh-CN');
+    const { body } = render(ProfileCard, { props: { profiles: [] } });
+
+    expect(body).toContain('还没有保存的壁纸方案');
+  });
+});
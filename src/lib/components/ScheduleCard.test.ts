@@ -0,0 +1,43 @@
+import { afterEach, describe, expect, it } from 'vitest';
+import { render } from 'svelte/server';
+
+import { resetPreferredLanguage, setPreferredLanguage } from '$lib/i18n';
+import ScheduleCard from './ScheduleCard.svelte';
+
+const status = {
+  slots: [
+    { name: 'day', start: 'sunrise', itemId: 'forest-101' },
+    { name: 'night', start: 'sunset-30', itemId: 'city-202' }
+  ],
+  activeSlot: 'night',
+  nextChange: 1_718_946_000,
+  pinned: true
+};
+
+describe('ScheduleCard', () => {
+  afterEach(() => {
+    resetPreferredLanguage();
+  });
+
+  it('shows the current slot and offers the others', () => {
+    const { body } = render(ScheduleCard, { props: { status } });
+
+    expect(body).toContain('Showing night');
+    expect(body).toContain('Pinned until');
+    expect(body).toContain('sunrise · forest-101');
+    expect(body).toContain('Show day now');
+    expect(body).not.toContain('Show night now');
+    expect(body).toContain('Follow schedule');
+  });
+
+  it('localizes the schedule copy', () => {
+    setPreferredLanguage('zh-CN');
+    const { body } = render(ScheduleCard, {
+      props: { status: { ...status, pinned: false, issue: 'Schedule slot night: bad start' } }
+    });
+
+    expect(body).toContain('当前时段：night');
+    expect(body).toContain('Schedule slot night: bad start');
+    expect(body).not.toContain('恢复按计划');
+  });
+});
@@ -0,0 +1,42 @@
+import { afterEach, describe, expect, it } from 'vitest';
+import { render } from 'svelte/server';
+
+import { resetPreferredLanguage, setPreferredLanguage } from '$lib/i18n';
+import WallpaperFailureNotice from './WallpaperFailureNotice.svelte';
+
+const failure = {
+  output: 'DP-1',
+  path: '/walls/broken.mkv',
+  reason: 'mpv cannot render it: unsupported codec',
+  failures: 3,
+  fallback: '#000000'
+};
+
+describe('WallpaperFailureNotice', () => {
+  afterEach(() => {
+    resetPreferredLanguage();
+  });
+
+  it('names the wallpaper, the monitor and what is shown instead', () => {
+    const { body } = render(WallpaperFailureNotice, { props: { failures: [failure] } });
+
+    expect(body).toContain('data-wallpaper-failures');
+    expect(body).toContain('Wallpaper stopped');
+    expect(body).toContain(
+      'broken.mkv failed 3 times in a row on DP-1 and is no longer retried. Showing #000000 until another wallpaper is applied.'
+    );
+    expect(body).toContain('mpv cannot render it: unsupported codec');
+  });
+
+  it('renders nothing without failures and translates the notice', () => {
+    expect(render(WallpaperFailureNotice, { props: { failures: [] } }).body).not.toContain(
+      'data-wallpaper-failures'
+    );
+
+    setPreferredLanguage('zh-CN');
+    const { body } = render(WallpaperFailureNotice, { props: { failures: [failure] } });
+
+    expect(body).toContain('壁纸已停止');
+    expect(body).toContain('broken.mkv 在 DP-1 上连续失败 3 次');
+  });
+});
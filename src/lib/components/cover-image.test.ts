@@ -1,6 +1,16 @@
-import { describe, expect, it } from 'vitest';
+import { afterEach, describe, expect, it, vi } from 'vitest';
 
-import { shouldRenderCoverImage } from './cover-image';
+const { convertFileSrc } = vi.hoisted(() => ({
+  convertFileSrc: vi.fn(
+    (filePath: string, protocol = 'asset') => `${protocol}://localhost/${encodeURIComponent(filePath)}`
+  )
+}));
+
+vi.mock('@tauri-apps/api/core', () => ({
+  convertFileSrc
+}));
+
+import { resolveCoverSrc, shouldRenderCoverImage } from './cover-image';
 
 describe('cover image fallback', () => {
   it('falls back to the placeholder after a load failure', () => {
@@ -13,3 +23,17 @@ describe('cover image fallback', () => {
     expect(shouldRenderCoverImage('', false)).toBe(false);
   });
 });
+
+describe('cover image source', () => {
+  afterEach(() => {
+    vi.unstubAllGlobals();
+  });
+
+  it('serves card covers from the thumbnail cache and detail covers at full size', () => {
+    vi.stubGlobal('window', {});
+
+    expect(resolveCoverSrc('/covers/item.gif', true)).toBe('lwe-thumb://localhost/%2Fcovers%2Fitem.gif');
+    expect(resolveCoverSrc('/covers/item.gif')).toBe('asset://localhost/%2Fcovers%2Fitem.gif');
+    expect(resolveCoverSrc('https://example.com/item.gif', true)).toBe('https://example.com/item.gif');
+  });
+});
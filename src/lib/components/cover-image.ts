@@ -3,7 +3,10 @@ import { convertFileSrc } from '@tauri-apps/api/core';
 export const shouldRenderCoverImage = (coverPath: string | null, loadFailed: boolean) =>
   Boolean(coverPath) && !loadFailed;
 
-export const resolveCoverSrc = (coverPath: string | null) => {
+// Served by the shell's downscaled thumbnail cache instead of the full-size file.
+export const THUMBNAIL_PROTOCOL = 'lwe-thumb';
+
+export const resolveCoverSrc = (coverPath: string | null, thumbnail = false) => {
   if (!coverPath) {
     return undefined;
   }
@@ -17,7 +20,7 @@ export const resolveCoverSrc = (coverPath: string | null) => {
       return undefined;
     }
 
-    return convertFileSrc(coverPath);
+    return thumbnail ? convertFileSrc(coverPath, THUMBNAIL_PROTOCOL) : convertFileSrc(coverPath);
   }
 
   return coverPath;
@@ -0,0 +1,13 @@
+import { describe, expect, it } from 'vitest';
+
+import { freedBytes } from './delete-items';
+
+describe('delete items', () => {
+  it('counts the cache as freed when local files are deleted', () => {
+    const estimate = { items: [], localFileBytes: 2048, workshopCacheBytes: 512 };
+
+    expect(freedBytes(estimate, 'remove_from_library')).toBe(0);
+    expect(freedBytes(estimate, 'delete_local_files')).toBe(2560);
+    expect(freedBytes(estimate, 'clear_workshop_cache')).toBe(512);
+  });
+});
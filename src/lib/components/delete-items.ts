@@ -0,0 +1,19 @@
+import type { LibraryDeleteEstimate, LibraryDeleteMode } from '$lib/types';
+
+export const DELETE_MODES: LibraryDeleteMode[] = [
+  'remove_from_library',
+  'delete_local_files',
+  'clear_workshop_cache'
+];
+
+/** Disk space a delete mode frees; removing from the library frees none. */
+export const freedBytes = (estimate: LibraryDeleteEstimate, mode: LibraryDeleteMode) => {
+  switch (mode) {
+    case 'delete_local_files':
+      return estimate.localFileBytes + estimate.workshopCacheBytes;
+    case 'clear_workshop_cache':
+      return estimate.workshopCacheBytes;
+    default:
+      return 0;
+  }
+};
@@ -0,0 +1,17 @@
+import { describe, expect, it } from 'vitest';
+
+import { formatTagInput, parseTagInput, resolveTitleEdit } from './library-detail';
+
+describe('library detail editing', () => {
+  it('splits tag input on commas and drops blank and repeated tags', () => {
+    expect(parseTagInput(' rain, night ,, rain，城市 ')).toEqual(['rain', 'night', '城市']);
+    expect(parseTagInput('   ')).toEqual([]);
+    expect(formatTagInput(['rain', 'night'])).toBe('rain, night');
+  });
+
+  it('clears the user title when it is blank or matches the scanned title', () => {
+    expect(resolveTitleEdit('  Misty Forest ', 'Forest Scene')).toBe('Misty Forest');
+    expect(resolveTitleEdit('Forest Scene', 'Forest Scene')).toBeNull();
+    expect(resolveTitleEdit('   ', 'Forest Scene')).toBeNull();
+  });
+});
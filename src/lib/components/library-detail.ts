@@ -0,0 +1,18 @@
+// Tags are edited as one comma-separated line; the backend drops blanks and repeats too.
+export const parseTagInput = (text: string) => {
+  const tags: string[] = [];
+  for (const tag of text.split(/[,，]/).map((part) => part.trim())) {
+    if (tag && !tags.includes(tag)) {
+      tags.push(tag);
+    }
+  }
+  return tags;
+};
+
+export const formatTagInput = (tags: string[]) => tags.join(', ');
+
+// A blank title or the scanned title itself clears the user's title.
+export const resolveTitleEdit = (draft: string, originalTitle: string) => {
+  const title = draft.trim();
+  return title && title !== originalTitle ? title : null;
+};
@@ -0,0 +1,41 @@
+import { describe, expect, it } from 'vitest';
+
+import {
+  carriesLibraryItem,
+  LIBRARY_ITEM_DRAG_TYPE,
+  readLibraryItemDrag,
+  startLibraryItemDrag
+} from './monitor-drop';
+
+const createDataTransfer = () => {
+  const data = new Map<string, string>();
+  return {
+    effectAllowed: 'all',
+    get types() {
+      return [...data.keys()];
+    },
+    setData: (type: string, value: string) => void data.set(type, value),
+    getData: (type: string) => data.get(type) ?? ''
+  } as unknown as DataTransfer;
+};
+
+describe('monitor drop', () => {
+  it('carries the dragged library item id under its own type', () => {
+    const dataTransfer = createDataTransfer();
+    startLibraryItemDrag(dataTransfer, 'video-1');
+
+    expect(dataTransfer.types).toEqual([LIBRARY_ITEM_DRAG_TYPE]);
+    expect(dataTransfer.effectAllowed).toBe('copy');
+    expect(carriesLibraryItem(dataTransfer)).toBe(true);
+    expect(readLibraryItemDrag(dataTransfer)).toBe('video-1');
+  });
+
+  it('ignores drags that did not start on a library card', () => {
+    const dataTransfer = createDataTransfer();
+    dataTransfer.setData('text/plain', 'rain.mp4');
+
+    expect(carriesLibraryItem(dataTransfer)).toBe(false);
+    expect(readLibraryItemDrag(dataTransfer)).toBeNull();
+    expect(carriesLibraryItem(null)).toBe(false);
+  });
+});
@@ -0,0 +1,17 @@
+// Library cards carry their item id under a private type, so files or text dragged in from
+// other apps never light up a monitor tile.
+export const LIBRARY_ITEM_DRAG_TYPE = 'application/x-lwe-library-item';
+
+export const startLibraryItemDrag = (dataTransfer: DataTransfer | null, itemId: string) => {
+  if (!dataTransfer) {
+    return;
+  }
+  dataTransfer.setData(LIBRARY_ITEM_DRAG_TYPE, itemId);
+  dataTransfer.effectAllowed = 'copy';
+};
+
+export const carriesLibraryItem = (dataTransfer: DataTransfer | null) =>
+  Array.from(dataTransfer?.types ?? []).includes(LIBRARY_ITEM_DRAG_TYPE);
+
+export const readLibraryItemDrag = (dataTransfer: DataTransfer | null) =>
+  dataTransfer?.getData(LIBRARY_ITEM_DRAG_TYPE) || null;
@@ -0,0 +1,27 @@
+import { afterEach, describe, expect, it, vi } from 'vitest';
+
+const { convertFileSrc } = vi.hoisted(() => ({
+  convertFileSrc: vi.fn(
+    (filePath: string, protocol = 'asset') => `${protocol}://localhost/${encodeURIComponent(filePath)}`
+  )
+}));
+
+vi.mock('@tauri-apps/api/core', () => ({
+  convertFileSrc
+}));
+
+import { resolveMonitorPreviewSrc } from './monitor-preview';
+
+describe('monitor preview', () => {
+  afterEach(() => {
+    vi.unstubAllGlobals();
+  });
+
+  it('requests each snapshot from the monitor protocol by monitor id', () => {
+    expect(resolveMonitorPreviewSrc('DP-1', 0)).toBeUndefined();
+
+    vi.stubGlobal('window', {});
+
+    expect(resolveMonitorPreviewSrc('HDMI-A-1', 2)).toBe('lwe-monitor://localhost/HDMI-A-1?frame=2');
+  });
+});
@@ -0,0 +1,15 @@
+import { convertFileSrc } from '@tauri-apps/api/core';
+
+// Frames are read back from the running wallpaper engine, so only monitors with a wallpaper have one.
+export const MONITOR_PREVIEW_PROTOCOL = 'lwe-monitor';
+
+// A hovered tile asks for a new snapshot this long after the previous one loaded.
+export const MONITOR_PREVIEW_INTERVAL_MS = 2000;
+
+export const resolveMonitorPreviewSrc = (monitorId: string, frame: number) => {
+  if (typeof window === 'undefined') {
+    return undefined;
+  }
+
+  return `${convertFileSrc(monitorId, MONITOR_PREVIEW_PROTOCOL)}?frame=${frame}`;
+};
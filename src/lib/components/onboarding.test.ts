@@ -0,0 +1,51 @@
+import { describe, expect, it } from 'vitest';
+
+import type { OnboardingSnapshot } from '$lib/types';
+import {
+  buildOnboardingInput,
+  initialOnboardingChoices,
+  offersRenderDeviceChoice,
+  toggleFolder
+} from './onboarding';
+
+const snapshot = (overrides: Partial<OnboardingSnapshot> = {}): OnboardingSnapshot => ({
+  needed: true,
+  steamFound: true,
+  wallpaperEngineFound: true,
+  workshopItemCount: 12,
+  suggestedFolders: ['/home/u/Videos/Wallpapers', '/home/u/Pictures/Wallpapers'],
+  renderBackend: { ok: true, detail: 'Wayland layer shell v4, EGL 1.5 (Mesa Project)' },
+  hardwareDecoding: { ok: true, detail: 'VAAPI on /dev/dri/renderD128 (Mesa Gallium driver)' },
+  renderDevices: [{ path: '/dev/dri/renderD128', detail: 'Mesa Gallium driver', hardwareDecoding: true }],
+  launchOnLoginAvailable: true,
+  ...overrides
+});
+
+describe('onboarding choices', () => {
+  it('starts with every suggested folder and automatic GPU choice', () => {
+    const choices = initialOnboardingChoices(snapshot(), 'system');
+
+    expect(choices.folders).toEqual(['/home/u/Videos/Wallpapers', '/home/u/Pictures/Wallpapers']);
+    expect(choices.renderDevice).toBeNull();
+    expect(choices.launchOnLogin).toBe(false);
+  });
+
+  it('toggles folders without duplicates', () => {
+    expect(toggleFolder(['/a'], '/a', true)).toEqual(['/a']);
+    expect(toggleFolder(['/a'], '/b', true)).toEqual(['/a', '/b']);
+    expect(toggleFolder(['/a', '/b'], '/a', false)).toEqual(['/b']);
+  });
+
+  it('only sends choices this system can honor', () => {
+    const single = snapshot({ launchOnLoginAvailable: false });
+    const input = buildOnboardingInput(single, {
+      language: 'zh-CN',
+      folders: ['/a'],
+      renderDevice: '/dev/dri/renderD128',
+      launchOnLogin: true
+    });
+
+    expect(offersRenderDeviceChoice(single)).toBe(false);
+    expect(input).toEqual({ language: 'zh-CN', folders: ['/a'], renderDevice: null, launchOnLogin: false });
+  });
+});
@@ -0,0 +1,40 @@
+import type { PreferredLanguage } from '$lib/i18n';
+import type { OnboardingFinishInput, OnboardingSnapshot } from '$lib/types';
+
+export const ONBOARDING_STEPS = ['welcome', 'workshop', 'folders', 'system', 'startup'] as const;
+
+export type OnboardingStep = (typeof ONBOARDING_STEPS)[number];
+
+export type OnboardingChoices = {
+  language: PreferredLanguage;
+  folders: string[];
+  renderDevice: string | null;
+  launchOnLogin: boolean;
+};
+
+// Every suggested folder starts ticked; the GPU stays on automatic choice.
+export const initialOnboardingChoices = (
+  snapshot: OnboardingSnapshot,
+  language: PreferredLanguage
+): OnboardingChoices => ({
+  language,
+  folders: [...snapshot.suggestedFolders],
+  renderDevice: null,
+  launchOnLogin: false
+});
+
+// Picking a GPU only matters when there is more than one render node.
+export const offersRenderDeviceChoice = (snapshot: OnboardingSnapshot) => snapshot.renderDevices.length > 1;
+
+export const toggleFolder = (folders: string[], folder: string, picked: boolean) =>
+  picked ? Array.from(new Set([...folders, folder])) : folders.filter((entry) => entry !== folder);
+
+export const buildOnboardingInput = (
+  snapshot: OnboardingSnapshot,
+  choices: OnboardingChoices
+): OnboardingFinishInput => ({
+  language: choices.language,
+  folders: choices.folders,
+  renderDevice: offersRenderDeviceChoice(snapshot) ? choices.renderDevice : null,
+  launchOnLogin: snapshot.launchOnLoginAvailable && choices.launchOnLogin
+});
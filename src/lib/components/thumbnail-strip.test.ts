@@ -0,0 +1,46 @@
+import { afterEach, describe, expect, it, vi } from 'vitest';
+
+const { convertFileSrc } = vi.hoisted(() => ({
+  convertFileSrc: vi.fn(
+    (filePath: string, protocol = 'asset') => `${protocol}://localhost/${encodeURIComponent(filePath)}`
+  )
+}));
+
+vi.mock('@tauri-apps/api/core', () => ({
+  convertFileSrc
+}));
+
+import { resolveStripSrc, stripFrameIndex, stripFrameStyle, supportsHoverScrub } from './thumbnail-strip';
+
+describe('thumbnail strip', () => {
+  afterEach(() => {
+    vi.unstubAllGlobals();
+  });
+
+  it('only scrubs videos', () => {
+    expect(supportsHoverScrub('video')).toBe(true);
+    expect(supportsHoverScrub('scene')).toBe(false);
+  });
+
+  it('requests the strip from the strip protocol by item id', () => {
+    expect(resolveStripSrc('video-7')).toBeUndefined();
+
+    vi.stubGlobal('window', {});
+
+    expect(resolveStripSrc('video-7')).toBe('lwe-strip://localhost/video-7');
+  });
+
+  it('maps the pointer position across the card to a frame', () => {
+    expect(stripFrameIndex(0)).toBe(0);
+    expect(stripFrameIndex(0.55)).toBe(5);
+    expect(stripFrameIndex(1)).toBe(9);
+    expect(stripFrameIndex(-0.2)).toBe(0);
+    expect(stripFrameIndex(1.4)).toBe(9);
+  });
+
+  it('positions the strip so only the chosen frame shows', () => {
+    expect(stripFrameStyle('lwe-strip://localhost/video-7', 0)).toContain('background-position: 0% 0;');
+    expect(stripFrameStyle('lwe-strip://localhost/video-7', 9)).toContain('background-position: 100% 0;');
+    expect(stripFrameStyle('lwe-strip://localhost/video-7', 3, 4)).toContain('background-size: 400% 100%;');
+  });
+});
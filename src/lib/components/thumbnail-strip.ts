@@ -0,0 +1,30 @@
+import { convertFileSrc } from '@tauri-apps/api/core';
+
+import type { ItemType } from '$lib/types';
+
+// Sprite strips are generated once by the shell and kept in the library database.
+export const STRIP_PROTOCOL = 'lwe-strip';
+
+// Must match `STRIP_FRAMES` in lwe-library.
+export const STRIP_FRAMES = 10;
+
+export const supportsHoverScrub = (itemType: ItemType) => itemType === 'video';
+
+export const resolveStripSrc = (itemId: string) => {
+  if (typeof window === 'undefined') {
+    return undefined;
+  }
+
+  return convertFileSrc(itemId, STRIP_PROTOCOL);
+};
+
+// Maps the pointer's horizontal position across the card to a strip frame.
+export const stripFrameIndex = (fraction: number, frames = STRIP_FRAMES) => {
+  const clamped = Math.min(Math.max(fraction, 0), 1);
+  return Math.min(Math.floor(clamped * frames), frames - 1);
+};
+
+export const stripFrameStyle = (src: string, index: number, frames = STRIP_FRAMES) => {
+  const offset = frames > 1 ? (index / (frames - 1)) * 100 : 0;
+  return `background-image: url("${src}"); background-size: ${frames * 100}% 100%; background-position: ${offset}% 0;`;
+};
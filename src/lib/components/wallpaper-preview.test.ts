@@ -0,0 +1,33 @@
+import { afterEach, describe, expect, it, vi } from 'vitest';
+
+const { convertFileSrc } = vi.hoisted(() => ({
+  convertFileSrc: vi.fn(
+    (filePath: string, protocol = 'asset') => `${protocol}://localhost/${encodeURIComponent(filePath)}`
+  )
+}));
+
+vi.mock('@tauri-apps/api/core', () => ({
+  convertFileSrc
+}));
+
+import { resolvePreviewSrc, supportsLivePreview } from './wallpaper-preview';
+
+describe('wallpaper preview', () => {
+  afterEach(() => {
+    vi.unstubAllGlobals();
+  });
+
+  it('only plays videos live', () => {
+    expect(supportsLivePreview('video')).toBe(true);
+    expect(supportsLivePreview('scene')).toBe(false);
+    expect(supportsLivePreview('web')).toBe(false);
+  });
+
+  it('requests each frame from the preview protocol by item id', () => {
+    expect(resolvePreviewSrc('video-7', 0)).toBeUndefined();
+
+    vi.stubGlobal('window', {});
+
+    expect(resolvePreviewSrc('video-7', 3)).toBe('lwe-preview://localhost/video-7?frame=3');
+  });
+});
@@ -0,0 +1,19 @@
+import { convertFileSrc } from '@tauri-apps/api/core';
+
+import type { ItemType } from '$lib/types';
+
+// Frames are rendered by the shell's shared preview player, without the engine running.
+export const PREVIEW_PROTOCOL = 'lwe-preview';
+
+// The next frame is requested this long after the previous one finished loading.
+export const PREVIEW_FRAME_INTERVAL_MS = 80;
+
+export const supportsLivePreview = (itemType: ItemType) => itemType === 'video';
+
+export const resolvePreviewSrc = (itemId: string, frame: number) => {
+  if (typeof window === 'undefined') {
+    return undefined;
+  }
+
+  return `${convertFileSrc(itemId, PREVIEW_PROTOCOL)}?frame=${frame}`;
+};
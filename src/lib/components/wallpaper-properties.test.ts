@@ -0,0 +1,16 @@
+import { describe, expect, it } from 'vitest';
+
+import { hexToPropertyColor, propertyColorToHex } from './wallpaper-properties';
+
+describe('wallpaper property colors', () => {
+  it('converts Wallpaper Engine colors to color input values', () => {
+    expect(propertyColorToHex('1 0.5 0')).toBe('#ff8000');
+    expect(propertyColorToHex('0 0 1')).toBe('#0000ff');
+    expect(propertyColorToHex('not a color')).toBe('#000000');
+  });
+
+  it('converts color input values back to Wallpaper Engine colors', () => {
+    expect(hexToPropertyColor('#ff8000')).toBe('1 0.502 0');
+    expect(hexToPropertyColor('#0000ff')).toBe('0 0 1');
+  });
+});
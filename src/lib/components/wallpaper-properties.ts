@@ -0,0 +1,22 @@
+// Wallpaper Engine colors are "r g b" with channels from 0 to 1; color inputs want #rrggbb.
+export const propertyColorToHex = (color: string) => {
+  const channels = color.trim().split(/\s+/).map(Number);
+  if (channels.length !== 3 || channels.some((channel) => Number.isNaN(channel))) {
+    return '#000000';
+  }
+
+  return `#${channels
+    .map((channel) =>
+      Math.round(Math.min(Math.max(channel, 0), 1) * 255)
+        .toString(16)
+        .padStart(2, '0')
+    )
+    .join('')}`;
+};
+
+export const hexToPropertyColor = (hex: string) => {
+  const digits = hex.replace(/^#/, '');
+  return [0, 2, 4]
+    .map((offset) => Number(((parseInt(digits.slice(offset, offset + 2), 16) || 0) / 255).toFixed(3)))
+    .join(' ');
+};
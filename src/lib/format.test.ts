@@ -0,0 +1,38 @@
+import { describe, expect, it } from 'vitest';
+
+import { formatCopy } from '$lib/i18n';
+
+import { formatBytes, formatClockTime, formatDuration, formatFrameRate, formatNumber } from './format';
+
+describe('format', () => {
+  it('groups digits for the locale', () => {
+    expect(formatNumber(1234567, 'en')).toBe('1,234,567');
+    expect(formatNumber(1234567, 'zh-CN')).toBe('1,234,567');
+  });
+
+  it('formats sizes in binary units', () => {
+    expect(formatBytes(512, 'en')).toBe('512 B');
+    expect(formatBytes(1536, 'en')).toBe('1.5 KiB');
+    expect(formatBytes(3 * 1024 * 1024 * 1024, 'zh-CN')).toBe('3.0 GiB');
+  });
+
+  it('spells durations with localized units', () => {
+    expect(formatDuration(3725, 'en')).toBe('1h 2m 5s');
+    expect(formatDuration(90, 'zh-CN')).toBe('1分钟30秒');
+    expect(formatDuration(0, 'en')).toBe('0s');
+  });
+
+  it('rounds frame rates to two decimals', () => {
+    expect(formatFrameRate(30000 / 1001, 'en')).toBe('29.97 fps');
+    expect(formatFrameRate(24, 'zh-CN')).toBe('24 fps');
+  });
+
+  it('formats clock times without a date', () => {
+    expect(formatClockTime(1_718_946_000, 'en')).toMatch(/^\d{1,2}:\d{2}( [AP]M)?$/);
+  });
+
+  it('formats numbers filled into copy', () => {
+    expect(formatCopy('{count} items', { count: 12000 })).toBe('12,000 items');
+    expect(formatCopy('Open {itemId}', { itemId: '12000' })).toBe('Open 12000');
+  });
+});
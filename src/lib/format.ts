@@ -0,0 +1,54 @@
+import type { SupportedLocale } from '$lib/i18n';
+
+const SIZE_UNITS = ['KiB', 'MiB', 'GiB', 'TiB'];
+
+/** `1234567` -> `1,234,567` */
+export const formatNumber = (value: number, locale: SupportedLocale) =>
+  new Intl.NumberFormat(locale).format(value);
+
+/** `52428800` -> `50.0 MiB`, in binary units like the `lwe` command line */
+export const formatBytes = (bytes: number, locale: SupportedLocale) => {
+  if (bytes < 1024) {
+    return `${formatNumber(bytes, locale)} B`;
+  }
+
+  let size = bytes / 1024;
+  let unit = 0;
+  while (size >= 1024 && unit < SIZE_UNITS.length - 1) {
+    size /= 1024;
+    unit += 1;
+  }
+
+  const number = new Intl.NumberFormat(locale, {
+    minimumFractionDigits: 1,
+    maximumFractionDigits: 1
+  }).format(size);
+  return `${number} ${SIZE_UNITS[unit]}`;
+};
+
+/** `3725` -> `1h 2m 5s` or `1小时2分钟5秒` */
+export const formatDuration = (seconds: number, locale: SupportedLocale) => {
+  const total = Math.max(0, Math.round(seconds));
+  const parts = [
+    ['hour', Math.floor(total / 3600)],
+    ['minute', Math.floor((total % 3600) / 60)],
+    ['second', total % 60]
+  ] as const;
+  const shown = parts.filter(([, value]) => value > 0);
+
+  return new Intl.ListFormat(locale, { type: 'unit', style: 'narrow' }).format(
+    (shown.length ? shown : [parts[2]]).map(([unit, value]) =>
+      new Intl.NumberFormat(locale, { style: 'unit', unit, unitDisplay: 'narrow' }).format(value)
+    )
+  );
+};
+
+/** `29.97002997` -> `29.97 fps`; whole rates lose the decimals */
+export const formatFrameRate = (fps: number, locale: SupportedLocale) =>
+  `${new Intl.NumberFormat(locale, { maximumFractionDigits: 2 }).format(fps)} fps`;
+
+/** Unix seconds -> local `19:42` or `7:42 PM` */
+export const formatClockTime = (unixSeconds: number, locale: SupportedLocale) =>
+  new Intl.DateTimeFormat(locale, { hour: 'numeric', minute: '2-digit' }).format(
+    new Date(unixSeconds * 1000)
+  );
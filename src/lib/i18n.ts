@@ -1,5 +1,7 @@
 import { derived, writable } from 'svelte/store';
 
+import { formatNumber } from '$lib/format';
+
 import type {
   CompatibilityBadge,
   DesktopRestoreState,
@@ -25,6 +27,22 @@ const dictionaries = {
     library: {
       pageTitle: 'Library',
       selectItemLabel: 'Select Library item {itemTitle}',
+      markedCount: '{count} marked',
+      deleteMarked: 'Delete…',
+      clearMarked: 'Clear',
+      batch: {
+        toolbarLabel: 'Marked item actions',
+        tagInput: 'Tag for marked items',
+        tagPlaceholder: 'Tag',
+        addTag: 'Add tag',
+        addToCollection: 'Add to collection…',
+        noCollections: 'No collections yet',
+        regenerateThumbnails: 'Regenerate thumbnails',
+        exportPaths: 'Export paths…'
+      },
+      addFolder: 'Add folder…',
+      addFiles: 'Add files…',
+      thumbnailsPending: 'Preparing {count} previews…',
       navLabel: 'Library',
       navShortLabel: 'Browse',
       navDescription: 'Review local content and current app state.',
@@ -35,7 +53,21 @@ const dictionaries = {
       empty: 'No Library items are available in the current snapshot.',
       monitorDiscoveryUnavailable: 'Monitor discovery is currently unavailable.',
       desktopAssignmentsUnavailable: 'Desktop assignments are currently unavailable.',
-      desktopAssignmentDataUnavailable: 'Desktop assignment data is currently unavailable.'
+      desktopAssignmentDataUnavailable: 'Desktop assignment data is currently unavailable.',
+      view: 'Show',
+      views: {
+        all: 'All items',
+        favorites: 'Favorites',
+        recent: 'Recently used'
+      },
+      sort: 'Sort by',
+      sorts: {
+        name: 'Name',
+        last_used: 'Last used',
+        rating: 'Rating'
+      },
+      emptyFavorites: 'No favorites yet. Mark items as favorites from their detail panel.',
+      emptyRecent: 'No item has been applied yet.'
     },
     workshop: {
       pageTitle: 'Workshop',
@@ -106,7 +138,159 @@ const dictionaries = {
       activeOutputs: 'Active outputs',
       missingMonitorRestores: 'Missing monitor restores',
       runtimeDeferred:
-        'The runtime control surface stays deferred until a later task exposes real commands.'
+        'The runtime control surface stays deferred until a later task exposes real commands.',
+      schedule: {
+        eyebrow: 'Time of day',
+        current: 'Showing {slot}',
+        noCurrent: 'No schedule slot is active',
+        until: 'Until {time}',
+        pinnedUntil: 'Pinned until {time}, then the schedule takes over',
+        slotDetail: '{start} · {itemId}',
+        showNow: 'Show now',
+        showNowAriaLabel: 'Show {slot} now',
+        follow: 'Follow schedule'
+      },
+      profiles: {
+        eyebrow: 'Profiles',
+        title: 'Saved setups',
+        empty: 'No profiles yet. Save the current wallpapers to switch back to them later.',
+        apply: 'Switch',
+        applyAriaLabel: 'Switch to {profile}',
+        namePlaceholder: 'Profile name',
+        save: 'Save current setup'
+      }
+    },
+    playlists: {
+      pageTitle: 'Playlists',
+      navLabel: 'Playlists',
+      navShortLabel: 'Rotate',
+      navDescription: 'Build wallpaper rotations and assign them to monitors.',
+      headerTitle: 'Playlist editor',
+      headerSubtitle: 'Order Library items, set how long each one stays, and pick the monitors that rotate through them.',
+      loading: 'Loading playlists…',
+      requestError: 'Unable to complete the playlist request.',
+      savedPlaylists: 'Saved playlists',
+      newPlaylist: 'New playlist',
+      empty: 'No playlists yet. Create one to start rotating wallpapers.',
+      untitled: 'Untitled playlist',
+      itemCount: '{count} items',
+      name: 'Name',
+      shuffle: 'Shuffle order each cycle',
+      transition: 'Transition',
+      transitionOptions: {
+        none: 'Cut',
+        fade: 'Fade'
+      },
+      defaultDuration: 'Default duration (seconds)',
+      items: 'Items',
+      itemsEmpty: 'Add Library items below, then drag them into the order you want.',
+      playFolder: 'Play a folder instead',
+      folderPath: 'Folder',
+      folderPathPlaceholder: '/home/you/Videos/Wallpapers',
+      folderPattern: 'File names',
+      folderPatternPlaceholder: 'All files (e.g. *.mp4 *.png)',
+      imageDuration: 'Image duration (seconds)',
+      folderHint: 'Plays the videos and images directly in this folder by file name, picking up files as they are added. The folder joins the Library when saved.',
+      folderRequired: 'Pick the folder this playlist plays.',
+      addItem: 'Add item',
+      addItemAriaLabel: 'Library item to add',
+      selectItem: 'Select a Library item',
+      libraryUnavailable: 'Library items are currently unavailable.',
+      missingItem: 'Missing item ({itemId})',
+      dragHandleAriaLabel: 'Drag {title} to reorder',
+      durationAriaLabel: 'Duration in seconds for {title}',
+      durationPlaceholder: 'Default',
+      moveUp: 'Move up',
+      moveDown: 'Move down',
+      remove: 'Remove',
+      outputs: 'Monitors',
+      outputsUnavailable: 'Monitor discovery is currently unavailable.',
+      save: 'Save playlist',
+      saving: 'Saving…',
+      delete: 'Delete',
+      nameRequired: 'Give the playlist a name before saving.',
+      durationTooShort: 'Durations must be at least {seconds} seconds.',
+      nowRotating: 'Now rotating',
+      rotationShowing: 'Showing {title}',
+      rotationPaused: 'Paused',
+      previousItem: 'Previous',
+      nextItem: 'Next',
+      pauseRotation: 'Pause',
+      resumeRotation: 'Resume',
+      collections: {
+        eyebrow: 'Library groups',
+        title: 'Collections',
+        empty: 'No collections yet. Name one below to group Library items.',
+        namePlaceholder: 'New collection name',
+        create: 'Create collection',
+        nameAriaLabel: 'Name of {name}',
+        rename: 'Rename',
+        itemsEmpty: 'This collection has no items yet.',
+        removeItemAriaLabel: 'Remove {title} from {name}',
+        addItemAriaLabel: 'Library item to add to {name}',
+        addItem: 'Add',
+        targetAriaLabel: 'Monitors to rotate {name} on',
+        allMonitors: 'All monitors',
+        apply: 'Rotate as playlist',
+        applyHint: 'Rotating a collection saves it as the playlist of the same name.',
+        delete: 'Delete collection'
+      }
+    },
+    diagnostics: {
+      pageTitle: 'Diagnostics',
+      navLabel: 'Diagnostics',
+      navShortLabel: 'Debug',
+      navDescription: 'Inspect engine status, capabilities, and live logs.',
+      headerTitle: 'Diagnostics',
+      headerSubtitle: 'Check what the engine sees on this system and copy a report to attach to bug reports.',
+      loading: 'Loading diagnostics…',
+      requestError: 'Unable to load diagnostics.',
+      refresh: 'Refresh',
+      copyReport: 'Copy report',
+      reportCopied: 'Report copied to the clipboard.',
+      copyFailed: 'Unable to copy the report to the clipboard.',
+      appVersion: 'App version {version}',
+      engine: 'Engine',
+      engineStatus: {
+        running: 'Running',
+        not_started: 'Not started',
+        unavailable: 'Unavailable'
+      },
+      engineNotStarted: 'The engine starts the first time a wallpaper is applied.',
+      noOutputs: 'The engine has not reported any outputs.',
+      noWallpaper: 'No wallpaper',
+      renderBackend: 'Drawing with {backend}',
+      power: {
+        ac: 'On AC power',
+        battery: 'On battery',
+        batteryPaused: 'On battery, wallpapers paused to save power'
+      },
+      softwareDecoding: 'Software decoding',
+      hardwareDecoding: '{hwdec} decoding',
+      frameRate: '{fps} fps',
+      droppedFrames: '{percent}% dropped',
+      gpuSeverity: {
+        error: 'GPU error',
+        warning: 'GPU warning'
+      },
+      gpuRepeated: '{message} (×{count})',
+      capabilities: 'Capabilities',
+      capabilityLabels: {
+        wayland_session: 'Wayland session',
+        monitor_discovery: 'Monitor discovery',
+        steam: 'Steam',
+        wallpaper_engine: 'Wallpaper Engine',
+        compositor: 'Compositor',
+        library_database: 'Library database',
+        resource_limits: 'Resource limits',
+        gpu_debug: 'GPU debug output',
+        event_channel: 'Engine event channel'
+      },
+      available: 'Available',
+      missing: 'Missing',
+      logs: 'Live log',
+      logsEmpty: 'No log lines captured yet.',
+      followLogs: 'Follow new lines'
     },
     settings: {
       navLabel: 'Settings',
@@ -132,6 +316,9 @@ const dictionaries = {
       launchOnLoginUnavailable: 'Launch-on-login is currently unavailable on this machine.',
       launchPreferencePrefix: 'Saved preference:',
       startOnSession: 'Start LWE automatically when the graphical desktop session begins.',
+      confirmApplyToAll: 'Confirm apply to all monitors',
+      confirmApplyToAllDescription: 'Ask before replacing the wallpaper on every monitor at once.',
+      confirmApplyToAllSaved: 'Confirm apply to all monitors:',
       saveChanges: 'Save changes',
       saving: 'Saving…',
       cancel: 'Cancel',
@@ -139,6 +326,12 @@ const dictionaries = {
       unsavedChanges: 'Unsaved changes',
       steamIntegration: 'Steam integration',
       currentState: 'Current state',
+      thumbnailCache: 'Thumbnail cache',
+      thumbnailCacheUsage: '{used} of {budget} used',
+      thumbnailCacheDescription:
+        'Covers and scrub strips kept so the Library opens fast. Thumbnails of deleted wallpapers and the least recently shown ones over the budget are removed at startup; change the budget with thumbnail_cache_mb under [library] in settings.toml.',
+      clearThumbnailCache: 'Clear thumbnail cache',
+      clearingThumbnailCache: 'Clearing…',
       steamRequired: 'Steam is required',
       steamOptional: 'Steam is optional',
       savedLanguage: 'Saved language:',
@@ -149,6 +342,11 @@ const dictionaries = {
       disabled: 'disabled',
       preferEnabled: 'Prefer enabled when available',
       preferDisabled: 'Prefer disabled when available',
+      configIssuesTitle: 'Problems in settings.toml',
+      configIssuesHint: 'LWE ignores these entries until they are fixed. Run `lwe config validate` to check the file from a terminal.',
+      configIssueLine: 'Line {line}',
+      configIssueError: 'Error',
+      configIssueWarning: 'Warning',
       languageOptions: {
         en: 'English',
         'zh-CN': 'Simplified Chinese',
@@ -192,7 +390,10 @@ const dictionaries = {
         title: 'Quick actions',
         applyFromDetails: 'Apply from details',
         applyFromDetailsDescription:
-          'Selects this item so you can choose a monitor and apply it from the detail panel.'
+          'Selects this item so you can choose a monitor and apply it from the detail panel.',
+        revealInFolder: 'Show in folder',
+        playExternally: 'Play in external player',
+        deleteItem: 'Delete…'
       },
       compatibilityPanel: {
         ariaLabel: 'Compatibility explanation',
@@ -216,6 +417,49 @@ const dictionaries = {
         applyTargetMonitor: 'Apply target monitor',
         selectMonitor: 'Select a monitor',
         noMonitorsAvailable: 'No monitors available',
+        allMonitors: 'All monitors',
+        confirmApplyAll: 'Replace the wallpaper on all {count} monitors?',
+        confirmApplyAllAction: 'Apply to all',
+        cancelApply: 'Cancel',
+        appliedToAll: 'Applied to all monitors.',
+        undoApply: 'Undo ({duration})',
+        favorite: 'Favorite',
+        unfavorite: 'Remove from favorites',
+        rating: 'Rating',
+        rateItem: 'Rate {rating} of 5',
+        clearRating: 'Clear rating',
+        rename: 'Rename',
+        titleInput: 'Title',
+        saveTitle: 'Save',
+        cancelRename: 'Cancel',
+        restoreTitle: 'Use original title',
+        originalTitle: 'Originally “{title}”',
+        media: 'Media',
+        resolution: 'Resolution',
+        duration: 'Duration',
+        codec: 'Codec',
+        frameRate: 'Frame rate',
+        hdr: 'HDR',
+        hdrFormats: { pq: 'HDR10 (PQ)', hlg: 'HLG' },
+        fileSize: 'File size',
+        unknownMedia: 'Unknown',
+        userTags: 'Your tags',
+        userTagsPlaceholder: 'Comma-separated, e.g. rain, night',
+        authorTags: 'From the author: {tags}',
+        workshopOrigin: 'Steam Workshop item {id}',
+        openInSteam: 'Open in Steam',
+        properties: 'Properties',
+        propertiesDescription: 'Settings the author exposes for this wallpaper. Changes reach a running web wallpaper right away.',
+        propertiesSceneNote: 'Scenes are not rendered yet; these values are kept for when they are.',
+        resetProperties: 'Reset to defaults',
+        effects: 'Effects',
+        effectsDescription: 'Post-processing applied the next time this wallpaper is shown.',
+        shader: 'Shader',
+        noShader: 'None',
+        chooseEffect: 'Choose…',
+        removeEffect: 'Remove',
+        lut: 'Color LUT',
+        noLut: 'Global LUT, if any',
         applying: 'Applying…',
         cover: 'Cover',
         coverDescription: 'Compact artwork preview for quick confirmation without dominating the panel.',
@@ -271,6 +515,27 @@ const dictionaries = {
         clearWallpaperAriaLabel: 'Clear wallpaper from {displayName}',
         clear: 'Clear',
         clearing: 'Clearing…',
+        volume: 'Volume',
+        volumeAriaLabel: 'Volume for {displayName}',
+        volumeValue: '{volume}%',
+        muted: 'Muted',
+        mute: 'Mute',
+        unmute: 'Unmute',
+        muteAriaLabel: 'Mute {displayName}',
+        unmuteAriaLabel: 'Unmute {displayName}',
+        color: 'Color',
+        colorLabels: {
+          brightness: 'Brightness',
+          contrast: 'Contrast',
+          gamma: 'Gamma',
+          saturation: 'Saturation'
+        },
+        colorValue: '{value}%',
+        colorAriaLabel: '{property} for {displayName}',
+        resetColor: 'Reset',
+        resetColorAriaLabel: 'Reset colors of {displayName}',
+        appliedAgo: 'Applied {ago} ago',
+        appliedAgoWithLatency: 'Applied {ago} ago · took {latency} ms',
         restoreState: 'Restore state',
         viewStatusDetails: 'View status details',
         hideStatusDetails: 'Hide status details',
@@ -280,7 +545,74 @@ const dictionaries = {
         expandStatusHint: 'Expand this section to review the latest restore status for this display.'
       },
       itemCard: {
-        assignedTo: 'Assigned to'
+        assignedTo: 'Assigned to',
+        markLabel: 'Mark {itemTitle} for batch actions'
+      },
+      monitorDropStrip: {
+        ariaLabel: 'Monitors',
+        dragHint: 'Drag a card onto a monitor to show it there.',
+        dropHint: 'Drop on a monitor to apply, or press Escape to cancel.',
+        tileAriaLabel: 'Apply to {monitor}',
+        noWallpaper: 'No wallpaper',
+        previewAlt: 'What {monitor} shows now'
+      },
+      onboardingWizard: {
+        title: 'Welcome to LWE',
+        stepLabel: 'Step {current} of {total}',
+        steps: {
+          welcome: 'Language',
+          workshop: 'Steam Workshop',
+          folders: 'Wallpaper folders',
+          system: 'System check',
+          startup: 'Startup'
+        },
+        welcome: 'Set up LWE in a few steps. Everything here can be changed later in Settings.',
+        probing: 'Checking this system…',
+        workshopFound: 'Wallpaper Engine was found with {count} Workshop items. They show up in the Library on their own.',
+        steamWithoutWorkshop: 'Steam was found, but Wallpaper Engine is not installed. Local folders still work.',
+        steamMissing: 'Steam was not found. Workshop wallpapers need Steam and Wallpaper Engine; local folders work without them.',
+        foldersHint: 'These folders look like they hold wallpapers. Ticked folders are scanned into the Library.',
+        noFolders: 'No wallpaper folders were found. Add folders later from the Library page.',
+        renderBackend: 'Rendering',
+        hardwareDecoding: 'Hardware decoding',
+        renderDevice: 'GPU for wallpapers',
+        renderDeviceAuto: 'Choose automatically',
+        launchOnLogin: 'Start LWE when I log in',
+        launchOnLoginUnavailable: 'Starting on login is not available on this machine.',
+        back: 'Back',
+        next: 'Next',
+        skip: 'Skip setup',
+        finish: 'Finish',
+        finishing: 'Saving…'
+      },
+      wallpaperFailureNotice: {
+        title: 'Wallpaper stopped',
+        message: '{file} failed {failures} times in a row on {output} and is no longer retried. Showing {fallback} until another wallpaper is applied.',
+        dismiss: 'Dismiss'
+      },
+      deleteItemsDialog: {
+        title: 'Delete wallpapers',
+        description: 'Choose what happens to {count} selected item(s).',
+        estimating: 'Measuring disk usage…',
+        cancel: 'Cancel',
+        deleting: 'Deleting…',
+        modes: {
+          remove_from_library: 'Remove from library',
+          delete_local_files: 'Delete local files',
+          clear_workshop_cache: 'Clear Workshop cache copy'
+        },
+        modeDescriptions: {
+          remove_from_library: 'Hides the items in LWE. Files stay on disk.',
+          delete_local_files:
+            'Deletes the downloaded Workshop folders. Steam downloads them again while you stay subscribed.',
+          clear_workshop_cache:
+            "Deletes Steam's leftover download copies and LWE thumbnails. The wallpapers keep working."
+        },
+        confirm: {
+          remove_from_library: 'Remove',
+          delete_local_files: 'Delete files',
+          clear_workshop_cache: 'Clear cache'
+        }
       }
     }
   },
@@ -294,6 +626,22 @@ const dictionaries = {
     library: {
       pageTitle: '内容库',
       selectItemLabel: '选择内容项 {itemTitle}',
+      markedCount: '已标记 {count} 项',
+      deleteMarked: '删除…',
+      clearMarked: '清除',
+      batch: {
+        toolbarLabel: '已标记项操作',
+        tagInput: '为已标记项添加的标签',
+        tagPlaceholder: '标签',
+        addTag: '添加标签',
+        addToCollection: '加入合集…',
+        noCollections: '尚无合集',
+        regenerateThumbnails: '重新生成缩略图',
+        exportPaths: '导出路径…'
+      },
+      addFolder: '添加文件夹…',
+      addFiles: '添加文件…',
+      thumbnailsPending: '正在生成 {count} 张预览图…',
       navLabel: '内容库',
       navShortLabel: '浏览',
       navDescription: '查看本地内容与当前应用状态。',
@@ -304,7 +652,21 @@ const dictionaries = {
       empty: '当前快照中没有可用的内容项。',
       monitorDiscoveryUnavailable: '当前无法发现显示器。',
       desktopAssignmentsUnavailable: '当前无法获取桌面分配。',
-      desktopAssignmentDataUnavailable: '当前无法获取桌面分配数据。'
+      desktopAssignmentDataUnavailable: '当前无法获取桌面分配数据。',
+      view: '显示',
+      views: {
+        all: '全部内容',
+        favorites: '收藏',
+        recent: '最近使用'
+      },
+      sort: '排序',
+      sorts: {
+        name: '名称',
+        last_used: '最近使用',
+        rating: '评分'
+      },
+      emptyFavorites: '还没有收藏。可以在内容详情中收藏内容项。',
+      emptyRecent: '还没有应用过任何内容项。'
     },
     workshop: {
       pageTitle: '创意工坊',
@@ -372,7 +734,159 @@ const dictionaries = {
       snapshotUnavailable: '当前快照中暂时无法获取桌面显示器数据。',
       activeOutputs: '活动输出',
       missingMonitorRestores: '缺失显示器恢复项',
-      runtimeDeferred: '运行时控制面板仍会延后到后续任务开放真实命令。'
+      runtimeDeferred: '运行时控制面板仍会延后到后续任务开放真实命令。',
+      schedule: {
+        eyebrow: '按时段切换',
+        current: '当前时段：{slot}',
+        noCurrent: '没有生效的时段',
+        until: '持续到 {time}',
+        pinnedUntil: '固定到 {time}，之后恢复按计划切换',
+        slotDetail: '{start} · {itemId}',
+        showNow: '立即显示',
+        showNowAriaLabel: '立即显示 {slot}',
+        follow: '恢复按计划'
+      },
+      profiles: {
+        eyebrow: '壁纸方案',
+        title: '已保存的方案',
+        empty: '还没有保存的壁纸方案。保存当前壁纸后可以随时切换回来。',
+        apply: '切换',
+        applyAriaLabel: '切换到 {profile}',
+        namePlaceholder: '方案名称',
+        save: '保存当前设置'
+      }
+    },
+    playlists: {
+      pageTitle: '播放列表',
+      navLabel: '播放列表',
+      navShortLabel: '轮播',
+      navDescription: '编排壁纸轮播并分配到显示器。',
+      headerTitle: '播放列表编辑器',
+      headerSubtitle: '排列内容库项目，设置每项的显示时长，并选择要轮播的显示器。',
+      loading: '正在加载播放列表…',
+      requestError: '无法完成播放列表请求。',
+      savedPlaylists: '已保存的播放列表',
+      newPlaylist: '新建播放列表',
+      empty: '还没有播放列表。创建一个即可开始轮播壁纸。',
+      untitled: '未命名播放列表',
+      itemCount: '{count} 项',
+      name: '名称',
+      shuffle: '每轮随机顺序',
+      transition: '切换效果',
+      transitionOptions: {
+        none: '直接切换',
+        fade: '淡入淡出'
+      },
+      defaultDuration: '默认时长（秒）',
+      items: '项目',
+      itemsEmpty: '在下方添加内容库项目，然后拖动调整顺序。',
+      playFolder: '改为播放一个文件夹',
+      folderPath: '文件夹',
+      folderPathPlaceholder: '/home/you/Videos/Wallpapers',
+      folderPattern: '文件名',
+      folderPatternPlaceholder: '全部文件（例如 *.mp4 *.png）',
+      imageDuration: '图片时长（秒）',
+      folderHint: '按文件名顺序播放此文件夹中直接包含的视频和图片，新加入的文件会自动纳入。保存后该文件夹会加入内容库。',
+      folderRequired: '请指定此播放列表要播放的文件夹。',
+      addItem: '添加项目',
+      addItemAriaLabel: '要添加的内容库项目',
+      selectItem: '选择内容库项目',
+      libraryUnavailable: '当前无法获取内容库项目。',
+      missingItem: '缺失项目（{itemId}）',
+      dragHandleAriaLabel: '拖动 {title} 以调整顺序',
+      durationAriaLabel: '{title} 的显示时长（秒）',
+      durationPlaceholder: '默认',
+      moveUp: '上移',
+      moveDown: '下移',
+      remove: '移除',
+      outputs: '显示器',
+      outputsUnavailable: '当前无法发现显示器。',
+      save: '保存播放列表',
+      saving: '正在保存…',
+      delete: '删除',
+      nameRequired: '保存前请为播放列表命名。',
+      durationTooShort: '时长至少为 {seconds} 秒。',
+      nowRotating: '正在轮播',
+      rotationShowing: '正在显示 {title}',
+      rotationPaused: '已暂停',
+      previousItem: '上一项',
+      nextItem: '下一项',
+      pauseRotation: '暂停',
+      resumeRotation: '继续',
+      collections: {
+        eyebrow: '内容库分组',
+        title: '合集',
+        empty: '还没有合集。在下方输入名称即可将内容库项目分组。',
+        namePlaceholder: '新合集名称',
+        create: '创建合集',
+        nameAriaLabel: '{name} 的名称',
+        rename: '重命名',
+        itemsEmpty: '这个合集还没有项目。',
+        removeItemAriaLabel: '从 {name} 中移除 {title}',
+        addItemAriaLabel: '要添加到 {name} 的内容库项目',
+        addItem: '添加',
+        targetAriaLabel: '轮播 {name} 的显示器',
+        allMonitors: '所有显示器',
+        apply: '作为播放列表轮播',
+        applyHint: '轮播合集时会将其保存为同名播放列表。',
+        delete: '删除合集'
+      }
+    },
+    diagnostics: {
+      pageTitle: '诊断',
+      navLabel: '诊断',
+      navShortLabel: '调试',
+      navDescription: '查看引擎状态、功能支持与实时日志。',
+      headerTitle: '诊断',
+      headerSubtitle: '检查引擎在此系统上的运行情况，并复制报告附加到问题反馈中。',
+      loading: '正在加载诊断信息…',
+      requestError: '无法加载诊断信息。',
+      refresh: '刷新',
+      copyReport: '复制报告',
+      reportCopied: '报告已复制到剪贴板。',
+      copyFailed: '无法将报告复制到剪贴板。',
+      appVersion: '应用版本 {version}',
+      engine: '引擎',
+      engineStatus: {
+        running: '运行中',
+        not_started: '未启动',
+        unavailable: '不可用'
+      },
+      engineNotStarted: '首次应用壁纸时引擎才会启动。',
+      noOutputs: '引擎尚未报告任何输出。',
+      noWallpaper: '无壁纸',
+      renderBackend: '使用 {backend} 绘制',
+      power: {
+        ac: '使用交流电',
+        battery: '使用电池',
+        batteryPaused: '使用电池，已暂停壁纸以节省电量'
+      },
+      softwareDecoding: '软件解码',
+      hardwareDecoding: '{hwdec} 解码',
+      frameRate: '{fps} fps',
+      droppedFrames: '丢帧 {percent}%',
+      gpuSeverity: {
+        error: 'GPU 错误',
+        warning: 'GPU 警告'
+      },
+      gpuRepeated: '{message}（×{count}）',
+      capabilities: '功能支持',
+      capabilityLabels: {
+        wayland_session: 'Wayland 会话',
+        monitor_discovery: '显示器发现',
+        steam: 'Steam',
+        wallpaper_engine: 'Wallpaper Engine',
+        compositor: '合成器',
+        library_database: '内容库数据库',
+        resource_limits: '资源限制',
+        gpu_debug: 'GPU 调试输出',
+        event_channel: '引擎事件通道'
+      },
+      available: '可用',
+      missing: '缺失',
+      logs: '实时日志',
+      logsEmpty: '尚未捕获任何日志。',
+      followLogs: '跟随新日志'
     },
     settings: {
       navLabel: '设置',
@@ -398,6 +912,9 @@ const dictionaries = {
       launchOnLoginUnavailable: '当前设备暂不支持登录时自动启动。',
       launchPreferencePrefix: '已保存偏好：',
       startOnSession: '在图形桌面会话开始时自动启动 LWE。',
+      confirmApplyToAll: '应用到所有显示器前确认',
+      confirmApplyToAllDescription: '一次替换所有显示器的壁纸前先进行确认。',
+      confirmApplyToAllSaved: '应用到所有显示器前确认：',
       saveChanges: '保存更改',
       saving: '正在保存…',
       cancel: '取消',
@@ -405,6 +922,12 @@ const dictionaries = {
       unsavedChanges: '有未保存的更改',
       steamIntegration: 'Steam 集成',
       currentState: '当前状态',
+      thumbnailCache: '缩略图缓存',
+      thumbnailCacheUsage: '已使用 {used}，上限 {budget}',
+      thumbnailCacheDescription:
+        '保存封面和预览条，让内容库打开更快。已删除壁纸的缩略图，以及超出上限时最久未显示的缩略图，会在启动时移除；可在 settings.toml 的 [library] 中通过 thumbnail_cache_mb 修改上限。',
+      clearThumbnailCache: '清除缩略图缓存',
+      clearingThumbnailCache: '正在清除…',
       steamRequired: '需要 Steam',
       steamOptional: 'Steam 可选',
       savedLanguage: '已保存语言：',
@@ -415,6 +938,11 @@ const dictionaries = {
       disabled: '已禁用',
       preferEnabled: '可用时优先启用',
       preferDisabled: '可用时优先禁用',
+      configIssuesTitle: 'settings.toml 中的问题',
+      configIssuesHint: '在修正之前，LWE 会忽略这些条目。可在终端运行 `lwe config validate` 检查该文件。',
+      configIssueLine: '第 {line} 行',
+      configIssueError: '错误',
+      configIssueWarning: '警告',
       languageOptions: {
         en: 'English',
         'zh-CN': '简体中文',
@@ -457,7 +985,10 @@ const dictionaries = {
         triggerAriaLabel: '显示 {itemTitle} 的快捷操作',
         title: '快捷操作',
         applyFromDetails: '从详情中应用',
-        applyFromDetailsDescription: '先选择这个内容项，然后可以在详情面板中选择显示器并应用。'
+        applyFromDetailsDescription: '先选择这个内容项，然后可以在详情面板中选择显示器并应用。',
+        revealInFolder: '在文件夹中显示',
+        playExternally: '在外部播放器中播放',
+        deleteItem: '删除…'
       },
       compatibilityPanel: {
         ariaLabel: '兼容性说明',
@@ -481,6 +1012,49 @@ const dictionaries = {
         applyTargetMonitor: '选择要应用到的显示器',
         selectMonitor: '选择一个显示器',
         noMonitorsAvailable: '没有可用的显示器',
+        allMonitors: '所有显示器',
+        confirmApplyAll: '要替换全部 {count} 个显示器上的壁纸吗？',
+        confirmApplyAllAction: '全部应用',
+        cancelApply: '取消',
+        appliedToAll: '已应用到所有显示器。',
+        undoApply: '撤销（{duration}）',
+        favorite: '收藏',
+        unfavorite: '取消收藏',
+        rating: '评分',
+        rateItem: '评为 {rating} 分（满分 5 分）',
+        clearRating: '清除评分',
+        rename: '重命名',
+        titleInput: '标题',
+        saveTitle: '保存',
+        cancelRename: '取消',
+        restoreTitle: '恢复原标题',
+        originalTitle: '原标题：“{title}”',
+        media: '媒体信息',
+        resolution: '分辨率',
+        duration: '时长',
+        codec: '编码',
+        frameRate: '帧率',
+        hdr: 'HDR',
+        hdrFormats: { pq: 'HDR10（PQ）', hlg: 'HLG' },
+        fileSize: '文件大小',
+        unknownMedia: '未知',
+        userTags: '我的标签',
+        userTagsPlaceholder: '用逗号分隔，例如：雨, 夜晚',
+        authorTags: '作者标签：{tags}',
+        workshopOrigin: 'Steam 创意工坊项目 {id}',
+        openInSteam: '在 Steam 中打开',
+        properties: '属性',
+        propertiesDescription: '作者为这张壁纸提供的设置。修改会立即作用于正在显示的网页壁纸。',
+        propertiesSceneNote: '场景暂时还不能渲染；这些值会保留到支持场景之后使用。',
+        resetProperties: '恢复默认值',
+        effects: '效果',
+        effectsDescription: '下次显示这张壁纸时应用的后期处理。',
+        shader: '着色器',
+        noShader: '无',
+        chooseEffect: '选择…',
+        removeEffect: '移除',
+        lut: '调色 LUT',
+        noLut: '使用全局 LUT（如有）',
         applying: '正在应用…',
         cover: '封面',
         coverDescription: '以紧凑的预览图快速确认内容，而不会占据整个面板。',
@@ -535,6 +1109,27 @@ const dictionaries = {
         clearWallpaperAriaLabel: '从 {displayName} 清除壁纸',
         clear: '清除',
         clearing: '正在清除…',
+        volume: '音量',
+        volumeAriaLabel: '{displayName} 的音量',
+        volumeValue: '{volume}%',
+        muted: '已静音',
+        mute: '静音',
+        unmute: '取消静音',
+        muteAriaLabel: '将 {displayName} 静音',
+        unmuteAriaLabel: '取消 {displayName} 的静音',
+        color: '色彩',
+        colorLabels: {
+          brightness: '亮度',
+          contrast: '对比度',
+          gamma: '伽马',
+          saturation: '饱和度'
+        },
+        colorValue: '{value}%',
+        colorAriaLabel: '{displayName} 的{property}',
+        resetColor: '重置',
+        resetColorAriaLabel: '重置 {displayName} 的色彩',
+        appliedAgo: '{ago}前应用',
+        appliedAgoWithLatency: '{ago}前应用 · 用时 {latency} 毫秒',
         restoreState: '恢复状态',
         viewStatusDetails: '查看状态详情',
         hideStatusDetails: '隐藏状态详情',
@@ -543,7 +1138,72 @@ const dictionaries = {
         expandStatusHint: '展开此区域以查看这个显示器的最新恢复状态。'
       },
       itemCard: {
-        assignedTo: '已分配到'
+        assignedTo: '已分配到',
+        markLabel: '标记 {itemTitle} 以进行批量操作'
+      },
+      monitorDropStrip: {
+        ariaLabel: '显示器',
+        dragHint: '将卡片拖到显示器上即可在该显示器上显示。',
+        dropHint: '松开以应用到该显示器，按 Esc 取消。',
+        tileAriaLabel: '应用到 {monitor}',
+        noWallpaper: '没有壁纸',
+        previewAlt: '{monitor} 当前显示的画面'
+      },
+      onboardingWizard: {
+        title: '欢迎使用 LWE',
+        stepLabel: '第 {current} 步，共 {total} 步',
+        steps: {
+          welcome: '语言',
+          workshop: 'Steam 创意工坊',
+          folders: '壁纸文件夹',
+          system: '系统检查',
+          startup: '启动'
+        },
+        welcome: '只需几步即可设置好 LWE。这里的所有选项之后都可以在设置中修改。',
+        probing: '正在检查本机…',
+        workshopFound: '已找到 Wallpaper Engine，共有 {count} 个创意工坊项目，它们会自动出现在内容库中。',
+        steamWithoutWorkshop: '已找到 Steam，但未安装 Wallpaper Engine。本地文件夹仍可使用。',
+        steamMissing: '未找到 Steam。创意工坊壁纸需要 Steam 和 Wallpaper Engine；本地文件夹无需它们也能使用。',
+        foldersHint: '这些文件夹中似乎有壁纸。勾选的文件夹会被扫描进内容库。',
+        noFolders: '没有找到壁纸文件夹。之后可以在内容库页面添加文件夹。',
+        renderBackend: '渲染',
+        hardwareDecoding: '硬件解码',
+        renderDevice: '用于壁纸的 GPU',
+        renderDeviceAuto: '自动选择',
+        launchOnLogin: '登录时启动 LWE',
+        launchOnLoginUnavailable: '本机无法设置登录时启动。',
+        back: '上一步',
+        next: '下一步',
+        skip: '跳过设置',
+        finish: '完成',
+        finishing: '正在保存…'
+      },
+      wallpaperFailureNotice: {
+        title: '壁纸已停止',
+        message: '{file} 在 {output} 上连续失败 {failures} 次，不再重试。在应用其他壁纸之前将显示 {fallback}。',
+        dismiss: '关闭'
+      },
+      deleteItemsDialog: {
+        title: '删除壁纸',
+        description: '选择如何处理所选的 {count} 项。',
+        estimating: '正在计算磁盘占用…',
+        cancel: '取消',
+        deleting: '正在删除…',
+        modes: {
+          remove_from_library: '从内容库移除',
+          delete_local_files: '删除本地文件',
+          clear_workshop_cache: '清除创意工坊缓存副本'
+        },
+        modeDescriptions: {
+          remove_from_library: '在 LWE 中隐藏这些内容项，文件保留在磁盘上。',
+          delete_local_files: '删除已下载的创意工坊文件夹。只要仍在订阅，Steam 会重新下载。',
+          clear_workshop_cache: '删除 Steam 残留的下载副本和 LWE 缩略图，壁纸仍可正常使用。'
+        },
+        confirm: {
+          remove_from_library: '移除',
+          delete_local_files: '删除文件',
+          clear_workshop_cache: '清除缓存'
+        }
       }
     }
   }
@@ -582,13 +1242,19 @@ export const getDesktopRestoreStateLabel = (
   restoreState: DesktopRestoreState
 ) => copyValue.components.desktopMonitorCard.statusBadges.restoreStates[restoreState];
 
+let activeLocale: SupportedLocale = 'en';
+
+/** Fills `{key}` placeholders; numbers are formatted for the current language. */
 export const formatCopy = (
   template: string,
   values: Record<string, CopyFormatValue>
 ) =>
   template.replace(/\{(\w+)\}/g, (match, key) => {
     const value = values[key];
-    return value === undefined ? match : String(value);
+    if (value === undefined) {
+      return match;
+    }
+    return typeof value === 'number' ? formatNumber(value, activeLocale) : value;
   });
 
 const preferredLanguage = writable<PreferredLanguage>('en');
@@ -597,6 +1263,10 @@ export const locale = derived(preferredLanguage, ($preferredLanguage): Supported
   resolveLocale($preferredLanguage)
 );
 
+locale.subscribe(($locale) => {
+  activeLocale = $locale;
+});
+
 export const copy = derived(locale, ($locale) => dictionaries[$locale]);
 
 export const setPreferredLanguage = (language: PreferredLanguage) => {
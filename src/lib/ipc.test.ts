@@ -1,17 +1,52 @@
 import { afterEach, describe, expect, it, vi } from 'vitest';
 
-const { invoke } = vi.hoisted(() => ({
-  invoke: vi.fn().mockResolvedValue({ ok: false, message: 'unavailable' })
+const { invoke, listen } = vi.hoisted(() => ({
+  invoke: vi.fn().mockResolvedValue({ ok: false, message: 'unavailable' }),
+  listen: vi.fn().mockResolvedValue(() => {})
 }));
 
 vi.mock('@tauri-apps/api/core', () => ({
   invoke
 }));
 
+vi.mock('@tauri-apps/api/event', () => ({
+  listen
+}));
+
 import {
+  addCollectionItems,
+  addLibraryFiles,
+  addLibraryFolders,
+  applyCollection,
+  applyLibraryItemToAllMonitors,
   applyLibraryItemToMonitor,
+  applyProfile,
   clearLibraryItemFromMonitor,
+  clearLibraryItemLut,
+  clearLibraryItemShader,
+  createCollection,
+  listProfiles,
+  loadMonitorPalettes,
+  loadScheduleStatus,
+  onLibraryChanged,
+  onSettingsChanged,
+  onThumbnailProgress,
+  onWallpaperFailed,
+  pickLibraryItemLut,
+  pickLibraryItemShader,
+  queueThumbnails,
+  removeCollectionItem,
+  renameCollection,
+  saveProfile,
   searchWorkshopOnline,
+  setMonitorPlaylist,
+  setMonitorAudio,
+  setMonitorColor,
+  setPlaylistPaused,
+  setScheduleSlot,
+  skipPlaylistItem,
+  previousPlaylistItem,
+  undoDesktopApply,
   updateSettings
 } from './ipc';
 
@@ -32,6 +67,133 @@ describe('ipc desktop flow bridge', () => {
       monitorId: 'DISPLAY-1'
     });
   });
+
+  it('opens the portal pickers through the library source commands', async () => {
+    await addLibraryFolders();
+    await addLibraryFiles();
+
+    expect(invoke).toHaveBeenNthCalledWith(1, 'add_library_folders', undefined);
+    expect(invoke).toHaveBeenNthCalledWith(2, 'add_library_files', undefined);
+  });
+
+  it('picks and clears the shader of a library item', async () => {
+    await pickLibraryItemShader('item-1');
+    await clearLibraryItemShader('item-1');
+
+    expect(invoke).toHaveBeenNthCalledWith(1, 'pick_library_item_shader', { itemId: 'item-1' });
+    expect(invoke).toHaveBeenNthCalledWith(2, 'clear_library_item_shader', { itemId: 'item-1' });
+  });
+
+  it('picks and clears the LUT of a library item', async () => {
+    await pickLibraryItemLut('item-1');
+    await clearLibraryItemLut('item-1');
+
+    expect(invoke).toHaveBeenNthCalledWith(1, 'pick_library_item_lut', { itemId: 'item-1' });
+    expect(invoke).toHaveBeenNthCalledWith(2, 'clear_library_item_lut', { itemId: 'item-1' });
+  });
+
+  it('queues covers for the thumbnail workers and listens for their progress', async () => {
+    await queueThumbnails(['/covers/a.gif']);
+    const progress = vi.fn();
+    await onThumbnailProgress(progress);
+    listen.mock.calls[0][1]({ payload: { coverPath: '/covers/a.gif', remaining: 3 } });
+
+    expect(invoke).toHaveBeenCalledWith('queue_thumbnails', { coverPaths: ['/covers/a.gif'] });
+    expect(listen).toHaveBeenCalledWith('thumbnail-progress', expect.any(Function));
+    expect(progress).toHaveBeenCalledWith({ coverPath: '/covers/a.gif', remaining: 3 });
+  });
+
+  it('listens for files changing in library folders', async () => {
+    listen.mockClear();
+    const changed = vi.fn();
+    await onLibraryChanged(changed);
+    listen.mock.calls[0][1]({ payload: { paths: ['/walls/beach.mp4', '/walls/coast.mp4'] } });
+
+    expect(listen).toHaveBeenCalledWith('library-changed', expect.any(Function));
+    expect(changed).toHaveBeenCalledWith({ paths: ['/walls/beach.mp4', '/walls/coast.mp4'] });
+  });
+
+  it('listens for changes to the settings file', async () => {
+    listen.mockClear();
+    const changed = vi.fn();
+    await onSettingsChanged(changed);
+    listen.mock.calls[0][1]({ payload: null });
+
+    expect(listen).toHaveBeenCalledWith('settings-changed', expect.any(Function));
+    expect(changed).toHaveBeenCalledOnce();
+  });
+
+  it('listens for wallpapers the engine gives up on', async () => {
+    listen.mockClear();
+    const failed = vi.fn();
+    const failure = {
+      output: 'DP-1',
+      path: '/walls/broken.mkv',
+      reason: 'mpv cannot render it: unsupported codec',
+      failures: 3,
+      fallback: '#000000'
+    };
+    await onWallpaperFailed(failed);
+    listen.mock.calls[0][1]({ payload: failure });
+
+    expect(listen).toHaveBeenCalledWith('wallpaper-failed', expect.any(Function));
+    expect(failed).toHaveBeenCalledWith(failure);
+  });
+
+  it('sends per-monitor volume and mute together', async () => {
+    await setMonitorAudio('DISPLAY-1', 35, false);
+
+    expect(invoke).toHaveBeenCalledWith('set_monitor_audio', {
+      monitorId: 'DISPLAY-1',
+      volume: 35,
+      muted: false
+    });
+  });
+
+  it('sends all four color values of a monitor', async () => {
+    const color = { brightness: 90, contrast: 100, gamma: 100, saturation: 120 };
+    await setMonitorColor('DISPLAY-1', color);
+
+    expect(invoke).toHaveBeenCalledWith('set_monitor_color', { monitorId: 'DISPLAY-1', color });
+  });
+
+  it('requests palettes for one monitor or all of them', async () => {
+    await loadMonitorPalettes('DISPLAY-1');
+    await loadMonitorPalettes();
+
+    expect(invoke).toHaveBeenCalledWith('load_monitor_palettes', { monitorId: 'DISPLAY-1' });
+    expect(invoke).toHaveBeenCalledWith('load_monitor_palettes', { monitorId: null });
+  });
+
+  it('loads the schedule and pins or releases a slot', async () => {
+    await loadScheduleStatus();
+    await setScheduleSlot('night');
+    await setScheduleSlot(null);
+
+    expect(invoke).toHaveBeenNthCalledWith(1, 'load_schedule_status', undefined);
+    expect(invoke).toHaveBeenNthCalledWith(2, 'set_schedule_slot', { slot: 'night' });
+    expect(invoke).toHaveBeenNthCalledWith(3, 'set_schedule_slot', { slot: null });
+  });
+
+  it('lists, saves and applies profiles', async () => {
+    await listProfiles();
+    await saveProfile('evening');
+    await applyProfile('evening');
+
+    expect(invoke).toHaveBeenNthCalledWith(1, 'list_profiles', undefined);
+    expect(invoke).toHaveBeenNthCalledWith(2, 'save_profile', { name: 'evening' });
+    expect(invoke).toHaveBeenNthCalledWith(3, 'apply_profile', { name: 'evening' });
+  });
+
+  it('invokes the apply-to-all and undo commands', async () => {
+    await applyLibraryItemToAllMonitors('item-1');
+    await undoDesktopApply();
+
+    expect(invoke).toHaveBeenNthCalledWith(1, 'apply_library_item_to_all_monitors', {
+      itemId: 'item-1'
+    });
+    expect(invoke).toHaveBeenNthCalledWith(2, 'undo_desktop_apply', undefined);
+  });
 });
 
 describe('ipc settings flow bridge', () => {
@@ -47,7 +209,8 @@ describe('ipc settings flow bridge', () => {
       steamWebApiKey: 'test-key',
       workshopQuery: 'forest',
       workshopAgeRatings: ['g', 'pg_13'],
-      workshopItemTypes: ['video', 'application']
+      workshopItemTypes: ['video', 'application'],
+      confirmApplyToAll: false
     });
 
     expect(invoke).toHaveBeenCalledWith('update_settings', {
@@ -58,7 +221,8 @@ describe('ipc settings flow bridge', () => {
         steamWebApiKey: 'test-key',
         workshopQuery: 'forest',
         workshopAgeRatings: ['g', 'pg_13'],
-        workshopItemTypes: ['video', 'application']
+        workshopItemTypes: ['video', 'application'],
+        confirmApplyToAll: false
       }
     });
   });
@@ -83,3 +247,58 @@ describe('ipc settings flow bridge', () => {
     });
   });
 });
+
+describe('ipc playlist rotation bridge', () => {
+  afterEach(() => {
+    invoke.mockClear();
+  });
+
+  it('invokes the rotation commands with monitor targets', async () => {
+    await setMonitorPlaylist('DISPLAY-1', 4);
+    await skipPlaylistItem(null);
+    await setPlaylistPaused('DISPLAY-1', true);
+    await previousPlaylistItem('DISPLAY-1');
+
+    expect(invoke).toHaveBeenNthCalledWith(1, 'set_monitor_playlist', {
+      monitorId: 'DISPLAY-1',
+      playlistId: 4
+    });
+    expect(invoke).toHaveBeenNthCalledWith(2, 'skip_playlist_item', { monitorId: null });
+    expect(invoke).toHaveBeenNthCalledWith(3, 'set_playlist_paused', {
+      monitorId: 'DISPLAY-1',
+      paused: true
+    });
+    expect(invoke).toHaveBeenNthCalledWith(4, 'previous_playlist_item', {
+      monitorId: 'DISPLAY-1'
+    });
+  });
+
+  it('invokes the collection commands with collection ids and item ids', async () => {
+    await createCollection('Nature', ['scene-7']);
+    await renameCollection(2, 'Forests');
+    await addCollectionItems(2, ['video-3']);
+    await removeCollectionItem(2, 'scene-7');
+    await applyCollection(2, null);
+
+    expect(invoke).toHaveBeenNthCalledWith(1, 'create_collection', {
+      name: 'Nature',
+      itemIds: ['scene-7']
+    });
+    expect(invoke).toHaveBeenNthCalledWith(2, 'rename_collection', {
+      collectionId: 2,
+      name: 'Forests'
+    });
+    expect(invoke).toHaveBeenNthCalledWith(3, 'add_collection_items', {
+      collectionId: 2,
+      itemIds: ['video-3']
+    });
+    expect(invoke).toHaveBeenNthCalledWith(4, 'remove_collection_item', {
+      collectionId: 2,
+      itemId: 'scene-7'
+    });
+    expect(invoke).toHaveBeenNthCalledWith(5, 'apply_collection', {
+      collectionId: 2,
+      monitorId: null
+    });
+  });
+});
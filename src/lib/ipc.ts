@@ -1,13 +1,31 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 import type {
   ActionOutcome,
   AppShellSnapshot,
   DesktopPageSnapshot,
+  DiagnosticsLogLine,
+  DiagnosticsPageSnapshot,
+  LibraryChanged,
+  LibraryDeleteEstimate,
+  LibraryDeleteMode,
   LibraryItemDetail,
+  LibraryItemPropertyValue,
   LibraryPageSnapshot,
+  MonitorColor,
+  MonitorPalette,
+  OnboardingFinishInput,
+  OnboardingSnapshot,
+  PlaylistPageSnapshot,
+  PlaylistSaveInput,
+  ProfileSummary,
+  ScheduleStatusSnapshot,
   SettingsPageSnapshot,
   SettingsUpdateInput,
+  ThumbnailCacheSummary,
+  ThumbnailProgress,
+  WallpaperFailed,
   WorkshopItemDetail,
   WorkshopOnlineSearchInput,
   WorkshopOnlineSearchResult,
@@ -22,11 +40,94 @@ const invokeCommand = <T>(command: string, args?: InvokeArgs) => {
 
 export const loadAppShell = () => invokeCommand<AppShellSnapshot>('load_app_shell');
 
+export const loadOnboarding = () => invokeCommand<OnboardingSnapshot>('load_onboarding');
+
+export const finishOnboarding = (input: OnboardingFinishInput) =>
+  invokeCommand<ActionOutcome<null>>('finish_onboarding', { input });
+
 export const loadLibraryPage = () => invokeCommand<LibraryPageSnapshot>('load_library_page');
 
 export const loadLibraryItemDetail = (itemId: string) =>
   invokeCommand<LibraryItemDetail>('load_library_item_detail', { itemId });
 
+export const stopLibraryPreview = () => invokeCommand<void>('stop_library_preview');
+
+export const queueThumbnails = (coverPaths: string[]) =>
+  invokeCommand<void>('queue_thumbnails', { coverPaths });
+
+// The shell reports each cover its thumbnail workers finish.
+export const onThumbnailProgress = (listener: (progress: ThumbnailProgress) => void) =>
+  listen<ThumbnailProgress>('thumbnail-progress', (event) => listener(event.payload));
+
+export const onLibraryChanged = (listener: (change: LibraryChanged) => void) =>
+  listen<LibraryChanged>('library-changed', (event) => listener(event.payload));
+
+// The shell reports each change to settings.toml once the engine has it.
+export const onSettingsChanged = (listener: () => void) =>
+  listen<null>('settings-changed', () => listener());
+
+// The shell reports each wallpaper the engine gives up on and replaces with its safe-mode fallback.
+export const onWallpaperFailed = (listener: (failure: WallpaperFailed) => void) =>
+  listen<WallpaperFailed>('wallpaper-failed', (event) => listener(event.payload));
+
+export const setLibraryItemFavorite = (itemId: string, favorite: boolean) =>
+  invokeCommand<ActionOutcome<null>>('set_library_item_favorite', { itemId, favorite });
+
+export const setLibraryItemRating = (itemId: string, rating: number) =>
+  invokeCommand<ActionOutcome<null>>('set_library_item_rating', { itemId, rating });
+
+export const setLibraryItemTitle = (itemId: string, title: string | null) =>
+  invokeCommand<ActionOutcome<null>>('set_library_item_title', { itemId, title });
+
+export const setLibraryItemTags = (itemId: string, tags: string[]) =>
+  invokeCommand<ActionOutcome<null>>('set_library_item_tags', { itemId, tags });
+
+export const setLibraryItemProperty = (
+  itemId: string,
+  name: string,
+  value: LibraryItemPropertyValue
+) => invokeCommand<ActionOutcome<null>>('set_library_item_property', { itemId, name, value });
+
+export const resetLibraryItemProperties = (itemId: string) =>
+  invokeCommand<ActionOutcome<null>>('reset_library_item_properties', { itemId });
+
+export const pickLibraryItemShader = (itemId: string) =>
+  invokeCommand<ActionOutcome<null>>('pick_library_item_shader', { itemId });
+
+export const clearLibraryItemShader = (itemId: string) =>
+  invokeCommand<ActionOutcome<null>>('clear_library_item_shader', { itemId });
+
+export const pickLibraryItemLut = (itemId: string) =>
+  invokeCommand<ActionOutcome<null>>('pick_library_item_lut', { itemId });
+
+export const clearLibraryItemLut = (itemId: string) =>
+  invokeCommand<ActionOutcome<null>>('clear_library_item_lut', { itemId });
+
+export const addLibraryItemsTag = (itemIds: string[], tag: string) =>
+  invokeCommand<ActionOutcome<null>>('add_library_items_tag', { itemIds, tag });
+
+export const regenerateLibraryThumbnails = (itemIds: string[]) =>
+  invokeCommand<ActionOutcome<null>>('regenerate_library_thumbnails', { itemIds });
+
+export const exportLibraryItemPaths = (itemIds: string[]) =>
+  invokeCommand<ActionOutcome<null>>('export_library_item_paths', { itemIds });
+
+export const revealLibraryItem = (itemId: string) =>
+  invokeCommand<ActionOutcome<null>>('reveal_library_item', { itemId });
+
+export const playLibraryItemExternally = (itemId: string) =>
+  invokeCommand<ActionOutcome<null>>('play_library_item_externally', { itemId });
+
+export const addLibraryFolders = () => invokeCommand<ActionOutcome<null>>('add_library_folders');
+
+export const addLibraryFiles = () => invokeCommand<ActionOutcome<null>>('add_library_files');
+
+export const estimateLibraryDeletion = (itemIds: string[]) =>
+  invokeCommand<LibraryDeleteEstimate>('estimate_library_deletion', { itemIds });
+
+export const deleteLibraryItems = (itemIds: string[], mode: LibraryDeleteMode) =>
+  invokeCommand<ActionOutcome<null>>('delete_library_items', { itemIds, mode });
+
 export const loadWorkshopPage = () => invokeCommand<WorkshopPageSnapshot>('load_workshop_page');
 
 export const loadWorkshopItemDetail = (workshopId: string) =>
@@ -46,10 +147,106 @@ export const loadDesktopPage = () => invokeCommand<DesktopPageSnapshot>('load_de
 export const applyLibraryItemToMonitor = (monitorId: string, itemId: string) =>
   invokeCommand<ActionOutcome<null>>('apply_library_item_to_monitor', { monitorId, itemId });
 
+export const applyLibraryItemToAllMonitors = (itemId: string) =>
+  invokeCommand<ActionOutcome<null>>('apply_library_item_to_all_monitors', { itemId });
+
+export const undoDesktopApply = () => invokeCommand<ActionOutcome<null>>('undo_desktop_apply');
+
 export const clearLibraryItemFromMonitor = (monitorId: string) =>
   invokeCommand<ActionOutcome<null>>('clear_library_item_from_monitor', { monitorId });
 
+export const setMonitorAudio = (monitorId: string, volume: number, muted: boolean) =>
+  invokeCommand<ActionOutcome<DesktopPageSnapshot>>('set_monitor_audio', {
+    monitorId,
+    volume,
+    muted
+  });
+
+export const setMonitorColor = (monitorId: string, color: MonitorColor) =>
+  invokeCommand<ActionOutcome<DesktopPageSnapshot>>('set_monitor_color', { monitorId, color });
+
+export const loadMonitorPalettes = (monitorId: string | null = null) =>
+  invokeCommand<MonitorPalette[]>('load_monitor_palettes', { monitorId });
+
+export const loadScheduleStatus = () =>
+  invokeCommand<ScheduleStatusSnapshot>('load_schedule_status');
+
+export const setScheduleSlot = (slot: string | null) =>
+  invokeCommand<ActionOutcome<ScheduleStatusSnapshot>>('set_schedule_slot', { slot });
+
+export const listProfiles = () => invokeCommand<ProfileSummary[]>('list_profiles');
+
+export const saveProfile = (name: string) =>
+  invokeCommand<ActionOutcome<ProfileSummary[]>>('save_profile', { name });
+
+export const applyProfile = (name: string) =>
+  invokeCommand<ActionOutcome<null>>('apply_profile', { name });
+
+export const loadPlaylistPage = () => invokeCommand<PlaylistPageSnapshot>('load_playlist_page');
+
+export const savePlaylist = (input: PlaylistSaveInput) =>
+  invokeCommand<ActionOutcome<PlaylistPageSnapshot>>('save_playlist', { input });
+
+export const deletePlaylist = (playlistId: number) =>
+  invokeCommand<ActionOutcome<PlaylistPageSnapshot>>('delete_playlist', { playlistId });
+
+export const setMonitorPlaylist = (monitorId: string, playlistId: number | null) =>
+  invokeCommand<ActionOutcome<PlaylistPageSnapshot>>('set_monitor_playlist', {
+    monitorId,
+    playlistId
+  });
+
+export const skipPlaylistItem = (monitorId: string | null) =>
+  invokeCommand<ActionOutcome<PlaylistPageSnapshot>>('skip_playlist_item', { monitorId });
+
+export const previousPlaylistItem = (monitorId: string | null) =>
+  invokeCommand<ActionOutcome<PlaylistPageSnapshot>>('previous_playlist_item', { monitorId });
+
+export const setPlaylistPaused = (monitorId: string | null, paused: boolean) =>
+  invokeCommand<ActionOutcome<PlaylistPageSnapshot>>('set_playlist_paused', {
+    monitorId,
+    paused
+  });
+
+export const createCollection = (name: string, itemIds: string[]) =>
+  invokeCommand<ActionOutcome<PlaylistPageSnapshot>>('create_collection', { name, itemIds });
+
+export const renameCollection = (collectionId: number, name: string) =>
+  invokeCommand<ActionOutcome<PlaylistPageSnapshot>>('rename_collection', { collectionId, name });
+
+export const deleteCollection = (collectionId: number) =>
+  invokeCommand<ActionOutcome<PlaylistPageSnapshot>>('delete_collection', { collectionId });
+
+export const addCollectionItems = (collectionId: number, itemIds: string[]) =>
+  invokeCommand<ActionOutcome<PlaylistPageSnapshot>>('add_collection_items', {
+    collectionId,
+    itemIds
+  });
+
+export const removeCollectionItem = (collectionId: number, itemId: string) =>
+  invokeCommand<ActionOutcome<PlaylistPageSnapshot>>('remove_collection_item', {
+    collectionId,
+    itemId
+  });
+
+export const applyCollection = (collectionId: number, monitorId: string | null) =>
+  invokeCommand<ActionOutcome<PlaylistPageSnapshot>>('apply_collection', {
+    collectionId,
+    monitorId
+  });
+
+export const loadDiagnosticsPage = () =>
+  invokeCommand<DiagnosticsPageSnapshot>('load_diagnostics_page');
+
+export const loadDiagnosticsLogs = (afterSequence: number | null) =>
+  invokeCommand<DiagnosticsLogLine[]>('load_diagnostics_logs', { afterSequence });
+
 export const loadSettingsPage = () => invokeCommand<SettingsPageSnapshot>('load_settings_page');
 
 export const updateSettings = (input: SettingsUpdateInput) =>
   invokeCommand<ActionOutcome<SettingsPageSnapshot>>('update_settings', { input });
+
+export const loadThumbnailCache = () => invokeCommand<ThumbnailCacheSummary>('load_thumbnail_cache');
+
+export const clearThumbnailCache = () =>
+  invokeCommand<ActionOutcome<ThumbnailCacheSummary>>('clear_thumbnail_cache');
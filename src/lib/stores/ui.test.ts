@@ -31,6 +31,8 @@ const resetCache = () => {
     library: { snapshot: null, detail: null, stale: false },
     workshop: { snapshot: null, detail: null, stale: false },
     desktop: { snapshot: null, detail: null, stale: false },
+    playlists: { snapshot: null, detail: null, stale: false },
+    diagnostics: { snapshot: null, detail: null, stale: false },
     settings: { snapshot: null, detail: null, stale: false }
   });
 };
@@ -91,7 +93,9 @@ describe('ui page cache', () => {
           ageRating: 'g',
           source: 'workshop',
           compatibility: compatibilitySummary,
-          favorite: false
+          favorite: false,
+          rating: 0,
+          useCount: 0
         },
         {
           id: 'b',
@@ -101,7 +105,9 @@ describe('ui page cache', () => {
           ageRating: 'g',
           source: 'workshop',
           compatibility: compatibilitySummary,
-          favorite: false
+          favorite: false,
+          rating: 0,
+          useCount: 0
         }
       ],
       selectedItemId: null,
@@ -117,6 +123,7 @@ describe('ui page cache', () => {
       {
         id: 'a',
         title: 'A',
+        originalTitle: 'A',
         itemType: 'scene',
         coverPath: null,
         source: 'workshop',
@@ -125,7 +132,12 @@ describe('ui page cache', () => {
         desktopAssignmentIssue: null,
         desktopAssignmentsAvailable: false,
         description: null,
-        tags: []
+        tags: [],
+        userTags: [],
+        media: {},
+        favorite: false,
+        rating: 0,
+        properties: []
       },
       'a'
     );
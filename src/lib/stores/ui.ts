@@ -2,9 +2,11 @@ import { get, writable } from 'svelte/store';
 
 import type {
   DesktopPageSnapshot,
+  DiagnosticsPageSnapshot,
   InvalidatedPage,
   LibraryItemDetail,
   LibraryPageSnapshot,
+  PlaylistPageSnapshot,
   SettingsPageSnapshot,
   WorkshopAgeRating,
   WorkshopItemDetail,
@@ -13,7 +15,7 @@ import type {
   WorkshopPageSnapshot
 } from '$lib/types';
 
-export type PageKey = 'library' | 'workshop' | 'desktop' | 'settings';
+export type PageKey = 'library' | 'workshop' | 'desktop' | 'playlists' | 'diagnostics' | 'settings';
 
 type DetailPageKey = 'library' | 'workshop';
 
@@ -27,6 +29,8 @@ type PageCache = {
   library: CacheEntry<LibraryPageSnapshot, LibraryItemDetail>;
   workshop: CacheEntry<WorkshopPageSnapshot, WorkshopItemDetail>;
   desktop: CacheEntry<DesktopPageSnapshot, null>;
+  playlists: CacheEntry<PlaylistPageSnapshot, null>;
+  diagnostics: CacheEntry<DiagnosticsPageSnapshot, null>;
   settings: CacheEntry<SettingsPageSnapshot, null>;
 };
 
@@ -34,6 +38,9 @@ const createEmptyCache = (): PageCache => ({
   library: { snapshot: null, detail: null, stale: false },
   workshop: { snapshot: null, detail: null, stale: false },
   desktop: { snapshot: null, detail: null, stale: false },
+  playlists: { snapshot: null, detail: null, stale: false },
+  diagnostics: { snapshot: null, detail: null, stale: false },
+  diagnostics: { snapshot: null, detail: null, stale: false },
   settings: { snapshot: null, detail: null, stale: false }
 });
 
@@ -191,6 +198,28 @@ export const setDesktopSnapshot = (snapshot: DesktopPageSnapshot) => {
   }));
 };
 
+export const setPlaylistSnapshot = (snapshot: PlaylistPageSnapshot) => {
+  pageCache.update((cache) => ({
+    ...cache,
+    playlists: {
+      snapshot,
+      detail: null,
+      stale: false
+    }
+  }));
+};
+
+export const setDiagnosticsSnapshot = (snapshot: DiagnosticsPageSnapshot) => {
+  pageCache.update((cache) => ({
+    ...cache,
+    diagnostics: {
+      snapshot,
+      detail: null,
+      stale: false
+    }
+  }));
+};
+
 export const setSettingsSnapshot = (snapshot: SettingsPageSnapshot) => {
   pageCache.update((cache) => ({
     ...cache,
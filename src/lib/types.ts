@@ -1,4 +1,4 @@
-export type InvalidatedPage = 'library' | 'workshop' | 'desktop' | 'settings';
+export type InvalidatedPage = 'library' | 'workshop' | 'desktop' | 'playlists' | 'settings';
 export type ItemType = 'video' | 'scene' | 'web' | 'application' | 'other';
 export type WorkshopAgeRating = 'g' | 'pg_13' | 'r_18';
 export type WorkshopOnlineItemType = 'video' | 'scene' | 'web' | 'application';
@@ -12,6 +12,7 @@ export type CompatibilityNextStep =
 export type LibrarySource = 'local' | 'workshop' | 'core' | 'other';
 export type RuntimeStatus = 'running' | 'idle' | 'unsupported' | 'error';
 export type DesktopRestoreState = 'restored' | 'missing_monitor' | 'missing_item' | 'unavailable';
+export type PlaylistTransition = 'none' | 'fade';
 
 export interface DesktopMissingMonitorRestore {
   monitorId: string;
@@ -109,6 +110,9 @@ export interface LibraryItemSummary {
   source: LibrarySource;
   compatibility: CompatibilitySummaryModel;
   favorite: boolean;
+  rating: number;
+  useCount: number;
+  lastUsedAt?: string | null;
   assignedMonitorLabels?: string[];
 }
 
@@ -122,9 +126,44 @@ export interface LibraryPageSnapshot {
   stale: boolean;
 }
 
+export interface ThumbnailProgress {
+  coverPath: string;
+  remaining: number;
+}
+
+export interface LibraryChanged {
+  paths: string[];
+}
+
+// The engine stopped retrying a wallpaper that kept failing on `output`.
+export interface WallpaperFailed {
+  output: string;
+  path: string;
+  reason: string;
+  failures: number;
+  fallback: string;
+}
+
+export type LibraryDeleteMode = 'remove_from_library' | 'delete_local_files' | 'clear_workshop_cache';
+
+export interface LibraryDeleteEstimateItem {
+  id: string;
+  title: string;
+  localFileBytes: number;
+  workshopCacheBytes: number;
+}
+
+export interface LibraryDeleteEstimate {
+  items: LibraryDeleteEstimateItem[];
+  localFileBytes: number;
+  workshopCacheBytes: number;
+}
+
 export interface LibraryItemDetail {
   id: string;
+  /** The user's title when they gave one, else the scanned title */
   title: string;
+  originalTitle: string;
   itemType: ItemType;
   coverPath: string | null;
   source: LibrarySource;
@@ -136,6 +175,46 @@ export interface LibraryItemDetail {
   assignedMonitorLabels?: string[];
   description: string | null;
   tags: string[];
+  userTags: string[];
+  workshopId?: string;
+  media: LibraryItemMedia;
+  favorite: boolean;
+  rating: number;
+  properties: LibraryItemProperty[];
+  shaderPath?: string | null;
+  lutPath?: string | null;
+}
+
+/** Fields are missing when the file's headers do not say */
+export interface LibraryItemMedia {
+  width?: number;
+  height?: number;
+  durationSecs?: number;
+  codec?: string;
+  fps?: number;
+  /** Only set for HDR video */
+  hdr?: 'pq' | 'hlg';
+  fileSize?: number;
+}
+
+export type LibraryItemPropertyValue = string | number | boolean;
+
+export interface LibraryItemPropertyOption {
+  label: string;
+  value: LibraryItemPropertyValue;
+}
+
+/** A user property of a Wallpaper Engine project; colors are "r g b" from 0 to 1 */
+export interface LibraryItemProperty {
+  name: string;
+  kind: 'slider' | 'bool' | 'color' | 'combo' | 'textinput';
+  label: string;
+  value: LibraryItemPropertyValue;
+  defaultValue: LibraryItemPropertyValue;
+  min?: number;
+  max?: number;
+  step?: number;
+  options: LibraryItemPropertyOption[];
 }
 
 export interface DesktopMonitorSummary {
@@ -149,6 +228,43 @@ export interface DesktopMonitorSummary {
   restoreState?: DesktopRestoreState | null;
   restoreIssue?: string | null;
   runtimeStatus: RuntimeStatus;
+  volume: number;
+  muted: boolean;
+  color: MonitorColor;
+  appliedSecondsAgo?: number | null;
+  applyLatencyMs?: number | null;
+}
+
+/** Brightness, contrast, gamma and saturation in percent; 100 is unchanged */
+export interface MonitorColor {
+  brightness: number;
+  contrast: number;
+  gamma: number;
+  saturation: number;
+}
+
+export interface MonitorPalette {
+  monitorId: string;
+  colors: string[];
+}
+
+export interface ProfileSummary {
+  name: string;
+  monitorIds: string[];
+}
+
+export interface ScheduleSlot {
+  name: string;
+  start: string;
+  itemId: string;
+}
+
+export interface ScheduleStatusSnapshot {
+  slots: ScheduleSlot[];
+  activeSlot?: string | null;
+  nextChange?: number | null;
+  pinned: boolean;
+  issue?: string | null;
 }
 
 export interface DesktopPageSnapshot {
@@ -162,6 +278,137 @@ export interface DesktopPageSnapshot {
   stale: boolean;
 }
 
+export interface PlaylistEntry {
+  itemId: string;
+  itemTitle: string | null;
+  durationSecs: number | null;
+}
+
+export interface PlaylistSummary {
+  id: number;
+  name: string;
+  shuffle: boolean;
+  transition: PlaylistTransition;
+  defaultDurationSecs: number;
+  items: PlaylistEntry[];
+  monitorIds: string[];
+  folder: PlaylistFolder | null;
+}
+
+export interface PlaylistFolder {
+  path: string;
+  pattern: string | null;
+  imageDurationSecs: number | null;
+}
+
+export interface CollectionItem {
+  itemId: string;
+  itemTitle: string | null;
+}
+
+export interface Collection {
+  id: number;
+  name: string;
+  items: CollectionItem[];
+}
+
+export interface PlaylistLibraryItem {
+  id: string;
+  title: string;
+}
+
+export interface PlaylistMonitor {
+  monitorId: string;
+  displayName: string;
+}
+
+export interface PlaylistRotation {
+  monitorId: string;
+  playlistId: number;
+  currentItemId: string | null;
+  currentItemTitle: string | null;
+  paused: boolean;
+}
+
+export interface PlaylistPageSnapshot {
+  playlists: PlaylistSummary[];
+  collections: Collection[];
+  libraryItems: PlaylistLibraryItem[];
+  libraryIssue?: string | null;
+  monitors: PlaylistMonitor[];
+  monitorsAvailable: boolean;
+  monitorDiscoveryIssue?: string | null;
+  rotations: PlaylistRotation[];
+  stale: boolean;
+}
+
+export interface PlaylistSaveInput {
+  id: number | null;
+  name: string;
+  shuffle: boolean;
+  transition: PlaylistTransition;
+  defaultDurationSecs: number;
+  items: Array<{ itemId: string; durationSecs: number | null }>;
+  monitorIds: string[];
+  folder: PlaylistFolder | null;
+}
+
+export type DiagnosticsEngineStatus = 'running' | 'not_started' | 'unavailable';
+
+export interface DiagnosticsLogLine {
+  sequence: number;
+  timestampMs: number;
+  level: string;
+  target: string;
+  message: string;
+}
+
+export interface DiagnosticsCapability {
+  key: string;
+  available: boolean;
+  detail: string;
+}
+
+export type DiagnosticsGpuSeverity = 'error' | 'warning';
+
+export interface DiagnosticsGpuMessage {
+  severity: DiagnosticsGpuSeverity;
+  message: string;
+  count: number;
+}
+
+export interface DiagnosticsPlayback {
+  title: string | null;
+  durationSecs: number | null;
+  hwdec: string | null;
+  fps: number | null;
+  skipRate: number | null;
+}
+
+export interface DiagnosticsOutput {
+  name: string;
+  resolution: string;
+  wallpaperPath: string | null;
+  gpuMessages: DiagnosticsGpuMessage[];
+  playback?: DiagnosticsPlayback | null;
+}
+
+export interface DiagnosticsEngine {
+  status: DiagnosticsEngineStatus;
+  issue?: string | null;
+  outputs: DiagnosticsOutput[];
+  renderBackend?: string | null;
+  onBattery?: boolean;
+  powerPaused?: boolean;
+}
+
+export interface DiagnosticsPageSnapshot {
+  appVersion: string;
+  engine: DiagnosticsEngine;
+  capabilities: DiagnosticsCapability[];
+  logs: DiagnosticsLogLine[];
+}
+
 export interface SettingsPageSnapshot {
   language: string;
   theme: string;
@@ -171,9 +418,56 @@ export interface SettingsPageSnapshot {
   workshopQuery: string;
   workshopAgeRatings: WorkshopAgeRating[];
   workshopItemTypes: WorkshopOnlineItemType[];
+  confirmApplyToAll: boolean;
   steamRequired: boolean;
   steamStatusMessage: string;
   stale: boolean;
+  configIssues: SettingsConfigIssue[];
+}
+
+/** Space the stored thumbnails take, and their budget */
+export interface ThumbnailCacheSummary {
+  bytes: number;
+  budgetBytes: number;
+}
+
+export interface OnboardingCheck {
+  ok: boolean;
+  detail: string;
+}
+
+export interface OnboardingRenderDevice {
+  path: string;
+  detail: string;
+  hardwareDecoding: boolean;
+}
+
+export interface OnboardingSnapshot {
+  needed: boolean;
+  steamFound: boolean;
+  wallpaperEngineFound: boolean;
+  workshopItemCount: number;
+  suggestedFolders: string[];
+  renderBackend: OnboardingCheck;
+  hardwareDecoding: OnboardingCheck;
+  renderDevices: OnboardingRenderDevice[];
+  launchOnLoginAvailable: boolean;
+}
+
+export interface OnboardingFinishInput {
+  language: string | null;
+  folders: string[];
+  renderDevice: string | null;
+  launchOnLogin: boolean;
+}
+
+export type ConfigIssueSeverity = 'error' | 'warning';
+
+export interface SettingsConfigIssue {
+  severity: ConfigIssueSeverity;
+  line: number | null;
+  key: string | null;
+  message: string;
 }
 
 export interface SettingsUpdateInput {
@@ -184,6 +478,7 @@ export interface SettingsUpdateInput {
   workshopQuery?: string | null;
   workshopAgeRatings?: WorkshopAgeRating[] | null;
   workshopItemTypes?: WorkshopOnlineItemType[] | null;
+  confirmApplyToAll?: boolean | null;
 }
 
 export interface AppShellPatch {
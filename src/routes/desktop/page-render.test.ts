@@ -13,6 +13,8 @@ const resetCache = () => {
     library: { snapshot: null, detail: null, stale: false },
     workshop: { snapshot: null, detail: null, stale: false },
     desktop: { snapshot: null, detail: null, stale: false },
+    playlists: { snapshot: null, detail: null, stale: false },
+    diagnostics: { snapshot: null, detail: null, stale: false },
     settings: { snapshot: null, detail: null, stale: false }
   });
 };
@@ -36,7 +38,10 @@ describe('desktop page render', () => {
           clearSupported: true,
           restoreState: 'restored',
           restoreIssue: null,
-          runtimeStatus: 'unsupported'
+          runtimeStatus: 'unsupported',
+          volume: 100,
+          muted: true,
+          color: { brightness: 100, contrast: 100, gamma: 100, saturation: 100 }
         }
       ],
       missingMonitorRestores: [
@@ -70,6 +75,8 @@ describe('desktop page render', () => {
     expect(body).toContain(
       'Saved assignment for missing monitor DISPLAY-3 still points to Forest Scene (scene-7).'
     );
+    expect(body).toContain('Volume for Primary');
+    expect(body).toContain('Unmute Primary');
   });
 
   it('renders zh-CN route and monitor card copy when Simplified Chinese is active', () => {
@@ -87,7 +94,10 @@ describe('desktop page render', () => {
           clearSupported: true,
           restoreState: 'restored',
           restoreIssue: null,
-          runtimeStatus: 'unsupported'
+          runtimeStatus: 'unsupported',
+          volume: 100,
+          muted: true,
+          color: { brightness: 100, contrast: 100, gamma: 100, saturation: 100 }
         }
       ],
       missingMonitorRestores: [
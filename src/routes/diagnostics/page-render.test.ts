@@ -0,0 +1,86 @@
+import { afterEach, describe, expect, it } from 'vitest';
+import { render } from 'svelte/server';
+
+import DiagnosticsPage from './+page.svelte';
+import { resetPreferredLanguage, setPreferredLanguage } from '$lib/i18n';
+import { pageCache, setDiagnosticsSnapshot } from '$lib/stores/ui';
+import type { DiagnosticsPageSnapshot } from '$lib/types';
+
+const resetCache = () => {
+  pageCache.set({
+    library: { snapshot: null, detail: null, stale: false },
+    workshop: { snapshot: null, detail: null, stale: false },
+    desktop: { snapshot: null, detail: null, stale: false },
+    playlists: { snapshot: null, detail: null, stale: false },
+    diagnostics: { snapshot: null, detail: null, stale: false },
+    settings: { snapshot: null, detail: null, stale: false }
+  });
+};
+
+const snapshot: DiagnosticsPageSnapshot = {
+  appVersion: '0.6.1',
+  engine: {
+    status: 'running',
+    issue: null,
+    outputs: [
+      {
+        name: 'DP-1',
+        resolution: '2560x1440',
+        wallpaperPath: '/videos/forest.mp4',
+        gpuMessages: [{ severity: 'error', message: 'GL_INVALID_OPERATION (0x0502)', count: 3 }],
+        playback: { title: 'Forest', durationSecs: 75.4, hwdec: 'vaapi', fps: 59.94, skipRate: 0.012 }
+      }
+    ],
+    renderBackend: 'OpenGL',
+    onBattery: true,
+    powerPaused: true
+  },
+  capabilities: [{ key: 'library_database', available: true, detail: '/home/user/.local/share/lwe/library.db' }],
+  logs: [
+    {
+      sequence: 1,
+      timestampMs: Date.UTC(2026, 0, 2, 3, 4, 5, 678),
+      level: 'INFO',
+      target: 'lwe_engine',
+      message: 'Output DP-1 configured'
+    }
+  ]
+};
+
+describe('diagnostics page render', () => {
+  afterEach(() => {
+    resetPreferredLanguage();
+    resetCache();
+  });
+
+  it('renders engine outputs, capabilities, and captured log lines', () => {
+    setDiagnosticsSnapshot(snapshot);
+
+    const { body } = render(DiagnosticsPage);
+
+    expect(body).toContain('Copy report');
+    expect(body).toContain('App version 0.6.1');
+    expect(body).toContain('DP-1 (2560x1440)');
+    expect(body).toContain('GL_INVALID_OPERATION (0x0502) (×3)');
+    expect(body).toContain('Drawing with OpenGL');
+    expect(body).toContain('On battery, wallpapers paused to save power');
+    expect(body).toContain('vaapi decoding · 59.9 fps · 1.2% dropped');
+    expect(body).toContain('Library database');
+    expect(body).toContain('03:04:05.678 INFO  lwe_engine: Output DP-1 configured');
+  });
+
+  it('renders zh-CN copy when the engine has not started', () => {
+    setPreferredLanguage('zh-CN');
+    setDiagnosticsSnapshot({
+      ...snapshot,
+      engine: { status: 'not_started', issue: null, outputs: [] },
+      logs: []
+    });
+
+    const { body } = render(DiagnosticsPage);
+
+    expect(body).toContain('未启动');
+    expect(body).toContain('首次应用壁纸时引擎才会启动。');
+    expect(body).toContain('尚未捕获任何日志。');
+  });
+});
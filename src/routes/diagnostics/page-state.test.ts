@@ -0,0 +1,99 @@
+import { describe, expect, it } from 'vitest';
+
+import { getCopyForLanguage } from '$lib/i18n';
+import type { DiagnosticsPageSnapshot } from '$lib/types';
+import {
+  appendLogLines,
+  buildDiagnosticsReport,
+  capabilityLabel,
+  formatClock,
+  formatLogLine,
+  lastLogSequence,
+  playbackSummary
+} from './page-state';
+
+const line = (sequence: number, message: string) => ({
+  sequence,
+  timestampMs: Date.UTC(2026, 0, 2, 3, 4, 5, 678),
+  level: 'WARN',
+  target: 'lwe_engine',
+  message
+});
+
+const snapshot: DiagnosticsPageSnapshot = {
+  appVersion: '0.6.1',
+  engine: {
+    status: 'running',
+    issue: null,
+    outputs: [
+      {
+        name: 'DP-1',
+        resolution: '2560x1440',
+        wallpaperPath: '/videos/forest.mp4',
+        gpuMessages: [{ severity: 'error', message: 'GL_INVALID_OPERATION (0x0502)', count: 3 }],
+        playback: { title: 'Forest', durationSecs: 75.4, hwdec: 'vaapi', fps: 59.94, skipRate: 0.012 }
+      }
+    ],
+    renderBackend: 'OpenGL',
+    onBattery: true,
+    powerPaused: true
+  },
+  capabilities: [
+    { key: 'steam', available: false, detail: 'Steam was not detected' },
+    { key: 'wayland_session', available: true, detail: 'wayland-1 (niri|wlroots)' }
+  ],
+  logs: []
+};
+
+describe('diagnostics page state', () => {
+  it('appends only unseen log lines and keeps the newest within the limit', () => {
+    const existing = [line(1, 'first'), line(2, 'second')];
+
+    expect(appendLogLines(existing, [line(2, 'second')])).toBe(existing);
+    expect(
+      appendLogLines(existing, [line(2, 'second'), line(3, 'third')], 2).map((entry) => entry.message)
+    ).toEqual(['second', 'third']);
+    expect(lastLogSequence(existing)).toBe(2);
+    expect(lastLogSequence([])).toBeNull();
+  });
+
+  it('formats log lines with time, level, and target', () => {
+    expect(formatLogLine(line(1, 'Surface configure timed out'))).toBe(
+      '03:04:05.678 WARN  lwe_engine: Surface configure timed out'
+    );
+  });
+
+  it('labels known capabilities and falls back to the raw key', () => {
+    const copy = getCopyForLanguage('en');
+
+    expect(capabilityLabel('library_database', copy)).toBe('Library database');
+    expect(capabilityLabel('vaapi', copy)).toBe('vaapi');
+  });
+
+  it('summarizes decoder, frame rate, and dropped frames of a playing wallpaper', () => {
+    const copy = getCopyForLanguage('en');
+
+    expect(
+      playbackSummary({ title: null, durationSecs: null, hwdec: 'no', fps: 30, skipRate: 0 }, copy)
+    ).toBe('Software decoding · 30.0 fps · 0.0% dropped');
+    expect(
+      playbackSummary({ title: null, durationSecs: null, hwdec: null, fps: null, skipRate: null }, copy)
+    ).toBe('');
+    expect(formatClock(3725)).toBe('1:02:05');
+  });
+
+  it('bundles version, engine, capabilities, and logs into a markdown report', () => {
+    const report = buildDiagnosticsReport(snapshot, [line(4, 'Surface configure timed out')]);
+
+    expect(report).toContain('- Version: 0.6.1');
+    expect(report).toContain('- Output DP-1 (2560x1440): /videos/forest.mp4');
+    expect(report).toContain('  - GPU error: GL_INVALID_OPERATION (0x0502) (x3)');
+    expect(report).toContain('- Backend: OpenGL');
+    expect(report).toContain('- Power: battery, paused');
+    expect(report).toContain('  - Title: Forest (1:15)');
+    expect(report).toContain('  - Playback: hwdec vaapi, 59.9 fps, 1.2% dropped');
+    expect(report).toContain('| steam | no | Steam was not detected |');
+    expect(report).toContain('| wayland_session | yes | wayland-1 (niri\\|wlroots) |');
+    expect(report).toContain('```text\n03:04:05.678 WARN  lwe_engine: Surface configure timed out\n```');
+  });
+});
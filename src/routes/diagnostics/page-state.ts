@@ -0,0 +1,139 @@
+import { formatCopy, type CopyDictionary } from '$lib/i18n';
+import type {
+  DiagnosticsEngine,
+  DiagnosticsLogLine,
+  DiagnosticsPageSnapshot,
+  DiagnosticsPlayback
+} from '$lib/types';
+
+export const MAX_VISIBLE_LOG_LINES = 500;
+export const LOG_POLL_INTERVAL_MS = 2000;
+
+export const appendLogLines = (
+  lines: DiagnosticsLogLine[],
+  incoming: DiagnosticsLogLine[],
+  limit = MAX_VISIBLE_LOG_LINES
+) => {
+  const lastSequence = lines.at(-1)?.sequence ?? 0;
+  const fresh = incoming.filter((line) => line.sequence > lastSequence);
+  if (fresh.length === 0) {
+    return lines;
+  }
+
+  return [...lines, ...fresh].slice(-limit);
+};
+
+export const lastLogSequence = (lines: DiagnosticsLogLine[]) => lines.at(-1)?.sequence ?? null;
+
+export const formatLogTime = (timestampMs: number) => new Date(timestampMs).toISOString().slice(11, 23);
+
+export const formatLogLine = (line: DiagnosticsLogLine) =>
+  `${formatLogTime(line.timestampMs)} ${line.level.padEnd(5)} ${line.target}: ${line.message}`;
+
+export const capabilityLabel = (key: string, copyValue: CopyDictionary) =>
+  (copyValue.diagnostics.capabilityLabels as Record<string, string>)[key] ?? key;
+
+/** `75.4` -> `1:15` */
+export const formatClock = (seconds: number) => {
+  const total = Math.round(seconds);
+  const minutes = Math.floor(total / 60);
+  const rest = String(total % 60).padStart(2, '0');
+  return minutes >= 60
+    ? `${Math.floor(minutes / 60)}:${String(minutes % 60).padStart(2, '0')}:${rest}`
+    : `${minutes}:${rest}`;
+};
+
+export const powerLabel = (engine: DiagnosticsEngine, copyValue: CopyDictionary) => {
+  if (!engine.onBattery) {
+    return copyValue.diagnostics.power.ac;
+  }
+  return engine.powerPaused ? copyValue.diagnostics.power.batteryPaused : copyValue.diagnostics.power.battery;
+};
+
+/** Decoder, frame rate and dropped frames of a playing wallpaper, joined for one line */
+export const playbackSummary = (playback: DiagnosticsPlayback, copyValue: CopyDictionary) => {
+  const parts: string[] = [];
+  if (playback.hwdec === 'no') {
+    parts.push(copyValue.diagnostics.softwareDecoding);
+  } else if (playback.hwdec) {
+    parts.push(formatCopy(copyValue.diagnostics.hardwareDecoding, { hwdec: playback.hwdec }));
+  }
+  if (playback.fps !== null) {
+    parts.push(formatCopy(copyValue.diagnostics.frameRate, { fps: playback.fps.toFixed(1) }));
+  }
+  if (playback.skipRate !== null) {
+    parts.push(
+      formatCopy(copyValue.diagnostics.droppedFrames, { percent: (playback.skipRate * 100).toFixed(1) })
+    );
+  }
+  return parts.join(' · ');
+};
+
+// Reports stay in English so they read the same to maintainers regardless of UI language.
+export const buildDiagnosticsReport = (
+  snapshot: DiagnosticsPageSnapshot,
+  lines: DiagnosticsLogLine[]
+) => {
+  const engineLines = [`- Status: ${snapshot.engine.status}`];
+  if (snapshot.engine.issue) {
+    engineLines.push(`- Issue: ${snapshot.engine.issue}`);
+  }
+  if (snapshot.engine.renderBackend) {
+    engineLines.push(`- Backend: ${snapshot.engine.renderBackend}`);
+  }
+  if (snapshot.engine.status === 'running') {
+    const power = snapshot.engine.onBattery ? 'battery' : 'AC';
+    engineLines.push(`- Power: ${power}${snapshot.engine.powerPaused ? ', paused' : ''}`);
+  }
+  for (const output of snapshot.engine.outputs) {
+    engineLines.push(
+      `- Output ${output.name} (${output.resolution}): ${output.wallpaperPath ?? 'no wallpaper'}`
+    );
+    if (output.playback) {
+      const { title, durationSecs, hwdec, fps, skipRate } = output.playback;
+      if (title) {
+        engineLines.push(`  - Title: ${title}${durationSecs !== null ? ` (${formatClock(durationSecs)})` : ''}`);
+      }
+      const stats = [
+        hwdec ? `hwdec ${hwdec}` : null,
+        fps !== null ? `${fps.toFixed(1)} fps` : null,
+        skipRate !== null ? `${(skipRate * 100).toFixed(1)}% dropped` : null
+      ].filter((part) => part !== null);
+      if (stats.length > 0) {
+        engineLines.push(`  - Playback: ${stats.join(', ')}`);
+      }
+    }
+    for (const message of output.gpuMessages) {
+      const repeated = message.count > 1 ? ` (x${message.count})` : '';
+      engineLines.push(`  - GPU ${message.severity}: ${message.message}${repeated}`);
+    }
+  }
+
+  const capabilityRows = snapshot.capabilities.map(
+    (capability) =>
+      `| ${capability.key} | ${capability.available ? 'yes' : 'no'} | ${capability.detail.replaceAll('|', '\\|')} |`
+  );
+
+  return [
+    '### LWE diagnostics',
+    '',
+    `- Version: ${snapshot.appVersion}`,
+    '',
+    '#### Engine',
+    '',
+    ...engineLines,
+    '',
+    '#### Capabilities',
+    '',
+    '| Capability | Available | Detail |',
+    '| --- | --- | --- |',
+    ...capabilityRows,
+    '',
+    '#### Log',
+    '',
+    '```text',
+    ...lines.map(formatLogLine),
+    '```',
+    ''
+  ].join('\n');
+};
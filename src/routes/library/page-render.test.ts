@@ -10,6 +10,8 @@ const resetCache = () => {
     library: { snapshot: null, detail: null, stale: false },
     workshop: { snapshot: null, detail: null, stale: false },
     desktop: { snapshot: null, detail: null, stale: false },
+    playlists: { snapshot: null, detail: null, stale: false },
+    diagnostics: { snapshot: null, detail: null, stale: false },
     settings: { snapshot: null, detail: null, stale: false }
   });
 };
@@ -45,6 +47,8 @@ describe('library page render', () => {
             summaryCopy: 'Ready to use'
           },
           favorite: false,
+          rating: 0,
+          useCount: 0,
           assignedMonitorLabels: ['Primary', 'DISPLAY-2 (missing)']
         }
       ],
@@ -59,6 +63,7 @@ describe('library page render', () => {
     setLibraryDetail({
       id: 'scene-7',
       title: 'Forest Scene',
+      originalTitle: 'Forest Scene',
       itemType: 'scene',
       coverPath: null,
       source: 'workshop',
@@ -77,7 +82,12 @@ describe('library page render', () => {
       desktopAssignmentsAvailable: true,
       assignedMonitorLabels: ['Primary', 'DISPLAY-2 (missing)'],
       description: null,
-      tags: []
+      tags: [],
+      userTags: [],
+      media: {},
+      favorite: false,
+      rating: 0,
+      properties: []
     });
 
     const { body } = render(LibraryPage);
@@ -129,6 +139,8 @@ describe('library page render', () => {
             summaryCopy: 'Ready to use'
           },
           favorite: false,
+          rating: 0,
+          useCount: 0,
           assignedMonitorLabels: []
         }
       ],
@@ -143,6 +155,7 @@ describe('library page render', () => {
     setLibraryDetail({
       id: 'scene-7',
       title: 'Forest Scene',
+      originalTitle: 'Forest Scene',
       itemType: 'scene',
       coverPath: null,
       source: 'workshop',
@@ -161,7 +174,12 @@ describe('library page render', () => {
       desktopAssignmentsAvailable: true,
       assignedMonitorLabels: [],
       description: null,
-      tags: []
+      tags: [],
+      userTags: [],
+      media: {},
+      favorite: false,
+      rating: 0,
+      properties: []
     });
 
     const { body } = render(LibraryPage);
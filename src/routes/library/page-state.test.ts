@@ -1,7 +1,17 @@
 import { describe, expect, it } from 'vitest';
 
 import { getCopyForLanguage } from '$lib/i18n';
-import { resolveLibraryApplyRefreshState, resolveLibraryPageState } from './page-state';
+import {
+  ALL_MONITORS_TARGET,
+  arrangeLibraryItems,
+  extendMarkedItems,
+  itemsInRect,
+  needsApplyConfirmation,
+  nextPageCoverPaths,
+  resolveApplyTargetId,
+  resolveLibraryApplyRefreshState,
+  resolveLibraryPageState
+} from './page-state';
 
 const libraryCopy = getCopyForLanguage('en').library;
 
@@ -80,4 +90,73 @@ describe('library page state', () => {
       emptyMessage: 'No Library items are available in the current snapshot.'
     });
   });
+
+  it('only asks for confirmation when applying to every monitor with the setting enabled', () => {
+    expect(needsApplyConfirmation(ALL_MONITORS_TARGET, true)).toBe(true);
+    expect(needsApplyConfirmation(ALL_MONITORS_TARGET, false)).toBe(false);
+    expect(needsApplyConfirmation('DISPLAY-1', true)).toBe(false);
+  });
+
+  it('keeps a valid apply target and falls back to the first monitor otherwise', () => {
+    expect(resolveApplyTargetId('DISPLAY-2', ['DISPLAY-1', 'DISPLAY-2'])).toBe('DISPLAY-2');
+    expect(resolveApplyTargetId('DISPLAY-9', ['DISPLAY-1', 'DISPLAY-2'])).toBe('DISPLAY-1');
+    expect(resolveApplyTargetId(ALL_MONITORS_TARGET, ['DISPLAY-1', 'DISPLAY-2'])).toBe(
+      ALL_MONITORS_TARGET
+    );
+    expect(resolveApplyTargetId(ALL_MONITORS_TARGET, ['DISPLAY-1'])).toBe('DISPLAY-1');
+    expect(resolveApplyTargetId('DISPLAY-1', [])).toBe('');
+  });
+
+  it('prefetches the local covers of the next page only', () => {
+    const items = [
+      { coverPath: '/covers/1.gif' },
+      { coverPath: '/covers/2.gif' },
+      { coverPath: '/covers/3.gif' },
+      { coverPath: null },
+      { coverPath: 'https://example.com/5.jpg' },
+      { coverPath: '/covers/6.gif' }
+    ];
+
+    expect(nextPageCoverPaths(items, 1, 2)).toEqual(['/covers/3.gif']);
+    expect(nextPageCoverPaths(items, 2, 2)).toEqual(['/covers/6.gif']);
+    expect(nextPageCoverPaths(items, 3, 2)).toEqual([]);
+  });
+
+  it('narrows to favorites or recent use and orders by rating or last use', () => {
+    const items = [
+      { id: 'a', favorite: true, rating: 2, lastUsedAt: '2026-03-01T10:00:00+00:00' },
+      { id: 'b', favorite: false, rating: 5, lastUsedAt: null },
+      { id: 'c', favorite: true, rating: 4, lastUsedAt: '2026-03-02T08:00:00+00:00' },
+      { id: 'd', favorite: false, rating: 0 }
+    ];
+    const ids = (arranged: { id: string }[]) => arranged.map((item) => item.id);
+
+    expect(ids(arrangeLibraryItems(items, 'all', 'name'))).toEqual(['a', 'b', 'c', 'd']);
+    expect(ids(arrangeLibraryItems(items, 'all', 'rating'))).toEqual(['b', 'c', 'a', 'd']);
+    expect(ids(arrangeLibraryItems(items, 'all', 'last_used'))).toEqual(['c', 'a', 'b', 'd']);
+    expect(ids(arrangeLibraryItems(items, 'favorites', 'name'))).toEqual(['a', 'c']);
+    expect(ids(arrangeLibraryItems(items, 'recent', 'rating'))).toEqual(['c', 'a']);
+  });
+
+  it('toggles marks with ctrl-click and marks ranges with shift-click', () => {
+    const ids = ['a', 'b', 'c', 'd', 'e'];
+
+    expect(extendMarkedItems([], ids, null, 'b', 'toggle')).toEqual(['b']);
+    expect(extendMarkedItems(['b', 'c'], ids, 'b', 'b', 'toggle')).toEqual(['c']);
+    expect(extendMarkedItems(['a'], ids, 'd', 'b', 'range')).toEqual(['a', 'b', 'c', 'd']);
+    expect(extendMarkedItems([], ids, null, 'c', 'range')).toEqual(['c']);
+    expect(extendMarkedItems(['a'], ids, 'a', 'gone', 'range')).toEqual(['a']);
+  });
+
+  it('marks the cards a rubber band touches', () => {
+    const card = (itemId: string, left: number, top: number) => ({
+      itemId,
+      rect: { left, top, right: left + 100, bottom: top + 100 }
+    });
+    const cards = [card('a', 0, 0), card('b', 120, 0), card('c', 0, 120), card('d', 120, 120)];
+
+    expect(itemsInRect({ left: 50, top: 50, right: 130, bottom: 90 }, cards)).toEqual(['a', 'b']);
+    expect(itemsInRect({ left: 105, top: 105, right: 115, bottom: 115 }, cards)).toEqual([]);
+    expect(itemsInRect({ left: 90, top: 90, right: 130, bottom: 130 }, cards)).toEqual(['a', 'b', 'c', 'd']);
+  });
 });
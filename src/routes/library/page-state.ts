@@ -1,4 +1,9 @@
-import type { InvalidatedPage, LibraryItemDetail, LibraryPageSnapshot } from '$lib/types';
+import type {
+  InvalidatedPage,
+  LibraryItemDetail,
+  LibraryItemSummary,
+  LibraryPageSnapshot
+} from '$lib/types';
 
 type LibraryAvailabilitySource = Pick<
   LibraryPageSnapshot,
@@ -16,6 +21,114 @@ type LibraryApplyRefreshState = {
   refreshLibraryDetailId: string | null;
 };
 
+export const ALL_MONITORS_TARGET = '__all__';
+export const APPLY_UNDO_WINDOW_SECS = 5;
+
+export const needsApplyConfirmation = (targetMonitorId: string, confirmApplyToAll: boolean) =>
+  targetMonitorId === ALL_MONITORS_TARGET && confirmApplyToAll;
+
+// Local covers on the page after the current one, generated behind the visible cards.
+export const nextPageCoverPaths = (
+  items: Pick<LibraryItemSummary, 'coverPath'>[],
+  currentPage: number,
+  pageSize: number
+) =>
+  items
+    .slice(currentPage * pageSize, (currentPage + 1) * pageSize)
+    .map((item) => item.coverPath)
+    .filter((coverPath): coverPath is string => Boolean(coverPath?.startsWith('/')));
+
+export type LibraryView = 'all' | 'favorites' | 'recent';
+export type LibrarySort = 'name' | 'last_used' | 'rating';
+
+type ArrangeableItem = Pick<LibraryItemSummary, 'favorite' | 'rating' | 'lastUsedAt'>;
+
+const lastUsedTime = (item: ArrangeableItem) =>
+  item.lastUsedAt ? Date.parse(item.lastUsedAt) : Number.NEGATIVE_INFINITY;
+
+// Items arrive sorted by name; the other orders keep it among ties.
+export const arrangeLibraryItems = <T extends ArrangeableItem>(
+  items: T[],
+  view: LibraryView,
+  sort: LibrarySort
+): T[] => {
+  const visible = items.filter((item) => {
+    if (view === 'favorites') {
+      return item.favorite;
+    }
+    if (view === 'recent') {
+      return Boolean(item.lastUsedAt);
+    }
+    return true;
+  });
+
+  // Recently used always lists the latest use first
+  const order = view === 'recent' ? 'last_used' : sort;
+  if (order === 'last_used') {
+    return [...visible].sort((a, b) => lastUsedTime(b) - lastUsedTime(a));
+  }
+  if (order === 'rating') {
+    return [...visible].sort((a, b) => b.rating - a.rating);
+  }
+  return visible;
+};
+
+// Ctrl-click toggles one card; shift-click marks every card from the anchor to the target in
+// the order shown, keeping earlier marks.
+export const extendMarkedItems = (
+  marked: string[],
+  orderedIds: string[],
+  anchorId: string | null,
+  targetId: string,
+  mode: 'toggle' | 'range'
+): string[] => {
+  if (mode === 'toggle') {
+    return marked.includes(targetId)
+      ? marked.filter((itemId) => itemId !== targetId)
+      : [...marked, targetId];
+  }
+
+  const anchor = anchorId ? orderedIds.indexOf(anchorId) : -1;
+  const target = orderedIds.indexOf(targetId);
+  if (target < 0) {
+    return marked;
+  }
+  const [start, end] = anchor < 0 ? [target, target] : [Math.min(anchor, target), Math.max(anchor, target)];
+  return Array.from(new Set([...marked, ...orderedIds.slice(start, end + 1)]));
+};
+
+export type SelectionRect = { left: number; top: number; right: number; bottom: number };
+
+// Rubber-band selection marks every card the dragged rectangle touches.
+export const itemsInRect = (
+  rect: SelectionRect,
+  cards: { itemId: string; rect: SelectionRect }[]
+): string[] =>
+  cards
+    .filter(
+      (card) =>
+        card.rect.left < rect.right &&
+        card.rect.right > rect.left &&
+        card.rect.top < rect.bottom &&
+        card.rect.bottom > rect.top
+    )
+    .map((card) => card.itemId);
+
+export const resolveApplyTargetId = (
+  targetMonitorId: string,
+  monitorIds: string[]
+): string => {
+  if (monitorIds.length === 0) {
+    return '';
+  }
+
+  if (targetMonitorId === ALL_MONITORS_TARGET && monitorIds.length > 1) {
+    return targetMonitorId;
+  }
+
+  return monitorIds.includes(targetMonitorId) ? targetMonitorId : monitorIds[0];
+};
+
 export type LibraryCopy = {
   empty: string;
   monitorDiscoveryUnavailable: string;
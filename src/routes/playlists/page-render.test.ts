@@ -0,0 +1,99 @@
+import { afterEach, describe, expect, it } from 'vitest';
+import { render } from 'svelte/server';
+
+import PlaylistsPage from './+page.svelte';
+import { resetPreferredLanguage, setPreferredLanguage } from '$lib/i18n';
+import { pageCache, setPlaylistSnapshot } from '$lib/stores/ui';
+import type { PlaylistRotation } from '$lib/types';
+
+const resetCache = () => {
+  pageCache.set({
+    library: { snapshot: null, detail: null, stale: false },
+    workshop: { snapshot: null, detail: null, stale: false },
+    desktop: { snapshot: null, detail: null, stale: false },
+    playlists: { snapshot: null, detail: null, stale: false },
+    diagnostics: { snapshot: null, detail: null, stale: false },
+    settings: { snapshot: null, detail: null, stale: false }
+  });
+};
+
+const snapshot = {
+  playlists: [
+    {
+      id: 1,
+      name: 'Evening',
+      shuffle: false,
+      transition: 'fade' as const,
+      defaultDurationSecs: 300,
+      items: [{ itemId: 'scene-7', itemTitle: 'Forest Scene', durationSecs: 60 }],
+      monitorIds: ['DISPLAY-1'],
+      folder: null
+    }
+  ],
+  collections: [
+    {
+      id: 2,
+      name: 'Nature',
+      items: [{ itemId: 'scene-7', itemTitle: 'Forest Scene' }]
+    }
+  ],
+  libraryItems: [{ id: 'scene-7', title: 'Forest Scene' }],
+  libraryIssue: null,
+  monitors: [{ monitorId: 'DISPLAY-1', displayName: 'Primary (1920x1080)' }],
+  monitorsAvailable: true,
+  monitorDiscoveryIssue: null,
+  rotations: [] as PlaylistRotation[],
+  stale: false
+};
+
+describe('playlists page render', () => {
+  afterEach(() => {
+    resetPreferredLanguage();
+    resetCache();
+  });
+
+  it('renders saved playlists and monitor choices from the snapshot', () => {
+    setPlaylistSnapshot(snapshot);
+
+    const { body } = render(PlaylistsPage);
+
+    expect(body).toContain('Playlist editor');
+    expect(body).toContain('Evening');
+    expect(body).toContain('1 items');
+    expect(body).toContain('Primary (1920x1080)');
+    expect(body).toContain('Save playlist');
+    expect(body).toContain('Collections');
+    expect(body).toContain('Rotate as playlist');
+  });
+
+  it('renders rotation controls for monitors running a playlist', () => {
+    setPlaylistSnapshot({
+      ...snapshot,
+      rotations: [
+        {
+          monitorId: 'DISPLAY-1',
+          playlistId: 1,
+          currentItemId: 'scene-7',
+          currentItemTitle: 'Forest Scene',
+          paused: true
+        }
+      ]
+    });
+
+    const { body } = render(PlaylistsPage);
+
+    expect(body).toContain('Now rotating');
+    expect(body).toContain('Evening · Paused');
+    expect(body).toContain('Resume');
+  });
+
+  it('renders zh-CN copy when Simplified Chinese is active', () => {
+    setPreferredLanguage('zh-CN');
+    setPlaylistSnapshot({ ...snapshot, playlists: [] });
+
+    const { body } = render(PlaylistsPage);
+
+    expect(body).toContain('播放列表编辑器');
+    expect(body).toContain('还没有播放列表。创建一个即可开始轮播壁纸。');
+  });
+});
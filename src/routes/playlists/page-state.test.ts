@@ -0,0 +1,165 @@
+import { describe, expect, it } from 'vitest';
+
+import { getCopyForLanguage } from '$lib/i18n';
+import {
+  addDraftItem,
+  createPlaylistDraft,
+  describeRotation,
+  draftFromPlaylist,
+  draftItemLabel,
+  moveDraftItem,
+  removeDraftItem,
+  setDraftFolderImageDuration,
+  setDraftItemDuration,
+  toPlaylistSaveInput,
+  toggleDraftMonitor,
+  validatePlaylistDraft
+} from './page-state';
+
+const copy = getCopyForLanguage('en');
+
+const items = [
+  { itemId: 'scene-1', itemTitle: 'Forest', durationSecs: null },
+  { itemId: 'scene-2', itemTitle: 'Ocean', durationSecs: 60 },
+  { itemId: 'scene-3', itemTitle: null, durationSecs: null }
+];
+
+describe('playlist page state', () => {
+  it('moves dragged items to the drop position and ignores out-of-range moves', () => {
+    expect(moveDraftItem(items, 0, 2).map((item) => item.itemId)).toEqual([
+      'scene-2',
+      'scene-3',
+      'scene-1'
+    ]);
+    expect(moveDraftItem(items, 2, 0).map((item) => item.itemId)).toEqual([
+      'scene-3',
+      'scene-1',
+      'scene-2'
+    ]);
+    expect(moveDraftItem(items, 1, 5)).toBe(items);
+  });
+
+  it('adds, removes, and times items without mutating the draft', () => {
+    const added = addDraftItem(items, { id: 'video-4', title: 'City' });
+    expect(added.at(-1)).toEqual({ itemId: 'video-4', itemTitle: 'City', durationSecs: null });
+    expect(items).toHaveLength(3);
+
+    expect(removeDraftItem(items, 1).map((item) => item.itemId)).toEqual(['scene-1', 'scene-3']);
+    expect(setDraftItemDuration(items, 0, '45')[0].durationSecs).toBe(45);
+    expect(setDraftItemDuration(items, 1, '')[1].durationSecs).toBeNull();
+  });
+
+  it('toggles monitor assignments without duplicates', () => {
+    expect(toggleDraftMonitor(['DP-1'], 'DP-1', true)).toEqual(['DP-1']);
+    expect(toggleDraftMonitor(['DP-1'], 'HDMI-A-1', true)).toEqual(['DP-1', 'HDMI-A-1']);
+    expect(toggleDraftMonitor(['DP-1', 'HDMI-A-1'], 'DP-1', false)).toEqual(['HDMI-A-1']);
+  });
+
+  it('labels entries whose Library item has disappeared', () => {
+    expect(draftItemLabel(items[0], copy)).toBe('Forest');
+    expect(draftItemLabel(items[2], copy)).toBe('Missing item (scene-3)');
+  });
+
+  it('validates names and minimum durations before saving', () => {
+    const draft = { ...createPlaylistDraft(), items };
+    expect(validatePlaylistDraft(draft, copy)).toBe('Give the playlist a name before saving.');
+    expect(
+      validatePlaylistDraft(
+        { ...draft, name: 'Evening', items: setDraftItemDuration(items, 0, '2') },
+        copy
+      )
+    ).toBe('Durations must be at least 5 seconds.');
+    expect(validatePlaylistDraft({ ...draft, name: 'Evening' }, copy)).toBeNull();
+  });
+
+  it('round-trips a saved playlist into a save payload', () => {
+    const draft = draftFromPlaylist({
+      id: 3,
+      name: ' Evening ',
+      shuffle: true,
+      transition: 'fade',
+      defaultDurationSecs: 600,
+      items,
+      monitorIds: ['DP-1'],
+      folder: null
+    });
+
+    expect(toPlaylistSaveInput(draft)).toEqual({
+      id: 3,
+      name: 'Evening',
+      shuffle: true,
+      transition: 'fade',
+      defaultDurationSecs: 600,
+      items: [
+        { itemId: 'scene-1', durationSecs: null },
+        { itemId: 'scene-2', durationSecs: 60 },
+        { itemId: 'scene-3', durationSecs: null }
+      ],
+      monitorIds: ['DP-1'],
+      folder: null
+    });
+  });
+
+  it('validates and trims folder playlists', () => {
+    const draft = {
+      ...createPlaylistDraft(),
+      name: 'Wallpapers',
+      folder: { path: ' ', pattern: null, imageDurationSecs: null }
+    };
+    expect(validatePlaylistDraft(draft, copy)).toBe('Pick the folder this playlist plays.');
+
+    const folder = setDraftFolderImageDuration(
+      { path: ' /home/user/Wallpapers ', pattern: ' *.mp4 *.png ', imageDurationSecs: null },
+      '3'
+    );
+    expect(validatePlaylistDraft({ ...draft, folder }, copy)).toBe(
+      'Durations must be at least 5 seconds.'
+    );
+    expect(toPlaylistSaveInput({ ...draft, folder: { ...folder, imageDurationSecs: 30 } }).folder).toEqual({
+      path: '/home/user/Wallpapers',
+      pattern: '*.mp4 *.png',
+      imageDurationSecs: 30
+    });
+  });
+
+  it('describes rotations with monitor and playlist names', () => {
+    const snapshot = {
+      playlists: [
+        {
+          id: 4,
+          name: 'Evening',
+          shuffle: false,
+          transition: 'none' as const,
+          defaultDurationSecs: 300,
+          items: [],
+          monitorIds: ['DP-1'],
+          folder: null
+        }
+      ],
+      collections: [],
+      libraryItems: [],
+      monitors: [{ monitorId: 'DP-1', displayName: 'Primary (2560x1440)' }],
+      monitorsAvailable: true,
+      rotations: [],
+      stale: false
+    };
+    const rotation = {
+      monitorId: 'DP-1',
+      playlistId: 4,
+      currentItemId: 'scene-1',
+      currentItemTitle: 'Forest',
+      paused: false
+    };
+
+    expect(describeRotation(rotation, snapshot, copy)).toEqual({
+      monitor: 'Primary (2560x1440)',
+      playlist: 'Evening',
+      showing: 'Showing Forest'
+    });
+    expect(describeRotation({ ...rotation, monitorId: 'HDMI-A-1', paused: true }, snapshot, copy)).toEqual({
+      monitor: 'HDMI-A-1',
+      playlist: 'Evening',
+      showing: 'Paused'
+    });
+  });
+});
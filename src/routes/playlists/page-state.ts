@@ -0,0 +1,163 @@
+import { formatCopy, type CopyDictionary } from '$lib/i18n';
+import type {
+  PlaylistFolder,
+  PlaylistLibraryItem,
+  PlaylistPageSnapshot,
+  PlaylistRotation,
+  PlaylistSaveInput,
+  PlaylistSummary,
+  PlaylistTransition
+} from '$lib/types';
+
+export const MIN_PLAYLIST_DURATION_SECS = 5;
+export const DEFAULT_PLAYLIST_DURATION_SECS = 300;
+
+export type PlaylistDraftEntry = {
+  itemId: string;
+  itemTitle: string | null;
+  durationSecs: number | null;
+};
+
+export type PlaylistDraft = {
+  id: number | null;
+  name: string;
+  shuffle: boolean;
+  transition: PlaylistTransition;
+  defaultDurationSecs: number;
+  items: PlaylistDraftEntry[];
+  monitorIds: string[];
+  folder: PlaylistFolder | null;
+};
+
+export const createPlaylistDraft = (): PlaylistDraft => ({
+  id: null,
+  name: '',
+  shuffle: false,
+  transition: 'none',
+  defaultDurationSecs: DEFAULT_PLAYLIST_DURATION_SECS,
+  items: [],
+  monitorIds: [],
+  folder: null
+});
+
+export const draftFromPlaylist = (playlist: PlaylistSummary): PlaylistDraft => ({
+  id: playlist.id,
+  name: playlist.name,
+  shuffle: playlist.shuffle,
+  transition: playlist.transition,
+  defaultDurationSecs: playlist.defaultDurationSecs,
+  items: playlist.items.map((item) => ({ ...item })),
+  monitorIds: [...playlist.monitorIds],
+  folder: playlist.folder ? { ...playlist.folder } : null
+});
+
+export const moveDraftItem = (items: PlaylistDraftEntry[], from: number, to: number) => {
+  if (from === to || from < 0 || from >= items.length || to < 0 || to >= items.length) {
+    return items;
+  }
+
+  const next = [...items];
+  const [moved] = next.splice(from, 1);
+  next.splice(to, 0, moved);
+  return next;
+};
+
+export const addDraftItem = (items: PlaylistDraftEntry[], item: PlaylistLibraryItem) => [
+  ...items,
+  { itemId: item.id, itemTitle: item.title, durationSecs: null }
+];
+
+export const removeDraftItem = (items: PlaylistDraftEntry[], index: number) =>
+  items.filter((_, itemIndex) => itemIndex !== index);
+
+export const setDraftItemDuration = (
+  items: PlaylistDraftEntry[],
+  index: number,
+  rawValue: string
+) => {
+  const parsed = Number.parseInt(rawValue, 10);
+  const durationSecs = Number.isFinite(parsed) && parsed > 0 ? parsed : null;
+
+  return items.map((item, itemIndex) => (itemIndex === index ? { ...item, durationSecs } : item));
+};
+
+export const emptyPlaylistFolder = (): PlaylistFolder => ({
+  path: '',
+  pattern: null,
+  imageDurationSecs: null
+});
+
+/** Image duration typed into the folder fields; blank falls back to the default duration */
+export const setDraftFolderImageDuration = (folder: PlaylistFolder, rawValue: string) => {
+  const parsed = Number.parseInt(rawValue, 10);
+  return { ...folder, imageDurationSecs: Number.isFinite(parsed) && parsed > 0 ? parsed : null };
+};
+
+export const toggleDraftMonitor = (monitorIds: string[], monitorId: string, enabled: boolean) =>
+  enabled
+    ? Array.from(new Set([...monitorIds, monitorId]))
+    : monitorIds.filter((entry) => entry !== monitorId);
+
+export const draftItemLabel = (item: PlaylistDraftEntry, copyValue: CopyDictionary) =>
+  item.itemTitle ?? formatCopy(copyValue.playlists.missingItem, { itemId: item.itemId });
+
+export const describeRotation = (
+  rotation: PlaylistRotation,
+  snapshot: PlaylistPageSnapshot,
+  copyValue: CopyDictionary
+) => ({
+  monitor:
+    snapshot.monitors.find((monitor) => monitor.monitorId === rotation.monitorId)?.displayName ??
+    rotation.monitorId,
+  playlist:
+    snapshot.playlists.find((playlist) => playlist.id === rotation.playlistId)?.name ??
+    copyValue.playlists.untitled,
+  showing: rotation.paused
+    ? copyValue.playlists.rotationPaused
+    : formatCopy(copyValue.playlists.rotationShowing, {
+        title:
+          rotation.currentItemTitle ??
+          formatCopy(copyValue.playlists.missingItem, { itemId: rotation.currentItemId ?? '' })
+      })
+});
+
+export const validatePlaylistDraft = (draft: PlaylistDraft, copyValue: CopyDictionary) => {
+  if (!draft.name.trim()) {
+    return copyValue.playlists.nameRequired;
+  }
+
+  if (draft.folder && !draft.folder.path.trim()) {
+    return copyValue.playlists.folderRequired;
+  }
+
+  const durations = [
+    draft.defaultDurationSecs,
+    ...(draft.folder
+      ? [draft.folder.imageDurationSecs]
+      : draft.items.map((item) => item.durationSecs))
+  ];
+  if (durations.some((duration) => duration !== null && duration < MIN_PLAYLIST_DURATION_SECS)) {
+    return formatCopy(copyValue.playlists.durationTooShort, {
+      seconds: MIN_PLAYLIST_DURATION_SECS
+    });
+  }
+
+  return null;
+};
+
+export const toPlaylistSaveInput = (draft: PlaylistDraft): PlaylistSaveInput => ({
+  id: draft.id,
+  name: draft.name.trim(),
+  shuffle: draft.shuffle,
+  transition: draft.transition,
+  defaultDurationSecs: draft.defaultDurationSecs,
+  items: draft.items.map((item) => ({ itemId: item.itemId, durationSecs: item.durationSecs })),
+  monitorIds: draft.monitorIds,
+  folder: draft.folder
+    ? {
+        path: draft.folder.path.trim(),
+        pattern: draft.folder.pattern?.trim() || null,
+        imageDurationSecs: draft.folder.imageDurationSecs
+      }
+    : null
+});
@@ -10,6 +10,8 @@ const resetCache = () => {
     library: { snapshot: null, detail: null, stale: false },
     workshop: { snapshot: null, detail: null, stale: false },
     desktop: { snapshot: null, detail: null, stale: false },
+    playlists: { snapshot: null, detail: null, stale: false },
+    diagnostics: { snapshot: null, detail: null, stale: false },
     settings: { snapshot: null, detail: null, stale: false }
   });
 };
@@ -30,9 +32,11 @@ describe('settings page render', () => {
       workshopQuery: 'forest',
       workshopAgeRatings: ['g', 'pg_13'],
       workshopItemTypes: ['video', 'scene', 'web', 'application'],
+      confirmApplyToAll: true,
       steamRequired: true,
       steamStatusMessage: 'Steam is required to launch Wallpaper Engine content.',
-      stale: false
+      stale: false,
+      configIssues: []
     });
 
     const { body } = render(SettingsPage);
@@ -47,6 +51,9 @@ describe('settings page render', () => {
     expect(body).toContain('abcd...wxyz');
     expect(body).toContain('Launch on login:');
     expect(body).toContain('enabled');
+    expect(body).toContain('Confirm apply to all monitors:');
+    expect(body).toContain('Thumbnail cache');
+    expect(body).toContain('Clear thumbnail cache');
     expect(body).not.toContain('Save changes');
     expect(body).not.toContain('Rust backend');
     expect(body).not.toContain('backend-owned settings file');
@@ -63,9 +70,11 @@ describe('settings page render', () => {
       workshopQuery: 'city',
       workshopAgeRatings: ['g', 'pg_13'],
       workshopItemTypes: ['video', 'scene', 'web', 'application'],
+      confirmApplyToAll: true,
       steamRequired: false,
       steamStatusMessage: 'Steam is optional for the current setup.',
-      stale: false
+      stale: false,
+      configIssues: []
     });
 
     const { body } = render(SettingsPage, { props: { initialEditing: true } });
@@ -89,9 +98,11 @@ describe('settings page render', () => {
       workshopQuery: 'city',
       workshopAgeRatings: ['g', 'pg_13'],
       workshopItemTypes: ['video', 'scene', 'web', 'application'],
+      confirmApplyToAll: true,
       steamRequired: false,
       steamStatusMessage: 'Steam is optional for the current setup.',
-      stale: false
+      stale: false,
+      configIssues: []
     });
 
     const { body } = render(SettingsPage);
@@ -112,9 +123,11 @@ describe('settings page render', () => {
       workshopQuery: '',
       workshopAgeRatings: ['g', 'pg_13'],
       workshopItemTypes: ['video', 'scene', 'web', 'application'],
+      confirmApplyToAll: true,
       steamRequired: false,
       steamStatusMessage: 'Steam is optional for the current setup.',
-      stale: false
+      stale: false,
+      configIssues: []
     });
 
     const { body } = render(SettingsPage);
@@ -127,4 +140,38 @@ describe('settings page render', () => {
     expect(editingBody).not.toContain('aria-label="Launch on login"');
     expect(body).not.toContain('Launch on login:');
   });
+
+  it('lists problems found in the settings file with their lines', () => {
+    setSettingsSnapshot({
+      language: 'en',
+      theme: 'system',
+      launchOnLogin: false,
+      launchOnLoginAvailable: true,
+      steamWebApiKey: '',
+      workshopQuery: '',
+      workshopAgeRatings: ['g'],
+      workshopItemTypes: ['video'],
+      confirmApplyToAll: true,
+      steamRequired: false,
+      steamStatusMessage: 'Steam is optional for the current setup.',
+      stale: false,
+      configIssues: [
+        { severity: 'warning', line: 2, key: 'them', message: 'unknown key, ignored' },
+        {
+          severity: 'error',
+          line: 5,
+          key: 'outputs.DP-1.layout',
+          message: 'Unknown layout mode: fil'
+        }
+      ]
+    });
+
+    const { body } = render(SettingsPage);
+
+    expect(body).toContain('Problems in settings.toml');
+    expect(body).toContain('Line 2');
+    expect(body).toContain('<code>outputs.DP-1.layout</code>');
+    expect(body).toContain('Unknown layout mode: fil');
+    expect(body).toContain('lwe config validate');
+  });
 });
@@ -10,6 +10,8 @@ const resetCache = () => {
     library: { snapshot: null, detail: null, stale: false },
     workshop: { snapshot: null, detail: null, stale: false },
     desktop: { snapshot: null, detail: null, stale: false },
+    playlists: { snapshot: null, detail: null, stale: false },
+    diagnostics: { snapshot: null, detail: null, stale: false },
     settings: { snapshot: null, detail: null, stale: false }
   });
 };